@@ -125,6 +125,17 @@ async function fetchLatestSqliteVecUrl(platform, arch) {
   }
 }
 
+function matchLlamaCppAsset(assets, platform, arch) {
+  const suffix =
+    platform === 'darwin'
+      ? `bin-macos-${arch === 'arm64' ? 'arm64' : 'x64'}.zip`
+      : platform === 'win32'
+        ? `bin-win-cpu-${arch === 'arm64' ? 'arm64' : 'x64'}.zip`
+        : `bin-ubuntu-${arch === 'arm64' ? 'arm64' : 'x64'}.zip`
+  const asset = assets.find((a) => a && a.name && a.name.endsWith(suffix))
+  return asset ? asset.browser_download_url : null
+}
+
 function getPlatformArch() {
   const platform = os.platform() // 'darwin', 'linux', 'win32'
   const arch = os.arch() // 'x64', 'arm64', etc.
@@ -393,6 +404,54 @@ async function main() {
     console.log('sqlite-vec download step failed (non-fatal):', err)
   }
 
+  // ----- llama-quantize (optional, model quantization) -----
+  // Copied with the shared libraries it links to; only used when the installed
+  // llama.cpp backend does not ship its own copy.
+  try {
+    const quantizeDir = 'src-tauri/resources/bin/llama-quantize'
+    const exe = platform === 'win32' ? 'llama-quantize.exe' : 'llama-quantize'
+    mkdirSync(quantizeDir, { recursive: true })
+
+    if (fs.existsSync(path.join(quantizeDir, exe))) {
+      console.log(`llama-quantize already present at ${quantizeDir}`)
+    } else {
+      const rel = await getJson('https://api.github.com/repos/ggml-org/llama.cpp/releases/latest')
+      const url = matchLlamaCppAsset(rel.assets || [], platform, os.arch())
+      if (!url) {
+        console.log('No llama.cpp release asset for this platform; skipping llama-quantize.')
+      } else {
+        const archivePath = path.join(tempBinDir, 'llama-cpp.zip')
+        const extractDir = path.join(tempBinDir, 'llama-cpp')
+        await download(url, archivePath)
+        await decompress(archivePath, extractDir)
+        let binSrc = null
+        function findExe(dir) {
+          for (const entry of fs.readdirSync(dir)) {
+            const full = path.join(dir, entry)
+            if (fs.statSync(full).isDirectory()) findExe(full)
+            else if (entry === exe && !binSrc) binSrc = full
+          }
+        }
+        findExe(extractDir)
+        if (!binSrc) {
+          console.log('llama-quantize not found in the llama.cpp release; skipping.')
+        } else {
+          const libExt = /\.(so(\.\d+)*|dylib|dll)$/
+          const srcDir = path.dirname(binSrc)
+          for (const entry of fs.readdirSync(srcDir)) {
+            if (entry === exe || libExt.test(entry)) {
+              fs.copyFileSync(path.join(srcDir, entry), path.join(quantizeDir, entry))
+            }
+          }
+          fs.chmodSync(path.join(quantizeDir, exe), 0o755)
+          console.log(`llama-quantize installed at ${quantizeDir}`)
+        }
+      }
+    }
+  } catch (err) {
+    console.log('llama-quantize download step failed (non-fatal):', err)
+  }
+
   console.log('Downloads completed.')
 }
 
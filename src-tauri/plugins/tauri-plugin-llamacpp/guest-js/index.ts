@@ -80,6 +80,17 @@ export function normalizeLlamacppConfig(config: any): LlamacppConfig {
     rope_freq_scale: asNumber(config.rope_freq_scale, 1.0),
 
     ctx_shift: asBool(config.ctx_shift),
+
+    lora_adapters: Array.isArray(config.lora_adapters)
+      ? config.lora_adapters.filter((p: unknown) => typeof p === 'string' && p)
+      : [],
+
+    draft_model_path: asString(config.draft_model_path),
+    draft_max: asNumber(config.draft_max),
+    draft_min: asNumber(config.draft_min),
+    draft_p_min: asNumber(config.draft_p_min),
+
+    slot_save_path: asString(config.slot_save_path),
   }
 }
 
@@ -64,6 +64,15 @@ export type LlamacppConfig = {
   rope_freq_base: number
   rope_freq_scale: number
   ctx_shift: boolean
+  // LoRA adapter paths, loaded disabled and scaled per request
+  lora_adapters?: string[]
+  // Speculative decoding; an empty draft_model_path disables it
+  draft_model_path?: string
+  draft_max?: number
+  draft_min?: number
+  draft_p_min?: number
+  // Folder for saved slot KV caches (prompt cache); empty disables it
+  slot_save_path?: string
 }
 
 export type ModelPlan = {
@@ -38,6 +38,25 @@ pub struct LlamacppConfig {
     pub rope_freq_base: f32,
     pub rope_freq_scale: f32,
     pub ctx_shift: bool,
+    /// LoRA adapter files loaded next to the model, disabled until scaled via `/lora-adapters`
+    #[serde(default)]
+    pub lora_adapters: Vec<String>,
+    /// Draft model for speculative decoding; empty disables it
+    #[serde(default)]
+    pub draft_model_path: String,
+    #[serde(default)]
+    pub draft_max: i32,
+    #[serde(default)]
+    pub draft_min: i32,
+    #[serde(default)]
+    pub draft_p_min: f32,
+    /// Folder llama-server saves and restores slot KV caches in; empty disables slot saving
+    #[serde(default)]
+    pub slot_save_path: String,
+    /// Serve `/rerank` with a cross-encoder instead of `/embeddings`; only used when the
+    /// model is loaded in embedding mode
+    #[serde(default)]
+    pub reranking: bool,
 }
 
 /// Minimum llama.cpp build number that changed --flash-attn from a boolean
@@ -110,6 +129,11 @@ impl ArgumentBuilder {
         // Multimodal projector settings
         self.add_mmproj_args(mmproj_path);
 
+        // LoRA adapters
+        if !self.is_embedding {
+            self.add_lora_args();
+        }
+
         // Model alias and port
         self.args.push("-a".to_string());
         self.args.push(model_id.to_string());
@@ -177,6 +201,25 @@ impl ArgumentBuilder {
         }
     }
 
+    fn add_lora_args(&mut self) {
+        let adapters: Vec<String> = self
+            .config
+            .lora_adapters
+            .iter()
+            .filter(|p| !p.is_empty())
+            .cloned()
+            .collect();
+        if adapters.is_empty() {
+            return;
+        }
+        for path in adapters {
+            self.args.push("--lora".to_string());
+            self.args.push(path);
+        }
+        // Adapters start at scale 0 so they can be switched per request without a reload
+        self.args.push("--lora-init-without-apply".to_string());
+    }
+
     fn add_chat_template(&mut self) {
         if !self.config.chat_template.is_empty() {
             self.args.push("--chat-template".to_string());
@@ -184,13 +227,17 @@ impl ArgumentBuilder {
         }
     }
 
-    fn add_gpu_layers(&mut self) {
-        let gpu_layers = if self.config.n_gpu_layers >= 0 && self.config.n_gpu_layers != 100 {
+    fn gpu_layers(&self) -> i32 {
+        if self.config.n_gpu_layers >= 0 && self.config.n_gpu_layers != 100 {
             // 100 means load all layers
             self.config.n_gpu_layers
         } else {
             -1
-        };
+        }
+    }
+
+    fn add_gpu_layers(&mut self) {
+        let gpu_layers = self.gpu_layers();
         self.args.push("-ngl".to_string());
         self.args.push(gpu_layers.to_string());
     }
@@ -290,6 +337,11 @@ impl ArgumentBuilder {
     }
 
     fn add_embedding_args(&mut self) {
+        if self.config.reranking {
+            // Implies --embedding with rank pooling
+            self.args.push("--reranking".to_string());
+            return;
+        }
         self.args.push("--embedding".to_string());
         self.args.push("--pooling".to_string());
         self.args.push("mean".to_string());
@@ -327,6 +379,39 @@ impl ArgumentBuilder {
         }
 
         self.add_rope_settings();
+        self.add_speculative_args();
+
+        if !self.config.slot_save_path.is_empty() {
+            self.args.push("--slot-save-path".to_string());
+            self.args.push(self.config.slot_save_path.clone());
+        }
+    }
+
+    fn add_speculative_args(&mut self) {
+        if self.config.draft_model_path.is_empty() {
+            return;
+        }
+        self.args.push("--model-draft".to_string());
+        self.args.push(self.config.draft_model_path.clone());
+        // The draft model is offloaded like the main model
+        let gpu_layers = self.gpu_layers();
+        self.args.push("--gpu-layers-draft".to_string());
+        self.args.push(gpu_layers.to_string());
+
+        if self.config.draft_max > 0 {
+            self.args.push("--draft-max".to_string());
+            self.args.push(self.config.draft_max.to_string());
+        }
+
+        if self.config.draft_min > 0 {
+            self.args.push("--draft-min".to_string());
+            self.args.push(self.config.draft_min.to_string());
+        }
+
+        if self.config.draft_p_min > 0.0 {
+            self.args.push("--draft-p-min".to_string());
+            self.args.push(self.config.draft_p_min.to_string());
+        }
     }
 
     fn add_rope_settings(&mut self) {
@@ -416,6 +501,13 @@ mod tests {
             rope_freq_base: 0.0,
             rope_freq_scale: 1.0,
             ctx_shift: false,
+            lora_adapters: Vec::new(),
+            draft_model_path: String::new(),
+            draft_max: 0,
+            draft_min: 0,
+            draft_p_min: 0.0,
+            slot_save_path: String::new(),
+            reranking: false,
         }
     }
 
@@ -506,6 +598,23 @@ mod tests {
         assert_arg_pair(&args, "--pooling", "mean");
     }
 
+    #[test]
+    fn test_reranking_mode_arguments() {
+        let mut config = default_config();
+        config.reranking = true;
+        let builder = ArgumentBuilder::new(config.clone(), true).unwrap();
+        let args = builder.build("rerank-model", "/path/to/model", 8080, None);
+
+        assert_has_flag(&args, "--reranking");
+        assert_no_flag(&args, "--embedding");
+        assert_no_flag(&args, "--pooling");
+
+        // Only applies to embedding mode
+        let builder = ArgumentBuilder::new(config, false).unwrap();
+        let args = builder.build("test", "/path", 8080, None);
+        assert_no_flag(&args, "--reranking");
+    }
+
     #[test]
     fn test_text_generation_mode_no_embedding_flags() {
         let config = default_config();
@@ -680,6 +789,64 @@ mod tests {
         assert_no_flag(&args, "--mmproj");
     }
 
+    #[test]
+    fn test_lora_adapters() {
+        let mut config = default_config();
+        config.lora_adapters = vec!["/path/a.gguf".to_string(), "/path/b.gguf".to_string()];
+        let builder = ArgumentBuilder::new(config.clone(), false).unwrap();
+        let args = builder.build("test-model", "/path/to/model.gguf", 8080, None);
+
+        assert_eq!(args.iter().filter(|a| *a == "--lora").count(), 2);
+        assert_arg_pair(&args, "--lora", "/path/a.gguf");
+        assert_has_flag(&args, "--lora-init-without-apply");
+
+        let builder = ArgumentBuilder::new(config, true).unwrap();
+        let args = builder.build("test-model", "/path/to/model.gguf", 8080, None);
+        assert_no_flag(&args, "--lora");
+        assert_no_flag(&args, "--lora-init-without-apply");
+    }
+
+    #[test]
+    fn test_speculative_decoding() {
+        let mut config = default_config();
+        config.draft_model_path = "/path/draft.gguf".to_string();
+        config.draft_max = 16;
+        config.draft_p_min = 0.75;
+        let builder = ArgumentBuilder::new(config.clone(), false).unwrap();
+        let args = builder.build("test", "/path", 8080, None);
+
+        assert_arg_pair(&args, "--model-draft", "/path/draft.gguf");
+        assert_arg_pair(&args, "--gpu-layers-draft", "-1");
+        assert_arg_pair(&args, "--draft-max", "16");
+        assert_arg_pair(&args, "--draft-p-min", "0.75");
+        assert_no_flag(&args, "--draft-min");
+
+        let builder = ArgumentBuilder::new(config, true).unwrap();
+        let args = builder.build("test", "/path", 8080, None);
+        assert_no_flag(&args, "--model-draft");
+    }
+
+    #[test]
+    fn test_no_draft_model_not_added() {
+        let builder = ArgumentBuilder::new(default_config(), false).unwrap();
+        let args = builder.build("test", "/path", 8080, None);
+        assert_no_flag(&args, "--model-draft");
+        assert_no_flag(&args, "--draft-max");
+    }
+
+    #[test]
+    fn test_slot_save_path() {
+        let mut config = default_config();
+        config.slot_save_path = "/data/prompt_cache".to_string();
+        let builder = ArgumentBuilder::new(config.clone(), false).unwrap();
+        let args = builder.build("test", "/path", 8080, None);
+        assert_arg_pair(&args, "--slot-save-path", "/data/prompt_cache");
+
+        let builder = ArgumentBuilder::new(default_config(), false).unwrap();
+        let args = builder.build("test", "/path", 8080, None);
+        assert_no_flag(&args, "--slot-save-path");
+    }
+
     #[test]
     fn test_chat_template() {
         let mut config = default_config();
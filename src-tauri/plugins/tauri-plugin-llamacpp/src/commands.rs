@@ -5,7 +5,7 @@ use std::collections::HashMap;
 use std::process::Stdio;
 use std::sync::Arc;
 use std::time::Duration;
-use tauri::{Manager, Runtime, State};
+use tauri::{Emitter, Manager, Runtime, State};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 use tokio::sync::{mpsc, Mutex};
@@ -19,7 +19,10 @@ use crate::process::{
     find_session_by_model_id, get_all_active_sessions, get_all_loaded_model_ids,
     get_random_available_port, is_process_running_by_pid,
 };
-use crate::state::{LLamaBackendSession, LlamacppState, SessionInfo};
+use crate::state::{LLamaBackendSession, LlamacppState, LoadRequest, SessionInfo};
+use jan_utils::progress::{
+    PhaseReporter, ProgressOperation, ProgressPayload, ProgressSink, ProgressState, PROGRESS_EVENT,
+};
 use jan_utils::{
     add_cuda_paths, binary_requires_cuda, setup_library_path, setup_windows_process_flags,
 };
@@ -38,7 +41,27 @@ pub struct UnloadResult {
     error: Option<String>,
 }
 
-/// Core model loading logic usable without an AppHandle (CLI / test support).
+/// Index in `ProgressOperation::ModelLoad.phases()` of the phase a llama-server log line
+/// starts, if any
+fn load_phase(line_lower: &str) -> Option<usize> {
+    let phase = if line_lower.contains("load_tensors:") {
+        "loading_weights"
+    } else if line_lower.contains("llama_context:") || line_lower.contains("llama_init_from_model")
+    {
+        "creating_context"
+    } else if line_lower.contains("warming up") {
+        "warming_up"
+    } else {
+        return None;
+    };
+    ProgressOperation::ModelLoad
+        .phases()
+        .iter()
+        .position(|p| *p == phase)
+}
+
+/// Core model loading logic usable without an AppHandle (CLI / test support). The phases of
+/// the load are passed to `on_progress`, if given.
 pub async fn load_llama_model_impl(
     process_map_arc: Arc<Mutex<HashMap<i32, LLamaBackendSession>>>,
     backend_path: &str,
@@ -50,6 +73,44 @@ pub async fn load_llama_model_impl(
     mmproj_path: Option<String>,
     is_embedding: bool,
     timeout: u64,
+    on_progress: Option<ProgressSink>,
+) -> ServerResult<SessionInfo> {
+    let operation = ProgressOperation::ModelLoad;
+    let reporter = PhaseReporter::new(operation, &model_id, &model_id, on_progress);
+    reporter.phase(operation.phases()[0]);
+    let result = start_llama_server(
+        process_map_arc,
+        backend_path,
+        model_id,
+        model_path,
+        port,
+        config,
+        envs,
+        mmproj_path,
+        is_embedding,
+        timeout,
+        reporter.clone(),
+    )
+    .await;
+    reporter.finish(match result {
+        Ok(_) => ProgressState::Completed,
+        Err(_) => ProgressState::Failed,
+    });
+    result
+}
+
+async fn start_llama_server(
+    process_map_arc: Arc<Mutex<HashMap<i32, LLamaBackendSession>>>,
+    backend_path: &str,
+    model_id: String,
+    model_path: String,
+    port: u16,
+    config: LlamacppConfig,
+    envs: HashMap<String, String>,
+    mmproj_path: Option<String>,
+    is_embedding: bool,
+    timeout: u64,
+    reporter: PhaseReporter,
 ) -> ServerResult<SessionInfo> {
     let mut process_map = process_map_arc.lock().await;
 
@@ -57,6 +118,13 @@ pub async fn load_llama_model_impl(
     log::info!("Using configuration: {:?}", config);
 
     let bin_path = validate_binary_path(backend_path)?;
+    // Kept with the session so it can be loaded again, e.g. on the next launch
+    let load = LoadRequest {
+        backend_path: backend_path.to_string(),
+        config: config.clone(),
+        envs: envs.clone(),
+        timeout,
+    };
 
     // Build arguments using the ArgumentBuilder
     let builder = ArgumentBuilder::new(config.clone(), is_embedding)
@@ -163,6 +231,7 @@ pub async fn load_llama_model_impl(
         let mut reader = BufReader::new(stderr);
         let mut byte_buffer = Vec::new();
         let mut stderr_buffer = String::new();
+        let mut phase = 0;
 
         loop {
             byte_buffer.clear();
@@ -179,6 +248,11 @@ pub async fn load_llama_model_impl(
 
                         // Check for readiness indicator
                         let line_lower = line.to_string().to_lowercase();
+                        // Log lines of a phase can repeat later, so only move forward
+                        if let Some(next) = load_phase(&line_lower).filter(|next| *next > phase) {
+                            phase = next;
+                            reporter.phase(ProgressOperation::ModelLoad.phases()[next]);
+                        }
                         if line_lower.contains("server is listening on")
                             || line_lower.contains("starting the main loop")
                             || line_lower.contains("server listening on")
@@ -269,6 +343,7 @@ pub async fn load_llama_model_impl(
         LLamaBackendSession {
             child,
             info: session_info.clone(),
+            load,
         },
     );
 
@@ -290,19 +365,34 @@ pub async fn load_llama_model<R: Runtime>(
     timeout: u64,
 ) -> ServerResult<SessionInfo> {
     let state: State<LlamacppState> = app_handle.state();
-    load_llama_model_impl(
-        state.llama_server_process.clone(),
-        backend_path,
-        model_id,
-        model_path,
-        port,
-        config,
-        envs,
-        mmproj_path,
-        is_embedding,
-        timeout,
-    )
-    .await
+    let emitter = app_handle.clone();
+    let on_progress: ProgressSink = Arc::new(move |payload: ProgressPayload| {
+        if let Err(e) = emitter.emit(PROGRESS_EVENT, &payload) {
+            log::warn!("Failed to emit {PROGRESS_EVENT}: {e}");
+        }
+    });
+    let process_map = state.llama_server_process.clone();
+    let backend_path = backend_path.to_string();
+    let key = model_id.clone();
+    // A double click must not start a second server for the same model
+    state
+        .load_once(&key, async move {
+            load_llama_model_impl(
+                process_map,
+                &backend_path,
+                model_id,
+                model_path,
+                port,
+                config,
+                envs,
+                mmproj_path,
+                is_embedding,
+                timeout,
+                Some(on_progress),
+            )
+            .await
+        })
+        .await
 }
 
 /// Unload a llama model by terminating its process
@@ -409,3 +499,78 @@ pub async fn get_session_by_model<R: Runtime>(
 ) -> Result<Option<SessionInfo>, String> {
     find_session_by_model_id(app_handle, &model_id).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_phase_from_server_logs() {
+        let phase = |line: &str| {
+            load_phase(&line.to_lowercase()).map(|i| ProgressOperation::ModelLoad.phases()[i])
+        };
+        assert_eq!(
+            phase("load_tensors: loading model tensors, this can take a while..."),
+            Some("loading_weights")
+        );
+        assert_eq!(
+            phase("llama_context: constructing llama_context"),
+            Some("creating_context")
+        );
+        assert_eq!(
+            phase("common_init_from_params: warming up the model with an empty run"),
+            Some("warming_up")
+        );
+        assert_eq!(
+            phase("main: server is listening on http://127.0.0.1:3000"),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_loads_share_one_session() {
+        let state = LlamacppState::new();
+        let loads = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let load = |pid: i32| {
+            let loads = loads.clone();
+            async move {
+                loads.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                Ok(SessionInfo {
+                    pid,
+                    port: 3000,
+                    model_id: "llama".to_string(),
+                    model_path: "/models/llama.gguf".to_string(),
+                    is_embedding: false,
+                    api_key: String::new(),
+                    mmproj_path: None,
+                })
+            }
+        };
+
+        let (first, second) = tokio::join!(
+            state.load_once("llama", load(1)),
+            state.load_once("llama", load(2)),
+        );
+        assert_eq!(first.unwrap().pid, 1);
+        assert_eq!(second.unwrap().pid, 1);
+        assert_eq!(loads.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+        // A failed load is reported to every caller with its error code
+        let (first, second) = tokio::join!(
+            state.load_once("llama", async {
+                Err(ServerError::InvalidArgument("bad".to_string()))
+            }),
+            state.load_once("llama", load(3)),
+        );
+        for result in [first, second] {
+            assert!(matches!(
+                result,
+                Err(ServerError::Llamacpp(LlamacppError {
+                    code: ErrorCode::InvalidArgument,
+                    ..
+                }))
+            ));
+        }
+    }
+}
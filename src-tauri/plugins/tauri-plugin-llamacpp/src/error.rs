@@ -80,6 +80,12 @@ impl LlamacppError {
     }
 }
 
+impl From<String> for LlamacppError {
+    fn from(message: String) -> Self {
+        Self::new(ErrorCode::InternalError, message, None)
+    }
+}
+
 // Error type for server commands
 #[derive(Debug, thiserror::Error)]
 pub enum ServerError {
@@ -96,13 +102,10 @@ pub enum ServerError {
     InvalidArgument(String),
 }
 
-// impl serialization for tauri
-impl serde::Serialize for ServerError {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        let error_to_serialize: LlamacppError = match self {
+impl ServerError {
+    /// The structured error the frontend receives
+    pub fn to_llamacpp_error(&self) -> LlamacppError {
+        match self {
             ServerError::Llamacpp(err) => err.clone(),
             ServerError::Io(e) => LlamacppError::new(
                 ErrorCode::IoError,
@@ -119,8 +122,17 @@ impl serde::Serialize for ServerError {
                 "Invalid configuration argument provided.".into(),
                 Some(msg.clone()),
             ),
-        };
-        error_to_serialize.serialize(serializer)
+        }
+    }
+}
+
+// impl serialization for tauri
+impl serde::Serialize for ServerError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        self.to_llamacpp_error().serialize(serializer)
     }
 }
 
@@ -16,7 +16,10 @@ pub mod state;
 pub use args::LlamacppConfig;
 pub use cleanup::cleanup_llama_processes;
 pub use commands::load_llama_model_impl;
-pub use state::{LLamaBackendSession, LlamacppState};
+pub use gguf::types::GgufMetadata;
+pub use gguf::utils::read_gguf_metadata_internal as read_gguf_metadata;
+pub use process::get_random_available_port;
+pub use state::{LLamaBackendSession, LlamacppState, LoadRequest};
 
 /// Initializes the plugin.
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
@@ -1,9 +1,14 @@
+use jan_utils::InFlight;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
 use tokio::process::Child;
 use tokio::sync::Mutex;
 
+use crate::args::LlamacppConfig;
+use crate::error::{ServerError, ServerResult};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionInfo {
     pub pid: i32,  // opaque handle for unload/chat
@@ -16,20 +21,33 @@ pub struct SessionInfo {
     pub mmproj_path: Option<String>,
 }
 
+/// Arguments a session was loaded with, enough to load it again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoadRequest {
+    pub backend_path: String,
+    pub config: LlamacppConfig,
+    pub envs: HashMap<String, String>,
+    pub timeout: u64,
+}
+
 pub struct LLamaBackendSession {
     pub child: Child,
     pub info: SessionInfo,
+    pub load: LoadRequest,
 }
 
 /// LlamaCpp plugin state
 pub struct LlamacppState {
     pub llama_server_process: Arc<Mutex<HashMap<i32, LLamaBackendSession>>>,
+    /// Model loads running now, so a second load of a model waits for the first
+    pub loads_in_flight: InFlight<String>,
 }
 
 impl Default for LlamacppState {
     fn default() -> Self {
         Self {
             llama_server_process: Arc::new(Mutex::new(HashMap::new())),
+            loads_in_flight: InFlight::default(),
         }
     }
 }
@@ -38,4 +56,18 @@ impl LlamacppState {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Load `model_id` with `load`, unless a load of it is already running. Then that load's
+    /// session is returned instead of starting a second server.
+    pub async fn load_once<F>(&self, model_id: &str, load: F) -> ServerResult<SessionInfo>
+    where
+        F: Future<Output = ServerResult<SessionInfo>> + Send + 'static,
+    {
+        self.loads_in_flight
+            .run(format!("load of model {model_id}"), async move {
+                load.await.map_err(|e| e.to_llamacpp_error())
+            })
+            .await
+            .map_err(ServerError::Llamacpp)
+    }
 }
@@ -3,7 +3,7 @@ use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::sync::Arc;
 use std::time::Duration;
-use tauri::{Manager, Runtime, State};
+use tauri::{Emitter, Manager, Runtime, State};
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::Command;
 use tokio::sync::{mpsc, Mutex};
@@ -15,6 +15,9 @@ use crate::process::{
     get_random_available_port, is_process_running_by_pid,
 };
 use crate::state::{MlxBackendSession, MlxState, SessionInfo};
+use jan_utils::progress::{
+    PhaseReporter, ProgressOperation, ProgressPayload, ProgressSink, ProgressState, PROGRESS_EVENT,
+};
 
 #[cfg(unix)]
 use crate::process::graceful_terminate_process;
@@ -35,6 +38,7 @@ pub struct MlxConfig {
 /// Core model-loading logic, decoupled from Tauri AppHandle.
 /// `binary_path` must point to the mlx-server executable.
 /// `process_map_arc` is the shared session map from MlxState.
+/// The start and end of the load are passed to `on_progress`, if given.
 pub async fn load_mlx_model_impl(
     process_map_arc: Arc<Mutex<HashMap<i32, MlxBackendSession>>>,
     binary_path: &Path,
@@ -45,6 +49,41 @@ pub async fn load_mlx_model_impl(
     envs: HashMap<String, String>,
     is_embedding: bool,
     timeout: u64,
+    on_progress: Option<ProgressSink>,
+) -> ServerResult<SessionInfo> {
+    // mlx-server doesn't log its loading steps, so the load stays in its first phase
+    let operation = ProgressOperation::ModelLoad;
+    let reporter = PhaseReporter::new(operation, &model_id, &model_id, on_progress);
+    reporter.phase(operation.phases()[0]);
+    let result = start_mlx_server(
+        process_map_arc,
+        binary_path,
+        model_id,
+        model_path,
+        port,
+        config,
+        envs,
+        is_embedding,
+        timeout,
+    )
+    .await;
+    reporter.finish(match result {
+        Ok(_) => ProgressState::Completed,
+        Err(_) => ProgressState::Failed,
+    });
+    result
+}
+
+async fn start_mlx_server(
+    process_map_arc: Arc<Mutex<HashMap<i32, MlxBackendSession>>>,
+    binary_path: &Path,
+    model_id: String,
+    model_path: String,
+    port: u16,
+    config: MlxConfig,
+    envs: HashMap<String, String>,
+    is_embedding: bool,
+    timeout: u64,
 ) -> ServerResult<SessionInfo> {
     let mut process_map = process_map_arc.lock().await;
 
@@ -296,6 +335,12 @@ pub async fn load_mlx_model<R: Runtime>(
             )
         })?
         .join("resources/bin/mlx-server");
+    let emitter = app_handle.clone();
+    let on_progress: ProgressSink = Arc::new(move |payload: ProgressPayload| {
+        if let Err(e) = emitter.emit(PROGRESS_EVENT, &payload) {
+            log::warn!("Failed to emit {PROGRESS_EVENT}: {e}");
+        }
+    });
     load_mlx_model_impl(
         state.mlx_server_process.clone(),
         &binary_path,
@@ -306,6 +351,7 @@ pub async fn load_mlx_model<R: Runtime>(
         envs,
         is_embedding,
         timeout,
+        Some(on_progress),
     )
     .await
 }
@@ -8,6 +8,7 @@ mod error;
 mod commands;
 
 pub use error::RagError;
+pub use parser::{parse_document, parse_pdf_pages};
 
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("rag")
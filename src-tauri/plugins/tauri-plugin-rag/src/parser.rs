@@ -50,6 +50,18 @@ pub fn parse_pdf(file_path: &str) -> Result<String, RagError> {
     Ok(text)
 }
 
+/// Text of each page of a PDF, for locating passages of the text returned by `parse_pdf`
+pub fn parse_pdf_pages(file_path: &str) -> Result<Vec<String>, RagError> {
+    let bytes = fs::read(file_path)?;
+    match catch_unwind(AssertUnwindSafe(|| pdf_extract::extract_text_from_mem_by_pages(&bytes))) {
+        Ok(Ok(pages)) => Ok(pages),
+        Ok(Err(e)) => Err(RagError::ParseError(format!("PDF parse error: {}", e))),
+        Err(_) => Err(RagError::ParseError(
+            "PDF parsing failed unexpectedly".to_string(),
+        )),
+    }
+}
+
 pub fn parse_text(file_path: &str) -> Result<String, RagError> {
     read_text_auto(file_path)
 }
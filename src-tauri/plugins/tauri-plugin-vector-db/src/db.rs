@@ -554,6 +554,39 @@ pub fn list_attachments(
     Ok(out)
 }
 
+pub fn get_file(conn: &Connection, file_id: &str) -> Result<Option<AttachmentFileInfo>, VectorDBError> {
+    let file = conn
+        .prepare("SELECT id, path, name, type, size, chunk_count FROM files WHERE id = ?1")?
+        .query_row(params![file_id], |row| {
+            Ok(AttachmentFileInfo {
+                id: row.get(0)?,
+                path: row.get(1)?,
+                name: row.get(2)?,
+                file_type: row.get(3)?,
+                size: row.get(4)?,
+                chunk_count: row.get(5)?,
+            })
+        })
+        .optional()?;
+    Ok(file)
+}
+
+pub fn get_chunk(conn: &Connection, chunk_id: &str) -> Result<Option<SearchResult>, VectorDBError> {
+    let chunk = conn
+        .prepare("SELECT id, text, file_id, chunk_file_order FROM chunks WHERE id = ?1")?
+        .query_row(params![chunk_id], |row| {
+            Ok(SearchResult {
+                id: row.get(0)?,
+                text: row.get(1)?,
+                score: None,
+                file_id: row.get(2)?,
+                chunk_file_order: row.get(3)?,
+            })
+        })
+        .optional()?;
+    Ok(chunk)
+}
+
 // ============================================================================
 // Delete Operations
 // ============================================================================
@@ -5,7 +5,7 @@ use tauri::{
 };
 
 mod commands;
-mod db;
+pub mod db;
 mod error;
 mod state;
 mod utils;
@@ -1,7 +1,7 @@
 //! jan — headless CLI for Jan.
 //!
 //! Shares all core logic with the Jan desktop app.
-//! Build with: cargo build --features cli --bin jan
+//! Build with: cargo build --features cli --bin jan-cli
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -13,12 +13,17 @@ use indicatif::{ProgressBar, ProgressStyle};
 // Import the library crate so we can access core modules.
 // The lib target is named "app_lib" (see [lib] section in Cargo.toml).
 use app_lib::core::cli::{
-    cli_delete_thread, cli_get_config, cli_get_data_folder, cli_get_thread,
-    cli_list_messages, cli_list_threads, discover_llamacpp_binary,
+    cli_acquire_server_lock, cli_complete_onboarding_step, cli_delete_thread, cli_get_config,
+    cli_get_data_folder, cli_get_thread, cli_install_default_mcp_servers, cli_install_service,
+    cli_list_mcp_servers, cli_list_messages, cli_list_threads, cli_onboarding_state,
+    cli_record_model_load, cli_reset_onboarding, cli_service_status, cli_skip_onboarding_step,
+    cli_uninstall_service,
+    default_mcp_server_names, discover_llamacpp_binary,
     discover_mlx_binary, download_hf_model, fetch_hf_gguf_files, init_llamacpp_state,
     init_mlx_state, list_models, load_llama_model_impl, load_mlx_model_impl,
     looks_like_hf_repo, resolve_model_by_id, resolve_model_engine, HfFileInfo,
-    LlamacppConfig, MlxConfig,
+    LlamacppConfig, MlxConfig, OnboardingState, OnboardingStep, ServiceOptions, StepOutcome,
+    scan_hardware,
 };
 use std::path::PathBuf;
 
@@ -40,7 +45,9 @@ Models downloaded in the Jan desktop app are automatically available here.",
   jan serve qwen3.5-35b-a3b                       # expose a model at localhost:6767/v1\n  \
   jan serve qwen3.5-35b-a3b --fit                 # auto-fit context to available VRAM\n  \
   jan serve qwen3.5-35b-a3b --detach              # run in the background\n  \
-  jan models list                                 # show all installed models",
+  jan models list                                 # show all installed models\n  \
+  jan model pull unsloth/Qwen3-8B-GGUF            # download a GGUF model from HuggingFace\n  \
+  jan mcp list                                    # show configured MCP servers",
     version
 )]
 struct Cli {
@@ -93,24 +100,43 @@ enum Commands {
         #[arg(long, default_value_t = false)]
         select: bool,
     },
+    /// Walk through first-run setup: hardware scan, recommended model, default MCP servers
+    #[command(display_order = 3)]
+    Setup {
+        /// Start over instead of resuming where setup stopped
+        #[arg(long, default_value_t = false)]
+        reset: bool,
+    },
     /// List and inspect conversation threads saved by the Jan app
     #[command(display_order = 10)]
     Threads {
         #[command(subcommand)]
         cmd: ThreadsCommands,
     },
-    /// List and load models installed in the Jan data folder
-    #[command(display_order = 11)]
+    /// List, download, and load models installed in the Jan data folder
+    #[command(display_order = 11, visible_alias = "model")]
     Models {
         #[command(subcommand)]
         cmd: ModelsCommands,
     },
-    /// Show app configuration and data folder location
+    /// Inspect the MCP servers configured in the Jan app
     #[command(display_order = 12)]
+    Mcp {
+        #[command(subcommand)]
+        cmd: McpCommands,
+    },
+    /// Show app configuration and data folder location
+    #[command(display_order = 13)]
     App {
         #[command(subcommand)]
         cmd: AppCommands,
     },
+    /// Run `jan serve` as a background service that starts at login
+    #[command(display_order = 14)]
+    Service {
+        #[command(subcommand)]
+        cmd: ServiceCommands,
+    },
 }
 
 
@@ -130,7 +156,8 @@ enum ThreadsCommands {
         /// Thread ID
         id: String,
     },
-    /// Print all messages in a thread as JSON
+    /// Print all messages in a thread as JSON. Private threads need their passphrase in
+    /// `JAN_PRIVATE_PASSPHRASE`
     Messages {
         /// Thread ID
         thread_id: String,
@@ -200,6 +227,14 @@ enum ModelsCommands {
         #[arg(long, default_value = "all")]
         engine: String,
     },
+    /// Download a GGUF model from HuggingFace into the Jan data folder
+    Pull {
+        /// HuggingFace repo ID (e.g. unsloth/Qwen3-8B-GGUF)
+        repo_id: String,
+        /// Show the quantization selection list instead of picking Q4_K_XL
+        #[arg(long, default_value_t = false)]
+        select: bool,
+    },
     /// Load a model and serve it — alias for the top-level `serve` command
     Load {
         #[command(flatten)]
@@ -234,6 +269,14 @@ enum ModelsCommands {
     },
 }
 
+// ── MCP subcommands ────────────────────────────────────────────────────────
+
+#[derive(Subcommand)]
+enum McpCommands {
+    /// Print all configured MCP servers as JSON
+    List,
+}
+
 // ── App subcommands ────────────────────────────────────────────────────────
 
 #[derive(Subcommand)]
@@ -244,6 +287,33 @@ enum AppCommands {
     Config,
 }
 
+// ── Service subcommands ─────────────────────────────────────────────────────
+
+#[derive(Subcommand)]
+enum ServiceCommands {
+    /// Install and start the service (launchd agent, systemd user unit or Windows logon task)
+    Install {
+        /// Model ID to serve
+        model_id: String,
+        /// Port the model server listens on
+        #[arg(long, default_value_t = 6767)]
+        port: u16,
+        /// API key required by clients
+        #[arg(long, default_value = "")]
+        api_key: String,
+        /// Context window size in tokens (default: `jan serve`'s)
+        #[arg(long)]
+        ctx_size: Option<i32>,
+        /// Auto-fit context to available VRAM
+        #[arg(long, default_value_t = false)]
+        fit: bool,
+    },
+    /// Stop and remove the service
+    Uninstall,
+    /// Print whether the service is installed and which server is running, as JSON
+    Status,
+}
+
 // ── ASCII logo ─────────────────────────────────────────────────────────────
 
 /// Build a left-aligned, bright-yellow ASCII logo for the help header.
@@ -300,7 +370,10 @@ async fn main() {
     match cli.command {
         Commands::Threads { cmd } => handle_threads(cmd).await,
         Commands::Models { cmd } => handle_models(cmd).await,
+        Commands::Mcp { cmd } => handle_mcp(cmd),
         Commands::App { cmd } => handle_app(cmd),
+        Commands::Service { cmd } => handle_service(cmd),
+        Commands::Setup { reset } => handle_setup(reset).await,
         Commands::Serve { args } => handle_serve(args).await,
         Commands::Launch { program, program_args, model, bin, port, api_key, n_gpu_layers, ctx_size, fit, verbose, select } => {
             let program = program.unwrap_or_else(select_program_interactively);
@@ -341,7 +414,10 @@ async fn handle_threads(cmd: ThreadsCommands) {
             }
         },
 
-        ThreadsCommands::Messages { thread_id } => match cli_list_messages(&thread_id) {
+        ThreadsCommands::Messages { thread_id } => match cli_list_messages(
+            &thread_id,
+            private_passphrase().as_deref(),
+        ) {
             Ok(messages) => println!("{}", serde_json::to_string_pretty(&messages).unwrap()),
             Err(e) => {
                 eprintln!("Error: {e}");
@@ -378,6 +454,15 @@ async fn handle_models(cmd: ModelsCommands) {
             println!("{}", serde_json::to_string_pretty(&output).unwrap());
         }
 
+        ModelsCommands::Pull { repo_id, select } => {
+            if !looks_like_hf_repo(&repo_id) {
+                eprintln!("Error: '{repo_id}' is not a HuggingFace repo ID (expected owner/repo)");
+                std::process::exit(1);
+            }
+            let model_id = auto_download_hf_model(&repo_id, select).await;
+            println!("{}", serde_json::json!({ "id": model_id, "engine": "llamacpp" }));
+        }
+
         ModelsCommands::Load { args } => handle_serve(args).await,
 
         ModelsCommands::LoadMlx {
@@ -428,17 +513,21 @@ async fn handle_models(cmd: ModelsCommands) {
             match load_mlx_model_impl(
                 mlx_state.mlx_server_process.clone(),
                 Path::new(&bin_path),
-                model_id,
+                model_id.clone(),
                 resolved_model_path,
                 port,
                 MlxConfig { ctx_size },
                 envs,
                 embedding,
                 timeout,
+                None,
             )
             .await
             {
-                Ok(info) => println!("{}", serde_json::to_string_pretty(&info).unwrap()),
+                Ok(info) => {
+                    cli_record_model_load(&model_id);
+                    println!("{}", serde_json::to_string_pretty(&info).unwrap())
+                }
                 Err(e) => {
                     eprintln!(
                         "Error loading MLX model:\n{}",
@@ -491,6 +580,13 @@ fn finish_progress(pb: Option<ProgressBar>, msg: impl AsRef<str>) {
 
 // ── HuggingFace auto-download ──────────────────────────────────────────────
 
+/// Read the private thread passphrase from `JAN_PRIVATE_PASSPHRASE`.
+fn private_passphrase() -> Option<String> {
+    std::env::var("JAN_PRIVATE_PASSPHRASE")
+        .ok()
+        .filter(|s| !s.is_empty())
+}
+
 /// Read `HF_TOKEN` or `HUGGING_FACE_HUB_TOKEN` from the environment.
 fn hf_token() -> Option<String> {
     std::env::var("HF_TOKEN")
@@ -821,6 +917,12 @@ async fn handle_serve(args: ServeArgs) {
         return;
     }
 
+    // One server per data folder, whether started by hand or by the service
+    let _lock = cli_acquire_server_lock(&model_id, args.port).unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    });
+
     let ServeArgs {
         model_id: _,
         model_path,
@@ -911,10 +1013,12 @@ async fn handle_serve(args: ServeArgs) {
             envs,
             embedding,
             timeout,
+            None,
         )
         .await
         {
             Ok(info) => {
+                cli_record_model_load(&model_id);
                 let url = format!("http://127.0.0.1:{}", info.port);
                 finish_progress(pb, format!("✓ {model_id} ready · {url}"));
                 eprintln!();
@@ -965,10 +1069,12 @@ async fn handle_serve(args: ServeArgs) {
             resolved_mmproj,
             embedding,
             timeout,
+            None,
         )
         .await
         {
             Ok(info) => {
+                cli_record_model_load(&model_id);
                 let url = format!("http://127.0.0.1:{}", info.port);
                 finish_progress(pb, format!("✓ {model_id} ready · {url}"));
                 eprintln!();
@@ -1263,6 +1369,7 @@ async fn start_model_server(
             envs,
             false,
             120,
+            None,
         ).await {
             Ok(info) => info,
             Err(e) => {
@@ -1271,6 +1378,7 @@ async fn start_model_server(
                 std::process::exit(1);
             }
         };
+        cli_record_model_load(&model_id);
         let url = format!("http://127.0.0.1:{}", info.port);
         finish_progress(pb, format!("✓ {model_id} ready · {url}"));
         (info.pid, info.port as u16)
@@ -1300,6 +1408,7 @@ async fn start_model_server(
             mmproj.map(|p| p.to_string_lossy().into_owned()),
             false,
             120,
+            None,
         ).await {
             Ok(info) => info,
             Err(e) => {
@@ -1308,6 +1417,7 @@ async fn start_model_server(
                 std::process::exit(1);
             }
         };
+        cli_record_model_load(&model_id);
         let url = format!("http://127.0.0.1:{}", info.port);
         finish_progress(pb, format!("✓ {model_id} ready · {url}"));
         (info.pid, info.port as u16)
@@ -1356,6 +1466,27 @@ fn build_llamacpp_config(n_gpu_layers: i32, ctx_size: i32, timeout: i32, fit: bo
         rope_freq_base: 0.0,
         rope_freq_scale: 0.0,
         ctx_shift: false,
+        lora_adapters: Vec::new(),
+        draft_model_path: String::new(),
+        draft_max: 0,
+        draft_min: 0,
+        draft_p_min: 0.0,
+        slot_save_path: String::new(),
+        reranking: false,
+    }
+}
+
+// ── MCP handlers ───────────────────────────────────────────────────────────
+
+fn handle_mcp(cmd: McpCommands) {
+    match cmd {
+        McpCommands::List => match cli_list_mcp_servers() {
+            Ok(servers) => println!("{}", serde_json::to_string_pretty(&servers).unwrap()),
+            Err(e) => {
+                eprintln!("Error: {e}");
+                std::process::exit(1);
+            }
+        },
     }
 }
 
@@ -1381,3 +1512,127 @@ fn handle_app(cmd: AppCommands) {
         },
     }
 }
+
+// ── Service handlers ───────────────────────────────────────────────────────
+
+fn handle_service(cmd: ServiceCommands) {
+    let result = match cmd {
+        ServiceCommands::Install { model_id, port, api_key, ctx_size, fit } => {
+            let options = ServiceOptions { model_id, port, api_key, ctx_size, fit };
+            cli_install_service(&options).map(|status| serde_json::to_value(status).unwrap())
+        }
+        ServiceCommands::Uninstall => {
+            cli_uninstall_service().map(|()| serde_json::json!({ "installed": false }))
+        }
+        ServiceCommands::Status => Ok(serde_json::to_value(cli_service_status()).unwrap()),
+    };
+    match result {
+        Ok(value) => println!("{}", serde_json::to_string_pretty(&value).unwrap()),
+        Err(e) => {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+// ── Setup handlers ─────────────────────────────────────────────────────────
+
+/// Run the setup steps left, resuming where the app or an earlier run stopped.
+async fn handle_setup(reset: bool) {
+    if reset {
+        if let Err(e) = cli_reset_onboarding().await {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    }
+    loop {
+        let state = cli_onboarding_state();
+        let result = match state.step {
+            OnboardingStep::HardwareScan => {
+                let spinner = make_spinner("Scanning hardware…".to_string());
+                let hardware = scan_hardware();
+                spinner.finish_and_clear();
+                eprintln!("  Memory  {}", fmt_bytes(hardware.total_memory_mb * 1024 * 1024));
+                for gpu in &hardware.gpus {
+                    eprintln!("  GPU     {} ({})", gpu.name, fmt_bytes(gpu.vram_mb * 1024 * 1024));
+                }
+                cli_complete_onboarding_step(StepOutcome::HardwareScan { hardware }).await
+            }
+            OnboardingStep::ModelDownload => setup_download_model(&state).await,
+            OnboardingStep::ProviderKey => {
+                eprintln!("  Provider API keys are entered in the Jan app; skipping.");
+                cli_skip_onboarding_step(OnboardingStep::ProviderKey).await
+            }
+            OnboardingStep::McpServers => setup_mcp_servers().await,
+            OnboardingStep::Done => {
+                eprintln!("  ✓ Setup complete");
+                return;
+            }
+        };
+        if let Err(e) = result {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn setup_download_model(state: &OnboardingState) -> Result<OnboardingState, String> {
+    let model = state
+        .recommended_model
+        .clone()
+        .ok_or("No model was recommended; run `jan setup --reset`")?;
+    let download = dialoguer::Confirm::new()
+        .with_prompt(format!(
+            "Download the recommended model {} (about {})?",
+            model.model_id,
+            fmt_bytes(model.approx_size_bytes)
+        ))
+        .default(true)
+        .interact()
+        .unwrap_or(false);
+    if !download {
+        return cli_skip_onboarding_step(OnboardingStep::ModelDownload).await;
+    }
+
+    let file = HfFileInfo {
+        filename: model.filename.clone(),
+        size: model.approx_size_bytes,
+        sha256: None,
+        download_url: model.url.clone(),
+    };
+    let dl_pb = ProgressBar::new(model.approx_size_bytes);
+    dl_pb.set_style(
+        ProgressStyle::default_bar()
+            .template(
+                "  {bar:45.yellow/dim}  {bytes:>9}/{total_bytes}  {bytes_per_sec}  eta {eta}",
+            )
+            .unwrap()
+            .progress_chars("█▉▊▋▌▍▎▏  "),
+    );
+    let dl_pb_clone = dl_pb.clone();
+    let token = hf_token();
+    let model_id = download_hf_model(&model.model_id, &file, token.as_deref(), move |done, total| {
+        dl_pb_clone.set_length(total);
+        dl_pb_clone.set_position(done);
+    })
+    .await;
+    dl_pb.finish_and_clear();
+    let model_id = model_id?;
+    eprintln!("  ✓ {model_id} saved to Jan data folder");
+    cli_complete_onboarding_step(StepOutcome::ModelDownload { model_id }).await
+}
+
+async fn setup_mcp_servers() -> Result<OnboardingState, String> {
+    let names = default_mcp_server_names();
+    let chosen = dialoguer::MultiSelect::new()
+        .with_prompt("Default MCP servers to install (space to select, enter to confirm)")
+        .items(&names)
+        .interact()
+        .unwrap_or_default();
+    if chosen.is_empty() {
+        return cli_skip_onboarding_step(OnboardingStep::McpServers).await;
+    }
+    let servers: Vec<String> = chosen.into_iter().map(|i| names[i].clone()).collect();
+    cli_install_default_mcp_servers(&servers)?;
+    cli_complete_onboarding_step(StepOutcome::McpServers { servers }).await
+}
@@ -0,0 +1,103 @@
+//! Tools implemented in the core and offered to the agent loop next to MCP tools. They are
+//! listed under the `BUILTIN_TOOL_SERVER` pseudo server so approval policies can address them.
+
+use std::path::Path;
+
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Runtime};
+
+use super::constants::BUILTIN_TOOL_SERVER;
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::code_exec::constants::RUN_CODE_TOOL;
+use crate::core::code_exec::helpers::{
+    format_for_model as format_run_for_model, read_settings as read_code_exec_settings,
+    request_from_arguments, run_code, run_code_tool,
+};
+use crate::core::mcp::models::ToolWithServer;
+use crate::core::offline::constants::OFFLINE_ERROR;
+use crate::core::offline::helpers::{check_url, is_offline};
+use crate::core::search::constants::WEB_SEARCH_TOOL;
+use crate::core::search::helpers::{
+    format_for_model, query_from_arguments, read_settings as read_search_settings, run_search,
+    web_search_tool,
+};
+use crate::core::tool_artifacts::constants::READ_ARTIFACT_TOOL;
+use crate::core::tool_artifacts::helpers::{
+    call_read_artifact_tool, read_artifact_tool, read_settings as read_artifact_settings,
+};
+use crate::core::web_fetch::constants::WEB_FETCH_TOOL;
+use crate::core::web_fetch::helpers::{
+    fetch_page, format_for_model as format_page_for_model, read_settings as read_fetch_settings,
+    url_from_arguments, web_fetch_tool,
+};
+use crate::core::workspaces::constants::{LIST_DIRECTORY_TOOL, READ_FILE_TOOL};
+use crate::core::workspaces::helpers::{call_workspace_tool, workspace_tools};
+
+/// Built-in tools that are enabled and not shadowed by an MCP tool of the same name. File tools
+/// are only offered when the run has a `workspace`, web tools only while online.
+pub fn builtin_tools<R: Runtime>(
+    app: &AppHandle<R>,
+    mcp_tools: &[ToolWithServer],
+    workspace: Option<&Path>,
+) -> Vec<ToolWithServer> {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let mut tools = Vec::new();
+
+    let online = !is_offline(app);
+    let search = read_search_settings(&data_folder);
+    if online && search.builtin_tool_enabled && search.engine.is_some() {
+        tools.push(web_search_tool(BUILTIN_TOOL_SERVER));
+    }
+    if online && read_fetch_settings(&data_folder).builtin_tool_enabled {
+        tools.push(web_fetch_tool(BUILTIN_TOOL_SERVER));
+    }
+    if read_code_exec_settings(&data_folder).builtin_tool_enabled {
+        tools.push(run_code_tool(BUILTIN_TOOL_SERVER));
+    }
+    if workspace.is_some() {
+        tools.extend(workspace_tools(BUILTIN_TOOL_SERVER));
+    }
+    if read_artifact_settings(&data_folder).enabled {
+        tools.push(read_artifact_tool(BUILTIN_TOOL_SERVER));
+    }
+
+    tools.retain(|tool| !mcp_tools.iter().any(|t| t.name == tool.name));
+    tools
+}
+
+/// Run a built-in tool and return the text fed back to the model.
+pub async fn call_builtin_tool<R: Runtime>(
+    app: &AppHandle<R>,
+    name: &str,
+    arguments: &Map<String, Value>,
+    workspace: Option<&Path>,
+) -> Result<String, String> {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    match name {
+        WEB_SEARCH_TOOL => {
+            if is_offline(app) {
+                return Err(OFFLINE_ERROR.to_string());
+            }
+            let query = query_from_arguments(arguments)?;
+            let response = run_search(&read_search_settings(&data_folder), query).await?;
+            Ok(format_for_model(&response))
+        }
+        WEB_FETCH_TOOL => {
+            let url = url_from_arguments(arguments)?;
+            check_url(app, &url)?;
+            let page = fetch_page(&data_folder, &url, false).await?;
+            Ok(format_page_for_model(&page))
+        }
+        RUN_CODE_TOOL => {
+            let request = request_from_arguments(arguments)?;
+            let result = run_code(&data_folder, request).await?;
+            Ok(format_run_for_model(&result))
+        }
+        READ_ARTIFACT_TOOL => call_read_artifact_tool(&data_folder, arguments),
+        LIST_DIRECTORY_TOOL | READ_FILE_TOOL => {
+            let root = workspace.ok_or_else(|| "This thread has no workspace".to_string())?;
+            call_workspace_tool(root, name, arguments)
+        }
+        _ => Err(format!("Unknown built-in tool '{name}'")),
+    }
+}
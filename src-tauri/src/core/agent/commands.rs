@@ -0,0 +1,852 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use futures::future::join_all;
+use rmcp::model::CallToolRequestParam;
+use serde_json::{json, Value};
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Manager, Runtime, State};
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
+use uuid::Uuid;
+
+use super::builtin_tools::{builtin_tools, call_builtin_tool};
+use super::constants::{
+    BUILTIN_TOOL_SERVER, DEFAULT_MAX_ITERATIONS, MAX_ITERATIONS_LIMIT, MODEL_TURN_TIMEOUT_SECS,
+};
+use super::helpers::{
+    assistant_message, emit_agent_event, emit_tool_call_delta, parse_tool_arguments,
+    stream_model_turn, tool_result_text, tools_to_openai,
+};
+use super::models::{
+    AgentEvent, AgentRunRequest, AgentRunResult, AgentStopReason, ProviderFailure, ToolCall,
+    TranscriptEntry, TranscriptSummary, TurnDelta, TurnError, TurnTranscript,
+};
+use super::parallel::{acquire_server_permit, plan_waves, sequential_requested};
+use super::transcript::{
+    delete_transcript, list_transcripts, read_transcript, save_transcript, TranscriptRecorder,
+};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::approvals::{helpers::authorize_tool_call, ToolApprovalState};
+use crate::core::assistants::helpers::resolve_tool_scope;
+use crate::core::cancellation::models::CancelScope;
+use crate::core::context::helpers::check_context;
+use crate::core::context::models::{ContextBudgetRequest, ContextCheck};
+use crate::core::guardrails::constants::AGENT_BUDGET_KEY;
+use crate::core::guardrails::Guardrails;
+use crate::core::inference::errors::content_filter_error;
+use crate::core::inference::helpers::resolve_model_endpoint;
+use crate::core::inference::models::{ModelEndpoint, Recovery, TokenUsage};
+use crate::core::inference::native_tools::{apply_native_tools, authorize_native_tools};
+use crate::core::inference::reasoning::{context_reasoning, store_reasoning};
+use crate::core::inference::tool_schema::compact_tools;
+use crate::core::mcp::helpers::{call_tool_on_server, collect_tools};
+use crate::core::mcp::models::ToolWithServer;
+use crate::core::notifications::helpers::notify_generation_finished;
+use crate::core::param_profiles::helpers::resolved_parameters;
+use crate::core::param_profiles::models::ResolveParamsRequest;
+use crate::core::plugins::{models::MessageHook, runtime::run_message_hooks};
+use crate::core::redaction::helpers::{attach_report, redactor_for_endpoint};
+use crate::core::scheduler::constants::PREEMPTED_ERROR;
+use crate::core::scheduler::helpers::acquire_for_endpoint;
+use crate::core::scheduler::models::GenerationPriority;
+use crate::core::settings::helpers::reasoning_settings;
+use crate::core::state::AppState;
+use crate::core::streaming::helpers::TokenStreamer;
+use crate::core::streaming::models::TokenChunk;
+use crate::core::telemetry::helpers::{record, record_usage};
+use crate::core::telemetry::models::Metric;
+use crate::core::tool_artifacts::helpers::tool_result_for_model;
+use crate::core::vision::helpers::{limits_for_endpoint, preprocess_messages_async};
+use crate::core::workspaces::helpers::{scope_tool_arguments, workspace_for_thread};
+
+/// Execute one tool call and return the text fed back to the model and whether it failed.
+/// Returns `None` when the run is cancelled.
+async fn execute_tool_call<R: Runtime>(
+    app: &AppHandle<R>,
+    call: &ToolCall,
+    tools: &HashMap<String, ToolWithServer>,
+    assistant_id: Option<&str>,
+    workspace: Option<&Path>,
+    timeout_duration: Duration,
+    cancel: &CancellationToken,
+) -> Option<(String, bool)> {
+    let Some(tool) = tools.get(&call.name) else {
+        return Some((format!("Tool '{}' is not available", call.name), true));
+    };
+    let server = &tool.server;
+    let arguments = match parse_tool_arguments(&call.arguments) {
+        Ok(arguments) => arguments,
+        Err(e) => return Some((e, true)),
+    };
+    // Scoped before approval so the user sees the paths that will actually be used
+    let arguments = match workspace {
+        Some(root) => match scope_tool_arguments(root, &tool.input_schema, arguments) {
+            Ok(scoped) => scoped,
+            Err(e) => return Some((e, true)),
+        },
+        None => arguments,
+    };
+
+    let approvals = app.state::<ToolApprovalState>();
+    if let Err(e) = authorize_tool_call(
+        app,
+        &approvals,
+        server,
+        &call.name,
+        Some(&arguments),
+        assistant_id,
+        Some(cancel),
+    )
+    .await
+    {
+        if cancel.is_cancelled() {
+            return None;
+        }
+        return Some((e, true));
+    }
+
+    // Taken after approval, so a call waiting for the user doesn't hold a slot
+    let _permit = tokio::select! {
+        permit = acquire_server_permit(server) => match permit {
+            Ok(permit) => permit,
+            Err(e) => return Some((e, true)),
+        },
+        _ = cancel.cancelled() => return None,
+    };
+
+    if server == BUILTIN_TOOL_SERVER {
+        let result = tokio::select! {
+            result = call_builtin_tool(app, &call.name, &arguments, workspace) => result,
+            _ = cancel.cancelled() => return None,
+        };
+        record(app, Metric::ToolCall);
+        return Some(match result {
+            Ok(text) => (text, false),
+            Err(e) => {
+                record(app, Metric::ToolCallFailed);
+                (e, true)
+            }
+        });
+    }
+
+    let state = app.state::<AppState>();
+    let params = CallToolRequestParam {
+        name: call.name.clone().into(),
+        arguments: Some(arguments),
+    };
+    let result = tokio::select! {
+        result = call_tool_on_server(&state.mcp_servers, server, params, timeout_duration) => result,
+        _ = cancel.cancelled() => return None,
+    };
+    let (text, is_error) = match result {
+        Ok(result) => (tool_result_text(&result), result.is_error == Some(true)),
+        Err(e) => (e, true),
+    };
+    record(app, Metric::ToolCall);
+    if is_error {
+        record(app, Metric::ToolCallFailed);
+    }
+    Some((text, is_error))
+}
+
+/// Emit an agent event and add it to the run's transcript.
+fn emit_recorded<R: Runtime>(
+    app: &AppHandle<R>,
+    recorder: &TranscriptRecorder,
+    event: &AgentEvent,
+) {
+    recorder.record(event);
+    emit_agent_event(app, event);
+}
+
+/// Tool definitions as advertised to `endpoint`, compacted to its schema limits
+fn tools_for_endpoint(tools: &[Value], endpoint: &ModelEndpoint) -> Vec<Value> {
+    let mut tools = tools.to_vec();
+    if let Some(limits) = &endpoint.tool_schema {
+        let saved = compact_tools(&mut tools, limits);
+        if saved > 0 {
+            log::debug!(
+                "Compacted tool schemas for '{}' by {saved} bytes",
+                endpoint.model_id
+            );
+        }
+    }
+    tools
+}
+
+/// Resolve the next failover model through the router, skipping models it can't resolve
+async fn next_failover_endpoint<R: Runtime>(
+    app: &AppHandle<R>,
+    failover: &mut VecDeque<String>,
+) -> Option<ModelEndpoint> {
+    while let Some(model) = failover.pop_front() {
+        match resolve_model_endpoint(app, &model).await {
+            Ok(endpoint) => return Some(endpoint),
+            Err(e) => log::warn!("Skipping failover model '{model}': {e}"),
+        }
+    }
+    None
+}
+
+async fn run_agent_loop<R: Runtime>(
+    app: &AppHandle<R>,
+    run_id: &str,
+    request: AgentRunRequest,
+    cancel: &CancellationToken,
+    streamer: Option<&TokenStreamer>,
+    recorder: &TranscriptRecorder,
+) -> Result<AgentRunResult, String> {
+    let max_iterations = request
+        .max_iterations
+        .unwrap_or(DEFAULT_MAX_ITERATIONS)
+        .clamp(1, MAX_ITERATIONS_LIMIT);
+    let mut endpoint = resolve_model_endpoint(app, &request.model).await?;
+    let mut redactor = redactor_for_endpoint(app, &endpoint, request.thread_id.as_deref());
+    // Tried in order, each at most once, when a model fails in a way failover can fix
+    let mut failover: VecDeque<String> = endpoint.failover_models.iter().cloned().collect();
+
+    let state = app.state::<AppState>();
+    let turn_scope = CancelScope::Turn(run_id.to_string());
+    let timeout_duration = state.mcp_settings.lock().await.tool_call_timeout_duration();
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let scope = resolve_tool_scope(&data_folder, request.assistant_id.as_deref())?;
+    let workspace = request
+        .thread_id
+        .as_deref()
+        .and_then(|thread_id| workspace_for_thread(&data_folder, thread_id));
+    let mut tools = collect_tools(app, timeout_duration, scope.as_ref()).await;
+    let builtin = builtin_tools(app, &tools, workspace.as_deref());
+    tools.extend(builtin.into_iter().filter(|tool| {
+        scope
+            .as_ref()
+            .map_or(true, |s| s.permits(&tool.server, &tool.name))
+    }));
+    let tools_by_name: HashMap<String, ToolWithServer> =
+        tools.iter().map(|t| (t.name.clone(), t.clone())).collect();
+    let declared_tools = tools_to_openai(&tools);
+    let mut openai_tools = tools_for_endpoint(&declared_tools, &endpoint);
+    // Provider-native tools are authorized once per run; the provider runs them without
+    // asking again
+    let mut native_tools = match &endpoint.native_tools {
+        Some(native) => {
+            let mut native = native.clone();
+            let server = native.server();
+            native.tools.retain(|tool| {
+                scope
+                    .as_ref()
+                    .map_or(true, |s| s.permits(&server, tool.kind.name()))
+            });
+            let allowed =
+                authorize_native_tools(app, &native, request.assistant_id.as_deref(), Some(cancel))
+                    .await;
+            Some((native.dialect, allowed))
+        }
+        None => None,
+    };
+    let parameters = resolved_parameters(
+        app,
+        ResolveParamsRequest {
+            model: request.model.clone(),
+            assistant_id: request.assistant_id.clone(),
+            thread_id: request.thread_id.clone(),
+            parameters: request.parameters.clone(),
+        },
+    );
+
+    let mut conversation = request.messages.clone();
+    if let Some(last) = conversation.last_mut() {
+        if last.get("role").and_then(Value::as_str) == Some("user") {
+            *last = run_message_hooks(app, MessageHook::BeforeSend, last.take()).await;
+        }
+    }
+    // Oversized attachments fail the run here rather than at the provider
+    let (mut conversation, _) =
+        preprocess_messages_async(conversation, limits_for_endpoint(&endpoint)).await?;
+    let retention = reasoning_settings(app).retention;
+    context_reasoning(&mut conversation, retention);
+    let mut produced = Vec::new();
+    let mut content = String::new();
+    let mut usage = TokenUsage::default();
+    let finish = |iterations: usize,
+                  stop_reason: AgentStopReason,
+                  produced: Vec<Value>,
+                  content: String,
+                  usage: TokenUsage| {
+        emit_recorded(
+            app,
+            recorder,
+            &AgentEvent::Finished {
+                run_id: run_id.to_string(),
+                stop_reason: stop_reason.clone(),
+                iterations,
+            },
+        );
+        AgentRunResult {
+            run_id: run_id.to_string(),
+            messages: produced,
+            content,
+            iterations,
+            stop_reason,
+            context_overflow: None,
+            provider_error: None,
+            usage,
+        }
+    };
+
+    for iteration in 1..=max_iterations {
+        if cancel.is_cancelled() {
+            return Ok(finish(
+                iteration - 1,
+                AgentStopReason::Cancelled,
+                produced,
+                content,
+                usage,
+            ));
+        }
+        emit_recorded(
+            app,
+            recorder,
+            &AgentEvent::StepStarted {
+                run_id: run_id.to_string(),
+                iteration,
+            },
+        );
+
+        let mut retries = 0;
+        let (turn, redaction) = loop {
+            let mut body = Value::Object(parameters.clone());
+            body["messages"] = Value::Array(conversation.clone());
+            if !openai_tools.is_empty() {
+                body["tools"] = Value::Array(openai_tools.clone());
+            }
+            if let Some((dialect, tools)) = &native_tools {
+                apply_native_tools(&mut body, *dialect, tools);
+            }
+            if let Some(context_size) = request.context_size {
+                let check = check_context(&ContextBudgetRequest {
+                    body: body.clone(),
+                    context_size,
+                    reserve_tokens: None,
+                    alternative_models: Vec::new(),
+                });
+                if let ContextCheck::Overflow(overflow) = check {
+                    // Stop before the provider rejects the request; the caller picks a remediation
+                    let mut result = finish(
+                        iteration - 1,
+                        AgentStopReason::ContextOverflow,
+                        produced,
+                        content,
+                        usage,
+                    );
+                    result.context_overflow = Some(overflow);
+                    return Ok(result);
+                }
+            }
+            // A runaway loop stops here once the hourly token budget is spent
+            if let Some(guardrails) = app.try_state::<Guardrails>() {
+                guardrails.check(AGENT_BUDGET_KEY, &mut body)?;
+            }
+            // Redacted ahead of `build_request` to learn what was removed, which is attached
+            // to the reply
+            let redaction = redactor
+                .as_ref()
+                .map(|redactor| redactor.redact_body(&mut body))
+                .filter(|report| !report.is_empty());
+            // Hold the local engine only for the model turn, not while tools run
+            let permit = tokio::select! {
+                permit = acquire_for_endpoint(app, &endpoint, run_id, request.priority) => permit?,
+                _ = cancel.cancelled() => {
+                    return Ok(finish(
+                        iteration - 1,
+                        AgentStopReason::Cancelled,
+                        produced,
+                        content,
+                        usage,
+                    ));
+                }
+            };
+            let preempted = permit
+                .as_ref()
+                .map(|p| p.preempted().clone())
+                .unwrap_or_default();
+            // Deltas already reached the UI, so a turn that breaks off after them isn't redone
+            let mut streamed = false;
+            let chat_span = tracing::info_span!(
+                "llm.chat",
+                iteration,
+                "gen_ai.request.model" = %endpoint.model_id,
+                local = endpoint.is_local,
+                "gen_ai.usage.input_tokens" = tracing::field::Empty,
+                "gen_ai.usage.output_tokens" = tracing::field::Empty,
+            );
+            let turn = tokio::select! {
+                turn = stream_model_turn(
+                    &endpoint,
+                    body,
+                    request.thread_id.as_deref(),
+                    Duration::from_secs(MODEL_TURN_TIMEOUT_SECS),
+                    cancel,
+                    |delta| {
+                        streamed = true;
+                        match (delta, streamer) {
+                            (TurnDelta::Content(delta), Some(streamer)) => {
+                                recorder.record(&AgentEvent::ContentDelta {
+                                    run_id: run_id.to_string(),
+                                    delta: delta.to_string(),
+                                });
+                                streamer.push(delta)
+                            }
+                            (TurnDelta::Content(delta), None) => emit_recorded(
+                                app,
+                                recorder,
+                                &AgentEvent::ContentDelta {
+                                    run_id: run_id.to_string(),
+                                    delta: delta.to_string(),
+                                },
+                            ),
+                            (TurnDelta::Reasoning(delta), _) => emit_recorded(
+                                app,
+                                recorder,
+                                &AgentEvent::ReasoningDelta {
+                                    run_id: run_id.to_string(),
+                                    delta: delta.to_string(),
+                                },
+                            ),
+                            // Not recorded: the transcript keeps the complete call instead
+                            (TurnDelta::ToolCall { index, call, fragment }, _) => {
+                                emit_tool_call_delta(app, run_id, index, call, fragment)
+                            }
+                        }
+                    },
+                ).instrument(chat_span.clone()) => turn,
+                _ = preempted.cancelled() => Err(TurnError::Other(PREEMPTED_ERROR.to_string())),
+            };
+            drop(permit);
+            match &turn {
+                Ok(turn) => {
+                    chat_span.record("gen_ai.usage.input_tokens", turn.usage.input_tokens);
+                    chat_span.record("gen_ai.usage.output_tokens", turn.usage.output_tokens);
+                }
+                Err(e) => tracing::error!(parent: &chat_span, error = %e, "Model turn failed"),
+            }
+            let error = match turn {
+                Ok(turn) => {
+                    let produced_output = !turn.content.is_empty() || !turn.tool_calls.is_empty();
+                    match content_filter_error(turn.finish_reason.as_deref(), produced_output) {
+                        Some(error) => error,
+                        None => break (turn, redaction),
+                    }
+                }
+                Err(_) if cancel.is_cancelled() => {
+                    return Ok(finish(
+                        iteration,
+                        AgentStopReason::Cancelled,
+                        produced,
+                        content,
+                        usage,
+                    ));
+                }
+                Err(TurnError::Provider(error)) if !streamed => error,
+                Err(e) => return Err(e.to_string()),
+            };
+
+            match error.recovery() {
+                Recovery::Backoff if retries < endpoint.policy.max_retries => {
+                    let delay = error.retry_delay(&endpoint.policy, retries);
+                    retries += 1;
+                    log::warn!(
+                        "Model '{}' failed: {error}, retrying in {delay:?} ({retries}/{})",
+                        endpoint.model_id,
+                        endpoint.policy.max_retries
+                    );
+                    emit_recorded(
+                        app,
+                        recorder,
+                        &AgentEvent::TurnRetry {
+                            run_id: run_id.to_string(),
+                            error,
+                            attempt: retries,
+                            delay_ms: delay.as_millis() as u64,
+                        },
+                    );
+                    tokio::select! {
+                        _ = tokio::time::sleep(delay) => {}
+                        _ = cancel.cancelled() => {
+                            return Ok(finish(
+                                iteration - 1,
+                                AgentStopReason::Cancelled,
+                                produced,
+                                content,
+                                usage,
+                            ));
+                        }
+                    }
+                    continue;
+                }
+                Recovery::Backoff | Recovery::Failover => {
+                    if let Some(next) = next_failover_endpoint(app, &mut failover).await {
+                        log::warn!(
+                            "Model '{}' failed: {error}, failing over to '{}'",
+                            endpoint.model_id,
+                            next.model_id
+                        );
+                        emit_recorded(
+                            app,
+                            recorder,
+                            &AgentEvent::Failover {
+                                run_id: run_id.to_string(),
+                                error,
+                                from_model: endpoint.model_id.clone(),
+                                to_model: next.model_id.clone(),
+                            },
+                        );
+                        endpoint = next;
+                        redactor =
+                            redactor_for_endpoint(app, &endpoint, request.thread_id.as_deref());
+                        openai_tools = tools_for_endpoint(&declared_tools, &endpoint);
+                        // Native tools were declared by, and authorized for, the failed provider
+                        native_tools = None;
+                        retries = 0;
+                        continue;
+                    }
+                }
+                Recovery::Surface => {}
+            }
+            let mut result = finish(
+                iteration - 1,
+                AgentStopReason::ProviderError,
+                produced,
+                content,
+                usage,
+            );
+            result.provider_error = Some(ProviderFailure {
+                model: endpoint.model_id.clone(),
+                error,
+                alternative_models: failover.into_iter().collect(),
+            });
+            return Ok(result);
+        };
+        usage.add(&turn.usage);
+
+        let mut message = assistant_message(&turn);
+        content = turn.content.clone();
+        if turn.tool_calls.is_empty() {
+            message = run_message_hooks(app, MessageHook::AfterReceive, message).await;
+            if let Some(text) = message.get("content").and_then(Value::as_str) {
+                content = text.to_string();
+            }
+        }
+        store_reasoning(&mut message, retention);
+        let mut context = message.clone();
+        context_reasoning(std::slice::from_mut(&mut context), retention);
+        conversation.push(context);
+        if let Some(report) = &redaction {
+            attach_report(&mut message, report);
+        }
+        produced.push(message);
+
+        if turn.tool_calls.is_empty() {
+            return Ok(finish(
+                iteration,
+                AgentStopReason::Completed,
+                produced,
+                content,
+                usage,
+            ));
+        }
+
+        let sequential = sequential_requested(&request.parameters);
+        let mut results: Vec<Option<(String, bool)>> = vec![None; turn.tool_calls.len()];
+        for (wave, indices) in plan_waves(&turn.tool_calls, sequential)
+            .into_iter()
+            .enumerate()
+        {
+            let calls = indices.into_iter().map(|index| {
+                let call = &turn.tool_calls[index];
+                let (tools_by_name, data_folder, state) = (&tools_by_name, &data_folder, &state);
+                let turn_scope = &turn_scope;
+                let (assistant_id, workspace) =
+                    (request.assistant_id.as_deref(), workspace.as_deref());
+                async move {
+                    let started = Instant::now();
+                    emit_recorded(
+                        app,
+                        recorder,
+                        &AgentEvent::ToolCall {
+                            run_id: run_id.to_string(),
+                            call: call.clone(),
+                            server: tools_by_name.get(&call.name).map(|t| t.server.clone()),
+                            wave: Some(wave),
+                        },
+                    );
+                    // Each call has its own scope, so it can be cancelled without ending the run
+                    let call_key = match call.id.as_str() {
+                        "" => index.to_string(),
+                        id => id.to_string(),
+                    };
+                    let call_guard = state.cancellations.open(
+                        CancelScope::ToolCall(format!("{run_id}:{call_key}")),
+                        Some(turn_scope),
+                    );
+                    let call_cancel = match &call_guard {
+                        Ok(guard) => guard.token().clone(),
+                        Err(_) => cancel.child_token(),
+                    };
+                    let server = tools_by_name.get(&call.name).map(|t| t.server.as_str());
+                    let call_span = tracing::info_span!(
+                        "tool.call",
+                        "gen_ai.tool.name" = %call.name,
+                        "gen_ai.tool.call.id" = %call.id,
+                        server = server.unwrap_or_default(),
+                        wave,
+                    );
+                    let outcome = execute_tool_call(
+                        app,
+                        call,
+                        tools_by_name,
+                        assistant_id,
+                        workspace,
+                        timeout_duration,
+                        &call_cancel,
+                    )
+                    .instrument(call_span.clone())
+                    .await;
+                    // The error text may quote the arguments, so only the outcome is traced
+                    match &outcome {
+                        Some((_, true)) => tracing::error!(parent: &call_span, "Tool call failed"),
+                        None => tracing::info!(parent: &call_span, "Tool call cancelled"),
+                        Some(_) => {}
+                    }
+                    drop(call_guard);
+                    let (text, is_error) = match outcome {
+                        Some(outcome) => outcome,
+                        None if cancel.is_cancelled() => return None,
+                        None => (format!("Tool call '{}' was cancelled", call.name), true),
+                    };
+                    let text = match (is_error, tools_by_name.get(&call.name)) {
+                        (false, Some(tool)) => tool_result_for_model(
+                            data_folder,
+                            &tool.server,
+                            &call.name,
+                            &call.id,
+                            text,
+                        ),
+                        _ => text,
+                    };
+                    emit_recorded(
+                        app,
+                        recorder,
+                        &AgentEvent::ToolResult {
+                            run_id: run_id.to_string(),
+                            call_id: call.id.clone(),
+                            is_error,
+                            content: text.clone(),
+                            duration_ms: Some(started.elapsed().as_millis() as u64),
+                        },
+                    );
+                    Some((index, text, is_error))
+                }
+            });
+            for outcome in join_all(calls).await {
+                let Some((index, text, is_error)) = outcome else {
+                    return Ok(finish(
+                        iteration,
+                        AgentStopReason::Cancelled,
+                        produced,
+                        content,
+                        usage,
+                    ));
+                };
+                results[index] = Some((text, is_error));
+            }
+        }
+
+        // Tool messages follow the order of the calls, whatever order they finished in
+        for (call, result) in turn.tool_calls.iter().zip(results) {
+            let Some((text, _)) = result else {
+                continue;
+            };
+            let tool_message = json!({
+                "role": "tool",
+                "tool_call_id": call.id,
+                "content": text,
+            });
+            conversation.push(tool_message.clone());
+            produced.push(tool_message);
+        }
+    }
+
+    Ok(finish(
+        max_iterations,
+        AgentStopReason::MaxIterations,
+        produced,
+        content,
+        usage,
+    ))
+}
+
+/// Runs an agent loop registered under its run id so it can be cancelled, reporting failures
+/// as `error` events. Shared by the `agent_run` command and background jobs.
+pub async fn execute_agent_run<R: Runtime>(
+    app: &AppHandle<R>,
+    request: AgentRunRequest,
+    on_token: Option<Channel<TokenChunk>>,
+) -> Result<AgentRunResult, String> {
+    let run_id = request
+        .run_id
+        .clone()
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    // Cancelling the thread, or the app shutting down, cancels the run as well
+    let thread = request.thread_id.clone().map(CancelScope::Thread);
+    let turn = app
+        .state::<AppState>()
+        .cancellations
+        .open(CancelScope::Turn(run_id.clone()), thread.as_ref())?;
+    let cancel = turn.token().clone();
+
+    let interactive = request.priority == GenerationPriority::Interactive;
+    let started = Instant::now();
+    let streamer = on_token.map(|channel| TokenStreamer::for_channel(run_id.clone(), channel));
+    let recorder = TranscriptRecorder::new(&run_id, request.thread_id.clone(), &request.model);
+    let span = tracing::info_span!(
+        "agent.run",
+        run_id = %run_id,
+        thread_id = request.thread_id.as_deref().unwrap_or_default(),
+        "gen_ai.request.model" = %request.model,
+        iterations = tracing::field::Empty,
+        stop_reason = tracing::field::Empty,
+    );
+    let result = run_agent_loop(app, &run_id, request, &cancel, streamer.as_ref(), &recorder)
+        .instrument(span.clone())
+        .await;
+    match &result {
+        Ok(run) => {
+            span.record("iterations", run.iterations);
+            span.record("stop_reason", tracing::field::debug(&run.stop_reason));
+        }
+        Err(e) => tracing::error!(parent: &span, error = %e, "Agent run failed"),
+    }
+    if let Some(streamer) = streamer {
+        streamer.finish().await;
+    }
+    drop(turn);
+
+    record(
+        app,
+        match &result {
+            Ok(run) if run.stop_reason == AgentStopReason::Cancelled => Metric::GenerationCancelled,
+            Ok(run) if run.stop_reason == AgentStopReason::ProviderError => {
+                Metric::GenerationFailed
+            }
+            Ok(_) => Metric::GenerationCompleted,
+            Err(_) => Metric::GenerationFailed,
+        },
+    );
+    if let Ok(run) = &result {
+        record_usage(app, &run.usage);
+    }
+    match &result {
+        Ok(run) if interactive && run.stop_reason == AgentStopReason::Completed => {
+            notify_generation_finished(app, started.elapsed(), &run.content);
+        }
+        Ok(_) => {}
+        Err(e) => emit_recorded(
+            app,
+            &recorder,
+            &AgentEvent::Error {
+                run_id: run_id.clone(),
+                message: e.clone(),
+            },
+        ),
+    }
+    if let Err(e) = save_transcript(&get_jan_data_folder_path(app.clone()), &recorder.finish()) {
+        log::warn!("Failed to save transcript of agent run {run_id}: {e}");
+    }
+    result
+}
+
+/// Runs the agent tool-call loop until the model answers without tool calls, the
+/// iteration limit is reached, or the run is cancelled. Progress is reported through
+/// `agent-event` events; the produced messages are returned for persistence.
+/// When `on_token` is given, content deltas are coalesced and streamed over that channel
+/// instead of being emitted as `content_delta` events.
+#[tauri::command]
+pub async fn agent_run<R: Runtime>(
+    app: AppHandle<R>,
+    request: AgentRunRequest,
+    on_token: Option<Channel<TokenChunk>>,
+) -> Result<AgentRunResult, String> {
+    execute_agent_run(&app, request, on_token).await
+}
+
+/// Cancels a running agent loop, including its provider stream and any pending approval or
+/// tool call.
+#[tauri::command]
+pub async fn agent_cancel(state: State<'_, AppState>, run_id: String) -> Result<(), String> {
+    if state
+        .cancellations
+        .cancel(&CancelScope::Turn(run_id.clone()))
+    {
+        Ok(())
+    } else {
+        Err(format!("Agent run {run_id} not found"))
+    }
+}
+
+/// Lists recorded agent runs, newest first, optionally only those of one thread.
+#[tauri::command]
+pub async fn list_turn_transcripts<R: Runtime>(
+    app: AppHandle<R>,
+    thread_id: Option<String>,
+) -> Result<Vec<TranscriptSummary>, String> {
+    Ok(list_transcripts(
+        &get_jan_data_folder_path(app),
+        thread_id.as_deref(),
+    ))
+}
+
+/// Returns the recorded event sequence of an agent run.
+#[tauri::command]
+pub async fn get_turn_transcript<R: Runtime>(
+    app: AppHandle<R>,
+    run_id: String,
+) -> Result<TurnTranscript, String> {
+    read_transcript(&get_jan_data_folder_path(app), &run_id)
+}
+
+/// Re-emits the recorded events of an agent run over `on_event`. With a `speed` the original
+/// timing is reproduced (2.0 plays twice as fast); without one all events are sent at once.
+#[tauri::command]
+pub async fn replay_turn<R: Runtime>(
+    app: AppHandle<R>,
+    run_id: String,
+    on_event: Channel<TranscriptEntry>,
+    speed: Option<f64>,
+) -> Result<usize, String> {
+    let transcript = read_transcript(&get_jan_data_folder_path(app), &run_id)?;
+    let speed = speed.filter(|s| s.is_finite() && *s > 0.0);
+    let started = Instant::now();
+    for entry in &transcript.entries {
+        if let Some(speed) = speed {
+            let due = Duration::from_secs_f64(entry.offset_ms as f64 / 1000.0 / speed);
+            if let Some(wait) = due.checked_sub(started.elapsed()) {
+                tokio::time::sleep(wait).await;
+            }
+        }
+        on_event
+            .send(entry.clone())
+            .map_err(|e| format!("Failed to send replay event: {e}"))?;
+    }
+    Ok(transcript.entries.len())
+}
+
+/// Deletes the recorded transcript of an agent run.
+#[tauri::command]
+pub async fn delete_turn_transcript<R: Runtime>(
+    app: AppHandle<R>,
+    run_id: String,
+) -> Result<(), String> {
+    delete_transcript(&get_jan_data_folder_path(app), &run_id)
+}
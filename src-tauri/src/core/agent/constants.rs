@@ -0,0 +1,19 @@
+// Agent constants
+pub const AGENT_EVENT: &str = "agent-event";
+pub const DEFAULT_MAX_ITERATIONS: usize = 10;
+/// Hard cap regardless of what the caller requests
+pub const MAX_ITERATIONS_LIMIT: usize = 50;
+/// Timeout for a single streamed model turn
+pub const MODEL_TURN_TIMEOUT_SECS: u64 = 600;
+/// Server name under which built-in tools are listed and checked against approval policies
+pub const BUILTIN_TOOL_SERVER: &str = "jan";
+/// Folder holding the recorded event sequence of each agent run
+pub const TRANSCRIPTS_DIR: &str = "agent_transcripts";
+/// Oldest transcripts beyond this count are removed
+pub const MAX_TRANSCRIPTS: usize = 200;
+/// Event carrying tool-call arguments while the model is still streaming them
+pub const TOOL_CALL_DELTA_EVENT: &str = "tool-call-delta";
+/// Arguments longer than this are forwarded as raw fragments without a partial parse
+pub const PARTIAL_ARGUMENTS_MAX_BYTES: usize = 64 * 1024;
+/// Tool calls running at the same time on one server, across all agent runs
+pub const MAX_PARALLEL_CALLS_PER_SERVER: usize = 4;
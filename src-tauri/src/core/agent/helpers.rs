@@ -0,0 +1,311 @@
+use std::time::Duration;
+
+use rmcp::model::CallToolResult;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Runtime};
+use tokio_util::sync::CancellationToken;
+
+use super::constants::{AGENT_EVENT, PARTIAL_ARGUMENTS_MAX_BYTES, TOOL_CALL_DELTA_EVENT};
+use super::models::{AgentEvent, ModelTurn, ToolCall, ToolCallDelta, TurnDelta, TurnError};
+use crate::core::inference::cache_control::request_stream_usage;
+use crate::core::inference::errors::{classify_response, network_error};
+use crate::core::inference::helpers::build_request;
+use crate::core::inference::models::{ModelEndpoint, ProviderError, StreamEvent};
+use crate::core::inference::reasoning::REASONING_FIELDS;
+use crate::core::inference::retry::{next_chunk, policy_client, send_with_retry};
+use crate::core::inference::stream::{
+    parse_partial_json, SseData, SseLineBuffer, StreamNormalizer,
+};
+use crate::core::mcp::models::ToolWithServer;
+
+pub fn emit_agent_event<R: Runtime>(app: &AppHandle<R>, event: &AgentEvent) {
+    if let Err(e) = app.emit(AGENT_EVENT, event) {
+        log::error!("Failed to emit agent event: {e}");
+    }
+}
+
+/// Forward a streamed tool-call fragment so the UI can show the arguments as they form
+pub fn emit_tool_call_delta<R: Runtime>(
+    app: &AppHandle<R>,
+    run_id: &str,
+    index: usize,
+    call: &ToolCall,
+    fragment: &str,
+) {
+    let payload = ToolCallDelta {
+        run_id: run_id.to_string(),
+        index,
+        call_id: Some(call.id.clone()).filter(|id| !id.is_empty()),
+        name: Some(call.name.clone()).filter(|name| !name.is_empty()),
+        delta: fragment.to_string(),
+        // Re-parsing on every fragment is quadratic, so huge payloads only get raw fragments
+        partial_arguments: (call.arguments.len() <= PARTIAL_ARGUMENTS_MAX_BYTES)
+            .then(|| parse_partial_json(&call.arguments))
+            .flatten(),
+    };
+    if let Err(e) = app.emit(TOOL_CALL_DELTA_EVENT, &payload) {
+        log::error!("Failed to emit tool call delta: {e}");
+    }
+}
+
+/// Convert MCP tools to the OpenAI `tools` request format
+pub fn tools_to_openai(tools: &[ToolWithServer]) -> Vec<Value> {
+    tools
+        .iter()
+        .map(|tool| {
+            json!({
+                "type": "function",
+                "function": {
+                    "name": tool.name,
+                    "description": tool.description.clone().unwrap_or_default(),
+                    "parameters": tool.input_schema,
+                }
+            })
+        })
+        .collect()
+}
+
+/// Flatten a tool result into the text fed back to the model
+pub fn tool_result_text(result: &CallToolResult) -> String {
+    result
+        .content
+        .iter()
+        .map(|content| match content.as_text() {
+            Some(text) => text.text.clone(),
+            None => serde_json::to_string(content).unwrap_or_default(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Build the assistant message recorded for a model turn
+pub fn assistant_message(turn: &ModelTurn) -> Value {
+    let mut message = json!({
+        "role": "assistant",
+        "content": turn.content,
+    });
+    if !turn.reasoning.is_empty() {
+        message[REASONING_FIELDS[0]] = Value::String(turn.reasoning.clone());
+    }
+    if !turn.tool_calls.is_empty() {
+        message["tool_calls"] = Value::Array(
+            turn.tool_calls
+                .iter()
+                .map(|call| {
+                    json!({
+                        "id": call.id,
+                        "type": "function",
+                        "function": { "name": call.name, "arguments": call.arguments }
+                    })
+                })
+                .collect(),
+        );
+    }
+    message
+}
+
+/// Parse tool call arguments; an empty string means no arguments
+pub fn parse_tool_arguments(arguments: &str) -> Result<serde_json::Map<String, Value>, String> {
+    if arguments.trim().is_empty() {
+        return Ok(serde_json::Map::new());
+    }
+    match serde_json::from_str::<Value>(arguments) {
+        Ok(Value::Object(map)) => Ok(map),
+        Ok(_) => Err("Tool arguments must be a JSON object".to_string()),
+        Err(e) => Err(format!("Invalid tool arguments: {e}")),
+    }
+}
+
+/// Incrementally rebuilds a model turn from provider streaming chunks
+#[derive(Debug, Default)]
+pub struct StreamAccumulator {
+    pub turn: ModelTurn,
+    normalizer: StreamNormalizer,
+    /// First error the provider reported inside the stream
+    error: Option<ProviderError>,
+}
+
+impl StreamAccumulator {
+    /// Apply a raw stream chunk and return the content delta it carried, if any
+    pub fn apply_chunk(&mut self, chunk: &Value) -> Option<String> {
+        let mut content = String::new();
+        self.apply_chunk_with(chunk, &mut |delta| {
+            if let TurnDelta::Content(text) = delta {
+                content.push_str(text);
+            }
+        });
+        (!content.is_empty()).then_some(content)
+    }
+
+    /// Apply a raw stream chunk, reporting content and tool-call fragments as they arrive
+    pub fn apply_chunk_with(&mut self, chunk: &Value, on_delta: &mut impl FnMut(TurnDelta<'_>)) {
+        for event in self.normalizer.normalize(chunk) {
+            let tool_fragment = match &event {
+                StreamEvent::ToolCallDelta {
+                    index, arguments, ..
+                } => Some((*index, arguments.clone())),
+                _ => None,
+            };
+            let reasoning = match &event {
+                StreamEvent::ReasoningDelta { text } => Some(text.clone()),
+                _ => None,
+            };
+            if let Some(text) = self.apply_event(event) {
+                on_delta(TurnDelta::Content(&text));
+            }
+            if let Some(text) = reasoning {
+                on_delta(TurnDelta::Reasoning(&text));
+            }
+            if let Some((index, fragment)) = tool_fragment {
+                on_delta(TurnDelta::ToolCall {
+                    index,
+                    call: &self.turn.tool_calls[index],
+                    fragment: &fragment,
+                });
+            }
+        }
+    }
+
+    /// Apply a normalized stream event and return its content delta, if any
+    pub fn apply_event(&mut self, event: StreamEvent) -> Option<String> {
+        match event {
+            StreamEvent::TextDelta { text } => {
+                self.turn.content.push_str(&text);
+                Some(text)
+            }
+            StreamEvent::ReasoningDelta { text } => {
+                self.turn.reasoning.push_str(&text);
+                None
+            }
+            StreamEvent::ToolCallDelta {
+                index,
+                id,
+                name,
+                arguments,
+            } => {
+                while self.turn.tool_calls.len() <= index {
+                    self.turn.tool_calls.push(ToolCall::default());
+                }
+                let entry = &mut self.turn.tool_calls[index];
+                if let Some(id) = id {
+                    entry.id = id;
+                }
+                if let Some(name) = name {
+                    entry.name.push_str(&name);
+                }
+                entry.arguments.push_str(&arguments);
+                None
+            }
+            StreamEvent::Usage {
+                input_tokens,
+                output_tokens,
+                cache_read_tokens,
+                cache_write_tokens,
+            } => {
+                let usage = &mut self.turn.usage;
+                usage.input_tokens += input_tokens.unwrap_or(0);
+                usage.output_tokens += output_tokens.unwrap_or(0);
+                usage.cache_read_tokens += cache_read_tokens.unwrap_or(0);
+                usage.cache_write_tokens += cache_write_tokens.unwrap_or(0);
+                None
+            }
+            StreamEvent::Finish { reason } => {
+                self.turn.finish_reason = Some(reason);
+                None
+            }
+            StreamEvent::Error { error } => {
+                self.error.get_or_insert(error);
+                None
+            }
+        }
+    }
+
+    /// The error the provider reported inside the stream, if any
+    pub fn take_error(&mut self) -> Option<ProviderError> {
+        self.error.take()
+    }
+
+    /// Finish the turn, assigning ids to tool calls the model left unnamed
+    pub fn finish(mut self) -> ModelTurn {
+        for event in self.normalizer.finish() {
+            self.apply_event(event);
+        }
+        self.turn.tool_calls.retain(|c| !c.name.is_empty());
+        for (i, call) in self.turn.tool_calls.iter_mut().enumerate() {
+            if call.id.is_empty() {
+                call.id = format!("call_{i}");
+            }
+        }
+        self.turn
+    }
+}
+
+/// Stream a `/chat/completions` turn, invoking `on_delta` for each content or tool-call delta.
+/// Returns an error if the request fails, the stream breaks or the run is cancelled; provider
+/// failures are classified.
+pub async fn stream_model_turn(
+    endpoint: &ModelEndpoint,
+    mut body: Value,
+    thread_id: Option<&str>,
+    timeout: Duration,
+    cancel: &CancellationToken,
+    mut on_delta: impl FnMut(TurnDelta<'_>),
+) -> Result<ModelTurn, TurnError> {
+    body["model"] = Value::String(endpoint.model_id.clone());
+    body["stream"] = Value::Bool(true);
+    // Remote providers report prompt-cache hits in the final usage chunk
+    if !endpoint.is_local {
+        request_stream_usage(&mut body);
+    }
+
+    let client = policy_client(&endpoint.policy, timeout).map_err(TurnError::Other)?;
+    let request = build_request(&client, endpoint, "/chat/completions", thread_id, &body);
+    let response = tokio::select! {
+        response = send_with_retry(&endpoint.policy, request) => {
+            response.map_err(|e| TurnError::Provider(network_error(&e, false)))?
+        }
+        _ = cancel.cancelled() => return Err(TurnError::Cancelled),
+    };
+    let status = response.status();
+    if !status.is_success() {
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string);
+        let text = response.text().await.unwrap_or_default();
+        return Err(TurnError::Provider(classify_response(
+            status.as_u16(),
+            &text,
+            retry_after.as_deref(),
+        )));
+    }
+
+    let mut stream = response.bytes_stream();
+    let mut lines = SseLineBuffer::default();
+    let mut accumulator = StreamAccumulator::default();
+    loop {
+        let chunk = tokio::select! {
+            chunk = next_chunk(&mut stream, endpoint.policy.read_timeout()) => chunk,
+            _ = cancel.cancelled() => return Err(TurnError::Cancelled),
+        };
+        let Some(chunk) = chunk else { break };
+        let chunk = chunk.map_err(|e| TurnError::Provider(network_error(&e, true)))?;
+        for data in lines.push(&chunk) {
+            match data {
+                SseData::Json(value) => accumulator.apply_chunk_with(&value, &mut on_delta),
+                SseData::Done => return Ok(accumulator.finish()),
+            }
+            if let Some(error) = accumulator.take_error() {
+                return Err(TurnError::Provider(error));
+            }
+        }
+    }
+    if let Some(SseData::Json(value)) = lines.finish() {
+        accumulator.apply_chunk_with(&value, &mut on_delta);
+    }
+    match accumulator.take_error() {
+        Some(error) => Err(TurnError::Provider(error)),
+        None => Ok(accumulator.finish()),
+    }
+}
@@ -0,0 +1,29 @@
+/*!
+   Agent Tool-Call Loop
+
+   Drives the "model responds with tool_calls -> execute -> feed results back" loop in the core,
+   so the desktop UI and headless/API clients share the same agent behavior:
+   - model output is streamed from the resolved provider/local engine,
+   - tool calls are executed through the connected MCP servers (respecting assistant tool scopes)
+     or by built-in tools implemented in the core,
+   - independent tool calls of a turn run concurrently, bounded per server, while calls that
+     refer to an earlier call's id wait for it,
+   - each tool call is checked against the tool approval policies, which may ask the user,
+   - tool results above the artifact threshold are stored as tool artifacts and only a
+     preview is fed back, which the model can page through with `read_tool_artifact`,
+   - runs are bounded by a maximum iteration count and can be cancelled at any time; each run
+     is a turn scope of the cancellation tree, under its thread, with its tool calls below it,
+   - every event of a run is recorded with its timing in `agent_transcripts/` so the run can be
+     inspected or replayed later.
+*/
+
+pub mod builtin_tools;
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+pub mod parallel;
+pub mod transcript;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,239 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::core::context::models::ContextOverflow;
+use crate::core::inference::models::{ProviderError, TokenUsage};
+use crate::core::scheduler::models::GenerationPriority;
+
+/// Request to run the agent loop
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentRunRequest {
+    /// Client-chosen id used for events and cancellation; generated when absent
+    #[serde(default)]
+    pub run_id: Option<String>,
+    pub model: String,
+    /// OpenAI-style chat messages
+    pub messages: Vec<Value>,
+    /// Assistant whose tool scope limits the tools offered to the model
+    #[serde(default)]
+    pub assistant_id: Option<String>,
+    /// Thread the run belongs to; its workspace, if any, scopes file access
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    /// Extra body parameters (temperature, top_p, ...)
+    #[serde(default)]
+    pub parameters: Map<String, Value>,
+    #[serde(default)]
+    pub max_iterations: Option<usize>,
+    /// Scheduling priority on local engines
+    #[serde(default)]
+    pub priority: GenerationPriority,
+    /// Context window of the model; when set, each turn is checked against it before sending
+    #[serde(default)]
+    pub context_size: Option<usize>,
+}
+
+/// A tool call requested by the model, with accumulated streamed arguments
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    /// Raw JSON argument string as produced by the model
+    pub arguments: String,
+}
+
+/// Payload of the `tool-call-delta` event, sent for each streamed fragment of a tool call
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ToolCallDelta {
+    pub run_id: String,
+    /// Position of the call within the model turn
+    pub index: usize,
+    /// Call id and tool name, once the provider has sent them
+    pub call_id: Option<String>,
+    pub name: Option<String>,
+    /// Raw argument fragment carried by this chunk
+    pub delta: String,
+    /// Best-effort parse of the arguments received so far
+    pub partial_arguments: Option<Value>,
+}
+
+/// Incremental output of a model turn while it is being streamed
+#[derive(Debug, Clone, Copy)]
+pub enum TurnDelta<'a> {
+    Content(&'a str),
+    Reasoning(&'a str),
+    /// A fragment of the arguments of `call`, which holds everything received so far
+    ToolCall {
+        index: usize,
+        call: &'a ToolCall,
+        fragment: &'a str,
+    },
+}
+
+/// Result of a single streamed model turn
+#[derive(Debug, Clone, Default)]
+pub struct ModelTurn {
+    pub content: String,
+    pub reasoning: String,
+    pub tool_calls: Vec<ToolCall>,
+    pub finish_reason: Option<String>,
+    pub usage: TokenUsage,
+}
+
+/// Why a model turn produced no result
+#[derive(Debug, Clone, PartialEq)]
+pub enum TurnError {
+    Cancelled,
+    /// The provider failed; classified so the caller can pick a recovery
+    Provider(ProviderError),
+    /// Anything else, such as a request that couldn't be built
+    Other(String),
+}
+
+impl std::fmt::Display for TurnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cancelled => f.write_str("Cancelled"),
+            Self::Provider(error) if error.mid_stream => write!(f, "Model stream failed: {error}"),
+            Self::Provider(error) => write!(f, "Model request failed with {error}"),
+            Self::Other(message) => f.write_str(message),
+        }
+    }
+}
+
+impl From<TurnError> for String {
+    fn from(error: TurnError) -> Self {
+        error.to_string()
+    }
+}
+
+/// Why the run stopped
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AgentStopReason {
+    Completed,
+    MaxIterations,
+    Cancelled,
+    /// The conversation no longer fits the context window; see `context_overflow`
+    ContextOverflow,
+    /// The provider failed in a way retrying and failover couldn't fix; see `provider_error`
+    ProviderError,
+}
+
+/// A provider failure that ended a run, for the UI to offer a retry with a different model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderFailure {
+    /// The model that failed last
+    pub model: String,
+    pub error: ProviderError,
+    /// Failover models of the provider that weren't tried
+    pub alternative_models: Vec<String>,
+}
+
+/// Final outcome of an agent run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentRunResult {
+    pub run_id: String,
+    /// Messages produced during the run (assistant and tool messages), to be appended to the thread
+    pub messages: Vec<Value>,
+    pub content: String,
+    pub iterations: usize,
+    pub stop_reason: AgentStopReason,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_overflow: Option<ContextOverflow>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_error: Option<ProviderFailure>,
+    /// Tokens used by all model turns of the run, as reported by the provider
+    #[serde(default)]
+    pub usage: TokenUsage,
+}
+
+/// Events emitted on the `agent-event` channel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AgentEvent {
+    StepStarted {
+        run_id: String,
+        iteration: usize,
+    },
+    ContentDelta {
+        run_id: String,
+        delta: String,
+    },
+    ReasoningDelta {
+        run_id: String,
+        delta: String,
+    },
+    ToolCall {
+        run_id: String,
+        call: ToolCall,
+        server: Option<String>,
+        /// Wave of concurrently executed calls within the turn, starting at 0
+        #[serde(default)]
+        wave: Option<usize>,
+    },
+    ToolResult {
+        run_id: String,
+        call_id: String,
+        is_error: bool,
+        content: String,
+        /// Time from the start of the call, including approval, to its result
+        #[serde(default)]
+        duration_ms: Option<u64>,
+    },
+    /// A model turn broke off and is retried with the same model after `delay_ms`
+    TurnRetry {
+        run_id: String,
+        error: ProviderError,
+        attempt: u32,
+        delay_ms: u64,
+    },
+    /// The model failed and the run continues with the next failover model
+    Failover {
+        run_id: String,
+        error: ProviderError,
+        from_model: String,
+        to_model: String,
+    },
+    Finished {
+        run_id: String,
+        stop_reason: AgentStopReason,
+        iterations: usize,
+    },
+    Error {
+        run_id: String,
+        message: String,
+    },
+}
+
+/// An agent event with its time since the start of the run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptEntry {
+    pub offset_ms: u64,
+    pub event: AgentEvent,
+}
+
+/// Everything emitted during one agent run, in order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TurnTranscript {
+    pub run_id: String,
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    pub model: String,
+    /// Milliseconds since the Unix epoch
+    pub started_at: i64,
+    pub duration_ms: u64,
+    pub entries: Vec<TranscriptEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptSummary {
+    pub run_id: String,
+    pub thread_id: Option<String>,
+    pub model: String,
+    pub started_at: i64,
+    pub duration_ms: u64,
+    pub tool_calls: usize,
+    /// None when the run ended with an error
+    pub stop_reason: Option<AgentStopReason>,
+}
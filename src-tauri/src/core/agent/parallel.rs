@@ -0,0 +1,79 @@
+//! Concurrent execution of the tool calls of one model turn. Calls are grouped into waves:
+//! every call of a wave is independent of the others and they run concurrently, while a
+//! call that depends on an earlier one waits for the wave of that call to finish. Calls to
+//! the same server are bounded by a process-wide per-server limiter, which also covers
+//! concurrent agent runs.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use serde_json::{Map, Value};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use super::constants::MAX_PARALLEL_CALLS_PER_SERVER;
+use super::models::ToolCall;
+
+/// Whether the request asked for one tool call at a time (`parallel_tool_calls: false`)
+pub fn sequential_requested(parameters: &Map<String, Value>) -> bool {
+    parameters
+        .get("parallel_tool_calls")
+        .and_then(Value::as_bool)
+        == Some(false)
+}
+
+/// Indices of the earlier calls `calls[index]` depends on. A call depends on an earlier
+/// one when its arguments refer to that call's id, which is how a model marks a call as
+/// having to run after another.
+pub fn dependencies(calls: &[ToolCall], index: usize) -> Vec<usize> {
+    let arguments = &calls[index].arguments;
+    calls[..index]
+        .iter()
+        .enumerate()
+        .filter(|(_, earlier)| !earlier.id.is_empty() && arguments.contains(&earlier.id))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Group calls into waves that run one after another; calls within a wave run
+/// concurrently. With `sequential` every call gets its own wave, in order.
+pub fn plan_waves(calls: &[ToolCall], sequential: bool) -> Vec<Vec<usize>> {
+    if sequential {
+        return (0..calls.len()).map(|i| vec![i]).collect();
+    }
+    let mut levels: Vec<usize> = Vec::with_capacity(calls.len());
+    for index in 0..calls.len() {
+        let level = dependencies(calls, index)
+            .into_iter()
+            .map(|i| levels[i] + 1)
+            .max()
+            .unwrap_or(0);
+        levels.push(level);
+    }
+    let mut waves: Vec<Vec<usize>> = Vec::new();
+    for (index, level) in levels.into_iter().enumerate() {
+        if waves.len() <= level {
+            waves.resize_with(level + 1, Vec::new);
+        }
+        waves[level].push(index);
+    }
+    waves
+}
+
+static SERVER_LIMITS: OnceLock<Mutex<HashMap<String, Arc<Semaphore>>>> = OnceLock::new();
+
+fn server_semaphore(server: &str) -> Arc<Semaphore> {
+    let limits = SERVER_LIMITS.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut limits = limits.lock().unwrap_or_else(|e| e.into_inner());
+    limits
+        .entry(server.to_string())
+        .or_insert_with(|| Arc::new(Semaphore::new(MAX_PARALLEL_CALLS_PER_SERVER)))
+        .clone()
+}
+
+/// Wait for a free tool-call slot on `server`; the slot is released when the permit drops
+pub async fn acquire_server_permit(server: &str) -> Result<OwnedSemaphorePermit, String> {
+    server_semaphore(server)
+        .acquire_owned()
+        .await
+        .map_err(|e| format!("Tool call limiter for '{server}' is closed: {e}"))
+}
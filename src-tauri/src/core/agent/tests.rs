@@ -0,0 +1,235 @@
+use super::helpers::{assistant_message, parse_tool_arguments, tools_to_openai, StreamAccumulator};
+use super::models::{AgentEvent, AgentStopReason, ToolCall, TurnDelta, TurnError};
+use super::parallel::{dependencies, plan_waves, sequential_requested};
+use super::transcript::{
+    delete_transcript, list_transcripts, read_transcript, save_transcript, TranscriptRecorder,
+};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::inference::models::ProviderErrorKind;
+use crate::core::inference::stream::{parse_sse_line, SseData};
+use crate::core::mcp::models::ToolWithServer;
+use serde_json::json;
+
+#[test]
+fn test_parse_sse_line() {
+    assert_eq!(parse_sse_line("data: [DONE]"), Some(SseData::Done));
+    assert_eq!(
+        parse_sse_line("data: {\"a\":1}\n"),
+        Some(SseData::Json(json!({"a": 1})))
+    );
+    assert_eq!(parse_sse_line(": keep-alive"), None);
+    assert_eq!(parse_sse_line("event: ping"), None);
+    assert_eq!(parse_sse_line("data: not json"), None);
+}
+
+#[test]
+fn test_accumulator_content_and_tool_calls() {
+    let mut acc = StreamAccumulator::default();
+    assert_eq!(
+        acc.apply_chunk(&json!({"choices": [{"delta": {"content": "Hel"}}]})),
+        Some("Hel".to_string())
+    );
+    acc.apply_chunk(&json!({"choices": [{"delta": {"content": "lo"}}]}));
+    acc.apply_chunk(&json!({"choices": [{"delta": {"tool_calls": [
+        {"index": 0, "id": "call_a", "function": {"name": "fetch", "arguments": "{\"url\":"}}
+    ]}}]}));
+    acc.apply_chunk(&json!({"choices": [{"delta": {"tool_calls": [
+        {"index": 0, "function": {"arguments": "\"https://jan.ai\"}"}},
+        {"index": 1, "function": {"name": "search", "arguments": "{}"}}
+    ]}}]}));
+    acc.apply_chunk(&json!({"choices": [{"delta": {}, "finish_reason": "tool_calls"}]}));
+
+    let turn = acc.finish();
+    assert_eq!(turn.content, "Hello");
+    assert_eq!(turn.finish_reason.as_deref(), Some("tool_calls"));
+    assert_eq!(turn.tool_calls.len(), 2);
+    assert_eq!(turn.tool_calls[0].id, "call_a");
+    assert_eq!(turn.tool_calls[0].arguments, "{\"url\":\"https://jan.ai\"}");
+    assert_eq!(turn.tool_calls[1].id, "call_1");
+    assert_eq!(turn.tool_calls[1].name, "search");
+}
+
+#[test]
+fn test_accumulator_reports_tool_call_fragments() {
+    let mut acc = StreamAccumulator::default();
+    let mut fragments = Vec::new();
+    for args in ["{\"q\":", "\"rust\"}"] {
+        acc.apply_chunk_with(
+            &json!({"choices": [{"delta": {"tool_calls": [
+                {"index": 0, "id": "c1", "function": {"name": "search", "arguments": args}}
+            ]}}]}),
+            &mut |delta| {
+                if let TurnDelta::ToolCall {
+                    index,
+                    call,
+                    fragment,
+                } = delta
+                {
+                    fragments.push((index, fragment.to_string(), call.arguments.clone()));
+                }
+            },
+        );
+    }
+    assert_eq!(
+        fragments,
+        vec![
+            (0, "{\"q\":".to_string(), "{\"q\":".to_string()),
+            (0, "\"rust\"}".to_string(), "{\"q\":\"rust\"}".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_tool_arguments() {
+    assert!(parse_tool_arguments("").unwrap().is_empty());
+    assert_eq!(
+        parse_tool_arguments("{\"q\":\"rust\"}").unwrap()["q"],
+        json!("rust")
+    );
+    assert!(parse_tool_arguments("[1]").is_err());
+    assert!(parse_tool_arguments("{").is_err());
+}
+
+#[test]
+fn test_assistant_message_includes_tool_calls() {
+    let mut acc = StreamAccumulator::default();
+    acc.apply_chunk(&json!({"choices": [{"delta": {"tool_calls": [
+        {"index": 0, "id": "c1", "function": {"name": "fetch", "arguments": "{}"}}
+    ]}}]}));
+    let message = assistant_message(&acc.finish());
+    assert_eq!(message["role"], "assistant");
+    assert_eq!(message["tool_calls"][0]["function"]["name"], "fetch");
+
+    let plain = assistant_message(&StreamAccumulator::default().finish());
+    assert!(plain.get("tool_calls").is_none());
+    assert!(plain.get("reasoning_content").is_none());
+}
+
+#[test]
+fn test_accumulator_separates_reasoning() {
+    let mut acc = StreamAccumulator::default();
+    let mut reasoning = String::new();
+    for content in ["<think>count", "ing</think>", "Three"] {
+        acc.apply_chunk_with(
+            &json!({"choices": [{"delta": {"content": content}}]}),
+            &mut |delta| {
+                if let TurnDelta::Reasoning(text) = delta {
+                    reasoning.push_str(text);
+                }
+            },
+        );
+    }
+    assert_eq!(reasoning, "counting");
+    let message = assistant_message(&acc.finish());
+    assert_eq!(message["content"], "Three");
+    assert_eq!(message["reasoning_content"], "counting");
+}
+
+#[test]
+fn test_accumulator_keeps_stream_error() {
+    let mut acc = StreamAccumulator::default();
+    acc.apply_chunk(&json!({"choices": [{"delta": {"content": "Par"}}]}));
+    acc.apply_chunk(
+        &json!({"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}}),
+    );
+    acc.apply_chunk(&json!({"error": {"message": "Later", "code": "rate_limit_exceeded"}}));
+    let error = acc.take_error().unwrap();
+    assert_eq!(error.kind, ProviderErrorKind::Overloaded);
+    assert!(acc.take_error().is_none());
+    assert_eq!(
+        TurnError::Provider(error).to_string(),
+        "Model stream failed: Overloaded (overloaded)"
+    );
+    assert_eq!(acc.finish().content, "Par");
+}
+
+#[test]
+fn test_tools_to_openai() {
+    let tools = vec![ToolWithServer {
+        name: "fetch".to_string(),
+        description: Some("Fetch a URL".to_string()),
+        input_schema: json!({"type": "object"}),
+        server: "fetch".to_string(),
+    }];
+    let converted = tools_to_openai(&tools);
+    assert_eq!(converted[0]["type"], "function");
+    assert_eq!(converted[0]["function"]["parameters"]["type"], "object");
+}
+
+#[test]
+fn test_transcript_record_and_list() {
+    let app = tauri::test::mock_app();
+    let data_dir = get_jan_data_folder_path(app.handle().clone());
+    let run_id = "schedule:abc:1";
+    let recorder = TranscriptRecorder::new(run_id, Some("thread-1".to_string()), "llama");
+    recorder.record(&AgentEvent::ContentDelta {
+        run_id: run_id.to_string(),
+        delta: "Hi".to_string(),
+    });
+    recorder.record(&AgentEvent::ToolCall {
+        run_id: run_id.to_string(),
+        call: ToolCall {
+            id: "call_1".to_string(),
+            name: "web_search".to_string(),
+            arguments: "{}".to_string(),
+        },
+        server: Some("jan".to_string()),
+        wave: None,
+    });
+    recorder.record(&AgentEvent::Finished {
+        run_id: run_id.to_string(),
+        stop_reason: AgentStopReason::Completed,
+        iterations: 2,
+    });
+    save_transcript(&data_dir, &recorder.finish()).unwrap();
+
+    let transcript = read_transcript(&data_dir, run_id).unwrap();
+    assert_eq!(transcript.entries.len(), 3);
+    assert!(transcript
+        .entries
+        .windows(2)
+        .all(|pair| pair[0].offset_ms <= pair[1].offset_ms));
+
+    let summaries = list_transcripts(&data_dir, Some("thread-1"));
+    assert_eq!(summaries.len(), 1);
+    assert_eq!(summaries[0].tool_calls, 1);
+    assert_eq!(summaries[0].stop_reason, Some(AgentStopReason::Completed));
+    assert!(list_transcripts(&data_dir, Some("thread-2")).is_empty());
+
+    delete_transcript(&data_dir, run_id).unwrap();
+    assert!(read_transcript(&data_dir, run_id).is_err());
+    let _ = std::fs::remove_dir_all(data_dir);
+}
+
+fn call(id: &str, arguments: &str) -> ToolCall {
+    ToolCall {
+        id: id.to_string(),
+        name: "search".to_string(),
+        arguments: arguments.to_string(),
+    }
+}
+
+#[test]
+fn test_plan_waves_respects_references() {
+    let calls = vec![
+        call("call_a", r#"{"q":"rust"}"#),
+        call("call_b", r#"{"q":"tauri"}"#),
+        call("call_c", r#"{"from":"call_a"}"#),
+        call("call_d", r#"{"after":["call_c","call_b"]}"#),
+        call("call_e", "{}"),
+    ];
+    assert_eq!(dependencies(&calls, 3), vec![1, 2]);
+    assert_eq!(
+        plan_waves(&calls, false),
+        vec![vec![0, 1, 4], vec![2], vec![3]]
+    );
+    assert_eq!(
+        plan_waves(&calls, true),
+        vec![vec![0], vec![1], vec![2], vec![3], vec![4]]
+    );
+    assert!(plan_waves(&[], false).is_empty());
+
+    let parameters = json!({"parallel_tool_calls": false});
+    assert!(sequential_requested(parameters.as_object().unwrap()));
+    assert!(!sequential_requested(&serde_json::Map::new()));
+}
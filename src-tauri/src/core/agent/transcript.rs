@@ -0,0 +1,150 @@
+//! Recording of the full event sequence of agent runs (content deltas, tool calls, tool
+//! results and their timing) so a run can be inspected or replayed later.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+
+use super::constants::{MAX_TRANSCRIPTS, TRANSCRIPTS_DIR};
+use super::models::{AgentEvent, TranscriptEntry, TranscriptSummary, TurnTranscript};
+
+/// Collects the events of a running agent loop
+pub struct TranscriptRecorder {
+    run_id: String,
+    thread_id: Option<String>,
+    model: String,
+    started_at: i64,
+    started: Instant,
+    entries: Mutex<Vec<TranscriptEntry>>,
+}
+
+impl TranscriptRecorder {
+    pub fn new(run_id: &str, thread_id: Option<String>, model: &str) -> Self {
+        Self {
+            run_id: run_id.to_string(),
+            thread_id,
+            model: model.to_string(),
+            started_at: chrono::Utc::now().timestamp_millis(),
+            started: Instant::now(),
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn record(&self, event: &AgentEvent) {
+        let entry = TranscriptEntry {
+            offset_ms: self.started.elapsed().as_millis() as u64,
+            event: event.clone(),
+        };
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.push(entry);
+        }
+    }
+
+    pub fn finish(self) -> TurnTranscript {
+        TurnTranscript {
+            duration_ms: self.started.elapsed().as_millis() as u64,
+            entries: self.entries.into_inner().unwrap_or_default(),
+            run_id: self.run_id,
+            thread_id: self.thread_id,
+            model: self.model,
+            started_at: self.started_at,
+        }
+    }
+}
+
+pub fn get_transcripts_dir(data_folder: &Path) -> PathBuf {
+    data_folder.join(TRANSCRIPTS_DIR)
+}
+
+/// Run ids are client-chosen, so they are reduced to file-name-safe characters.
+fn transcript_path(data_folder: &Path, run_id: &str) -> PathBuf {
+    let name: String = run_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    get_transcripts_dir(data_folder).join(format!("{name}.json"))
+}
+
+/// Store a transcript and drop the oldest ones beyond `MAX_TRANSCRIPTS`.
+pub fn save_transcript(data_folder: &Path, transcript: &TurnTranscript) -> Result<(), String> {
+    let dir = get_transcripts_dir(data_folder);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let data = serde_json::to_string(transcript).map_err(|e| e.to_string())?;
+    fs::write(transcript_path(data_folder, &transcript.run_id), data).map_err(|e| e.to_string())?;
+
+    let mut files: Vec<(std::time::SystemTime, PathBuf)> = fs::read_dir(&dir)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .filter_map(|entry| {
+            let modified = entry.metadata().ok()?.modified().ok()?;
+            Some((modified, entry.path()))
+        })
+        .collect();
+    if files.len() > MAX_TRANSCRIPTS {
+        files.sort_by(|a, b| b.0.cmp(&a.0));
+        for (_, path) in files.into_iter().skip(MAX_TRANSCRIPTS) {
+            let _ = fs::remove_file(path);
+        }
+    }
+    Ok(())
+}
+
+pub fn read_transcript(data_folder: &Path, run_id: &str) -> Result<TurnTranscript, String> {
+    let data = fs::read_to_string(transcript_path(data_folder, run_id))
+        .map_err(|_| format!("No transcript recorded for run {run_id}"))?;
+    serde_json::from_str(&data).map_err(|e| format!("Invalid transcript for run {run_id}: {e}"))
+}
+
+pub fn delete_transcript(data_folder: &Path, run_id: &str) -> Result<(), String> {
+    fs::remove_file(transcript_path(data_folder, run_id))
+        .map_err(|_| format!("No transcript recorded for run {run_id}"))
+}
+
+pub fn summarize(transcript: &TurnTranscript) -> TranscriptSummary {
+    let mut tool_calls = 0;
+    let mut stop_reason = None;
+    for entry in &transcript.entries {
+        match &entry.event {
+            AgentEvent::ToolCall { .. } => tool_calls += 1,
+            AgentEvent::Finished {
+                stop_reason: reason,
+                ..
+            } => stop_reason = Some(reason.clone()),
+            _ => {}
+        }
+    }
+    TranscriptSummary {
+        run_id: transcript.run_id.clone(),
+        thread_id: transcript.thread_id.clone(),
+        model: transcript.model.clone(),
+        started_at: transcript.started_at,
+        duration_ms: transcript.duration_ms,
+        tool_calls,
+        stop_reason,
+    }
+}
+
+/// Summaries of stored transcripts, newest first, optionally limited to one thread.
+pub fn list_transcripts(data_folder: &Path, thread_id: Option<&str>) -> Vec<TranscriptSummary> {
+    let Ok(entries) = fs::read_dir(get_transcripts_dir(data_folder)) else {
+        return Vec::new();
+    };
+    let mut summaries: Vec<TranscriptSummary> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let data = fs::read_to_string(entry.path()).ok()?;
+            let transcript: TurnTranscript = serde_json::from_str(&data).ok()?;
+            Some(summarize(&transcript))
+        })
+        .filter(|summary| thread_id.map_or(true, |id| summary.thread_id.as_deref() == Some(id)))
+        .collect();
+    summaries.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    summaries
+}
@@ -4,6 +4,7 @@ use tauri::{AppHandle, Manager, Runtime, State};
 use super::{
     constants::CONFIGURATION_FILE_NAME, helpers::copy_dir_recursive, models::AppConfiguration,
 };
+use crate::core::config_store::helpers::config_store;
 use crate::core::state::AppState;
 
 /// Resolve the Jan config file path without an AppHandle (for CLI use).
@@ -77,7 +78,7 @@ pub fn get_app_configurations<R: Runtime>(app_handle: tauri::AppHandle<R>) -> Ap
 
         app_default_configuration.data_folder = default_data_folder;
 
-        if let Err(err) = fs::write(
+        if let Err(err) = config_store().write(
             &configuration_file,
             serde_json::to_string(&app_default_configuration).unwrap(),
         ) {
@@ -87,7 +88,7 @@ pub fn get_app_configurations<R: Runtime>(app_handle: tauri::AppHandle<R>) -> Ap
         return app_default_configuration;
     }
 
-    match fs::read_to_string(&configuration_file) {
+    match config_store().read(&configuration_file) {
         Ok(content) => {
             match serde_json::from_str::<AppConfiguration>(&content) {
                 Ok(app_configurations) => app_configurations,
@@ -114,11 +115,10 @@ pub fn update_app_configuration<R: Runtime>(
     let configuration_file = get_configuration_file_path(app_handle);
     log::info!("update_app_configuration, configuration_file: {configuration_file:?}");
 
-    fs::write(
-        configuration_file,
+    config_store().write(
+        &configuration_file,
         serde_json::to_string(&configuration).map_err(|e| e.to_string())?,
     )
-    .map_err(|e| e.to_string())
 }
 
 #[tauri::command]
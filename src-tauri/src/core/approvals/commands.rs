@@ -0,0 +1,87 @@
+use tauri::{AppHandle, Runtime, State};
+
+use super::helpers::{ensure_unlocked, is_locked, read_policies, upsert_rule, write_policies};
+use super::models::{ApprovalResponse, PolicyDecision, PolicyRule, ToolPolicies};
+use super::ToolApprovalState;
+use crate::core::app::commands::get_jan_data_folder_path;
+
+/// Returns the current tool policies. `locked` reflects the effective admin lock.
+#[tauri::command]
+pub async fn get_tool_policies<R: Runtime>(app: AppHandle<R>) -> Result<ToolPolicies, String> {
+    let mut policies = read_policies(&get_jan_data_folder_path(app))?;
+    policies.locked = is_locked(&policies);
+    Ok(policies)
+}
+
+/// Adds or replaces the rule for a server/tool/assistant target.
+#[tauri::command]
+pub async fn set_tool_policy<R: Runtime>(
+    app: AppHandle<R>,
+    rule: PolicyRule,
+) -> Result<(), String> {
+    let data_folder = get_jan_data_folder_path(app);
+    let mut policies = read_policies(&data_folder)?;
+    ensure_unlocked(&policies)?;
+    upsert_rule(&mut policies, rule);
+    write_policies(&data_folder, &policies)
+}
+
+/// Removes the rule for a server/tool/assistant target.
+#[tauri::command]
+pub async fn remove_tool_policy<R: Runtime>(
+    app: AppHandle<R>,
+    server: String,
+    tool: Option<String>,
+    assistant_id: Option<String>,
+) -> Result<(), String> {
+    let data_folder = get_jan_data_folder_path(app);
+    let mut policies = read_policies(&data_folder)?;
+    ensure_unlocked(&policies)?;
+    policies
+        .rules
+        .retain(|r| !(r.server == server && r.tool == tool && r.assistant_id == assistant_id));
+    write_policies(&data_folder, &policies)
+}
+
+/// Sets the decision used when no rule matches.
+#[tauri::command]
+pub async fn set_default_tool_policy<R: Runtime>(
+    app: AppHandle<R>,
+    decision: PolicyDecision,
+) -> Result<(), String> {
+    let data_folder = get_jan_data_folder_path(app);
+    let mut policies = read_policies(&data_folder)?;
+    ensure_unlocked(&policies)?;
+    policies.default_decision = decision;
+    write_policies(&data_folder, &policies)
+}
+
+/// Enables the admin-locked mode. Unlocking requires editing `tool_policies.json` directly.
+#[tauri::command]
+pub async fn lock_tool_policies<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    let data_folder = get_jan_data_folder_path(app);
+    let mut policies = read_policies(&data_folder)?;
+    policies.locked = true;
+    write_policies(&data_folder, &policies)
+}
+
+/// Answers a pending `tool-approval-required` request.
+#[tauri::command]
+pub async fn respond_tool_approval(
+    approvals: State<'_, ToolApprovalState>,
+    approval_id: String,
+    approved: bool,
+    remember: Option<bool>,
+) -> Result<(), String> {
+    let sender = approvals
+        .pending
+        .lock()
+        .await
+        .remove(&approval_id)
+        .ok_or_else(|| format!("Approval request {approval_id} not found"))?;
+    let _ = sender.send(ApprovalResponse {
+        approved,
+        remember: remember.unwrap_or(false),
+    });
+    Ok(())
+}
@@ -0,0 +1,9 @@
+// Tool approval constants
+pub const TOOL_POLICIES_FILE: &str = "tool_policies.json";
+pub const TOOL_APPROVAL_EVENT: &str = "tool-approval-required";
+/// Environment variable forcing the admin-locked mode
+pub const TOOL_POLICY_LOCK_ENV: &str = "JAN_TOOL_POLICY_LOCKED";
+/// Unanswered approval requests are denied after this delay
+pub const APPROVAL_TIMEOUT_SECS: u64 = 300;
+/// Wildcard matching every server
+pub const ANY_SERVER: &str = "*";
@@ -0,0 +1,160 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Emitter, Runtime};
+use tokio::sync::oneshot;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+use super::constants::{
+    APPROVAL_TIMEOUT_SECS, TOOL_APPROVAL_EVENT, TOOL_POLICIES_FILE, TOOL_POLICY_LOCK_ENV,
+};
+use super::models::{
+    ApprovalResponse, PolicyDecision, PolicyRule, ToolApprovalRequest, ToolPolicies,
+};
+use super::ToolApprovalState;
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::config_store::helpers::write_json;
+
+pub fn get_policies_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(TOOL_POLICIES_FILE)
+}
+
+pub fn read_policies(data_folder: &Path) -> Result<ToolPolicies, String> {
+    let path = get_policies_path(data_folder);
+    if !path.exists() {
+        return Ok(ToolPolicies::default());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&data).map_err(|e| format!("Failed to parse tool policies: {e}"))
+}
+
+pub fn write_policies(data_folder: &Path, policies: &ToolPolicies) -> Result<(), String> {
+    write_json(&get_policies_path(data_folder), policies)
+}
+
+/// Whether policies are admin-locked, either by the file or the environment
+pub fn is_locked(policies: &ToolPolicies) -> bool {
+    policies.locked
+        || std::env::var(TOOL_POLICY_LOCK_ENV)
+            .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+            .unwrap_or(false)
+}
+
+pub fn ensure_unlocked(policies: &ToolPolicies) -> Result<(), String> {
+    if is_locked(policies) {
+        return Err("Tool policies are locked by an administrator".to_string());
+    }
+    Ok(())
+}
+
+/// Evaluate the decision for a call: the most specific matching rule wins,
+/// ties resolve to the most restrictive decision.
+pub fn evaluate(
+    policies: &ToolPolicies,
+    server: &str,
+    tool: &str,
+    assistant_id: Option<&str>,
+) -> PolicyDecision {
+    policies
+        .rules
+        .iter()
+        .filter(|r| r.matches(server, tool, assistant_id))
+        .max_by_key(|r| (r.specificity(), r.decision))
+        .map(|r| r.decision)
+        .unwrap_or(policies.default_decision)
+}
+
+/// Insert a rule, replacing any existing rule for the same target
+pub fn upsert_rule(policies: &mut ToolPolicies, rule: PolicyRule) {
+    policies.rules.retain(|r| !r.same_target(&rule));
+    policies.rules.push(rule);
+}
+
+/// Check the policy for a tool call, asking the user when required.
+/// Returns an error describing why the call may not proceed.
+pub async fn authorize_tool_call<R: Runtime>(
+    app: &AppHandle<R>,
+    approvals: &ToolApprovalState,
+    server: &str,
+    tool: &str,
+    arguments: Option<&Map<String, Value>>,
+    assistant_id: Option<&str>,
+    cancel: Option<&CancellationToken>,
+) -> Result<(), String> {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let policies = read_policies(&data_folder)?;
+    match evaluate(&policies, server, tool, assistant_id) {
+        PolicyDecision::Allow => return Ok(()),
+        PolicyDecision::Deny => {
+            return Err(format!(
+                "Tool call '{tool}' on server '{server}' is denied by policy"
+            ))
+        }
+        PolicyDecision::Ask => {}
+    }
+
+    let approval_id = Uuid::new_v4().to_string();
+    let (tx, rx) = oneshot::channel::<ApprovalResponse>();
+    approvals
+        .pending
+        .lock()
+        .await
+        .insert(approval_id.clone(), tx);
+
+    let request = ToolApprovalRequest {
+        approval_id: approval_id.clone(),
+        server: server.to_string(),
+        tool: tool.to_string(),
+        arguments: arguments.cloned().map(Value::Object).unwrap_or(Value::Null),
+        assistant_id: assistant_id.map(|a| a.to_string()),
+        can_remember: !is_locked(&policies),
+    };
+    if let Err(e) = app.emit(TOOL_APPROVAL_EVENT, &request) {
+        log::error!("Failed to emit tool approval event: {e}");
+    }
+
+    let never = CancellationToken::new();
+    let cancel = cancel.unwrap_or(&never);
+    let response = tokio::select! {
+        response = tokio::time::timeout(Duration::from_secs(APPROVAL_TIMEOUT_SECS), rx) => {
+            response.ok().and_then(|r| r.ok())
+        }
+        _ = cancel.cancelled() => None,
+    };
+    approvals.pending.lock().await.remove(&approval_id);
+    let response = response.unwrap_or_default();
+
+    if response.remember {
+        // Re-read so concurrent changes are not lost
+        let mut policies = read_policies(&data_folder)?;
+        if is_locked(&policies) {
+            log::warn!("Not remembering tool approval: policies are locked");
+        } else {
+            upsert_rule(
+                &mut policies,
+                PolicyRule {
+                    server: server.to_string(),
+                    tool: Some(tool.to_string()),
+                    assistant_id: assistant_id.map(|a| a.to_string()),
+                    decision: if response.approved {
+                        PolicyDecision::Allow
+                    } else {
+                        PolicyDecision::Deny
+                    },
+                },
+            );
+            write_policies(&data_folder, &policies)?;
+        }
+    }
+
+    if response.approved {
+        Ok(())
+    } else {
+        Err(format!(
+            "Tool call '{tool}' on server '{server}' was not approved"
+        ))
+    }
+}
@@ -0,0 +1,34 @@
+/*!
+   Tool Approval Policies
+
+   Every MCP tool call is checked against a policy before it runs. Rules map a server, an
+   optional tool and an optional assistant to `allow`, `ask` or `deny`; the most specific
+   matching rule wins and ties resolve to the most restrictive decision.
+
+   `ask` emits a `tool-approval-required` event carrying the call arguments and waits for
+   `respond_tool_approval`. The user may ask to remember the choice, which persists a rule.
+   Policies live in `tool_policies.json` in the data folder. When the file is marked
+   `locked` (or `JAN_TOOL_POLICY_LOCKED` is set) policies can only be changed by an
+   administrator editing the file, never from the app.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::sync::{oneshot, Mutex};
+
+use models::ApprovalResponse;
+
+/// Pending approval requests awaiting an answer from the user
+#[derive(Default)]
+pub struct ToolApprovalState {
+    pub pending: Arc<Mutex<HashMap<String, oneshot::Sender<ApprovalResponse>>>>,
+}
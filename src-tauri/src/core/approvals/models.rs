@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// What to do when a tool is called
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyDecision {
+    #[default]
+    Allow,
+    Ask,
+    Deny,
+}
+
+/// A single policy rule. `tool` and `assistant_id` narrow the rule when set.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PolicyRule {
+    /// Server name, or `*` for every server
+    pub server: String,
+    #[serde(default)]
+    pub tool: Option<String>,
+    #[serde(default)]
+    pub assistant_id: Option<String>,
+    pub decision: PolicyDecision,
+}
+
+impl PolicyRule {
+    pub fn matches(&self, server: &str, tool: &str, assistant_id: Option<&str>) -> bool {
+        (self.server == super::constants::ANY_SERVER || self.server == server)
+            && self.tool.as_deref().map_or(true, |t| t == tool)
+            && self
+                .assistant_id
+                .as_deref()
+                .map_or(true, |a| Some(a) == assistant_id)
+    }
+
+    /// Higher is more specific: assistant > tool > concrete server
+    pub fn specificity(&self) -> u8 {
+        let mut score = 0;
+        if self.assistant_id.is_some() {
+            score += 4;
+        }
+        if self.tool.is_some() {
+            score += 2;
+        }
+        if self.server != super::constants::ANY_SERVER {
+            score += 1;
+        }
+        score
+    }
+
+    /// Whether two rules target exactly the same calls
+    pub fn same_target(&self, other: &PolicyRule) -> bool {
+        self.server == other.server
+            && self.tool == other.tool
+            && self.assistant_id == other.assistant_id
+    }
+}
+
+/// Contents of `tool_policies.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolPolicies {
+    /// Decision used when no rule matches
+    #[serde(default)]
+    pub default_decision: PolicyDecision,
+    #[serde(default)]
+    pub rules: Vec<PolicyRule>,
+    /// Admin-locked: policies cannot be modified from the app
+    #[serde(default)]
+    pub locked: bool,
+}
+
+/// Payload of the `tool-approval-required` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolApprovalRequest {
+    pub approval_id: String,
+    pub server: String,
+    pub tool: String,
+    pub arguments: Value,
+    pub assistant_id: Option<String>,
+    /// Whether "remember my choice" can be offered (false in admin-locked mode)
+    pub can_remember: bool,
+}
+
+/// Answer to an approval request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApprovalResponse {
+    pub approved: bool,
+    /// Persist the choice as a rule for this server/tool/assistant
+    #[serde(default)]
+    pub remember: bool,
+}
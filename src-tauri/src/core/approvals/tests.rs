@@ -0,0 +1,123 @@
+use super::helpers::{evaluate, upsert_rule};
+use super::models::{PolicyDecision, PolicyRule, ToolPolicies};
+
+fn rule(
+    server: &str,
+    tool: Option<&str>,
+    assistant: Option<&str>,
+    decision: PolicyDecision,
+) -> PolicyRule {
+    PolicyRule {
+        server: server.to_string(),
+        tool: tool.map(|t| t.to_string()),
+        assistant_id: assistant.map(|a| a.to_string()),
+        decision,
+    }
+}
+
+#[test]
+fn test_default_decision_when_no_rule_matches() {
+    let policies = ToolPolicies::default();
+    assert_eq!(
+        evaluate(&policies, "fetch", "fetch", None),
+        PolicyDecision::Allow
+    );
+
+    let policies = ToolPolicies {
+        default_decision: PolicyDecision::Ask,
+        ..Default::default()
+    };
+    assert_eq!(
+        evaluate(&policies, "fetch", "fetch", None),
+        PolicyDecision::Ask
+    );
+}
+
+#[test]
+fn test_most_specific_rule_wins() {
+    let policies = ToolPolicies {
+        rules: vec![
+            rule("*", None, None, PolicyDecision::Ask),
+            rule("filesystem", None, None, PolicyDecision::Deny),
+            rule("filesystem", Some("read_file"), None, PolicyDecision::Allow),
+            rule(
+                "filesystem",
+                Some("read_file"),
+                Some("coder"),
+                PolicyDecision::Deny,
+            ),
+        ],
+        ..Default::default()
+    };
+    assert_eq!(
+        evaluate(&policies, "fetch", "fetch", None),
+        PolicyDecision::Ask
+    );
+    assert_eq!(
+        evaluate(&policies, "filesystem", "write_file", None),
+        PolicyDecision::Deny
+    );
+    assert_eq!(
+        evaluate(&policies, "filesystem", "read_file", None),
+        PolicyDecision::Allow
+    );
+    assert_eq!(
+        evaluate(&policies, "filesystem", "read_file", Some("coder")),
+        PolicyDecision::Deny
+    );
+    assert_eq!(
+        evaluate(&policies, "filesystem", "read_file", Some("writer")),
+        PolicyDecision::Allow
+    );
+}
+
+#[test]
+fn test_ties_resolve_to_most_restrictive() {
+    let policies = ToolPolicies {
+        rules: vec![
+            rule("fetch", Some("fetch"), None, PolicyDecision::Allow),
+            rule("*", Some("fetch"), Some("a"), PolicyDecision::Ask),
+            rule("fetch", None, Some("a"), PolicyDecision::Deny),
+        ],
+        ..Default::default()
+    };
+    // assistant+tool on any server (6) beats assistant on server (5)
+    assert_eq!(
+        evaluate(&policies, "fetch", "fetch", Some("a")),
+        PolicyDecision::Ask
+    );
+
+    let tied = ToolPolicies {
+        rules: vec![
+            rule("fetch", Some("fetch"), None, PolicyDecision::Allow),
+            rule("fetch", Some("fetch"), None, PolicyDecision::Deny),
+        ],
+        ..Default::default()
+    };
+    assert_eq!(
+        evaluate(&tied, "fetch", "fetch", None),
+        PolicyDecision::Deny
+    );
+}
+
+#[test]
+fn test_upsert_rule_replaces_same_target() {
+    let mut policies = ToolPolicies::default();
+    upsert_rule(
+        &mut policies,
+        rule("fetch", Some("fetch"), None, PolicyDecision::Ask),
+    );
+    upsert_rule(
+        &mut policies,
+        rule("fetch", Some("fetch"), None, PolicyDecision::Allow),
+    );
+    upsert_rule(
+        &mut policies,
+        rule("fetch", None, None, PolicyDecision::Deny),
+    );
+    assert_eq!(policies.rules.len(), 2);
+    assert_eq!(
+        evaluate(&policies, "fetch", "fetch", None),
+        PolicyDecision::Allow
+    );
+}
@@ -0,0 +1,154 @@
+use std::fs;
+use std::path::Path;
+
+use tauri::Runtime;
+use uuid::Uuid;
+
+use super::helpers::{
+    get_assistant_path, read_all_assistants, read_assistant, validate_assistant_id, write_assistant,
+};
+use super::models::{Assistant, AssistantExport, ToolScope};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::config_history::helpers::record_config_change;
+use crate::core::config_store::helpers::write_atomic;
+
+const ASSISTANT_EXPORT_VERSION: u32 = 1;
+
+/// Lists all assistants stored in the data folder.
+#[tauri::command]
+pub async fn list_assistants<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+) -> Result<Vec<Assistant>, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    read_all_assistants(&data_folder)
+}
+
+/// Retrieves a single assistant by id.
+#[tauri::command]
+pub async fn get_assistant<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    assistant_id: String,
+) -> Result<Assistant, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    read_assistant(&data_folder, &assistant_id)
+}
+
+/// Creates a new assistant. A unique id is assigned when none is provided.
+/// Returns an error if an assistant with the same id already exists.
+#[tauri::command]
+pub async fn create_assistant<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    mut assistant: Assistant,
+) -> Result<Assistant, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    if assistant.id.trim().is_empty() {
+        assistant.id = Uuid::new_v4().to_string();
+    }
+    validate_assistant_id(&assistant.id)?;
+    if get_assistant_path(&data_folder, &assistant.id).exists() {
+        return Err(format!("Assistant {} already exists", assistant.id));
+    }
+    write_assistant(&data_folder, &assistant)?;
+    record_config_change(&data_folder, &format!("Add assistant {}", assistant.id));
+    Ok(assistant)
+}
+
+/// Overwrites an existing assistant definition.
+#[tauri::command]
+pub async fn update_assistant<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    assistant: Assistant,
+) -> Result<Assistant, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    validate_assistant_id(&assistant.id)?;
+    if !get_assistant_path(&data_folder, &assistant.id).exists() {
+        return Err(format!("Assistant {} not found", assistant.id));
+    }
+    write_assistant(&data_folder, &assistant)?;
+    record_config_change(&data_folder, &format!("Update assistant {}", assistant.id));
+    Ok(assistant)
+}
+
+/// Deletes an assistant and its directory.
+#[tauri::command]
+pub async fn delete_assistant<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    assistant_id: String,
+) -> Result<(), String> {
+    validate_assistant_id(&assistant_id)?;
+    let data_folder = get_jan_data_folder_path(app_handle);
+    let path = get_assistant_path(&data_folder, &assistant_id);
+    if let Some(dir) = path.parent() {
+        if dir.exists() {
+            fs::remove_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+    }
+    record_config_change(&data_folder, &format!("Delete assistant {assistant_id}"));
+    Ok(())
+}
+
+/// Sets (or clears, when `None`) the MCP tool scope of an assistant.
+#[tauri::command]
+pub async fn set_assistant_tool_scope<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    assistant_id: String,
+    tool_scope: Option<ToolScope>,
+) -> Result<Assistant, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    let mut assistant = read_assistant(&data_folder, &assistant_id)?;
+    assistant.tool_scope = tool_scope;
+    write_assistant(&data_folder, &assistant)?;
+    record_config_change(
+        &data_folder,
+        &format!("Set the tool scope of assistant {assistant_id}"),
+    );
+    Ok(assistant)
+}
+
+/// Exports an assistant definition to a standalone JSON file.
+#[tauri::command]
+pub async fn export_assistant<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    assistant_id: String,
+    path: String,
+) -> Result<(), String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    let assistant = read_assistant(&data_folder, &assistant_id)?;
+    let export = AssistantExport {
+        version: ASSISTANT_EXPORT_VERSION,
+        assistant,
+    };
+    let data = serde_json::to_string_pretty(&export).map_err(|e| e.to_string())?;
+    write_atomic(Path::new(&path), data.as_bytes())
+}
+
+/// Imports an assistant definition previously produced by `export_assistant`.
+/// When the id is already taken a new id is assigned unless `overwrite` is set.
+#[tauri::command]
+pub async fn import_assistant<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    path: String,
+    overwrite: Option<bool>,
+) -> Result<Assistant, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    let data =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read assistant export: {e}"))?;
+    let export: AssistantExport =
+        serde_json::from_str(&data).map_err(|e| format!("Invalid assistant export: {e}"))?;
+    if export.version > ASSISTANT_EXPORT_VERSION {
+        return Err(format!(
+            "Unsupported assistant export version {}",
+            export.version
+        ));
+    }
+
+    let mut assistant = export.assistant;
+    if validate_assistant_id(&assistant.id).is_err()
+        || (get_assistant_path(&data_folder, &assistant.id).exists() && !overwrite.unwrap_or(false))
+    {
+        assistant.id = Uuid::new_v4().to_string();
+    }
+    write_assistant(&data_folder, &assistant)?;
+    record_config_change(&data_folder, &format!("Import assistant {}", assistant.id));
+    Ok(assistant)
+}
@@ -0,0 +1,3 @@
+// Assistant Constants
+pub const ASSISTANTS_DIR: &str = "assistants";
+pub const ASSISTANT_FILE: &str = "assistant.json";
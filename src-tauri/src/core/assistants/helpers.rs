@@ -0,0 +1,78 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::constants::{ASSISTANTS_DIR, ASSISTANT_FILE};
+use super::models::{Assistant, ToolScope};
+use crate::core::config_store::helpers::write_json;
+
+pub fn get_assistants_dir(data_folder: &Path) -> PathBuf {
+    data_folder.join(ASSISTANTS_DIR)
+}
+
+pub fn get_assistant_path(data_folder: &Path, assistant_id: &str) -> PathBuf {
+    get_assistants_dir(data_folder)
+        .join(assistant_id)
+        .join(ASSISTANT_FILE)
+}
+
+/// Reject ids that would escape the assistants directory
+pub fn validate_assistant_id(assistant_id: &str) -> Result<(), String> {
+    if assistant_id.is_empty()
+        || assistant_id.contains(['/', '\\'])
+        || assistant_id == "."
+        || assistant_id == ".."
+    {
+        return Err(format!("Invalid assistant id: '{assistant_id}'"));
+    }
+    Ok(())
+}
+
+pub fn read_assistant(data_folder: &Path, assistant_id: &str) -> Result<Assistant, String> {
+    validate_assistant_id(assistant_id)?;
+    let path = get_assistant_path(data_folder, assistant_id);
+    if !path.exists() {
+        return Err(format!("Assistant {assistant_id} not found"));
+    }
+    let data = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&data).map_err(|e| format!("Failed to parse assistant: {e}"))
+}
+
+pub fn write_assistant(data_folder: &Path, assistant: &Assistant) -> Result<(), String> {
+    validate_assistant_id(&assistant.id)?;
+    // Atomic, through the config store, so a crash never leaves a truncated assistant
+    write_json(&get_assistant_path(data_folder, &assistant.id), assistant)
+}
+
+/// Read every assistant in the data folder, skipping invalid files
+pub fn read_all_assistants(data_folder: &Path) -> Result<Vec<Assistant>, String> {
+    let dir = get_assistants_dir(data_folder);
+    let mut assistants = Vec::new();
+    if !dir.exists() {
+        return Ok(assistants);
+    }
+    for entry in fs::read_dir(&dir).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let path = entry.path().join(ASSISTANT_FILE);
+        if !path.exists() {
+            continue;
+        }
+        let data = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+        match serde_json::from_str::<Assistant>(&data) {
+            Ok(assistant) => assistants.push(assistant),
+            Err(e) => log::warn!("Skipping invalid assistant file {}: {e}", path.display()),
+        }
+    }
+    Ok(assistants)
+}
+
+/// Resolve the tool scope for an optional assistant id.
+/// Returns `None` (unrestricted) when no assistant is given or the assistant has no scope.
+pub fn resolve_tool_scope(
+    data_folder: &Path,
+    assistant_id: Option<&str>,
+) -> Result<Option<ToolScope>, String> {
+    match assistant_id {
+        Some(id) => Ok(read_assistant(data_folder, id)?.tool_scope),
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,17 @@
+/*!
+   Assistant Profiles
+
+   Assistants are persisted as `assistants/<id>/assistant.json` in the Jan data folder, the same
+   layout used by the assistant extension. On top of the name, instructions (system prompt),
+   model and generation parameters, each assistant may carry a `tool_scope` restricting which
+   MCP servers and tools it is allowed to invoke. The scope is enforced by the MCP tool-call
+   path, so it cannot be bypassed by the frontend.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::core::lora::models::LoraSelection;
+
+/// MCP servers and tools an assistant is allowed to invoke
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ToolScope {
+    /// Servers the assistant may call tools on
+    #[serde(default)]
+    pub allowed_servers: Vec<String>,
+    /// Optional per-server tool allow-list. Servers without an entry expose all their tools.
+    #[serde(default)]
+    pub allowed_tools: HashMap<String, Vec<String>>,
+}
+
+impl ToolScope {
+    /// Whether the assistant may use any tool of `server`
+    pub fn permits_server(&self, server: &str) -> bool {
+        self.allowed_servers.iter().any(|s| s == server)
+    }
+
+    /// Whether the assistant may call `tool` on `server`
+    pub fn permits(&self, server: &str, tool: &str) -> bool {
+        if !self.permits_server(server) {
+            return false;
+        }
+        match self.allowed_tools.get(server) {
+            Some(tools) => tools.iter().any(|t| t == tool),
+            None => true,
+        }
+    }
+}
+
+/// Assistant definition as stored in `assistant.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Assistant {
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// System prompt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Generation parameters such as `temperature` or `top_p`
+    #[serde(default)]
+    pub parameters: Map<String, Value>,
+    /// `None` means the assistant may use every connected MCP server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_scope: Option<ToolScope>,
+    /// LoRA adapters applied to the local model while this assistant is active
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lora_adapters: Vec<LoraSelection>,
+    /// Fields owned by other components are preserved as-is
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl Assistant {
+    pub fn temperature(&self) -> Option<f64> {
+        self.parameters.get("temperature").and_then(|v| v.as_f64())
+    }
+}
+
+/// Portable envelope used by import/export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssistantExport {
+    pub version: u32,
+    pub assistant: Assistant,
+}
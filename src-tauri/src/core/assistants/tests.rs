@@ -0,0 +1,96 @@
+use super::commands::*;
+use super::models::{Assistant, ToolScope};
+use crate::core::app::commands::get_jan_data_folder_path;
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs;
+use tauri::test::mock_app;
+
+fn test_assistant(id: &str) -> Assistant {
+    serde_json::from_value(json!({
+        "id": id,
+        "name": "Researcher",
+        "instructions": "You are a careful researcher.",
+        "parameters": { "temperature": 0.2 },
+        "created_at": 123
+    }))
+    .unwrap()
+}
+
+#[test]
+fn test_tool_scope_permits() {
+    let scope = ToolScope {
+        allowed_servers: vec!["fetch".to_string(), "filesystem".to_string()],
+        allowed_tools: HashMap::from([("filesystem".to_string(), vec!["read_file".to_string()])]),
+    };
+    assert!(scope.permits("fetch", "fetch"));
+    assert!(scope.permits("filesystem", "read_file"));
+    assert!(!scope.permits("filesystem", "write_file"));
+    assert!(!scope.permits("exa", "web_search"));
+}
+
+#[test]
+fn test_assistant_preserves_unknown_fields() {
+    let assistant = test_assistant("researcher");
+    assert_eq!(assistant.temperature(), Some(0.2));
+    let value = serde_json::to_value(&assistant).unwrap();
+    assert_eq!(value["created_at"], 123);
+}
+
+#[tokio::test]
+async fn test_assistant_crud_and_import_export() {
+    let app = mock_app();
+    let data_dir = get_jan_data_folder_path(app.handle().clone());
+
+    let created = create_assistant(app.handle().clone(), test_assistant("researcher"))
+        .await
+        .unwrap();
+    assert!(
+        create_assistant(app.handle().clone(), test_assistant("researcher"))
+            .await
+            .is_err()
+    );
+
+    let scoped = set_assistant_tool_scope(
+        app.handle().clone(),
+        created.id.clone(),
+        Some(ToolScope {
+            allowed_servers: vec!["fetch".to_string()],
+            allowed_tools: HashMap::new(),
+        }),
+    )
+    .await
+    .unwrap();
+    assert!(scoped.tool_scope.unwrap().permits("fetch", "fetch"));
+
+    let export_path = data_dir.join("researcher.export.json");
+    export_assistant(
+        app.handle().clone(),
+        created.id.clone(),
+        export_path.to_string_lossy().to_string(),
+    )
+    .await
+    .unwrap();
+    let imported = import_assistant(
+        app.handle().clone(),
+        export_path.to_string_lossy().to_string(),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_ne!(imported.id, created.id);
+    assert_eq!(imported.name, created.name);
+    assert_eq!(
+        list_assistants(app.handle().clone()).await.unwrap().len(),
+        2
+    );
+
+    delete_assistant(app.handle().clone(), created.id.clone())
+        .await
+        .unwrap();
+    assert!(get_assistant(app.handle().clone(), created.id)
+        .await
+        .is_err());
+
+    let _ = fs::remove_dir_all(data_dir);
+}
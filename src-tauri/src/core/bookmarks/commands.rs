@@ -0,0 +1,87 @@
+use tauri::{AppHandle, Runtime};
+
+use super::constants::DEFAULT_PINNED_LIMIT;
+use super::helpers::{
+    delete_bookmark, delete_pin, load_store, normalize_tags, save_bookmark, save_pin,
+    set_pinned_flag,
+};
+use super::models::{PinnedMessage, ThreadBookmark};
+
+/// Bookmarks a thread with optional tags and note, or updates its existing bookmark.
+#[tauri::command]
+pub async fn bookmark_thread<R: Runtime>(
+    app_handle: AppHandle<R>,
+    thread_id: String,
+    tags: Vec<String>,
+    note: Option<String>,
+) -> Result<ThreadBookmark, String> {
+    let bookmark = ThreadBookmark {
+        thread_id,
+        tags: normalize_tags(tags)?,
+        note: note.filter(|n| !n.trim().is_empty()),
+        created_at: chrono::Utc::now().timestamp_millis(),
+    };
+    save_bookmark(&app_handle, bookmark).await
+}
+
+/// Removes the bookmark of a thread.
+#[tauri::command]
+pub async fn remove_thread_bookmark<R: Runtime>(
+    app_handle: AppHandle<R>,
+    thread_id: String,
+) -> Result<(), String> {
+    delete_bookmark(&app_handle, &thread_id).await
+}
+
+/// Lists bookmarked threads, newest first, optionally only those carrying `tag`.
+#[tauri::command]
+pub async fn list_thread_bookmarks<R: Runtime>(
+    app_handle: AppHandle<R>,
+    tag: Option<String>,
+) -> Result<Vec<ThreadBookmark>, String> {
+    let store = load_store(&app_handle).await?;
+    Ok(store.bookmarks_with_tag(tag.as_deref()))
+}
+
+/// Pins a message so it is always part of the assembled context, tagging the pin.
+#[tauri::command]
+pub async fn pin_message<R: Runtime>(
+    app_handle: AppHandle<R>,
+    thread_id: String,
+    message_id: String,
+    tags: Vec<String>,
+) -> Result<PinnedMessage, String> {
+    let tags = normalize_tags(tags)?;
+    set_pinned_flag(&app_handle, &thread_id, &message_id, true).await?;
+    save_pin(&app_handle, &thread_id, &message_id, Some(tags)).await
+}
+
+/// Unpins a message. The pin is dropped even when the message no longer exists.
+#[tauri::command]
+pub async fn unpin_message<R: Runtime>(
+    app_handle: AppHandle<R>,
+    thread_id: String,
+    message_id: String,
+) -> Result<(), String> {
+    delete_pin(&app_handle, &thread_id, &message_id).await?;
+    if let Err(e) = set_pinned_flag(&app_handle, &thread_id, &message_id, false).await {
+        log::warn!("Failed to clear the pinned flag of message {message_id}: {e}");
+    }
+    Ok(())
+}
+
+/// Lists the most recently pinned messages, optionally of one thread and carrying `tag`.
+#[tauri::command]
+pub async fn list_pinned_messages<R: Runtime>(
+    app_handle: AppHandle<R>,
+    thread_id: Option<String>,
+    tag: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<PinnedMessage>, String> {
+    let store = load_store(&app_handle).await?;
+    Ok(store.recent_pins(
+        thread_id.as_deref(),
+        tag.as_deref(),
+        limit.unwrap_or(DEFAULT_PINNED_LIMIT),
+    ))
+}
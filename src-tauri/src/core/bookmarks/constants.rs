@@ -0,0 +1,6 @@
+// Bookmark constants
+pub const BOOKMARKS_FILE: &str = "bookmarks.json";
+/// Pins returned by `list_pinned_messages` when no limit is given
+pub const DEFAULT_PINNED_LIMIT: usize = 50;
+pub const MAX_TAGS: usize = 20;
+pub const MAX_TAG_CHARS: usize = 64;
@@ -0,0 +1,127 @@
+//! SQLite storage of bookmarks and pins, next to the threads and messages they refer to.
+//! Rows are stored as JSON like threads and messages, and are removed with them through
+//! `ON DELETE CASCADE`.
+
+use sqlx::{Row, SqlitePool};
+
+use super::models::{BookmarkStore, PinnedMessage, ThreadBookmark};
+use crate::core::threads::db::get_pool;
+
+/// Create the bookmark tables; part of the database migrations
+pub async fn create_tables(pool: &SqlitePool) -> Result<(), String> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS thread_bookmarks (
+            thread_id TEXT PRIMARY KEY,
+            data TEXT NOT NULL,
+            FOREIGN KEY (thread_id) REFERENCES threads(id) ON DELETE CASCADE
+        );
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to create thread_bookmarks table: {}", e))?;
+
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS pinned_messages (
+            message_id TEXT PRIMARY KEY,
+            thread_id TEXT NOT NULL,
+            data TEXT NOT NULL,
+            FOREIGN KEY (message_id) REFERENCES messages(id) ON DELETE CASCADE
+        );
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| format!("Failed to create pinned_messages table: {}", e))?;
+
+    Ok(())
+}
+
+fn parse_rows<T: serde::de::DeserializeOwned>(
+    rows: &[sqlx::sqlite::SqliteRow],
+) -> Result<Vec<T>, String> {
+    rows.iter()
+        .map(|row| {
+            let data: String = row.get("data");
+            serde_json::from_str(&data).map_err(|e| e.to_string())
+        })
+        .collect()
+}
+
+/// Load all bookmarks and pins
+pub async fn db_load_store() -> Result<BookmarkStore, String> {
+    let pool = get_pool().await?;
+
+    let bookmarks = sqlx::query("SELECT data FROM thread_bookmarks")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| format!("Failed to list bookmarks: {}", e))?;
+    let pins = sqlx::query("SELECT data FROM pinned_messages")
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| format!("Failed to list pinned messages: {}", e))?;
+
+    Ok(BookmarkStore {
+        bookmarks: parse_rows(&bookmarks)?,
+        pins: parse_rows(&pins)?,
+    })
+}
+
+/// Insert or replace the bookmark of a thread
+pub async fn db_put_bookmark(bookmark: &ThreadBookmark) -> Result<(), String> {
+    let pool = get_pool().await?;
+    let data = serde_json::to_string(bookmark).map_err(|e| e.to_string())?;
+
+    sqlx::query("INSERT OR REPLACE INTO thread_bookmarks (thread_id, data) VALUES (?1, ?2)")
+        .bind(&bookmark.thread_id)
+        .bind(&data)
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to save bookmark: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn db_delete_bookmark(thread_id: &str) -> Result<(), String> {
+    let pool = get_pool().await?;
+
+    sqlx::query("DELETE FROM thread_bookmarks WHERE thread_id = ?1")
+        .bind(thread_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to delete bookmark: {}", e))?;
+
+    Ok(())
+}
+
+/// Insert or replace the pin of a message
+pub async fn db_put_pin(pin: &PinnedMessage) -> Result<(), String> {
+    let pool = get_pool().await?;
+    let data = serde_json::to_string(pin).map_err(|e| e.to_string())?;
+
+    sqlx::query(
+        "INSERT OR REPLACE INTO pinned_messages (message_id, thread_id, data) VALUES (?1, ?2, ?3)",
+    )
+    .bind(&pin.message_id)
+    .bind(&pin.thread_id)
+    .bind(&data)
+    .execute(&pool)
+    .await
+    .map_err(|e| format!("Failed to save pin: {}", e))?;
+
+    Ok(())
+}
+
+pub async fn db_delete_pin(message_id: &str) -> Result<(), String> {
+    let pool = get_pool().await?;
+
+    sqlx::query("DELETE FROM pinned_messages WHERE message_id = ?1")
+        .bind(message_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to delete pin: {}", e))?;
+
+    Ok(())
+}
@@ -0,0 +1,295 @@
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+use tauri::{AppHandle, Runtime};
+
+use super::constants::{BOOKMARKS_FILE, MAX_TAGS, MAX_TAG_CHARS};
+#[cfg(any(target_os = "android", target_os = "ios"))]
+use super::db;
+use super::models::{BookmarkStore, PinnedMessage, ThreadBookmark};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::config_store::helpers::config_store;
+use crate::core::threads::commands::{list_messages, modify_message};
+use crate::core::threads::helpers::should_use_sqlite;
+
+pub fn get_bookmarks_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(BOOKMARKS_FILE)
+}
+
+/// Trim tags and drop empty ones and case-insensitive duplicates, keeping the given order
+pub fn normalize_tags(tags: Vec<String>) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim();
+        if tag.is_empty() || has_tag(&normalized, tag) {
+            continue;
+        }
+        if tag.chars().count() > MAX_TAG_CHARS {
+            return Err(format!(
+                "Tag '{tag}' is longer than {MAX_TAG_CHARS} characters"
+            ));
+        }
+        normalized.push(tag.to_string());
+    }
+    if normalized.len() > MAX_TAGS {
+        return Err(format!("At most {MAX_TAGS} tags are allowed"));
+    }
+    Ok(normalized)
+}
+
+/// Tags match case-insensitively
+pub fn has_tag(tags: &[String], tag: &str) -> bool {
+    tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+}
+
+impl BookmarkStore {
+    /// Add or update the bookmark of a thread; an existing bookmark keeps its creation time
+    pub fn put_bookmark(&mut self, mut bookmark: ThreadBookmark) -> ThreadBookmark {
+        match self
+            .bookmarks
+            .iter_mut()
+            .find(|b| b.thread_id == bookmark.thread_id)
+        {
+            Some(existing) => {
+                bookmark.created_at = existing.created_at;
+                *existing = bookmark.clone();
+            }
+            None => self.bookmarks.push(bookmark.clone()),
+        }
+        bookmark
+    }
+
+    pub fn remove_bookmark(&mut self, thread_id: &str) -> bool {
+        let before = self.bookmarks.len();
+        self.bookmarks.retain(|b| b.thread_id != thread_id);
+        self.bookmarks.len() != before
+    }
+
+    /// Pin a message, or refresh the pin time of an existing pin. `tags` of `None` keeps the
+    /// tags of an existing pin.
+    pub fn put_pin(
+        &mut self,
+        thread_id: &str,
+        message_id: &str,
+        tags: Option<Vec<String>>,
+        pinned_at: i64,
+    ) -> PinnedMessage {
+        let previous = self
+            .pins
+            .iter()
+            .position(|p| p.thread_id == thread_id && p.message_id == message_id)
+            .map(|i| self.pins.remove(i));
+        let pin = PinnedMessage {
+            thread_id: thread_id.to_string(),
+            message_id: message_id.to_string(),
+            tags: tags
+                .or_else(|| previous.map(|p| p.tags))
+                .unwrap_or_default(),
+            pinned_at,
+        };
+        self.pins.push(pin.clone());
+        pin
+    }
+
+    pub fn remove_pin(&mut self, thread_id: &str, message_id: &str) -> bool {
+        let before = self.pins.len();
+        self.pins
+            .retain(|p| p.thread_id != thread_id || p.message_id != message_id);
+        self.pins.len() != before
+    }
+
+    /// Drop the bookmark and pins of a deleted thread
+    pub fn forget_thread(&mut self, thread_id: &str) -> bool {
+        let before = self.bookmarks.len() + self.pins.len();
+        self.bookmarks.retain(|b| b.thread_id != thread_id);
+        self.pins.retain(|p| p.thread_id != thread_id);
+        self.bookmarks.len() + self.pins.len() != before
+    }
+
+    /// Bookmarks carrying `tag` (all when `None`), newest first
+    pub fn bookmarks_with_tag(&self, tag: Option<&str>) -> Vec<ThreadBookmark> {
+        let mut bookmarks: Vec<ThreadBookmark> = self
+            .bookmarks
+            .iter()
+            .filter(|b| tag.map_or(true, |tag| has_tag(&b.tags, tag)))
+            .cloned()
+            .collect();
+        bookmarks.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        bookmarks
+    }
+
+    /// Most recently pinned messages, optionally of one thread and carrying `tag`
+    pub fn recent_pins(
+        &self,
+        thread_id: Option<&str>,
+        tag: Option<&str>,
+        limit: usize,
+    ) -> Vec<PinnedMessage> {
+        let mut pins: Vec<PinnedMessage> = self
+            .pins
+            .iter()
+            .filter(|p| thread_id.map_or(true, |id| p.thread_id == id))
+            .filter(|p| tag.map_or(true, |tag| has_tag(&p.tags, tag)))
+            .cloned()
+            .collect();
+        pins.sort_by(|a, b| b.pinned_at.cmp(&a.pinned_at));
+        pins.truncate(limit);
+        pins
+    }
+}
+
+/// Contents of `bookmarks.json`, empty when missing or unreadable
+pub fn read_store(data_folder: &Path) -> BookmarkStore {
+    let Ok(data) = config_store().read(&get_bookmarks_path(data_folder)) else {
+        return BookmarkStore::default();
+    };
+    serde_json::from_str(&data).unwrap_or_else(|e| {
+        log::warn!("Ignoring unreadable {BOOKMARKS_FILE}: {e}");
+        BookmarkStore::default()
+    })
+}
+
+/// Read-modify-write `bookmarks.json` under the config store's file lock
+pub fn update_store<T>(
+    data_folder: &Path,
+    change: impl FnOnce(&mut BookmarkStore) -> T,
+) -> Result<T, String> {
+    let mut result = None;
+    config_store().update(&get_bookmarks_path(data_folder), |raw| {
+        let mut store: BookmarkStore = raw
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        result = Some(change(&mut store));
+        serde_json::to_string_pretty(&store).map_err(|e| e.to_string())
+    })?;
+    result.ok_or_else(|| "Bookmark store was not updated".to_string())
+}
+
+/// All bookmarks and pins, from SQLite or `bookmarks.json`
+pub async fn load_store<R: Runtime>(app: &AppHandle<R>) -> Result<BookmarkStore, String> {
+    if should_use_sqlite() {
+        #[cfg(any(target_os = "android", target_os = "ios"))]
+        return db::db_load_store().await;
+    }
+    Ok(read_store(&get_jan_data_folder_path(app.clone())))
+}
+
+pub async fn save_bookmark<R: Runtime>(
+    app: &AppHandle<R>,
+    bookmark: ThreadBookmark,
+) -> Result<ThreadBookmark, String> {
+    if should_use_sqlite() {
+        #[cfg(any(target_os = "android", target_os = "ios"))]
+        {
+            let saved = db::db_load_store().await?.put_bookmark(bookmark);
+            db::db_put_bookmark(&saved).await?;
+            return Ok(saved);
+        }
+    }
+    let data_folder = get_jan_data_folder_path(app.clone());
+    update_store(&data_folder, |store| store.put_bookmark(bookmark))
+}
+
+pub async fn delete_bookmark<R: Runtime>(
+    app: &AppHandle<R>,
+    thread_id: &str,
+) -> Result<(), String> {
+    if should_use_sqlite() {
+        #[cfg(any(target_os = "android", target_os = "ios"))]
+        return db::db_delete_bookmark(thread_id).await;
+    }
+    let data_folder = get_jan_data_folder_path(app.clone());
+    update_store(&data_folder, |store| store.remove_bookmark(thread_id)).map(|_| ())
+}
+
+/// Record a pin in the store; `tags` of `None` keeps the tags of an existing pin
+pub async fn save_pin<R: Runtime>(
+    app: &AppHandle<R>,
+    thread_id: &str,
+    message_id: &str,
+    tags: Option<Vec<String>>,
+) -> Result<PinnedMessage, String> {
+    let now = chrono::Utc::now().timestamp_millis();
+    if should_use_sqlite() {
+        #[cfg(any(target_os = "android", target_os = "ios"))]
+        {
+            let pin = db::db_load_store()
+                .await?
+                .put_pin(thread_id, message_id, tags, now);
+            db::db_put_pin(&pin).await?;
+            return Ok(pin);
+        }
+    }
+    let data_folder = get_jan_data_folder_path(app.clone());
+    update_store(&data_folder, |store| {
+        store.put_pin(thread_id, message_id, tags, now)
+    })
+}
+
+pub async fn delete_pin<R: Runtime>(
+    app: &AppHandle<R>,
+    thread_id: &str,
+    message_id: &str,
+) -> Result<(), String> {
+    if should_use_sqlite() {
+        #[cfg(any(target_os = "android", target_os = "ios"))]
+        return db::db_delete_pin(message_id).await;
+    }
+    let data_folder = get_jan_data_folder_path(app.clone());
+    update_store(&data_folder, |store| {
+        store.remove_pin(thread_id, message_id)
+    })
+    .map(|_| ())
+}
+
+/// Remove what `bookmarks.json` holds for a deleted thread. SQLite rows go with the thread.
+pub fn forget_thread(data_folder: &Path, thread_id: &str) -> Result<(), String> {
+    if !get_bookmarks_path(data_folder).exists() {
+        return Ok(());
+    }
+    update_store(data_folder, |store| store.forget_thread(thread_id)).map(|_| ())
+}
+
+/// Remove the pin of a deleted message from `bookmarks.json`
+pub fn forget_message(data_folder: &Path, thread_id: &str, message_id: &str) -> Result<(), String> {
+    if !get_bookmarks_path(data_folder).exists() {
+        return Ok(());
+    }
+    update_store(data_folder, |store| store.remove_pin(thread_id, message_id)).map(|_| ())
+}
+
+/// Ids of the pinned messages of a thread, for context assembly
+pub async fn pinned_message_ids<R: Runtime>(app: &AppHandle<R>, thread_id: &str) -> Vec<String> {
+    match load_store(app).await {
+        Ok(store) => store
+            .pins
+            .into_iter()
+            .filter(|p| p.thread_id == thread_id)
+            .map(|p| p.message_id)
+            .collect(),
+        Err(e) => {
+            log::warn!("Failed to read pinned messages of thread {thread_id}: {e}");
+            Vec::new()
+        }
+    }
+}
+
+/// Set `metadata.pinned` on a message of the active branch and return the updated message
+pub async fn set_pinned_flag<R: Runtime>(
+    app: &AppHandle<R>,
+    thread_id: &str,
+    message_id: &str,
+    pinned: bool,
+) -> Result<Value, String> {
+    let messages = list_messages(app.clone(), thread_id.to_string()).await?;
+    let mut message = messages
+        .into_iter()
+        .find(|m| m.get("id").and_then(|v| v.as_str()) == Some(message_id))
+        .ok_or_else(|| format!("Message {message_id} not found in thread {thread_id}"))?;
+
+    if !message.get("metadata").is_some_and(|m| m.is_object()) {
+        message["metadata"] = serde_json::json!({});
+    }
+    message["metadata"]["pinned"] = Value::Bool(pinned);
+    modify_message(app.clone(), message).await
+}
@@ -0,0 +1,20 @@
+/*!
+   Bookmarks Module
+
+   Pinned messages and bookmarked threads, both carrying free-form tags. Where threads live in
+   SQLite (mobile), pins and bookmarks are rows of the same database and disappear with their
+   thread or message; on desktop they are kept in `bookmarks.json` in the data folder and
+   removed when the thread is deleted. Both backends answer the same queries: bookmarks by
+   tag and the most recently pinned messages. Context assembly reads the pins of a thread so
+   pinned messages always make it into the prompt.
+*/
+
+pub mod commands;
+pub mod constants;
+#[cfg(any(target_os = "android", target_os = "ios"))]
+pub mod db;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThreadBookmark {
+    pub thread_id: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+    /// Milliseconds since the epoch
+    pub created_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PinnedMessage {
+    pub thread_id: String,
+    pub message_id: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Milliseconds since the epoch
+    pub pinned_at: i64,
+}
+
+/// Contents of `bookmarks.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BookmarkStore {
+    pub bookmarks: Vec<ThreadBookmark>,
+    pub pins: Vec<PinnedMessage>,
+}
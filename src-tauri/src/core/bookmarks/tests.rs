@@ -0,0 +1,158 @@
+use super::commands::*;
+use super::helpers::{forget_thread, normalize_tags, read_store, update_store};
+use super::models::{BookmarkStore, ThreadBookmark};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::threads::commands::{create_message, create_thread, delete_thread};
+use serde_json::json;
+
+fn bookmark(thread_id: &str, tags: &[&str], created_at: i64) -> ThreadBookmark {
+    ThreadBookmark {
+        thread_id: thread_id.to_string(),
+        tags: tags.iter().map(|t| t.to_string()).collect(),
+        note: None,
+        created_at,
+    }
+}
+
+fn tags(tags: &[&str]) -> Vec<String> {
+    tags.iter().map(|t| t.to_string()).collect()
+}
+
+#[test]
+fn test_normalize_tags() {
+    assert_eq!(
+        normalize_tags(tags(&[" work ", "", "Work", "ideas"])).unwrap(),
+        tags(&["work", "ideas"])
+    );
+    assert!(normalize_tags(vec!["x".repeat(65)]).is_err());
+    let many: Vec<String> = (0..21).map(|i| format!("t{i}")).collect();
+    assert!(normalize_tags(many).is_err());
+}
+
+#[test]
+fn test_bookmarks_by_tag_newest_first() {
+    let mut store = BookmarkStore::default();
+    store.put_bookmark(bookmark("a", &["work"], 1));
+    store.put_bookmark(bookmark("b", &["home"], 2));
+    store.put_bookmark(bookmark("c", &["Work"], 3));
+    // Updating keeps the original creation time
+    let updated = store.put_bookmark(bookmark("a", &["work", "urgent"], 99));
+    assert_eq!(updated.created_at, 1);
+
+    let ids = |list: Vec<ThreadBookmark>| -> Vec<String> {
+        list.into_iter().map(|b| b.thread_id).collect()
+    };
+    assert_eq!(ids(store.bookmarks_with_tag(Some("work"))), vec!["c", "a"]);
+    assert_eq!(ids(store.bookmarks_with_tag(None)), vec!["c", "b", "a"]);
+    assert!(store.remove_bookmark("b"));
+    assert!(!store.remove_bookmark("b"));
+}
+
+#[test]
+fn test_recent_pins() {
+    let mut store = BookmarkStore::default();
+    store.put_pin("t1", "m1", Some(tags(&["quote"])), 1);
+    store.put_pin("t1", "m2", None, 2);
+    store.put_pin("t2", "m3", Some(tags(&["quote"])), 3);
+    // Re-pinning refreshes the time and keeps the tags
+    let repinned = store.put_pin("t1", "m1", None, 4);
+    assert_eq!(repinned.tags, tags(&["quote"]));
+
+    let ids = |pins: Vec<super::models::PinnedMessage>| -> Vec<String> {
+        pins.into_iter().map(|p| p.message_id).collect()
+    };
+    assert_eq!(
+        ids(store.recent_pins(None, None, 10)),
+        vec!["m1", "m3", "m2"]
+    );
+    assert_eq!(ids(store.recent_pins(Some("t1"), None, 1)), vec!["m1"]);
+    assert_eq!(
+        ids(store.recent_pins(None, Some("QUOTE"), 10)),
+        vec!["m1", "m3"]
+    );
+
+    assert!(store.forget_thread("t1"));
+    assert_eq!(ids(store.recent_pins(None, None, 10)), vec!["m3"]);
+}
+
+#[test]
+fn test_file_store_round_trip() {
+    let data_folder = std::env::temp_dir().join(format!("jan-bookmarks-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&data_folder).unwrap();
+    assert_eq!(read_store(&data_folder), BookmarkStore::default());
+
+    update_store(&data_folder, |store| {
+        store.put_bookmark(bookmark("t1", &["work"], 1));
+        store.put_pin("t1", "m1", None, 2);
+    })
+    .unwrap();
+    let store = read_store(&data_folder);
+    assert_eq!(store.bookmarks.len(), 1);
+    assert_eq!(store.pins.len(), 1);
+
+    forget_thread(&data_folder, "t1").unwrap();
+    assert_eq!(read_store(&data_folder), BookmarkStore::default());
+
+    let _ = std::fs::remove_dir_all(data_folder);
+}
+
+#[tokio::test]
+async fn test_pin_message_sets_flag_and_store() {
+    let app = tauri::test::mock_app();
+    let handle = app.handle().clone();
+    let thread = create_thread(handle.clone(), json!({"title": "Pins"}))
+        .await
+        .unwrap();
+    let thread_id = thread["id"].as_str().unwrap().to_string();
+    let message = create_message(
+        handle.clone(),
+        json!({"thread_id": thread_id, "role": "user", "content": [{"type": "text", "text": "keep me"}]}),
+    )
+    .await
+    .unwrap();
+    let message_id = message["id"].as_str().unwrap().to_string();
+
+    let pin = pin_message(
+        handle.clone(),
+        thread_id.clone(),
+        message_id.clone(),
+        tags(&["Facts"]),
+    )
+    .await
+    .unwrap();
+    assert_eq!(pin.tags, tags(&["Facts"]));
+
+    let pins = list_pinned_messages(
+        handle.clone(),
+        Some(thread_id.clone()),
+        Some("facts".into()),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(pins.len(), 1);
+    assert_eq!(pins[0].message_id, message_id);
+
+    let bookmark = bookmark_thread(handle.clone(), thread_id.clone(), tags(&["Facts"]), None)
+        .await
+        .unwrap();
+    assert_eq!(bookmark.thread_id, thread_id);
+
+    unpin_message(handle.clone(), thread_id.clone(), message_id)
+        .await
+        .unwrap();
+    let pins = list_pinned_messages(handle.clone(), Some(thread_id.clone()), None, None)
+        .await
+        .unwrap();
+    assert!(pins.is_empty());
+
+    // Deleting the thread drops its bookmark
+    delete_thread(handle.clone(), thread_id.clone())
+        .await
+        .unwrap();
+    let data_folder = get_jan_data_folder_path(handle.clone());
+    assert!(read_store(&data_folder)
+        .bookmarks
+        .iter()
+        .all(|b| b.thread_id != thread_id));
+}
@@ -0,0 +1,26 @@
+use tauri::State;
+
+use super::models::{CancelScope, CancellableOperation};
+use crate::core::state::AppState;
+
+/// Cancels an open scope together with everything running under it, e.g. a whole thread.
+#[tauri::command]
+pub async fn cancel_operation(
+    state: State<'_, AppState>,
+    scope: CancelScope,
+) -> Result<(), String> {
+    if state.cancellations.cancel(&scope) {
+        log::info!("Cancelled {scope}");
+        Ok(())
+    } else {
+        Err(format!("No running {scope}"))
+    }
+}
+
+/// Lists the running operations that can be cancelled, oldest first.
+#[tauri::command]
+pub async fn list_cancellable_operations(
+    state: State<'_, AppState>,
+) -> Result<Vec<CancellableOperation>, String> {
+    Ok(state.cancellations.list())
+}
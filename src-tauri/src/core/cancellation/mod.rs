@@ -0,0 +1,220 @@
+/*!
+   Cancellation Hierarchy
+
+   One tree of cancellation tokens for everything the user can stop: app → thread → turn →
+   tool call or download. Cancelling a scope cancels every scope opened under it, so stopping
+   a turn aborts its in-flight provider stream and MCP calls, deleting a thread stops the runs
+   writing into it, and shutting the app down stops all of them.
+
+   Work opens its scope for as long as it runs and holds the returned guard; dropping the guard
+   closes the scope without cancelling it. Thread scopes are opened implicitly by their first
+   turn and closed with their last one.
+*/
+
+pub mod commands;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use tokio_util::sync::CancellationToken;
+
+use models::{CancelScope, CancellableOperation};
+
+struct Node {
+    token: CancellationToken,
+    parent: Option<CancelScope>,
+    /// Tells a scope apart from a later one opened under the same key
+    generation: u64,
+    children: usize,
+}
+
+#[derive(Default)]
+struct TreeState {
+    nodes: HashMap<CancelScope, Node>,
+    next_generation: u64,
+}
+
+impl TreeState {
+    fn insert(
+        &mut self,
+        scope: CancelScope,
+        token: CancellationToken,
+        parent: Option<CancelScope>,
+    ) -> u64 {
+        self.next_generation += 1;
+        let generation = self.next_generation;
+        if let Some(node) = parent.as_ref().and_then(|p| self.nodes.get_mut(p)) {
+            node.children += 1;
+        }
+        self.nodes.insert(
+            scope,
+            Node {
+                token,
+                parent,
+                generation,
+                children: 0,
+            },
+        );
+        generation
+    }
+
+    fn remove(&mut self, scope: &CancelScope) {
+        let Some(parent) = self.nodes.remove(scope).and_then(|node| node.parent) else {
+            return;
+        };
+        if let Some(node) = self.nodes.get_mut(&parent) {
+            node.children = node.children.saturating_sub(1);
+            if node.children == 0 && matches!(parent, CancelScope::Thread(_)) {
+                self.nodes.remove(&parent);
+            }
+        }
+    }
+}
+
+/// Cancellation tokens of all running work, cheap to clone
+#[derive(Clone, Default)]
+pub struct CancellationTree {
+    root: CancellationToken,
+    state: Arc<Mutex<TreeState>>,
+}
+
+impl CancellationTree {
+    fn state(&self) -> MutexGuard<'_, TreeState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Open `scope` under `parent`, or under the app when there is none. A thread parent is
+    /// opened on demand; any other parent must be open. Fails when `scope` is already open.
+    pub fn open(
+        &self,
+        scope: CancelScope,
+        parent: Option<&CancelScope>,
+    ) -> Result<CancelGuard, String> {
+        self.open_scope(scope, parent, false)
+    }
+
+    /// Like `open`, but a scope already open under the same key is cancelled and replaced
+    pub fn open_replacing(
+        &self,
+        scope: CancelScope,
+        parent: Option<&CancelScope>,
+    ) -> Result<CancelGuard, String> {
+        self.open_scope(scope, parent, true)
+    }
+
+    fn open_scope(
+        &self,
+        scope: CancelScope,
+        parent: Option<&CancelScope>,
+        replace: bool,
+    ) -> Result<CancelGuard, String> {
+        let mut state = self.state();
+        if let Some(existing) = state.nodes.get(&scope) {
+            if !replace {
+                return Err(format!("{scope} is already running"));
+            }
+            log::info!("Cancelling {scope}, which is started again");
+            existing.token.cancel();
+            state.remove(&scope);
+        }
+        let parent_token = match parent {
+            None => self.root.clone(),
+            Some(parent) => match state.nodes.get(parent) {
+                Some(node) => node.token.clone(),
+                None if matches!(parent, CancelScope::Thread(_)) => {
+                    let token = self.root.child_token();
+                    state.insert(parent.clone(), token.clone(), None);
+                    token
+                }
+                None => return Err(format!("{parent} is not running")),
+            },
+        };
+        let token = parent_token.child_token();
+        let generation = state.insert(scope.clone(), token.clone(), parent.cloned());
+        Ok(CancelGuard {
+            tree: self.clone(),
+            scope,
+            generation,
+            token,
+        })
+    }
+
+    fn close(&self, scope: &CancelScope, generation: u64) {
+        let mut state = self.state();
+        if state.nodes.get(scope).map(|node| node.generation) == Some(generation) {
+            state.remove(scope);
+        }
+    }
+
+    /// Cancel an open scope and everything under it. Returns whether the scope was open.
+    pub fn cancel(&self, scope: &CancelScope) -> bool {
+        match self.state().nodes.get(scope) {
+            Some(node) => {
+                node.token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Cancel every open scope matching `filter`. Returns how many were cancelled.
+    pub fn cancel_where(&self, filter: impl Fn(&CancelScope) -> bool) -> usize {
+        let state = self.state();
+        let mut cancelled = 0;
+        for (scope, node) in state.nodes.iter() {
+            if filter(scope) {
+                node.token.cancel();
+                cancelled += 1;
+            }
+        }
+        cancelled
+    }
+
+    /// Cancel all running work; scopes opened afterwards start out cancelled
+    pub fn shutdown(&self) {
+        self.root.cancel();
+    }
+
+    /// Open scopes in the order they were opened
+    pub fn list(&self) -> Vec<CancellableOperation> {
+        let state = self.state();
+        let mut nodes: Vec<_> = state.nodes.iter().collect();
+        nodes.sort_by_key(|(_, node)| node.generation);
+        nodes
+            .into_iter()
+            .map(|(scope, node)| CancellableOperation {
+                scope: scope.clone(),
+                parent: node.parent.clone(),
+                cancelled: node.token.is_cancelled(),
+            })
+            .collect()
+    }
+}
+
+/// Keeps a scope open. Dropping it closes the scope without cancelling it.
+pub struct CancelGuard {
+    tree: CancellationTree,
+    scope: CancelScope,
+    generation: u64,
+    token: CancellationToken,
+}
+
+impl CancelGuard {
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    pub fn scope(&self) -> &CancelScope {
+        &self.scope
+    }
+}
+
+impl Drop for CancelGuard {
+    fn drop(&mut self) {
+        self.tree.close(&self.scope, self.generation);
+    }
+}
@@ -0,0 +1,35 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Node of the cancellation tree. A scope is cancelled together with the scope it was opened
+/// under: the app, a thread, a turn, then the tool calls and downloads at the leaves.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "id", rename_all = "snake_case")]
+pub enum CancelScope {
+    Thread(String),
+    /// An agent run, keyed by its run id
+    Turn(String),
+    ToolCall(String),
+    Download(String),
+}
+
+impl fmt::Display for CancelScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Thread(id) => write!(f, "thread {id}"),
+            Self::Turn(id) => write!(f, "agent run {id}"),
+            Self::ToolCall(id) => write!(f, "tool call {id}"),
+            Self::Download(id) => write!(f, "download task {id}"),
+        }
+    }
+}
+
+/// An open scope of the cancellation tree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CancellableOperation {
+    pub scope: CancelScope,
+    /// `None` for scopes opened directly under the app
+    pub parent: Option<CancelScope>,
+    pub cancelled: bool,
+}
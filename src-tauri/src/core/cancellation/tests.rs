@@ -0,0 +1,91 @@
+use super::models::CancelScope;
+use super::CancellationTree;
+
+fn turn(id: &str) -> CancelScope {
+    CancelScope::Turn(id.to_string())
+}
+
+fn thread(id: &str) -> CancelScope {
+    CancelScope::Thread(id.to_string())
+}
+
+#[test]
+fn test_cancelling_a_scope_cancels_its_children() {
+    let tree = CancellationTree::default();
+    let run = tree.open(turn("run-1"), Some(&thread("t1"))).unwrap();
+    let call = tree
+        .open(CancelScope::ToolCall("call-1".into()), Some(&turn("run-1")))
+        .unwrap();
+    let other = tree.open(turn("run-2"), Some(&thread("t2"))).unwrap();
+
+    assert!(tree.cancel(&thread("t1")));
+    assert!(run.token().is_cancelled());
+    assert!(call.token().is_cancelled());
+    assert!(!other.token().is_cancelled());
+
+    tree.shutdown();
+    assert!(other.token().is_cancelled());
+    // Work started during shutdown is cancelled from the start
+    let late = tree.open(CancelScope::Download("d".into()), None).unwrap();
+    assert!(late.token().is_cancelled());
+}
+
+#[test]
+fn test_cancelling_a_leaf_leaves_its_parent_running() {
+    let tree = CancellationTree::default();
+    let run = tree.open(turn("run-1"), None).unwrap();
+    let call = tree
+        .open(CancelScope::ToolCall("call-1".into()), Some(&turn("run-1")))
+        .unwrap();
+    assert!(tree.cancel(call.scope()));
+    assert!(call.token().is_cancelled());
+    assert!(!run.token().is_cancelled());
+}
+
+#[test]
+fn test_scopes_close_with_their_guards() {
+    let tree = CancellationTree::default();
+    let run = tree.open(turn("run-1"), Some(&thread("t1"))).unwrap();
+    assert!(tree.open(turn("run-1"), None).is_err());
+    assert!(tree
+        .open(CancelScope::ToolCall("c".into()), Some(&turn("missing")))
+        .is_err());
+    assert_eq!(tree.list().len(), 2);
+
+    // The thread closes with its last turn
+    drop(run);
+    assert!(tree.list().is_empty());
+    assert!(!tree.cancel(&turn("run-1")));
+}
+
+#[test]
+fn test_open_replacing_cancels_the_previous_scope() {
+    let tree = CancellationTree::default();
+    let scope = CancelScope::Download("model".into());
+    let first = tree.open_replacing(scope.clone(), None).unwrap();
+    let second = tree.open_replacing(scope.clone(), None).unwrap();
+    assert!(first.token().is_cancelled());
+    assert!(!second.token().is_cancelled());
+
+    // The replaced guard doesn't close its successor
+    drop(first);
+    assert_eq!(tree.list().len(), 1);
+    assert!(tree.cancel(&scope));
+    assert!(second.token().is_cancelled());
+}
+
+#[test]
+fn test_cancel_where() {
+    let tree = CancellationTree::default();
+    let runs = [
+        tree.open(turn("a"), None).unwrap(),
+        tree.open(turn("b"), None).unwrap(),
+    ];
+    let download = tree.open(CancelScope::Download("d".into()), None).unwrap();
+    assert_eq!(
+        tree.cancel_where(|scope| matches!(scope, CancelScope::Turn(_))),
+        2
+    );
+    assert!(runs.iter().all(|run| run.token().is_cancelled()));
+    assert!(!download.token().is_cancelled());
+}
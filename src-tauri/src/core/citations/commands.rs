@@ -0,0 +1,26 @@
+use serde_json::Value;
+use tauri::{AppHandle, Runtime};
+
+use super::helpers::{attach_citations, citation_source};
+use super::models::{Citation, CitationSource, CitationSourceRequest};
+
+/// The exact passage of a cited document, with surrounding text and its page when known
+#[tauri::command]
+pub async fn get_citation_source<R: Runtime>(
+    app_handle: AppHandle<R>,
+    request: CitationSourceRequest,
+) -> Result<CitationSource, String> {
+    citation_source(&app_handle, request).await
+}
+
+/// Store the citations of the assembled context that a finished answer refers to in the
+/// message metadata, and return the updated message
+#[tauri::command]
+pub async fn attach_message_citations<R: Runtime>(
+    app_handle: AppHandle<R>,
+    thread_id: String,
+    message_id: String,
+    citations: Vec<Citation>,
+) -> Result<Value, String> {
+    attach_citations(&app_handle, &thread_id, &message_id, citations).await
+}
@@ -0,0 +1,13 @@
+// Citations constants
+
+/// Maximum share of the context budget the retrieved sources may take
+pub const SOURCES_BUDGET_RATIO: f64 = 0.4;
+/// Characters of surrounding text returned around a cited excerpt by default
+pub const DEFAULT_CONTEXT_CHARS: usize = 300;
+pub const MAX_CONTEXT_CHARS: usize = 5_000;
+/// Length of the preview stored with each citation
+pub const SNIPPET_CHARS: usize = 200;
+/// Characters of a chunk used to find the page it is on
+pub const PAGE_PROBE_CHARS: usize = 80;
+
+pub const SOURCES_PROMPT: &str = "Use the numbered sources below when they are relevant to the question. Cite every statement based on a source with its number in square brackets, like [1] or [1, 3]. Do not cite sources you did not use.";
@@ -0,0 +1,269 @@
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde_json::Value;
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_vector_db::{db, VectorDBState};
+
+use super::constants::*;
+use super::models::{Citation, CitationSource, CitationSourceRequest, SourceChunk};
+use crate::core::context::helpers::{estimate_message_tokens, message_text};
+use crate::core::threads::commands::{list_messages, modify_message};
+
+/// Prefix of `text` of at most `max_chars` characters
+pub fn char_prefix(text: &str, max_chars: usize) -> &str {
+    match text.char_indices().nth(max_chars) {
+        Some((byte, _)) => &text[..byte],
+        None => text,
+    }
+}
+
+/// Characters `start..end` of `text`, clamped to its length
+pub fn char_slice(text: &str, start: usize, end: usize) -> &str {
+    let byte_at = |chars: usize| {
+        text.char_indices()
+            .nth(chars)
+            .map_or(text.len(), |(byte, _)| byte)
+    };
+    let start = byte_at(start);
+    let end = byte_at(end).max(start);
+    &text[start..end]
+}
+
+fn source_label(chunk: &SourceChunk) -> String {
+    let name = chunk
+        .file_name
+        .clone()
+        .or_else(|| {
+            chunk.path.as_ref().and_then(|p| {
+                Path::new(p)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+            })
+        })
+        .unwrap_or_else(|| chunk.file_id.clone());
+    match chunk.page {
+        Some(page) => format!("{name}, page {page}"),
+        None => name,
+    }
+}
+
+pub fn citation_for(index: usize, chunk: &SourceChunk) -> Citation {
+    Citation {
+        index,
+        chunk_id: chunk.id.clone(),
+        collection: chunk.collection.clone(),
+        file_id: chunk.file_id.clone(),
+        file_name: chunk.file_name.clone(),
+        path: chunk.path.clone(),
+        page: chunk.page,
+        start_char: chunk.start_char,
+        end_char: chunk.end_char,
+        score: chunk.score,
+        snippet: char_prefix(chunk.text.trim(), SNIPPET_CHARS).to_string(),
+    }
+}
+
+/// System message listing `chunks` as numbered sources, in the given order, skipping those
+/// that don't fit `budget` tokens. Returns the message content, its estimated tokens and
+/// the citations of the included chunks, numbered from 1 as they appear in the prompt.
+pub fn build_sources_message(
+    chunks: &[SourceChunk],
+    budget: usize,
+) -> Option<(String, usize, Vec<Citation>)> {
+    let mut content = SOURCES_PROMPT.to_string();
+    let mut tokens = estimate_message_tokens(&content);
+    let mut citations = Vec::new();
+    for chunk in chunks {
+        let index = citations.len() + 1;
+        let entry = format!(
+            "\n\n[{index}] {}\n{}",
+            source_label(chunk),
+            chunk.text.trim()
+        );
+        let entry_tokens = estimate_message_tokens(&entry);
+        if tokens + entry_tokens > budget {
+            continue;
+        }
+        tokens += entry_tokens;
+        content.push_str(&entry);
+        citations.push(citation_for(index, chunk));
+    }
+    (!citations.is_empty()).then_some((content, tokens, citations))
+}
+
+fn marker_regex() -> &'static Regex {
+    static MARKER: OnceLock<Regex> = OnceLock::new();
+    MARKER.get_or_init(|| Regex::new(r"\[(\d+(?:\s*,\s*\d+)*)\]").unwrap())
+}
+
+/// Source numbers referenced by `[n]` and `[n, m]` markers in an answer
+pub fn cited_indices(text: &str) -> BTreeSet<usize> {
+    marker_regex()
+        .captures_iter(text)
+        .flat_map(|caps| {
+            caps[1]
+                .split(',')
+                .filter_map(|n| n.trim().parse().ok())
+                .collect::<Vec<usize>>()
+        })
+        .collect()
+}
+
+/// Citations an answer refers to. When the answer has no markers at all, every source is
+/// kept, since the model may have used them without citing.
+pub fn cited_citations(citations: Vec<Citation>, answer: &str) -> Vec<Citation> {
+    let cited = cited_indices(answer);
+    if cited.is_empty() {
+        return citations;
+    }
+    citations
+        .into_iter()
+        .filter(|c| cited.contains(&c.index))
+        .collect()
+}
+
+/// Character range of `chunk` in `document`: at `hint` when it matches there, otherwise
+/// the occurrence closest to `hint`
+pub fn locate_chunk(document: &str, chunk: &str, hint: Option<usize>) -> Option<(usize, usize)> {
+    if chunk.is_empty() {
+        return None;
+    }
+    let length = chunk.chars().count();
+    if let Some(start) = hint {
+        if char_slice(document, start, start + length) == chunk {
+            return Some((start, start + length));
+        }
+    }
+    let hint = hint.unwrap_or(0);
+    document
+        .match_indices(chunk)
+        .map(|(byte, _)| document[..byte].chars().count())
+        .min_by_key(|start| start.abs_diff(hint))
+        .map(|start| (start, start + length))
+}
+
+/// 1-based page of the character at `offset`. Documents with form feeds are paged by
+/// them; otherwise the page is the first of `pages` containing the start of the passage.
+pub fn page_at(document: &str, offset: usize, pages: &[String]) -> Option<u32> {
+    if document.contains('\u{c}') {
+        let before = char_slice(document, 0, offset);
+        return Some(before.matches('\u{c}').count() as u32 + 1);
+    }
+    let normalize = |text: &str| text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let probe = normalize(char_slice(document, offset, offset + PAGE_PROBE_CHARS));
+    if probe.is_empty() {
+        return None;
+    }
+    pages
+        .iter()
+        .position(|page| normalize(page).contains(&probe))
+        .map(|index| index as u32 + 1)
+}
+
+/// The passage `start..end` of `document` with up to `context` characters on each side
+pub fn excerpt(
+    document: &str,
+    start: usize,
+    end: usize,
+    context: usize,
+) -> (String, String, String) {
+    (
+        char_slice(document, start.saturating_sub(context), start).to_string(),
+        char_slice(document, start, end).to_string(),
+        char_slice(document, end, end + context).to_string(),
+    )
+}
+
+/// Read the cited document again and return the exact passage of a citation
+pub async fn citation_source<R: Runtime>(
+    app: &AppHandle<R>,
+    request: CitationSourceRequest,
+) -> Result<CitationSource, String> {
+    let collection = {
+        let state = app
+            .try_state::<VectorDBState>()
+            .ok_or_else(|| "The vector database is not available".to_string())?;
+        db::collection_path(&state.base_dir, &request.collection)
+    };
+    tokio::task::spawn_blocking(move || {
+        let conn = db::open_or_init_conn(&collection).map_err(|e| e.to_string())?;
+        let file = db::get_file(&conn, &request.file_id)
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("File {} is not in the collection", request.file_id))?;
+        let path = file
+            .path
+            .clone()
+            .ok_or_else(|| format!("File {} has no source path", file.id))?;
+        let chunk = match &request.chunk_id {
+            Some(id) => Some(
+                db::get_chunk(&conn, id)
+                    .map_err(|e| e.to_string())?
+                    .ok_or_else(|| format!("Chunk {id} is not in the collection"))?,
+            ),
+            None => None,
+        };
+        drop(conn);
+
+        let file_type = Path::new(&path)
+            .extension()
+            .map(|e| e.to_string_lossy().to_ascii_lowercase())
+            .or_else(|| file.file_type.clone())
+            .unwrap_or_default();
+        let document = tauri_plugin_rag::parse_document(&path, &file_type)
+            .map_err(|e| format!("Failed to read {path}: {e}"))?;
+
+        let (start, end) = match (&chunk, request.start_char, request.end_char) {
+            (Some(chunk), hint, _) => locate_chunk(&document, &chunk.text, hint)
+                .ok_or_else(|| "The cited passage is no longer in the document".to_string())?,
+            (None, Some(start), Some(end)) if start < end => (start, end),
+            _ => return Err("A chunk id or a character range is required".to_string()),
+        };
+        let pages = if file_type == "pdf" && !document.contains('\u{c}') {
+            tauri_plugin_rag::parse_pdf_pages(&path).unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        let context = request
+            .context_chars
+            .unwrap_or(DEFAULT_CONTEXT_CHARS)
+            .min(MAX_CONTEXT_CHARS);
+        let (before, excerpt, after) = excerpt(&document, start, end, context);
+        Ok(CitationSource {
+            file_id: file.id,
+            file_name: file.name,
+            path: Some(path),
+            page: page_at(&document, start, &pages),
+            start_char: start,
+            end_char: start + excerpt.chars().count(),
+            excerpt,
+            before,
+            after,
+        })
+    })
+    .await
+    .map_err(|e| format!("Reading the citation source failed: {e}"))?
+}
+
+/// Store the citations an assistant message refers to in its metadata
+pub async fn attach_citations<R: Runtime>(
+    app: &AppHandle<R>,
+    thread_id: &str,
+    message_id: &str,
+    citations: Vec<Citation>,
+) -> Result<Value, String> {
+    let messages = list_messages(app.clone(), thread_id.to_string()).await?;
+    let mut message = messages
+        .into_iter()
+        .find(|m| m.get("id").and_then(|v| v.as_str()) == Some(message_id))
+        .ok_or_else(|| format!("Message {message_id} not found in thread {thread_id}"))?;
+
+    let cited = cited_citations(citations, &message_text(&message));
+    if !message.get("metadata").is_some_and(|m| m.is_object()) {
+        message["metadata"] = serde_json::json!({});
+    }
+    message["metadata"]["citations"] = serde_json::to_value(cited).map_err(|e| e.to_string())?;
+    modify_message(app.clone(), message).await
+}
@@ -0,0 +1,23 @@
+/*!
+   Citations
+
+   Tracks which retrieved chunks an answer was grounded on, from retrieval to the rendered
+   message:
+   - Chunks passed to the context assembler as `sources` are numbered and added to the
+     prompt with an instruction to cite them as `[n]`; the assembled context returns one
+     `Citation` per included chunk with its provenance (collection, file, page, character
+     offsets into the parsed document).
+   - Once the answer is complete, the citations its markers refer to are stored in the
+     message metadata under `citations`, so the frontend can render them as links.
+   - `get_citation_source` re-reads the cited document and returns the exact excerpt with
+     some surrounding text. Offsets are verified against the chunk text and recomputed when
+     the document changed since it was indexed.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
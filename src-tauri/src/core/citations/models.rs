@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+
+/// A chunk returned by retrieval, with where it came from
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceChunk {
+    /// Vector-db chunk id
+    pub id: String,
+    pub text: String,
+    /// Vector-db collection (knowledge base or thread attachments) the chunk is from
+    pub collection: String,
+    pub file_id: String,
+    #[serde(default)]
+    pub file_name: Option<String>,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub chunk_file_order: Option<i64>,
+    /// 1-based page, when known
+    #[serde(default)]
+    pub page: Option<u32>,
+    /// Character offsets of the chunk in the parsed document, when known
+    #[serde(default)]
+    pub start_char: Option<usize>,
+    #[serde(default)]
+    pub end_char: Option<usize>,
+    #[serde(default)]
+    pub score: Option<f32>,
+}
+
+/// A numbered source of an answer, as stored in message metadata
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Citation {
+    /// The `n` of the `[n]` markers referring to it
+    pub index: usize,
+    pub chunk_id: String,
+    pub collection: String,
+    pub file_id: String,
+    #[serde(default)]
+    pub file_name: Option<String>,
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub page: Option<u32>,
+    #[serde(default)]
+    pub start_char: Option<usize>,
+    #[serde(default)]
+    pub end_char: Option<usize>,
+    #[serde(default)]
+    pub score: Option<f32>,
+    /// Beginning of the chunk, for previews
+    pub snippet: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CitationSourceRequest {
+    pub collection: String,
+    pub file_id: String,
+    /// Used to verify or recover the offsets; the offsets alone are trusted without it
+    #[serde(default)]
+    pub chunk_id: Option<String>,
+    #[serde(default)]
+    pub start_char: Option<usize>,
+    #[serde(default)]
+    pub end_char: Option<usize>,
+    /// Characters of surrounding text to return on each side
+    #[serde(default)]
+    pub context_chars: Option<usize>,
+}
+
+/// The cited passage of a document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CitationSource {
+    pub file_id: String,
+    pub file_name: Option<String>,
+    pub path: Option<String>,
+    pub page: Option<u32>,
+    pub start_char: usize,
+    pub end_char: usize,
+    pub excerpt: String,
+    pub before: String,
+    pub after: String,
+}
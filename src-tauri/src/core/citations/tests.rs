@@ -0,0 +1,91 @@
+use super::helpers::*;
+use super::models::SourceChunk;
+
+fn chunk(id: &str, text: &str, page: Option<u32>) -> SourceChunk {
+    SourceChunk {
+        id: id.to_string(),
+        text: text.to_string(),
+        collection: "kb".to_string(),
+        file_id: format!("file-{id}"),
+        file_name: Some(format!("{id}.pdf")),
+        path: None,
+        chunk_file_order: Some(0),
+        page,
+        start_char: None,
+        end_char: None,
+        score: Some(0.5),
+    }
+}
+
+#[test]
+fn test_build_sources_message_numbers_included_chunks() {
+    let chunks = vec![
+        chunk("a", "short passage", Some(3)),
+        chunk("big", &"x".repeat(4_000), None),
+        chunk("b", "another passage", None),
+    ];
+    let (content, tokens, citations) = build_sources_message(&chunks, 200).unwrap();
+    assert!(content.contains("[1] a.pdf, page 3\nshort passage"));
+    assert!(content.contains("[2] b.pdf\nanother passage"));
+    assert!(!content.contains("xxxx"));
+    assert!(tokens <= 200);
+    let indices: Vec<(usize, &str)> = citations
+        .iter()
+        .map(|c| (c.index, c.chunk_id.as_str()))
+        .collect();
+    assert_eq!(indices, vec![(1, "a"), (2, "b")]);
+    assert!(build_sources_message(&chunks, 10).is_none());
+}
+
+#[test]
+fn test_cited_citations() {
+    let chunks = vec![
+        chunk("a", "one", None),
+        chunk("b", "two", None),
+        chunk("c", "three", None),
+    ];
+    let (_, _, citations) = build_sources_message(&chunks, 1_000).unwrap();
+    let answer = "It is blue [1]. Also round [1, 3] but not [x] or [].";
+    assert_eq!(
+        cited_indices(answer).into_iter().collect::<Vec<_>>(),
+        vec![1, 3]
+    );
+    let cited: Vec<usize> = cited_citations(citations.clone(), answer)
+        .iter()
+        .map(|c| c.index)
+        .collect();
+    assert_eq!(cited, vec![1, 3]);
+    assert_eq!(cited_citations(citations, "No markers").len(), 3);
+}
+
+#[test]
+fn test_locate_chunk_prefers_hint() {
+    let document = "héllo world. héllo world.";
+    assert_eq!(locate_chunk(document, "héllo", Some(13)), Some((13, 18)));
+    assert_eq!(locate_chunk(document, "héllo", Some(11)), Some((13, 18)));
+    assert_eq!(locate_chunk(document, "héllo", None), Some((0, 5)));
+    assert_eq!(locate_chunk(document, "missing", Some(0)), None);
+    assert_eq!(char_slice(document, 13, 18), "héllo");
+    assert_eq!(char_slice(document, 20, 100), "orld.");
+}
+
+#[test]
+fn test_page_at_and_excerpt() {
+    let paged = "first page\u{c}second page\u{c}third";
+    assert_eq!(page_at(paged, 0, &[]), Some(1));
+    assert_eq!(page_at(paged, 12, &[]), Some(2));
+    assert_eq!(page_at(paged, 25, &[]), Some(3));
+
+    let document = "Intro text.\n\nThe   results\nshow growth.";
+    let pages = vec![
+        "Intro text.".to_string(),
+        "The results show growth.".to_string(),
+    ];
+    assert_eq!(page_at(document, 13, &pages), Some(2));
+    assert_eq!(page_at(document, 13, &[]), None);
+
+    let (before, passage, after) = excerpt(document, 13, 26, 5);
+    assert_eq!(before, "xt.\n\n");
+    assert_eq!(passage, "The   results");
+    assert_eq!(after, "\nshow");
+}
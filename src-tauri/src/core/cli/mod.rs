@@ -6,11 +6,34 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::core::app::commands::{resolve_config_file_path, resolve_jan_data_folder};
+use crate::core::guardrails::Guardrails;
+use crate::core::mcp::admin::McpAdminHandle;
+use crate::core::mcp::migrations::load_config as load_mcp_config;
+use crate::core::offline::helpers::read_settings as read_offline_settings;
+use crate::core::offline::OfflineMode;
+use crate::core::onboarding::helpers::{
+    complete_step, install_default_mcp_servers, read_state as read_onboarding_state,
+    skip_step, update_state as update_onboarding_state,
+};
+use crate::core::param_profiles::helpers::read_config as read_param_profiles;
+use crate::core::param_profiles::ParamProfiles;
+use crate::core::peers::helpers::peers_path;
+use crate::core::peers::PeerAccess;
+use crate::core::redaction::helpers::read_config as read_redaction_config;
+use crate::core::redaction::RedactionFilter;
+use crate::core::scheduler::GenerationScheduler;
 use crate::core::server::proxy;
+use crate::core::service::helpers::{
+    acquire_server_lock, install_service, service_status, uninstall_service,
+};
+use crate::core::settings::helpers::read_settings;
 use crate::core::state::AppState;
+use crate::core::storage::helpers::record_model_load;
 use crate::core::threads::{
+    branches::{active_messages, read_branch_state},
     constants::THREADS_FILE,
     helpers::read_messages_from_file,
+    private::{open_messages, private_key_of, unlock_key},
     utils::{ensure_data_dirs, get_data_dir, get_thread_dir, get_thread_metadata_path},
 };
 use tauri_plugin_llamacpp::state::LlamacppState;
@@ -20,6 +43,10 @@ use tauri_plugin_mlx::state::MlxState;
 pub use tauri_plugin_llamacpp::{load_llama_model_impl, LlamacppConfig};
 pub use tauri_plugin_mlx::{load_mlx_model_impl, MlxConfig};
 pub use tauri_plugin_mlx::state::SessionInfo;
+pub use crate::core::onboarding::helpers::{default_mcp_server_names, scan_hardware};
+pub use crate::core::onboarding::models::{OnboardingState, OnboardingStep, StepOutcome};
+pub use crate::core::service::helpers::ServerLock;
+pub use crate::core::service::models::{ServerLockInfo, ServiceOptions, ServiceStatus};
 
 // ── State constructors ─────────────────────────────────────────────────────
 
@@ -63,10 +90,31 @@ pub async fn cli_list_threads() -> Result<Vec<serde_json::Value>, String> {
     Ok(threads)
 }
 
-/// List messages for a thread.
-pub fn cli_list_messages(thread_id: &str) -> Result<Vec<serde_json::Value>, String> {
+/// List messages on the active branch of a thread. The messages of a private thread are
+/// opened with `passphrase`, and refused without one.
+pub fn cli_list_messages(
+    thread_id: &str,
+    passphrase: Option<&str>,
+) -> Result<Vec<serde_json::Value>, String> {
     let data_folder = resolve_jan_data_folder();
-    read_messages_from_file(&data_folder, thread_id)
+    let private = std::fs::read_to_string(get_thread_metadata_path(&data_folder, thread_id))
+        .ok()
+        .and_then(|data| serde_json::from_str::<serde_json::Value>(&data).ok())
+        .and_then(|thread| private_key_of(&thread));
+    let key = match (private, passphrase) {
+        (Some(private), Some(passphrase)) => Some(unlock_key(&private, passphrase)?),
+        (Some(_), None) => {
+            return Err(format!(
+                "Thread '{thread_id}' is private; set JAN_PRIVATE_PASSPHRASE to read its messages"
+            ))
+        }
+        (None, _) => None,
+    };
+    let messages = open_messages(key.as_ref(), read_messages_from_file(&data_folder, thread_id)?)?;
+    Ok(active_messages(
+        &messages,
+        &read_branch_state(&data_folder, thread_id),
+    ))
 }
 
 /// Delete a thread directory.
@@ -105,6 +153,19 @@ pub async fn cli_start_server(
     api_key: String,
     proxy_timeout: u64,
 ) -> Result<u16, String> {
+    // Requests are redacted, blocked, given profile defaults and capped the same way as in the app
+    let data_folder = resolve_jan_data_folder();
+    let redaction = RedactionFilter::default();
+    redaction.set_config(read_redaction_config(&data_folder));
+    let offline = OfflineMode::default();
+    offline.set_enabled(read_offline_settings(&data_folder).enabled);
+    let profiles = ParamProfiles::default();
+    profiles.set_config(read_param_profiles(&data_folder));
+    let guardrails = Guardrails::default();
+    guardrails.set_settings(read_settings(&data_folder).guardrails);
+    // Paired peers keep their access; new ones can't pair without the app to show the code
+    let peers = PeerAccess::default();
+    peers.load(peers_path(&data_folder));
     proxy::start_server(
         app_state.server_handle.clone(),
         llama_state.llama_server_process.clone(),
@@ -116,6 +177,15 @@ pub async fn cli_start_server(
         vec![vec![]],
         proxy_timeout,
         app_state.provider_configs.clone(),
+        // The CLI runs headless, so only API requests are scheduled here
+        GenerationScheduler::default(),
+        redaction,
+        profiles,
+        guardrails,
+        offline,
+        // No app to manage MCP servers with
+        McpAdminHandle::default(),
+        peers,
     )
     .await
     .map_err(|e| e.to_string())
@@ -559,3 +629,132 @@ pub fn cli_get_config() -> Result<serde_json::Value, String> {
     let data = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
     serde_json::from_str(&data).map_err(|e| e.to_string())
 }
+
+// ── MCP servers ───────────────────────────────────────────────────────────
+
+/// List the MCP servers configured in `mcp_config.json`, sorted by name.
+///
+/// Each entry carries the server name, whether it is marked active, its
+/// transport, and the command or URL it is reached through. Servers are not
+/// started; the CLI only reads the configuration shared with the app.
+pub fn cli_list_mcp_servers() -> Result<Vec<serde_json::Value>, String> {
+    let path = resolve_jan_data_folder().join("mcp_config.json");
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let config = load_mcp_config(&path)?;
+    let Some(servers) = config.get("mcpServers").and_then(|s| s.as_object()) else {
+        return Ok(Vec::new());
+    };
+
+    let mut entries: Vec<(&String, &serde_json::Value)> = servers.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    Ok(entries
+        .into_iter()
+        .map(|(name, server)| {
+            let url = server.get("url").and_then(|u| u.as_str()).filter(|u| !u.is_empty());
+            let transport = server
+                .get("type")
+                .and_then(|t| t.as_str())
+                .unwrap_or(if url.is_some() { "http" } else { "stdio" });
+            let target = match url {
+                Some(url) => url.to_string(),
+                None => {
+                    let command = server.get("command").and_then(|c| c.as_str()).unwrap_or("");
+                    let args = server
+                        .get("args")
+                        .and_then(|a| a.as_array())
+                        .map(|args| args.iter().filter_map(|a| a.as_str()).collect::<Vec<_>>())
+                        .unwrap_or_default();
+                    std::iter::once(command)
+                        .chain(args)
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                }
+            };
+            serde_json::json!({
+                "name": name,
+                "active": server.get("active").and_then(|a| a.as_bool()).unwrap_or(false),
+                "transport": transport,
+                "target": target,
+            })
+        })
+        .collect())
+}
+
+// ── Server service ─────────────────────────────────────────────────────────
+
+/// Install this binary's `serve` as a service started at login.
+pub fn cli_install_service(options: &ServiceOptions) -> Result<ServiceStatus, String> {
+    let program = std::env::current_exe().map_err(|e| e.to_string())?;
+    install_service(&program, options, &resolve_jan_data_folder())
+}
+
+pub fn cli_uninstall_service() -> Result<(), String> {
+    uninstall_service()
+}
+
+pub fn cli_service_status() -> ServiceStatus {
+    service_status(&resolve_jan_data_folder())
+}
+
+/// Claim the data folder for `jan serve`; released when the returned lock is dropped.
+pub fn cli_acquire_server_lock(model_id: &str, port: u16) -> Result<ServerLock, String> {
+    acquire_server_lock(
+        &resolve_jan_data_folder(),
+        &ServerLockInfo {
+            pid: std::process::id(),
+            model_id: model_id.to_string(),
+            port,
+            started_at: chrono::Utc::now().to_rfc3339(),
+        },
+    )
+}
+
+// ── First-run setup ────────────────────────────────────────────────────────
+
+/// Where setup stands; the app and `jan setup` share it.
+pub fn cli_onboarding_state() -> OnboardingState {
+    read_onboarding_state(&resolve_jan_data_folder())
+}
+
+/// Finish the current setup step with its outcome.
+pub async fn cli_complete_onboarding_step(outcome: StepOutcome) -> Result<OnboardingState, String> {
+    let now = chrono::Utc::now().timestamp_millis();
+    update_onboarding_state(&resolve_jan_data_folder(), |state| {
+        complete_step(state, outcome, now)
+    })
+    .await
+}
+
+/// Skip the current setup step, when it is optional.
+pub async fn cli_skip_onboarding_step(step: OnboardingStep) -> Result<OnboardingState, String> {
+    let now = chrono::Utc::now().timestamp_millis();
+    update_onboarding_state(&resolve_jan_data_folder(), |state| {
+        skip_step(state, step, now)
+    })
+    .await
+}
+
+pub async fn cli_reset_onboarding() -> Result<OnboardingState, String> {
+    update_onboarding_state(&resolve_jan_data_folder(), |state| {
+        *state = OnboardingState::default();
+        Ok(())
+    })
+    .await
+}
+
+/// Add default MCP servers to `mcp_config.json`; they start with the app.
+pub fn cli_install_default_mcp_servers(names: &[String]) -> Result<(), String> {
+    install_default_mcp_servers(&resolve_jan_data_folder(), names).map(|_| ())
+}
+
+// ── Storage ────────────────────────────────────────────────────────────────
+
+/// Record a model load, so the app's storage cleanup knows the model is in use.
+pub fn cli_record_model_load(model_id: &str) {
+    let now = chrono::Utc::now().timestamp_millis();
+    if let Err(e) = record_model_load(&resolve_jan_data_folder(), model_id, now) {
+        log::warn!("Failed to record the load of {model_id}: {e}");
+    }
+}
@@ -0,0 +1,44 @@
+use tauri::{AppHandle, Runtime};
+
+use super::helpers::{detect_runtime, read_settings, run_code, write_settings};
+use super::models::{CodeExecSettings, CodeRunRequest, CodeRunResult, CodeSandboxStatus};
+use crate::core::app::commands::get_jan_data_folder_path;
+
+/// Reports which container runtime would run snippets.
+#[tauri::command]
+pub async fn get_code_sandbox_status<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<CodeSandboxStatus, String> {
+    let settings = read_settings(&get_jan_data_folder_path(app_handle));
+    let runtime = detect_runtime(&settings).await;
+    Ok(CodeSandboxStatus {
+        available: runtime.is_some(),
+        runtime,
+    })
+}
+
+/// Returns the code execution settings.
+#[tauri::command]
+pub async fn get_code_exec_settings<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<CodeExecSettings, String> {
+    Ok(read_settings(&get_jan_data_folder_path(app_handle)))
+}
+
+/// Replaces the code execution settings and returns them with limits clamped.
+#[tauri::command]
+pub async fn update_code_exec_settings<R: Runtime>(
+    app_handle: AppHandle<R>,
+    settings: CodeExecSettings,
+) -> Result<CodeExecSettings, String> {
+    write_settings(&get_jan_data_folder_path(app_handle), settings)
+}
+
+/// Runs a snippet in the sandbox.
+#[tauri::command]
+pub async fn run_code_snippet<R: Runtime>(
+    app_handle: AppHandle<R>,
+    request: CodeRunRequest,
+) -> Result<CodeRunResult, String> {
+    run_code(&get_jan_data_folder_path(app_handle), request).await
+}
@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+pub const CODE_EXEC_DIR: &str = "code_exec";
+pub const CODE_EXEC_SETTINGS_FILE: &str = "settings.json";
+pub const CODE_EXEC_RUNS_DIR: &str = "runs";
+
+/// Name of the built-in code execution tool offered to the agent loop
+pub const RUN_CODE_TOOL: &str = "run_code";
+
+pub const DEFAULT_PYTHON_IMAGE: &str = "python:3.12-slim";
+pub const DEFAULT_NODE_IMAGE: &str = "node:22-slim";
+
+/// Mount point of the run directory inside the container
+pub const WORKSPACE_MOUNT: &str = "/workspace";
+/// Directory below the workspace whose files are returned as artifacts
+pub const ARTIFACTS_DIR: &str = "out";
+/// Containers are named with this prefix so they can be killed on timeout
+pub const CONTAINER_PREFIX: &str = "jan-code-";
+
+pub const DEFAULT_MEMORY_MB: u64 = 256;
+pub const MIN_MEMORY_MB: u64 = 64;
+pub const MAX_MEMORY_MB: u64 = 4096;
+pub const DEFAULT_CPUS: f32 = 1.0;
+pub const MAX_CPUS: f32 = 4.0;
+pub const DEFAULT_TIMEOUT_SECS: u64 = 30;
+pub const MAX_TIMEOUT_SECS: u64 = 300;
+pub const PIDS_LIMIT: u32 = 64;
+pub const TMPFS_SIZE: &str = "64m";
+
+/// Stdout and stderr are each cut off after this many bytes
+pub const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+pub const MAX_ARTIFACTS: usize = 20;
+pub const MAX_ARTIFACT_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Time allowed for `docker version` when detecting a runtime
+pub const RUNTIME_PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+/// Extra time for container start-up on top of the snippet's time limit
+pub const STARTUP_GRACE: Duration = Duration::from_secs(10);
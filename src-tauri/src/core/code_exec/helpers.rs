@@ -0,0 +1,431 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Map, Value};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::Command;
+use tokio_util::sync::CancellationToken;
+
+use super::constants::{
+    ARTIFACTS_DIR, CODE_EXEC_DIR, CODE_EXEC_RUNS_DIR, CODE_EXEC_SETTINGS_FILE, CONTAINER_PREFIX,
+    DEFAULT_CPUS, MAX_ARTIFACTS, MAX_ARTIFACT_BYTES, MAX_CPUS, MAX_MEMORY_MB, MAX_OUTPUT_BYTES,
+    MAX_TIMEOUT_SECS, MIN_MEMORY_MB, PIDS_LIMIT, RUNTIME_PROBE_TIMEOUT, RUN_CODE_TOOL,
+    STARTUP_GRACE, TMPFS_SIZE, WORKSPACE_MOUNT,
+};
+use super::models::{
+    CodeArtifact, CodeExecSettings, CodeLanguage, CodeRunRequest, CodeRunResult, ContainerRuntime,
+};
+use crate::core::mcp::models::ToolWithServer;
+
+#[cfg(target_os = "windows")]
+fn hide_window(cmd: &mut Command) {
+    use std::os::windows::process::CommandExt;
+    cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+}
+
+#[cfg(not(target_os = "windows"))]
+fn hide_window(_cmd: &mut Command) {}
+
+pub fn get_settings_path(data_folder: &Path) -> PathBuf {
+    data_folder
+        .join(CODE_EXEC_DIR)
+        .join(CODE_EXEC_SETTINGS_FILE)
+}
+
+/// Read sandbox settings, defaulting to the built-in tool being disabled
+pub fn read_settings(data_folder: &Path) -> CodeExecSettings {
+    fs::read_to_string(get_settings_path(data_folder))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+/// Write settings with limits clamped to the supported ranges.
+pub fn write_settings(
+    data_folder: &Path,
+    settings: CodeExecSettings,
+) -> Result<CodeExecSettings, String> {
+    let settings = CodeExecSettings {
+        memory_mb: settings.memory_mb.clamp(MIN_MEMORY_MB, MAX_MEMORY_MB),
+        cpus: if settings.cpus.is_finite() {
+            settings.cpus.clamp(0.1, MAX_CPUS)
+        } else {
+            DEFAULT_CPUS
+        },
+        timeout_secs: settings.timeout_secs.clamp(1, MAX_TIMEOUT_SECS),
+        ..settings
+    };
+    if settings.python_image.trim().is_empty() || settings.node_image.trim().is_empty() {
+        return Err("Sandbox images cannot be empty".to_string());
+    }
+    let path = get_settings_path(data_folder);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let data = serde_json::to_string_pretty(&settings).map_err(|e| e.to_string())?;
+    fs::write(path, data).map_err(|e| e.to_string())?;
+    Ok(settings)
+}
+
+async fn runtime_responds(runtime: ContainerRuntime) -> bool {
+    let mut cmd = Command::new(runtime.binary());
+    cmd.args(["version", "--format", "{{.Server.Version}}"])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true);
+    hide_window(&mut cmd);
+    matches!(
+        tokio::time::timeout(RUNTIME_PROBE_TIMEOUT, cmd.status()).await,
+        Ok(Ok(status)) if status.success()
+    )
+}
+
+/// The configured runtime if it responds, otherwise the first responding of Docker and Podman.
+pub async fn detect_runtime(settings: &CodeExecSettings) -> Option<ContainerRuntime> {
+    let candidates = match settings.runtime {
+        Some(runtime) => vec![runtime],
+        None => vec![ContainerRuntime::Docker, ContainerRuntime::Podman],
+    };
+    for runtime in candidates {
+        if runtime_responds(runtime).await {
+            return Some(runtime);
+        }
+    }
+    None
+}
+
+/// Time limit for a run: the requested limit capped at the configured one.
+pub fn effective_timeout(settings: &CodeExecSettings, requested: Option<u64>) -> Duration {
+    let limit = settings.timeout_secs.clamp(1, MAX_TIMEOUT_SECS);
+    Duration::from_secs(requested.unwrap_or(limit).clamp(1, limit))
+}
+
+/// Arguments for `<runtime> run` executing the snippet in `run_dir`.
+pub fn container_args(
+    settings: &CodeExecSettings,
+    language: CodeLanguage,
+    run_dir: &Path,
+    container_name: &str,
+) -> Vec<String> {
+    let image = match language {
+        CodeLanguage::Python => &settings.python_image,
+        CodeLanguage::Javascript => &settings.node_image,
+    };
+    let network = if settings.allow_network {
+        "bridge"
+    } else {
+        "none"
+    };
+    let memory = settings.memory_mb.clamp(MIN_MEMORY_MB, MAX_MEMORY_MB);
+    vec![
+        "run".into(),
+        "--rm".into(),
+        "--name".into(),
+        container_name.into(),
+        "--network".into(),
+        network.into(),
+        "--memory".into(),
+        format!("{memory}m"),
+        // Equal to --memory, so the container cannot swap
+        "--memory-swap".into(),
+        format!("{memory}m"),
+        "--cpus".into(),
+        format!("{:.2}", settings.cpus.clamp(0.1, MAX_CPUS)),
+        "--pids-limit".into(),
+        PIDS_LIMIT.to_string(),
+        "--cap-drop".into(),
+        "ALL".into(),
+        "--security-opt".into(),
+        "no-new-privileges".into(),
+        "--read-only".into(),
+        "--tmpfs".into(),
+        format!("/tmp:rw,noexec,nosuid,size={TMPFS_SIZE}"),
+        "--volume".into(),
+        format!("{}:{WORKSPACE_MOUNT}", run_dir.display()),
+        "--workdir".into(),
+        WORKSPACE_MOUNT.into(),
+        "--env".into(),
+        "HOME=/tmp".into(),
+        "--env".into(),
+        "PYTHONDONTWRITEBYTECODE=1".into(),
+        "--env".into(),
+        "PYTHONUNBUFFERED=1".into(),
+        image.clone(),
+        language.interpreter().into(),
+        format!("{WORKSPACE_MOUNT}/{}", language.file_name()),
+    ]
+}
+
+/// Decode output, cutting it off at `MAX_OUTPUT_BYTES` on a character boundary.
+pub fn truncate_output(bytes: &[u8]) -> (String, bool) {
+    let text = String::from_utf8_lossy(bytes);
+    if text.len() <= MAX_OUTPUT_BYTES {
+        return (text.into_owned(), false);
+    }
+    let mut end = MAX_OUTPUT_BYTES;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    (text[..end].to_string(), true)
+}
+
+/// Read `reader` to the end, keeping at most `cap` bytes. Stops reading as soon as the
+/// stream goes past the cap, and says whether it did.
+pub async fn read_capped<R: AsyncRead + Unpin>(
+    mut reader: R,
+    cap: usize,
+) -> std::io::Result<(Vec<u8>, bool)> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 8192];
+    loop {
+        let read = reader.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok((buffer, false));
+        }
+        let room = cap - buffer.len();
+        if read > room {
+            buffer.extend_from_slice(&chunk[..room]);
+            return Ok((buffer, true));
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+}
+
+/// Capture one of the container's pipes, cancelling `full` once it passes the output cap
+async fn capture_pipe<R: AsyncRead + Unpin>(
+    pipe: Option<R>,
+    full: &CancellationToken,
+) -> std::io::Result<(Vec<u8>, bool)> {
+    let Some(pipe) = pipe else {
+        return Ok((Vec::new(), false));
+    };
+    let (bytes, capped) = read_capped(pipe, MAX_OUTPUT_BYTES).await?;
+    if capped {
+        full.cancel();
+    }
+    Ok((bytes, capped))
+}
+
+pub fn get_runs_dir(data_folder: &Path) -> PathBuf {
+    data_folder.join(CODE_EXEC_DIR).join(CODE_EXEC_RUNS_DIR)
+}
+
+/// Create the run directory with the snippet and a world-writable artifacts directory, since
+/// the container user does not own the host directory.
+fn prepare_run_dir(run_dir: &Path, request: &CodeRunRequest) -> Result<(), String> {
+    let out_dir = run_dir.join(ARTIFACTS_DIR);
+    fs::create_dir_all(&out_dir).map_err(|e| e.to_string())?;
+    fs::write(run_dir.join(request.language.file_name()), &request.code)
+        .map_err(|e| e.to_string())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&out_dir, fs::Permissions::from_mode(0o777))
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Regular files in the artifacts directory, by name. Oversized files and files beyond
+/// `MAX_ARTIFACTS` are left out.
+pub fn collect_artifacts(run_dir: &Path) -> Vec<CodeArtifact> {
+    let Ok(entries) = fs::read_dir(run_dir.join(ARTIFACTS_DIR)) else {
+        return Vec::new();
+    };
+    let mut artifacts: Vec<CodeArtifact> = entries
+        .flatten()
+        .filter_map(|entry| {
+            // symlink_metadata so links out of the workspace are not followed
+            let metadata = entry.path().symlink_metadata().ok()?;
+            if !metadata.is_file() || metadata.len() > MAX_ARTIFACT_BYTES {
+                return None;
+            }
+            Some(CodeArtifact {
+                name: entry.file_name().to_string_lossy().to_string(),
+                path: entry.path().to_string_lossy().to_string(),
+                size: metadata.len(),
+            })
+        })
+        .collect();
+    artifacts.sort_by(|a, b| a.name.cmp(&b.name));
+    artifacts.truncate(MAX_ARTIFACTS);
+    artifacts
+}
+
+async fn kill_container(runtime: ContainerRuntime, name: &str) {
+    let mut cmd = Command::new(runtime.binary());
+    cmd.args(["kill", name])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    hide_window(&mut cmd);
+    if let Err(e) = cmd.status().await {
+        log::warn!("Failed to kill sandbox container {name}: {e}");
+    }
+}
+
+/// Run a snippet in a fresh container and collect its output and artifacts.
+pub async fn run_code(
+    data_folder: &Path,
+    request: CodeRunRequest,
+) -> Result<CodeRunResult, String> {
+    if request.code.trim().is_empty() {
+        return Err("Code cannot be empty".to_string());
+    }
+    let settings = read_settings(data_folder);
+    let runtime = detect_runtime(&settings)
+        .await
+        .ok_or_else(|| "No container runtime available; install Docker or Podman".to_string())?;
+    let timeout = effective_timeout(&settings, request.timeout_secs);
+
+    let run_id = uuid::Uuid::new_v4().to_string();
+    let run_dir = get_runs_dir(data_folder).join(&run_id);
+    prepare_run_dir(&run_dir, &request)?;
+    let container_name = format!("{CONTAINER_PREFIX}{run_id}");
+
+    let mut cmd = Command::new(runtime.binary());
+    cmd.args(container_args(
+        &settings,
+        request.language,
+        &run_dir,
+        &container_name,
+    ))
+    .stdin(Stdio::null())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .kill_on_drop(true);
+    hide_window(&mut cmd);
+
+    let started = Instant::now();
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start {}: {e}", runtime.binary()))?;
+    let stdout_pipe = child.stdout.take();
+    let stderr_pipe = child.stderr.take();
+
+    // Once either pipe passes the cap the container is stopped, so a snippet printing in a
+    // loop cannot fill memory before the time limit
+    let full = CancellationToken::new();
+    let collect = async {
+        let (stdout, stderr) = tokio::try_join!(
+            capture_pipe(stdout_pipe, &full),
+            capture_pipe(stderr_pipe, &full)
+        )?;
+        let status = child.wait().await?;
+        Ok::<_, std::io::Error>((status, stdout, stderr))
+    };
+    let stop_when_full = async {
+        full.cancelled().await;
+        kill_container(runtime, &container_name).await;
+        std::future::pending::<()>().await;
+    };
+    // The grace period covers container start-up on top of the snippet's own limit
+    let output = tokio::time::timeout(timeout + STARTUP_GRACE, async {
+        tokio::select! {
+            output = collect => output,
+            _ = stop_when_full => unreachable!(),
+        }
+    })
+    .await;
+    let duration_ms = started.elapsed().as_millis() as u64;
+
+    let (exit_code, stdout, stderr, capped, timed_out) = match output {
+        Ok(Ok((status, (stdout, stdout_capped), (stderr, stderr_capped)))) => (
+            status.code(),
+            stdout,
+            stderr,
+            stdout_capped || stderr_capped,
+            false,
+        ),
+        Ok(Err(e)) => {
+            let _ = fs::remove_dir_all(&run_dir);
+            return Err(format!("Sandbox run failed: {e}"));
+        }
+        Err(_) => {
+            kill_container(runtime, &container_name).await;
+            (None, Vec::new(), Vec::new(), false, true)
+        }
+    };
+    let (stdout, stdout_truncated) = truncate_output(&stdout);
+    let (stderr, stderr_truncated) = truncate_output(&stderr);
+
+    let _ = fs::remove_file(run_dir.join(request.language.file_name()));
+    let artifacts = collect_artifacts(&run_dir);
+    if artifacts.is_empty() {
+        let _ = fs::remove_dir_all(&run_dir);
+    }
+
+    Ok(CodeRunResult {
+        run_id,
+        language: request.language,
+        exit_code,
+        stdout,
+        stderr,
+        timed_out,
+        truncated: capped || stdout_truncated || stderr_truncated,
+        duration_ms,
+        artifacts,
+    })
+}
+
+/// Render a run for the model.
+pub fn format_for_model(result: &CodeRunResult) -> String {
+    let mut text = if result.timed_out {
+        "The code was stopped because it exceeded the time limit.\n".to_string()
+    } else {
+        match result.exit_code {
+            Some(code) => format!("Exit code: {code}\n"),
+            None => "The code was terminated.\n".to_string(),
+        }
+    };
+    if !result.stdout.is_empty() {
+        text.push_str(&format!("\nstdout:\n{}\n", result.stdout.trim_end()));
+    }
+    if !result.stderr.is_empty() {
+        text.push_str(&format!("\nstderr:\n{}\n", result.stderr.trim_end()));
+    }
+    if result.truncated {
+        text.push_str("\n[Output truncated]\n");
+    }
+    if !result.artifacts.is_empty() {
+        text.push_str("\nFiles written:\n");
+        for artifact in &result.artifacts {
+            text.push_str(&format!("- {} ({} bytes)\n", artifact.name, artifact.size));
+        }
+    }
+    text
+}
+
+/// Definition of the built-in code execution tool.
+pub fn run_code_tool(server: &str) -> ToolWithServer {
+    ToolWithServer {
+        name: RUN_CODE_TOOL.to_string(),
+        description: Some(format!(
+            "Run a Python or JavaScript snippet in an isolated sandbox without network access and \
+             return its stdout and stderr. Files written to {WORKSPACE_MOUNT}/{ARTIFACTS_DIR} are \
+             returned to the user."
+        )),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "language": {"type": "string", "enum": ["python", "javascript"]},
+                "code": {"type": "string", "description": "Complete program to run"},
+                "timeout_secs": {
+                    "type": "integer",
+                    "description": "Time limit in seconds",
+                    "minimum": 1,
+                    "maximum": MAX_TIMEOUT_SECS,
+                }
+            },
+            "required": ["language", "code"]
+        }),
+        server: server.to_string(),
+    }
+}
+
+/// Parse the built-in tool's arguments into a run request.
+pub fn request_from_arguments(arguments: &Map<String, Value>) -> Result<CodeRunRequest, String> {
+    serde_json::from_value(Value::Object(arguments.clone()))
+        .map_err(|e| format!("Invalid run_code arguments: {e}"))
+}
@@ -0,0 +1,22 @@
+/*!
+   Code Execution Sandbox
+
+   Runs Python and JavaScript snippets for the agent loop's built-in `run_code` tool and for
+   the `run_code_snippet` command, so users don't have to trust an arbitrary MCP execution
+   server. Snippets run in a throwaway OCI container started through the Docker or Podman CLI:
+   - no network unless explicitly allowed in the settings,
+   - memory, CPU, process count and wall-clock time are limited,
+   - all capabilities are dropped and the root filesystem is read-only.
+
+   Each run gets a directory under `code_exec/runs/<run_id>/` mounted at `/workspace`. Files the
+   snippet writes to `/workspace/out` are returned as artifacts; runs without artifacts are
+   removed afterwards.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+
+use super::constants::{
+    DEFAULT_CPUS, DEFAULT_MEMORY_MB, DEFAULT_NODE_IMAGE, DEFAULT_PYTHON_IMAGE, DEFAULT_TIMEOUT_SECS,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CodeLanguage {
+    Python,
+    #[serde(alias = "js", alias = "node")]
+    Javascript,
+}
+
+impl CodeLanguage {
+    pub fn file_name(self) -> &'static str {
+        match self {
+            CodeLanguage::Python => "main.py",
+            CodeLanguage::Javascript => "main.js",
+        }
+    }
+
+    pub fn interpreter(self) -> &'static str {
+        match self {
+            CodeLanguage::Python => "python",
+            CodeLanguage::Javascript => "node",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContainerRuntime {
+    Docker,
+    Podman,
+}
+
+impl ContainerRuntime {
+    pub fn binary(self) -> &'static str {
+        match self {
+            ContainerRuntime::Docker => "docker",
+            ContainerRuntime::Podman => "podman",
+        }
+    }
+}
+
+fn default_python_image() -> String {
+    DEFAULT_PYTHON_IMAGE.to_string()
+}
+
+fn default_node_image() -> String {
+    DEFAULT_NODE_IMAGE.to_string()
+}
+
+fn default_memory_mb() -> u64 {
+    DEFAULT_MEMORY_MB
+}
+
+fn default_cpus() -> f32 {
+    DEFAULT_CPUS
+}
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_TIMEOUT_SECS
+}
+
+/// Contents of `code_exec/settings.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodeExecSettings {
+    /// Offer code execution to the agent loop as the built-in `run_code` tool
+    #[serde(default)]
+    pub builtin_tool_enabled: bool,
+    /// Runtime to use; detected (Docker first, then Podman) when not set
+    #[serde(default)]
+    pub runtime: Option<ContainerRuntime>,
+    #[serde(default = "default_python_image")]
+    pub python_image: String,
+    #[serde(default = "default_node_image")]
+    pub node_image: String,
+    #[serde(default = "default_memory_mb")]
+    pub memory_mb: u64,
+    #[serde(default = "default_cpus")]
+    pub cpus: f32,
+    /// Default and upper bound for a run's time limit
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Give containers network access. Off by default.
+    #[serde(default)]
+    pub allow_network: bool,
+}
+
+impl Default for CodeExecSettings {
+    fn default() -> Self {
+        Self {
+            builtin_tool_enabled: false,
+            runtime: None,
+            python_image: default_python_image(),
+            node_image: default_node_image(),
+            memory_mb: DEFAULT_MEMORY_MB,
+            cpus: DEFAULT_CPUS,
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+            allow_network: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeRunRequest {
+    pub language: CodeLanguage,
+    pub code: String,
+    /// Shorter time limit for this run; capped at the configured limit
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+/// A file the snippet wrote to `/workspace/out`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CodeArtifact {
+    pub name: String,
+    /// Absolute path on the host
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeRunResult {
+    pub run_id: String,
+    pub language: CodeLanguage,
+    /// None when the run was killed
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub timed_out: bool,
+    /// Whether stdout or stderr was cut off
+    pub truncated: bool,
+    pub duration_ms: u64,
+    pub artifacts: Vec<CodeArtifact>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CodeSandboxStatus {
+    /// Runtime that would be used, None when neither Docker nor Podman is usable
+    pub runtime: Option<ContainerRuntime>,
+    pub available: bool,
+}
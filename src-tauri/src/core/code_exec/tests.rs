@@ -0,0 +1,185 @@
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use serde_json::json;
+
+use super::constants::{ARTIFACTS_DIR, MAX_MEMORY_MB, MAX_OUTPUT_BYTES};
+use super::helpers::{
+    collect_artifacts, container_args, effective_timeout, format_for_model, read_capped,
+    read_settings, request_from_arguments, truncate_output, write_settings,
+};
+use super::models::{CodeArtifact, CodeExecSettings, CodeLanguage, CodeRunResult};
+use crate::core::app::commands::get_jan_data_folder_path;
+
+fn arg_after<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+    args.iter()
+        .position(|a| a == flag)
+        .map(|i| args[i + 1].as_str())
+}
+
+#[test]
+fn test_container_args_are_locked_down() {
+    let settings = CodeExecSettings::default();
+    let args = container_args(
+        &settings,
+        CodeLanguage::Python,
+        Path::new("/data/code_exec/runs/1"),
+        "jan-code-1",
+    );
+    assert_eq!(args[0], "run");
+    assert!(args.contains(&"--rm".to_string()));
+    assert!(args.contains(&"--read-only".to_string()));
+    assert_eq!(arg_after(&args, "--network"), Some("none"));
+    assert_eq!(arg_after(&args, "--memory"), Some("256m"));
+    assert_eq!(arg_after(&args, "--memory-swap"), Some("256m"));
+    assert_eq!(arg_after(&args, "--cap-drop"), Some("ALL"));
+    assert_eq!(
+        arg_after(&args, "--volume"),
+        Some("/data/code_exec/runs/1:/workspace")
+    );
+    let tail = &args[args.len() - 3..];
+    assert_eq!(tail, ["python:3.12-slim", "python", "/workspace/main.py"]);
+
+    let networked = CodeExecSettings {
+        allow_network: true,
+        ..settings
+    };
+    let args = container_args(&networked, CodeLanguage::Javascript, Path::new("/r"), "n");
+    assert_eq!(arg_after(&args, "--network"), Some("bridge"));
+    assert_eq!(args.last().map(String::as_str), Some("/workspace/main.js"));
+}
+
+#[test]
+fn test_effective_timeout_is_capped() {
+    let settings = CodeExecSettings {
+        timeout_secs: 20,
+        ..Default::default()
+    };
+    assert_eq!(effective_timeout(&settings, None), Duration::from_secs(20));
+    assert_eq!(
+        effective_timeout(&settings, Some(5)),
+        Duration::from_secs(5)
+    );
+    assert_eq!(
+        effective_timeout(&settings, Some(500)),
+        Duration::from_secs(20)
+    );
+    assert_eq!(
+        effective_timeout(&settings, Some(0)),
+        Duration::from_secs(1)
+    );
+}
+
+#[test]
+fn test_settings_are_clamped() {
+    let app = tauri::test::mock_app();
+    let data_dir = get_jan_data_folder_path(app.handle().clone());
+    assert!(!read_settings(&data_dir).builtin_tool_enabled);
+
+    let saved = write_settings(
+        &data_dir,
+        CodeExecSettings {
+            builtin_tool_enabled: true,
+            memory_mb: 1_000_000,
+            timeout_secs: 0,
+            cpus: f32::NAN,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    assert_eq!(saved.memory_mb, MAX_MEMORY_MB);
+    assert_eq!(saved.timeout_secs, 1);
+    assert_eq!(saved.cpus, 1.0);
+    assert_eq!(read_settings(&data_dir), saved);
+
+    let empty_image = CodeExecSettings {
+        python_image: " ".to_string(),
+        ..Default::default()
+    };
+    assert!(write_settings(&data_dir, empty_image).is_err());
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_read_capped() {
+    let (bytes, capped) = read_capped(&b"hello"[..], 5).await.unwrap();
+    assert_eq!((bytes.as_slice(), capped), (&b"hello"[..], false));
+    let endless = tokio::io::repeat(b'x');
+    let (bytes, capped) = read_capped(endless, MAX_OUTPUT_BYTES).await.unwrap();
+    assert!(capped);
+    assert_eq!(bytes.len(), MAX_OUTPUT_BYTES);
+}
+
+#[test]
+fn test_truncate_output() {
+    assert_eq!(truncate_output(b"hello"), ("hello".to_string(), false));
+    let long = "é".repeat(MAX_OUTPUT_BYTES);
+    let (text, truncated) = truncate_output(long.as_bytes());
+    assert!(truncated);
+    assert!(text.len() <= MAX_OUTPUT_BYTES);
+}
+
+#[test]
+fn test_collect_artifacts_skips_directories() {
+    let app = tauri::test::mock_app();
+    let data_dir = get_jan_data_folder_path(app.handle().clone());
+    let run_dir = data_dir.join("run");
+    let out_dir = run_dir.join(ARTIFACTS_DIR);
+    fs::create_dir_all(out_dir.join("nested")).unwrap();
+    fs::write(out_dir.join("plot.png"), b"png").unwrap();
+    fs::write(out_dir.join("data.csv"), b"a,b\n1,2\n").unwrap();
+
+    let artifacts = collect_artifacts(&run_dir);
+    let names: Vec<&str> = artifacts.iter().map(|a| a.name.as_str()).collect();
+    assert_eq!(names, ["data.csv", "plot.png"]);
+    assert_eq!(artifacts[1].size, 3);
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[test]
+fn test_request_from_arguments() {
+    let request = request_from_arguments(
+        json!({"language": "js", "code": "console.log(1)", "timeout_secs": 3})
+            .as_object()
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(request.language, CodeLanguage::Javascript);
+    assert_eq!(request.timeout_secs, Some(3));
+    assert!(request_from_arguments(json!({"code": "x"}).as_object().unwrap()).is_err());
+    assert!(request_from_arguments(
+        json!({"language": "ruby", "code": "x"})
+            .as_object()
+            .unwrap()
+    )
+    .is_err());
+}
+
+#[test]
+fn test_format_for_model() {
+    let mut result = CodeRunResult {
+        run_id: "1".to_string(),
+        language: CodeLanguage::Python,
+        exit_code: Some(1),
+        stdout: "partial\n".to_string(),
+        stderr: "Traceback\n".to_string(),
+        timed_out: false,
+        truncated: false,
+        duration_ms: 12,
+        artifacts: vec![CodeArtifact {
+            name: "out.txt".to_string(),
+            path: "/tmp/out.txt".to_string(),
+            size: 4,
+        }],
+    };
+    let text = format_for_model(&result);
+    assert!(text.starts_with("Exit code: 1\n"));
+    assert!(text.contains("stdout:\npartial\n"));
+    assert!(text.contains("stderr:\nTraceback\n"));
+    assert!(text.contains("- out.txt (4 bytes)"));
+
+    result.timed_out = true;
+    result.exit_code = None;
+    assert!(format_for_model(&result).contains("exceeded the time limit"));
+}
@@ -0,0 +1,47 @@
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Runtime};
+
+use super::helpers::{
+    cancel_comparison, list_comparisons, read_comparison, run_comparison, validate_request,
+};
+use super::models::{CompareRequest, ComparisonResult};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::streaming::models::TokenChunk;
+
+/// Sends one prompt to 2–4 models at once. Each candidate's tokens are streamed under the
+/// stream id `{comparison_id}:{index}`; its status, latency, token usage and cost are
+/// reported through `model-comparison-candidate` events and the returned comparison.
+#[tauri::command]
+pub async fn compare_models<R: Runtime>(
+    app_handle: AppHandle<R>,
+    request: CompareRequest,
+    on_token: Option<Channel<TokenChunk>>,
+) -> Result<ComparisonResult, String> {
+    validate_request(&request)?;
+    run_comparison(&app_handle, request, on_token).await
+}
+
+/// Stops every candidate of a running comparison; answers received so far are discarded.
+#[tauri::command]
+pub async fn cancel_model_comparison(comparison_id: String) -> Result<bool, String> {
+    Ok(cancel_comparison(&comparison_id))
+}
+
+#[tauri::command]
+pub async fn get_model_comparison<R: Runtime>(
+    app_handle: AppHandle<R>,
+    comparison_id: String,
+) -> Result<ComparisonResult, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    read_comparison(&data_folder, &comparison_id)
+}
+
+/// Lists stored comparisons, newest first, optionally only those of one thread.
+#[tauri::command]
+pub async fn list_model_comparisons<R: Runtime>(
+    app_handle: AppHandle<R>,
+    thread_id: Option<String>,
+) -> Result<Vec<ComparisonResult>, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    Ok(list_comparisons(&data_folder, thread_id.as_deref()))
+}
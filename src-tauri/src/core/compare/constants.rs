@@ -0,0 +1,6 @@
+// Model comparison constants
+pub const MIN_CANDIDATES: usize = 2;
+pub const MAX_CANDIDATES: usize = 4;
+/// Emitted with a `CandidateResult` when a candidate starts and when it finishes
+pub const COMPARISON_CANDIDATE_EVENT: &str = "model-comparison-candidate";
+pub const COMPARISONS_DIR: &str = "comparisons";
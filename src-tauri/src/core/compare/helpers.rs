@@ -0,0 +1,322 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use futures::future::join_all;
+use serde_json::Value;
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Emitter, Runtime};
+use tokio_util::sync::CancellationToken;
+
+use super::constants::{
+    COMPARISONS_DIR, COMPARISON_CANDIDATE_EVENT, MAX_CANDIDATES, MIN_CANDIDATES,
+};
+use super::models::{
+    CandidateResult, CandidateStatus, CompareCandidate, CompareRequest, ComparisonResult,
+    ModelPricing,
+};
+use crate::core::agent::constants::MODEL_TURN_TIMEOUT_SECS;
+use crate::core::agent::helpers::stream_model_turn;
+use crate::core::agent::models::TurnDelta;
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::config_store::helpers::write_json;
+use crate::core::inference::helpers::resolve_model_endpoint;
+use crate::core::inference::models::TokenUsage;
+use crate::core::scheduler::helpers::acquire_for_endpoint;
+use crate::core::scheduler::models::GenerationPriority;
+use crate::core::streaming::helpers::TokenStreamer;
+use crate::core::streaming::models::TokenChunk;
+
+/// Comparison id -> cancellation token of the running comparison
+static RUNNING: OnceLock<Mutex<HashMap<String, CancellationToken>>> = OnceLock::new();
+
+fn running() -> std::sync::MutexGuard<'static, HashMap<String, CancellationToken>> {
+    RUNNING
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+/// Comparison ids end up in file names
+pub fn is_valid_comparison_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 128
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+pub fn validate_request(request: &CompareRequest) -> Result<(), String> {
+    let count = request.candidates.len();
+    if !(MIN_CANDIDATES..=MAX_CANDIDATES).contains(&count) {
+        return Err(format!(
+            "Select between {MIN_CANDIDATES} and {MAX_CANDIDATES} models to compare"
+        ));
+    }
+    if request
+        .candidates
+        .iter()
+        .any(|candidate| candidate.model.trim().is_empty())
+    {
+        return Err("Every candidate needs a model".to_string());
+    }
+    if request.messages.is_empty() {
+        return Err("Nothing to send".to_string());
+    }
+    if let Some(id) = &request.comparison_id {
+        if !is_valid_comparison_id(id) {
+            return Err(format!("Invalid comparison id '{id}'"));
+        }
+    }
+    Ok(())
+}
+
+pub fn stream_id(comparison_id: &str, index: usize) -> String {
+    format!("{comparison_id}:{index}")
+}
+
+/// Cost of a candidate's tokens. Local models cost nothing; remote ones without a known
+/// price have no cost.
+pub fn candidate_cost(
+    usage: &TokenUsage,
+    pricing: Option<&ModelPricing>,
+    is_local: bool,
+) -> Option<f64> {
+    if is_local {
+        return Some(0.0);
+    }
+    pricing.map(|pricing| {
+        (usage.input_tokens as f64 * pricing.input_per_million
+            + usage.output_tokens as f64 * pricing.output_per_million)
+            / 1_000_000.0
+    })
+}
+
+/// Generation speed, measured from the first token so the prompt processing time and the
+/// network round trip don't count
+pub fn tokens_per_second(output_tokens: u64, first_token_ms: u64, latency_ms: u64) -> Option<f64> {
+    let generating_ms = latency_ms.saturating_sub(first_token_ms);
+    (output_tokens > 1 && generating_ms > 0)
+        .then(|| (output_tokens - 1) as f64 * 1000.0 / generating_ms as f64)
+}
+
+/// Text of the last user message
+pub fn prompt_text(messages: &[Value]) -> String {
+    let Some(message) = messages.iter().rev().find(|m| m["role"] == "user") else {
+        return String::new();
+    };
+    match &message["content"] {
+        Value::String(text) => text.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(|part| part["text"].as_str())
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+fn candidate_body(request: &CompareRequest, candidate: &CompareCandidate) -> Value {
+    let mut parameters = request.parameters.clone();
+    parameters.extend(candidate.parameters.clone());
+    let mut body = Value::Object(parameters);
+    body["messages"] = Value::Array(request.messages.clone());
+    body
+}
+
+pub fn get_comparisons_dir(data_folder: &Path) -> PathBuf {
+    data_folder.join(COMPARISONS_DIR)
+}
+
+pub fn save_comparison(data_folder: &Path, comparison: &ComparisonResult) -> Result<(), String> {
+    let dir = get_comparisons_dir(data_folder);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    write_json(&dir.join(format!("{}.json", comparison.id)), comparison)
+}
+
+pub fn read_comparison(data_folder: &Path, id: &str) -> Result<ComparisonResult, String> {
+    if !is_valid_comparison_id(id) {
+        return Err(format!("Invalid comparison id '{id}'"));
+    }
+    let path = get_comparisons_dir(data_folder).join(format!("{id}.json"));
+    let data = fs::read_to_string(&path).map_err(|_| format!("Comparison {id} not found"))?;
+    serde_json::from_str(&data).map_err(|e| format!("Invalid comparison {id}: {e}"))
+}
+
+/// Stored comparisons, newest first, optionally only those of one thread
+pub fn list_comparisons(data_folder: &Path, thread_id: Option<&str>) -> Vec<ComparisonResult> {
+    let Ok(entries) = fs::read_dir(get_comparisons_dir(data_folder)) else {
+        return Vec::new();
+    };
+    let mut comparisons: Vec<ComparisonResult> = entries
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|entry| {
+            let data = fs::read_to_string(entry.path()).ok()?;
+            serde_json::from_str(&data).ok()
+        })
+        .filter(|comparison: &ComparisonResult| match thread_id {
+            Some(thread_id) => comparison.thread_id.as_deref() == Some(thread_id),
+            None => true,
+        })
+        .collect();
+    comparisons.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    comparisons
+}
+
+fn emit_candidate<R: Runtime>(app: &AppHandle<R>, result: &CandidateResult) {
+    if let Err(e) = app.emit(COMPARISON_CANDIDATE_EVENT, result) {
+        log::warn!("Failed to emit {COMPARISON_CANDIDATE_EVENT}: {e}");
+    }
+}
+
+/// Stream one candidate's answer. Failures end up in the result so the other candidates
+/// are not affected.
+async fn run_candidate<R: Runtime>(
+    app: &AppHandle<R>,
+    request: &CompareRequest,
+    mut result: CandidateResult,
+    candidate: &CompareCandidate,
+    channel: Option<Channel<TokenChunk>>,
+    cancel: &CancellationToken,
+) -> CandidateResult {
+    emit_candidate(app, &result);
+    let started = Instant::now();
+    let streamer =
+        channel.map(|channel| TokenStreamer::for_channel(result.stream_id.clone(), channel));
+    let mut first_token = None;
+
+    let turn = async {
+        let endpoint = resolve_model_endpoint(app, &candidate.model).await?;
+        let body = candidate_body(request, candidate);
+        let _permit = tokio::select! {
+            permit = acquire_for_endpoint(
+                app,
+                &endpoint,
+                &result.stream_id,
+                GenerationPriority::Interactive,
+            ) => permit?,
+            _ = cancel.cancelled() => return Err("Cancelled".to_string()),
+        };
+        let turn = stream_model_turn(
+            &endpoint,
+            body,
+            request.thread_id.as_deref(),
+            Duration::from_secs(MODEL_TURN_TIMEOUT_SECS),
+            cancel,
+            |delta| {
+                if let TurnDelta::Content(delta) = delta {
+                    first_token.get_or_insert_with(|| started.elapsed());
+                    if let Some(streamer) = &streamer {
+                        streamer.push(delta);
+                    }
+                }
+            },
+        )
+        .await?;
+        Ok::<_, String>((turn, endpoint.is_local))
+    }
+    .await;
+    if let Some(streamer) = streamer {
+        streamer.finish().await;
+    }
+
+    result.latency_ms = started.elapsed().as_millis() as u64;
+    result.first_token_ms = first_token.map(|elapsed| elapsed.as_millis() as u64);
+    match turn {
+        Ok((turn, is_local)) => {
+            result.status = CandidateStatus::Completed;
+            result.tokens_per_second = tokens_per_second(
+                turn.usage.output_tokens,
+                result.first_token_ms.unwrap_or(result.latency_ms),
+                result.latency_ms,
+            );
+            result.cost = candidate_cost(&turn.usage, candidate.pricing.as_ref(), is_local);
+            result.usage = turn.usage;
+            result.content = turn.content;
+            result.finish_reason = turn.finish_reason;
+        }
+        Err(_) if cancel.is_cancelled() => result.status = CandidateStatus::Cancelled,
+        Err(e) => {
+            result.status = CandidateStatus::Failed;
+            result.error = Some(e);
+        }
+    }
+    emit_candidate(app, &result);
+    result
+}
+
+/// Run every candidate of a validated request concurrently and store the comparison
+pub async fn run_comparison<R: Runtime>(
+    app: &AppHandle<R>,
+    request: CompareRequest,
+    on_token: Option<Channel<TokenChunk>>,
+) -> Result<ComparisonResult, String> {
+    let id = request
+        .comparison_id
+        .clone()
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let cancel = CancellationToken::new();
+    {
+        let mut running = running();
+        if running.contains_key(&id) {
+            return Err(format!("Comparison {id} is already running"));
+        }
+        running.insert(id.clone(), cancel.clone());
+    }
+
+    let created_at = chrono::Utc::now().timestamp_millis();
+    let candidates = join_all(
+        request
+            .candidates
+            .iter()
+            .enumerate()
+            .map(|(index, candidate)| {
+                let result = CandidateResult {
+                    comparison_id: id.clone(),
+                    index,
+                    stream_id: stream_id(&id, index),
+                    model: candidate.model.clone(),
+                    label: candidate.label.clone(),
+                    status: CandidateStatus::Running,
+                    content: String::new(),
+                    error: None,
+                    finish_reason: None,
+                    first_token_ms: None,
+                    latency_ms: 0,
+                    usage: TokenUsage::default(),
+                    tokens_per_second: None,
+                    cost: None,
+                };
+                run_candidate(app, &request, result, candidate, on_token.clone(), &cancel)
+            }),
+    )
+    .await;
+    running().remove(&id);
+
+    let comparison = ComparisonResult {
+        id,
+        thread_id: request.thread_id.clone(),
+        created_at,
+        prompt: prompt_text(&request.messages),
+        candidates,
+    };
+    let data_folder = get_jan_data_folder_path(app.clone());
+    if let Err(e) = save_comparison(&data_folder, &comparison) {
+        log::warn!("Failed to store comparison {}: {e}", comparison.id);
+    }
+    Ok(comparison)
+}
+
+/// Cancel a running comparison. Returns whether it was running.
+pub fn cancel_comparison(id: &str) -> bool {
+    match running().get(id) {
+        Some(cancel) => {
+            cancel.cancel();
+            true
+        }
+        None => false,
+    }
+}
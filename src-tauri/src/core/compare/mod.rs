@@ -0,0 +1,22 @@
+/*!
+   Model Comparison
+
+   Runs one prompt against 2–4 models or providers at the same time so the UI can show the
+   answers side by side:
+   - every candidate streams its answer under its own stream id, `{comparison_id}:{index}`,
+     over the channel passed to `compare_models`,
+   - each candidate reports its status as `model-comparison-candidate` events when it starts
+     and when it finishes, independently of the others,
+   - latency, time to first token, token usage and cost (from the pricing supplied per
+     candidate; local models are free) are recorded per candidate,
+   - finished comparisons are stored in `comparisons/` and a running comparison can be
+     cancelled as a whole.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
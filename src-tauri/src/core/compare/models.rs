@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::core::inference::models::TokenUsage;
+
+/// Price of a model in the caller's currency
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompareCandidate {
+    pub model: String,
+    /// Shown instead of the model id, e.g. to tell two providers of one model apart
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Overrides the shared request parameters for this candidate
+    #[serde(default)]
+    pub parameters: Map<String, Value>,
+    #[serde(default)]
+    pub pricing: Option<ModelPricing>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompareRequest {
+    /// Generated when missing; used to cancel the comparison
+    #[serde(default)]
+    pub comparison_id: Option<String>,
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    pub messages: Vec<Value>,
+    /// Request parameters such as `temperature`, sent to every candidate
+    #[serde(default)]
+    pub parameters: Map<String, Value>,
+    pub candidates: Vec<CompareCandidate>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CandidateStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CandidateResult {
+    pub comparison_id: String,
+    pub index: usize,
+    /// Stream id of the candidate's token chunks
+    pub stream_id: String,
+    pub model: String,
+    #[serde(default)]
+    pub label: Option<String>,
+    pub status: CandidateStatus,
+    #[serde(default)]
+    pub content: String,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub finish_reason: Option<String>,
+    /// Milliseconds until the first token arrived
+    #[serde(default)]
+    pub first_token_ms: Option<u64>,
+    /// Milliseconds until the answer was complete
+    #[serde(default)]
+    pub latency_ms: u64,
+    #[serde(default)]
+    pub usage: TokenUsage,
+    /// Output tokens per second after the first token
+    #[serde(default)]
+    pub tokens_per_second: Option<f64>,
+    /// `None` when the price of a remote model is unknown
+    #[serde(default)]
+    pub cost: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComparisonResult {
+    pub id: String,
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    /// Milliseconds since epoch
+    pub created_at: i64,
+    /// The last user message, to recognize the comparison in a list
+    #[serde(default)]
+    pub prompt: String,
+    pub candidates: Vec<CandidateResult>,
+}
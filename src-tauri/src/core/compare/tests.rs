@@ -0,0 +1,97 @@
+use serde_json::{json, Map};
+
+use super::helpers::{
+    candidate_cost, list_comparisons, prompt_text, read_comparison, save_comparison,
+    tokens_per_second, validate_request,
+};
+use super::models::{CompareCandidate, CompareRequest, ComparisonResult, ModelPricing};
+use crate::core::inference::models::TokenUsage;
+
+fn request(models: &[&str]) -> CompareRequest {
+    CompareRequest {
+        comparison_id: None,
+        thread_id: None,
+        messages: vec![json!({"role": "user", "content": "Hi"})],
+        parameters: Map::new(),
+        candidates: models
+            .iter()
+            .map(|model| CompareCandidate {
+                model: model.to_string(),
+                label: None,
+                parameters: Map::new(),
+                pricing: None,
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn test_validate_request() {
+    assert!(validate_request(&request(&["a", "b"])).is_ok());
+    assert!(validate_request(&request(&["a", "b", "c", "d"])).is_ok());
+    assert!(validate_request(&request(&["a"])).is_err());
+    assert!(validate_request(&request(&["a", "b", "c", "d", "e"])).is_err());
+    assert!(validate_request(&request(&["a", " "])).is_err());
+
+    let mut empty = request(&["a", "b"]);
+    empty.messages.clear();
+    assert!(validate_request(&empty).is_err());
+    let mut bad_id = request(&["a", "b"]);
+    bad_id.comparison_id = Some("../x".to_string());
+    assert!(validate_request(&bad_id).is_err());
+}
+
+#[test]
+fn test_candidate_stats() {
+    let usage = TokenUsage {
+        input_tokens: 1_000,
+        output_tokens: 500,
+        ..Default::default()
+    };
+    let pricing = ModelPricing {
+        input_per_million: 3.0,
+        output_per_million: 15.0,
+    };
+    let cost = candidate_cost(&usage, Some(&pricing), false).unwrap();
+    assert!((cost - 0.0105).abs() < 1e-12);
+    assert_eq!(candidate_cost(&usage, None, false), None);
+    assert_eq!(candidate_cost(&usage, Some(&pricing), true), Some(0.0));
+
+    // 10 tokens after the first one, in the 500 ms after it
+    assert_eq!(tokens_per_second(11, 200, 700), Some(20.0));
+    assert_eq!(tokens_per_second(1, 200, 700), None);
+    assert_eq!(tokens_per_second(11, 700, 700), None);
+
+    let messages = vec![
+        json!({"role": "user", "content": "first"}),
+        json!({"role": "assistant", "content": "answer"}),
+        json!({"role": "user", "content": [{"type": "text", "text": "second"}]}),
+    ];
+    assert_eq!(prompt_text(&messages), "second");
+}
+
+#[test]
+fn test_store_comparisons() {
+    let data_folder = std::env::temp_dir().join(format!("jan-compare-{}", uuid::Uuid::new_v4()));
+    let comparison = |id: &str, created_at: i64, thread_id: Option<&str>| ComparisonResult {
+        id: id.to_string(),
+        thread_id: thread_id.map(str::to_string),
+        created_at,
+        prompt: "Hi".to_string(),
+        candidates: Vec::new(),
+    };
+    save_comparison(&data_folder, &comparison("old", 1, Some("t1"))).unwrap();
+    save_comparison(&data_folder, &comparison("new", 2, None)).unwrap();
+
+    let ids: Vec<_> = list_comparisons(&data_folder, None)
+        .into_iter()
+        .map(|c| c.id)
+        .collect();
+    assert_eq!(ids, vec!["new", "old"]);
+    assert_eq!(list_comparisons(&data_folder, Some("t1")).len(), 1);
+    assert_eq!(read_comparison(&data_folder, "old").unwrap().created_at, 1);
+    assert!(read_comparison(&data_folder, "missing").is_err());
+    assert!(read_comparison(&data_folder, "../old").is_err());
+
+    let _ = std::fs::remove_dir_all(data_folder);
+}
@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use tauri::{AppHandle, Manager, Runtime};
+
+use super::constants::MCP_CONFIG_FILE;
+use super::helpers::{is_versioned_path, list_history, read_version, restore_version};
+use super::models::ConfigVersion;
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::mcp::models::McpSettings;
+use crate::core::settings::helpers::enforce_mcp_policy;
+use crate::core::state::AppState;
+
+/// Lists recorded versions of the prompt templates, assistants and MCP config, newest
+/// first. With `path`, only the versions that changed that file.
+#[tauri::command]
+pub async fn list_config_history<R: Runtime>(
+    app_handle: AppHandle<R>,
+    path: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<ConfigVersion>, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    list_history(&data_folder, path.as_deref(), limit)
+}
+
+/// Contents of the versioned files as of `version`, or only of `path` when given
+#[tauri::command]
+pub async fn get_config_version<R: Runtime>(
+    app_handle: AppHandle<R>,
+    version: String,
+    path: Option<String>,
+) -> Result<HashMap<String, String>, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    if let Some(path) = &path {
+        if !is_versioned_path(path) {
+            return Err(format!("{path} is not a versioned config file"));
+        }
+    }
+    Ok(read_version(&data_folder, &version)?
+        .into_iter()
+        .filter(|(file, _)| match &path {
+            Some(path) => file == path,
+            None => true,
+        })
+        .map(|(file, data)| (file, String::from_utf8_lossy(&data).into_owned()))
+        .collect())
+}
+
+/// Rolls the versioned files, or only `path`, back to `version`. Returns the files that
+/// changed. Restored MCP settings apply right away; restored servers on their next start.
+#[tauri::command]
+pub async fn restore_config_version<R: Runtime>(
+    app_handle: AppHandle<R>,
+    version: String,
+    path: Option<String>,
+) -> Result<Vec<String>, String> {
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+    let restored = restore_version(&data_folder, &version, path.as_deref())?;
+    log::info!("Restored {restored:?} to config version {version}");
+
+    if restored.iter().any(|file| file == MCP_CONFIG_FILE) {
+        let settings = std::fs::read_to_string(data_folder.join(MCP_CONFIG_FILE))
+            .ok()
+            .and_then(|data| serde_json::from_str::<serde_json::Value>(&data).ok())
+            .and_then(|config| config.get("mcpSettings").cloned())
+            .and_then(|settings| serde_json::from_value::<McpSettings>(settings).ok())
+            .unwrap_or_default();
+        let settings = enforce_mcp_policy(settings);
+        *app_handle.state::<AppState>().mcp_settings.lock().await = settings;
+    }
+    Ok(restored)
+}
@@ -0,0 +1,9 @@
+// Config history constants
+/// Bare git repository in the data folder holding the history
+pub const HISTORY_DIR: &str = "config_history.git";
+pub const MCP_CONFIG_FILE: &str = "mcp_config.json";
+pub const HISTORY_AUTHOR_NAME: &str = "Jan";
+pub const HISTORY_AUTHOR_EMAIL: &str = "jan@localhost";
+pub const DEFAULT_HISTORY_LIMIT: usize = 50;
+/// Message of the snapshot taken at startup, which holds edits made while Jan wasn't running
+pub const EXTERNAL_CHANGES_MESSAGE: &str = "Changes made outside Jan";
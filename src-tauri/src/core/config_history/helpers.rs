@@ -0,0 +1,432 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use gix::bstr::{BString, ByteSlice};
+use gix::objs::tree::{Entry, EntryKind};
+use gix::ObjectId;
+use serde_json::Value;
+use tauri::{AppHandle, Runtime};
+
+use super::constants::{
+    DEFAULT_HISTORY_LIMIT, EXTERNAL_CHANGES_MESSAGE, HISTORY_AUTHOR_EMAIL, HISTORY_AUTHOR_NAME,
+    HISTORY_DIR, MCP_CONFIG_FILE,
+};
+use super::models::ConfigVersion;
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::assistants::constants::{ASSISTANTS_DIR, ASSISTANT_FILE};
+use crate::core::config_store::helpers::config_store;
+use crate::core::mcp::sharing::{
+    export_config, fill_secrets, local_secrets, required_secrets, secret_placeholder,
+};
+use crate::core::prompts::constants::{PROMPTS_DIR, TEMPLATES_FILE};
+
+/// Versioned files by path relative to the data folder, `/`-separated
+type Snapshot = BTreeMap<String, Vec<u8>>;
+
+// One writer at a time, so concurrent edits don't commit on top of the same parent
+static HISTORY_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+pub fn get_history_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(HISTORY_DIR)
+}
+
+/// Whether `path` names a versioned file
+pub fn is_versioned_path(path: &str) -> bool {
+    let parts: Vec<&str> = path.split('/').collect();
+    match parts.as_slice() {
+        [file] => *file == MCP_CONFIG_FILE,
+        [dir, file] => *dir == PROMPTS_DIR && *file == TEMPLATES_FILE,
+        [dir, id, file] => {
+            *dir == ASSISTANTS_DIR
+                && *file == ASSISTANT_FILE
+                && !id.is_empty()
+                && *id != "."
+                && *id != ".."
+                && !id.contains('\\')
+        }
+        _ => false,
+    }
+}
+
+fn local_path(data_folder: &Path, path: &str) -> PathBuf {
+    path.split('/')
+        .fold(data_folder.to_path_buf(), |dir, part| dir.join(part))
+}
+
+/// `mcp_config.json` with the secret env vars and headers replaced by placeholders, as in a
+/// shared setup, so the history never holds credentials. A file that doesn't parse is kept
+/// as it is, since its secrets can't be told apart.
+fn redact_mcp_config(data: String) -> Vec<u8> {
+    let Ok(mut config) = serde_json::from_str::<Value>(&data) else {
+        return data.into_bytes();
+    };
+    let servers = export_config(&config).mcp_servers;
+    let unchanged = config
+        .get("mcpServers")
+        .and_then(Value::as_object)
+        .map_or(true, |current| *current == servers);
+    if unchanged {
+        return data.into_bytes();
+    }
+    config["mcpServers"] = Value::Object(servers);
+    serde_json::to_vec_pretty(&config).unwrap_or_else(|_| data.into_bytes())
+}
+
+/// A versioned `mcp_config.json` with its placeholders filled from the secrets of the live
+/// config. Secrets the live config no longer has stay placeholders, to be entered again.
+fn restore_mcp_secrets(data: &[u8], live: Option<&str>) -> String {
+    let raw = || String::from_utf8_lossy(data).into_owned();
+    let Ok(mut config) = serde_json::from_slice::<Value>(data) else {
+        return raw();
+    };
+    let Some(servers) = config.get_mut("mcpServers").and_then(Value::as_object_mut) else {
+        return raw();
+    };
+    let required = required_secrets(servers);
+    if required.is_empty() {
+        return raw();
+    }
+    let live = live
+        .and_then(|live| serde_json::from_str(live).ok())
+        .unwrap_or(Value::Null);
+    let mut secrets: HashMap<String, String> = local_secrets(servers, &live);
+    for secret in required {
+        if !secrets.contains_key(&secret.name) {
+            log::warn!(
+                "Restored mcp_config.json has no value for the secret {}",
+                secret.name
+            );
+            let placeholder = secret_placeholder(&secret.name);
+            secrets.insert(secret.name, placeholder);
+        }
+    }
+    if fill_secrets(servers, &secrets).is_err() {
+        return raw();
+    }
+    serde_json::to_string_pretty(&config).unwrap_or_else(|_| raw())
+}
+
+/// Current contents of the versioned files, secrets left out
+pub fn read_snapshot(data_folder: &Path) -> Snapshot {
+    let mut paths = vec![
+        MCP_CONFIG_FILE.to_string(),
+        format!("{PROMPTS_DIR}/{TEMPLATES_FILE}"),
+    ];
+    if let Ok(entries) = fs::read_dir(data_folder.join(ASSISTANTS_DIR)) {
+        paths.extend(entries.flatten().filter_map(|entry| {
+            let id = entry.file_name().to_str()?.to_string();
+            Some(format!("{ASSISTANTS_DIR}/{id}/{ASSISTANT_FILE}"))
+        }));
+    }
+    paths
+        .into_iter()
+        .filter(|path| is_versioned_path(path))
+        .filter_map(|path| {
+            // Through the store, which has writes that are still pending
+            let data = config_store().read(&local_path(data_folder, &path)).ok()?;
+            let data = if path == MCP_CONFIG_FILE {
+                redact_mcp_config(data)
+            } else {
+                data.into_bytes()
+            };
+            Some((path, data))
+        })
+        .collect()
+}
+
+fn open_repo(data_folder: &Path) -> Result<gix::Repository, String> {
+    let path = get_history_path(data_folder);
+    if path.exists() {
+        gix::open(&path).map_err(|e| format!("Failed to open config history: {e}"))
+    } else {
+        gix::init_bare(&path).map_err(|e| format!("Failed to create config history: {e}"))
+    }
+}
+
+fn head_id(repo: &gix::Repository) -> Result<Option<ObjectId>, String> {
+    let head = repo.head().map_err(|e| e.to_string())?;
+    Ok(head.id().map(|id| id.detach()))
+}
+
+fn parse_version(version: &str) -> Result<ObjectId, String> {
+    ObjectId::from_hex(version.trim().as_bytes())
+        .map_err(|_| format!("Invalid config version: {version}"))
+}
+
+enum Node {
+    File(ObjectId),
+    Dir(BTreeMap<String, Node>),
+}
+
+fn write_tree(repo: &gix::Repository, nodes: &BTreeMap<String, Node>) -> Result<ObjectId, String> {
+    let mut entries = Vec::with_capacity(nodes.len());
+    for (name, node) in nodes {
+        let (mode, oid) = match node {
+            Node::File(oid) => (EntryKind::Blob, *oid),
+            Node::Dir(children) => (EntryKind::Tree, write_tree(repo, children)?),
+        };
+        entries.push(Entry {
+            mode: mode.into(),
+            filename: BString::from(name.as_str()),
+            oid,
+        });
+    }
+    // Git orders trees as if their name ended with `/`
+    entries.sort();
+    repo.write_object(&gix::objs::Tree { entries })
+        .map(|id| id.detach())
+        .map_err(|e| e.to_string())
+}
+
+fn write_snapshot(repo: &gix::Repository, snapshot: &Snapshot) -> Result<ObjectId, String> {
+    let mut root = BTreeMap::new();
+    for (path, data) in snapshot {
+        let blob = repo.write_blob(data).map_err(|e| e.to_string())?.detach();
+        let mut parts: Vec<&str> = path.split('/').collect();
+        let file = parts.pop().unwrap_or_default();
+        let mut dir = &mut root;
+        for part in parts {
+            let node = dir
+                .entry(part.to_string())
+                .or_insert_with(|| Node::Dir(BTreeMap::new()));
+            let Node::Dir(children) = node else {
+                return Err(format!("{part} is both a file and a folder"));
+            };
+            dir = children;
+        }
+        dir.insert(file.to_string(), Node::File(blob));
+    }
+    write_tree(repo, &root)
+}
+
+fn read_tree(
+    repo: &gix::Repository,
+    tree: ObjectId,
+    prefix: &str,
+    snapshot: &mut Snapshot,
+) -> Result<(), String> {
+    let object = repo.find_object(tree).map_err(|e| e.to_string())?;
+    let tree = object.try_into_tree().map_err(|e| e.to_string())?;
+    let entries: Vec<_> = tree
+        .decode()
+        .map_err(|e| e.to_string())?
+        .entries
+        .iter()
+        .map(|entry| {
+            (
+                entry.mode,
+                entry.filename.to_str_lossy().into_owned(),
+                entry.oid.to_owned(),
+            )
+        })
+        .collect();
+    for (mode, name, oid) in entries {
+        let path = format!("{prefix}{name}");
+        if mode.is_tree() {
+            read_tree(repo, oid, &format!("{path}/"), snapshot)?;
+        } else if mode.is_blob() {
+            let blob = repo.find_object(oid).map_err(|e| e.to_string())?;
+            snapshot.insert(path, blob.detach().data);
+        }
+    }
+    Ok(())
+}
+
+fn commit_tree(repo: &gix::Repository, commit: ObjectId) -> Result<ObjectId, String> {
+    let commit = repo
+        .find_object(commit)
+        .map_err(|e| e.to_string())?
+        .try_into_commit()
+        .map_err(|e| e.to_string())?;
+    commit
+        .tree_id()
+        .map(|id| id.detach())
+        .map_err(|e| e.to_string())
+}
+
+fn commit_snapshot_files(repo: &gix::Repository, commit: ObjectId) -> Result<Snapshot, String> {
+    let mut snapshot = Snapshot::new();
+    read_tree(repo, commit_tree(repo, commit)?, "", &mut snapshot)?;
+    Ok(snapshot)
+}
+
+/// The versioned files as of `version`
+pub fn read_version(data_folder: &Path, version: &str) -> Result<Snapshot, String> {
+    let repo = open_repo(data_folder)?;
+    commit_snapshot_files(&repo, parse_version(version)?)
+        .map_err(|_| format!("Config version {version} not found"))
+}
+
+/// Commit the versioned files if they changed since the last version. Returns the new
+/// version, `None` when nothing changed.
+pub fn commit_snapshot(data_folder: &Path, message: &str) -> Result<Option<String>, String> {
+    let _guard = HISTORY_LOCK
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let repo = open_repo(data_folder)?;
+    let tree = write_snapshot(&repo, &read_snapshot(data_folder))?;
+    let parent = head_id(&repo)?;
+    if let Some(parent) = parent {
+        if commit_tree(&repo, parent)? == tree {
+            return Ok(None);
+        }
+    }
+
+    let signature = gix::actor::Signature {
+        name: HISTORY_AUTHOR_NAME.into(),
+        email: HISTORY_AUTHOR_EMAIL.into(),
+        time: gix::date::Time::now_local_or_utc(),
+    };
+    let id = repo
+        .commit_as(&signature, &signature, "HEAD", message, tree, parent)
+        .map_err(|e| format!("Failed to record config change: {e}"))?;
+    Ok(Some(id.to_string()))
+}
+
+/// Record a change of the versioned files. History is best effort: a failure is logged and
+/// never fails the edit itself.
+pub fn record_config_change(data_folder: &Path, message: &str) {
+    match commit_snapshot(data_folder, message) {
+        Ok(Some(version)) => log::debug!("Recorded config version {version}: {message}"),
+        Ok(None) => {}
+        Err(e) => log::warn!("Failed to record config change '{message}': {e}"),
+    }
+}
+
+/// Snapshot the versioned files at startup, so edits made outside Jan can be rolled back too
+pub fn load_config_history<R: Runtime>(app: &AppHandle<R>) {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    tauri::async_runtime::spawn_blocking(move || {
+        record_config_change(&data_folder, EXTERNAL_CHANGES_MESSAGE);
+    });
+}
+
+fn changed_paths(old: &Snapshot, new: &Snapshot) -> Vec<String> {
+    let mut paths: Vec<String> = new
+        .iter()
+        .filter(|(path, data)| old.get(*path) != Some(*data))
+        .map(|(path, _)| path.clone())
+        .chain(old.keys().filter(|path| !new.contains_key(*path)).cloned())
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Versions, newest first, optionally only those that changed `path`
+pub fn list_history(
+    data_folder: &Path,
+    path: Option<&str>,
+    limit: Option<usize>,
+) -> Result<Vec<ConfigVersion>, String> {
+    let limit = limit.unwrap_or(DEFAULT_HISTORY_LIMIT);
+    if !get_history_path(data_folder).exists() {
+        return Ok(Vec::new());
+    }
+    let repo = open_repo(data_folder)?;
+    let Some(head) = head_id(&repo)? else {
+        return Ok(Vec::new());
+    };
+
+    let mut versions = Vec::new();
+    let mut id = Some(head);
+    let mut files = commit_snapshot_files(&repo, head)?;
+    while let Some(current) = id {
+        if versions.len() >= limit {
+            break;
+        }
+        let commit = repo
+            .find_object(current)
+            .map_err(|e| e.to_string())?
+            .try_into_commit()
+            .map_err(|e| e.to_string())?;
+        // History is linear: every version has at most one parent
+        let parent = commit.parent_ids().next().map(|id| id.detach());
+        let parent_files = match parent {
+            Some(parent) => commit_snapshot_files(&repo, parent)?,
+            None => Snapshot::new(),
+        };
+        let paths = changed_paths(&parent_files, &files);
+        let matches = match path {
+            Some(path) => paths.iter().any(|changed| changed == path),
+            None => true,
+        };
+        if matches {
+            versions.push(ConfigVersion {
+                id: current.to_string(),
+                message: commit
+                    .message_raw_sloppy()
+                    .to_str_lossy()
+                    .trim()
+                    .to_string(),
+                timestamp: commit.time().map(|time| time.seconds).unwrap_or_default(),
+                paths,
+            });
+        }
+        id = parent;
+        files = parent_files;
+    }
+    Ok(versions)
+}
+
+/// Put the versioned files back as they were in `version`: only `path` when given,
+/// otherwise all of them, removing those that didn't exist then. The restore is recorded
+/// as a new version. Returns the restored paths.
+pub fn restore_version(
+    data_folder: &Path,
+    version: &str,
+    path: Option<&str>,
+) -> Result<Vec<String>, String> {
+    if let Some(path) = path {
+        if !is_versioned_path(path) {
+            return Err(format!("{path} is not a versioned config file"));
+        }
+    }
+    let old = read_version(data_folder, version)?;
+    let current = read_snapshot(data_folder);
+    let targets: Vec<String> = match path {
+        Some(path) => vec![path.to_string()],
+        None => changed_paths(&current, &old),
+    };
+
+    let mut restored = Vec::new();
+    for target in targets {
+        if current.get(&target) == old.get(&target) {
+            continue;
+        }
+        let file = local_path(data_folder, &target);
+        match old.get(&target) {
+            Some(data) => {
+                if let Some(parent) = file.parent() {
+                    fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                }
+                if target == MCP_CONFIG_FILE {
+                    config_store()
+                        .update(&file, |live| Ok(restore_mcp_secrets(data, live.as_deref())))?;
+                } else {
+                    config_store().write(&file, data)?;
+                }
+            }
+            None => {
+                fs::remove_file(&file).map_err(|e| e.to_string())?;
+                // An assistant is its folder
+                if target.starts_with(&format!("{ASSISTANTS_DIR}/")) {
+                    if let Some(parent) = file.parent() {
+                        let _ = fs::remove_dir_all(parent);
+                    }
+                }
+            }
+        }
+        restored.push(target);
+    }
+
+    let short = version.get(..7).unwrap_or(version);
+    let message = match path {
+        Some(path) => format!("Restore {path} to {short}"),
+        None => format!("Restore config to {short}"),
+    };
+    commit_snapshot(data_folder, &message)?;
+    Ok(restored)
+}
@@ -0,0 +1,26 @@
+/*!
+   Config History
+
+   Every change to the prompt templates (`prompts/templates.json`), the assistant definitions
+   (`assistants/<id>/assistant.json`) and `mcp_config.json` is committed to a git repository
+   in the data folder (`config_history.git`), so a broken edit can be rolled back.
+
+   - Each commit is a snapshot of all versioned files; edits that leave them unchanged record
+     nothing.
+   - A snapshot is also taken at startup, capturing edits made while Jan was not running.
+   - Secret env vars and headers of `mcp_config.json` are committed as `{{secret:NAME}}`
+     placeholders, like in a shared setup; restoring fills them from the live config.
+   - Restoring writes the files of an older version back and records that as a new version,
+     so a restore can itself be undone.
+
+   The repository is bare and written with gix, so no git installation is needed; it can
+   still be inspected with `git --git-dir config_history.git log`.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
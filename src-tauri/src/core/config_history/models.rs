@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// One recorded change of the versioned config files
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigVersion {
+    /// Commit id
+    pub id: String,
+    pub message: String,
+    /// Seconds since the Unix epoch
+    pub timestamp: i64,
+    /// Files added, changed or removed by this version, relative to the data folder
+    pub paths: Vec<String>,
+}
@@ -0,0 +1,112 @@
+use std::fs;
+use std::path::PathBuf;
+
+use super::helpers::{
+    commit_snapshot, is_versioned_path, list_history, read_version, restore_version,
+};
+
+fn data_folder() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("jan-config-history-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn write(dir: &std::path::Path, path: &str, data: &str) {
+    let path = dir.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, data).unwrap();
+}
+
+#[test]
+fn test_is_versioned_path() {
+    assert!(is_versioned_path("mcp_config.json"));
+    assert!(is_versioned_path("prompts/templates.json"));
+    assert!(is_versioned_path("assistants/jan/assistant.json"));
+    assert!(!is_versioned_path("assistants/../assistant.json"));
+    assert!(!is_versioned_path("settings.json"));
+    assert!(!is_versioned_path("assistants/jan/other.json"));
+}
+
+#[test]
+fn test_history_and_restore() {
+    let dir = data_folder();
+    write(&dir, "mcp_config.json", r#"{"mcpServers": {}}"#);
+    write(&dir, "assistants/jan/assistant.json", r#"{"id": "jan"}"#);
+    let first = commit_snapshot(&dir, "First").unwrap().unwrap();
+    // Nothing changed, nothing recorded
+    assert_eq!(commit_snapshot(&dir, "Again").unwrap(), None);
+
+    write(&dir, "mcp_config.json", r#"{"mcpServers": {"broken": "#);
+    write(
+        &dir,
+        "assistants/coder/assistant.json",
+        r#"{"id": "coder"}"#,
+    );
+    commit_snapshot(&dir, "Second").unwrap().unwrap();
+
+    let history = list_history(&dir, None, None).unwrap();
+    let messages: Vec<_> = history.iter().map(|v| v.message.as_str()).collect();
+    assert_eq!(messages, vec!["Second", "First"]);
+    assert_eq!(
+        history[0].paths,
+        vec!["assistants/coder/assistant.json", "mcp_config.json"]
+    );
+    let assistant_history =
+        list_history(&dir, Some("assistants/jan/assistant.json"), None).unwrap();
+    assert_eq!(assistant_history.len(), 1);
+    assert_eq!(assistant_history[0].id, first);
+
+    let old = read_version(&dir, &first).unwrap();
+    assert_eq!(old["mcp_config.json"], br#"{"mcpServers": {}}"#.to_vec());
+
+    // Restoring one file leaves the others as they are
+    let restored = restore_version(&dir, &first, Some("mcp_config.json")).unwrap();
+    assert_eq!(restored, vec!["mcp_config.json"]);
+    assert_eq!(
+        fs::read_to_string(dir.join("mcp_config.json")).unwrap(),
+        r#"{"mcpServers": {}}"#
+    );
+    assert!(dir.join("assistants/coder/assistant.json").exists());
+
+    // Restoring everything removes what didn't exist then
+    restore_version(&dir, &first, None).unwrap();
+    assert!(!dir.join("assistants/coder").exists());
+    let history = list_history(&dir, None, Some(2)).unwrap();
+    assert_eq!(
+        history[0].message,
+        format!("Restore config to {}", &first[..7])
+    );
+    assert_eq!(history.len(), 2);
+
+    assert!(restore_version(&dir, &first, Some("settings.json")).is_err());
+    assert!(read_version(&dir, "not-a-version").is_err());
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_secrets_stay_out_of_history() {
+    let dir = data_folder();
+    let config = |token: &str, mode: &str| {
+        format!(
+            r#"{{"mcpServers": {{"github": {{"command": "gh", "env": {{"GITHUB_TOKEN": "{token}", "MODE": "{mode}"}}}}}}}}"#
+        )
+    };
+    write(&dir, "mcp_config.json", &config("abc123", "read"));
+    let first = commit_snapshot(&dir, "First").unwrap().unwrap();
+    let committed =
+        String::from_utf8(read_version(&dir, &first).unwrap()["mcp_config.json"].clone()).unwrap();
+    assert!(!committed.contains("abc123"));
+    assert!(committed.contains("{{secret:"));
+
+    // Restoring keeps the live secret
+    write(&dir, "mcp_config.json", &config("def456", "write"));
+    commit_snapshot(&dir, "Second").unwrap().unwrap();
+    restore_version(&dir, &first, Some("mcp_config.json")).unwrap();
+    let restored: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(dir.join("mcp_config.json")).unwrap()).unwrap();
+    assert_eq!(
+        restored["mcpServers"]["github"]["env"],
+        serde_json::json!({ "GITHUB_TOKEN": "def456", "MODE": "read" })
+    );
+    let _ = fs::remove_dir_all(dir);
+}
@@ -0,0 +1,2 @@
+// How long debounced writes wait for further changes before they are persisted
+pub const CONFIG_FLUSH_DEBOUNCE_MS: u64 = 500;
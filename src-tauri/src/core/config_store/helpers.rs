@@ -0,0 +1,67 @@
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use super::ConfigStore;
+
+// Shared by every writer in the process, so the per-file locks actually exclude each other
+static CONFIG_STORE: OnceLock<ConfigStore> = OnceLock::new();
+
+pub fn config_store() -> &'static ConfigStore {
+    CONFIG_STORE.get_or_init(ConfigStore::default)
+}
+
+/// Run config file IO on the blocking thread pool, so async callers don't stall the runtime
+/// on a slow disk
+pub async fn blocking_io<T, F>(io: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce() -> T + Send + 'static,
+{
+    tokio::task::spawn_blocking(io)
+        .await
+        .map_err(|e| format!("Config IO task failed: {e}"))
+}
+
+/// Replace `path` with `data` through a synced temporary file in the same folder, so the
+/// rename is atomic and readers see either the old or the new document
+pub fn write_atomic(path: &Path, data: &[u8]) -> Result<(), String> {
+    let parent = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .ok_or_else(|| format!("Invalid config path: {}", path.display()))?;
+    let tmp = parent.join(format!(".{file_name}.{}.tmp", Uuid::new_v4().simple()));
+
+    let result = File::create(&tmp)
+        .and_then(|mut file| {
+            file.write_all(data)?;
+            file.sync_all()
+        })
+        .and_then(|_| fs::rename(&tmp, path));
+    result.map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        format!("Failed to write {}: {e}", path.display())
+    })
+}
+
+/// Serialize `value` as pretty JSON and write it through the store right away
+pub fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let data = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    config_store().write(path, data)
+}
+
+/// Serialize `value` as pretty JSON and persist it with the next debounced batch
+pub fn write_json_debounced<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let data = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    config_store().write_debounced(path, data);
+    Ok(())
+}
@@ -0,0 +1,167 @@
+/*!
+   Config Store Module
+
+   Single path for persisting configuration files such as `mcp_config.json`, `settings.json`
+   and the per-feature settings documents.
+
+   - Writes go to a temporary file in the same folder, are synced and then renamed over the
+     target, so a crash never leaves a truncated document behind.
+   - A per-file lock serializes read-modify-write cycles within the process.
+   - Async callers use the `*_async` variants, which run the file IO on the blocking thread
+     pool instead of stalling the runtime on a slow disk.
+   - Debounced writes are kept in memory and persisted as one batch shortly after the first
+     of them; reads through the store see them immediately. They are flushed on exit.
+*/
+
+pub mod constants;
+pub mod helpers;
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use constants::CONFIG_FLUSH_DEBOUNCE_MS;
+use helpers::{blocking_io, write_atomic};
+
+#[derive(Default)]
+pub struct ConfigStore {
+    locks: Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>,
+    pending: Mutex<HashMap<PathBuf, Vec<u8>>>,
+    flush_scheduled: AtomicBool,
+}
+
+impl ConfigStore {
+    /// Lock guarding the file at `path`
+    fn file_lock(&self, path: &Path) -> Arc<Mutex<()>> {
+        let mut locks = self.locks.lock().unwrap_or_else(|e| e.into_inner());
+        locks.entry(path.to_path_buf()).or_default().clone()
+    }
+
+    fn take_pending(&self, path: &Path) -> Option<Vec<u8>> {
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(path)
+    }
+
+    /// Current contents of `path`, including a write that is still pending
+    pub fn read(&self, path: &Path) -> std::io::Result<String> {
+        let pending = self
+            .pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(path)
+            .cloned();
+        match pending {
+            Some(data) => String::from_utf8(data)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+            None => std::fs::read_to_string(path),
+        }
+    }
+
+    /// Persist `data` to `path` now, replacing any pending write
+    pub fn write(&self, path: &Path, data: impl AsRef<[u8]>) -> Result<(), String> {
+        let lock = self.file_lock(path);
+        let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+        self.take_pending(path);
+        write_atomic(path, data.as_ref())
+    }
+
+    /// Read-modify-write `path` under its lock. `update` gets the current contents, `None` when
+    /// the file does not exist, and returns the new ones.
+    pub fn update<F>(&self, path: &Path, update: F) -> Result<(), String>
+    where
+        F: FnOnce(Option<String>) -> Result<String, String>,
+    {
+        let lock = self.file_lock(path);
+        let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+        let current = match self.take_pending(path) {
+            Some(data) => Some(String::from_utf8(data).map_err(|e| e.to_string())?),
+            None if path.exists() => {
+                Some(std::fs::read_to_string(path).map_err(|e| e.to_string())?)
+            }
+            None => None,
+        };
+        write_atomic(path, update(current)?.as_bytes())
+    }
+
+    /// `read` without blocking the async runtime
+    pub async fn read_async(&'static self, path: &Path) -> std::io::Result<String> {
+        let path = path.to_path_buf();
+        blocking_io(move || self.read(&path))
+            .await
+            .map_err(std::io::Error::other)?
+    }
+
+    /// `write` without blocking the async runtime
+    pub async fn write_async(
+        &'static self,
+        path: &Path,
+        data: impl Into<Vec<u8>>,
+    ) -> Result<(), String> {
+        let path = path.to_path_buf();
+        let data = data.into();
+        blocking_io(move || self.write(&path, data)).await?
+    }
+
+    /// `update` without blocking the async runtime. Concurrent updates of one file are still
+    /// serialized by its lock, which is taken on the blocking thread.
+    pub async fn update_async<F>(&'static self, path: &Path, update: F) -> Result<(), String>
+    where
+        F: FnOnce(Option<String>) -> Result<String, String> + Send + 'static,
+    {
+        let path = path.to_path_buf();
+        blocking_io(move || self.update(&path, update)).await?
+    }
+
+    /// Keep `data` as the contents of `path` and persist it with the next batch
+    pub fn write_debounced(&'static self, path: &Path, data: impl Into<Vec<u8>>) {
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(path.to_path_buf(), data.into());
+        if self.flush_scheduled.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(CONFIG_FLUSH_DEBOUNCE_MS)).await;
+            if let Err(e) = self.flush() {
+                log::error!("Failed to persist config files: {e}");
+            }
+        });
+    }
+
+    /// Persist all pending writes
+    pub fn flush(&self) -> Result<(), String> {
+        self.flush_scheduled.store(false, Ordering::Release);
+        let pending: Vec<PathBuf> = self
+            .pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .keys()
+            .cloned()
+            .collect();
+        let mut errors = Vec::new();
+        for path in pending {
+            let lock = self.file_lock(&path);
+            let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+            // A direct write may have persisted newer contents in the meantime
+            let Some(data) = self.take_pending(&path) else {
+                continue;
+            };
+            if let Err(e) = write_atomic(&path, &data) {
+                errors.push(format!("{}: {e}", path.display()));
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.join("; "))
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::helpers::write_atomic;
+use super::ConfigStore;
+
+fn temp_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("jan-config-store-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn file_names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_write_atomic_replaces_without_leftovers() {
+    let dir = temp_dir();
+    let path = dir.join("nested").join("settings.json");
+    write_atomic(&path, b"{\"a\":1}").unwrap();
+    write_atomic(&path, b"{\"a\":2}").unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "{\"a\":2}");
+    assert_eq!(file_names(path.parent().unwrap()), ["settings.json"]);
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_concurrent_updates_are_serialized() {
+    let dir = temp_dir();
+    let path = dir.join("counter.json");
+    let store = Arc::new(ConfigStore::default());
+    store.write(&path, "0").unwrap();
+
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let store = store.clone();
+            let path = path.clone();
+            std::thread::spawn(move || {
+                for _ in 0..10 {
+                    store
+                        .update(&path, |current| {
+                            let n: u32 = current.unwrap().parse().map_err(|_| "not a number")?;
+                            Ok((n + 1).to_string())
+                        })
+                        .unwrap();
+                }
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    assert_eq!(fs::read_to_string(&path).unwrap(), "80");
+
+    // A failed update leaves the file alone
+    assert!(store.update(&path, |_| Err("nope".to_string())).is_err());
+    assert_eq!(fs::read_to_string(&path).unwrap(), "80");
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn test_debounced_writes_are_batched() {
+    let dir = temp_dir();
+    let first = dir.join("first.json");
+    let second = dir.join("second.json");
+    // A store of its own, so flushes scheduled by other tests cannot interfere
+    let store: &'static ConfigStore = Box::leak(Box::default());
+
+    store.write_debounced(&first, "1");
+    store.write_debounced(&first, "2");
+    store.write_debounced(&second, "3");
+    // Readers see pending contents before they hit the disk
+    assert!(!first.exists());
+    assert_eq!(store.read(&first).unwrap(), "2");
+
+    // A direct write supersedes the pending one
+    store.write(&second, "4").unwrap();
+    store.flush().unwrap();
+    assert_eq!(fs::read_to_string(&first).unwrap(), "2");
+    assert_eq!(fs::read_to_string(&second).unwrap(), "4");
+
+    store.write_debounced(&first, "5");
+    tokio::time::sleep(std::time::Duration::from_millis(
+        super::constants::CONFIG_FLUSH_DEBOUNCE_MS * 3,
+    ))
+    .await;
+    assert_eq!(fs::read_to_string(&first).unwrap(), "5");
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_concurrent_async_updates_are_serialized() {
+    let dir = temp_dir();
+    let path = dir.join("counter.json");
+    let store: &'static ConfigStore = Box::leak(Box::default());
+    store.write_async(&path, "0").await.unwrap();
+
+    let tasks: Vec<_> = (0..8)
+        .map(|_| {
+            let path = path.clone();
+            tokio::spawn(async move {
+                for _ in 0..10 {
+                    store
+                        .update_async(&path, |current| {
+                            let n: u32 = current.unwrap().parse().map_err(|_| "not a number")?;
+                            Ok((n + 1).to_string())
+                        })
+                        .await
+                        .unwrap();
+                }
+            })
+        })
+        .collect();
+    // Sync writers share the per-file lock with async ones
+    store
+        .update(&path, |current| {
+            let n: u32 = current.unwrap().parse().map_err(|_| "not a number")?;
+            Ok((n + 100).to_string())
+        })
+        .unwrap();
+    for task in tasks {
+        task.await.unwrap();
+    }
+    assert_eq!(store.read_async(&path).await.unwrap(), "180");
+
+    // Pending debounced contents are visible to async readers too
+    store.write_debounced(&path, "pending");
+    assert_eq!(store.read_async(&path).await.unwrap(), "pending");
+    store.flush().unwrap();
+    assert!(store.read_async(&dir.join("missing.json")).await.is_err());
+    let _ = fs::remove_dir_all(dir);
+}
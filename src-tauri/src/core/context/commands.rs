@@ -0,0 +1,313 @@
+use serde_json::{json, Value};
+use tauri::{AppHandle, Runtime};
+
+use super::constants::{DEFAULT_RESPONSE_RESERVE_TOKENS, SUMMARY_BUDGET_RATIO};
+use super::helpers::{
+    build_summary_request, check_context, drop_attachments, estimate_message_tokens,
+    insert_after_system, message_text, read_cached_summary, select_messages, take_oldest_messages,
+    truncate_to_tokens, write_cached_summary,
+};
+use super::models::{
+    AssembledContext, ChatMessage, ContextBudgetRequest, ContextCandidate, ContextCheck,
+    ContextRequest, ContextSummary, RemediationOption,
+};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::bookmarks::helpers::{delete_pin, pinned_message_ids, save_pin, set_pinned_flag};
+use crate::core::citations::constants::SOURCES_BUDGET_RATIO;
+use crate::core::citations::helpers::build_sources_message;
+use crate::core::inference::helpers::{
+    chat_completion, completion_text, resolve_model_endpoint, DEFAULT_COMPLETION_TIMEOUT,
+};
+use crate::core::language::helpers::{resolve_thread_language, with_response_language};
+use crate::core::scheduler::helpers::acquire_for_endpoint;
+use crate::core::scheduler::models::GenerationPriority;
+use crate::core::threads::commands::list_messages;
+
+/// Summarize the overflowing history, reusing the cached summary when it already covers it
+/// and extending it incrementally when more history has fallen out of the window.
+async fn summarize_overflow<R: Runtime>(
+    app: &AppHandle<R>,
+    thread_id: &str,
+    model: &str,
+    overflow: &[&ContextCandidate],
+) -> Result<String, String> {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let newest = overflow
+        .last()
+        .ok_or_else(|| "Nothing to summarize".to_string())?;
+    let cached = read_cached_summary(&data_folder, thread_id);
+
+    if let Some(cached) = &cached {
+        if cached.last_message_id == newest.id {
+            return Ok(cached.summary.clone());
+        }
+    }
+
+    // Only summarize messages newer than the cached summary, if it is still a prefix
+    let (previous, pending): (Option<&str>, Vec<&ChatMessage>) =
+        match cached.as_ref().and_then(|c| {
+            overflow
+                .iter()
+                .position(|m| m.id == c.last_message_id)
+                .map(|pos| (c, pos))
+        }) {
+            Some((cached, pos)) => (
+                Some(cached.summary.as_str()),
+                overflow[pos + 1..].iter().map(|m| &m.message).collect(),
+            ),
+            None => (None, overflow.iter().map(|m| &m.message).collect()),
+        };
+
+    let endpoint = resolve_model_endpoint(app, model).await?;
+    let request = build_summary_request(&pending, previous);
+    // Assembling context is on the send path, so the user is waiting for it
+    let permit = acquire_for_endpoint(
+        app,
+        &endpoint,
+        &format!("summary:{thread_id}"),
+        GenerationPriority::Interactive,
+    )
+    .await?;
+    let response = chat_completion(
+        &endpoint,
+        request,
+        Some(thread_id),
+        DEFAULT_COMPLETION_TIMEOUT,
+    )
+    .await?;
+    drop(permit);
+    let summary = completion_text(&response)
+        .filter(|s| !s.trim().is_empty())
+        .ok_or_else(|| "Summarizer returned an empty response".to_string())?;
+
+    let record = ContextSummary {
+        last_message_id: newest.id.clone(),
+        message_count: overflow.len(),
+        summary: summary.clone(),
+        model: model.to_string(),
+        created_at: chrono::Utc::now().timestamp_millis(),
+    };
+    if let Err(e) = write_cached_summary(&data_folder, thread_id, &record) {
+        log::warn!("Failed to cache context summary for thread {thread_id}: {e}");
+    }
+    Ok(summary)
+}
+
+/// Assembles the prompt for a thread so it fits the model context window.
+/// Pinned and recent messages are kept verbatim; older history is summarized with
+/// `summarizer_model` when provided, otherwise dropped.
+#[tauri::command]
+pub async fn assemble_thread_context<R: Runtime>(
+    app_handle: AppHandle<R>,
+    request: ContextRequest,
+) -> Result<AssembledContext, String> {
+    let reserve = request
+        .reserve_tokens
+        .unwrap_or(DEFAULT_RESPONSE_RESERVE_TOKENS);
+    let mut budget = request.context_size.saturating_sub(reserve);
+
+    let messages = list_messages(app_handle.clone(), request.thread_id.clone()).await?;
+    let pinned_ids = pinned_message_ids(&app_handle, &request.thread_id).await;
+    let candidates: Vec<ContextCandidate> = messages
+        .iter()
+        .filter_map(ContextCandidate::from_thread_message)
+        .map(|mut candidate| {
+            candidate.pinned |= pinned_ids.contains(&candidate.id);
+            candidate
+        })
+        .collect();
+
+    let latest_user_message = candidates
+        .iter()
+        .rev()
+        .find(|candidate| candidate.message.role == "user")
+        .map(|candidate| candidate.message.content.as_str());
+    let response_language = resolve_thread_language(
+        &get_jan_data_folder_path(app_handle.clone()),
+        &request.thread_id,
+        latest_user_message,
+    )
+    .await;
+    let system_message = with_response_language(
+        request.system_prompt.as_deref(),
+        response_language.as_deref(),
+    )
+    .map(|content| ChatMessage {
+        role: "system".to_string(),
+        content,
+    });
+    if let Some(system) = &system_message {
+        budget = budget.saturating_sub(estimate_message_tokens(&system.content));
+    }
+
+    let sources_budget = (budget as f64 * SOURCES_BUDGET_RATIO) as usize;
+    let sources = build_sources_message(&request.sources, sources_budget);
+    if let Some((_, tokens, _)) = &sources {
+        budget = budget.saturating_sub(*tokens);
+    }
+
+    // Reserve room for the summary up front when summarization is possible
+    let summary_budget = if request.summarizer_model.is_some() {
+        (budget as f64 * SUMMARY_BUDGET_RATIO) as usize
+    } else {
+        0
+    };
+    let mut selection = select_messages(&candidates, budget.saturating_sub(summary_budget));
+
+    let mut summary = None;
+    let mut summarized_message_ids = Vec::new();
+    if let (Some(model), false) = (&request.summarizer_model, selection.overflow.is_empty()) {
+        let overflow: Vec<&ContextCandidate> =
+            selection.overflow.iter().map(|&i| &candidates[i]).collect();
+        match summarize_overflow(&app_handle, &request.thread_id, model, &overflow).await {
+            Ok(text) => {
+                summary = Some(truncate_to_tokens(&text, summary_budget));
+                summarized_message_ids = overflow.iter().map(|c| c.id.clone()).collect();
+            }
+            Err(e) => {
+                log::warn!(
+                    "Failed to summarize history for thread {}: {e}",
+                    request.thread_id
+                );
+                // Give the unused summary budget back to verbatim history
+                selection = select_messages(&candidates, budget);
+            }
+        }
+    }
+
+    let mut assembled = Vec::new();
+    if let Some(system) = system_message {
+        assembled.push(system);
+    }
+    let mut citations = Vec::new();
+    if let Some((content, _, included)) = sources {
+        assembled.push(ChatMessage {
+            role: "system".to_string(),
+            content,
+        });
+        citations = included;
+    }
+    if let Some(summary) = &summary {
+        assembled.push(ChatMessage {
+            role: "system".to_string(),
+            content: format!("Summary of the earlier conversation:\n{summary}"),
+        });
+    }
+    for &i in &selection.kept {
+        assembled.push(candidates[i].message.clone());
+    }
+
+    let estimated_tokens: usize = assembled
+        .iter()
+        .map(|m| estimate_message_tokens(&m.content))
+        .sum();
+
+    Ok(AssembledContext {
+        messages: assembled,
+        included_message_ids: selection
+            .kept
+            .iter()
+            .map(|&i| candidates[i].id.clone())
+            .collect(),
+        pinned_message_ids: candidates
+            .iter()
+            .filter(|c| c.pinned)
+            .map(|c| c.id.clone())
+            .collect(),
+        dropped_message_ids: if summary.is_some() {
+            Vec::new()
+        } else {
+            selection
+                .overflow
+                .iter()
+                .map(|&i| candidates[i].id.clone())
+                .collect()
+        },
+        summarized_message_ids,
+        summary,
+        citations,
+        response_language,
+        estimated_tokens,
+    })
+}
+
+/// Marks a message as pinned (always included in the assembled context) or unpins it.
+#[tauri::command]
+pub async fn set_message_pinned<R: Runtime>(
+    app_handle: AppHandle<R>,
+    thread_id: String,
+    message_id: String,
+    pinned: bool,
+) -> Result<serde_json::Value, String> {
+    let message = set_pinned_flag(&app_handle, &thread_id, &message_id, pinned).await?;
+    if pinned {
+        save_pin(&app_handle, &thread_id, &message_id, None).await?;
+    } else {
+        delete_pin(&app_handle, &thread_id, &message_id).await?;
+    }
+    Ok(message)
+}
+
+/// Estimate a chat completion request against the model context window before sending it.
+/// An overflow lists the remediations that bring it back within budget.
+#[tauri::command]
+pub fn check_context_budget(request: ContextBudgetRequest) -> ContextCheck {
+    check_context(&request)
+}
+
+/// Apply a remediation returned by `check_context_budget` and return the new request body.
+/// Summaries are written by `summarizer_model`, or the request's own model.
+#[tauri::command]
+pub async fn apply_context_remediation<R: Runtime>(
+    app_handle: AppHandle<R>,
+    mut body: Value,
+    option: RemediationOption,
+    summarizer_model: Option<String>,
+) -> Result<Value, String> {
+    match option {
+        RemediationOption::TruncateOldest { message_count, .. } => {
+            take_oldest_messages(&mut body, message_count);
+        }
+        RemediationOption::DropAttachments { .. } => drop_attachments(&mut body),
+        RemediationOption::SwitchModel { model_id, .. } => body["model"] = Value::String(model_id),
+        RemediationOption::Summarize { message_count, .. } => {
+            let model = summarizer_model
+                .or_else(|| body.get("model").and_then(Value::as_str).map(String::from))
+                .ok_or("No model to summarize with")?;
+            let taken: Vec<ChatMessage> = take_oldest_messages(&mut body, message_count)
+                .iter()
+                .map(|m| ChatMessage {
+                    role: m
+                        .get("role")
+                        .and_then(Value::as_str)
+                        .unwrap_or("user")
+                        .to_string(),
+                    content: message_text(m),
+                })
+                .collect();
+            let endpoint = resolve_model_endpoint(&app_handle, &model).await?;
+            let request = build_summary_request(&taken.iter().collect::<Vec<_>>(), None);
+            let permit = acquire_for_endpoint(
+                &app_handle,
+                &endpoint,
+                "context-remediation",
+                GenerationPriority::Interactive,
+            )
+            .await?;
+            let response =
+                chat_completion(&endpoint, request, None, DEFAULT_COMPLETION_TIMEOUT).await?;
+            drop(permit);
+            let summary = completion_text(&response)
+                .filter(|s| !s.trim().is_empty())
+                .ok_or("Summarizer returned an empty response")?;
+            insert_after_system(
+                &mut body,
+                json!({
+                    "role": "system",
+                    "content": format!("Summary of the earlier conversation:\n{summary}"),
+                }),
+            );
+        }
+    }
+    Ok(body)
+}
@@ -0,0 +1,18 @@
+// Context window constants
+pub const CONTEXT_SUMMARY_FILE: &str = "context_summary.json";
+
+/// Rough characters-per-token ratio used for estimation
+pub const CHARS_PER_TOKEN: usize = 4;
+/// Per-message framing overhead (role, separators) in tokens
+pub const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+/// Tokens reserved for the model response when the caller does not specify it
+pub const DEFAULT_RESPONSE_RESERVE_TOKENS: usize = 1024;
+/// Maximum share of the budget the history summary may take
+pub const SUMMARY_BUDGET_RATIO: f64 = 0.25;
+
+pub const SUMMARY_PROMPT: &str = "Summarize the following conversation so it can replace the original messages as context for continuing the conversation. Keep facts, decisions, names, numbers and open questions. Be concise and write in the language of the conversation.";
+
+/// Estimated tokens of an image attachment
+pub const IMAGE_ATTACHMENT_TOKENS: usize = 768;
+/// Placeholder left where attachments were dropped
+pub const ATTACHMENT_REMOVED_TEXT: &str = "[Attachment removed to fit the context window]";
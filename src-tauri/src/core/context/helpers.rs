@@ -0,0 +1,339 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+use super::constants::{
+    ATTACHMENT_REMOVED_TEXT, CHARS_PER_TOKEN, CONTEXT_SUMMARY_FILE,
+    DEFAULT_RESPONSE_RESERVE_TOKENS, IMAGE_ATTACHMENT_TOKENS, MESSAGE_OVERHEAD_TOKENS,
+    SUMMARY_BUDGET_RATIO, SUMMARY_PROMPT,
+};
+use super::models::{
+    ChatMessage, ContextBudgetRequest, ContextCandidate, ContextCheck, ContextOverflow,
+    ContextSummary, RemediationOption, Selection, TokenBreakdown,
+};
+use crate::core::threads::utils::get_thread_dir;
+
+/// Estimate the token count of a piece of text
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+pub fn estimate_message_tokens(content: &str) -> usize {
+    estimate_tokens(content) + MESSAGE_OVERHEAD_TOKENS
+}
+
+/// Concatenate the text parts of a thread message.
+/// Supports both `{"type":"text","text":"..."}` and `{"type":"text","text":{"value":"..."}}` parts,
+/// as well as plain string content.
+pub fn message_text(message: &Value) -> String {
+    match message.get("content") {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Array(parts)) => parts
+            .iter()
+            .filter(|p| p.get("type").and_then(|t| t.as_str()) == Some("text"))
+            .filter_map(|p| match p.get("text") {
+                Some(Value::String(text)) => Some(text.clone()),
+                Some(Value::Object(obj)) => obj
+                    .get("value")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
+/// Select which candidates fit into `budget` tokens.
+/// Pinned messages and the latest message are always kept; the rest is filled newest-first.
+pub fn select_messages(candidates: &[ContextCandidate], budget: usize) -> Selection {
+    let mut keep = vec![false; candidates.len()];
+    let mut used = 0usize;
+
+    if let Some(last) = candidates.len().checked_sub(1) {
+        keep[last] = true;
+        used += candidates[last].tokens;
+    }
+    for (i, candidate) in candidates.iter().enumerate() {
+        if candidate.pinned && !keep[i] {
+            keep[i] = true;
+            used += candidate.tokens;
+        }
+    }
+
+    for i in (0..candidates.len()).rev() {
+        if keep[i] {
+            continue;
+        }
+        if used + candidates[i].tokens > budget {
+            // Stop at the first message that does not fit so the kept history stays contiguous
+            break;
+        }
+        keep[i] = true;
+        used += candidates[i].tokens;
+    }
+
+    let mut selection = Selection::default();
+    for (i, kept) in keep.into_iter().enumerate() {
+        if kept {
+            selection.kept.push(i);
+        } else {
+            selection.overflow.push(i);
+        }
+    }
+    selection
+}
+
+/// Build the summarization request for the overflowing messages
+pub fn build_summary_request(messages: &[&ChatMessage], previous: Option<&str>) -> Value {
+    let mut transcript = String::new();
+    if let Some(previous) = previous {
+        transcript.push_str(&format!("[Earlier summary]\n{previous}\n\n"));
+    }
+    for message in messages {
+        transcript.push_str(&format!("{}: {}\n\n", message.role, message.content));
+    }
+    json!({
+        "messages": [
+            { "role": "system", "content": SUMMARY_PROMPT },
+            { "role": "user", "content": transcript }
+        ],
+        "temperature": 0.2
+    })
+}
+
+pub fn get_summary_path(data_folder: &Path, thread_id: &str) -> PathBuf {
+    get_thread_dir(data_folder, thread_id).join(CONTEXT_SUMMARY_FILE)
+}
+
+pub fn read_cached_summary(data_folder: &Path, thread_id: &str) -> Option<ContextSummary> {
+    let data = fs::read_to_string(get_summary_path(data_folder, thread_id)).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+pub fn write_cached_summary(
+    data_folder: &Path,
+    thread_id: &str,
+    summary: &ContextSummary,
+) -> Result<(), String> {
+    let path = get_summary_path(data_folder, thread_id);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let data = serde_json::to_string_pretty(summary).map_err(|e| e.to_string())?;
+    fs::write(path, data).map_err(|e| e.to_string())
+}
+
+/// Truncate text to roughly `max_tokens` estimated tokens
+pub fn truncate_to_tokens(text: &str, max_tokens: usize) -> String {
+    let max_chars = max_tokens * CHARS_PER_TOKEN;
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    text.chars().take(max_chars).collect()
+}
+
+fn is_system_message(message: &Value) -> bool {
+    matches!(
+        message.get("role").and_then(Value::as_str),
+        Some("system" | "developer")
+    )
+}
+
+fn is_attachment_part(part: &Value) -> bool {
+    part.get("type").and_then(Value::as_str) != Some("text")
+}
+
+/// Estimated tokens of the non-text content parts of a message, and how many there are
+pub fn attachment_tokens(message: &Value) -> (usize, usize) {
+    let Some(Value::Array(parts)) = message.get("content") else {
+        return (0, 0);
+    };
+    parts
+        .iter()
+        .filter(|p| is_attachment_part(p))
+        .fold((0, 0), |(tokens, count), part| {
+            let part_tokens = match part.get("type").and_then(Value::as_str) {
+                Some("image_url" | "image") => IMAGE_ATTACHMENT_TOKENS,
+                _ => estimate_tokens(&part.to_string()),
+            };
+            (tokens + part_tokens, count + 1)
+        })
+}
+
+/// Estimated tokens of a chat message: text, tool calls and attachments
+pub fn message_tokens(message: &Value) -> usize {
+    let tool_calls = message
+        .get("tool_calls")
+        .map_or(0, |calls| estimate_tokens(&calls.to_string()));
+    estimate_message_tokens(&message_text(message)) + tool_calls + attachment_tokens(message).0
+}
+
+/// Tokens kept free for the response
+pub fn response_reserve(body: &Value, reserve_tokens: Option<usize>) -> usize {
+    reserve_tokens
+        .or_else(|| {
+            ["max_completion_tokens", "max_tokens"]
+                .iter()
+                .find_map(|key| body.get(*key).and_then(Value::as_u64))
+                .map(|n| n as usize)
+        })
+        .unwrap_or(DEFAULT_RESPONSE_RESERVE_TOKENS)
+}
+
+fn body_messages(body: &Value) -> &[Value] {
+    body.get("messages")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+pub fn token_breakdown(body: &Value) -> TokenBreakdown {
+    let mut breakdown = TokenBreakdown {
+        tools: body
+            .get("tools")
+            .map_or(0, |tools| estimate_tokens(&tools.to_string())),
+        ..Default::default()
+    };
+    for message in body_messages(body) {
+        let (attachments, _) = attachment_tokens(message);
+        let text = message_tokens(message) - attachments;
+        breakdown.attachments += attachments;
+        if is_system_message(message) {
+            breakdown.system += text;
+        } else {
+            breakdown.history += text;
+        }
+    }
+    breakdown
+}
+
+/// Number of oldest non-system messages to drop to free `needed` tokens, and the tokens freed.
+/// The latest message is never dropped, and tool results whose call was dropped go with it.
+pub fn oldest_messages_to_free(messages: &[Value], needed: usize) -> Option<(usize, usize)> {
+    let history: Vec<&Value> = messages.iter().filter(|m| !is_system_message(m)).collect();
+    let droppable = history.len().checked_sub(1)?;
+    let mut count = 0;
+    let mut freed = 0;
+    while count < droppable && freed < needed {
+        freed += message_tokens(history[count]);
+        count += 1;
+    }
+    while count < droppable && history[count].get("role").and_then(Value::as_str) == Some("tool") {
+        freed += message_tokens(history[count]);
+        count += 1;
+    }
+    (freed >= needed && count > 0).then_some((count, freed))
+}
+
+/// Estimate a request against its context window and list the remediations that fit it
+pub fn check_context(request: &ContextBudgetRequest) -> ContextCheck {
+    let reserve = response_reserve(&request.body, request.reserve_tokens);
+    let budget = request.context_size.saturating_sub(reserve);
+    let breakdown = token_breakdown(&request.body);
+    let estimated_tokens = breakdown.total();
+    if estimated_tokens <= budget {
+        return ContextCheck::Fits {
+            estimated_tokens,
+            budget,
+        };
+    }
+
+    let excess_tokens = estimated_tokens - budget;
+    let messages = body_messages(&request.body);
+    let mut options = Vec::new();
+    if let Some((message_count, freed_tokens)) = oldest_messages_to_free(messages, excess_tokens) {
+        options.push(RemediationOption::TruncateOldest {
+            message_count,
+            freed_tokens,
+        });
+    }
+    // The summary itself takes up to its share of the budget
+    let summary_tokens = (budget as f64 * SUMMARY_BUDGET_RATIO) as usize;
+    if let Some((message_count, freed)) =
+        oldest_messages_to_free(messages, excess_tokens + summary_tokens)
+    {
+        options.push(RemediationOption::Summarize {
+            message_count,
+            freed_tokens: freed - summary_tokens,
+        });
+    }
+    let (attachment_tokens_total, attachment_count) = messages
+        .iter()
+        .map(attachment_tokens)
+        .fold((0, 0), |(t, c), (tokens, count)| (t + tokens, c + count));
+    if attachment_count > 0 && attachment_tokens_total >= excess_tokens {
+        options.push(RemediationOption::DropAttachments {
+            attachment_count,
+            freed_tokens: attachment_tokens_total,
+        });
+    }
+    let mut alternatives: Vec<_> = request
+        .alternative_models
+        .iter()
+        .filter(|m| m.context_size.saturating_sub(reserve) >= estimated_tokens)
+        .collect();
+    alternatives.sort_by_key(|m| m.context_size);
+    options.extend(
+        alternatives
+            .into_iter()
+            .map(|m| RemediationOption::SwitchModel {
+                model_id: m.model_id.clone(),
+                context_size: m.context_size,
+            }),
+    );
+
+    ContextCheck::Overflow(ContextOverflow {
+        estimated_tokens,
+        budget,
+        excess_tokens,
+        breakdown,
+        options,
+    })
+}
+
+/// Take out the oldest `count` non-system messages, keeping system messages in place
+pub fn take_oldest_messages(body: &mut Value, count: usize) -> Vec<Value> {
+    let Some(messages) = body.get_mut("messages").and_then(Value::as_array_mut) else {
+        return Vec::new();
+    };
+    let mut taken = Vec::new();
+    let mut kept = Vec::with_capacity(messages.len());
+    for message in messages.drain(..) {
+        if taken.len() < count && !is_system_message(&message) {
+            taken.push(message);
+        } else {
+            kept.push(message);
+        }
+    }
+    *messages = kept;
+    taken
+}
+
+/// Insert a system message after the leading system messages
+pub fn insert_after_system(body: &mut Value, message: Value) {
+    if let Some(messages) = body.get_mut("messages").and_then(Value::as_array_mut) {
+        let pos = messages
+            .iter()
+            .position(|m| !is_system_message(m))
+            .unwrap_or(messages.len());
+        messages.insert(pos, message);
+    }
+}
+
+/// Replace image and file parts with a short placeholder
+pub fn drop_attachments(body: &mut Value) {
+    let Some(messages) = body.get_mut("messages").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for message in messages {
+        if let Some(Value::Array(parts)) = message.get_mut("content") {
+            if parts.iter().any(is_attachment_part) {
+                parts.retain(|p| !is_attachment_part(p));
+                parts.push(json!({ "type": "text", "text": ATTACHMENT_REMOVED_TEXT }));
+            }
+        }
+    }
+}
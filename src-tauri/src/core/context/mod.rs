@@ -0,0 +1,27 @@
+/*!
+   Thread Context Window Manager
+
+   Assembles the prompt sent to a model for a thread, given the model context size:
+   - Pinned messages (`metadata.pinned == true` or pinned in the bookmark store) and the
+     latest message are always kept.
+   - Remaining messages are added newest-first until the token budget is exhausted.
+   - Messages that do not fit are summarized with a (cheap) summarizer model, and the
+     summary is cached per thread so it is only regenerated when more history falls out.
+   - Retrieved chunks passed as `sources` are listed as numbered sources after the system
+     prompt, within a share of the budget, and returned as citations.
+   - The system prompt asks for replies in the thread's response language (see `language`).
+   Token counts are estimated, so a safety margin is reserved from the context size.
+
+   Requests built elsewhere (the agent loop, the frontend) can be checked against the context
+   window before they are sent. An overflow is reported as a `ContextOverflow` with the
+   remediations that would each fit the request: truncating the oldest messages, summarizing
+   them, dropping attachments or switching to a model with a larger context window.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,180 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::core::citations::models::{Citation, SourceChunk};
+
+/// Parameters for assembling the context of a thread
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextRequest {
+    pub thread_id: String,
+    /// Model context window size in tokens
+    pub context_size: usize,
+    /// Tokens reserved for the response
+    #[serde(default)]
+    pub reserve_tokens: Option<usize>,
+    /// System prompt placed first in the assembled context
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    /// Model used to summarize history that does not fit; history is dropped when absent
+    #[serde(default)]
+    pub summarizer_model: Option<String>,
+    /// Retrieved chunks to ground the answer on, most relevant first
+    #[serde(default)]
+    pub sources: Vec<SourceChunk>,
+}
+
+/// OpenAI-style chat message
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+/// The assembled prompt and how it was built
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssembledContext {
+    pub messages: Vec<ChatMessage>,
+    pub included_message_ids: Vec<String>,
+    pub pinned_message_ids: Vec<String>,
+    pub summarized_message_ids: Vec<String>,
+    pub dropped_message_ids: Vec<String>,
+    pub summary: Option<String>,
+    /// Sources included in the prompt, numbered as the model was asked to cite them
+    pub citations: Vec<Citation>,
+    /// Language the system prompt asks replies to be in, chosen for the thread or detected
+    #[serde(default)]
+    pub response_language: Option<String>,
+    pub estimated_tokens: usize,
+}
+
+/// Cached history summary of a thread
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextSummary {
+    /// Id of the newest message covered by the summary
+    pub last_message_id: String,
+    pub message_count: usize,
+    pub summary: String,
+    pub model: String,
+    pub created_at: i64,
+}
+
+/// Message reduced to what context assembly needs
+#[derive(Debug, Clone)]
+pub struct ContextCandidate {
+    pub id: String,
+    pub message: ChatMessage,
+    pub pinned: bool,
+    pub tokens: usize,
+}
+
+impl ContextCandidate {
+    pub fn from_thread_message(message: &Value) -> Option<Self> {
+        let id = message.get("id")?.as_str()?.to_string();
+        let role = message.get("role")?.as_str()?.to_string();
+        let content = super::helpers::message_text(message);
+        let pinned = message
+            .get("metadata")
+            .and_then(|m| m.get("pinned"))
+            .and_then(|p| p.as_bool())
+            .unwrap_or(false);
+        let tokens = super::helpers::estimate_message_tokens(&content);
+        Some(Self {
+            id,
+            message: ChatMessage { role, content },
+            pinned,
+            tokens,
+        })
+    }
+}
+
+/// Outcome of budget-based message selection
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Selection {
+    /// Indices kept verbatim, in chronological order
+    pub kept: Vec<usize>,
+    /// Indices that did not fit, in chronological order
+    pub overflow: Vec<usize>,
+}
+
+/// A model the request could be moved to, with its context window size
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModelContext {
+    pub model_id: String,
+    pub context_size: usize,
+}
+
+/// A chat completion request to check against a context window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextBudgetRequest {
+    /// OpenAI-style request body: `messages`, optional `tools` and `max_tokens`
+    pub body: Value,
+    pub context_size: usize,
+    /// Tokens reserved for the response; defaults to the body's `max_tokens`
+    #[serde(default)]
+    pub reserve_tokens: Option<usize>,
+    /// Larger-context models offered as a remediation
+    #[serde(default)]
+    pub alternative_models: Vec<ModelContext>,
+}
+
+/// Estimated tokens of a request by part
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TokenBreakdown {
+    pub system: usize,
+    pub history: usize,
+    pub attachments: usize,
+    pub tools: usize,
+}
+
+impl TokenBreakdown {
+    pub fn total(&self) -> usize {
+        self.system + self.history + self.attachments + self.tools
+    }
+}
+
+/// A change that brings an overflowing request back within the budget on its own
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RemediationOption {
+    /// Drop the oldest `message_count` non-system messages
+    TruncateOldest {
+        message_count: usize,
+        freed_tokens: usize,
+    },
+    /// Replace the oldest `message_count` non-system messages with a summary
+    Summarize {
+        message_count: usize,
+        freed_tokens: usize,
+    },
+    /// Remove images and files from the messages
+    DropAttachments {
+        attachment_count: usize,
+        freed_tokens: usize,
+    },
+    /// Send the request to a model with a larger context window
+    SwitchModel {
+        model_id: String,
+        context_size: usize,
+    },
+}
+
+/// Why a request does not fit and what can be done about it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ContextOverflow {
+    pub estimated_tokens: usize,
+    pub budget: usize,
+    pub excess_tokens: usize,
+    pub breakdown: TokenBreakdown,
+    /// Empty when no single remediation is enough
+    pub options: Vec<RemediationOption>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ContextCheck {
+    Fits {
+        estimated_tokens: usize,
+        budget: usize,
+    },
+    Overflow(ContextOverflow),
+}
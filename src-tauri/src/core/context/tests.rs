@@ -0,0 +1,224 @@
+use super::helpers::{
+    build_summary_request, check_context, drop_attachments, estimate_tokens, message_text,
+    oldest_messages_to_free, select_messages, take_oldest_messages,
+};
+use super::models::{
+    ChatMessage, ContextBudgetRequest, ContextCandidate, ContextCheck, ModelContext,
+    RemediationOption,
+};
+use serde_json::{json, Value};
+
+fn candidate(id: &str, tokens: usize, pinned: bool) -> ContextCandidate {
+    ContextCandidate {
+        id: id.to_string(),
+        message: ChatMessage {
+            role: "user".to_string(),
+            content: id.to_string(),
+        },
+        pinned,
+        tokens,
+    }
+}
+
+#[test]
+fn test_estimate_tokens() {
+    assert_eq!(estimate_tokens(""), 0);
+    assert_eq!(estimate_tokens("abcd"), 1);
+    assert_eq!(estimate_tokens("abcde"), 2);
+}
+
+#[test]
+fn test_message_text_formats() {
+    let nested = json!({
+        "content": [
+            {"type": "text", "text": {"value": "hello", "annotations": []}},
+            {"type": "image_url", "image_url": {"url": "data:"}},
+            {"type": "text", "text": "world"}
+        ]
+    });
+    assert_eq!(message_text(&nested), "hello\nworld");
+    assert_eq!(message_text(&json!({"content": "plain"})), "plain");
+    assert_eq!(message_text(&json!({})), "");
+}
+
+#[test]
+fn test_from_thread_message_reads_pin() {
+    let message = json!({
+        "id": "m1",
+        "role": "assistant",
+        "content": [{"type": "text", "text": {"value": "answer"}}],
+        "metadata": {"pinned": true}
+    });
+    let candidate = ContextCandidate::from_thread_message(&message).unwrap();
+    assert!(candidate.pinned);
+    assert_eq!(candidate.message.content, "answer");
+}
+
+#[test]
+fn test_select_messages_keeps_recent_within_budget() {
+    let candidates = vec![
+        candidate("a", 10, false),
+        candidate("b", 10, false),
+        candidate("c", 10, false),
+        candidate("d", 10, false),
+    ];
+    let selection = select_messages(&candidates, 25);
+    assert_eq!(selection.kept, vec![2, 3]);
+    assert_eq!(selection.overflow, vec![0, 1]);
+}
+
+#[test]
+fn test_select_messages_always_keeps_pinned_and_latest() {
+    let candidates = vec![
+        candidate("a", 10, true),
+        candidate("b", 10, false),
+        candidate("c", 10, false),
+        candidate("d", 50, false),
+    ];
+    let selection = select_messages(&candidates, 20);
+    assert_eq!(selection.kept, vec![0, 3]);
+    assert_eq!(selection.overflow, vec![1, 2]);
+}
+
+#[test]
+fn test_select_messages_empty() {
+    let selection = select_messages(&[], 100);
+    assert!(selection.kept.is_empty());
+    assert!(selection.overflow.is_empty());
+}
+
+#[test]
+fn test_build_summary_request_includes_previous_summary() {
+    let message = ChatMessage {
+        role: "user".to_string(),
+        content: "What is Rust?".to_string(),
+    };
+    let request = build_summary_request(&[&message], Some("Earlier talk"));
+    let transcript = request["messages"][1]["content"].as_str().unwrap();
+    assert!(transcript.contains("Earlier talk"));
+    assert!(transcript.contains("user: What is Rust?"));
+}
+
+/// A message of roughly `tokens` estimated tokens
+fn sized_message(role: &str, tokens: usize) -> Value {
+    json!({"role": role, "content": "x".repeat((tokens - 4) * 4)})
+}
+
+#[test]
+fn test_check_context_fits() {
+    let request = ContextBudgetRequest {
+        body: json!({"messages": [sized_message("user", 100)], "max_tokens": 100}),
+        context_size: 1000,
+        reserve_tokens: None,
+        alternative_models: Vec::new(),
+    };
+    assert_eq!(
+        check_context(&request),
+        ContextCheck::Fits {
+            estimated_tokens: 100,
+            budget: 900
+        }
+    );
+}
+
+#[test]
+fn test_check_context_overflow_options() {
+    let request = ContextBudgetRequest {
+        body: json!({
+            "messages": [
+                sized_message("system", 100),
+                sized_message("user", 400),
+                sized_message("assistant", 400),
+                {"role": "user", "content": [
+                    {"type": "text", "text": "What is in this picture?"},
+                    {"type": "image_url", "image_url": {"url": "data:image/png;base64,AAAA"}}
+                ]}
+            ],
+            "max_tokens": 200
+        }),
+        context_size: 1600,
+        reserve_tokens: None,
+        alternative_models: vec![
+            ModelContext {
+                model_id: "small".to_string(),
+                context_size: 1024,
+            },
+            ModelContext {
+                model_id: "large".to_string(),
+                context_size: 32768,
+            },
+        ],
+    };
+    let ContextCheck::Overflow(overflow) = check_context(&request) else {
+        panic!("expected an overflow");
+    };
+    assert_eq!(overflow.budget, 1400);
+    assert_eq!(overflow.breakdown.system, 100);
+    assert_eq!(overflow.breakdown.attachments, 768);
+    assert_eq!(overflow.excess_tokens, overflow.estimated_tokens - 1400);
+    assert!(overflow
+        .options
+        .contains(&RemediationOption::TruncateOldest {
+            message_count: 1,
+            freed_tokens: 400
+        }));
+    // The summary takes room of its own, so it has to cover more messages
+    assert!(overflow.options.contains(&RemediationOption::Summarize {
+        message_count: 2,
+        freed_tokens: 450
+    }));
+    assert!(overflow.options.iter().any(|o| matches!(
+        o,
+        RemediationOption::DropAttachments {
+            attachment_count: 1,
+            ..
+        }
+    )));
+    assert!(overflow.options.contains(&RemediationOption::SwitchModel {
+        model_id: "large".to_string(),
+        context_size: 32768
+    }));
+    assert!(!overflow.options.iter().any(
+        |o| matches!(o, RemediationOption::SwitchModel { model_id, .. } if model_id == "small")
+    ));
+}
+
+#[test]
+fn test_truncation_keeps_tool_results_with_their_call() {
+    let messages = vec![
+        sized_message("user", 50),
+        json!({"role": "assistant", "content": "", "tool_calls": [{"id": "a", "function": {"name": "f", "arguments": "{}"}}]}),
+        json!({"role": "tool", "tool_call_id": "a", "content": "result"}),
+        sized_message("user", 50),
+    ];
+    // Freeing the first message alone is enough, but the call and its result go together
+    let (count, _) = oldest_messages_to_free(&messages, 10).unwrap();
+    assert_eq!(count, 1);
+    let (count, _) = oldest_messages_to_free(&messages, 55).unwrap();
+    assert_eq!(count, 3);
+    // The latest message is never dropped
+    assert!(oldest_messages_to_free(&messages, 10_000).is_none());
+}
+
+#[test]
+fn test_apply_truncation_and_attachment_removal() {
+    let mut body = json!({
+        "messages": [
+            {"role": "system", "content": "sys"},
+            {"role": "user", "content": "old"},
+            {"role": "user", "content": [
+                {"type": "text", "text": "look"},
+                {"type": "image_url", "image_url": {"url": "data:"}}
+            ]}
+        ]
+    });
+    let taken = take_oldest_messages(&mut body, 1);
+    assert_eq!(taken[0]["content"], "old");
+    drop_attachments(&mut body);
+    let messages = body["messages"].as_array().unwrap();
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[0]["role"], "system");
+    let parts = messages[1]["content"].as_array().unwrap();
+    assert!(parts.iter().all(|p| p["type"] == "text"));
+    assert_eq!(parts.len(), 2);
+}
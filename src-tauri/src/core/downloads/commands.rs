@@ -1,10 +1,16 @@
-use super::helpers::{_download_files_internal, err_to_string};
+use super::helpers::{
+    _download_files_internal, download_name, emit_download_finished, err_to_string,
+};
 use super::models::DownloadItem;
 use crate::core::app::commands::get_jan_data_folder_path;
-use crate::core::state::AppState;
+use crate::core::cancellation::models::CancelScope;
+use crate::core::messages::helpers::localize;
+use crate::core::messages::models::{LocalizedMessage, MessageCode};
+use crate::core::notifications::{helpers::notify, models::NotificationCategory};
+use crate::core::offline::helpers::check_url;
+use crate::core::state::{AppState, InFlightOperation};
 use std::collections::HashMap;
-use tauri::{Runtime, State};
-use tokio_util::sync::CancellationToken;
+use tauri::{AppHandle, Manager, Runtime, State};
 
 #[tauri::command]
 pub async fn download_files<R: Runtime>(
@@ -14,34 +20,46 @@ pub async fn download_files<R: Runtime>(
     task_id: &str,
     headers: HashMap<String, String>,
 ) -> Result<(), String> {
-    // insert cancel tokens
-    let cancel_token = CancellationToken::new();
-    {
-        let mut download_manager = state.download_manager.lock().await;
-        if let Some(existing_token) = download_manager.cancel_tokens.remove(task_id) {
-            log::info!("Cancelling existing download task: {task_id}");
-            existing_token.cancel();
-        }
-        download_manager
-            .cancel_tokens
-            .insert(task_id.to_string(), cancel_token.clone());
+    for item in &items {
+        check_url(&app, &item.url)?;
     }
+    // A second request for a task that is still downloading waits for it rather than racing it
+    state
+        .in_flight
+        .run(
+            InFlightOperation::Download(task_id.to_string()),
+            download_task(app, items, task_id.to_string(), headers),
+        )
+        .await
+}
+
+async fn download_task<R: Runtime>(
+    app: AppHandle<R>,
+    items: Vec<DownloadItem>,
+    task_id: String,
+    headers: HashMap<String, String>,
+) -> Result<(), String> {
+    // A scope left open under the same id is cancelled and replaced
+    let guard = app
+        .state::<AppState>()
+        .cancellations
+        .open_replacing(CancelScope::Download(task_id.clone()), None)?;
+    let cancel_token = guard.token().clone();
     // TODO: Support resuming downloads when FE is ready
     let result = _download_files_internal(
         app.clone(),
         &items,
         &headers,
-        task_id,
+        &task_id,
         false,
         cancel_token.clone(),
     )
     .await;
 
-    // cleanup
-    {
-        let mut download_manager = state.download_manager.lock().await;
-        download_manager.cancel_tokens.remove(task_id);
-    }
+    drop(guard);
+
+    emit_download_finished(&app, &items, &task_id, cancel_token.is_cancelled(), &result);
+    let name = download_name(&items, &task_id).to_string();
 
     // delete files if cancelled
     if cancel_token.is_cancelled() {
@@ -50,6 +68,20 @@ pub async fn download_files<R: Runtime>(
             let save_path = jan_data_folder.join(&item.save_path);
             let _ = std::fs::remove_file(&save_path); // don't check error
         }
+    } else if result.is_ok() {
+        notify(
+            &app,
+            NotificationCategory::DownloadFinished,
+            &localize(
+                &app,
+                &LocalizedMessage::new(MessageCode::NotificationDownloadCompleteTitle),
+            ),
+            &localize(
+                &app,
+                &LocalizedMessage::new(MessageCode::NotificationDownloadCompleteBody)
+                    .with("name", &name),
+            ),
+        );
     }
 
     result.map_err(err_to_string)
@@ -58,9 +90,10 @@ pub async fn download_files<R: Runtime>(
 #[tauri::command]
 pub async fn cancel_download_task(state: State<'_, AppState>, task_id: &str) -> Result<(), String> {
     // NOTE: might want to add User-Agent header
-    let mut download_manager = state.download_manager.lock().await;
-    if let Some(token) = download_manager.cancel_tokens.remove(task_id) {
-        token.cancel();
+    if state
+        .cancellations
+        .cancel(&CancelScope::Download(task_id.to_string()))
+    {
         log::info!("Cancelled download task: {task_id}");
         Ok(())
     } else {
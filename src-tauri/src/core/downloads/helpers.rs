@@ -1,16 +1,26 @@
 use super::models::{DownloadEvent, DownloadItem, ProgressTracker, ProxyConfig};
 use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::events::helpers::{emit_coalesced, emit_final, emit_task_progress};
+use crate::core::network::helpers::subscribe_network_changes;
+use crate::core::settings::helpers::download_settings;
+use crate::core::settings::models::MirrorPolicy;
 use crate::core::updater::session::get_session_id;
 use crate::core::updater::hmac_client::SignedRequestHeaders;
+use crate::core::watchdog::{
+    constants::DOWNLOAD_STALL_TIMEOUT, helpers::watch, models::WatchedKind, WatchHandle,
+};
 use futures_util::StreamExt;
 use jan_utils::normalize_path;
+use jan_utils::progress::{ProgressOperation, ProgressPayload, ProgressState};
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use std::collections::HashMap;
 use std::path::Path;
+use std::sync::Arc;
 use std::time::Duration;
 use tauri::{Emitter, Runtime};
 use tokio::fs::File;
 use tokio::io::AsyncWriteExt;
+use tokio::sync::{watch, Semaphore};
 use tokio_util::sync::CancellationToken;
 use url::Url;
 
@@ -48,8 +58,28 @@ const SECRET_KEY: &str = match option_env!("JAN_SIGNING_KEY") {
     None => "local-dev-test-key-not-for-production",
 };
 
+/// Reconnects of a single file after network changes, so a flapping network can't keep a
+/// download going forever
+const MAX_DOWNLOAD_RECONNECTS: u32 = 10;
+/// How long a download that lost its connection waits for the network to come back
+const DOWNLOAD_NETWORK_WAIT: Duration = Duration::from_secs(30);
+
 // ===== UTILITY FUNCTIONS =====
 
+/// Wait for the next network change, up to `DOWNLOAD_NETWORK_WAIT`. False when none came or
+/// the download was cancelled meanwhile.
+async fn wait_for_network_change(
+    network: &mut watch::Receiver<u64>,
+    cancel_token: &CancellationToken,
+) -> bool {
+    tokio::select! {
+        changed = tokio::time::timeout(DOWNLOAD_NETWORK_WAIT, network.changed()) => {
+            matches!(changed, Ok(Ok(())))
+        }
+        _ = cancel_token.cancelled() => false,
+    }
+}
+
 pub fn err_to_string<E: std::fmt::Display>(e: E) -> String {
     format!("Error: {e}")
 }
@@ -369,9 +399,57 @@ pub async fn _get_file_size(
 struct DownloadCtx {
     header_map: HeaderMap,
     resume: bool,
+    use_mirror: bool,
     cancel_token: CancellationToken,
+    task_id: String,
+    /// Model or task the progress labels name
+    name: String,
     evt_name: String,
     progress_tracker: ProgressTracker,
+    watch: WatchHandle,
+}
+
+/// Emit the combined progress of a task's files as its download event and in the shared
+/// progress format
+async fn emit_transfer_progress<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    evt_name: &str,
+    task_id: &str,
+    name: &str,
+    progress_tracker: &ProgressTracker,
+) {
+    let (transferred, total) = progress_tracker.get_total_progress().await;
+    let evt = DownloadEvent { transferred, total };
+    emit_coalesced(app, evt_name, task_id, evt);
+    emit_task_progress(app, &progress_tracker.payload(task_id, name, transferred, total));
+}
+
+/// Name shown in the progress of a download task: its model, or the task id
+pub fn download_name<'a>(items: &'a [DownloadItem], task_id: &'a str) -> &'a str {
+    items
+        .iter()
+        .find_map(|item| item.model_id.as_deref())
+        .unwrap_or(task_id)
+}
+
+/// Emit the last progress of a download task in the shared progress format
+pub fn emit_download_finished<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    items: &[DownloadItem],
+    task_id: &str,
+    cancelled: bool,
+    result: &Result<(), String>,
+) {
+    let state = match result {
+        _ if cancelled => ProgressState::Cancelled,
+        Ok(()) => ProgressState::Completed,
+        Err(_) => ProgressState::Failed,
+    };
+    let name = download_name(items, task_id);
+    emit_task_progress(
+        app,
+        &ProgressPayload::finished(ProgressOperation::Download, task_id, name, state),
+    );
 }
 
 /// Downloads multiple files in parallel with individual progress tracking
@@ -386,6 +464,14 @@ pub async fn _download_files_internal(
     log::info!("Start download task: {task_id}");
 
     let header_map = _convert_headers(headers).map_err(err_to_string)?;
+    let transfer = watch(
+        &app,
+        WatchedKind::Download,
+        task_id,
+        None,
+        Some(DOWNLOAD_STALL_TIMEOUT),
+    );
+    transfer.handle().stage("checking file sizes");
 
     // Calculate sizes for each file
     let mut file_sizes: HashMap<String, u64> = HashMap::new();
@@ -401,6 +487,7 @@ pub async fn _download_files_internal(
     log::info!("Total download size: {total_size}");
 
     let evt_name = format!("download-{task_id}");
+    transfer.handle().stage("transferring");
 
     // Create progress tracker
     let progress_tracker = ProgressTracker::new(items, file_sizes.clone());
@@ -408,6 +495,10 @@ pub async fn _download_files_internal(
     // save file under Jan data folder
     let jan_data_folder = get_jan_data_folder_path(app.clone());
 
+    // Read once per task, so settings changed meanwhile apply to the next download
+    let settings = download_settings(&app);
+    let permits = Arc::new(Semaphore::new(settings.max_parallel_files.max(1)));
+
     // Collect download tasks for parallel execution
     let mut download_tasks = Vec::new();
 
@@ -432,12 +523,18 @@ pub async fn _download_files_internal(
         let ctx = DownloadCtx {
             header_map: header_map.clone(),
             resume,
+            use_mirror: settings.mirror == MirrorPolicy::Auto,
             cancel_token: cancel_token.clone(),
+            task_id: task_id.to_string(),
+            name: download_name(items, task_id).to_string(),
             evt_name: evt_name.clone(),
             progress_tracker: progress_tracker.clone(),
+            watch: transfer.handle().clone(),
         };
 
+        let permits = permits.clone();
         let task = tokio::spawn(async move {
+            let _permit = permits.acquire_owned().await.map_err(err_to_string)?;
             download_single_file(app_clone, &item_clone, &save_path, file_id, file_size, ctx).await
         });
 
@@ -471,6 +568,8 @@ pub async fn _download_files_internal(
             Err(e) => return Err(e),
         }
     }
+    // Hashing a large file is no transfer, so it can't stall one
+    drop(transfer);
 
     let model_id = items
         .iter()
@@ -499,6 +598,15 @@ pub async fn _download_files_internal(
             }),
         )
         .unwrap();
+        emit_task_progress(
+            &app,
+            &ProgressPayload::running(
+                ProgressOperation::Download,
+                task_id,
+                download_name(items, task_id),
+                "verifying",
+            ),
+        );
         log::info!("Starting validation for model: {model_id}");
     }
 
@@ -524,7 +632,7 @@ pub async fn _download_files_internal(
     // Emit final progress
     let (transferred, total) = progress_tracker.get_total_progress().await;
     let final_evt = DownloadEvent { transferred, total };
-    app.emit(&evt_name, final_evt).unwrap();
+    emit_final(&app, &evt_name, task_id, final_evt);
     Ok(())
 }
 
@@ -540,9 +648,13 @@ async fn download_single_file(
     let DownloadCtx {
         header_map,
         resume,
+        use_mirror,
         cancel_token,
+        task_id,
+        name,
         evt_name,
         progress_tracker,
+        watch,
     } = ctx;
     // Create parent directories if they don't exist
     if let Some(parent) = save_path.parent() {
@@ -601,13 +713,7 @@ async fn download_single_file(
                     .await;
 
                 // Emit initial combined progress
-                let (combined_transferred, combined_total) =
-                    progress_tracker.get_total_progress().await;
-                let evt = DownloadEvent {
-                    transferred: combined_transferred,
-                    total: combined_total,
-                };
-                app.emit(&evt_name, evt).unwrap();
+                emit_transfer_progress(&app, &evt_name, &task_id, &name, &progress_tracker).await;
 
                 (resp, item.url.clone())
             }
@@ -615,12 +721,12 @@ async fn download_single_file(
                 // fallback to normal download with proxy support
                 log::warn!("Failed to resume download: {e}");
                 should_resume = false;
-                _get_maybe_resume_with_fallback(&client, &item.url, 0).await?
+                _get_maybe_resume_with_fallback(&client, &item.url, 0, use_mirror).await?
             }
         }
     } else {
         // Use mirror fallback for new downloads
-        _get_maybe_resume_with_fallback(&client, &item.url, 0).await?
+        _get_maybe_resume_with_fallback(&client, &item.url, 0, use_mirror).await?
     };
     
     // Log which URL is being used for download
@@ -644,9 +750,55 @@ async fn download_single_file(
     };
     let mut writer = tokio::io::BufWriter::new(file);
     let mut total_transferred = initial_progress;
+    let mut network = subscribe_network_changes();
+    let mut reconnects = 0;
 
     // write chunk to file
-    while let Some(chunk) = stream.next().await {
+    loop {
+        let next = tokio::select! {
+            chunk = stream.next() => Some(chunk),
+            Ok(()) = network.changed() => None,
+        };
+        let chunk = match next {
+            Some(None) => break,
+            Some(Some(Ok(chunk))) => chunk,
+            interrupted => {
+                // Reopen the connection, which may be bound to a network that is gone. A
+                // dropped connection first waits for the network to change.
+                let mut error = match interrupted {
+                    Some(Some(Err(e))) => Some(err_to_string(e)),
+                    _ => None,
+                };
+                writer.flush().await.map_err(err_to_string)?;
+                loop {
+                    if let Some(e) = error {
+                        if !wait_for_network_change(&mut network, &cancel_token).await {
+                            return Err(e);
+                        }
+                    }
+                    reconnects += 1;
+                    if reconnects > MAX_DOWNLOAD_RECONNECTS {
+                        return Err(format!(
+                            "Download of {} was interrupted too often",
+                            item.url
+                        ));
+                    }
+                    log::info!(
+                        "Network changed, reconnecting download {} at {total_transferred} bytes",
+                        item.url
+                    );
+                    let client = _get_client_for_item(item, &header_map).map_err(err_to_string)?;
+                    match _get_maybe_resume(&client, &item.url, total_transferred).await {
+                        Ok(resp) => {
+                            stream = resp.bytes_stream();
+                            break;
+                        }
+                        Err(e) => error = Some(e),
+                    }
+                }
+                continue;
+            }
+        };
         if cancel_token.is_cancelled() {
             if !should_resume {
                 tokio::fs::remove_dir_all(&save_path.parent().unwrap())
@@ -657,8 +809,8 @@ async fn download_single_file(
             return Err("Download cancelled".to_string());
         }
 
-        let chunk = chunk.map_err(err_to_string)?;
         writer.write_all(&chunk).await.map_err(err_to_string)?;
+        watch.progress(chunk.len() as u64);
         download_delta += chunk.len() as u64;
         total_transferred += chunk.len() as u64;
 
@@ -670,13 +822,7 @@ async fn download_single_file(
                 .await;
 
             // Emit combined progress event
-            let (combined_transferred, combined_total) =
-                progress_tracker.get_total_progress().await;
-            let evt = DownloadEvent {
-                transferred: combined_transferred,
-                total: combined_total,
-            };
-            app.emit(&evt_name, evt).unwrap();
+            emit_transfer_progress(&app, &evt_name, &task_id, &name, &progress_tracker).await;
 
             download_delta = 0u64;
         }
@@ -690,12 +836,7 @@ async fn download_single_file(
         .await;
 
     // Emit final combined progress
-    let (combined_transferred, combined_total) = progress_tracker.get_total_progress().await;
-    let evt = DownloadEvent {
-        transferred: combined_transferred,
-        total: combined_total,
-    };
-    app.emit(&evt_name, evt).unwrap();
+    emit_transfer_progress(&app, &evt_name, &task_id, &name, &progress_tracker).await;
 
     // rename tmp file to final file
     tokio::fs::rename(&tmp_save_path, &save_path)
@@ -721,9 +862,10 @@ pub async fn _get_maybe_resume_with_fallback(
     client: &reqwest::Client,
     url: &str,
     start_bytes: u64,
+    use_mirror: bool,
 ) -> Result<(reqwest::Response, String), String> {
     // Try mirror URL first if applicable
-    if let Some(mirror_url) = convert_to_mirror_url(url) {
+    if let Some(mirror_url) = convert_to_mirror_url(url).filter(|_| use_mirror) {
         log::info!("Attempting download from Jan mirror: {}", mirror_url);
         match _get_maybe_resume_with_hmac(client, &mirror_url, start_bytes).await {
             Ok(resp) => {
@@ -1,12 +1,7 @@
+use jan_utils::progress::{EtaEstimator, ProgressOperation, ProgressPayload, ProgressUnit};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use tokio_util::sync::CancellationToken;
-
-#[derive(Default)]
-pub struct DownloadManagerState {
-    pub cancel_tokens: HashMap<String, CancellationToken>,
-}
 
 #[derive(serde::Deserialize, Clone, Debug)]
 pub struct ProxyConfig {
@@ -38,6 +33,7 @@ pub struct DownloadEvent {
 pub struct ProgressTracker {
     file_progress: Arc<Mutex<HashMap<String, u64>>>,
     total_size: u64,
+    eta: EtaEstimator,
 }
 
 impl ProgressTracker {
@@ -46,6 +42,7 @@ impl ProgressTracker {
         ProgressTracker {
             file_progress: Arc::new(Mutex::new(HashMap::new())),
             total_size,
+            eta: EtaEstimator::new(0),
         }
     }
 
@@ -59,4 +56,17 @@ impl ProgressTracker {
         let total_transferred: u64 = progress.values().sum();
         (total_transferred, self.total_size)
     }
+
+    /// Combined progress of the task's files in the shared progress format
+    pub fn payload(
+        &self,
+        task_id: &str,
+        name: &str,
+        transferred: u64,
+        total: u64,
+    ) -> ProgressPayload {
+        ProgressPayload::running(ProgressOperation::Download, task_id, name, "downloading")
+            .with_amount(transferred, total, ProgressUnit::Bytes)
+            .with_eta(self.eta.eta(transferred, total))
+    }
 }
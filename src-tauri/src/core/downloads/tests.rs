@@ -322,12 +322,6 @@ fn test_convert_headers_invalid_header_value() {
     assert!(result.is_err());
 }
 
-#[test]
-fn test_download_manager_state_default() {
-    let state = DownloadManagerState::default();
-    assert!(state.cancel_tokens.is_empty());
-}
-
 #[test]
 fn test_download_event_serialization() {
     let event = DownloadEvent {
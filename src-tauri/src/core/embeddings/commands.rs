@@ -0,0 +1,15 @@
+use tauri::{AppHandle, Runtime};
+
+use super::helpers::{create_embeddings as embed, local_embedding_endpoint};
+use super::models::{EmbeddingRequest, EmbeddingResponse};
+
+/// Embed texts with an embedding model loaded in the local engine. Accepts the same request
+/// as the server's `/v1/embeddings` route.
+#[tauri::command]
+pub async fn create_embeddings<R: Runtime>(
+    app_handle: AppHandle<R>,
+    request: EmbeddingRequest,
+) -> Result<EmbeddingResponse, String> {
+    let endpoint = local_embedding_endpoint(&app_handle, &request.model).await?;
+    embed(&endpoint, request).await
+}
@@ -0,0 +1,14 @@
+// Embeddings constants
+
+/// Inputs sent to the engine per request when the caller doesn't choose
+pub const DEFAULT_BATCH_SIZE: usize = 32;
+pub const MAX_BATCH_SIZE: usize = 512;
+/// Upper bound on the estimated tokens of one batch, so it fits the engine's default
+/// physical batch size. Single inputs above it are still sent on their own.
+pub const MAX_BATCH_TOKENS: usize = 2048;
+/// Inputs accepted in a single request
+pub const MAX_INPUTS: usize = 4096;
+/// Rough characters per token used to estimate batch sizes
+pub const CHARS_PER_TOKEN: usize = 4;
+/// Timeout of a single batch request to the engine, in seconds
+pub const EMBEDDING_TIMEOUT_SECS: u64 = 300;
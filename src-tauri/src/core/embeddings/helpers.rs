@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use std::time::Duration;
+
+use base64::Engine;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_llamacpp::state::LlamacppState;
+use tauri_plugin_llamacpp::LLamaBackendSession;
+
+use super::constants::*;
+use super::models::{
+    Embedding, EmbeddingRequest, EmbeddingResponse, EmbeddingUsage, EmbeddingVector, EncodingFormat,
+};
+use crate::core::inference::helpers::build_request;
+use crate::core::inference::models::{ModelEndpoint, RequestPolicy};
+use crate::core::inference::retry::{policy_client, send_with_retry};
+
+/// Rough token count of one input
+pub fn estimate_tokens(item: &Value) -> usize {
+    match item {
+        Value::String(text) => text.chars().count() / CHARS_PER_TOKEN + 1,
+        Value::Array(tokens) => tokens.len(),
+        _ => 1,
+    }
+}
+
+/// Split inputs into consecutive batches of at most `batch_size` inputs and, where possible,
+/// `MAX_BATCH_TOKENS` estimated tokens
+pub fn plan_batches(items: &[Value], batch_size: usize) -> Vec<Range<usize>> {
+    let batch_size = batch_size.clamp(1, MAX_BATCH_SIZE);
+    let mut batches = Vec::new();
+    let mut start = 0;
+    let mut tokens = 0;
+    for (index, item) in items.iter().enumerate() {
+        let item_tokens = estimate_tokens(item);
+        let full = index - start >= batch_size || tokens + item_tokens > MAX_BATCH_TOKENS;
+        if index > start && full {
+            batches.push(start..index);
+            start = index;
+            tokens = 0;
+        }
+        tokens += item_tokens;
+    }
+    if start < items.len() {
+        batches.push(start..items.len());
+    }
+    batches
+}
+
+/// Scale `vector` to unit length; zero vectors are left alone
+pub fn l2_normalize(vector: &mut [f32]) {
+    let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        vector.iter_mut().for_each(|v| *v /= norm);
+    }
+}
+
+/// Apply the truncation, normalization and encoding options of a request to one vector
+pub fn finish_vector(
+    mut vector: Vec<f32>,
+    dimensions: Option<usize>,
+    normalize: bool,
+    format: EncodingFormat,
+) -> EmbeddingVector {
+    if let Some(dimensions) = dimensions {
+        vector.truncate(dimensions);
+    }
+    if normalize {
+        l2_normalize(&mut vector);
+    }
+    match format {
+        EncodingFormat::Float => EmbeddingVector::Float(vector),
+        EncodingFormat::Base64 => {
+            let bytes: Vec<u8> = vector.iter().flat_map(|v| v.to_le_bytes()).collect();
+            EmbeddingVector::Base64(base64::engine::general_purpose::STANDARD.encode(bytes))
+        }
+    }
+}
+
+/// Vectors of an OpenAI-style embeddings response in input order, with its prompt tokens
+pub fn parse_batch_response(
+    response: &Value,
+    expected: usize,
+) -> Result<(Vec<Vec<f32>>, u64), String> {
+    let data = response
+        .get("data")
+        .and_then(|d| d.as_array())
+        .ok_or_else(|| "Embedding response has no data".to_string())?;
+    let mut vectors: Vec<Option<Vec<f32>>> = vec![None; expected];
+    for (position, item) in data.iter().enumerate() {
+        let index = item
+            .get("index")
+            .and_then(|i| i.as_u64())
+            .map_or(position, |i| i as usize);
+        let vector = item
+            .get("embedding")
+            .and_then(|e| serde_json::from_value::<Vec<f32>>(e.clone()).ok())
+            .ok_or_else(|| format!("Embedding {index} is not a vector of numbers"))?;
+        match vectors.get_mut(index) {
+            Some(slot) => *slot = Some(vector),
+            None => return Err(format!("Embedding index {index} is out of range")),
+        }
+    }
+    let vectors = vectors
+        .into_iter()
+        .enumerate()
+        .map(|(index, v)| v.ok_or_else(|| format!("Embedding {index} is missing")))
+        .collect::<Result<Vec<_>, _>>()?;
+    let tokens = response
+        .pointer("/usage/prompt_tokens")
+        .and_then(|t| t.as_u64())
+        .unwrap_or_default();
+    Ok((vectors, tokens))
+}
+
+/// Endpoint of a llama.cpp session on this machine
+pub fn session_endpoint(model_id: &str, port: i32, api_key: &str) -> ModelEndpoint {
+    ModelEndpoint {
+        model_id: model_id.to_string(),
+        base_url: format!("http://127.0.0.1:{port}/v1"),
+        api_key: Some(api_key.to_string()),
+        custom_headers: Vec::new(),
+        is_local: true,
+        policy: RequestPolicy::local(),
+        tool_schema: None,
+        native_tools: None,
+        failover_models: Vec::new(),
+    }
+}
+
+/// Endpoint of the llama.cpp session serving `model_id`, which must have been loaded in
+/// embedding mode
+pub fn find_embedding_endpoint(
+    sessions: &HashMap<i32, LLamaBackendSession>,
+    model_id: &str,
+) -> Result<ModelEndpoint, String> {
+    let session = sessions
+        .values()
+        .find(|s| s.info.model_id == model_id)
+        .ok_or_else(|| format!("No running session found for model '{model_id}'"))?;
+    if !session.info.is_embedding {
+        return Err(format!(
+            "Model '{model_id}' is not loaded as an embedding model"
+        ));
+    }
+    Ok(session_endpoint(
+        model_id,
+        session.info.port,
+        &session.info.api_key,
+    ))
+}
+
+pub async fn local_embedding_endpoint<R: Runtime>(
+    app: &AppHandle<R>,
+    model_id: &str,
+) -> Result<ModelEndpoint, String> {
+    let state = app
+        .try_state::<LlamacppState>()
+        .ok_or_else(|| "The llama.cpp engine is not available".to_string())?;
+    let sessions = state.llama_server_process.lock().await;
+    find_embedding_endpoint(&sessions, model_id)
+}
+
+/// Embed the inputs of `request` with the engine behind `endpoint`, one batch at a time
+pub async fn create_embeddings(
+    endpoint: &ModelEndpoint,
+    request: EmbeddingRequest,
+) -> Result<EmbeddingResponse, String> {
+    let items = request.input.into_items();
+    if items.is_empty() {
+        return Err("Embedding input must not be empty".to_string());
+    }
+    if items.len() > MAX_INPUTS {
+        return Err(format!(
+            "At most {MAX_INPUTS} inputs can be embedded per request, got {}",
+            items.len()
+        ));
+    }
+    if request.dimensions == Some(0) {
+        return Err("dimensions must be greater than 0".to_string());
+    }
+
+    let client = policy_client(
+        &endpoint.policy,
+        Duration::from_secs(EMBEDDING_TIMEOUT_SECS),
+    )?;
+    let batches = plan_batches(&items, request.batch_size.unwrap_or(DEFAULT_BATCH_SIZE));
+    let mut data = Vec::with_capacity(items.len());
+    let mut usage = EmbeddingUsage::default();
+    for range in batches {
+        let body = json!({
+            "model": endpoint.model_id,
+            "input": &items[range.clone()],
+            "encoding_format": "float",
+        });
+        let response = send_with_retry(
+            &endpoint.policy,
+            build_request(&client, endpoint, "/embeddings", None, &body),
+        )
+        .await
+        .map_err(|e| format!("Embedding request failed: {e}"))?;
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            return Err(format!(
+                "Embedding request failed with status {status}: {text}"
+            ));
+        }
+        let response: Value = response
+            .json()
+            .await
+            .map_err(|e| format!("Invalid embedding response: {e}"))?;
+        let (vectors, tokens) = parse_batch_response(&response, range.len())?;
+        usage.prompt_tokens += tokens;
+        for (offset, vector) in vectors.into_iter().enumerate() {
+            data.push(Embedding {
+                object: "embedding".to_string(),
+                index: range.start + offset,
+                embedding: finish_vector(
+                    vector,
+                    request.dimensions,
+                    request.normalize,
+                    request.encoding_format,
+                ),
+            });
+        }
+    }
+    usage.total_tokens = usage.prompt_tokens;
+    Ok(EmbeddingResponse {
+        object: "list".to_string(),
+        data,
+        model: endpoint.model_id.clone(),
+        usage,
+    })
+}
@@ -0,0 +1,20 @@
+/*!
+   Embeddings
+
+   Runs embedding models loaded in the local llama.cpp engine for both the `/v1/embeddings`
+   route of the local API server and the `create_embeddings` command used by RAG ingestion.
+   Requests follow the OpenAI embeddings API and add a few options:
+   - inputs are split into batches so large ingestion jobs don't exceed the engine's batch
+     size, and the results are merged back in input order,
+   - `normalize` L2-normalizes every vector, and `dimensions` truncates vectors of models
+     trained for it (Matryoshka embeddings) before normalization,
+   - `encoding_format: "base64"` returns little-endian f32 vectors as base64, as OpenAI does.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
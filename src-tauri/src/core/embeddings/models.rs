@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The `input` of an embeddings request: one or many texts, or pre-tokenized inputs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum EmbeddingInput {
+    Text(String),
+    Texts(Vec<String>),
+    Tokens(Vec<u32>),
+    TokenBatches(Vec<Vec<u32>>),
+}
+
+impl EmbeddingInput {
+    /// Individual inputs as they are sent to the engine
+    pub fn into_items(self) -> Vec<Value> {
+        match self {
+            EmbeddingInput::Text(text) => vec![Value::from(text)],
+            EmbeddingInput::Texts(texts) => texts.into_iter().map(Value::from).collect(),
+            EmbeddingInput::Tokens(tokens) => vec![Value::from(tokens)],
+            EmbeddingInput::TokenBatches(batches) => batches.into_iter().map(Value::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EncodingFormat {
+    #[default]
+    Float,
+    Base64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmbeddingRequest {
+    pub model: String,
+    pub input: EmbeddingInput,
+    #[serde(default)]
+    pub encoding_format: EncodingFormat,
+    /// Keep only the first `dimensions` components of each vector
+    #[serde(default)]
+    pub dimensions: Option<usize>,
+    /// L2-normalize each vector after truncation
+    #[serde(default)]
+    pub normalize: bool,
+    /// Inputs per engine request
+    #[serde(default)]
+    pub batch_size: Option<usize>,
+}
+
+/// Vector of an embedding, as floats or as base64 of little-endian f32s
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum EmbeddingVector {
+    Float(Vec<f32>),
+    Base64(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Embedding {
+    pub object: String,
+    pub index: usize,
+    pub embedding: EmbeddingVector,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmbeddingUsage {
+    pub prompt_tokens: u64,
+    pub total_tokens: u64,
+}
+
+/// OpenAI-compatible embeddings response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmbeddingResponse {
+    pub object: String,
+    pub data: Vec<Embedding>,
+    pub model: String,
+    pub usage: EmbeddingUsage,
+}
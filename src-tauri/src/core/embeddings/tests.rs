@@ -0,0 +1,79 @@
+use base64::Engine;
+use serde_json::{json, Value};
+
+use super::constants::MAX_BATCH_TOKENS;
+use super::helpers::*;
+use super::models::{EmbeddingInput, EmbeddingRequest, EmbeddingVector, EncodingFormat};
+
+#[test]
+fn test_request_inputs() {
+    let request: EmbeddingRequest =
+        serde_json::from_value(json!({"model": "m", "input": "hello"})).unwrap();
+    assert_eq!(request.input, EmbeddingInput::Text("hello".to_string()));
+    assert_eq!(request.encoding_format, EncodingFormat::Float);
+    assert!(!request.normalize);
+
+    let request: EmbeddingRequest = serde_json::from_value(json!({
+        "model": "m",
+        "input": [[1, 2], [3]],
+        "encoding_format": "base64",
+        "normalize": true
+    }))
+    .unwrap();
+    assert_eq!(request.encoding_format, EncodingFormat::Base64);
+    assert_eq!(request.input.into_items(), vec![json!([1, 2]), json!([3])]);
+}
+
+#[test]
+fn test_plan_batches() {
+    let items: Vec<Value> = (0..10).map(|i| json!(format!("text {i}"))).collect();
+    assert_eq!(plan_batches(&items, 4), vec![0..4, 4..8, 8..10]);
+    assert_eq!(plan_batches(&items, 0).len(), 10);
+    assert!(plan_batches(&[], 4).is_empty());
+
+    // Long inputs close a batch early; an oversized one goes on its own
+    let long = json!("x".repeat(MAX_BATCH_TOKENS * 4));
+    let items = vec![json!("a"), long, json!("b"), json!("c")];
+    assert_eq!(plan_batches(&items, 32), vec![0..1, 1..2, 2..4]);
+}
+
+#[test]
+fn test_finish_vector() {
+    let vector = finish_vector(vec![3.0, 4.0, 12.0], Some(2), true, EncodingFormat::Float);
+    assert_eq!(vector, EmbeddingVector::Float(vec![0.6, 0.8]));
+
+    let EmbeddingVector::Base64(data) =
+        finish_vector(vec![1.0, -2.0], None, false, EncodingFormat::Base64)
+    else {
+        panic!("expected base64");
+    };
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .unwrap();
+    assert_eq!(&bytes[..4], &1.0f32.to_le_bytes());
+    assert_eq!(&bytes[4..], &(-2.0f32).to_le_bytes());
+
+    let mut zero = vec![0.0, 0.0];
+    l2_normalize(&mut zero);
+    assert_eq!(zero, vec![0.0, 0.0]);
+}
+
+#[test]
+fn test_parse_batch_response() {
+    let response = json!({
+        "data": [
+            {"object": "embedding", "index": 1, "embedding": [0.5, 0.5]},
+            {"object": "embedding", "index": 0, "embedding": [1.0, 0.0]}
+        ],
+        "usage": {"prompt_tokens": 7, "total_tokens": 7}
+    });
+    let (vectors, tokens) = parse_batch_response(&response, 2).unwrap();
+    assert_eq!(vectors, vec![vec![1.0, 0.0], vec![0.5, 0.5]]);
+    assert_eq!(tokens, 7);
+
+    assert!(parse_batch_response(&response, 3)
+        .unwrap_err()
+        .contains("missing"));
+    assert!(parse_batch_response(&response, 1).is_err());
+    assert!(parse_batch_response(&json!({"error": "boom"}), 1).is_err());
+}
@@ -0,0 +1,11 @@
+use tauri::State;
+
+use super::models::EventStats;
+use super::EventCoalescer;
+
+/// Returns how many values of each high-frequency event were sent, held back or dropped as
+/// stale. The interval is changed through `set_settings`.
+#[tauri::command]
+pub fn get_event_stats(coalescer: State<'_, EventCoalescer>) -> Vec<EventStats> {
+    coalescer.stats()
+}
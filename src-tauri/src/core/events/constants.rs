@@ -0,0 +1,7 @@
+use std::time::Duration;
+
+use crate::core::settings::constants::DEFAULT_EVENT_COALESCE_INTERVAL_MS;
+
+/// Used until the settings are loaded
+pub const DEFAULT_COALESCE_INTERVAL: Duration =
+    Duration::from_millis(DEFAULT_EVENT_COALESCE_INTERVAL_MS);
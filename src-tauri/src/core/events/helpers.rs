@@ -0,0 +1,90 @@
+use std::time::{Duration, Instant};
+
+use jan_utils::progress::{ProgressPayload, PROGRESS_EVENT};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use super::{EventCoalescer, Submission};
+use crate::core::settings::helpers::event_settings;
+
+fn emit_value<R: Runtime>(app: &AppHandle<R>, event: &str, value: &Value) {
+    if let Err(e) = app.emit(event, value) {
+        log::warn!("Failed to emit {event}: {e}");
+    }
+}
+
+/// Emit the next value of a high-frequency `event`, such as a progress update. Values of
+/// the same `stream`, e.g. one download task, are rate limited and may be dropped for a newer
+/// one; other streams of the event are unaffected.
+pub fn emit_coalesced<R: Runtime, S: Serialize>(
+    app: &AppHandle<R>,
+    event: &str,
+    stream: &str,
+    payload: S,
+) {
+    let value = match serde_json::to_value(payload) {
+        Ok(value) => value,
+        Err(e) => {
+            log::warn!("Failed to serialize {event}: {e}");
+            return;
+        }
+    };
+    let Some(coalescer) = app.try_state::<EventCoalescer>() else {
+        emit_value(app, event, &value);
+        return;
+    };
+    match coalescer.submit(event, stream, value, Instant::now()) {
+        Submission::Emit(value) => emit_value(app, event, &value),
+        Submission::Flush(delay) => {
+            let coalescer = coalescer.inner().clone();
+            let app = app.clone();
+            let (event, stream) = (event.to_string(), stream.to_string());
+            tauri::async_runtime::spawn(async move {
+                tokio::time::sleep(delay).await;
+                coalescer.flush(&event, &stream, Instant::now(), |value| {
+                    emit_value(&app, &event, value)
+                });
+            });
+        }
+        Submission::Held => {}
+    }
+}
+
+/// Emit the last value of a stream, e.g. final progress or a finished status. It is never
+/// dropped, and a value still held back for the stream is discarded so it can't arrive later.
+pub fn emit_final<R: Runtime, S: Serialize>(
+    app: &AppHandle<R>,
+    event: &str,
+    stream: &str,
+    payload: S,
+) {
+    let value = match serde_json::to_value(payload) {
+        Ok(value) => value,
+        Err(e) => {
+            log::warn!("Failed to serialize {event}: {e}");
+            return;
+        }
+    };
+    match app.try_state::<EventCoalescer>() {
+        Some(coalescer) => coalescer.finish(event, stream, || emit_value(app, event, &value)),
+        None => emit_value(app, event, &value),
+    }
+}
+
+/// Emit the progress of a long-running task on the shared progress event. Updates of a
+/// running task are coalesced per task; its last payload is never dropped.
+pub fn emit_task_progress<R: Runtime>(app: &AppHandle<R>, payload: &ProgressPayload) {
+    let stream = payload.stream();
+    if payload.state.is_final() {
+        emit_final(app, PROGRESS_EVENT, &stream, payload);
+    } else {
+        emit_coalesced(app, PROGRESS_EVENT, &stream, payload);
+    }
+}
+
+/// Apply the stored coalescing interval
+pub fn load_event_settings<R: Runtime>(app: &AppHandle<R>) {
+    let interval = Duration::from_millis(event_settings(app).coalesce_interval_ms);
+    app.state::<EventCoalescer>().set_interval(interval);
+}
@@ -0,0 +1,169 @@
+/*!
+   Event Coalescing
+
+   Download progress and other high-frequency progress events can be emitted hundreds of times
+   per second. They go through `emit_coalesced`, which sends the first value of a stream right
+   away and then at most one value per `events.coalesce_interval_ms`: values arriving in
+   between are held back, and a newer one replaces a held value, which is dropped as stale.
+   The held value is sent once the interval has passed, so the latest progress always reaches
+   the UI.
+
+   Values that must not be dropped, such as the final progress of a download, go through
+   `emit_final`, which discards the held value and sends right away. Emitted, held back and
+   dropped values are counted per event.
+
+   Besides their own events, downloads, model pulls and loads, quantization, imports and
+   knowledge base indexing report their progress through `emit_task_progress` in the shared
+   `jan_utils::progress` format, so progress UIs and screen-reader announcements are built once.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use constants::DEFAULT_COALESCE_INTERVAL;
+use models::EventStats;
+
+/// What to do with a value submitted to a stream
+#[derive(Debug, PartialEq)]
+pub enum Submission {
+    /// Send it now
+    Emit(Value),
+    /// It is held back; flush the stream after the delay
+    Flush(Duration),
+    /// It replaced a held value, whose flush is already scheduled
+    Held,
+}
+
+#[derive(Default)]
+struct Stream {
+    last_emit: Option<Instant>,
+    held: Option<Value>,
+}
+
+struct CoalescerState {
+    interval: Duration,
+    /// Keyed by event name and stream id
+    streams: HashMap<(String, String), Stream>,
+    stats: BTreeMap<String, EventStats>,
+}
+
+impl Default for CoalescerState {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_COALESCE_INTERVAL,
+            streams: HashMap::new(),
+            stats: BTreeMap::new(),
+        }
+    }
+}
+
+impl CoalescerState {
+    fn stats(&mut self, event: &str, stream: &str) -> &mut EventStats {
+        let name = metric_name(event, stream);
+        self.stats
+            .entry(name.to_string())
+            .or_insert_with(|| EventStats {
+                event: name.to_string(),
+                ..Default::default()
+            })
+    }
+}
+
+/// Event names that embed the stream id, like `download-{task}`, are counted without it
+fn metric_name<'a>(event: &'a str, stream: &str) -> &'a str {
+    if stream.is_empty() {
+        return event;
+    }
+    event
+        .strip_suffix(stream)
+        .map(|prefix| prefix.trim_end_matches(['-', ':', '/']))
+        .filter(|prefix| !prefix.is_empty())
+        .unwrap_or(event)
+}
+
+/// Rate limits high-frequency events per stream, cheap to clone
+#[derive(Clone, Default)]
+pub struct EventCoalescer {
+    state: Arc<Mutex<CoalescerState>>,
+}
+
+impl EventCoalescer {
+    fn state(&self) -> MutexGuard<'_, CoalescerState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Shortest time between two values of a stream; zero sends every value
+    pub fn set_interval(&self, interval: Duration) {
+        self.state().interval = interval;
+    }
+
+    /// Submit the next `value` of `stream` of `event`
+    pub fn submit(&self, event: &str, stream: &str, value: Value, now: Instant) -> Submission {
+        let mut state = self.state();
+        let interval = state.interval;
+        let key = (event.to_string(), stream.to_string());
+        let entry = state.streams.entry(key).or_default();
+        if entry.held.is_some() {
+            entry.held = Some(value);
+            state.stats(event, stream).dropped += 1;
+            return Submission::Held;
+        }
+        let since_last = entry.last_emit.map(|at| now.saturating_duration_since(at));
+        match since_last {
+            Some(elapsed) if elapsed < interval => {
+                entry.held = Some(value);
+                state.stats(event, stream).coalesced += 1;
+                Submission::Flush(interval - elapsed)
+            }
+            _ => {
+                entry.last_emit = Some(now);
+                state.stats(event, stream).emitted += 1;
+                Submission::Emit(value)
+            }
+        }
+    }
+
+    /// Send the held value of a stream through `emit`, if it still has one. `emit` runs under
+    /// the lock, so it can't race with `finish`.
+    pub fn flush(&self, event: &str, stream: &str, now: Instant, emit: impl FnOnce(&Value)) {
+        let mut state = self.state();
+        let key = (event.to_string(), stream.to_string());
+        let Some(entry) = state.streams.get_mut(&key) else {
+            return;
+        };
+        let Some(value) = entry.held.take() else {
+            return;
+        };
+        entry.last_emit = Some(now);
+        emit(&value);
+        state.stats(event, stream).emitted += 1;
+    }
+
+    /// Close a stream: its held value is dropped and `emit` sends the final one instead.
+    /// `emit` runs under the lock, so a scheduled flush can't overtake it.
+    pub fn finish(&self, event: &str, stream: &str, emit: impl FnOnce()) {
+        let mut state = self.state();
+        let key = (event.to_string(), stream.to_string());
+        if let Some(Stream { held: Some(_), .. }) = state.streams.remove(&key) {
+            state.stats(event, stream).dropped += 1;
+        }
+        emit();
+        state.stats(event, stream).emitted += 1;
+    }
+
+    /// Counters of every event sent through the coalescer, by event name
+    pub fn stats(&self) -> Vec<EventStats> {
+        self.state().stats.values().cloned().collect()
+    }
+}
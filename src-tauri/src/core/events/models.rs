@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// Counters of one coalesced event since the app started
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventStats {
+    pub event: String,
+    /// Values sent to the UI
+    pub emitted: u64,
+    /// Values held back and sent once their interval passed
+    pub coalesced: u64,
+    /// Stale values replaced by a newer one before they were sent
+    pub dropped: u64,
+}
@@ -0,0 +1,120 @@
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+use super::{metric_name, EventCoalescer, Submission};
+
+const INTERVAL: Duration = Duration::from_millis(100);
+
+fn coalescer() -> EventCoalescer {
+    let coalescer = EventCoalescer::default();
+    coalescer.set_interval(INTERVAL);
+    coalescer
+}
+
+fn flushed(coalescer: &EventCoalescer, event: &str, stream: &str, now: Instant) -> Option<Value> {
+    let mut sent = None;
+    coalescer.flush(event, stream, now, |value| sent = Some(value.clone()));
+    sent
+}
+
+#[test]
+fn test_values_within_an_interval_are_coalesced_to_the_latest() {
+    let coalescer = coalescer();
+    let start = Instant::now();
+    let submit = |value: u64, after: u64| {
+        coalescer.submit(
+            "download-a",
+            "a",
+            json!(value),
+            start + Duration::from_millis(after),
+        )
+    };
+
+    assert_eq!(submit(1, 0), Submission::Emit(json!(1)));
+    assert_eq!(submit(2, 30), Submission::Flush(Duration::from_millis(70)));
+    assert_eq!(submit(3, 60), Submission::Held);
+    let end = start + INTERVAL;
+    assert_eq!(flushed(&coalescer, "download-a", "a", end), Some(json!(3)));
+    assert_eq!(flushed(&coalescer, "download-a", "a", end), None);
+
+    // The next value waits for a full interval after the flushed one
+    assert_eq!(submit(4, 150), Submission::Flush(Duration::from_millis(50)));
+    assert_eq!(submit(5, 250), Submission::Held);
+
+    let stats = coalescer.stats();
+    assert_eq!(stats.len(), 1);
+    assert_eq!(stats[0].event, "download");
+    assert_eq!(
+        (stats[0].emitted, stats[0].coalesced, stats[0].dropped),
+        (2, 2, 2)
+    );
+}
+
+#[test]
+fn test_streams_of_one_event_are_independent() {
+    let coalescer = coalescer();
+    let now = Instant::now();
+    let event = "ollama-pull-progress";
+    assert!(matches!(
+        coalescer.submit(event, "llama", json!(1), now),
+        Submission::Emit(_)
+    ));
+    assert!(matches!(
+        coalescer.submit(event, "qwen", json!(1), now),
+        Submission::Emit(_)
+    ));
+    assert!(matches!(
+        coalescer.submit(event, "llama", json!(2), now),
+        Submission::Flush(_)
+    ));
+    assert_eq!(coalescer.stats()[0].event, event);
+}
+
+#[test]
+fn test_finish_discards_the_held_value() {
+    let coalescer = coalescer();
+    let now = Instant::now();
+    coalescer.submit("quantize", "job", json!(1), now);
+    coalescer.submit("quantize", "job", json!(2), now);
+
+    let mut finished = false;
+    coalescer.finish("quantize", "job", || finished = true);
+    assert!(finished);
+    // The scheduled flush finds nothing to send, so it can't arrive after the final value
+    assert_eq!(flushed(&coalescer, "quantize", "job", now + INTERVAL), None);
+    // A finished stream starts over
+    assert!(matches!(
+        coalescer.submit("quantize", "job", json!(3), now),
+        Submission::Emit(_)
+    ));
+    let stats = &coalescer.stats()[0];
+    assert_eq!((stats.emitted, stats.dropped), (3, 1));
+}
+
+#[test]
+fn test_zero_interval_sends_every_value() {
+    let coalescer = coalescer();
+    coalescer.set_interval(Duration::ZERO);
+    let now = Instant::now();
+    for value in 0..3 {
+        assert_eq!(
+            coalescer.submit("import", "", json!(value), now),
+            Submission::Emit(json!(value))
+        );
+    }
+}
+
+#[test]
+fn test_metric_name_strips_the_stream_id() {
+    assert_eq!(metric_name("download-abc", "abc"), "download");
+    assert_eq!(
+        metric_name("ollama-pull-progress", "llama3"),
+        "ollama-pull-progress"
+    );
+    assert_eq!(
+        metric_name("chat-import-progress", ""),
+        "chat-import-progress"
+    );
+    assert_eq!(metric_name("abc", "abc"), "abc");
+}
@@ -0,0 +1,11 @@
+use tauri::State;
+
+use super::models::GuardrailStatus;
+use super::Guardrails;
+
+/// Returns the guardrail settings in effect, the tokens each budget key used in the last
+/// hour and the most recent violations. Limits are changed through `set_settings`.
+#[tauri::command]
+pub fn get_guardrail_status(guardrails: State<'_, Guardrails>) -> GuardrailStatus {
+    guardrails.status()
+}
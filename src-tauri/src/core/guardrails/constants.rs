@@ -0,0 +1,12 @@
+use std::time::Duration;
+
+// Guardrail constants
+/// Emitted with a `GuardrailViolation` whenever a request is changed or refused
+pub const GUARDRAIL_VIOLATION_EVENT: &str = "guardrail-violation";
+/// Window of the per-key token budget
+pub const BUDGET_WINDOW: Duration = Duration::from_secs(3_600);
+/// Violations kept for `get_guardrail_status`
+pub const MAX_RECENT_VIOLATIONS: usize = 100;
+/// Budget key of agent runs started in the app
+pub const AGENT_BUDGET_KEY: &str = "agent";
+pub const ANONYMOUS_BUDGET_KEY: &str = "anonymous";
@@ -0,0 +1,136 @@
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use super::constants::{ANONYMOUS_BUDGET_KEY, GUARDRAIL_VIOLATION_EVENT};
+use super::models::{Enforcement, ViolationKind};
+use super::Guardrails;
+use crate::core::peers::helpers::hash_token;
+use crate::core::settings::helpers::guardrail_settings;
+use crate::core::settings::models::GuardrailSettings;
+
+/// Budget key of an API request: the LAN peer, else a hash prefix of the API key
+pub fn budget_key(peer: Option<&str>, authorization: Option<&str>) -> String {
+    if let Some(peer) = peer {
+        return format!("peer:{peer}");
+    }
+    match authorization
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+    {
+        Some(token) => format!("key:{}", &hash_token(token)[..12]),
+        None => ANONYMOUS_BUDGET_KEY.to_string(),
+    }
+}
+
+/// Remove the banned sequences from `stop` (OpenAI) and `stop_sequences` (Anthropic).
+/// Returns the removed ones.
+pub fn strip_stop_sequences(body: &mut Value, banned: &[String]) -> Vec<String> {
+    let mut removed = Vec::new();
+    let Some(object) = body.as_object_mut() else {
+        return removed;
+    };
+    for key in ["stop", "stop_sequences"] {
+        let emptied = match object.get_mut(key) {
+            Some(Value::String(stop)) if banned.contains(stop) => {
+                removed.push(stop.clone());
+                true
+            }
+            Some(Value::Array(stops)) => {
+                stops.retain(|stop| match stop.as_str() {
+                    Some(stop) if banned.iter().any(|b| b == stop) => {
+                        removed.push(stop.to_string());
+                        false
+                    }
+                    _ => true,
+                });
+                stops.is_empty()
+            }
+            _ => false,
+        };
+        if emptied {
+            object.remove(key);
+        }
+    }
+    removed
+}
+
+/// Apply the guardrails to a request body, given the tokens its key used this hour.
+/// Fails when the budget is used up.
+pub fn enforce_request(
+    settings: &GuardrailSettings,
+    used: u64,
+    body: &mut Value,
+) -> Result<Enforcement, String> {
+    let mut enforcement = Enforcement::default();
+    if !settings.enabled || !body.is_object() {
+        return Ok(enforcement);
+    }
+    let remaining = match settings.max_tokens_per_hour {
+        0 => None,
+        limit if used >= limit => {
+            return Err(format!(
+                "The hourly budget of {limit} output tokens is used up"
+            ));
+        }
+        limit => Some(limit - used),
+    };
+
+    for stop in strip_stop_sequences(body, &settings.banned_stop_sequences) {
+        enforcement.violations.push((
+            ViolationKind::StopSequenceRemoved,
+            format!("Removed stop sequence {stop:?}"),
+        ));
+        enforcement.changed = true;
+    }
+
+    let field = if body.get("max_completion_tokens").is_some() {
+        "max_completion_tokens"
+    } else {
+        "max_tokens"
+    };
+    let requested = body.get(field).and_then(Value::as_u64);
+    let per_request =
+        (settings.max_tokens_per_request > 0).then_some(settings.max_tokens_per_request);
+    let cap = match (per_request, remaining) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (cap, None) | (None, cap) => cap,
+    };
+    enforcement.reserved = match (requested, cap) {
+        (Some(requested), Some(cap)) if requested > cap => {
+            enforcement.violations.push((
+                ViolationKind::MaxTokensClamped,
+                format!("{field} {requested} -> {cap}"),
+            ));
+            body[field] = Value::from(cap);
+            enforcement.changed = true;
+            cap
+        }
+        (Some(requested), _) => requested,
+        (None, Some(cap)) => {
+            body[field] = Value::from(cap);
+            enforcement.changed = true;
+            cap
+        }
+        (None, None) => 0,
+    };
+    Ok(enforcement)
+}
+
+/// Apply the stored settings and announce violations in the app
+pub fn load_guardrails<R: Runtime>(app: &AppHandle<R>) {
+    let guardrails = app.state::<Guardrails>();
+    guardrails.set_settings(guardrail_settings(app));
+    let app = app.clone();
+    guardrails.set_violation_listener(move |violation| {
+        log::warn!(
+            "Guardrail {:?} for {}: {}",
+            violation.kind,
+            violation.key,
+            violation.detail
+        );
+        if let Err(e) = app.emit(GUARDRAIL_VIOLATION_EVENT, violation) {
+            log::warn!("Failed to emit {GUARDRAIL_VIOLATION_EVENT}: {e}");
+        }
+    });
+}
@@ -0,0 +1,156 @@
+/*!
+   Token Guardrails
+
+   Hard caps enforced in the core, so neither an external API client nor a runaway agent loop
+   can run up a paid provider's bill:
+   - `max_tokens` of every request is capped at `guardrails.max_tokens_per_request`, and set
+     to the cap when the request leaves it out,
+   - each API key, LAN peer and the app's agent runs get `guardrails.max_tokens_per_hour`
+     output tokens per rolling hour; further requests are refused until it frees up,
+   - stop sequences listed in `guardrails.banned_stop_sequences` are removed from requests.
+
+   The budget counts the `max_tokens` each request may use rather than what it used, so it
+   holds even when a provider streams without reporting usage. Every clamp, removal and
+   refusal is emitted as a `guardrail-violation` event.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+
+use serde_json::Value;
+
+use crate::core::settings::models::GuardrailSettings;
+use constants::{BUDGET_WINDOW, MAX_RECENT_VIOLATIONS};
+use helpers::enforce_request;
+use models::{GuardrailStatus, GuardrailViolation, KeyUsage, ViolationKind};
+
+pub type ViolationListener = Arc<dyn Fn(&GuardrailViolation) + Send + Sync>;
+
+#[derive(Default)]
+struct GuardrailState {
+    settings: GuardrailSettings,
+    /// Tokens reserved per budget key, oldest first
+    usage: HashMap<String, VecDeque<(Instant, u64)>>,
+    recent: VecDeque<GuardrailViolation>,
+    listener: Option<ViolationListener>,
+}
+
+impl GuardrailState {
+    fn used(&mut self, key: &str, now: Instant) -> u64 {
+        let Some(entries) = self.usage.get_mut(key) else {
+            return 0;
+        };
+        while entries
+            .front()
+            .is_some_and(|(at, _)| now.duration_since(*at) >= BUDGET_WINDOW)
+        {
+            entries.pop_front();
+        }
+        entries.iter().map(|(_, tokens)| tokens).sum()
+    }
+
+    fn record(&mut self, key: &str, kind: ViolationKind, detail: String) -> GuardrailViolation {
+        let violation = GuardrailViolation {
+            kind,
+            key: key.to_string(),
+            detail,
+            at: chrono::Utc::now().timestamp_millis(),
+        };
+        if self.recent.len() == MAX_RECENT_VIOLATIONS {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(violation.clone());
+        violation
+    }
+}
+
+/// Guardrail settings and token budgets, cheap to clone
+#[derive(Clone, Default)]
+pub struct Guardrails {
+    state: Arc<Mutex<GuardrailState>>,
+}
+
+impl Guardrails {
+    fn state(&self) -> MutexGuard<'_, GuardrailState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn set_settings(&self, settings: GuardrailSettings) {
+        self.state().settings = settings;
+    }
+
+    /// Called with every violation, outside of the guardrail lock
+    pub fn set_violation_listener(
+        &self,
+        listener: impl Fn(&GuardrailViolation) + Send + Sync + 'static,
+    ) {
+        self.state().listener = Some(Arc::new(listener));
+    }
+
+    /// Enforce the guardrails on a request body of budget `key` and reserve its tokens.
+    /// Returns whether the body was changed, or why the request is refused.
+    pub fn check(&self, key: &str, body: &mut Value) -> Result<bool, String> {
+        let now = Instant::now();
+        let (result, violations, listener) = {
+            let mut state = self.state();
+            let used = state.used(key, now);
+            let settings = state.settings.clone();
+            match enforce_request(&settings, used, body) {
+                Ok(enforcement) => {
+                    if enforcement.reserved > 0 && settings.max_tokens_per_hour > 0 {
+                        state
+                            .usage
+                            .entry(key.to_string())
+                            .or_default()
+                            .push_back((now, enforcement.reserved));
+                    }
+                    let violations: Vec<_> = enforcement
+                        .violations
+                        .into_iter()
+                        .map(|(kind, detail)| state.record(key, kind, detail))
+                        .collect();
+                    (Ok(enforcement.changed), violations, state.listener.clone())
+                }
+                Err(e) => {
+                    let violation = state.record(key, ViolationKind::BudgetExceeded, e.clone());
+                    (Err(e), vec![violation], state.listener.clone())
+                }
+            }
+        };
+        if let Some(listener) = listener {
+            for violation in &violations {
+                listener(violation);
+            }
+        }
+        result
+    }
+
+    pub fn status(&self) -> GuardrailStatus {
+        let now = Instant::now();
+        let mut state = self.state();
+        let keys: Vec<String> = state.usage.keys().cloned().collect();
+        let mut usage: Vec<KeyUsage> = keys
+            .into_iter()
+            .map(|key| KeyUsage {
+                used_tokens: state.used(&key, now),
+                key,
+            })
+            .filter(|usage| usage.used_tokens > 0)
+            .collect();
+        usage.sort_by(|a, b| a.key.cmp(&b.key));
+        GuardrailStatus {
+            settings: state.settings.clone(),
+            usage,
+            recent_violations: state.recent.iter().cloned().collect(),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::settings::models::GuardrailSettings;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ViolationKind {
+    /// `max_tokens` was lowered to the per-request cap or the remaining budget
+    MaxTokensClamped,
+    StopSequenceRemoved,
+    /// The hourly budget was used up; the request was refused
+    BudgetExceeded,
+}
+
+/// Payload of `guardrail-violation`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GuardrailViolation {
+    pub kind: ViolationKind,
+    /// Budget key: `agent`, `peer:<name>`, `key:<hash prefix>` or `anonymous`
+    pub key: String,
+    pub detail: String,
+    /// Milliseconds since epoch
+    pub at: i64,
+}
+
+/// What enforcing the guardrails did to a request body
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Enforcement {
+    /// Output tokens counted against the budget
+    pub reserved: u64,
+    pub violations: Vec<(ViolationKind, String)>,
+    pub changed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyUsage {
+    pub key: String,
+    /// Output tokens reserved in the last hour
+    pub used_tokens: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GuardrailStatus {
+    pub settings: GuardrailSettings,
+    pub usage: Vec<KeyUsage>,
+    /// Newest last
+    pub recent_violations: Vec<GuardrailViolation>,
+}
@@ -0,0 +1,113 @@
+use serde_json::json;
+
+use super::constants::ANONYMOUS_BUDGET_KEY;
+use super::helpers::{budget_key, enforce_request, strip_stop_sequences};
+use super::models::ViolationKind;
+use super::Guardrails;
+use crate::core::settings::models::GuardrailSettings;
+
+fn settings(per_request: u64, per_hour: u64) -> GuardrailSettings {
+    GuardrailSettings {
+        enabled: true,
+        max_tokens_per_request: per_request,
+        max_tokens_per_hour: per_hour,
+        banned_stop_sequences: vec!["</s>".to_string()],
+    }
+}
+
+#[test]
+fn test_budget_key() {
+    assert_eq!(
+        budget_key(Some("laptop"), Some("Bearer abc")),
+        "peer:laptop"
+    );
+    let key = budget_key(None, Some("Bearer abc"));
+    assert!(key.starts_with("key:"));
+    assert_eq!(key.len(), "key:".len() + 12);
+    assert_ne!(key, budget_key(None, Some("Bearer abd")));
+    assert_eq!(budget_key(None, Some("Bearer ")), ANONYMOUS_BUDGET_KEY);
+    assert_eq!(budget_key(None, None), ANONYMOUS_BUDGET_KEY);
+}
+
+#[test]
+fn test_strip_stop_sequences() {
+    let banned = vec!["</s>".to_string()];
+    let mut body = json!({"stop": ["</s>", "\n\n"], "stop_sequences": "</s>"});
+    assert_eq!(
+        strip_stop_sequences(&mut body, &banned),
+        vec!["</s>".to_string(), "</s>".to_string()]
+    );
+    assert_eq!(body, json!({"stop": ["\n\n"]}));
+
+    let mut body = json!({"stop": ["</s>"]});
+    strip_stop_sequences(&mut body, &banned);
+    assert_eq!(body, json!({}));
+    let mut body = json!({"stop": "END"});
+    assert!(strip_stop_sequences(&mut body, &banned).is_empty());
+}
+
+#[test]
+fn test_enforce_request() {
+    let mut body = json!({"max_tokens": 5000, "stop": "</s>"});
+    let enforcement = enforce_request(&settings(1000, 10_000), 0, &mut body).unwrap();
+    assert_eq!(body, json!({"max_tokens": 1000}));
+    assert_eq!(enforcement.reserved, 1000);
+    assert!(enforcement.changed);
+    let kinds: Vec<_> = enforcement.violations.iter().map(|v| v.0).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            ViolationKind::StopSequenceRemoved,
+            ViolationKind::MaxTokensClamped
+        ]
+    );
+
+    // A missing cap is set to what is left of the budget
+    let mut body = json!({"max_completion_tokens": null});
+    let enforcement = enforce_request(&settings(1000, 10_000), 9_800, &mut body).unwrap();
+    assert_eq!(body["max_completion_tokens"], 200);
+    assert!(enforcement.violations.is_empty());
+
+    let mut body = json!({"max_tokens": 10});
+    let enforcement = enforce_request(&settings(0, 0), 0, &mut body).unwrap();
+    assert_eq!(enforcement.reserved, 10);
+    assert!(!enforcement.changed);
+
+    assert!(enforce_request(&settings(1000, 10_000), 10_000, &mut json!({})).is_err());
+    let disabled = GuardrailSettings {
+        enabled: false,
+        ..settings(1, 1)
+    };
+    assert_eq!(
+        enforce_request(&disabled, 5, &mut json!({"max_tokens": 50}))
+            .unwrap()
+            .reserved,
+        0
+    );
+}
+
+#[test]
+fn test_guardrails_budget_per_key() {
+    let guardrails = Guardrails::default();
+    guardrails.set_settings(settings(600, 1000));
+    assert!(guardrails.check("a", &mut json!({})).unwrap());
+    let mut body = json!({"max_tokens": 600});
+    assert!(guardrails.check("a", &mut body).unwrap());
+    assert_eq!(body["max_tokens"], 400);
+    assert!(guardrails.check("a", &mut json!({})).is_err());
+    assert!(guardrails
+        .check("b", &mut json!({"max_tokens": 10}))
+        .is_ok());
+
+    let status = guardrails.status();
+    let used: Vec<_> = status
+        .usage
+        .iter()
+        .map(|usage| (usage.key.as_str(), usage.used_tokens))
+        .collect();
+    assert_eq!(used, vec![("a", 1000), ("b", 10)]);
+    assert_eq!(
+        status.recent_violations.last().unwrap().kind,
+        ViolationKind::BudgetExceeded
+    );
+}
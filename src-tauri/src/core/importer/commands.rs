@@ -0,0 +1,141 @@
+use std::path::PathBuf;
+
+use jan_utils::progress::{EtaEstimator, ProgressOperation, ProgressPayload, ProgressState};
+use tauri::{AppHandle, Runtime};
+use uuid::Uuid;
+
+use super::constants::IMPORT_PROGRESS_EVENT;
+use super::helpers::{
+    imported_keys, load_conversations, messages_json, progress_payload, thread_json,
+};
+use super::models::{
+    ImportProgress, ImportSource, ImportStage, ImportSummary, ImportedConversation,
+};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::events::helpers::{emit_coalesced, emit_final, emit_task_progress};
+use crate::core::threads::branches::{write_branch_state, BranchState};
+use crate::core::threads::commands::{create_message, create_thread, delete_thread, list_threads};
+use crate::core::threads::helpers::should_use_sqlite;
+
+fn emit_progress<R: Runtime>(app: &AppHandle<R>, progress: ImportProgress, eta: Option<u64>) {
+    emit_task_progress(app, &progress_payload(&progress, eta));
+    let import_id = progress.import_id.clone();
+    if progress.stage == ImportStage::Done {
+        emit_final(app, IMPORT_PROGRESS_EVENT, &import_id, progress);
+    } else {
+        emit_coalesced(app, IMPORT_PROGRESS_EVENT, &import_id, progress);
+    }
+}
+
+/// Writes one conversation as a new thread and returns the thread id.
+/// A thread that cannot be completed is removed again so a retry does not see it as imported.
+async fn import_conversation<R: Runtime>(
+    app: &AppHandle<R>,
+    source: ImportSource,
+    conversation: &ImportedConversation,
+) -> Result<String, String> {
+    let thread = create_thread(app.clone(), thread_json(source, conversation)).await?;
+    let thread_id = thread["id"]
+        .as_str()
+        .ok_or_else(|| "Created thread has no id".to_string())?
+        .to_string();
+
+    let (messages, active_leaf) = messages_json(source, &thread_id, conversation);
+    for message in messages {
+        if let Err(e) = create_message(app.clone(), message).await {
+            let _ = delete_thread(app.clone(), thread_id.clone()).await;
+            return Err(e);
+        }
+    }
+    if !should_use_sqlite() {
+        write_branch_state(
+            &get_jan_data_folder_path(app.clone()),
+            &thread_id,
+            &BranchState {
+                active_leaf_id: active_leaf,
+            },
+        )?;
+    }
+    Ok(thread_id)
+}
+
+/// Imports conversations exported from another chat app into Jan's threads.
+/// Conversations brought in by an earlier import are skipped. Progress is reported through
+/// `chat-import-progress` events tagged with the returned `import_id`.
+#[tauri::command]
+pub async fn import_chat_history<R: Runtime>(
+    app_handle: AppHandle<R>,
+    source: ImportSource,
+    path: String,
+) -> Result<ImportSummary, String> {
+    let import_id = Uuid::new_v4().to_string();
+    let progress = |stage, processed, total| ImportProgress {
+        import_id: import_id.clone(),
+        source,
+        stage,
+        processed,
+        total,
+    };
+
+    emit_progress(&app_handle, progress(ImportStage::Parsing, 0, 0), None);
+    let path = PathBuf::from(path);
+    let loaded = async {
+        let conversations = tokio::task::spawn_blocking(move || load_conversations(source, &path))
+            .await
+            .map_err(|e| e.to_string())??;
+        let existing = imported_keys(&list_threads(app_handle.clone()).await?);
+        Ok::<_, String>((conversations, existing))
+    }
+    .await;
+    let (conversations, existing) = match loaded {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            emit_task_progress(
+                &app_handle,
+                &ProgressPayload::finished(
+                    ProgressOperation::Import,
+                    &import_id,
+                    source.as_str(),
+                    ProgressState::Failed,
+                ),
+            );
+            return Err(e);
+        }
+    };
+
+    let total = conversations.len();
+    let mut summary = ImportSummary {
+        import_id: import_id.clone(),
+        ..Default::default()
+    };
+    let eta = EtaEstimator::new(0);
+    for (index, conversation) in conversations.iter().enumerate() {
+        emit_progress(
+            &app_handle,
+            progress(ImportStage::Importing, index, total),
+            eta.eta(index as u64, total as u64),
+        );
+        let key = (source.as_str().to_string(), conversation.source_id.clone());
+        if conversation.messages.is_empty() || existing.contains(&key) {
+            summary.skipped += 1;
+            continue;
+        }
+        match import_conversation(&app_handle, source, conversation).await {
+            Ok(thread_id) => summary.thread_ids.push(thread_id),
+            Err(e) => {
+                log::warn!("Failed to import conversation {}: {e}", conversation.title);
+                summary.failed.push(format!("{}: {e}", conversation.title));
+            }
+        }
+    }
+    emit_progress(&app_handle, progress(ImportStage::Done, total, total), None);
+
+    log::info!(
+        "Imported {} {} conversations ({} skipped, {} failed)",
+        summary.thread_ids.len(),
+        source.as_str(),
+        summary.skipped,
+        summary.failed.len()
+    );
+    Ok(summary)
+}
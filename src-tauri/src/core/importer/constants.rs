@@ -0,0 +1,10 @@
+pub const IMPORT_PROGRESS_EVENT: &str = "chat-import-progress";
+
+/// Metadata key recording the origin of an imported thread or message
+pub const IMPORT_METADATA_KEY: &str = "import";
+
+pub const LMSTUDIO_CONVERSATION_SUFFIX: &str = ".conversation.json";
+
+pub const OLLAMA_HISTORY_TITLE: &str = "Ollama history";
+/// Source id of the single thread created from Ollama's prompt history
+pub const OLLAMA_HISTORY_ID: &str = "history";
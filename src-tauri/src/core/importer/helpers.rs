@@ -0,0 +1,504 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::Path;
+
+use jan_utils::progress::{ProgressOperation, ProgressPayload, ProgressState, ProgressUnit};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use super::constants::{
+    IMPORT_METADATA_KEY, LMSTUDIO_CONVERSATION_SUFFIX, OLLAMA_HISTORY_ID, OLLAMA_HISTORY_TITLE,
+};
+use super::models::{
+    ImportProgress, ImportSource, ImportStage, ImportedAttachment, ImportedConversation,
+    ImportedMessage,
+};
+
+/// Import progress in the shared progress format
+pub fn progress_payload(progress: &ImportProgress, eta_secs: Option<u64>) -> ProgressPayload {
+    let operation = ProgressOperation::Import;
+    let (id, name) = (progress.import_id.as_str(), progress.source.as_str());
+    match progress.stage {
+        ImportStage::Parsing => ProgressPayload::running(operation, id, name, "parsing"),
+        ImportStage::Importing => ProgressPayload::running(operation, id, name, "importing")
+            .with_amount(
+                progress.processed as u64,
+                progress.total as u64,
+                ProgressUnit::Items,
+            )
+            .with_eta(eta_secs),
+        ImportStage::Done => {
+            ProgressPayload::finished(operation, id, name, ProgressState::Completed)
+        }
+    }
+}
+
+fn seconds_to_millis(value: &Value) -> Option<i64> {
+    value.as_f64().map(|secs| (secs * 1000.0) as i64)
+}
+
+/// Map a source role onto Jan's roles; tool output and unknown roles are not imported.
+fn map_role(role: &str) -> Option<&'static str> {
+    match role {
+        "user" => Some("user"),
+        "assistant" => Some("assistant"),
+        "system" => Some("system"),
+        _ => None,
+    }
+}
+
+/// Parse a ChatGPT `conversations.json` export.
+pub fn parse_chatgpt_export(content: &str) -> Result<Vec<ImportedConversation>, String> {
+    let value: Value =
+        serde_json::from_str(content).map_err(|e| format!("Invalid ChatGPT export: {e}"))?;
+    let conversations = value
+        .as_array()
+        .ok_or_else(|| "Invalid ChatGPT export: expected a list of conversations".to_string())?;
+    Ok(conversations
+        .iter()
+        .filter_map(parse_chatgpt_conversation)
+        .collect())
+}
+
+fn parse_chatgpt_conversation(conversation: &Value) -> Option<ImportedConversation> {
+    let source_id = conversation
+        .get("conversation_id")
+        .or_else(|| conversation.get("id"))
+        .and_then(Value::as_str)?
+        .to_string();
+    let mapping = conversation.get("mapping")?.as_object()?;
+
+    // Walk the tree breadth-first so parents are emitted before their children. Nodes that
+    // are not imported (hidden system prompts, tool output) are skipped and their children
+    // attach to the nearest imported ancestor.
+    let mut queue: VecDeque<(&str, Option<String>)> = mapping
+        .iter()
+        .filter(|(_, node)| {
+            node.get("parent")
+                .and_then(Value::as_str)
+                .map_or(true, |parent| !mapping.contains_key(parent))
+        })
+        .map(|(id, _)| (id.as_str(), None))
+        .collect();
+    let mut kept = HashMap::new();
+    let mut messages = Vec::new();
+    while let Some((node_id, parent)) = queue.pop_front() {
+        let Some(node) = mapping.get(node_id) else {
+            continue;
+        };
+        let own = node
+            .get("message")
+            .and_then(|message| parse_chatgpt_message(node_id, message, parent.clone()));
+        let next_parent = match own {
+            Some(message) => {
+                let id = message.source_id.clone();
+                kept.insert(node_id.to_string(), id.clone());
+                messages.push(message);
+                Some(id)
+            }
+            None => parent,
+        };
+        for child in node
+            .get("children")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            queue.push_back((child, next_parent.clone()));
+        }
+    }
+    if messages.is_empty() {
+        return None;
+    }
+
+    // The current node may be a skipped one; fall back to its nearest imported ancestor
+    let mut active_leaf = None;
+    let mut cursor = conversation.get("current_node").and_then(Value::as_str);
+    while let Some(node_id) = cursor {
+        if let Some(id) = kept.get(node_id) {
+            active_leaf = Some(id.clone());
+            break;
+        }
+        cursor = mapping
+            .get(node_id)
+            .and_then(|node| node.get("parent"))
+            .and_then(Value::as_str);
+    }
+
+    Some(ImportedConversation {
+        source_id,
+        title: conversation
+            .get("title")
+            .and_then(Value::as_str)
+            .filter(|title| !title.trim().is_empty())
+            .unwrap_or("Imported conversation")
+            .to_string(),
+        created: conversation
+            .get("create_time")
+            .and_then(Value::as_f64)
+            .map(|secs| secs as i64),
+        updated: conversation
+            .get("update_time")
+            .and_then(Value::as_f64)
+            .map(|secs| secs as i64),
+        messages,
+        active_leaf,
+    })
+}
+
+fn parse_chatgpt_message(
+    node_id: &str,
+    message: &Value,
+    parent_source_id: Option<String>,
+) -> Option<ImportedMessage> {
+    let role = map_role(message.pointer("/author/role")?.as_str()?)?;
+    let metadata = message.get("metadata");
+    if metadata
+        .and_then(|m| m.get("is_visually_hidden_from_conversation"))
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+    {
+        return None;
+    }
+
+    let mut text = Vec::new();
+    let mut attachments: Vec<ImportedAttachment> = metadata
+        .and_then(|m| m.get("attachments"))
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|attachment| {
+            Some(ImportedAttachment {
+                name: attachment.get("name")?.as_str()?.to_string(),
+                mime_type: attachment
+                    .get("mime_type")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                source_id: attachment
+                    .get("id")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+            })
+        })
+        .collect();
+    for part in message
+        .pointer("/content/parts")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        match part {
+            Value::String(part) if !part.is_empty() => text.push(part.clone()),
+            Value::Object(_) => {
+                // Uploaded or generated images are referenced by an asset pointer
+                if let Some(pointer) = part.get("asset_pointer").and_then(Value::as_str) {
+                    attachments.push(ImportedAttachment {
+                        name: pointer.rsplit('/').next().unwrap_or(pointer).to_string(),
+                        mime_type: part
+                            .get("content_type")
+                            .and_then(Value::as_str)
+                            .filter(|t| t.contains('/'))
+                            .map(str::to_string),
+                        source_id: Some(pointer.to_string()),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(code) = message.pointer("/content/text").and_then(Value::as_str) {
+        text.push(code.to_string());
+    }
+    let text = text.join("\n");
+    if text.trim().is_empty() && attachments.is_empty() {
+        return None;
+    }
+
+    Some(ImportedMessage {
+        source_id: message
+            .get("id")
+            .and_then(Value::as_str)
+            .unwrap_or(node_id)
+            .to_string(),
+        parent_source_id,
+        role: role.to_string(),
+        text,
+        created_at: message.get("create_time").and_then(seconds_to_millis),
+        attachments,
+    })
+}
+
+/// Collect the text of an LM Studio content block list, descending into multi-step answers.
+fn lmstudio_text(value: &Value, text: &mut Vec<String>, attachments: &mut Vec<ImportedAttachment>) {
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                lmstudio_text(item, text, attachments);
+            }
+        }
+        Value::Object(block) => match block.get("type").and_then(Value::as_str) {
+            Some("text") => {
+                if let Some(t) = block.get("text").and_then(Value::as_str) {
+                    text.push(t.to_string());
+                }
+            }
+            Some("file") => {
+                let name = block
+                    .get("name")
+                    .or_else(|| block.get("fileIdentifier"))
+                    .and_then(Value::as_str);
+                if let Some(name) = name {
+                    attachments.push(ImportedAttachment {
+                        name: name.to_string(),
+                        mime_type: block
+                            .get("fileType")
+                            .and_then(Value::as_str)
+                            .map(str::to_string),
+                        source_id: block
+                            .get("fileIdentifier")
+                            .and_then(Value::as_str)
+                            .map(str::to_string),
+                    });
+                }
+            }
+            Some("contentBlock") => {
+                if let Some(content) = block.get("content") {
+                    lmstudio_text(content, text, attachments);
+                }
+            }
+            _ => {}
+        },
+        _ => {}
+    }
+}
+
+/// Parse one LM Studio conversation file. `source_id` identifies it across imports.
+pub fn parse_lmstudio_conversation(
+    source_id: &str,
+    content: &str,
+) -> Result<ImportedConversation, String> {
+    let value: Value = serde_json::from_str(content)
+        .map_err(|e| format!("Invalid LM Studio conversation {source_id}: {e}"))?;
+    let created = value
+        .get("createdAt")
+        .and_then(Value::as_i64)
+        .map(|ms| ms / 1000);
+
+    let mut messages: Vec<ImportedMessage> = Vec::new();
+    for (index, message) in value
+        .get("messages")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .enumerate()
+    {
+        let selected = message
+            .get("currentlySelected")
+            .and_then(Value::as_u64)
+            .unwrap_or(0) as usize;
+        let Some(version) = message
+            .get("versions")
+            .and_then(Value::as_array)
+            .and_then(|versions| versions.get(selected).or_else(|| versions.first()))
+        else {
+            continue;
+        };
+        let Some(role) = version
+            .get("role")
+            .and_then(Value::as_str)
+            .and_then(map_role)
+        else {
+            continue;
+        };
+        let mut text = Vec::new();
+        let mut attachments = Vec::new();
+        for key in ["content", "steps"] {
+            if let Some(blocks) = version.get(key) {
+                lmstudio_text(blocks, &mut text, &mut attachments);
+            }
+        }
+        let text = text.join("\n");
+        if text.trim().is_empty() && attachments.is_empty() {
+            continue;
+        }
+        messages.push(ImportedMessage {
+            source_id: format!("{source_id}:{index}"),
+            parent_source_id: messages.last().map(|m| m.source_id.clone()),
+            role: role.to_string(),
+            text,
+            created_at: None,
+            attachments,
+        });
+    }
+
+    Ok(ImportedConversation {
+        source_id: source_id.to_string(),
+        title: value
+            .get("name")
+            .and_then(Value::as_str)
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or("LM Studio conversation")
+            .to_string(),
+        created,
+        updated: created,
+        messages,
+        active_leaf: None,
+    })
+}
+
+/// Parse Ollama's CLI prompt history. REPL commands such as `/bye` are dropped.
+pub fn parse_ollama_history(content: &str) -> ImportedConversation {
+    let messages = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('/'))
+        .enumerate()
+        .map(|(index, line)| ImportedMessage {
+            source_id: format!("{OLLAMA_HISTORY_ID}:{index}"),
+            parent_source_id: index
+                .checked_sub(1)
+                .map(|prev| format!("{OLLAMA_HISTORY_ID}:{prev}")),
+            role: "user".to_string(),
+            text: line.to_string(),
+            created_at: None,
+            attachments: Vec::new(),
+        })
+        .collect();
+    ImportedConversation {
+        source_id: OLLAMA_HISTORY_ID.to_string(),
+        title: OLLAMA_HISTORY_TITLE.to_string(),
+        created: None,
+        updated: None,
+        messages,
+        active_leaf: None,
+    }
+}
+
+/// Read and parse the conversations at `path` for the given source. LM Studio accepts either a
+/// single conversation file or a folder of them.
+pub fn load_conversations(
+    source: ImportSource,
+    path: &Path,
+) -> Result<Vec<ImportedConversation>, String> {
+    let read = |path: &Path| {
+        fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))
+    };
+    match source {
+        ImportSource::ChatGpt => parse_chatgpt_export(&read(path)?),
+        ImportSource::Ollama => Ok(vec![parse_ollama_history(&read(path)?)]),
+        ImportSource::LmStudio => {
+            let files = if path.is_dir() {
+                let mut files: Vec<_> = fs::read_dir(path)
+                    .map_err(|e| e.to_string())?
+                    .filter_map(|entry| entry.ok().map(|e| e.path()))
+                    .filter(|p| {
+                        p.file_name()
+                            .and_then(|n| n.to_str())
+                            .is_some_and(|n| n.ends_with(LMSTUDIO_CONVERSATION_SUFFIX))
+                    })
+                    .collect();
+                files.sort();
+                files
+            } else {
+                vec![path.to_path_buf()]
+            };
+            files
+                .iter()
+                .map(|file| {
+                    let name = file.file_name().and_then(|n| n.to_str()).unwrap_or("");
+                    let source_id = name
+                        .strip_suffix(LMSTUDIO_CONVERSATION_SUFFIX)
+                        .unwrap_or(name);
+                    parse_lmstudio_conversation(source_id, &read(file)?)
+                })
+                .collect()
+        }
+    }
+}
+
+/// Origin recorded on imported threads, e.g. `{"source": "chatgpt", "source_id": "..."}`.
+fn import_origin(source: ImportSource, source_id: &str) -> Value {
+    json!({"source": source.as_str(), "source_id": source_id})
+}
+
+/// Keys (`source`, `source_id`) of conversations already imported into the given threads.
+pub fn imported_keys(threads: &[Value]) -> HashSet<(String, String)> {
+    threads
+        .iter()
+        .filter_map(|thread| {
+            let origin = thread.get("metadata")?.get(IMPORT_METADATA_KEY)?;
+            Some((
+                origin.get("source")?.as_str()?.to_string(),
+                origin.get("source_id")?.as_str()?.to_string(),
+            ))
+        })
+        .collect()
+}
+
+/// Thread metadata for an imported conversation.
+pub fn thread_json(source: ImportSource, conversation: &ImportedConversation) -> Value {
+    let now = chrono::Utc::now().timestamp();
+    let created = conversation.created.unwrap_or(now);
+    json!({
+        "object": "thread",
+        "title": conversation.title,
+        "assistants": [],
+        "created": created,
+        "updated": conversation.updated.unwrap_or(created),
+        "metadata": {IMPORT_METADATA_KEY: import_origin(source, &conversation.source_id)},
+    })
+}
+
+/// Jan messages for an imported conversation, with fresh ids and parent links preserved.
+/// Also returns the Jan id of the message that should be the active leaf.
+pub fn messages_json(
+    source: ImportSource,
+    thread_id: &str,
+    conversation: &ImportedConversation,
+) -> (Vec<Value>, Option<String>) {
+    let fallback_time = conversation
+        .created
+        .map(|secs| secs * 1000)
+        .unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+    let mut ids: HashMap<&str, String> = HashMap::new();
+    let mut messages = Vec::with_capacity(conversation.messages.len());
+    for message in &conversation.messages {
+        let id = Uuid::new_v4().to_string();
+        ids.insert(&message.source_id, id.clone());
+        let parent_id = message
+            .parent_source_id
+            .as_deref()
+            .and_then(|parent| ids.get(parent).cloned());
+        let created_at = message.created_at.unwrap_or(fallback_time);
+        let attachments = if message.attachments.is_empty() {
+            Value::Null
+        } else {
+            json!(message.attachments)
+        };
+        messages.push(json!({
+            "id": id,
+            "object": "message",
+            "thread_id": thread_id,
+            "parent_id": parent_id,
+            "role": message.role,
+            "content": [{"type": "text", "text": {"value": message.text, "annotations": []}}],
+            "attachments": attachments,
+            "status": "ready",
+            "created_at": created_at,
+            "completed_at": created_at,
+            "metadata": {IMPORT_METADATA_KEY: import_origin(source, &message.source_id)},
+        }));
+    }
+    let active_leaf = conversation
+        .active_leaf
+        .as_deref()
+        .and_then(|leaf| ids.get(leaf).cloned())
+        .or_else(|| {
+            messages
+                .last()
+                .and_then(|m| m["id"].as_str())
+                .map(str::to_string)
+        });
+    (messages, active_leaf)
+}
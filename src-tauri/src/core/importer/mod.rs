@@ -0,0 +1,24 @@
+/*!
+   Chat History Importer
+
+   Imports conversations exported from other chat apps into Jan's thread store:
+   - ChatGPT: the `conversations.json` file from a ChatGPT data export. Every branch of a
+     conversation (regenerations and edits) is kept and the branch that was on screen becomes
+     the active one.
+   - LM Studio: a `*.conversation.json` file or the folder holding them. The selected version of
+     each message is imported.
+   - Ollama: the CLI prompt history file (`~/.ollama/history`), imported as one thread of user
+     prompts since Ollama does not keep answers.
+
+   Imported threads carry their origin in `metadata.import`, so importing the same export again
+   skips conversations that are already present. Progress is reported through
+   `chat-import-progress` events.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
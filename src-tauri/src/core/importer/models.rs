@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportSource {
+    #[serde(rename = "chatgpt")]
+    ChatGpt,
+    #[serde(rename = "lmstudio")]
+    LmStudio,
+    Ollama,
+}
+
+impl ImportSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ChatGpt => "chatgpt",
+            Self::LmStudio => "lmstudio",
+            Self::Ollama => "ollama",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportedAttachment {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    /// Identifier of the file in the source app
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_id: Option<String>,
+}
+
+/// One message parsed from a source export, before it is written to a thread
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedMessage {
+    /// Identifier of the message in the source app
+    pub source_id: String,
+    /// Source id of the parent message; `None` for a root
+    pub parent_source_id: Option<String>,
+    pub role: String,
+    pub text: String,
+    /// Milliseconds since the Unix epoch
+    pub created_at: Option<i64>,
+    pub attachments: Vec<ImportedAttachment>,
+}
+
+/// One conversation parsed from a source export
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedConversation {
+    /// Identifier of the conversation in the source app, used for deduplication
+    pub source_id: String,
+    pub title: String,
+    /// Seconds since the Unix epoch
+    pub created: Option<i64>,
+    pub updated: Option<i64>,
+    /// Messages ordered so that every parent precedes its children
+    pub messages: Vec<ImportedMessage>,
+    /// Source id of the leaf of the branch to show; the last message when `None`
+    pub active_leaf: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportStage {
+    Parsing,
+    Importing,
+    Done,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportProgress {
+    pub import_id: String,
+    pub source: ImportSource,
+    pub stage: ImportStage,
+    pub processed: usize,
+    pub total: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportSummary {
+    pub import_id: String,
+    /// Ids of the threads created by this import
+    pub thread_ids: Vec<String>,
+    /// Conversations skipped because an earlier import already brought them in
+    pub skipped: usize,
+    /// Conversations that could not be written, with the reason
+    pub failed: Vec<String>,
+}
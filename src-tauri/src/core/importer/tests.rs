@@ -0,0 +1,165 @@
+use super::commands::*;
+use super::helpers::{parse_chatgpt_export, parse_lmstudio_conversation, parse_ollama_history};
+use super::models::ImportSource;
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::threads::commands::{list_messages, list_threads};
+use serde_json::json;
+use std::fs;
+use tauri::test::mock_app;
+
+/// A conversation where the assistant answer was regenerated; the second answer is on screen.
+fn chatgpt_export() -> String {
+    json!([{
+        "conversation_id": "conv-1",
+        "title": "Rust lifetimes",
+        "create_time": 1700000000.5,
+        "update_time": 1700000100.0,
+        "current_node": "a2",
+        "mapping": {
+            "root": {"id": "root", "message": null, "parent": null, "children": ["sys"]},
+            "sys": {
+                "id": "sys",
+                "message": {
+                    "id": "sys",
+                    "author": {"role": "system"},
+                    "content": {"content_type": "text", "parts": [""]},
+                    "metadata": {"is_visually_hidden_from_conversation": true}
+                },
+                "parent": "root",
+                "children": ["u1"]
+            },
+            "u1": {
+                "id": "u1",
+                "message": {
+                    "id": "u1",
+                    "author": {"role": "user"},
+                    "create_time": 1700000001.0,
+                    "content": {"content_type": "text", "parts": ["What is 'a?"]},
+                    "metadata": {"attachments": [{"id": "file-1", "name": "main.rs", "mime_type": "text/x-rust"}]}
+                },
+                "parent": "sys",
+                "children": ["a1", "a2"]
+            },
+            "a1": {
+                "id": "a1",
+                "message": {
+                    "id": "a1",
+                    "author": {"role": "assistant"},
+                    "content": {"content_type": "text", "parts": ["First answer"]},
+                    "metadata": {}
+                },
+                "parent": "u1",
+                "children": []
+            },
+            "a2": {
+                "id": "a2",
+                "message": {
+                    "id": "a2",
+                    "author": {"role": "assistant"},
+                    "content": {"content_type": "text", "parts": ["Second answer"]},
+                    "metadata": {}
+                },
+                "parent": "u1",
+                "children": []
+            }
+        }
+    }])
+    .to_string()
+}
+
+#[test]
+fn test_parse_chatgpt_keeps_branches_and_skips_hidden_messages() {
+    let conversations = parse_chatgpt_export(&chatgpt_export()).unwrap();
+    assert_eq!(conversations.len(), 1);
+    let conversation = &conversations[0];
+    assert_eq!(conversation.source_id, "conv-1");
+    assert_eq!(conversation.created, Some(1700000000));
+
+    let ids: Vec<_> = conversation
+        .messages
+        .iter()
+        .map(|m| m.source_id.as_str())
+        .collect();
+    assert_eq!(ids, vec!["u1", "a1", "a2"]);
+    let user = &conversation.messages[0];
+    assert_eq!(user.parent_source_id, None);
+    assert_eq!(user.created_at, Some(1700000001000));
+    assert_eq!(user.attachments[0].name, "main.rs");
+    assert_eq!(
+        conversation.messages[2].parent_source_id.as_deref(),
+        Some("u1")
+    );
+    assert_eq!(conversation.active_leaf.as_deref(), Some("a2"));
+}
+
+#[test]
+fn test_parse_lmstudio_uses_selected_version() {
+    let content = json!({
+        "name": "Haiku",
+        "createdAt": 1700000000000_i64,
+        "messages": [
+            {"versions": [{"type": "singleStep", "role": "user", "content": [{"type": "text", "text": "Write a haiku"}]}], "currentlySelected": 0},
+            {"versions": [
+                {"type": "multiStep", "role": "assistant", "steps": [{"type": "contentBlock", "content": [{"type": "text", "text": "Old"}]}]},
+                {"type": "multiStep", "role": "assistant", "steps": [{"type": "contentBlock", "content": [{"type": "text", "text": "New"}]}]}
+            ], "currentlySelected": 1}
+        ]
+    })
+    .to_string();
+
+    let conversation = parse_lmstudio_conversation("1700000000000", &content).unwrap();
+    assert_eq!(conversation.title, "Haiku");
+    assert_eq!(conversation.created, Some(1700000000));
+    assert_eq!(conversation.messages.len(), 2);
+    assert_eq!(conversation.messages[1].role, "assistant");
+    assert_eq!(conversation.messages[1].text, "New");
+    assert_eq!(
+        conversation.messages[1].parent_source_id,
+        Some(conversation.messages[0].source_id.clone())
+    );
+}
+
+#[test]
+fn test_parse_ollama_history_drops_commands() {
+    let conversation = parse_ollama_history("why is the sky blue?\n/bye\n\nwrite a poem\n");
+    let texts: Vec<_> = conversation
+        .messages
+        .iter()
+        .map(|m| m.text.as_str())
+        .collect();
+    assert_eq!(texts, vec!["why is the sky blue?", "write a poem"]);
+    assert!(conversation.messages.iter().all(|m| m.role == "user"));
+}
+
+#[tokio::test]
+async fn test_import_chatgpt_is_deduplicated() {
+    let app = mock_app();
+    let data_dir = get_jan_data_folder_path(app.handle().clone());
+    fs::create_dir_all(&data_dir).unwrap();
+    let export = data_dir.join("conversations.json");
+    fs::write(&export, chatgpt_export()).unwrap();
+    let path = export.to_string_lossy().to_string();
+
+    let summary = import_chat_history(app.handle().clone(), ImportSource::ChatGpt, path.clone())
+        .await
+        .unwrap();
+    assert_eq!(summary.thread_ids.len(), 1);
+    assert!(summary.failed.is_empty());
+
+    // The active branch is the answer that was on screen in ChatGPT
+    let messages = list_messages(app.handle().clone(), summary.thread_ids[0].clone())
+        .await
+        .unwrap();
+    assert_eq!(messages.len(), 2);
+    assert_eq!(messages[1]["content"][0]["text"]["value"], "Second answer");
+    assert_eq!(messages[0]["attachments"][0]["name"], "main.rs");
+
+    let again = import_chat_history(app.handle().clone(), ImportSource::ChatGpt, path)
+        .await
+        .unwrap();
+    assert!(again.thread_ids.is_empty());
+    assert_eq!(again.skipped, 1);
+    assert_eq!(list_threads(app.handle().clone()).await.unwrap().len(), 1);
+
+    let _ = fs::remove_dir_all(data_dir);
+}
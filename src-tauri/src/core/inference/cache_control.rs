@@ -0,0 +1,88 @@
+use serde_json::{json, Map, Value};
+
+/// Anthropic allows at most this many cache breakpoints per request
+pub const MAX_CACHE_BREAKPOINTS: usize = 4;
+
+/// Whether any part of the request already carries a `cache_control` marker
+pub fn has_cache_control(value: &Value) -> bool {
+    match value {
+        Value::Object(map) => {
+            map.contains_key("cache_control") || map.values().any(has_cache_control)
+        }
+        Value::Array(items) => items.iter().any(has_cache_control),
+        _ => false,
+    }
+}
+
+fn mark(block: &mut Map<String, Value>) {
+    block.insert("cache_control".to_string(), json!({"type": "ephemeral"}));
+}
+
+/// Mark the last block of `content`, turning a plain string into a text block first.
+/// Returns whether a breakpoint was added.
+fn mark_last_block(content: &mut Value) -> bool {
+    if let Some(text) = content.as_str().filter(|t| !t.is_empty()) {
+        *content = json!([{"type": "text", "text": text}]);
+    }
+    match content
+        .as_array_mut()
+        .and_then(|blocks| blocks.last_mut())
+        .and_then(|block| block.as_object_mut())
+    {
+        Some(block) => {
+            mark(block);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Mark the stable prefix of an Anthropic `/messages` request for prompt caching: the last
+/// tool definition, the last system block and the last block of the final message, so the
+/// next turn of the conversation reads everything before it from the cache. Requests that
+/// already place their own breakpoints are left alone. Returns the number of breakpoints added.
+pub fn apply_anthropic_cache_control(body: &mut Value) -> usize {
+    if !body.is_object() || has_cache_control(body) {
+        return 0;
+    }
+    let mut added = 0;
+    if let Some(tool) = body
+        .get_mut("tools")
+        .and_then(|t| t.as_array_mut())
+        .and_then(|tools| tools.last_mut())
+        .and_then(|tool| tool.as_object_mut())
+    {
+        mark(tool);
+        added += 1;
+    }
+    if let Some(system) = body.get_mut("system") {
+        added += usize::from(mark_last_block(system));
+    }
+    if let Some(content) = body
+        .get_mut("messages")
+        .and_then(|m| m.as_array_mut())
+        .and_then(|messages| messages.last_mut())
+        .and_then(|message| message.get_mut("content"))
+    {
+        added += usize::from(mark_last_block(content));
+    }
+    debug_assert!(added <= MAX_CACHE_BREAKPOINTS);
+    added
+}
+
+/// Ask an OpenAI-compatible stream to end with a usage chunk. OpenAI caches long prompt
+/// prefixes automatically and only reports the cached share in that chunk.
+pub fn request_stream_usage(body: &mut Value) {
+    if body.get("stream").and_then(|s| s.as_bool()) != Some(true) {
+        return;
+    }
+    match body
+        .get_mut("stream_options")
+        .and_then(|o| o.as_object_mut())
+    {
+        Some(options) => {
+            options.entry("include_usage").or_insert(Value::Bool(true));
+        }
+        None => body["stream_options"] = json!({"include_usage": true}),
+    }
+}
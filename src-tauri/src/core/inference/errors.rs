@@ -0,0 +1,276 @@
+//! Classification of provider failures. Error responses, errors reported inside a stream
+//! and broken connections all become a `ProviderError` whose kind decides the recovery:
+//! transient failures are retried or handed to a failover model, while content filters and
+//! request errors are surfaced for the user to decide.
+
+use std::fmt;
+use std::time::Duration;
+
+use serde_json::Value;
+
+use super::models::{ProviderError, ProviderErrorKind, Recovery, RequestPolicy};
+
+/// Longest provider error message kept, in characters
+pub const MAX_ERROR_MESSAGE_CHARS: usize = 2_000;
+
+/// Provider error codes and types by kind, covering OpenAI, Anthropic, Gemini and Azure
+const ERROR_CODES: &[(ProviderErrorKind, &[&str])] = &[
+    (
+        ProviderErrorKind::RateLimit,
+        &[
+            "rate_limit_error",
+            "rate_limit_exceeded",
+            "resource_exhausted",
+            "too_many_requests",
+        ],
+    ),
+    (
+        ProviderErrorKind::Overloaded,
+        &[
+            "overloaded_error",
+            "overloaded",
+            "server_overloaded",
+            "unavailable",
+            "api_error",
+        ],
+    ),
+    (
+        ProviderErrorKind::QuotaExceeded,
+        &[
+            "insufficient_quota",
+            "billing_hard_limit_reached",
+            "quota_exceeded",
+            "billing_error",
+        ],
+    ),
+    (
+        ProviderErrorKind::ContentFilter,
+        &[
+            "content_filter",
+            "content_policy_violation",
+            "safety",
+            "prohibited_content",
+        ],
+    ),
+    (
+        ProviderErrorKind::Authentication,
+        &[
+            "authentication_error",
+            "permission_error",
+            "invalid_api_key",
+            "unauthenticated",
+        ],
+    ),
+    (
+        ProviderErrorKind::InvalidRequest,
+        &[
+            "invalid_request_error",
+            "invalid_argument",
+            "not_found_error",
+            "context_length_exceeded",
+        ],
+    ),
+];
+
+impl ProviderErrorKind {
+    /// Kind of a provider error code or type, matched case-insensitively
+    pub fn from_code(code: &str) -> Option<Self> {
+        ERROR_CODES
+            .iter()
+            .find(|(_, codes)| codes.iter().any(|c| c.eq_ignore_ascii_case(code)))
+            .map(|(kind, _)| *kind)
+    }
+
+    /// Kind of an HTTP status when the body doesn't say more
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            429 => Self::RateLimit,
+            502 | 503 | 504 | 529 => Self::Overloaded,
+            408 => Self::Network,
+            402 => Self::QuotaExceeded,
+            401 | 403 => Self::Authentication,
+            400..=499 => Self::InvalidRequest,
+            _ => Self::Other,
+        }
+    }
+}
+
+impl fmt::Display for ProviderErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            Self::RateLimit => "rate limited",
+            Self::Overloaded => "overloaded",
+            Self::Network => "network error",
+            Self::QuotaExceeded => "quota exceeded",
+            Self::ContentFilter => "blocked by content filter",
+            Self::Authentication => "authentication failed",
+            Self::InvalidRequest => "invalid request",
+            Self::Other => "provider error",
+        };
+        f.write_str(label)
+    }
+}
+
+impl fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.status {
+            Some(status) => write!(f, "status {status} ({}): {}", self.kind, self.message),
+            None => write!(f, "{} ({})", self.message, self.kind),
+        }
+    }
+}
+
+impl ProviderError {
+    /// How to recover from the failure
+    pub fn recovery(&self) -> Recovery {
+        match self.kind {
+            ProviderErrorKind::RateLimit
+            | ProviderErrorKind::Overloaded
+            | ProviderErrorKind::Network
+                if self.mid_stream =>
+            {
+                Recovery::Backoff
+            }
+            // The request policy already backed off before giving up
+            ProviderErrorKind::RateLimit
+            | ProviderErrorKind::Overloaded
+            | ProviderErrorKind::Network
+            | ProviderErrorKind::QuotaExceeded => Recovery::Failover,
+            ProviderErrorKind::ContentFilter
+            | ProviderErrorKind::Authentication
+            | ProviderErrorKind::InvalidRequest
+            | ProviderErrorKind::Other => Recovery::Surface,
+        }
+    }
+
+    /// Delay before retry number `retry` (0-based): what the provider asked for, capped by the
+    /// policy, otherwise the policy's backoff
+    pub fn retry_delay(&self, policy: &RequestPolicy, retry: u32) -> Duration {
+        match self.retry_after_secs {
+            Some(secs) => {
+                Duration::from_secs(secs).min(Duration::from_millis(policy.backoff_max_ms))
+            }
+            None => policy.backoff_delay(retry),
+        }
+    }
+}
+
+fn truncate_message(message: &str) -> String {
+    let message = message.trim();
+    match message.char_indices().nth(MAX_ERROR_MESSAGE_CHARS) {
+        Some((end, _)) => format!("{}…", &message[..end]),
+        None => message.to_string(),
+    }
+}
+
+/// Error fields of a provider error body: `{"error": {...}}` as OpenAI, Anthropic and Gemini
+/// send it, `{"error": "..."}` or a bare object with a `message`
+struct ErrorDetails {
+    /// Codes in the order they are trusted: specific code, status, type
+    codes: Vec<String>,
+    status: Option<u16>,
+    message: Option<String>,
+}
+
+fn error_details(body: &Value) -> Option<ErrorDetails> {
+    let error = match body.get("error") {
+        Some(Value::String(message)) => {
+            return Some(ErrorDetails {
+                codes: Vec::new(),
+                status: None,
+                message: Some(message.clone()),
+            })
+        }
+        Some(error @ Value::Object(_)) => error,
+        _ if body.get("type").and_then(Value::as_str) == Some("error") => body,
+        _ => return None,
+    };
+    let codes = ["code", "status", "type"]
+        .iter()
+        .filter_map(|key| error.get(*key).and_then(Value::as_str))
+        .map(str::to_string)
+        .collect();
+    let status = ["code", "status"]
+        .iter()
+        .find_map(|key| error.get(*key).and_then(Value::as_u64))
+        .and_then(|status| u16::try_from(status).ok());
+    let message = error
+        .get("message")
+        .or_else(|| body.get("message"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    Some(ErrorDetails {
+        codes,
+        status,
+        message,
+    })
+}
+
+fn from_details(details: ErrorDetails, status: Option<u16>, fallback: &str) -> ProviderError {
+    let status = status.or(details.status);
+    let kind = details
+        .codes
+        .iter()
+        .find_map(|code| ProviderErrorKind::from_code(code))
+        .or_else(|| status.map(ProviderErrorKind::from_status))
+        .unwrap_or(ProviderErrorKind::Other);
+    ProviderError {
+        kind,
+        status,
+        code: details.codes.into_iter().next(),
+        message: truncate_message(details.message.as_deref().unwrap_or(fallback)),
+        retry_after_secs: None,
+        mid_stream: false,
+    }
+}
+
+/// Classify an error response from its status, body and `Retry-After` header
+pub fn classify_response(status: u16, body: &str, retry_after: Option<&str>) -> ProviderError {
+    let details = serde_json::from_str::<Value>(body)
+        .ok()
+        .as_ref()
+        .and_then(error_details)
+        .unwrap_or(ErrorDetails {
+            codes: Vec::new(),
+            status: None,
+            message: None,
+        });
+    let mut error = from_details(details, Some(status), body);
+    error.retry_after_secs = retry_after.and_then(|value| value.trim().parse().ok());
+    error
+}
+
+/// Classify an error a provider sent inside a stream, `None` for chunks that aren't errors
+pub fn classify_stream_error(chunk: &Value) -> Option<ProviderError> {
+    let details = error_details(chunk)?;
+    let mut error = from_details(details, None, "The provider reported an error");
+    error.mid_stream = true;
+    Some(error)
+}
+
+/// A request that never got a response, or a stream that broke off
+pub fn network_error(message: &str, mid_stream: bool) -> ProviderError {
+    ProviderError {
+        kind: ProviderErrorKind::Network,
+        status: None,
+        code: None,
+        message: truncate_message(message),
+        retry_after_secs: None,
+        mid_stream,
+    }
+}
+
+/// A turn the provider's content filter stopped before the model produced anything
+pub fn content_filter_error(finish_reason: Option<&str>, produced: bool) -> Option<ProviderError> {
+    if produced || finish_reason != Some("content_filter") {
+        return None;
+    }
+    Some(ProviderError {
+        kind: ProviderErrorKind::ContentFilter,
+        status: None,
+        code: Some("content_filter".to_string()),
+        message: "The provider's content filter blocked the response".to_string(),
+        retry_after_secs: None,
+        mid_stream: false,
+    })
+}
@@ -0,0 +1,205 @@
+use std::time::Duration;
+
+use serde_json::Value;
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_llamacpp::state::LlamacppState;
+use tauri_plugin_mlx::state::MlxState;
+
+use super::models::{LocalEndpoint, ModelEndpoint, NativeTools, RequestPolicy, ToolSchemaLimits};
+use super::retry::{policy_client, send_with_retry};
+use crate::core::offline::helpers::{check_url, is_loopback_url};
+use crate::core::ollama::constants::OLLAMA_PROVIDER;
+use crate::core::redaction::helpers::outbound_body;
+use crate::core::redaction::RedactionFilter;
+use crate::core::state::AppState;
+
+/// Default timeout for non-streaming completions issued by the core
+pub const DEFAULT_COMPLETION_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Resolve a model id to an OpenAI-compatible endpoint.
+/// Mirrors the routing of the local API server: remote providers take precedence
+/// (by configured model, `provider/model` prefix or provider name), then running local sessions.
+pub async fn resolve_model_endpoint<R: Runtime>(
+    app: &AppHandle<R>,
+    model_id: &str,
+) -> Result<ModelEndpoint, String> {
+    if let Some(state) = app.try_state::<AppState>() {
+        let configs = state.provider_configs.read().await;
+        let provider = configs
+            .values()
+            .find(|c| c.models.iter().any(|m| m == model_id))
+            .or_else(|| {
+                model_id
+                    .split_once('/')
+                    .and_then(|(prefix, _)| configs.get(prefix))
+            })
+            .or_else(|| configs.get(model_id));
+        if let Some(provider) = provider {
+            let base_url = provider
+                .base_url
+                .clone()
+                .ok_or_else(|| format!("Provider '{}' has no base URL", provider.provider))?;
+            check_url(app, &base_url)?;
+            return Ok(ModelEndpoint {
+                model_id: model_id.to_string(),
+                base_url,
+                api_key: provider.api_key.clone(),
+                custom_headers: provider.custom_headers.clone(),
+                // Ollama shares this machine's hardware with the built-in engines
+                is_local: provider.provider == OLLAMA_PROVIDER,
+                policy: RequestPolicy::from_provider(provider),
+                tool_schema: ToolSchemaLimits::from_provider(provider),
+                native_tools: NativeTools::from_provider(provider),
+                failover_models: provider.failover_models.clone(),
+            });
+        }
+    }
+
+    if let Some(endpoint) = local_session_endpoint(app, model_id).await {
+        return Ok(endpoint);
+    }
+
+    Err(format!(
+        "No running session or provider found for model '{model_id}'"
+    ))
+}
+
+/// Endpoint of a running llama.cpp or MLX session serving `model_id`
+async fn local_session_endpoint<R: Runtime>(
+    app: &AppHandle<R>,
+    model_id: &str,
+) -> Option<ModelEndpoint> {
+    if let Some(llama_state) = app.try_state::<LlamacppState>() {
+        let sessions = llama_state.llama_server_process.lock().await;
+        if let Some(session) = sessions.values().find(|s| s.info.model_id == model_id) {
+            return Some(ModelEndpoint {
+                model_id: model_id.to_string(),
+                base_url: format!("http://127.0.0.1:{}/v1", session.info.port),
+                api_key: Some(session.info.api_key.clone()),
+                custom_headers: Vec::new(),
+                is_local: true,
+                policy: RequestPolicy::local(),
+                tool_schema: None,
+                native_tools: None,
+                failover_models: Vec::new(),
+            });
+        }
+    }
+
+    if let Some(mlx_state) = app.try_state::<MlxState>() {
+        let sessions = mlx_state.mlx_server_process.lock().await;
+        if let Some(session) = sessions.values().find(|s| s.info.model_id == model_id) {
+            return Some(ModelEndpoint {
+                model_id: model_id.to_string(),
+                base_url: format!("http://127.0.0.1:{}/v1", session.info.port),
+                api_key: Some(session.info.api_key.clone()),
+                custom_headers: Vec::new(),
+                is_local: true,
+                policy: RequestPolicy::local(),
+                tool_schema: None,
+                native_tools: None,
+                failover_models: Vec::new(),
+            });
+        }
+    }
+    None
+}
+
+/// Resolve a model id to an endpoint on this machine: a running llama.cpp or MLX session, or
+/// an Ollama provider listening on a loopback address. Remote providers are never returned,
+/// even when they also serve `model_id`.
+pub async fn resolve_local_endpoint<R: Runtime>(
+    app: &AppHandle<R>,
+    model_id: &str,
+) -> Result<LocalEndpoint, String> {
+    if let Some(endpoint) = local_session_endpoint(app, model_id).await {
+        return Ok(LocalEndpoint::new(endpoint));
+    }
+    if let Some(state) = app.try_state::<AppState>() {
+        let configs = state.provider_configs.read().await;
+        let ollama = configs.get(OLLAMA_PROVIDER).filter(|c| {
+            c.base_url.as_deref().is_some_and(is_loopback_url)
+                && c.models.iter().any(|m| m == model_id)
+        });
+        if let Some(provider) = ollama {
+            return Ok(LocalEndpoint::new(ModelEndpoint {
+                model_id: model_id.to_string(),
+                base_url: provider.base_url.clone().unwrap_or_default(),
+                api_key: provider.api_key.clone(),
+                custom_headers: provider.custom_headers.clone(),
+                is_local: true,
+                policy: RequestPolicy::from_provider(provider),
+                tool_schema: ToolSchemaLimits::from_provider(provider),
+                native_tools: None,
+                failover_models: Vec::new(),
+            }));
+        }
+    }
+    Err(format!("Model '{model_id}' is not running on this machine"))
+}
+
+/// Build a request posting `body` to `path` on the endpoint with authentication and custom
+/// headers applied. Every request the core sends to a model is built here, so a remote
+/// endpoint only ever receives the body scrubbed by the redaction filters of `thread_id`.
+pub fn build_request(
+    client: &reqwest::Client,
+    endpoint: &ModelEndpoint,
+    path: &str,
+    thread_id: Option<&str>,
+    body: &Value,
+) -> reqwest::RequestBuilder {
+    let mut request = client.post(endpoint.url(path));
+    if let Some(api_key) = endpoint.api_key.as_deref().filter(|k| !k.is_empty()) {
+        request = request.bearer_auth(api_key);
+    }
+    for header in &endpoint.custom_headers {
+        request = request.header(header.header.as_str(), header.value.as_str());
+    }
+    request.json(&outbound_body(
+        &RedactionFilter::shared(),
+        endpoint,
+        thread_id,
+        body,
+    ))
+}
+
+/// Issue a non-streaming `/chat/completions` request for `thread_id`, if it belongs to one.
+/// The `model` field is filled in from the endpoint and `stream` is forced to false.
+pub async fn chat_completion(
+    endpoint: &ModelEndpoint,
+    mut body: Value,
+    thread_id: Option<&str>,
+    timeout: Duration,
+) -> Result<Value, String> {
+    body["model"] = Value::String(endpoint.model_id.clone());
+    body["stream"] = Value::Bool(false);
+
+    let client = policy_client(&endpoint.policy, timeout)?;
+    let request = build_request(&client, endpoint, "/chat/completions", thread_id, &body);
+    let response = send_with_retry(&endpoint.policy, request)
+        .await
+        .map_err(|e| format!("Completion request failed: {e}"))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!(
+            "Completion request failed with status {status}: {text}"
+        ));
+    }
+    response
+        .json::<Value>()
+        .await
+        .map_err(|e| format!("Invalid completion response: {e}"))
+}
+
+/// Extract the assistant text of the first choice of a chat completion response
+pub fn completion_text(response: &Value) -> Option<String> {
+    response
+        .get("choices")
+        .and_then(|c| c.get(0))
+        .and_then(|c| c.get("message"))
+        .and_then(|m| m.get("content"))
+        .and_then(|c| c.as_str())
+        .map(|s| s.to_string())
+}
@@ -0,0 +1,54 @@
+/*!
+   Core Inference Client
+
+   Minimal OpenAI-compatible client used by core services (context summarization, agents,
+   background jobs) that need to talk to a model without going through the frontend.
+   Model ids are resolved the same way the local API server routes requests: registered
+   remote providers first, then running llama.cpp and MLX sessions. Work that must not leave
+   the machine resolves a `LocalEndpoint` instead, which the router only hands out for local
+   sessions and loopback Ollama, never for remote providers.
+
+   Streaming responses are normalized into a single `StreamEvent` shape regardless of whether
+   the upstream speaks OpenAI deltas, Anthropic message events or Gemini candidates, so the
+   agent loop and the local API server consume one format. Reasoning is its own event:
+   `reasoning_content` deltas, Anthropic thinking blocks, Gemini thoughts and the `<think>`
+   tags local models write into their answer all become `ReasoningDelta`s.
+
+   Requests to remote providers follow the provider's request policy: connect and read
+   timeouts, plus retries with exponential backoff on connection failures and retryable
+   statuses, so a flaky proxy doesn't surface as an immediate failure.
+
+   Failures are classified, whether they come as an error response, as an error event inside
+   the stream or as a broken connection: rate limits, overload and network failures are
+   transient; quota, content filter, authentication and request errors aren't. Agent runs
+   retry a stream that broke off with the same backoff, hand transient and quota failures to
+   the provider's `failover_models` through the router, and stop with the classified error,
+   and the models left to try, for anything else.
+
+   Prompt caching: Anthropic requests get `cache_control` breakpoints on their stable prefix,
+   and OpenAI streams ask for the final usage chunk that reports automatically cached tokens.
+   Cache reads and writes are part of the normalized `Usage` event.
+
+   Tool schemas: MCP servers can declare tools with schemas of tens of kilobytes, more than
+   some providers accept in a request. Before tools are advertised to a remote provider their
+   schemas are compacted: examples and comments are dropped, long enums become a hint in the
+   description and long descriptions are truncated. Limits are set per provider.
+
+   Native tools: a provider config can declare tools the provider runs itself, such as
+   Anthropic web search or OpenAI file search. Agent runs write them into the request in the
+   provider's format, after checking each one against the tool approval policies under the
+   server `provider:<provider>`. The local API server passes client requests through as sent.
+*/
+
+pub mod cache_control;
+pub mod errors;
+pub mod helpers;
+pub mod models;
+pub mod native_tools;
+pub mod reasoning;
+pub mod retry;
+pub mod stream;
+pub mod tool_schema;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,244 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::core::state::ProviderCustomHeader;
+
+/// Resolved OpenAI-compatible endpoint for a model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelEndpoint {
+    pub model_id: String,
+    /// Base URL without a trailing slash, e.g. `http://127.0.0.1:3456/v1`
+    pub base_url: String,
+    pub api_key: Option<String>,
+    #[serde(default)]
+    pub custom_headers: Vec<ProviderCustomHeader>,
+    /// Whether the endpoint is served by a local engine process
+    pub is_local: bool,
+    /// Timeouts and retries applied to requests to this endpoint
+    #[serde(default)]
+    pub policy: RequestPolicy,
+    /// How tool schemas are compacted before they are advertised to this endpoint;
+    /// `None` sends them as the MCP servers declared them
+    #[serde(default)]
+    pub tool_schema: Option<ToolSchemaLimits>,
+    /// Tools the provider runs itself, offered to the model besides the MCP tools
+    #[serde(default)]
+    pub native_tools: Option<NativeTools>,
+    /// Models to continue with, in order, when this endpoint is rate limited, overloaded or
+    /// out of quota
+    #[serde(default)]
+    pub failover_models: Vec<String>,
+}
+
+impl ModelEndpoint {
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url.trim_end_matches('/'), path)
+    }
+}
+
+/// An endpoint served on this machine. Only the router creates these, after checking that
+/// requests can't leave the machine, so code that must never reach a remote provider takes
+/// a `LocalEndpoint` instead of a `ModelEndpoint`.
+#[derive(Debug, Clone)]
+pub struct LocalEndpoint(ModelEndpoint);
+
+impl LocalEndpoint {
+    pub(super) fn new(endpoint: ModelEndpoint) -> Self {
+        Self(endpoint)
+    }
+}
+
+impl std::ops::Deref for LocalEndpoint {
+    type Target = ModelEndpoint;
+
+    fn deref(&self) -> &ModelEndpoint {
+        &self.0
+    }
+}
+
+/// Timeouts and retry behaviour for requests to a model endpoint. Resolved from the optional
+/// fields of a `ProviderConfig`, with defaults for anything the provider leaves unset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RequestPolicy {
+    pub connect_timeout_secs: u64,
+    /// Longest wait for response headers or for the next chunk of a response body.
+    /// `None` leaves only the overall request timeout in place.
+    pub read_timeout_secs: Option<u64>,
+    /// Retries after the first attempt; 0 disables retrying
+    pub max_retries: u32,
+    /// Response statuses that are retried. Connection failures and timeouts always are.
+    pub retry_on_statuses: Vec<u16>,
+    /// Delay before the first retry, doubled for every further one
+    pub backoff_initial_ms: u64,
+    pub backoff_max_ms: u64,
+}
+
+/// Limits applied to tool definitions before they are sent to a provider. Resolved from the
+/// optional fields of a `ProviderConfig`; a limit of 0 leaves that part of the schema alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolSchemaLimits {
+    /// Longer `description`s inside parameter schemas are truncated
+    pub max_description_chars: usize,
+    /// Longer enums are collapsed into a hint in the description
+    pub max_enum_values: usize,
+}
+
+/// Kind of a tool run by the provider rather than by Jan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NativeToolKind {
+    WebSearch,
+    FileSearch,
+    CodeExecution,
+}
+
+/// A provider-native tool declared in a provider config
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NativeTool {
+    pub kind: NativeToolKind,
+    /// Provider-specific fields added to the tool definition, e.g. `max_uses` or
+    /// `vector_store_ids`
+    #[serde(default)]
+    pub options: Map<String, Value>,
+}
+
+/// How native tools are written into a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NativeToolDialect {
+    Anthropic,
+    OpenAi,
+}
+
+/// Native tools of the provider serving an endpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NativeTools {
+    pub provider: String,
+    pub dialect: NativeToolDialect,
+    pub tools: Vec<NativeTool>,
+}
+
+/// Canonical streaming event. Provider-specific SSE chunks (OpenAI deltas, Anthropic
+/// message events, Gemini candidates) are normalized into this shape before they reach
+/// the agent loop or the local API server.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamEvent {
+    TextDelta {
+        text: String,
+    },
+    /// Reasoning the model did before answering: OpenAI-style `reasoning_content`, Anthropic
+    /// thinking blocks, Gemini thoughts or the `<think>` tags of local models
+    ReasoningDelta {
+        text: String,
+    },
+    /// Fragment of a tool call. `index` identifies the call within the turn; `id` and
+    /// `name` are only present on the fragment that opens the call.
+    ToolCallDelta {
+        index: usize,
+        id: Option<String>,
+        name: Option<String>,
+        arguments: String,
+    },
+    /// Token counts as reported by the provider. `input_tokens` includes prompt tokens served
+    /// from the provider's prompt cache (`cache_read_tokens`) or written to it
+    /// (`cache_write_tokens`).
+    Usage {
+        input_tokens: Option<u64>,
+        output_tokens: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_read_tokens: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_write_tokens: Option<u64>,
+    },
+    /// End of the turn. `reason` uses the OpenAI vocabulary: `stop`, `length`,
+    /// `tool_calls` or `content_filter`; unknown provider reasons are passed through.
+    Finish {
+        reason: String,
+    },
+    /// The provider reported a failure inside the stream, such as an Anthropic
+    /// `overloaded_error` event
+    Error {
+        error: ProviderError,
+    },
+}
+
+/// Class of a provider failure, which decides how it is recovered from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderErrorKind {
+    RateLimit,
+    Overloaded,
+    Network,
+    QuotaExceeded,
+    ContentFilter,
+    Authentication,
+    InvalidRequest,
+    Other,
+}
+
+/// How a provider failure is recovered from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Recovery {
+    /// Retry the same model after a delay
+    Backoff,
+    /// Continue with the next failover model
+    Failover,
+    /// Give up and let the user decide, e.g. to retry with a different provider
+    Surface,
+}
+
+/// A classified provider failure
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProviderError {
+    pub kind: ProviderErrorKind,
+    /// HTTP status of the failed response, or the one reported in a stream error
+    #[serde(default)]
+    pub status: Option<u16>,
+    /// The provider's own error code or type, e.g. `overloaded_error`
+    #[serde(default)]
+    pub code: Option<String>,
+    pub message: String,
+    #[serde(default)]
+    pub retry_after_secs: Option<u64>,
+    /// The stream broke after the request was accepted. The request policy only retries
+    /// failures before that.
+    #[serde(default)]
+    pub mid_stream: bool,
+}
+
+/// Token usage of one or more model turns
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Prompt tokens read from the provider's prompt cache
+    pub cache_read_tokens: u64,
+    /// Prompt tokens written to the provider's prompt cache
+    pub cache_write_tokens: u64,
+}
+
+impl TokenUsage {
+    /// Share of the prompt served from the cache, `None` before any prompt was counted
+    pub fn cache_hit_rate(&self) -> Option<f64> {
+        (self.input_tokens > 0).then(|| self.cache_read_tokens as f64 / self.input_tokens as f64)
+    }
+
+    pub fn add(&mut self, other: &TokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_read_tokens += other.cache_read_tokens;
+        self.cache_write_tokens += other.cache_write_tokens;
+    }
+}
+
+/// Wire format of a provider stream
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StreamFormat {
+    OpenAi,
+    Anthropic,
+    Gemini,
+}
@@ -0,0 +1,185 @@
+//! Tools a provider runs on its side, such as Anthropic web search or OpenAI file search.
+//! A provider config declares them; they are written into requests in the provider's own
+//! format and their results come back as part of the model's answer, so Jan never executes
+//! them. Before a run offers them, each one goes through the tool approval policies like an
+//! MCP tool, as tool `<kind>` of server `provider:<provider>`.
+
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Manager, Runtime};
+use tokio_util::sync::CancellationToken;
+
+use super::models::{NativeTool, NativeToolDialect, NativeToolKind, NativeTools};
+use crate::core::approvals::{helpers::authorize_tool_call, ToolApprovalState};
+use crate::core::state::ProviderConfig;
+
+/// Tool approval policies address a provider's native tools under this server prefix
+pub const NATIVE_TOOL_SERVER_PREFIX: &str = "provider:";
+
+// Tool versions of the Anthropic Messages API
+pub const ANTHROPIC_WEB_SEARCH_TYPE: &str = "web_search_20250305";
+pub const ANTHROPIC_CODE_EXECUTION_TYPE: &str = "code_execution_20250522";
+
+impl NativeToolKind {
+    /// Name of the tool in approval policies and Anthropic requests
+    pub fn name(&self) -> &'static str {
+        match self {
+            NativeToolKind::WebSearch => "web_search",
+            NativeToolKind::FileSearch => "file_search",
+            NativeToolKind::CodeExecution => "code_execution",
+        }
+    }
+}
+
+/// Request format of a provider's native tools, `None` for providers without any
+pub fn dialect_for(provider: &str, base_url: Option<&str>) -> Option<NativeToolDialect> {
+    let host = base_url.unwrap_or_default().to_ascii_lowercase();
+    if provider.eq_ignore_ascii_case("anthropic") || host.contains("api.anthropic.com") {
+        Some(NativeToolDialect::Anthropic)
+    } else if provider.eq_ignore_ascii_case("openai") || host.contains("api.openai.com") {
+        Some(NativeToolDialect::OpenAi)
+    } else {
+        None
+    }
+}
+
+impl NativeTools {
+    /// Native tools declared by a provider, `None` when it declares none or has no native
+    /// tools at all
+    pub fn from_provider(config: &ProviderConfig) -> Option<Self> {
+        if config.native_tools.is_empty() {
+            return None;
+        }
+        let Some(dialect) = dialect_for(&config.provider, config.base_url.as_deref()) else {
+            log::warn!(
+                "Provider '{}' declares native tools but doesn't support any",
+                config.provider
+            );
+            return None;
+        };
+        Some(Self {
+            provider: config.provider.clone(),
+            dialect,
+            tools: config.native_tools.clone(),
+        })
+    }
+
+    /// Server name the approval policies use for this provider's tools
+    pub fn server(&self) -> String {
+        format!("{NATIVE_TOOL_SERVER_PREFIX}{}", self.provider)
+    }
+}
+
+/// Where a native tool goes in a request
+#[derive(Debug, Clone, PartialEq)]
+pub enum NativeToolEntry {
+    /// An entry of `tools`
+    Tool(Value),
+    /// A top-level field of the request
+    Field(&'static str, Value),
+}
+
+fn with_options(mut definition: Map<String, Value>, options: &Map<String, Value>) -> Value {
+    for (key, value) in options {
+        definition.insert(key.clone(), value.clone());
+    }
+    Value::Object(definition)
+}
+
+/// The request entry of `tool` for the dialect, `None` when the provider doesn't offer it
+pub fn native_tool_entry(dialect: NativeToolDialect, tool: &NativeTool) -> Option<NativeToolEntry> {
+    let definition = |kind: &str, name: Option<&str>| {
+        let mut definition = Map::new();
+        definition.insert("type".to_string(), json!(kind));
+        if let Some(name) = name {
+            definition.insert("name".to_string(), json!(name));
+        }
+        with_options(definition, &tool.options)
+    };
+    let name = tool.kind.name();
+    match (dialect, tool.kind) {
+        (NativeToolDialect::Anthropic, NativeToolKind::WebSearch) => Some(NativeToolEntry::Tool(
+            definition(ANTHROPIC_WEB_SEARCH_TYPE, Some(name)),
+        )),
+        (NativeToolDialect::Anthropic, NativeToolKind::CodeExecution) => Some(
+            NativeToolEntry::Tool(definition(ANTHROPIC_CODE_EXECUTION_TYPE, Some(name))),
+        ),
+        (NativeToolDialect::Anthropic, NativeToolKind::FileSearch) => None,
+        // Chat completions search through the options of the request rather than a tool
+        (NativeToolDialect::OpenAi, NativeToolKind::WebSearch) => Some(NativeToolEntry::Field(
+            "web_search_options",
+            Value::Object(tool.options.clone()),
+        )),
+        (NativeToolDialect::OpenAi, NativeToolKind::FileSearch) => {
+            Some(NativeToolEntry::Tool(definition("file_search", None)))
+        }
+        (NativeToolDialect::OpenAi, NativeToolKind::CodeExecution) => {
+            let mut code_interpreter = Map::new();
+            code_interpreter.insert("type".to_string(), json!("code_interpreter"));
+            code_interpreter.insert("container".to_string(), json!({"type": "auto"}));
+            Some(NativeToolEntry::Tool(with_options(
+                code_interpreter,
+                &tool.options,
+            )))
+        }
+    }
+}
+
+/// Write `tools` into a request body, after the tools already in it. Returns the number of
+/// tools added.
+pub fn apply_native_tools(
+    body: &mut Value,
+    dialect: NativeToolDialect,
+    tools: &[NativeTool],
+) -> usize {
+    let Some(body) = body.as_object_mut() else {
+        return 0;
+    };
+    let mut added = 0;
+    for tool in tools {
+        match native_tool_entry(dialect, tool) {
+            Some(NativeToolEntry::Tool(entry)) => {
+                let entries = body.entry("tools").or_insert_with(|| json!([]));
+                if let Some(entries) = entries.as_array_mut() {
+                    entries.push(entry);
+                    added += 1;
+                }
+            }
+            Some(NativeToolEntry::Field(field, value)) => {
+                body.insert(field.to_string(), value);
+                added += 1;
+            }
+            None => log::debug!("{:?} has no native {} tool", dialect, tool.kind.name()),
+        }
+    }
+    added
+}
+
+/// The native tools a run may offer: those the approval policies allow, asking the user
+/// where they say so. Refused tools are left out rather than failing the run.
+pub async fn authorize_native_tools<R: Runtime>(
+    app: &AppHandle<R>,
+    native: &NativeTools,
+    assistant_id: Option<&str>,
+    cancel: Option<&CancellationToken>,
+) -> Vec<NativeTool> {
+    let approvals = app.state::<ToolApprovalState>();
+    let server = native.server();
+    let mut allowed = Vec::new();
+    for tool in &native.tools {
+        let authorized = authorize_tool_call(
+            app,
+            &approvals,
+            &server,
+            tool.kind.name(),
+            Some(&tool.options),
+            assistant_id,
+            cancel,
+        )
+        .await;
+        match authorized {
+            Ok(()) => allowed.push(tool.clone()),
+            Err(e) => log::info!("Not offering native tool: {e}"),
+        }
+    }
+    allowed
+}
@@ -0,0 +1,176 @@
+//! Reasoning segments of model output. Streams carry them as `StreamEvent::ReasoningDelta`,
+//! whichever way the provider sends them; local models that write `<think>` tags into their
+//! answer are split by `ThinkTagSplitter`. The `reasoning.retention` setting decides whether
+//! they are stored with a message and sent back to the model with later turns.
+
+use serde_json::{json, Value};
+
+use super::models::StreamEvent;
+use crate::core::settings::models::ReasoningRetention;
+use crate::core::threads::export::split_reasoning;
+
+pub const THINK_OPEN_TAG: &str = "<think>";
+pub const THINK_CLOSE_TAG: &str = "</think>";
+/// Content part type of reasoning stored with a thread message
+pub const REASONING_PART_TYPE: &str = "reasoning";
+/// Message fields OpenAI-compatible providers use for reasoning; the first one is written
+pub const REASONING_FIELDS: &[&str] = &["reasoning_content", "reasoning"];
+
+/// Splits `<think>…</think>` blocks out of streamed text. Tags may be cut across chunks, so
+/// text that could be the start of a tag is held back until the next chunk decides it.
+#[derive(Debug, Default)]
+pub struct ThinkTagSplitter {
+    thinking: bool,
+    pending: String,
+}
+
+/// Length of the longest proper prefix of `tag` that `text` ends with
+fn partial_tag_len(text: &str, tag: &str) -> usize {
+    (1..tag.len())
+        .rev()
+        .find(|&len| text.ends_with(&tag[..len]))
+        .unwrap_or(0)
+}
+
+impl ThinkTagSplitter {
+    /// Text and reasoning events for the next chunk of streamed text
+    pub fn push(&mut self, text: &str) -> Vec<StreamEvent> {
+        let mut rest = std::mem::take(&mut self.pending);
+        rest.push_str(text);
+        let mut events = Vec::new();
+        loop {
+            let tag = if self.thinking {
+                THINK_CLOSE_TAG
+            } else {
+                THINK_OPEN_TAG
+            };
+            match rest.find(tag) {
+                Some(start) => {
+                    events.extend(self.event(&rest[..start]));
+                    rest.drain(..start + tag.len());
+                    self.thinking = !self.thinking;
+                }
+                None => {
+                    let split = rest.len() - partial_tag_len(&rest, tag);
+                    events.extend(self.event(&rest[..split]));
+                    self.pending = rest[split..].to_string();
+                    return events;
+                }
+            }
+        }
+    }
+
+    /// Text held back at the end of the stream
+    pub fn flush(&mut self) -> Vec<StreamEvent> {
+        let rest = std::mem::take(&mut self.pending);
+        self.event(&rest).into_iter().collect()
+    }
+
+    fn event(&self, text: &str) -> Option<StreamEvent> {
+        if text.is_empty() {
+            return None;
+        }
+        let text = text.to_string();
+        Some(if self.thinking {
+            StreamEvent::ReasoningDelta { text }
+        } else {
+            StreamEvent::TextDelta { text }
+        })
+    }
+}
+
+/// Text of a content part, written either as `"text": "…"` or `"text": {"value": "…"}`
+fn part_text(part: &Value) -> Option<&str> {
+    match part.get("text")? {
+        Value::String(text) => Some(text),
+        text => text.get("value").and_then(Value::as_str),
+    }
+}
+
+fn set_part_text(part: &mut Value, value: &str) {
+    match part.get_mut("text") {
+        Some(Value::Object(text)) => {
+            text.insert("value".to_string(), json!(value));
+        }
+        _ => part["text"] = json!(value),
+    }
+}
+
+/// Take the reasoning out of a message: its reasoning fields and parts and the `<think>`
+/// blocks of its text. Returns the reasoning, in order.
+fn take_reasoning(message: &mut Value) -> Vec<String> {
+    let mut reasoning = Vec::new();
+    let Some(object) = message.as_object_mut() else {
+        return reasoning;
+    };
+    for field in REASONING_FIELDS {
+        if let Some(Value::String(text)) = object.remove(*field) {
+            reasoning.push(text);
+        }
+    }
+    match object.get_mut("content") {
+        Some(Value::String(content)) => {
+            if let (Some(thought), answer) = split_reasoning(content) {
+                reasoning.push(thought.to_string());
+                *content = answer.to_string();
+            }
+        }
+        Some(Value::Array(parts)) => {
+            for part in parts.iter_mut() {
+                let is_reasoning =
+                    part.get("type").and_then(Value::as_str) == Some(REASONING_PART_TYPE);
+                let Some(text) = part_text(part).map(str::to_string) else {
+                    continue;
+                };
+                if is_reasoning {
+                    reasoning.push(text);
+                } else if let (Some(thought), answer) = split_reasoning(&text) {
+                    reasoning.push(thought.to_string());
+                    let answer = answer.to_string();
+                    set_part_text(part, &answer);
+                }
+            }
+            parts.retain(|part| {
+                part.get("type").and_then(Value::as_str) != Some(REASONING_PART_TYPE)
+            });
+        }
+        _ => {}
+    }
+    reasoning.retain(|text| !text.trim().is_empty());
+    reasoning
+}
+
+/// Prepare an assistant message for storage: its reasoning goes into a `reasoning` part in
+/// front of the answer, or is dropped when the retention doesn't keep it
+pub fn store_reasoning(message: &mut Value, retention: ReasoningRetention) {
+    if message.get("role").and_then(Value::as_str) != Some("assistant") {
+        return;
+    }
+    let reasoning = take_reasoning(message);
+    if reasoning.is_empty() || retention == ReasoningRetention::Strip {
+        return;
+    }
+    let part = json!({
+        "type": REASONING_PART_TYPE,
+        "text": { "value": reasoning.join("\n\n"), "annotations": [] },
+    });
+    match message.get_mut("content") {
+        Some(Value::Array(parts)) => parts.insert(0, part),
+        _ => message[REASONING_FIELDS[0]] = json!(reasoning.join("\n\n")),
+    }
+}
+
+/// Prepare a conversation for being sent to a model: the reasoning of earlier assistant
+/// messages goes along as `reasoning_content` when the retention says so, and is dropped
+/// otherwise
+pub fn context_reasoning(messages: &mut [Value], retention: ReasoningRetention) {
+    for message in messages {
+        if message.get("role").and_then(Value::as_str) != Some("assistant") {
+            continue;
+        }
+        let reasoning = take_reasoning(message);
+        if !reasoning.is_empty() && retention == ReasoningRetention::Context {
+            message[REASONING_FIELDS[0]] = json!(reasoning.join("\n\n"));
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use futures_util::{Stream, StreamExt};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+
+use super::models::RequestPolicy;
+use crate::core::state::ProviderConfig;
+
+pub const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 15;
+pub const DEFAULT_MAX_RETRIES: u32 = 2;
+pub const DEFAULT_RETRY_ON_STATUSES: [u16; 6] = [408, 429, 502, 503, 504, 529];
+pub const DEFAULT_BACKOFF_INITIAL_MS: u64 = 500;
+pub const DEFAULT_BACKOFF_MAX_MS: u64 = 8_000;
+
+/// Ceilings for provider overrides, so a typo can't stall a chat for hours
+pub const MAX_RETRIES_LIMIT: u32 = 10;
+pub const MAX_TIMEOUT_SECS: u64 = 3_600;
+pub const MAX_BACKOFF_MS: u64 = 60_000;
+
+impl Default for RequestPolicy {
+    fn default() -> Self {
+        Self {
+            connect_timeout_secs: DEFAULT_CONNECT_TIMEOUT_SECS,
+            read_timeout_secs: None,
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_on_statuses: DEFAULT_RETRY_ON_STATUSES.to_vec(),
+            backoff_initial_ms: DEFAULT_BACKOFF_INITIAL_MS,
+            backoff_max_ms: DEFAULT_BACKOFF_MAX_MS,
+        }
+    }
+}
+
+impl RequestPolicy {
+    /// Policy for engines on this machine: a refused connection means the engine is gone,
+    /// not that the network is flaky
+    pub fn local() -> Self {
+        Self {
+            max_retries: 0,
+            ..Self::default()
+        }
+    }
+
+    /// Apply a provider's overrides on top of the defaults, clamped to sane ranges
+    pub fn from_provider(config: &ProviderConfig) -> Self {
+        let defaults = Self::default();
+        let backoff_max_ms = config
+            .backoff_max_ms
+            .unwrap_or(defaults.backoff_max_ms)
+            .clamp(1, MAX_BACKOFF_MS);
+        Self {
+            connect_timeout_secs: config
+                .connect_timeout_secs
+                .unwrap_or(defaults.connect_timeout_secs)
+                .clamp(1, MAX_TIMEOUT_SECS),
+            read_timeout_secs: config
+                .read_timeout_secs
+                .filter(|secs| *secs > 0)
+                .map(|secs| secs.min(MAX_TIMEOUT_SECS)),
+            max_retries: config
+                .max_retries
+                .unwrap_or(defaults.max_retries)
+                .min(MAX_RETRIES_LIMIT),
+            retry_on_statuses: config
+                .retry_on_statuses
+                .clone()
+                .unwrap_or(defaults.retry_on_statuses),
+            backoff_initial_ms: config
+                .backoff_initial_ms
+                .unwrap_or(defaults.backoff_initial_ms)
+                .clamp(1, backoff_max_ms),
+            backoff_max_ms,
+        }
+    }
+
+    pub fn connect_timeout(&self) -> Duration {
+        Duration::from_secs(self.connect_timeout_secs)
+    }
+
+    pub fn read_timeout(&self) -> Option<Duration> {
+        self.read_timeout_secs.map(Duration::from_secs)
+    }
+
+    pub fn should_retry_status(&self, status: StatusCode) -> bool {
+        self.retry_on_statuses.contains(&status.as_u16())
+    }
+
+    /// Exponential backoff before retry number `retry` (0-based), capped at `backoff_max_ms`
+    pub fn backoff_delay(&self, retry: u32) -> Duration {
+        let delay = self
+            .backoff_initial_ms
+            .saturating_mul(1u64 << retry.min(20))
+            .min(self.backoff_max_ms);
+        Duration::from_millis(delay)
+    }
+
+    /// Delay before retry number `retry`: the server's `Retry-After` seconds when given, capped
+    /// at `backoff_max_ms`, otherwise the exponential backoff
+    pub fn retry_delay(&self, retry: u32, retry_after: Option<&str>) -> Duration {
+        retry_after
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .map(|secs| Duration::from_secs(secs).min(Duration::from_millis(self.backoff_max_ms)))
+            .unwrap_or_else(|| self.backoff_delay(retry))
+    }
+}
+
+/// Shared client for a connect timeout and overall timeout. Clients are cached so requests
+/// with the same policy keep reusing pooled connections.
+pub fn policy_client(policy: &RequestPolicy, timeout: Duration) -> Result<Client, String> {
+    static CLIENTS: OnceLock<Mutex<HashMap<(u64, Duration), Client>>> = OnceLock::new();
+    let key = (policy.connect_timeout_secs, timeout);
+    let mut clients = CLIENTS
+        .get_or_init(Default::default)
+        .lock()
+        .map_err(|e| e.to_string())?;
+    if let Some(client) = clients.get(&key) {
+        return Ok(client.clone());
+    }
+    let client = Client::builder()
+        .connect_timeout(policy.connect_timeout())
+        .timeout(timeout)
+        .build()
+        .map_err(|e| e.to_string())?;
+    clients.insert(key, client.clone());
+    Ok(client)
+}
+
+struct SendError {
+    message: String,
+    retryable: bool,
+}
+
+async fn send_once(policy: &RequestPolicy, request: RequestBuilder) -> Result<Response, SendError> {
+    let sent = match policy.read_timeout() {
+        Some(read_timeout) => match tokio::time::timeout(read_timeout, request.send()).await {
+            Ok(sent) => sent,
+            Err(_) => {
+                return Err(SendError {
+                    message: format!("no response within {}s", read_timeout.as_secs()),
+                    retryable: true,
+                })
+            }
+        },
+        None => request.send().await,
+    };
+    sent.map_err(|e| SendError {
+        retryable: e.is_connect() || e.is_timeout(),
+        message: e.to_string(),
+    })
+}
+
+/// Send `request`, retrying connection failures, timeouts and the policy's retryable statuses
+/// with exponential backoff. Once retries are exhausted the last response is returned as is,
+/// so callers see the provider's own error. Requests with a streaming body can't be replayed
+/// and are sent once.
+pub async fn send_with_retry(
+    policy: &RequestPolicy,
+    request: RequestBuilder,
+) -> Result<Response, String> {
+    let mut retry = 0;
+    loop {
+        let Some(attempt) = request.try_clone() else {
+            return send_once(policy, request).await.map_err(|e| e.message);
+        };
+        let delay = match send_once(policy, attempt).await {
+            Ok(response)
+                if retry < policy.max_retries && policy.should_retry_status(response.status()) =>
+            {
+                let retry_after = response
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok());
+                let delay = policy.retry_delay(retry, retry_after);
+                log::warn!(
+                    "Provider responded with {}, retrying in {delay:?} ({}/{})",
+                    response.status(),
+                    retry + 1,
+                    policy.max_retries
+                );
+                delay
+            }
+            Err(e) if e.retryable && retry < policy.max_retries => {
+                let delay = policy.backoff_delay(retry);
+                log::warn!(
+                    "Provider request failed: {}, retrying in {delay:?} ({}/{})",
+                    e.message,
+                    retry + 1,
+                    policy.max_retries
+                );
+                delay
+            }
+            result => return result.map_err(|e| e.message),
+        };
+        tokio::time::sleep(delay).await;
+        retry += 1;
+    }
+}
+
+/// Next item of a response body stream, failing when the stream stalls for longer than the
+/// read timeout
+pub async fn next_chunk<S, T, E>(
+    stream: &mut S,
+    read_timeout: Option<Duration>,
+) -> Option<Result<T, String>>
+where
+    S: Stream<Item = Result<T, E>> + Unpin,
+    E: std::fmt::Display,
+{
+    let next = match read_timeout {
+        Some(read_timeout) => match tokio::time::timeout(read_timeout, stream.next()).await {
+            Ok(next) => next,
+            Err(_) => {
+                return Some(Err(format!(
+                    "no data received for {}s",
+                    read_timeout.as_secs()
+                )))
+            }
+        },
+        None => stream.next().await,
+    };
+    next.map(|item| item.map_err(|e| e.to_string()))
+}
@@ -0,0 +1,490 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::errors::classify_stream_error;
+use super::models::{StreamEvent, StreamFormat};
+use super::reasoning::ThinkTagSplitter;
+
+/// Server-sent event payload
+#[derive(Debug, PartialEq)]
+pub enum SseData {
+    Json(Value),
+    Done,
+}
+
+/// Parse a single SSE line, ignoring comments, other fields and malformed payloads
+pub fn parse_sse_line(line: &str) -> Option<SseData> {
+    let data = line.trim().strip_prefix("data:")?.trim();
+    if data == "[DONE]" {
+        return Some(SseData::Done);
+    }
+    serde_json::from_str(data).ok().map(SseData::Json)
+}
+
+/// Reassembles SSE lines from network chunks. Lines are split on raw bytes so multi-byte
+/// characters and lines spanning chunk boundaries stay intact.
+#[derive(Debug, Default)]
+pub struct SseLineBuffer {
+    buffer: Vec<u8>,
+}
+
+impl SseLineBuffer {
+    /// Append a network chunk and return the payloads of the lines it completed
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<SseData> {
+        self.buffer.extend_from_slice(chunk);
+        let mut payloads = Vec::new();
+        while let Some(pos) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+            if let Some(data) = parse_sse_line(&String::from_utf8_lossy(&line)) {
+                payloads.push(data);
+            }
+        }
+        payloads
+    }
+
+    /// Parse a trailing line the stream ended without terminating
+    pub fn finish(&mut self) -> Option<SseData> {
+        let line = std::mem::take(&mut self.buffer);
+        parse_sse_line(&String::from_utf8_lossy(&line))
+    }
+}
+
+impl StreamFormat {
+    /// Guess the wire format from the shape of a chunk
+    pub fn detect(chunk: &Value) -> Option<Self> {
+        if chunk.get("choices").is_some() {
+            Some(Self::OpenAi)
+        } else if chunk.get("candidates").is_some() || chunk.get("usageMetadata").is_some() {
+            Some(Self::Gemini)
+        } else if chunk
+            .get("type")
+            .and_then(|t| t.as_str())
+            .is_some_and(|t| t.starts_with("message_") || t.starts_with("content_block_"))
+        {
+            Some(Self::Anthropic)
+        } else {
+            None
+        }
+    }
+}
+
+/// Map a provider finish reason to the OpenAI vocabulary used by `StreamEvent::Finish`
+pub fn normalize_finish_reason(reason: &str) -> String {
+    match reason {
+        "end_turn" | "stop_sequence" | "STOP" => "stop",
+        "max_tokens" | "MAX_TOKENS" => "length",
+        "tool_use" => "tool_calls",
+        "SAFETY" | "RECITATION" | "BLOCKLIST" | "PROHIBITED_CONTENT" | "SPII" => "content_filter",
+        other => other,
+    }
+    .to_string()
+}
+
+/// Turns provider stream chunks into canonical `StreamEvent`s. The format is detected from
+/// the first recognizable chunk unless it is fixed up front.
+#[derive(Debug, Default)]
+pub struct StreamNormalizer {
+    format: Option<StreamFormat>,
+    /// Anthropic content block index -> tool call index
+    tool_blocks: HashMap<usize, usize>,
+    tool_calls: usize,
+    /// Separates the `<think>` blocks of OpenAI-style content
+    think_tags: ThinkTagSplitter,
+}
+
+impl StreamNormalizer {
+    pub fn new(format: StreamFormat) -> Self {
+        Self {
+            format: Some(format),
+            ..Self::default()
+        }
+    }
+
+    /// Normalize a decoded chunk. Chunks that carry nothing of interest yield no events.
+    pub fn normalize(&mut self, chunk: &Value) -> Vec<StreamEvent> {
+        // Errors look alike in every format and may come before anything that tells it
+        if let Some(error) = classify_stream_error(chunk) {
+            return vec![StreamEvent::Error { error }];
+        }
+        if self.format.is_none() {
+            self.format = StreamFormat::detect(chunk);
+        }
+        match self.format {
+            Some(StreamFormat::OpenAi) => self.normalize_openai(chunk),
+            Some(StreamFormat::Anthropic) => self.normalize_anthropic(chunk),
+            Some(StreamFormat::Gemini) => self.normalize_gemini(chunk),
+            None => Vec::new(),
+        }
+    }
+
+    /// Events held back at the end of the stream, such as text that looked like the start
+    /// of a `<think>` tag
+    pub fn finish(&mut self) -> Vec<StreamEvent> {
+        self.think_tags.flush()
+    }
+
+    fn normalize_openai(&mut self, chunk: &Value) -> Vec<StreamEvent> {
+        let mut events = Vec::new();
+        let choice = chunk.get("choices").and_then(|c| c.get(0));
+        let delta = choice.and_then(|c| c.get("delta"));
+
+        // `reasoning_content` (DeepSeek, llama.cpp) or `reasoning` (OpenRouter, Ollama)
+        if let Some(text) = delta
+            .and_then(|d| d.get("reasoning_content").or_else(|| d.get("reasoning")))
+            .and_then(|r| r.as_str())
+            .filter(|t| !t.is_empty())
+        {
+            events.push(StreamEvent::ReasoningDelta {
+                text: text.to_string(),
+            });
+        }
+
+        if let Some(text) = delta
+            .and_then(|d| d.get("content"))
+            .and_then(|c| c.as_str())
+            .filter(|t| !t.is_empty())
+        {
+            events.extend(self.think_tags.push(text));
+        }
+
+        if let Some(calls) = delta
+            .and_then(|d| d.get("tool_calls"))
+            .and_then(|c| c.as_array())
+        {
+            for call in calls {
+                let index = call
+                    .get("index")
+                    .and_then(|i| i.as_u64())
+                    .map(|i| i as usize)
+                    .unwrap_or(self.tool_calls);
+                self.tool_calls = self.tool_calls.max(index + 1);
+                let function = call.get("function");
+                events.push(StreamEvent::ToolCallDelta {
+                    index,
+                    id: call.get("id").and_then(|v| v.as_str()).map(str::to_string),
+                    name: function
+                        .and_then(|f| f.get("name"))
+                        .and_then(|v| v.as_str())
+                        .map(str::to_string),
+                    arguments: function
+                        .and_then(|f| f.get("arguments"))
+                        .and_then(|v| v.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                });
+            }
+        }
+
+        // Only sent when the request asked for `stream_options.include_usage`
+        if let Some(usage) = chunk.get("usage").filter(|u| u.is_object()) {
+            // Automatic prompt caching reports the cached share of the prompt separately
+            events.push(StreamEvent::Usage {
+                input_tokens: usage.get("prompt_tokens").and_then(|v| v.as_u64()),
+                output_tokens: usage.get("completion_tokens").and_then(|v| v.as_u64()),
+                cache_read_tokens: usage
+                    .get("prompt_tokens_details")
+                    .and_then(|d| d.get("cached_tokens"))
+                    .and_then(|v| v.as_u64()),
+                cache_write_tokens: None,
+            });
+        }
+
+        if let Some(reason) = choice
+            .and_then(|c| c.get("finish_reason"))
+            .and_then(|r| r.as_str())
+        {
+            events.extend(self.think_tags.flush());
+            events.push(StreamEvent::Finish {
+                reason: normalize_finish_reason(reason),
+            });
+        }
+        events
+    }
+
+    fn normalize_anthropic(&mut self, chunk: &Value) -> Vec<StreamEvent> {
+        let block_index = chunk
+            .get("index")
+            .and_then(|i| i.as_u64())
+            .map(|i| i as usize)
+            .unwrap_or(0);
+        match chunk.get("type").and_then(|t| t.as_str()) {
+            Some("message_start") => {
+                let usage = chunk.get("message").and_then(|m| m.get("usage"));
+                let count = |key: &str| usage.and_then(|u| u.get(key)).and_then(|v| v.as_u64());
+                // Anthropic leaves cached tokens out of `input_tokens`; count them in, as
+                // OpenAI does
+                let cache_read_tokens = count("cache_read_input_tokens");
+                let cache_write_tokens = count("cache_creation_input_tokens");
+                match count("input_tokens") {
+                    Some(input_tokens) => vec![StreamEvent::Usage {
+                        input_tokens: Some(
+                            input_tokens
+                                + cache_read_tokens.unwrap_or(0)
+                                + cache_write_tokens.unwrap_or(0),
+                        ),
+                        output_tokens: None,
+                        cache_read_tokens,
+                        cache_write_tokens,
+                    }],
+                    None => Vec::new(),
+                }
+            }
+            Some("content_block_start") => {
+                let block = chunk.get("content_block");
+                match block.and_then(|b| b.get("type")).and_then(|t| t.as_str()) {
+                    Some("tool_use") => {
+                        let index = self.tool_calls;
+                        self.tool_calls += 1;
+                        self.tool_blocks.insert(block_index, index);
+                        vec![StreamEvent::ToolCallDelta {
+                            index,
+                            id: block
+                                .and_then(|b| b.get("id"))
+                                .and_then(|v| v.as_str())
+                                .map(str::to_string),
+                            name: block
+                                .and_then(|b| b.get("name"))
+                                .and_then(|v| v.as_str())
+                                .map(str::to_string),
+                            arguments: String::new(),
+                        }]
+                    }
+                    Some("text") => block
+                        .and_then(|b| b.get("text"))
+                        .and_then(|t| t.as_str())
+                        .filter(|t| !t.is_empty())
+                        .map(|text| {
+                            vec![StreamEvent::TextDelta {
+                                text: text.to_string(),
+                            }]
+                        })
+                        .unwrap_or_default(),
+                    Some("thinking") => block
+                        .and_then(|b| b.get("thinking"))
+                        .and_then(|t| t.as_str())
+                        .filter(|t| !t.is_empty())
+                        .map(|text| {
+                            vec![StreamEvent::ReasoningDelta {
+                                text: text.to_string(),
+                            }]
+                        })
+                        .unwrap_or_default(),
+                    _ => Vec::new(),
+                }
+            }
+            Some("content_block_delta") => {
+                let delta = chunk.get("delta");
+                match delta.and_then(|d| d.get("type")).and_then(|t| t.as_str()) {
+                    Some("text_delta") => delta
+                        .and_then(|d| d.get("text"))
+                        .and_then(|t| t.as_str())
+                        .filter(|t| !t.is_empty())
+                        .map(|text| {
+                            vec![StreamEvent::TextDelta {
+                                text: text.to_string(),
+                            }]
+                        })
+                        .unwrap_or_default(),
+                    Some("thinking_delta") => delta
+                        .and_then(|d| d.get("thinking"))
+                        .and_then(|t| t.as_str())
+                        .filter(|t| !t.is_empty())
+                        .map(|text| {
+                            vec![StreamEvent::ReasoningDelta {
+                                text: text.to_string(),
+                            }]
+                        })
+                        .unwrap_or_default(),
+                    Some("input_json_delta") => {
+                        let Some(&index) = self.tool_blocks.get(&block_index) else {
+                            return Vec::new();
+                        };
+                        vec![StreamEvent::ToolCallDelta {
+                            index,
+                            id: None,
+                            name: None,
+                            arguments: delta
+                                .and_then(|d| d.get("partial_json"))
+                                .and_then(|v| v.as_str())
+                                .unwrap_or_default()
+                                .to_string(),
+                        }]
+                    }
+                    _ => Vec::new(),
+                }
+            }
+            Some("message_delta") => {
+                let mut events = Vec::new();
+                if let Some(output_tokens) = chunk
+                    .get("usage")
+                    .and_then(|u| u.get("output_tokens"))
+                    .and_then(|v| v.as_u64())
+                {
+                    events.push(StreamEvent::Usage {
+                        input_tokens: None,
+                        output_tokens: Some(output_tokens),
+                        cache_read_tokens: None,
+                        cache_write_tokens: None,
+                    });
+                }
+                if let Some(reason) = chunk
+                    .get("delta")
+                    .and_then(|d| d.get("stop_reason"))
+                    .and_then(|r| r.as_str())
+                {
+                    events.push(StreamEvent::Finish {
+                        reason: normalize_finish_reason(reason),
+                    });
+                }
+                events
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn normalize_gemini(&mut self, chunk: &Value) -> Vec<StreamEvent> {
+        let mut events = Vec::new();
+        let candidate = chunk.get("candidates").and_then(|c| c.get(0));
+        let parts = candidate
+            .and_then(|c| c.get("content"))
+            .and_then(|c| c.get("parts"))
+            .and_then(|p| p.as_array());
+
+        for part in parts.into_iter().flatten() {
+            if let Some(text) = part
+                .get("text")
+                .and_then(|t| t.as_str())
+                .filter(|t| !t.is_empty())
+            {
+                // Thought summaries are reasoning, not part of the answer
+                let text = text.to_string();
+                let thought = part.get("thought").and_then(|t| t.as_bool()) == Some(true);
+                events.push(if thought {
+                    StreamEvent::ReasoningDelta { text }
+                } else {
+                    StreamEvent::TextDelta { text }
+                });
+            }
+            // Gemini sends each function call whole, so it becomes a single complete delta
+            if let Some(call) = part.get("functionCall") {
+                let index = self.tool_calls;
+                self.tool_calls += 1;
+                events.push(StreamEvent::ToolCallDelta {
+                    index,
+                    id: call.get("id").and_then(|v| v.as_str()).map(str::to_string),
+                    name: call
+                        .get("name")
+                        .and_then(|v| v.as_str())
+                        .map(str::to_string),
+                    arguments: call
+                        .get("args")
+                        .map(|args| args.to_string())
+                        .unwrap_or_else(|| "{}".to_string()),
+                });
+            }
+        }
+
+        if let Some(usage) = chunk.get("usageMetadata") {
+            events.push(StreamEvent::Usage {
+                input_tokens: usage.get("promptTokenCount").and_then(|v| v.as_u64()),
+                output_tokens: usage.get("candidatesTokenCount").and_then(|v| v.as_u64()),
+                cache_read_tokens: usage
+                    .get("cachedContentTokenCount")
+                    .and_then(|v| v.as_u64()),
+                cache_write_tokens: None,
+            });
+        }
+
+        if let Some(reason) = candidate
+            .and_then(|c| c.get("finishReason"))
+            .and_then(|r| r.as_str())
+        {
+            // Gemini reports STOP even when the turn ends in function calls
+            let reason = match normalize_finish_reason(reason) {
+                r if r == "stop" && self.tool_calls > 0 => "tool_calls".to_string(),
+                r => r,
+            };
+            events.push(StreamEvent::Finish { reason });
+        }
+        events
+    }
+}
+
+/// Best-effort parse of a JSON document that is still being streamed. Open strings and
+/// containers are closed and a trailing incomplete member is dropped, so
+/// `{"path": "/tmp/a", "content": "hel` yields `{"path": "/tmp/a", "content": "hel"}`.
+/// Returns `None` when nothing usable has been produced yet.
+pub fn parse_partial_json(input: &str) -> Option<Value> {
+    let input = input.trim();
+    if input.is_empty() {
+        return None;
+    }
+    if let Ok(value) = serde_json::from_str(input) {
+        return Some(value);
+    }
+    let mut end = input.len();
+    loop {
+        if let Some(value) =
+            complete_json(&input[..end]).and_then(|s| serde_json::from_str(&s).ok())
+        {
+            return Some(value);
+        }
+        // Step back to the previous member boundary and try again
+        end = input[..end]
+            .char_indices()
+            .rev()
+            .find_map(|(i, c)| match c {
+                ',' if i < end => Some(i),
+                '{' | '[' if i + 1 < end => Some(i + 1),
+                _ => None,
+            })
+            .filter(|&e| e > 0)?;
+    }
+}
+
+/// Close the open strings and containers of a truncated JSON document
+fn complete_json(partial: &str) -> Option<String> {
+    let mut stack = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in partial.chars() {
+        if in_string {
+            match (escaped, c) {
+                (true, _) => escaped = false,
+                (false, '\\') => escaped = true,
+                (false, '"') => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => stack.push('}'),
+            '[' => stack.push(']'),
+            '}' | ']' => {
+                if stack.pop() != Some(c) {
+                    return None;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut completed = partial.to_string();
+    if in_string {
+        if escaped {
+            completed.pop();
+        }
+        completed.push('"');
+    }
+    let trimmed_len = completed.trim_end().len();
+    completed.truncate(trimmed_len);
+    if completed.ends_with(',') {
+        completed.pop();
+    } else if completed.ends_with(':') {
+        completed.push_str("null");
+    }
+    completed.extend(stack.iter().rev());
+    Some(completed)
+}
@@ -0,0 +1,792 @@
+use std::time::Duration;
+
+use super::cache_control::{apply_anthropic_cache_control, request_stream_usage};
+use super::errors::{
+    classify_response, classify_stream_error, content_filter_error, network_error,
+};
+use super::models::{
+    NativeTool, NativeToolDialect, NativeToolKind, NativeTools, ProviderErrorKind, Recovery,
+    RequestPolicy, StreamEvent, StreamFormat, TokenUsage, ToolSchemaLimits,
+};
+use super::native_tools::{apply_native_tools, dialect_for, ANTHROPIC_WEB_SEARCH_TYPE};
+use super::reasoning::{context_reasoning, store_reasoning, ThinkTagSplitter};
+use super::retry::{send_with_retry, DEFAULT_MAX_RETRIES, MAX_RETRIES_LIMIT};
+use super::stream::{
+    normalize_finish_reason, parse_partial_json, SseData, SseLineBuffer, StreamNormalizer,
+};
+use super::tool_schema::{
+    compact_request_tools, compact_schema, DEFAULT_MAX_SCHEMA_DESCRIPTION_CHARS,
+};
+use crate::core::settings::models::ReasoningRetention;
+use crate::core::state::ProviderConfig;
+use serde_json::json;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+fn normalize_all(chunks: &[serde_json::Value]) -> Vec<StreamEvent> {
+    let mut normalizer = StreamNormalizer::default();
+    chunks
+        .iter()
+        .flat_map(|chunk| normalizer.normalize(chunk))
+        .collect()
+}
+
+#[test]
+fn test_sse_line_buffer_reassembles_split_lines() {
+    let mut buffer = SseLineBuffer::default();
+    assert!(buffer.push(b"data: {\"a\":").is_empty());
+    let payloads = buffer.push(b"1}\n\n: ping\ndata: [DONE]\n");
+    assert_eq!(
+        payloads,
+        vec![SseData::Json(json!({"a": 1})), SseData::Done]
+    );
+    assert!(buffer.push(b"data: {\"b\":2}").is_empty());
+    assert_eq!(buffer.finish(), Some(SseData::Json(json!({"b": 2}))));
+}
+
+#[test]
+fn test_detect_stream_format() {
+    assert_eq!(
+        StreamFormat::detect(&json!({"choices": []})),
+        Some(StreamFormat::OpenAi)
+    );
+    assert_eq!(
+        StreamFormat::detect(&json!({"type": "message_start", "message": {}})),
+        Some(StreamFormat::Anthropic)
+    );
+    assert_eq!(
+        StreamFormat::detect(&json!({"candidates": []})),
+        Some(StreamFormat::Gemini)
+    );
+    assert_eq!(StreamFormat::detect(&json!({"type": "ping"})), None);
+}
+
+#[test]
+fn test_normalize_openai_stream() {
+    let events = normalize_all(&[
+        json!({"choices": [{"delta": {"role": "assistant", "content": "Hi"}}]}),
+        json!({"choices": [{"delta": {"tool_calls": [
+            {"index": 0, "id": "call_a", "function": {"name": "fetch", "arguments": "{\"u"}}
+        ]}}]}),
+        json!({"choices": [{"delta": {}, "finish_reason": "tool_calls"}]}),
+        json!({"choices": [], "usage": {"prompt_tokens": 12, "completion_tokens": 5}}),
+    ]);
+    assert_eq!(
+        events,
+        vec![
+            StreamEvent::TextDelta {
+                text: "Hi".to_string()
+            },
+            StreamEvent::ToolCallDelta {
+                index: 0,
+                id: Some("call_a".to_string()),
+                name: Some("fetch".to_string()),
+                arguments: "{\"u".to_string(),
+            },
+            StreamEvent::Finish {
+                reason: "tool_calls".to_string()
+            },
+            StreamEvent::Usage {
+                input_tokens: Some(12),
+                output_tokens: Some(5),
+                cache_read_tokens: None,
+                cache_write_tokens: None
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_normalize_anthropic_stream() {
+    let events = normalize_all(&[
+        json!({"type": "message_start", "message": {"usage": {"input_tokens": 20}}}),
+        json!({"type": "content_block_start", "index": 0, "content_block": {"type": "text", "text": ""}}),
+        json!({"type": "content_block_delta", "index": 0, "delta": {"type": "text_delta", "text": "Let me check"}}),
+        json!({"type": "content_block_stop", "index": 0}),
+        json!({"type": "content_block_start", "index": 1, "content_block": {"type": "tool_use", "id": "toolu_1", "name": "search", "input": {}}}),
+        json!({"type": "content_block_delta", "index": 1, "delta": {"type": "input_json_delta", "partial_json": "{\"q\":1}"}}),
+        json!({"type": "message_delta", "delta": {"stop_reason": "tool_use"}, "usage": {"output_tokens": 9}}),
+        json!({"type": "message_stop"}),
+    ]);
+    assert_eq!(
+        events,
+        vec![
+            StreamEvent::Usage {
+                input_tokens: Some(20),
+                output_tokens: None,
+                cache_read_tokens: None,
+                cache_write_tokens: None
+            },
+            StreamEvent::TextDelta {
+                text: "Let me check".to_string()
+            },
+            StreamEvent::ToolCallDelta {
+                index: 0,
+                id: Some("toolu_1".to_string()),
+                name: Some("search".to_string()),
+                arguments: String::new(),
+            },
+            StreamEvent::ToolCallDelta {
+                index: 0,
+                id: None,
+                name: None,
+                arguments: "{\"q\":1}".to_string(),
+            },
+            StreamEvent::Usage {
+                input_tokens: None,
+                output_tokens: Some(9),
+                cache_read_tokens: None,
+                cache_write_tokens: None
+            },
+            StreamEvent::Finish {
+                reason: "tool_calls".to_string()
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_normalize_gemini_stream() {
+    let events = normalize_all(&[
+        json!({"candidates": [{"content": {"parts": [{"text": "Sure"}]}}]}),
+        json!({
+            "candidates": [{
+                "content": {"parts": [{"functionCall": {"name": "search", "args": {"q": "rust"}}}]},
+                "finishReason": "STOP"
+            }],
+            "usageMetadata": {"promptTokenCount": 7, "candidatesTokenCount": 3}
+        }),
+    ]);
+    assert_eq!(
+        events,
+        vec![
+            StreamEvent::TextDelta {
+                text: "Sure".to_string()
+            },
+            StreamEvent::ToolCallDelta {
+                index: 0,
+                id: None,
+                name: Some("search".to_string()),
+                arguments: "{\"q\":\"rust\"}".to_string(),
+            },
+            StreamEvent::Usage {
+                input_tokens: Some(7),
+                output_tokens: Some(3),
+                cache_read_tokens: None,
+                cache_write_tokens: None
+            },
+            StreamEvent::Finish {
+                reason: "tool_calls".to_string()
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_normalize_finish_reason() {
+    assert_eq!(normalize_finish_reason("end_turn"), "stop");
+    assert_eq!(normalize_finish_reason("MAX_TOKENS"), "length");
+    assert_eq!(normalize_finish_reason("SAFETY"), "content_filter");
+    assert_eq!(normalize_finish_reason("custom"), "custom");
+}
+
+#[test]
+fn test_parse_partial_json() {
+    assert_eq!(parse_partial_json(""), None);
+    assert_eq!(parse_partial_json("{"), Some(json!({})));
+    assert_eq!(
+        parse_partial_json("{\"path\": \"/tmp/a\", \"content\": \"hel"),
+        Some(json!({"path": "/tmp/a", "content": "hel"}))
+    );
+    assert_eq!(
+        parse_partial_json("{\"a\": 1, \"b\": "),
+        Some(json!({"a": 1, "b": null}))
+    );
+    // An unfinished key or literal is dropped
+    assert_eq!(parse_partial_json("{\"a\": 1, \"b"), Some(json!({"a": 1})));
+    assert_eq!(
+        parse_partial_json("{\"a\": [1, tr"),
+        Some(json!({"a": [1]}))
+    );
+    assert_eq!(parse_partial_json("{\"a\": \"x\\"), Some(json!({"a": "x"})));
+    assert_eq!(parse_partial_json("{\"a\": 1}"), Some(json!({"a": 1})));
+}
+
+#[test]
+fn test_request_policy_from_provider() {
+    let policy = RequestPolicy::from_provider(&ProviderConfig::default());
+    assert_eq!(policy, RequestPolicy::default());
+    assert_eq!(policy.max_retries, DEFAULT_MAX_RETRIES);
+    assert_eq!(policy.read_timeout(), None);
+
+    let policy = RequestPolicy::from_provider(&ProviderConfig {
+        connect_timeout_secs: Some(0),
+        read_timeout_secs: Some(30),
+        max_retries: Some(1_000),
+        retry_on_statuses: Some(vec![500]),
+        backoff_initial_ms: Some(90_000),
+        backoff_max_ms: Some(2_000),
+        ..Default::default()
+    });
+    assert_eq!(policy.connect_timeout(), Duration::from_secs(1));
+    assert_eq!(policy.read_timeout(), Some(Duration::from_secs(30)));
+    assert_eq!(policy.max_retries, MAX_RETRIES_LIMIT);
+    assert!(policy.should_retry_status(reqwest::StatusCode::INTERNAL_SERVER_ERROR));
+    assert!(!policy.should_retry_status(reqwest::StatusCode::TOO_MANY_REQUESTS));
+    // The initial delay never exceeds the cap
+    assert_eq!(policy.backoff_initial_ms, 2_000);
+}
+
+#[test]
+fn test_request_policy_backoff() {
+    let policy = RequestPolicy {
+        backoff_initial_ms: 500,
+        backoff_max_ms: 3_000,
+        ..Default::default()
+    };
+    assert_eq!(policy.backoff_delay(0), Duration::from_millis(500));
+    assert_eq!(policy.backoff_delay(1), Duration::from_millis(1_000));
+    assert_eq!(policy.backoff_delay(2), Duration::from_millis(2_000));
+    assert_eq!(policy.backoff_delay(3), Duration::from_millis(3_000));
+    assert_eq!(policy.backoff_delay(64), Duration::from_millis(3_000));
+
+    // Retry-After seconds win over the backoff, but stay under the cap
+    assert_eq!(policy.retry_delay(0, Some("2")), Duration::from_secs(2));
+    assert_eq!(
+        policy.retry_delay(0, Some("120")),
+        Duration::from_millis(3_000)
+    );
+    assert_eq!(
+        policy.retry_delay(1, Some("Wed, 21 Oct 2026 07:28:00 GMT")),
+        Duration::from_millis(1_000)
+    );
+}
+
+/// Serve the given raw HTTP responses to consecutive connections
+async fn serve_responses(responses: Vec<&'static str>) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        for response in responses {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = socket.read(&mut buf).await;
+            socket.write_all(response.as_bytes()).await.unwrap();
+            let _ = socket.shutdown().await;
+        }
+    });
+    format!("http://{addr}/")
+}
+
+#[tokio::test]
+async fn test_send_with_retry_retries_retryable_statuses() {
+    let url = serve_responses(vec![
+        "HTTP/1.1 503 Service Unavailable\r\nconnection: close\r\ncontent-length: 0\r\n\r\n",
+        "HTTP/1.1 200 OK\r\nconnection: close\r\ncontent-length: 2\r\n\r\nok",
+    ])
+    .await;
+    let policy = RequestPolicy {
+        backoff_initial_ms: 1,
+        ..Default::default()
+    };
+    let request = reqwest::Client::new().post(&url).body("{}");
+    let response = send_with_retry(&policy, request).await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(response.text().await.unwrap(), "ok");
+}
+
+#[tokio::test]
+async fn test_send_with_retry_returns_last_response() {
+    let url = serve_responses(vec![
+        "HTTP/1.1 429 Too Many Requests\r\nconnection: close\r\ncontent-length: 0\r\n\r\n",
+        "HTTP/1.1 429 Too Many Requests\r\nconnection: close\r\ncontent-length: 4\r\n\r\nslow",
+    ])
+    .await;
+    let policy = RequestPolicy {
+        max_retries: 1,
+        backoff_initial_ms: 1,
+        ..Default::default()
+    };
+    let request = reqwest::Client::new().post(&url).body("{}");
+    let response = send_with_retry(&policy, request).await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(response.text().await.unwrap(), "slow");
+
+    // Local endpoints fail fast on a refused connection
+    let closed = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/", closed.local_addr().unwrap());
+    drop(closed);
+    let request = reqwest::Client::new().post(&url).body("{}");
+    assert!(send_with_retry(&RequestPolicy::local(), request)
+        .await
+        .is_err());
+}
+
+#[test]
+fn test_normalize_cache_usage() {
+    let events = normalize_all(&[json!({
+        "choices": [],
+        "usage": {
+            "prompt_tokens": 2_000,
+            "completion_tokens": 10,
+            "prompt_tokens_details": {"cached_tokens": 1_920}
+        }
+    })]);
+    assert_eq!(
+        events,
+        vec![StreamEvent::Usage {
+            input_tokens: Some(2_000),
+            output_tokens: Some(10),
+            cache_read_tokens: Some(1_920),
+            cache_write_tokens: None
+        }]
+    );
+
+    // Anthropic reports cached tokens next to, not inside, `input_tokens`
+    let events = normalize_all(&[json!({
+        "type": "message_start",
+        "message": {"usage": {
+            "input_tokens": 12,
+            "cache_read_input_tokens": 1_500,
+            "cache_creation_input_tokens": 300
+        }}
+    })]);
+    assert_eq!(
+        events,
+        vec![StreamEvent::Usage {
+            input_tokens: Some(1_812),
+            output_tokens: None,
+            cache_read_tokens: Some(1_500),
+            cache_write_tokens: Some(300)
+        }]
+    );
+
+    let usage = TokenUsage {
+        input_tokens: 2_000,
+        cache_read_tokens: 1_500,
+        ..Default::default()
+    };
+    assert_eq!(usage.cache_hit_rate(), Some(0.75));
+    assert_eq!(TokenUsage::default().cache_hit_rate(), None);
+}
+
+#[test]
+fn test_apply_anthropic_cache_control() {
+    let mut body = json!({
+        "model": "claude",
+        "system": "You are a careful assistant.",
+        "tools": [{"name": "a"}, {"name": "b"}],
+        "messages": [
+            {"role": "user", "content": "hi"},
+            {"role": "assistant", "content": "hello"},
+            {"role": "user", "content": [{"type": "text", "text": "more"}]}
+        ]
+    });
+    assert_eq!(apply_anthropic_cache_control(&mut body), 3);
+    let ephemeral = json!({"type": "ephemeral"});
+    assert_eq!(body["tools"][1]["cache_control"], ephemeral);
+    assert!(body["tools"][0].get("cache_control").is_none());
+    assert_eq!(
+        body["system"],
+        json!([{"type": "text", "text": "You are a careful assistant.", "cache_control": ephemeral}])
+    );
+    assert_eq!(
+        body["messages"][2]["content"][0]["cache_control"],
+        ephemeral
+    );
+    assert_eq!(body["messages"][0]["content"], "hi");
+
+    // Requests placing their own breakpoints are left alone
+    let before = body.clone();
+    assert_eq!(apply_anthropic_cache_control(&mut body), 0);
+    assert_eq!(body, before);
+}
+
+#[test]
+fn test_request_stream_usage() {
+    let mut body = json!({"stream": true});
+    request_stream_usage(&mut body);
+    assert_eq!(body["stream_options"], json!({"include_usage": true}));
+
+    let mut body = json!({"stream": true, "stream_options": {"include_usage": false}});
+    request_stream_usage(&mut body);
+    assert_eq!(body["stream_options"]["include_usage"], false);
+
+    let mut body = json!({"stream": false});
+    request_stream_usage(&mut body);
+    assert!(body.get("stream_options").is_none());
+}
+
+#[test]
+fn test_tool_schema_limits_from_provider() {
+    let limits = ToolSchemaLimits::from_provider(&ProviderConfig::default()).unwrap();
+    assert_eq!(
+        limits.max_description_chars,
+        DEFAULT_MAX_SCHEMA_DESCRIPTION_CHARS
+    );
+    assert!(ToolSchemaLimits::from_provider(&ProviderConfig {
+        compact_tool_schemas: Some(false),
+        ..Default::default()
+    })
+    .is_none());
+}
+
+#[test]
+fn test_compact_schema() {
+    let limits = ToolSchemaLimits {
+        max_description_chars: 10,
+        max_enum_values: 3,
+    };
+    let mut schema = json!({
+        "type": "object",
+        "$comment": "generated",
+        "properties": {
+            // Property names that look like keywords are kept
+            "description": {"type": "string", "description": "A short one"},
+            "examples": {"type": "array", "examples": [["a"]]},
+            "region": {"enum": ["eu-1", "eu-2", "us-1", "us-2"], "description": "Region"},
+            "mode": {"type": "string", "enum": ["a", "b"]}
+        },
+        "required": ["description"]
+    });
+    assert!(compact_schema(&mut schema, &limits));
+    assert_eq!(schema.get("$comment"), None);
+    let properties = &schema["properties"];
+    assert_eq!(properties["description"]["description"], "A short o…");
+    assert_eq!(properties["examples"], json!({"type": "array"}));
+    assert_eq!(properties["region"].get("enum"), None);
+    assert_eq!(properties["region"]["type"], "string");
+    assert!(properties["region"]["description"]
+        .as_str()
+        .unwrap()
+        .starts_with("Region (O"));
+    assert_eq!(properties["mode"]["enum"], json!(["a", "b"]));
+    assert_eq!(schema["required"], json!(["description"]));
+    // A compacted schema is left as it is
+    assert!(!compact_schema(&mut schema, &limits));
+}
+
+#[test]
+fn test_compact_request_tools() {
+    let limits = ToolSchemaLimits {
+        max_description_chars: 16,
+        max_enum_values: 0,
+    };
+    let description = "x".repeat(100);
+    let mut body = json!({
+        "tools": [
+            {"type": "function", "function": {
+                "name": "search",
+                "parameters": {"type": "object", "properties": {
+                    "q": {"type": "string", "description": description}
+                }}
+            }},
+            {"name": "fetch", "input_schema": {"type": "object", "examples": [{}]}}
+        ]
+    });
+    assert!(compact_request_tools(&mut body, &limits) > 0);
+    let tools = body["tools"].as_array().unwrap();
+    assert_eq!(
+        tools[0]["function"]["parameters"]["properties"]["q"]["description"]
+            .as_str()
+            .unwrap()
+            .chars()
+            .count(),
+        16
+    );
+    assert_eq!(tools[1]["input_schema"], json!({"type": "object"}));
+    assert_eq!(compact_request_tools(&mut json!({}), &limits), 0);
+}
+
+#[test]
+fn test_native_tools() {
+    let tool = |kind, options: serde_json::Value| NativeTool {
+        kind,
+        options: options.as_object().cloned().unwrap_or_default(),
+    };
+    assert_eq!(
+        dialect_for("anthropic", None),
+        Some(NativeToolDialect::Anthropic)
+    );
+    assert_eq!(
+        dialect_for("my-proxy", Some("https://API.openai.com/v1")),
+        Some(NativeToolDialect::OpenAi)
+    );
+    assert_eq!(dialect_for("groq", Some("https://api.groq.com/v1")), None);
+
+    let config = ProviderConfig {
+        provider: "anthropic".to_string(),
+        native_tools: vec![tool(NativeToolKind::WebSearch, json!({"max_uses": 3}))],
+        ..Default::default()
+    };
+    let native = NativeTools::from_provider(&config).unwrap();
+    assert_eq!(native.server(), "provider:anthropic");
+    assert!(NativeTools::from_provider(&ProviderConfig::default()).is_none());
+    assert!(NativeTools::from_provider(&ProviderConfig {
+        provider: "groq".to_string(),
+        ..config.clone()
+    })
+    .is_none());
+
+    // Anthropic: after the MCP tools, file search isn't offered
+    let mut body = json!({"tools": [{"type": "function"}]});
+    let tools = [
+        tool(NativeToolKind::WebSearch, json!({"max_uses": 3})),
+        tool(NativeToolKind::FileSearch, json!({})),
+    ];
+    assert_eq!(
+        apply_native_tools(&mut body, NativeToolDialect::Anthropic, &tools),
+        1
+    );
+    assert_eq!(
+        body["tools"][1],
+        json!({"type": ANTHROPIC_WEB_SEARCH_TYPE, "name": "web_search", "max_uses": 3})
+    );
+
+    // OpenAI: web search is a request option, file search a tool
+    let mut body = json!({});
+    let tools = [
+        tool(
+            NativeToolKind::WebSearch,
+            json!({"search_context_size": "low"}),
+        ),
+        tool(
+            NativeToolKind::FileSearch,
+            json!({"vector_store_ids": ["vs_1"]}),
+        ),
+    ];
+    assert_eq!(
+        apply_native_tools(&mut body, NativeToolDialect::OpenAi, &tools),
+        2
+    );
+    assert_eq!(body["web_search_options"]["search_context_size"], "low");
+    assert_eq!(
+        body["tools"],
+        json!([{"type": "file_search", "vector_store_ids": ["vs_1"]}])
+    );
+
+    let unknown = serde_json::from_value::<NativeTool>(json!({"kind": "computer_use"}));
+    assert!(unknown.is_err());
+}
+
+#[test]
+fn test_normalize_reasoning() {
+    let reasoning = |text: &str| StreamEvent::ReasoningDelta {
+        text: text.to_string(),
+    };
+    let text = |text: &str| StreamEvent::TextDelta {
+        text: text.to_string(),
+    };
+    let events = normalize_all(&[
+        json!({"choices": [{"delta": {"reasoning_content": "Two plus two"}}]}),
+        json!({"choices": [{"delta": {"content": "4"}}]}),
+    ]);
+    assert_eq!(events, vec![reasoning("Two plus two"), text("4")]);
+
+    let events = normalize_all(&[
+        json!({"type": "content_block_start", "index": 0, "content_block": {"type": "thinking", "thinking": ""}}),
+        json!({"type": "content_block_delta", "index": 0, "delta": {"type": "thinking_delta", "thinking": "Hmm"}}),
+        json!({"type": "content_block_delta", "index": 0, "delta": {"type": "signature_delta", "signature": "sig"}}),
+        json!({"type": "content_block_delta", "index": 1, "delta": {"type": "text_delta", "text": "Yes"}}),
+    ]);
+    assert_eq!(events, vec![reasoning("Hmm"), text("Yes")]);
+
+    let events = normalize_all(&[json!({"candidates": [{"content": {"parts": [
+        {"text": "Considering", "thought": true},
+        {"text": "Done"}
+    ]}}]})]);
+    assert_eq!(events, vec![reasoning("Considering"), text("Done")]);
+
+    // Tags of local models, cut across chunks
+    let events = normalize_all(&[
+        json!({"choices": [{"delta": {"content": "<thi"}}]}),
+        json!({"choices": [{"delta": {"content": "nk>plan</th"}}]}),
+        json!({"choices": [{"delta": {"content": "ink>answer <"}}]}),
+        json!({"choices": [{"delta": {}, "finish_reason": "stop"}]}),
+    ]);
+    assert_eq!(
+        events,
+        vec![
+            reasoning("plan"),
+            text("answer "),
+            text("<"),
+            StreamEvent::Finish {
+                reason: "stop".to_string()
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_think_tag_splitter() {
+    let mut splitter = ThinkTagSplitter::default();
+    assert_eq!(
+        splitter.push("a <b> c"),
+        vec![StreamEvent::TextDelta {
+            text: "a <b> c".to_string()
+        }]
+    );
+    assert_eq!(
+        splitter.push("<think>x"),
+        vec![StreamEvent::ReasoningDelta {
+            text: "x".to_string()
+        }]
+    );
+    assert!(splitter.push("</").is_empty());
+    assert_eq!(
+        splitter.flush(),
+        vec![StreamEvent::ReasoningDelta {
+            text: "</".to_string()
+        }]
+    );
+}
+
+#[test]
+fn test_reasoning_retention() {
+    let thread_message = || {
+        json!({
+            "role": "assistant",
+            "content": [{"type": "text", "text": {"value": "<think>plan</think>\n\nanswer", "annotations": []}}]
+        })
+    };
+    let mut message = thread_message();
+    store_reasoning(&mut message, ReasoningRetention::Persist);
+    assert_eq!(message["content"][0]["type"], "reasoning");
+    assert_eq!(message["content"][0]["text"]["value"], "plan");
+    assert_eq!(message["content"][1]["text"]["value"], "answer");
+
+    let mut stripped = thread_message();
+    store_reasoning(&mut stripped, ReasoningRetention::Strip);
+    assert_eq!(stripped["content"].as_array().unwrap().len(), 1);
+    assert_eq!(stripped["content"][0]["text"]["value"], "answer");
+
+    // User messages are stored as written
+    let mut user = json!({"role": "user", "content": "<think>literal</think>"});
+    store_reasoning(&mut user, ReasoningRetention::Strip);
+    assert_eq!(user["content"], "<think>literal</think>");
+
+    let conversation = vec![
+        json!({"role": "user", "content": "2+2?"}),
+        json!({"role": "assistant", "content": "4", "reasoning_content": "add"}),
+    ];
+    let mut sent = conversation.clone();
+    context_reasoning(&mut sent, ReasoningRetention::Persist);
+    assert!(sent[1].get("reasoning_content").is_none());
+    assert_eq!(sent[1]["content"], "4");
+
+    let mut sent = conversation;
+    sent.push(message);
+    context_reasoning(&mut sent, ReasoningRetention::Context);
+    assert_eq!(sent[1]["reasoning_content"], "add");
+    assert_eq!(sent[2]["reasoning_content"], "plan");
+    assert_eq!(sent[2]["content"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn test_classify_response() {
+    let error = classify_response(
+        429,
+        r#"{"error": {"message": "Rate limit reached", "type": "requests", "code": "rate_limit_exceeded"}}"#,
+        Some("7"),
+    );
+    assert_eq!(error.kind, ProviderErrorKind::RateLimit);
+    assert_eq!(error.status, Some(429));
+    assert_eq!(error.code.as_deref(), Some("rate_limit_exceeded"));
+    assert_eq!(error.message, "Rate limit reached");
+    assert_eq!(error.retry_after_secs, Some(7));
+    assert_eq!(error.recovery(), Recovery::Failover);
+
+    // The code wins over the status
+    let error = classify_response(
+        429,
+        r#"{"error": {"message": "You exceeded your current quota", "type": "insufficient_quota", "code": "insufficient_quota"}}"#,
+        None,
+    );
+    assert_eq!(error.kind, ProviderErrorKind::QuotaExceeded);
+
+    let error = classify_response(
+        529,
+        r#"{"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}}"#,
+        None,
+    );
+    assert_eq!(error.kind, ProviderErrorKind::Overloaded);
+    assert_eq!(error.to_string(), "status 529 (overloaded): Overloaded");
+
+    let error = classify_response(
+        400,
+        r#"{"error": {"message": "Your request was rejected", "code": "content_policy_violation"}}"#,
+        None,
+    );
+    assert_eq!(error.kind, ProviderErrorKind::ContentFilter);
+    assert_eq!(error.recovery(), Recovery::Surface);
+
+    let error = classify_response(
+        429,
+        r#"{"error": {"code": 429, "message": "Quota exceeded", "status": "RESOURCE_EXHAUSTED"}}"#,
+        None,
+    );
+    assert_eq!(error.kind, ProviderErrorKind::RateLimit);
+
+    // Bodies that aren't JSON fall back to the status
+    let error = classify_response(503, "Service Unavailable", None);
+    assert_eq!(error.kind, ProviderErrorKind::Overloaded);
+    assert_eq!(error.message, "Service Unavailable");
+    let error = classify_response(401, "", None);
+    assert_eq!(error.kind, ProviderErrorKind::Authentication);
+    assert_eq!(error.recovery(), Recovery::Surface);
+}
+
+#[test]
+fn test_stream_errors() {
+    let error = classify_stream_error(
+        &json!({"type": "error", "error": {"type": "overloaded_error", "message": "Overloaded"}}),
+    )
+    .unwrap();
+    assert_eq!(error.kind, ProviderErrorKind::Overloaded);
+    assert!(error.mid_stream);
+    assert_eq!(error.recovery(), Recovery::Backoff);
+    assert!(classify_stream_error(&json!({"choices": [{"delta": {"content": "Hi"}}]})).is_none());
+
+    let events = normalize_all(&[
+        json!({"choices": [{"delta": {"content": "Hi"}}]}),
+        json!({"error": {"message": "Rate limit reached", "code": "rate_limit_exceeded"}}),
+    ]);
+    assert_eq!(events.len(), 2);
+    match &events[1] {
+        StreamEvent::Error { error } => assert_eq!(error.kind, ProviderErrorKind::RateLimit),
+        event => panic!("unexpected event {event:?}"),
+    }
+
+    let error = network_error("connection reset", true);
+    assert_eq!(error.recovery(), Recovery::Backoff);
+    assert_eq!(error.to_string(), "connection reset (network error)");
+    assert_eq!(
+        network_error("connection refused", false).recovery(),
+        Recovery::Failover
+    );
+}
+
+#[test]
+fn test_content_filter_error() {
+    let error = content_filter_error(Some("content_filter"), false).unwrap();
+    assert_eq!(error.kind, ProviderErrorKind::ContentFilter);
+    assert_eq!(error.recovery(), Recovery::Surface);
+    // A partial answer is kept rather than failing the turn
+    assert!(content_filter_error(Some("content_filter"), true).is_none());
+    assert!(content_filter_error(Some("stop"), false).is_none());
+    assert!(content_filter_error(None, false).is_none());
+}
+
+#[test]
+fn test_provider_error_retry_delay() {
+    let policy = RequestPolicy {
+        backoff_initial_ms: 500,
+        backoff_max_ms: 3_000,
+        ..Default::default()
+    };
+    let mut error = network_error("connection reset", true);
+    assert_eq!(error.retry_delay(&policy, 1), Duration::from_millis(1_000));
+    error.retry_after_secs = Some(2);
+    assert_eq!(error.retry_delay(&policy, 1), Duration::from_secs(2));
+    error.retry_after_secs = Some(60);
+    assert_eq!(error.retry_delay(&policy, 1), Duration::from_millis(3_000));
+}
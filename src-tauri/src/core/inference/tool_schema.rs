@@ -0,0 +1,169 @@
+use serde_json::{Map, Value};
+
+use super::models::ToolSchemaLimits;
+use crate::core::state::ProviderConfig;
+
+pub const DEFAULT_MAX_SCHEMA_DESCRIPTION_CHARS: usize = 256;
+pub const DEFAULT_MAX_SCHEMA_ENUM_VALUES: usize = 32;
+/// Tool descriptions carry the instructions for when to call a tool, so they get more room
+/// than the descriptions of single parameters
+pub const MAX_TOOL_DESCRIPTION_CHARS: usize = 2048;
+/// Values of a collapsed enum that are kept as a hint in the description
+pub const ENUM_HINT_VALUES: usize = 8;
+
+/// Keywords that only document a schema and are dropped when compacting
+const DOC_ONLY_KEYWORDS: [&str; 3] = ["examples", "example", "$comment"];
+/// Keywords whose object keys are names chosen by the server, each mapping to a subschema
+const NAMED_SUBSCHEMA_KEYWORDS: [&str; 5] = [
+    "properties",
+    "patternProperties",
+    "$defs",
+    "definitions",
+    "dependentSchemas",
+];
+/// Keywords holding instance values rather than subschemas
+const VALUE_KEYWORDS: [&str; 4] = ["enum", "const", "default", "required"];
+
+impl ToolSchemaLimits {
+    /// Limits for a provider, or `None` when it opted out of compaction
+    pub fn from_provider(config: &ProviderConfig) -> Option<Self> {
+        if config.compact_tool_schemas == Some(false) {
+            return None;
+        }
+        Some(Self {
+            max_description_chars: config
+                .max_schema_description_chars
+                .unwrap_or(DEFAULT_MAX_SCHEMA_DESCRIPTION_CHARS),
+            max_enum_values: config
+                .max_schema_enum_values
+                .unwrap_or(DEFAULT_MAX_SCHEMA_ENUM_VALUES),
+        })
+    }
+}
+
+/// Cut `text` to `max_chars` characters including the ellipsis that marks the cut
+fn truncate_chars(text: &mut String, max_chars: usize) -> bool {
+    if max_chars == 0 || text.chars().count() <= max_chars {
+        return false;
+    }
+    match text.char_indices().nth(max_chars - 1) {
+        Some((cut, _)) => {
+            text.truncate(cut);
+            let kept = text.trim_end().len();
+            text.truncate(kept);
+            text.push('…');
+            true
+        }
+        None => false,
+    }
+}
+
+fn json_type(value: &Value) -> Option<&'static str> {
+    match value {
+        Value::String(_) => Some("string"),
+        Value::Number(n) if n.is_i64() || n.is_u64() => Some("integer"),
+        Value::Number(_) => Some("number"),
+        Value::Bool(_) => Some("boolean"),
+        _ => None,
+    }
+}
+
+/// Replace an enum longer than `max_values` with its type and a hint listing the first values.
+/// A truncated enum would reject valid values, so the constraint is dropped entirely.
+fn collapse_enum(schema: &mut Map<String, Value>, max_values: usize) -> bool {
+    let values = match schema.get("enum") {
+        Some(Value::Array(values)) if max_values > 0 && values.len() > max_values => values,
+        _ => return false,
+    };
+    let hint = values
+        .iter()
+        .take(ENUM_HINT_VALUES)
+        .map(|value| match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let hint = format!("One of {} values, e.g. {hint}", values.len());
+    let first_type = values.first().and_then(json_type);
+    let uniform_type = first_type.filter(|t| values.iter().all(|v| json_type(v) == Some(*t)));
+    if let Some(value_type) = uniform_type {
+        schema
+            .entry("type")
+            .or_insert_with(|| Value::String(value_type.to_string()));
+    }
+    schema.remove("enum");
+    let description = match schema.get("description").and_then(Value::as_str) {
+        Some(existing) if !existing.is_empty() => format!("{existing} ({hint})"),
+        _ => hint,
+    };
+    schema.insert("description".to_string(), Value::String(description));
+    true
+}
+
+/// Compact a JSON schema in place: drop examples and comments, collapse long enums and truncate
+/// long descriptions. Property names are never touched, even when they look like keywords.
+/// Returns whether anything changed.
+pub fn compact_schema(schema: &mut Value, limits: &ToolSchemaLimits) -> bool {
+    match schema {
+        Value::Object(map) => {
+            let mut changed = false;
+            for keyword in DOC_ONLY_KEYWORDS {
+                changed |= map.remove(keyword).is_some();
+            }
+            // Collapse first so the enum hint is truncated along with the description
+            changed |= collapse_enum(map, limits.max_enum_values);
+            if let Some(Value::String(description)) = map.get_mut("description") {
+                changed |= truncate_chars(description, limits.max_description_chars);
+            }
+            for (keyword, value) in map.iter_mut() {
+                if NAMED_SUBSCHEMA_KEYWORDS.contains(&keyword.as_str()) {
+                    if let Value::Object(named) = value {
+                        for subschema in named.values_mut() {
+                            changed |= compact_schema(subschema, limits);
+                        }
+                    }
+                } else if !VALUE_KEYWORDS.contains(&keyword.as_str()) {
+                    changed |= compact_schema(value, limits);
+                }
+            }
+            changed
+        }
+        Value::Array(items) => items.iter_mut().fold(false, |changed, item| {
+            compact_schema(item, limits) | changed
+        }),
+        _ => false,
+    }
+}
+
+/// Compact the tool definitions of a request body, in OpenAI (`function.parameters`) or
+/// Anthropic (`input_schema`) form. Returns the number of bytes saved.
+pub fn compact_tools(tools: &mut [Value], limits: &ToolSchemaLimits) -> usize {
+    let before: usize = tools.iter().map(|tool| tool.to_string().len()).sum();
+    for tool in tools.iter_mut() {
+        let is_openai = tool.get("function").is_some_and(Value::is_object);
+        let definition = if is_openai {
+            &mut tool["function"]
+        } else {
+            tool
+        };
+        if let Some(Value::String(description)) = definition.get_mut("description") {
+            truncate_chars(description, MAX_TOOL_DESCRIPTION_CHARS);
+        }
+        for key in ["parameters", "input_schema"] {
+            if let Some(schema) = definition.get_mut(key) {
+                compact_schema(schema, limits);
+            }
+        }
+    }
+    let after: usize = tools.iter().map(|tool| tool.to_string().len()).sum();
+    before.saturating_sub(after)
+}
+
+/// Compact the `tools` of a chat request body in place. Returns the number of bytes saved.
+pub fn compact_request_tools(body: &mut Value, limits: &ToolSchemaLimits) -> usize {
+    match body.get_mut("tools").and_then(Value::as_array_mut) {
+        Some(tools) => compact_tools(tools, limits),
+        None => 0,
+    }
+}
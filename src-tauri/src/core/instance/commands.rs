@@ -0,0 +1,8 @@
+use super::helpers::take_pending_files;
+
+/// Returns the files passed to this and forwarded launches that were not taken yet, for
+/// the window to open once it is ready. Later ones also arrive as `instance-open-files`.
+#[tauri::command]
+pub fn take_launch_files() -> Vec<String> {
+    take_pending_files()
+}
@@ -0,0 +1,14 @@
+use std::time::Duration;
+
+// Single instance constants
+pub const INSTANCE_LOCK_FILE: &str = "app.lock";
+/// Emitted with each forwarded `jan://` link, the event the deep link handler listens to
+pub const DEEP_LINK_EVENT: &str = "deep-link";
+/// Emitted with the paths of files passed to a launch
+pub const OPEN_FILES_EVENT: &str = "instance-open-files";
+pub const DEEP_LINK_SCHEME: &str = "jan://";
+/// How long a second instance waits for the running one to take its arguments
+pub const FORWARD_TIMEOUT: Duration = Duration::from_secs(3);
+/// Largest forwarded request read
+pub const MAX_FORWARD_BYTES: u64 = 64 * 1024;
+pub const FORWARD_ACCEPTED: &str = "ok";
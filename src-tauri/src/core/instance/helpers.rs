@@ -0,0 +1,272 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
+
+use super::constants::{
+    DEEP_LINK_EVENT, DEEP_LINK_SCHEME, FORWARD_ACCEPTED, FORWARD_TIMEOUT, INSTANCE_LOCK_FILE,
+    MAX_FORWARD_BYTES, OPEN_FILES_EVENT,
+};
+use super::models::{ForwardRequest, InstanceLockInfo, LaunchRequest};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::mcp::lockfile::is_process_alive;
+
+/// Our `app.lock`, removed on exit
+static INSTANCE_LOCK: OnceLock<Mutex<Option<PathBuf>>> = OnceLock::new();
+/// Files passed before the window listened for them
+static PENDING_FILES: OnceLock<Mutex<Vec<String>>> = OnceLock::new();
+
+pub fn instance_lock_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(INSTANCE_LOCK_FILE)
+}
+
+/// The instance holding the lock, if its process is alive
+pub fn read_instance_lock(data_folder: &Path) -> Option<InstanceLockInfo> {
+    let data = fs::read_to_string(instance_lock_path(data_folder)).ok()?;
+    let info: InstanceLockInfo = serde_json::from_str(&data).ok()?;
+    (info.pid != std::process::id() && is_process_alive(info.pid)).then_some(info)
+}
+
+/// Deep links and existing files among launch arguments; `argv[0]` and flags are skipped
+pub fn parse_launch_args(argv: &[String], cwd: &Path) -> LaunchRequest {
+    let mut launch = LaunchRequest::default();
+    for arg in argv.iter().skip(1) {
+        if arg.starts_with(DEEP_LINK_SCHEME) {
+            launch.deep_links.push(arg.clone());
+        } else if !arg.starts_with('-') {
+            let path = cwd.join(arg);
+            if path.is_file() {
+                let path = path.canonicalize().unwrap_or(path);
+                launch.files.push(path.to_string_lossy().into_owned());
+            }
+        }
+    }
+    launch
+}
+
+/// Hand a launch to the instance listening on `port`. Fails when it does not take it.
+pub fn forward_launch(port: u16, request: &ForwardRequest) -> Result<(), String> {
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let mut stream = TcpStream::connect_timeout(&address, FORWARD_TIMEOUT)
+        .map_err(|e| format!("Failed to reach the running instance: {e}"))?;
+    stream
+        .set_read_timeout(Some(FORWARD_TIMEOUT))
+        .map_err(|e| e.to_string())?;
+    let mut line = serde_json::to_string(request).map_err(|e| e.to_string())?;
+    line.push('\n');
+    stream
+        .write_all(line.as_bytes())
+        .map_err(|e| format!("Failed to forward the launch: {e}"))?;
+    let mut reply = String::new();
+    BufReader::new(stream.take(64))
+        .read_line(&mut reply)
+        .map_err(|e| format!("The running instance did not answer: {e}"))?;
+    if reply.trim() == FORWARD_ACCEPTED {
+        Ok(())
+    } else {
+        Err(format!(
+            "The running instance refused the launch: {}",
+            reply.trim()
+        ))
+    }
+}
+
+/// Take forwarded launches carrying `token` until the listener fails
+pub async fn serve_forwards(
+    listener: tokio::net::TcpListener,
+    token: String,
+    on_launch: impl Fn(ForwardRequest) + Send + Sync + 'static,
+) {
+    loop {
+        let mut stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                log::warn!("Stopped taking forwarded launches: {e}");
+                return;
+            }
+        };
+        let (reader, mut writer) = stream.split();
+        let mut line = String::new();
+        let read = tokio::time::timeout(
+            FORWARD_TIMEOUT,
+            tokio::io::BufReader::new(reader.take(MAX_FORWARD_BYTES)).read_line(&mut line),
+        )
+        .await;
+        let reply = match read {
+            Ok(Ok(_)) => match serde_json::from_str::<ForwardRequest>(&line) {
+                Ok(request) if request.token == token => {
+                    on_launch(request);
+                    FORWARD_ACCEPTED
+                }
+                Ok(_) => "denied",
+                Err(_) => "invalid",
+            },
+            _ => continue,
+        };
+        let _ = writer.write_all(format!("{reply}\n").as_bytes()).await;
+    }
+}
+
+fn focus_main_window<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Bring the window to the front and open what a launch asked for
+pub fn deliver_launch<R: Runtime>(app: &AppHandle<R>, launch: LaunchRequest) {
+    focus_main_window(app);
+    for link in launch.deep_links {
+        if let Err(e) = app.emit(DEEP_LINK_EVENT, link) {
+            log::warn!("Failed to emit {DEEP_LINK_EVENT}: {e}");
+        }
+    }
+    if !launch.files.is_empty() {
+        queue_files(&launch.files);
+        if let Err(e) = app.emit(OPEN_FILES_EVENT, &launch.files) {
+            log::warn!("Failed to emit {OPEN_FILES_EVENT}: {e}");
+        }
+    }
+}
+
+fn queue_files(files: &[String]) {
+    PENDING_FILES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .extend(files.iter().cloned());
+}
+
+/// Files passed to launches that the window has not taken yet
+pub fn take_pending_files() -> Vec<String> {
+    std::mem::take(
+        &mut *PENDING_FILES
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()),
+    )
+}
+
+/// A second launch of this build, reported by the single-instance plugin
+pub fn handle_second_instance<R: Runtime>(app: &AppHandle<R>, argv: Vec<String>, cwd: String) {
+    log::info!("Second launch with {argv:?}");
+    #[allow(unused_mut)]
+    let mut launch = parse_launch_args(&argv, Path::new(&cwd));
+    // The deep link plugin already reported these
+    #[cfg(feature = "deep-link")]
+    launch.deep_links.clear();
+    deliver_launch(app, launch);
+}
+
+fn write_lock(path: &Path, info: &InstanceLockInfo) -> std::io::Result<()> {
+    let data = serde_json::to_string_pretty(info).map_err(std::io::Error::other)?;
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)?;
+    file.write_all(data.as_bytes())
+}
+
+/// Claim the data folder for this instance, or forward this launch to the instance holding
+/// it. Returns false when the launch was forwarded and this process should exit.
+pub fn claim_data_folder<R: Runtime>(app: &AppHandle<R>) -> bool {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let path = instance_lock_path(&data_folder);
+    let argv: Vec<String> = std::env::args().collect();
+    let cwd = std::env::current_dir().unwrap_or_default();
+
+    if let Some(owner) = read_instance_lock(&data_folder) {
+        let request = ForwardRequest {
+            token: owner.token.clone(),
+            argv: argv.clone(),
+            cwd: cwd.to_string_lossy().into_owned(),
+        };
+        match forward_launch(owner.port, &request) {
+            Ok(()) => {
+                log::info!(
+                    "Jan ({}, PID {}) already uses {}; forwarded this launch",
+                    owner.identifier,
+                    owner.pid,
+                    data_folder.display()
+                );
+                return false;
+            }
+            Err(e) => log::warn!("Taking over {} from PID {}: {e}", path.display(), owner.pid),
+        }
+    }
+    let _ = fs::remove_file(&path);
+
+    let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+        .and_then(|listener| listener.set_nonblocking(true).map(|()| listener))
+    {
+        Ok(listener) => listener,
+        Err(e) => {
+            log::error!("Failed to listen for forwarded launches: {e}");
+            return true;
+        }
+    };
+    let info = InstanceLockInfo {
+        pid: std::process::id(),
+        port: listener.local_addr().map(|a| a.port()).unwrap_or_default(),
+        token: uuid::Uuid::new_v4().simple().to_string(),
+        identifier: app.config().identifier.clone(),
+        started_at: chrono::Utc::now().to_rfc3339(),
+    };
+    if let Err(e) = write_lock(&path, &info) {
+        // Another instance claimed it in the meantime; hand the launch over
+        if let Some(owner) = read_instance_lock(&data_folder) {
+            let request = ForwardRequest {
+                token: owner.token,
+                argv,
+                cwd: cwd.to_string_lossy().into_owned(),
+            };
+            if forward_launch(owner.port, &request).is_ok() {
+                return false;
+            }
+        }
+        log::error!("Failed to write {}: {e}", path.display());
+        return true;
+    }
+    *INSTANCE_LOCK
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner()) = Some(path);
+
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match tokio::net::TcpListener::from_std(listener) {
+            Ok(listener) => {
+                serve_forwards(listener, info.token, move |request| {
+                    let launch = parse_launch_args(&request.argv, Path::new(&request.cwd));
+                    deliver_launch(&handle, launch);
+                })
+                .await
+            }
+            Err(e) => log::error!("Failed to listen for forwarded launches: {e}"),
+        }
+    });
+
+    // Files passed to this launch wait for the window to ask for them
+    queue_files(&parse_launch_args(&argv, &cwd).files);
+    true
+}
+
+/// Remove our `app.lock`, on exit
+pub fn release_data_folder() {
+    let path = INSTANCE_LOCK
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take();
+    if let Some(path) = path {
+        if let Err(e) = fs::remove_file(&path) {
+            log::warn!("Failed to remove {}: {e}", path.display());
+        }
+    }
+}
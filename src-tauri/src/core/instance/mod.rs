@@ -0,0 +1,22 @@
+/*!
+   Single Instance
+
+   One Jan per data folder. The single-instance plugin already stops a second launch of the
+   same build, but a nightly and a stable build, or two builds pointed at one custom data
+   folder, would otherwise run side by side, overwrite each other's configs and fight over
+   ports.
+
+   The first instance holds `app.lock` in the data folder, naming its PID and a loopback port
+   it listens on for forwarded launches. A later instance on the same data folder sends its
+   arguments there with the lock's token and exits; the running one comes to the front and
+   opens what was passed: `jan://` deep links are emitted as `deep-link`, files as
+   `instance-open-files`. A lock whose owner is gone or doesn't answer is taken over.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
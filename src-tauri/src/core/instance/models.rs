@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+/// Contents of `app.lock`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstanceLockInfo {
+    pub pid: u32,
+    /// Loopback port taking forwarded launches
+    pub port: u16,
+    /// Sent back with forwarded launches, so only who can read the lock may forward
+    pub token: String,
+    /// Identifier of the build holding the lock
+    pub identifier: String,
+    /// RFC 3339
+    pub started_at: String,
+}
+
+/// What a second instance sends to the running one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForwardRequest {
+    pub token: String,
+    pub argv: Vec<String>,
+    pub cwd: String,
+}
+
+/// What a launch asked to open
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LaunchRequest {
+    pub deep_links: Vec<String>,
+    /// Absolute paths of existing files
+    pub files: Vec<String>,
+}
@@ -0,0 +1,63 @@
+use std::path::Path;
+
+use super::helpers::{forward_launch, parse_launch_args, serve_forwards};
+use super::models::ForwardRequest;
+
+#[test]
+fn test_parse_launch_args() {
+    let dir = std::env::temp_dir().join(format!("jan-instance-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("notes.md"), "hi").unwrap();
+    let argv: Vec<String> = [
+        "/opt/jan",
+        "--flag",
+        "jan://models/qwen",
+        "notes.md",
+        "gone.md",
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect();
+    let launch = parse_launch_args(&argv, &dir);
+    assert_eq!(launch.deep_links, vec!["jan://models/qwen"]);
+    assert_eq!(launch.files.len(), 1);
+    assert!(Path::new(&launch.files[0]).ends_with("notes.md"));
+    assert!(Path::new(&launch.files[0]).is_absolute());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn test_forward_launch() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let (sender, mut received) = tokio::sync::mpsc::unbounded_channel();
+    tokio::spawn(serve_forwards(
+        listener,
+        "secret".to_string(),
+        move |request| {
+            let _ = sender.send(request);
+        },
+    ));
+
+    let request = ForwardRequest {
+        token: "secret".to_string(),
+        argv: vec!["jan".to_string(), "jan://threads".to_string()],
+        cwd: "/".to_string(),
+    };
+    let sent = request.clone();
+    tokio::task::spawn_blocking(move || forward_launch(port, &sent))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(received.recv().await, Some(request.clone()));
+
+    let wrong = ForwardRequest {
+        token: "guess".to_string(),
+        ..request
+    };
+    let refused = tokio::task::spawn_blocking(move || forward_launch(port, &wrong))
+        .await
+        .unwrap();
+    assert!(refused.unwrap_err().contains("denied"));
+    assert!(received.try_recv().is_err());
+}
@@ -0,0 +1,142 @@
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_vector_db::{db, VectorDBState};
+
+use super::helpers::{
+    binding_extensions, build_status, find_binding, get_manifest_path, is_syncing, read_bindings,
+    read_manifest, scan_folder, sync_binding, update_bindings, validate_binding,
+};
+use super::models::{FolderBinding, SyncStatus};
+use crate::core::app::commands::get_jan_data_folder_path;
+
+/// Bind a knowledge base to a folder, replacing an existing binding of it. The first sync
+/// runs in the background.
+#[tauri::command]
+pub async fn bind_knowledge_folder<R: Runtime>(
+    app_handle: AppHandle<R>,
+    mut binding: FolderBinding,
+) -> Result<FolderBinding, String> {
+    validate_binding(&binding)?;
+    binding.extensions = binding_extensions(&binding);
+    if binding.created_at == 0 {
+        binding.created_at = chrono::Utc::now().timestamp_millis();
+    }
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+    let saved = binding.clone();
+    update_bindings(&data_folder, move |store| {
+        store
+            .bindings
+            .retain(|b| b.knowledge_base != saved.knowledge_base);
+        store.bindings.push(saved);
+        Ok(())
+    })?;
+
+    if binding.enabled {
+        let app = app_handle.clone();
+        let first = binding.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = sync_binding(&app, &first, false).await {
+                log::warn!("Knowledge sync of '{}' failed: {e}", first.knowledge_base);
+            }
+        });
+    }
+    Ok(binding)
+}
+
+/// Stop syncing a knowledge base. With `remove_documents` the synced documents are also
+/// removed from its collection; otherwise they stay searchable.
+#[tauri::command]
+pub async fn unbind_knowledge_folder<R: Runtime>(
+    app_handle: AppHandle<R>,
+    knowledge_base: String,
+    remove_documents: bool,
+) -> Result<(), String> {
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+    let kb = knowledge_base.clone();
+    let removed = update_bindings(&data_folder, move |store| {
+        let before = store.bindings.len();
+        store.bindings.retain(|b| b.knowledge_base != kb);
+        Ok(store.bindings.len() != before)
+    })?;
+    if !removed {
+        return Err(format!(
+            "Knowledge base '{knowledge_base}' is not bound to a folder"
+        ));
+    }
+
+    let manifest = read_manifest(&data_folder, &knowledge_base);
+    if remove_documents {
+        let collection = {
+            let state = app_handle.state::<VectorDBState>();
+            db::collection_path(&state.base_dir, &knowledge_base)
+        };
+        let file_ids: Vec<String> = manifest
+            .files
+            .values()
+            .filter_map(|r| r.file_id.clone())
+            .collect();
+        tokio::task::spawn_blocking(move || {
+            let conn = db::open_or_init_conn(&collection).map_err(|e| e.to_string())?;
+            for file_id in file_ids {
+                db::delete_file(&conn, &file_id).map_err(|e| e.to_string())?;
+            }
+            Ok::<_, String>(())
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+    }
+    let path = get_manifest_path(&data_folder, &knowledge_base);
+    if path.exists() {
+        std::fs::remove_file(&path)
+            .map_err(|e| format!("Failed to remove {}: {e}", path.display()))?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_knowledge_folders<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<Vec<FolderBinding>, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    Ok(read_bindings(&data_folder).bindings)
+}
+
+/// Sync a knowledge base now. `retry_failed` also retries files that failed before and
+/// haven't changed since.
+#[tauri::command]
+pub async fn sync_knowledge_folder<R: Runtime>(
+    app_handle: AppHandle<R>,
+    knowledge_base: String,
+    retry_failed: bool,
+) -> Result<SyncStatus, String> {
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+    let binding = find_binding(&data_folder, &knowledge_base)?;
+    sync_binding(&app_handle, &binding, retry_failed).await
+}
+
+/// Pending files, failed files with their last error and the last sync of a knowledge base
+#[tauri::command]
+pub async fn get_knowledge_sync_status<R: Runtime>(
+    app_handle: AppHandle<R>,
+    knowledge_base: String,
+) -> Result<SyncStatus, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    let binding = find_binding(&data_folder, &knowledge_base)?;
+    let manifest = read_manifest(&data_folder, &knowledge_base);
+    let folder = PathBuf::from(&binding.folder);
+    let extensions = binding_extensions(&binding);
+    let scanned = tokio::task::spawn_blocking(move || scan_folder(&folder, &extensions))
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut status = build_status(
+        &binding,
+        &manifest,
+        scanned.as_ref().ok(),
+        is_syncing(&knowledge_base),
+    );
+    if let Err(e) = scanned {
+        status.last_error = Some(e);
+    }
+    Ok(status)
+}
@@ -0,0 +1,21 @@
+// Knowledge sync constants
+pub const BINDINGS_FILE: &str = "knowledge_sync.json";
+/// Folder of the per-knowledge-base manifests, inside the data folder
+pub const MANIFESTS_DIR: &str = "knowledge_sync";
+/// Emitted after a sync of a knowledge base finishes
+pub const KNOWLEDGE_SYNC_EVENT: &str = "knowledge-sync";
+
+/// How often bound folders are rescanned
+pub const SCAN_INTERVAL_SECS: u64 = 30;
+
+/// Chunking defaults, matching the RAG extension
+pub const DEFAULT_CHUNK_SIZE: usize = 512;
+pub const DEFAULT_CHUNK_OVERLAP: usize = 64;
+/// Files above this size are reported as failed instead of being parsed
+pub const MAX_FILE_BYTES: u64 = 50 * 1024 * 1024;
+
+/// Extensions synced when a binding doesn't list its own; all are understood by the
+/// document parser
+pub const DEFAULT_EXTENSIONS: [&str; 11] = [
+    "pdf", "txt", "md", "csv", "xlsx", "xls", "ods", "pptx", "html", "htm", "docx",
+];
@@ -0,0 +1,599 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, UNIX_EPOCH};
+
+use jan_utils::progress::{
+    EtaEstimator, ProgressOperation, ProgressPayload, ProgressState, ProgressUnit,
+};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_vector_db::{db, VectorDBState};
+
+use super::constants::*;
+use super::models::{
+    BindingStore, FileRecord, FileStat, FileSyncError, FolderBinding, SyncManifest, SyncPlan,
+    SyncStatus,
+};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::config_store::helpers::{config_store, write_json};
+use crate::core::embeddings::constants::MAX_INPUTS;
+use crate::core::embeddings::helpers::{create_embeddings, local_embedding_endpoint};
+use crate::core::embeddings::models::{
+    EmbeddingInput, EmbeddingRequest, EmbeddingVector, EncodingFormat,
+};
+use crate::core::events::helpers::emit_task_progress;
+use crate::core::inference::models::ModelEndpoint;
+
+pub fn get_bindings_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(BINDINGS_FILE)
+}
+
+/// Manifest of a knowledge base, named like its vector-db collection
+pub fn get_manifest_path(data_folder: &Path, knowledge_base: &str) -> PathBuf {
+    let clean = knowledge_base.replace(['/', '\\'], "_");
+    data_folder
+        .join(MANIFESTS_DIR)
+        .join(format!("{clean}.json"))
+}
+
+/// Contents of `knowledge_sync.json`, empty when missing or unreadable
+pub fn read_bindings(data_folder: &Path) -> BindingStore {
+    let Ok(data) = config_store().read(&get_bindings_path(data_folder)) else {
+        return BindingStore::default();
+    };
+    serde_json::from_str(&data).unwrap_or_else(|e| {
+        log::warn!("Ignoring unreadable {BINDINGS_FILE}: {e}");
+        BindingStore::default()
+    })
+}
+
+/// Read-modify-write `knowledge_sync.json` under the config store's file lock
+pub fn update_bindings<T>(
+    data_folder: &Path,
+    change: impl FnOnce(&mut BindingStore) -> Result<T, String>,
+) -> Result<T, String> {
+    let mut result = None;
+    config_store().update(&get_bindings_path(data_folder), |raw| {
+        let mut store: BindingStore = raw
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        result = Some(change(&mut store)?);
+        serde_json::to_string_pretty(&store).map_err(|e| e.to_string())
+    })?;
+    result.ok_or_else(|| "Knowledge sync bindings were not updated".to_string())
+}
+
+pub fn find_binding(data_folder: &Path, knowledge_base: &str) -> Result<FolderBinding, String> {
+    read_bindings(data_folder)
+        .bindings
+        .into_iter()
+        .find(|b| b.knowledge_base == knowledge_base)
+        .ok_or_else(|| format!("Knowledge base '{knowledge_base}' is not bound to a folder"))
+}
+
+pub fn read_manifest(data_folder: &Path, knowledge_base: &str) -> SyncManifest {
+    let path = get_manifest_path(data_folder, knowledge_base);
+    let Ok(data) = config_store().read(&path) else {
+        return SyncManifest::default();
+    };
+    serde_json::from_str(&data).unwrap_or_else(|e| {
+        log::warn!("Ignoring unreadable sync manifest {}: {e}", path.display());
+        SyncManifest::default()
+    })
+}
+
+pub fn write_manifest(
+    data_folder: &Path,
+    knowledge_base: &str,
+    manifest: &SyncManifest,
+) -> Result<(), String> {
+    write_json(&get_manifest_path(data_folder, knowledge_base), manifest)
+}
+
+pub fn validate_binding(binding: &FolderBinding) -> Result<(), String> {
+    if binding.knowledge_base.trim().is_empty() {
+        return Err("A knowledge base name is required".to_string());
+    }
+    if binding.embedding_model.trim().is_empty() {
+        return Err("An embedding model is required".to_string());
+    }
+    if !Path::new(&binding.folder).is_dir() {
+        return Err(format!("'{}' is not a folder", binding.folder));
+    }
+    let chunk_size = binding.chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE);
+    if chunk_size == 0 {
+        return Err("chunk_size must be greater than 0".to_string());
+    }
+    if binding.chunk_overlap.unwrap_or(DEFAULT_CHUNK_OVERLAP) >= chunk_size {
+        return Err("chunk_overlap must be smaller than chunk_size".to_string());
+    }
+    Ok(())
+}
+
+/// Lowercase extensions synced for `binding`
+pub fn binding_extensions(binding: &FolderBinding) -> Vec<String> {
+    if binding.extensions.is_empty() {
+        return DEFAULT_EXTENSIONS.iter().map(|e| e.to_string()).collect();
+    }
+    binding
+        .extensions
+        .iter()
+        .map(|e| e.trim_start_matches('.').to_ascii_lowercase())
+        .collect()
+}
+
+fn extension_of(path: &str) -> Option<String> {
+    Path::new(path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+}
+
+/// Files under `folder` with one of `extensions`, keyed by their `/`-separated path relative
+/// to the folder. Hidden files and folders and symlinked folders are skipped.
+pub fn scan_folder(
+    folder: &Path,
+    extensions: &[String],
+) -> Result<BTreeMap<String, FileStat>, String> {
+    let mut files = BTreeMap::new();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(relative) = pending.pop() {
+        let dir = folder.join(&relative);
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if relative.as_os_str().is_empty() => {
+                return Err(format!("Failed to read {}: {e}", folder.display()));
+            }
+            Err(e) => {
+                log::warn!("Skipping unreadable folder {}: {e}", dir.display());
+                continue;
+            }
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.') {
+                continue;
+            }
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                pending.push(relative.join(&name));
+                continue;
+            }
+            if !extension_of(&name).is_some_and(|ext| extensions.contains(&ext)) {
+                continue;
+            }
+            // Follows symlinks to files
+            let Ok(metadata) = fs::metadata(entry.path()) else {
+                continue;
+            };
+            if !metadata.is_file() {
+                continue;
+            }
+            let modified_ms = metadata
+                .modified()
+                .ok()
+                .and_then(|m| m.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |d| d.as_millis() as i64);
+            let key = relative
+                .join(&name)
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.insert(
+                key,
+                FileStat {
+                    size: metadata.len(),
+                    modified_ms,
+                },
+            );
+        }
+    }
+    Ok(files)
+}
+
+/// Compare a scan with the manifest. Files whose size or modification time changed are
+/// modified; files that failed to sync are only retried once they change again, or when
+/// `retry_failed` is set.
+pub fn plan_changes(
+    records: &BTreeMap<String, FileRecord>,
+    scanned: &BTreeMap<String, FileStat>,
+    retry_failed: bool,
+) -> SyncPlan {
+    let mut plan = SyncPlan::default();
+    for (path, stat) in scanned {
+        match records.get(path) {
+            None => plan.added.push(path.clone()),
+            Some(record) => {
+                let changed = record.size != stat.size || record.modified_ms != stat.modified_ms;
+                let retry = retry_failed && record.last_error.is_some();
+                if changed || retry {
+                    plan.modified.push(path.clone());
+                }
+            }
+        }
+    }
+    plan.deleted = records
+        .keys()
+        .filter(|path| !scanned.contains_key(*path))
+        .cloned()
+        .collect();
+    plan
+}
+
+/// Hex SHA-256 of a file's contents
+pub fn hash_file(path: &Path) -> Result<String, String> {
+    let mut file =
+        fs::File::open(path).map_err(|e| format!("Failed to open {}: {e}", path.display()))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Status of a knowledge base from its manifest and, when available, a fresh scan
+pub fn build_status(
+    binding: &FolderBinding,
+    manifest: &SyncManifest,
+    scanned: Option<&BTreeMap<String, FileStat>>,
+    syncing: bool,
+) -> SyncStatus {
+    let pending = scanned
+        .map(|scanned| plan_changes(&manifest.files, scanned, false).pending())
+        .unwrap_or_default();
+    let failed = manifest
+        .files
+        .iter()
+        .filter_map(|(path, record)| {
+            record.last_error.as_ref().map(|error| FileSyncError {
+                path: path.clone(),
+                error: error.clone(),
+            })
+        })
+        .collect();
+    SyncStatus {
+        knowledge_base: binding.knowledge_base.clone(),
+        folder: binding.folder.clone(),
+        enabled: binding.enabled,
+        syncing,
+        pending,
+        failed,
+        synced_files: manifest
+            .files
+            .values()
+            .filter(|r| r.last_error.is_none() && r.file_id.is_some())
+            .count(),
+        last_sync_at: manifest.last_sync_at,
+        last_error: manifest.last_error.clone(),
+    }
+}
+
+// Knowledge bases with a sync in progress, so the watcher and manual syncs don't overlap
+static SYNCING: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+
+fn syncing() -> &'static Mutex<HashSet<String>> {
+    SYNCING.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+pub fn is_syncing(knowledge_base: &str) -> bool {
+    syncing()
+        .lock()
+        .map(|set| set.contains(knowledge_base))
+        .unwrap_or(false)
+}
+
+/// Marks a knowledge base as syncing until dropped
+struct SyncClaim(String);
+
+impl SyncClaim {
+    fn acquire(knowledge_base: &str) -> Option<Self> {
+        let mut set = syncing().lock().ok()?;
+        set.insert(knowledge_base.to_string())
+            .then(|| SyncClaim(knowledge_base.to_string()))
+    }
+}
+
+impl Drop for SyncClaim {
+    fn drop(&mut self) {
+        if let Ok(mut set) = syncing().lock() {
+            set.remove(&self.0);
+        }
+    }
+}
+
+async fn blocking<T: Send + 'static>(
+    task: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tokio::task::spawn_blocking(task)
+        .await
+        .map_err(|e| format!("Knowledge sync task failed: {e}"))?
+}
+
+/// Remove the chunks of a synced file from the collection
+async fn delete_document(collection: PathBuf, file_id: String) -> Result<(), String> {
+    blocking(move || {
+        let conn = db::open_or_init_conn(&collection).map_err(|e| e.to_string())?;
+        db::delete_file(&conn, &file_id).map_err(|e| e.to_string())
+    })
+    .await
+}
+
+/// Parse, chunk and embed one file and replace its previous chunks in the collection.
+/// Returns the new vector-db file id and chunk count.
+async fn sync_document(
+    binding: &FolderBinding,
+    endpoint: &ModelEndpoint,
+    collection: &Path,
+    path: PathBuf,
+    size: u64,
+    previous: Option<String>,
+) -> Result<(String, usize), String> {
+    if size > MAX_FILE_BYTES {
+        return Err(format!(
+            "File is larger than {} MB",
+            MAX_FILE_BYTES / 1024 / 1024
+        ));
+    }
+    let path_str = path.to_string_lossy().into_owned();
+    let extension = extension_of(&path_str).unwrap_or_default();
+    let text = {
+        let (path_str, extension) = (path_str.clone(), extension.clone());
+        blocking(move || {
+            tauri_plugin_rag::parse_document(&path_str, &extension).map_err(|e| e.to_string())
+        })
+        .await?
+    };
+    let chunks = db::chunk_text(
+        text,
+        binding.chunk_size.unwrap_or(DEFAULT_CHUNK_SIZE),
+        binding.chunk_overlap.unwrap_or(DEFAULT_CHUNK_OVERLAP),
+    );
+
+    let mut embeddings = Vec::with_capacity(chunks.len());
+    for group in chunks.chunks(MAX_INPUTS) {
+        let response = create_embeddings(
+            endpoint,
+            EmbeddingRequest {
+                model: binding.embedding_model.clone(),
+                input: EmbeddingInput::Texts(group.to_vec()),
+                encoding_format: EncodingFormat::Float,
+                dimensions: None,
+                normalize: false,
+                batch_size: None,
+            },
+        )
+        .await?;
+        for embedding in response.data {
+            match embedding.embedding {
+                EmbeddingVector::Float(vector) => embeddings.push(vector),
+                EmbeddingVector::Base64(_) => {
+                    return Err("Expected float embeddings".to_string());
+                }
+            }
+        }
+    }
+
+    let collection = collection.to_path_buf();
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned());
+    blocking(move || {
+        let conn = db::open_or_init_conn(&collection).map_err(|e| e.to_string())?;
+        if let Some(dimension) = embeddings.first().map(|e| e.len()) {
+            db::create_schema(&conn, dimension).map_err(|e| e.to_string())?;
+        }
+        if let Some(previous) = previous {
+            db::delete_file(&conn, &previous).map_err(|e| e.to_string())?;
+        }
+        let file = db::create_file(
+            &conn,
+            &path_str,
+            name.as_deref(),
+            Some(&extension),
+            Some(size as i64),
+        )
+        .map_err(|e| e.to_string())?;
+        let count = chunks.len();
+        let inputs = chunks
+            .into_iter()
+            .zip(embeddings)
+            .map(|(text, embedding)| db::MinimalChunkInput { text, embedding })
+            .collect();
+        let vec_loaded = db::try_load_sqlite_vec(&conn);
+        db::insert_chunks(&conn, &file.id, inputs, vec_loaded).map_err(|e| e.to_string())?;
+        Ok((file.id, count))
+    })
+    .await
+}
+
+/// Indexing progress of knowledge base `kb` while it runs through `phase`
+fn indexing_progress<R: Runtime>(
+    app: &AppHandle<R>,
+    kb: &str,
+    phase: &str,
+    done: usize,
+    total: usize,
+    eta: Option<&EtaEstimator>,
+) {
+    let (done, total) = (done as u64, total as u64);
+    let payload = ProgressPayload::running(ProgressOperation::Indexing, kb, kb, phase)
+        .with_amount(done, total, ProgressUnit::Items)
+        .with_eta(eta.and_then(|eta| eta.eta(done, total)));
+    emit_task_progress(app, &payload);
+}
+
+fn indexing_finished<R: Runtime>(app: &AppHandle<R>, kb: &str, state: ProgressState) {
+    let payload = ProgressPayload::finished(ProgressOperation::Indexing, kb, kb, state);
+    emit_task_progress(app, &payload);
+}
+
+/// Bring the collection of `binding` in step with its folder. Only added, modified and
+/// deleted files are processed, and modified files whose contents didn't change are not
+/// re-embedded. The manifest is saved after every file, so an interrupted sync resumes
+/// where it stopped.
+pub async fn sync_binding<R: Runtime>(
+    app: &AppHandle<R>,
+    binding: &FolderBinding,
+    retry_failed: bool,
+) -> Result<SyncStatus, String> {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let kb = binding.knowledge_base.as_str();
+    let Some(_claim) = SyncClaim::acquire(kb) else {
+        let manifest = read_manifest(&data_folder, kb);
+        return Ok(build_status(binding, &manifest, None, true));
+    };
+    let mut manifest = read_manifest(&data_folder, kb);
+
+    let folder = PathBuf::from(&binding.folder);
+    let extensions = binding_extensions(binding);
+    let scanned = {
+        let folder = folder.clone();
+        blocking(move || scan_folder(&folder, &extensions)).await
+    };
+    let scanned = match scanned {
+        Ok(scanned) => scanned,
+        Err(e) => {
+            // Keep the documents of a folder that is only temporarily unavailable
+            manifest.last_error = Some(e);
+            write_manifest(&data_folder, kb, &manifest)?;
+            return Ok(build_status(binding, &manifest, None, false));
+        }
+    };
+    let plan = plan_changes(&manifest.files, &scanned, retry_failed);
+    let collection = {
+        let state = app.state::<VectorDBState>();
+        db::collection_path(&state.base_dir, kb)
+    };
+
+    for (index, path) in plan.deleted.iter().enumerate() {
+        indexing_progress(app, kb, "removing", index, plan.deleted.len(), None);
+        if let Some(file_id) = manifest.files.get(path).and_then(|r| r.file_id.clone()) {
+            if let Err(e) = delete_document(collection.clone(), file_id).await {
+                log::warn!("Failed to remove '{path}' from knowledge base '{kb}': {e}");
+                continue;
+            }
+        }
+        manifest.files.remove(path);
+        write_manifest(&data_folder, kb, &manifest)?;
+    }
+
+    let changed: Vec<&String> = plan.added.iter().chain(&plan.modified).collect();
+    if !changed.is_empty() {
+        let endpoint = match local_embedding_endpoint(app, &binding.embedding_model).await {
+            Ok(endpoint) => endpoint,
+            Err(e) => {
+                // Files stay pending until the embedding model is loaded
+                indexing_finished(app, kb, ProgressState::Failed);
+                manifest.last_error = Some(e);
+                write_manifest(&data_folder, kb, &manifest)?;
+                return Ok(build_status(binding, &manifest, Some(&scanned), false));
+            }
+        };
+        let eta = EtaEstimator::new(0);
+        for (index, &path) in changed.iter().enumerate() {
+            indexing_progress(app, kb, "indexing", index, changed.len(), Some(&eta));
+            let stat = scanned[path];
+            let absolute = folder.join(path);
+            let previous = manifest.files.get(path).cloned().unwrap_or_default();
+            let sha256 = {
+                let absolute = absolute.clone();
+                blocking(move || hash_file(&absolute)).await
+            };
+            let record = match sha256 {
+                Ok(sha256)
+                    if previous.sha256.as_deref() == Some(sha256.as_str())
+                        && previous.file_id.is_some()
+                        && previous.last_error.is_none() =>
+                {
+                    // Touched but unchanged
+                    FileRecord {
+                        size: stat.size,
+                        modified_ms: stat.modified_ms,
+                        ..previous
+                    }
+                }
+                Ok(sha256) => {
+                    let result = sync_document(
+                        binding,
+                        &endpoint,
+                        &collection,
+                        absolute,
+                        stat.size,
+                        previous.file_id.clone(),
+                    )
+                    .await;
+                    match result {
+                        Ok((file_id, chunks)) => FileRecord {
+                            size: stat.size,
+                            modified_ms: stat.modified_ms,
+                            sha256: Some(sha256),
+                            file_id: Some(file_id),
+                            chunks,
+                            synced_at: Some(chrono::Utc::now().timestamp_millis()),
+                            last_error: None,
+                        },
+                        Err(e) => FileRecord {
+                            size: stat.size,
+                            modified_ms: stat.modified_ms,
+                            last_error: Some(e),
+                            ..previous
+                        },
+                    }
+                }
+                Err(e) => FileRecord {
+                    size: stat.size,
+                    modified_ms: stat.modified_ms,
+                    last_error: Some(e),
+                    ..previous
+                },
+            };
+            if let Some(e) = &record.last_error {
+                log::warn!("Failed to sync '{path}' into knowledge base '{kb}': {e}");
+            }
+            manifest.files.insert(path.clone(), record);
+            write_manifest(&data_folder, kb, &manifest)?;
+        }
+    }
+
+    manifest.last_sync_at = Some(chrono::Utc::now().timestamp_millis());
+    manifest.last_error = None;
+    write_manifest(&data_folder, kb, &manifest)?;
+    let status = build_status(binding, &manifest, Some(&scanned), false);
+    if !plan.is_empty() {
+        indexing_finished(app, kb, ProgressState::Completed);
+        if let Err(e) = app.emit(KNOWLEDGE_SYNC_EVENT, &status) {
+            log::warn!("Failed to emit {KNOWLEDGE_SYNC_EVENT}: {e}");
+        }
+    }
+    Ok(status)
+}
+
+/// Rescan enabled bindings every `SCAN_INTERVAL_SECS` and sync what changed
+pub fn start_knowledge_sync_watcher<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(SCAN_INTERVAL_SECS));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+            let data_folder = get_jan_data_folder_path(app.clone());
+            for binding in read_bindings(&data_folder).bindings {
+                if !binding.enabled {
+                    continue;
+                }
+                if let Err(e) = sync_binding(&app, &binding, false).await {
+                    log::warn!("Knowledge sync of '{}' failed: {e}", binding.knowledge_base);
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,26 @@
+/*!
+   Knowledge Base Folder Sync
+
+   A knowledge base (a vector-db collection) can be bound to a folder on disk. A background
+   watcher rescans bound folders periodically and keeps the collection in step with them:
+   - new files are parsed, chunked, embedded with the binding's embedding model and inserted,
+   - modified files (size or modification time changed, confirmed by content hash) have
+     their old chunks replaced; files that were only touched are not re-embedded,
+   - deleted files have their chunks removed.
+
+   A manifest per knowledge base under `knowledge_sync/` records the size, modification
+   time, hash, vector-db file id and last error of every synced file, so a rescan only
+   processes what changed. Files that fail keep their error and are retried once they
+   change again or when a sync is requested with `retry_failed`.
+
+   Scanning polls instead of subscribing to file system events, which also works for
+   network drives and folders on removable media.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A knowledge base kept in sync with a folder
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FolderBinding {
+    pub knowledge_base: String,
+    pub folder: String,
+    /// Embedding model loaded in the local engine
+    pub embedding_model: String,
+    /// Chunk size and overlap in characters
+    #[serde(default)]
+    pub chunk_size: Option<usize>,
+    #[serde(default)]
+    pub chunk_overlap: Option<usize>,
+    /// Lowercase extensions without the dot; empty for the defaults
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub created_at: i64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Contents of `knowledge_sync.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BindingStore {
+    #[serde(default)]
+    pub bindings: Vec<FolderBinding>,
+}
+
+/// Size and modification time of a file found by a scan
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileStat {
+    pub size: u64,
+    pub modified_ms: i64,
+}
+
+/// What the manifest knows about one file, by path relative to the folder
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FileRecord {
+    pub size: u64,
+    pub modified_ms: i64,
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Vector-db file id; absent until the file was synced once
+    #[serde(default)]
+    pub file_id: Option<String>,
+    #[serde(default)]
+    pub chunks: usize,
+    #[serde(default)]
+    pub synced_at: Option<i64>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+/// Per-knowledge-base sync manifest
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncManifest {
+    #[serde(default)]
+    pub files: BTreeMap<String, FileRecord>,
+    #[serde(default)]
+    pub last_sync_at: Option<i64>,
+    /// Why the last sync stopped before processing files, e.g. a missing folder
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+/// Relative paths that differ between a manifest and a scan
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncPlan {
+    pub added: Vec<String>,
+    pub modified: Vec<String>,
+    pub deleted: Vec<String>,
+}
+
+impl SyncPlan {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.modified.is_empty() && self.deleted.is_empty()
+    }
+
+    pub fn pending(&self) -> Vec<String> {
+        let mut pending: Vec<String> = self
+            .added
+            .iter()
+            .chain(&self.modified)
+            .chain(&self.deleted)
+            .cloned()
+            .collect();
+        pending.sort();
+        pending
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FileSyncError {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncStatus {
+    pub knowledge_base: String,
+    pub folder: String,
+    pub enabled: bool,
+    /// Whether a sync is running right now
+    pub syncing: bool,
+    /// Files added, modified or deleted since the last sync
+    pub pending: Vec<String>,
+    pub failed: Vec<FileSyncError>,
+    pub synced_files: usize,
+    pub last_sync_at: Option<i64>,
+    pub last_error: Option<String>,
+}
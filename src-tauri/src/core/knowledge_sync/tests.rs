@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use super::helpers::*;
+use super::models::{FileRecord, FileStat, FolderBinding, SyncManifest};
+
+fn temp_folder() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("knowledge-sync-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn binding(folder: &str) -> FolderBinding {
+    FolderBinding {
+        knowledge_base: "notes".to_string(),
+        folder: folder.to_string(),
+        embedding_model: "nomic-embed".to_string(),
+        chunk_size: None,
+        chunk_overlap: None,
+        extensions: Vec::new(),
+        enabled: true,
+        created_at: 0,
+    }
+}
+
+fn record(size: u64, modified_ms: i64) -> FileRecord {
+    FileRecord {
+        size,
+        modified_ms,
+        file_id: Some("file-1".to_string()),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_scan_folder_filters_and_relativizes() {
+    let dir = temp_folder();
+    fs::create_dir_all(dir.join("sub/deeper")).unwrap();
+    fs::create_dir_all(dir.join(".git")).unwrap();
+    fs::write(dir.join("a.md"), "alpha").unwrap();
+    fs::write(dir.join("image.png"), "png").unwrap();
+    fs::write(dir.join(".hidden.md"), "hidden").unwrap();
+    fs::write(dir.join(".git/config.txt"), "git").unwrap();
+    fs::write(dir.join("sub/deeper/B.TXT"), "beta!").unwrap();
+
+    let extensions = binding_extensions(&binding(&dir.to_string_lossy()));
+    let scanned = scan_folder(&dir, &extensions).unwrap();
+    let paths: Vec<&str> = scanned.keys().map(|k| k.as_str()).collect();
+    assert_eq!(paths, vec!["a.md", "sub/deeper/B.TXT"]);
+    assert_eq!(scanned["a.md"].size, 5);
+
+    assert!(scan_folder(&dir.join("missing"), &extensions).is_err());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_plan_changes() {
+    let mut records = BTreeMap::new();
+    records.insert("same.md".to_string(), record(10, 100));
+    records.insert("grown.md".to_string(), record(10, 100));
+    records.insert("gone.md".to_string(), record(10, 100));
+    records.insert(
+        "failed.md".to_string(),
+        FileRecord {
+            last_error: Some("parse error".to_string()),
+            ..record(10, 100)
+        },
+    );
+
+    let stat = |size, modified_ms| FileStat { size, modified_ms };
+    let mut scanned = BTreeMap::new();
+    scanned.insert("same.md".to_string(), stat(10, 100));
+    scanned.insert("grown.md".to_string(), stat(12, 100));
+    scanned.insert("failed.md".to_string(), stat(10, 100));
+    scanned.insert("new.md".to_string(), stat(1, 1));
+
+    let plan = plan_changes(&records, &scanned, false);
+    assert_eq!(plan.added, vec!["new.md"]);
+    assert_eq!(plan.modified, vec!["grown.md"]);
+    assert_eq!(plan.deleted, vec!["gone.md"]);
+    assert_eq!(plan.pending(), vec!["gone.md", "grown.md", "new.md"]);
+
+    let retry = plan_changes(&records, &scanned, true);
+    assert_eq!(retry.modified, vec!["failed.md", "grown.md"]);
+
+    records.remove("gone.md");
+    records.insert("new.md".to_string(), record(1, 1));
+    records.insert("grown.md".to_string(), record(12, 100));
+    assert!(plan_changes(&records, &scanned, false).is_empty());
+}
+
+#[test]
+fn test_build_status_and_validation() {
+    let dir = temp_folder();
+    let folder = dir.to_string_lossy().into_owned();
+    let mut manifest = SyncManifest::default();
+    manifest.files.insert("ok.md".to_string(), record(1, 1));
+    manifest.files.insert(
+        "bad.pdf".to_string(),
+        FileRecord {
+            last_error: Some("Embedding request failed".to_string()),
+            ..Default::default()
+        },
+    );
+    let mut scanned = BTreeMap::new();
+    scanned.insert(
+        "ok.md".to_string(),
+        FileStat {
+            size: 1,
+            modified_ms: 1,
+        },
+    );
+    scanned.insert(
+        "bad.pdf".to_string(),
+        FileStat {
+            size: 0,
+            modified_ms: 0,
+        },
+    );
+    scanned.insert(
+        "todo.md".to_string(),
+        FileStat {
+            size: 3,
+            modified_ms: 3,
+        },
+    );
+
+    let status = build_status(&binding(&folder), &manifest, Some(&scanned), false);
+    assert_eq!(status.pending, vec!["todo.md"]);
+    assert_eq!(status.failed.len(), 1);
+    assert_eq!(status.failed[0].path, "bad.pdf");
+    assert_eq!(status.synced_files, 1);
+
+    assert!(validate_binding(&binding(&folder)).is_ok());
+    assert!(validate_binding(&binding(&dir.join("missing").to_string_lossy())).is_err());
+    assert!(validate_binding(&FolderBinding {
+        chunk_size: Some(64),
+        chunk_overlap: Some(64),
+        ..binding(&folder)
+    })
+    .is_err());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_hash_file_detects_content_changes() {
+    let dir = temp_folder();
+    let path = dir.join("doc.txt");
+    fs::write(&path, "first").unwrap();
+    let first = hash_file(&path).unwrap();
+    fs::write(&path, "first").unwrap();
+    assert_eq!(hash_file(&path).unwrap(), first);
+    fs::write(&path, "second").unwrap();
+    assert_ne!(hash_file(&path).unwrap(), first);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+use tauri::{AppHandle, Runtime, State};
+
+use super::constants::{DEFAULT_DISCOVERY_TIMEOUT_MS, MAX_DISCOVERY_TIMEOUT_MS};
+use super::helpers::{confirm_pairing, connect_lan_server, discover_servers, request_pairing};
+use super::models::{LanConnection, LanServer};
+use crate::core::state::AppState;
+
+/// Looks for other Jan API servers on the LAN for `timeout_ms` (3 seconds by default)
+#[tauri::command]
+pub async fn discover_lan_servers(timeout_ms: Option<u64>) -> Result<Vec<LanServer>, String> {
+    let timeout_ms = timeout_ms
+        .unwrap_or(DEFAULT_DISCOVERY_TIMEOUT_MS)
+        .min(MAX_DISCOVERY_TIMEOUT_MS);
+    discover_servers(Duration::from_millis(timeout_ms)).await
+}
+
+/// Registers a discovered server as a remote provider with the models it runs. Connecting
+/// again refreshes the model list; `unregister_provider_config` disconnects it.
+#[tauri::command]
+pub async fn connect_lan_server_provider<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, AppState>,
+    server: LanServer,
+    api_key: Option<String>,
+) -> Result<LanConnection, String> {
+    connect_lan_server(&app, &state, &server, api_key).await
+}
+
+/// Asks a discovered server to pair with this instance, under `name` or the host name. The
+/// server shows a pairing code; returns the pairing request to confirm with it.
+#[tauri::command]
+pub async fn request_lan_pairing<R: Runtime>(
+    app: AppHandle<R>,
+    server: LanServer,
+    name: Option<String>,
+) -> Result<String, String> {
+    let name = name
+        .filter(|name| !name.trim().is_empty())
+        .or_else(|| {
+            hostname::get()
+                .ok()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .unwrap_or_else(|| "Jan".to_string());
+    request_pairing(&app, &server, &name).await
+}
+
+/// Completes pairing with the code shown on the server and registers it as a remote
+/// provider. The returned connection carries the token to connect with later.
+#[tauri::command]
+pub async fn confirm_lan_pairing<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, AppState>,
+    server: LanServer,
+    request_id: String,
+    code: String,
+) -> Result<LanConnection, String> {
+    confirm_pairing(&app, &state, &server, &request_id, &code).await
+}
@@ -0,0 +1,23 @@
+use std::time::Duration;
+
+/// mDNS service type the local API server is advertised as
+pub const JAN_SERVICE_TYPE: &str = "_jan-api._tcp.local.";
+
+// TXT record keys
+pub const TXT_INSTANCE_ID: &str = "id";
+pub const TXT_VERSION: &str = "version";
+pub const TXT_PREFIX: &str = "prefix";
+/// "1" when the server requires an API key
+pub const TXT_AUTH: &str = "auth";
+
+pub const DEFAULT_DISCOVERY_TIMEOUT_MS: u64 = 3_000;
+pub const MAX_DISCOVERY_TIMEOUT_MS: u64 = 15_000;
+
+/// Prefix of the provider a discovered instance is registered as
+pub const LAN_PROVIDER_PREFIX: &str = "jan-lan-";
+/// Timeout for listing the models of a discovered instance
+pub const LAN_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// `owned_by` of the models an instance runs itself, as listed by its `/models`
+pub const LOCAL_MODEL_OWNERS: &[&str] = &["llama.cpp", "mlx"];
+/// Timeout of the pairing requests sent to a discovered instance
+pub const LAN_PAIRING_TIMEOUT: Duration = Duration::from_secs(10);
@@ -0,0 +1,364 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Runtime};
+
+use super::constants::{
+    JAN_SERVICE_TYPE, LAN_PAIRING_TIMEOUT, LAN_PROVIDER_PREFIX, LAN_REQUEST_TIMEOUT,
+    LOCAL_MODEL_OWNERS, TXT_AUTH, TXT_INSTANCE_ID, TXT_PREFIX, TXT_VERSION,
+};
+use super::models::{LanConnection, LanServer};
+use crate::core::offline::helpers::check_url;
+use crate::core::peers::constants::{PEER_CONFIRM_PATH, PEER_PAIR_PATH};
+use crate::core::settings::helpers::lan_settings;
+use crate::core::state::{AppState, ProviderConfig};
+
+#[derive(Default)]
+struct Lan {
+    daemon: Option<ServiceDaemon>,
+    /// Full mDNS name of the advertised server
+    advertised: Option<String>,
+}
+
+static LAN: OnceLock<Mutex<Lan>> = OnceLock::new();
+
+fn lan() -> std::sync::MutexGuard<'static, Lan> {
+    LAN.get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+/// The mDNS daemon, started on first use
+fn daemon(lan: &mut Lan) -> Result<ServiceDaemon, String> {
+    if let Some(daemon) = &lan.daemon {
+        return Ok(daemon.clone());
+    }
+    let daemon = ServiceDaemon::new().map_err(|e| format!("Failed to start mDNS: {e}"))?;
+    lan.daemon = Some(daemon.clone());
+    Ok(daemon)
+}
+
+/// Random id of this process, so discovery skips the instance's own advertisement
+pub fn instance_id() -> &'static str {
+    static ID: OnceLock<String> = OnceLock::new();
+    ID.get_or_init(|| uuid::Uuid::new_v4().simple().to_string())
+}
+
+/// Whether a server listening on `host` can be reached from other machines
+pub fn is_lan_host(host: &str) -> bool {
+    let host = host.trim().trim_start_matches('[').trim_end_matches(']');
+    match host.parse::<IpAddr>() {
+        Ok(ip) => !ip.is_loopback(),
+        Err(_) => !host.is_empty() && !host.eq_ignore_ascii_case("localhost"),
+    }
+}
+
+/// Advertise the API server when `lan.advertise` is on and it listens on the LAN, and
+/// withdraw any earlier advertisement otherwise
+pub fn update_advertisement<R: Runtime>(
+    app: &AppHandle<R>,
+    host: &str,
+    port: u16,
+    prefix: &str,
+    requires_api_key: bool,
+) {
+    if !lan_settings(app).advertise || !is_lan_host(host) {
+        withdraw_advertisement();
+        return;
+    }
+    if let Err(e) = advertise(port, prefix, requires_api_key) {
+        log::warn!("Failed to advertise the API server on the LAN: {e}");
+    }
+}
+
+fn advertise(port: u16, prefix: &str, requires_api_key: bool) -> Result<(), String> {
+    let mut lan = lan();
+    let daemon = daemon(&mut lan)?;
+    if let Some(fullname) = lan.advertised.take() {
+        let _ = daemon.unregister(&fullname);
+    }
+    let host_name = hostname::get()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| "jan".to_string());
+    let properties = [
+        (TXT_INSTANCE_ID, instance_id()),
+        (TXT_VERSION, env!("CARGO_PKG_VERSION")),
+        (TXT_PREFIX, prefix),
+        (TXT_AUTH, if requires_api_key { "1" } else { "0" }),
+    ];
+    let service = ServiceInfo::new(
+        JAN_SERVICE_TYPE,
+        &host_name,
+        &format!("{host_name}.local."),
+        "",
+        port,
+        &properties[..],
+    )
+    .map_err(|e| e.to_string())?
+    .enable_addr_auto();
+    let fullname = service.get_fullname().to_string();
+    daemon.register(service).map_err(|e| e.to_string())?;
+    log::info!("Advertising the API server on the LAN as {fullname}");
+    lan.advertised = Some(fullname);
+    Ok(())
+}
+
+pub fn withdraw_advertisement() {
+    let mut lan = lan();
+    let Some(fullname) = lan.advertised.take() else {
+        return;
+    };
+    if let Some(daemon) = &lan.daemon {
+        let _ = daemon.unregister(&fullname);
+    }
+    log::info!("Stopped advertising the API server on the LAN");
+}
+
+/// The server a resolved advertisement describes; `None` for this instance's own
+pub fn lan_server_from_service(service: &ServiceInfo) -> Option<LanServer> {
+    if service.get_property_val_str(TXT_INSTANCE_ID) == Some(instance_id()) {
+        return None;
+    }
+    let mut addresses: Vec<IpAddr> = service.get_addresses().iter().copied().collect();
+    // IPv4 first, as link-local IPv6 addresses need a scope to be usable in a URL
+    addresses.sort_by_key(|addr| (addr.is_ipv6(), *addr));
+    let address = addresses.first()?;
+    let host = match address {
+        IpAddr::V4(ip) => ip.to_string(),
+        IpAddr::V6(ip) => format!("[{ip}]"),
+    };
+    let prefix = service
+        .get_property_val_str(TXT_PREFIX)
+        .unwrap_or_default()
+        .trim_end_matches('/')
+        .to_string();
+    let port = service.get_port();
+    let name = service
+        .get_fullname()
+        .strip_suffix(JAN_SERVICE_TYPE)
+        .unwrap_or(service.get_fullname())
+        .trim_end_matches('.')
+        .to_string();
+    Some(LanServer {
+        name,
+        host: service.get_hostname().trim_end_matches('.').to_string(),
+        addresses: addresses.iter().map(IpAddr::to_string).collect(),
+        port,
+        base_url: format!("http://{host}:{port}{prefix}"),
+        prefix,
+        version: service
+            .get_property_val_str(TXT_VERSION)
+            .map(str::to_string),
+        requires_api_key: service.get_property_val_str(TXT_AUTH) == Some("1"),
+    })
+}
+
+/// Browse the LAN for Jan API servers for `window`
+pub async fn discover_servers(window: Duration) -> Result<Vec<LanServer>, String> {
+    let daemon = daemon(&mut lan())?;
+    let events = daemon
+        .browse(JAN_SERVICE_TYPE)
+        .map_err(|e| format!("Failed to browse the LAN: {e}"))?;
+    let mut servers: HashMap<String, LanServer> = HashMap::new();
+    let deadline = tokio::time::Instant::now() + window;
+    while let Ok(Ok(event)) = tokio::time::timeout_at(deadline, events.recv_async()).await {
+        match event {
+            ServiceEvent::ServiceResolved(service) => {
+                if let Some(server) = lan_server_from_service(&service) {
+                    servers.insert(service.get_fullname().to_string(), server);
+                }
+            }
+            ServiceEvent::ServiceRemoved(_, fullname) => {
+                servers.remove(&fullname);
+            }
+            _ => {}
+        }
+    }
+    let _ = daemon.stop_browse(JAN_SERVICE_TYPE);
+    let mut servers: Vec<LanServer> = servers.into_values().collect();
+    servers.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(servers)
+}
+
+/// Provider name of a discovered server
+pub fn lan_provider_name(server: &LanServer) -> String {
+    let host: String = server
+        .host
+        .trim_end_matches(".local")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("{LAN_PROVIDER_PREFIX}{}", host.trim_matches('-'))
+}
+
+/// The models a `/models` response lists that the instance runs itself
+pub fn local_models(models: &Value) -> Vec<String> {
+    models
+        .get("data")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|model| {
+            model
+                .get("owned_by")
+                .and_then(Value::as_str)
+                .is_some_and(|owner| LOCAL_MODEL_OWNERS.contains(&owner))
+        })
+        .filter_map(|model| model.get("id")?.as_str().map(str::to_string))
+        .collect()
+}
+
+/// Client for another instance, which is never reached through a proxy
+fn lan_client(timeout: Duration) -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .no_proxy()
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// The JSON body of a response to a pairing request, or the reason it was refused
+async fn pairing_response(
+    server: &LanServer,
+    response: reqwest::Response,
+) -> Result<Value, String> {
+    let status = response.status();
+    let body: Value = response.json().await.unwrap_or(Value::Null);
+    if !status.is_success() {
+        let reason = body
+            .get("error")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("HTTP status {status}"));
+        return Err(format!("{} refused to pair: {reason}", server.name));
+    }
+    Ok(body)
+}
+
+/// Ask `server` to pair with this instance as `name`. The server shows a pairing code;
+/// returns the id of the pairing request to confirm with that code.
+pub async fn request_pairing<R: Runtime>(
+    app: &AppHandle<R>,
+    server: &LanServer,
+    name: &str,
+) -> Result<String, String> {
+    check_url(app, &server.base_url)?;
+    let response = lan_client(LAN_PAIRING_TIMEOUT)?
+        .post(format!("{}{PEER_PAIR_PATH}", server.base_url))
+        .json(&json!({ "name": name }))
+        .send()
+        .await
+        .map_err(|e| {
+            format!(
+                "{} is not reachable at {}: {e}",
+                server.name, server.base_url
+            )
+        })?;
+    let body = pairing_response(server, response).await?;
+    body.get("requestId")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| format!("Invalid pairing response from {}", server.name))
+}
+
+/// Confirm a pairing request with the code shown on `server`, and connect to it with the
+/// token it issued
+pub async fn confirm_pairing<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &AppState,
+    server: &LanServer,
+    request_id: &str,
+    code: &str,
+) -> Result<LanConnection, String> {
+    check_url(app, &server.base_url)?;
+    let response = lan_client(LAN_PAIRING_TIMEOUT)?
+        .post(format!("{}{PEER_CONFIRM_PATH}", server.base_url))
+        .json(&json!({ "requestId": request_id, "code": code }))
+        .send()
+        .await
+        .map_err(|e| {
+            format!(
+                "{} is not reachable at {}: {e}",
+                server.name, server.base_url
+            )
+        })?;
+    let body = pairing_response(server, response).await?;
+    let token = body
+        .get("token")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| format!("Invalid pairing response from {}", server.name))?;
+    let mut connection = connect_lan_server(app, state, server, Some(token.clone())).await?;
+    connection.api_key = Some(token);
+    Ok(connection)
+}
+
+/// List the models `server` runs and register it as a remote provider
+pub async fn connect_lan_server<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &AppState,
+    server: &LanServer,
+    api_key: Option<String>,
+) -> Result<LanConnection, String> {
+    check_url(app, &server.base_url)?;
+    let api_key = api_key.filter(|key| !key.trim().is_empty());
+    if server.requires_api_key && api_key.is_none() {
+        return Err(format!("{} requires an API key", server.name));
+    }
+    let mut request = lan_client(LAN_REQUEST_TIMEOUT)?.get(format!("{}/models", server.base_url));
+    if let Some(key) = &api_key {
+        request = request.bearer_auth(key);
+    }
+    let response = request.send().await.map_err(|e| {
+        format!(
+            "{} is not reachable at {}: {e}",
+            server.name, server.base_url
+        )
+    })?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "{} refused to list its models: HTTP status {}",
+            server.name,
+            response.status()
+        ));
+    }
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid model list from {}: {e}", server.name))?;
+
+    let connection = LanConnection {
+        provider: lan_provider_name(server),
+        models: local_models(&body),
+        api_key: None,
+    };
+    let config = ProviderConfig {
+        provider: connection.provider.clone(),
+        api_key,
+        base_url: Some(server.base_url.clone()),
+        models: connection.models.clone(),
+        ..Default::default()
+    };
+    state
+        .provider_configs
+        .write()
+        .await
+        .insert(connection.provider.clone(), config);
+    log::info!(
+        "Registered {} at {} with {} models",
+        connection.provider,
+        server.base_url,
+        connection.models.len()
+    );
+    Ok(connection)
+}
@@ -0,0 +1,22 @@
+/*!
+   LAN Discovery
+
+   Lets Jan on one machine use the models loaded by Jan on another, e.g. a laptop using the
+   desktop's GPU. While `lan.advertise` is on and the local API server listens on a
+   non-loopback address, the server is advertised over mDNS as `_jan-api._tcp`, with its
+   API prefix and whether it needs an API key in the TXT record. Other instances browse for
+   it on demand and can register a discovered one as a remote provider (`jan-lan-<host>`)
+   with the models it runs locally; models it proxies for its own remote providers are left
+   out, so two instances connected to each other don't route in circles.
+
+   A server with an API key can also be paired with instead (see `peers`): the server shows
+   a code, the user enters it here, and the token it issues is used as the API key.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
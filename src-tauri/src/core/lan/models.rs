@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+/// A Jan API server found on the LAN
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanServer {
+    /// mDNS instance name, usually the host name of the machine
+    pub name: String,
+    pub host: String,
+    pub addresses: Vec<String>,
+    pub port: u16,
+    /// API prefix such as `/v1`
+    pub prefix: String,
+    pub version: Option<String>,
+    pub requires_api_key: bool,
+    /// Base URL of its OpenAI-compatible API, through the first IPv4 address if it has one
+    pub base_url: String,
+}
+
+/// A discovered instance registered as a remote provider
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LanConnection {
+    pub provider: String,
+    pub models: Vec<String>,
+    /// Token issued when pairing, to connect with again later
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+}
@@ -0,0 +1,65 @@
+use mdns_sd::ServiceInfo;
+use serde_json::json;
+
+use super::constants::JAN_SERVICE_TYPE;
+use super::helpers::{
+    instance_id, is_lan_host, lan_provider_name, lan_server_from_service, local_models,
+};
+
+fn advertisement(id: &str, addresses: &str) -> ServiceInfo {
+    let properties = [
+        ("id", id),
+        ("version", "0.7.0"),
+        ("prefix", "/v1/"),
+        ("auth", "1"),
+    ];
+    ServiceInfo::new(
+        JAN_SERVICE_TYPE,
+        "Desktop",
+        "desktop.local.",
+        addresses,
+        1337,
+        &properties[..],
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_is_lan_host() {
+    assert!(is_lan_host("0.0.0.0"));
+    assert!(is_lan_host("192.168.1.10"));
+    assert!(is_lan_host("desktop.lan"));
+    assert!(!is_lan_host("127.0.0.1"));
+    assert!(!is_lan_host("[::1]"));
+    assert!(!is_lan_host("localhost"));
+}
+
+#[test]
+fn test_lan_server_from_service() {
+    let server = lan_server_from_service(&advertisement("other", "fe80::1,192.168.1.5")).unwrap();
+    assert_eq!(server.name, "Desktop");
+    assert_eq!(server.host, "desktop.local");
+    assert_eq!(server.addresses, vec!["192.168.1.5", "fe80::1"]);
+    assert_eq!(server.prefix, "/v1");
+    assert_eq!(server.version.as_deref(), Some("0.7.0"));
+    assert!(server.requires_api_key);
+    assert_eq!(server.base_url, "http://192.168.1.5:1337/v1");
+    assert_eq!(lan_provider_name(&server), "jan-lan-desktop");
+
+    // The instance's own advertisement
+    assert!(lan_server_from_service(&advertisement(instance_id(), "192.168.1.5")).is_none());
+}
+
+#[test]
+fn test_local_models_skip_proxied_ones() {
+    let models = json!({
+        "object": "list",
+        "data": [
+            { "id": "qwen3-8b", "owned_by": "llama.cpp" },
+            { "id": "gemma-3-4b", "owned_by": "mlx" },
+            { "id": "gpt-4o", "owned_by": "remote" },
+        ]
+    });
+    assert_eq!(local_models(&models), vec!["qwen3-8b", "gemma-3-4b"]);
+    assert!(local_models(&json!({})).is_empty());
+}
@@ -0,0 +1,41 @@
+use tauri::{AppHandle, Runtime};
+
+use super::helpers::{detect_language, language_lock, normalize_language, read_store, write_store};
+use super::models::{DetectedLanguage, ThreadLanguage};
+use crate::core::app::commands::get_jan_data_folder_path;
+
+/// Returns the response language state of a thread: the language chosen for it, if any, and
+/// the one last detected in it.
+#[tauri::command]
+pub async fn get_thread_language<R: Runtime>(
+    app_handle: AppHandle<R>,
+    thread_id: String,
+) -> Result<ThreadLanguage, String> {
+    let store = read_store(&get_jan_data_folder_path(app_handle));
+    Ok(store.threads.get(&thread_id).cloned().unwrap_or_default())
+}
+
+/// Sets the language replies in a thread are asked to be in, overriding detection. `None`
+/// goes back to the detected language.
+#[tauri::command]
+pub async fn set_thread_language<R: Runtime>(
+    app_handle: AppHandle<R>,
+    thread_id: String,
+    language: Option<String>,
+) -> Result<ThreadLanguage, String> {
+    let preferred = language.as_deref().map(normalize_language).transpose()?;
+    let data_folder = get_jan_data_folder_path(app_handle);
+    let _guard = language_lock().lock().await;
+    let mut store = read_store(&data_folder);
+    let thread = store.threads.entry(thread_id).or_default();
+    thread.preferred = preferred;
+    let thread = thread.clone();
+    write_store(&data_folder, &store)?;
+    Ok(thread)
+}
+
+/// Detects the language of a text, `None` when it is too short or too mixed to tell.
+#[tauri::command]
+pub fn detect_text_language(text: String) -> Option<DetectedLanguage> {
+    detect_language(&text)
+}
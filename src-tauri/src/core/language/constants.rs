@@ -0,0 +1,173 @@
+// Response language constants
+pub const LANGUAGE_DIR: &str = "language";
+pub const LANGUAGE_FILE: &str = "languages.json";
+
+/// Letters a message needs before its language is detected
+pub const MIN_DETECTION_LETTERS: usize = 12;
+/// Share of the letters a script needs to decide the language on its own
+pub const DOMINANT_SCRIPT_RATIO: f32 = 0.5;
+/// Function words a Latin-script message needs to match for a language
+pub const MIN_FUNCTION_WORD_HITS: usize = 2;
+/// Confidence below which a detection is ignored
+pub const MIN_DETECTION_CONFIDENCE: f32 = 0.6;
+
+/// Instruction added to the system prompt; `{language}` is replaced by the language name
+pub const RESPONSE_LANGUAGE_PROMPT: &str = "Respond in {language}, whatever the language of these instructions or of any quoted material, unless the user explicitly asks for another language.";
+
+/// Languages told apart by function words, with words common in them and rare elsewhere
+pub const FUNCTION_WORDS: &[(&str, &[&str])] = &[
+    (
+        "en",
+        &[
+            "the", "and", "is", "are", "was", "you", "that", "this", "with", "what", "how", "have",
+            "for", "not", "it", "of", "to", "can", "please", "my",
+        ],
+    ),
+    (
+        "fr",
+        &[
+            "le", "la", "les", "et", "est", "une", "des", "je", "vous", "que", "qui", "pas",
+            "pour", "dans", "avec", "ce", "sont", "mais", "du", "au",
+        ],
+    ),
+    (
+        "de",
+        &[
+            "der", "die", "das", "und", "ist", "nicht", "ich", "sie", "mit", "ein", "eine", "zu",
+            "auf", "für", "wie", "auch", "sind", "dem", "den", "bitte",
+        ],
+    ),
+    (
+        "es",
+        &[
+            "el", "los", "las", "y", "es", "un", "una", "que", "por", "para", "con", "como",
+            "pero", "está", "qué", "cómo", "yo", "del", "muy", "son",
+        ],
+    ),
+    (
+        "it",
+        &[
+            "il", "lo", "gli", "e", "è", "che", "non", "per", "una", "con", "sono", "come",
+            "della", "questo", "anche", "ma", "io", "mi", "ho", "cosa",
+        ],
+    ),
+    (
+        "pt",
+        &[
+            "o", "os", "as", "e", "é", "um", "uma", "que", "não", "para", "com", "como", "mas",
+            "está", "você", "isso", "eu", "do", "da", "são",
+        ],
+    ),
+    (
+        "nl",
+        &[
+            "de", "het", "een", "en", "is", "niet", "ik", "je", "van", "dat", "met", "voor",
+            "zijn", "op", "ook", "wat", "hoe", "maar", "dit", "wij",
+        ],
+    ),
+    (
+        "pl",
+        &[
+            "i", "w", "nie", "się", "na", "jest", "to", "że", "jak", "co", "ale", "czy", "dla",
+            "z", "mi", "mnie", "tak", "jestem", "proszę", "być",
+        ],
+    ),
+    (
+        "tr",
+        &[
+            "ve", "bir", "bu", "da", "de", "ne", "için", "ile", "mi", "ben", "sen", "çok", "nasıl",
+            "var", "yok", "ama", "gibi", "daha", "olarak", "lütfen",
+        ],
+    ),
+    (
+        "id",
+        &[
+            "dan",
+            "yang",
+            "di",
+            "ini",
+            "itu",
+            "dengan",
+            "untuk",
+            "tidak",
+            "saya",
+            "anda",
+            "ada",
+            "apa",
+            "bisa",
+            "dari",
+            "ke",
+            "akan",
+            "juga",
+            "bagaimana",
+            "tolong",
+            "kami",
+        ],
+    ),
+    (
+        "vi",
+        &[
+            "và", "là", "của", "không", "có", "tôi", "bạn", "này", "được", "những", "một", "cho",
+            "với", "các", "như", "gì", "làm", "thế", "nào", "để",
+        ],
+    ),
+];
+
+/// Letters that only a few of the Latin-script languages use, counted as extra hits
+pub const TELLING_LETTERS: &[(char, &str)] = &[
+    ('ß', "de"),
+    ('ä', "de"),
+    ('ö', "de"),
+    ('ü', "de"),
+    ('ñ', "es"),
+    ('¿', "es"),
+    ('¡', "es"),
+    ('ã', "pt"),
+    ('õ', "pt"),
+    ('ç', "fr"),
+    ('œ', "fr"),
+    ('ł', "pl"),
+    ('ą', "pl"),
+    ('ę', "pl"),
+    ('ğ', "tr"),
+    ('ş', "tr"),
+    ('ı', "tr"),
+    ('ơ', "vi"),
+    ('ư', "vi"),
+    ('đ', "vi"),
+];
+
+/// English names of the languages the prompt may name, by primary language subtag
+pub const LANGUAGE_NAMES: &[(&str, &str)] = &[
+    ("ar", "Arabic"),
+    ("bn", "Bengali"),
+    ("cs", "Czech"),
+    ("da", "Danish"),
+    ("de", "German"),
+    ("el", "Greek"),
+    ("en", "English"),
+    ("es", "Spanish"),
+    ("fa", "Persian"),
+    ("fi", "Finnish"),
+    ("fr", "French"),
+    ("he", "Hebrew"),
+    ("hi", "Hindi"),
+    ("hu", "Hungarian"),
+    ("id", "Indonesian"),
+    ("it", "Italian"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+    ("nl", "Dutch"),
+    ("no", "Norwegian"),
+    ("pl", "Polish"),
+    ("pt", "Portuguese"),
+    ("ro", "Romanian"),
+    ("ru", "Russian"),
+    ("sv", "Swedish"),
+    ("ta", "Tamil"),
+    ("th", "Thai"),
+    ("tr", "Turkish"),
+    ("uk", "Ukrainian"),
+    ("vi", "Vietnamese"),
+    ("zh", "Chinese"),
+];
@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use tokio::sync::Mutex;
+
+use super::constants::{
+    DOMINANT_SCRIPT_RATIO, FUNCTION_WORDS, LANGUAGE_DIR, LANGUAGE_FILE, LANGUAGE_NAMES,
+    MIN_DETECTION_CONFIDENCE, MIN_DETECTION_LETTERS, MIN_FUNCTION_WORD_HITS,
+    RESPONSE_LANGUAGE_PROMPT, TELLING_LETTERS,
+};
+use super::models::{DetectedLanguage, LanguageStore, ThreadLanguage};
+
+// Global lock serializing read-modify-write cycles on languages.json
+static LANGUAGE_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+pub fn language_lock() -> &'static Mutex<()> {
+    LANGUAGE_LOCK.get_or_init(|| Mutex::new(()))
+}
+
+pub fn get_store_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(LANGUAGE_DIR).join(LANGUAGE_FILE)
+}
+
+pub fn read_store(data_folder: &Path) -> LanguageStore {
+    fs::read_to_string(get_store_path(data_folder))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+pub fn write_store(data_folder: &Path, store: &LanguageStore) -> Result<(), String> {
+    let path = get_store_path(data_folder);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let data = serde_json::to_string_pretty(store).map_err(|e| e.to_string())?;
+    fs::write(path, data).map_err(|e| e.to_string())
+}
+
+/// Writing systems that identify a language, or a small family, on their own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Script {
+    Latin,
+    Cyrillic,
+    Greek,
+    Arabic,
+    Hebrew,
+    Devanagari,
+    Bengali,
+    Tamil,
+    Thai,
+    Hangul,
+    Kana,
+    Han,
+}
+
+fn script_of(c: char) -> Option<Script> {
+    let script = match c as u32 {
+        0x0041..=0x005A | 0x0061..=0x007A | 0x00C0..=0x024F | 0x1E00..=0x1EFF => Script::Latin,
+        0x0370..=0x03FF => Script::Greek,
+        0x0400..=0x052F => Script::Cyrillic,
+        0x0590..=0x05FF => Script::Hebrew,
+        0x0600..=0x06FF | 0x0750..=0x077F => Script::Arabic,
+        0x0900..=0x097F => Script::Devanagari,
+        0x0980..=0x09FF => Script::Bengali,
+        0x0B80..=0x0BFF => Script::Tamil,
+        0x0E00..=0x0E7F => Script::Thai,
+        0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF => Script::Hangul,
+        0x3040..=0x30FF => Script::Kana,
+        0x3400..=0x4DBF | 0x4E00..=0x9FFF => Script::Han,
+        _ => return None,
+    };
+    Some(script)
+}
+
+/// Language of a non-Latin script, looking at the letters that tell related languages apart.
+/// Latin script is shared by too many languages to tell.
+fn script_language(
+    script: Script,
+    text: &str,
+    counts: &HashMap<Script, usize>,
+) -> Option<&'static str> {
+    let has_any = |letters: &str| text.chars().any(|c| letters.contains(c));
+    let language = match script {
+        Script::Cyrillic if has_any("іїєґІЇЄҐ") => "uk",
+        Script::Cyrillic => "ru",
+        Script::Greek => "el",
+        Script::Arabic if has_any("پچژگ") => "fa",
+        Script::Arabic => "ar",
+        Script::Hebrew => "he",
+        Script::Devanagari => "hi",
+        Script::Bengali => "bn",
+        Script::Tamil => "ta",
+        Script::Thai => "th",
+        Script::Hangul => "ko",
+        // Japanese mixes kana into its Han characters, Chinese has none
+        Script::Kana => "ja",
+        Script::Han if counts.contains_key(&Script::Kana) => "ja",
+        Script::Han => "zh",
+        Script::Latin => return None,
+    };
+    Some(language)
+}
+
+/// Language of Latin-script text by the function words and telling letters it contains
+fn latin_language(text: &str) -> Option<DetectedLanguage> {
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower
+        .split(|c: char| !c.is_alphabetic())
+        .filter(|word| !word.is_empty())
+        .collect();
+    let mut hits: HashMap<&str, usize> = HashMap::new();
+    for (language, function_words) in FUNCTION_WORDS {
+        let count = words
+            .iter()
+            .filter(|word| function_words.contains(*word))
+            .count();
+        hits.insert(*language, count);
+    }
+    for c in lower.chars() {
+        if let Some((_, language)) = TELLING_LETTERS.iter().find(|(letter, _)| *letter == c) {
+            *hits.entry(*language).or_default() += 1;
+        }
+    }
+
+    let mut ranked: Vec<(&str, usize)> = hits.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1));
+    let (language, best) = ranked.first().copied()?;
+    let runner_up = ranked.get(1).map(|(_, count)| *count).unwrap_or_default();
+    // Related languages share words, so the lead over the closest one is what counts
+    if best < MIN_FUNCTION_WORD_HITS || runner_up == best {
+        return None;
+    }
+    Some(DetectedLanguage {
+        language: language.to_string(),
+        confidence: best as f32 / (best + runner_up) as f32,
+    })
+}
+
+/// Detect the language of `text`. Returns `None` when it is too short or too mixed to tell.
+pub fn detect_language(text: &str) -> Option<DetectedLanguage> {
+    let mut counts: HashMap<Script, usize> = HashMap::new();
+    for script in text.chars().filter_map(script_of) {
+        *counts.entry(script).or_default() += 1;
+    }
+    let letters: usize = counts.values().sum();
+    // Han and Hangul pack a word into a character or two
+    let weight = counts.get(&Script::Han).copied().unwrap_or_default()
+        + counts.get(&Script::Hangul).copied().unwrap_or_default();
+    if letters + weight * 2 < MIN_DETECTION_LETTERS {
+        return None;
+    }
+
+    let (script, count) = counts
+        .iter()
+        .filter(|(script, _)| **script != Script::Kana && **script != Script::Han)
+        .map(|(script, count)| (*script, *count))
+        .chain(std::iter::once((
+            Script::Han,
+            counts.get(&Script::Han).copied().unwrap_or_default()
+                + counts.get(&Script::Kana).copied().unwrap_or_default(),
+        )))
+        .max_by_key(|(_, count)| *count)?;
+    let share = count as f32 / letters as f32;
+    if share < DOMINANT_SCRIPT_RATIO {
+        return None;
+    }
+    match script_language(script, text, &counts) {
+        Some(language) => Some(DetectedLanguage {
+            language: language.to_string(),
+            confidence: share,
+        }),
+        None => latin_language(text),
+    }
+}
+
+/// Normalized language tag chosen by a user: lowercase primary subtag, uppercase region
+pub fn normalize_language(language: &str) -> Result<String, String> {
+    let language = language.trim().replace('_', "-");
+    let valid = !language.is_empty()
+        && language.len() <= 35
+        && language
+            .split('-')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()));
+    if !valid {
+        return Err(format!("'{language}' is not a language tag"));
+    }
+    let mut parts = language.split('-');
+    let primary = parts.next().unwrap_or_default().to_ascii_lowercase();
+    if !(2..=3).contains(&primary.len()) || !primary.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(format!("'{language}' is not a language tag"));
+    }
+    Ok(std::iter::once(primary)
+        .chain(parts.map(|part| match part.len() {
+            2 => part.to_ascii_uppercase(),
+            _ => part.to_string(),
+        }))
+        .collect::<Vec<_>>()
+        .join("-"))
+}
+
+/// English name of a language tag, the tag itself for languages without a known name
+pub fn language_name(language: &str) -> String {
+    let primary = language.split('-').next().unwrap_or(language);
+    let name = LANGUAGE_NAMES
+        .iter()
+        .find(|(tag, _)| tag.eq_ignore_ascii_case(primary))
+        .map(|(_, name)| *name);
+    match (name, language.len() > primary.len()) {
+        (Some(name), true) => format!("{name} ({language})"),
+        (Some(name), false) => name.to_string(),
+        (None, _) => language.to_string(),
+    }
+}
+
+/// Instruction asking the model to answer in `language`
+pub fn response_language_instruction(language: &str) -> String {
+    RESPONSE_LANGUAGE_PROMPT.replace("{language}", &language_name(language))
+}
+
+/// System prompt of a thread with the response language instruction appended
+pub fn with_response_language(
+    system_prompt: Option<&str>,
+    language: Option<&str>,
+) -> Option<String> {
+    let instruction = language.map(response_language_instruction);
+    match (
+        system_prompt.filter(|prompt| !prompt.trim().is_empty()),
+        instruction,
+    ) {
+        (Some(prompt), Some(instruction)) => {
+            Some(format!("{}\n\n{instruction}", prompt.trim_end()))
+        }
+        (Some(prompt), None) => Some(prompt.to_string()),
+        (None, instruction) => instruction,
+    }
+}
+
+/// Update a thread's detected language with the latest user message. Returns whether it
+/// changed.
+pub fn observe_message(thread: &mut ThreadLanguage, text: &str) -> bool {
+    let Some(detected) = detect_language(text) else {
+        return false;
+    };
+    if detected.confidence < MIN_DETECTION_CONFIDENCE
+        || thread.detected.as_deref() == Some(detected.language.as_str())
+    {
+        return false;
+    }
+    thread.detected = Some(detected.language);
+    true
+}
+
+/// Language replies in a thread should be in, after looking at its latest user message.
+/// A newly detected language is remembered for messages too short to tell.
+pub async fn resolve_thread_language(
+    data_folder: &Path,
+    thread_id: &str,
+    latest_user_message: Option<&str>,
+) -> Option<String> {
+    let _guard = language_lock().lock().await;
+    let mut store = read_store(data_folder);
+    let thread = store.threads.entry(thread_id.to_string()).or_default();
+    let changed = latest_user_message.is_some_and(|text| observe_message(thread, text));
+    let language = thread.effective().map(str::to_string);
+    if changed {
+        if let Err(e) = write_store(data_folder, &store) {
+            log::warn!("Failed to remember the language of thread {thread_id}: {e}");
+        }
+    }
+    language
+}
+
+/// Forget a thread's language, e.g. when the thread is deleted.
+pub async fn forget_thread_language(data_folder: &Path, thread_id: &str) -> Result<(), String> {
+    let _guard = language_lock().lock().await;
+    let mut store = read_store(data_folder);
+    if store.threads.remove(thread_id).is_some() {
+        write_store(data_folder, &store)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,20 @@
+/*!
+   Response Language
+
+   Keeps replies in the language of the conversation. Before a thread's context is assembled,
+   the language of its latest user message is detected with a lightweight heuristic: the
+   dominant script decides for non-Latin text, and common function words (plus a few telling
+   letters) for Latin text. Messages too short or too mixed to tell keep the language detected
+   last. A language the user chose for the thread overrides detection. Either way, the system
+   prompt asks the model to answer in that language.
+
+   Per-thread choices and detected languages live in `language/languages.json`.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Language of a text, as a primary language subtag (`en`, `fr`, ...)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DetectedLanguage {
+    pub language: String,
+    /// 0.0 to 1.0
+    pub confidence: f32,
+}
+
+/// Response language state of a thread
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThreadLanguage {
+    /// Language the user chose for replies; detection decides when absent
+    #[serde(default)]
+    pub preferred: Option<String>,
+    /// Language last detected in the thread's user messages
+    #[serde(default)]
+    pub detected: Option<String>,
+}
+
+impl ThreadLanguage {
+    /// The language replies should be in, if any is known
+    pub fn effective(&self) -> Option<&str> {
+        self.preferred.as_deref().or(self.detected.as_deref())
+    }
+}
+
+/// Contents of `language/languages.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LanguageStore {
+    /// Response language per thread id
+    #[serde(default)]
+    pub threads: HashMap<String, ThreadLanguage>,
+}
@@ -0,0 +1,150 @@
+use super::helpers::{
+    detect_language, forget_thread_language, language_name, normalize_language, observe_message,
+    read_store, resolve_thread_language, with_response_language,
+};
+use super::models::ThreadLanguage;
+
+fn detected(text: &str) -> Option<String> {
+    detect_language(text).map(|detected| detected.language)
+}
+
+#[test]
+fn test_detect_language_by_script() {
+    assert_eq!(
+        detected("Как изменить пароль моей учетной записи?").as_deref(),
+        Some("ru")
+    );
+    assert_eq!(
+        detected("Як змінити пароль мого облікового запису?").as_deref(),
+        Some("uk")
+    );
+    assert_eq!(
+        detected("パスワードを変更するにはどうすればいいですか").as_deref(),
+        Some("ja")
+    );
+    assert_eq!(detected("如何更改我的帐户密码").as_deref(), Some("zh"));
+    assert_eq!(
+        detected("비밀번호를 어떻게 변경하나요").as_deref(),
+        Some("ko")
+    );
+}
+
+#[test]
+fn test_detect_language_by_function_words() {
+    assert_eq!(
+        detected("How do I reset my password on this machine?").as_deref(),
+        Some("en")
+    );
+    assert_eq!(
+        detected("Comment est-ce que je peux changer le mot de passe ?").as_deref(),
+        Some("fr")
+    );
+    assert_eq!(
+        detected("Wie kann ich das Passwort ändern, bitte?").as_deref(),
+        Some("de")
+    );
+    assert_eq!(
+        detected("¿Cómo puedo cambiar la contraseña de mi cuenta?").as_deref(),
+        Some("es")
+    );
+    assert_eq!(
+        detected("Como posso mudar a senha da minha conta? Não está funcionando").as_deref(),
+        Some("pt")
+    );
+}
+
+#[test]
+fn test_detect_language_needs_enough_text() {
+    assert_eq!(detect_language("ok thanks"), None);
+    assert_eq!(detect_language("fn main() { println!(\"hi\"); }"), None);
+    assert_eq!(detect_language(""), None);
+}
+
+#[test]
+fn test_observe_message_keeps_last_language() {
+    let mut thread = ThreadLanguage::default();
+    assert!(observe_message(
+        &mut thread,
+        "Comment est-ce que je peux changer le mot de passe ?"
+    ));
+    assert!(!observe_message(&mut thread, "ok"));
+    assert!(!observe_message(
+        &mut thread,
+        "Est-ce que vous pouvez le faire pour moi ?"
+    ));
+    assert_eq!(thread.effective(), Some("fr"));
+
+    thread.preferred = Some("de".to_string());
+    assert!(observe_message(
+        &mut thread,
+        "How do I reset my password on this machine?"
+    ));
+    assert_eq!(thread.detected.as_deref(), Some("en"));
+    assert_eq!(thread.effective(), Some("de"));
+}
+
+#[test]
+fn test_normalize_language() {
+    assert_eq!(normalize_language("EN").unwrap(), "en");
+    assert_eq!(normalize_language(" pt_br ").unwrap(), "pt-BR");
+    assert_eq!(normalize_language("zh-Hant-tw").unwrap(), "zh-Hant-TW");
+    assert!(normalize_language("").is_err());
+    assert!(normalize_language("x").is_err());
+    assert!(normalize_language("e n").is_err());
+    assert!(normalize_language("12").is_err());
+}
+
+#[test]
+fn test_response_language_prompt() {
+    assert_eq!(language_name("fr"), "French");
+    assert_eq!(language_name("pt-BR"), "Portuguese (pt-BR)");
+    assert_eq!(language_name("tlh"), "tlh");
+
+    let prompt = with_response_language(Some("Be brief."), Some("fr")).unwrap();
+    assert!(prompt.starts_with("Be brief.\n\n"));
+    assert!(prompt.contains("Respond in French"));
+    assert!(with_response_language(None, Some("ja"))
+        .unwrap()
+        .contains("Respond in Japanese"));
+    assert_eq!(
+        with_response_language(Some("Be brief."), None).as_deref(),
+        Some("Be brief.")
+    );
+    assert_eq!(with_response_language(Some("  "), None), None);
+}
+
+#[tokio::test]
+async fn test_resolve_thread_language_remembers_detection() {
+    let data_folder = std::env::temp_dir().join(format!("jan-language-{}", uuid::Uuid::new_v4()));
+    let data_folder = data_folder.as_path();
+
+    assert_eq!(
+        resolve_thread_language(data_folder, "t1", Some("ok")).await,
+        None
+    );
+    assert_eq!(
+        resolve_thread_language(
+            data_folder,
+            "t1",
+            Some("Wie kann ich das Passwort ändern, bitte?")
+        )
+        .await
+        .as_deref(),
+        Some("de")
+    );
+    assert_eq!(
+        resolve_thread_language(data_folder, "t1", Some("danke"))
+            .await
+            .as_deref(),
+        Some("de")
+    );
+    assert_eq!(
+        read_store(data_folder).threads["t1"].detected.as_deref(),
+        Some("de")
+    );
+
+    forget_thread_language(data_folder, "t1").await.unwrap();
+    assert!(read_store(data_folder).threads.is_empty());
+
+    let _ = std::fs::remove_dir_all(data_folder);
+}
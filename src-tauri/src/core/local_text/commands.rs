@@ -0,0 +1,34 @@
+use std::fs;
+
+use serde_json::Value;
+use tauri::{AppHandle, Runtime};
+
+use super::helpers::{suggest_filename, suggest_title};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::threads::utils::get_thread_metadata_path;
+
+/// Suggests a title for a thread from its first messages, using a local model only.
+/// Fails when no local model is configured and running.
+#[tauri::command]
+pub async fn suggest_thread_title<R: Runtime>(
+    app_handle: AppHandle<R>,
+    thread_id: String,
+) -> Result<String, String> {
+    suggest_title(&app_handle, &thread_id).await
+}
+
+/// Suggests a file name, without extension, for exporting a thread. Generated by a local
+/// model when one is available, otherwise derived from the thread's title.
+#[tauri::command]
+pub async fn suggest_export_filename<R: Runtime>(
+    app_handle: AppHandle<R>,
+    thread_id: String,
+) -> Result<String, String> {
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+    let title = fs::read_to_string(get_thread_metadata_path(&data_folder, &thread_id))
+        .ok()
+        .and_then(|data| serde_json::from_str::<Value>(&data).ok())
+        .and_then(|thread| thread["title"].as_str().map(str::to_string))
+        .unwrap_or_default();
+    Ok(suggest_filename(&app_handle, &thread_id, &title).await)
+}
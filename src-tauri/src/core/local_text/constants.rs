@@ -0,0 +1,13 @@
+// Local text generation constants
+/// Messages from the start of a thread shown to the model to name it
+pub const EXCERPT_MESSAGES: usize = 6;
+/// Each message of the excerpt is cut to this many estimated tokens
+pub const EXCERPT_MESSAGE_TOKENS: usize = 300;
+
+pub const MAX_FILENAME_CHARS: usize = 60;
+pub const DEFAULT_FILENAME: &str = "conversation";
+pub const MAX_SNIPPET_CHARS: usize = 120;
+
+pub const TITLE_PROMPT: &str = "Write a title of at most six words for the conversation below, which may be a summary or its first messages. Write in the language of the conversation and reply with the title only, without quotes or punctuation at the end.";
+pub const FILENAME_PROMPT: &str = "Suggest a short file name of two to five words for an export of the conversation below. Leave out names, email addresses, numbers and anything else that identifies a person. Reply with the words only, separated by spaces.";
+pub const SNIPPET_PROMPT: &str = "Describe in one short sentence what the reply below is about, for a notification that may be read by others. Leave out names, email addresses, numbers, credentials and anything else personal. Reply with the sentence only.";
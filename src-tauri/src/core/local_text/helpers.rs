@@ -0,0 +1,198 @@
+use serde_json::{json, Value};
+use tauri::{AppHandle, Runtime};
+
+use super::constants::{
+    DEFAULT_FILENAME, EXCERPT_MESSAGES, EXCERPT_MESSAGE_TOKENS, FILENAME_PROMPT,
+    MAX_FILENAME_CHARS, MAX_SNIPPET_CHARS, SNIPPET_PROMPT, TITLE_PROMPT,
+};
+use crate::core::context::helpers::{message_text, truncate_to_tokens};
+use crate::core::inference::helpers::resolve_local_endpoint;
+use crate::core::inference::models::LocalEndpoint;
+use crate::core::settings::helpers::{local_text_settings, summary_settings};
+use crate::core::thread_summaries::helpers::{
+    clean_title, complete_in_background, summarizable_messages,
+};
+use crate::core::threads::commands::list_messages;
+use crate::core::threads::export::split_reasoning;
+
+/// The local model for titles, filenames and snippets. Fails rather than falling back to a
+/// remote provider when no configured model runs on this machine.
+pub async fn local_text_endpoint<R: Runtime>(app: &AppHandle<R>) -> Result<LocalEndpoint, String> {
+    let model = local_text_settings(app).model;
+    let model = if model.trim().is_empty() {
+        summary_settings(app).model
+    } else {
+        model
+    };
+    if model.trim().is_empty() {
+        return Err("No local model is configured for titles and snippets".to_string());
+    }
+    resolve_local_endpoint(app, &model).await
+}
+
+/// The first messages of a thread, each cut short, as a plain transcript
+pub fn thread_excerpt(messages: &[Value]) -> String {
+    summarizable_messages(messages)
+        .into_iter()
+        .take(EXCERPT_MESSAGES)
+        .map(|message| {
+            let role = message
+                .get("role")
+                .and_then(Value::as_str)
+                .unwrap_or("user");
+            let text = message_text(message);
+            let (_, answer) = split_reasoning(&text);
+            format!(
+                "{role}: {}\n\n",
+                truncate_to_tokens(answer, EXCERPT_MESSAGE_TOKENS)
+            )
+        })
+        .collect()
+}
+
+fn build_request(prompt: &str, text: &str, max_tokens: u32) -> Value {
+    json!({
+        "messages": [
+            { "role": "system", "content": prompt },
+            { "role": "user", "content": text }
+        ],
+        "temperature": 0.2,
+        "max_tokens": max_tokens
+    })
+}
+
+/// Lowercase words of `raw` joined by dashes, safe as a file name on every platform.
+/// A file extension the model may have added is dropped.
+pub fn clean_filename(raw: &str) -> Option<String> {
+    let (_, answer) = split_reasoning(raw);
+    let line = answer.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let line = match line.rsplit_once('.') {
+        Some((stem, ext)) if ext.len() <= 4 && ext.chars().all(|c| c.is_ascii_alphanumeric()) => {
+            stem
+        }
+        _ => line,
+    };
+    let mut name = String::new();
+    for c in line.chars() {
+        if c.is_alphanumeric() {
+            name.extend(c.to_lowercase());
+        } else if (c.is_whitespace() || matches!(c, '-' | '_' | '.')) && !name.ends_with('-') {
+            name.push('-');
+        }
+    }
+    let name: String = name
+        .trim_matches('-')
+        .chars()
+        .take(MAX_FILENAME_CHARS)
+        .collect();
+    let name = name.trim_end_matches('-');
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// One line of at most `MAX_SNIPPET_CHARS` characters
+pub fn clean_snippet(raw: &str) -> Option<String> {
+    let (_, answer) = split_reasoning(raw);
+    let line = answer.split_whitespace().collect::<Vec<_>>().join(" ");
+    let line = line.trim_matches(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '*'));
+    if line.is_empty() {
+        return None;
+    }
+    match line.char_indices().nth(MAX_SNIPPET_CHARS - 1) {
+        Some((cut, _)) => Some(format!("{}…", line[..cut].trim_end())),
+        None => Some(line.to_string()),
+    }
+}
+
+/// A title for the conversation in `text`, a summary or an excerpt of it
+pub async fn generate_title<R: Runtime>(
+    app: &AppHandle<R>,
+    endpoint: &LocalEndpoint,
+    job_id: &str,
+    text: &str,
+) -> Result<String, String> {
+    let raw = complete_in_background(
+        app,
+        endpoint,
+        job_id,
+        None,
+        &build_request(TITLE_PROMPT, text, 32),
+    )
+    .await?;
+    clean_title(&raw).ok_or_else(|| "The model returned no title".to_string())
+}
+
+/// A file name without extension for an export of the conversation in `text`
+pub async fn generate_filename<R: Runtime>(
+    app: &AppHandle<R>,
+    endpoint: &LocalEndpoint,
+    job_id: &str,
+    text: &str,
+) -> Result<String, String> {
+    let request = build_request(FILENAME_PROMPT, text, 24);
+    let raw = complete_in_background(app, endpoint, job_id, None, &request).await?;
+    clean_filename(&raw).ok_or_else(|| "The model returned no file name".to_string())
+}
+
+/// A notification snippet describing `reply` without quoting it
+pub async fn generate_snippet<R: Runtime>(
+    app: &AppHandle<R>,
+    endpoint: &LocalEndpoint,
+    job_id: &str,
+    reply: &str,
+) -> Result<String, String> {
+    let (_, answer) = split_reasoning(reply);
+    let text = truncate_to_tokens(answer, EXCERPT_MESSAGE_TOKENS * 2);
+    let request = build_request(SNIPPET_PROMPT, &text, 48);
+    let raw = complete_in_background(app, endpoint, job_id, None, &request).await?;
+    clean_snippet(&raw).ok_or_else(|| "The model returned no snippet".to_string())
+}
+
+/// A title for a thread generated from its first messages
+pub async fn suggest_title<R: Runtime>(
+    app: &AppHandle<R>,
+    thread_id: &str,
+) -> Result<String, String> {
+    let endpoint = local_text_endpoint(app).await?;
+    let messages = list_messages(app.clone(), thread_id.to_string()).await?;
+    let excerpt = thread_excerpt(&messages);
+    if excerpt.is_empty() {
+        return Err("The thread has no messages yet".to_string());
+    }
+    generate_title(
+        app,
+        &endpoint,
+        &format!("local-title:{thread_id}"),
+        &excerpt,
+    )
+    .await
+}
+
+/// An export file name for a thread. Without a local model, or when generation fails, the
+/// name is derived from the thread's title.
+pub async fn suggest_filename<R: Runtime>(
+    app: &AppHandle<R>,
+    thread_id: &str,
+    title: &str,
+) -> String {
+    let generated = async {
+        let endpoint = local_text_endpoint(app).await?;
+        let messages = list_messages(app.clone(), thread_id.to_string()).await?;
+        let excerpt = thread_excerpt(&messages);
+        if excerpt.is_empty() {
+            return Err("The thread has no messages yet".to_string());
+        }
+        generate_filename(
+            app,
+            &endpoint,
+            &format!("local-filename:{thread_id}"),
+            &excerpt,
+        )
+        .await
+    }
+    .await;
+    generated
+        .map_err(|e| log::info!("Naming the export of thread {thread_id} from its title: {e}"))
+        .ok()
+        .or_else(|| clean_filename(title))
+        .unwrap_or_else(|| DEFAULT_FILENAME.to_string())
+}
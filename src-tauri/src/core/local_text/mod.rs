@@ -0,0 +1,21 @@
+/*!
+   Local-Only Text Generation
+
+   Small generation jobs whose output is shown outside the conversation: thread titles,
+   export filenames and the snippets of generation notifications. They run on a local model
+   only, even when the thread itself talks to a remote provider, so conversation content is
+   never sent anywhere just to name a thread or a file.
+
+   This is enforced by the types rather than by convention: every job takes a
+   `LocalEndpoint`, which the inference router only resolves for running local sessions and
+   Ollama on a loopback address. The model comes from `local_text.model`, falling back to
+   `summaries.model` when that one runs locally; without a local model the callers fall back
+   to their non-generated defaults. Jobs run at background priority, behind interactive chat.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+
+#[cfg(test)]
+mod tests;
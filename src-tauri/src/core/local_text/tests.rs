@@ -0,0 +1,51 @@
+use serde_json::json;
+
+use super::helpers::{clean_filename, clean_snippet, thread_excerpt};
+
+#[test]
+fn test_clean_filename() {
+    assert_eq!(
+        clean_filename("Rust Lifetimes  Explained").as_deref(),
+        Some("rust-lifetimes-explained")
+    );
+    assert_eq!(
+        clean_filename("<think>hmm</think>\ntrip_to/lisbon.html").as_deref(),
+        Some("trip-tolisbon")
+    );
+    assert_eq!(
+        clean_filename("Ünïcode Café").as_deref(),
+        Some("ünïcode-café")
+    );
+    assert_eq!(clean_filename("  ../..  "), None);
+    assert_eq!(clean_filename(&"word ".repeat(40)).unwrap().len(), 59);
+}
+
+#[test]
+fn test_clean_snippet() {
+    assert_eq!(
+        clean_snippet("\"A recipe for\n  sourdough bread.\"").as_deref(),
+        Some("A recipe for sourdough bread.")
+    );
+    let long = clean_snippet(&"x".repeat(500)).unwrap();
+    assert_eq!(long.chars().count(), 120);
+    assert!(long.ends_with('…'));
+    assert_eq!(clean_snippet("<think>only thinking</think>"), None);
+}
+
+#[test]
+fn test_thread_excerpt() {
+    let messages: Vec<_> = (0..10)
+        .map(|i| {
+            json!({
+                "id": format!("m{i}"),
+                "role": if i % 2 == 0 { "user" } else { "assistant" },
+                "content": format!("message {i}"),
+            })
+        })
+        .collect();
+    let excerpt = thread_excerpt(&messages);
+    assert!(excerpt.starts_with("user: message 0\n\nassistant: message 1"));
+    assert!(excerpt.contains("message 5"));
+    assert!(!excerpt.contains("message 6"));
+    assert_eq!(thread_excerpt(&[]), "");
+}
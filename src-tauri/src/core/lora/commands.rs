@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Runtime};
+
+use super::helpers::{
+    adapter_entry, adapter_from_metadata, apply_session_scales, check_compatibility, read_metadata,
+};
+use super::models::{LoraAdapterEntry, LoraSelection, SessionLoraAdapter};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::assistants::helpers::{read_assistant, write_assistant};
+use crate::core::assistants::models::Assistant;
+use crate::core::model_catalog::constants::LLAMACPP_ENGINE;
+use crate::core::model_catalog::helpers::{
+    adapter_dir, catalog_path, list_adapter_ids, read_model, resolve_catalog_path, write_adapter,
+};
+
+async fn base_model_metadata(
+    data_folder: &Path,
+    model_id: &str,
+) -> Result<HashMap<String, String>, String> {
+    let model = read_model(data_folder, LLAMACPP_ENGINE, model_id)?;
+    read_metadata(&resolve_catalog_path(data_folder, &model.model_path)).await
+}
+
+/// Installed LoRA adapters. With `model_id`, only the adapters that model can load.
+#[tauri::command]
+pub async fn list_lora_adapters<R: Runtime>(
+    app: AppHandle<R>,
+    model_id: Option<String>,
+) -> Result<Vec<LoraAdapterEntry>, String> {
+    let data_folder = get_jan_data_folder_path(app);
+    let base_metadata = match model_id {
+        Some(model_id) => Some(base_model_metadata(&data_folder, &model_id).await?),
+        None => None,
+    };
+
+    let mut entries = Vec::new();
+    for adapter_id in list_adapter_ids(&data_folder, LLAMACPP_ENGINE) {
+        let entry = match adapter_entry(&data_folder, &adapter_id) {
+            Ok(entry) => entry,
+            Err(e) => {
+                log::warn!("Skipping adapter {adapter_id}: {e}");
+                continue;
+            }
+        };
+        if base_metadata.as_ref().map_or(true, |meta| {
+            check_compatibility(&entry.adapter, meta).is_ok()
+        }) {
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
+
+/// Add a LoRA adapter GGUF to the catalog. Files outside the adapter folder are copied in;
+/// a file downloaded straight into `llamacpp/adapters/<adapter_id>/` is registered in place.
+#[tauri::command]
+pub async fn import_lora_adapter<R: Runtime>(
+    app: AppHandle<R>,
+    path: String,
+    adapter_id: Option<String>,
+    name: Option<String>,
+) -> Result<LoraAdapterEntry, String> {
+    let data_folder = get_jan_data_folder_path(app);
+    let source = resolve_catalog_path(&data_folder, &path);
+    let mut adapter = adapter_from_metadata(&read_metadata(&source).await?)?;
+
+    let file_name = source
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .ok_or_else(|| format!("Invalid adapter path '{path}'"))?;
+    let adapter_id = adapter_id.unwrap_or_else(|| {
+        file_name
+            .strip_suffix(".gguf")
+            .unwrap_or(&file_name)
+            .to_string()
+    });
+    let dir = adapter_dir(&data_folder, LLAMACPP_ENGINE, &adapter_id)?;
+    let target = dir.join(&file_name);
+    let in_place = source.parent() == Some(dir.as_path());
+    if !in_place {
+        if dir.exists() {
+            return Err(format!("Adapter '{adapter_id}' already exists"));
+        }
+        fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        if let Err(e) = tokio::fs::copy(&source, &target).await {
+            let _ = fs::remove_dir_all(&dir);
+            return Err(format!("Failed to copy the adapter: {e}"));
+        }
+    }
+
+    adapter.adapter_path = catalog_path(&data_folder, &target);
+    adapter.size_bytes = fs::metadata(&target).map_err(|e| e.to_string())?.len();
+    if name.is_some() {
+        adapter.name = name;
+    }
+    write_adapter(&data_folder, LLAMACPP_ENGINE, &adapter_id, &adapter)?;
+    log::info!(
+        "Imported LoRA adapter {adapter_id} ({})",
+        adapter.architecture
+    );
+    adapter_entry(&data_folder, &adapter_id)
+}
+
+#[tauri::command]
+pub async fn delete_lora_adapter<R: Runtime>(
+    app: AppHandle<R>,
+    adapter_id: String,
+) -> Result<(), String> {
+    let data_folder = get_jan_data_folder_path(app);
+    let dir = adapter_dir(&data_folder, LLAMACPP_ENGINE, &adapter_id)?;
+    if !dir.exists() {
+        return Err(format!("Adapter '{adapter_id}' not found"));
+    }
+    fs::remove_dir_all(dir).map_err(|e| e.to_string())
+}
+
+/// Fails with the reason when the adapter cannot be used with the model
+#[tauri::command]
+pub async fn check_lora_compatibility<R: Runtime>(
+    app: AppHandle<R>,
+    adapter_id: String,
+    model_id: String,
+) -> Result<(), String> {
+    let data_folder = get_jan_data_folder_path(app);
+    let entry = adapter_entry(&data_folder, &adapter_id)?;
+    check_compatibility(
+        &entry.adapter,
+        &base_model_metadata(&data_folder, &model_id).await?,
+    )
+}
+
+/// Set (or clear, when empty) the adapters an assistant uses
+#[tauri::command]
+pub async fn set_assistant_lora_adapters<R: Runtime>(
+    app: AppHandle<R>,
+    assistant_id: String,
+    adapters: Vec<LoraSelection>,
+) -> Result<Assistant, String> {
+    let data_folder = get_jan_data_folder_path(app);
+    for selection in &adapters {
+        adapter_entry(&data_folder, &selection.adapter_id)?;
+        if !selection.scale.is_finite() {
+            return Err(format!("Invalid scale for '{}'", selection.adapter_id));
+        }
+    }
+    let mut assistant = read_assistant(&data_folder, &assistant_id)?;
+    assistant.lora_adapters = adapters;
+    write_assistant(&data_folder, &assistant)?;
+    Ok(assistant)
+}
+
+async fn apply_selection<R: Runtime>(
+    app: &AppHandle<R>,
+    model_id: &str,
+    adapters: &[LoraSelection],
+) -> Result<Vec<SessionLoraAdapter>, String> {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let wanted = adapters
+        .iter()
+        .map(|selection| {
+            let entry = adapter_entry(&data_folder, &selection.adapter_id)?;
+            Ok((PathBuf::from(entry.path), selection.scale))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    apply_session_scales(app, model_id, &wanted).await
+}
+
+/// Enable exactly `adapters` on the running session of `model_id`; the base model stays loaded
+#[tauri::command]
+pub async fn apply_lora_adapters<R: Runtime>(
+    app: AppHandle<R>,
+    model_id: String,
+    adapters: Vec<LoraSelection>,
+) -> Result<Vec<SessionLoraAdapter>, String> {
+    apply_selection(&app, &model_id, &adapters).await
+}
+
+/// Switch the running session of `model_id` to the adapters of an assistant
+#[tauri::command]
+pub async fn apply_assistant_lora_adapters<R: Runtime>(
+    app: AppHandle<R>,
+    assistant_id: String,
+    model_id: String,
+) -> Result<Vec<SessionLoraAdapter>, String> {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let assistant = read_assistant(&data_folder, &assistant_id)?;
+    apply_selection(&app, &model_id, &assistant.lora_adapters).await
+}
@@ -0,0 +1,13 @@
+use std::time::Duration;
+
+// llama-server endpoint listing and scaling the loaded adapters
+pub const LORA_ADAPTERS_PATH: &str = "/lora-adapters";
+pub const LORA_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// GGUF metadata keys
+pub const GENERAL_TYPE_KEY: &str = "general.type";
+pub const GENERAL_NAME_KEY: &str = "general.name";
+pub const ARCHITECTURE_KEY: &str = "general.architecture";
+pub const BASE_MODEL_NAME_KEY: &str = "general.base_model.0.name";
+pub const ADAPTER_TYPE_KEY: &str = "adapter.type";
+pub const LORA_ALPHA_KEY: &str = "adapter.lora.alpha";
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_llamacpp::state::LlamacppState;
+
+use super::constants::{
+    ADAPTER_TYPE_KEY, ARCHITECTURE_KEY, BASE_MODEL_NAME_KEY, GENERAL_NAME_KEY, GENERAL_TYPE_KEY,
+    LORA_ADAPTERS_PATH, LORA_ALPHA_KEY, LORA_REQUEST_TIMEOUT,
+};
+use super::models::{LoraAdapterEntry, SessionLoraAdapter};
+use crate::core::model_catalog::constants::LLAMACPP_ENGINE;
+use crate::core::model_catalog::helpers::{read_adapter, resolve_catalog_path};
+use crate::core::model_catalog::models::CatalogAdapter;
+
+/// GGUF metadata of a local file as key -> value strings
+pub async fn read_metadata(path: &Path) -> Result<HashMap<String, String>, String> {
+    tauri_plugin_llamacpp::read_gguf_metadata(path.to_string_lossy().into_owned())
+        .await
+        .map(|gguf| gguf.metadata)
+}
+
+/// Build a catalog entry from the metadata of an adapter file, rejecting anything but LoRA
+pub fn adapter_from_metadata(metadata: &HashMap<String, String>) -> Result<CatalogAdapter, String> {
+    let general_type = metadata.get(GENERAL_TYPE_KEY).map(String::as_str);
+    if general_type.is_some_and(|t| t != "adapter") {
+        return Err(format!(
+            "The file is a {} file, not an adapter",
+            general_type.unwrap_or_default()
+        ));
+    }
+    match metadata.get(ADAPTER_TYPE_KEY).map(String::as_str) {
+        Some("lora") => {}
+        Some(other) => return Err(format!("Unsupported adapter type '{other}'")),
+        None => return Err("The file is not a LoRA adapter".to_string()),
+    }
+    let architecture = metadata
+        .get(ARCHITECTURE_KEY)
+        .filter(|a| !a.is_empty())
+        .ok_or("The adapter does not declare an architecture")?;
+
+    Ok(CatalogAdapter {
+        name: metadata.get(GENERAL_NAME_KEY).cloned(),
+        architecture: architecture.clone(),
+        base_model: metadata.get(BASE_MODEL_NAME_KEY).cloned(),
+        alpha: metadata
+            .get(LORA_ALPHA_KEY)
+            .and_then(|alpha| alpha.parse().ok()),
+        ..Default::default()
+    })
+}
+
+/// Check that `adapter` was trained for the architecture of the base model
+pub fn check_compatibility(
+    adapter: &CatalogAdapter,
+    base_metadata: &HashMap<String, String>,
+) -> Result<(), String> {
+    if base_metadata
+        .get(GENERAL_TYPE_KEY)
+        .is_some_and(|t| t == "adapter")
+    {
+        return Err("The base model is itself an adapter".to_string());
+    }
+    match base_metadata.get(ARCHITECTURE_KEY) {
+        Some(arch) if arch == &adapter.architecture => Ok(()),
+        Some(arch) => Err(format!(
+            "The adapter was trained for {} models and cannot be used with a {arch} model",
+            adapter.architecture
+        )),
+        None => Err("The base model does not declare an architecture".to_string()),
+    }
+}
+
+pub fn adapter_entry(data_folder: &Path, adapter_id: &str) -> Result<LoraAdapterEntry, String> {
+    let adapter = read_adapter(data_folder, LLAMACPP_ENGINE, adapter_id)?;
+    Ok(LoraAdapterEntry {
+        id: adapter_id.to_string(),
+        path: resolve_catalog_path(data_folder, &adapter.adapter_path)
+            .to_string_lossy()
+            .into_owned(),
+        adapter,
+    })
+}
+
+fn same_file(a: &Path, b: &Path) -> bool {
+    a == b
+        || matches!(
+            (a.canonicalize(), b.canonicalize()),
+            (Ok(a), Ok(b)) if a == b
+        )
+}
+
+/// New scales of the adapters loaded in a session: the wanted ones get their scale, all
+/// others are disabled. Fails when a wanted adapter was not loaded with the model.
+pub fn adapter_scales(
+    loaded: &[SessionLoraAdapter],
+    wanted: &[(PathBuf, f32)],
+) -> Result<Vec<SessionLoraAdapter>, String> {
+    if let Some((missing, _)) = wanted
+        .iter()
+        .find(|(path, _)| !loaded.iter().any(|l| same_file(Path::new(&l.path), path)))
+    {
+        return Err(format!(
+            "Adapter {} is not loaded; reload the model to attach it",
+            missing.display()
+        ));
+    }
+    Ok(loaded
+        .iter()
+        .map(|adapter| SessionLoraAdapter {
+            scale: wanted
+                .iter()
+                .find(|(path, _)| same_file(Path::new(&adapter.path), path))
+                .map(|(_, scale)| *scale)
+                .unwrap_or(0.0),
+            ..adapter.clone()
+        })
+        .collect())
+}
+
+/// Set the adapter scales of the running llama.cpp session of `model_id`
+pub async fn apply_session_scales<R: Runtime>(
+    app: &AppHandle<R>,
+    model_id: &str,
+    wanted: &[(PathBuf, f32)],
+) -> Result<Vec<SessionLoraAdapter>, String> {
+    let (port, api_key) = {
+        let state = app.state::<LlamacppState>();
+        let sessions = state.llama_server_process.lock().await;
+        let session = sessions
+            .values()
+            .find(|s| s.info.model_id == model_id)
+            .ok_or_else(|| format!("Model '{model_id}' is not running"))?;
+        (session.info.port, session.info.api_key.clone())
+    };
+    let url = format!("http://127.0.0.1:{port}{LORA_ADAPTERS_PATH}");
+    let client = reqwest::Client::builder()
+        .timeout(LORA_REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let loaded: Vec<SessionLoraAdapter> = client
+        .get(&url)
+        .bearer_auth(&api_key)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to list the adapters of '{model_id}': {e}"))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid adapter list from llama-server: {e}"))?;
+    let scales = adapter_scales(&loaded, wanted)?;
+
+    let body: Vec<serde_json::Value> = scales
+        .iter()
+        .map(|a| serde_json::json!({ "id": a.id, "scale": a.scale }))
+        .collect();
+    client
+        .post(&url)
+        .bearer_auth(&api_key)
+        .json(&body)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to apply adapters to '{model_id}': {e}"))?;
+    Ok(scales)
+}
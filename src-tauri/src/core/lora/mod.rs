@@ -0,0 +1,22 @@
+/*!
+   LoRA Adapters
+
+   Installs LoRA adapters for llama.cpp models and switches them per assistant without
+   reloading the base model:
+   - adapters are imported from a local GGUF file (downloads land in the adapter folder through
+     the download manager first) and catalogued next to the models; the GGUF metadata must
+     describe a LoRA adapter, and its architecture decides which base models can use it,
+   - compatible adapters are passed to llama-server when the model is loaded, disabled
+     (`--lora-init-without-apply`),
+   - applying an assistant's selection sets the adapter scales of the running session through
+     llama-server's `/lora-adapters` endpoint; adapters the assistant does not use are scaled
+     to zero. Scales are per session, so the last assistant applied wins.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::model_catalog::models::CatalogAdapter;
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+/// An adapter enabled for an assistant
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LoraSelection {
+    pub adapter_id: String,
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+}
+
+/// A catalogued adapter as returned to the frontend
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct LoraAdapterEntry {
+    pub id: String,
+    /// Absolute path of the adapter file, as passed to llama-server
+    pub path: String,
+    #[serde(flatten)]
+    pub adapter: CatalogAdapter,
+}
+
+/// An adapter loaded in a llama-server session, as reported by `/lora-adapters`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionLoraAdapter {
+    pub id: u32,
+    #[serde(default)]
+    pub path: String,
+    pub scale: f32,
+}
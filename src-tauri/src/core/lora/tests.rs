@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+use tauri::test::mock_app;
+
+use super::commands::{
+    check_lora_compatibility, delete_lora_adapter, import_lora_adapter, list_lora_adapters,
+    set_assistant_lora_adapters,
+};
+use super::helpers::{adapter_from_metadata, adapter_scales, check_compatibility};
+use super::models::{LoraSelection, SessionLoraAdapter};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::assistants::helpers::{read_assistant, write_assistant};
+
+fn metadata(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+/// Minimal GGUF v3 file with string metadata and no tensors
+fn write_gguf(path: &Path, pairs: &[(&str, &str)]) {
+    let mut data = b"GGUF".to_vec();
+    data.extend(3u32.to_le_bytes());
+    data.extend(0u64.to_le_bytes());
+    data.extend((pairs.len() as u64).to_le_bytes());
+    for (key, value) in pairs {
+        data.extend((key.len() as u64).to_le_bytes());
+        data.extend(key.as_bytes());
+        data.extend(8u32.to_le_bytes());
+        data.extend((value.len() as u64).to_le_bytes());
+        data.extend(value.as_bytes());
+    }
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, data).unwrap();
+}
+
+#[test]
+fn test_adapter_from_metadata() {
+    let adapter = adapter_from_metadata(&metadata(&[
+        ("general.type", "adapter"),
+        ("adapter.type", "lora"),
+        ("general.architecture", "llama"),
+        ("adapter.lora.alpha", "16"),
+        ("general.base_model.0.name", "Llama 3.1 8B"),
+    ]))
+    .unwrap();
+    assert_eq!(adapter.architecture, "llama");
+    assert_eq!(adapter.alpha, Some(16.0));
+    assert_eq!(adapter.base_model.as_deref(), Some("Llama 3.1 8B"));
+
+    assert!(adapter_from_metadata(&metadata(&[
+        ("general.type", "model"),
+        ("general.architecture", "llama"),
+    ]))
+    .is_err());
+    assert!(adapter_from_metadata(&metadata(&[
+        ("adapter.type", "control_vector"),
+        ("general.architecture", "llama"),
+    ]))
+    .is_err());
+    assert!(adapter_from_metadata(&metadata(&[("adapter.type", "lora")])).is_err());
+}
+
+#[test]
+fn test_check_compatibility() {
+    let adapter = adapter_from_metadata(&metadata(&[
+        ("adapter.type", "lora"),
+        ("general.architecture", "qwen3"),
+    ]))
+    .unwrap();
+    assert!(check_compatibility(&adapter, &metadata(&[("general.architecture", "qwen3")])).is_ok());
+    let err =
+        check_compatibility(&adapter, &metadata(&[("general.architecture", "llama")])).unwrap_err();
+    assert!(err.contains("qwen3") && err.contains("llama"));
+    assert!(check_compatibility(
+        &adapter,
+        &metadata(&[
+            ("general.type", "adapter"),
+            ("general.architecture", "qwen3")
+        ])
+    )
+    .is_err());
+}
+
+#[test]
+fn test_adapter_scales() {
+    let loaded = vec![
+        SessionLoraAdapter {
+            id: 0,
+            path: "/a.gguf".to_string(),
+            scale: 1.0,
+        },
+        SessionLoraAdapter {
+            id: 1,
+            path: "/b.gguf".to_string(),
+            scale: 0.0,
+        },
+    ];
+    let scales = adapter_scales(&loaded, &[(PathBuf::from("/b.gguf"), 0.5)]).unwrap();
+    assert_eq!(
+        scales.iter().map(|a| (a.id, a.scale)).collect::<Vec<_>>(),
+        vec![(0, 0.0), (1, 0.5)]
+    );
+    // Nothing wanted disables every adapter
+    assert!(adapter_scales(&loaded, &[])
+        .unwrap()
+        .iter()
+        .all(|a| a.scale == 0.0));
+    assert!(adapter_scales(&loaded, &[(PathBuf::from("/c.gguf"), 1.0)]).is_err());
+}
+
+#[tokio::test]
+async fn test_import_and_list_lora_adapters() {
+    let app = mock_app();
+    let data = get_jan_data_folder_path(app.handle().clone());
+    let source_dir = data.join("lora-import-test");
+    let source = source_dir.join("style.gguf");
+    write_gguf(
+        &source,
+        &[
+            ("general.type", "adapter"),
+            ("adapter.type", "lora"),
+            ("general.architecture", "llama"),
+        ],
+    );
+    let models = data.join("llamacpp").join("models");
+    for (id, arch) in [("lora-test-llama", "llama"), ("lora-test-qwen", "qwen3")] {
+        write_gguf(
+            &models.join(id).join("model.gguf"),
+            &[("general.architecture", arch)],
+        );
+        fs::write(
+            models.join(id).join("model.yml"),
+            format!("model_path: llamacpp/models/{id}/model.gguf\n"),
+        )
+        .unwrap();
+    }
+
+    let entry = import_lora_adapter(
+        app.handle().clone(),
+        source.to_string_lossy().into_owned(),
+        Some("lora-test-style".to_string()),
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        entry.adapter.adapter_path,
+        "llamacpp/adapters/lora-test-style/style.gguf"
+    );
+    assert!(Path::new(&entry.path).is_file());
+    assert!(import_lora_adapter(
+        app.handle().clone(),
+        source.to_string_lossy().into_owned(),
+        Some("lora-test-style".to_string()),
+        None,
+    )
+    .await
+    .is_err());
+
+    let for_llama = list_lora_adapters(app.handle().clone(), Some("lora-test-llama".to_string()))
+        .await
+        .unwrap();
+    assert!(for_llama.iter().any(|a| a.id == "lora-test-style"));
+    let for_qwen = list_lora_adapters(app.handle().clone(), Some("lora-test-qwen".to_string()))
+        .await
+        .unwrap();
+    assert!(!for_qwen.iter().any(|a| a.id == "lora-test-style"));
+    assert!(check_lora_compatibility(
+        app.handle().clone(),
+        "lora-test-style".to_string(),
+        "lora-test-qwen".to_string()
+    )
+    .await
+    .is_err());
+
+    delete_lora_adapter(app.handle().clone(), "lora-test-style".to_string())
+        .await
+        .unwrap();
+    assert!(!Path::new(&entry.path).exists());
+
+    let _ = fs::remove_dir_all(source_dir);
+    let _ = fs::remove_dir_all(models.join("lora-test-llama"));
+    let _ = fs::remove_dir_all(models.join("lora-test-qwen"));
+}
+
+#[tokio::test]
+async fn test_set_assistant_lora_adapters() {
+    let app = mock_app();
+    let data = get_jan_data_folder_path(app.handle().clone());
+    let adapter_dir = data
+        .join("llamacpp")
+        .join("adapters")
+        .join("lora-test-tone");
+    write_gguf(
+        &adapter_dir.join("tone.gguf"),
+        &[("adapter.type", "lora"), ("general.architecture", "llama")],
+    );
+    import_lora_adapter(
+        app.handle().clone(),
+        adapter_dir.join("tone.gguf").to_string_lossy().into_owned(),
+        Some("lora-test-tone".to_string()),
+        Some("Tone".to_string()),
+    )
+    .await
+    .unwrap();
+
+    let assistant =
+        serde_json::from_value(json!({"id": "lora-test-assistant", "name": "Writer"})).unwrap();
+    write_assistant(&data, &assistant).unwrap();
+    let selection = vec![LoraSelection {
+        adapter_id: "lora-test-tone".to_string(),
+        scale: 0.8,
+    }];
+    set_assistant_lora_adapters(
+        app.handle().clone(),
+        "lora-test-assistant".to_string(),
+        selection.clone(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        read_assistant(&data, "lora-test-assistant")
+            .unwrap()
+            .lora_adapters,
+        selection
+    );
+    assert!(set_assistant_lora_adapters(
+        app.handle().clone(),
+        "lora-test-assistant".to_string(),
+        vec![LoraSelection {
+            adapter_id: "missing".to_string(),
+            scale: 1.0,
+        }],
+    )
+    .await
+    .is_err());
+
+    let _ = fs::remove_dir_all(data.join("assistants").join("lora-test-assistant"));
+    let _ = fs::remove_dir_all(adapter_dir);
+}
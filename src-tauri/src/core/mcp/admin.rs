@@ -0,0 +1,256 @@
+//! REST management of MCP servers on the local API server, for headless deployments:
+//!
+//! - `GET  {prefix}/mcp/servers` lists configured and running servers
+//! - `GET  {prefix}/mcp/servers/{name}` returns one server's status
+//! - `GET  {prefix}/mcp/servers/{name}/logs?limit=N` returns its recent log lines
+//! - `POST {prefix}/mcp/servers/{name}/start|stop|restart` control it
+//!
+//! The endpoints mirror the Tauri commands and are only served when the server has an API key.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, Runtime};
+
+use super::commands::{activate_mcp_server, deactivate_mcp_server};
+use super::constants::{DEFAULT_MCP_LOG_LIMIT, MCP_ADMIN_PATH};
+use super::logs::{is_log_muted, server_logs};
+use super::metrics::{dropped_log_lines, record_restart, server_counters};
+use super::migrations::load_config_async;
+use super::models::McpServerStatus;
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::state::AppState;
+
+/// Operations behind the management endpoints
+#[async_trait]
+pub trait McpAdmin: Send + Sync {
+    /// Configured servers and those running without a config entry
+    async fn servers(&self) -> Result<Vec<McpServerStatus>, String>;
+
+    async fn start(&self, name: &str) -> Result<(), String>;
+
+    async fn stop(&self, name: &str) -> Result<(), String>;
+}
+
+/// Management backend handed to the proxy; empty where servers cannot be managed, like the CLI
+#[derive(Clone, Default)]
+pub struct McpAdminHandle(Option<Arc<dyn McpAdmin>>);
+
+impl McpAdminHandle {
+    pub fn new(admin: impl McpAdmin + 'static) -> Self {
+        Self(Some(Arc::new(admin)))
+    }
+}
+
+#[async_trait]
+impl<R: Runtime> McpAdmin for AppHandle<R> {
+    async fn servers(&self) -> Result<Vec<McpServerStatus>, String> {
+        let state = self.state::<AppState>();
+        let path = get_jan_data_folder_path(self.clone()).join("mcp_config.json");
+        let configured = if path.exists() {
+            load_config_async(&path)
+                .await?
+                .get("mcpServers")
+                .and_then(Value::as_object)
+                .cloned()
+                .unwrap_or_default()
+        } else {
+            Default::default()
+        };
+        let running: BTreeSet<String> = state.mcp_servers.read().await.keys().cloned().collect();
+        let pids = state.mcp_server_pids.lock().await.clone();
+
+        let names: BTreeSet<&String> = configured.keys().chain(running.iter()).collect();
+        Ok(names
+            .into_iter()
+            .map(|name| {
+                let (restarts, health_check_failures) = server_counters(name);
+                McpServerStatus {
+                    name: name.clone(),
+                    enabled: configured
+                        .get(name)
+                        .and_then(|config| config.get("active"))
+                        .and_then(Value::as_bool)
+                        .unwrap_or(false),
+                    running: running.contains(name),
+                    pid: pids.get(name).copied(),
+                    restarts,
+                    health_check_failures,
+                    dropped_log_lines: dropped_log_lines(name),
+                    log_muted: is_log_muted(name),
+                }
+            })
+            .collect())
+    }
+
+    async fn start(&self, name: &str) -> Result<(), String> {
+        let path = get_jan_data_folder_path(self.clone()).join("mcp_config.json");
+        let config = load_config_async(&path)
+            .await?
+            .get("mcpServers")
+            .and_then(|servers| servers.get(name))
+            .cloned()
+            .ok_or_else(|| format!("Server {name} is not configured"))?;
+        activate_mcp_server(self.clone(), self.state(), name.to_string(), config).await
+    }
+
+    async fn stop(&self, name: &str) -> Result<(), String> {
+        deactivate_mcp_server(self.clone(), self.state(), name.to_string()).await
+    }
+}
+
+/// A management request, parsed from the method and the path below the API prefix
+#[derive(Debug, PartialEq, Eq)]
+pub enum AdminRoute {
+    List,
+    Status(String),
+    Logs(String),
+    Start(String),
+    Stop(String),
+    Restart(String),
+}
+
+/// Decode `%XX` escapes of a path segment, so server names may contain spaces
+fn decode_segment(segment: &str) -> Option<String> {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = segment.get(i + 1..i + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+pub fn is_admin_path(path: &str) -> bool {
+    path == MCP_ADMIN_PATH || path.starts_with(&format!("{MCP_ADMIN_PATH}/"))
+}
+
+pub fn parse_route(method: &str, path: &str) -> Option<AdminRoute> {
+    let rest = path.strip_prefix(MCP_ADMIN_PATH)?.trim_matches('/');
+    let segments: Vec<&str> = if rest.is_empty() {
+        Vec::new()
+    } else {
+        rest.split('/').collect()
+    };
+    match (method, segments.as_slice()) {
+        ("GET", []) => Some(AdminRoute::List),
+        ("GET", [name]) => Some(AdminRoute::Status(decode_segment(name)?)),
+        ("GET", [name, "logs"]) => Some(AdminRoute::Logs(decode_segment(name)?)),
+        ("POST", [name, action]) => {
+            let name = decode_segment(name)?;
+            match *action {
+                "start" => Some(AdminRoute::Start(name)),
+                "stop" => Some(AdminRoute::Stop(name)),
+                "restart" => Some(AdminRoute::Restart(name)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// `limit` query parameter of a logs request
+fn log_limit(query: Option<&str>) -> usize {
+    query
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .find_map(|pair| pair.strip_prefix("limit="))
+        .and_then(|limit| limit.parse().ok())
+        .unwrap_or(DEFAULT_MCP_LOG_LIMIT)
+}
+
+fn error(status: u16, message: impl Into<String>) -> (u16, Value) {
+    (status, json!({ "error": message.into() }))
+}
+
+async fn find_server(admin: &dyn McpAdmin, name: &str) -> Result<McpServerStatus, (u16, Value)> {
+    admin
+        .servers()
+        .await
+        .map_err(|e| error(500, e))?
+        .into_iter()
+        .find(|server| server.name == name)
+        .ok_or_else(|| error(404, format!("MCP server '{name}' not found")))
+}
+
+async fn run(admin: &dyn McpAdmin, route: AdminRoute, query: Option<&str>) -> (u16, Value) {
+    let result = match route {
+        AdminRoute::List => admin.servers().await.map(|servers| json!(servers)),
+        AdminRoute::Status(name) => {
+            return match find_server(admin, &name).await {
+                Ok(server) => (200, json!(server)),
+                Err(response) => response,
+            };
+        }
+        AdminRoute::Logs(name) => {
+            if let Err(response) = find_server(admin, &name).await {
+                return response;
+            }
+            Ok(json!(server_logs(&name, log_limit(query))))
+        }
+        AdminRoute::Start(name) => match find_server(admin, &name).await {
+            Err(response) => return response,
+            Ok(server) if server.running => Ok(json!(server)),
+            Ok(_) => admin.start(&name).await.map(|_| json!({ "started": name })),
+        },
+        AdminRoute::Stop(name) => match find_server(admin, &name).await {
+            Err(response) => return response,
+            Ok(server) if !server.running => Ok(json!(server)),
+            Ok(_) => admin.stop(&name).await.map(|_| json!({ "stopped": name })),
+        },
+        AdminRoute::Restart(name) => match find_server(admin, &name).await {
+            Err(response) => return response,
+            Ok(server) => {
+                let stopped = if server.running {
+                    admin.stop(&name).await
+                } else {
+                    Ok(())
+                };
+                record_restart(&name);
+                match stopped {
+                    Ok(()) => admin
+                        .start(&name)
+                        .await
+                        .map(|_| json!({ "restarted": name })),
+                    Err(e) => Err(e),
+                }
+            }
+        },
+    };
+    match result {
+        Ok(body) => (200, body),
+        Err(e) => error(500, e),
+    }
+}
+
+/// Serve a management request. Returns the status code and JSON body.
+pub async fn handle_admin_request(
+    handle: &McpAdminHandle,
+    method: &str,
+    path: &str,
+    query: Option<&str>,
+    has_api_key: bool,
+) -> (u16, Value) {
+    if !has_api_key {
+        return error(
+            403,
+            "Set an API key on the local API server to manage MCP servers remotely",
+        );
+    }
+    let Some(admin) = handle.0.as_deref() else {
+        return error(503, "MCP servers cannot be managed from this server");
+    };
+    match parse_route(method, path) {
+        Some(route) => run(admin, route, query).await,
+        None => error(404, "Not Found"),
+    }
+}
@@ -0,0 +1,75 @@
+//! Typed command layer for the MCP surface. The commands and the types they exchange are
+//! described with `specta`, and `tauri-specta` generates their TypeScript signatures into
+//! `web-app/src/types/mcp-bindings.ts`, so the webview no longer hand-writes these contracts.
+//!
+//! Run `cargo test export_mcp_bindings -- --ignored` after changing a command or one of its
+//! types; a test fails while the committed file is out of date.
+
+use std::path::Path;
+
+use specta_typescript::{BigIntExportBehavior, Typescript};
+use tauri::Runtime;
+use tauri_specta::{collect_commands, Builder};
+
+use super::commands::{
+    activate_mcp_server, cancel_tool_call, check_jan_browser_extension_connected,
+    check_mcp_server_updates, clear_mcp_wire_log, deactivate_mcp_server, export_mcp_config,
+    get_cached_tools, get_connected_servers, get_mcp_configs, get_mcp_wire_log, get_tools,
+    import_mcp_config, list_mcp_recordings, respond_mcp_secret, restart_mcp_servers,
+    save_mcp_configs, send_mcp_request, set_mcp_wire_logging, start_mcp_recording,
+    stop_mcp_recording, update_mcp_server_version,
+};
+use super::models::{McpSettings, McpToolCallResult, ToolWithServer};
+
+/// Location of the generated bindings, relative to the `src-tauri` crate
+pub const MCP_BINDINGS_PATH: &str = "../web-app/src/types/mcp-bindings.ts";
+
+/// Builder describing every MCP command. Commands are still dispatched by
+/// `tauri::generate_handler!`; this builder is only used to export their types.
+pub fn mcp_specta_builder<R: Runtime>() -> Builder<R> {
+    Builder::<R>::new()
+        .commands(collect_commands![
+            activate_mcp_server::<R>,
+            deactivate_mcp_server::<R>,
+            restart_mcp_servers::<R>,
+            get_connected_servers::<R>,
+            get_tools::<R>,
+            get_cached_tools::<R>,
+            cancel_tool_call,
+            get_mcp_configs::<R>,
+            save_mcp_configs::<R>,
+            check_jan_browser_extension_connected,
+            check_mcp_server_updates::<R>,
+            update_mcp_server_version::<R>,
+            send_mcp_request,
+            set_mcp_wire_logging,
+            get_mcp_wire_log,
+            clear_mcp_wire_log,
+            start_mcp_recording::<R>,
+            stop_mcp_recording,
+            list_mcp_recordings::<R>,
+            export_mcp_config::<R>,
+            import_mcp_config::<R>,
+            respond_mcp_secret,
+        ])
+        // `call_tool` takes a parameter named `arguments`, which cannot be a parameter name in
+        // strict-mode JavaScript, so only its types are exported and the webview keeps
+        // invoking it with an object
+        .typ::<ToolWithServer>()
+        .typ::<McpToolCallResult>()
+        // Not part of a command signature; the settings UI edits it inside mcp_config.json
+        .typ::<McpSettings>()
+}
+
+/// Write the TypeScript bindings to `path`.
+pub fn export_mcp_bindings<R: Runtime>(path: &Path) -> Result<(), String> {
+    mcp_specta_builder::<R>()
+        .export(
+            Typescript::default()
+                // Timeouts and delays are u64 on the Rust side but always fit a JS number
+                .bigint(BigIntExportBehavior::Number)
+                .header("// @ts-nocheck"),
+            path,
+        )
+        .map_err(|e| format!("Failed to export MCP bindings: {e}"))
+}
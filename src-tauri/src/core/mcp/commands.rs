@@ -1,27 +1,70 @@
-use rmcp::model::{CallToolRequestParam, CallToolResult};
+use rmcp::{
+    model::{CallToolRequestParam, CallToolResult},
+    service::Peer,
+    RoleClient,
+};
 use serde_json::{json, Map, Value};
 use tauri::{AppHandle, Emitter, Manager, Runtime, State};
-use tokio::sync::oneshot;
 use tokio::time::timeout;
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
 
 use super::{
-    constants::DEFAULT_MCP_CONFIG,
-    helpers::{restart_active_mcp_servers, start_mcp_server},
+    admin::McpAdmin,
+    constants::{DEFAULT_MCP_CONFIG, DEFAULT_MCP_WIRE_FRAME_LIMIT},
+    helpers::{
+        collect_tools, emit_mcp_update_event, refresh_tool_cache, restart_active_mcp_servers,
+        server_peer, server_peers, start_mcp_server, tool_call_span,
+    },
+    inspector::{
+        clear_wire_frames, parse_request, set_wire_logging, wire_frames, wire_logging_enabled,
+    },
+    logs::record_server_log,
+    metrics::{record_restart, record_tool_call, ToolCallOutcome},
+    migrations::{
+        load_config_async, migrate_config, update_config_async, upgrade_config, write_config_async,
+    },
+    recording::{is_recording, list_recordings, start_recording, stop_recording},
+    sharing::{
+        export_config, fill_secrets, local_secrets, merge_servers, parse_export, request_secret,
+        required_secrets,
+    },
+    tool_cache::{cached_server_tools, config_hash, prune_tool_cache},
+    versions::{
+        check_server_version, compare_versions, is_exact_version, latest_version, server_package,
+    },
 };
 use crate::core::{
-    app::commands::get_jan_data_folder_path, mcp::models::McpSettings, state::AppState,
+    app::commands::get_jan_data_folder_path,
+    approvals::{helpers::authorize_tool_call, ToolApprovalState},
+    assistants::helpers::resolve_tool_scope,
+    cancellation::models::CancelScope,
+    config_history::helpers::record_config_change,
+    config_store::helpers::config_store,
+    mcp::models::{
+        McpConfigImport, McpPackageVersion, McpRecording, McpSettings, McpToolCallResult,
+        McpWireLog,
+    },
+    settings::helpers::enforce_mcp_policy,
+    state::{AppState, InFlightOperation},
+    telemetry::{helpers::record, models::Metric},
+    tool_artifacts::helpers::offload_call_result,
+    workspaces::helpers::scope_thread_tool_arguments,
 };
 use crate::core::{
     mcp::models::ToolWithServer,
     state::{RunningServiceEnum, SharedMcpServers},
 };
-use std::{fs, time::Duration};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 async fn tool_call_timeout(state: &State<'_, AppState>) -> Duration {
     state.mcp_settings.lock().await.tool_call_timeout_duration()
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn activate_mcp_server<R: Runtime>(
     app: tauri::AppHandle<R>,
     state: State<'_, AppState>,
@@ -30,11 +73,19 @@ pub async fn activate_mcp_server<R: Runtime>(
 ) -> Result<(), String> {
     let servers: SharedMcpServers = state.mcp_servers.clone();
 
-    // Use the modified start_mcp_server that returns first attempt result
-    start_mcp_server(app, servers, name, config).await
+    // Use the modified start_mcp_server that returns first attempt result. Activating a
+    // server that is still starting waits for that start instead of spawning it twice.
+    state
+        .in_flight
+        .run(
+            InFlightOperation::McpStart(name.clone()),
+            start_mcp_server(app, servers, name, config),
+        )
+        .await
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn deactivate_mcp_server<R: Runtime>(
     app: AppHandle<R>,
     state: State<'_, AppState>,
@@ -42,19 +93,14 @@ pub async fn deactivate_mcp_server<R: Runtime>(
 ) -> Result<(), String> {
     log::info!("Deactivating MCP server: {name}");
 
-    // Get port from config before removing (for lock file cleanup later)
-    let bridge_port = if name == "Jan Browser MCP" {
-        let active_servers = state.mcp_active_servers.lock().await;
-        active_servers.get(&name).and_then(|config| {
-            config
-                .get("envs")
-                .and_then(|envs| envs.get("BRIDGE_PORT"))
-                .and_then(|port| port.as_str())
-                .and_then(|port_str| port_str.parse::<u16>().ok())
-        })
-    } else {
-        None
-    };
+    // Ports the server actually got, for lock file cleanup later
+    let server_ports: Vec<u16> = state
+        .mcp_server_ports
+        .lock()
+        .await
+        .get(&name)
+        .map(|ports| ports.values().copied().collect())
+        .unwrap_or_default();
 
     // First, mark server as manually deactivated
     // Remove from active servers list
@@ -66,7 +112,7 @@ pub async fn deactivate_mcp_server<R: Runtime>(
 
     // Now remove and stop the server
     let servers = state.mcp_servers.clone();
-    let mut servers_map = servers.lock().await;
+    let mut servers_map = servers.write().await;
 
     let service = servers_map
         .remove(&name)
@@ -90,18 +136,17 @@ pub async fn deactivate_mcp_server<R: Runtime>(
         let mut pids = state.mcp_server_pids.lock().await;
         pids.remove(&name);
     }
-    // Delete lock file if this is Jan Browser MCP and we have a port
-    if name == "Jan Browser MCP" {
-        if let Some(port) = bridge_port {
-            use crate::core::mcp::lockfile::delete_lock_file;
+    // Delete the lock files of the server's bridge ports
+    for port in server_ports {
+        use crate::core::mcp::lockfile::delete_lock_file;
 
-            if let Err(e) = delete_lock_file(&app, port) {
-                log::warn!("Failed to delete lock file for port {}: {}", port, e);
-            }
+        if let Err(e) = delete_lock_file(&app, port) {
+            log::warn!("Failed to delete lock file for port {}: {}", port, e);
         }
     }
 
     log::info!("Server {name} stopped successfully and marked as deactivated.");
+    record_server_log(&name, log::Level::Info, "Stopped");
 
     // Emit mcp-update event so frontend can refresh tools list
     if let Err(e) = app.emit(
@@ -117,6 +162,7 @@ pub async fn deactivate_mcp_server<R: Runtime>(
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn restart_mcp_servers<R: Runtime>(
     app: AppHandle<R>,
     state: State<'_, AppState>,
@@ -137,65 +183,294 @@ pub async fn restart_mcp_servers<R: Runtime>(
 }
 
 #[tauri::command]
-pub async fn get_connected_servers(
-    _app: AppHandle<impl Runtime>,
+#[specta::specta]
+pub async fn get_connected_servers<R: Runtime>(
+    _app: AppHandle<R>,
     state: State<'_, AppState>,
 ) -> Result<Vec<String>, String> {
     let servers = state.mcp_servers.clone();
-    let servers_map = servers.lock().await;
+    let servers_map = servers.read().await;
     Ok(servers_map.keys().cloned().collect())
 }
 
+/// Check configured npx/uvx-based servers, or only `name`, for newer package versions.
+/// Servers that aren't run from a package are skipped; a failed check of one server is
+/// logged and skipped too, unless it was the only one asked for.
+#[tauri::command]
+#[specta::specta]
+pub async fn check_mcp_server_updates<R: Runtime>(
+    app: AppHandle<R>,
+    name: Option<String>,
+) -> Result<Vec<McpPackageVersion>, String> {
+    let path = get_jan_data_folder_path(app.clone()).join("mcp_config.json");
+    let servers = load_config_async(&path)
+        .await?
+        .get("mcpServers")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+
+    if let Some(name) = name {
+        let config = servers
+            .get(&name)
+            .ok_or_else(|| format!("Server {name} is not configured"))?;
+        return Ok(vec![check_server_version(&app, &name, config).await?]);
+    }
+    let mut versions = Vec::new();
+    for (name, config) in &servers {
+        if server_package(name, config).is_err() {
+            continue;
+        }
+        match check_server_version(&app, name, config).await {
+            Ok(version) => versions.push(version),
+            Err(e) => log::warn!("Version check of MCP server {name} failed: {e}"),
+        }
+    }
+    Ok(versions)
+}
+
+/// Pin an npx/uvx-based server to `version`, or to the newest release when unset, and
+/// restart it when it is running
+#[tauri::command]
+#[specta::specta]
+pub async fn update_mcp_server_version<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, AppState>,
+    name: String,
+    version: Option<String>,
+) -> Result<McpPackageVersion, String> {
+    let path = get_jan_data_folder_path(app.clone()).join("mcp_config.json");
+    let config = load_config_async(&path)
+        .await?
+        .get("mcpServers")
+        .and_then(|servers| servers.get(&name))
+        .cloned()
+        .ok_or_else(|| format!("Server {name} is not configured"))?;
+    let (registry, spec) = server_package(&name, &config)?;
+    let latest = latest_version(&app, registry, &spec.name).await?;
+    let version = match version.map(|v| v.trim().to_string()) {
+        Some(v) if !v.is_empty() => v,
+        _ => latest.clone(),
+    };
+    if !is_exact_version(&version) {
+        return Err(format!("'{version}' is not a version number"));
+    }
+
+    let (server_name, pinned) = (name.clone(), version.clone());
+    update_config_async(&path, move |config| {
+        let server = config
+            .get_mut("mcpServers")
+            .and_then(|servers| servers.get_mut(&server_name))
+            .and_then(Value::as_object_mut)
+            .ok_or_else(|| format!("Server {server_name} is not configured"))?;
+        server.insert("version".to_string(), json!(pinned));
+        Ok(())
+    })
+    .await?;
+    log::info!("Pinned MCP server {name} to {} {version}", spec.name);
+    if let Some(data_folder) = path.parent() {
+        record_config_change(data_folder, &format!("Pin MCP server {name} to {version}"));
+    }
+    record_server_log(
+        &name,
+        log::Level::Info,
+        &format!("Pinned to version {version}"),
+    );
+
+    let running = state.mcp_servers.read().await.contains_key(&name);
+    if running {
+        record_restart(&name);
+        McpAdmin::stop(&app, &name).await?;
+        McpAdmin::start(&app, &name).await?;
+    }
+    emit_mcp_update_event(&app, &name);
+
+    Ok(McpPackageVersion {
+        server: name,
+        package: spec.name,
+        registry: registry.as_str().to_string(),
+        update_available: compare_versions(&latest, &version) == std::cmp::Ordering::Greater,
+        pinned: Some(version),
+        latest,
+    })
+}
+
+/// Send a raw JSON-RPC request to a running server and return the raw result, for the
+/// MCP inspector. Only the request methods of the MCP client role can be sent, except
+/// `tools/call`. The server is asked through its handle, so the lock on the running servers
+/// isn't held while waiting for the answer.
+#[tauri::command]
+#[specta::specta]
+pub async fn send_mcp_request(
+    state: State<'_, AppState>,
+    server: String,
+    method: String,
+    params: Option<Value>,
+) -> Result<Value, String> {
+    let request = parse_request(&method, params)?;
+    let timeout_duration = tool_call_timeout(&state).await;
+    let peer = server_peer(&state.mcp_servers, &server)
+        .await
+        .ok_or_else(|| format!("Server {server} is not running"))?;
+    let result = timeout(timeout_duration, peer.send_request(request))
+        .await
+        .map_err(|_| format!("{method} to {server} timed out"))?
+        .map_err(|e| format!("{method} to {server} failed: {e}"))?;
+    serde_json::to_value(result).map_err(|e| e.to_string())
+}
+
+/// Turn recording of every JSON-RPC frame exchanged with `server` on or off
+#[tauri::command]
+#[specta::specta]
+pub async fn set_mcp_wire_logging(server: String, enabled: bool) -> Result<(), String> {
+    set_wire_logging(&server, enabled);
+    record_server_log(
+        &server,
+        log::Level::Info,
+        format!(
+            "Wire logging {}",
+            if enabled { "enabled" } else { "disabled" }
+        ),
+    );
+    Ok(())
+}
+
+/// The last `limit` recorded frames of `server`, oldest first
+#[tauri::command]
+#[specta::specta]
+pub async fn get_mcp_wire_log(server: String, limit: Option<usize>) -> Result<McpWireLog, String> {
+    Ok(McpWireLog {
+        enabled: wire_logging_enabled(&server),
+        recording: is_recording(&server),
+        frames: wire_frames(&server, limit.unwrap_or(DEFAULT_MCP_WIRE_FRAME_LIMIT)),
+    })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn clear_mcp_wire_log(server: String) -> Result<(), String> {
+    clear_wire_frames(&server);
+    Ok(())
+}
+
+/// Start recording every request and response of `server` into a new session file in
+/// the recordings folder. Returns the file name, usable as the `replay` of a server config.
+#[tauri::command]
+#[specta::specta]
+pub async fn start_mcp_recording<R: Runtime>(
+    app: AppHandle<R>,
+    server: String,
+) -> Result<String, String> {
+    let path = start_recording(&get_jan_data_folder_path(app), &server)?;
+    record_server_log(
+        &server,
+        log::Level::Info,
+        format!("Recording to {}", path.display()),
+    );
+    Ok(path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default())
+}
+
+/// Stop recording `server`. Returns the session file name, or null when it wasn't recorded.
+#[tauri::command]
+#[specta::specta]
+pub async fn stop_mcp_recording(server: String) -> Result<Option<String>, String> {
+    let path = stop_recording(&server);
+    if path.is_some() {
+        record_server_log(&server, log::Level::Info, "Recording stopped");
+    }
+    Ok(path.and_then(|path| {
+        path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+    }))
+}
+
+/// The recorded sessions, newest first
+#[tauri::command]
+#[specta::specta]
+pub async fn list_mcp_recordings<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<Vec<McpRecording>, String> {
+    Ok(list_recordings(&get_jan_data_folder_path(app)))
+}
+
 /// Retrieves all available tools from all MCP servers with server information
 ///
 /// # Arguments
 /// * `state` - Application state containing MCP server connections
+/// * `assistant_id` - Optional assistant whose tool scope filters the returned tools
 ///
 /// # Returns
 /// * `Result<Vec<Tool>, String>` - A vector of all tools if successful, or an error message if failed
 ///
 /// This function:
 /// 1. Locks the MCP servers mutex to access server connections
-/// 2. Iterates through all connected servers
+/// 2. Iterates through all connected servers the assistant is allowed to use
 /// 3. Gets the list of tools from each server
 /// 4. Associates each tool with its parent server name
 /// 5. Combines all tools into a single vector
 /// 6. Returns the combined list of all available tools with server information
 #[tauri::command]
-pub async fn get_tools(state: State<'_, AppState>) -> Result<Vec<ToolWithServer>, String> {
+#[specta::specta]
+pub async fn get_tools<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, AppState>,
+    assistant_id: Option<String>,
+) -> Result<Vec<ToolWithServer>, String> {
+    let scope = resolve_tool_scope(
+        &get_jan_data_folder_path(app.clone()),
+        assistant_id.as_deref(),
+    )?;
     let timeout_duration = tool_call_timeout(&state).await;
-    let servers = state.mcp_servers.lock().await;
-    let mut all_tools: Vec<ToolWithServer> = Vec::new();
-
-    for (server_name, service) in servers.iter() {
-        // List tools with timeout
-        let tools_future = service.list_all_tools();
-        let tools = match timeout(timeout_duration, tools_future).await {
-            Ok(Ok(tools)) => tools,
-            Ok(Err(e)) => {
-                log::warn!("MCP server {} failed to list tools: {}", server_name, e);
-                continue;
-            }
-            Err(_) => {
-                log::warn!(
-                    "Listing tools timed out after {} seconds",
-                    timeout_duration.as_secs()
-                );
-                continue; // Skip this server and continue with others
-            }
-        };
-
-        for tool in tools {
-            all_tools.push(ToolWithServer {
-                name: tool.name.to_string(),
-                description: tool.description.as_ref().map(|d| d.to_string()),
-                input_schema: serde_json::Value::Object((*tool.input_schema).clone()),
-                server: server_name.clone(),
-            });
+    Ok(collect_tools(&app, timeout_duration, scope.as_ref()).await)
+}
+
+/// Returns the last-known tools of the active servers from the tool cache, so they can be
+/// shown before the servers are up, and reconciles the cache with the running servers in
+/// the background, emitting `mcp-update` when their tools changed.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_cached_tools<R: Runtime>(
+    app: AppHandle<R>,
+    assistant_id: Option<String>,
+) -> Result<Vec<ToolWithServer>, String> {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let scope = resolve_tool_scope(&data_folder, assistant_id.as_deref())?;
+    let servers = load_config_async(&data_folder.join("mcp_config.json"))
+        .await?
+        .get("mcpServers")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    prune_tool_cache(&data_folder, &servers.keys().cloned().collect::<Vec<_>>());
+
+    let mut tools = Vec::new();
+    for (name, config) in &servers {
+        let active = config.get("active").and_then(Value::as_bool) == Some(true);
+        if !active || scope.as_ref().is_some_and(|s| !s.permits_server(name)) {
+            continue;
         }
+        let cached =
+            cached_server_tools(&data_folder, name, &config_hash(config)).unwrap_or_default();
+        tools.extend(
+            cached
+                .into_iter()
+                .filter(|tool| !scope.as_ref().is_some_and(|s| !s.permits(name, &tool.name))),
+        );
     }
 
-    Ok(all_tools)
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let timeout_duration = tool_call_timeout(&app_handle.state::<AppState>()).await;
+        if refresh_tool_cache(&app_handle, timeout_duration).await {
+            if let Err(e) = app_handle.emit("mcp-update", "MCP tools updated") {
+                log::error!("Failed to emit mcp-update event: {e}");
+            }
+        }
+    });
+    Ok(tools)
 }
 
 /// Calls a tool on an MCP server by name with optional arguments
@@ -206,9 +481,11 @@ pub async fn get_tools(state: State<'_, AppState>) -> Result<Vec<ToolWithServer>
 /// * `server_name` - Optional name of the server to call the tool from (for disambiguation)
 /// * `arguments` - Optional map of argument names to values
 /// * `cancellation_token` - Optional token to allow cancellation from JS side
+/// * `assistant_id` - Optional assistant whose tool scope restricts which servers and tools may be called
+/// * `thread_id` - Optional thread making the call; path arguments are scoped to its workspace
 ///
 /// # Returns
-/// * `Result<CallToolResult, String>` - Result of the tool call if successful, or error message if failed
+/// * `Result<McpToolCallResult, String>` - Result of the tool call if successful, or error message if failed
 ///
 /// This function:
 /// 1. Locks the MCP servers mutex to access server connections
@@ -217,114 +494,229 @@ pub async fn get_tools(state: State<'_, AppState>) -> Result<Vec<ToolWithServer>
 /// 4. When found, calls the tool on that server with the provided arguments
 /// 5. Supports cancellation via cancellation_token
 /// 6. Returns error if no server has the requested tool or if specified server not found
+/// 7. Returns error if the assistant is not allowed to call the tool
+/// 8. Scopes the path arguments to the thread's workspace, if it is bound to one
+/// 9. Consults the tool approval policy, asking the user when required, before calling
 #[tauri::command]
-pub async fn call_tool(
+#[specta::specta]
+#[allow(clippy::too_many_arguments)]
+pub async fn call_tool<R: Runtime>(
+    app: AppHandle<R>,
     state: State<'_, AppState>,
+    approvals: State<'_, ToolApprovalState>,
     tool_name: String,
     server_name: Option<String>,
     arguments: Option<Map<String, Value>>,
     cancellation_token: Option<String>,
-) -> Result<CallToolResult, String> {
-    let timeout_duration = tool_call_timeout(&state).await;
-    // Set up cancellation if token is provided
-    let (cancel_tx, cancel_rx) = oneshot::channel::<()>();
-
-    if let Some(token) = &cancellation_token {
-        let mut cancellations = state.tool_call_cancellations.lock().await;
-        cancellations.insert(token.clone(), cancel_tx);
+    assistant_id: Option<String>,
+    thread_id: Option<String>,
+) -> Result<McpToolCallResult, String> {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let scope = resolve_tool_scope(&data_folder, assistant_id.as_deref())?;
+    if let (Some(scope), Some(server)) = (&scope, &server_name) {
+        if !scope.permits(server, &tool_name) {
+            return Err(format!(
+                "Assistant is not allowed to call tool '{tool_name}' on server '{server}'"
+            ));
+        }
     }
+    let timeout_duration = tool_call_timeout(&state).await;
+    // Set up cancellation if token is provided; the scope closes when the call returns
+    let cancel_guard = match &cancellation_token {
+        Some(token) => Some(
+            state
+                .cancellations
+                .open(CancelScope::ToolCall(token.clone()), None)?,
+        ),
+        None => None,
+    };
 
-    let servers = state.mcp_servers.lock().await;
+    // Find the server providing the tool. Servers are asked through their handles, so
+    // neither listing tools nor a pending approval holds the lock on the running servers.
+    let mut denied_by_scope = false;
+    let mut target_server: Option<(String, Value)> = None;
+    {
+        // If server_name is provided, only check that specific server
+        let servers_to_check: Vec<(String, Peer<RoleClient>)> = server_peers(&state.mcp_servers)
+            .await
+            .into_iter()
+            .filter(|(name, _)| match &server_name {
+                Some(server) => name == server,
+                None => true,
+            })
+            .collect();
+
+        if servers_to_check.is_empty() {
+            if let Some(server) = server_name {
+                return Err(format!("Server '{server}' not found"));
+            }
+        }
 
-    // If server_name is provided, only check that specific server
-    let servers_to_check: Vec<(&String, &crate::core::state::RunningServiceEnum)> =
-        if let Some(ref server) = server_name {
-            servers.iter().filter(|(name, _)| *name == server).collect()
-        } else {
-            servers.iter().collect()
-        };
+        // Iterate through servers and find the one that contains the tool
+        for (srv_name, peer) in servers_to_check {
+            let tools = match peer.list_all_tools().await {
+                Ok(tools) => tools,
+                Err(_) => continue, // Skip this server if we can't list tools
+            };
+
+            let Some(tool) = tools.iter().find(|t| t.name == tool_name) else {
+                continue; // Tool not found in this server, try next
+            };
+
+            if let Some(scope) = &scope {
+                if !scope.permits(&srv_name, &tool_name) {
+                    denied_by_scope = true;
+                    continue; // Assistant is not granted this server/tool, try next
+                }
+            }
 
-    if servers_to_check.is_empty() {
-        if let Some(server) = server_name {
-            return Err(format!("Server '{server}' not found"));
+            target_server = Some((srv_name, Value::Object((*tool.input_schema).clone())));
+            break;
         }
     }
 
-    // Iterate through servers and find the one that contains the tool
-    for (srv_name, service) in servers_to_check.iter() {
-        let tools = match service.list_all_tools().await {
-            Ok(tools) => tools,
-            Err(_) => continue, // Skip this server if we can't list tools
-        };
-
-        if !tools.iter().any(|t| t.name == tool_name) {
-            continue; // Tool not found in this server, try next
+    let called_server = target_server
+        .as_ref()
+        .map(|(srv_name, _)| srv_name.clone())
+        .unwrap_or_default();
+    let result = match target_server {
+        Some((srv_name, input_schema)) => {
+            println!("Found tool {tool_name} in server {srv_name}");
+            // Scoped before approval so the user sees the paths that will actually be used
+            let arguments = arguments
+                .map(|arguments| {
+                    scope_thread_tool_arguments(
+                        &data_folder,
+                        thread_id.as_deref(),
+                        &input_schema,
+                        arguments,
+                    )
+                })
+                .transpose()?;
+            match authorize_tool_call(
+                &app,
+                &approvals,
+                &srv_name,
+                &tool_name,
+                arguments.as_ref(),
+                assistant_id.as_deref(),
+                None,
+            )
+            .await
+            {
+                Ok(()) => {
+                    let result = call_found_tool(
+                        &state,
+                        &srv_name,
+                        &tool_name,
+                        arguments,
+                        timeout_duration,
+                        cancel_guard.as_ref().map(|guard| guard.token()),
+                    )
+                    .await;
+                    record(&app, Metric::ToolCall);
+                    if !matches!(&result, Ok(r) if r.is_error != Some(true)) {
+                        record(&app, Metric::ToolCallFailed);
+                    }
+                    result
+                }
+                Err(e) => Err(e),
+            }
         }
+        None if denied_by_scope => Err(format!(
+            "Assistant is not allowed to call tool '{tool_name}'"
+        )),
+        None => Err(format!("Tool {tool_name} not found")),
+    };
 
-        println!("Found tool {tool_name} in server {srv_name}");
+    drop(cancel_guard);
 
-        // Call the tool with timeout and cancellation support
-        let tool_call = service.call_tool(CallToolRequestParam {
-            name: tool_name.clone().into(),
+    result.map(|result| {
+        offload_call_result(
+            &data_folder,
+            &called_server,
+            &tool_name,
+            McpToolCallResult::from(result),
+        )
+    })
+}
+
+/// Calls a tool on the given server with timeout and optional cancellation support
+async fn call_found_tool(
+    state: &State<'_, AppState>,
+    srv_name: &str,
+    tool_name: &str,
+    arguments: Option<Map<String, Value>>,
+    timeout_duration: Duration,
+    cancel: Option<&CancellationToken>,
+) -> Result<CallToolResult, String> {
+    let peer = server_peer(&state.mcp_servers, srv_name)
+        .await
+        .ok_or_else(|| format!("Server '{srv_name}' not found"))?;
+
+    let span = tool_call_span(srv_name, tool_name);
+    let tool_call = peer
+        .call_tool(CallToolRequestParam {
+            name: tool_name.to_string().into(),
             arguments,
-        });
-
-        // Race between timeout, tool call, and cancellation
-        let result = if cancellation_token.is_some() {
-            tokio::select! {
-                result = timeout(timeout_duration, tool_call) => {
-                    match result {
-                        Ok(call_result) => call_result.map_err(|e| e.to_string()),
-                        Err(_) => Err(format!(
-                            "Tool call '{tool_name}' timed out after {} seconds",
-                            timeout_duration.as_secs()
-                        )),
-                    }
-                }
-                _ = cancel_rx => {
-                    Err(format!("Tool call '{tool_name}' was cancelled"))
+        })
+        .instrument(span.clone());
+
+    let started = Instant::now();
+    // Race between timeout, tool call, and cancellation
+    let result = if let Some(cancel) = cancel {
+        tokio::select! {
+            result = timeout(timeout_duration, tool_call) => {
+                match result {
+                    Ok(call_result) => call_result.map_err(|e| e.to_string()),
+                    Err(_) => Err(format!(
+                        "Tool call '{tool_name}' timed out after {} seconds",
+                        timeout_duration.as_secs()
+                    )),
                 }
             }
-        } else {
-            match timeout(timeout_duration, tool_call).await {
-                Ok(call_result) => call_result.map_err(|e| e.to_string()),
-                Err(_) => Err(format!(
-                    "Tool call '{tool_name}' timed out after {} seconds",
-                    timeout_duration.as_secs()
-                )),
+            _ = cancel.cancelled() => {
+                Err(format!("Tool call '{tool_name}' was cancelled"))
             }
-        };
-
-        // Clean up cancellation token
-        if let Some(token) = &cancellation_token {
-            let mut cancellations = state.tool_call_cancellations.lock().await;
-            cancellations.remove(token);
         }
-
-        return result;
+    } else {
+        match timeout(timeout_duration, tool_call).await {
+            Ok(call_result) => call_result.map_err(|e| e.to_string()),
+            Err(_) => Err(format!(
+                "Tool call '{tool_name}' timed out after {} seconds",
+                timeout_duration.as_secs()
+            )),
+        }
+    };
+    if let Err(e) = &result {
+        tracing::error!(parent: &span, error = %e, "MCP tool call failed");
     }
-
-    Err(format!("Tool {tool_name} not found"))
+    record_tool_call(
+        srv_name,
+        ToolCallOutcome::of(&result, |r| r.is_error == Some(true)),
+        started.elapsed(),
+    );
+    result
 }
 
 /// Cancels a running tool call by its cancellation token
 ///
 /// # Arguments
-/// * `state` - Application state containing cancellation tokens
+/// * `state` - Application state containing the cancellation tree
 /// * `cancellation_token` - Token identifying the tool call to cancel
 ///
 /// # Returns
 /// * `Result<(), String>` - Success if token found and cancelled, error otherwise
 #[tauri::command]
+#[specta::specta]
 pub async fn cancel_tool_call(
     state: State<'_, AppState>,
     cancellation_token: String,
 ) -> Result<(), String> {
-    let mut cancellations = state.tool_call_cancellations.lock().await;
-
-    if let Some(cancel_tx) = cancellations.remove(&cancellation_token) {
-        // Send cancellation signal - ignore if receiver is already dropped
-        let _ = cancel_tx.send(());
+    if state
+        .cancellations
+        .cancel(&CancelScope::ToolCall(cancellation_token.clone()))
+    {
         println!("Tool call with token {cancellation_token} cancelled");
         Ok(())
     } else {
@@ -332,13 +724,17 @@ pub async fn cancel_tool_call(
     }
 }
 
+/// MCP settings stored in the config, with the values the admin policy fixes
 fn parse_mcp_settings(value: Option<&Value>) -> McpSettings {
-    value
-        .and_then(|v| serde_json::from_value::<McpSettings>(v.clone()).ok())
-        .unwrap_or_default()
+    enforce_mcp_policy(
+        value
+            .and_then(|v| serde_json::from_value::<McpSettings>(v.clone()).ok())
+            .unwrap_or_default(),
+    )
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn get_mcp_configs<R: Runtime>(app: AppHandle<R>) -> Result<String, String> {
     let mut path = get_jan_data_folder_path(app.clone());
     path.push("mcp_config.json");
@@ -346,11 +742,16 @@ pub async fn get_mcp_configs<R: Runtime>(app: AppHandle<R>) -> Result<String, St
     // Create default empty config if file doesn't exist
     if !path.exists() {
         log::info!("mcp_config.json not found, creating default empty config");
-        fs::write(&path, DEFAULT_MCP_CONFIG)
+        config_store()
+            .write_async(&path, DEFAULT_MCP_CONFIG)
+            .await
             .map_err(|e| format!("Failed to create default MCP config: {e}"))?;
     }
 
-    let config_string = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let config_string = config_store()
+        .read_async(&path)
+        .await
+        .map_err(|e| e.to_string())?;
 
     let mut config_value: Value = if config_string.trim().is_empty() {
         json!({})
@@ -365,7 +766,7 @@ pub async fn get_mcp_configs<R: Runtime>(app: AppHandle<R>) -> Result<String, St
         config_value = json!({});
     }
 
-    let mut mutated = false;
+    let mut mutated = upgrade_config(&path, &config_string, &mut config_value)?;
     let config_object = config_value.as_object_mut().unwrap();
 
     let settings = parse_mcp_settings(config_object.get("mcpSettings"));
@@ -409,12 +810,7 @@ pub async fn get_mcp_configs<R: Runtime>(app: AppHandle<R>) -> Result<String, St
 
     // Persist any mutations back to disk
     if mutated {
-        fs::write(
-            &path,
-            serde_json::to_string_pretty(&config_value)
-                .map_err(|e| format!("Failed to serialize MCP config: {e}"))?,
-        )
-        .map_err(|e| format!("Failed to write MCP config: {e}"))?;
+        write_config_async(&path, &config_value).await?;
     }
 
     // Update in-memory state with latest settings
@@ -459,17 +855,16 @@ fn get_result_text(result: &rmcp::model::CallToolResult) -> Option<&str> {
 
 /// Check if Jan Browser extension is connected via MCP
 #[tauri::command]
+#[specta::specta]
 pub async fn check_jan_browser_extension_connected(
     state: State<'_, AppState>,
 ) -> Result<bool, String> {
-    let servers = state.mcp_servers.lock().await;
-    let service = match servers.get("Jan Browser MCP") {
-        Some(s) => s,
-        None => return Ok(false),
+    let Some(peer) = server_peer(&state.mcp_servers, "Jan Browser MCP").await else {
+        return Ok(false);
     };
 
     // Check available tools
-    let tools = match timeout(Duration::from_secs(2), service.list_all_tools()).await {
+    let tools = match timeout(Duration::from_secs(2), peer.list_all_tools()).await {
         Ok(Ok(tools)) if !tools.is_empty() => tools,
         _ => return Ok(false),
     };
@@ -478,7 +873,7 @@ pub async fn check_jan_browser_extension_connected(
 
     // Try simple ping first if available
     if has_ping {
-        match try_ping_tool(service).await {
+        match try_ping_tool(&peer).await {
             PingResult::Connected => return Ok(true),
             PingResult::NotConnected => return Ok(false),
             PingResult::ToolNotAvailable => {
@@ -488,7 +883,7 @@ pub async fn check_jan_browser_extension_connected(
     }
 
     // Fallback to browser_snapshot
-    try_browser_snapshot_tool(service).await
+    try_browser_snapshot_tool(&peer).await
 }
 
 enum PingResult {
@@ -497,10 +892,10 @@ enum PingResult {
     ToolNotAvailable,
 }
 
-async fn try_ping_tool(service: &RunningServiceEnum) -> PingResult {
+async fn try_ping_tool(peer: &Peer<RoleClient>) -> PingResult {
     let result = timeout(
         Duration::from_secs(3),
-        service.call_tool(CallToolRequestParam {
+        peer.call_tool(CallToolRequestParam {
             name: "ping".into(),
             arguments: Some(Map::new()),
         }),
@@ -533,12 +928,12 @@ async fn try_ping_tool(service: &RunningServiceEnum) -> PingResult {
     }
 }
 
-async fn try_browser_snapshot_tool(service: &RunningServiceEnum) -> Result<bool, String> {
+async fn try_browser_snapshot_tool(peer: &Peer<RoleClient>) -> Result<bool, String> {
     let result = timeout(
         // Snapshot tool is very time-consuming
         // Extend timeout to make sure the tool call has enough time to succeed
         Duration::from_secs(20),
-        service.call_tool(CallToolRequestParam {
+        peer.call_tool(CallToolRequestParam {
             name: "browser_snapshot".into(),
             arguments: Some(Map::new()),
         }),
@@ -561,6 +956,7 @@ async fn try_browser_snapshot_tool(service: &RunningServiceEnum) -> Result<bool,
 }
 
 #[tauri::command]
+#[specta::specta]
 pub async fn save_mcp_configs<R: Runtime>(
     app: AppHandle<R>,
     configs: String,
@@ -572,9 +968,8 @@ pub async fn save_mcp_configs<R: Runtime>(
     let mut config_value: Value =
         serde_json::from_str(&configs).map_err(|e| format!("Invalid MCP config payload: {e}"))?;
 
-    if !config_value.is_object() {
-        return Err("MCP config must be a JSON object".to_string());
-    }
+    // An older UI may still send a previous layout
+    migrate_config(&mut config_value)?;
 
     let config_object = config_value.as_object_mut().unwrap();
     let settings = parse_mcp_settings(config_object.get("mcpSettings"));
@@ -590,12 +985,10 @@ pub async fn save_mcp_configs<R: Runtime>(
         config_object.insert("mcpServers".to_string(), json!({}));
     }
 
-    fs::write(
-        &path,
-        serde_json::to_string_pretty(&config_value)
-            .map_err(|e| format!("Failed to serialize MCP config: {e}"))?,
-    )
-    .map_err(|e| e.to_string())?;
+    write_config_async(&path, &config_value).await?;
+    if let Some(data_folder) = path.parent() {
+        record_config_change(data_folder, "Update MCP config");
+    }
 
     {
         let state = app.state::<AppState>();
@@ -605,3 +998,80 @@ pub async fn save_mcp_configs<R: Runtime>(
 
     Ok(())
 }
+
+/// The MCP config as a shareable setup, with the values of secret env vars and headers
+/// replaced by `{{secret:<NAME>}}` placeholders
+#[tauri::command]
+#[specta::specta]
+pub async fn export_mcp_config<R: Runtime>(app: AppHandle<R>) -> Result<String, String> {
+    let path = get_jan_data_folder_path(app).join("mcp_config.json");
+    let config = load_config_async(&path).await?;
+    serde_json::to_string_pretty(&export_config(&config))
+        .map_err(|e| format!("Failed to serialize MCP config export: {e}"))
+}
+
+/// Add the servers of a shared setup to the MCP config, replacing servers of the same name.
+/// Secrets come from `secrets`, then from the local config of the same server; the user is
+/// asked for the others through `mcp-secret-required`. Nothing changes when one is declined.
+#[tauri::command]
+#[specta::specta]
+pub async fn import_mcp_config<R: Runtime>(
+    app: AppHandle<R>,
+    data: String,
+    secrets: Option<HashMap<String, String>>,
+) -> Result<McpConfigImport, String> {
+    let mut export = parse_export(&data)?;
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let path = data_folder.join("mcp_config.json");
+    let local = load_config_async(&path).await.unwrap_or(Value::Null);
+    let mut known = local_secrets(&export.mcp_servers, &local);
+    known.extend(secrets.unwrap_or_default());
+    for secret in required_secrets(&export.mcp_servers) {
+        if known.contains_key(&secret.name) {
+            continue;
+        }
+        let value = request_secret(&app, &secret)
+            .await
+            .ok_or_else(|| format!("MCP config import cancelled: no value for {}", secret.name))?;
+        known.insert(secret.name, value);
+    }
+    fill_secrets(&mut export.mcp_servers, &known)?;
+
+    let import = Arc::new(std::sync::Mutex::new(McpConfigImport::default()));
+    let merged = import.clone();
+    let servers = export.mcp_servers;
+    update_config_async(&path, move |config| {
+        let import = merge_servers(config, servers)?;
+        *merged.lock().unwrap_or_else(|e| e.into_inner()) = import;
+        Ok(())
+    })
+    .await?;
+    record_config_change(&data_folder, "Import MCP config");
+
+    let import = import.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    log::info!(
+        "Imported MCP config: added {:?}, replaced {:?}",
+        import.added,
+        import.replaced
+    );
+    Ok(import)
+}
+
+/// Answer a pending `mcp-secret-required` request. No value declines it, which cancels the
+/// import.
+#[tauri::command]
+#[specta::specta]
+pub async fn respond_mcp_secret(
+    state: State<'_, AppState>,
+    request_id: String,
+    value: Option<String>,
+) -> Result<(), String> {
+    let sender = state
+        .mcp_secret_requests
+        .lock()
+        .await
+        .remove(&request_id)
+        .ok_or_else(|| format!("Secret request {request_id} not found"))?;
+    let _ = sender.send(value);
+    Ok(())
+}
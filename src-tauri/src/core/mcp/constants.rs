@@ -1,10 +1,35 @@
+use std::time::Duration;
+
 // Default MCP runtime settings
 pub const DEFAULT_MCP_TOOL_CALL_TIMEOUT_SECS: u64 = 30;
 pub const DEFAULT_MCP_BASE_RESTART_DELAY_MS: u64 = 1000; // Start with 1 second
 pub const DEFAULT_MCP_MAX_RESTART_DELAY_MS: u64 = 30000; // Cap at 30 seconds
 pub const DEFAULT_MCP_BACKOFF_MULTIPLIER: f64 = 2.0; // Double the delay each time
+pub const DEFAULT_MCP_STARTUP_CONCURRENCY: usize = 4;
+pub const DEFAULT_MCP_STARTUP_BUDGET_SECS: u64 = 30; // 0 waits for all servers
+
+// Health checks of running servers. Local servers are checked often and dropped on their
+// first failed check; remote servers are checked less often and get a few chances, so a
+// network blip doesn't drop them. The `healthCheck` entry of a server config overrides these.
+pub const MCP_HEALTH_CHECK_INTERVAL_SECS: u64 = 5;
+pub const MCP_HEALTH_CHECK_TIMEOUT_SECS: u64 = 2;
+pub const MCP_HEALTH_CHECK_FAILURE_THRESHOLD: u32 = 1;
+pub const MCP_REMOTE_HEALTH_CHECK_INTERVAL_SECS: u64 = 30;
+pub const MCP_REMOTE_HEALTH_CHECK_TIMEOUT_SECS: u64 = 10;
+pub const MCP_REMOTE_HEALTH_CHECK_FAILURE_THRESHOLD: u32 = 3;
+
+// Reconnects of remote servers whose connection dropped. Only the session is rebuilt, so
+// retries start right away and stay close together.
+pub const MCP_RECONNECT_ATTEMPTS: u32 = 5;
+pub const MCP_RECONNECT_BASE_DELAY_MS: u64 = 250;
+pub const MCP_RECONNECT_MAX_DELAY_MS: u64 = 4000;
+
+/// Layout version of `mcp_config.json` written by this build; see `mcp::migrations`
+pub const MCP_CONFIG_VERSION: u64 = 1;
+pub const MCP_CONFIG_VERSION_KEY: &str = "version";
 
 pub const DEFAULT_MCP_CONFIG: &str = r#"{
+  "version": 1,
   "mcpServers": {
     "Jan Browser MCP": {
       "command": "npx",
@@ -63,6 +88,111 @@ pub const DEFAULT_MCP_CONFIG: &str = r#"{
     "toolCallTimeoutSeconds": 30,
     "baseRestartDelayMs": 1000,
     "maxRestartDelayMs": 30000,
-    "backoffMultiplier": 2.0
+    "backoffMultiplier": 2.0,
+    "startupConcurrency": 4,
+    "startupBudgetSeconds": 30
   }
 }"#;
+
+// Socket transport ("unix" on Unix, "pipe" on Windows named pipes)
+pub const SOCKET_TRANSPORT_TYPES: [&str; 2] = ["unix", "pipe"];
+/// Environment variable telling a spawned server where to listen
+pub const SOCKET_PATH_ENV: &str = "MCP_SOCKET_PATH";
+/// How long to wait for a spawned server to create its socket
+pub const SOCKET_WAIT_TIMEOUT_SECS: u64 = 15;
+pub const SOCKET_POLL_INTERVAL_MS: u64 = 100;
+
+// Recent log lines kept per MCP server for the management API
+pub const MAX_MCP_LOG_LINES: usize = 500;
+pub const DEFAULT_MCP_LOG_LIMIT: usize = 100;
+// Output limits of a server's stderr; see `logs::OutputLimiter`
+pub const MAX_MCP_LOG_LINE_BYTES: usize = 8 * 1024;
+pub const MAX_MCP_STARTUP_ERROR_BYTES: u64 = 64 * 1024;
+pub const MCP_LOG_LINES_PER_SEC: f64 = 100.0;
+pub const MCP_LOG_BURST_LINES: f64 = 1_000.0;
+/// A line costs one more line of budget per this many bytes
+pub const MCP_LOG_LINE_COST_BYTES: usize = 1024;
+/// Dropping this many lines within the window mutes the server
+pub const MCP_LOG_MUTE_THRESHOLD: u64 = 5_000;
+pub const MCP_LOG_MUTE_WINDOW: Duration = Duration::from_secs(10);
+pub const MCP_LOG_MUTE_DURATION: Duration = Duration::from_secs(300);
+pub const MCP_SERVER_MUTED_EVENT: &str = "mcp-server-muted";
+
+// Management endpoints on the local API server, below its prefix
+pub const MCP_ADMIN_PATH: &str = "/mcp/servers";
+
+// Placeholders resolved in server configs at spawn time, see `templates`
+pub const TEMPLATE_DATA_DIR: &str = "data_dir";
+pub const TEMPLATE_THREAD_WORKSPACE: &str = "thread_workspace";
+pub const TEMPLATE_PORT_PREFIX: &str = "port:";
+pub const MAX_TEMPLATE_PORT_ATTEMPTS: usize = 16;
+
+// Env vars holding a port a server listens on; `BRIDGE_PORT` always counts, others are
+// listed under the config's `bridgePorts`
+pub const DEFAULT_BRIDGE_PORT_ENV: &str = "BRIDGE_PORT";
+// Ports scanned above a busy bridge port before asking the OS for any free one
+pub const MAX_BRIDGE_PORT_SCAN: u16 = 100;
+pub const MCP_PORT_CHANGED_EVENT: &str = "mcp-port-changed";
+
+// Package registries asked for the newest version of npx/uvx-based servers
+pub const NPM_REGISTRY_URL: &str = "https://registry.npmjs.org";
+pub const PYPI_REGISTRY_URL: &str = "https://pypi.org/pypi";
+pub const VERSION_CHECK_TIMEOUT_SECS: u64 = 10;
+// Flags of npx and uvx that take a value, so the value isn't taken for the package
+pub const NPX_VALUE_FLAGS: &[&str] = &["--registry", "--cache", "--userconfig", "-c", "--call"];
+pub const UVX_VALUE_FLAGS: &[&str] = &[
+    "--with",
+    "--with-editable",
+    "--with-requirements",
+    "--python",
+    "-p",
+    "--index",
+    "--index-url",
+    "--extra-index-url",
+    "--cache-dir",
+];
+
+// Last-known tool lists of the servers, in the data folder
+pub const TOOL_CACHE_FILE: &str = "mcp_tool_cache.json";
+// Config fields that decide which tools a server offers; the cache is keyed by their hash
+pub const TOOL_CACHE_CONFIG_KEYS: &[&str] = &[
+    "type", "command", "args", "env", "url", "headers", "socket", "version", "replay",
+];
+
+// Startup of servers beyond the startup budget
+pub const MCP_STARTUP_EVENT: &str = "mcp-startup";
+pub const MCP_STARTUP_DEFERRED: &str = "deferred";
+pub const MCP_STARTUP_STARTED: &str = "started";
+pub const MCP_STARTUP_FAILED: &str = "failed";
+
+// Inspector wire log
+pub const MAX_MCP_WIRE_FRAMES: usize = 1000;
+pub const DEFAULT_MCP_WIRE_FRAME_LIMIT: usize = 200;
+pub const MCP_WIRE_SENT: &str = "sent";
+pub const MCP_WIRE_RECEIVED: &str = "received";
+
+// Recorded sessions, replayed by servers with a `replay` config
+pub const MCP_RECORDINGS_DIR: &str = "mcp_recordings";
+pub const MCP_RECORDING_EXTENSION: &str = "jsonl";
+
+// Shared setups, see `sharing`
+pub const MCP_EXPORT_FORMAT: &str = "jan-mcp-config";
+pub const TEMPLATE_SECRET_PREFIX: &str = "secret:";
+// Server config fields holding values by name, the secret ones of which an export leaves out
+pub const SECRET_CONFIG_FIELDS: &[&str] = &["env", "headers"];
+// Env vars and headers whose name contains one of these (case-insensitive) hold secrets
+pub const SECRET_KEY_MARKERS: &[&str] = &[
+    "KEY",
+    "TOKEN",
+    "SECRET",
+    "PASSWORD",
+    "PASSWD",
+    "AUTH",
+    "CREDENTIAL",
+    "COOKIE",
+];
+// Schemes kept in front of a secret header value, so only the credential is asked for
+pub const AUTH_SCHEMES: &[&str] = &["Bearer ", "Basic ", "Token "];
+pub const MCP_SECRET_REQUIRED_EVENT: &str = "mcp-secret-required";
+// Unanswered secret requests cancel the import after this delay
+pub const MCP_SECRET_TIMEOUT_SECS: u64 = 300;
@@ -1,26 +1,81 @@
 use rmcp::{
-    model::{ClientCapabilities, ClientInfo, Implementation},
+    model::{
+        CallToolRequestParam, CallToolResult, ClientCapabilities, ClientInfo, ClientRequest,
+        ErrorCode, Implementation,
+    },
+    service::Peer,
     transport::{
         streamable_http_client::StreamableHttpClientTransportConfig, SseClientTransport,
         StreamableHttpClientTransport, TokioChildProcess,
     },
-    ServiceExt,
+    RoleClient, ServiceError, ServiceExt,
 };
 use serde_json::Value;
-use std::{collections::HashMap, env, process::Stdio, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    process::Stdio,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tauri::{AppHandle, Emitter, Manager, Runtime, State};
 use tauri_plugin_http::reqwest;
 use tokio::{
     io::AsyncReadExt,
     process::Command,
-    sync::Mutex,
+    sync::{Mutex, Semaphore},
     time::{sleep, timeout},
 };
+use tracing::{Instrument, Span};
 
 use crate::core::{
     app::commands::get_jan_data_folder_path,
-    mcp::models::{McpServerConfig, McpSettings},
+    assistants::models::ToolScope,
+    mcp::constants::{
+        MAX_MCP_STARTUP_ERROR_BYTES, MCP_HEALTH_CHECK_FAILURE_THRESHOLD,
+        MCP_HEALTH_CHECK_INTERVAL_SECS, MCP_HEALTH_CHECK_TIMEOUT_SECS, MCP_PORT_CHANGED_EVENT,
+        MCP_RECONNECT_ATTEMPTS, MCP_RECONNECT_BASE_DELAY_MS, MCP_RECONNECT_MAX_DELAY_MS,
+        MCP_REMOTE_HEALTH_CHECK_FAILURE_THRESHOLD, MCP_REMOTE_HEALTH_CHECK_INTERVAL_SECS,
+        MCP_REMOTE_HEALTH_CHECK_TIMEOUT_SECS, MCP_STARTUP_DEFERRED, MCP_STARTUP_EVENT,
+        MCP_STARTUP_FAILED, MCP_STARTUP_STARTED, SOCKET_PATH_ENV, SOCKET_TRANSPORT_TYPES,
+        SOCKET_WAIT_TIMEOUT_SECS,
+    },
+    mcp::inspector::inspected,
+    mcp::logs::{forward_server_output, record_server_log},
+    mcp::metrics::{
+        record_health_check_failure, record_reconnect, record_restart, record_tool_call,
+        ToolCallOutcome,
+    },
+    mcp::migrations::{load_config_async, update_config_async},
+    mcp::models::{
+        HealthCheck, HealthCheckOverrides, HealthProbe, McpPortChange, McpServerConfig,
+        McpSettings, McpStartupStatus, ToolWithServer,
+    },
+    mcp::ports::{bridge_ports, replacement_port},
+    mcp::recording::{recording_path, ReplayTransport},
+    mcp::roots::JanClient,
+    mcp::socket::{connect_socket, remove_stale_socket, validate_socket_path, wait_for_socket},
+    mcp::templates::{
+        allocate_free_port, latest_workspace, resolve_server_config, TemplateContext,
+    },
+    mcp::tool_cache::{cached_server_tools, config_hash, remember_server_tools},
+    mcp::versions::{pin_package_args, PackageRegistry},
+    messages::{
+        helpers::localize,
+        models::{LocalizedMessage, MessageCode},
+    },
+    notifications::{helpers::notify, models::NotificationCategory},
+    offline::helpers::check_url,
+    runtimes::helpers::{ensure_runtime, runtime_cache_dir},
+    runtimes::models::RuntimeKind,
+    settings::helpers::{admin_policy, check_mcp_command_allowed, enforce_mcp_policy},
     state::{AppState, RunningServiceEnum, SharedMcpServers},
+    watchdog::{
+        constants::{MCP_SHUTDOWN_GRACE, MCP_STARTUP_EXPECTED},
+        helpers::watch,
+        models::WatchedKind,
+        WatchHandle,
+    },
 };
 use jan_utils::{can_override_npx, can_override_uvx};
 
@@ -68,22 +123,19 @@ pub async fn run_mcp_commands<R: Runtime>(
         "Load MCP configs from {}",
         app_path_str.clone() + "/mcp_config.json"
     );
-    let config_content = std::fs::read_to_string(app_path_str + "/mcp_config.json")
-        .map_err(|e| format!("Failed to read config file: {e}"))?;
-
-    let mcp_servers: serde_json::Value = serde_json::from_str(&config_content)
-        .map_err(|e| format!("Failed to parse config: {e}"))?;
+    let mcp_servers = load_config_async(&app_path.join("mcp_config.json")).await?;
 
     // Update runtime MCP settings from config
-    {
-        let settings = mcp_servers
+    let settings = enforce_mcp_policy(
+        mcp_servers
             .get("mcpSettings")
             .and_then(|value| serde_json::from_value::<McpSettings>(value.clone()).ok())
-            .unwrap_or_default();
-
+            .unwrap_or_default(),
+    );
+    {
         let app_state = app.state::<AppState>();
         let mut guard = app_state.mcp_settings.lock().await;
-        *guard = settings;
+        *guard = settings.clone();
     }
 
     let server_map = mcp_servers
@@ -93,6 +145,12 @@ pub async fn run_mcp_commands<R: Runtime>(
 
     log::trace!("MCP Servers: {server_map:#?}");
 
+    let permits = Arc::new(Semaphore::new(settings.startup_concurrency.max(1)));
+    let tracker = Arc::new(std::sync::Mutex::new(StartupTracker::default()));
+    let deadline = settings
+        .startup_budget()
+        .map(|budget| tokio::time::Instant::now() + budget);
+
     // Collect handles for initial server startup
     let mut startup_handles = Vec::new();
 
@@ -106,9 +164,15 @@ pub async fn run_mcp_commands<R: Runtime>(
         let servers_clone = servers_state.clone();
         let name_clone = name.clone();
         let config_clone = config.clone();
+        let permits = permits.clone();
+        let tracker = tracker.clone();
+        lock_tracker(&tracker).pending.insert(name.clone());
 
         // Spawn task for initial startup attempt
         let handle = tauri::async_runtime::spawn(async move {
+            // Throttle how many servers start at once; the semaphore is never closed
+            let _permit = permits.acquire_owned().await.ok();
+
             // Only wait for the initial startup attempt, not the monitoring
             let result = start_mcp_server(
                 app_clone.clone(),
@@ -123,18 +187,38 @@ pub async fn run_mcp_commands<R: Runtime>(
                 log::error!("Initial startup failed for MCP server {name_clone}: {e}");
             }
 
+            let deferred = {
+                let mut tracker = lock_tracker(&tracker);
+                tracker.pending.remove(&name_clone);
+                tracker.deferred
+            };
+            if deferred {
+                let (status, error) = match &result {
+                    Ok(_) => (MCP_STARTUP_STARTED, None),
+                    Err(e) => (MCP_STARTUP_FAILED, Some(e.clone())),
+                };
+                emit_startup_status(&app_clone, &name_clone, status, error);
+            }
+
             (name_clone, result)
         });
 
         startup_handles.push(handle);
     }
 
-    // Wait for all initial startup attempts to complete
+    // Wait for the initial startup attempts to complete, up to the startup budget
     let mut successful_count = 0;
     let mut failed_count = 0;
 
-    for handle in startup_handles {
-        match handle.await {
+    for mut handle in startup_handles {
+        let joined = match deadline {
+            Some(deadline) => match tokio::time::timeout_at(deadline, &mut handle).await {
+                Ok(joined) => joined,
+                Err(_) => break,
+            },
+            None => handle.await,
+        };
+        match joined {
             Ok((name, result)) => match result {
                 Ok(_) => {
                     log::info!("MCP server {name} initialized successfully");
@@ -152,76 +236,241 @@ pub async fn run_mcp_commands<R: Runtime>(
         }
     }
 
+    // Servers still starting keep doing so in the background and report when they are up
+    let deferred: Vec<String> = {
+        let mut tracker = lock_tracker(&tracker);
+        tracker.deferred = true;
+        tracker.pending.iter().cloned().collect()
+    };
+    for name in &deferred {
+        log::warn!("MCP server {name} did not start within the startup budget, continuing in the background");
+        emit_startup_status(app, name, MCP_STARTUP_DEFERRED, None);
+    }
+
     log::info!(
-        "MCP server initialization complete: {successful_count} successful, {failed_count} failed"
+        "MCP server initialization complete: {successful_count} successful, {failed_count} failed, {} deferred",
+        deferred.len()
     );
 
     Ok(())
 }
 
-/// Monitor MCP server health without removing it from the HashMap
-pub async fn monitor_mcp_server_handle(
+/// Servers of a `run_mcp_commands` call whose initial startup hasn't finished yet
+#[derive(Default)]
+struct StartupTracker {
+    pending: HashSet<String>,
+    /// Set once launch stopped waiting; servers finishing later report their status
+    deferred: bool,
+}
+
+fn lock_tracker(
+    tracker: &std::sync::Mutex<StartupTracker>,
+) -> std::sync::MutexGuard<'_, StartupTracker> {
+    tracker.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn emit_startup_status<R: Runtime>(
+    app: &AppHandle<R>,
+    server: &str,
+    status: &str,
+    error: Option<String>,
+) {
+    let payload = McpStartupStatus {
+        server: server.to_string(),
+        status: status.to_string(),
+        error,
+    };
+    if let Err(e) = app.emit(MCP_STARTUP_EVENT, payload) {
+        log::warn!("Failed to emit MCP startup status for {server}: {e}");
+    }
+}
+
+/// Health-check a server every `interval` by listing its tools, until it fails a check, is
+/// removed or the shutdown flag is set. A failed server is removed from the running services.
+pub async fn monitor_mcp_server_health(
     servers_state: SharedMcpServers,
     name: String,
     shutdown_flag: Arc<Mutex<bool>>,
+    interval: Duration,
+    check_timeout: Duration,
 ) -> Option<rmcp::service::QuitReason> {
-    log::info!("Monitoring MCP server {name} health");
+    let health = HealthCheck {
+        probe: HealthProbe::ListTools,
+        interval,
+        timeout: check_timeout,
+        failure_threshold: 1,
+    };
+    monitor_mcp_server(servers_state, name, shutdown_flag, health).await
+}
+
+/// Health check of a server: the defaults of its transport, with the `healthCheck` entry of
+/// its config applied. Local servers are probed often, remote ones less often and with more
+/// failures tolerated.
+pub fn health_check_config(config: Option<&Value>, transport_type: Option<&str>) -> HealthCheck {
+    let remote = matches!(transport_type, Some("http") | Some("sse"));
+    let mut health = if remote {
+        HealthCheck {
+            probe: HealthProbe::Ping,
+            interval: Duration::from_secs(MCP_REMOTE_HEALTH_CHECK_INTERVAL_SECS),
+            timeout: Duration::from_secs(MCP_REMOTE_HEALTH_CHECK_TIMEOUT_SECS),
+            failure_threshold: MCP_REMOTE_HEALTH_CHECK_FAILURE_THRESHOLD,
+        }
+    } else {
+        HealthCheck {
+            probe: HealthProbe::Ping,
+            interval: Duration::from_secs(MCP_HEALTH_CHECK_INTERVAL_SECS),
+            timeout: Duration::from_secs(MCP_HEALTH_CHECK_TIMEOUT_SECS),
+            failure_threshold: MCP_HEALTH_CHECK_FAILURE_THRESHOLD,
+        }
+    };
+    let Some(config) = config else {
+        return health;
+    };
+    let overrides = match serde_json::from_value::<HealthCheckOverrides>(config.clone()) {
+        Ok(overrides) => overrides,
+        Err(e) => {
+            log::warn!("Ignoring invalid MCP healthCheck config: {e}");
+            return health;
+        }
+    };
+    if let Some(probe) = overrides.probe {
+        health.probe = probe;
+    }
+    // Zero would probe in a busy loop or never let a check succeed
+    if let Some(interval) = overrides.interval_seconds.filter(|secs| *secs > 0) {
+        health.interval = Duration::from_secs(interval);
+    }
+    if let Some(timeout) = overrides.timeout_seconds.filter(|secs| *secs > 0) {
+        health.timeout = Duration::from_secs(timeout);
+    }
+    if let Some(threshold) = overrides.failure_threshold {
+        health.failure_threshold = threshold.max(1);
+    }
+    health
+}
+
+/// Result of probing a running server once
+#[derive(Debug)]
+enum ProbeOutcome {
+    Healthy,
+    /// The server doesn't implement the probe
+    Unsupported,
+    Failed(String),
+}
+
+async fn probe_server(
+    peer: &Peer<RoleClient>,
+    probe: HealthProbe,
+    check_timeout: Duration,
+) -> ProbeOutcome {
+    let result = match probe {
+        HealthProbe::None => return ProbeOutcome::Healthy,
+        HealthProbe::Ping => {
+            let ping: ClientRequest = match serde_json::from_value(serde_json::json!({
+                "method": "ping"
+            })) {
+                Ok(ping) => ping,
+                Err(_) => return ProbeOutcome::Unsupported,
+            };
+            timeout(check_timeout, peer.send_request(ping))
+                .await
+                .map(|result| result.map(drop))
+        }
+        HealthProbe::ListTools => timeout(check_timeout, peer.list_all_tools())
+            .await
+            .map(|result| result.map(drop)),
+    };
+    match result {
+        Ok(Ok(())) => ProbeOutcome::Healthy,
+        Ok(Err(ServiceError::McpError(e))) if e.code == ErrorCode::METHOD_NOT_FOUND => {
+            ProbeOutcome::Unsupported
+        }
+        Ok(Err(e)) => ProbeOutcome::Failed(format!("health check failed: {e}")),
+        Err(_) => ProbeOutcome::Failed("health check timed out".to_string()),
+    }
+}
+
+/// Health-check a server as `health` says until it fails `failure_threshold` checks in a
+/// row, is removed or the shutdown flag is set. A failed server is removed from the running
+/// services and `Closed` returned; `Cancelled` means monitoring was stopped from outside.
+/// Servers without a probe aren't monitored.
+pub async fn monitor_mcp_server(
+    servers_state: SharedMcpServers,
+    name: String,
+    shutdown_flag: Arc<Mutex<bool>>,
+    health: HealthCheck,
+) -> Option<rmcp::service::QuitReason> {
+    if health.probe == HealthProbe::None {
+        log::info!("Health checks of MCP server {name} are off");
+        return None;
+    }
+    log::info!(
+        "Monitoring MCP server {name} health with {:?} every {}s",
+        health.probe,
+        health.interval.as_secs_f32()
+    );
+    let mut probe = health.probe;
+    let mut failures = 0;
 
     // Monitor server health with periodic checks
     loop {
         // Small delay between health checks
-        sleep(Duration::from_secs(5)).await;
+        sleep(health.interval).await;
 
         {
             let shutdown = shutdown_flag.lock().await;
             if *shutdown {
-                return Some(rmcp::service::QuitReason::Closed);
+                return Some(rmcp::service::QuitReason::Cancelled);
             }
         }
 
-        let health_check_result = {
-            let servers = servers_state.lock().await;
-            if let Some(service) = servers.get(&name) {
-                // Try to list tools as a health check with a short timeout
-                match timeout(Duration::from_secs(2), service.list_all_tools()).await {
-                    Ok(Ok(_)) => {
-                        // Server responded successfully
-                        true
-                    }
-                    Ok(Err(e)) => {
-                        log::warn!("MCP server {name} health check failed: {e}");
-                        false
-                    }
-                    Err(_) => {
-                        log::warn!("MCP server {name} health check timed out");
-                        false
-                    }
-                }
-            } else {
-                // Server was removed from HashMap (e.g., by deactivate_mcp_server)
-                log::info!("MCP server {name} no longer in running services");
-                return Some(rmcp::service::QuitReason::Closed);
-            }
+        // Checked without holding the lock, so a slow server doesn't block tool calls
+        let Some(peer) = server_peer(&servers_state, &name).await else {
+            // Server was removed from HashMap (e.g., by deactivate_mcp_server)
+            log::info!("MCP server {name} no longer in running services");
+            return Some(rmcp::service::QuitReason::Cancelled);
         };
+        match probe_server(&peer, probe, health.timeout).await {
+            ProbeOutcome::Healthy => failures = 0,
+            ProbeOutcome::Unsupported => {
+                log::info!("MCP server {name} doesn't answer pings, listing tools instead");
+                probe = HealthProbe::ListTools;
+            }
+            ProbeOutcome::Failed(e) => {
+                failures += 1;
+                log::warn!(
+                    "MCP server {name} {e} ({failures} of {})",
+                    health.failure_threshold
+                );
+                record_health_check_failure(&name);
+                if failures < health.failure_threshold {
+                    record_server_log(&name, log::Level::Warn, format!("Health check: {e}"));
+                    continue;
+                }
 
-        if !health_check_result {
-            // Server failed health check - remove it and return
-            log::error!("MCP server {name} failed health check, removing from active servers");
-            let mut servers = servers_state.lock().await;
-            if let Some(service) = servers.remove(&name) {
-                // Try to cancel the service gracefully
-                match service {
-                    RunningServiceEnum::NoInit(service) => {
-                        log::info!("Stopping server {name}...");
-                        let _ = service.cancel().await;
-                    }
-                    RunningServiceEnum::WithInit(service) => {
-                        log::info!("Stopping server {name} with initialization...");
-                        let _ = service.cancel().await;
+                // Server failed health check - remove it and return
+                log::error!("MCP server {name} failed health check, removing from active servers");
+                record_server_log(
+                    &name,
+                    log::Level::Error,
+                    "Health check failed, server stopped",
+                );
+                let service = servers_state.write().await.remove(&name);
+                if let Some(service) = service {
+                    // Try to cancel the service gracefully
+                    match service {
+                        RunningServiceEnum::NoInit(service) => {
+                            log::info!("Stopping server {name}...");
+                            let _ = service.cancel().await;
+                        }
+                        RunningServiceEnum::WithInit(service) => {
+                            log::info!("Stopping server {name} with initialization...");
+                            let _ = service.cancel().await;
+                        }
                     }
                 }
+                return Some(rmcp::service::QuitReason::Closed);
             }
-            return Some(rmcp::service::QuitReason::Closed);
         }
     }
 }
@@ -242,227 +491,234 @@ pub async fn start_mcp_server<R: Runtime>(
 
     // Try the first start attempt and return its result
     log::info!("Starting MCP server {name} (Initial attempt)");
+    let startup = watch(
+        &app,
+        WatchedKind::McpStartup,
+        name.as_str(),
+        Some(MCP_STARTUP_EXPECTED),
+        None,
+    );
     let first_start_result = schedule_mcp_start_task(
         app.clone(),
         servers_state.clone(),
         name.clone(),
         config.clone(),
+        startup.handle(),
     )
     .await;
+    drop(startup);
 
     match first_start_result {
         Ok(_) => {
             log::info!("MCP server {name} started successfully");
+            record_server_log(&name, log::Level::Info, "Started");
             Ok(())
         }
         Err(e) => {
             log::error!("Failed to start MCP server {name} on first attempt: {e}");
+            record_server_log(&name, log::Level::Error, e.as_str());
+            notify(
+                &app,
+                NotificationCategory::McpServerFailed,
+                &localize(
+                    &app,
+                    &LocalizedMessage::new(MessageCode::NotificationMcpServerFailedTitle),
+                ),
+                &localize(
+                    &app,
+                    &LocalizedMessage::new(MessageCode::NotificationMcpServerFailedBody)
+                        .with("name", &name),
+                ),
+            );
             Err(e)
         }
     }
 }
 
+/// Resolve the template placeholders of a server config, reusing the ports the server got
+/// on its previous start while they are free, and record the ports it ends up with
+async fn resolve_config_templates<R: Runtime>(
+    app: &AppHandle<R>,
+    name: &str,
+    config: &mut McpServerConfig,
+) -> Result<(), String> {
+    let data_dir = get_jan_data_folder_path(app.clone());
+    let app_state = app.state::<AppState>();
+    let mut ports = app_state
+        .mcp_server_ports
+        .lock()
+        .await
+        .get(name)
+        .cloned()
+        .unwrap_or_default();
+    ports.retain(|_, port| jan_utils::network::is_port_available(*port));
+
+    let mut ctx = TemplateContext {
+        data_dir: data_dir.to_string_lossy().into_owned(),
+        thread_workspace: latest_workspace(&data_dir),
+        ports,
+    };
+    resolve_server_config(config, &mut ctx, &mut allocate_free_port)
+        .map_err(|e| format!("Failed to resolve the config of MCP server {name}: {e}"))?;
+
+    let mut recorded = app_state.mcp_server_ports.lock().await;
+    if ctx.ports.is_empty() {
+        recorded.remove(name);
+    } else {
+        log::info!("MCP server {name} uses ports {:?}", ctx.ports);
+        recorded.insert(name.to_string(), ctx.ports);
+    }
+    Ok(())
+}
+
+/// Make sure every bridge port of a server is free before spawning it. A busy port is
+/// first reclaimed from an orphaned MCP process; when that fails the server gets the next
+/// free port in its env instead, and dependent components are told through
+/// `MCP_PORT_CHANGED_EVENT`. The ports the server ends up with are recorded in `AppState`.
+async fn resolve_bridge_ports<R: Runtime>(
+    app: &AppHandle<R>,
+    name: &str,
+    config: &mut McpServerConfig,
+) -> Result<(), String> {
+    let ports = bridge_ports(config);
+    if ports.is_empty() {
+        return Ok(());
+    }
+    let mut taken: HashSet<u16> = ports.iter().map(|(_, port)| *port).collect();
+    let mut actual = Vec::with_capacity(ports.len());
+    for (env, configured_port) in ports {
+        if jan_utils::network::is_port_available(configured_port) {
+            actual.push((env, configured_port));
+            continue;
+        }
+        log::warn!("Port {configured_port} of MCP server {name} occupied, attempting cleanup");
+        match kill_orphaned_mcp_process_with_app(app, configured_port).await {
+            Ok(true) => {
+                log::info!("Cleaned up orphaned process on port {configured_port}");
+                actual.push((env, configured_port));
+                continue;
+            }
+            Ok(false) => {}
+            Err(e) => log::warn!("Failed to clean up port {configured_port}: {e}"),
+        }
+
+        let port = replacement_port(configured_port, &taken).map_err(|e| {
+            format!("Port {configured_port} of MCP server {name} is already in use: {e}")
+        })?;
+        taken.insert(port);
+        log::warn!("MCP server {name} gets port {port} in {env}; {configured_port} is in use");
+        record_server_log(
+            name,
+            log::Level::Warn,
+            &format!("Port {configured_port} in use, using {port} for {env}"),
+        );
+        config
+            .envs
+            .insert(env.clone(), Value::String(port.to_string()));
+        let change = McpPortChange {
+            server: name.to_string(),
+            env: env.clone(),
+            configured_port,
+            port,
+        };
+        if let Err(e) = app.emit(MCP_PORT_CHANGED_EVENT, &change) {
+            log::error!("Failed to emit {MCP_PORT_CHANGED_EVENT} event: {e}");
+        }
+        actual.push((env, port));
+    }
+
+    let app_state = app.state::<AppState>();
+    app_state
+        .mcp_server_ports
+        .lock()
+        .await
+        .entry(name.to_string())
+        .or_default()
+        .extend(actual);
+    Ok(())
+}
+
 async fn schedule_mcp_start_task<R: Runtime>(
     app: tauri::AppHandle<R>,
     servers: SharedMcpServers,
     name: String,
     config: Value,
+    watch: &WatchHandle,
 ) -> Result<(), String> {
     let app_path = get_jan_data_folder_path(app.clone());
-    let exe_path = env::current_exe().expect("Failed to get current exe path");
-    let exe_parent_path = exe_path
-        .parent()
-        .expect("Executable must have a parent directory");
-    let bin_path = exe_parent_path.to_path_buf();
-
-    let config_params = extract_command_args(&config)
-        .ok_or_else(|| format!("Failed to extract command args from config for {name}"))?;
-
-    if config_params.transport_type.as_deref() == Some("http") && config_params.url.is_some() {
-        let transport = StreamableHttpClientTransport::with_client(
-            reqwest::Client::builder()
-                .default_headers({
-                    // Map envs to request headers
-                    let mut headers: tauri::http::HeaderMap = reqwest::header::HeaderMap::new();
-                    for (key, value) in config_params.headers.iter() {
-                        if let Some(v_str) = value.as_str() {
-                            // Try to map env keys to HTTP header names (case-insensitive)
-                            // Most HTTP headers are Title-Case, so we try to convert
-                            let header_name =
-                                reqwest::header::HeaderName::from_bytes(key.as_bytes());
-                            if let Ok(header_name) = header_name {
-                                if let Ok(header_value) =
-                                    reqwest::header::HeaderValue::from_str(v_str)
-                                {
-                                    headers.insert(header_name, header_value);
-                                }
-                            }
-                        }
-                    }
-                    headers
-                })
-                .connect_timeout(config_params.timeout.unwrap_or(Duration::MAX))
-                .build()
-                .unwrap(),
-            StreamableHttpClientTransportConfig {
-                uri: config_params.url.unwrap().into(),
-                ..Default::default()
-            },
-        );
-
-        let client_info = ClientInfo {
-            protocol_version: Default::default(),
-            capabilities: ClientCapabilities::default(),
-            client_info: Implementation {
-                name: "Jan Streamable Client".to_string(),
-                version: "0.0.1".to_string(),
-                title: None,
-                website_url: None,
-                icons: None,
-            },
-        };
-        let client = client_info.serve(transport).await.inspect_err(|e| {
-            log::error!("client error: {e:?}");
-        });
 
-        match client {
-            Ok(client) => {
-                log::info!("Connected to server: {:?}", client.peer_info());
-                servers
-                    .lock()
-                    .await
-                    .insert(name.clone(), RunningServiceEnum::WithInit(client));
+    watch.stage("resolving config");
 
-                emit_mcp_update_event(&app, &name);
-            }
-            Err(e) => {
-                log::error!("Failed to connect to server: {e}");
-                return Err(format!("Failed to connect to server: {e}"));
+    let mut config_params = extract_command_args(&config)
+        .ok_or_else(|| format!("Failed to extract command args from config for {name}"))?;
+    resolve_config_templates(&app, &name, &mut config_params).await?;
+    let health = config_params.health_check;
+    if let Some(session) = &config_params.replay {
+        return start_replay_server(&app, &servers, &name, session).await;
+    }
+    check_mcp_command_allowed(admin_policy(), &config_params.command)?;
+    if let Some(version) = &config_params.version {
+        match PackageRegistry::for_command(&config_params.command) {
+            Some(registry) if pin_package_args(registry, &mut config_params.args, version) => {
+                log::info!("MCP server {name} pinned to version {version}");
             }
+            _ => log::warn!("Ignoring version {version} of MCP server {name}: no npx/uvx package"),
         }
-    } else if config_params.transport_type.as_deref() == Some("sse") && config_params.url.is_some()
-    {
-        let transport = SseClientTransport::start_with_client(
-            reqwest::Client::builder()
-                .default_headers({
-                    // Map envs to request headers
-                    let mut headers = reqwest::header::HeaderMap::new();
-                    for (key, value) in config_params.headers.iter() {
-                        if let Some(v_str) = value.as_str() {
-                            // Try to map env keys to HTTP header names (case-insensitive)
-                            // Most HTTP headers are Title-Case, so we try to convert
-                            let header_name =
-                                reqwest::header::HeaderName::from_bytes(key.as_bytes());
-                            if let Ok(header_name) = header_name {
-                                if let Ok(header_value) =
-                                    reqwest::header::HeaderValue::from_str(v_str)
-                                {
-                                    headers.insert(header_name, header_value);
-                                }
-                            }
-                        }
-                    }
-                    headers
-                })
-                .connect_timeout(config_params.timeout.unwrap_or(Duration::MAX))
-                .build()
-                .unwrap(),
-            rmcp::transport::sse_client::SseClientConfig {
-                sse_endpoint: config_params.url.unwrap().into(),
-                ..Default::default()
-            },
-        )
-        .await
-        .map_err(|e| {
-            log::error!("transport error: {e:?}");
-            format!("Failed to start SSE transport: {e}")
-        })?;
+    }
+    if matches!(
+        config_params.transport_type.as_deref(),
+        Some("http" | "sse")
+    ) {
+        if let Some(url) = &config_params.url {
+            check_url(&app, url)?;
+        }
+    }
 
-        let client_info = ClientInfo {
-            protocol_version: Default::default(),
-            capabilities: ClientCapabilities::default(),
-            client_info: Implementation {
-                name: "Jan SSE Client".to_string(),
-                version: "0.0.1".to_string(),
-                title: None,
-                website_url: None,
-                icons: None,
-            },
-        };
-        let client = client_info.serve(transport).await.map_err(|e| {
-            log::error!("client error: {e:?}");
-            e.to_string()
+    if is_remote_transport(&config_params) {
+        watch.stage(match config_params.transport_type.as_deref() {
+            Some("sse") => "connecting over SSE",
+            _ => "connecting over streamable HTTP",
         });
-
-        match client {
-            Ok(client) => {
-                log::info!("Connected to server: {:?}", client.peer_info());
-                servers
-                    .lock()
-                    .await
-                    .insert(name.clone(), RunningServiceEnum::WithInit(client));
-
-                emit_mcp_update_event(&app, &name);
-            }
-            Err(e) => {
-                log::error!("Failed to connect to server: {e}");
-                return Err(format!("Failed to connect to server: {e}"));
-            }
-        }
+        let client = connect_remote_server(&app_path, &name, &config_params).await?;
+        servers.write().await.insert(name.clone(), client);
+        emit_mcp_update_event(&app, &name);
+    } else if is_socket_transport(&config_params) && config_params.command.is_empty() {
+        // Connect to a server that is already listening
+        watch.stage("connecting to socket");
+        let socket = config_params.socket.clone().unwrap_or_default();
+        start_socket_server(&app, &servers, &name, &socket, None, health).await?;
     } else {
-        if name == "Jan Browser MCP" {
-            if let Some(port_str) = config_params.envs.get("BRIDGE_PORT") {
-                if let Some(port_str) = port_str.as_str() {
-                    if let Ok(port) = port_str.parse::<u16>() {
-                        if !jan_utils::network::is_port_available(port) {
-                            log::warn!("Port {} occupied, attempting cleanup", port);
-                            match kill_orphaned_mcp_process_with_app(&app, port).await {
-                                Ok(true) => {
-                                    log::info!("Cleaned up orphaned process on port {}", port);
-                                }
-                                Ok(false) => {
-                                    return Err(format!(
-                                        "Port {} is already in use. Please close the application using this port or restart Jan.",
-                                        port
-                                    ));
-                                }
-                                Err(e) => return Err(e),
-                            }
-                        }
-                    }
-                }
-            }
-        }
+        watch.stage("preparing command");
+        resolve_bridge_ports(&app, &name, &mut config_params).await?;
 
         let mut cmd = Command::new(config_params.command.clone());
-        let bun_x_path = if cfg!(windows) {
-            bin_path.join("bun.exe")
-        } else {
-            bin_path.join("bun")
-        };
-        if config_params.command.clone() == "npx"
-            && can_override_npx(bun_x_path.display().to_string())
-        {
-            let mut cache_dir = app_path.clone();
-            cache_dir.push(".npx");
-            cmd = Command::new(bun_x_path.display().to_string());
-            cmd.arg("x");
-            cmd.env("BUN_INSTALL", cache_dir.to_str().unwrap());
+        if config_params.command == "npx" {
+            if let Some(bun_path) = ensure_runtime(&app, RuntimeKind::Bun)
+                .await
+                .filter(|path| can_override_npx(path.display().to_string()))
+            {
+                cmd = Command::new(bun_path);
+                cmd.arg("x");
+                cmd.env(
+                    "BUN_INSTALL",
+                    runtime_cache_dir(&app_path, RuntimeKind::Bun),
+                );
+            }
         }
 
-        let uv_path = if cfg!(windows) {
-            bin_path.join("uv.exe")
-        } else {
-            bin_path.join("uv")
-        };
-        if config_params.command.clone() == "uvx" && can_override_uvx(uv_path.display().to_string())
-        {
-            let mut cache_dir = app_path.clone();
-            cache_dir.push(".uvx");
-            cmd = Command::new(uv_path);
-            cmd.arg("tool");
-            cmd.arg("run");
-            cmd.env("UV_CACHE_DIR", cache_dir.to_str().unwrap());
+        if config_params.command == "uvx" {
+            if let Some(uv_path) = ensure_runtime(&app, RuntimeKind::Uv)
+                .await
+                .filter(|path| can_override_uvx(path.display().to_string()))
+            {
+                cmd = Command::new(uv_path);
+                cmd.arg("tool");
+                cmd.arg("run");
+                cmd.env(
+                    "UV_CACHE_DIR",
+                    runtime_cache_dir(&app_path, RuntimeKind::Uv),
+                );
+            }
         }
         #[cfg(windows)]
         {
@@ -484,6 +740,12 @@ async fn schedule_mcp_start_task<R: Runtime>(
             }
         });
 
+        if is_socket_transport(&config_params) {
+            let socket = config_params.socket.clone().unwrap_or_default();
+            return start_socket_server(&app, &servers, &name, &socket, Some(cmd), health).await;
+        }
+
+        watch.stage(format!("spawning {}", config_params.command));
         let (process, stderr) = TokioChildProcess::builder(cmd)
             .stderr(Stdio::piped())
             .spawn()
@@ -500,8 +762,9 @@ async fn schedule_mcp_start_task<R: Runtime>(
             pids.insert(name.clone(), pid);
         }
 
-        let service = ()
-            .serve(process)
+        watch.stage("initializing");
+        let service = JanClient::new(&app_path, ClientInfo::default())
+            .serve(inspected(&name, process))
             .await
             .map_err(|e| format!("Failed to start MCP server {name}: {e}"));
 
@@ -509,15 +772,19 @@ async fn schedule_mcp_start_task<R: Runtime>(
             Ok(server) => {
                 log::trace!("Connected to server: {:#?}", server.peer_info());
                 servers
-                    .lock()
+                    .write()
                     .await
                     .insert(name.clone(), RunningServiceEnum::NoInit(server));
                 log::info!("Server {name} started successfully.");
+                if let Some(stderr) = stderr {
+                    forward_server_output(&app, &name, stderr);
+                }
             }
             Err(_) => {
                 let mut buffer = String::new();
                 let error = match stderr
                     .expect("stderr must be piped")
+                    .take(MAX_MCP_STARTUP_ERROR_BYTES)
                     .read_to_string(&mut buffer)
                     .await
                 {
@@ -531,12 +798,13 @@ async fn schedule_mcp_start_task<R: Runtime>(
 
         // Wait a short time to verify the server is stable before marking as connected
         // This prevents race conditions where the server quits immediately
+        watch.stage("verifying");
         let verification_delay = Duration::from_millis(500);
         sleep(verification_delay).await;
 
         // Check if server is still running after the verification delay
         let server_still_running = {
-            let servers_map = servers.lock().await;
+            let servers_map = servers.read().await;
             servers_map.contains_key(&name)
         };
 
@@ -544,26 +812,370 @@ async fn schedule_mcp_start_task<R: Runtime>(
             return Err(format!("MCP server {name} quit immediately after starting"));
         }
 
-        // Create lock file for Jan Browser MCP
-        if name == "Jan Browser MCP" {
-            if let Some(port_str) = config_params.envs.get("BRIDGE_PORT") {
-                if let Some(port_str) = port_str.as_str() {
-                    if let Ok(port) = port_str.parse::<u16>() {
-                        use crate::core::mcp::lockfile::create_lock_file;
-                        if let Err(e) = create_lock_file(&app, port, &name) {
-                            log::warn!("Failed to create lock file for port {}: {}", port, e);
+        // Create lock files for the bridge ports, so an orphaned process holding one can be
+        // recognized and cleaned up on the next start
+        for (_, port) in bridge_ports(&config_params) {
+            use crate::core::mcp::lockfile::create_lock_file;
+            if let Err(e) = create_lock_file(&app, port, &name) {
+                log::warn!("Failed to create lock file for port {}: {}", port, e);
+            }
+        }
+
+        emit_mcp_update_event(&app, &name);
+    }
+    // Socket servers are monitored by `start_socket_server`, which also cleans up after them
+    if !is_socket_transport(&config_params) {
+        let remote = is_remote_transport(&config_params);
+        spawn_health_monitor(&app, &servers, &name, health, remote).await;
+    }
+    Ok(())
+}
+
+/// Health-check a started server in the background, telling the UI when it is dropped. A
+/// remote server failing its checks has most likely lost its connection and is reconnected
+/// first. A monitor left over from an earlier start of the server is stopped.
+async fn spawn_health_monitor<R: Runtime>(
+    app: &AppHandle<R>,
+    servers: &SharedMcpServers,
+    name: &str,
+    health: HealthCheck,
+    remote: bool,
+) {
+    if health.probe == HealthProbe::None {
+        return;
+    }
+    let monitor = {
+        let app = app.clone();
+        let servers = servers.clone();
+        let name = name.to_string();
+        tokio::spawn(async move {
+            loop {
+                let shutdown_flag = Arc::new(Mutex::new(false));
+                let quit =
+                    monitor_mcp_server(servers.clone(), name.clone(), shutdown_flag, health).await;
+                if remote && matches!(quit, Some(rmcp::service::QuitReason::Closed)) {
+                    let config = app
+                        .state::<AppState>()
+                        .mcp_active_servers
+                        .lock()
+                        .await
+                        .get(&name)
+                        .cloned();
+                    if let Some(config) = config {
+                        if reconnect_mcp_server(&app, &servers, &name, &config)
+                            .await
+                            .is_ok()
+                        {
+                            continue;
                         }
                     }
                 }
+                if quit.is_some() {
+                    emit_mcp_update_event(&app, &name);
+                }
+                break;
+            }
+        })
+    };
+    let state = app.state::<AppState>();
+    let previous = state
+        .mcp_monitoring_tasks
+        .lock()
+        .await
+        .insert(name.to_string(), monitor);
+    if let Some(previous) = previous {
+        previous.abort();
+    }
+}
+
+fn is_remote_transport(config: &McpServerConfig) -> bool {
+    matches!(config.transport_type.as_deref(), Some("http" | "sse")) && config.url.is_some()
+}
+
+/// HTTP client of a remote server, sending the headers of its config
+fn remote_http_client(config: &McpServerConfig) -> Result<reqwest::Client, String> {
+    let mut headers = reqwest::header::HeaderMap::new();
+    for (key, value) in config.headers.iter() {
+        if let Some(v_str) = value.as_str() {
+            // Try to map env keys to HTTP header names (case-insensitive)
+            // Most HTTP headers are Title-Case, so we try to convert
+            let header_name = reqwest::header::HeaderName::from_bytes(key.as_bytes());
+            if let Ok(header_name) = header_name {
+                if let Ok(header_value) = reqwest::header::HeaderValue::from_str(v_str) {
+                    headers.insert(header_name, header_value);
+                }
             }
         }
+    }
+    reqwest::Client::builder()
+        .default_headers(headers)
+        .connect_timeout(config.timeout.unwrap_or(Duration::MAX))
+        .build()
+        .map_err(|e| format!("Failed to build HTTP client: {e}"))
+}
 
-        emit_mcp_update_event(&app, &name);
+/// Open a session with a server over streamable HTTP or SSE
+async fn connect_remote_server(
+    data_folder: &Path,
+    name: &str,
+    config: &McpServerConfig,
+) -> Result<RunningServiceEnum, String> {
+    let url = config
+        .url
+        .clone()
+        .ok_or_else(|| format!("MCP server {name} has no URL"))?;
+    let client = remote_http_client(config)?;
+    let sse = config.transport_type.as_deref() == Some("sse");
+    let client_info = ClientInfo {
+        protocol_version: Default::default(),
+        capabilities: ClientCapabilities::default(),
+        client_info: Implementation {
+            name: if sse {
+                "Jan SSE Client"
+            } else {
+                "Jan Streamable Client"
+            }
+            .to_string(),
+            version: "0.0.1".to_string(),
+            title: None,
+            website_url: None,
+            icons: None,
+        },
+    };
+    let client = if sse {
+        let transport = SseClientTransport::start_with_client(
+            client,
+            rmcp::transport::sse_client::SseClientConfig {
+                sse_endpoint: url.into(),
+                ..Default::default()
+            },
+        )
+        .await
+        .map_err(|e| {
+            log::error!("transport error: {e:?}");
+            format!("Failed to start SSE transport: {e}")
+        })?;
+        JanClient::new(data_folder, client_info)
+            .serve(inspected(name, transport))
+            .await
+            .map_err(|e| {
+                log::error!("client error: {e:?}");
+                format!("Failed to connect to server: {e}")
+            })?
+    } else {
+        let transport = StreamableHttpClientTransport::with_client(
+            client,
+            StreamableHttpClientTransportConfig {
+                uri: url.into(),
+                ..Default::default()
+            },
+        );
+        JanClient::new(data_folder, client_info)
+            .serve(inspected(name, transport))
+            .await
+            .map_err(|e| {
+                log::error!("client error: {e:?}");
+                format!("Failed to connect to server: {e}")
+            })?
+    };
+    log::info!("Connected to server: {:?}", client.peer_info());
+    Ok(RunningServiceEnum::WithInit(client))
+}
+
+/// Delay before reconnect attempt `retry` (0-based) of a remote server
+pub fn reconnect_delay(retry: u32) -> Duration {
+    let delay = MCP_RECONNECT_BASE_DELAY_MS.saturating_mul(1 << retry.min(16));
+    Duration::from_millis(delay.min(MCP_RECONNECT_MAX_DELAY_MS))
+}
+
+/// Whether `config` is a server reached over HTTP or SSE, which `reconnect_mcp_server` can
+/// reconnect
+pub fn is_remote_server_config(config: &Value) -> bool {
+    extract_command_args(config).is_some_and(|params| is_remote_transport(&params))
+}
+
+/// Reconnect a remote server whose connection dropped or went stale. Unlike a restart, only
+/// the transport and session are rebuilt, retrying quickly a few times: the server keeps its
+/// health monitor and cached tools, a running session is only replaced once the new one is
+/// up, and no start or failure notification is recorded unless every attempt fails.
+pub async fn reconnect_mcp_server<R: Runtime>(
+    app: &AppHandle<R>,
+    servers: &SharedMcpServers,
+    name: &str,
+    config: &Value,
+) -> Result<(), String> {
+    let mut params = extract_command_args(config)
+        .ok_or_else(|| format!("Failed to extract command args from config for {name}"))?;
+    if !is_remote_transport(&params) {
+        return Err(format!("MCP server {name} isn't reached over HTTP or SSE"));
+    }
+    resolve_config_templates(app, name, &mut params).await?;
+    if let Some(url) = &params.url {
+        check_url(app, url)?;
+    }
+
+    let state = app.state::<AppState>();
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let mut last_error = String::new();
+    for attempt in 0..MCP_RECONNECT_ATTEMPTS {
+        if attempt > 0 {
+            sleep(reconnect_delay(attempt - 1)).await;
+        }
+        if *state.mcp_shutdown_in_progress.lock().await {
+            return Err("MCP servers are shutting down".to_string());
+        }
+        if !state.mcp_active_servers.lock().await.contains_key(name) {
+            return Err(format!("MCP server {name} was deactivated"));
+        }
+        match connect_remote_server(&data_folder, name, &params).await {
+            Ok(service) => {
+                let previous = servers.write().await.insert(name.to_string(), service);
+                if let Some(previous) = previous {
+                    tauri::async_runtime::spawn(async move {
+                        let _ = match previous {
+                            RunningServiceEnum::NoInit(service) => service.cancel().await.map(drop),
+                            RunningServiceEnum::WithInit(service) => {
+                                service.cancel().await.map(drop)
+                            }
+                        };
+                    });
+                }
+                log::info!("Reconnected MCP server {name}");
+                record_reconnect(name);
+                record_server_log(name, log::Level::Info, "Reconnected");
+                emit_mcp_update_event(app, name);
+                return Ok(());
+            }
+            Err(e) => {
+                log::warn!(
+                    "Reconnecting MCP server {name} failed (attempt {} of {MCP_RECONNECT_ATTEMPTS}): {e}",
+                    attempt + 1
+                );
+                last_error = e;
+            }
+        }
     }
+    record_server_log(
+        name,
+        log::Level::Error,
+        format!("Reconnect failed: {last_error}"),
+    );
+    Err(last_error)
+}
+
+fn is_socket_transport(config: &McpServerConfig) -> bool {
+    config
+        .transport_type
+        .as_deref()
+        .is_some_and(|t| SOCKET_TRANSPORT_TYPES.contains(&t))
+}
+
+/// Serve a recorded session in place of the server, without spawning anything
+async fn start_replay_server<R: Runtime>(
+    app: &AppHandle<R>,
+    servers: &SharedMcpServers,
+    name: &str,
+    session: &str,
+) -> Result<(), String> {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let path = recording_path(&data_folder, session);
+    let transport = ReplayTransport::open(name, &path)?;
+    let service = JanClient::new(&data_folder, ClientInfo::default())
+        .serve(inspected(name, transport))
+        .await
+        .map_err(|e| format!("Failed to replay MCP server {name}: {e}"))?;
+    log::info!("Replaying MCP server {name} from {}", path.display());
+    record_server_log(
+        name,
+        log::Level::Info,
+        format!("Replaying {}", path.display()),
+    );
+    servers
+        .write()
+        .await
+        .insert(name.to_string(), RunningServiceEnum::NoInit(service));
+    emit_mcp_update_event(app, name);
     Ok(())
 }
 
-fn emit_mcp_update_event<R: Runtime>(app: &AppHandle<R>, name: &str) {
+/// Start an MCP server reachable over a Unix domain socket or named pipe. With a `cmd` the
+/// server is spawned first (after removing a stale socket it may have left behind) and told
+/// where to listen through `MCP_SOCKET_PATH`; otherwise Jan connects to a running server.
+/// The connection is then health-checked like other servers, and a spawned process is
+/// stopped once the server is removed.
+async fn start_socket_server<R: Runtime>(
+    app: &AppHandle<R>,
+    servers: &SharedMcpServers,
+    name: &str,
+    socket: &str,
+    cmd: Option<Command>,
+    health: HealthCheck,
+) -> Result<(), String> {
+    validate_socket_path(socket).map_err(|e| format!("MCP server {name}: {e}"))?;
+
+    let mut child = None;
+    let stream = match cmd {
+        Some(mut cmd) => {
+            remove_stale_socket(socket).await;
+            cmd.env(SOCKET_PATH_ENV, socket)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::piped())
+                .kill_on_drop(true);
+            let mut spawned = cmd.spawn().map_err(|e| {
+                log::error!("Failed to run command {name}: {e}");
+                format!("Failed to run command {name}: {e}")
+            })?;
+            if let Some(stderr) = spawned.stderr.take() {
+                forward_server_output(app, name, stderr);
+            }
+            if let Some(pid) = spawned.id() {
+                log::info!("MCP server {name} spawned with PID {pid}");
+                let app_state = app.state::<AppState>();
+                app_state
+                    .mcp_server_pids
+                    .lock()
+                    .await
+                    .insert(name.to_string(), pid);
+            }
+            child = Some(spawned);
+            wait_for_socket(socket, Duration::from_secs(SOCKET_WAIT_TIMEOUT_SECS)).await
+        }
+        None => connect_socket(socket).await,
+    }
+    .map_err(|e| format!("Failed to connect to MCP server {name} at {socket}: {e}"))?;
+
+    let client = JanClient::new(
+        &get_jan_data_folder_path(app.clone()),
+        ClientInfo::default(),
+    );
+    let service = client
+        .serve(inspected(name, stream))
+        .await
+        .map_err(|e| format!("Failed to start MCP server {name}: {e}"))?;
+    log::info!("Connected to MCP server {name} over {socket}");
+    servers
+        .write()
+        .await
+        .insert(name.to_string(), RunningServiceEnum::NoInit(service));
+    emit_mcp_update_event(app, name);
+
+    let app = app.clone();
+    let servers = servers.clone();
+    let name = name.to_string();
+    let socket = socket.to_string();
+    tauri::async_runtime::spawn(async move {
+        monitor_mcp_server(servers, name.clone(), Arc::new(Mutex::new(false)), health).await;
+        if let Some(mut child) = child {
+            let _ = child.kill().await;
+            remove_stale_socket(&socket).await;
+        }
+        emit_mcp_update_event(&app, &name);
+    });
+    Ok(())
+}
+
+pub fn emit_mcp_update_event<R: Runtime>(app: &AppHandle<R>, name: &str) {
     if let Err(e) = app.emit(
         "mcp-update",
         serde_json::json!({
@@ -579,6 +1191,7 @@ pub fn extract_command_args(config: &Value) -> Option<McpServerConfig> {
     let command = obj.get("command")?.as_str()?.to_string();
     let args = obj.get("args")?.as_array()?.clone();
     let url = obj.get("url").and_then(|u| u.as_str()).map(String::from);
+    let socket = obj.get("socket").and_then(|s| s.as_str()).map(String::from);
     let transport_type = obj.get("type").and_then(|t| t.as_str()).map(String::from);
     let timeout = obj
         .get("timeout")
@@ -594,14 +1207,40 @@ pub fn extract_command_args(config: &Value) -> Option<McpServerConfig> {
         .unwrap_or(&Value::Object(serde_json::Map::new()))
         .as_object()?
         .clone();
+    let bridge_ports = obj
+        .get("bridgePorts")
+        .and_then(|p| p.as_array())
+        .map(|names| {
+            names
+                .iter()
+                .filter_map(|n| n.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+    let version = obj
+        .get("version")
+        .and_then(|v| v.as_str())
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+    let replay = obj
+        .get("replay")
+        .and_then(|r| r.as_str())
+        .map(|r| r.trim().to_string())
+        .filter(|r| !r.is_empty());
+    let health_check = health_check_config(obj.get("healthCheck"), transport_type.as_deref());
     Some(McpServerConfig {
         timeout,
         transport_type,
         url,
+        socket,
         command,
         args,
         envs,
         headers,
+        bridge_ports,
+        version,
+        replay,
+        health_check,
     })
 }
 
@@ -626,6 +1265,8 @@ pub async fn restart_active_mcp_servers<R: Runtime>(
 
     for (name, config) in active_servers.iter() {
         log::info!("Restarting MCP server: {name}");
+        record_restart(name);
+        record_server_log(name, log::Level::Info, "Restarting");
 
         // Start server with restart monitoring - spawn async task
         let app_clone = app.clone();
@@ -836,6 +1477,14 @@ pub async fn stop_mcp_servers_with_context<R: Runtime>(
     let _guard = ShutdownGuard {
         flag: state.mcp_shutdown_in_progress.clone(),
     };
+    let shutdown = watch(
+        app,
+        WatchedKind::McpShutdown,
+        format!("{context:?}"),
+        Some(context.overall_timeout() + MCP_SHUTDOWN_GRACE),
+        None,
+    );
+    shutdown.handle().stage("stopping health monitors");
 
     {
         let mut monitoring_tasks = state.mcp_monitoring_tasks.lock().await;
@@ -850,27 +1499,20 @@ pub async fn stop_mcp_servers_with_context<R: Runtime>(
         let pids = state.mcp_server_pids.lock().await;
         pids.clone()
     };
-    let servers_to_stop: Vec<(String, RunningServiceEnum, Option<u16>)> = {
-        let mut servers_map = state.mcp_servers.lock().await;
+    let servers_to_stop: Vec<(String, RunningServiceEnum, Vec<u16>)> = {
+        let mut servers_map = state.mcp_servers.write().await;
+        let server_ports = state.mcp_server_ports.lock().await;
         let keys: Vec<String> = servers_map.keys().cloned().collect();
 
         let mut result = Vec::new();
         for key in keys {
             if let Some(service) = servers_map.remove(&key) {
-                let port = if key == "Jan Browser MCP" {
-                    let active_servers = state.mcp_active_servers.lock().await;
-                    active_servers.get(&key).and_then(|config| {
-                        config
-                            .get("env")
-                            .and_then(|e| e.get("BRIDGE_PORT"))
-                            .and_then(|p| p.as_str())
-                            .and_then(|s| s.parse::<u16>().ok())
-                    })
-                } else {
-                    None
-                };
+                let ports = server_ports
+                    .get(&key)
+                    .map(|ports| ports.values().copied().collect())
+                    .unwrap_or_default();
 
-                result.push((key, service, port));
+                result.push((key, service, ports));
             }
         }
         result
@@ -884,10 +1526,13 @@ pub async fn stop_mcp_servers_with_context<R: Runtime>(
         .iter()
         .map(|(name, _, _)| name.clone())
         .collect();
+    shutdown
+        .handle()
+        .stage(format!("stopping {}", server_names.join(", ")));
     let per_server_timeout = context.per_server_timeout();
     let stop_handles: Vec<_> = servers_to_stop
         .into_iter()
-        .map(|(name, service, port)| {
+        .map(|(name, service, ports)| {
             let app_clone = app.clone();
 
             tauri::async_runtime::spawn(async move {
@@ -903,12 +1548,12 @@ pub async fn stop_mcp_servers_with_context<R: Runtime>(
                     .map(|r| r.is_ok())
                     .unwrap_or(false);
 
-                if name == "Jan Browser MCP" {
-                    if let Some(port) = port {
-                        use crate::core::mcp::lockfile::delete_lock_file;
-                        if success {
-                            tokio::time::sleep(Duration::from_millis(100)).await;
-                        }
+                if !ports.is_empty() {
+                    use crate::core::mcp::lockfile::delete_lock_file;
+                    if success {
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                    }
+                    for port in ports {
                         let _ = delete_lock_file(&app_clone, port);
                     }
                 }
@@ -944,6 +1589,11 @@ pub async fn stop_mcp_servers_with_context<R: Runtime>(
     };
 
     // Force-kill processes that didn't stop gracefully
+    if !failed_servers.is_empty() {
+        shutdown
+            .handle()
+            .stage(format!("force-killing {}", failed_servers.join(", ")));
+    }
     for server_name in &failed_servers {
         if let Some(&pid) = pids_snapshot.get(server_name) {
             log::warn!("Force-killing MCP server {} (PID {})", server_name, pid);
@@ -977,16 +1627,16 @@ pub async fn store_active_server_config(
 }
 
 // Add a new server configuration to the MCP config file
-pub fn add_server_config<R: Runtime>(
+pub async fn add_server_config<R: Runtime>(
     app_handle: tauri::AppHandle<R>,
     server_key: String,
     server_value: Value,
 ) -> Result<(), String> {
-    add_server_config_with_path(app_handle, server_key, server_value, None)
+    add_server_config_with_path(app_handle, server_key, server_value, None).await
 }
 
 // Add a new server configuration to the MCP config file with custom path support
-pub fn add_server_config_with_path<R: Runtime>(
+pub async fn add_server_config_with_path<R: Runtime>(
     app_handle: tauri::AppHandle<R>,
     server_key: String,
     server_value: Value,
@@ -995,27 +1645,199 @@ pub fn add_server_config_with_path<R: Runtime>(
     let config_filename = config_filename.unwrap_or("mcp_config.json");
     let config_path = get_jan_data_folder_path(app_handle).join(config_filename);
 
-    let mut config: Value = serde_json::from_str(
-        &std::fs::read_to_string(&config_path)
-            .map_err(|e| format!("Failed to read config file: {e}"))?,
+    update_config_async(&config_path, move |config| {
+        config
+            .as_object_mut()
+            .ok_or("Config root is not an object")?
+            .entry("mcpServers")
+            .or_insert_with(|| Value::Object(serde_json::Map::new()))
+            .as_object_mut()
+            .ok_or("mcpServers is not an object")?
+            .insert(server_key, server_value);
+        Ok(())
+    })
+    .await
+}
+
+/// Lists the tools of one server, tagged with its name; `None` when it fails or times out.
+async fn list_server_tools(
+    server_name: &str,
+    peer: &Peer<RoleClient>,
+    timeout_duration: Duration,
+) -> Option<Vec<ToolWithServer>> {
+    // List tools with timeout
+    let tools = match timeout(timeout_duration, peer.list_all_tools()).await {
+        Ok(Ok(tools)) => tools,
+        Ok(Err(e)) => {
+            log::warn!("MCP server {} failed to list tools: {}", server_name, e);
+            return None;
+        }
+        Err(_) => {
+            log::warn!(
+                "Listing tools timed out after {} seconds",
+                timeout_duration.as_secs()
+            );
+            return None;
+        }
+    };
+    Some(
+        tools
+            .into_iter()
+            .map(|tool| ToolWithServer {
+                name: tool.name.to_string(),
+                description: tool.description.as_ref().map(|d| d.to_string()),
+                input_schema: serde_json::Value::Object((*tool.input_schema).clone()),
+                server: server_name.to_string(),
+            })
+            .collect(),
     )
-    .map_err(|e| format!("Failed to parse config: {e}"))?;
+}
 
-    config
-        .as_object_mut()
-        .ok_or("Config root is not an object")?
-        .entry("mcpServers")
-        .or_insert_with(|| Value::Object(serde_json::Map::new()))
-        .as_object_mut()
-        .ok_or("mcpServers is not an object")?
-        .insert(server_key, server_value);
-
-    std::fs::write(
-        &config_path,
-        serde_json::to_string_pretty(&config)
-            .map_err(|e| format!("Failed to serialize config: {e}"))?,
+/// List the tools of the running servers permitted by `scope`, keeping the tool cache in
+/// sync: servers that answer replace their cache entry, and servers that fail or time out
+/// contribute their cached tools instead. Also returns whether any cache entry changed.
+async fn collect_tools_and_cache<R: Runtime>(
+    app: &AppHandle<R>,
+    timeout_duration: Duration,
+    scope: Option<&ToolScope>,
+) -> (Vec<ToolWithServer>, bool) {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let state = app.state::<AppState>();
+    let configs = state.mcp_active_servers.lock().await.clone();
+    // Listed outside the lock, so a slow server doesn't block tool calls to the others
+    let peers = server_peers(&state.mcp_servers).await;
+    let mut all_tools: Vec<ToolWithServer> = Vec::new();
+    let mut changed = false;
+
+    for (server_name, peer) in peers.iter() {
+        if let Some(scope) = scope {
+            if !scope.permits_server(server_name) {
+                continue;
+            }
+        }
+        let hash = configs.get(server_name).map(config_hash);
+        let tools = match list_server_tools(server_name, peer, timeout_duration).await {
+            Some(tools) => {
+                if let Some(hash) = &hash {
+                    changed |= remember_server_tools(&data_folder, server_name, hash, &tools);
+                }
+                tools
+            }
+            None => match hash.and_then(|h| cached_server_tools(&data_folder, server_name, &h)) {
+                Some(cached) => {
+                    log::info!("Using the cached tools of MCP server {server_name}");
+                    cached
+                }
+                None => continue, // Skip this server and continue with others
+            },
+        };
+
+        all_tools.extend(
+            tools
+                .into_iter()
+                .filter(|tool| !scope.is_some_and(|scope| !scope.permits(server_name, &tool.name))),
+        );
+    }
+
+    (all_tools, changed)
+}
+
+/// Collects the tools of all connected servers, tagged with their server name.
+/// Servers that fail or time out while listing contribute their cached tools, if any, and
+/// are skipped otherwise. When a tool scope is given, only the servers and tools it permits
+/// are returned.
+pub async fn collect_tools<R: Runtime>(
+    app: &AppHandle<R>,
+    timeout_duration: Duration,
+    scope: Option<&ToolScope>,
+) -> Vec<ToolWithServer> {
+    collect_tools_and_cache(app, timeout_duration, scope)
+        .await
+        .0
+}
+
+/// List the tools of every running server to reconcile the tool cache with them. Returns
+/// whether any server's tools differ from what was cached.
+pub async fn refresh_tool_cache<R: Runtime>(
+    app: &AppHandle<R>,
+    timeout_duration: Duration,
+) -> bool {
+    collect_tools_and_cache(app, timeout_duration, None).await.1
+}
+
+/// Handle of a running server. The lock is only held for the lookup, so requests sent
+/// through the handle don't block other users of the running servers.
+pub async fn server_peer(servers: &SharedMcpServers, name: &str) -> Option<Peer<RoleClient>> {
+    servers.read().await.get(name).map(RunningServiceEnum::peer)
+}
+
+/// Handles of all running servers, by server name
+pub async fn server_peers(servers: &SharedMcpServers) -> Vec<(String, Peer<RoleClient>)> {
+    servers
+        .read()
+        .await
+        .iter()
+        .map(|(name, service)| (name.clone(), service.peer()))
+        .collect()
+}
+
+/// Input schema of `tool_name` on a running server
+pub async fn tool_input_schema(
+    servers: &SharedMcpServers,
+    server_name: &str,
+    tool_name: &str,
+) -> Result<Value, String> {
+    let peer = server_peer(servers, server_name)
+        .await
+        .ok_or_else(|| format!("Server '{server_name}' not found"))?;
+    let tools = peer
+        .list_all_tools()
+        .await
+        .map_err(|e| format!("Failed to list the tools of {server_name}: {e}"))?;
+    tools
+        .into_iter()
+        .find(|tool| tool.name == tool_name)
+        .map(|tool| Value::Object((*tool.input_schema).clone()))
+        .ok_or_else(|| format!("Tool {tool_name} not found on server {server_name}"))
+}
+
+/// Span of one `tools/call` request, nested in the span of the turn that made it
+pub fn tool_call_span(server_name: &str, tool_name: &str) -> Span {
+    tracing::info_span!(
+        "mcp.call_tool",
+        server = server_name,
+        "gen_ai.tool.name" = tool_name,
     )
-    .map_err(|e| format!("Failed to write config file: {e}"))?;
+}
 
-    Ok(())
+/// Calls a tool on a specific connected server with a timeout.
+pub async fn call_tool_on_server(
+    servers: &SharedMcpServers,
+    server_name: &str,
+    params: CallToolRequestParam,
+    timeout_duration: Duration,
+) -> Result<CallToolResult, String> {
+    let tool_name = params.name.to_string();
+    let peer = server_peer(servers, server_name)
+        .await
+        .ok_or_else(|| format!("Server '{server_name}' not found"))?;
+    let span = tool_call_span(server_name, &tool_name);
+    let started = Instant::now();
+    let call = peer.call_tool(params).instrument(span.clone());
+    let result = match timeout(timeout_duration, call).await {
+        Ok(result) => result.map_err(|e| e.to_string()),
+        Err(_) => Err(format!(
+            "Tool call '{tool_name}' timed out after {} seconds",
+            timeout_duration.as_secs()
+        )),
+    };
+    if let Err(e) = &result {
+        tracing::error!(parent: &span, error = %e, "MCP tool call failed");
+    }
+    record_tool_call(
+        server_name,
+        ToolCallOutcome::of(&result, |r| r.is_error == Some(true)),
+        started.elapsed(),
+    );
+    result
 }
@@ -0,0 +1,155 @@
+//! Inspector for MCP server developers: raw JSON-RPC requests to a running server, and a
+//! wire log of every frame exchanged with a server. Every transport is wrapped in
+//! `InspectedTransport`, which records frames only while wire logging is enabled for its
+//! server, and passes them on to a session recording (see `recording`). Frames are kept in
+//! memory and bounded like the server logs.
+
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::{Mutex, OnceLock};
+
+use rmcp::{
+    model::ClientRequest,
+    service::{RxJsonRpcMessage, TxJsonRpcMessage},
+    transport::{IntoTransport, Transport},
+    RoleClient,
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::constants::{MAX_MCP_WIRE_FRAMES, MCP_WIRE_RECEIVED, MCP_WIRE_SENT};
+use super::models::McpWireFrame;
+use super::recording::record_session_frame;
+
+#[derive(Default)]
+struct WireLog {
+    enabled: bool,
+    frames: VecDeque<McpWireFrame>,
+}
+
+static WIRE_LOGS: OnceLock<Mutex<HashMap<String, WireLog>>> = OnceLock::new();
+
+fn wire_logs() -> std::sync::MutexGuard<'static, HashMap<String, WireLog>> {
+    WIRE_LOGS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+/// Start or stop recording the frames of `server`. Frames recorded so far are kept.
+pub fn set_wire_logging(server: &str, enabled: bool) {
+    wire_logs().entry(server.to_string()).or_default().enabled = enabled;
+}
+
+pub fn wire_logging_enabled(server: &str) -> bool {
+    wire_logs().get(server).is_some_and(|log| log.enabled)
+}
+
+/// Append a frame to the wire log of `server` if logging is enabled, dropping the oldest
+/// beyond `MAX_MCP_WIRE_FRAMES`
+pub fn record_wire_frame(server: &str, direction: &str, message: &impl Serialize) {
+    let mut logs = wire_logs();
+    let Some(log) = logs.get_mut(server).filter(|log| log.enabled) else {
+        return;
+    };
+    let message = serde_json::to_value(message)
+        .unwrap_or_else(|e| Value::String(format!("<unserializable frame: {e}>")));
+    if log.frames.len() >= MAX_MCP_WIRE_FRAMES {
+        log.frames.pop_front();
+    }
+    log.frames.push_back(McpWireFrame {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        direction: direction.to_string(),
+        message,
+    });
+}
+
+/// The last `limit` frames of `server`, oldest first
+pub fn wire_frames(server: &str, limit: usize) -> Vec<McpWireFrame> {
+    wire_logs()
+        .get(server)
+        .map(|log| {
+            let skip = log.frames.len().saturating_sub(limit);
+            log.frames.iter().skip(skip).cloned().collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn clear_wire_frames(server: &str) {
+    if let Some(log) = wire_logs().get_mut(server) {
+        log.frames.clear();
+    }
+}
+
+/// Build the request for `method`. Only requests rmcp knows can be sent, since its peer
+/// matches responses to typed requests; `initialize` is refused because the session of a
+/// running server is already initialized, and `tools/call` because tool calls must go
+/// through `call_tool`, which applies the approval policy, the assistant's tool scope and
+/// the thread's workspace.
+pub fn parse_request(method: &str, params: Option<Value>) -> Result<ClientRequest, String> {
+    let method = method.trim();
+    if method == "initialize" {
+        return Err("The server is already initialized".to_string());
+    }
+    if method == "tools/call" {
+        return Err("Tools can't be called from the inspector, use call_tool".to_string());
+    }
+    let mut request = Map::new();
+    request.insert("method".to_string(), Value::String(method.to_string()));
+    if let Some(params) = params.filter(|params| !params.is_null()) {
+        request.insert("params".to_string(), params);
+    }
+    serde_json::from_value(Value::Object(request))
+        .map_err(|e| format!("Unsupported MCP request {method} or invalid params: {e}"))
+}
+
+/// Transport recording the frames it carries in the wire log of its server
+pub struct InspectedTransport<T> {
+    server: String,
+    inner: T,
+}
+
+/// Wrap `transport` so its frames can be inspected
+pub fn inspected<E, A>(
+    server: &str,
+    transport: impl IntoTransport<RoleClient, E, A>,
+) -> InspectedTransport<impl Transport<RoleClient, Error = E>>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    InspectedTransport {
+        server: server.to_string(),
+        inner: transport.into_transport(),
+    }
+}
+
+impl<T> Transport<RoleClient> for InspectedTransport<T>
+where
+    T: Transport<RoleClient>,
+{
+    type Error = T::Error;
+
+    fn send(
+        &mut self,
+        item: TxJsonRpcMessage<RoleClient>,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send + 'static {
+        record_wire_frame(&self.server, MCP_WIRE_SENT, &item);
+        record_session_frame(&self.server, MCP_WIRE_SENT, &item);
+        self.inner.send(item)
+    }
+
+    fn receive(&mut self) -> impl Future<Output = Option<RxJsonRpcMessage<RoleClient>>> + Send {
+        async move {
+            let item = self.inner.receive().await;
+            if let Some(item) = &item {
+                record_wire_frame(&self.server, MCP_WIRE_RECEIVED, item);
+                record_session_frame(&self.server, MCP_WIRE_RECEIVED, item);
+            }
+            item
+        }
+    }
+
+    fn close(&mut self) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        self.inner.close()
+    }
+}
@@ -0,0 +1,223 @@
+//! Recent log lines per MCP server: lifecycle events, health check failures and the server's
+//! own stderr output. Kept in memory and bounded, for the management API.
+//!
+//! A server's output passes an `OutputLimiter` before it reaches the app log. Lines are cut
+//! at `MAX_MCP_LOG_LINE_BYTES`, a token bucket lets through `MCP_LOG_LINES_PER_SEC` on
+//! average, and a server dropping `MCP_LOG_MUTE_THRESHOLD` lines within
+//! `MCP_LOG_MUTE_WINDOW` is muted for `MCP_LOG_MUTE_DURATION`. Its output is still read
+//! while muted, so the server never blocks on a full pipe.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+use tauri::{AppHandle, Emitter, Runtime};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead};
+
+use super::constants::{
+    MAX_MCP_LOG_LINES, MAX_MCP_LOG_LINE_BYTES, MCP_LOG_BURST_LINES, MCP_LOG_LINES_PER_SEC,
+    MCP_LOG_LINE_COST_BYTES, MCP_LOG_MUTE_DURATION, MCP_LOG_MUTE_THRESHOLD, MCP_LOG_MUTE_WINDOW,
+    MCP_SERVER_MUTED_EVENT,
+};
+use super::metrics::{dropped_log_lines, record_dropped_log_lines};
+use super::models::{McpLogEntry, McpServerMuted};
+
+static MCP_LOGS: OnceLock<Mutex<HashMap<String, VecDeque<McpLogEntry>>>> = OnceLock::new();
+static MUTED: OnceLock<Mutex<HashMap<String, Instant>>> = OnceLock::new();
+
+fn logs() -> &'static Mutex<HashMap<String, VecDeque<McpLogEntry>>> {
+    MCP_LOGS.get_or_init(Default::default)
+}
+
+fn muted() -> std::sync::MutexGuard<'static, HashMap<String, Instant>> {
+    MUTED
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+/// Append a line to the log of `server`, dropping the oldest beyond `MAX_MCP_LOG_LINES`
+pub fn record_server_log(server: &str, level: log::Level, message: impl Into<String>) {
+    let mut logs = logs().lock().unwrap_or_else(|e| e.into_inner());
+    let lines = logs.entry(server.to_string()).or_default();
+    if lines.len() >= MAX_MCP_LOG_LINES {
+        lines.pop_front();
+    }
+    lines.push_back(McpLogEntry {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        level: level.as_str().to_lowercase(),
+        message: message.into(),
+    });
+}
+
+/// The last `limit` lines of `server`, oldest first
+pub fn server_logs(server: &str, limit: usize) -> Vec<McpLogEntry> {
+    let logs = logs().lock().unwrap_or_else(|e| e.into_inner());
+    logs.get(server)
+        .map(|lines| {
+            let skip = lines.len().saturating_sub(limit);
+            lines.iter().skip(skip).cloned().collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Whether the output of `server` is muted right now
+pub fn is_log_muted(server: &str) -> bool {
+    muted()
+        .get(server)
+        .is_some_and(|until| *until > Instant::now())
+}
+
+/// What to do with a line of output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Admission {
+    Log,
+    Drop,
+    /// Drop it, and the server just got muted
+    Mute,
+}
+
+/// Rate limit of one server's output
+#[derive(Debug)]
+pub struct OutputLimiter {
+    tokens: f64,
+    refilled_at: Instant,
+    window_start: Instant,
+    window_dropped: u64,
+    muted_until: Option<Instant>,
+    /// Lines dropped since the last one let through
+    pending_dropped: u64,
+}
+
+impl OutputLimiter {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            tokens: MCP_LOG_BURST_LINES,
+            refilled_at: now,
+            window_start: now,
+            window_dropped: 0,
+            muted_until: None,
+            pending_dropped: 0,
+        }
+    }
+
+    pub fn admit(&mut self, bytes: usize, now: Instant) -> Admission {
+        if let Some(until) = self.muted_until {
+            if now < until {
+                self.pending_dropped += 1;
+                return Admission::Drop;
+            }
+            self.muted_until = None;
+            self.tokens = MCP_LOG_BURST_LINES;
+            self.window_start = now;
+            self.window_dropped = 0;
+        }
+
+        let elapsed = now.saturating_duration_since(self.refilled_at);
+        self.tokens =
+            (self.tokens + elapsed.as_secs_f64() * MCP_LOG_LINES_PER_SEC).min(MCP_LOG_BURST_LINES);
+        self.refilled_at = now;
+        let cost = (1 + bytes / MCP_LOG_LINE_COST_BYTES) as f64;
+        if self.tokens >= cost {
+            self.tokens -= cost;
+            return Admission::Log;
+        }
+
+        self.pending_dropped += 1;
+        if now.saturating_duration_since(self.window_start) > MCP_LOG_MUTE_WINDOW {
+            self.window_start = now;
+            self.window_dropped = 0;
+        }
+        self.window_dropped += 1;
+        if self.window_dropped >= MCP_LOG_MUTE_THRESHOLD {
+            self.muted_until = Some(now + MCP_LOG_MUTE_DURATION);
+            return Admission::Mute;
+        }
+        Admission::Drop
+    }
+
+    /// Lines dropped since the last call
+    pub fn take_dropped(&mut self) -> u64 {
+        std::mem::take(&mut self.pending_dropped)
+    }
+}
+
+/// Read a line of at most `max_bytes`, discarding the rest of a longer one. Returns the full
+/// length of the line, `None` at the end of the stream.
+pub async fn read_capped_line<R: AsyncBufRead + Unpin>(
+    reader: &mut R,
+    line: &mut Vec<u8>,
+    max_bytes: usize,
+) -> std::io::Result<Option<usize>> {
+    line.clear();
+    let mut total = 0;
+    loop {
+        let available = reader.fill_buf().await?;
+        if available.is_empty() {
+            return Ok((total > 0).then_some(total));
+        }
+        let newline = available.iter().position(|b| *b == b'\n');
+        let chunk = &available[..newline.unwrap_or(available.len())];
+        let room = max_bytes.saturating_sub(line.len());
+        line.extend_from_slice(&chunk[..chunk.len().min(room)]);
+        total += chunk.len();
+        let consumed = chunk.len() + usize::from(newline.is_some());
+        reader.consume(consumed);
+        if newline.is_some() {
+            return Ok(Some(total));
+        }
+    }
+}
+
+/// Log the output of a server's process through its rate limit and keep it for the
+/// management API
+pub fn forward_server_output<R: Runtime>(
+    app: &AppHandle<R>,
+    server: &str,
+    output: impl AsyncRead + Unpin + Send + 'static,
+) {
+    let app = app.clone();
+    let server = server.to_string();
+    tauri::async_runtime::spawn(async move {
+        let mut reader = tokio::io::BufReader::new(output);
+        let mut limiter = OutputLimiter::new(Instant::now());
+        let mut buf = Vec::new();
+        while let Ok(Some(len)) =
+            read_capped_line(&mut reader, &mut buf, MAX_MCP_LOG_LINE_BYTES).await
+        {
+            match limiter.admit(len, Instant::now()) {
+                Admission::Log => {
+                    let dropped = limiter.take_dropped();
+                    if dropped > 0 {
+                        let note = format!("{dropped} lines of output dropped by the rate limit");
+                        log::warn!("[{server}] {note}");
+                        record_server_log(&server, log::Level::Warn, note);
+                    }
+                    let mut line = String::from_utf8_lossy(&buf).trim_end().to_string();
+                    if len > buf.len() {
+                        line.push_str(&format!(" [{} more bytes]", len - buf.len()));
+                    }
+                    log::info!("[{server}] {line}");
+                    record_server_log(&server, log::Level::Info, line);
+                }
+                Admission::Drop => record_dropped_log_lines(&server, 1),
+                Admission::Mute => {
+                    record_dropped_log_lines(&server, 1);
+                    muted().insert(server.clone(), Instant::now() + MCP_LOG_MUTE_DURATION);
+                    let secs = MCP_LOG_MUTE_DURATION.as_secs();
+                    let note = format!("Output flooded the log; muted for {secs}s");
+                    log::warn!("[{server}] {note}");
+                    record_server_log(&server, log::Level::Warn, note);
+                    let payload = McpServerMuted {
+                        server: server.clone(),
+                        dropped_lines: dropped_log_lines(&server),
+                        muted_for_secs: secs,
+                    };
+                    if let Err(e) = app.emit(MCP_SERVER_MUTED_EVENT, payload) {
+                        log::warn!("Failed to emit {MCP_SERVER_MUTED_EVENT}: {e}");
+                    }
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,265 @@
+//! Per-server MCP metrics in the Prometheus text exposition format, served by the local API
+//! server at `/metrics`.
+//!
+//! Counters live in a process-wide registry so every tool call path records into the same
+//! series without threading state through. They reset when the app restarts, which
+//! Prometheus handles as a counter reset.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Upper bounds in seconds of the tool call duration buckets
+pub const TOOL_CALL_DURATION_BUCKETS: [f64; 10] =
+    [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ToolCallOutcome {
+    Success,
+    Error,
+    Timeout,
+    Cancelled,
+}
+
+impl ToolCallOutcome {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Error => "error",
+            Self::Timeout => "timeout",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    /// Classify a finished call from its result
+    pub fn of<T>(result: &Result<T, String>, is_error: impl FnOnce(&T) -> bool) -> Self {
+        match result {
+            Ok(value) if is_error(value) => Self::Error,
+            Ok(_) => Self::Success,
+            Err(e) if e.contains("timed out") => Self::Timeout,
+            Err(e) if e.contains("was cancelled") => Self::Cancelled,
+            Err(_) => Self::Error,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+struct Histogram {
+    // Non-cumulative counts per bucket; the last slot holds observations above every bound
+    buckets: [u64; TOOL_CALL_DURATION_BUCKETS.len() + 1],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, seconds: f64) {
+        let index = TOOL_CALL_DURATION_BUCKETS
+            .iter()
+            .position(|bound| seconds <= *bound)
+            .unwrap_or(TOOL_CALL_DURATION_BUCKETS.len());
+        self.buckets[index] += 1;
+        self.sum += seconds;
+        self.count += 1;
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct McpMetrics {
+    tool_calls: BTreeMap<(String, ToolCallOutcome), u64>,
+    durations: BTreeMap<String, Histogram>,
+    restarts: BTreeMap<String, u64>,
+    reconnects: BTreeMap<String, u64>,
+    health_check_failures: BTreeMap<String, u64>,
+    dropped_log_lines: BTreeMap<String, u64>,
+}
+
+static MCP_METRICS: OnceLock<Mutex<McpMetrics>> = OnceLock::new();
+
+fn with_metrics<T>(f: impl FnOnce(&mut McpMetrics) -> T) -> T {
+    let mut metrics = MCP_METRICS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    f(&mut metrics)
+}
+
+impl McpMetrics {
+    pub fn record_tool_call(&mut self, server: &str, outcome: ToolCallOutcome, elapsed: Duration) {
+        *self
+            .tool_calls
+            .entry((server.to_string(), outcome))
+            .or_default() += 1;
+        self.durations
+            .entry(server.to_string())
+            .or_default()
+            .observe(elapsed.as_secs_f64());
+    }
+
+    pub fn record_restart(&mut self, server: &str) {
+        *self.restarts.entry(server.to_string()).or_default() += 1;
+    }
+
+    pub fn record_reconnect(&mut self, server: &str) {
+        *self.reconnects.entry(server.to_string()).or_default() += 1;
+    }
+
+    pub fn record_health_check_failure(&mut self, server: &str) {
+        *self
+            .health_check_failures
+            .entry(server.to_string())
+            .or_default() += 1;
+    }
+
+    pub fn record_dropped_log_lines(&mut self, server: &str, lines: u64) {
+        *self
+            .dropped_log_lines
+            .entry(server.to_string())
+            .or_default() += lines;
+    }
+
+    /// Render all series in the Prometheus text format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        header(
+            &mut out,
+            "jan_mcp_tool_calls_total",
+            "counter",
+            "MCP tool calls by server and outcome.",
+        );
+        for ((server, outcome), count) in &self.tool_calls {
+            let _ = writeln!(
+                out,
+                "jan_mcp_tool_calls_total{{server=\"{}\",outcome=\"{}\"}} {count}",
+                escape_label(server),
+                outcome.label()
+            );
+        }
+
+        header(
+            &mut out,
+            "jan_mcp_tool_call_duration_seconds",
+            "histogram",
+            "Duration of MCP tool calls by server.",
+        );
+        for (server, histogram) in &self.durations {
+            let server = escape_label(server);
+            let mut cumulative = 0;
+            for (bound, count) in TOOL_CALL_DURATION_BUCKETS.iter().zip(&histogram.buckets) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "jan_mcp_tool_call_duration_seconds_bucket{{server=\"{server}\",le=\"{bound}\"}} {cumulative}"
+                );
+            }
+            let _ = writeln!(
+                out,
+                "jan_mcp_tool_call_duration_seconds_bucket{{server=\"{server}\",le=\"+Inf\"}} {}",
+                histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "jan_mcp_tool_call_duration_seconds_sum{{server=\"{server}\"}} {}",
+                histogram.sum
+            );
+            let _ = writeln!(
+                out,
+                "jan_mcp_tool_call_duration_seconds_count{{server=\"{server}\"}} {}",
+                histogram.count
+            );
+        }
+
+        counter_family(
+            &mut out,
+            "jan_mcp_server_restarts_total",
+            "MCP server restarts.",
+            &self.restarts,
+        );
+        counter_family(
+            &mut out,
+            "jan_mcp_server_reconnects_total",
+            "Remote MCP server sessions reconnected without a restart.",
+            &self.reconnects,
+        );
+        counter_family(
+            &mut out,
+            "jan_mcp_health_check_failures_total",
+            "Failed MCP server health checks.",
+            &self.health_check_failures,
+        );
+        counter_family(
+            &mut out,
+            "jan_mcp_dropped_log_lines_total",
+            "MCP server output lines dropped by the rate limit.",
+            &self.dropped_log_lines,
+        );
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn counter_family(out: &mut String, name: &str, help: &str, values: &BTreeMap<String, u64>) {
+    header(out, name, "counter", help);
+    for (server, count) in values {
+        let _ = writeln!(out, "{name}{{server=\"{}\"}} {count}", escape_label(server));
+    }
+}
+
+/// Escape a label value as required by the exposition format
+pub fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+pub fn record_tool_call(server: &str, outcome: ToolCallOutcome, elapsed: Duration) {
+    with_metrics(|metrics| metrics.record_tool_call(server, outcome, elapsed));
+}
+
+pub fn record_restart(server: &str) {
+    with_metrics(|metrics| metrics.record_restart(server));
+}
+
+pub fn record_reconnect(server: &str) {
+    with_metrics(|metrics| metrics.record_reconnect(server));
+}
+
+pub fn record_health_check_failure(server: &str) {
+    with_metrics(|metrics| metrics.record_health_check_failure(server));
+}
+
+pub fn record_dropped_log_lines(server: &str, lines: u64) {
+    with_metrics(|metrics| metrics.record_dropped_log_lines(server, lines));
+}
+
+/// Current metrics of all servers in the Prometheus text format
+pub fn render_metrics() -> String {
+    with_metrics(|metrics| metrics.render())
+}
+
+/// Restart and failed health check counts of `server`
+pub fn server_counters(server: &str) -> (u64, u64) {
+    with_metrics(|metrics| {
+        (
+            metrics.restarts.get(server).copied().unwrap_or(0),
+            metrics
+                .health_check_failures
+                .get(server)
+                .copied()
+                .unwrap_or(0),
+        )
+    })
+}
+
+/// Output lines of `server` dropped by the rate limit
+pub fn dropped_log_lines(server: &str) -> u64 {
+    with_metrics(|metrics| metrics.dropped_log_lines.get(server).copied().unwrap_or(0))
+}
@@ -0,0 +1,172 @@
+//! Wire format versioning of `mcp_config.json`.
+//!
+//! The document carries a top-level `version`. Files without one are version 0. On read,
+//! older documents are upgraded step by step through `MIGRATIONS`, the original text is kept
+//! in a timestamped backup next to the file, and the upgraded document is written back
+//! through the config store. Documents newer than this build are left untouched.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Map, Value};
+
+use super::constants::{MCP_CONFIG_VERSION, MCP_CONFIG_VERSION_KEY};
+use crate::core::config_store::helpers::{blocking_io, config_store};
+
+/// Upgrade steps; `MIGRATIONS[n]` turns a version `n` document into version `n + 1`
+const MIGRATIONS: [fn(&mut Map<String, Value>); MCP_CONFIG_VERSION as usize] = [migrate_v0_to_v1];
+
+/// Spellings of the streamable HTTP transport used by other MCP clients
+const HTTP_TRANSPORT_ALIASES: [&str; 4] = [
+    "streamable-http",
+    "streamable_http",
+    "streamableHttp",
+    "http-streamable",
+];
+
+/// v1: `transport` and `envs` become `type` and `env`, streamable HTTP aliases become `http`,
+/// and URL-based servers get the empty `command`/`args` the loader expects.
+fn migrate_v0_to_v1(config: &mut Map<String, Value>) {
+    if !config.get("mcpServers").is_some_and(Value::is_object) {
+        config.insert("mcpServers".to_string(), json!({}));
+    }
+    let Some(servers) = config.get_mut("mcpServers").and_then(Value::as_object_mut) else {
+        return;
+    };
+    for server in servers.values_mut().filter_map(Value::as_object_mut) {
+        rename_field(server, "transport", "type");
+        rename_field(server, "envs", "env");
+        if let Some(transport) = server.get("type").and_then(Value::as_str) {
+            if HTTP_TRANSPORT_ALIASES.contains(&transport) {
+                server.insert("type".to_string(), json!("http"));
+            }
+        }
+        if server.contains_key("url") {
+            server.entry("command").or_insert_with(|| json!(""));
+            server.entry("args").or_insert_with(|| json!([]));
+        }
+    }
+}
+
+/// Move `from` to `to` unless `to` is already set
+fn rename_field(object: &mut Map<String, Value>, from: &str, to: &str) {
+    if let Some(value) = object.remove(from) {
+        object.entry(to).or_insert(value);
+    }
+}
+
+/// Layout version of a document, 0 when absent
+pub fn config_version(config: &Value) -> u64 {
+    config
+        .get(MCP_CONFIG_VERSION_KEY)
+        .and_then(Value::as_u64)
+        .unwrap_or(0)
+}
+
+/// Upgrade `config` in place to `MCP_CONFIG_VERSION`. Returns the version it had when it was
+/// upgraded, `None` when it was current or newer.
+pub fn migrate_config(config: &mut Value) -> Result<Option<u64>, String> {
+    let from = config_version(config);
+    if from > MCP_CONFIG_VERSION {
+        log::warn!(
+            "mcp_config.json has version {from}, newer than the supported {MCP_CONFIG_VERSION}"
+        );
+        return Ok(None);
+    }
+    let object = config
+        .as_object_mut()
+        .ok_or("MCP config must be a JSON object")?;
+    if from == MCP_CONFIG_VERSION {
+        return Ok(None);
+    }
+    for (version, migration) in MIGRATIONS.iter().enumerate().skip(from as usize) {
+        log::info!("Migrating mcp_config.json from version {version}");
+        migration(object);
+    }
+    object.insert(
+        MCP_CONFIG_VERSION_KEY.to_string(),
+        json!(MCP_CONFIG_VERSION),
+    );
+    Ok(Some(from))
+}
+
+/// Where the pre-migration copy of `path` at `version` is kept
+pub fn backup_path(path: &Path, version: u64) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%S%3f");
+    path.with_file_name(format!("{file_name}.v{version}-{stamp}.bak"))
+}
+
+/// Replace `path` with `config` through the config store, so readers never see a partial
+/// document
+pub fn write_config_atomic(path: &Path, config: &Value) -> Result<(), String> {
+    let data = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize MCP config: {e}"))?;
+    config_store().write(path, data)
+}
+
+/// Upgrade `config`, parsed from the `raw` contents of `path`. When it changes, `raw` is
+/// backed up first; the caller writes the upgraded document.
+pub fn upgrade_config(path: &Path, raw: &str, config: &mut Value) -> Result<bool, String> {
+    let Some(from) = migrate_config(config)? else {
+        return Ok(false);
+    };
+    let backup = backup_path(path, from);
+    fs::write(&backup, raw).map_err(|e| format!("Failed to back up MCP config: {e}"))?;
+    log::info!("Backed up mcp_config.json to {}", backup.display());
+    Ok(true)
+}
+
+/// Read the config at `path`, upgrading and rewriting it when it uses an older layout
+pub fn load_config(path: &Path) -> Result<Value, String> {
+    let raw = config_store()
+        .read(path)
+        .map_err(|e| format!("Failed to read config file: {e}"))?;
+    let mut config: Value =
+        serde_json::from_str(&raw).map_err(|e| format!("Failed to parse config: {e}"))?;
+    if upgrade_config(path, &raw, &mut config)? {
+        write_config_atomic(path, &config)?;
+    }
+    Ok(config)
+}
+
+/// `load_config` on the blocking thread pool, for async callers
+pub async fn load_config_async(path: &Path) -> Result<Value, String> {
+    let path = path.to_path_buf();
+    blocking_io(move || load_config(&path)).await?
+}
+
+/// `write_config_atomic` on the blocking thread pool, for async callers
+pub async fn write_config_async(path: &Path, config: &Value) -> Result<(), String> {
+    let data = serde_json::to_string_pretty(config)
+        .map_err(|e| format!("Failed to serialize MCP config: {e}"))?;
+    config_store().write_async(path, data).await
+}
+
+/// `update_config` on the blocking thread pool, for async callers
+pub async fn update_config_async<F>(path: &Path, update: F) -> Result<(), String>
+where
+    F: FnOnce(&mut Value) -> Result<(), String> + Send + 'static,
+{
+    let path = path.to_path_buf();
+    blocking_io(move || update_config(&path, update)).await?
+}
+
+/// Read-modify-write the config at `path` under the store's file lock, upgrading it first
+pub fn update_config<F>(path: &Path, update: F) -> Result<(), String>
+where
+    F: FnOnce(&mut Value) -> Result<(), String>,
+{
+    config_store().update(path, |raw| {
+        let raw = raw.ok_or("Failed to read config file: not found")?;
+        let mut config: Value =
+            serde_json::from_str(&raw).map_err(|e| format!("Failed to parse config: {e}"))?;
+        upgrade_config(path, &raw, &mut config)?;
+        update(&mut config)?;
+        serde_json::to_string_pretty(&config)
+            .map_err(|e| format!("Failed to serialize MCP config: {e}"))
+    })
+}
@@ -1,8 +1,24 @@
+pub mod admin;
+pub mod bindings;
 pub mod commands;
 pub mod constants;
 pub mod helpers;
+pub mod inspector;
 pub mod lockfile;
+pub mod logs;
+pub mod metrics;
+pub mod migrations;
 pub mod models;
+pub mod ports;
+pub mod recording;
+pub mod roots;
+pub mod sharing;
+pub mod socket;
+pub mod templates;
+pub mod tool_cache;
+pub mod versions;
 
+#[cfg(test)]
+pub mod test_support;
 #[cfg(test)]
 mod tests;
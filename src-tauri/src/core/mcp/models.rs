@@ -8,11 +8,53 @@ use serde_json::Value;
 pub struct McpServerConfig {
     pub transport_type: Option<String>,
     pub url: Option<String>,
+    /// Socket path or pipe name for the "unix"/"pipe" transports
+    pub socket: Option<String>,
     pub command: String,
     pub args: Vec<Value>,
     pub envs: serde_json::Map<String, Value>,
     pub timeout: Option<Duration>,
     pub headers: serde_json::Map<String, Value>,
+    /// Env vars besides `BRIDGE_PORT` that hold a port the server listens on
+    pub bridge_ports: Vec<String>,
+    /// Package version an npx/uvx-based server is pinned to
+    pub version: Option<String>,
+    /// Recorded session served instead of spawning the server
+    pub replay: Option<String>,
+    pub health_check: HealthCheck,
+}
+
+/// How a running server is checked for being alive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthProbe {
+    /// MCP `ping`, falling back to `ListTools` for servers that don't implement it
+    Ping,
+    /// List the server's tools, for servers that answer pings even when they are stuck
+    ListTools,
+    /// Not checked; the server is only dropped when its connection closes
+    None,
+}
+
+/// Health check of a server, from the defaults of its transport and the `healthCheck` entry
+/// of its config
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HealthCheck {
+    pub probe: HealthProbe,
+    pub interval: Duration,
+    pub timeout: Duration,
+    /// Failed checks in a row before the server is dropped
+    pub failure_threshold: u32,
+}
+
+/// `healthCheck` entry of a server config; unset fields keep the transport's defaults
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HealthCheckOverrides {
+    pub probe: Option<HealthProbe>,
+    pub interval_seconds: Option<u64>,
+    pub timeout_seconds: Option<u64>,
+    pub failure_threshold: Option<u32>,
 }
 
 fn default_tool_call_timeout_seconds() -> u64 {
@@ -31,8 +73,16 @@ fn default_backoff_multiplier() -> f64 {
     super::constants::DEFAULT_MCP_BACKOFF_MULTIPLIER
 }
 
+fn default_startup_concurrency() -> usize {
+    super::constants::DEFAULT_MCP_STARTUP_CONCURRENCY
+}
+
+fn default_startup_budget_seconds() -> u64 {
+    super::constants::DEFAULT_MCP_STARTUP_BUDGET_SECS
+}
+
 /// Runtime MCP settings that can be adjusted via UI
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 #[serde(rename_all = "camelCase")]
 pub struct McpSettings {
     #[serde(default = "default_tool_call_timeout_seconds")]
@@ -43,6 +93,13 @@ pub struct McpSettings {
     pub max_restart_delay_ms: u64,
     #[serde(default = "default_backoff_multiplier")]
     pub backoff_multiplier: f64,
+    /// How many servers start at the same time when Jan launches
+    #[serde(default = "default_startup_concurrency")]
+    pub startup_concurrency: usize,
+    /// How long launch waits for servers to start; servers not up by then keep starting
+    /// in the background. 0 waits for all of them.
+    #[serde(default = "default_startup_budget_seconds")]
+    pub startup_budget_seconds: u64,
 }
 
 impl Default for McpSettings {
@@ -52,6 +109,8 @@ impl Default for McpSettings {
             base_restart_delay_ms: super::constants::DEFAULT_MCP_BASE_RESTART_DELAY_MS,
             max_restart_delay_ms: super::constants::DEFAULT_MCP_MAX_RESTART_DELAY_MS,
             backoff_multiplier: super::constants::DEFAULT_MCP_BACKOFF_MULTIPLIER,
+            startup_concurrency: super::constants::DEFAULT_MCP_STARTUP_CONCURRENCY,
+            startup_budget_seconds: super::constants::DEFAULT_MCP_STARTUP_BUDGET_SECS,
         }
     }
 }
@@ -61,10 +120,16 @@ impl McpSettings {
     pub fn tool_call_timeout_duration(&self) -> std::time::Duration {
         std::time::Duration::from_secs(self.tool_call_timeout_seconds.max(1))
     }
+
+    /// The startup budget, `None` when launch waits for every server
+    pub fn startup_budget(&self) -> Option<std::time::Duration> {
+        (self.startup_budget_seconds > 0)
+            .then(|| std::time::Duration::from_secs(self.startup_budget_seconds))
+    }
 }
 
 /// Tool with server information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
 pub struct ToolWithServer {
     pub name: String,
     pub description: Option<String>,
@@ -72,3 +137,175 @@ pub struct ToolWithServer {
     pub input_schema: serde_json::Value,
     pub server: String,
 }
+
+/// Result of a tool call as returned to the webview. Mirrors the wire format of the MCP
+/// `CallToolResult`, which has no TypeScript type of its own.
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct McpToolCallResult {
+    /// MCP content blocks such as `{ "type": "text", "text": "..." }`
+    pub content: Vec<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub is_error: Option<bool>,
+    /// Set when the full result was too large and stored as a tool artifact; `content` then
+    /// holds a preview
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub artifact_id: Option<String>,
+}
+
+impl From<rmcp::model::CallToolResult> for McpToolCallResult {
+    fn from(result: rmcp::model::CallToolResult) -> Self {
+        Self {
+            content: result
+                .content
+                .iter()
+                .filter_map(|content| serde_json::to_value(content).ok())
+                .collect(),
+            structured_content: result.structured_content,
+            is_error: result.is_error,
+            artifact_id: None,
+        }
+    }
+}
+
+/// State of a configured or running MCP server, as reported by the management API
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct McpServerStatus {
+    pub name: String,
+    /// Whether the config marks the server as active
+    pub enabled: bool,
+    /// Whether the server is connected right now
+    pub running: bool,
+    pub pid: Option<u32>,
+    pub restarts: u64,
+    pub health_check_failures: u64,
+    /// Output lines dropped by the rate limit
+    pub dropped_log_lines: u64,
+    /// Whether the output is muted for flooding the log
+    pub log_muted: bool,
+}
+
+/// One line of a server's recent activity
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct McpLogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub message: String,
+}
+
+/// A JSON-RPC frame exchanged with a server, recorded while wire logging is enabled
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+pub struct McpWireFrame {
+    pub timestamp: String,
+    /// `sent` to the server or `received` from it
+    pub direction: String,
+    pub message: serde_json::Value,
+}
+
+/// Wire log of a server as shown by the inspector
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+pub struct McpWireLog {
+    pub enabled: bool,
+    /// Whether the server's traffic is being recorded to a session file
+    pub recording: bool,
+    pub frames: Vec<McpWireFrame>,
+}
+
+/// A recorded MCP session in the recordings folder
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct McpRecording {
+    /// File name, usable as the `replay` of a server config
+    pub name: String,
+    pub server: String,
+    pub started_at: String,
+    pub frames: usize,
+}
+
+/// Sent when a bridge port was busy and the server was started on another one
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct McpPortChange {
+    pub server: String,
+    /// Env var the port is passed in
+    pub env: String,
+    pub configured_port: u16,
+    pub port: u16,
+}
+
+/// Payload of `mcp-server-muted`, sent when a server floods its output
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct McpServerMuted {
+    pub server: String,
+    /// Lines dropped so far
+    pub dropped_lines: u64,
+    pub muted_for_secs: u64,
+}
+
+/// Startup progress of a server whose start was moved to the background
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct McpStartupStatus {
+    pub server: String,
+    /// `deferred`, then `started` or `failed`
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Version of the package an npx/uvx-based server runs, compared with its newest release
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct McpPackageVersion {
+    pub server: String,
+    pub package: String,
+    /// `npm` or `pypi`
+    pub registry: String,
+    /// Version the server is pinned to; unset when it runs whatever is newest
+    pub pinned: Option<String>,
+    pub latest: String,
+    /// Whether `latest` is newer than the pinned version
+    pub update_available: bool,
+}
+
+/// A secret a shared setup needs, referenced as `{{secret:<name>}}` in its server configs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpSecret {
+    pub name: String,
+    /// Servers using the secret
+    pub servers: Vec<String>,
+}
+
+/// A shareable MCP setup, as written by `export_mcp_config`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpConfigExport {
+    /// Always `jan-mcp-config`
+    pub format: String,
+    /// Config version the servers are written in
+    pub version: u64,
+    pub mcp_servers: serde_json::Map<String, serde_json::Value>,
+    /// Secrets left out, to be supplied on import
+    #[serde(default)]
+    pub secrets: Vec<McpSecret>,
+}
+
+/// Payload of `mcp-secret-required`, answered with `respond_mcp_secret`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpSecretRequest {
+    pub request_id: String,
+    pub name: String,
+    pub servers: Vec<String>,
+}
+
+/// What `import_mcp_config` changed in the MCP config
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct McpConfigImport {
+    /// Servers that weren't configured before
+    pub added: Vec<String>,
+    /// Configured servers whose config was replaced
+    pub replaced: Vec<String>,
+}
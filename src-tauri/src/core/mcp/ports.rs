@@ -0,0 +1,55 @@
+//! Bridge ports: ports an MCP server listens on for other components (e.g. the browser
+//! extension bridge), passed in env vars. When the configured port is busy and no orphaned
+//! MCP process holding it can be cleaned up, the server gets the next free port instead
+//! of failing to start.
+
+use std::collections::HashSet;
+
+use super::constants::{DEFAULT_BRIDGE_PORT_ENV, MAX_BRIDGE_PORT_SCAN};
+use super::models::McpServerConfig;
+use super::templates::allocate_free_port;
+
+/// The bridge port env vars of a server that hold a valid port, with that port
+pub fn bridge_ports(config: &McpServerConfig) -> Vec<(String, u16)> {
+    let mut names = vec![DEFAULT_BRIDGE_PORT_ENV.to_string()];
+    for name in &config.bridge_ports {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let port = config
+                .envs
+                .get(&name)
+                .and_then(|value| match value {
+                    serde_json::Value::String(s) => s.trim().parse::<u16>().ok(),
+                    serde_json::Value::Number(n) => n.as_u64().and_then(|n| n.try_into().ok()),
+                    _ => None,
+                })
+                .filter(|port| *port != 0)?;
+            Some((name, port))
+        })
+        .collect()
+}
+
+/// The first port above `busy` that is free and not `taken`, scanning at most
+/// `MAX_BRIDGE_PORT_SCAN` ports
+pub fn next_free_port(
+    busy: u16,
+    taken: &HashSet<u16>,
+    is_free: impl Fn(u16) -> bool,
+) -> Option<u16> {
+    (1..=MAX_BRIDGE_PORT_SCAN)
+        .filter_map(|offset| busy.checked_add(offset))
+        .find(|port| !taken.contains(port) && is_free(*port))
+}
+
+/// A replacement for the busy port `busy`: the next free one above it, or any free port
+pub fn replacement_port(busy: u16, taken: &HashSet<u16>) -> Result<u16, String> {
+    match next_free_port(busy, taken, jan_utils::network::is_port_available) {
+        Some(port) => Ok(port),
+        None => allocate_free_port(),
+    }
+}
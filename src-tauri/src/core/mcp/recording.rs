@@ -0,0 +1,341 @@
+//! Record-and-replay of MCP sessions. While a server is being recorded, every frame of its
+//! wire traffic is appended to a session file in `mcp_recordings/`: a header line naming the
+//! server, then one JSON line per frame. A server whose config has `"replay": "<session>"`
+//! isn't spawned; `ReplayTransport` answers its requests with the recorded responses
+//! instead, which reproduces a session offline and without the real server.
+
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use rmcp::{
+    service::{RxJsonRpcMessage, TxJsonRpcMessage},
+    transport::Transport,
+    RoleClient,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+
+use super::constants::{
+    MCP_RECORDINGS_DIR, MCP_RECORDING_EXTENSION, MCP_WIRE_RECEIVED, MCP_WIRE_SENT,
+};
+use super::models::{McpRecording, McpWireFrame};
+
+struct ActiveRecording {
+    path: PathBuf,
+    file: File,
+}
+
+static RECORDINGS: OnceLock<Mutex<HashMap<String, ActiveRecording>>> = OnceLock::new();
+
+fn recordings() -> std::sync::MutexGuard<'static, HashMap<String, ActiveRecording>> {
+    RECORDINGS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn recordings_dir(data_folder: &Path) -> PathBuf {
+    data_folder.join(MCP_RECORDINGS_DIR)
+}
+
+/// Path of a session given by file name, which is looked up in the recordings folder, or
+/// by absolute path
+pub fn recording_path(data_folder: &Path, session: &str) -> PathBuf {
+    let path = Path::new(session);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        recordings_dir(data_folder).join(path)
+    }
+}
+
+/// Start recording the traffic of `server` into a new session file. Recording continues
+/// across restarts of the server, so restart it to capture the initialization as well.
+pub fn start_recording(data_folder: &Path, server: &str) -> Result<PathBuf, String> {
+    let mut active = recordings();
+    if let Some(recording) = active.get(server) {
+        return Ok(recording.path.clone());
+    }
+    let dir = recordings_dir(data_folder);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    let file_stem: String = server
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let started_at = chrono::Utc::now();
+    let path = dir.join(format!(
+        "{file_stem}-{}.{MCP_RECORDING_EXTENSION}",
+        started_at.format("%Y%m%d-%H%M%S%3f")
+    ));
+    let mut file = OpenOptions::new()
+        .create_new(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to create {}: {e}", path.display()))?;
+    let header = json!({ "server": server, "startedAt": started_at.to_rfc3339() });
+    writeln!(file, "{header}").map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    active.insert(
+        server.to_string(),
+        ActiveRecording {
+            path: path.clone(),
+            file,
+        },
+    );
+    Ok(path)
+}
+
+/// Stop recording `server`; returns the session file when it was being recorded
+pub fn stop_recording(server: &str) -> Option<PathBuf> {
+    recordings().remove(server).map(|recording| recording.path)
+}
+
+pub fn is_recording(server: &str) -> bool {
+    recordings().contains_key(server)
+}
+
+/// Session files currently being written
+pub fn active_recording_paths() -> Vec<PathBuf> {
+    recordings()
+        .values()
+        .map(|recording| recording.path.clone())
+        .collect()
+}
+
+/// Append a frame to the session file of `server` if it is being recorded
+pub fn record_session_frame(server: &str, direction: &str, message: &impl Serialize) {
+    let mut active = recordings();
+    let Some(recording) = active.get_mut(server) else {
+        return;
+    };
+    let frame = McpWireFrame {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        direction: direction.to_string(),
+        message: serde_json::to_value(message).unwrap_or(Value::Null),
+    };
+    let result = serde_json::to_string(&frame)
+        .map_err(|e| e.to_string())
+        .and_then(|line| writeln!(recording.file, "{line}").map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        log::warn!("Failed to record a frame of MCP server {server}: {e}");
+    }
+}
+
+/// The session files in the recordings folder, newest first
+pub fn list_recordings(data_folder: &Path) -> Vec<McpRecording> {
+    let Ok(entries) = fs::read_dir(recordings_dir(data_folder)) else {
+        return Vec::new();
+    };
+    let mut sessions: Vec<McpRecording> = entries
+        .flatten()
+        .filter(|entry| {
+            entry.path().extension().and_then(|ext| ext.to_str()) == Some(MCP_RECORDING_EXTENSION)
+        })
+        .filter_map(|entry| {
+            let data = fs::read_to_string(entry.path()).ok()?;
+            let mut lines = data.lines();
+            let header: Value = serde_json::from_str(lines.next()?).ok()?;
+            Some(McpRecording {
+                name: entry.file_name().to_string_lossy().into_owned(),
+                server: header.get("server")?.as_str()?.to_string(),
+                started_at: header
+                    .get("startedAt")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                frames: lines.filter(|line| !line.trim().is_empty()).count(),
+            })
+        })
+        .collect();
+    sessions.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    sessions
+}
+
+/// A request sent during a recorded session and the response it got
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedExchange {
+    pub method: String,
+    pub params: Value,
+    pub response: Value,
+}
+
+/// Params as compared during replay: `_meta` carries per-call data like progress tokens
+fn comparable_params(params: Option<&Value>) -> Value {
+    let mut params = params.cloned().unwrap_or(Value::Null);
+    if let Some(params) = params.as_object_mut() {
+        params.remove("_meta");
+    }
+    params
+}
+
+/// Pair the requests of a session file with their responses, in the order they were sent
+pub fn load_exchanges(path: &Path) -> Result<Vec<RecordedExchange>, String> {
+    let data = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read recording {}: {e}", path.display()))?;
+    let mut pending: HashMap<String, (String, Value)> = HashMap::new();
+    let mut exchanges = Vec::new();
+    // The header line has no direction and is skipped like any malformed line
+    for frame in data
+        .lines()
+        .filter_map(|line| serde_json::from_str::<McpWireFrame>(line).ok())
+    {
+        let message = &frame.message;
+        let Some(id) = message.get("id").map(Value::to_string) else {
+            continue;
+        };
+        if frame.direction == MCP_WIRE_SENT {
+            if let Some(method) = message.get("method").and_then(Value::as_str) {
+                pending.insert(
+                    id,
+                    (method.to_string(), comparable_params(message.get("params"))),
+                );
+            }
+        } else if frame.direction == MCP_WIRE_RECEIVED
+            && (message.get("result").is_some() || message.get("error").is_some())
+        {
+            if let Some((method, params)) = pending.remove(&id) {
+                exchanges.push(RecordedExchange {
+                    method,
+                    params,
+                    response: message.clone(),
+                });
+            }
+        }
+    }
+    Ok(exchanges)
+}
+
+/// Answers requests from the exchanges of a session
+#[derive(Debug, Clone)]
+pub struct Replay {
+    server: String,
+    exchanges: Vec<RecordedExchange>,
+    used: Vec<bool>,
+}
+
+impl Replay {
+    pub fn new(server: &str, exchanges: Vec<RecordedExchange>) -> Self {
+        Self {
+            server: server.to_string(),
+            used: vec![false; exchanges.len()],
+            exchanges,
+        }
+    }
+
+    /// The response to a request: the first unused recorded exchange with the same method
+    /// and params, else with the same method, else the last one of the method again.
+    /// Notifications and responses to server requests get no answer.
+    pub fn respond(&mut self, request: &Value) -> Option<Value> {
+        let id = request.get("id")?.clone();
+        let method = request.get("method")?.as_str()?;
+        let params = comparable_params(request.get("params"));
+        let unused = |index: &usize| !self.used[*index];
+        let same_method = |index: &usize| self.exchanges[*index].method == method;
+        let index = (0..self.exchanges.len())
+            .filter(unused)
+            .filter(same_method)
+            .find(|index| self.exchanges[*index].params == params)
+            .or_else(|| (0..self.exchanges.len()).filter(unused).find(same_method))
+            .or_else(|| (0..self.exchanges.len()).rev().find(same_method));
+
+        let mut response = match index {
+            Some(index) => {
+                self.used[index] = true;
+                self.exchanges[index].response.clone()
+            }
+            None => self.fallback_response(method, request),
+        };
+        if let Some(response) = response.as_object_mut() {
+            response.insert("id".to_string(), id);
+        }
+        Some(response)
+    }
+
+    /// Answer for a request the session has no response to. Sessions recorded after the
+    /// server started lack the handshake, so initialization and pings always succeed.
+    fn fallback_response(&self, method: &str, request: &Value) -> Value {
+        match method {
+            "initialize" => json!({
+                "jsonrpc": "2.0",
+                "result": {
+                    "protocolVersion": request
+                        .pointer("/params/protocolVersion")
+                        .cloned()
+                        .unwrap_or(Value::Null),
+                    "capabilities": { "tools": {} },
+                    "serverInfo": { "name": self.server, "version": "replay" },
+                },
+            }),
+            "ping" => json!({ "jsonrpc": "2.0", "result": {} }),
+            _ => json!({
+                "jsonrpc": "2.0",
+                "error": {
+                    "code": -32601,
+                    "message": format!("No recorded response for {method}"),
+                },
+            }),
+        }
+    }
+}
+
+/// Transport serving a recorded session in place of a real server
+pub struct ReplayTransport {
+    replay: Replay,
+    responses: mpsc::UnboundedSender<Value>,
+    received: mpsc::UnboundedReceiver<Value>,
+}
+
+impl ReplayTransport {
+    pub fn open(server: &str, path: &Path) -> Result<Self, String> {
+        let replay = Replay::new(server, load_exchanges(path)?);
+        let (responses, received) = mpsc::unbounded_channel();
+        Ok(Self {
+            replay,
+            responses,
+            received,
+        })
+    }
+}
+
+impl Transport<RoleClient> for ReplayTransport {
+    type Error = std::io::Error;
+
+    fn send(
+        &mut self,
+        item: TxJsonRpcMessage<RoleClient>,
+    ) -> impl Future<Output = Result<(), Self::Error>> + Send + 'static {
+        let result = serde_json::to_value(&item)
+            .map_err(std::io::Error::other)
+            .map(|request| {
+                if let Some(response) = self.replay.respond(&request) {
+                    // The receiver lives as long as the transport
+                    let _ = self.responses.send(response);
+                }
+            });
+        std::future::ready(result)
+    }
+
+    fn receive(&mut self) -> impl Future<Output = Option<RxJsonRpcMessage<RoleClient>>> + Send {
+        async move {
+            loop {
+                let response = self.received.recv().await?;
+                match serde_json::from_value(response) {
+                    Ok(message) => return Some(message),
+                    Err(e) => log::warn!(
+                        "Skipping a recorded response of MCP server {} that doesn't parse: {e}",
+                        self.replay.server
+                    ),
+                }
+            }
+        }
+    }
+
+    fn close(&mut self) -> impl Future<Output = Result<(), Self::Error>> + Send {
+        self.received.close();
+        std::future::ready(Ok(()))
+    }
+}
@@ -0,0 +1,76 @@
+//! The client side of every MCP session. Jan advertises the folders bound to threads as its
+//! roots, so servers that honour roots (like the reference filesystem server) stay inside
+//! the workspaces, and tells every running server when the bindings change.
+
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+use rmcp::{
+    model::{ClientInfo, ListRootsResult, Root, RootsCapabilities},
+    service::RequestContext,
+    ClientHandler, ErrorData, RoleClient,
+};
+
+use super::helpers::server_peers;
+use crate::core::state::SharedMcpServers;
+use crate::core::workspaces::helpers::bound_workspaces;
+
+/// Handler answering the requests a server sends to Jan
+#[derive(Clone)]
+pub struct JanClient {
+    info: ClientInfo,
+    data_folder: PathBuf,
+}
+
+impl JanClient {
+    /// A client introducing itself with `info`, with roots support added
+    pub fn new(data_folder: &Path, mut info: ClientInfo) -> Self {
+        info.capabilities.roots = Some(RootsCapabilities {
+            list_changed: Some(true),
+        });
+        Self {
+            info,
+            data_folder: data_folder.to_path_buf(),
+        }
+    }
+}
+
+impl ClientHandler for JanClient {
+    fn get_info(&self) -> ClientInfo {
+        self.info.clone()
+    }
+
+    fn list_roots(
+        &self,
+        _context: RequestContext<RoleClient>,
+    ) -> impl Future<Output = Result<ListRootsResult, ErrorData>> + Send + '_ {
+        std::future::ready(Ok(ListRootsResult {
+            roots: workspace_roots(&self.data_folder),
+        }))
+    }
+}
+
+/// The workspaces bound to threads, as `file://` roots
+pub fn workspace_roots(data_folder: &Path) -> Vec<Root> {
+    bound_workspaces(data_folder)
+        .into_iter()
+        .filter_map(|folder| {
+            let uri = url::Url::from_directory_path(&folder).ok()?;
+            Some(Root {
+                uri: uri.to_string(),
+                name: folder
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned()),
+            })
+        })
+        .collect()
+}
+
+/// Tell every running server to ask for the roots again, after a workspace binding changed
+pub async fn notify_roots_changed(servers: &SharedMcpServers) {
+    for (name, peer) in server_peers(servers).await {
+        if let Err(e) = peer.notify_roots_list_changed().await {
+            log::debug!("Failed to notify MCP server {name} of changed roots: {e}");
+        }
+    }
+}
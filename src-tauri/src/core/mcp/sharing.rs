@@ -0,0 +1,331 @@
+//! Sharing MCP setups between machines. An export carries every configured server, with the
+//! values of secret env vars and headers replaced by `{{secret:<NAME>}}` placeholders, so a
+//! team can pass a standard tool setup around without passing credentials along. A value
+//! used by several servers gets one placeholder, and an auth scheme such as `Bearer ` stays
+//! in front of it.
+//!
+//! Importing fills the placeholders in again, from the values given, from the local config
+//! of the same server, or by asking the user through `mcp-secret-required`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+use super::constants::{
+    AUTH_SCHEMES, MCP_CONFIG_VERSION, MCP_CONFIG_VERSION_KEY, MCP_EXPORT_FORMAT,
+    MCP_SECRET_REQUIRED_EVENT, MCP_SECRET_TIMEOUT_SECS, SECRET_CONFIG_FIELDS, SECRET_KEY_MARKERS,
+    TEMPLATE_SECRET_PREFIX,
+};
+use super::migrations::migrate_config;
+use super::models::{McpConfigExport, McpConfigImport, McpSecret, McpSecretRequest};
+use crate::core::state::AppState;
+
+/// Whether an env var or header of this name holds a secret
+pub fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_uppercase();
+    SECRET_KEY_MARKERS.iter().any(|marker| key.contains(marker))
+}
+
+pub fn secret_placeholder(name: &str) -> String {
+    format!("{{{{{TEMPLATE_SECRET_PREFIX}{name}}}}}")
+}
+
+/// Names of the secret placeholders in `text`, in order
+pub fn secret_names(text: &str) -> Vec<&str> {
+    let open = format!("{{{{{TEMPLATE_SECRET_PREFIX}");
+    let mut names = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        names.push(&after[..end]);
+        rest = &after[end + 2..];
+    }
+    names
+}
+
+/// `key` as a placeholder name: upper case, with anything but letters and digits as `_`
+fn to_secret_name(key: &str) -> String {
+    key.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Split the auth scheme off a header value
+fn split_scheme(value: &str) -> (&str, &str) {
+    AUTH_SCHEMES
+        .iter()
+        .find_map(|scheme| {
+            value
+                .get(..scheme.len())
+                .filter(|prefix| prefix.eq_ignore_ascii_case(scheme))
+                .map(|prefix| (prefix, &value[scheme.len()..]))
+        })
+        .unwrap_or(("", value))
+}
+
+/// A placeholder name for the secret `value` of `key`: the key itself, unless another secret
+/// already has that name
+fn unique_secret_name(
+    taken: &HashMap<String, String>,
+    server: &str,
+    key: &str,
+    value: &str,
+) -> String {
+    let free = |name: &String| !taken.get(name).is_some_and(|taken| taken != value);
+    let qualified = to_secret_name(&format!("{server}_{key}"));
+    [to_secret_name(key), qualified.clone()]
+        .into_iter()
+        .chain((2..).map(|n| format!("{qualified}_{n}")))
+        .find(free)
+        .unwrap_or(qualified)
+}
+
+fn visit_strings(value: &Value, visit: &mut dyn FnMut(&str)) {
+    match value {
+        Value::String(text) => visit(text),
+        Value::Array(items) => items.iter().for_each(|item| visit_strings(item, visit)),
+        Value::Object(entries) => entries
+            .values()
+            .for_each(|entry| visit_strings(entry, visit)),
+        _ => {}
+    }
+}
+
+fn map_strings(value: &mut Value, map: &mut dyn FnMut(&str) -> String) {
+    match value {
+        Value::String(text) => *text = map(text),
+        Value::Array(items) => items.iter_mut().for_each(|item| map_strings(item, map)),
+        Value::Object(entries) => entries
+            .values_mut()
+            .for_each(|entry| map_strings(entry, map)),
+        _ => {}
+    }
+}
+
+/// The shareable form of `config`
+pub fn export_config(config: &Value) -> McpConfigExport {
+    let mut servers = config
+        .get("mcpServers")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    // Secret values by placeholder name, so a value used by several servers is asked once
+    let mut values: HashMap<String, String> = HashMap::new();
+    for (server, server_config) in servers.iter_mut() {
+        for field in SECRET_CONFIG_FIELDS {
+            let Some(entries) = server_config.get_mut(*field).and_then(Value::as_object_mut) else {
+                continue;
+            };
+            for (key, value) in entries.iter_mut() {
+                let Some(text) = value.as_str() else {
+                    continue;
+                };
+                // Other placeholders are resolved on the machine the server runs on
+                if !is_secret_key(key) || text.contains("{{") {
+                    continue;
+                }
+                let (scheme, secret) = split_scheme(text);
+                if secret.is_empty() {
+                    continue;
+                }
+                let name = unique_secret_name(&values, server, key, secret);
+                let replaced = format!("{scheme}{}", secret_placeholder(&name));
+                values.insert(name, secret.to_string());
+                *value = Value::String(replaced);
+            }
+        }
+    }
+    McpConfigExport {
+        format: MCP_EXPORT_FORMAT.to_string(),
+        version: MCP_CONFIG_VERSION,
+        secrets: required_secrets(&servers),
+        mcp_servers: servers,
+    }
+}
+
+/// Read a shared setup, upgrading servers written by an older Jan
+pub fn parse_export(data: &str) -> Result<McpConfigExport, String> {
+    let mut export: McpConfigExport =
+        serde_json::from_str(data).map_err(|e| format!("Invalid MCP config export: {e}"))?;
+    if export.format != MCP_EXPORT_FORMAT {
+        return Err(format!(
+            "Not an MCP config export: format is '{}'",
+            export.format
+        ));
+    }
+    if export.version > MCP_CONFIG_VERSION {
+        return Err(format!(
+            "The MCP config export has version {}, newer than the supported {MCP_CONFIG_VERSION}",
+            export.version
+        ));
+    }
+    if export.version < MCP_CONFIG_VERSION {
+        let mut config = json!({ "mcpServers": export.mcp_servers });
+        config[MCP_CONFIG_VERSION_KEY] = json!(export.version);
+        migrate_config(&mut config)?;
+        export.mcp_servers = config
+            .get("mcpServers")
+            .and_then(Value::as_object)
+            .cloned()
+            .unwrap_or_default();
+        export.version = MCP_CONFIG_VERSION;
+    }
+    Ok(export)
+}
+
+/// Secrets referenced by the server configs, with the servers using them, sorted by name
+pub fn required_secrets(servers: &Map<String, Value>) -> Vec<McpSecret> {
+    let mut used_by: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (server, config) in servers {
+        visit_strings(config, &mut |text| {
+            for name in secret_names(text) {
+                let servers = used_by.entry(name.to_string()).or_default();
+                if !servers.contains(server) {
+                    servers.push(server.clone());
+                }
+            }
+        });
+    }
+    used_by
+        .into_iter()
+        .map(|(name, servers)| McpSecret { name, servers })
+        .collect()
+}
+
+/// The secret in `value` where `template` holds a single placeholder, e.g. `abc` for
+/// `Bearer {{secret:TOKEN}}` and `Bearer abc`
+fn extract_secret(template: &str, value: &str) -> Option<(String, String)> {
+    let names = secret_names(template);
+    let [name] = names.as_slice() else {
+        return None;
+    };
+    let placeholder = secret_placeholder(name);
+    let start = template.find(&placeholder)?;
+    let secret = value
+        .strip_prefix(&template[..start])?
+        .strip_suffix(&template[start + placeholder.len()..])?;
+    (!secret.is_empty() && !secret.contains("{{")).then(|| (name.to_string(), secret.to_string()))
+}
+
+/// Secrets the local config already has: the values of the env vars and headers of the same
+/// servers where the shared setup has a placeholder
+pub fn local_secrets(servers: &Map<String, Value>, local: &Value) -> HashMap<String, String> {
+    let mut found = HashMap::new();
+    for (server, config) in servers {
+        let Some(local_config) = local.get("mcpServers").and_then(|s| s.get(server)) else {
+            continue;
+        };
+        for field in SECRET_CONFIG_FIELDS {
+            let Some(entries) = config.get(*field).and_then(Value::as_object) else {
+                continue;
+            };
+            for (key, template) in entries {
+                let current = local_config
+                    .get(*field)
+                    .and_then(|local_entries| local_entries.get(key))
+                    .and_then(Value::as_str);
+                let extracted = template
+                    .as_str()
+                    .zip(current)
+                    .and_then(|(template, current)| extract_secret(template, current));
+                if let Some((name, secret)) = extracted {
+                    found.entry(name).or_insert(secret);
+                }
+            }
+        }
+    }
+    found
+}
+
+/// Replace the secret placeholders of the server configs with `secrets`. Fails naming the
+/// secrets without a value, leaving the configs untouched.
+pub fn fill_secrets(
+    servers: &mut Map<String, Value>,
+    secrets: &HashMap<String, String>,
+) -> Result<(), String> {
+    let missing: Vec<String> = required_secrets(servers)
+        .into_iter()
+        .map(|secret| secret.name)
+        .filter(|name| !secrets.contains_key(name))
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!("No value for the secrets {}", missing.join(", ")));
+    }
+    for config in servers.values_mut() {
+        map_strings(config, &mut |text| {
+            secret_names(text)
+                .into_iter()
+                .fold(text.to_string(), |filled, name| {
+                    filled.replace(&secret_placeholder(name), &secrets[name])
+                })
+        });
+    }
+    Ok(())
+}
+
+/// Add the servers of a shared setup to `config`, replacing configured servers of the same
+/// name
+pub fn merge_servers(
+    config: &mut Value,
+    servers: Map<String, Value>,
+) -> Result<McpConfigImport, String> {
+    let configured = config
+        .as_object_mut()
+        .ok_or("MCP config must be a JSON object")?
+        .entry("mcpServers")
+        .or_insert_with(|| json!({}))
+        .as_object_mut()
+        .ok_or("mcpServers is not an object")?;
+    let mut import = McpConfigImport::default();
+    for (name, server) in servers {
+        if configured.insert(name.clone(), server).is_some() {
+            import.replaced.push(name);
+        } else {
+            import.added.push(name);
+        }
+    }
+    Ok(import)
+}
+
+/// Ask the user for a secret through `mcp-secret-required`. `None` when it was declined or
+/// not answered in time.
+pub async fn request_secret<R: Runtime>(app: &AppHandle<R>, secret: &McpSecret) -> Option<String> {
+    let state = app.state::<AppState>();
+    let request_id = Uuid::new_v4().to_string();
+    let (tx, rx) = oneshot::channel();
+    state
+        .mcp_secret_requests
+        .lock()
+        .await
+        .insert(request_id.clone(), tx);
+
+    let request = McpSecretRequest {
+        request_id: request_id.clone(),
+        name: secret.name.clone(),
+        servers: secret.servers.clone(),
+    };
+    if let Err(e) = app.emit(MCP_SECRET_REQUIRED_EVENT, &request) {
+        log::error!("Failed to emit {MCP_SECRET_REQUIRED_EVENT} event: {e}");
+    }
+
+    let value = tokio::time::timeout(Duration::from_secs(MCP_SECRET_TIMEOUT_SECS), rx)
+        .await
+        .ok()
+        .and_then(|answer| answer.ok())
+        .flatten();
+    state.mcp_secret_requests.lock().await.remove(&request_id);
+    value.filter(|value| !value.is_empty())
+}
@@ -0,0 +1,85 @@
+//! Connections to MCP servers listening on a Unix domain socket or a Windows named pipe.
+//! The stream carries the same newline-delimited JSON-RPC as stdio.
+
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use tokio::time::{sleep, Instant};
+
+use super::constants::SOCKET_POLL_INTERVAL_MS;
+
+#[cfg(unix)]
+pub type SocketStream = tokio::net::UnixStream;
+#[cfg(windows)]
+pub type SocketStream = tokio::net::windows::named_pipe::NamedPipeClient;
+
+#[cfg(unix)]
+pub async fn connect_socket(path: &str) -> io::Result<SocketStream> {
+    tokio::net::UnixStream::connect(path).await
+}
+
+#[cfg(windows)]
+pub async fn connect_socket(path: &str) -> io::Result<SocketStream> {
+    tokio::net::windows::named_pipe::ClientOptions::new().open(path)
+}
+
+/// Connect to the socket, retrying until it accepts connections or `wait` has passed.
+/// Used after spawning a server, which creates its socket some time after starting.
+pub async fn wait_for_socket(path: &str, wait: Duration) -> io::Result<SocketStream> {
+    let deadline = Instant::now() + wait;
+    loop {
+        match connect_socket(path).await {
+            Ok(stream) => return Ok(stream),
+            Err(e) if Instant::now() >= deadline => return Err(e),
+            Err(_) => sleep(Duration::from_millis(SOCKET_POLL_INTERVAL_MS)).await,
+        }
+    }
+}
+
+/// Remove a socket file left behind by a server that is no longer running, so a freshly
+/// spawned server can bind it. Returns whether a file was removed. Named pipes vanish with
+/// their server, so this is a no-op on Windows.
+pub async fn remove_stale_socket(path: &str) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileTypeExt;
+
+        let is_socket = std::fs::symlink_metadata(path)
+            .map(|metadata| metadata.file_type().is_socket())
+            .unwrap_or(false);
+        if !is_socket || connect_socket(path).await.is_ok() {
+            return false;
+        }
+        match std::fs::remove_file(path) {
+            Ok(()) => {
+                log::info!("Removed stale MCP socket {path}");
+                true
+            }
+            Err(e) => {
+                log::warn!("Failed to remove stale MCP socket {path}: {e}");
+                false
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+/// Whether `path` is usable as socket location on this platform.
+pub fn validate_socket_path(path: &str) -> Result<(), String> {
+    if path.trim().is_empty() {
+        return Err("Socket path cannot be empty".to_string());
+    }
+    if cfg!(windows) {
+        if !path.starts_with(r"\\.\pipe\") {
+            return Err(format!(r"Named pipe '{path}' must start with \\.\pipe\"));
+        }
+    } else if !Path::new(path).is_absolute() {
+        return Err(format!("Socket path '{path}' must be absolute"));
+    }
+    Ok(())
+}
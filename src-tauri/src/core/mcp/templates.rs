@@ -0,0 +1,143 @@
+//! Placeholders in MCP server configs, resolved each time a server is spawned so configs
+//! don't hardcode machine-specific paths and ports:
+//! - `{{data_dir}}`: the Jan data folder
+//! - `{{thread_workspace}}`: the most recently used thread workspace folder
+//! - `{{port:auto}}`, `{{port:<name>}}`: a free local port, the same one for every
+//!   occurrence of the placeholder within a server config. The port a server got is kept
+//!   in `AppState` and reused on restart while it is still free.
+//!
+//! Unknown placeholders are left untouched.
+
+use std::collections::HashMap;
+use std::net::TcpListener;
+use std::path::Path;
+
+use serde_json::Value;
+
+use super::constants::{
+    MAX_TEMPLATE_PORT_ATTEMPTS, TEMPLATE_DATA_DIR, TEMPLATE_PORT_PREFIX, TEMPLATE_THREAD_WORKSPACE,
+};
+use super::models::McpServerConfig;
+use crate::core::workspaces::helpers::{canonical_workspace, read_store};
+
+/// Values the placeholders of one server config resolve to
+#[derive(Debug, Clone, Default)]
+pub struct TemplateContext {
+    pub data_dir: String,
+    pub thread_workspace: Option<String>,
+    /// Ports by placeholder name (`auto` or the name after `port:`); filled as ports are
+    /// allocated
+    pub ports: HashMap<String, u16>,
+}
+
+/// A local port nothing listens on, as picked by the OS
+pub fn allocate_free_port() -> Result<u16, String> {
+    TcpListener::bind(("127.0.0.1", 0))
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .map_err(|e| format!("Failed to allocate a free port: {e}"))
+}
+
+/// The most recently used workspace folder that still exists
+pub fn latest_workspace(data_folder: &Path) -> Option<String> {
+    read_store(data_folder)
+        .recent
+        .iter()
+        .find_map(|recent| canonical_workspace(&recent.path).ok())
+        .map(|path| path.to_string_lossy().into_owned())
+}
+
+fn resolve_placeholder(
+    name: &str,
+    ctx: &mut TemplateContext,
+    allocate: &mut dyn FnMut() -> Result<u16, String>,
+) -> Result<Option<String>, String> {
+    if name == TEMPLATE_DATA_DIR {
+        return Ok(Some(ctx.data_dir.clone()));
+    }
+    if name == TEMPLATE_THREAD_WORKSPACE {
+        return ctx.thread_workspace.clone().map(Some).ok_or_else(|| {
+            "{{thread_workspace}} is used but no workspace folder has been chosen yet".to_string()
+        });
+    }
+    if let Some(port_name) = name.strip_prefix(TEMPLATE_PORT_PREFIX) {
+        let port_name = port_name.trim();
+        if port_name.is_empty() {
+            return Err("Port placeholders need a name, e.g. {{port:auto}}".to_string());
+        }
+        let port = match ctx.ports.get(port_name) {
+            Some(port) => *port,
+            None => {
+                // The OS may hand out a port again once it is released, so skip ports
+                // another placeholder of this config already got
+                let mut port = allocate()?;
+                for _ in 0..MAX_TEMPLATE_PORT_ATTEMPTS {
+                    if !ctx.ports.values().any(|p| *p == port) {
+                        break;
+                    }
+                    port = allocate()?;
+                }
+                ctx.ports.insert(port_name.to_string(), port);
+                port
+            }
+        };
+        return Ok(Some(port.to_string()));
+    }
+    Ok(None)
+}
+
+/// Replace the placeholders in `text`
+pub fn resolve_template(
+    text: &str,
+    ctx: &mut TemplateContext,
+    allocate: &mut dyn FnMut() -> Result<u16, String>,
+) -> Result<String, String> {
+    let mut resolved = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let name = &rest[start + 2..start + 2 + end];
+        resolved.push_str(&rest[..start]);
+        match resolve_placeholder(name.trim(), ctx, allocate)? {
+            Some(value) => resolved.push_str(&value),
+            None => resolved.push_str(&rest[start..start + end + 4]),
+        }
+        rest = &rest[start + end + 4..];
+    }
+    resolved.push_str(rest);
+    Ok(resolved)
+}
+
+fn resolve_values<'a>(
+    values: impl Iterator<Item = &'a mut Value>,
+    ctx: &mut TemplateContext,
+    allocate: &mut dyn FnMut() -> Result<u16, String>,
+) -> Result<(), String> {
+    for value in values {
+        if let Value::String(text) = value {
+            *text = resolve_template(text, ctx, allocate)?;
+        }
+    }
+    Ok(())
+}
+
+/// Resolve the placeholders in the command, args, env values, url, socket and headers
+pub fn resolve_server_config(
+    config: &mut McpServerConfig,
+    ctx: &mut TemplateContext,
+    allocate: &mut dyn FnMut() -> Result<u16, String>,
+) -> Result<(), String> {
+    config.command = resolve_template(&config.command, ctx, allocate)?;
+    resolve_values(config.args.iter_mut(), ctx, allocate)?;
+    resolve_values(config.envs.values_mut(), ctx, allocate)?;
+    resolve_values(config.headers.values_mut(), ctx, allocate)?;
+    if let Some(url) = &config.url {
+        config.url = Some(resolve_template(url, ctx, allocate)?);
+    }
+    if let Some(socket) = &config.socket {
+        config.socket = Some(resolve_template(socket, ctx, allocate)?);
+    }
+    Ok(())
+}
@@ -0,0 +1,299 @@
+//! Test support: an in-process mock MCP server, and a small deterministic generator for
+//! property tests. The mock listens on a Unix socket, so tests start it through the real
+//! start path with a `"type": "unix"` config that has no command, and it speaks the
+//! newline-delimited JSON-RPC of rmcp's socket and stdio transports. Its tools, latencies
+//! and crashes are configurable, and it keeps the methods it was asked for.
+
+#[cfg(unix)]
+pub use mock_server::{MockMcpServer, MockServerOptions, MockTool};
+
+#[cfg(unix)]
+mod mock_server {
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use serde_json::{json, Value};
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::{UnixListener, UnixStream};
+    use tokio::sync::watch;
+    use tokio::task::JoinHandle;
+
+    /// A tool offered by the mock server
+    #[derive(Debug, Clone)]
+    pub struct MockTool {
+        pub name: String,
+        /// Text the tool answers with; the error text when `fails` is set
+        pub output: String,
+        pub latency: Duration,
+        pub fails: bool,
+    }
+
+    impl MockTool {
+        pub fn new(name: &str, output: &str) -> Self {
+            Self {
+                name: name.to_string(),
+                output: output.to_string(),
+                latency: Duration::ZERO,
+                fails: false,
+            }
+        }
+
+        pub fn with_latency(mut self, latency: Duration) -> Self {
+            self.latency = latency;
+            self
+        }
+
+        pub fn failing(mut self) -> Self {
+            self.fails = true;
+            self
+        }
+    }
+
+    #[derive(Debug, Clone, Default)]
+    pub struct MockServerOptions {
+        pub tools: Vec<MockTool>,
+        /// Delay before answering `tools/list`, which is also the health check
+        pub list_latency: Duration,
+        /// Crash instead of answering the nth `tools/call`, counting from 1
+        pub crash_on_call: Option<usize>,
+    }
+
+    struct MockState {
+        options: Mutex<MockServerOptions>,
+        requests: Mutex<Vec<String>>,
+        calls: AtomicUsize,
+        accepted: AtomicUsize,
+        open: AtomicUsize,
+        /// Bumped to drop every open connection
+        crashes: watch::Sender<usize>,
+    }
+
+    impl MockState {
+        fn crash(&self) {
+            self.crashes.send_modify(|crashes| *crashes += 1);
+        }
+    }
+
+    /// Mock MCP server, stopped when dropped
+    pub struct MockMcpServer {
+        socket: PathBuf,
+        state: Arc<MockState>,
+        listener: JoinHandle<()>,
+    }
+
+    impl MockMcpServer {
+        /// Start listening on a fresh socket. Must be called within a Tokio runtime.
+        pub fn start(options: MockServerOptions) -> Self {
+            // Socket paths are limited to ~100 bytes, so keep the name short
+            let id = uuid::Uuid::new_v4().simple().to_string();
+            let socket = std::env::temp_dir().join(format!("jan-mock-{}.sock", &id[..12]));
+            let listener = UnixListener::bind(&socket).expect("Failed to bind the mock MCP socket");
+            let state = Arc::new(MockState {
+                options: Mutex::new(options),
+                requests: Mutex::new(Vec::new()),
+                calls: AtomicUsize::new(0),
+                accepted: AtomicUsize::new(0),
+                open: AtomicUsize::new(0),
+                crashes: watch::channel(0).0,
+            });
+            let accept_state = state.clone();
+            let listener = tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    accept_state.accepted.fetch_add(1, Ordering::SeqCst);
+                    accept_state.open.fetch_add(1, Ordering::SeqCst);
+                    let state = accept_state.clone();
+                    tokio::spawn(async move {
+                        serve_connection(stream, &state).await;
+                        state.open.fetch_sub(1, Ordering::SeqCst);
+                    });
+                }
+            });
+            Self {
+                socket,
+                state,
+                listener,
+            }
+        }
+
+        pub fn socket(&self) -> String {
+            self.socket.to_string_lossy().into_owned()
+        }
+
+        /// Server config connecting to the mock
+        pub fn config(&self) -> Value {
+            json!({ "type": "unix", "socket": self.socket(), "command": "", "args": [] })
+        }
+
+        pub fn update(&self, f: impl FnOnce(&mut MockServerOptions)) {
+            f(&mut self.state.options.lock().unwrap());
+        }
+
+        /// Drop every open connection, as if the server process died. The mock keeps listening,
+        /// so the server can be started again.
+        pub fn crash(&self) {
+            self.state.crash();
+        }
+
+        /// Methods of the requests received so far, in order
+        pub fn requests(&self) -> Vec<String> {
+            self.state.requests.lock().unwrap().clone()
+        }
+
+        /// Connections accepted so far
+        pub fn connections(&self) -> usize {
+            self.state.accepted.load(Ordering::SeqCst)
+        }
+
+        pub fn open_connections(&self) -> usize {
+            self.state.open.load(Ordering::SeqCst)
+        }
+    }
+
+    impl Drop for MockMcpServer {
+        fn drop(&mut self) {
+            self.listener.abort();
+            self.state.crash();
+            let _ = std::fs::remove_file(&self.socket);
+        }
+    }
+
+    async fn serve_connection(stream: UnixStream, state: &Arc<MockState>) {
+        let (reader, writer) = stream.into_split();
+        let writer = Arc::new(tokio::sync::Mutex::new(writer));
+        let mut lines = BufReader::new(reader).lines();
+        let mut crashes = state.crashes.subscribe();
+        loop {
+            let line = tokio::select! {
+                _ = crashes.changed() => return,
+                line = lines.next_line() => match line {
+                    Ok(Some(line)) => line,
+                    _ => return,
+                },
+            };
+            let Ok(request) = serde_json::from_str::<Value>(&line) else {
+                continue;
+            };
+            // Notifications and responses need no answer
+            let (Some(id), Some(method)) = (
+                request.get("id").cloned(),
+                request.get("method").and_then(Value::as_str),
+            ) else {
+                continue;
+            };
+            state.requests.lock().unwrap().push(method.to_string());
+            let Some((latency, mut response)) = respond(state, method, &request) else {
+                state.crash();
+                return;
+            };
+            response["jsonrpc"] = json!("2.0");
+            response["id"] = id;
+
+            // Answer concurrently, so a slow call doesn't hold up the others
+            let writer = writer.clone();
+            let mut crashes = state.crashes.subscribe();
+            tokio::spawn(async move {
+                tokio::select! {
+                    _ = crashes.changed() => {}
+                    _ = tokio::time::sleep(latency) => {
+                        let line = format!("{response}\n");
+                        let _ = writer.lock().await.write_all(line.as_bytes()).await;
+                    }
+                }
+            });
+        }
+    }
+
+    /// The latency and body of the response to a request, or `None` to crash
+    fn respond(state: &MockState, method: &str, request: &Value) -> Option<(Duration, Value)> {
+        let options = state.options.lock().unwrap().clone();
+        let response = match method {
+            "initialize" => json!({
+                "result": {
+                    "protocolVersion": request
+                        .pointer("/params/protocolVersion")
+                        .cloned()
+                        .unwrap_or(json!("2025-03-26")),
+                    "capabilities": { "tools": {} },
+                    "serverInfo": { "name": "mock", "version": "1.0.0" },
+                }
+            }),
+            "ping" => json!({ "result": {} }),
+            "tools/list" => {
+                let tools: Vec<Value> = options
+                    .tools
+                    .iter()
+                    .map(|tool| {
+                        json!({
+                            "name": tool.name,
+                            "description": format!("Mock tool {}", tool.name),
+                            "inputSchema": { "type": "object", "properties": {} },
+                        })
+                    })
+                    .collect();
+                return Some((
+                    options.list_latency,
+                    json!({ "result": { "tools": tools } }),
+                ));
+            }
+            "tools/call" => {
+                let call = state.calls.fetch_add(1, Ordering::SeqCst) + 1;
+                if options.crash_on_call == Some(call) {
+                    return None;
+                }
+                let name = request.pointer("/params/name").and_then(Value::as_str);
+                let Some(tool) = options
+                    .tools
+                    .iter()
+                    .find(|tool| Some(tool.name.as_str()) == name)
+                else {
+                    return Some((
+                        Duration::ZERO,
+                        json!({ "error": { "code": -32602, "message": "Unknown tool" } }),
+                    ));
+                };
+                let result = json!({
+                    "content": [{ "type": "text", "text": tool.output }],
+                    "isError": tool.fails,
+                });
+                return Some((tool.latency, json!({ "result": result })));
+            }
+            _ => {
+                json!({ "error": { "code": -32601, "message": format!("Unknown method {method}") } })
+            }
+        };
+        Some((Duration::ZERO, response))
+    }
+}
+
+/// Deterministic xorshift generator for property tests, so failures reproduce
+pub struct TestRng(u64);
+
+impl TestRng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A number in `0..bound`
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound.max(1) as u64) as usize
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    /// Up to `max_parts` fragments joined together
+    pub fn text(&mut self, fragments: &[&str], max_parts: usize) -> String {
+        let parts = self.below(max_parts + 1);
+        (0..parts).map(|_| *self.pick(fragments)).collect()
+    }
+}
@@ -8,14 +8,14 @@ use std::io::Write;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tauri::{test::mock_app, Manager};
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, RwLock};
 
 #[tokio::test]
 async fn test_run_mcp_commands() {
     let app = mock_app();
 
     // Register AppState so state::<AppState>() calls succeed
-    let servers_state: SharedMcpServers = Arc::new(Mutex::new(HashMap::new()));
+    let servers_state: SharedMcpServers = Arc::new(RwLock::new(HashMap::new()));
     app.manage(AppState {
         mcp_servers: servers_state.clone(),
         ..Default::default()
@@ -45,8 +45,8 @@ async fn test_run_mcp_commands() {
     std::fs::remove_file(&config_path).expect("Failed to remove config file");
 }
 
-#[test]
-fn test_add_server_config_new_file() {
+#[tokio::test]
+async fn test_add_server_config_new_file() {
     let app = mock_app();
     let app_path = get_jan_data_folder_path(app.handle().clone());
     let config_path = app_path.join("mcp_config_test_new.json");
@@ -75,7 +75,8 @@ fn test_add_server_config_new_file() {
         "test_server".to_string(),
         server_value.clone(),
         Some("mcp_config_test_new.json"),
-    );
+    )
+    .await;
 
     assert!(result.is_ok(), "Failed to add server config: {result:?}");
 
@@ -96,8 +97,8 @@ fn test_add_server_config_new_file() {
     std::fs::remove_file(&config_path).expect("Failed to remove config file");
 }
 
-#[test]
-fn test_add_server_config_existing_servers() {
+#[tokio::test]
+async fn test_add_server_config_existing_servers() {
     let app = mock_app();
     let app_path = get_jan_data_folder_path(app.handle().clone());
     let config_path = app_path.join("mcp_config_test_existing.json");
@@ -139,7 +140,8 @@ fn test_add_server_config_existing_servers() {
         "new_server".to_string(),
         new_server_value,
         Some("mcp_config_test_existing.json"),
-    );
+    )
+    .await;
 
     assert!(result.is_ok(), "Failed to add server config: {result:?}");
 
@@ -163,8 +165,8 @@ fn test_add_server_config_existing_servers() {
     std::fs::remove_file(&config_path).expect("Failed to remove config file");
 }
 
-#[test]
-fn test_add_server_config_missing_config_file() {
+#[tokio::test]
+async fn test_add_server_config_missing_config_file() {
     let app = mock_app();
     let app_path = get_jan_data_folder_path(app.handle().clone());
 
@@ -187,7 +189,7 @@ fn test_add_server_config_missing_config_file() {
         "active": false
     });
 
-    let result = add_server_config(app.handle().clone(), "test".to_string(), server_value);
+    let result = add_server_config(app.handle().clone(), "test".to_string(), server_value).await;
 
     assert!(
         result.is_err(),
@@ -293,7 +295,7 @@ async fn test_background_cleanup_with_empty_state() {
     use super::helpers::background_cleanup_mcp_servers;
 
     let app = mock_app();
-    let servers_state: SharedMcpServers = Arc::new(Mutex::new(HashMap::new()));
+    let servers_state: SharedMcpServers = Arc::new(RwLock::new(HashMap::new()));
     app.manage(AppState {
         mcp_servers: servers_state.clone(),
         ..Default::default()
@@ -302,7 +304,7 @@ async fn test_background_cleanup_with_empty_state() {
     let state = app.state::<AppState>();
     background_cleanup_mcp_servers(app.handle(), &state).await;
 
-    let servers = state.mcp_servers.lock().await;
+    let servers = state.mcp_servers.read().await;
     assert!(servers.is_empty());
 
     let active = state.mcp_active_servers.lock().await;
@@ -314,7 +316,7 @@ async fn test_stop_mcp_servers_with_context_empty_servers() {
     use super::helpers::{stop_mcp_servers_with_context, ShutdownContext};
 
     let app = mock_app();
-    let servers_state: SharedMcpServers = Arc::new(Mutex::new(HashMap::new()));
+    let servers_state: SharedMcpServers = Arc::new(RwLock::new(HashMap::new()));
     app.manage(AppState {
         mcp_servers: servers_state.clone(),
         ..Default::default()
@@ -332,7 +334,7 @@ async fn test_stop_mcp_servers_prevents_concurrent_shutdown() {
     use super::helpers::{stop_mcp_servers_with_context, ShutdownContext};
 
     let app = mock_app();
-    let servers_state: SharedMcpServers = Arc::new(Mutex::new(HashMap::new()));
+    let servers_state: SharedMcpServers = Arc::new(RwLock::new(HashMap::new()));
     app.manage(AppState {
         mcp_servers: servers_state.clone(),
         ..Default::default()
@@ -407,3 +409,1536 @@ fn test_extension_connected_response_detection() {
         );
     }
 }
+
+#[test]
+fn test_extract_socket_config() {
+    use super::helpers::extract_command_args;
+
+    let config = serde_json::json!({
+        "type": "unix",
+        "socket": "/tmp/server.sock",
+        "command": "",
+        "args": [],
+        "env": {}
+    });
+    let params = extract_command_args(&config).unwrap();
+    assert_eq!(params.transport_type.as_deref(), Some("unix"));
+    assert_eq!(params.socket.as_deref(), Some("/tmp/server.sock"));
+}
+
+#[test]
+fn test_health_check_config() {
+    use super::helpers::{extract_command_args, health_check_config};
+    use super::models::HealthProbe;
+
+    // Local servers are pinged often and dropped on the first failure
+    let local = health_check_config(None, None);
+    assert_eq!(local.probe, HealthProbe::Ping);
+    assert_eq!(local.interval, Duration::from_secs(5));
+    assert_eq!(local.failure_threshold, 1);
+
+    // Remote servers are pinged less often and get a few chances
+    let remote = health_check_config(None, Some("http"));
+    assert_eq!(remote.probe, HealthProbe::Ping);
+    assert_eq!(remote.interval, Duration::from_secs(30));
+    assert_eq!(remote.failure_threshold, 3);
+    assert_eq!(health_check_config(None, Some("sse")), remote);
+
+    let config = serde_json::json!({
+        "type": "http",
+        "url": "https://mcp.example.com/mcp",
+        "command": "",
+        "args": [],
+        "healthCheck": { "probe": "list_tools", "intervalSeconds": 60, "failureThreshold": 0 }
+    });
+    let health = extract_command_args(&config).unwrap().health_check;
+    assert_eq!(health.probe, HealthProbe::ListTools);
+    assert_eq!(health.interval, Duration::from_secs(60));
+    assert_eq!(health.timeout, remote.timeout);
+    // A threshold of zero would never let a check pass
+    assert_eq!(health.failure_threshold, 1);
+
+    let off = health_check_config(Some(&serde_json::json!({ "probe": "none" })), None);
+    assert_eq!(off.probe, HealthProbe::None);
+    // A zero interval and an invalid entry keep the defaults
+    assert_eq!(
+        health_check_config(Some(&serde_json::json!({ "intervalSeconds": 0 })), None),
+        local
+    );
+    assert_eq!(
+        health_check_config(Some(&serde_json::json!({ "probe": "telnet" })), None),
+        local
+    );
+}
+
+#[test]
+fn test_remote_reconnect_policy() {
+    use super::helpers::{is_remote_server_config, reconnect_delay};
+
+    // Reconnects back off quickly and stay well below a restart's backoff
+    assert_eq!(reconnect_delay(0), Duration::from_millis(250));
+    assert_eq!(reconnect_delay(1), Duration::from_millis(500));
+    assert_eq!(reconnect_delay(3), Duration::from_secs(2));
+    assert_eq!(reconnect_delay(4), Duration::from_secs(4));
+    assert_eq!(reconnect_delay(u32::MAX), Duration::from_secs(4));
+
+    let remote = |transport: &str| {
+        serde_json::json!({
+            "type": transport,
+            "url": "https://mcp.example.com/mcp",
+            "command": "",
+            "args": []
+        })
+    };
+    assert!(is_remote_server_config(&remote("http")));
+    assert!(is_remote_server_config(&remote("sse")));
+    assert!(!is_remote_server_config(&remote("stdio")));
+    assert!(!is_remote_server_config(&serde_json::json!({
+        "command": "npx",
+        "args": ["-y", "exa-mcp-server"]
+    })));
+}
+
+#[test]
+fn test_mcp_config_export_and_import() {
+    use super::sharing::{
+        export_config, fill_secrets, local_secrets, merge_servers, parse_export, required_secrets,
+    };
+
+    let config = serde_json::json!({
+        "version": 1,
+        "mcpServers": {
+            "exa": {
+                "command": "npx",
+                "args": ["-y", "exa-mcp-server"],
+                "env": { "EXA_API_KEY": "exa-123", "LOG_LEVEL": "info" },
+                "active": true
+            },
+            "docs": {
+                "type": "http",
+                "url": "https://mcp.example.com/mcp",
+                "headers": { "Authorization": "Bearer docs-456" },
+                "command": "",
+                "args": []
+            },
+            "search": {
+                "command": "npx",
+                "args": [],
+                "env": { "EXA_API_KEY": "other-789", "DATA": "{{data_dir}}" }
+            }
+        }
+    });
+
+    let export = export_config(&config);
+    let servers = &export.mcp_servers;
+    assert_eq!(
+        servers["exa"]["env"]["EXA_API_KEY"],
+        "{{secret:EXA_API_KEY}}"
+    );
+    // Settings that aren't secrets and other placeholders are shared as they are
+    assert_eq!(servers["exa"]["env"]["LOG_LEVEL"], "info");
+    assert_eq!(servers["search"]["env"]["DATA"], "{{data_dir}}");
+    // The auth scheme stays; a different value of a taken name gets a qualified name
+    assert_eq!(
+        servers["docs"]["headers"]["Authorization"],
+        "Bearer {{secret:AUTHORIZATION}}"
+    );
+    assert_eq!(
+        servers["search"]["env"]["EXA_API_KEY"],
+        "{{secret:SEARCH_EXA_API_KEY}}"
+    );
+    let names: Vec<_> = export.secrets.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(
+        names,
+        ["AUTHORIZATION", "EXA_API_KEY", "SEARCH_EXA_API_KEY"]
+    );
+    let data = serde_json::to_string(&export).unwrap();
+    assert!(!data.contains("exa-123") && !data.contains("docs-456"));
+
+    // Importing on a machine that has the docs server takes its token from there
+    let mut imported = parse_export(&data).unwrap();
+    let local = serde_json::json!({
+        "mcpServers": {
+            "docs": { "headers": { "Authorization": "Bearer local-000" } },
+            "mine": { "command": "uvx", "args": [] }
+        }
+    });
+    let mut secrets = local_secrets(&imported.mcp_servers, &local);
+    assert_eq!(secrets.len(), 1);
+    assert_eq!(secrets["AUTHORIZATION"], "local-000");
+    secrets.insert("EXA_API_KEY".to_string(), "team-key".to_string());
+
+    let mut servers = imported.mcp_servers.clone();
+    let missing = fill_secrets(&mut servers, &secrets).unwrap_err();
+    assert!(missing.contains("SEARCH_EXA_API_KEY"));
+    assert_eq!(servers, imported.mcp_servers);
+
+    secrets.insert("SEARCH_EXA_API_KEY".to_string(), "search-key".to_string());
+    fill_secrets(&mut imported.mcp_servers, &secrets).unwrap();
+    assert!(required_secrets(&imported.mcp_servers).is_empty());
+    assert_eq!(
+        imported.mcp_servers["docs"]["headers"]["Authorization"],
+        "Bearer local-000"
+    );
+    assert_eq!(
+        imported.mcp_servers["exa"]["env"]["EXA_API_KEY"],
+        "team-key"
+    );
+
+    let mut local = local;
+    let import = merge_servers(&mut local, imported.mcp_servers).unwrap();
+    assert_eq!(import.replaced, ["docs"]);
+    assert_eq!(import.added.len(), 2);
+    assert!(local["mcpServers"].get("mine").is_some());
+
+    assert!(parse_export(r#"{"format":"other","version":1,"mcpServers":{}}"#).is_err());
+    assert!(parse_export(r#"{"format":"jan-mcp-config","version":99,"mcpServers":{}}"#).is_err());
+}
+
+#[test]
+fn test_validate_socket_path() {
+    use super::socket::validate_socket_path;
+
+    assert!(validate_socket_path("").is_err());
+    if cfg!(windows) {
+        assert!(validate_socket_path(r"\\.\pipe\jan-mcp").is_ok());
+        assert!(validate_socket_path("jan-mcp").is_err());
+    } else {
+        assert!(validate_socket_path("/tmp/jan-mcp.sock").is_ok());
+        assert!(validate_socket_path("jan-mcp.sock").is_err());
+    }
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_socket_connect_and_stale_cleanup() {
+    use super::socket::{remove_stale_socket, wait_for_socket};
+    use std::time::Duration;
+
+    let app = mock_app();
+    let dir = get_jan_data_folder_path(app.handle().clone());
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("mcp.sock");
+    let path_str = path.to_str().unwrap().to_string();
+
+    let listener = tokio::net::UnixListener::bind(&path).unwrap();
+    // A live socket is left alone and accepts connections
+    assert!(!remove_stale_socket(&path_str).await);
+    assert!(wait_for_socket(&path_str, Duration::from_secs(1))
+        .await
+        .is_ok());
+
+    // Once the listener is gone the file is stale
+    drop(listener);
+    assert!(path.exists());
+    assert!(remove_stale_socket(&path_str).await);
+    assert!(!path.exists());
+    assert!(wait_for_socket(&path_str, Duration::from_millis(300))
+        .await
+        .is_err());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+fn committed_mcp_bindings_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(super::bindings::MCP_BINDINGS_PATH)
+}
+
+#[test]
+fn test_mcp_bindings_are_current() {
+    use super::bindings::export_mcp_bindings;
+
+    let path = std::env::temp_dir().join(format!("jan-mcp-bindings-{}.ts", uuid::Uuid::new_v4()));
+    export_mcp_bindings::<tauri::test::MockRuntime>(&path).expect("Failed to export MCP bindings");
+    let exported = std::fs::read_to_string(&path).unwrap();
+    let _ = std::fs::remove_file(&path);
+    assert!(exported.contains("getTools("));
+    assert!(exported.contains("export type McpToolCallResult"));
+    assert!(exported.contains("export type ToolWithServer"));
+
+    let committed = std::fs::read_to_string(committed_mcp_bindings_path()).unwrap_or_default();
+    assert!(
+        committed == exported,
+        "web-app/src/types/mcp-bindings.ts is out of date; run the export with \
+         `cargo test export_mcp_bindings -- --ignored`"
+    );
+}
+
+/// Regenerates the committed bindings; run explicitly with `--ignored`
+#[test]
+#[ignore]
+fn export_mcp_bindings() {
+    super::bindings::export_mcp_bindings::<tauri::test::MockRuntime>(
+        &committed_mcp_bindings_path(),
+    )
+    .expect("Failed to export MCP bindings");
+}
+
+#[test]
+fn test_migrate_config_v0_layout() {
+    use super::constants::MCP_CONFIG_VERSION;
+    use super::migrations::{config_version, migrate_config};
+
+    let mut config = serde_json::json!({
+        "mcpServers": {
+            "remote": {"transport": "streamable-http", "url": "https://example.com/mcp"},
+            "local": {"command": "npx", "args": ["-y", "server"], "envs": {"KEY": "1"}}
+        }
+    });
+    assert_eq!(migrate_config(&mut config), Ok(Some(0)));
+    assert_eq!(config_version(&config), MCP_CONFIG_VERSION);
+
+    let remote = &config["mcpServers"]["remote"];
+    assert_eq!(remote["type"], "http");
+    assert_eq!(remote["command"], "");
+    assert_eq!(remote["args"], serde_json::json!([]));
+    assert!(remote.get("transport").is_none());
+    assert_eq!(config["mcpServers"]["local"]["env"]["KEY"], "1");
+    assert!(config["mcpServers"]["local"].get("envs").is_none());
+
+    // Current documents are left alone
+    let before = config.clone();
+    assert_eq!(migrate_config(&mut config), Ok(None));
+    assert_eq!(config, before);
+}
+
+#[test]
+fn test_migrate_config_newer_and_invalid() {
+    use super::migrations::migrate_config;
+
+    let mut newer = serde_json::json!({"version": 99, "mcpServers": {"a": {"transport": "sse"}}});
+    let before = newer.clone();
+    assert_eq!(migrate_config(&mut newer), Ok(None));
+    assert_eq!(newer, before);
+
+    let mut empty = serde_json::json!({});
+    assert_eq!(migrate_config(&mut empty), Ok(Some(0)));
+    assert!(empty["mcpServers"].is_object());
+
+    assert!(migrate_config(&mut serde_json::json!([])).is_err());
+}
+
+#[test]
+fn test_default_mcp_config_is_current() {
+    use super::constants::{DEFAULT_MCP_CONFIG, MCP_CONFIG_VERSION};
+    use super::migrations::config_version;
+
+    let config: serde_json::Value = serde_json::from_str(DEFAULT_MCP_CONFIG).unwrap();
+    assert_eq!(config_version(&config), MCP_CONFIG_VERSION);
+}
+
+#[test]
+fn test_load_config_backs_up_and_rewrites() {
+    use super::migrations::{config_version, load_config};
+
+    let dir = std::env::temp_dir().join(format!("jan-mcp-migrate-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("mcp_config.json");
+    let original = r#"{"mcpServers": {"a": {"transport": "sse", "url": "http://localhost:1"}}}"#;
+    std::fs::write(&path, original).unwrap();
+
+    let config = load_config(&path).unwrap();
+    assert_eq!(config["mcpServers"]["a"]["type"], "sse");
+
+    let stored: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(stored, config);
+    let backups = || {
+        std::fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .filter(|name| name.starts_with("mcp_config.json.v0-") && name.ends_with(".bak"))
+            .collect::<Vec<_>>()
+    };
+    let found = backups();
+    assert_eq!(found.len(), 1);
+    assert_eq!(
+        std::fs::read_to_string(dir.join(&found[0])).unwrap(),
+        original
+    );
+
+    // An upgraded file is not backed up again
+    assert_eq!(config_version(&load_config(&path).unwrap()), 1);
+    assert_eq!(backups().len(), 1);
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_prometheus_metrics_rendering() {
+    use super::metrics::{McpMetrics, ToolCallOutcome};
+    use std::time::Duration;
+
+    let mut metrics = McpMetrics::default();
+    metrics.record_tool_call("exa", ToolCallOutcome::Success, Duration::from_millis(80));
+    metrics.record_tool_call("exa", ToolCallOutcome::Success, Duration::from_secs(3));
+    metrics.record_tool_call("exa", ToolCallOutcome::Timeout, Duration::from_secs(120));
+    metrics.record_restart("exa");
+    metrics.record_reconnect("exa");
+    metrics.record_reconnect("exa");
+    metrics.record_health_check_failure("my \"server\"");
+
+    let text = metrics.render();
+    assert!(text.contains("# TYPE jan_mcp_tool_call_duration_seconds histogram"));
+    assert!(text.contains("jan_mcp_tool_calls_total{server=\"exa\",outcome=\"success\"} 2"));
+    assert!(text.contains("jan_mcp_tool_calls_total{server=\"exa\",outcome=\"timeout\"} 1"));
+    // Buckets are cumulative and +Inf covers calls above every bound
+    assert!(
+        text.contains("jan_mcp_tool_call_duration_seconds_bucket{server=\"exa\",le=\"0.05\"} 0")
+    );
+    assert!(text.contains("jan_mcp_tool_call_duration_seconds_bucket{server=\"exa\",le=\"0.1\"} 1"));
+    assert!(text.contains("jan_mcp_tool_call_duration_seconds_bucket{server=\"exa\",le=\"5\"} 2"));
+    assert!(text.contains("jan_mcp_tool_call_duration_seconds_bucket{server=\"exa\",le=\"60\"} 2"));
+    assert!(
+        text.contains("jan_mcp_tool_call_duration_seconds_bucket{server=\"exa\",le=\"+Inf\"} 3")
+    );
+    assert!(text.contains("jan_mcp_tool_call_duration_seconds_count{server=\"exa\"} 3"));
+    assert!(text.contains("jan_mcp_server_restarts_total{server=\"exa\"} 1"));
+    assert!(text.contains("jan_mcp_server_reconnects_total{server=\"exa\"} 2"));
+    assert!(text.contains("jan_mcp_health_check_failures_total{server=\"my \\\"server\\\"\"} 1"));
+}
+
+#[test]
+fn test_tool_call_outcome_classification() {
+    use super::metrics::ToolCallOutcome;
+
+    let ok: Result<bool, String> = Ok(false);
+    assert_eq!(ToolCallOutcome::of(&ok, |e| *e), ToolCallOutcome::Success);
+    assert_eq!(
+        ToolCallOutcome::of(&Ok(true), |e| *e),
+        ToolCallOutcome::Error
+    );
+    let timed_out: Result<bool, String> =
+        Err("Tool call 'x' timed out after 30 seconds".to_string());
+    assert_eq!(
+        ToolCallOutcome::of(&timed_out, |e| *e),
+        ToolCallOutcome::Timeout
+    );
+    let cancelled: Result<bool, String> = Err("Tool call 'x' was cancelled".to_string());
+    assert_eq!(
+        ToolCallOutcome::of(&cancelled, |e| *e),
+        ToolCallOutcome::Cancelled
+    );
+}
+
+#[test]
+fn test_admin_route_parsing() {
+    use super::admin::{is_admin_path, parse_route, AdminRoute};
+
+    assert!(is_admin_path("/mcp/servers"));
+    assert!(is_admin_path("/mcp/servers/exa/logs"));
+    assert!(!is_admin_path("/mcp/serversx"));
+    assert_eq!(parse_route("GET", "/mcp/servers/"), Some(AdminRoute::List));
+    assert_eq!(
+        parse_route("GET", "/mcp/servers/Jan%20Browser%20MCP"),
+        Some(AdminRoute::Status("Jan Browser MCP".to_string()))
+    );
+    assert_eq!(
+        parse_route("GET", "/mcp/servers/exa/logs"),
+        Some(AdminRoute::Logs("exa".to_string()))
+    );
+    assert_eq!(
+        parse_route("POST", "/mcp/servers/exa/restart"),
+        Some(AdminRoute::Restart("exa".to_string()))
+    );
+    assert_eq!(parse_route("GET", "/mcp/servers/exa/restart"), None);
+    assert_eq!(parse_route("POST", "/mcp/servers/exa/reload"), None);
+    assert_eq!(parse_route("GET", "/mcp/servers/bad%zz"), None);
+}
+
+#[tokio::test]
+async fn test_admin_requests() {
+    use super::admin::{handle_admin_request, McpAdmin, McpAdminHandle};
+    use super::models::McpServerStatus;
+
+    #[derive(Default)]
+    struct FakeAdmin {
+        running: std::sync::Mutex<bool>,
+    }
+
+    #[async_trait::async_trait]
+    impl McpAdmin for FakeAdmin {
+        async fn servers(&self) -> Result<Vec<McpServerStatus>, String> {
+            Ok(vec![McpServerStatus {
+                name: "exa".to_string(),
+                enabled: true,
+                running: *self.running.lock().unwrap(),
+                pid: None,
+                restarts: 0,
+                health_check_failures: 0,
+                dropped_log_lines: 0,
+                log_muted: false,
+            }])
+        }
+
+        async fn start(&self, _name: &str) -> Result<(), String> {
+            *self.running.lock().unwrap() = true;
+            Ok(())
+        }
+
+        async fn stop(&self, _name: &str) -> Result<(), String> {
+            *self.running.lock().unwrap() = false;
+            Ok(())
+        }
+    }
+
+    let handle = McpAdminHandle::new(FakeAdmin::default());
+    let (status, _) = handle_admin_request(&handle, "GET", "/mcp/servers", None, false).await;
+    assert_eq!(status, 403);
+    let (status, _) = handle_admin_request(
+        &McpAdminHandle::default(),
+        "GET",
+        "/mcp/servers",
+        None,
+        true,
+    )
+    .await;
+    assert_eq!(status, 503);
+
+    let (status, body) =
+        handle_admin_request(&handle, "POST", "/mcp/servers/exa/start", None, true).await;
+    assert_eq!((status, body["started"].as_str()), (200, Some("exa")));
+    let (status, body) = handle_admin_request(&handle, "GET", "/mcp/servers", None, true).await;
+    assert_eq!(status, 200);
+    assert_eq!(body[0]["running"], true);
+
+    let (status, _) =
+        handle_admin_request(&handle, "POST", "/mcp/servers/missing/stop", None, true).await;
+    assert_eq!(status, 404);
+    let (status, body) =
+        handle_admin_request(&handle, "POST", "/mcp/servers/exa/restart", None, true).await;
+    assert_eq!((status, body["restarted"].as_str()), (200, Some("exa")));
+    let (status, body) = handle_admin_request(
+        &handle,
+        "GET",
+        "/mcp/servers/exa/logs",
+        Some("limit=5"),
+        true,
+    )
+    .await;
+    assert_eq!(status, 200);
+    assert!(body.is_array());
+}
+
+#[test]
+fn test_resolve_template_placeholders() {
+    use super::templates::{resolve_template, TemplateContext};
+
+    let mut next = 40000;
+    let mut allocate = || {
+        next += 1;
+        Ok(next)
+    };
+    let mut ctx = TemplateContext {
+        data_dir: "/data/jan".to_string(),
+        thread_workspace: None,
+        ports: HashMap::new(),
+    };
+
+    let resolved = resolve_template(
+        "--db={{data_dir}}/db --port {{port:auto}} --again {{ port:auto }} --admin {{port:admin}}",
+        &mut ctx,
+        &mut allocate,
+    )
+    .unwrap();
+    assert_eq!(
+        resolved,
+        "--db=/data/jan/db --port 40001 --again 40001 --admin 40002"
+    );
+    assert_eq!(ctx.ports.get("auto"), Some(&40001));
+    assert_eq!(ctx.ports.get("admin"), Some(&40002));
+
+    // Unknown and unterminated placeholders are kept as written
+    assert_eq!(
+        resolve_template("{{user}} {{data_dir", &mut ctx, &mut allocate).unwrap(),
+        "{{user}} {{data_dir"
+    );
+    assert!(resolve_template("{{thread_workspace}}", &mut ctx, &mut allocate).is_err());
+    assert!(resolve_template("{{port:}}", &mut ctx, &mut allocate).is_err());
+}
+
+#[test]
+fn test_resolve_server_config_skips_taken_ports() {
+    use super::helpers::health_check_config;
+    use super::models::McpServerConfig;
+    use super::templates::{resolve_server_config, TemplateContext};
+
+    let mut handed_out = vec![5000, 5000, 5001].into_iter();
+    let mut allocate = || handed_out.next().ok_or_else(|| "exhausted".to_string());
+    let mut ctx = TemplateContext {
+        data_dir: "/data".to_string(),
+        thread_workspace: Some("/work".to_string()),
+        ports: HashMap::new(),
+    };
+    let mut config = McpServerConfig {
+        transport_type: Some("http".to_string()),
+        url: Some("http://127.0.0.1:{{port:auto}}/mcp".to_string()),
+        socket: None,
+        command: "server".to_string(),
+        args: vec![
+            serde_json::json!("{{thread_workspace}}"),
+            serde_json::json!(3),
+        ],
+        envs: serde_json::json!({ "BRIDGE_PORT": "{{port:bridge}}" })
+            .as_object()
+            .unwrap()
+            .clone(),
+        timeout: None,
+        headers: serde_json::Map::new(),
+        bridge_ports: Vec::new(),
+        version: None,
+        replay: None,
+        health_check: health_check_config(None, Some("http")),
+    };
+
+    resolve_server_config(&mut config, &mut ctx, &mut allocate).unwrap();
+    assert_eq!(config.url.as_deref(), Some("http://127.0.0.1:5001/mcp"));
+    assert_eq!(config.args[0], "/work");
+    assert_eq!(config.args[1], 3);
+    assert_eq!(config.envs["BRIDGE_PORT"], "5000");
+}
+
+#[test]
+fn test_bridge_ports_and_replacement() {
+    use super::helpers::extract_command_args;
+    use super::ports::{bridge_ports, next_free_port};
+    use std::collections::HashSet;
+
+    let config = extract_command_args(&serde_json::json!({
+        "command": "npx",
+        "args": [],
+        "env": {
+            "BRIDGE_PORT": "17389",
+            "WS_PORT": 18000,
+            "DB_PORT": "5432",
+            "BAD_PORT": "not a port"
+        },
+        "bridgePorts": ["WS_PORT", "BAD_PORT", "BRIDGE_PORT"]
+    }))
+    .unwrap();
+    assert_eq!(
+        bridge_ports(&config),
+        vec![
+            ("BRIDGE_PORT".to_string(), 17389),
+            ("WS_PORT".to_string(), 18000)
+        ]
+    );
+
+    let taken: HashSet<u16> = [17390].into_iter().collect();
+    let busy: HashSet<u16> = [17391].into_iter().collect();
+    assert_eq!(
+        next_free_port(17389, &taken, |port| !busy.contains(&port)),
+        Some(17392)
+    );
+    assert_eq!(next_free_port(17389, &taken, |_| false), None);
+    assert_eq!(next_free_port(u16::MAX, &HashSet::new(), |_| true), None);
+}
+
+#[test]
+fn test_package_version_pinning() {
+    use super::versions::{
+        compare_versions, find_package, pin_package_args, pinned_version, PackageRegistry,
+    };
+    use std::cmp::Ordering;
+
+    let npm = PackageRegistry::for_command("/usr/local/bin/npx").unwrap();
+    let mut args = vec![
+        serde_json::json!("-y"),
+        serde_json::json!("--registry"),
+        serde_json::json!("https://registry.example"),
+        serde_json::json!("@scope/server@latest"),
+        serde_json::json!("--port"),
+    ];
+    let spec = find_package(npm, &args).unwrap();
+    assert_eq!((spec.index, spec.name.as_str()), (3, "@scope/server"));
+    assert_eq!(spec.version.as_deref(), Some("latest"));
+    assert_eq!(pinned_version(&serde_json::json!({}), &spec), None);
+    assert!(pin_package_args(npm, &mut args, "1.4.0"));
+    assert_eq!(args[3], "@scope/server@1.4.0");
+    assert_eq!(
+        npm.latest_url("@scope/server"),
+        "https://registry.npmjs.org/@scope%2Fserver/latest"
+    );
+
+    let pypi = PackageRegistry::for_command("uvx").unwrap();
+    let mut args = vec![
+        serde_json::json!("--from"),
+        serde_json::json!("mcp-server-git[cli]==0.6.1"),
+        serde_json::json!("mcp-server-git"),
+    ];
+    let spec = find_package(pypi, &args).unwrap();
+    assert_eq!((spec.index, spec.name.as_str()), (1, "mcp-server-git[cli]"));
+    assert_eq!(
+        pinned_version(&serde_json::json!({ "version": "0.7.0" }), &spec).as_deref(),
+        Some("0.7.0")
+    );
+    assert!(pin_package_args(pypi, &mut args, "0.7.0"));
+    assert_eq!(args[1], "mcp-server-git[cli]==0.7.0");
+    assert_eq!(
+        pypi.latest_url(&spec.name),
+        "https://pypi.org/pypi/mcp-server-git/json"
+    );
+    assert_eq!(
+        pypi.parse_latest(&serde_json::json!({ "info": { "version": "0.8.0" } })),
+        Some("0.8.0".to_string())
+    );
+    assert!(PackageRegistry::for_command("node").is_none());
+
+    assert_eq!(compare_versions("1.10.0", "1.9.3"), Ordering::Greater);
+    assert_eq!(compare_versions("2.0.0-rc.1", "2.0.0"), Ordering::Less);
+    assert_eq!(compare_versions("1.0", "1.0.0"), Ordering::Equal);
+    assert_eq!(compare_versions("0.7.0", "0.7.0+local"), Ordering::Equal);
+}
+
+#[test]
+fn test_tool_cache_follows_config_hash() {
+    use super::models::ToolWithServer;
+    use super::tool_cache::{
+        cache_path, cached_server_tools, config_hash, prune_tool_cache, remember_server_tools,
+    };
+
+    let data = std::env::temp_dir().join(format!("mcp-tool-cache-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&data).unwrap();
+    let tool = |name: &str| ToolWithServer {
+        name: name.to_string(),
+        description: None,
+        input_schema: serde_json::json!({ "type": "object" }),
+        server: "files".to_string(),
+    };
+
+    let config = serde_json::json!({ "command": "npx", "args": ["server"], "active": true });
+    let hash = config_hash(&config);
+    // Fields that don't change the tools don't change the hash
+    assert_eq!(
+        hash,
+        config_hash(&serde_json::json!({ "args": ["server"], "command": "npx", "active": false }))
+    );
+    assert_ne!(
+        hash,
+        config_hash(&serde_json::json!({ "command": "npx", "args": ["other"] }))
+    );
+
+    assert!(cached_server_tools(&data, "files", &hash).is_none());
+    assert!(remember_server_tools(
+        &data,
+        "files",
+        &hash,
+        &[tool("read")]
+    ));
+    assert!(!remember_server_tools(
+        &data,
+        "files",
+        &hash,
+        &[tool("read")]
+    ));
+    assert!(remember_server_tools(
+        &data,
+        "files",
+        &hash,
+        &[tool("read"), tool("write")]
+    ));
+    assert!(cache_path(&data).exists());
+    assert_eq!(cached_server_tools(&data, "files", &hash).unwrap().len(), 2);
+    assert!(cached_server_tools(&data, "files", "other-hash").is_none());
+
+    prune_tool_cache(&data, &["search".to_string()]);
+    assert!(cached_server_tools(&data, "files", &hash).is_none());
+    std::fs::remove_dir_all(&data).unwrap();
+}
+
+#[test]
+fn test_mcp_settings_startup_defaults() {
+    use super::constants::{DEFAULT_MCP_STARTUP_BUDGET_SECS, DEFAULT_MCP_STARTUP_CONCURRENCY};
+    use super::models::McpSettings;
+
+    // Configs written before the startup settings existed get the defaults
+    let settings: McpSettings = serde_json::from_value(serde_json::json!({
+        "toolCallTimeoutSeconds": 30,
+        "baseRestartDelayMs": 1000,
+        "maxRestartDelayMs": 30000,
+        "backoffMultiplier": 2.0
+    }))
+    .unwrap();
+    assert_eq!(
+        settings.startup_concurrency,
+        DEFAULT_MCP_STARTUP_CONCURRENCY
+    );
+    assert_eq!(
+        settings.startup_budget(),
+        Some(Duration::from_secs(DEFAULT_MCP_STARTUP_BUDGET_SECS))
+    );
+
+    let settings = McpSettings {
+        startup_budget_seconds: 0,
+        ..McpSettings::default()
+    };
+    assert_eq!(settings.startup_budget(), None);
+}
+
+#[test]
+fn test_inspector_wire_log_and_requests() {
+    use super::constants::MAX_MCP_WIRE_FRAMES;
+    use super::inspector::{
+        clear_wire_frames, parse_request, record_wire_frame, set_wire_logging, wire_frames,
+        wire_logging_enabled,
+    };
+    use rmcp::model::ClientRequest;
+
+    let server = "inspector-test";
+    let frame = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": "ping" });
+
+    // Nothing is recorded until logging is enabled
+    record_wire_frame(server, "sent", &frame);
+    assert!(wire_frames(server, 10).is_empty());
+
+    set_wire_logging(server, true);
+    assert!(wire_logging_enabled(server));
+    for _ in 0..MAX_MCP_WIRE_FRAMES + 5 {
+        record_wire_frame(server, "sent", &frame);
+    }
+    record_wire_frame(
+        server,
+        "received",
+        &serde_json::json!({ "id": 1, "result": {} }),
+    );
+    assert_eq!(wire_frames(server, usize::MAX).len(), MAX_MCP_WIRE_FRAMES);
+    let last = wire_frames(server, 1);
+    assert_eq!(last[0].direction, "received");
+
+    // Disabling keeps what was recorded
+    set_wire_logging(server, false);
+    record_wire_frame(server, "sent", &frame);
+    assert_eq!(wire_frames(server, 1)[0].direction, "received");
+    clear_wire_frames(server);
+    assert!(wire_frames(server, 10).is_empty());
+
+    assert!(matches!(
+        parse_request("tools/list", None),
+        Ok(ClientRequest::ListToolsRequest(_))
+    ));
+    assert!(matches!(
+        parse_request("ping", Some(serde_json::Value::Null)),
+        Ok(ClientRequest::PingRequest(_))
+    ));
+    assert!(parse_request("initialize", None).is_err());
+    let call = serde_json::json!({ "name": "read_file", "arguments": {} });
+    assert!(parse_request("tools/call", Some(call)).is_err());
+    assert!(parse_request("no/such/method", None).is_err());
+}
+
+#[test]
+fn test_record_and_replay_session() {
+    use super::recording::{
+        list_recordings, load_exchanges, record_session_frame, recording_path, start_recording,
+        stop_recording, Replay,
+    };
+    use serde_json::json;
+
+    let data = std::env::temp_dir().join(format!("mcp-recording-{}", uuid::Uuid::new_v4()));
+    let server = "recorded/server";
+
+    // Frames of servers that aren't recorded are dropped
+    record_session_frame(server, "sent", &json!({ "id": 0, "method": "ping" }));
+    let path = start_recording(&data, server).unwrap();
+    let frames = [
+        (
+            "sent",
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" }),
+        ),
+        (
+            "sent",
+            json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+        ),
+        (
+            "received",
+            json!({ "jsonrpc": "2.0", "id": 1, "result": { "tools": [{ "name": "a" }] } }),
+        ),
+        (
+            "sent",
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/call",
+                    "params": { "name": "a", "arguments": { "q": 1 }, "_meta": { "progressToken": 7 } } }),
+        ),
+        (
+            "received",
+            json!({ "jsonrpc": "2.0", "id": 2, "result": { "content": [{ "type": "text", "text": "one" }] } }),
+        ),
+        (
+            "sent",
+            json!({ "jsonrpc": "2.0", "id": 3, "method": "tools/call",
+                    "params": { "name": "a", "arguments": { "q": 2 } } }),
+        ),
+        (
+            "received",
+            json!({ "jsonrpc": "2.0", "id": 3, "result": { "content": [{ "type": "text", "text": "two" }] } }),
+        ),
+    ];
+    for (direction, message) in &frames {
+        record_session_frame(server, direction, message);
+    }
+    assert_eq!(stop_recording(server), Some(path.clone()));
+    record_session_frame(server, "sent", &json!({ "id": 9, "method": "ping" }));
+
+    let name = path.file_name().unwrap().to_string_lossy().into_owned();
+    assert_eq!(recording_path(&data, &name), path);
+    let listed = list_recordings(&data);
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].server, server);
+    assert_eq!(listed[0].frames, frames.len());
+
+    let exchanges = load_exchanges(&path).unwrap();
+    assert_eq!(exchanges.len(), 3);
+    let mut replay = Replay::new(server, exchanges);
+
+    // Matched by params, ignoring `_meta`, and answered with the new request id
+    let second = replay
+        .respond(&json!({ "jsonrpc": "2.0", "id": 40, "method": "tools/call",
+                          "params": { "name": "a", "arguments": { "q": 2 } } }))
+        .unwrap();
+    assert_eq!(second["id"], 40);
+    assert_eq!(second["result"]["content"][0]["text"], "two");
+    let first = replay
+        .respond(&json!({ "jsonrpc": "2.0", "id": 41, "method": "tools/call",
+                          "params": { "name": "a", "arguments": { "q": 1 } } }))
+        .unwrap();
+    assert_eq!(first["result"]["content"][0]["text"], "one");
+    // Exhausted methods repeat their last recorded response
+    let again = replay
+        .respond(&json!({ "jsonrpc": "2.0", "id": 42, "method": "tools/call",
+                          "params": { "name": "b" } }))
+        .unwrap();
+    assert_eq!(again["result"]["content"][0]["text"], "two");
+
+    let init = replay
+        .respond(&json!({ "jsonrpc": "2.0", "id": 43, "method": "initialize",
+                          "params": { "protocolVersion": "2025-03-26" } }))
+        .unwrap();
+    assert_eq!(init["result"]["protocolVersion"], "2025-03-26");
+    let missing = replay
+        .respond(&json!({ "jsonrpc": "2.0", "id": 44, "method": "prompts/list" }))
+        .unwrap();
+    assert_eq!(missing["error"]["code"], -32601);
+    assert!(replay
+        .respond(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+        .is_none());
+
+    std::fs::remove_dir_all(&data).ok();
+}
+
+// ============================================================================
+// Mock MCP Server Tests
+// ============================================================================
+
+#[cfg(unix)]
+fn mock_app_with_state() -> (tauri::App<tauri::test::MockRuntime>, SharedMcpServers) {
+    let app = mock_app();
+    let servers_state: SharedMcpServers = Arc::new(RwLock::new(HashMap::new()));
+    app.manage(AppState {
+        mcp_servers: servers_state.clone(),
+        ..Default::default()
+    });
+    (app, servers_state)
+}
+
+/// Poll `condition` until it holds or `wait` has passed
+#[cfg(unix)]
+async fn eventually<F, Fut>(wait: Duration, mut condition: F) -> bool
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = bool>,
+{
+    let deadline = std::time::Instant::now() + wait;
+    while std::time::Instant::now() < deadline {
+        if condition().await {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    condition().await
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_start_mcp_server_with_mock() {
+    use super::helpers::{call_tool_on_server, start_mcp_server};
+    use super::test_support::{MockMcpServer, MockServerOptions, MockTool};
+    use rmcp::model::CallToolRequestParam;
+
+    let mock = MockMcpServer::start(MockServerOptions {
+        tools: vec![
+            MockTool::new("echo", "hello"),
+            MockTool::new("broken", "went wrong").failing(),
+            MockTool::new("slow", "late").with_latency(Duration::from_millis(500)),
+        ],
+        ..Default::default()
+    });
+    let (app, servers_state) = mock_app_with_state();
+
+    start_mcp_server(
+        app.handle().clone(),
+        servers_state.clone(),
+        "mock-start".to_string(),
+        mock.config(),
+    )
+    .await
+    .unwrap();
+    let active = app.state::<AppState>().mcp_active_servers.clone();
+    assert!(active.lock().await.contains_key("mock-start"));
+
+    {
+        let servers = servers_state.read().await;
+        let tools = servers["mock-start"].list_all_tools().await.unwrap();
+        let names: Vec<&str> = tools.iter().map(|tool| &*tool.name).collect();
+        assert_eq!(names, ["echo", "broken", "slow"]);
+    }
+    let requests = mock.requests();
+    assert_eq!(requests[0], "initialize");
+    assert!(requests.contains(&"tools/list".to_string()));
+
+    let call = |name: &str| CallToolRequestParam {
+        name: name.to_string().into(),
+        arguments: Some(serde_json::Map::new()),
+    };
+    let result = call_tool_on_server(
+        &servers_state,
+        "mock-start",
+        call("echo"),
+        Duration::from_secs(2),
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.is_error, Some(false));
+    let result = call_tool_on_server(
+        &servers_state,
+        "mock-start",
+        call("broken"),
+        Duration::from_secs(2),
+    )
+    .await
+    .unwrap();
+    assert_eq!(result.is_error, Some(true));
+    // Latency beyond the timeout surfaces as an error instead of hanging
+    assert!(call_tool_on_server(
+        &servers_state,
+        "mock-start",
+        call("slow"),
+        Duration::from_millis(100)
+    )
+    .await
+    .is_err());
+
+    // A server that isn't listening fails to start
+    let missing = serde_json::json!({
+        "type": "unix",
+        "socket": format!("{}.missing", mock.socket()),
+        "command": "",
+        "args": []
+    });
+    assert!(start_mcp_server(
+        app.handle().clone(),
+        servers_state.clone(),
+        "mock-missing".to_string(),
+        missing,
+    )
+    .await
+    .is_err());
+    assert!(!servers_state.read().await.contains_key("mock-missing"));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_slow_tool_call_does_not_hold_the_servers_lock() {
+    use super::helpers::{call_tool_on_server, start_mcp_server};
+    use super::test_support::{MockMcpServer, MockServerOptions, MockTool};
+    use rmcp::model::CallToolRequestParam;
+
+    let mock = MockMcpServer::start(MockServerOptions {
+        tools: vec![MockTool::new("slow", "late").with_latency(Duration::from_millis(500))],
+        ..Default::default()
+    });
+    let (app, servers_state) = mock_app_with_state();
+    start_mcp_server(
+        app.handle().clone(),
+        servers_state.clone(),
+        "mock-slow".to_string(),
+        mock.config(),
+    )
+    .await
+    .unwrap();
+
+    let servers = servers_state.clone();
+    let call = tokio::spawn(async move {
+        let params = CallToolRequestParam {
+            name: "slow".into(),
+            arguments: Some(serde_json::Map::new()),
+        };
+        call_tool_on_server(&servers, "mock-slow", params, Duration::from_secs(2)).await
+    });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    // Starting or stopping a server needs the write lock, which is free while the call runs
+    let guard = tokio::time::timeout(Duration::from_millis(100), servers_state.write()).await;
+    assert!(guard.is_ok());
+    drop(guard);
+    assert!(!call.is_finished());
+    assert_eq!(call.await.unwrap().unwrap().is_error, Some(false));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_health_monitor_removes_crashed_and_hung_servers() {
+    use super::helpers::{monitor_mcp_server_health, start_mcp_server};
+    use super::test_support::{MockMcpServer, MockServerOptions, MockTool};
+
+    let interval = Duration::from_millis(50);
+    let check_timeout = Duration::from_millis(200);
+    let (app, servers_state) = mock_app_with_state();
+    let options = MockServerOptions {
+        tools: vec![MockTool::new("echo", "hello")],
+        ..Default::default()
+    };
+
+    // A crashed server is noticed by the next health check and removed
+    let crashing = MockMcpServer::start(options.clone());
+    start_mcp_server(
+        app.handle().clone(),
+        servers_state.clone(),
+        "mock-crash".to_string(),
+        crashing.config(),
+    )
+    .await
+    .unwrap();
+    let monitor = tokio::spawn(monitor_mcp_server_health(
+        servers_state.clone(),
+        "mock-crash".to_string(),
+        Arc::new(Mutex::new(false)),
+        interval,
+        check_timeout,
+    ));
+    tokio::time::sleep(interval * 3).await;
+    assert!(servers_state.read().await.contains_key("mock-crash"));
+    crashing.crash();
+    let quit = tokio::time::timeout(Duration::from_secs(5), monitor)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(matches!(quit, Some(rmcp::service::QuitReason::Closed)));
+    assert!(!servers_state.read().await.contains_key("mock-crash"));
+
+    // A server answering slower than the check timeout counts as failed too
+    let hanging = MockMcpServer::start(options.clone());
+    start_mcp_server(
+        app.handle().clone(),
+        servers_state.clone(),
+        "mock-hang".to_string(),
+        hanging.config(),
+    )
+    .await
+    .unwrap();
+    hanging.update(|options| options.list_latency = Duration::from_secs(2));
+    let quit = tokio::time::timeout(
+        Duration::from_secs(5),
+        monitor_mcp_server_health(
+            servers_state.clone(),
+            "mock-hang".to_string(),
+            Arc::new(Mutex::new(false)),
+            interval,
+            check_timeout,
+        ),
+    )
+    .await
+    .unwrap();
+    assert!(quit.is_some());
+    assert!(!servers_state.read().await.contains_key("mock-hang"));
+
+    // The shutdown flag stops monitoring without touching the server
+    let healthy = MockMcpServer::start(options);
+    start_mcp_server(
+        app.handle().clone(),
+        servers_state.clone(),
+        "mock-healthy".to_string(),
+        healthy.config(),
+    )
+    .await
+    .unwrap();
+    let quit = tokio::time::timeout(
+        Duration::from_secs(5),
+        monitor_mcp_server_health(
+            servers_state.clone(),
+            "mock-healthy".to_string(),
+            Arc::new(Mutex::new(true)),
+            interval,
+            check_timeout,
+        ),
+    )
+    .await
+    .unwrap();
+    assert!(quit.is_some());
+    assert!(servers_state.read().await.contains_key("mock-healthy"));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_health_monitor_probe_and_failure_threshold() {
+    use super::helpers::{monitor_mcp_server, start_mcp_server};
+    use super::models::{HealthCheck, HealthProbe};
+    use super::test_support::{MockMcpServer, MockServerOptions, MockTool};
+
+    let (app, servers_state) = mock_app_with_state();
+    let hanging = MockMcpServer::start(MockServerOptions {
+        tools: vec![MockTool::new("echo", "hello")],
+        ..Default::default()
+    });
+    start_mcp_server(
+        app.handle().clone(),
+        servers_state.clone(),
+        "mock-slow-list".to_string(),
+        hanging.config(),
+    )
+    .await
+    .unwrap();
+    hanging.update(|options| options.list_latency = Duration::from_secs(2));
+    let mut health = HealthCheck {
+        probe: HealthProbe::Ping,
+        interval: Duration::from_millis(50),
+        timeout: Duration::from_millis(100),
+        failure_threshold: 3,
+    };
+
+    // Pings are answered even though listing tools is slow, so the server stays
+    let monitored = tokio::time::timeout(
+        Duration::from_millis(400),
+        monitor_mcp_server(
+            servers_state.clone(),
+            "mock-slow-list".to_string(),
+            Arc::new(Mutex::new(false)),
+            health,
+        ),
+    )
+    .await;
+    assert!(monitored.is_err());
+    assert!(servers_state.read().await.contains_key("mock-slow-list"));
+    assert!(hanging.requests().contains(&"ping".to_string()));
+
+    // Without a probe nothing is monitored
+    health.probe = HealthProbe::None;
+    let quit = monitor_mcp_server(
+        servers_state.clone(),
+        "mock-slow-list".to_string(),
+        Arc::new(Mutex::new(false)),
+        health,
+    )
+    .await;
+    assert!(quit.is_none());
+
+    // Listing tools times out, and the server is only dropped after three failures in a row
+    health.probe = HealthProbe::ListTools;
+    let started = std::time::Instant::now();
+    let quit = tokio::time::timeout(
+        Duration::from_secs(5),
+        monitor_mcp_server(
+            servers_state.clone(),
+            "mock-slow-list".to_string(),
+            Arc::new(Mutex::new(false)),
+            health,
+        ),
+    )
+    .await
+    .unwrap();
+    assert!(quit.is_some());
+    assert!(started.elapsed() >= (health.interval + health.timeout) * 3);
+    assert!(!servers_state.read().await.contains_key("mock-slow-list"));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_restart_reconnects_crashed_mock_server() {
+    use super::helpers::{monitor_mcp_server_health, restart_active_mcp_servers, start_mcp_server};
+    use super::test_support::{MockMcpServer, MockServerOptions, MockTool};
+    use rmcp::model::CallToolRequestParam;
+
+    let mock = MockMcpServer::start(MockServerOptions {
+        tools: vec![MockTool::new("echo", "hello")],
+        crash_on_call: Some(1),
+        ..Default::default()
+    });
+    let (app, servers_state) = mock_app_with_state();
+    start_mcp_server(
+        app.handle().clone(),
+        servers_state.clone(),
+        "mock-restart".to_string(),
+        mock.config(),
+    )
+    .await
+    .unwrap();
+
+    // The first call crashes the server
+    let params = CallToolRequestParam {
+        name: "echo".into(),
+        arguments: None,
+    };
+    let service_result = {
+        let servers = servers_state.read().await;
+        tokio::time::timeout(
+            Duration::from_secs(2),
+            servers["mock-restart"].call_tool(params),
+        )
+        .await
+    };
+    assert!(!matches!(service_result, Ok(Ok(_))));
+    monitor_mcp_server_health(
+        servers_state.clone(),
+        "mock-restart".to_string(),
+        Arc::new(Mutex::new(false)),
+        Duration::from_millis(20),
+        Duration::from_millis(200),
+    )
+    .await;
+    assert!(!servers_state.read().await.contains_key("mock-restart"));
+
+    // Restarting brings back the servers that were active
+    restart_active_mcp_servers(app.handle(), servers_state.clone())
+        .await
+        .unwrap();
+    let servers = servers_state.clone();
+    assert!(
+        eventually(Duration::from_secs(5), || {
+            let servers = servers.clone();
+            async move { servers.read().await.contains_key("mock-restart") }
+        })
+        .await
+    );
+    assert_eq!(mock.connections(), 2);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_shutdown_contexts_stop_mock_servers() {
+    use super::helpers::{start_mcp_server, stop_mcp_servers_with_context};
+    use super::test_support::{MockMcpServer, MockServerOptions};
+
+    for context in [
+        ShutdownContext::AppExit,
+        ShutdownContext::ManualRestart,
+        ShutdownContext::FactoryReset,
+    ] {
+        let (app, servers_state) = mock_app_with_state();
+        let mocks: Vec<MockMcpServer> = (0..2)
+            .map(|_| MockMcpServer::start(MockServerOptions::default()))
+            .collect();
+        for (index, mock) in mocks.iter().enumerate() {
+            start_mcp_server(
+                app.handle().clone(),
+                servers_state.clone(),
+                format!("mock-shutdown-{index}"),
+                mock.config(),
+            )
+            .await
+            .unwrap();
+        }
+        assert_eq!(servers_state.read().await.len(), 2);
+
+        let state = app.state::<AppState>();
+        let started = std::time::Instant::now();
+        stop_mcp_servers_with_context(app.handle(), &state, context)
+            .await
+            .unwrap();
+        assert!(started.elapsed() < context.overall_timeout() + Duration::from_secs(1));
+        assert!(servers_state.read().await.is_empty());
+        assert!(!*state.mcp_shutdown_in_progress.lock().await);
+        for mock in &mocks {
+            assert!(
+                eventually(Duration::from_secs(2), || async {
+                    mock.open_connections() == 0
+                })
+                .await
+            );
+        }
+    }
+}
+
+// ============================================================================
+// Property Tests
+// ============================================================================
+
+#[test]
+fn test_template_resolution_properties() {
+    use super::templates::{resolve_template, TemplateContext};
+    use super::test_support::TestRng;
+
+    let fragments = [
+        "{{",
+        "}}",
+        "{",
+        "}",
+        "data_dir",
+        "port:a",
+        "port:b",
+        "port:",
+        "thread_workspace",
+        " ",
+        "x",
+        "/",
+    ];
+    let mut rng = TestRng::new(0x5eed);
+    for _ in 0..2000 {
+        let text = rng.text(&fragments, 12);
+        let mut ctx = TemplateContext {
+            data_dir: "/data".to_string(),
+            thread_workspace: Some("/work".to_string()),
+            ports: HashMap::new(),
+        };
+        let mut next_port = 40000;
+        let mut allocate = || {
+            next_port += 1;
+            Ok::<u16, String>(next_port)
+        };
+        let Ok(resolved) = resolve_template(&text, &mut ctx, &mut allocate) else {
+            // Only a nameless port placeholder is an error
+            assert!(text.contains("port:"), "{text:?}");
+            continue;
+        };
+        if !text.contains("{{") {
+            assert_eq!(resolved, text);
+        }
+        // Resolving again reuses the ports, so a config resolves the same way every time
+        let again = resolve_template(&text, &mut ctx, &mut || {
+            Err::<u16, String>("no more ports".into())
+        });
+        assert_eq!(again.as_deref(), Ok(resolved.as_str()), "{text:?}");
+        // Each port name gets its own port
+        let ports: std::collections::HashSet<u16> = ctx.ports.values().copied().collect();
+        assert_eq!(ports.len(), ctx.ports.len(), "{text:?}");
+    }
+}
+
+#[test]
+fn test_version_parsing_properties() {
+    use super::test_support::TestRng;
+    use super::versions::{compare_versions, find_package, PackageRegistry};
+    use std::cmp::Ordering;
+
+    let version_parts = ["0", "1", "10", "2", ".", "-rc", "+build", "v", "a", ""];
+    let arg_parts = [
+        "-y",
+        "--registry",
+        "--from",
+        "-p",
+        "--",
+        "pkg",
+        "@scope/pkg@1.0",
+        "x==2",
+    ];
+    let mut rng = TestRng::new(42);
+    for _ in 0..2000 {
+        let a = rng.text(&version_parts, 6);
+        let b = rng.text(&version_parts, 6);
+        assert_eq!(compare_versions(&a, &a), Ordering::Equal, "{a:?}");
+        assert_eq!(
+            compare_versions(&a, &b),
+            compare_versions(&b, &a).reverse(),
+            "{a:?} {b:?}"
+        );
+
+        let args: Vec<serde_json::Value> = (0..rng.below(6))
+            .map(|_| serde_json::json!(rng.pick(&arg_parts)))
+            .collect();
+        for registry in [PackageRegistry::Npm, PackageRegistry::Pypi] {
+            if let Some(spec) = find_package(registry, &args) {
+                assert!(spec.index < args.len());
+                assert!(!spec.name.is_empty());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_output_limiter_drops_then_mutes() {
+    use super::constants::{MCP_LOG_BURST_LINES, MCP_LOG_MUTE_DURATION, MCP_LOG_MUTE_THRESHOLD};
+    use super::logs::{Admission, OutputLimiter};
+    use std::time::{Duration, Instant};
+
+    let start = Instant::now();
+    let mut limiter = OutputLimiter::new(start);
+    let burst = MCP_LOG_BURST_LINES as usize;
+    for _ in 0..burst {
+        assert_eq!(limiter.admit(10, start), Admission::Log);
+    }
+    assert_eq!(limiter.admit(10, start), Admission::Drop);
+    let later = start + Duration::from_secs(1);
+    assert_eq!(limiter.admit(10, later), Admission::Log);
+    assert_eq!(limiter.take_dropped(), 1);
+    // Long lines cost more of the budget
+    assert_eq!(limiter.admit(8 * 1024, later), Admission::Log);
+    assert_eq!(limiter.admit(200 * 1024, later), Admission::Drop);
+
+    let mut mutes = 0;
+    for _ in 0..2 * MCP_LOG_MUTE_THRESHOLD {
+        if limiter.admit(10, later) == Admission::Mute {
+            mutes += 1;
+        }
+    }
+    assert_eq!(mutes, 1);
+    // Muted: everything is dropped until the mute ends, then the full burst is back
+    let muted = later + Duration::from_secs(60);
+    assert_eq!(limiter.admit(10, muted), Admission::Drop);
+    let after = later + MCP_LOG_MUTE_DURATION + Duration::from_secs(1);
+    for _ in 0..burst {
+        assert_eq!(limiter.admit(10, after), Admission::Log);
+    }
+    assert!(limiter.take_dropped() > MCP_LOG_MUTE_THRESHOLD);
+}
+
+#[tokio::test]
+async fn test_read_capped_line() {
+    use super::logs::read_capped_line;
+
+    let long = "x".repeat(100);
+    let input = format!("short\r\n{long}\nlast");
+    let mut reader = tokio::io::BufReader::with_capacity(16, input.as_bytes());
+    let mut line = Vec::new();
+
+    assert_eq!(
+        read_capped_line(&mut reader, &mut line, 32).await.unwrap(),
+        Some(6)
+    );
+    assert_eq!(line, b"short\r");
+    assert_eq!(
+        read_capped_line(&mut reader, &mut line, 32).await.unwrap(),
+        Some(100)
+    );
+    assert_eq!(line.len(), 32);
+    assert_eq!(
+        read_capped_line(&mut reader, &mut line, 32).await.unwrap(),
+        Some(4)
+    );
+    assert_eq!(line, b"last");
+    assert_eq!(
+        read_capped_line(&mut reader, &mut line, 32).await.unwrap(),
+        None
+    );
+}
+
+#[test]
+fn test_workspace_roots() {
+    use super::roots::workspace_roots;
+    use crate::core::workspaces::helpers::{canonical_workspace, write_store};
+    use crate::core::workspaces::models::WorkspaceStore;
+
+    let data_dir = std::env::temp_dir().join(format!("jan-roots-{}", uuid::Uuid::new_v4()));
+    let folder = data_dir.join("project");
+    std::fs::create_dir_all(&folder).unwrap();
+    let folder = canonical_workspace(folder.to_str().unwrap()).unwrap();
+    let mut store = WorkspaceStore::default();
+    for thread_id in ["thread-1", "thread-2"] {
+        store
+            .bindings
+            .insert(thread_id.to_string(), folder.to_string_lossy().to_string());
+    }
+    write_store(&data_dir, &store).unwrap();
+
+    // A folder bound to several threads is one root
+    let roots = workspace_roots(&data_dir);
+    assert_eq!(roots.len(), 1);
+    assert!(roots[0].uri.starts_with("file://"));
+    assert_eq!(roots[0].name.as_deref(), Some("project"));
+    let _ = std::fs::remove_dir_all(data_dir);
+}
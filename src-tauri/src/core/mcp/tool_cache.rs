@@ -0,0 +1,134 @@
+//! Last-known tool lists of MCP servers, persisted in `mcp_tool_cache.json` so the tools can
+//! be shown right after startup, before the servers have started and answered. An entry is
+//! only used while the server's config still has the hash it was listed with, and it is
+//! replaced whenever the server lists its tools again.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use super::constants::{TOOL_CACHE_CONFIG_KEYS, TOOL_CACHE_FILE};
+use super::models::ToolWithServer;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CachedServerTools {
+    pub config_hash: String,
+    pub tools: Vec<ToolWithServer>,
+    /// Milliseconds since the Unix epoch
+    pub updated_at: i64,
+}
+
+/// Contents of `mcp_tool_cache.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolCache {
+    #[serde(default)]
+    pub servers: BTreeMap<String, CachedServerTools>,
+}
+
+// Caches by file, read from disk once
+static TOOL_CACHES: OnceLock<Mutex<HashMap<PathBuf, ToolCache>>> = OnceLock::new();
+
+pub fn cache_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(TOOL_CACHE_FILE)
+}
+
+fn with_cache<T>(data_folder: &Path, f: impl FnOnce(&mut ToolCache) -> T) -> T {
+    let path = cache_path(data_folder);
+    let caches = TOOL_CACHES.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut caches = caches.lock().unwrap_or_else(|e| e.into_inner());
+    let cache = caches.entry(path.clone()).or_insert_with(|| {
+        fs::read_to_string(&path)
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default()
+    });
+    f(cache)
+}
+
+fn save(data_folder: &Path, cache: &ToolCache) {
+    let result = serde_json::to_string(cache)
+        .map_err(|e| e.to_string())
+        .and_then(|data| fs::write(cache_path(data_folder), data).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        log::warn!("Failed to save the MCP tool cache: {e}");
+    }
+}
+
+/// Hash of the parts of a server config that decide which tools it offers
+pub fn config_hash(config: &Value) -> String {
+    let relevant: BTreeMap<&str, &Value> = TOOL_CACHE_CONFIG_KEYS
+        .iter()
+        .filter_map(|key| config.get(*key).map(|value| (*key, value)))
+        .collect();
+    // Maps serialize with sorted keys, so equal configs hash equally
+    let canonical = serde_json::to_string(&relevant).unwrap_or_default();
+    hex::encode(Sha256::digest(canonical.as_bytes()))
+}
+
+/// The cached tools of `server`, if they were listed with a config of hash `hash`
+pub fn cached_server_tools(
+    data_folder: &Path,
+    server: &str,
+    hash: &str,
+) -> Option<Vec<ToolWithServer>> {
+    with_cache(data_folder, |cache| {
+        cache
+            .servers
+            .get(server)
+            .filter(|entry| entry.config_hash == hash)
+            .map(|entry| entry.tools.clone())
+    })
+}
+
+fn same_tools(a: &[ToolWithServer], b: &[ToolWithServer]) -> bool {
+    a.len() == b.len()
+        && a.iter().zip(b).all(|(a, b)| {
+            a.name == b.name && a.description == b.description && a.input_schema == b.input_schema
+        })
+}
+
+/// Remember the tools `server` just listed. Returns whether they differ from the cache.
+pub fn remember_server_tools(
+    data_folder: &Path,
+    server: &str,
+    hash: &str,
+    tools: &[ToolWithServer],
+) -> bool {
+    with_cache(data_folder, |cache| {
+        let unchanged = cache
+            .servers
+            .get(server)
+            .is_some_and(|entry| entry.config_hash == hash && same_tools(&entry.tools, tools));
+        if unchanged {
+            return false;
+        }
+        cache.servers.insert(
+            server.to_string(),
+            CachedServerTools {
+                config_hash: hash.to_string(),
+                tools: tools.to_vec(),
+                updated_at: chrono::Utc::now().timestamp_millis(),
+            },
+        );
+        save(data_folder, cache);
+        true
+    })
+}
+
+/// Drop cache entries of servers that are no longer configured
+pub fn prune_tool_cache(data_folder: &Path, configured: &[String]) {
+    with_cache(data_folder, |cache| {
+        let before = cache.servers.len();
+        cache
+            .servers
+            .retain(|server, _| configured.contains(server));
+        if cache.servers.len() != before {
+            save(data_folder, cache);
+        }
+    })
+}
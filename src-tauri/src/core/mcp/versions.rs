@@ -0,0 +1,292 @@
+//! Version pinning of npx/uvx-based servers. A server config may carry a `version`, which
+//! replaces whatever version its package argument names when the server is spawned, so a
+//! new release of the package never gets picked up silently. Update checks ask npm or
+//! PyPI for the newest release.
+
+use std::cmp::Ordering;
+use std::path::Path;
+
+use serde_json::Value;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_http::reqwest;
+
+use super::constants::{
+    NPM_REGISTRY_URL, NPX_VALUE_FLAGS, PYPI_REGISTRY_URL, UVX_VALUE_FLAGS,
+    VERSION_CHECK_TIMEOUT_SECS,
+};
+use super::models::McpPackageVersion;
+use crate::core::offline::helpers::check_url;
+
+/// Where the package of a server comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageRegistry {
+    Npm,
+    Pypi,
+}
+
+impl PackageRegistry {
+    /// The registry of the packages a server command runs, for `npx` and `uvx`
+    pub fn for_command(command: &str) -> Option<Self> {
+        let program = Path::new(command.trim())
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_ascii_lowercase())?;
+        match program.as_str() {
+            "npx" => Some(Self::Npm),
+            "uvx" => Some(Self::Pypi),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Npm => "npm",
+            Self::Pypi => "pypi",
+        }
+    }
+
+    fn value_flags(&self) -> &'static [&'static str] {
+        match self {
+            Self::Npm => NPX_VALUE_FLAGS,
+            Self::Pypi => UVX_VALUE_FLAGS,
+        }
+    }
+
+    /// Flag naming the package explicitly, followed by the spec
+    fn package_flag(&self) -> &'static [&'static str] {
+        match self {
+            Self::Npm => &["-p", "--package"],
+            Self::Pypi => &["--from"],
+        }
+    }
+
+    /// Split a package spec such as `pkg@1.2.0`, `@scope/pkg@latest` or `pkg==1.2.0` into
+    /// the package name and the version it names
+    pub fn split_spec(&self, spec: &str) -> (String, Option<String>) {
+        match self {
+            Self::Npm => match spec.rfind('@') {
+                Some(at) if at > 0 => (spec[..at].to_string(), Some(spec[at + 1..].to_string())),
+                _ => (spec.to_string(), None),
+            },
+            Self::Pypi => {
+                if let Some((name, version)) = spec.split_once("==") {
+                    return (name.trim().to_string(), Some(version.trim().to_string()));
+                }
+                if let Some((name, version)) = spec.split_once('@') {
+                    return (name.trim().to_string(), Some(version.trim().to_string()));
+                }
+                let end = spec
+                    .find(|c: char| "<>=!~;".contains(c))
+                    .unwrap_or(spec.len());
+                (spec[..end].trim().to_string(), None)
+            }
+        }
+    }
+
+    /// The spec running `version` of package `name`
+    pub fn pinned_spec(&self, name: &str, version: &str) -> String {
+        match self {
+            Self::Npm => format!("{name}@{version}"),
+            Self::Pypi => format!("{name}=={version}"),
+        }
+    }
+
+    pub fn latest_url(&self, name: &str) -> String {
+        match self {
+            Self::Npm => format!("{NPM_REGISTRY_URL}/{}/latest", name.replace('/', "%2F")),
+            Self::Pypi => {
+                // Extras like `pkg[cli]` aren't part of the project name
+                let project = name.split('[').next().unwrap_or(name).trim();
+                format!("{PYPI_REGISTRY_URL}/{project}/json")
+            }
+        }
+    }
+
+    /// The newest version in a response from `latest_url`
+    pub fn parse_latest(&self, body: &Value) -> Option<String> {
+        let version = match self {
+            Self::Npm => body.get("version"),
+            Self::Pypi => body.get("info").and_then(|info| info.get("version")),
+        };
+        version.and_then(Value::as_str).map(String::from)
+    }
+}
+
+/// The package argument of a server: its position in the args, the package name and the
+/// version it names
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageSpec {
+    pub index: usize,
+    pub name: String,
+    pub version: Option<String>,
+}
+
+/// Find the package argument among the args of an npx/uvx command: the value of the
+/// explicit package flag, otherwise the first argument that isn't a flag or a flag's value
+pub fn find_package(registry: PackageRegistry, args: &[Value]) -> Option<PackageSpec> {
+    let args: Vec<Option<&str>> = args.iter().map(Value::as_str).collect();
+    let spec_at = |index: usize| {
+        let (name, version) = registry.split_spec(args.get(index).copied().flatten()?);
+        (!name.is_empty()).then_some(PackageSpec {
+            index,
+            name,
+            version,
+        })
+    };
+
+    if let Some(flag) = args
+        .iter()
+        .position(|arg| arg.is_some_and(|arg| registry.package_flag().contains(&arg)))
+    {
+        return spec_at(flag + 1);
+    }
+    let mut index = 0;
+    while index < args.len() {
+        match args[index] {
+            Some("--") => return spec_at(index + 1),
+            Some(arg) if registry.value_flags().contains(&arg) => index += 1,
+            Some(arg) if arg.starts_with('-') => {}
+            Some(_) => return spec_at(index),
+            None => {}
+        }
+        index += 1;
+    }
+    None
+}
+
+/// Whether `version` names one release rather than a tag like `latest`
+pub fn is_exact_version(version: &str) -> bool {
+    version.trim().starts_with(|c: char| c.is_ascii_digit())
+}
+
+/// Rewrite the package argument to run `version`. Returns false when the args have no
+/// package argument.
+pub fn pin_package_args(registry: PackageRegistry, args: &mut [Value], version: &str) -> bool {
+    let Some(spec) = find_package(registry, args) else {
+        return false;
+    };
+    args[spec.index] = Value::String(registry.pinned_spec(&spec.name, version.trim()));
+    true
+}
+
+fn release_parts(version: &str) -> (Vec<u64>, bool) {
+    let version = version.trim().trim_start_matches('v');
+    let mut parts = Vec::new();
+    let mut prerelease = false;
+    for part in version.split('.') {
+        let digits: String = part.chars().take_while(|c| c.is_ascii_digit()).collect();
+        if digits.is_empty() {
+            prerelease = true;
+            break;
+        }
+        parts.push(digits.parse().unwrap_or(0));
+        if digits.len() < part.len() {
+            // `1.0.0-rc.1`, `1.0rc1`, but not build metadata like `1.0.0+local`
+            prerelease = !part[digits.len()..].starts_with('+');
+            break;
+        }
+    }
+    (parts, prerelease)
+}
+
+/// Compare two versions by their numeric release parts; a pre-release sorts before the
+/// release it precedes
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a_parts, a_pre) = release_parts(a);
+    let (b_parts, b_pre) = release_parts(b);
+    let length = a_parts.len().max(b_parts.len());
+    for i in 0..length {
+        let ordering = a_parts
+            .get(i)
+            .unwrap_or(&0)
+            .cmp(b_parts.get(i).unwrap_or(&0));
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    b_pre.cmp(&a_pre)
+}
+
+/// The version a server is pinned to: its `version`, otherwise an exact version in its
+/// package argument
+pub fn pinned_version(config: &Value, spec: &PackageSpec) -> Option<String> {
+    config
+        .get("version")
+        .and_then(Value::as_str)
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .or_else(|| spec.version.clone().filter(|v| is_exact_version(v)))
+}
+
+/// The registry and package argument of a configured server
+pub fn server_package(
+    name: &str,
+    config: &Value,
+) -> Result<(PackageRegistry, PackageSpec), String> {
+    let command = config.get("command").and_then(Value::as_str).unwrap_or("");
+    let registry = PackageRegistry::for_command(command)
+        .ok_or_else(|| format!("MCP server {name} is not run with npx or uvx"))?;
+    let args = config
+        .get("args")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let spec = find_package(registry, args)
+        .ok_or_else(|| format!("MCP server {name} has no package argument"))?;
+    Ok((registry, spec))
+}
+
+/// Ask the registry for the newest version of a package
+pub async fn latest_version<R: Runtime>(
+    app: &AppHandle<R>,
+    registry: PackageRegistry,
+    package: &str,
+) -> Result<String, String> {
+    let url = registry.latest_url(package);
+    check_url(app, &url)?;
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(VERSION_CHECK_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client
+        .get(&url)
+        .header("Accept", "application/json")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to query {} for {package}: {e}", registry.as_str()))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "{} returned {} for {package}",
+            registry.as_str(),
+            response.status()
+        ));
+    }
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid {} response for {package}: {e}", registry.as_str()))?;
+    registry
+        .parse_latest(&body)
+        .ok_or_else(|| format!("{} reported no version for {package}", registry.as_str()))
+}
+
+/// Compare the version a configured server runs with the newest release of its package
+pub async fn check_server_version<R: Runtime>(
+    app: &AppHandle<R>,
+    name: &str,
+    config: &Value,
+) -> Result<McpPackageVersion, String> {
+    let (registry, spec) = server_package(name, config)?;
+    let latest = latest_version(app, registry, &spec.name).await?;
+    let pinned = pinned_version(config, &spec);
+    let update_available = pinned
+        .as_deref()
+        .is_some_and(|pinned| compare_versions(&latest, pinned) == Ordering::Greater);
+    Ok(McpPackageVersion {
+        server: name.to_string(),
+        package: spec.name,
+        registry: registry.as_str().to_string(),
+        pinned,
+        latest,
+        update_available,
+    })
+}
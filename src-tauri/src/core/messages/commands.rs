@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+
+use tauri::State;
+
+use super::helpers::check_template;
+use super::models::{MessageCode, MessageDefinition};
+use super::MessageCatalog;
+
+/// Lists every message the core can produce with its English template and parameters.
+#[tauri::command]
+pub fn get_message_registry() -> Vec<MessageDefinition> {
+    MessageCode::ALL
+        .into_iter()
+        .map(|code| MessageDefinition {
+            code: code.code().to_string(),
+            template: code.template().to_string(),
+            params: code.params().iter().map(|name| name.to_string()).collect(),
+        })
+        .collect()
+}
+
+/// Hands the core the translations of the user's language for the texts it shows itself,
+/// such as native notifications. Called whenever the language changes; codes without a
+/// translation fall back to English.
+#[tauri::command]
+pub fn set_message_catalog(
+    catalog: State<'_, MessageCatalog>,
+    locale: String,
+    messages: HashMap<String, String>,
+) -> Result<(), String> {
+    let mut templates = HashMap::new();
+    for (code, template) in messages {
+        let Some(message) = MessageCode::from_code(&code) else {
+            log::debug!("Ignoring translation of unknown message code {code}");
+            continue;
+        };
+        check_template(message, &template)?;
+        templates.insert(code, template);
+    }
+    log::info!(
+        "Using {} translated backend messages for {locale}",
+        templates.len()
+    );
+    catalog.set(templates);
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use std::collections::BTreeMap;
+
+use tauri::{AppHandle, Manager, Runtime};
+
+use super::models::{LocalizedMessage, MessageCode, MessagePayload};
+use super::MessageCatalog;
+
+/// Names of the `{{name}}` placeholders in `template`, in order of appearance
+pub fn placeholders(template: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        names.push(after[..end].trim());
+        rest = &after[end + 2..];
+    }
+    names
+}
+
+/// Fill the placeholders of `template`; unknown placeholders are kept as they are
+pub fn render(template: &str, params: &BTreeMap<String, String>) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        rendered.push_str(&rest[..start]);
+        match params.get(after[..end].trim()) {
+            Some(value) => rendered.push_str(value),
+            None => rendered.push_str(&rest[start..start + end + 4]),
+        }
+        rest = &after[end + 2..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Check that a translated template only uses the parameters of `code`
+pub fn check_template(code: MessageCode, template: &str) -> Result<(), String> {
+    match placeholders(template)
+        .into_iter()
+        .find(|name| !code.params().contains(name))
+    {
+        Some(name) => Err(format!(
+            "Unknown parameter '{name}' in the template of {}",
+            code.code()
+        )),
+        None => Ok(()),
+    }
+}
+
+/// The message carried by a command error, if it is one
+pub fn parse_message(error: &str) -> Option<MessagePayload> {
+    serde_json::from_str(error).ok()
+}
+
+/// Render `message` in the user's language, or in English without a translation
+pub fn localize<R: Runtime>(app: &AppHandle<R>, message: &LocalizedMessage) -> String {
+    app.try_state::<MessageCatalog>()
+        .and_then(|catalog| catalog.template(message.code.code()))
+        .map(|template| render(&template, &message.params))
+        .unwrap_or_else(|| message.english())
+}
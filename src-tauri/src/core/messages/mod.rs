@@ -0,0 +1,50 @@
+/*!
+   Backend Messages
+
+   User-visible text produced in the core is described by a message code from a fixed registry
+   and its parameters instead of an English sentence, so the frontend can show it in the
+   user's language:
+   - errors are returned as a JSON message `{code, params, message}`, where `message` is the
+     English text for logs and for frontends that don't know the code,
+   - texts the core shows itself, such as native notifications, are rendered from the
+     translations the frontend hands over with `set_message_catalog`, falling back to English.
+
+   The English templates live in `MessageCode` and in the frontend's `en/backend.json`; tests
+   keep the two in sync and keep converted modules free of raw English strings.
+
+   Not every module is converted yet. So far the registry covers the local API server's
+   start and stop errors, the native notifications and progress texts; other commands still
+   return English error strings, which the frontend's `localizeBackendError` shows as they
+   are. A module is converted by moving its user-visible strings to codes and adding it to
+   the `CONVERTED_SOURCES` of the tests.
+*/
+
+pub mod commands;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Translated message templates of the user's language, by message code
+#[derive(Clone, Default)]
+pub struct MessageCatalog {
+    templates: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl MessageCatalog {
+    fn templates(&self) -> MutexGuard<'_, HashMap<String, String>> {
+        self.templates.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn set(&self, templates: HashMap<String, String>) {
+        *self.templates() = templates;
+    }
+
+    pub fn template(&self, code: &str) -> Option<String> {
+        self.templates().get(code).cloned()
+    }
+}
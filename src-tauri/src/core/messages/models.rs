@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::helpers::render;
+
+/// Registry of user-visible backend messages. Each code has an English template with
+/// `{{name}}` placeholders for its parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageCode {
+    ServerAlreadyRunning,
+    ServerInvalidAddress,
+    ServerPortInUse,
+    ServerPortNotPermitted,
+    ServerStartFailed,
+    ServerStopFailed,
+    NotificationResponseReady,
+    NotificationDownloadCompleteTitle,
+    NotificationDownloadCompleteBody,
+    NotificationMcpServerFailedTitle,
+    NotificationMcpServerFailedBody,
+    NotificationScheduledPromptReady,
+    NotificationScheduledPromptFailed,
+    ProgressDownloadDownloading,
+    ProgressDownloadVerifying,
+    ProgressModelPullPreparing,
+    ProgressModelPullDownloading,
+    ProgressModelPullVerifying,
+    ProgressModelLoadStarting,
+    ProgressModelLoadLoadingWeights,
+    ProgressModelLoadCreatingContext,
+    ProgressModelLoadWarmingUp,
+    ProgressQuantizeQuantizing,
+    ProgressImportParsing,
+    ProgressImportImporting,
+    ProgressIndexingRemoving,
+    ProgressIndexingIndexing,
+    ProgressCompleted,
+    ProgressFailed,
+    ProgressCancelled,
+}
+
+impl MessageCode {
+    pub const ALL: [MessageCode; 30] = [
+        MessageCode::ServerAlreadyRunning,
+        MessageCode::ServerInvalidAddress,
+        MessageCode::ServerPortInUse,
+        MessageCode::ServerPortNotPermitted,
+        MessageCode::ServerStartFailed,
+        MessageCode::ServerStopFailed,
+        MessageCode::NotificationResponseReady,
+        MessageCode::NotificationDownloadCompleteTitle,
+        MessageCode::NotificationDownloadCompleteBody,
+        MessageCode::NotificationMcpServerFailedTitle,
+        MessageCode::NotificationMcpServerFailedBody,
+        MessageCode::NotificationScheduledPromptReady,
+        MessageCode::NotificationScheduledPromptFailed,
+        MessageCode::ProgressDownloadDownloading,
+        MessageCode::ProgressDownloadVerifying,
+        MessageCode::ProgressModelPullPreparing,
+        MessageCode::ProgressModelPullDownloading,
+        MessageCode::ProgressModelPullVerifying,
+        MessageCode::ProgressModelLoadStarting,
+        MessageCode::ProgressModelLoadLoadingWeights,
+        MessageCode::ProgressModelLoadCreatingContext,
+        MessageCode::ProgressModelLoadWarmingUp,
+        MessageCode::ProgressQuantizeQuantizing,
+        MessageCode::ProgressImportParsing,
+        MessageCode::ProgressImportImporting,
+        MessageCode::ProgressIndexingRemoving,
+        MessageCode::ProgressIndexingIndexing,
+        MessageCode::ProgressCompleted,
+        MessageCode::ProgressFailed,
+        MessageCode::ProgressCancelled,
+    ];
+
+    /// Stable code, also the key of the message in the frontend's `backend` namespace
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::ServerAlreadyRunning => "server.already_running",
+            Self::ServerInvalidAddress => "server.invalid_address",
+            Self::ServerPortInUse => "server.port_in_use",
+            Self::ServerPortNotPermitted => "server.port_not_permitted",
+            Self::ServerStartFailed => "server.start_failed",
+            Self::ServerStopFailed => "server.stop_failed",
+            Self::NotificationResponseReady => "notification.response_ready",
+            Self::NotificationDownloadCompleteTitle => "notification.download_complete.title",
+            Self::NotificationDownloadCompleteBody => "notification.download_complete.body",
+            Self::NotificationMcpServerFailedTitle => "notification.mcp_server_failed.title",
+            Self::NotificationMcpServerFailedBody => "notification.mcp_server_failed.body",
+            Self::NotificationScheduledPromptReady => "notification.scheduled_prompt.ready",
+            Self::NotificationScheduledPromptFailed => "notification.scheduled_prompt.failed",
+            Self::ProgressDownloadDownloading => "progress.download.downloading",
+            Self::ProgressDownloadVerifying => "progress.download.verifying",
+            Self::ProgressModelPullPreparing => "progress.model_pull.preparing",
+            Self::ProgressModelPullDownloading => "progress.model_pull.downloading",
+            Self::ProgressModelPullVerifying => "progress.model_pull.verifying",
+            Self::ProgressModelLoadStarting => "progress.model_load.starting",
+            Self::ProgressModelLoadLoadingWeights => "progress.model_load.loading_weights",
+            Self::ProgressModelLoadCreatingContext => "progress.model_load.creating_context",
+            Self::ProgressModelLoadWarmingUp => "progress.model_load.warming_up",
+            Self::ProgressQuantizeQuantizing => "progress.quantize.quantizing",
+            Self::ProgressImportParsing => "progress.import.parsing",
+            Self::ProgressImportImporting => "progress.import.importing",
+            Self::ProgressIndexingRemoving => "progress.indexing.removing",
+            Self::ProgressIndexingIndexing => "progress.indexing.indexing",
+            Self::ProgressCompleted => "progress.completed",
+            Self::ProgressFailed => "progress.failed",
+            Self::ProgressCancelled => "progress.cancelled",
+        }
+    }
+
+    pub fn template(self) -> &'static str {
+        match self {
+            Self::ServerAlreadyRunning => "The local API server is already running",
+            Self::ServerInvalidAddress => "{{address}} is not a valid server address",
+            Self::ServerPortInUse => "Port {{port}} is in use by another application",
+            Self::ServerPortNotPermitted => "Jan is not allowed to listen on port {{port}}",
+            Self::ServerStartFailed => "The local API server could not be started: {{reason}}",
+            Self::ServerStopFailed => "The local API server could not be stopped: {{reason}}",
+            Self::NotificationResponseReady => "Response ready",
+            Self::NotificationDownloadCompleteTitle => "Download complete",
+            Self::NotificationDownloadCompleteBody => "{{name}} has finished downloading",
+            Self::NotificationMcpServerFailedTitle => "MCP server failed",
+            Self::NotificationMcpServerFailedBody => "{{name}} could not be started",
+            Self::NotificationScheduledPromptReady => "New results are ready",
+            Self::NotificationScheduledPromptFailed => "Run failed: {{error}}",
+            Self::ProgressDownloadDownloading => "Downloading {{name}}",
+            Self::ProgressDownloadVerifying => "Verifying {{name}}",
+            Self::ProgressModelPullPreparing => "Preparing to pull {{name}}",
+            Self::ProgressModelPullDownloading => "Pulling {{name}}",
+            Self::ProgressModelPullVerifying => "Verifying {{name}}",
+            Self::ProgressModelLoadStarting => "Starting {{name}}",
+            Self::ProgressModelLoadLoadingWeights => "Loading the weights of {{name}}",
+            Self::ProgressModelLoadCreatingContext => "Allocating memory for {{name}}",
+            Self::ProgressModelLoadWarmingUp => "Warming up {{name}}",
+            Self::ProgressQuantizeQuantizing => "Quantizing {{name}}",
+            Self::ProgressImportParsing => "Reading conversations from {{name}}",
+            Self::ProgressImportImporting => "Importing conversations from {{name}}",
+            Self::ProgressIndexingRemoving => "Removing deleted files from {{name}}",
+            Self::ProgressIndexingIndexing => "Indexing files into {{name}}",
+            Self::ProgressCompleted => "{{name}} is done",
+            Self::ProgressFailed => "{{name}} failed",
+            Self::ProgressCancelled => "{{name}} was cancelled",
+        }
+    }
+
+    /// Names of the parameters the template expects
+    pub fn params(self) -> &'static [&'static str] {
+        match self {
+            Self::ServerInvalidAddress => &["address"],
+            Self::ServerPortInUse | Self::ServerPortNotPermitted => &["port"],
+            Self::ServerStartFailed | Self::ServerStopFailed => &["reason"],
+            Self::NotificationDownloadCompleteBody | Self::NotificationMcpServerFailedBody => {
+                &["name"]
+            }
+            Self::NotificationScheduledPromptFailed => &["error"],
+            code if code.code().starts_with("progress.") => &["name"],
+            _ => &[],
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|message| message.code() == code)
+    }
+}
+
+/// A user-visible message: a registered code with its parameters
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalizedMessage {
+    pub code: MessageCode,
+    pub params: BTreeMap<String, String>,
+}
+
+impl LocalizedMessage {
+    pub fn new(code: MessageCode) -> Self {
+        Self {
+            code,
+            params: BTreeMap::new(),
+        }
+    }
+
+    pub fn with(mut self, name: &str, value: impl fmt::Display) -> Self {
+        self.params.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// The message rendered from its English template
+    pub fn english(&self) -> String {
+        render(self.code.template(), &self.params)
+    }
+
+    pub fn payload(&self) -> MessagePayload {
+        MessagePayload {
+            code: self.code.code().to_string(),
+            params: self.params.clone(),
+            message: self.english(),
+        }
+    }
+}
+
+impl fmt::Display for LocalizedMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.english())
+    }
+}
+
+impl std::error::Error for LocalizedMessage {}
+
+/// Commands return messages as their JSON payload in the error string
+impl From<LocalizedMessage> for String {
+    fn from(message: LocalizedMessage) -> Self {
+        let payload = message.payload();
+        serde_json::to_string(&payload).unwrap_or(payload.message)
+    }
+}
+
+/// What the frontend receives for a message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessagePayload {
+    pub code: String,
+    #[serde(default)]
+    pub params: BTreeMap<String, String>,
+    /// English text, shown when the frontend has no translation for `code`
+    pub message: String,
+}
+
+/// A registered message, for translators and for checking locale files
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageDefinition {
+    pub code: String,
+    pub template: String,
+    pub params: Vec<String>,
+}
@@ -0,0 +1,182 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use jan_utils::progress::{ProgressOperation, ProgressPayload, ProgressState};
+use regex::Regex;
+use serde_json::Value;
+use tauri::test::mock_app;
+use tauri::Manager;
+
+use super::commands::get_message_registry;
+use super::helpers::{check_template, localize, parse_message, placeholders, render};
+use super::models::{LocalizedMessage, MessageCode};
+use super::MessageCatalog;
+
+/// English strings of the frontend's `backend` namespace
+const EN_LOCALE: &str = include_str!("../../../../web-app/src/locales/en/backend.json");
+
+/// Modules whose user-visible strings all go through the registry. Only these are checked;
+/// modules that aren't converted yet still return English errors.
+const CONVERTED_SOURCES: [(&str, &str); 2] = [
+    ("server/commands.rs", include_str!("../server/commands.rs")),
+    (
+        "notifications/helpers.rs",
+        include_str!("../notifications/helpers.rs"),
+    ),
+];
+
+fn flatten(prefix: &str, value: &Value, out: &mut BTreeMap<String, String>) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = match prefix {
+                    "" => key.clone(),
+                    prefix => format!("{prefix}.{key}"),
+                };
+                flatten(&key, value, out);
+            }
+        }
+        Value::String(text) => {
+            out.insert(prefix.to_string(), text.clone());
+        }
+        _ => panic!("Unexpected value at {prefix}"),
+    }
+}
+
+/// String literals that read like an English sentence, outside comments and log macros
+fn raw_strings(source: &str) -> Vec<String> {
+    let sentence = Regex::new(r#""[A-Z][a-z]*( [\w{}'.:]+)+"#).unwrap();
+    let mut found = Vec::new();
+    let mut in_log = false;
+    for line in source.lines().map(str::trim) {
+        in_log |= line.contains("log::") || line.contains("println!");
+        if !in_log && !line.starts_with("//") {
+            found.extend(sentence.find_iter(line).map(|m| m.as_str().to_string()));
+        }
+        if line.ends_with(';') {
+            in_log = false;
+        }
+    }
+    found
+}
+
+#[test]
+fn test_registry_templates_match_their_params() {
+    let mut codes = HashSet::new();
+    for code in MessageCode::ALL {
+        assert!(
+            codes.insert(code.code()),
+            "{} is registered twice",
+            code.code()
+        );
+        assert_eq!(MessageCode::from_code(code.code()), Some(code));
+
+        let used: HashSet<&str> = placeholders(code.template()).into_iter().collect();
+        let declared: HashSet<&str> = code.params().iter().copied().collect();
+        assert_eq!(used, declared, "parameters of {}", code.code());
+
+        let message = code
+            .params()
+            .iter()
+            .fold(LocalizedMessage::new(code), |message, name| {
+                message.with(name, "x")
+            });
+        assert!(!message.english().contains("{{"), "{}", code.code());
+    }
+    assert_eq!(get_message_registry().len(), MessageCode::ALL.len());
+}
+
+#[test]
+fn test_english_locale_matches_the_registry() {
+    let mut locale = BTreeMap::new();
+    flatten("", &serde_json::from_str(EN_LOCALE).unwrap(), &mut locale);
+    let registry: BTreeMap<String, String> = MessageCode::ALL
+        .into_iter()
+        .map(|code| (code.code().to_string(), code.template().to_string()))
+        .collect();
+    assert_eq!(locale, registry);
+}
+
+#[test]
+fn test_every_progress_label_is_registered() {
+    let mut labels: Vec<ProgressPayload> = ProgressOperation::ALL
+        .into_iter()
+        .flat_map(|operation| {
+            operation
+                .phases()
+                .iter()
+                .map(move |phase| ProgressPayload::running(operation, "task", "name", phase))
+        })
+        .collect();
+    for state in [
+        ProgressState::Completed,
+        ProgressState::Failed,
+        ProgressState::Cancelled,
+    ] {
+        labels.push(ProgressPayload::finished(
+            ProgressOperation::Download,
+            "task",
+            "name",
+            state,
+        ));
+    }
+    for payload in labels {
+        let code = MessageCode::from_code(&payload.label_key);
+        assert!(code.is_some(), "{} is not registered", payload.label_key);
+        let params: Vec<&str> = payload.label_params.keys().map(String::as_str).collect();
+        assert_eq!(code.unwrap().params(), params.as_slice());
+    }
+}
+
+#[test]
+fn test_converted_modules_have_no_raw_strings() {
+    assert_eq!(
+        raw_strings("return Err(\"Port is in use\".to_string());"),
+        vec!["\"Port is in use".to_string()]
+    );
+    assert!(raw_strings("log::warn!(\n    \"Failed to stop: {e}\"\n);").is_empty());
+    for (path, source) in CONVERTED_SOURCES {
+        assert_eq!(raw_strings(source), Vec::<String>::new(), "{path}");
+    }
+}
+
+#[test]
+fn test_errors_carry_code_params_and_english_text() {
+    let error: String = LocalizedMessage::new(MessageCode::ServerPortInUse)
+        .with("port", 1337)
+        .into();
+    let payload = parse_message(&error).unwrap();
+    assert_eq!(payload.code, "server.port_in_use");
+    assert_eq!(payload.params.get("port").map(String::as_str), Some("1337"));
+    assert_eq!(
+        payload.message,
+        "Port 1337 is in use by another application"
+    );
+    assert!(parse_message("Port 1337 is in use").is_none());
+}
+
+#[test]
+fn test_render_and_translated_templates() {
+    let params = BTreeMap::from([("name".to_string(), "llama".to_string())]);
+    assert_eq!(render("{{name}} / {{ name }}", &params), "llama / llama");
+    assert_eq!(render("{{missing}} {{", &params), "{{missing}} {{");
+
+    let code = MessageCode::NotificationDownloadCompleteBody;
+    assert!(check_template(code, "{{name}} ist heruntergeladen").is_ok());
+    assert!(check_template(code, "{{model}} ist heruntergeladen").is_err());
+
+    let app = mock_app();
+    app.manage(MessageCatalog::default());
+    let message = LocalizedMessage::new(code).with("name", "llama");
+    assert_eq!(
+        localize(app.handle(), &message),
+        "llama has finished downloading"
+    );
+    app.state::<MessageCatalog>().set(HashMap::from([(
+        code.code().to_string(),
+        "{{name}} ist heruntergeladen".to_string(),
+    )]));
+    assert_eq!(
+        localize(app.handle(), &message),
+        "llama ist heruntergeladen"
+    );
+}
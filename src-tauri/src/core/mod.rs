@@ -1,16 +1,83 @@
+pub mod agent;
 pub mod app;
+pub mod approvals;
+pub mod assistants;
+pub mod bookmarks;
+pub mod cancellation;
+pub mod citations;
 #[cfg(feature = "cli")]
 pub mod cli;
+pub mod code_exec;
+pub mod compare;
+pub mod config_history;
+pub mod config_store;
+pub mod context;
 pub mod downloads;
+pub mod embeddings;
+pub mod events;
 pub mod extensions;
 pub mod filesystem;
+pub mod guardrails;
+pub mod importer;
+pub mod inference;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+pub mod instance;
+pub mod knowledge_sync;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+pub mod lan;
+pub mod language;
+pub mod local_text;
+pub mod lora;
 pub mod mcp;
+pub mod messages;
+pub mod model_catalog;
+pub mod model_picker;
+pub mod network;
+pub mod notifications;
+pub mod offline;
+pub mod ollama;
+pub mod onboarding;
 pub mod openclaw;
+pub mod otel;
+pub mod outbox;
+pub mod param_profiles;
+pub mod peers;
+pub mod plugins;
+pub mod power;
+pub mod prompt_cache;
+pub mod prompts;
+pub mod quantize;
+pub mod redaction;
+pub mod rerank;
+pub mod retention;
+pub mod runtimes;
+pub mod scheduled_prompts;
+pub mod scheduler;
+pub mod scratchpad;
+pub mod search;
 pub mod server;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+pub mod service;
+pub mod session_restore;
+pub mod settings;
 pub mod setup;
+pub mod slash_commands;
+pub mod speculative;
 pub mod state;
+pub mod storage;
+pub mod streaming;
+pub mod structured_output;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+pub mod sync;
 pub mod system;
+pub mod telemetry;
+pub mod thread_summaries;
 pub mod threads;
+pub mod tool_artifacts;
 
 #[cfg(not(any(target_os = "android", target_os = "ios")))]
 pub mod updater;
+pub mod vision;
+pub mod watchdog;
+pub mod web_fetch;
+pub mod workspaces;
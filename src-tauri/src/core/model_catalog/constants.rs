@@ -0,0 +1,8 @@
+// Model catalog constants
+pub const LLAMACPP_ENGINE: &str = "llamacpp";
+pub const MODELS_DIR: &str = "models";
+pub const MODEL_YML: &str = "model.yml";
+pub const ADAPTERS_DIR: &str = "adapters";
+pub const ADAPTER_YML: &str = "adapter.yml";
+/// Engines with a local model catalog
+pub const MODEL_ENGINES: &[&str] = &[LLAMACPP_ENGINE, "mlx"];
@@ -0,0 +1,134 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use super::constants::{ADAPTERS_DIR, ADAPTER_YML, MODELS_DIR, MODEL_YML};
+use super::models::{CatalogAdapter, CatalogModel};
+
+/// Root folder holding the models of `engine`
+pub fn models_root(data_folder: &Path, engine: &str) -> PathBuf {
+    data_folder.join(engine).join(MODELS_DIR)
+}
+
+/// Reject ids that would escape the models folder. Ids are relative paths such as `org/repo`.
+pub fn validate_model_id(model_id: &str) -> Result<(), String> {
+    let path = Path::new(model_id);
+    let valid =
+        !model_id.trim().is_empty() && path.components().all(|c| matches!(c, Component::Normal(_)));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid model id '{model_id}'"))
+    }
+}
+
+pub fn model_dir(data_folder: &Path, engine: &str, model_id: &str) -> Result<PathBuf, String> {
+    validate_model_id(model_id)?;
+    Ok(models_root(data_folder, engine).join(model_id))
+}
+
+pub fn read_model(
+    data_folder: &Path,
+    engine: &str,
+    model_id: &str,
+) -> Result<CatalogModel, String> {
+    let path = model_dir(data_folder, engine, model_id)?.join(MODEL_YML);
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Model '{model_id}' not found in the {engine} catalog: {e}"))?;
+    serde_yaml::from_str(&content).map_err(|e| format!("Invalid {}: {e}", path.display()))
+}
+
+/// Write an entry, creating the model folder if needed
+pub fn write_model(
+    data_folder: &Path,
+    engine: &str,
+    model_id: &str,
+    model: &CatalogModel,
+) -> Result<(), String> {
+    let dir = model_dir(data_folder, engine, model_id)?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let yml = serde_yaml::to_string(model).map_err(|e| e.to_string())?;
+    fs::write(dir.join(MODEL_YML), yml).map_err(|e| e.to_string())
+}
+
+/// Resolve a catalog path, which is either absolute or relative to the data folder
+pub fn resolve_catalog_path(data_folder: &Path, path: &str) -> PathBuf {
+    let candidate = Path::new(path);
+    if candidate.is_absolute() {
+        candidate.to_path_buf()
+    } else {
+        data_folder.join(candidate)
+    }
+}
+
+/// Express `path` relative to the data folder when it lives inside it, as the extensions do
+pub fn catalog_path(data_folder: &Path, path: &Path) -> String {
+    path.strip_prefix(data_folder)
+        .map(|rel| {
+            rel.components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .unwrap_or_else(|_| path.to_string_lossy().into_owned())
+}
+
+/// Ids of all models of `engine`; model folders are not searched for nested models
+pub fn list_model_ids(data_folder: &Path, engine: &str) -> Vec<String> {
+    list_entry_ids(&models_root(data_folder, engine), MODEL_YML)
+}
+
+/// Root folder holding the LoRA adapters of `engine`
+pub fn adapters_root(data_folder: &Path, engine: &str) -> PathBuf {
+    data_folder.join(engine).join(ADAPTERS_DIR)
+}
+
+/// Adapter ids follow the same rules as model ids
+pub fn adapter_dir(data_folder: &Path, engine: &str, adapter_id: &str) -> Result<PathBuf, String> {
+    validate_model_id(adapter_id)?;
+    Ok(adapters_root(data_folder, engine).join(adapter_id))
+}
+
+pub fn read_adapter(
+    data_folder: &Path,
+    engine: &str,
+    adapter_id: &str,
+) -> Result<CatalogAdapter, String> {
+    let path = adapter_dir(data_folder, engine, adapter_id)?.join(ADAPTER_YML);
+    let content = fs::read_to_string(&path)
+        .map_err(|e| format!("Adapter '{adapter_id}' not found in the {engine} catalog: {e}"))?;
+    serde_yaml::from_str(&content).map_err(|e| format!("Invalid {}: {e}", path.display()))
+}
+
+/// Write an adapter entry, creating the adapter folder if needed
+pub fn write_adapter(
+    data_folder: &Path,
+    engine: &str,
+    adapter_id: &str,
+    adapter: &CatalogAdapter,
+) -> Result<(), String> {
+    let dir = adapter_dir(data_folder, engine, adapter_id)?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let yml = serde_yaml::to_string(adapter).map_err(|e| e.to_string())?;
+    fs::write(dir.join(ADAPTER_YML), yml).map_err(|e| e.to_string())
+}
+
+pub fn list_adapter_ids(data_folder: &Path, engine: &str) -> Vec<String> {
+    list_entry_ids(&adapters_root(data_folder, engine), ADAPTER_YML)
+}
+
+/// Relative paths of the folders below `root` holding `entry_file`
+fn list_entry_ids(root: &Path, entry_file: &str) -> Vec<String> {
+    let mut ids = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        if dir != root && dir.join(entry_file).is_file() {
+            ids.push(catalog_path(root, &dir));
+            continue;
+        }
+        if let Ok(entries) = fs::read_dir(&dir) {
+            stack.extend(entries.flatten().map(|e| e.path()).filter(|p| p.is_dir()));
+        }
+    }
+    ids.sort();
+    ids
+}
@@ -0,0 +1,19 @@
+/*!
+   Local Model Catalog
+
+   Read and write access to the `model.yml` entries of locally installed models, stored at
+   `<data_folder>/<engine>/models/<model_id>/model.yml`. The frontend engine extensions own
+   most of these files; core services that produce new model files (quantization) or attach
+   per-model settings go through this module so unknown fields written by the extensions are
+   preserved on every rewrite.
+
+   LoRA adapters are catalogued the same way, at
+   `<data_folder>/<engine>/adapters/<adapter_id>/adapter.yml`.
+*/
+
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,65 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A `model.yml` entry. Fields not modelled here are kept in `extra` and written back as-is.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CatalogModel {
+    /// Absolute, or relative to the Jan data folder
+    pub model_path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub size_bytes: u64,
+    #[serde(default)]
+    pub embedding: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mmproj_path: Option<String>,
+    /// Quantization type of the weights, e.g. `Q4_K_M`, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantization: Option<String>,
+    /// Catalog id of the model this one was derived from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_model: Option<String>,
+    /// Draft model pairing for speculative decoding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speculative: Option<SpeculativeSettings>,
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_yaml::Value>,
+}
+
+/// Speculative decoding settings of a model; unset values use the llama-server defaults
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SpeculativeSettings {
+    /// Catalog id of the draft model
+    pub draft_model: String,
+    /// Most tokens drafted per step
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub draft_max: Option<u32>,
+    /// Fewest tokens drafted per step
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub draft_min: Option<u32>,
+    /// Minimum draft token probability to keep drafting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub draft_p_min: Option<f32>,
+}
+
+/// An `adapter.yml` entry describing an installed LoRA adapter
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct CatalogAdapter {
+    /// Absolute, or relative to the Jan data folder
+    pub adapter_path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub size_bytes: u64,
+    /// `general.architecture` of the adapter; only base models of this architecture can use it
+    pub architecture: String,
+    /// Base model named in the adapter metadata, informational only
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alpha: Option<f32>,
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, serde_yaml::Value>,
+}
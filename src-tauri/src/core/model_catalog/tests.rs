@@ -0,0 +1,57 @@
+use std::fs;
+use std::path::PathBuf;
+
+use super::constants::LLAMACPP_ENGINE;
+use super::helpers::{
+    catalog_path, list_model_ids, read_model, resolve_catalog_path, validate_model_id, write_model,
+};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("jan-catalog-{name}-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_validate_model_id() {
+    assert!(validate_model_id("org/repo").is_ok());
+    assert!(validate_model_id("qwen3").is_ok());
+    assert!(validate_model_id("").is_err());
+    assert!(validate_model_id("../outside").is_err());
+    assert!(validate_model_id("/abs/path").is_err());
+}
+
+#[test]
+fn test_model_round_trip_keeps_unknown_fields() {
+    let data = temp_dir("roundtrip");
+    let dir = data.join("llamacpp/models/org/repo");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("model.yml"),
+        "model_path: llamacpp/models/org/repo/model.gguf\nname: repo\nsize_bytes: 42\nembedding: false\nmodel_sha256: abc\n",
+    )
+    .unwrap();
+
+    let mut model = read_model(&data, LLAMACPP_ENGINE, "org/repo").unwrap();
+    assert_eq!(model.size_bytes, 42);
+    model.quantization = Some("Q4_K_M".to_string());
+    write_model(&data, LLAMACPP_ENGINE, "org/repo", &model).unwrap();
+
+    let content = fs::read_to_string(dir.join("model.yml")).unwrap();
+    assert!(content.contains("model_sha256: abc"));
+    assert!(content.contains("quantization: Q4_K_M"));
+    assert_eq!(list_model_ids(&data, LLAMACPP_ENGINE), vec!["org/repo"]);
+
+    let _ = fs::remove_dir_all(data);
+}
+
+#[test]
+fn test_catalog_paths() {
+    let data = PathBuf::from("/data");
+    let file = data.join("llamacpp").join("models").join("m.gguf");
+    assert_eq!(catalog_path(&data, &file), "llamacpp/models/m.gguf");
+    assert_eq!(
+        resolve_catalog_path(&data, "llamacpp/models/m.gguf"),
+        data.join("llamacpp/models/m.gguf")
+    );
+}
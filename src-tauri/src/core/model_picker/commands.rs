@@ -0,0 +1,28 @@
+use tauri::{AppHandle, Runtime};
+
+use super::constants::DEFAULT_CONTEXT_SIZE;
+use super::helpers::{default_candidates, rank_models};
+use super::models::{ModelRecommendations, RecommendModelsRequest};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::onboarding::helpers::scan_hardware;
+
+/// Scores the given models, or the installed ones and the first-run suggestions, against
+/// this machine and returns the best quality, fastest and smallest picks
+#[tauri::command]
+pub async fn recommend_models<R: Runtime>(
+    app_handle: AppHandle<R>,
+    request: Option<RecommendModelsRequest>,
+) -> Result<ModelRecommendations, String> {
+    let request = request.unwrap_or_default();
+    let data_folder = get_jan_data_folder_path(app_handle);
+    tokio::task::spawn_blocking(move || {
+        let hardware = scan_hardware();
+        let candidates = request
+            .candidates
+            .unwrap_or_else(|| default_candidates(&data_folder));
+        let context_size = request.context_size.unwrap_or(DEFAULT_CONTEXT_SIZE);
+        rank_models(&hardware, candidates, context_size)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
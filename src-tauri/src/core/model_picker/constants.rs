@@ -0,0 +1,26 @@
+// Model picker constants
+pub const MIB: u64 = 1024 * 1024;
+/// Compute buffers and runtime state, in MiB, on top of the weights and the KV cache
+pub const RUNTIME_OVERHEAD_MB: u64 = 512;
+/// KV cache, in MiB, per 1024 tokens of context and GiB of weights
+pub const KV_CACHE_MB_PER_1K_TOKENS_PER_GB: u64 = 24;
+/// Context size assumed when the caller does not give one
+pub const DEFAULT_CONTEXT_SIZE: u64 = 8192;
+
+/// Share of the VRAM a model can use; the rest goes to the display and other programs
+pub const GPU_USABLE_PERCENT: u64 = 90;
+/// Share of the RAM a model can use, leaving room for the OS and the app
+pub const RAM_USABLE_PERCENT: u64 = 60;
+/// Share of unified memory (Apple silicon) the GPU can address
+pub const UNIFIED_GPU_PERCENT: u64 = 70;
+
+/// Typical memory bandwidths, in GB/s, used for the speed estimate
+pub const GPU_BANDWIDTH_GBPS: f64 = 300.0;
+pub const UNIFIED_BANDWIDTH_GBPS: f64 = 200.0;
+pub const CPU_BANDWIDTH_GBPS: f64 = 40.0;
+
+/// Below this many tokens per second a model is too slow to chat with
+pub const MIN_USABLE_TOKENS_PER_SEC: f64 = 5.0;
+
+/// Engines whose installed models are candidates
+pub const CANDIDATE_ENGINES: &[&str] = &["llamacpp", "mlx"];
@@ -0,0 +1,186 @@
+use std::fs;
+use std::path::Path;
+
+use super::constants::{
+    CANDIDATE_ENGINES, CPU_BANDWIDTH_GBPS, GPU_BANDWIDTH_GBPS, GPU_USABLE_PERCENT,
+    KV_CACHE_MB_PER_1K_TOKENS_PER_GB, MIB, RAM_USABLE_PERCENT, RUNTIME_OVERHEAD_MB,
+    UNIFIED_BANDWIDTH_GBPS, UNIFIED_GPU_PERCENT,
+};
+use super::models::{MemoryFit, ModelCandidate, ModelRecommendations, ScoredModel};
+use crate::core::model_catalog::helpers::{list_model_ids, read_model, resolve_catalog_path};
+use crate::core::onboarding::constants::RECOMMENDED_MODELS;
+use crate::core::onboarding::helpers::recommended_model;
+use crate::core::onboarding::models::HardwareSummary;
+
+/// Apple silicon, where the GPU shares the RAM
+pub fn is_unified_memory(hardware: &HardwareSummary) -> bool {
+    let os = hardware.os.to_lowercase();
+    (os.contains("mac") || os.contains("darwin"))
+        && matches!(hardware.arch.as_str(), "aarch64" | "arm64")
+}
+
+/// MiB of VRAM a model can use, on the largest GPU
+pub fn gpu_memory_mb(hardware: &HardwareSummary) -> u64 {
+    if is_unified_memory(hardware) {
+        return hardware.total_memory_mb * UNIFIED_GPU_PERCENT / 100;
+    }
+    let vram = hardware
+        .gpus
+        .iter()
+        .map(|gpu| gpu.vram_mb)
+        .max()
+        .unwrap_or(0);
+    vram * GPU_USABLE_PERCENT / 100
+}
+
+/// MiB of RAM a model can use
+pub fn ram_memory_mb(hardware: &HardwareSummary) -> u64 {
+    hardware.total_memory_mb * RAM_USABLE_PERCENT / 100
+}
+
+/// Memory, in MiB, to run a model of `size_bytes` with `context_size` tokens of context
+pub fn required_memory_mb(size_bytes: u64, context_size: u64) -> u64 {
+    let weights = size_bytes.div_ceil(MIB);
+    let kv_cache = KV_CACHE_MB_PER_1K_TOKENS_PER_GB
+        .saturating_mul(context_size)
+        .saturating_mul(size_bytes)
+        / (1024 * 1024 * MIB);
+    weights + kv_cache + RUNTIME_OVERHEAD_MB
+}
+
+pub fn score_model(
+    hardware: &HardwareSummary,
+    candidate: ModelCandidate,
+    context_size: u64,
+) -> ScoredModel {
+    let required = required_memory_mb(candidate.size_bytes, context_size);
+    let unified = is_unified_memory(hardware);
+    let gpu = gpu_memory_mb(hardware);
+    let ram = ram_memory_mb(hardware);
+
+    let (fit, gpu_share) = if gpu > 0 && required <= gpu {
+        (MemoryFit::Gpu, 1.0)
+    } else if gpu > 0 && !unified && required <= gpu + ram {
+        (MemoryFit::Partial, gpu as f64 / required as f64)
+    } else if required <= ram {
+        (MemoryFit::Cpu, 0.0)
+    } else {
+        (MemoryFit::TooLarge, 0.0)
+    };
+
+    let gpu_bandwidth = if unified {
+        UNIFIED_BANDWIDTH_GBPS
+    } else {
+        GPU_BANDWIDTH_GBPS
+    };
+    let tokens_per_sec = if fit == MemoryFit::TooLarge || candidate.size_bytes == 0 {
+        0.0
+    } else {
+        // Every weight is read once per generated token
+        let size_gb = candidate.size_bytes as f64 / 1e9;
+        let seconds =
+            size_gb * (gpu_share / gpu_bandwidth + (1.0 - gpu_share) / CPU_BANDWIDTH_GBPS);
+        (10.0 / seconds).round() / 10.0
+    };
+
+    ScoredModel {
+        candidate,
+        fit,
+        required_memory_mb: required,
+        gpu_percent: (gpu_share * 100.0).floor() as u8,
+        tokens_per_sec,
+    }
+}
+
+/// Score every candidate of known size and pick the tiers
+pub fn rank_models(
+    hardware: &HardwareSummary,
+    candidates: Vec<ModelCandidate>,
+    context_size: u64,
+) -> ModelRecommendations {
+    let mut models: Vec<ScoredModel> = candidates
+        .into_iter()
+        .filter(|candidate| candidate.size_bytes > 0)
+        .map(|candidate| score_model(hardware, candidate, context_size))
+        .collect();
+    models.sort_by(|a, b| {
+        b.usable()
+            .cmp(&a.usable())
+            .then(b.candidate.size_bytes.cmp(&a.candidate.size_bytes))
+            .then(a.candidate.model_id.cmp(&b.candidate.model_id))
+    });
+
+    let fitting = || models.iter().filter(|m| m.fit != MemoryFit::TooLarge);
+    let best_quality = models.iter().find(|m| m.usable()).cloned();
+    let fastest = fitting()
+        .max_by(|a, b| a.tokens_per_sec.total_cmp(&b.tokens_per_sec))
+        .cloned();
+    let smallest = fitting().min_by_key(|m| m.candidate.size_bytes).cloned();
+
+    ModelRecommendations {
+        hardware: hardware.clone(),
+        best_quality,
+        fastest,
+        smallest,
+        models,
+    }
+}
+
+/// The first-run suggestions, as not installed candidates
+pub fn starter_candidates() -> Vec<ModelCandidate> {
+    RECOMMENDED_MODELS
+        .iter()
+        .map(|spec| {
+            let model = recommended_model(spec);
+            ModelCandidate {
+                model_id: model.model_id,
+                size_bytes: model.approx_size_bytes,
+                quantization: None,
+                url: Some(model.url),
+                installed: false,
+            }
+        })
+        .collect()
+}
+
+/// Installed chat models. Entries without a recorded size are measured on disk; embedding
+/// models are left out.
+pub fn installed_candidates(data_folder: &Path) -> Vec<ModelCandidate> {
+    let mut candidates = Vec::new();
+    for engine in CANDIDATE_ENGINES {
+        for model_id in list_model_ids(data_folder, engine) {
+            let Ok(model) = read_model(data_folder, engine, &model_id) else {
+                continue;
+            };
+            if model.embedding {
+                continue;
+            }
+            let size_bytes = if model.size_bytes > 0 {
+                model.size_bytes
+            } else {
+                fs::metadata(resolve_catalog_path(data_folder, &model.model_path))
+                    .map(|meta| if meta.is_file() { meta.len() } else { 0 })
+                    .unwrap_or(0)
+            };
+            candidates.push(ModelCandidate {
+                model_id,
+                size_bytes,
+                quantization: model.quantization,
+                url: None,
+                installed: true,
+            });
+        }
+    }
+    candidates
+}
+
+/// Installed models and the first-run suggestions not yet installed
+pub fn default_candidates(data_folder: &Path) -> Vec<ModelCandidate> {
+    let mut candidates = installed_candidates(data_folder);
+    let starters: Vec<ModelCandidate> = starter_candidates()
+        .into_iter()
+        .filter(|starter| !candidates.iter().any(|c| c.model_id == starter.model_id))
+        .collect();
+    candidates.extend(starters);
+    candidates
+}
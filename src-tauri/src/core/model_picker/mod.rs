@@ -0,0 +1,21 @@
+/*!
+   Hardware-Based Model Picker
+
+   Scores models against the detected hardware and suggests which to run. A model's memory
+   need is its weight size plus the KV cache of the requested context and a fixed runtime
+   overhead. It fits entirely on the GPU, partially with the remaining layers offloaded to
+   RAM, on the CPU alone, or not at all. Generation speed is estimated as memory bandwidth
+   divided by the bytes read per token, which are the weights.
+
+   Candidates are the installed catalog entries plus the first-run suggestions, or those the
+   caller passes, such as the files listed in the model hub. Three picks are returned: the
+   largest model that runs at a usable speed, the fastest, and the smallest.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
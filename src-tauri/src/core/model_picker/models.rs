@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+use crate::core::onboarding::models::HardwareSummary;
+
+/// A model to score
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ModelCandidate {
+    pub model_id: String,
+    /// Size of the weights file
+    pub size_bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantization: Option<String>,
+    /// Download url, for models that are not installed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    pub installed: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct RecommendModelsRequest {
+    /// Models to score; the installed ones and the first-run suggestions when absent
+    pub candidates: Option<Vec<ModelCandidate>>,
+    pub context_size: Option<u64>,
+}
+
+/// Where a model's weights end up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemoryFit {
+    Gpu,
+    /// Split between the GPU and RAM
+    Partial,
+    Cpu,
+    TooLarge,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoredModel {
+    #[serde(flatten)]
+    pub candidate: ModelCandidate,
+    pub fit: MemoryFit,
+    /// Weights, KV cache and runtime overhead
+    pub required_memory_mb: u64,
+    /// Share of the model held in VRAM
+    pub gpu_percent: u8,
+    /// Estimated generation speed, 0 when it does not fit
+    pub tokens_per_sec: f64,
+}
+
+impl ScoredModel {
+    /// Fits in memory and generates fast enough to chat with
+    pub fn usable(&self) -> bool {
+        self.fit != MemoryFit::TooLarge
+            && self.tokens_per_sec >= super::constants::MIN_USABLE_TOKENS_PER_SEC
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelRecommendations {
+    pub hardware: HardwareSummary,
+    /// Largest usable model
+    pub best_quality: Option<ScoredModel>,
+    /// Highest estimated speed among the models that fit
+    pub fastest: Option<ScoredModel>,
+    /// Smallest model that fits
+    pub smallest: Option<ScoredModel>,
+    /// Every candidate, usable ones first, then largest first
+    pub models: Vec<ScoredModel>,
+}
@@ -0,0 +1,160 @@
+use std::fs;
+
+use super::helpers::{
+    default_candidates, gpu_memory_mb, installed_candidates, rank_models, required_memory_mb,
+    score_model,
+};
+use super::models::{MemoryFit, ModelCandidate};
+use crate::core::model_catalog::helpers::write_model;
+use crate::core::model_catalog::models::CatalogModel;
+use crate::core::onboarding::models::{GpuSummary, HardwareSummary};
+
+const GB: u64 = 1_000_000_000;
+
+fn hardware(os: &str, arch: &str, total_memory_mb: u64, vram_mb: &[u64]) -> HardwareSummary {
+    HardwareSummary {
+        os: os.to_string(),
+        arch: arch.to_string(),
+        cpu: "cpu".to_string(),
+        total_memory_mb,
+        gpus: vram_mb
+            .iter()
+            .map(|vram_mb| GpuSummary {
+                name: "gpu".to_string(),
+                vram_mb: *vram_mb,
+            })
+            .collect(),
+    }
+}
+
+fn candidate(model_id: &str, size_bytes: u64) -> ModelCandidate {
+    ModelCandidate {
+        model_id: model_id.to_string(),
+        size_bytes,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_required_memory_grows_with_context() {
+    let short = required_memory_mb(4 * GB, 2_048);
+    let long = required_memory_mb(4 * GB, 32_768);
+    assert!(short > 4 * GB / 1024 / 1024);
+    assert!(long > short);
+}
+
+#[test]
+fn test_score_model_fits() {
+    let pc = hardware("Windows 11", "x86_64", 32_768, &[8_192]);
+    let gpu = score_model(&pc, candidate("small", 2 * GB), 8_192);
+    assert_eq!(gpu.fit, MemoryFit::Gpu);
+    assert_eq!(gpu.gpu_percent, 100);
+
+    let partial = score_model(&pc, candidate("medium", 12 * GB), 8_192);
+    assert_eq!(partial.fit, MemoryFit::Partial);
+    assert!(partial.gpu_percent > 0 && partial.gpu_percent < 100);
+    assert!(partial.tokens_per_sec < gpu.tokens_per_sec);
+
+    let too_large = score_model(&pc, candidate("huge", 40 * GB), 8_192);
+    assert_eq!(too_large.fit, MemoryFit::TooLarge);
+    assert_eq!(too_large.tokens_per_sec, 0.0);
+
+    let laptop = hardware("Ubuntu 24.04", "x86_64", 16_384, &[]);
+    let cpu = score_model(&laptop, candidate("small", 2 * GB), 8_192);
+    assert_eq!(cpu.fit, MemoryFit::Cpu);
+    assert_eq!(cpu.gpu_percent, 0);
+    assert_eq!(cpu.tokens_per_sec, 20.0);
+}
+
+#[test]
+fn test_unified_memory() {
+    let mac = hardware("macOS 15.1", "aarch64", 32_768, &[]);
+    assert_eq!(gpu_memory_mb(&mac), 32_768 * 70 / 100);
+    assert_eq!(
+        score_model(&mac, candidate("medium", 12 * GB), 8_192).fit,
+        MemoryFit::Gpu
+    );
+    // No offloading: the GPU already addresses the RAM
+    assert_eq!(
+        score_model(&mac, candidate("large", 24 * GB), 8_192).fit,
+        MemoryFit::TooLarge
+    );
+}
+
+#[test]
+fn test_rank_models_tiers() {
+    let pc = hardware("Windows 11", "x86_64", 32_768, &[]);
+    let ranked = rank_models(
+        &pc,
+        vec![
+            candidate("tiny", GB / 2),
+            candidate("unknown", 0),
+            candidate("slow", 9 * GB),
+            candidate("large", 6 * GB),
+            candidate("huge", 40 * GB),
+        ],
+        8_192,
+    );
+    // 9 GB runs at ~4 tokens/s on the CPU, too slow to be the quality pick
+    assert_eq!(ranked.best_quality.unwrap().candidate.model_id, "large");
+    assert_eq!(ranked.fastest.unwrap().candidate.model_id, "tiny");
+    assert_eq!(ranked.smallest.unwrap().candidate.model_id, "tiny");
+    let order: Vec<&str> = ranked
+        .models
+        .iter()
+        .map(|m| m.candidate.model_id.as_str())
+        .collect();
+    assert_eq!(order, ["large", "tiny", "huge", "slow"]);
+}
+
+#[test]
+fn test_rank_models_nothing_fits() {
+    let pc = hardware("Windows 11", "x86_64", 1_024, &[]);
+    let ranked = rank_models(&pc, vec![candidate("large", 6 * GB)], 8_192);
+    assert!(ranked.best_quality.is_none());
+    assert!(ranked.fastest.is_none());
+    assert!(ranked.smallest.is_none());
+    assert_eq!(ranked.models.len(), 1);
+}
+
+#[test]
+fn test_installed_candidates() {
+    let dir = std::env::temp_dir().join(format!("jan-picker-{}", uuid::Uuid::new_v4()));
+    let model_dir = dir.join("llamacpp/models/local/chat");
+    fs::create_dir_all(&model_dir).unwrap();
+    fs::write(model_dir.join("model.gguf"), vec![0u8; 2048]).unwrap();
+    write_model(
+        &dir,
+        "llamacpp",
+        "local/chat",
+        &CatalogModel {
+            model_path: "llamacpp/models/local/chat/model.gguf".to_string(),
+            quantization: Some("Q4_K_M".to_string()),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    write_model(
+        &dir,
+        "llamacpp",
+        "local/embed",
+        &CatalogModel {
+            model_path: "embed.gguf".to_string(),
+            size_bytes: 100,
+            embedding: true,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    let installed = installed_candidates(&dir);
+    assert_eq!(installed.len(), 1);
+    assert_eq!(installed[0].model_id, "local/chat");
+    assert_eq!(installed[0].size_bytes, 2048);
+    assert!(installed[0].installed);
+
+    let all = default_candidates(&dir);
+    assert!(all.len() > 1);
+    assert!(all[1..].iter().all(|c| !c.installed && c.url.is_some()));
+    let _ = fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,6 @@
+use std::time::Duration;
+
+/// How often the network interfaces are compared with the last known ones
+pub const NETWORK_CHECK_INTERVAL: Duration = Duration::from_secs(3);
+
+pub const NETWORK_CHANGED_EVENT: &str = "network-changed";
@@ -0,0 +1,135 @@
+use std::collections::BTreeSet;
+use std::net::IpAddr;
+use std::sync::OnceLock;
+
+use serde_json::Value;
+use sysinfo::Networks;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tokio::sync::watch;
+use tokio::time::sleep;
+
+use super::constants::{NETWORK_CHANGED_EVENT, NETWORK_CHECK_INTERVAL};
+use super::models::{NetworkChange, NetworkSnapshot};
+use crate::core::mcp::helpers::{is_remote_server_config, reconnect_mcp_server};
+use crate::core::offline::helpers::{is_offline, reconnect_remote_mcp_servers};
+use crate::core::state::AppState;
+
+// Bumped on every network change
+static NETWORK_CHANGES: OnceLock<watch::Sender<u64>> = OnceLock::new();
+
+fn network_changes() -> &'static watch::Sender<u64> {
+    NETWORK_CHANGES.get_or_init(|| watch::channel(0).0)
+}
+
+/// Receiver that is notified of every network change from now on
+pub fn subscribe_network_changes() -> watch::Receiver<u64> {
+    network_changes().subscribe()
+}
+
+pub fn announce_network_change() {
+    network_changes().send_modify(|changes| *changes += 1);
+}
+
+/// Addresses that tell which networks this machine is on
+fn is_routable(addr: &IpAddr) -> bool {
+    match addr {
+        IpAddr::V4(ip) => !ip.is_loopback() && !ip.is_link_local() && !ip.is_unspecified(),
+        // fe80::/10
+        IpAddr::V6(ip) => {
+            !ip.is_loopback() && !ip.is_unspecified() && (ip.segments()[0] & 0xffc0) != 0xfe80
+        }
+    }
+}
+
+/// The current interfaces and their routable addresses. Interfaces without one are left
+/// out, so an interface going down looks like it disappeared.
+pub fn network_snapshot() -> NetworkSnapshot {
+    Networks::new_with_refreshed_list()
+        .iter()
+        .filter_map(|(name, data)| {
+            let addresses: BTreeSet<String> = data
+                .ip_networks()
+                .iter()
+                .filter(|network| is_routable(&network.addr))
+                .map(|network| format!("{}/{}", network.addr, network.prefix))
+                .collect();
+            (!addresses.is_empty()).then(|| (name.clone(), addresses))
+        })
+        .collect()
+}
+
+/// Interfaces that appeared, disappeared or changed addresses between two snapshots
+pub fn changed_interfaces(before: &NetworkSnapshot, after: &NetworkSnapshot) -> Vec<String> {
+    let names: BTreeSet<&String> = before.keys().chain(after.keys()).collect();
+    names
+        .into_iter()
+        .filter(|name| before.get(*name) != after.get(*name))
+        .cloned()
+        .collect()
+}
+
+/// Poll the network interfaces and react to changes
+pub fn start_network_monitor<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut known = network_snapshot();
+        loop {
+            sleep(NETWORK_CHECK_INTERVAL).await;
+            let current = network_snapshot();
+            let interfaces = changed_interfaces(&known, &current);
+            known = current;
+            if interfaces.is_empty() {
+                continue;
+            }
+            log::info!("Network changed: {}", interfaces.join(", "));
+            announce_network_change();
+            let reconnected_servers = reconnect_mcp_transports(&app).await;
+            let change = NetworkChange {
+                interfaces,
+                reconnected_servers,
+            };
+            if let Err(e) = app.emit(NETWORK_CHANGED_EVENT, &change) {
+                log::error!("Failed to emit {NETWORK_CHANGED_EVENT} event: {e}");
+            }
+        }
+    });
+}
+
+/// Reconnect the remote HTTP/SSE MCP servers, whose connections may be bound to a network
+/// that is gone, and start the ones that failed while the network was down. Running servers
+/// only get a new session and keep serving on the old one until it is up. Returns the
+/// servers being reconnected or started.
+async fn reconnect_mcp_transports<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    if is_offline(app)
+        || *app
+            .state::<AppState>()
+            .mcp_shutdown_in_progress
+            .lock()
+            .await
+    {
+        return Vec::new();
+    }
+    let state = app.state::<AppState>();
+    let running: Vec<(String, Value)> = {
+        let active = state.mcp_active_servers.lock().await;
+        let servers = state.mcp_servers.read().await;
+        active
+            .iter()
+            .filter(|(name, config)| servers.contains_key(*name) && is_remote_server_config(config))
+            .map(|(name, config)| (name.clone(), config.clone()))
+            .collect()
+    };
+
+    let mut names: Vec<String> = running.iter().map(|(name, _)| name.clone()).collect();
+    for (name, config) in running {
+        let app = app.clone();
+        let servers = state.mcp_servers.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = reconnect_mcp_server(&app, &servers, &name, &config).await {
+                log::warn!("Failed to reconnect MCP server {name} after a network change: {e}");
+            }
+        });
+    }
+    names.extend(reconnect_remote_mcp_servers(app).await);
+    names
+}
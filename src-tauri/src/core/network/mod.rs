@@ -0,0 +1,20 @@
+/*!
+   Network Change Detection
+
+   Connections opened on a network that went away (Wi-Fi switched, VPN connected or
+   dropped, cable pulled) usually don't fail; they hang until a long read timeout expires.
+   A monitor polls the network interfaces and their addresses, and when they change:
+   - remote MCP servers over HTTP and SSE get a new session at once, without a restart,
+   - running downloads reopen their connection and continue where they were, and
+     downloads that failed on a dropped connection retry when the network comes back,
+   - messages waiting in the outbox are retried without waiting for their backoff,
+   - `network-changed` is emitted for the UI.
+   Loopback and link-local addresses are ignored, so local-only changes don't trigger it.
+*/
+
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,16 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+/// Addresses of the network interfaces, by interface name
+pub type NetworkSnapshot = BTreeMap<String, BTreeSet<String>>;
+
+/// Payload of the `network-changed` event
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkChange {
+    /// Interfaces that appeared, disappeared or got different addresses
+    pub interfaces: Vec<String>,
+    /// Remote MCP servers that were reconnected
+    pub reconnected_servers: Vec<String>,
+}
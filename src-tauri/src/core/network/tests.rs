@@ -0,0 +1,42 @@
+use std::collections::BTreeSet;
+
+use super::helpers::{announce_network_change, changed_interfaces, subscribe_network_changes};
+use super::models::NetworkSnapshot;
+
+fn snapshot(interfaces: &[(&str, &[&str])]) -> NetworkSnapshot {
+    interfaces
+        .iter()
+        .map(|(name, addresses)| {
+            let addresses: BTreeSet<String> = addresses.iter().map(|a| a.to_string()).collect();
+            (name.to_string(), addresses)
+        })
+        .collect()
+}
+
+#[test]
+fn test_changed_interfaces() {
+    let home = snapshot(&[("en0", &["192.168.1.20/24"])]);
+    assert!(changed_interfaces(&home, &home).is_empty());
+
+    // Switched Wi-Fi networks
+    let office = snapshot(&[("en0", &["10.0.4.7/16"])]);
+    assert_eq!(changed_interfaces(&home, &office), vec!["en0"]);
+
+    // Connected a VPN, then dropped Wi-Fi
+    let vpn = snapshot(&[("en0", &["192.168.1.20/24"]), ("utun3", &["10.8.0.2/24"])]);
+    assert_eq!(changed_interfaces(&home, &vpn), vec!["utun3"]);
+    let vpn_only = snapshot(&[("utun3", &["10.8.0.2/24"])]);
+    assert_eq!(changed_interfaces(&vpn, &vpn_only), vec!["en0"]);
+    assert_eq!(changed_interfaces(&home, &vpn_only), vec!["en0", "utun3"]);
+}
+
+#[tokio::test]
+async fn test_network_change_notifies_subscribers() {
+    let mut network = subscribe_network_changes();
+    assert!(!network.has_changed().unwrap());
+    announce_network_change();
+    tokio::time::timeout(std::time::Duration::from_secs(1), network.changed())
+        .await
+        .expect("no network change notification")
+        .unwrap();
+}
@@ -0,0 +1,40 @@
+use tauri::{AppHandle, Runtime};
+
+use super::helpers::{read_settings, write_settings};
+use super::models::{NotificationCategory, NotificationSettings};
+use crate::core::app::commands::get_jan_data_folder_path;
+
+/// Returns the notification preferences.
+#[tauri::command]
+pub async fn get_notification_settings<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<NotificationSettings, String> {
+    Ok(read_settings(&get_jan_data_folder_path(app_handle)))
+}
+
+/// Turns all notifications on or off without losing per-category choices.
+#[tauri::command]
+pub async fn set_notifications_enabled<R: Runtime>(
+    app_handle: AppHandle<R>,
+    enabled: bool,
+) -> Result<NotificationSettings, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    let mut settings = read_settings(&data_folder);
+    settings.enabled = enabled;
+    write_settings(&data_folder, &settings)?;
+    Ok(settings)
+}
+
+/// Opts in or out of one notification category.
+#[tauri::command]
+pub async fn set_notification_category_enabled<R: Runtime>(
+    app_handle: AppHandle<R>,
+    category: NotificationCategory,
+    enabled: bool,
+) -> Result<NotificationSettings, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    let mut settings = read_settings(&data_folder);
+    settings.set_category(category, enabled);
+    write_settings(&data_folder, &settings)?;
+    Ok(settings)
+}
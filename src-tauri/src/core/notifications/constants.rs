@@ -0,0 +1,7 @@
+// Notification constants
+pub const NOTIFICATION_SETTINGS_FILE: &str = "notifications.json";
+/// Generations shorter than this never trigger a notification
+pub const LONG_GENERATION_SECS: u64 = 20;
+/// Characters of the answer shown in the completion notification
+pub const NOTIFICATION_PREVIEW_CHARS: usize = 120;
+pub const MAIN_WINDOW_LABEL: &str = "main";
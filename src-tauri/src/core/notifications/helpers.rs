@@ -0,0 +1,93 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_notification::NotificationExt;
+
+use super::constants::{
+    LONG_GENERATION_SECS, MAIN_WINDOW_LABEL, NOTIFICATION_PREVIEW_CHARS, NOTIFICATION_SETTINGS_FILE,
+};
+use super::models::{NotificationCategory, NotificationSettings};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::config_store::helpers::{config_store, write_json_debounced};
+use crate::core::local_text::helpers::{generate_snippet, local_text_endpoint};
+use crate::core::messages::helpers::localize;
+use crate::core::messages::models::{LocalizedMessage, MessageCode};
+use crate::core::settings::helpers::local_text_settings;
+
+pub fn get_settings_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(NOTIFICATION_SETTINGS_FILE)
+}
+
+/// Read notification preferences, defaulting to everything enabled
+pub fn read_settings(data_folder: &Path) -> NotificationSettings {
+    config_store()
+        .read(&get_settings_path(data_folder))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+pub fn write_settings(data_folder: &Path, settings: &NotificationSettings) -> Result<(), String> {
+    write_json_debounced(&get_settings_path(data_folder), settings)
+}
+
+/// Whether the main window currently has focus. Without a window, nobody is looking.
+pub fn is_main_window_focused<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.get_webview_window(MAIN_WINDOW_LABEL)
+        .and_then(|window| window.is_focused().ok())
+        .unwrap_or(false)
+}
+
+/// Show a native notification unless the user opted out of `category`
+pub fn notify<R: Runtime>(
+    app: &AppHandle<R>,
+    category: NotificationCategory,
+    title: &str,
+    body: &str,
+) {
+    let settings = read_settings(&get_jan_data_folder_path(app.clone()));
+    if !settings.is_enabled(category) {
+        return;
+    }
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        log::warn!("Failed to show {category:?} notification: {e}");
+    }
+}
+
+/// Notify about a finished generation when it took long and the user looked away. The body
+/// is the start of `reply`, or a snippet describing it written by the local model when
+/// `local_text.notification_snippets` is on.
+pub fn notify_generation_finished<R: Runtime>(app: &AppHandle<R>, elapsed: Duration, reply: &str) {
+    if elapsed < Duration::from_secs(LONG_GENERATION_SECS) || is_main_window_focused(app) {
+        return;
+    }
+    let category = NotificationCategory::GenerationFinished;
+    if !read_settings(&get_jan_data_folder_path(app.clone())).is_enabled(category) {
+        return;
+    }
+    let preview: String = reply.chars().take(NOTIFICATION_PREVIEW_CHARS).collect();
+    let title = localize(
+        app,
+        &LocalizedMessage::new(MessageCode::NotificationResponseReady),
+    );
+    if !local_text_settings(app).notification_snippets {
+        notify(app, category, &title, &preview);
+        return;
+    }
+
+    let app = app.clone();
+    let reply = reply.to_string();
+    tauri::async_runtime::spawn(async move {
+        let snippet = async {
+            let endpoint = local_text_endpoint(&app).await?;
+            generate_snippet(&app, &endpoint, "notification-snippet", &reply).await
+        }
+        .await
+        .unwrap_or_else(|e| {
+            log::info!("Showing the start of the reply in the notification: {e}");
+            preview
+        });
+        notify(&app, category, &title, &snippet);
+    });
+}
@@ -0,0 +1,20 @@
+/*!
+   Native OS Notifications
+
+   Fires OS notifications for events the user may be waiting on while Jan is in the background:
+   - a long generation finishing while the main window is unfocused,
+   - a model download completing,
+   - an MCP server failing to start,
+   - a scheduled prompt run finishing or failing.
+
+   Each category can be turned off individually; the preferences live in `notifications.json`
+   in the Jan data folder.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
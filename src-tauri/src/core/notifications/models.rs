@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationCategory {
+    GenerationFinished,
+    DownloadFinished,
+    McpServerFailed,
+    ScheduledPrompt,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NotificationSettings {
+    /// Master switch for all notifications
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Categories the user opted out of
+    #[serde(default)]
+    pub disabled_categories: Vec<NotificationCategory>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            disabled_categories: Vec::new(),
+        }
+    }
+}
+
+impl NotificationSettings {
+    pub fn is_enabled(&self, category: NotificationCategory) -> bool {
+        self.enabled && !self.disabled_categories.contains(&category)
+    }
+
+    pub fn set_category(&mut self, category: NotificationCategory, enabled: bool) {
+        self.disabled_categories.retain(|c| *c != category);
+        if !enabled {
+            self.disabled_categories.push(category);
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use super::commands::*;
+use super::models::{NotificationCategory, NotificationSettings};
+use crate::core::app::commands::get_jan_data_folder_path;
+use std::fs;
+use tauri::test::mock_app;
+
+#[test]
+fn test_settings_default_to_enabled() {
+    let settings = NotificationSettings::default();
+    assert!(settings.is_enabled(NotificationCategory::DownloadFinished));
+    assert!(settings.is_enabled(NotificationCategory::McpServerFailed));
+}
+
+#[test]
+fn test_master_switch_overrides_categories() {
+    let settings = NotificationSettings {
+        enabled: false,
+        disabled_categories: Vec::new(),
+    };
+    assert!(!settings.is_enabled(NotificationCategory::GenerationFinished));
+}
+
+#[tokio::test]
+async fn test_category_opt_out_is_persisted() {
+    let app = mock_app();
+    let data_dir = get_jan_data_folder_path(app.handle().clone());
+
+    set_notification_category_enabled(
+        app.handle().clone(),
+        NotificationCategory::DownloadFinished,
+        false,
+    )
+    .await
+    .unwrap();
+    // Repeating the same choice does not duplicate entries
+    let settings = set_notification_category_enabled(
+        app.handle().clone(),
+        NotificationCategory::DownloadFinished,
+        false,
+    )
+    .await
+    .unwrap();
+    assert_eq!(settings.disabled_categories.len(), 1);
+
+    let loaded = get_notification_settings(app.handle().clone())
+        .await
+        .unwrap();
+    assert!(!loaded.is_enabled(NotificationCategory::DownloadFinished));
+    assert!(loaded.is_enabled(NotificationCategory::GenerationFinished));
+
+    let settings = set_notification_category_enabled(
+        app.handle().clone(),
+        NotificationCategory::DownloadFinished,
+        true,
+    )
+    .await
+    .unwrap();
+    assert!(settings.disabled_categories.is_empty());
+
+    let _ = fs::remove_dir_all(data_dir);
+}
@@ -0,0 +1,49 @@
+use tauri::{AppHandle, Emitter, Runtime, State};
+
+use super::constants::OFFLINE_MODE_EVENT;
+use super::helpers::{disconnect_remote_mcp_servers, reconnect_remote_mcp_servers, write_settings};
+use super::models::{OfflineSettings, OfflineStatus};
+use super::OfflineMode;
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::outbox::helpers::retry_outbox_now;
+use crate::core::settings::helpers::admin_policy;
+
+#[tauri::command]
+pub fn get_offline_mode(mode: State<'_, OfflineMode>) -> bool {
+    mode.is_enabled()
+}
+
+/// Switch offline mode. Remote MCP servers are disconnected when going offline and
+/// reconnected when coming back; the new state is broadcast as `offline-mode-changed`.
+/// Offline mode can't be switched off while the admin policy forces it.
+#[tauri::command]
+pub async fn set_offline_mode<R: Runtime>(
+    app: AppHandle<R>,
+    mode: State<'_, OfflineMode>,
+    enabled: bool,
+) -> Result<OfflineStatus, String> {
+    if !enabled && admin_policy().force_offline {
+        return Err("Offline mode is locked on by the administrator policy".to_string());
+    }
+    write_settings(
+        &get_jan_data_folder_path(app.clone()),
+        &OfflineSettings { enabled },
+    )?;
+    mode.set_enabled(enabled);
+    log::info!("Offline mode {}", if enabled { "on" } else { "off" });
+
+    let mcp_servers = if enabled {
+        disconnect_remote_mcp_servers(&app).await
+    } else {
+        retry_outbox_now(&app).await;
+        reconnect_remote_mcp_servers(&app).await
+    };
+    let status = OfflineStatus {
+        offline: enabled,
+        mcp_servers,
+    };
+    if let Err(e) = app.emit(OFFLINE_MODE_EVENT, &status) {
+        log::warn!("Failed to emit offline mode change: {e}");
+    }
+    Ok(status)
+}
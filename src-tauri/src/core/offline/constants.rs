@@ -0,0 +1,6 @@
+pub const OFFLINE_SETTINGS_FILE: &str = "offline.json";
+
+/// Emitted with an `OfflineStatus` payload whenever offline mode is switched
+pub const OFFLINE_MODE_EVENT: &str = "offline-mode-changed";
+
+pub const OFFLINE_ERROR: &str = "Offline mode is on: connections to other machines are disabled";
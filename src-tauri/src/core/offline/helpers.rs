@@ -0,0 +1,136 @@
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+use tauri::{AppHandle, Manager, Runtime};
+use url::{Host, Url};
+
+use super::constants::OFFLINE_SETTINGS_FILE;
+use super::models::OfflineSettings;
+use super::OfflineMode;
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::config_store::helpers::write_json;
+use crate::core::mcp::helpers::{emit_mcp_update_event, extract_command_args, start_mcp_server};
+use crate::core::settings::helpers::admin_policy;
+use crate::core::state::{AppState, RunningServiceEnum};
+
+pub fn get_settings_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(OFFLINE_SETTINGS_FILE)
+}
+
+pub fn read_settings(data_folder: &Path) -> OfflineSettings {
+    fs::read_to_string(get_settings_path(data_folder))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+pub fn write_settings(data_folder: &Path, settings: &OfflineSettings) -> Result<(), String> {
+    write_json(&get_settings_path(data_folder), settings)
+}
+
+/// Apply the stored setting, so an app started offline never connects out. The admin policy
+/// may force offline mode regardless.
+pub fn load_offline_mode<R: Runtime>(app: &AppHandle<R>) {
+    let settings = read_settings(&get_jan_data_folder_path(app.clone()));
+    let enabled = settings.enabled || admin_policy().force_offline;
+    if enabled {
+        log::info!("Starting in offline mode");
+    }
+    app.state::<OfflineMode>().set_enabled(enabled);
+}
+
+/// Whether `url` points at this machine. Unparseable URLs are treated as remote.
+pub fn is_loopback_url(url: &str) -> bool {
+    let Ok(parsed) = Url::parse(url.trim()) else {
+        return false;
+    };
+    match parsed.host() {
+        Some(Host::Domain(domain)) => {
+            let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+            domain == "localhost" || domain.ends_with(".localhost")
+        }
+        Some(Host::Ipv4(ip)) => IpAddr::V4(ip).is_loopback(),
+        Some(Host::Ipv6(ip)) => IpAddr::V6(ip).is_loopback(),
+        None => false,
+    }
+}
+
+pub fn is_offline<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.try_state::<OfflineMode>()
+        .is_some_and(|mode| mode.is_enabled())
+}
+
+/// Refuse a connection to `url` while offline mode is on
+pub fn check_url<R: Runtime>(app: &AppHandle<R>, url: &str) -> Result<(), String> {
+    app.try_state::<OfflineMode>()
+        .map_or(Ok(()), |mode| mode.check_url(url))
+}
+
+/// URL of an MCP server reached over HTTP or SSE on another machine
+pub fn remote_mcp_url(config: &Value) -> Option<String> {
+    let params = extract_command_args(config)?;
+    if !matches!(params.transport_type.as_deref(), Some("http" | "sse")) {
+        return None;
+    }
+    params.url.filter(|url| !is_loopback_url(url))
+}
+
+/// Drop the connections to remote MCP servers. They stay in the active list so
+/// `reconnect_remote_mcp_servers` can bring them back.
+pub async fn disconnect_remote_mcp_servers<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    let state = app.state::<AppState>();
+    let remote: Vec<String> = state
+        .mcp_active_servers
+        .lock()
+        .await
+        .iter()
+        .filter(|(_, config)| remote_mcp_url(config).is_some())
+        .map(|(name, _)| name.clone())
+        .collect();
+
+    let mut disconnected = Vec::new();
+    for name in remote {
+        let Some(service) = state.mcp_servers.write().await.remove(&name) else {
+            continue;
+        };
+        let result = match service {
+            RunningServiceEnum::NoInit(service) => service.cancel().await.map(drop),
+            RunningServiceEnum::WithInit(service) => service.cancel().await.map(drop),
+        };
+        if let Err(e) = result {
+            log::warn!("Failed to close MCP server {name}: {e}");
+        }
+        log::info!("Disconnected remote MCP server {name}");
+        emit_mcp_update_event(app, &name);
+        disconnected.push(name);
+    }
+    disconnected
+}
+
+/// Start the active remote MCP servers that are not connected
+pub async fn reconnect_remote_mcp_servers<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    let state = app.state::<AppState>();
+    let pending: Vec<(String, Value)> = {
+        let active = state.mcp_active_servers.lock().await;
+        let running = state.mcp_servers.read().await;
+        active
+            .iter()
+            .filter(|(name, config)| {
+                !running.contains_key(*name) && remote_mcp_url(config).is_some()
+            })
+            .map(|(name, config)| (name.clone(), config.clone()))
+            .collect()
+    };
+
+    let names = pending.iter().map(|(name, _)| name.clone()).collect();
+    for (name, config) in pending {
+        let app = app.clone();
+        let servers = state.mcp_servers.clone();
+        tauri::async_runtime::spawn(async move {
+            let _ = start_mcp_server(app, servers, name, config).await;
+        });
+    }
+    names
+}
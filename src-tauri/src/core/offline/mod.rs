@@ -0,0 +1,52 @@
+/*!
+   Offline Mode
+
+   A global kill-switch for network access, enforced in the core rather than the UI. While it
+   is on, every path that would reach another machine refuses to connect:
+   - remote providers, both for core inference and in the local API server,
+   - the download manager,
+   - MCP servers over HTTP and SSE (connected ones are dropped, and reconnected when
+     offline mode is switched off again),
+   - queued outgoing messages, which are retried once offline mode is switched off,
+   - the web search and web fetch tools.
+   Loopback addresses stay reachable, so local engines, Ollama and MCP servers running on
+   this machine keep working. Switching emits `offline-mode-changed` for the UI.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use constants::OFFLINE_ERROR;
+use helpers::is_loopback_url;
+
+/// Shared offline switch, cheap to clone
+#[derive(Clone, Default)]
+pub struct OfflineMode {
+    enabled: Arc<AtomicBool>,
+}
+
+impl OfflineMode {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::SeqCst);
+    }
+
+    /// Refuse connections to `url` unless it points at this machine
+    pub fn check_url(&self, url: &str) -> Result<(), String> {
+        if self.is_enabled() && !is_loopback_url(url) {
+            return Err(OFFLINE_ERROR.to_string());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+/// Offline mode setting, stored in `offline.json` so it survives restarts
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct OfflineSettings {
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+/// Payload of `offline-mode-changed`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OfflineStatus {
+    pub offline: bool,
+    /// Remote MCP servers disconnected or reconnected by the switch
+    #[serde(default)]
+    pub mcp_servers: Vec<String>,
+}
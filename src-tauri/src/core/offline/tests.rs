@@ -0,0 +1,97 @@
+use serde_json::json;
+use tauri::Manager;
+
+use super::constants::OFFLINE_ERROR;
+use super::helpers::{check_url, is_loopback_url, read_settings, remote_mcp_url, write_settings};
+use super::models::OfflineSettings;
+use super::OfflineMode;
+use crate::core::inference::helpers::resolve_model_endpoint;
+use crate::core::state::{AppState, ProviderConfig};
+
+#[test]
+fn test_is_loopback_url() {
+    assert!(is_loopback_url("http://localhost:11434/v1"));
+    assert!(is_loopback_url("http://127.0.0.1:3000/mcp"));
+    assert!(is_loopback_url("http://[::1]:8080"));
+    assert!(is_loopback_url("http://api.localhost/"));
+    assert!(!is_loopback_url("https://api.openai.com/v1"));
+    assert!(!is_loopback_url("http://192.168.1.20:11434"));
+    assert!(!is_loopback_url("not a url"));
+}
+
+#[test]
+fn test_offline_mode_check_url() {
+    let mode = OfflineMode::default();
+    assert!(mode.check_url("https://huggingface.co/model.gguf").is_ok());
+    mode.set_enabled(true);
+    assert_eq!(
+        mode.check_url("https://huggingface.co/model.gguf"),
+        Err(OFFLINE_ERROR.to_string())
+    );
+    assert!(mode.check_url("http://127.0.0.1:39291/v1").is_ok());
+}
+
+#[test]
+fn test_remote_mcp_url() {
+    let remote =
+        json!({"command": "", "args": [], "type": "http", "url": "https://mcp.example.com"});
+    let local_sse =
+        json!({"command": "", "args": [], "type": "sse", "url": "http://localhost:9000/sse"});
+    let stdio = json!({"command": "npx", "args": ["-y", "server"]});
+    assert_eq!(
+        remote_mcp_url(&remote).as_deref(),
+        Some("https://mcp.example.com")
+    );
+    assert_eq!(remote_mcp_url(&local_sse), None);
+    assert_eq!(remote_mcp_url(&stdio), None);
+}
+
+#[test]
+fn test_settings_roundtrip() {
+    let data = std::env::temp_dir().join(format!("jan-offline-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&data).unwrap();
+    assert!(!read_settings(&data).enabled);
+    write_settings(&data, &OfflineSettings { enabled: true }).unwrap();
+    assert!(read_settings(&data).enabled);
+    let _ = std::fs::remove_dir_all(data);
+}
+
+#[tokio::test]
+async fn test_remote_providers_refused_while_offline() {
+    let app = tauri::test::mock_app();
+    app.manage(AppState::default());
+    app.manage(OfflineMode::default());
+    {
+        let state = app.state::<AppState>();
+        let mut configs = state.provider_configs.write().await;
+        for (name, base_url) in [
+            ("openai", "https://api.openai.com/v1"),
+            ("ollama", "http://localhost:11434/v1"),
+        ] {
+            configs.insert(
+                name.to_string(),
+                ProviderConfig {
+                    provider: name.to_string(),
+                    base_url: Some(base_url.to_string()),
+                    ..Default::default()
+                },
+            );
+        }
+    }
+    let handle = app.handle();
+    assert!(resolve_model_endpoint(handle, "openai/gpt-4o")
+        .await
+        .is_ok());
+
+    app.state::<OfflineMode>().set_enabled(true);
+    assert_eq!(
+        resolve_model_endpoint(handle, "openai/gpt-4o")
+            .await
+            .unwrap_err(),
+        OFFLINE_ERROR
+    );
+    assert!(resolve_model_endpoint(handle, "ollama/llama3")
+        .await
+        .is_ok());
+    assert!(check_url(handle, "https://example.com").is_err());
+}
@@ -0,0 +1,99 @@
+use jan_utils::progress::{ProgressOperation, ProgressPayload, ProgressState};
+use serde_json::Value;
+use tauri::{AppHandle, Runtime, State};
+
+use super::constants::{OLLAMA_PROVIDER, OLLAMA_PULL_EVENT};
+use super::helpers::{
+    get_ollama_version, list_ollama_models, ollama_base_url, pull_model, pull_payload,
+    register_ollama, set_keep_alive,
+};
+use super::models::{OllamaModel, OllamaStatus};
+use crate::core::events::helpers::{emit_coalesced, emit_final, emit_task_progress};
+use crate::core::state::AppState;
+
+/// Reports whether an Ollama instance is running and whether it is registered as a provider.
+#[tauri::command]
+pub async fn get_ollama_status(state: State<'_, AppState>) -> Result<OllamaStatus, String> {
+    let base_url = ollama_base_url();
+    let version = get_ollama_version(&base_url).await.ok();
+    let registered = state
+        .provider_configs
+        .read()
+        .await
+        .contains_key(OLLAMA_PROVIDER);
+    Ok(OllamaStatus {
+        running: version.is_some(),
+        base_url,
+        version,
+        registered,
+    })
+}
+
+/// Lists the models pulled into Ollama without changing the provider registration.
+#[tauri::command]
+pub async fn list_ollama_model_tags() -> Result<Vec<OllamaModel>, String> {
+    list_ollama_models(&ollama_base_url()).await
+}
+
+/// Registers the running Ollama instance as the `ollama` provider, refreshing its model list.
+#[tauri::command]
+pub async fn connect_ollama(state: State<'_, AppState>) -> Result<Vec<OllamaModel>, String> {
+    register_ollama(&state, &ollama_base_url()).await
+}
+
+/// Stops routing chats to Ollama. The Ollama instance itself keeps running.
+#[tauri::command]
+pub async fn disconnect_ollama(state: State<'_, AppState>) -> Result<(), String> {
+    state.provider_configs.write().await.remove(OLLAMA_PROVIDER);
+    Ok(())
+}
+
+/// Loads a model in Ollama and keeps it in memory for `keep_alive` (e.g. `"30m"`, `-1` for
+/// forever, `0` to unload). Defaults to Ollama's own keep-alive when omitted.
+#[tauri::command]
+pub async fn ollama_keep_alive(model: String, keep_alive: Option<Value>) -> Result<(), String> {
+    set_keep_alive(
+        &ollama_base_url(),
+        &model,
+        keep_alive.unwrap_or(Value::Null),
+    )
+    .await
+}
+
+/// Pulls a model into Ollama, emitting `ollama-pull-progress` and `task-progress` events, and
+/// refreshes the provider registration so the new model can be used right away.
+#[tauri::command]
+pub async fn pull_ollama_model<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, AppState>,
+    model: String,
+) -> Result<Vec<OllamaModel>, String> {
+    let base_url = ollama_base_url();
+    let mut layer_eta = None;
+    let mut finished = false;
+    let result = pull_model(&base_url, &model, |progress| {
+        if progress.status == "success" || progress.error.is_some() {
+            emit_final(&app, OLLAMA_PULL_EVENT, &model, &progress);
+        } else {
+            emit_coalesced(&app, OLLAMA_PULL_EVENT, &model, &progress);
+        }
+        let payload = pull_payload(&progress, &mut layer_eta);
+        finished |= payload.state.is_final();
+        emit_task_progress(&app, &payload);
+    })
+    .await;
+    if result.is_err() && !finished {
+        // Ollama was unreachable or the stream broke off without an error line
+        emit_task_progress(
+            &app,
+            &ProgressPayload::finished(
+                ProgressOperation::ModelPull,
+                &model,
+                &model,
+                ProgressState::Failed,
+            ),
+        );
+    }
+    result?;
+    register_ollama(&state, &base_url).await
+}
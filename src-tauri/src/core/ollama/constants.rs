@@ -0,0 +1,14 @@
+use std::time::Duration;
+
+/// Provider name the Ollama instance is registered under
+pub const OLLAMA_PROVIDER: &str = "ollama";
+pub const DEFAULT_OLLAMA_URL: &str = "http://127.0.0.1:11434";
+/// Environment variable Ollama itself reads to choose its listen address
+pub const OLLAMA_HOST_ENV: &str = "OLLAMA_HOST";
+
+/// Timeout for detection and model listing; a local instance answers immediately
+pub const OLLAMA_DETECT_TIMEOUT: Duration = Duration::from_secs(2);
+/// Loading a large model into memory for `keep_alive` can take a while
+pub const OLLAMA_LOAD_TIMEOUT: Duration = Duration::from_secs(300);
+
+pub const OLLAMA_PULL_EVENT: &str = "ollama-pull-progress";
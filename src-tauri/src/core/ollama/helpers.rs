@@ -0,0 +1,259 @@
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use jan_utils::progress::{
+    EtaEstimator, ProgressOperation, ProgressPayload, ProgressState, ProgressUnit,
+};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, Runtime};
+
+use super::constants::{
+    DEFAULT_OLLAMA_URL, OLLAMA_DETECT_TIMEOUT, OLLAMA_HOST_ENV, OLLAMA_LOAD_TIMEOUT,
+    OLLAMA_PROVIDER,
+};
+use super::models::{OllamaModel, OllamaPullProgress, OllamaTags};
+use crate::core::state::{AppState, ProviderConfig};
+
+/// Turn an `OLLAMA_HOST` value (`0.0.0.0`, `127.0.0.1:11500`, `http://host:port`) into a base
+/// URL this process can connect to.
+pub fn normalize_ollama_host(host: &str) -> String {
+    let host = host.trim().trim_end_matches('/');
+    if host.is_empty() {
+        return DEFAULT_OLLAMA_URL.to_string();
+    }
+    let (scheme, rest) = match host.split_once("://") {
+        Some((scheme, rest)) => (scheme, rest),
+        None => ("http", host),
+    };
+    // A wildcard listen address is reachable through loopback
+    let rest = rest.replacen("0.0.0.0", "127.0.0.1", 1);
+    let has_port = rest
+        .rsplit_once(':')
+        .is_some_and(|(_, port)| port.parse::<u16>().is_ok());
+    if has_port || scheme == "https" {
+        format!("{scheme}://{rest}")
+    } else {
+        format!("{scheme}://{rest}:11434")
+    }
+}
+
+/// Base URL of the Ollama instance, honouring `OLLAMA_HOST` like the Ollama CLI does.
+pub fn ollama_base_url() -> String {
+    std::env::var(OLLAMA_HOST_ENV)
+        .map(|host| normalize_ollama_host(&host))
+        .unwrap_or_else(|_| DEFAULT_OLLAMA_URL.to_string())
+}
+
+fn client(timeout: Option<Duration>) -> Result<reqwest::Client, String> {
+    let mut builder = reqwest::Client::builder();
+    if let Some(timeout) = timeout {
+        builder = builder.timeout(timeout);
+    }
+    builder.build().map_err(|e| e.to_string())
+}
+
+async fn check_status(response: reqwest::Response) -> Result<reqwest::Response, String> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let text = response.text().await.unwrap_or_default();
+    Err(format!(
+        "Ollama request failed with status {status}: {text}"
+    ))
+}
+
+/// Version of the Ollama instance at `base_url`; fails when nothing is listening.
+pub async fn get_ollama_version(base_url: &str) -> Result<String, String> {
+    let response = client(Some(OLLAMA_DETECT_TIMEOUT))?
+        .get(format!("{base_url}/api/version"))
+        .send()
+        .await
+        .map_err(|e| format!("Ollama is not reachable at {base_url}: {e}"))?;
+    let body: Value = check_status(response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid Ollama version response: {e}"))?;
+    Ok(body
+        .get("version")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string())
+}
+
+/// Models pulled into the Ollama instance.
+pub async fn list_ollama_models(base_url: &str) -> Result<Vec<OllamaModel>, String> {
+    let response = client(Some(OLLAMA_DETECT_TIMEOUT))?
+        .get(format!("{base_url}/api/tags"))
+        .send()
+        .await
+        .map_err(|e| format!("Ollama is not reachable at {base_url}: {e}"))?;
+    let tags: OllamaTags = check_status(response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid Ollama model list: {e}"))?;
+    Ok(tags.models)
+}
+
+/// Provider config routing the given models to Ollama's OpenAI-compatible API.
+pub fn ollama_provider_config(base_url: &str, models: &[OllamaModel]) -> ProviderConfig {
+    ProviderConfig {
+        provider: OLLAMA_PROVIDER.to_string(),
+        api_key: None,
+        base_url: Some(format!("{base_url}/v1")),
+        custom_headers: Vec::new(),
+        models: models.iter().map(|m| m.name.clone()).collect(),
+        ..Default::default()
+    }
+}
+
+/// List the instance's models and (re-)register it as the `ollama` provider.
+pub async fn register_ollama(state: &AppState, base_url: &str) -> Result<Vec<OllamaModel>, String> {
+    let models = list_ollama_models(base_url).await?;
+    let config = ollama_provider_config(base_url, &models);
+    state
+        .provider_configs
+        .write()
+        .await
+        .insert(OLLAMA_PROVIDER.to_string(), config);
+    log::info!(
+        "Registered Ollama at {base_url} with {} models",
+        models.len()
+    );
+    Ok(models)
+}
+
+/// Load a model and keep it resident for `keep_alive` (a duration such as `"10m"`, seconds, or
+/// `-1` for forever); `0` unloads it immediately.
+pub async fn set_keep_alive(base_url: &str, model: &str, keep_alive: Value) -> Result<(), String> {
+    let response = client(Some(OLLAMA_LOAD_TIMEOUT))?
+        .post(format!("{base_url}/api/generate"))
+        .json(&json!({"model": model, "keep_alive": keep_alive}))
+        .send()
+        .await
+        .map_err(|e| format!("Ollama is not reachable at {base_url}: {e}"))?;
+    check_status(response).await.map(|_| ())
+}
+
+/// Parse one line of the `/api/pull` progress stream.
+pub fn parse_pull_line(model: &str, line: &str) -> Option<OllamaPullProgress> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    let mut progress: OllamaPullProgress = match serde_json::from_str(line) {
+        Ok(progress) => progress,
+        // Errors are sent as `{"error": "..."}` without a status
+        Err(_) => {
+            let value: Value = serde_json::from_str(line).ok()?;
+            OllamaPullProgress {
+                model: String::new(),
+                status: "error".to_string(),
+                digest: None,
+                total: None,
+                completed: None,
+                error: Some(value.get("error")?.as_str()?.to_string()),
+            }
+        }
+    };
+    progress.model = model.to_string();
+    Some(progress)
+}
+
+/// Pull a model into Ollama, reporting every progress line. Pulls have no overall timeout
+/// since large models take a long time to download.
+pub async fn pull_model<F>(base_url: &str, model: &str, mut on_progress: F) -> Result<(), String>
+where
+    F: FnMut(OllamaPullProgress),
+{
+    let response = client(None)?
+        .post(format!("{base_url}/api/pull"))
+        .json(&json!({"model": model, "stream": true}))
+        .send()
+        .await
+        .map_err(|e| format!("Ollama is not reachable at {base_url}: {e}"))?;
+    let mut stream = check_status(response).await?.bytes_stream();
+
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut handle_line = |line: &str| -> Result<(), String> {
+        let Some(progress) = parse_pull_line(model, line) else {
+            return Ok(());
+        };
+        let error = progress.error.clone();
+        on_progress(progress);
+        match error {
+            Some(error) => Err(format!("Failed to pull {model}: {error}")),
+            None => Ok(()),
+        }
+    };
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Ollama pull interrupted: {e}"))?;
+        buffer.extend_from_slice(&chunk);
+        while let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=pos).collect();
+            handle_line(&String::from_utf8_lossy(&line))?;
+        }
+    }
+    handle_line(&String::from_utf8_lossy(&buffer))
+}
+
+/// Phase of a pull in the shared progress format, from Ollama's status line
+pub fn pull_phase(status: &str) -> &'static str {
+    if status.starts_with("pulling manifest") {
+        "preparing"
+    } else if status.starts_with("pulling") {
+        "downloading"
+    } else {
+        // Verifying the digest, writing the manifest and removing unused layers
+        "verifying"
+    }
+}
+
+/// A pull update in the shared progress format. Ollama downloads one layer after another,
+/// so the ETA covers the layer being downloaded and restarts with the next one.
+pub fn pull_payload(
+    progress: &OllamaPullProgress,
+    layer_eta: &mut Option<(String, EtaEstimator)>,
+) -> ProgressPayload {
+    let model = progress.model.as_str();
+    let operation = ProgressOperation::ModelPull;
+    if progress.error.is_some() {
+        return ProgressPayload::finished(operation, model, model, ProgressState::Failed);
+    }
+    if progress.status == "success" {
+        return ProgressPayload::finished(operation, model, model, ProgressState::Completed);
+    }
+    let payload = ProgressPayload::running(operation, model, model, pull_phase(&progress.status));
+    let (Some(completed), Some(total), Some(digest)) =
+        (progress.completed, progress.total, &progress.digest)
+    else {
+        return payload;
+    };
+    if layer_eta.as_ref().map(|(layer, _)| layer) != Some(digest) {
+        *layer_eta = Some((digest.clone(), EtaEstimator::new(completed)));
+    }
+    let eta = layer_eta
+        .as_ref()
+        .and_then(|(_, estimator)| estimator.eta(completed, total));
+    payload
+        .with_amount(completed, total, ProgressUnit::Bytes)
+        .with_eta(eta)
+}
+
+/// Look for a running Ollama instance in the background and register it when found.
+pub fn start_ollama_detection<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let base_url = ollama_base_url();
+        if get_ollama_version(&base_url).await.is_err() {
+            log::debug!("No Ollama instance found at {base_url}");
+            return;
+        }
+        let state = app.state::<AppState>();
+        if let Err(e) = register_ollama(&state, &base_url).await {
+            log::warn!("Failed to register Ollama: {e}");
+        }
+    });
+}
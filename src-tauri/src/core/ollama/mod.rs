@@ -0,0 +1,22 @@
+/*!
+   Ollama Integration
+
+   Uses an Ollama instance running on this machine as a local provider, so models already
+   pulled into Ollama can be used without downloading their GGUFs again. The instance is looked
+   up at `OLLAMA_HOST` (default `http://127.0.0.1:11434`) when the app starts and on demand;
+   when found, its models (from `/api/tags`) are registered as the `ollama` provider pointing
+   at Ollama's OpenAI-compatible `/v1` API. Chats are then routed to it through the regular
+   provider layer, by both the core inference client and the local API server.
+
+   Ollama's native API is used for what the OpenAI-compatible one lacks: loading or unloading
+   a model via `keep_alive` and pulling new models, with progress reported through
+   `ollama-pull-progress` events.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OllamaModelDetails {
+    #[serde(default)]
+    pub family: Option<String>,
+    #[serde(default)]
+    pub parameter_size: Option<String>,
+    #[serde(default)]
+    pub quantization_level: Option<String>,
+}
+
+/// A model pulled into Ollama, as listed by `/api/tags`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OllamaModel {
+    pub name: String,
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default)]
+    pub digest: Option<String>,
+    #[serde(default)]
+    pub modified_at: Option<String>,
+    #[serde(default)]
+    pub details: OllamaModelDetails,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OllamaTags {
+    #[serde(default)]
+    pub models: Vec<OllamaModel>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OllamaStatus {
+    pub running: bool,
+    pub base_url: String,
+    pub version: Option<String>,
+    /// Whether the instance is currently registered as a provider
+    pub registered: bool,
+}
+
+/// One line of the `/api/pull` progress stream, tagged with the model being pulled
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OllamaPullProgress {
+    #[serde(default)]
+    pub model: String,
+    pub status: String,
+    #[serde(default)]
+    pub digest: Option<String>,
+    #[serde(default)]
+    pub total: Option<u64>,
+    #[serde(default)]
+    pub completed: Option<u64>,
+    #[serde(default)]
+    pub error: Option<String>,
+}
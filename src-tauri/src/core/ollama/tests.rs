@@ -0,0 +1,97 @@
+use jan_utils::progress::{ProgressKind, ProgressState};
+
+use super::helpers::{
+    normalize_ollama_host, ollama_provider_config, parse_pull_line, pull_payload,
+};
+use super::models::{OllamaModel, OllamaModelDetails};
+
+#[test]
+fn test_normalize_ollama_host() {
+    assert_eq!(normalize_ollama_host(""), "http://127.0.0.1:11434");
+    assert_eq!(normalize_ollama_host("0.0.0.0"), "http://127.0.0.1:11434");
+    assert_eq!(
+        normalize_ollama_host("127.0.0.1:11500"),
+        "http://127.0.0.1:11500"
+    );
+    assert_eq!(
+        normalize_ollama_host("http://gpu-box:11434/"),
+        "http://gpu-box:11434"
+    );
+    assert_eq!(
+        normalize_ollama_host("https://ollama.example.com"),
+        "https://ollama.example.com"
+    );
+}
+
+#[test]
+fn test_provider_config_targets_openai_api() {
+    let models = vec![OllamaModel {
+        name: "llama3.2:3b".to_string(),
+        size: 2_000_000_000,
+        digest: None,
+        modified_at: None,
+        details: OllamaModelDetails::default(),
+    }];
+    let config = ollama_provider_config("http://127.0.0.1:11434", &models);
+    assert_eq!(config.provider, "ollama");
+    assert_eq!(
+        config.base_url.as_deref(),
+        Some("http://127.0.0.1:11434/v1")
+    );
+    assert_eq!(config.models, vec!["llama3.2:3b"]);
+    assert!(config.api_key.is_none());
+}
+
+#[test]
+fn test_parse_pull_line() {
+    let progress = parse_pull_line(
+        "qwen3",
+        r#"{"status":"pulling abc","digest":"sha256:abc","total":100,"completed":40}"#,
+    )
+    .unwrap();
+    assert_eq!(progress.model, "qwen3");
+    assert_eq!(progress.completed, Some(40));
+    assert!(progress.error.is_none());
+
+    let error = parse_pull_line(
+        "nope",
+        r#"{"error":"pull model manifest: file does not exist"}"#,
+    )
+    .unwrap();
+    assert_eq!(error.status, "error");
+    assert!(error.error.unwrap().contains("does not exist"));
+
+    assert!(parse_pull_line("qwen3", "  ").is_none());
+}
+
+#[test]
+fn test_pull_payload_follows_the_layers() {
+    let mut layer_eta = None;
+    let line = |line: &str| parse_pull_line("qwen3", line).unwrap();
+
+    let payload = pull_payload(&line(r#"{"status":"pulling manifest"}"#), &mut layer_eta);
+    assert_eq!(payload.phase, "preparing");
+    assert_eq!(payload.kind, ProgressKind::Indeterminate);
+
+    let payload = pull_payload(
+        &line(r#"{"status":"pulling abc","digest":"sha256:abc","total":100,"completed":40}"#),
+        &mut layer_eta,
+    );
+    assert_eq!(payload.phase, "downloading");
+    assert_eq!(payload.label_key, "progress.model_pull.downloading");
+    assert_eq!(payload.fraction, Some(0.4));
+    assert_eq!(layer_eta.as_ref().unwrap().0, "sha256:abc");
+
+    pull_payload(
+        &line(r#"{"status":"pulling def","digest":"sha256:def","total":10,"completed":0}"#),
+        &mut layer_eta,
+    );
+    assert_eq!(layer_eta.as_ref().unwrap().0, "sha256:def");
+
+    let payload = pull_payload(&line(r#"{"status":"writing manifest"}"#), &mut layer_eta);
+    assert_eq!(payload.phase, "verifying");
+    let payload = pull_payload(&line(r#"{"status":"success"}"#), &mut layer_eta);
+    assert_eq!(payload.state, ProgressState::Completed);
+    let payload = pull_payload(&line(r#"{"error":"no space left"}"#), &mut layer_eta);
+    assert_eq!(payload.state, ProgressState::Failed);
+}
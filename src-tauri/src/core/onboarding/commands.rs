@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+
+use tauri::{AppHandle, Emitter, Runtime, State};
+
+use super::constants::{ONBOARDING_CHANGED_EVENT, ONBOARDING_DOWNLOAD_TASK};
+use super::helpers::{
+    complete_step, install_default_mcp_servers, model_download_item, read_state, register_model,
+    scan_hardware, skip_step, update_state,
+};
+use super::models::{OnboardingState, OnboardingStep, ProviderKeyRequest, StepOutcome};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::cancellation::models::CancelScope;
+use crate::core::downloads::helpers::{_download_files_internal, emit_download_finished};
+use crate::core::mcp::helpers::start_mcp_server;
+use crate::core::offline::helpers::check_url;
+use crate::core::settings::helpers::{admin_policy, check_provider_allowed};
+use crate::core::state::{AppState, ProviderConfig};
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+fn emit_state<R: Runtime>(app: &AppHandle<R>, state: &OnboardingState) {
+    if let Err(e) = app.emit(ONBOARDING_CHANGED_EVENT, state) {
+        log::warn!("Failed to emit {ONBOARDING_CHANGED_EVENT}: {e}");
+    }
+}
+
+async fn finish_step<R: Runtime>(
+    app: &AppHandle<R>,
+    outcome: StepOutcome,
+) -> Result<OnboardingState, String> {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let state = update_state(&data_folder, |state| complete_step(state, outcome, now())).await?;
+    emit_state(app, &state);
+    Ok(state)
+}
+
+fn expect_step(state: &OnboardingState, step: OnboardingStep) -> Result<(), String> {
+    if state.step == step {
+        Ok(())
+    } else {
+        Err(format!("Setup is at {:?}, not {step:?}", state.step))
+    }
+}
+
+/// Returns where setup stands, to resume it
+#[tauri::command]
+pub fn get_onboarding_state<R: Runtime>(app_handle: AppHandle<R>) -> OnboardingState {
+    read_state(&get_jan_data_folder_path(app_handle))
+}
+
+/// Scans the hardware and picks the recommended model
+#[tauri::command]
+pub async fn run_onboarding_hardware_scan<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<OnboardingState, String> {
+    let hardware = tokio::task::spawn_blocking(scan_hardware)
+        .await
+        .map_err(|e| e.to_string())?;
+    finish_step(&app_handle, StepOutcome::HardwareScan { hardware }).await
+}
+
+/// Downloads the recommended model into the llama.cpp catalog. Progress is emitted as
+/// `download-onboarding-model`; an interrupted download continues where it stopped, and
+/// `cancel_download_task("onboarding-model")` stops it.
+#[tauri::command]
+pub async fn download_onboarding_model<R: Runtime>(
+    app_handle: AppHandle<R>,
+    state: State<'_, AppState>,
+) -> Result<OnboardingState, String> {
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+    let onboarding = read_state(&data_folder);
+    expect_step(&onboarding, OnboardingStep::ModelDownload)?;
+    let model = onboarding
+        .recommended_model
+        .ok_or("No model was recommended; scan the hardware first")?;
+    check_url(&app_handle, &model.url)?;
+
+    let guard = state.cancellations.open_replacing(
+        CancelScope::Download(ONBOARDING_DOWNLOAD_TASK.to_string()),
+        None,
+    )?;
+    let cancel_token = guard.token().clone();
+    let items = [model_download_item(&model)];
+    let result = _download_files_internal(
+        app_handle.clone(),
+        &items,
+        &HashMap::new(),
+        ONBOARDING_DOWNLOAD_TASK,
+        true,
+        cancel_token.clone(),
+    )
+    .await;
+    drop(guard);
+    emit_download_finished(
+        &app_handle,
+        &items,
+        ONBOARDING_DOWNLOAD_TASK,
+        cancel_token.is_cancelled(),
+        &result,
+    );
+    if cancel_token.is_cancelled() {
+        // The partial file stays, for the next attempt to continue
+        return Err("Download cancelled".to_string());
+    }
+    result?;
+
+    register_model(&data_folder, &model)?;
+    finish_step(
+        &app_handle,
+        StepOutcome::ModelDownload {
+            model_id: model.model_id,
+        },
+    )
+    .await
+}
+
+/// Registers a remote provider with its API key for this session. The key is kept with the
+/// provider settings, not in the onboarding state, which only records the provider's name.
+#[tauri::command]
+pub async fn set_onboarding_provider_key<R: Runtime>(
+    app_handle: AppHandle<R>,
+    state: State<'_, AppState>,
+    request: ProviderKeyRequest,
+) -> Result<OnboardingState, String> {
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+    expect_step(&read_state(&data_folder), OnboardingStep::ProviderKey)?;
+    if request.provider.trim().is_empty() || request.api_key.trim().is_empty() {
+        return Err("Enter a provider and its API key".to_string());
+    }
+    check_provider_allowed(admin_policy(), &request.provider)?;
+    state.provider_configs.write().await.insert(
+        request.provider.clone(),
+        ProviderConfig {
+            provider: request.provider.clone(),
+            api_key: Some(request.api_key),
+            base_url: request.base_url,
+            ..Default::default()
+        },
+    );
+    finish_step(
+        &app_handle,
+        StepOutcome::ProviderKey {
+            provider: request.provider,
+        },
+    )
+    .await
+}
+
+/// Adds the chosen default MCP servers to the config and starts them. A server that fails
+/// to start stays configured and is reported in the log.
+#[tauri::command]
+pub async fn install_onboarding_mcp_servers<R: Runtime>(
+    app_handle: AppHandle<R>,
+    state: State<'_, AppState>,
+    servers: Vec<String>,
+) -> Result<OnboardingState, String> {
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+    expect_step(&read_state(&data_folder), OnboardingStep::McpServers)?;
+    let installed = install_default_mcp_servers(&data_folder, &servers)?;
+    for (name, config) in installed {
+        if let Err(e) = start_mcp_server(
+            app_handle.clone(),
+            state.mcp_servers.clone(),
+            name.clone(),
+            config,
+        )
+        .await
+        {
+            log::warn!("Failed to start MCP server {name}: {e}");
+        }
+    }
+    finish_step(&app_handle, StepOutcome::McpServers { servers }).await
+}
+
+/// Skips the current step, when it is optional
+#[tauri::command]
+pub async fn skip_onboarding_step<R: Runtime>(
+    app_handle: AppHandle<R>,
+    step: OnboardingStep,
+) -> Result<OnboardingState, String> {
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+    let state = update_state(&data_folder, |state| skip_step(state, step, now())).await?;
+    emit_state(&app_handle, &state);
+    Ok(state)
+}
+
+/// Starts setup over. Downloaded models, keys and MCP servers stay.
+#[tauri::command]
+pub async fn reset_onboarding<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<OnboardingState, String> {
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+    let state = update_state(&data_folder, |state| {
+        *state = OnboardingState::default();
+        Ok(())
+    })
+    .await?;
+    emit_state(&app_handle, &state);
+    Ok(state)
+}
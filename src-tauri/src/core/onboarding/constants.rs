@@ -0,0 +1,38 @@
+// Onboarding constants
+pub const ONBOARDING_FILE: &str = "onboarding.json";
+/// Emitted with the `OnboardingState` after every change
+pub const ONBOARDING_CHANGED_EVENT: &str = "onboarding-changed";
+/// Download task of the recommended model; progress arrives as `download-onboarding-model`
+pub const ONBOARDING_DOWNLOAD_TASK: &str = "onboarding-model";
+
+/// A model suggested at first run
+pub struct RecommendedModelSpec {
+    pub repo: &'static str,
+    pub filename: &'static str,
+    /// Shown before downloading; the download reports the exact size
+    pub approx_size_bytes: u64,
+}
+
+/// Smallest first; the model picker recommends the largest that runs well here
+pub const RECOMMENDED_MODELS: &[RecommendedModelSpec] = &[
+    RecommendedModelSpec {
+        repo: "unsloth/Qwen3-0.6B-GGUF",
+        filename: "Qwen3-0.6B-Q4_K_M.gguf",
+        approx_size_bytes: 400_000_000,
+    },
+    RecommendedModelSpec {
+        repo: "unsloth/Qwen3-1.7B-GGUF",
+        filename: "Qwen3-1.7B-Q4_K_M.gguf",
+        approx_size_bytes: 1_100_000_000,
+    },
+    RecommendedModelSpec {
+        repo: "unsloth/Qwen3-4B-GGUF",
+        filename: "Qwen3-4B-Q4_K_M.gguf",
+        approx_size_bytes: 2_500_000_000,
+    },
+    RecommendedModelSpec {
+        repo: "unsloth/Qwen3-8B-GGUF",
+        filename: "Qwen3-8B-Q4_K_M.gguf",
+        approx_size_bytes: 5_000_000_000,
+    },
+];
@@ -0,0 +1,267 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde_json::{Map, Value};
+use tokio::sync::Mutex;
+
+use super::constants::{RecommendedModelSpec, ONBOARDING_FILE, RECOMMENDED_MODELS};
+use super::models::{
+    GpuSummary, HardwareSummary, OnboardingState, OnboardingStep, RecommendedModel, StepOutcome,
+    StepRecord, StepStatus,
+};
+use crate::core::config_history::helpers::record_config_change;
+use crate::core::config_store::helpers::{config_store, write_json};
+use crate::core::downloads::models::DownloadItem;
+use crate::core::mcp::constants::DEFAULT_MCP_CONFIG;
+use crate::core::mcp::migrations::update_config;
+use crate::core::model_catalog::helpers::write_model;
+use crate::core::model_catalog::models::CatalogModel;
+use crate::core::model_picker::constants::DEFAULT_CONTEXT_SIZE;
+use crate::core::model_picker::helpers::{rank_models, starter_candidates};
+
+static ONBOARDING_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+pub fn get_onboarding_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(ONBOARDING_FILE)
+}
+
+/// The stored state, a fresh one when there is none
+pub fn read_state(data_folder: &Path) -> OnboardingState {
+    let path = get_onboarding_path(data_folder);
+    let Ok(data) = fs::read_to_string(&path) else {
+        return OnboardingState::default();
+    };
+    serde_json::from_str(&data).unwrap_or_else(|e| {
+        log::warn!("Ignoring invalid {}: {e}", path.display());
+        OnboardingState::default()
+    })
+}
+
+/// Read-modify-write the state
+pub async fn update_state(
+    data_folder: &Path,
+    update: impl FnOnce(&mut OnboardingState) -> Result<(), String>,
+) -> Result<OnboardingState, String> {
+    let _guard = ONBOARDING_LOCK.get_or_init(Default::default).lock().await;
+    let mut state = read_state(data_folder);
+    update(&mut state)?;
+    write_json(&get_onboarding_path(data_folder), &state)?;
+    Ok(state)
+}
+
+pub fn next_step(step: OnboardingStep) -> OnboardingStep {
+    match step {
+        OnboardingStep::HardwareScan => OnboardingStep::ModelDownload,
+        OnboardingStep::ModelDownload => OnboardingStep::ProviderKey,
+        OnboardingStep::ProviderKey => OnboardingStep::McpServers,
+        OnboardingStep::McpServers | OnboardingStep::Done => OnboardingStep::Done,
+    }
+}
+
+pub fn is_optional(step: OnboardingStep) -> bool {
+    matches!(
+        step,
+        OnboardingStep::ModelDownload | OnboardingStep::ProviderKey | OnboardingStep::McpServers
+    )
+}
+
+fn outcome_step(outcome: &StepOutcome) -> OnboardingStep {
+    match outcome {
+        StepOutcome::HardwareScan { .. } => OnboardingStep::HardwareScan,
+        StepOutcome::ModelDownload { .. } => OnboardingStep::ModelDownload,
+        StepOutcome::ProviderKey { .. } => OnboardingStep::ProviderKey,
+        StepOutcome::McpServers { .. } => OnboardingStep::McpServers,
+    }
+}
+
+fn advance(state: &mut OnboardingState, status: StepStatus, now: i64) {
+    state.history.push(StepRecord {
+        step: state.step,
+        status,
+        at: now,
+    });
+    state.step = next_step(state.step);
+}
+
+/// Finish the current step with its outcome
+pub fn complete_step(
+    state: &mut OnboardingState,
+    outcome: StepOutcome,
+    now: i64,
+) -> Result<(), String> {
+    let step = outcome_step(&outcome);
+    if step != state.step {
+        return Err(format!("Setup is at {:?}, not {step:?}", state.step));
+    }
+    match outcome {
+        StepOutcome::HardwareScan { hardware } => {
+            state.recommended_model = Some(recommend_model(&hardware));
+            state.hardware = Some(hardware);
+        }
+        StepOutcome::ModelDownload { model_id } => state.installed_model = Some(model_id),
+        StepOutcome::ProviderKey { provider } => state.provider = Some(provider),
+        StepOutcome::McpServers { servers } => state.mcp_servers = servers,
+    }
+    advance(state, StepStatus::Completed, now);
+    Ok(())
+}
+
+/// Skip the current step, when it is optional
+pub fn skip_step(
+    state: &mut OnboardingState,
+    step: OnboardingStep,
+    now: i64,
+) -> Result<(), String> {
+    if step != state.step {
+        return Err(format!("Setup is at {:?}, not {step:?}", state.step));
+    }
+    if !is_optional(step) {
+        return Err(format!("{step:?} can't be skipped"));
+    }
+    advance(state, StepStatus::Skipped, now);
+    Ok(())
+}
+
+/// The suggestion scored best for `hardware` by the model picker, the smallest when none
+/// runs well
+pub fn recommend_model(hardware: &HardwareSummary) -> RecommendedModel {
+    let ranked = rank_models(hardware, starter_candidates(), DEFAULT_CONTEXT_SIZE);
+    let spec = ranked
+        .best_quality
+        .and_then(|pick| {
+            RECOMMENDED_MODELS
+                .iter()
+                .find(|spec| spec.repo == pick.candidate.model_id)
+        })
+        .unwrap_or(&RECOMMENDED_MODELS[0]);
+    recommended_model(spec)
+}
+
+pub fn recommended_model(spec: &RecommendedModelSpec) -> RecommendedModel {
+    RecommendedModel {
+        model_id: spec.repo.to_string(),
+        filename: spec.filename.to_string(),
+        url: format!(
+            "https://huggingface.co/{}/resolve/main/{}",
+            spec.repo, spec.filename
+        ),
+        approx_size_bytes: spec.approx_size_bytes,
+    }
+}
+
+#[cfg(feature = "hardware")]
+pub fn scan_hardware() -> HardwareSummary {
+    let info = tauri_plugin_hardware::get_system_info();
+    HardwareSummary {
+        os: info.os_name,
+        arch: info.cpu.arch,
+        cpu: info.cpu.name,
+        total_memory_mb: info.total_memory,
+        gpus: info
+            .gpus
+            .into_iter()
+            .map(|gpu| GpuSummary {
+                name: gpu.name,
+                vram_mb: gpu.total_memory,
+            })
+            .collect(),
+    }
+}
+
+/// Without the hardware plugin GPUs are not detected, so the RAM decides
+#[cfg(not(feature = "hardware"))]
+pub fn scan_hardware() -> HardwareSummary {
+    let mut system = sysinfo::System::new();
+    system.refresh_memory();
+    HardwareSummary {
+        os: sysinfo::System::long_os_version().unwrap_or_else(|| std::env::consts::OS.into()),
+        arch: std::env::consts::ARCH.to_string(),
+        cpu: String::new(),
+        total_memory_mb: system.total_memory() / 1024 / 1024,
+        gpus: Vec::new(),
+    }
+}
+
+/// Where the recommended model is saved, relative to the data folder
+pub fn model_save_path(model: &RecommendedModel) -> String {
+    format!("llamacpp/models/{}/{}", model.model_id, model.filename)
+}
+
+pub fn model_download_item(model: &RecommendedModel) -> DownloadItem {
+    DownloadItem {
+        url: model.url.clone(),
+        save_path: model_save_path(model),
+        proxy: None,
+        sha256: None,
+        size: None,
+        model_id: Some(model.model_id.clone()),
+    }
+}
+
+/// Add the downloaded model to the llama.cpp catalog
+pub fn register_model(data_folder: &Path, model: &RecommendedModel) -> Result<(), String> {
+    let save_path = model_save_path(model);
+    let size_bytes = fs::metadata(data_folder.join(&save_path))
+        .map_err(|e| format!("Downloaded model is missing: {e}"))?
+        .len();
+    let name = model.model_id.rsplit('/').next().map(str::to_string);
+    write_model(
+        data_folder,
+        "llamacpp",
+        &model.model_id,
+        &CatalogModel {
+            model_path: save_path,
+            name,
+            size_bytes,
+            ..Default::default()
+        },
+    )
+}
+
+fn default_mcp_servers() -> Map<String, Value> {
+    serde_json::from_str::<Value>(DEFAULT_MCP_CONFIG)
+        .ok()
+        .and_then(|config| config["mcpServers"].as_object().cloned())
+        .unwrap_or_default()
+}
+
+pub fn default_mcp_server_names() -> Vec<String> {
+    default_mcp_servers().keys().cloned().collect()
+}
+
+/// Add the chosen default servers to `mcp_config.json`, active, keeping the configuration
+/// of servers already there. Returns each server's configuration, to start it.
+pub fn install_default_mcp_servers(
+    data_folder: &Path,
+    names: &[String],
+) -> Result<Vec<(String, Value)>, String> {
+    let defaults = default_mcp_servers();
+    if let Some(unknown) = names.iter().find(|name| !defaults.contains_key(*name)) {
+        return Err(format!("'{unknown}' is not a default MCP server"));
+    }
+    let path = data_folder.join("mcp_config.json");
+    if !path.exists() {
+        config_store().write(&path, DEFAULT_MCP_CONFIG)?;
+    }
+    let mut installed = Vec::new();
+    update_config(&path, |config| {
+        let servers = config
+            .as_object_mut()
+            .ok_or("Invalid MCP config")?
+            .entry("mcpServers")
+            .or_insert_with(|| Value::Object(Map::new()))
+            .as_object_mut()
+            .ok_or("Invalid MCP config")?;
+        for name in names {
+            let server = servers
+                .entry(name.clone())
+                .or_insert_with(|| defaults[name].clone());
+            server["active"] = Value::Bool(true);
+            installed.push((name.clone(), server.clone()));
+        }
+        Ok(())
+    })?;
+    record_config_change(data_folder, "Install default MCP servers");
+    Ok(installed)
+}
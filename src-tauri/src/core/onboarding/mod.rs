@@ -0,0 +1,22 @@
+/*!
+   First-Run Setup
+
+   The onboarding flow as a state machine in the core, so the app and `jan setup` walk the
+   same steps and a user who quits halfway picks up where they left:
+   1. hardware scan, which also picks the recommended model,
+   2. download of the recommended model (optional),
+   3. a provider API key (optional),
+   4. default MCP servers (optional).
+
+   The state lives in `onboarding.json`. A step only completes with the outcome of that step,
+   and only optional steps can be skipped. An interrupted model download resumes from the
+   partial file.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
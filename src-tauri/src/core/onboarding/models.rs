@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OnboardingStep {
+    #[default]
+    HardwareScan,
+    ModelDownload,
+    ProviderKey,
+    McpServers,
+    Done,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GpuSummary {
+    pub name: String,
+    pub vram_mb: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HardwareSummary {
+    pub os: String,
+    pub arch: String,
+    pub cpu: String,
+    pub total_memory_mb: u64,
+    pub gpus: Vec<GpuSummary>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecommendedModel {
+    /// Catalog id, the Hugging Face repo
+    pub model_id: String,
+    pub filename: String,
+    pub url: String,
+    pub approx_size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    Completed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepRecord {
+    pub step: OnboardingStep,
+    pub status: StepStatus,
+    /// Milliseconds since epoch
+    pub at: i64,
+}
+
+/// Contents of `onboarding.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OnboardingState {
+    /// The step to do next, `done` once finished
+    pub step: OnboardingStep,
+    pub history: Vec<StepRecord>,
+    pub hardware: Option<HardwareSummary>,
+    pub recommended_model: Option<RecommendedModel>,
+    pub installed_model: Option<String>,
+    /// Name of the provider given a key; the key itself is not kept here
+    pub provider: Option<String>,
+    pub mcp_servers: Vec<String>,
+}
+
+/// What finishing a step produced
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum StepOutcome {
+    HardwareScan { hardware: HardwareSummary },
+    ModelDownload { model_id: String },
+    ProviderKey { provider: String },
+    McpServers { servers: Vec<String> },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderKeyRequest {
+    pub provider: String,
+    pub api_key: String,
+    pub base_url: Option<String>,
+}
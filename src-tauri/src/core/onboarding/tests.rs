@@ -0,0 +1,128 @@
+use serde_json::json;
+
+use super::helpers::{
+    complete_step, default_mcp_server_names, install_default_mcp_servers, read_state,
+    recommend_model, skip_step, update_state,
+};
+use super::models::{
+    GpuSummary, HardwareSummary, OnboardingState, OnboardingStep, StepOutcome, StepStatus,
+};
+
+fn hardware(total_memory_mb: u64, vram_mb: &[u64]) -> HardwareSummary {
+    HardwareSummary {
+        os: "linux".to_string(),
+        arch: "x86_64".to_string(),
+        cpu: "cpu".to_string(),
+        total_memory_mb,
+        gpus: vram_mb
+            .iter()
+            .map(|vram_mb| GpuSummary {
+                name: "gpu".to_string(),
+                vram_mb: *vram_mb,
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn test_recommend_model() {
+    assert_eq!(
+        recommend_model(&hardware(2_048, &[])).model_id,
+        "unsloth/Qwen3-0.6B-GGUF"
+    );
+    assert_eq!(
+        recommend_model(&hardware(16_384, &[])).model_id,
+        "unsloth/Qwen3-8B-GGUF"
+    );
+    let model = recommend_model(&hardware(6_144, &[]));
+    assert_eq!(model.model_id, "unsloth/Qwen3-4B-GGUF");
+    assert_eq!(
+        model.url,
+        "https://huggingface.co/unsloth/Qwen3-4B-GGUF/resolve/main/Qwen3-4B-Q4_K_M.gguf"
+    );
+    // A model larger than the VRAM still runs well split with the RAM
+    assert_eq!(
+        recommend_model(&hardware(65_536, &[2_048, 6_144])).model_id,
+        "unsloth/Qwen3-8B-GGUF"
+    );
+}
+
+#[test]
+fn test_step_transitions() {
+    let mut state = OnboardingState::default();
+    assert!(skip_step(&mut state, OnboardingStep::HardwareScan, 1).is_err());
+    let outcome = StepOutcome::ProviderKey {
+        provider: "openai".to_string(),
+    };
+    assert!(complete_step(&mut state, outcome, 1).is_err());
+
+    complete_step(
+        &mut state,
+        StepOutcome::HardwareScan {
+            hardware: hardware(8_192, &[]),
+        },
+        1,
+    )
+    .unwrap();
+    assert_eq!(state.step, OnboardingStep::ModelDownload);
+    assert!(state.recommended_model.is_some());
+
+    skip_step(&mut state, OnboardingStep::ModelDownload, 2).unwrap();
+    let outcome = StepOutcome::ProviderKey {
+        provider: "openai".to_string(),
+    };
+    complete_step(&mut state, outcome, 3).unwrap();
+    skip_step(&mut state, OnboardingStep::McpServers, 4).unwrap();
+    assert_eq!(state.step, OnboardingStep::Done);
+    assert_eq!(state.provider.as_deref(), Some("openai"));
+    let statuses: Vec<_> = state.history.iter().map(|r| r.status).collect();
+    assert_eq!(
+        statuses,
+        vec![
+            StepStatus::Completed,
+            StepStatus::Skipped,
+            StepStatus::Completed,
+            StepStatus::Skipped
+        ]
+    );
+    assert!(skip_step(&mut state, OnboardingStep::Done, 5).is_err());
+}
+
+#[tokio::test]
+async fn test_state_resumes_and_installs_mcp_servers() {
+    let dir = std::env::temp_dir().join(format!("jan-onboarding-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    // A refused transition leaves the stored state alone
+    update_state(&dir, |state| {
+        skip_step(state, OnboardingStep::HardwareScan, 1)
+    })
+    .await
+    .unwrap_err();
+    update_state(&dir, |state| {
+        complete_step(
+            state,
+            StepOutcome::HardwareScan {
+                hardware: hardware(4_096, &[]),
+            },
+            1,
+        )
+    })
+    .await
+    .unwrap();
+    assert_eq!(read_state(&dir).step, OnboardingStep::ModelDownload);
+
+    std::fs::write(
+        dir.join("mcp_config.json"),
+        json!({"version": 1, "mcpServers": {"fetch": {"command": "custom", "active": false}}})
+            .to_string(),
+    )
+    .unwrap();
+    let names = default_mcp_server_names();
+    assert!(names.contains(&"fetch".to_string()));
+    let installed = install_default_mcp_servers(&dir, &["fetch".to_string()]).unwrap();
+    assert_eq!(installed[0].1["command"], "custom");
+    assert_eq!(installed[0].1["active"], true);
+    assert!(install_default_mcp_servers(&dir, &["unknown".to_string()]).is_err());
+
+    let _ = std::fs::remove_dir_all(dir);
+}
@@ -0,0 +1,11 @@
+use tauri::State;
+
+use super::models::TraceExportStatus;
+use super::TraceExport;
+
+/// Returns whether spans are being exported and where to. Export is configured through the
+/// `tracing` section of `set_settings`.
+#[tauri::command]
+pub fn get_trace_export_status(export: State<'_, TraceExport>) -> TraceExportStatus {
+    export.status()
+}
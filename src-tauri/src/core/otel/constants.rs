@@ -0,0 +1,7 @@
+// Trace export constants
+/// Path of the OTLP/HTTP traces endpoint on a collector
+pub const OTLP_TRACES_PATH: &str = "/v1/traces";
+/// Instrumentation scope the spans are reported under
+pub const TRACER_NAME: &str = "jan";
+pub const EXPORT_UNAVAILABLE_ERROR: &str =
+    "Trace export is not available in this build (the otel feature is disabled)";
@@ -0,0 +1,109 @@
+//! OTLP export of `tracing` spans. The subscriber is installed globally on first use and only
+//! its export layer is swapped afterwards, so the endpoint can change while the app runs.
+
+#[cfg(feature = "otel")]
+pub use otlp::{stop_export, Exporter, AVAILABLE};
+
+#[cfg(not(feature = "otel"))]
+pub use unavailable::{stop_export, Exporter, AVAILABLE};
+
+#[cfg(feature = "otel")]
+mod otlp {
+    use std::sync::OnceLock;
+
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+    use opentelemetry_sdk::trace::{Tracer, TracerProvider};
+    use opentelemetry_sdk::{runtime, Resource};
+    use tracing_opentelemetry::OpenTelemetryLayer;
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::util::SubscriberInitExt;
+    use tracing_subscriber::{reload, Registry};
+
+    use super::super::constants::TRACER_NAME;
+
+    pub const AVAILABLE: bool = true;
+
+    type ExportLayer = OpenTelemetryLayer<Registry, Tracer>;
+    type LayerHandle = reload::Handle<Option<ExportLayer>, Registry>;
+
+    static LAYER: OnceLock<LayerHandle> = OnceLock::new();
+
+    fn layer_handle() -> Result<&'static LayerHandle, String> {
+        if let Some(handle) = LAYER.get() {
+            return Ok(handle);
+        }
+        let (layer, handle) = reload::Layer::new(None);
+        tracing_subscriber::registry()
+            .with(layer)
+            .try_init()
+            .map_err(|e| format!("Failed to install the trace subscriber: {e}"))?;
+        Ok(LAYER.get_or_init(|| handle))
+    }
+
+    pub struct Exporter {
+        provider: TracerProvider,
+    }
+
+    impl Exporter {
+        /// Start exporting spans to the OTLP/HTTP traces URL `url`. Must be called from within
+        /// the async runtime, which runs the batch export.
+        pub fn start(url: &str, service_name: &str, service_version: &str) -> Result<Self, String> {
+            let exporter = SpanExporter::builder()
+                .with_http()
+                .with_endpoint(url)
+                .build()
+                .map_err(|e| format!("Failed to create the OTLP exporter: {e}"))?;
+            let provider = TracerProvider::builder()
+                .with_batch_exporter(exporter, runtime::Tokio)
+                .with_resource(Resource::new([
+                    KeyValue::new("service.name", service_name.to_string()),
+                    KeyValue::new("service.version", service_version.to_string()),
+                ]))
+                .build();
+            let layer = tracing_opentelemetry::layer().with_tracer(provider.tracer(TRACER_NAME));
+            layer_handle()?
+                .reload(Some(layer))
+                .map_err(|e| format!("Failed to enable trace export: {e}"))?;
+            Ok(Self { provider })
+        }
+
+        /// Send the spans still buffered and stop. Blocks until the batch is sent.
+        pub fn shutdown(self) {
+            if let Err(e) = self.provider.shutdown() {
+                log::warn!("Failed to flush exported spans: {e}");
+            }
+        }
+    }
+
+    /// Stop recording spans for export; a running exporter keeps sending what it buffered
+    pub fn stop_export() {
+        if let Some(handle) = LAYER.get() {
+            let _ = handle.reload(None);
+        }
+    }
+}
+
+#[cfg(not(feature = "otel"))]
+mod unavailable {
+    use super::super::constants::EXPORT_UNAVAILABLE_ERROR;
+
+    pub const AVAILABLE: bool = false;
+
+    pub struct Exporter;
+
+    impl Exporter {
+        pub fn start(
+            _url: &str,
+            _service_name: &str,
+            _service_version: &str,
+        ) -> Result<Self, String> {
+            Err(EXPORT_UNAVAILABLE_ERROR.to_string())
+        }
+
+        pub fn shutdown(self) {}
+    }
+
+    pub fn stop_export() {}
+}
@@ -0,0 +1,96 @@
+use tauri::{AppHandle, Manager, Runtime};
+
+use super::constants::OTLP_TRACES_PATH;
+use super::exporter::{stop_export, Exporter};
+use super::TraceExport;
+use crate::core::offline::helpers::check_url;
+use crate::core::settings::helpers::tracing_settings;
+use crate::core::settings::models::TracingSettings;
+
+/// Traces URL for a configured OTLP/HTTP endpoint; a collector's base URL gets the standard
+/// `/v1/traces` path, any other path is kept as given
+pub fn otlp_traces_url(endpoint: &str) -> Result<String, String> {
+    let endpoint = endpoint.trim();
+    let url = url::Url::parse(endpoint)
+        .map_err(|e| format!("tracing.otlp_endpoint is not a valid URL: {e}"))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err("tracing.otlp_endpoint must be an http or https URL".to_string());
+    }
+    if url.path() == "/" && url.query().is_none() {
+        return Ok(format!(
+            "{}{OTLP_TRACES_PATH}",
+            endpoint.trim_end_matches('/')
+        ));
+    }
+    Ok(endpoint.to_string())
+}
+
+fn start_exporter<R: Runtime>(
+    app: &AppHandle<R>,
+    settings: &TracingSettings,
+) -> Result<Option<(Exporter, String)>, String> {
+    if !settings.enabled {
+        return Ok(None);
+    }
+    let url = otlp_traces_url(&settings.otlp_endpoint)?;
+    // Spans leave the machine unless the collector runs on it
+    check_url(app, &url)?;
+    let exporter = Exporter::start(
+        &url,
+        settings.service_name.trim(),
+        &app.package_info().version.to_string(),
+    )?;
+    log::info!("Exporting traces to {url}");
+    Ok(Some((exporter, url)))
+}
+
+/// Start, restart or stop the span export to match `settings`
+pub async fn apply_tracing_settings<R: Runtime>(
+    app: &AppHandle<R>,
+    settings: &TracingSettings,
+) -> Result<(), String> {
+    let Some(export) = app.try_state::<TraceExport>() else {
+        return Ok(());
+    };
+    let (previous, result) = match start_exporter(app, settings) {
+        Ok(Some(exporter)) => (export.install(Some(exporter)), Ok(())),
+        Ok(None) => {
+            stop_export();
+            (export.install(None), Ok(()))
+        }
+        Err(e) => {
+            log::warn!("Failed to start trace export: {e}");
+            stop_export();
+            (export.fail(&e), Err(e))
+        }
+    };
+    if let Some(previous) = previous {
+        // Sends the spans still buffered for the previous endpoint
+        let _ = tokio::task::spawn_blocking(move || previous.shutdown()).await;
+    }
+    result
+}
+
+/// Start exporting spans if the user enabled it. Call after the settings are loaded.
+pub fn load_tracing<R: Runtime>(app: &AppHandle<R>) {
+    let settings = tracing_settings(app);
+    if !settings.enabled {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        // Failures are logged and reported by `get_trace_export_status`
+        let _ = apply_tracing_settings(&app, &settings).await;
+    });
+}
+
+/// Send the buffered spans before the app exits
+pub fn shutdown_tracing<R: Runtime>(app: &AppHandle<R>) {
+    let Some(export) = app.try_state::<TraceExport>() else {
+        return;
+    };
+    stop_export();
+    if let Some(exporter) = export.install(None) {
+        tokio::task::block_in_place(|| exporter.shutdown());
+    }
+}
@@ -0,0 +1,73 @@
+/*!
+   Trace Export
+
+   Agent runs, model turns, provider requests and MCP tool calls are recorded as `tracing`
+   spans. A chat turn is one `agent.run` span with an `llm.chat` span per model turn and a
+   `tool.call` span per tool call, the MCP request nested inside it; requests to the local API
+   server get a `provider.request` span. Without a subscriber the spans cost next to nothing.
+
+   When `tracing.enabled` is set, the spans are exported over OTLP/HTTP to the configured
+   collector, e.g. Jaeger, so self-hosters can follow a turn end to end. Spans carry ids, model
+   and tool names, token counts and timings, never message content or tool arguments.
+   Exporting needs the `otel` feature; builds without it report it as unavailable.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod exporter;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use exporter::Exporter;
+use models::TraceExportStatus;
+
+#[derive(Default)]
+struct ExportState {
+    exporter: Option<Exporter>,
+    endpoint: Option<String>,
+    error: Option<String>,
+}
+
+/// The running span exporter, if any
+#[derive(Clone, Default)]
+pub struct TraceExport {
+    state: Arc<Mutex<ExportState>>,
+}
+
+impl TraceExport {
+    fn state(&self) -> MutexGuard<'_, ExportState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Make `exporter` the running one, returning the one it replaces
+    pub fn install(&self, exporter: Option<(Exporter, String)>) -> Option<Exporter> {
+        let mut state = self.state();
+        let (exporter, endpoint) = exporter.unzip();
+        state.endpoint = endpoint;
+        state.error = None;
+        std::mem::replace(&mut state.exporter, exporter)
+    }
+
+    /// Record that the configured exporter failed to start, returning the one it replaces
+    pub fn fail(&self, error: &str) -> Option<Exporter> {
+        let mut state = self.state();
+        state.endpoint = None;
+        state.error = Some(error.to_string());
+        state.exporter.take()
+    }
+
+    pub fn status(&self) -> TraceExportStatus {
+        let state = self.state();
+        TraceExportStatus {
+            available: exporter::AVAILABLE,
+            exporting: state.exporter.is_some(),
+            endpoint: state.endpoint.clone(),
+            error: state.error.clone(),
+        }
+    }
+}
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// State of the span export
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceExportStatus {
+    /// Whether this build can export spans at all
+    pub available: bool,
+    pub exporting: bool,
+    /// Traces URL spans are sent to while exporting
+    pub endpoint: Option<String>,
+    /// Why the configured export could not be started
+    pub error: Option<String>,
+}
@@ -0,0 +1,52 @@
+use serde_json::json;
+
+use super::helpers::otlp_traces_url;
+use super::TraceExport;
+use crate::core::settings::helpers::apply_patch;
+use crate::core::settings::models::Settings;
+
+#[test]
+fn test_otlp_traces_url() {
+    assert_eq!(
+        otlp_traces_url("http://localhost:4318").unwrap(),
+        "http://localhost:4318/v1/traces"
+    );
+    assert_eq!(
+        otlp_traces_url(" http://jaeger:4318/ ").unwrap(),
+        "http://jaeger:4318/v1/traces"
+    );
+    // A path is taken as the full traces URL, e.g. behind a reverse proxy
+    assert_eq!(
+        otlp_traces_url("https://otel.example.com/ingest/traces").unwrap(),
+        "https://otel.example.com/ingest/traces"
+    );
+    assert!(otlp_traces_url("localhost:4318").is_err());
+    assert!(otlp_traces_url("grpc://localhost:4317").is_err());
+}
+
+#[test]
+fn test_tracing_settings_are_validated_when_enabled() {
+    let current = Settings::default();
+    assert!(!current.tracing.enabled);
+    // An endpoint is only checked once export is enabled
+    let draft = apply_patch(&current, &json!({"tracing": {"otlp_endpoint": "nope"}})).unwrap();
+    let enabled = apply_patch(&draft, &json!({"tracing": {"enabled": true}}));
+    assert!(enabled.unwrap_err().contains("tracing.otlp_endpoint"));
+    let unnamed = apply_patch(&current, &json!({"tracing": {"service_name": " "}}));
+    assert!(unnamed.unwrap_err().contains("tracing.service_name"));
+}
+
+#[test]
+fn test_status_without_exporter() {
+    let export = TraceExport::default();
+    let status = export.status();
+    assert!(!status.exporting);
+    assert_eq!(status.endpoint, None);
+
+    assert!(export.fail("collector unreachable").is_none());
+    let status = export.status();
+    assert!(!status.exporting);
+    assert_eq!(status.error.as_deref(), Some("collector unreachable"));
+    assert!(export.install(None).is_none());
+    assert_eq!(export.status().error, None);
+}
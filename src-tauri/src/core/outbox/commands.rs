@@ -0,0 +1,116 @@
+use tauri::{AppHandle, Runtime};
+use uuid::Uuid;
+
+use super::helpers::{emit_delivery, read_outbox, update_outbox, wake_outbox};
+use super::models::{DeliveryStatus, OutboxMessage, QueueMessageInput};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::settings::helpers::outbox_settings;
+
+/// Queues a chat request for delivery once its provider can be reached. The reply is
+/// written into the thread; progress is reported through `outbox-delivery` events.
+#[tauri::command]
+pub async fn queue_outgoing_message<R: Runtime>(
+    app_handle: AppHandle<R>,
+    input: QueueMessageInput,
+) -> Result<OutboxMessage, String> {
+    if !outbox_settings(&app_handle).enabled {
+        return Err("Queueing messages is turned off in the settings".to_string());
+    }
+    if input.model.trim().is_empty() {
+        return Err("A model is required to queue a message".to_string());
+    }
+    if input.messages.is_empty() {
+        return Err("Nothing to send".to_string());
+    }
+    let message = OutboxMessage {
+        id: Uuid::new_v4().to_string(),
+        thread_id: input.thread_id,
+        model: input.model,
+        messages: input.messages,
+        parameters: input.parameters,
+        user_message_id: input.user_message_id,
+        status: DeliveryStatus::Queued,
+        attempts: 0,
+        created_at: chrono::Utc::now().timestamp_millis(),
+        next_attempt_at: 0,
+        last_error: None,
+        reply_message_id: None,
+    };
+
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+    let queued = message.clone();
+    update_outbox(&data_folder, move |queue| {
+        queue.push(queued);
+        Ok(())
+    })
+    .await?;
+    emit_delivery(&app_handle, &message);
+    wake_outbox();
+    Ok(message)
+}
+
+/// Lists the messages waiting in the outbox, optionally only those of one thread.
+#[tauri::command]
+pub async fn list_outbox<R: Runtime>(
+    app_handle: AppHandle<R>,
+    thread_id: Option<String>,
+) -> Result<Vec<OutboxMessage>, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    Ok(read_outbox(&data_folder)
+        .into_iter()
+        .filter(|message| match &thread_id {
+            Some(thread_id) => &message.thread_id == thread_id,
+            None => true,
+        })
+        .collect())
+}
+
+/// Retries a queued or failed message right away, with a fresh set of attempts.
+#[tauri::command]
+pub async fn retry_outbox_message<R: Runtime>(
+    app_handle: AppHandle<R>,
+    message_id: String,
+) -> Result<OutboxMessage, String> {
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+    let message = update_outbox(&data_folder, |queue| {
+        let message = queue
+            .iter_mut()
+            .find(|message| message.id == message_id)
+            .ok_or_else(|| format!("Queued message {message_id} not found"))?;
+        if message.status == DeliveryStatus::Sending {
+            return Err("The message is being sent".to_string());
+        }
+        message.status = DeliveryStatus::Queued;
+        message.attempts = 0;
+        message.next_attempt_at = 0;
+        Ok(message.clone())
+    })
+    .await?;
+    emit_delivery(&app_handle, &message);
+    wake_outbox();
+    Ok(message)
+}
+
+/// Removes a message from the outbox without sending it.
+#[tauri::command]
+pub async fn remove_outbox_message<R: Runtime>(
+    app_handle: AppHandle<R>,
+    message_id: String,
+) -> Result<(), String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    update_outbox(&data_folder, |queue| {
+        let index = queue
+            .iter()
+            .position(|message| message.id == message_id)
+            .ok_or_else(|| format!("Queued message {message_id} not found"))?;
+        if queue[index].status == DeliveryStatus::Sending {
+            return Err("The message is being sent".to_string());
+        }
+        queue.remove(index);
+        Ok(())
+    })
+    .await?;
+    // The next message of the thread may go now
+    wake_outbox();
+    Ok(())
+}
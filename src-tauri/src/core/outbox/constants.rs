@@ -0,0 +1,11 @@
+use std::time::Duration;
+
+// Outbox constants
+pub const OUTBOX_FILE: &str = "outbox.json";
+/// Emitted with the queued message whenever its delivery status changes
+pub const OUTBOX_DELIVERY_EVENT: &str = "outbox-delivery";
+/// How often the worker looks for messages that are due
+pub const OUTBOX_TICK: Duration = Duration::from_secs(10);
+/// Delay before the first retry, doubled on each further one
+pub const OUTBOX_BASE_RETRY_DELAY: Duration = Duration::from_secs(5);
+pub const OUTBOX_MAX_RETRY_DELAY: Duration = Duration::from_secs(300);
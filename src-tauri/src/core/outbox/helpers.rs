@@ -0,0 +1,272 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Runtime};
+use tokio::sync::{Mutex, Notify};
+
+use super::constants::{
+    OUTBOX_BASE_RETRY_DELAY, OUTBOX_DELIVERY_EVENT, OUTBOX_FILE, OUTBOX_MAX_RETRY_DELAY,
+    OUTBOX_TICK,
+};
+use super::models::{DeliveryStatus, OutboxMessage};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::config_store::helpers::write_json;
+use crate::core::inference::helpers::{
+    chat_completion, completion_text, resolve_model_endpoint, DEFAULT_COMPLETION_TIMEOUT,
+};
+use crate::core::network::helpers::subscribe_network_changes;
+use crate::core::offline::constants::OFFLINE_ERROR;
+use crate::core::settings::helpers::outbox_settings;
+use crate::core::threads::commands::create_message;
+
+static OUTBOX_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+static OUTBOX_WAKE: OnceLock<Notify> = OnceLock::new();
+
+fn outbox_wake() -> &'static Notify {
+    OUTBOX_WAKE.get_or_init(Notify::new)
+}
+
+pub fn get_outbox_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(OUTBOX_FILE)
+}
+
+/// Queued messages in the order they were queued, empty when there are none
+pub fn read_outbox(data_folder: &Path) -> Vec<OutboxMessage> {
+    let path = get_outbox_path(data_folder);
+    let Ok(data) = fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+    serde_json::from_str(&data).unwrap_or_else(|e| {
+        log::warn!("Ignoring invalid {}: {e}", path.display());
+        Vec::new()
+    })
+}
+
+/// Read-modify-write the queue
+pub async fn update_outbox<T>(
+    data_folder: &Path,
+    update: impl FnOnce(&mut Vec<OutboxMessage>) -> Result<T, String>,
+) -> Result<T, String> {
+    let _guard = OUTBOX_LOCK.get_or_init(Default::default).lock().await;
+    let path = get_outbox_path(data_folder);
+    let mut queue = read_outbox(data_folder);
+    let result = update(&mut queue)?;
+    if !queue.is_empty() || path.exists() {
+        write_json(&path, &queue)?;
+    }
+    Ok(result)
+}
+
+/// Wait before attempt `attempts + 1`: the base delay, doubled per failed attempt
+pub fn retry_delay(attempts: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+    OUTBOX_BASE_RETRY_DELAY
+        .saturating_mul(factor)
+        .min(OUTBOX_MAX_RETRY_DELAY)
+}
+
+/// The message to deliver next: the oldest undelivered message of a thread, when it is
+/// queued and due. Threads whose oldest message is sending or failed wait.
+pub fn next_due(queue: &[OutboxMessage], now: i64) -> Option<usize> {
+    let mut seen = HashSet::new();
+    queue.iter().enumerate().find_map(|(index, message)| {
+        if message.status == DeliveryStatus::Delivered || !seen.insert(&message.thread_id) {
+            return None;
+        }
+        (message.status == DeliveryStatus::Queued && message.next_attempt_at <= now)
+            .then_some(index)
+    })
+}
+
+/// Record the outcome of a delivery attempt. Waiting for offline mode to end is not an
+/// attempt; other failures count towards `max_attempts`.
+pub fn record_attempt(
+    message: &mut OutboxMessage,
+    result: Result<String, String>,
+    max_attempts: u32,
+    now: i64,
+) {
+    match result {
+        Ok(reply_message_id) => {
+            message.status = DeliveryStatus::Delivered;
+            message.attempts += 1;
+            message.reply_message_id = Some(reply_message_id);
+            message.last_error = None;
+        }
+        // Retried when offline mode is switched off, see `retry_outbox_now`
+        Err(e) if e == OFFLINE_ERROR => {
+            message.status = DeliveryStatus::Queued;
+            message.next_attempt_at = now + OUTBOX_MAX_RETRY_DELAY.as_millis() as i64;
+            message.last_error = Some(e);
+        }
+        Err(e) => {
+            message.attempts += 1;
+            message.last_error = Some(e);
+            if message.attempts >= max_attempts {
+                message.status = DeliveryStatus::Failed;
+            } else {
+                message.status = DeliveryStatus::Queued;
+                message.next_attempt_at = now + retry_delay(message.attempts).as_millis() as i64;
+            }
+        }
+    }
+}
+
+pub fn emit_delivery<R: Runtime>(app: &AppHandle<R>, message: &OutboxMessage) {
+    if let Err(e) = app.emit(OUTBOX_DELIVERY_EVENT, message) {
+        log::warn!("Failed to emit {OUTBOX_DELIVERY_EVENT}: {e}");
+    }
+}
+
+/// Look for due messages now rather than at the next tick
+pub fn wake_outbox() {
+    outbox_wake().notify_one();
+}
+
+/// Make every queued message due and deliver, after connectivity came back
+pub async fn retry_outbox_now<R: Runtime>(app: &AppHandle<R>) {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let rescheduled = update_outbox(&data_folder, |queue| {
+        for message in queue
+            .iter_mut()
+            .filter(|message| message.status == DeliveryStatus::Queued)
+        {
+            message.next_attempt_at = 0;
+        }
+        Ok(())
+    })
+    .await;
+    if let Err(e) = rescheduled {
+        log::warn!("Failed to reschedule queued messages: {e}");
+    }
+    wake_outbox();
+}
+
+/// Send a queued request and write the reply into its thread. Returns the reply's id.
+async fn deliver<R: Runtime>(
+    app: &AppHandle<R>,
+    message: &OutboxMessage,
+) -> Result<String, String> {
+    let endpoint = resolve_model_endpoint(app, &message.model).await?;
+    let mut body = Value::Object(message.parameters.clone());
+    body["messages"] = Value::Array(message.messages.clone());
+    let response = chat_completion(
+        &endpoint,
+        body,
+        Some(&message.thread_id),
+        DEFAULT_COMPLETION_TIMEOUT,
+    )
+    .await?;
+    let text = completion_text(&response).ok_or("The model returned no answer")?;
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let reply = create_message(
+        app.clone(),
+        json!({
+            "object": "message",
+            "thread_id": message.thread_id,
+            "role": "assistant",
+            "content": [{"type": "text", "text": {"value": text, "annotations": []}}],
+            "status": "ready",
+            "created_at": now,
+            "completed_at": now,
+            "metadata": {
+                "outbox_id": message.id,
+                "model": message.model,
+                "reply_to": message.user_message_id,
+            },
+        }),
+    )
+    .await?;
+    reply["id"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| "Created message has no id".to_string())
+}
+
+/// Deliver due messages one by one until none is left
+pub async fn process_outbox<R: Runtime>(app: &AppHandle<R>) {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    loop {
+        let now = chrono::Utc::now().timestamp_millis();
+        let claimed = update_outbox(&data_folder, |queue| {
+            Ok(next_due(queue, now).map(|index| {
+                queue[index].status = DeliveryStatus::Sending;
+                queue[index].clone()
+            }))
+        })
+        .await;
+        let message = match claimed {
+            Ok(Some(message)) => message,
+            Ok(None) => return,
+            Err(e) => {
+                log::error!("Failed to read the outbox: {e}");
+                return;
+            }
+        };
+        emit_delivery(app, &message);
+
+        let result = deliver(app, &message).await;
+        if let Err(e) = &result {
+            log::info!("Delivery of queued message {} failed: {e}", message.id);
+        }
+        let max_attempts = outbox_settings(app).max_attempts;
+        let now = chrono::Utc::now().timestamp_millis();
+        let updated = update_outbox(&data_folder, |queue| {
+            let Some(index) = queue.iter().position(|m| m.id == message.id) else {
+                return Ok(None);
+            };
+            record_attempt(&mut queue[index], result, max_attempts, now);
+            let updated = queue[index].clone();
+            if updated.status == DeliveryStatus::Delivered {
+                queue.remove(index);
+            }
+            Ok(Some(updated))
+        })
+        .await;
+        match updated {
+            Ok(Some(updated)) => emit_delivery(app, &updated),
+            // Removed while it was being sent
+            Ok(None) => {}
+            Err(e) => {
+                log::error!("Failed to update the outbox: {e}");
+                return;
+            }
+        }
+    }
+}
+
+/// Deliver queued messages in the background: on a timer, when a message is queued and
+/// right away when the network changes
+pub fn start_outbox_worker<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        // Messages that were being sent when the app quit go out again
+        let data_folder = get_jan_data_folder_path(app.clone());
+        let recovered = update_outbox(&data_folder, |queue| {
+            for message in queue.iter_mut() {
+                if message.status == DeliveryStatus::Sending {
+                    message.status = DeliveryStatus::Queued;
+                }
+            }
+            Ok(())
+        })
+        .await;
+        if let Err(e) = recovered {
+            log::warn!("Failed to recover the outbox: {e}");
+        }
+
+        let mut network = subscribe_network_changes();
+        loop {
+            process_outbox(&app).await;
+            tokio::select! {
+                _ = tokio::time::sleep(OUTBOX_TICK) => {}
+                _ = outbox_wake().notified() => {}
+                Ok(()) = network.changed() => retry_outbox_now(&app).await,
+            }
+        }
+    });
+}
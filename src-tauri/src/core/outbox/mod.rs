@@ -0,0 +1,24 @@
+/*!
+   Outbox
+
+   Messages that can't be sent, because offline mode is on or their provider is down, can be
+   queued instead when the `outbox.enabled` setting allows it. The queue is persisted in
+   `outbox.json` and delivered by a background worker, like an email outbox:
+
+   - Messages of one thread are delivered strictly in the order they were queued; a message
+     that failed for good holds back the later ones of its thread until it is retried or
+     removed. Different threads don't wait for each other.
+   - Failed attempts are retried with exponential backoff, up to `outbox.max_attempts`.
+     Retries happen right away when the network changes or offline mode is switched off.
+   - Each reply is written into the thread as an assistant message, and delivered messages
+     leave the queue.
+   - Every status change is emitted as `outbox-delivery` with the queued message.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeliveryStatus {
+    /// Waiting for its turn, connectivity or the next retry
+    Queued,
+    Sending,
+    /// Answered; the reply was written into the thread
+    Delivered,
+    /// Gave up after the configured attempts; retried only on request
+    Failed,
+}
+
+/// An outgoing chat request waiting to be delivered
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutboxMessage {
+    pub id: String,
+    pub thread_id: String,
+    pub model: String,
+    /// Conversation sent to the model, ending with the queued user message
+    pub messages: Vec<Value>,
+    /// Extra request parameters such as `temperature`
+    #[serde(default)]
+    pub parameters: Map<String, Value>,
+    /// The user message in the thread this request answers
+    #[serde(default)]
+    pub user_message_id: Option<String>,
+    pub status: DeliveryStatus,
+    #[serde(default)]
+    pub attempts: u32,
+    /// Milliseconds since epoch
+    pub created_at: i64,
+    #[serde(default)]
+    pub next_attempt_at: i64,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    /// Assistant message written with the reply
+    #[serde(default)]
+    pub reply_message_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueueMessageInput {
+    pub thread_id: String,
+    pub model: String,
+    pub messages: Vec<Value>,
+    #[serde(default)]
+    pub parameters: Map<String, Value>,
+    #[serde(default)]
+    pub user_message_id: Option<String>,
+}
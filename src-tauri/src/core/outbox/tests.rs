@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use serde_json::Map;
+
+use super::constants::OUTBOX_MAX_RETRY_DELAY;
+use super::helpers::{next_due, read_outbox, record_attempt, retry_delay, update_outbox};
+use super::models::{DeliveryStatus, OutboxMessage};
+use crate::core::offline::constants::OFFLINE_ERROR;
+
+fn queued(id: &str, thread_id: &str) -> OutboxMessage {
+    OutboxMessage {
+        id: id.to_string(),
+        thread_id: thread_id.to_string(),
+        model: "openai/gpt-4o".to_string(),
+        messages: vec![serde_json::json!({"role": "user", "content": id})],
+        parameters: Map::new(),
+        user_message_id: None,
+        status: DeliveryStatus::Queued,
+        attempts: 0,
+        created_at: 0,
+        next_attempt_at: 0,
+        last_error: None,
+        reply_message_id: None,
+    }
+}
+
+#[test]
+fn test_next_due_keeps_thread_order() {
+    let mut queue = vec![queued("a1", "a"), queued("a2", "a"), queued("b1", "b")];
+    assert_eq!(next_due(&queue, 0), Some(0));
+
+    // While the head of a thread is sending or waiting, its later messages wait too
+    queue[0].status = DeliveryStatus::Sending;
+    assert_eq!(next_due(&queue, 0), Some(2));
+    queue[0].status = DeliveryStatus::Queued;
+    queue[0].next_attempt_at = 1_000;
+    assert_eq!(next_due(&queue, 0), Some(2));
+    assert_eq!(next_due(&queue, 1_000), Some(0));
+
+    queue[0].status = DeliveryStatus::Failed;
+    queue[2].status = DeliveryStatus::Delivered;
+    assert_eq!(next_due(&queue, 0), None);
+}
+
+#[test]
+fn test_record_attempt() {
+    let mut message = queued("a1", "a");
+    record_attempt(&mut message, Err(OFFLINE_ERROR.to_string()), 2, 0);
+    assert_eq!(message.status, DeliveryStatus::Queued);
+    assert_eq!(message.attempts, 0);
+    assert!(message.next_attempt_at > 0);
+
+    record_attempt(&mut message, Err("502 Bad Gateway".to_string()), 2, 1_000);
+    assert_eq!(message.status, DeliveryStatus::Queued);
+    assert_eq!(message.attempts, 1);
+    assert_eq!(message.next_attempt_at, 6_000);
+    record_attempt(&mut message, Err("502 Bad Gateway".to_string()), 2, 7_000);
+    assert_eq!(message.status, DeliveryStatus::Failed);
+
+    let mut message = queued("a2", "a");
+    record_attempt(&mut message, Ok("reply".to_string()), 2, 0);
+    assert_eq!(message.status, DeliveryStatus::Delivered);
+    assert_eq!(message.reply_message_id.as_deref(), Some("reply"));
+}
+
+#[test]
+fn test_retry_delay() {
+    assert_eq!(retry_delay(1), Duration::from_secs(5));
+    assert_eq!(retry_delay(3), Duration::from_secs(20));
+    assert_eq!(retry_delay(40), OUTBOX_MAX_RETRY_DELAY);
+}
+
+#[tokio::test]
+async fn test_outbox_persists() {
+    let dir = std::env::temp_dir().join(format!("jan-outbox-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    assert!(read_outbox(&dir).is_empty());
+    update_outbox(&dir, |queue| {
+        queue.push(queued("a1", "a"));
+        Ok(())
+    })
+    .await
+    .unwrap();
+    assert_eq!(read_outbox(&dir), vec![queued("a1", "a")]);
+    let _ = std::fs::remove_dir_all(dir);
+}
@@ -0,0 +1,172 @@
+use tauri::{AppHandle, Runtime, State};
+
+use super::helpers::{limits_for_model, validate_params, validate_profile, write_config};
+use super::models::{
+    ModelParamLimits, ParamProfile, ProfileTarget, ProfilesConfig, ResolveParamsRequest,
+    ResolvedParams,
+};
+use super::ParamProfiles;
+use crate::core::app::commands::get_jan_data_folder_path;
+
+/// Apply `update` to the profiles, then persist and publish the result
+fn update_config<R: Runtime>(
+    app: AppHandle<R>,
+    profiles: &ParamProfiles,
+    update: impl FnOnce(&mut ProfilesConfig) -> Result<(), String>,
+) -> Result<ProfilesConfig, String> {
+    let mut config = profiles.config();
+    update(&mut config)?;
+    write_config(&get_jan_data_folder_path(app), &config)?;
+    profiles.set_config(config.clone());
+    Ok(config)
+}
+
+/// Profiles assigned to `model` must fit its ranges as they are
+fn check_model_profile(
+    config: &ProfilesConfig,
+    model: &str,
+    profile: &ParamProfile,
+) -> Result<(), String> {
+    validate_params(&profile.params, &limits_for_model(config, model))
+        .map_err(|e| format!("Profile '{}' doesn't fit {model}: {e}", profile.name))
+}
+
+#[tauri::command]
+pub fn get_param_profiles(profiles: State<'_, ParamProfiles>) -> ProfilesConfig {
+    profiles.config()
+}
+
+/// Creates or replaces a profile. Its values must be valid for every model it is assigned to.
+#[tauri::command]
+pub fn save_param_profile<R: Runtime>(
+    app: AppHandle<R>,
+    profiles: State<'_, ParamProfiles>,
+    profile: ParamProfile,
+) -> Result<ProfilesConfig, String> {
+    validate_profile(&profile)?;
+    update_config(app, &profiles, |config| {
+        for (model, profile_id) in &config.assignments.models {
+            if profile_id == &profile.id {
+                check_model_profile(config, model, &profile)?;
+            }
+        }
+        match config.profiles.iter_mut().find(|p| p.id == profile.id) {
+            Some(existing) => *existing = profile,
+            None => config.profiles.push(profile),
+        }
+        Ok(())
+    })
+}
+
+/// Deletes a profile along with its assignments.
+#[tauri::command]
+pub fn delete_param_profile<R: Runtime>(
+    app: AppHandle<R>,
+    profiles: State<'_, ParamProfiles>,
+    profile_id: String,
+) -> Result<ProfilesConfig, String> {
+    update_config(app, &profiles, |config| {
+        let count = config.profiles.len();
+        config.profiles.retain(|p| p.id != profile_id);
+        if config.profiles.len() == count {
+            return Err(format!("Profile {profile_id} not found"));
+        }
+        let assignments = &mut config.assignments;
+        for map in [
+            &mut assignments.assistants,
+            &mut assignments.threads,
+            &mut assignments.models,
+        ] {
+            map.retain(|_, assigned| assigned != &profile_id);
+        }
+        if config.default_profile.as_deref() == Some(&profile_id) {
+            config.default_profile = None;
+        }
+        Ok(())
+    })
+}
+
+/// Assigns a profile to an assistant, thread or model, or with `None` removes the assignment.
+#[tauri::command]
+pub fn assign_param_profile<R: Runtime>(
+    app: AppHandle<R>,
+    profiles: State<'_, ParamProfiles>,
+    target: ProfileTarget,
+    target_id: String,
+    profile_id: Option<String>,
+) -> Result<ProfilesConfig, String> {
+    update_config(app, &profiles, |config| {
+        let Some(profile_id) = profile_id else {
+            config.assignments.get_mut(target).remove(&target_id);
+            return Ok(());
+        };
+        let profile = config
+            .profiles
+            .iter()
+            .find(|p| p.id == profile_id)
+            .ok_or_else(|| format!("Profile {profile_id} not found"))?;
+        if target == ProfileTarget::Model {
+            check_model_profile(config, &target_id, profile)?;
+        }
+        config
+            .assignments
+            .get_mut(target)
+            .insert(target_id, profile_id);
+        Ok(())
+    })
+}
+
+/// Sets the profile used when nothing more specific is assigned, or none.
+#[tauri::command]
+pub fn set_default_param_profile<R: Runtime>(
+    app: AppHandle<R>,
+    profiles: State<'_, ParamProfiles>,
+    profile_id: Option<String>,
+) -> Result<ProfilesConfig, String> {
+    update_config(app, &profiles, |config| {
+        if let Some(profile_id) = &profile_id {
+            if !config.profiles.iter().any(|p| &p.id == profile_id) {
+                return Err(format!("Profile {profile_id} not found"));
+            }
+        }
+        config.default_profile = profile_id;
+        Ok(())
+    })
+}
+
+/// Overrides the parameter ranges of a model, or with `None` restores the built-in ones.
+/// The profile assigned to the model must still fit.
+#[tauri::command]
+pub fn set_model_param_limits<R: Runtime>(
+    app: AppHandle<R>,
+    profiles: State<'_, ParamProfiles>,
+    model: String,
+    limits: Option<ModelParamLimits>,
+) -> Result<ProfilesConfig, String> {
+    update_config(app, &profiles, |config| {
+        match limits {
+            Some(limits) => config.model_limits.insert(model.clone(), limits),
+            None => config.model_limits.remove(&model),
+        };
+        let assigned = config
+            .assignments
+            .models
+            .get(&model)
+            .and_then(|id| config.profiles.iter().find(|p| &p.id == id));
+        match assigned {
+            Some(profile) => check_model_profile(config, &model, profile),
+            None => Ok(()),
+        }
+    })
+}
+
+/// Resolves the generation parameters of a request: the request's own parameters, completed
+/// from the profile of its thread, assistant or model, or the default profile, fitted to
+/// the model.
+#[tauri::command]
+pub fn resolve_generation_params(
+    profiles: State<'_, ParamProfiles>,
+    request: ResolveParamsRequest,
+) -> ResolvedParams {
+    profiles.resolve(&request)
+}
@@ -0,0 +1,11 @@
+// Generation parameter profile constants
+pub const PARAM_PROFILES_FILE: &str = "param_profiles.json";
+/// Header naming the assistant of an API request, so its profile applies
+pub const ASSISTANT_ID_HEADER: &str = "x-jan-assistant-id";
+
+/// Ranges of OpenAI-compatible APIs and the local engines
+pub const DEFAULT_TEMPERATURE_RANGE: (f64, f64) = (0.0, 2.0);
+pub const DEFAULT_TOP_P_RANGE: (f64, f64) = (0.0, 1.0);
+pub const DEFAULT_PENALTY_RANGE: (f64, f64) = (-2.0, 2.0);
+/// Anthropic models accept a narrower temperature range and no penalties
+pub const ANTHROPIC_TEMPERATURE_RANGE: (f64, f64) = (0.0, 1.0);
@@ -0,0 +1,248 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager, Runtime};
+
+use super::constants::{
+    ANTHROPIC_TEMPERATURE_RANGE, DEFAULT_PENALTY_RANGE, DEFAULT_TEMPERATURE_RANGE,
+    DEFAULT_TOP_P_RANGE, PARAM_PROFILES_FILE,
+};
+use super::models::{
+    GenerationParams, ModelParamLimits, ParamProfile, ParamRange, ProfileSource, ProfilesConfig,
+    ResolveParamsRequest, ResolvedParams,
+};
+use super::ParamProfiles;
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::config_store::helpers::write_json;
+
+pub fn get_config_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(PARAM_PROFILES_FILE)
+}
+
+pub fn read_config(data_folder: &Path) -> ProfilesConfig {
+    fs::read_to_string(get_config_path(data_folder))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+pub fn write_config(data_folder: &Path, config: &ProfilesConfig) -> Result<(), String> {
+    write_json(&get_config_path(data_folder), config)
+}
+
+/// Load the stored profiles into the managed state
+pub fn load_param_profiles<R: Runtime>(app: &AppHandle<R>) {
+    let config = read_config(&get_jan_data_folder_path(app.clone()));
+    app.state::<ParamProfiles>().set_config(config);
+}
+
+fn range((min, max): (f64, f64)) -> Option<ParamRange> {
+    Some(ParamRange { min, max })
+}
+
+/// Widest ranges, used to check profiles that aren't tied to a model
+pub fn default_limits() -> ModelParamLimits {
+    ModelParamLimits {
+        temperature: range(DEFAULT_TEMPERATURE_RANGE),
+        top_p: range(DEFAULT_TOP_P_RANGE),
+        frequency_penalty: range(DEFAULT_PENALTY_RANGE),
+        presence_penalty: range(DEFAULT_PENALTY_RANGE),
+        max_output_tokens: None,
+    }
+}
+
+/// Ranges `model` supports: configured ones, or the built-in ones of its provider family
+pub fn limits_for_model(config: &ProfilesConfig, model: &str) -> ModelParamLimits {
+    if let Some(limits) = config.model_limits.get(model) {
+        return limits.clone();
+    }
+    if model.to_ascii_lowercase().contains("claude") {
+        return ModelParamLimits {
+            temperature: range(ANTHROPIC_TEMPERATURE_RANGE),
+            frequency_penalty: None,
+            presence_penalty: None,
+            ..default_limits()
+        };
+    }
+    default_limits()
+}
+
+/// Named float parameters of `params` with their ranges in `limits`
+fn float_params<'a>(
+    params: &'a mut GenerationParams,
+    limits: &ModelParamLimits,
+) -> [(&'static str, &'a mut Option<f64>, Option<ParamRange>); 4] {
+    [
+        ("temperature", &mut params.temperature, limits.temperature),
+        ("top_p", &mut params.top_p, limits.top_p),
+        (
+            "frequency_penalty",
+            &mut params.frequency_penalty,
+            limits.frequency_penalty,
+        ),
+        (
+            "presence_penalty",
+            &mut params.presence_penalty,
+            limits.presence_penalty,
+        ),
+    ]
+}
+
+/// Reject values outside the ranges of `limits`
+pub fn validate_params(params: &GenerationParams, limits: &ModelParamLimits) -> Result<(), String> {
+    let mut params = params.clone();
+    for (name, value, range) in float_params(&mut params, limits) {
+        let Some(value) = *value else { continue };
+        match range {
+            None => return Err(format!("{name} is not supported by this model")),
+            Some(range) if !range.contains(value) => {
+                return Err(format!(
+                    "{name} must be between {} and {}",
+                    range.min, range.max
+                ));
+            }
+            Some(_) => {}
+        }
+    }
+    match (params.max_tokens, limits.max_output_tokens) {
+        (Some(0), _) => Err("max_tokens must be at least 1".to_string()),
+        (Some(tokens), Some(max)) if tokens > max => {
+            Err(format!("max_tokens must be at most {max}"))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Clamp `params` into the ranges of `limits` and drop what the model doesn't support.
+/// Returns a note per changed value.
+pub fn fit_params(params: &mut GenerationParams, limits: &ModelParamLimits) -> Vec<String> {
+    let mut adjusted = Vec::new();
+    for (name, value, range) in float_params(params, limits) {
+        let Some(current) = *value else { continue };
+        match range {
+            None => {
+                *value = None;
+                adjusted.push(format!("{name} dropped"));
+            }
+            Some(range) if !range.contains(current) => {
+                let clamped = current.clamp(range.min, range.max);
+                *value = Some(clamped);
+                adjusted.push(format!("{name} {current} -> {clamped}"));
+            }
+            Some(_) => {}
+        }
+    }
+    if let (Some(tokens), Some(max)) = (params.max_tokens, limits.max_output_tokens) {
+        if tokens > max {
+            params.max_tokens = Some(max);
+            adjusted.push(format!("max_tokens {tokens} -> {max}"));
+        }
+    }
+    adjusted
+}
+
+pub fn validate_profile(profile: &ParamProfile) -> Result<(), String> {
+    let id_valid = !profile.id.is_empty()
+        && profile
+            .id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !id_valid {
+        return Err(format!("Invalid profile id '{}'", profile.id));
+    }
+    if profile.name.trim().is_empty() {
+        return Err("A profile needs a name".to_string());
+    }
+    validate_params(&profile.params, &default_limits())
+}
+
+/// The profile for a request and where it was assigned: thread, then assistant, then model,
+/// then the default. Assignments to deleted profiles are skipped.
+pub fn applicable_profile<'a>(
+    config: &'a ProfilesConfig,
+    request: &ResolveParamsRequest,
+) -> Option<(&'a ParamProfile, ProfileSource)> {
+    let assigned = |map: &std::collections::HashMap<String, String>, key: Option<&str>| {
+        key.and_then(|key| map.get(key)).cloned()
+    };
+    [
+        (
+            assigned(&config.assignments.threads, request.thread_id.as_deref()),
+            ProfileSource::Thread,
+        ),
+        (
+            assigned(
+                &config.assignments.assistants,
+                request.assistant_id.as_deref(),
+            ),
+            ProfileSource::Assistant,
+        ),
+        (
+            assigned(&config.assignments.models, Some(request.model.as_str())),
+            ProfileSource::Model,
+        ),
+        (config.default_profile.clone(), ProfileSource::Default),
+    ]
+    .into_iter()
+    .find_map(|(profile_id, source)| {
+        let profile_id = profile_id?;
+        let profile = config.profiles.iter().find(|p| p.id == profile_id)?;
+        Some((profile, source))
+    })
+}
+
+pub fn resolve_params(config: &ProfilesConfig, request: &ResolveParamsRequest) -> ResolvedParams {
+    let mut parameters = request.parameters.clone();
+    let Some((profile, source)) = applicable_profile(config, request) else {
+        return ResolvedParams {
+            parameters,
+            ..Default::default()
+        };
+    };
+    let mut params = profile.params.clone();
+    let adjusted = fit_params(&mut params, &limits_for_model(config, &request.model));
+    if let Ok(Value::Object(defaults)) = serde_json::to_value(&params) {
+        apply_defaults(&mut parameters, &defaults);
+    }
+    ResolvedParams {
+        parameters,
+        profile_id: Some(profile.id.clone()),
+        source: Some(source),
+        adjusted,
+    }
+}
+
+/// Add the `defaults` missing from `parameters`. Returns whether anything was added.
+pub fn apply_defaults(parameters: &mut Map<String, Value>, defaults: &Map<String, Value>) -> bool {
+    let mut changed = false;
+    for (key, value) in defaults {
+        if !parameters.contains_key(key) {
+            parameters.insert(key.clone(), value.clone());
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Parameters for a request made by the core, completed from the applicable profile
+pub fn resolved_parameters<R: Runtime>(
+    app: &AppHandle<R>,
+    request: ResolveParamsRequest,
+) -> Map<String, Value> {
+    match app.try_state::<ParamProfiles>() {
+        Some(profiles) => {
+            let resolved = profiles.resolve(&request);
+            if !resolved.adjusted.is_empty() {
+                log::info!(
+                    "Fitted profile {:?} to {}: {}",
+                    resolved.profile_id,
+                    request.model,
+                    resolved.adjusted.join(", ")
+                );
+            }
+            resolved.parameters
+        }
+        None => request.parameters,
+    }
+}
@@ -0,0 +1,54 @@
+/*!
+   Generation Parameter Profiles
+
+   Named sets of default generation parameters (temperature, top_p, penalties, max tokens)
+   kept in `param_profiles.json`. A profile can be assigned to a thread, an assistant or a
+   model, and one can be the default; the most specific assignment wins, in that order.
+
+   Profiles hold defaults only: parameters set on a request always take precedence. The
+   profile's values are checked against the ranges the model supports, built-in per provider
+   family or configured per model. Profiles assigned to a model must fit it; other profiles
+   are fitted when they are applied, clamping values and dropping unsupported parameters.
+
+   The agent loop and the local API server resolve parameters the same way, through
+   `ParamProfiles::resolve`. API clients name their thread and assistant with the
+   `x-jan-thread-id` and `x-jan-assistant-id` headers.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
+
+use std::sync::{Arc, RwLock};
+
+use models::{ProfilesConfig, ResolveParamsRequest, ResolvedParams};
+
+/// Shared parameter profiles, cheap to clone
+#[derive(Clone, Default)]
+pub struct ParamProfiles {
+    config: Arc<RwLock<ProfilesConfig>>,
+}
+
+impl ParamProfiles {
+    pub fn config(&self) -> ProfilesConfig {
+        self.config.read().map(|c| c.clone()).unwrap_or_default()
+    }
+
+    pub fn set_config(&self, config: ProfilesConfig) {
+        if let Ok(mut current) = self.config.write() {
+            *current = config;
+        }
+    }
+
+    /// Parameters for a request, completed from the profile that applies to it
+    pub fn resolve(&self, request: &ResolveParamsRequest) -> ResolvedParams {
+        match self.config.read() {
+            Ok(config) => helpers::resolve_params(&config, request),
+            Err(_) => helpers::resolve_params(&ProfilesConfig::default(), request),
+        }
+    }
+}
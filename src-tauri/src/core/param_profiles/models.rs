@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Sampling parameters of a profile; unset ones are left to the request or the model
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GenerationParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<u64>,
+}
+
+/// A named set of default generation parameters
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParamProfile {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub params: GenerationParams,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ParamRange {
+    pub min: f64,
+    pub max: f64,
+}
+
+impl ParamRange {
+    pub fn contains(&self, value: f64) -> bool {
+        (self.min..=self.max).contains(&value)
+    }
+}
+
+/// What a model accepts. A missing range means the model doesn't support the parameter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ModelParamLimits {
+    #[serde(default)]
+    pub temperature: Option<ParamRange>,
+    #[serde(default)]
+    pub top_p: Option<ParamRange>,
+    #[serde(default)]
+    pub frequency_penalty: Option<ParamRange>,
+    #[serde(default)]
+    pub presence_penalty: Option<ParamRange>,
+    /// Largest `max_tokens` the model accepts, unlimited when unset
+    #[serde(default)]
+    pub max_output_tokens: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileTarget {
+    Assistant,
+    Thread,
+    Model,
+}
+
+/// Profile ids assigned to assistants, threads and models, keyed by their ids
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfileAssignments {
+    pub assistants: HashMap<String, String>,
+    pub threads: HashMap<String, String>,
+    pub models: HashMap<String, String>,
+}
+
+impl ProfileAssignments {
+    pub fn get_mut(&mut self, target: ProfileTarget) -> &mut HashMap<String, String> {
+        match target {
+            ProfileTarget::Assistant => &mut self.assistants,
+            ProfileTarget::Thread => &mut self.threads,
+            ProfileTarget::Model => &mut self.models,
+        }
+    }
+}
+
+/// Contents of `param_profiles.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProfilesConfig {
+    pub profiles: Vec<ParamProfile>,
+    /// Profile used when nothing more specific is assigned
+    pub default_profile: Option<String>,
+    pub assignments: ProfileAssignments,
+    /// Ranges of models that differ from the built-in ones
+    pub model_limits: HashMap<String, ModelParamLimits>,
+}
+
+/// Where the applied profile was assigned
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileSource {
+    Thread,
+    Assistant,
+    Model,
+    Default,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResolveParamsRequest {
+    pub model: String,
+    #[serde(default)]
+    pub assistant_id: Option<String>,
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    /// Parameters set on the request itself, which take precedence over the profile
+    #[serde(default)]
+    pub parameters: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResolvedParams {
+    /// The request parameters completed with the profile's
+    pub parameters: Map<String, Value>,
+    pub profile_id: Option<String>,
+    pub source: Option<ProfileSource>,
+    /// Profile values changed or dropped to fit the model, e.g. `temperature 1.5 -> 1`
+    pub adjusted: Vec<String>,
+}
@@ -0,0 +1,113 @@
+use serde_json::{json, Map};
+
+use super::helpers::{
+    apply_defaults, default_limits, fit_params, limits_for_model, resolve_params, validate_params,
+    validate_profile,
+};
+use super::models::{
+    GenerationParams, ParamProfile, ProfileSource, ProfilesConfig, ResolveParamsRequest,
+};
+
+fn profile(id: &str, temperature: f64) -> ParamProfile {
+    ParamProfile {
+        id: id.to_string(),
+        name: id.to_string(),
+        params: GenerationParams {
+            temperature: Some(temperature),
+            frequency_penalty: Some(0.5),
+            max_tokens: Some(1024),
+            ..Default::default()
+        },
+    }
+}
+
+fn request(model: &str, thread_id: Option<&str>) -> ResolveParamsRequest {
+    ResolveParamsRequest {
+        model: model.to_string(),
+        assistant_id: Some("jan".to_string()),
+        thread_id: thread_id.map(str::to_string),
+        parameters: Map::new(),
+    }
+}
+
+#[test]
+fn test_validate_and_fit_params() {
+    assert!(validate_profile(&profile("creative", 1.5)).is_ok());
+    assert!(validate_profile(&profile("hot", 2.5)).is_err());
+    assert!(validate_profile(&profile("../x", 0.5)).is_err());
+
+    let config = ProfilesConfig::default();
+    let claude = limits_for_model(&config, "anthropic/claude-sonnet-4");
+    let params = profile("creative", 1.5).params;
+    assert!(validate_params(&params, &default_limits()).is_ok());
+    assert!(validate_params(&params, &claude).is_err());
+
+    let mut fitted = params.clone();
+    let adjusted = fit_params(&mut fitted, &claude);
+    assert_eq!(fitted.temperature, Some(1.0));
+    assert_eq!(fitted.frequency_penalty, None);
+    assert_eq!(fitted.max_tokens, Some(1024));
+    assert_eq!(
+        adjusted,
+        vec!["temperature 1.5 -> 1", "frequency_penalty dropped"]
+    );
+}
+
+#[test]
+fn test_resolve_precedence() {
+    let mut config = ProfilesConfig {
+        profiles: vec![
+            profile("default", 0.7),
+            profile("assistant", 0.3),
+            profile("thread", 1.2),
+        ],
+        default_profile: Some("default".to_string()),
+        ..Default::default()
+    };
+    let resolved = resolve_params(&config, &request("llama3", Some("t1")));
+    assert_eq!(resolved.source, Some(ProfileSource::Default));
+    assert_eq!(resolved.parameters["temperature"], json!(0.7));
+
+    config
+        .assignments
+        .assistants
+        .insert("jan".to_string(), "assistant".to_string());
+    config
+        .assignments
+        .threads
+        .insert("t1".to_string(), "thread".to_string());
+    let resolved = resolve_params(&config, &request("llama3", Some("t1")));
+    assert_eq!(resolved.profile_id.as_deref(), Some("thread"));
+    assert_eq!(resolved.source, Some(ProfileSource::Thread));
+    let resolved = resolve_params(&config, &request("llama3", Some("t2")));
+    assert_eq!(resolved.source, Some(ProfileSource::Assistant));
+
+    // Request parameters win; profile values are fitted to the model
+    let mut with_params = request("claude-haiku", Some("t1"));
+    with_params
+        .parameters
+        .insert("max_tokens".into(), json!(64));
+    let resolved = resolve_params(&config, &with_params);
+    assert_eq!(resolved.parameters["max_tokens"], json!(64));
+    assert_eq!(resolved.parameters["temperature"], json!(1.0));
+    assert!(!resolved.parameters.contains_key("frequency_penalty"));
+    assert_eq!(resolved.adjusted.len(), 2);
+
+    // Assignments to deleted profiles fall through
+    config.profiles.retain(|p| p.id != "thread");
+    let resolved = resolve_params(&config, &request("llama3", Some("t1")));
+    assert_eq!(resolved.source, Some(ProfileSource::Assistant));
+}
+
+#[test]
+fn test_apply_defaults() {
+    let mut parameters = Map::new();
+    parameters.insert("temperature".into(), json!(0.1));
+    let mut defaults = Map::new();
+    defaults.insert("temperature".into(), json!(0.9));
+    assert!(!apply_defaults(&mut parameters, &defaults));
+    defaults.insert("top_p".into(), json!(0.8));
+    assert!(apply_defaults(&mut parameters, &defaults));
+    assert_eq!(parameters["temperature"], json!(0.1));
+    assert_eq!(parameters["top_p"], json!(0.8));
+}
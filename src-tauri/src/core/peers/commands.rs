@@ -0,0 +1,35 @@
+use tauri::State;
+
+use super::models::{PairingRequest, Peer, PeerLimits};
+use super::PeerAccess;
+
+#[tauri::command]
+pub fn list_lan_peers(peers: State<'_, PeerAccess>) -> Vec<Peer> {
+    peers.peers()
+}
+
+/// Set the models, concurrency and bandwidth a peer is allowed
+#[tauri::command]
+pub fn update_lan_peer(
+    peers: State<'_, PeerAccess>,
+    peer_id: String,
+    limits: PeerLimits,
+) -> Result<Peer, String> {
+    peers.update_limits(&peer_id, limits)
+}
+
+#[tauri::command]
+pub fn remove_lan_peer(peers: State<'_, PeerAccess>, peer_id: String) -> Result<(), String> {
+    peers.remove(&peer_id)
+}
+
+/// Pairing requests waiting for their code, with the code to read out to the peer
+#[tauri::command]
+pub fn list_lan_pairing_requests(peers: State<'_, PeerAccess>) -> Vec<PairingRequest> {
+    peers.pending_pairings()
+}
+
+#[tauri::command]
+pub fn reject_lan_pairing_request(peers: State<'_, PeerAccess>, request_id: String) -> bool {
+    peers.reject_pairing(&request_id)
+}
@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+pub const PEERS_FILE: &str = "lan_peers.json";
+
+/// How long a pairing code can be entered
+pub const PAIRING_TTL: Duration = Duration::from_secs(120);
+/// Wrong codes after which a pairing request is dropped
+pub const MAX_PAIRING_ATTEMPTS: u32 = 3;
+pub const MAX_PENDING_PAIRINGS: usize = 5;
+/// Wrong codes from one address before it is locked out of pairing
+pub const MAX_PAIRING_FAILURES_PER_ADDRESS: u32 = 5;
+/// Wrong codes from all addresses before pairing is locked out for everyone, so that
+/// switching addresses does not help guessing
+pub const MAX_PAIRING_FAILURES: u32 = 20;
+/// First lockout, doubled with each further wrong code
+pub const PAIRING_LOCKOUT: Duration = Duration::from_secs(60);
+pub const MAX_PAIRING_LOCKOUT: Duration = Duration::from_secs(60 * 60);
+/// Concurrent requests a newly paired peer may make
+pub const DEFAULT_MAX_CONCURRENT_REQUESTS: u32 = 2;
+
+// Pairing endpoints of the local API server, below its prefix
+pub const PEER_PAIR_PATH: &str = "/peers/pair";
+pub const PEER_CONFIRM_PATH: &str = "/peers/confirm";
+/// Endpoints paired peers may use
+pub const PEER_PATHS: &[&str] = &[
+    "/models",
+    "/chat/completions",
+    "/completions",
+    "/embeddings",
+    "/messages",
+];
+
+pub const PAIRING_REQUEST_EVENT: &str = "lan-pairing-request";
@@ -0,0 +1,121 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use hyper::HeaderMap;
+use rand::Rng;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use super::constants::{PAIRING_REQUEST_EVENT, PAIRING_TTL, PEERS_FILE, PEER_CONFIRM_PATH};
+use super::models::Peer;
+use super::PeerAccess;
+use crate::core::app::commands::get_jan_data_folder_path;
+
+pub fn peers_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(PEERS_FILE)
+}
+
+pub fn read_peers(path: &Path) -> Vec<Peer> {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// 32 random bytes, hex encoded
+pub fn new_token() -> String {
+    hex::encode(rand::random::<[u8; 32]>())
+}
+
+pub fn new_pairing_code() -> String {
+    format!("{:06}", rand::thread_rng().gen_range(0..1_000_000))
+}
+
+/// The token a request carries as its API key, as a bearer token or in `X-Api-Key`
+pub fn peer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(hyper::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|auth| auth.strip_prefix("Bearer "))
+        .or_else(|| headers.get("X-Api-Key").and_then(|v| v.to_str().ok()))
+        .map(str::trim)
+        .filter(|token| !token.is_empty())
+}
+
+/// The `model` of a JSON request body
+pub fn requested_model(body: &[u8]) -> Option<String> {
+    let body: Value = serde_json::from_slice(body).ok()?;
+    body.get("model")?.as_str().map(str::to_string)
+}
+
+/// How long to wait before sending more, after `sent` bytes in `elapsed`, to stay within
+/// `kib_per_second` (0 for no limit)
+pub fn throttle_delay(sent: u64, elapsed: Duration, kib_per_second: u64) -> Duration {
+    if kib_per_second == 0 {
+        return Duration::ZERO;
+    }
+    let due = Duration::from_secs_f64(sent as f64 / (kib_per_second * 1024) as f64);
+    due.saturating_sub(elapsed)
+}
+
+/// Answer a request to one of the pairing endpoints from `address`, as a status and a JSON
+/// body. `path` is below the API prefix.
+pub fn handle_pairing_request(
+    peers: &PeerAccess,
+    path: &str,
+    address: &str,
+    body: &[u8],
+) -> (u16, Value) {
+    let body: Value = serde_json::from_slice(body).unwrap_or(Value::Null);
+    let field = |name: &str| {
+        body.get(name)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+    if path == PEER_CONFIRM_PATH {
+        let (Some(request_id), Some(code)) = (field("requestId"), field("code")) else {
+            return (400, json!({ "error": "requestId and code are required" }));
+        };
+        return match peers.confirm_pairing(request_id, code, address) {
+            Ok((peer, token)) => (200, json!({ "peerId": peer.id, "token": token })),
+            Err(e) => {
+                log::warn!("Pairing from {address} refused: {e}");
+                (403, json!({ "error": e }))
+            }
+        };
+    }
+    let Some(name) = field("name") else {
+        return (400, json!({ "error": "name is required" }));
+    };
+    match peers.begin_pairing(name, address) {
+        Ok(request) => (
+            202,
+            json!({ "requestId": request.id, "expiresIn": PAIRING_TTL.as_secs() }),
+        ),
+        Err(e) => (503, json!({ "error": e })),
+    }
+}
+
+/// Load the paired peers and show pairing codes in the app
+pub fn load_peers<R: Runtime>(app: &AppHandle<R>) {
+    let peers = app.state::<PeerAccess>();
+    peers.load(peers_path(&get_jan_data_folder_path(app.clone())));
+    let app = app.clone();
+    peers.set_pairing_listener(move |request| {
+        log::info!(
+            "Pairing requested by {} at {}",
+            request.name,
+            request.address
+        );
+        if let Err(e) = app.emit(PAIRING_REQUEST_EVENT, request) {
+            log::warn!("Failed to emit {PAIRING_REQUEST_EVENT}: {e}");
+        }
+    });
+}
@@ -0,0 +1,317 @@
+/*!
+   LAN Peers
+
+   Access control for other Jan instances using the local API server over the LAN. A peer
+   pairs by asking for a pairing request (`POST <prefix>/peers/pair`); the six-digit code is
+   shown on this machine only, and the peer's user types it in on theirs
+   (`POST <prefix>/peers/confirm`), which returns a token. Peers send that token as their API
+   key and skip the host and API key checks, but may only use the models running on this
+   machine, and only those on their allow-list, which starts empty, with caps on concurrent
+   requests and on response bandwidth. Wrong codes lock the sending address, and past a
+   global count every address, out of pairing for a while that doubles with each further
+   wrong code. Paired peers live in `lan_peers.json`, where only a hash of their
+   token is kept.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use tokio::sync::Semaphore;
+
+use crate::core::config_store::helpers::{write_json, write_json_debounced};
+use constants::{
+    DEFAULT_MAX_CONCURRENT_REQUESTS, MAX_PAIRING_ATTEMPTS, MAX_PAIRING_FAILURES,
+    MAX_PAIRING_FAILURES_PER_ADDRESS, MAX_PAIRING_LOCKOUT, MAX_PENDING_PAIRINGS, PAIRING_LOCKOUT,
+    PAIRING_TTL,
+};
+use helpers::{hash_token, new_pairing_code, new_token, read_peers};
+use models::{PairingRequest, Peer, PeerGrant, PeerLimits};
+
+pub type PairingListener = Arc<dyn Fn(&PairingRequest) + Send + Sync>;
+
+struct PendingPairing {
+    request: PairingRequest,
+    expires: Instant,
+    attempts: u32,
+}
+
+/// Wrong pairing codes counted towards a lockout
+#[derive(Default)]
+struct PairingFailures {
+    count: u32,
+    locked_until: Option<Instant>,
+}
+
+impl PairingFailures {
+    /// Count a wrong code, locking out once `limit` is reached
+    fn record(&mut self, limit: u32, now: Instant) {
+        self.count += 1;
+        if self.count >= limit {
+            let doublings = (self.count - limit).min(16);
+            let lockout = PAIRING_LOCKOUT
+                .saturating_mul(1 << doublings)
+                .min(MAX_PAIRING_LOCKOUT);
+            self.locked_until = Some(now + lockout);
+        }
+    }
+
+    fn remaining(&self, now: Instant) -> Option<Duration> {
+        self.locked_until
+            .filter(|until| *until > now)
+            .map(|until| until - now)
+    }
+}
+
+#[derive(Default)]
+struct PeerRegistry {
+    /// Where peers are saved, unset until loaded
+    path: Option<PathBuf>,
+    peers: Vec<Peer>,
+    pending: HashMap<String, PendingPairing>,
+    permits: HashMap<String, Arc<Semaphore>>,
+    listener: Option<PairingListener>,
+    /// Wrong codes by the address they came from
+    failures: HashMap<String, PairingFailures>,
+    global_failures: PairingFailures,
+}
+
+impl PeerRegistry {
+    fn prune(&mut self, now: Instant) {
+        self.pending.retain(|_, pairing| pairing.expires > now);
+    }
+
+    fn save(&self) -> Result<(), String> {
+        match &self.path {
+            Some(path) => write_json(path, &self.peers),
+            None => Ok(()),
+        }
+    }
+
+    /// Refuse pairing while `address`, or everyone, is locked out
+    fn check_lockout(&self, address: &str, now: Instant) -> Result<(), String> {
+        let remaining = self
+            .failures
+            .get(address)
+            .and_then(|failures| failures.remaining(now))
+            .max(self.global_failures.remaining(now));
+        match remaining {
+            Some(remaining) => Err(format!(
+                "Too many wrong pairing codes, try again in {} seconds",
+                remaining.as_secs().max(1)
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn record_failure(&mut self, address: &str, now: Instant) {
+        self.failures
+            .entry(address.to_string())
+            .or_default()
+            .record(MAX_PAIRING_FAILURES_PER_ADDRESS, now);
+        self.global_failures.record(MAX_PAIRING_FAILURES, now);
+    }
+}
+
+/// Paired peers and pending pairings, cheap to clone
+#[derive(Clone, Default)]
+pub struct PeerAccess {
+    registry: Arc<Mutex<PeerRegistry>>,
+}
+
+impl PeerAccess {
+    fn registry(&self) -> MutexGuard<'_, PeerRegistry> {
+        self.registry.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Use the peers stored at `path`, and save changes there
+    pub fn load(&self, path: PathBuf) {
+        let peers = read_peers(&path);
+        let mut registry = self.registry();
+        registry.peers = peers;
+        registry.path = Some(path);
+        registry.permits.clear();
+    }
+
+    /// Called with each new pairing request to show its code. Pairing is refused until one
+    /// is set, since nobody could see the code.
+    pub fn set_pairing_listener(&self, listener: impl Fn(&PairingRequest) + Send + Sync + 'static) {
+        self.registry().listener = Some(Arc::new(listener));
+    }
+
+    pub fn peers(&self) -> Vec<Peer> {
+        self.registry().peers.clone()
+    }
+
+    pub fn pending_pairings(&self) -> Vec<PairingRequest> {
+        let mut registry = self.registry();
+        registry.prune(Instant::now());
+        let mut requests: Vec<PairingRequest> = registry
+            .pending
+            .values()
+            .map(|pairing| pairing.request.clone())
+            .collect();
+        requests.sort_by(|a, b| a.expires_at.cmp(&b.expires_at));
+        requests
+    }
+
+    pub fn begin_pairing(&self, name: &str, address: &str) -> Result<PairingRequest, String> {
+        let (request, listener) = {
+            let mut registry = self.registry();
+            let now = Instant::now();
+            registry.prune(now);
+            registry.check_lockout(address, now)?;
+            let Some(listener) = registry.listener.clone() else {
+                return Err("Pairing is not available on this server".to_string());
+            };
+            if registry.pending.len() >= MAX_PENDING_PAIRINGS {
+                return Err("Too many pairing requests, try again later".to_string());
+            }
+            let expires_at =
+                chrono::Utc::now() + chrono::Duration::from_std(PAIRING_TTL).unwrap_or_default();
+            let request = PairingRequest {
+                id: uuid::Uuid::new_v4().simple().to_string(),
+                name: name.trim().to_string(),
+                address: address.to_string(),
+                code: new_pairing_code(),
+                expires_at: expires_at.to_rfc3339(),
+            };
+            registry.pending.insert(
+                request.id.clone(),
+                PendingPairing {
+                    request: request.clone(),
+                    expires: Instant::now() + PAIRING_TTL,
+                    attempts: 0,
+                },
+            );
+            (request, listener)
+        };
+        listener(&request);
+        Ok(request)
+    }
+
+    /// Pair the peer of `request_id` if `code`, sent from `address`, is its code; returns the
+    /// peer and its token
+    pub fn confirm_pairing(
+        &self,
+        request_id: &str,
+        code: &str,
+        address: &str,
+    ) -> Result<(Peer, String), String> {
+        let mut registry = self.registry();
+        let now = Instant::now();
+        registry.prune(now);
+        registry.check_lockout(address, now)?;
+        let Some(pairing) = registry.pending.get_mut(request_id) else {
+            return Err("Unknown or expired pairing request".to_string());
+        };
+        if pairing.request.code != code.trim() {
+            pairing.attempts += 1;
+            let dropped = pairing.attempts >= MAX_PAIRING_ATTEMPTS;
+            if dropped {
+                registry.pending.remove(request_id);
+            }
+            registry.record_failure(address, now);
+            if dropped {
+                return Err("Wrong pairing code, the pairing request was dropped".to_string());
+            }
+            return Err("Wrong pairing code".to_string());
+        }
+        let Some(pairing) = registry.pending.remove(request_id) else {
+            return Err("Unknown or expired pairing request".to_string());
+        };
+
+        let token = new_token();
+        let peer = Peer {
+            id: uuid::Uuid::new_v4().simple().to_string(),
+            name: pairing.request.name,
+            address: pairing.request.address,
+            token_hash: hash_token(&token),
+            // No models until the user grants some
+            allowed_models: Some(Vec::new()),
+            max_concurrent_requests: DEFAULT_MAX_CONCURRENT_REQUESTS,
+            max_kib_per_second: 0,
+            paired_at: chrono::Utc::now().to_rfc3339(),
+            last_seen: None,
+        };
+        registry.failures.remove(address);
+        registry.global_failures = PairingFailures::default();
+        registry.peers.push(peer.clone());
+        registry.save()?;
+        log::info!("Paired LAN peer {} at {}", peer.name, peer.address);
+        Ok((peer, token))
+    }
+
+    pub fn reject_pairing(&self, request_id: &str) -> bool {
+        self.registry().pending.remove(request_id).is_some()
+    }
+
+    /// The grant of the peer `token` belongs to
+    pub fn authenticate(&self, token: &str) -> Option<PeerGrant> {
+        let token_hash = hash_token(token);
+        let mut guard = self.registry();
+        let registry = &mut *guard;
+        let peer = registry
+            .peers
+            .iter_mut()
+            .find(|peer| peer.token_hash == token_hash)?;
+        peer.last_seen = Some(chrono::Utc::now().to_rfc3339());
+        let permits = (peer.max_concurrent_requests > 0).then(|| {
+            let limit = peer.max_concurrent_requests as usize;
+            registry
+                .permits
+                .entry(peer.id.clone())
+                .or_insert_with(|| Arc::new(Semaphore::new(limit)))
+                .clone()
+        });
+        let grant = PeerGrant {
+            id: peer.id.clone(),
+            name: peer.name.clone(),
+            allowed_models: peer.allowed_models.clone(),
+            max_kib_per_second: peer.max_kib_per_second,
+            permits,
+        };
+        if let Some(path) = &registry.path {
+            let _ = write_json_debounced(path, &registry.peers);
+        }
+        Some(grant)
+    }
+
+    pub fn update_limits(&self, peer_id: &str, limits: PeerLimits) -> Result<Peer, String> {
+        let mut registry = self.registry();
+        let peer = registry
+            .peers
+            .iter_mut()
+            .find(|peer| peer.id == peer_id)
+            .ok_or_else(|| format!("Unknown peer {peer_id}"))?;
+        peer.allowed_models = limits.allowed_models;
+        peer.max_concurrent_requests = limits.max_concurrent_requests;
+        peer.max_kib_per_second = limits.max_kib_per_second;
+        let peer = peer.clone();
+        // Requests in flight keep their slots; new ones use the new limit
+        registry.permits.remove(peer_id);
+        registry.save()?;
+        Ok(peer)
+    }
+
+    /// Unpair a peer; its token stops working right away
+    pub fn remove(&self, peer_id: &str) -> Result<(), String> {
+        let mut registry = self.registry();
+        let before = registry.peers.len();
+        registry.peers.retain(|peer| peer.id != peer_id);
+        if registry.peers.len() == before {
+            return Err(format!("Unknown peer {peer_id}"));
+        }
+        registry.permits.remove(peer_id);
+        registry.save()
+    }
+}
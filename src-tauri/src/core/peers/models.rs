@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// A Jan instance on the LAN allowed to use the local API server
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Peer {
+    pub id: String,
+    pub name: String,
+    /// Address the peer paired from
+    pub address: String,
+    /// SHA-256 of the peer's token; the token itself is only sent to the peer
+    pub token_hash: String,
+    /// Local models the peer may use, all of them when unset
+    #[serde(default)]
+    pub allowed_models: Option<Vec<String>>,
+    /// 0 for no limit
+    #[serde(default)]
+    pub max_concurrent_requests: u32,
+    /// Bandwidth of each response, 0 for no limit
+    #[serde(default)]
+    pub max_kib_per_second: u64,
+    pub paired_at: String,
+    #[serde(default)]
+    pub last_seen: Option<String>,
+}
+
+/// Access of a peer, as edited in the UI
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerLimits {
+    #[serde(default)]
+    pub allowed_models: Option<Vec<String>>,
+    #[serde(default)]
+    pub max_concurrent_requests: u32,
+    #[serde(default)]
+    pub max_kib_per_second: u64,
+}
+
+/// A peer waiting for its pairing code to be entered. The code is shown on this machine
+/// only, so whoever pairs must be able to see its screen.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PairingRequest {
+    pub id: String,
+    pub name: String,
+    pub address: String,
+    pub code: String,
+    pub expires_at: String,
+}
+
+/// What an authenticated peer may do with one request
+#[derive(Debug, Clone)]
+pub struct PeerGrant {
+    pub id: String,
+    pub name: String,
+    pub allowed_models: Option<Vec<String>>,
+    pub max_kib_per_second: u64,
+    /// Request slots shared by all requests of the peer, unlimited when unset
+    pub permits: Option<Arc<Semaphore>>,
+}
+
+impl PeerGrant {
+    pub fn allows(&self, model: &str) -> bool {
+        match &self.allowed_models {
+            Some(models) => models.iter().any(|m| m == model),
+            None => true,
+        }
+    }
+
+    /// Take one of the peer's request slots, to be held until its response is sent
+    pub fn try_acquire(&self) -> Result<Option<OwnedSemaphorePermit>, String> {
+        match &self.permits {
+            Some(permits) => permits
+                .clone()
+                .try_acquire_owned()
+                .map(Some)
+                .map_err(|_| format!("{} has too many requests in flight", self.name)),
+            None => Ok(None),
+        }
+    }
+}
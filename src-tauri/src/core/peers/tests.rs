@@ -0,0 +1,264 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use hyper::HeaderMap;
+
+use super::constants::{
+    DEFAULT_MAX_CONCURRENT_REQUESTS, MAX_PAIRING_ATTEMPTS, MAX_PAIRING_FAILURES,
+    MAX_PAIRING_FAILURES_PER_ADDRESS, PEER_CONFIRM_PATH, PEER_PAIR_PATH,
+};
+use super::helpers::{
+    handle_pairing_request, hash_token, peer_token, read_peers, requested_model, throttle_delay,
+};
+use super::models::{PairingRequest, PeerLimits};
+use super::PeerAccess;
+
+/// Access with a listener keeping the pairing requests it was shown
+fn access_with_listener() -> (PeerAccess, Arc<Mutex<Vec<PairingRequest>>>) {
+    let access = PeerAccess::default();
+    let shown = Arc::new(Mutex::new(Vec::new()));
+    let seen = shown.clone();
+    access.set_pairing_listener(move |request| seen.lock().unwrap().push(request.clone()));
+    (access, shown)
+}
+
+fn wrong_code(code: &str) -> &'static str {
+    if code == "000000" {
+        "111111"
+    } else {
+        "000000"
+    }
+}
+
+#[test]
+fn test_pairing_needs_a_listener() {
+    let access = PeerAccess::default();
+    assert!(access.begin_pairing("laptop", "192.168.1.20").is_err());
+}
+
+#[test]
+fn test_pairing_flow() {
+    let (access, shown) = access_with_listener();
+    let request = access.begin_pairing("laptop", "192.168.1.20").unwrap();
+    assert_eq!(request.code.len(), 6);
+    assert_eq!(
+        shown.lock().unwrap().as_slice(),
+        std::slice::from_ref(&request)
+    );
+    assert_eq!(access.pending_pairings(), vec![request.clone()]);
+
+    let wrong = wrong_code(&request.code);
+    assert!(access
+        .confirm_pairing(&request.id, wrong, &request.address)
+        .is_err());
+    let (peer, token) = access
+        .confirm_pairing(&request.id, &request.code, &request.address)
+        .unwrap();
+    assert_eq!(peer.name, "laptop");
+    assert_eq!(peer.token_hash, hash_token(&token));
+    assert_eq!(
+        peer.max_concurrent_requests,
+        DEFAULT_MAX_CONCURRENT_REQUESTS
+    );
+    assert!(access.pending_pairings().is_empty());
+    // A code works once
+    assert!(access
+        .confirm_pairing(&request.id, &request.code, &request.address)
+        .is_err());
+
+    let grant = access.authenticate(&token).unwrap();
+    assert_eq!(grant.id, peer.id);
+    assert!(access.authenticate("not-a-token").is_none());
+    assert!(access.peers()[0].last_seen.is_some());
+}
+
+#[test]
+fn test_pairing_dropped_after_wrong_codes() {
+    let (access, _) = access_with_listener();
+    let request = access.begin_pairing("laptop", "192.168.1.20").unwrap();
+    let wrong = wrong_code(&request.code);
+    for _ in 0..3 {
+        assert!(access
+            .confirm_pairing(&request.id, wrong, &request.address)
+            .is_err());
+    }
+    assert!(access
+        .confirm_pairing(&request.id, &request.code, &request.address)
+        .is_err());
+    assert!(access.peers().is_empty());
+}
+
+/// Send wrong codes from `address` until `failures` were counted
+fn fail_pairing(access: &PeerAccess, address: &str, failures: u32) {
+    let mut request = access.begin_pairing("guesser", address).unwrap();
+    for i in 0..failures {
+        if i > 0 && i % MAX_PAIRING_ATTEMPTS == 0 {
+            request = access.begin_pairing("guesser", address).unwrap();
+        }
+        let wrong = wrong_code(&request.code);
+        assert!(access.confirm_pairing(&request.id, wrong, address).is_err());
+    }
+    access.reject_pairing(&request.id);
+}
+
+#[test]
+fn test_wrong_codes_lock_out_the_address() {
+    let (access, _) = access_with_listener();
+    fail_pairing(&access, "10.0.0.2", MAX_PAIRING_FAILURES_PER_ADDRESS);
+    let error = access.begin_pairing("guesser", "10.0.0.2").unwrap_err();
+    assert!(error.contains("try again in"));
+
+    // Even a right code is refused while locked out
+    let request = access.begin_pairing("laptop", "10.0.0.3").unwrap();
+    assert!(access
+        .confirm_pairing(&request.id, &request.code, "10.0.0.2")
+        .is_err());
+    assert!(access
+        .confirm_pairing(&request.id, &request.code, "10.0.0.3")
+        .is_ok());
+}
+
+#[test]
+fn test_wrong_codes_lock_out_everyone() {
+    let (access, _) = access_with_listener();
+    let per_address = MAX_PAIRING_FAILURES_PER_ADDRESS - 1;
+    let mut failures = 0;
+    let mut host = 0;
+    while failures < MAX_PAIRING_FAILURES {
+        let count = per_address.min(MAX_PAIRING_FAILURES - failures);
+        fail_pairing(&access, &format!("10.0.1.{host}"), count);
+        failures += count;
+        host += 1;
+    }
+    let error = access.begin_pairing("laptop", "10.0.2.1").unwrap_err();
+    assert!(error.contains("try again in"));
+}
+
+#[test]
+fn test_pending_pairings_are_capped() {
+    let (access, _) = access_with_listener();
+    for i in 0..5 {
+        access
+            .begin_pairing(&format!("peer {i}"), "10.0.0.2")
+            .unwrap();
+    }
+    assert!(access.begin_pairing("one more", "10.0.0.2").is_err());
+    let request = access.pending_pairings()[0].clone();
+    assert!(access.reject_pairing(&request.id));
+    assert!(access.begin_pairing("one more", "10.0.0.2").is_ok());
+}
+
+#[test]
+fn test_peer_limits_and_removal() {
+    let (access, _) = access_with_listener();
+    let request = access.begin_pairing("laptop", "192.168.1.20").unwrap();
+    let (peer, token) = access
+        .confirm_pairing(&request.id, &request.code, &request.address)
+        .unwrap();
+
+    let grant = access.authenticate(&token).unwrap();
+    // New peers get no models until some are granted
+    assert!(!grant.allows("any-model"));
+    let permits: Vec<_> = (0..DEFAULT_MAX_CONCURRENT_REQUESTS)
+        .map(|_| grant.try_acquire().unwrap())
+        .collect();
+    assert!(grant.try_acquire().is_err());
+    drop(permits);
+    assert!(grant.try_acquire().unwrap().is_some());
+
+    let limits = PeerLimits {
+        allowed_models: Some(vec!["qwen3-8b".to_string()]),
+        max_concurrent_requests: 0,
+        max_kib_per_second: 512,
+    };
+    access.update_limits(&peer.id, limits).unwrap();
+    let grant = access.authenticate(&token).unwrap();
+    assert!(grant.allows("qwen3-8b"));
+    assert!(!grant.allows("llama3.2-3b"));
+    assert_eq!(grant.max_kib_per_second, 512);
+    assert!(grant.try_acquire().unwrap().is_none());
+
+    access.remove(&peer.id).unwrap();
+    assert!(access.authenticate(&token).is_none());
+    assert!(access.remove(&peer.id).is_err());
+}
+
+#[test]
+fn test_peers_are_saved() {
+    let dir = std::env::temp_dir().join(format!("jan-peers-{}", uuid::Uuid::new_v4()));
+    let path = dir.join("lan_peers.json");
+    let (access, _) = access_with_listener();
+    access.load(path.clone());
+    let request = access.begin_pairing("laptop", "192.168.1.20").unwrap();
+    let (peer, token) = access
+        .confirm_pairing(&request.id, &request.code, &request.address)
+        .unwrap();
+
+    let saved = read_peers(&path);
+    assert_eq!(saved.len(), 1);
+    assert_eq!(saved[0].id, peer.id);
+    assert!(!std::fs::read_to_string(&path).unwrap().contains(&token));
+
+    let reloaded = PeerAccess::default();
+    reloaded.load(path);
+    assert!(reloaded.authenticate(&token).is_some());
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_handle_pairing_request() {
+    let (access, _) = access_with_listener();
+    let (status, body) = handle_pairing_request(&access, PEER_PAIR_PATH, "10.0.0.2", b"{}");
+    assert_eq!(status, 400);
+    assert!(body.get("error").is_some());
+
+    let (status, body) =
+        handle_pairing_request(&access, PEER_PAIR_PATH, "10.0.0.2", br#"{"name":"laptop"}"#);
+    assert_eq!(status, 202);
+    let request_id = body["requestId"].as_str().unwrap().to_string();
+    // The code is never sent to the requester
+    assert!(body.get("code").is_none());
+
+    let code = access.pending_pairings()[0].code.clone();
+    let confirm = serde_json::json!({ "requestId": request_id, "code": code }).to_string();
+    let (status, body) =
+        handle_pairing_request(&access, PEER_CONFIRM_PATH, "10.0.0.2", confirm.as_bytes());
+    assert_eq!(status, 200);
+    assert!(access
+        .authenticate(body["token"].as_str().unwrap())
+        .is_some());
+}
+
+#[test]
+fn test_peer_token() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(peer_token(&headers), None);
+    headers.insert("X-Api-Key", "abc".parse().unwrap());
+    assert_eq!(peer_token(&headers), Some("abc"));
+    headers.insert(hyper::header::AUTHORIZATION, "Bearer xyz".parse().unwrap());
+    assert_eq!(peer_token(&headers), Some("xyz"));
+}
+
+#[test]
+fn test_requested_model() {
+    assert_eq!(
+        requested_model(br#"{"model":"qwen3-8b","messages":[]}"#),
+        Some("qwen3-8b".to_string())
+    );
+    assert_eq!(requested_model(br#"{"messages":[]}"#), None);
+    assert_eq!(requested_model(b"not json"), None);
+}
+
+#[test]
+fn test_throttle_delay() {
+    assert_eq!(throttle_delay(1_000_000, Duration::ZERO, 0), Duration::ZERO);
+    // 100 KiB at 100 KiB/s is due after a second
+    assert_eq!(
+        throttle_delay(100 * 1024, Duration::from_millis(400), 100),
+        Duration::from_millis(600)
+    );
+    assert_eq!(
+        throttle_delay(100 * 1024, Duration::from_secs(2), 100),
+        Duration::ZERO
+    );
+}
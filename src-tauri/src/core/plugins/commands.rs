@@ -0,0 +1,160 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::Value;
+use tauri::{AppHandle, Runtime, State};
+
+use super::helpers::{
+    find_plugin, get_plugin_dir, read_manifest, read_registry, registry_lock, resolve_grants,
+    update_plugin, write_registry,
+};
+use super::models::{InstalledPlugin, PluginCapability, PluginInfo};
+use super::runtime::{start_plugin, stop_plugin};
+use super::PluginHostState;
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::app::helpers::copy_dir_recursive;
+
+/// Lists installed plugins with their running state and registration.
+#[tauri::command]
+pub async fn list_plugins<R: Runtime>(
+    app_handle: AppHandle<R>,
+    host: State<'_, PluginHostState>,
+) -> Result<Vec<PluginInfo>, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    let plugins = {
+        let _guard = registry_lock().lock().await;
+        read_registry(&data_folder)?
+    };
+    let running = host.running.lock().await;
+    Ok(plugins
+        .into_iter()
+        .map(|plugin| {
+            let process = running.get(&plugin.manifest.id);
+            PluginInfo {
+                running: process.is_some(),
+                registration: process.map(|p| p.registration.clone()),
+                plugin,
+            }
+        })
+        .collect())
+}
+
+/// Installs (or updates) a plugin from a folder containing `plugin.json`, granting it the given
+/// capabilities. The plugin is installed disabled; an update keeps the enabled state.
+#[tauri::command]
+pub async fn install_plugin<R: Runtime>(
+    app_handle: AppHandle<R>,
+    source_path: String,
+    granted: Option<Vec<PluginCapability>>,
+) -> Result<InstalledPlugin, String> {
+    let source = PathBuf::from(source_path);
+    let manifest = read_manifest(&source)?;
+    let granted = resolve_grants(&manifest, granted)?;
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+
+    // Replace the files of a running plugin only after it has stopped
+    stop_plugin(&app_handle, &manifest.id).await;
+    let plugin_dir = get_plugin_dir(&data_folder, &manifest.id);
+    if plugin_dir.exists() {
+        fs::remove_dir_all(&plugin_dir).map_err(|e| e.to_string())?;
+    }
+    copy_dir_recursive(&source, &plugin_dir, &[]).map_err(|e| e.to_string())?;
+
+    let installed_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default();
+    let (plugin, enabled) = {
+        let _guard = registry_lock().lock().await;
+        let mut plugins = read_registry(&data_folder)?;
+        let enabled = plugins
+            .iter()
+            .find(|p| p.manifest.id == manifest.id)
+            .is_some_and(|p| p.enabled);
+        plugins.retain(|p| p.manifest.id != manifest.id);
+        let plugin = InstalledPlugin {
+            manifest,
+            enabled,
+            granted,
+            installed_at,
+        };
+        plugins.push(plugin.clone());
+        write_registry(&data_folder, &plugins)?;
+        (plugin, enabled)
+    };
+    if enabled {
+        start_plugin(&app_handle, &data_folder, &plugin).await?;
+    }
+    log::info!("Installed plugin {}", plugin.manifest.id);
+    Ok(plugin)
+}
+
+/// Enables a plugin and starts it. The plugin stays disabled if it fails to start.
+#[tauri::command]
+pub async fn enable_plugin<R: Runtime>(
+    app_handle: AppHandle<R>,
+    plugin_id: String,
+) -> Result<InstalledPlugin, String> {
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+    let plugin = find_plugin(&data_folder, &plugin_id).await?;
+    start_plugin(&app_handle, &data_folder, &plugin).await?;
+    update_plugin(&data_folder, &plugin_id, |p| p.enabled = true).await
+}
+
+/// Stops a plugin and keeps it from starting with the app.
+#[tauri::command]
+pub async fn disable_plugin<R: Runtime>(
+    app_handle: AppHandle<R>,
+    plugin_id: String,
+) -> Result<InstalledPlugin, String> {
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+    stop_plugin(&app_handle, &plugin_id).await;
+    update_plugin(&data_folder, &plugin_id, |p| p.enabled = false).await
+}
+
+/// Stops a plugin and removes its files, including its private storage.
+#[tauri::command]
+pub async fn uninstall_plugin<R: Runtime>(
+    app_handle: AppHandle<R>,
+    plugin_id: String,
+) -> Result<(), String> {
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+    stop_plugin(&app_handle, &plugin_id).await;
+    {
+        let _guard = registry_lock().lock().await;
+        let mut plugins = read_registry(&data_folder)?;
+        let before = plugins.len();
+        plugins.retain(|p| p.manifest.id != plugin_id);
+        if plugins.len() == before {
+            return Err(format!("Plugin {plugin_id} is not installed"));
+        }
+        write_registry(&data_folder, &plugins)?;
+    }
+    let plugin_dir = get_plugin_dir(&data_folder, &plugin_id);
+    if plugin_dir.exists() {
+        fs::remove_dir_all(plugin_dir).map_err(|e| e.to_string())?;
+    }
+    log::info!("Uninstalled plugin {plugin_id}");
+    Ok(())
+}
+
+/// Executes a command registered by a running plugin and returns its result.
+#[tauri::command]
+pub async fn execute_plugin_command(
+    host: State<'_, PluginHostState>,
+    plugin_id: String,
+    command: String,
+    args: Option<Value>,
+) -> Result<Value, String> {
+    let process = host
+        .running
+        .lock()
+        .await
+        .get(&plugin_id)
+        .cloned()
+        .ok_or_else(|| format!("Plugin {plugin_id} is not running"))?;
+    process
+        .execute_command(&command, args.unwrap_or(Value::Null))
+        .await
+}
@@ -0,0 +1,20 @@
+use std::time::Duration;
+
+pub const PLUGINS_DIR: &str = "plugins";
+pub const PLUGIN_REGISTRY_FILE: &str = "plugins.json";
+pub const PLUGIN_MANIFEST_FILE: &str = "plugin.json";
+pub const PLUGIN_STORAGE_FILE: &str = "storage.json";
+
+/// Version of the JSON-RPC protocol sent in `initialize`
+pub const PLUGIN_PROTOCOL_VERSION: u32 = 1;
+
+pub const PLUGIN_INIT_TIMEOUT: Duration = Duration::from_secs(10);
+pub const PLUGIN_CALL_TIMEOUT: Duration = Duration::from_secs(30);
+/// Time given to a plugin to exit after `shutdown` before it is killed
+pub const PLUGIN_SHUTDOWN_GRACE: Duration = Duration::from_secs(2);
+
+/// Upper bound on a plugin's storage file so a plugin cannot fill the disk
+pub const MAX_PLUGIN_STORAGE_BYTES: usize = 5 * 1024 * 1024;
+
+/// Prefix of provider names registered by plugins, e.g. `plugin:<id>:<name>`
+pub const PLUGIN_PROVIDER_PREFIX: &str = "plugin";
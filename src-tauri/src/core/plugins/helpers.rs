@@ -0,0 +1,261 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde_json::{Map, Value};
+use tokio::sync::Mutex;
+
+use super::constants::{
+    MAX_PLUGIN_STORAGE_BYTES, PLUGINS_DIR, PLUGIN_MANIFEST_FILE, PLUGIN_PROVIDER_PREFIX,
+    PLUGIN_REGISTRY_FILE, PLUGIN_STORAGE_FILE,
+};
+use super::models::{InstalledPlugin, PluginCapability, PluginManifest, PluginRegistration};
+use crate::core::settings::helpers::{admin_policy, check_provider_allowed};
+use crate::core::state::{AppState, ProviderConfig};
+
+// Global lock serializing read-modify-write cycles on plugins.json
+static REGISTRY_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+// Global lock serializing writes to plugin storage files
+static STORAGE_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+pub fn registry_lock() -> &'static Mutex<()> {
+    REGISTRY_LOCK.get_or_init(|| Mutex::new(()))
+}
+
+pub fn storage_lock() -> &'static Mutex<()> {
+    STORAGE_LOCK.get_or_init(|| Mutex::new(()))
+}
+
+pub fn get_plugins_dir(data_folder: &Path) -> PathBuf {
+    data_folder.join(PLUGINS_DIR)
+}
+
+pub fn get_plugin_dir(data_folder: &Path, plugin_id: &str) -> PathBuf {
+    get_plugins_dir(data_folder).join(plugin_id)
+}
+
+fn get_registry_path(data_folder: &Path) -> PathBuf {
+    get_plugins_dir(data_folder).join(PLUGIN_REGISTRY_FILE)
+}
+
+fn get_storage_path(data_folder: &Path, plugin_id: &str) -> PathBuf {
+    get_plugin_dir(data_folder, plugin_id).join(PLUGIN_STORAGE_FILE)
+}
+
+pub fn read_registry(data_folder: &Path) -> Result<Vec<InstalledPlugin>, String> {
+    let path = get_registry_path(data_folder);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&data).map_err(|e| format!("Invalid {PLUGIN_REGISTRY_FILE}: {e}"))
+}
+
+pub fn write_registry(data_folder: &Path, plugins: &[InstalledPlugin]) -> Result<(), String> {
+    let path = get_registry_path(data_folder);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let data = serde_json::to_string_pretty(plugins).map_err(|e| e.to_string())?;
+    fs::write(path, data).map_err(|e| e.to_string())
+}
+
+/// Apply `update` to one installed plugin and persist the registry.
+pub async fn update_plugin<F>(
+    data_folder: &Path,
+    plugin_id: &str,
+    update: F,
+) -> Result<InstalledPlugin, String>
+where
+    F: FnOnce(&mut InstalledPlugin),
+{
+    let _guard = registry_lock().lock().await;
+    let mut plugins = read_registry(data_folder)?;
+    let plugin = plugins
+        .iter_mut()
+        .find(|p| p.manifest.id == plugin_id)
+        .ok_or_else(|| format!("Plugin {plugin_id} is not installed"))?;
+    update(plugin);
+    let updated = plugin.clone();
+    write_registry(data_folder, &plugins)?;
+    Ok(updated)
+}
+
+pub async fn find_plugin(data_folder: &Path, plugin_id: &str) -> Result<InstalledPlugin, String> {
+    let _guard = registry_lock().lock().await;
+    read_registry(data_folder)?
+        .into_iter()
+        .find(|p| p.manifest.id == plugin_id)
+        .ok_or_else(|| format!("Plugin {plugin_id} is not installed"))
+}
+
+/// Plugin ids become folder names, so only a conservative character set is allowed.
+pub fn validate_plugin_id(id: &str) -> Result<(), String> {
+    let valid = !id.is_empty()
+        && id.len() <= 64
+        && id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        && !id.starts_with(['-', '_']);
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid plugin id '{id}': use lowercase letters, digits, '-' and '_'"
+        ))
+    }
+}
+
+/// Read and validate the manifest in a plugin folder.
+pub fn read_manifest(plugin_dir: &Path) -> Result<PluginManifest, String> {
+    let path = plugin_dir.join(PLUGIN_MANIFEST_FILE);
+    let data =
+        fs::read_to_string(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let manifest: PluginManifest =
+        serde_json::from_str(&data).map_err(|e| format!("Invalid {PLUGIN_MANIFEST_FILE}: {e}"))?;
+    validate_plugin_id(&manifest.id)?;
+    if manifest.command.trim().is_empty() {
+        return Err(format!("Plugin {} has no command", manifest.id));
+    }
+    Ok(manifest)
+}
+
+/// Grants requested by the user, limited to what the manifest asks for.
+pub fn resolve_grants(
+    manifest: &PluginManifest,
+    granted: Option<Vec<PluginCapability>>,
+) -> Result<Vec<PluginCapability>, String> {
+    let mut granted = granted.unwrap_or_default();
+    if let Some(extra) = granted.iter().find(|c| !manifest.capabilities.contains(c)) {
+        return Err(format!(
+            "Plugin {} does not request the {extra:?} capability",
+            manifest.id
+        ));
+    }
+    granted.sort();
+    granted.dedup();
+    Ok(granted)
+}
+
+/// Reject a registration that uses capabilities the plugin was not granted.
+pub fn check_registration(
+    plugin_id: &str,
+    granted: &[PluginCapability],
+    registration: &PluginRegistration,
+) -> Result<(), String> {
+    let uses = [
+        (
+            PluginCapability::Commands,
+            !registration.commands.is_empty(),
+        ),
+        (
+            PluginCapability::Providers,
+            !registration.providers.is_empty(),
+        ),
+        (
+            PluginCapability::MessageHooks,
+            !registration.hooks.is_empty(),
+        ),
+    ];
+    match uses
+        .iter()
+        .find(|(capability, used)| *used && !granted.contains(capability))
+    {
+        Some((capability, _)) => Err(format!(
+            "Plugin {plugin_id} registered {capability:?} without being granted that capability"
+        )),
+        None => Ok(()),
+    }
+}
+
+pub fn require_capability(
+    plugin_id: &str,
+    granted: &[PluginCapability],
+    capability: PluginCapability,
+) -> Result<(), String> {
+    if granted.contains(&capability) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Plugin {plugin_id} has not been granted the {capability:?} capability"
+        ))
+    }
+}
+
+fn read_storage(data_folder: &Path, plugin_id: &str) -> Map<String, Value> {
+    fs::read_to_string(get_storage_path(data_folder, plugin_id))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+pub async fn storage_get(data_folder: &Path, plugin_id: &str, key: &str) -> Value {
+    let _guard = storage_lock().lock().await;
+    read_storage(data_folder, plugin_id)
+        .remove(key)
+        .unwrap_or(Value::Null)
+}
+
+/// Set (or with `None`, delete) a key in the plugin's private store.
+pub async fn storage_set(
+    data_folder: &Path,
+    plugin_id: &str,
+    key: &str,
+    value: Option<Value>,
+) -> Result<(), String> {
+    let _guard = storage_lock().lock().await;
+    let mut storage = read_storage(data_folder, plugin_id);
+    match value {
+        Some(value) => storage.insert(key.to_string(), value),
+        None => storage.remove(key),
+    };
+    let data = serde_json::to_string(&storage).map_err(|e| e.to_string())?;
+    if data.len() > MAX_PLUGIN_STORAGE_BYTES {
+        return Err(format!(
+            "Plugin storage is limited to {MAX_PLUGIN_STORAGE_BYTES} bytes"
+        ));
+    }
+    fs::write(get_storage_path(data_folder, plugin_id), data).map_err(|e| e.to_string())
+}
+
+/// Provider name a plugin provider is registered under, e.g. `plugin:weather:openai`.
+pub fn plugin_provider_name(plugin_id: &str, provider: &str) -> String {
+    format!("{PLUGIN_PROVIDER_PREFIX}:{plugin_id}:{provider}")
+}
+
+/// Add the plugin's providers to the provider layer.
+pub async fn register_plugin_providers(
+    state: &AppState,
+    plugin_id: &str,
+    registration: &PluginRegistration,
+) {
+    let mut configs = state.provider_configs.write().await;
+    for provider in &registration.providers {
+        let name = plugin_provider_name(plugin_id, &provider.name);
+        if let Err(e) = check_provider_allowed(admin_policy(), &name) {
+            log::warn!("Not registering provider of plugin {plugin_id}: {e}");
+            continue;
+        }
+        configs.insert(
+            name.clone(),
+            ProviderConfig {
+                provider: name,
+                api_key: provider.api_key.clone(),
+                base_url: Some(provider.base_url.clone()),
+                custom_headers: Vec::new(),
+                models: provider.models.clone(),
+                ..Default::default()
+            },
+        );
+    }
+}
+
+/// Remove every provider the plugin registered.
+pub async fn unregister_plugin_providers(state: &AppState, plugin_id: &str) {
+    let prefix = plugin_provider_name(plugin_id, "");
+    state
+        .provider_configs
+        .write()
+        .await
+        .retain(|name, _| !name.starts_with(&prefix));
+}
@@ -0,0 +1,41 @@
+/*!
+   Plugin Host
+
+   Runs third-party plugins as child processes speaking newline-delimited JSON-RPC 2.0 over
+   stdio. This is separate from the frontend extensions in `extensions/`: plugins live in the
+   core and keep working when the app runs headless.
+
+   Each plugin is installed into `plugins/<id>/` with a `plugin.json` manifest naming the
+   executable to start and the capabilities it asks for. The user grants a subset of those at
+   install time and the host enforces them:
+   - `commands`: register commands the UI (or other core services) can execute
+   - `providers`: register OpenAI-compatible endpoints, routed through the regular provider layer
+   - `message_hooks`: rewrite user messages before they are sent and answers after they arrive
+   - `storage`: read and write a private key/value store in `plugins/<id>/storage.json`
+
+   On start the host sends `initialize` and the plugin answers with what it registers; anything
+   outside its granted capabilities is rejected. Plugins call back into the host for storage and
+   logging. Installed plugins and their grants are recorded in `plugins/plugins.json`; enabled
+   plugins are started at app setup.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+pub mod runtime;
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use runtime::PluginProcess;
+
+/// Running plugin processes by plugin id
+#[derive(Default)]
+pub struct PluginHostState {
+    pub running: Arc<Mutex<HashMap<String, Arc<PluginProcess>>>>,
+}
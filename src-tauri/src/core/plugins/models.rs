@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginCapability {
+    Commands,
+    Providers,
+    MessageHooks,
+    Storage,
+}
+
+/// Contents of a plugin's `plugin.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginManifest {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Executable to start, relative to the plugin folder or looked up on `PATH`
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Capabilities the plugin asks for
+    #[serde(default)]
+    pub capabilities: Vec<PluginCapability>,
+}
+
+/// An installed plugin as recorded in `plugins.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstalledPlugin {
+    pub manifest: PluginManifest,
+    pub enabled: bool,
+    /// Capabilities granted by the user; always a subset of the requested ones
+    pub granted: Vec<PluginCapability>,
+    pub installed_at: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageHook {
+    /// Runs on a user message before it is sent to the model
+    BeforeSend,
+    /// Runs on an assistant message after the model produced it
+    AfterReceive,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginCommand {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// An OpenAI-compatible endpoint served by a plugin
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginProvider {
+    pub name: String,
+    pub base_url: String,
+    #[serde(default)]
+    pub api_key: Option<String>,
+    #[serde(default)]
+    pub models: Vec<String>,
+}
+
+/// What a plugin registers in its `initialize` response
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PluginRegistration {
+    #[serde(default)]
+    pub commands: Vec<PluginCommand>,
+    #[serde(default)]
+    pub providers: Vec<PluginProvider>,
+    #[serde(default)]
+    pub hooks: Vec<MessageHook>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginInfo {
+    #[serde(flatten)]
+    pub plugin: InstalledPlugin,
+    pub running: bool,
+    /// Registration of the running process, if any
+    pub registration: Option<PluginRegistration>,
+}
+
+/// A JSON-RPC message read from a plugin's stdout
+#[derive(Debug, Clone, Deserialize)]
+pub struct RpcMessage {
+    #[serde(default)]
+    pub id: Option<Value>,
+    #[serde(default)]
+    pub method: Option<String>,
+    #[serde(default)]
+    pub params: Option<Value>,
+    #[serde(default)]
+    pub result: Option<Value>,
+    #[serde(default)]
+    pub error: Option<RpcError>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
@@ -0,0 +1,478 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, Command};
+use tokio::sync::{oneshot, Mutex};
+
+use super::constants::{
+    PLUGIN_CALL_TIMEOUT, PLUGIN_INIT_TIMEOUT, PLUGIN_PROTOCOL_VERSION, PLUGIN_SHUTDOWN_GRACE,
+};
+use super::helpers::{
+    check_registration, get_plugin_dir, read_registry, register_plugin_providers,
+    require_capability, storage_get, storage_set, unregister_plugin_providers,
+};
+use super::models::{
+    InstalledPlugin, MessageHook, PluginCapability, PluginRegistration, RpcError, RpcMessage,
+};
+use super::PluginHostState;
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::state::AppState;
+
+const METHOD_NOT_FOUND: i64 = -32601;
+const HOST_ERROR: i64 = -32000;
+
+type PendingCalls = std::sync::Mutex<HashMap<u64, oneshot::Sender<Result<Value, String>>>>;
+
+/// JSON-RPC connection over a plugin's stdin/stdout
+pub struct RpcConnection {
+    stdin: Mutex<ChildStdin>,
+    pending: PendingCalls,
+    next_id: AtomicU64,
+}
+
+impl RpcConnection {
+    fn new(stdin: ChildStdin) -> Self {
+        Self {
+            stdin: Mutex::new(stdin),
+            pending: Default::default(),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    async fn send(&self, message: Value) -> Result<(), String> {
+        let mut line = serde_json::to_string(&message).map_err(|e| e.to_string())?;
+        line.push('\n');
+        let mut stdin = self.stdin.lock().await;
+        stdin
+            .write_all(line.as_bytes())
+            .await
+            .map_err(|e| format!("Failed to write to plugin: {e}"))?;
+        stdin.flush().await.map_err(|e| e.to_string())
+    }
+
+    /// Send a request and wait for the plugin's response.
+    pub async fn request(
+        &self,
+        method: &str,
+        params: Value,
+        timeout: Duration,
+    ) -> Result<Value, String> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id, tx);
+
+        let sent = self
+            .send(json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}))
+            .await;
+        if let Err(e) = sent {
+            self.pending.lock().unwrap().remove(&id);
+            return Err(e);
+        }
+        match tokio::time::timeout(timeout, rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err("Plugin exited before answering".to_string()),
+            Err(_) => {
+                self.pending.lock().unwrap().remove(&id);
+                Err(format!("Plugin did not answer {method} in time"))
+            }
+        }
+    }
+
+    pub async fn notify(&self, method: &str, params: Value) -> Result<(), String> {
+        self.send(json!({"jsonrpc": "2.0", "method": method, "params": params}))
+            .await
+    }
+
+    async fn respond(&self, id: Value, result: Result<Value, RpcError>) {
+        let message = match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err(error) => json!({"jsonrpc": "2.0", "id": id, "error": error}),
+        };
+        if let Err(e) = self.send(message).await {
+            log::warn!("{e}");
+        }
+    }
+
+    fn resolve(&self, message: RpcMessage) {
+        let Some(id) = message.id.as_ref().and_then(Value::as_u64) else {
+            return;
+        };
+        let Some(tx) = self.pending.lock().unwrap().remove(&id) else {
+            return;
+        };
+        let result = match message.error {
+            Some(error) => Err(error.message),
+            None => Ok(message.result.unwrap_or(Value::Null)),
+        };
+        let _ = tx.send(result);
+    }
+
+    fn fail_all(&self) {
+        // Dropping the senders wakes every waiting request with an error
+        self.pending.lock().unwrap().clear();
+    }
+}
+
+/// Answer a request the plugin sent to the host.
+async fn handle_host_request(
+    data_folder: &Path,
+    plugin_id: &str,
+    granted: &[PluginCapability],
+    method: &str,
+    params: Value,
+) -> Result<Value, RpcError> {
+    let host_error = |message: String| RpcError {
+        code: HOST_ERROR,
+        message,
+    };
+    let key = || {
+        params
+            .get("key")
+            .and_then(Value::as_str)
+            .ok_or_else(|| host_error("Missing storage key".to_string()))
+    };
+    match method {
+        "storage.get" | "storage.set" | "storage.delete" => {
+            require_capability(plugin_id, granted, PluginCapability::Storage)
+                .map_err(host_error)?;
+            let key = key()?;
+            match method {
+                "storage.get" => Ok(storage_get(data_folder, plugin_id, key).await),
+                "storage.set" => {
+                    let value = params.get("value").cloned().unwrap_or(Value::Null);
+                    storage_set(data_folder, plugin_id, key, Some(value))
+                        .await
+                        .map(|_| Value::Null)
+                        .map_err(host_error)
+                }
+                _ => storage_set(data_folder, plugin_id, key, None)
+                    .await
+                    .map(|_| Value::Null)
+                    .map_err(host_error),
+            }
+        }
+        _ => Err(RpcError {
+            code: METHOD_NOT_FOUND,
+            message: format!("Unknown host method {method}"),
+        }),
+    }
+}
+
+fn log_from_plugin(plugin_id: &str, params: &Value) {
+    let message = params
+        .get("message")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    match params.get("level").and_then(Value::as_str) {
+        Some("error") => log::error!("[plugin {plugin_id}] {message}"),
+        Some("warn") => log::warn!("[plugin {plugin_id}] {message}"),
+        Some("debug") => log::debug!("[plugin {plugin_id}] {message}"),
+        _ => log::info!("[plugin {plugin_id}] {message}"),
+    }
+}
+
+/// A running plugin process and what it registered
+pub struct PluginProcess {
+    pub plugin_id: String,
+    pub granted: Vec<PluginCapability>,
+    pub registration: PluginRegistration,
+    rpc: Arc<RpcConnection>,
+    child: Mutex<Child>,
+}
+
+impl PluginProcess {
+    /// Start the plugin, serve its requests in the background and run the `initialize`
+    /// handshake. The process is killed again if the handshake fails or the registration uses
+    /// capabilities that were not granted.
+    pub async fn start<R: Runtime>(
+        app: &AppHandle<R>,
+        data_folder: &Path,
+        plugin: &InstalledPlugin,
+    ) -> Result<Arc<Self>, String> {
+        let manifest = &plugin.manifest;
+        let plugin_dir = get_plugin_dir(data_folder, &manifest.id);
+        let local_command = plugin_dir.join(&manifest.command);
+        let program = if local_command.exists() {
+            local_command
+        } else {
+            PathBuf::from(&manifest.command)
+        };
+
+        let mut cmd = Command::new(program);
+        cmd.args(&manifest.args)
+            .current_dir(&plugin_dir)
+            .env("JAN_PLUGIN_ID", &manifest.id)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true);
+        #[cfg(windows)]
+        {
+            cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+        }
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| format!("Failed to start plugin {}: {e}", manifest.id))?;
+        let stdin = child.stdin.take().ok_or("Plugin stdin unavailable")?;
+        let stdout = child.stdout.take().ok_or("Plugin stdout unavailable")?;
+        if let Some(stderr) = child.stderr.take() {
+            let plugin_id = manifest.id.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut lines = BufReader::new(stderr).lines();
+                while let Ok(Some(line)) = lines.next_line().await {
+                    log::debug!("[plugin {plugin_id}] {line}");
+                }
+            });
+        }
+
+        let rpc = Arc::new(RpcConnection::new(stdin));
+        spawn_reader(
+            app.clone(),
+            data_folder.to_path_buf(),
+            plugin.clone(),
+            rpc.clone(),
+            stdout,
+        );
+
+        let handshake = rpc
+            .request(
+                "initialize",
+                json!({
+                    "protocol_version": PLUGIN_PROTOCOL_VERSION,
+                    "host_version": app.package_info().version.to_string(),
+                    "granted": plugin.granted,
+                }),
+                PLUGIN_INIT_TIMEOUT,
+            )
+            .await
+            .and_then(|result| {
+                serde_json::from_value::<PluginRegistration>(result)
+                    .map_err(|e| format!("Invalid initialize response: {e}"))
+            })
+            .and_then(|registration| {
+                check_registration(&manifest.id, &plugin.granted, &registration)
+                    .map(|_| registration)
+            });
+        let registration = match handshake {
+            Ok(registration) => registration,
+            Err(e) => {
+                let _ = child.kill().await;
+                return Err(format!("Plugin {} failed to initialize: {e}", manifest.id));
+            }
+        };
+
+        Ok(Arc::new(Self {
+            plugin_id: manifest.id.clone(),
+            granted: plugin.granted.clone(),
+            registration,
+            rpc,
+            child: Mutex::new(child),
+        }))
+    }
+
+    /// Execute a command the plugin registered.
+    pub async fn execute_command(&self, command: &str, args: Value) -> Result<Value, String> {
+        require_capability(&self.plugin_id, &self.granted, PluginCapability::Commands)?;
+        if !self.registration.commands.iter().any(|c| c.name == command) {
+            return Err(format!(
+                "Plugin {} has no command {command}",
+                self.plugin_id
+            ));
+        }
+        self.rpc
+            .request(
+                "command.execute",
+                json!({"name": command, "args": args}),
+                PLUGIN_CALL_TIMEOUT,
+            )
+            .await
+    }
+
+    pub fn has_hook(&self, hook: MessageHook) -> bool {
+        self.granted.contains(&PluginCapability::MessageHooks)
+            && self.registration.hooks.contains(&hook)
+    }
+
+    /// Let the plugin rewrite a message. The plugin answers `{"message": ...}`; anything else
+    /// leaves the message unchanged.
+    pub async fn apply_hook(&self, hook: MessageHook, message: Value) -> Result<Value, String> {
+        let result = self
+            .rpc
+            .request(
+                "hook.message",
+                json!({"hook": hook, "message": message}),
+                PLUGIN_CALL_TIMEOUT,
+            )
+            .await?;
+        Ok(result.get("message").cloned().unwrap_or(message))
+    }
+
+    /// Ask the plugin to exit, killing it if it does not within the grace period.
+    pub async fn shutdown(&self) {
+        let _ = self.rpc.notify("shutdown", Value::Null).await;
+        let mut child = self.child.lock().await;
+        if tokio::time::timeout(PLUGIN_SHUTDOWN_GRACE, child.wait())
+            .await
+            .is_err()
+        {
+            let _ = child.kill().await;
+        }
+        self.rpc.fail_all();
+    }
+}
+
+/// Read the plugin's stdout: resolve responses, serve host requests and handle notifications.
+/// When the plugin exits it is dropped from the running set and its providers are removed.
+fn spawn_reader<R: Runtime>(
+    app: AppHandle<R>,
+    data_folder: PathBuf,
+    plugin: InstalledPlugin,
+    rpc: Arc<RpcConnection>,
+    stdout: tokio::process::ChildStdout,
+) {
+    tauri::async_runtime::spawn(async move {
+        let plugin_id = plugin.manifest.id.clone();
+        let mut lines = BufReader::new(stdout).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let message: RpcMessage = match serde_json::from_str(&line) {
+                Ok(message) => message,
+                Err(e) => {
+                    log::warn!("[plugin {plugin_id}] invalid message: {e}");
+                    continue;
+                }
+            };
+            match (message.method.clone(), message.id.clone()) {
+                (Some(method), Some(id)) => {
+                    let params = message.params.unwrap_or(Value::Null);
+                    let result = handle_host_request(
+                        &data_folder,
+                        &plugin_id,
+                        &plugin.granted,
+                        &method,
+                        params,
+                    )
+                    .await;
+                    rpc.respond(id, result).await;
+                }
+                (Some(method), None) if method == "log" => {
+                    log_from_plugin(&plugin_id, &message.params.unwrap_or(Value::Null))
+                }
+                (Some(method), None) => {
+                    log::debug!("[plugin {plugin_id}] ignoring notification {method}")
+                }
+                (None, _) => rpc.resolve(message),
+            }
+        }
+
+        rpc.fail_all();
+        let host = app.state::<PluginHostState>();
+        let mut running = host.running.lock().await;
+        if running
+            .get(&plugin_id)
+            .is_some_and(|p| Arc::ptr_eq(&p.rpc, &rpc))
+        {
+            running.remove(&plugin_id);
+            drop(running);
+            unregister_plugin_providers(&app.state::<AppState>(), &plugin_id).await;
+            log::warn!("Plugin {plugin_id} exited");
+        }
+    });
+}
+
+/// Start a plugin and make its providers available.
+pub async fn start_plugin<R: Runtime>(
+    app: &AppHandle<R>,
+    data_folder: &Path,
+    plugin: &InstalledPlugin,
+) -> Result<Arc<PluginProcess>, String> {
+    let host = app.state::<PluginHostState>();
+    if let Some(process) = host.running.lock().await.get(&plugin.manifest.id) {
+        return Ok(process.clone());
+    }
+    let process = PluginProcess::start(app, data_folder, plugin).await?;
+    register_plugin_providers(
+        &app.state::<AppState>(),
+        &process.plugin_id,
+        &process.registration,
+    )
+    .await;
+    host.running
+        .lock()
+        .await
+        .insert(process.plugin_id.clone(), process.clone());
+    log::info!("Started plugin {}", process.plugin_id);
+    Ok(process)
+}
+
+/// Stop a running plugin and remove its providers. Does nothing if it is not running.
+pub async fn stop_plugin<R: Runtime>(app: &AppHandle<R>, plugin_id: &str) {
+    let process = app
+        .state::<PluginHostState>()
+        .running
+        .lock()
+        .await
+        .remove(plugin_id);
+    if let Some(process) = process {
+        unregister_plugin_providers(&app.state::<AppState>(), plugin_id).await;
+        process.shutdown().await;
+        log::info!("Stopped plugin {plugin_id}");
+    }
+}
+
+/// Start every enabled plugin in the background.
+pub fn start_enabled_plugins<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let data_folder = get_jan_data_folder_path(app.clone());
+        let plugins = match read_registry(&data_folder) {
+            Ok(plugins) => plugins,
+            Err(e) => {
+                log::error!("Failed to read plugin registry: {e}");
+                return;
+            }
+        };
+        for plugin in plugins.iter().filter(|p| p.enabled) {
+            if let Err(e) = start_plugin(&app, &data_folder, plugin).await {
+                log::error!("{e}");
+            }
+        }
+    });
+}
+
+/// Pass a message through every running plugin that registered `hook`, in plugin id order.
+/// A failing plugin is logged and skipped so it cannot block the conversation.
+pub async fn run_message_hooks<R: Runtime>(
+    app: &AppHandle<R>,
+    hook: MessageHook,
+    mut message: Value,
+) -> Value {
+    let Some(host) = app.try_state::<PluginHostState>() else {
+        return message;
+    };
+    let mut processes: Vec<Arc<PluginProcess>> = host
+        .running
+        .lock()
+        .await
+        .values()
+        .filter(|p| p.has_hook(hook))
+        .cloned()
+        .collect();
+    processes.sort_by(|a, b| a.plugin_id.cmp(&b.plugin_id));
+    for process in processes {
+        match process.apply_hook(hook, message.clone()).await {
+            Ok(updated) => message = updated,
+            Err(e) => log::warn!("Plugin {} hook {hook:?} failed: {e}", process.plugin_id),
+        }
+    }
+    message
+}
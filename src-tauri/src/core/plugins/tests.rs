@@ -0,0 +1,162 @@
+use super::commands::*;
+use super::helpers::{
+    check_registration, resolve_grants, storage_get, storage_set, validate_plugin_id,
+};
+use super::models::{
+    MessageHook, PluginCapability, PluginCommand, PluginManifest, PluginRegistration,
+};
+use super::PluginHostState;
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::state::AppState;
+use serde_json::json;
+use std::fs;
+use tauri::{test::mock_app, Manager};
+
+fn manifest(capabilities: Vec<PluginCapability>) -> PluginManifest {
+    PluginManifest {
+        id: "echo".to_string(),
+        name: "Echo".to_string(),
+        version: "1.0.0".to_string(),
+        description: None,
+        command: "sh".to_string(),
+        args: vec!["plugin.sh".to_string()],
+        capabilities,
+    }
+}
+
+#[test]
+fn test_validate_plugin_id() {
+    assert!(validate_plugin_id("weather-tools_2").is_ok());
+    assert!(validate_plugin_id("").is_err());
+    assert!(validate_plugin_id("../escape").is_err());
+    assert!(validate_plugin_id("Upper").is_err());
+    assert!(validate_plugin_id("-leading").is_err());
+}
+
+#[test]
+fn test_grants_limited_to_requested_capabilities() {
+    let manifest = manifest(vec![PluginCapability::Commands, PluginCapability::Storage]);
+    assert_eq!(
+        resolve_grants(
+            &manifest,
+            Some(vec![PluginCapability::Storage, PluginCapability::Storage])
+        )
+        .unwrap(),
+        vec![PluginCapability::Storage]
+    );
+    assert!(resolve_grants(&manifest, Some(vec![PluginCapability::Providers])).is_err());
+    assert!(resolve_grants(&manifest, None).unwrap().is_empty());
+}
+
+#[test]
+fn test_registration_requires_grants() {
+    let registration = PluginRegistration {
+        commands: vec![PluginCommand {
+            name: "hello".to_string(),
+            description: None,
+        }],
+        providers: vec![],
+        hooks: vec![MessageHook::BeforeSend],
+    };
+    assert!(check_registration(
+        "echo",
+        &[PluginCapability::Commands, PluginCapability::MessageHooks],
+        &registration
+    )
+    .is_ok());
+    let err = check_registration("echo", &[PluginCapability::Commands], &registration).unwrap_err();
+    assert!(err.contains("MessageHooks"));
+}
+
+#[tokio::test]
+async fn test_storage_is_per_plugin() {
+    let app = mock_app();
+    let data_folder = get_jan_data_folder_path(app.handle().clone());
+    for id in ["a", "b"] {
+        fs::create_dir_all(data_folder.join("plugins").join(id)).unwrap();
+    }
+
+    storage_set(&data_folder, "a", "token", Some(json!("secret")))
+        .await
+        .unwrap();
+    assert_eq!(
+        storage_get(&data_folder, "a", "token").await,
+        json!("secret")
+    );
+    assert_eq!(storage_get(&data_folder, "b", "token").await, json!(null));
+
+    storage_set(&data_folder, "a", "token", None).await.unwrap();
+    assert_eq!(storage_get(&data_folder, "a", "token").await, json!(null));
+
+    let _ = fs::remove_dir_all(data_folder);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_plugin_lifecycle() {
+    let app = mock_app();
+    app.manage(AppState::default());
+    app.manage(PluginHostState::default());
+    let data_folder = get_jan_data_folder_path(app.handle().clone());
+
+    // A plugin answering the handshake and one command, then waiting for shutdown
+    let source = data_folder.join("echo-source");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(
+        source.join("plugin.json"),
+        serde_json::to_string(&manifest(vec![PluginCapability::Commands])).unwrap(),
+    )
+    .unwrap();
+    fs::write(
+        source.join("plugin.sh"),
+        r#"read line
+echo '{"jsonrpc":"2.0","id":1,"result":{"commands":[{"name":"hello"}]}}'
+read line
+echo '{"jsonrpc":"2.0","id":2,"result":"world"}'
+read line
+"#,
+    )
+    .unwrap();
+
+    let installed = install_plugin(
+        app.handle().clone(),
+        source.to_string_lossy().to_string(),
+        Some(vec![PluginCapability::Commands]),
+    )
+    .await
+    .unwrap();
+    assert!(!installed.enabled);
+
+    let enabled = enable_plugin(app.handle().clone(), "echo".to_string())
+        .await
+        .unwrap();
+    assert!(enabled.enabled);
+
+    let host = app.state::<PluginHostState>();
+    let plugins = list_plugins(app.handle().clone(), host.clone())
+        .await
+        .unwrap();
+    assert!(plugins[0].running);
+    assert_eq!(
+        plugins[0].registration.as_ref().unwrap().commands[0].name,
+        "hello"
+    );
+
+    let result = execute_plugin_command(host.clone(), "echo".into(), "hello".into(), None)
+        .await
+        .unwrap();
+    assert_eq!(result, json!("world"));
+    assert!(
+        execute_plugin_command(host.clone(), "echo".into(), "missing".into(), None)
+            .await
+            .is_err()
+    );
+
+    uninstall_plugin(app.handle().clone(), "echo".to_string())
+        .await
+        .unwrap();
+    assert!(host.running.lock().await.is_empty());
+    assert!(!data_folder.join("plugins").join("echo").exists());
+
+    let _ = fs::remove_dir_all(data_folder);
+}
@@ -0,0 +1,13 @@
+use std::time::Duration;
+
+/// How often the watcher compares the monotonic and the wall clock
+pub const SLEEP_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// Wall-clock time beyond the check interval that counts as sleep. Large enough that a busy
+/// runtime or a small clock adjustment doesn't trigger a recovery.
+pub const SLEEP_GAP_THRESHOLD: Duration = Duration::from_secs(15);
+
+/// Timeout for probing an MCP server or engine session after resume. Servers that are
+/// alive answer quickly; the regular health checks deal with slow ones.
+pub const RESUME_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+pub const SYSTEM_RESUMED_EVENT: &str = "system-resumed";
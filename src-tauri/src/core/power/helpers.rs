@@ -0,0 +1,232 @@
+use std::collections::HashSet;
+use std::time::{Duration, SystemTime};
+
+use futures::future::join_all;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_llamacpp::state::LlamacppState;
+use tokio::time::{sleep, timeout};
+
+use super::constants::{
+    RESUME_PROBE_TIMEOUT, SLEEP_CHECK_INTERVAL, SLEEP_GAP_THRESHOLD, SYSTEM_RESUMED_EVENT,
+};
+use super::models::SystemResume;
+use crate::core::mcp::helpers::{
+    emit_mcp_update_event, is_remote_server_config, reconnect_mcp_server, start_mcp_server,
+};
+use crate::core::mcp::logs::record_server_log;
+use crate::core::state::{AppState, RunningServiceEnum};
+
+/// How long the machine slept, given the wall-clock time between two checks that were
+/// `interval` apart on the monotonic clock. `None` unless the gap exceeds the threshold;
+/// a wall clock set back is never sleep.
+pub fn sleep_gap(interval: Duration, wall_elapsed: Option<Duration>) -> Option<Duration> {
+    wall_elapsed?
+        .checked_sub(interval)
+        .filter(|gap| *gap > SLEEP_GAP_THRESHOLD)
+}
+
+/// Watch for the machine waking up and recover MCP servers and engine sessions then
+pub fn start_sleep_watcher<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut running = running_mcp_servers(&app).await;
+        let mut last_check = SystemTime::now();
+        loop {
+            sleep(SLEEP_CHECK_INTERVAL).await;
+            let now = SystemTime::now();
+            let wall_elapsed = now.duration_since(last_check).ok();
+            last_check = now;
+            if let Some(slept) = sleep_gap(SLEEP_CHECK_INTERVAL, wall_elapsed) {
+                log::info!("System resumed after about {}s asleep", slept.as_secs());
+                recover_after_resume(&app, slept, &running).await;
+            }
+            // Servers started or stopped since are picked up here, so after a resume the
+            // servers that were running before the sleep are known
+            running = running_mcp_servers(&app).await;
+        }
+    });
+}
+
+async fn running_mcp_servers<R: Runtime>(app: &AppHandle<R>) -> HashSet<String> {
+    let state = app.state::<AppState>();
+    let servers = state.mcp_servers.read().await;
+    servers.keys().cloned().collect()
+}
+
+/// Probe everything that may have died while the machine slept, restart what did and tell
+/// the UI through `system-resumed`
+pub async fn recover_after_resume<R: Runtime>(
+    app: &AppHandle<R>,
+    slept: Duration,
+    was_running: &HashSet<String>,
+) -> SystemResume {
+    let (restarted_servers, lost_models) = tokio::join!(
+        recover_mcp_servers(app, was_running),
+        recover_engine_sessions(app)
+    );
+    let resume = SystemResume {
+        slept_seconds: slept.as_secs(),
+        restarted_servers,
+        lost_models,
+    };
+    if let Err(e) = app.emit(SYSTEM_RESUMED_EVENT, &resume) {
+        log::error!("Failed to emit {SYSTEM_RESUMED_EVENT} event: {e}");
+    }
+    resume
+}
+
+/// Probe the MCP servers of `was_running` that are still active, and restart the ones that
+/// are gone or don't answer. Remote servers are reconnected first and only restarted if that
+/// fails. Returns the recovered servers.
+async fn recover_mcp_servers<R: Runtime>(
+    app: &AppHandle<R>,
+    was_running: &HashSet<String>,
+) -> Vec<String> {
+    let state = app.state::<AppState>();
+    if *state.mcp_shutdown_in_progress.lock().await {
+        return Vec::new();
+    }
+    let active = state.mcp_active_servers.lock().await.clone();
+    let peers: Vec<_> = {
+        let servers = state.mcp_servers.read().await;
+        was_running
+            .iter()
+            .filter(|name| active.contains_key(*name))
+            .map(|name| {
+                (
+                    name.clone(),
+                    servers.get(name).map(RunningServiceEnum::peer),
+                )
+            })
+            .collect()
+    };
+
+    // Probe outside the lock, all servers at once
+    let probes = peers.into_iter().map(|(name, peer)| async move {
+        let alive = match peer {
+            Some(peer) => matches!(
+                timeout(RESUME_PROBE_TIMEOUT, peer.list_all_tools()).await,
+                Ok(Ok(_))
+            ),
+            None => false,
+        };
+        (name, alive)
+    });
+    let dead: Vec<String> = join_all(probes)
+        .await
+        .into_iter()
+        .filter(|(_, alive)| !alive)
+        .map(|(name, _)| name)
+        .collect();
+
+    for name in &dead {
+        let Some(config) = active.get(name).cloned() else {
+            continue;
+        };
+        // A remote server usually just lost its connection; a new session will do
+        if is_remote_server_config(&config)
+            && reconnect_mcp_server(app, &state.mcp_servers, name, &config)
+                .await
+                .is_ok()
+        {
+            continue;
+        }
+        log::warn!("MCP server {name} didn't survive the system sleep, restarting");
+        record_server_log(
+            name,
+            log::Level::Warn,
+            "Lost during system sleep, restarting",
+        );
+        let service = state.mcp_servers.write().await.remove(name);
+        if let Some(service) = service {
+            let result = match service {
+                RunningServiceEnum::NoInit(service) => service.cancel().await.map(drop),
+                RunningServiceEnum::WithInit(service) => service.cancel().await.map(drop),
+            };
+            if let Err(e) = result {
+                log::warn!("Failed to close MCP server {name}: {e}");
+            }
+            emit_mcp_update_event(app, name);
+        }
+        // Started directly rather than through a restart, so the restart metrics only count
+        // servers that failed on their own
+        let app = app.clone();
+        let servers = state.mcp_servers.clone();
+        let name = name.clone();
+        tauri::async_runtime::spawn(async move {
+            let _ = start_mcp_server(app, servers, name, config).await;
+        });
+    }
+    dead
+}
+
+/// Remove the llama.cpp sessions whose process exited or no longer accepts connections.
+/// Returns the models that were loaded in them.
+async fn recover_engine_sessions<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    let Some(state) = app.try_state::<LlamacppState>() else {
+        return Vec::new();
+    };
+    let mut lost = Vec::new();
+    let alive: Vec<(i32, i32)> = {
+        let mut sessions = state.llama_server_process.lock().await;
+        let exited: Vec<i32> = sessions
+            .iter_mut()
+            .filter(|(_, session)| !matches!(session.child.try_wait(), Ok(None)))
+            .map(|(pid, _)| *pid)
+            .collect();
+        for pid in exited {
+            if let Some(session) = sessions.remove(&pid) {
+                log::warn!(
+                    "llama.cpp session of {} exited during the system sleep",
+                    session.info.model_id
+                );
+                lost.push(session.info.model_id);
+            }
+        }
+        sessions
+            .iter()
+            .map(|(pid, session)| (*pid, session.info.port))
+            .collect()
+    };
+
+    let client = match reqwest::Client::builder()
+        .timeout(RESUME_PROBE_TIMEOUT)
+        .no_proxy()
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            log::warn!("Failed to probe llama.cpp sessions: {e}");
+            return lost;
+        }
+    };
+    let probes = alive.into_iter().map(|(pid, port)| {
+        let client = client.clone();
+        async move {
+            let url = format!("http://127.0.0.1:{port}/health");
+            // Any answer, even "loading", means the server is there; a slow answer is left
+            // to the regular request timeouts
+            let unreachable = matches!(client.get(url).send().await, Err(e) if e.is_connect());
+            (pid, unreachable)
+        }
+    });
+    let unreachable: Vec<i32> = join_all(probes)
+        .await
+        .into_iter()
+        .filter(|(_, unreachable)| *unreachable)
+        .map(|(pid, _)| pid)
+        .collect();
+
+    let mut sessions = state.llama_server_process.lock().await;
+    for pid in unreachable {
+        if let Some(mut session) = sessions.remove(&pid) {
+            log::warn!(
+                "llama.cpp session of {} stopped accepting connections during the system sleep",
+                session.info.model_id
+            );
+            let _ = session.child.kill().await;
+            lost.push(session.info.model_id);
+        }
+    }
+    lost
+}
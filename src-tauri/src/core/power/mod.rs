@@ -0,0 +1,23 @@
+/*!
+   System Sleep and Resume
+
+   After the machine sleeps, stdio MCP servers and HTTP/SSE sessions are often dead, but the
+   health checks only notice after their interval and timeouts. A watcher notices the resume
+   instead: it wakes every few seconds and checks how much wall-clock time passed. Timers
+   don't fire while the machine sleeps but the wall clock keeps going, so a check arriving
+   far later than it was due means the machine was asleep. This works the same on every
+   platform without hooking into their power notification APIs.
+
+   On resume every MCP server that was running before is probed at once. Dead servers are
+   dropped and started again right away, HTTP/SSE servers reconnecting with a fresh session;
+   these restarts don't count towards the restart metrics. Local llama.cpp sessions whose
+   process died or no longer answers are removed, so the next request loads the model again.
+   A `system-resumed` event tells the UI what was recovered.
+*/
+
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// Payload of the `system-resumed` event
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemResume {
+    /// Approximate time the machine was asleep
+    pub slept_seconds: u64,
+    /// MCP servers found dead and started again
+    pub restarted_servers: Vec<String>,
+    /// Models whose llama.cpp session was lost and must be loaded again
+    pub lost_models: Vec<String>,
+}
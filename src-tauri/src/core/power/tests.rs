@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+use super::constants::{SLEEP_CHECK_INTERVAL, SLEEP_GAP_THRESHOLD};
+use super::helpers::sleep_gap;
+use super::models::SystemResume;
+
+#[test]
+fn test_sleep_gap() {
+    let interval = SLEEP_CHECK_INTERVAL;
+    // On time, or late within the threshold
+    assert_eq!(sleep_gap(interval, Some(interval)), None);
+    assert_eq!(
+        sleep_gap(interval, Some(interval + SLEEP_GAP_THRESHOLD)),
+        None
+    );
+    // A wall clock set back or running slow isn't sleep
+    assert_eq!(sleep_gap(interval, None), None);
+    assert_eq!(sleep_gap(interval, Some(Duration::from_secs(1))), None);
+
+    let asleep = Duration::from_secs(3600);
+    assert_eq!(sleep_gap(interval, Some(interval + asleep)), Some(asleep));
+}
+
+#[test]
+fn test_system_resume_payload() {
+    let resume = SystemResume {
+        slept_seconds: 600,
+        restarted_servers: vec!["filesystem".to_string()],
+        lost_models: vec!["llama3.2-3b".to_string()],
+    };
+    assert_eq!(
+        serde_json::to_value(&resume).unwrap(),
+        serde_json::json!({
+            "sleptSeconds": 600,
+            "restartedServers": ["filesystem"],
+            "lostModels": ["llama3.2-3b"],
+        })
+    );
+}
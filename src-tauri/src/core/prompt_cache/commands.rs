@@ -0,0 +1,206 @@
+use std::fs;
+use std::path::Path;
+
+use serde_json::json;
+use tauri::{AppHandle, Manager, Runtime, State};
+use tauri_plugin_llamacpp::state::LlamacppState;
+
+use super::constants::MIN_PROMPT_CHARS;
+use super::helpers::{
+    cache_dir, cache_file_name, cache_key, evict_to_limit, invalidate_model, model_fingerprint,
+    prompt_hash, read_index, remove_entry_files, server_post, write_index,
+};
+use super::models::{PromptCacheEntry, PromptCacheIndex, PromptCacheResult};
+use super::PromptCacheState;
+use crate::core::app::commands::get_jan_data_folder_path;
+
+/// Folder to pass as `slot_save_path` when loading a llama.cpp model
+#[tauri::command]
+pub async fn get_prompt_cache_dir<R: Runtime>(app: AppHandle<R>) -> Result<String, String> {
+    let dir = cache_dir(&get_jan_data_folder_path(app));
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir.to_string_lossy().into_owned())
+}
+
+/// Make sure a slot of the running `model_id` session holds the KV cache of `system_prompt`:
+/// restored from disk when a matching entry exists, otherwise prefilled once and saved.
+/// Returns `None` for prompts too short to be worth caching.
+#[tauri::command]
+pub async fn warm_prompt_cache<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, PromptCacheState>,
+    model_id: String,
+    system_prompt: String,
+    slot_id: Option<u32>,
+) -> Result<Option<PromptCacheResult>, String> {
+    if system_prompt.chars().count() < MIN_PROMPT_CHARS {
+        return Ok(None);
+    }
+    let slot_id = slot_id.unwrap_or(0);
+    let (pid, port, api_key, model_path) = {
+        let llama_state = app.state::<LlamacppState>();
+        let sessions = llama_state.llama_server_process.lock().await;
+        let session = sessions
+            .values()
+            .find(|s| s.info.model_id == model_id && !s.info.is_embedding)
+            .ok_or_else(|| format!("Model '{model_id}' is not running"))?;
+        (
+            session.info.pid,
+            session.info.port,
+            session.info.api_key.clone(),
+            session.info.model_path.clone(),
+        )
+    };
+    let fingerprint = model_fingerprint(Path::new(&model_path))?;
+    let key = cache_key(&model_id, &fingerprint, &prompt_hash(&system_prompt));
+    let dir = cache_dir(&get_jan_data_folder_path(app));
+
+    // One cache operation at a time keeps the index and the slots consistent
+    let mut active = state.active.lock().await;
+    let mut index = read_index(&dir);
+    let stale = invalidate_model(&mut index, &model_id, &fingerprint);
+    if !stale.is_empty() {
+        log::info!(
+            "Dropping {} prompt cache entries of {model_id}: the model changed",
+            stale.len()
+        );
+        remove_entry_files(&dir, &stale);
+    }
+    let now = chrono::Utc::now().timestamp_millis();
+    let slot_path = format!("/slots/{slot_id}");
+    let body = json!({ "filename": cache_file_name(&key) });
+
+    if let Some(pos) = index.entries.iter().position(|e| e.key == key) {
+        if active.get(&(pid, slot_id)) == Some(&key) {
+            index.entries[pos].last_used_at = now;
+            write_index(&dir, &index)?;
+            return Ok(Some(PromptCacheResult {
+                key,
+                slot_id,
+                restored: false,
+                n_tokens: index.entries[pos].n_tokens,
+            }));
+        }
+        match server_post(
+            port,
+            &api_key,
+            &format!("{slot_path}?action=restore"),
+            &body,
+        )
+        .await
+        {
+            Ok(_) => {
+                index.entries[pos].last_used_at = now;
+                let n_tokens = index.entries[pos].n_tokens;
+                write_index(&dir, &index)?;
+                active.insert((pid, slot_id), key.clone());
+                return Ok(Some(PromptCacheResult {
+                    key,
+                    slot_id,
+                    restored: true,
+                    n_tokens,
+                }));
+            }
+            Err(e) => {
+                log::warn!("Discarding prompt cache {key}: {e}");
+                let entry = index.entries.remove(pos);
+                remove_entry_files(&dir, &[entry]);
+            }
+        }
+    }
+
+    // Prefill the system prompt alone in the slot, then save it
+    let rendered = server_post(
+        port,
+        &api_key,
+        "/apply-template",
+        &json!({ "messages": [{ "role": "system", "content": system_prompt }] }),
+    )
+    .await?;
+    let prompt = rendered
+        .get("prompt")
+        .and_then(|p| p.as_str())
+        .ok_or("llama-server returned no prompt from /apply-template")?;
+    server_post(
+        port,
+        &api_key,
+        "/completion",
+        &json!({ "prompt": prompt, "n_predict": 0, "id_slot": slot_id, "cache_prompt": true }),
+    )
+    .await?;
+    active.insert((pid, slot_id), key.clone());
+    let saved = server_post(port, &api_key, &format!("{slot_path}?action=save"), &body).await?;
+
+    let n_tokens = saved.get("n_saved").and_then(|n| n.as_u64()).unwrap_or(0);
+    let size_bytes = saved
+        .get("n_written")
+        .and_then(|n| n.as_u64())
+        .or_else(|| {
+            fs::metadata(dir.join(cache_file_name(&key)))
+                .ok()
+                .map(|m| m.len())
+        })
+        .unwrap_or(0);
+    index.entries.push(PromptCacheEntry {
+        key: key.clone(),
+        model_id,
+        model_fingerprint: fingerprint,
+        prompt_hash: prompt_hash(&system_prompt),
+        size_bytes,
+        n_tokens,
+        created_at: now,
+        last_used_at: now,
+    });
+    let max_bytes = index.max_bytes;
+    let evicted = evict_to_limit(&mut index, max_bytes);
+    remove_entry_files(&dir, &evicted);
+    write_index(&dir, &index)?;
+
+    Ok(Some(PromptCacheResult {
+        key,
+        slot_id,
+        restored: false,
+        n_tokens,
+    }))
+}
+
+#[tauri::command]
+pub async fn list_prompt_cache<R: Runtime>(app: AppHandle<R>) -> Result<PromptCacheIndex, String> {
+    Ok(read_index(&cache_dir(&get_jan_data_folder_path(app))))
+}
+
+/// Delete the saved caches of `model_id`, or all of them
+#[tauri::command]
+pub async fn clear_prompt_cache<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, PromptCacheState>,
+    model_id: Option<String>,
+) -> Result<(), String> {
+    let dir = cache_dir(&get_jan_data_folder_path(app));
+    let mut active = state.active.lock().await;
+    let mut index = read_index(&dir);
+    let (removed, kept): (Vec<_>, Vec<_>) = index
+        .entries
+        .drain(..)
+        .partition(|e| model_id.as_ref().map_or(true, |id| &e.model_id == id));
+    index.entries = kept;
+    remove_entry_files(&dir, &removed);
+    active.retain(|_, key| !removed.iter().any(|e| &e.key == key));
+    write_index(&dir, &index)
+}
+
+/// Change the byte limit of the cache folder, evicting entries that no longer fit
+#[tauri::command]
+pub async fn set_prompt_cache_limit<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, PromptCacheState>,
+    max_bytes: u64,
+) -> Result<(), String> {
+    let dir = cache_dir(&get_jan_data_folder_path(app));
+    let _active = state.active.lock().await;
+    let mut index = read_index(&dir);
+    index.max_bytes = max_bytes;
+    let evicted = evict_to_limit(&mut index, max_bytes);
+    remove_entry_files(&dir, &evicted);
+    write_index(&dir, &index)
+}
@@ -0,0 +1,14 @@
+use std::time::Duration;
+
+// Prompt cache constants
+pub const PROMPT_CACHE_DIR: &str = "prompt_cache";
+pub const INDEX_FILE: &str = "index.json";
+pub const CACHE_FILE_EXTENSION: &str = "bin";
+
+/// Default byte limit of the cache folder
+pub const DEFAULT_MAX_CACHE_BYTES: u64 = 4 * 1024 * 1024 * 1024;
+/// System prompts shorter than this are not worth a cache file
+pub const MIN_PROMPT_CHARS: usize = 2048;
+
+/// Saving and restoring large caches is disk bound
+pub const SLOT_REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
@@ -0,0 +1,127 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use super::constants::{CACHE_FILE_EXTENSION, INDEX_FILE, PROMPT_CACHE_DIR, SLOT_REQUEST_TIMEOUT};
+use super::models::{PromptCacheEntry, PromptCacheIndex};
+use crate::core::model_catalog::constants::LLAMACPP_ENGINE;
+
+/// Folder passed to llama-server as `--slot-save-path`
+pub fn cache_dir(data_folder: &Path) -> PathBuf {
+    data_folder.join(LLAMACPP_ENGINE).join(PROMPT_CACHE_DIR)
+}
+
+pub fn prompt_hash(prompt: &str) -> String {
+    hex::encode(Sha256::digest(prompt.as_bytes()))
+}
+
+/// Identify a model file by path, size and modification time, so a re-downloaded or
+/// re-quantized file under the same model id invalidates its caches
+pub fn model_fingerprint(model_path: &Path) -> Result<String, String> {
+    let meta = fs::metadata(model_path)
+        .map_err(|e| format!("Model file {} is missing: {e}", model_path.display()))?;
+    let modified = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_secs());
+    let identity = format!("{}|{}|{modified}", model_path.display(), meta.len());
+    Ok(hex::encode(Sha256::digest(identity.as_bytes()))[..16].to_string())
+}
+
+pub fn cache_key(model_id: &str, model_fingerprint: &str, prompt_hash: &str) -> String {
+    let material = format!("{model_id}\n{model_fingerprint}\n{prompt_hash}");
+    hex::encode(Sha256::digest(material.as_bytes()))[..32].to_string()
+}
+
+/// File name inside the cache folder; llama-server only accepts plain file names
+pub fn cache_file_name(key: &str) -> String {
+    format!("{key}.{CACHE_FILE_EXTENSION}")
+}
+
+/// Read the index; a missing or unreadable index starts an empty cache
+pub fn read_index(dir: &Path) -> PromptCacheIndex {
+    let path = dir.join(INDEX_FILE);
+    let Ok(data) = fs::read_to_string(&path) else {
+        return PromptCacheIndex::default();
+    };
+    serde_json::from_str(&data).unwrap_or_else(|e| {
+        log::warn!(
+            "Resetting invalid prompt cache index {}: {e}",
+            path.display()
+        );
+        PromptCacheIndex::default()
+    })
+}
+
+pub fn write_index(dir: &Path, index: &PromptCacheIndex) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    let data = serde_json::to_string_pretty(index).map_err(|e| e.to_string())?;
+    fs::write(dir.join(INDEX_FILE), data).map_err(|e| e.to_string())
+}
+
+/// Take out the entries of `model_id` computed with another model file
+pub fn invalidate_model(
+    index: &mut PromptCacheIndex,
+    model_id: &str,
+    model_fingerprint: &str,
+) -> Vec<PromptCacheEntry> {
+    let (stale, kept) = index
+        .entries
+        .drain(..)
+        .partition(|e| e.model_id == model_id && e.model_fingerprint != model_fingerprint);
+    index.entries = kept;
+    stale
+}
+
+/// Take out the least recently used entries until the cache fits `max_bytes`
+pub fn evict_to_limit(index: &mut PromptCacheIndex, max_bytes: u64) -> Vec<PromptCacheEntry> {
+    index
+        .entries
+        .sort_by_key(|e| std::cmp::Reverse(e.last_used_at));
+    let mut total = 0u64;
+    let mut evicted = Vec::new();
+    index.entries.retain(|e| {
+        total = total.saturating_add(e.size_bytes);
+        if total > max_bytes {
+            evicted.push(e.clone());
+            false
+        } else {
+            true
+        }
+    });
+    evicted
+}
+
+pub fn remove_entry_files(dir: &Path, entries: &[PromptCacheEntry]) {
+    for entry in entries {
+        let _ = fs::remove_file(dir.join(cache_file_name(&entry.key)));
+    }
+}
+
+/// POST a JSON body to the llama-server of `port`
+pub async fn server_post(
+    port: i32,
+    api_key: &str,
+    path: &str,
+    body: &Value,
+) -> Result<Value, String> {
+    let client = reqwest::Client::builder()
+        .timeout(SLOT_REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    client
+        .post(format!("http://127.0.0.1:{port}{path}"))
+        .bearer_auth(api_key)
+        .json(body)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("llama-server request {path} failed: {e}"))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid response from llama-server {path}: {e}"))
+}
@@ -0,0 +1,33 @@
+/*!
+   Prompt Cache
+
+   Saves the llama.cpp KV cache after prefilling a long system prompt and restores it on later
+   turns, so assistants with large fixed preambles (RAG context, tool schemas) skip the prefill.
+   llama-server is started with `--slot-save-path` pointing at the cache folder and the cache
+   files are written through its `/slots/{id}?action=save|restore` endpoints.
+
+   Entries are keyed by the model id, a fingerprint of the loaded model file and the hash of
+   the system prompt:
+   - entries made for another build of the same model id are dropped when the model changes,
+   - the folder is kept under a byte limit by evicting the least recently used entries,
+   - prompts shorter than a threshold are not cached; re-prefilling them is cheaper.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+
+use tokio::sync::Mutex;
+
+/// Cache key currently held by each slot, keyed by `(server pid, slot id)`. Restores are skipped
+/// when the slot already holds the entry.
+#[derive(Default)]
+pub struct PromptCacheState {
+    pub active: Mutex<HashMap<(i32, u32), String>>,
+}
@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+use super::constants::DEFAULT_MAX_CACHE_BYTES;
+
+fn default_max_bytes() -> u64 {
+    DEFAULT_MAX_CACHE_BYTES
+}
+
+/// A saved slot cache
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PromptCacheEntry {
+    pub key: String,
+    pub model_id: String,
+    /// Identifies the model file the cache was computed with
+    pub model_fingerprint: String,
+    pub prompt_hash: String,
+    pub size_bytes: u64,
+    pub n_tokens: u64,
+    /// Milliseconds since the Unix epoch
+    pub created_at: i64,
+    pub last_used_at: i64,
+}
+
+/// `index.json` of the cache folder
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PromptCacheIndex {
+    #[serde(default = "default_max_bytes")]
+    pub max_bytes: u64,
+    #[serde(default)]
+    pub entries: Vec<PromptCacheEntry>,
+}
+
+impl Default for PromptCacheIndex {
+    fn default() -> Self {
+        Self {
+            max_bytes: DEFAULT_MAX_CACHE_BYTES,
+            entries: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PromptCacheResult {
+    pub key: String,
+    /// Slot holding the prompt; send the following completions with this `id_slot`
+    pub slot_id: u32,
+    /// Whether the cache was read from disk; false when the slot already held it or it was
+    /// just prefilled
+    pub restored: bool,
+    pub n_tokens: u64,
+}
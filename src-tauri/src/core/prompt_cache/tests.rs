@@ -0,0 +1,152 @@
+use std::fs;
+
+use tauri::test::mock_app;
+use tauri::Manager;
+
+use super::commands::{clear_prompt_cache, list_prompt_cache, set_prompt_cache_limit};
+use super::helpers::{
+    cache_dir, cache_file_name, cache_key, evict_to_limit, invalidate_model, model_fingerprint,
+    prompt_hash, read_index, write_index,
+};
+use super::models::{PromptCacheEntry, PromptCacheIndex};
+use super::PromptCacheState;
+use crate::core::app::commands::get_jan_data_folder_path;
+
+fn entry(key: &str, model_id: &str, fingerprint: &str, size: u64, used: i64) -> PromptCacheEntry {
+    PromptCacheEntry {
+        key: key.to_string(),
+        model_id: model_id.to_string(),
+        model_fingerprint: fingerprint.to_string(),
+        prompt_hash: String::new(),
+        size_bytes: size,
+        n_tokens: 0,
+        created_at: used,
+        last_used_at: used,
+    }
+}
+
+#[test]
+fn test_cache_key() {
+    let hash = prompt_hash("You are a helpful assistant.");
+    let key = cache_key("qwen3", "abc", &hash);
+    assert_eq!(key.len(), 32);
+    assert_eq!(key, cache_key("qwen3", "abc", &hash));
+    assert_ne!(key, cache_key("qwen3", "def", &hash));
+    assert_ne!(key, cache_key("llama", "abc", &hash));
+    assert_ne!(key, cache_key("qwen3", "abc", &prompt_hash("Other prompt")));
+    assert_eq!(cache_file_name(&key), format!("{key}.bin"));
+}
+
+#[test]
+fn test_model_fingerprint_changes_with_file() {
+    let dir = std::env::temp_dir().join(format!("jan-prompt-cache-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    let model = dir.join("model.gguf");
+    fs::write(&model, b"weights").unwrap();
+    let before = model_fingerprint(&model).unwrap();
+    assert_eq!(before, model_fingerprint(&model).unwrap());
+    fs::write(&model, b"other weights").unwrap();
+    assert_ne!(before, model_fingerprint(&model).unwrap());
+    assert!(model_fingerprint(&dir.join("missing.gguf")).is_err());
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn test_invalidate_model() {
+    let mut index = PromptCacheIndex {
+        entries: vec![
+            entry("a", "qwen3", "old", 10, 1),
+            entry("b", "qwen3", "new", 10, 2),
+            entry("c", "llama", "old", 10, 3),
+        ],
+        ..Default::default()
+    };
+    let stale = invalidate_model(&mut index, "qwen3", "new");
+    assert_eq!(stale.len(), 1);
+    assert_eq!(stale[0].key, "a");
+    assert_eq!(
+        index
+            .entries
+            .iter()
+            .map(|e| e.key.as_str())
+            .collect::<Vec<_>>(),
+        vec!["b", "c"]
+    );
+}
+
+#[test]
+fn test_evict_to_limit_drops_least_recently_used() {
+    let mut index = PromptCacheIndex {
+        entries: vec![
+            entry("old", "m", "f", 40, 1),
+            entry("recent", "m", "f", 40, 3),
+            entry("middle", "m", "f", 40, 2),
+        ],
+        ..Default::default()
+    };
+    let evicted = evict_to_limit(&mut index, 100);
+    assert_eq!(evicted.len(), 1);
+    assert_eq!(evicted[0].key, "old");
+    assert_eq!(index.entries.len(), 2);
+    assert!(evict_to_limit(&mut index, 100).is_empty());
+}
+
+#[test]
+fn test_read_index_defaults() {
+    let dir = std::env::temp_dir().join(format!("jan-prompt-index-{}", uuid::Uuid::new_v4()));
+    assert_eq!(read_index(&dir), PromptCacheIndex::default());
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("index.json"), "not json").unwrap();
+    assert_eq!(read_index(&dir), PromptCacheIndex::default());
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn test_clear_and_limit_prompt_cache() {
+    let app = mock_app();
+    app.manage(PromptCacheState::default());
+    let dir = cache_dir(&get_jan_data_folder_path(app.handle().clone()));
+    let index = PromptCacheIndex {
+        max_bytes: 1000,
+        entries: vec![
+            entry("cache-test-a", "cache-test-qwen", "f", 300, 1),
+            entry("cache-test-b", "cache-test-qwen", "f", 300, 2),
+            entry("cache-test-c", "cache-test-llama", "f", 300, 3),
+        ],
+    };
+    write_index(&dir, &index).unwrap();
+    for e in &index.entries {
+        fs::write(dir.join(cache_file_name(&e.key)), b"kv").unwrap();
+    }
+
+    let state = app.state::<PromptCacheState>();
+    set_prompt_cache_limit(app.handle().clone(), state.clone(), 700)
+        .await
+        .unwrap();
+    let listed = list_prompt_cache(app.handle().clone()).await.unwrap();
+    assert_eq!(listed.max_bytes, 700);
+    assert_eq!(listed.entries.len(), 2);
+    assert!(!dir.join(cache_file_name("cache-test-a")).exists());
+
+    clear_prompt_cache(
+        app.handle().clone(),
+        state.clone(),
+        Some("cache-test-qwen".to_string()),
+    )
+    .await
+    .unwrap();
+    let listed = list_prompt_cache(app.handle().clone()).await.unwrap();
+    assert_eq!(listed.entries.len(), 1);
+    assert_eq!(listed.entries[0].key, "cache-test-c");
+    assert!(!dir.join(cache_file_name("cache-test-b")).exists());
+
+    clear_prompt_cache(app.handle().clone(), state.clone(), None)
+        .await
+        .unwrap();
+    assert!(list_prompt_cache(app.handle().clone())
+        .await
+        .unwrap()
+        .entries
+        .is_empty());
+    let _ = fs::remove_dir_all(dir);
+}
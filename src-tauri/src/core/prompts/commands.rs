@@ -0,0 +1,193 @@
+use std::collections::HashMap;
+
+use tauri::Runtime;
+use uuid::Uuid;
+
+use super::helpers::{
+    builtin_values, extract_variables, library_lock, read_library, render_template, write_library,
+};
+use super::models::{PromptTemplate, PromptTemplateInput, RenderedPrompt};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::config_history::helpers::record_config_change;
+
+fn validate_input(input: &PromptTemplateInput) -> Result<Vec<String>, String> {
+    if input.name.trim().is_empty() {
+        return Err("Template name cannot be empty".to_string());
+    }
+    extract_variables(&input.content)
+}
+
+/// Lists all prompt templates in the library.
+#[tauri::command]
+pub async fn list_prompt_templates<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+) -> Result<Vec<PromptTemplate>, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    Ok(read_library(&data_folder)?.templates)
+}
+
+/// Retrieves a single prompt template by id.
+#[tauri::command]
+pub async fn get_prompt_template<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    template_id: String,
+) -> Result<PromptTemplate, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    read_library(&data_folder)?
+        .templates
+        .into_iter()
+        .find(|t| t.id == template_id)
+        .ok_or_else(|| format!("Prompt template {template_id} not found"))
+}
+
+/// Creates a new prompt template after validating its placeholders.
+#[tauri::command]
+pub async fn create_prompt_template<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    template: PromptTemplateInput,
+) -> Result<PromptTemplate, String> {
+    let variables = validate_input(&template)?;
+    let data_folder = get_jan_data_folder_path(app_handle);
+    let now = chrono::Utc::now().timestamp_millis();
+    let created = PromptTemplate {
+        id: Uuid::new_v4().to_string(),
+        name: template.name,
+        description: template.description,
+        content: template.content,
+        variables,
+        created_at: now,
+        updated_at: now,
+    };
+
+    let _guard = library_lock().lock().await;
+    let mut library = read_library(&data_folder)?;
+    library.templates.push(created.clone());
+    write_library(&data_folder, &library)?;
+    record_config_change(
+        &data_folder,
+        &format!("Add prompt template '{}'", created.name),
+    );
+    Ok(created)
+}
+
+/// Updates the name, description and content of an existing prompt template.
+#[tauri::command]
+pub async fn update_prompt_template<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    template_id: String,
+    template: PromptTemplateInput,
+) -> Result<PromptTemplate, String> {
+    let variables = validate_input(&template)?;
+    let data_folder = get_jan_data_folder_path(app_handle);
+
+    let _guard = library_lock().lock().await;
+    let mut library = read_library(&data_folder)?;
+    let existing = library
+        .templates
+        .iter_mut()
+        .find(|t| t.id == template_id)
+        .ok_or_else(|| format!("Prompt template {template_id} not found"))?;
+    existing.name = template.name;
+    existing.description = template.description;
+    existing.content = template.content;
+    existing.variables = variables;
+    existing.updated_at = chrono::Utc::now().timestamp_millis();
+    let updated = existing.clone();
+    write_library(&data_folder, &library)?;
+    record_config_change(
+        &data_folder,
+        &format!("Update prompt template '{}'", updated.name),
+    );
+    Ok(updated)
+}
+
+/// Deletes a prompt template and removes any assistant bindings pointing at it.
+#[tauri::command]
+pub async fn delete_prompt_template<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    template_id: String,
+) -> Result<(), String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+
+    let _guard = library_lock().lock().await;
+    let mut library = read_library(&data_folder)?;
+    library.templates.retain(|t| t.id != template_id);
+    library
+        .assistant_defaults
+        .retain(|_, bound_id| *bound_id != template_id);
+    write_library(&data_folder, &library)?;
+    record_config_change(
+        &data_folder,
+        &format!("Delete prompt template {template_id}"),
+    );
+    Ok(())
+}
+
+/// Renders a prompt template with the given variables.
+/// Built-in variables (`date`, `time`, `datetime`) are filled in automatically;
+/// any other undefined variable results in an error.
+#[tauri::command]
+pub async fn render_prompt_template<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    template_id: String,
+    variables: Option<HashMap<String, String>>,
+) -> Result<RenderedPrompt, String> {
+    let template = get_prompt_template(app_handle, template_id).await?;
+    let content = render_template(
+        &template.content,
+        &variables.unwrap_or_default(),
+        &builtin_values(),
+    )?;
+    Ok(RenderedPrompt {
+        template_id: template.id,
+        content,
+    })
+}
+
+/// Binds a default prompt template to an assistant, or clears the binding when
+/// `template_id` is `None`.
+#[tauri::command]
+pub async fn set_assistant_default_template<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    assistant_id: String,
+    template_id: Option<String>,
+) -> Result<(), String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+
+    let _guard = library_lock().lock().await;
+    let mut library = read_library(&data_folder)?;
+    match template_id {
+        Some(template_id) => {
+            if !library.templates.iter().any(|t| t.id == template_id) {
+                return Err(format!("Prompt template {template_id} not found"));
+            }
+            library
+                .assistant_defaults
+                .insert(assistant_id.clone(), template_id);
+        }
+        None => {
+            library.assistant_defaults.remove(&assistant_id);
+        }
+    }
+    write_library(&data_folder, &library)?;
+    record_config_change(
+        &data_folder,
+        &format!("Set the default template of assistant {assistant_id}"),
+    );
+    Ok(())
+}
+
+/// Returns the default prompt template bound to an assistant, if any.
+#[tauri::command]
+pub async fn get_assistant_default_template<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    assistant_id: String,
+) -> Result<Option<PromptTemplate>, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    let library = read_library(&data_folder)?;
+    Ok(library
+        .assistant_defaults
+        .get(&assistant_id)
+        .and_then(|template_id| library.templates.iter().find(|t| &t.id == template_id))
+        .cloned())
+}
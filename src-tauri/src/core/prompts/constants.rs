@@ -0,0 +1,3 @@
+// Prompt template constants
+pub const PROMPTS_DIR: &str = "prompts";
+pub const TEMPLATES_FILE: &str = "templates.json";
@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use tokio::sync::Mutex;
+
+use super::constants::{PROMPTS_DIR, TEMPLATES_FILE};
+use super::models::PromptLibrary;
+use crate::core::config_store::helpers::write_json;
+
+// Global lock serializing read-modify-write cycles on templates.json
+static LIBRARY_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+pub fn library_lock() -> &'static Mutex<()> {
+    LIBRARY_LOCK.get_or_init(|| Mutex::new(()))
+}
+
+pub fn get_templates_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(PROMPTS_DIR).join(TEMPLATES_FILE)
+}
+
+/// Read the prompt library, returning an empty library if the file does not exist yet
+pub fn read_library(data_folder: &Path) -> Result<PromptLibrary, String> {
+    let path = get_templates_path(data_folder);
+    if !path.exists() {
+        return Ok(PromptLibrary::default());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    if data.trim().is_empty() {
+        return Ok(PromptLibrary::default());
+    }
+    serde_json::from_str(&data).map_err(|e| format!("Failed to parse prompt templates: {e}"))
+}
+
+/// Persist the prompt library atomically through the config store, creating the prompts
+/// directory if needed
+pub fn write_library(data_folder: &Path, library: &PromptLibrary) -> Result<(), String> {
+    write_json(&get_templates_path(data_folder), library)
+}
+
+/// Extract the unique variable names referenced by `{{name}}` placeholders, in order of appearance.
+/// Returns an error for unterminated or empty placeholders.
+pub fn extract_variables(content: &str) -> Result<Vec<String>, String> {
+    let mut variables: Vec<String> = Vec::new();
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or_else(|| "Unterminated template variable: missing '}}'".to_string())?;
+        let name = after[..end].trim();
+        if name.is_empty() {
+            return Err("Empty template variable '{{}}'".to_string());
+        }
+        if !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '-')
+        {
+            return Err(format!("Invalid template variable name: '{name}'"));
+        }
+        if !variables.iter().any(|v| v == name) {
+            variables.push(name.to_string());
+        }
+        rest = &after[end + 2..];
+    }
+    Ok(variables)
+}
+
+/// Values for the built-in variables at the current local time
+pub fn builtin_values() -> HashMap<String, String> {
+    let now = chrono::Local::now();
+    let mut values = HashMap::new();
+    values.insert("date".to_string(), now.format("%Y-%m-%d").to_string());
+    values.insert("time".to_string(), now.format("%H:%M").to_string());
+    values.insert("datetime".to_string(), now.to_rfc3339());
+    values
+}
+
+/// Render `content` substituting every `{{name}}` placeholder.
+/// Caller-provided values take precedence over built-ins.
+/// Fails listing all undefined variables if any placeholder cannot be resolved.
+pub fn render_template(
+    content: &str,
+    values: &HashMap<String, String>,
+    builtins: &HashMap<String, String>,
+) -> Result<String, String> {
+    let variables = extract_variables(content)?;
+    let undefined: Vec<&str> = variables
+        .iter()
+        .filter(|v| !values.contains_key(v.as_str()) && !builtins.contains_key(v.as_str()))
+        .map(|v| v.as_str())
+        .collect();
+    if !undefined.is_empty() {
+        return Err(format!(
+            "Undefined template variables: {}",
+            undefined.join(", ")
+        ));
+    }
+
+    let mut output = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        // Placeholders were validated by extract_variables above
+        let end = after.find("}}").unwrap_or(after.len());
+        let name = after[..end].trim();
+        let value = values
+            .get(name)
+            .or_else(|| builtins.get(name))
+            .map(String::as_str)
+            .unwrap_or_default();
+        output.push_str(value);
+        rest = &after[(end + 2).min(after.len())..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
@@ -0,0 +1,19 @@
+/*!
+   Prompt Template Library
+
+   Reusable prompt templates (system prompts, snippets) are stored in the Jan data folder
+   under `prompts/templates.json`, together with the per-assistant default template bindings.
+
+   Templates may reference variables using the `{{name}}` syntax. Rendering is performed in
+   the core so every client gets the same substitution and validation rules: built-in
+   variables (`date`, `time`, `datetime`) are always available, other variables must be
+   supplied by the caller, and rendering fails if any referenced variable is undefined.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,47 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A reusable prompt template
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PromptTemplate {
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub content: String,
+    /// Variables referenced by `content`, derived on save
+    #[serde(default)]
+    pub variables: Vec<String>,
+    #[serde(default)]
+    pub created_at: i64,
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+/// Payload used to create or update a template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTemplateInput {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub content: String,
+}
+
+/// On-disk layout of `prompts/templates.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptLibrary {
+    #[serde(default)]
+    pub templates: Vec<PromptTemplate>,
+    /// Assistant id -> default template id
+    #[serde(default)]
+    pub assistant_defaults: HashMap<String, String>,
+}
+
+/// Result of rendering a template
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenderedPrompt {
+    pub template_id: String,
+    pub content: String,
+}
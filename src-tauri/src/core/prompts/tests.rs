@@ -0,0 +1,114 @@
+use super::commands::*;
+use super::helpers::{extract_variables, render_template};
+use super::models::PromptTemplateInput;
+use crate::core::app::commands::get_jan_data_folder_path;
+use std::collections::HashMap;
+use std::fs;
+use tauri::test::mock_app;
+
+fn values(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[test]
+fn test_extract_variables() {
+    let vars =
+        extract_variables("Summarize {{selection}} as of {{ date }}. {{selection}}").unwrap();
+    assert_eq!(vars, vec!["selection".to_string(), "date".to_string()]);
+}
+
+#[test]
+fn test_extract_variables_invalid() {
+    assert!(extract_variables("Hello {{name").is_err());
+    assert!(extract_variables("Hello {{  }}").is_err());
+    assert!(extract_variables("Hello {{na me}}").is_err());
+}
+
+#[test]
+fn test_render_template_substitutes_values() {
+    let rendered = render_template(
+        "Today is {{date}}. Explain: {{ selection }}",
+        &values(&[("selection", "borrow checker")]),
+        &values(&[("date", "2025-01-01")]),
+    )
+    .unwrap();
+    assert_eq!(rendered, "Today is 2025-01-01. Explain: borrow checker");
+}
+
+#[test]
+fn test_render_template_caller_overrides_builtin() {
+    let rendered = render_template(
+        "{{date}}",
+        &values(&[("date", "custom")]),
+        &values(&[("date", "2025-01-01")]),
+    )
+    .unwrap();
+    assert_eq!(rendered, "custom");
+}
+
+#[test]
+fn test_render_template_reports_undefined_variables() {
+    let err =
+        render_template("{{a}} {{b}} {{date}}", &values(&[("a", "1")]), &values(&[])).unwrap_err();
+    assert!(err.contains("b"));
+    assert!(err.contains("date"));
+    assert!(!err.contains("a,"));
+}
+
+#[tokio::test]
+async fn test_template_crud_and_assistant_binding() {
+    let app = mock_app();
+    let data_dir = get_jan_data_folder_path(app.handle().clone());
+
+    let created = create_prompt_template(
+        app.handle().clone(),
+        PromptTemplateInput {
+            name: "Explain".to_string(),
+            description: None,
+            content: "Explain {{selection}}".to_string(),
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(created.variables, vec!["selection".to_string()]);
+
+    let rendered = render_prompt_template(
+        app.handle().clone(),
+        created.id.clone(),
+        Some(values(&[("selection", "lifetimes")])),
+    )
+    .await
+    .unwrap();
+    assert_eq!(rendered.content, "Explain lifetimes");
+
+    set_assistant_default_template(
+        app.handle().clone(),
+        "jan".to_string(),
+        Some(created.id.clone()),
+    )
+    .await
+    .unwrap();
+    let bound = get_assistant_default_template(app.handle().clone(), "jan".to_string())
+        .await
+        .unwrap();
+    assert_eq!(bound.map(|t| t.id), Some(created.id.clone()));
+
+    delete_prompt_template(app.handle().clone(), created.id.clone())
+        .await
+        .unwrap();
+    assert!(list_prompt_templates(app.handle().clone())
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(
+        get_assistant_default_template(app.handle().clone(), "jan".to_string())
+            .await
+            .unwrap()
+            .is_none()
+    );
+
+    let _ = fs::remove_dir_all(data_dir);
+}
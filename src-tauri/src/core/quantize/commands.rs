@@ -0,0 +1,250 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tauri::{AppHandle, Runtime, State};
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+use super::constants::{PARTIAL_SUFFIX, QUANTIZE_PROGRESS_EVENT, QUANT_TYPES};
+use super::helpers::{
+    check_disk_space, detect_quantization, estimate_output_size, find_quantize_binary, job_payload,
+    needs_requantize, quantize_args, register_quantized_model, run_quantize, target_file_name,
+    validate_target,
+};
+use super::models::{QuantizationType, QuantizeJob, QuantizeRequest, QuantizeStatus};
+use super::QuantizeState;
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::events::helpers::{emit_coalesced, emit_final, emit_task_progress};
+use crate::core::model_catalog::constants::LLAMACPP_ENGINE;
+use crate::core::model_catalog::helpers::{model_dir, read_model, resolve_catalog_path};
+use crate::core::model_catalog::models::CatalogModel;
+
+fn emit_job<R: Runtime>(app: &AppHandle<R>, job: &QuantizeJob) {
+    if job.status == QuantizeStatus::Running {
+        emit_coalesced(app, QUANTIZE_PROGRESS_EVENT, &job.job_id, job);
+    } else {
+        emit_final(app, QUANTIZE_PROGRESS_EVENT, &job.job_id, job);
+    }
+    emit_task_progress(
+        app,
+        &job_payload(job, chrono::Utc::now().timestamp_millis()),
+    );
+}
+
+/// Quantization types that can be requested
+#[tauri::command]
+pub fn list_quantization_types() -> Vec<QuantizationType> {
+    QUANT_TYPES
+        .iter()
+        .map(|(name, bits)| QuantizationType {
+            name: name.to_string(),
+            bits_per_weight: *bits,
+        })
+        .collect()
+}
+
+/// Start converting a llama.cpp model to a smaller quantization. Returns as soon as the job
+/// is running; progress and the final state arrive as `model-quantize-progress` events.
+#[tauri::command]
+pub async fn quantize_model<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, QuantizeState>,
+    request: QuantizeRequest,
+) -> Result<QuantizeJob, String> {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let source = read_model(&data_folder, LLAMACPP_ENGINE, &request.model_id)?;
+    let source_path = resolve_catalog_path(&data_folder, &source.model_path);
+    let source_size = fs::metadata(&source_path)
+        .map_err(|e| format!("Model file {} is missing: {e}", source_path.display()))?
+        .len();
+    let source_file_name = source_path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let source_quant = source
+        .quantization
+        .clone()
+        .or_else(|| detect_quantization(&source_file_name));
+    let quant = validate_target(source_quant.as_deref(), &request.quant_type)?;
+
+    let target_id = request
+        .target_model_id
+        .clone()
+        .unwrap_or_else(|| format!("{}-{quant}", request.model_id));
+    let target_dir = model_dir(&data_folder, LLAMACPP_ENGINE, &target_id)?;
+    if target_dir.exists() {
+        return Err(format!("Model '{target_id}' already exists"));
+    }
+    {
+        let jobs = state.jobs.lock().await;
+        if jobs
+            .values()
+            .any(|job| job.target_model_id == target_id && job.status == QuantizeStatus::Running)
+        {
+            return Err(format!("'{target_id}' is already being created"));
+        }
+    }
+
+    let output_size = estimate_output_size(source_size, source_quant.as_deref(), &quant);
+    check_disk_space(&data_folder, output_size)?;
+    let binary = find_quantize_binary(&app, &data_folder).ok_or_else(|| {
+        "llama-quantize was not found. Install a llama.cpp backend that ships it.".to_string()
+    })?;
+
+    let output = target_dir.join(target_file_name(
+        &source_file_name,
+        source_quant.as_deref(),
+        &quant,
+    ));
+    let args = quantize_args(
+        &source_path,
+        &PathBuf::from(format!("{}{PARTIAL_SUFFIX}", output.display())),
+        &quant,
+        needs_requantize(source_quant.as_deref()),
+        request.threads,
+    );
+
+    let job = QuantizeJob {
+        job_id: uuid::Uuid::new_v4().to_string(),
+        model_id: request.model_id.clone(),
+        target_model_id: target_id,
+        quant_type: quant,
+        status: QuantizeStatus::Running,
+        tensors_done: 0,
+        tensors_total: 0,
+        output_size,
+        error: None,
+        started_at: chrono::Utc::now().timestamp_millis(),
+    };
+    let cancel = CancellationToken::new();
+    state
+        .jobs
+        .lock()
+        .await
+        .insert(job.job_id.clone(), job.clone());
+    state
+        .cancel_tokens
+        .lock()
+        .await
+        .insert(job.job_id.clone(), cancel.clone());
+    emit_job(&app, &job);
+
+    let jobs = state.jobs.clone();
+    let cancel_tokens = state.cancel_tokens.clone();
+    let spawned = job.clone();
+    tauri::async_runtime::spawn(async move {
+        run_job(
+            app,
+            jobs,
+            cancel_tokens,
+            spawned,
+            source,
+            binary,
+            args,
+            output,
+            cancel,
+        )
+        .await;
+    });
+    Ok(job)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_job<R: Runtime>(
+    app: AppHandle<R>,
+    jobs: Arc<Mutex<HashMap<String, QuantizeJob>>>,
+    cancel_tokens: Arc<Mutex<HashMap<String, CancellationToken>>>,
+    mut job: QuantizeJob,
+    source: CatalogModel,
+    binary: PathBuf,
+    args: Vec<std::ffi::OsString>,
+    output: PathBuf,
+    cancel: CancellationToken,
+) {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let partial = PathBuf::from(format!("{}{PARTIAL_SUFFIX}", output.display()));
+    let target_dir = output.parent().map(PathBuf::from).unwrap_or_default();
+
+    let result = match fs::create_dir_all(&target_dir) {
+        Ok(()) => {
+            let mut last_percent = None;
+            run_quantize(&binary, &args, &cancel, |done, total| {
+                // Throttle events to whole percent steps
+                let percent = done * 100 / total;
+                if last_percent != Some(percent) {
+                    last_percent = Some(percent);
+                    job.tensors_done = done;
+                    job.tensors_total = total;
+                    // Best effort: a concurrent listing just sees the previous step
+                    if let Ok(mut jobs) = jobs.try_lock() {
+                        jobs.insert(job.job_id.clone(), job.clone());
+                    }
+                    emit_job(&app, &job);
+                }
+            })
+            .await
+        }
+        Err(e) => Err(e.to_string()),
+    };
+
+    let result = result.and_then(|()| {
+        fs::rename(&partial, &output).map_err(|e| e.to_string())?;
+        register_quantized_model(
+            &data_folder,
+            &job.model_id,
+            &source,
+            &job.target_model_id,
+            &output,
+            &job.quant_type,
+        )
+    });
+    match result {
+        Ok(model) => {
+            log::info!("Quantized {} to {}", job.model_id, job.target_model_id);
+            job.status = QuantizeStatus::Completed;
+            job.tensors_done = job.tensors_total;
+            job.output_size = model.size_bytes;
+        }
+        Err(e) => {
+            // Leave nothing half-written behind
+            let _ = fs::remove_dir_all(&target_dir);
+            if cancel.is_cancelled() {
+                job.status = QuantizeStatus::Cancelled;
+            } else {
+                log::error!("Quantizing {} failed: {e}", job.model_id);
+                job.status = QuantizeStatus::Failed;
+                job.error = Some(e);
+            }
+        }
+    }
+
+    cancel_tokens.lock().await.remove(&job.job_id);
+    jobs.lock().await.insert(job.job_id.clone(), job.clone());
+    emit_job(&app, &job);
+}
+
+/// Quantization jobs started in this session, newest first
+#[tauri::command]
+pub async fn list_quantize_jobs(
+    state: State<'_, QuantizeState>,
+) -> Result<Vec<QuantizeJob>, String> {
+    let mut jobs: Vec<QuantizeJob> = state.jobs.lock().await.values().cloned().collect();
+    jobs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+    Ok(jobs)
+}
+
+#[tauri::command]
+pub async fn cancel_quantize_job(
+    state: State<'_, QuantizeState>,
+    job_id: String,
+) -> Result<(), String> {
+    match state.cancel_tokens.lock().await.remove(&job_id) {
+        Some(token) => {
+            token.cancel();
+            Ok(())
+        }
+        None => Err(format!("No running quantization job: {job_id}")),
+    }
+}
@@ -0,0 +1,35 @@
+// Quantization constants
+pub const QUANTIZE_PROGRESS_EVENT: &str = "model-quantize-progress";
+
+#[cfg(windows)]
+pub const QUANTIZE_BINARY: &str = "llama-quantize.exe";
+#[cfg(not(windows))]
+pub const QUANTIZE_BINARY: &str = "llama-quantize";
+
+/// Resource folder holding the bundled quantize tool and the shared libraries it links to
+pub const BUNDLED_QUANTIZE_DIR: &str = "resources/bin/llama-quantize";
+
+/// Target types offered for conversion with their approximate bits per weight
+pub const QUANT_TYPES: &[(&str, f32)] = &[
+    ("Q2_K", 3.0),
+    ("Q3_K_S", 3.5),
+    ("Q3_K_M", 3.9),
+    ("Q3_K_L", 4.3),
+    ("Q4_0", 4.55),
+    ("Q4_K_S", 4.6),
+    ("Q4_K_M", 4.9),
+    ("Q5_0", 5.55),
+    ("Q5_K_S", 5.55),
+    ("Q5_K_M", 5.7),
+    ("Q6_K", 6.6),
+    ("Q8_0", 8.5),
+];
+
+/// Unquantized weight types, which can be converted without `--allow-requantize`
+pub const UNQUANTIZED_TYPES: &[(&str, f32)] = &[("F32", 32.0), ("F16", 16.0), ("BF16", 16.0)];
+
+/// Free space required on top of the estimated output size
+pub const DISK_HEADROOM_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Suffix of the output file while the conversion is running
+pub const PARTIAL_SUFFIX: &str = ".partial";
@@ -0,0 +1,330 @@
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+
+use jan_utils::progress::{
+    estimate_eta, ProgressOperation, ProgressPayload, ProgressState, ProgressUnit,
+};
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tokio_util::sync::CancellationToken;
+
+use super::constants::{
+    BUNDLED_QUANTIZE_DIR, DISK_HEADROOM_BYTES, QUANTIZE_BINARY, QUANT_TYPES, UNQUANTIZED_TYPES,
+};
+use super::models::{QuantizeJob, QuantizeStatus};
+use crate::core::model_catalog::constants::LLAMACPP_ENGINE;
+use crate::core::model_catalog::helpers::{catalog_path, write_model};
+use crate::core::model_catalog::models::CatalogModel;
+
+#[cfg(target_os = "windows")]
+fn hide_window(cmd: &mut Command) {
+    use std::os::windows::process::CommandExt;
+    cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+}
+
+#[cfg(not(target_os = "windows"))]
+fn hide_window(_cmd: &mut Command) {}
+
+/// Approximate bits per weight of a quantization type, case-insensitive
+pub fn bits_per_weight(quant: &str) -> Option<f32> {
+    QUANT_TYPES
+        .iter()
+        .chain(UNQUANTIZED_TYPES)
+        .find(|(name, _)| name.eq_ignore_ascii_case(quant))
+        .map(|(_, bits)| *bits)
+}
+
+/// Quantization type named in a GGUF file name, e.g. `Qwen3-8B-Q4_K_M.gguf` -> `Q4_K_M`
+pub fn detect_quantization(file_name: &str) -> Option<String> {
+    let stem = file_name
+        .strip_suffix(".gguf")
+        .or_else(|| file_name.strip_suffix(".GGUF"))
+        .unwrap_or(file_name);
+    stem.split(['-', '.'])
+        .rev()
+        .find(|token| bits_per_weight(token).is_some())
+        .map(|token| token.to_ascii_uppercase())
+}
+
+/// Validate the target type against the source, returning its canonical name
+pub fn validate_target(source_quant: Option<&str>, target: &str) -> Result<String, String> {
+    let (name, target_bits) = QUANT_TYPES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(target))
+        .ok_or_else(|| format!("Unsupported quantization type '{target}'"))?;
+    if let Some(source_bits) = source_quant.and_then(bits_per_weight) {
+        if *target_bits >= source_bits {
+            return Err(format!(
+                "{name} is not smaller than the source quantization {}",
+                source_quant.unwrap_or_default()
+            ));
+        }
+    }
+    Ok(name.to_string())
+}
+
+/// Whether llama-quantize needs `--allow-requantize` for this source
+pub fn needs_requantize(source_quant: Option<&str>) -> bool {
+    !source_quant.is_some_and(|quant| {
+        UNQUANTIZED_TYPES
+            .iter()
+            .any(|(name, _)| name.eq_ignore_ascii_case(quant))
+    })
+}
+
+/// Estimated output size. Without a known source type the source size is used, which
+/// bounds the output of a conversion to a smaller type.
+pub fn estimate_output_size(source_size: u64, source_quant: Option<&str>, target: &str) -> u64 {
+    match (
+        source_quant.and_then(bits_per_weight),
+        bits_per_weight(target),
+    ) {
+        (Some(source_bits), Some(target_bits)) => {
+            (source_size as f64 * (target_bits / source_bits) as f64) as u64
+        }
+        _ => source_size,
+    }
+}
+
+/// File name of the output: the source type in the name is swapped for the target type
+pub fn target_file_name(
+    source_file_name: &str,
+    source_quant: Option<&str>,
+    target: &str,
+) -> String {
+    let stem = source_file_name
+        .strip_suffix(".gguf")
+        .unwrap_or(source_file_name);
+    let replaced = source_quant.and_then(|quant| {
+        let start = stem.to_ascii_uppercase().rfind(quant)?;
+        Some(format!(
+            "{}{target}{}",
+            &stem[..start],
+            &stem[start + quant.len()..]
+        ))
+    });
+    format!(
+        "{}.gguf",
+        replaced.unwrap_or_else(|| format!("{stem}-{target}"))
+    )
+}
+
+/// Available bytes on the disk holding `path`
+pub fn available_space(path: &Path) -> Option<u64> {
+    let path = path.canonicalize().ok()?;
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| disk.available_space())
+}
+
+/// Fail early when `dir` cannot hold `needed` bytes plus headroom. Unknown disks pass.
+pub fn check_disk_space(dir: &Path, needed: u64) -> Result<(), String> {
+    let Some(available) = available_space(dir) else {
+        log::warn!("Could not determine free space for {}", dir.display());
+        return Ok(());
+    };
+    let required = needed.saturating_add(DISK_HEADROOM_BYTES);
+    if available < required {
+        return Err(format!(
+            "Not enough disk space: {} MB required, {} MB available",
+            required / (1024 * 1024),
+            available / (1024 * 1024)
+        ));
+    }
+    Ok(())
+}
+
+/// Locate llama-quantize: next to the llama-server of an installed backend first, so it
+/// matches the engine build, then the bundled copy
+pub fn find_quantize_binary<R: Runtime>(app: &AppHandle<R>, data_folder: &Path) -> Option<PathBuf> {
+    let backends_dir = data_folder.join(LLAMACPP_ENGINE).join("backends");
+    let mut versions: Vec<PathBuf> = fs::read_dir(&backends_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .collect();
+    // Prefer the latest version
+    versions.sort_by(|a, b| b.file_name().cmp(&a.file_name()));
+    for version in versions {
+        let mut backends: Vec<PathBuf> = fs::read_dir(&version)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_dir())
+            .collect();
+        backends.sort();
+        for backend in backends {
+            for candidate in [
+                backend.join("build").join("bin").join(QUANTIZE_BINARY),
+                backend.join(QUANTIZE_BINARY),
+            ] {
+                if candidate.is_file() {
+                    return Some(candidate);
+                }
+            }
+        }
+    }
+
+    app.path()
+        .resource_dir()
+        .ok()
+        .map(|dir| dir.join(BUNDLED_QUANTIZE_DIR).join(QUANTIZE_BINARY))
+        .filter(|path| path.is_file())
+}
+
+/// Parse llama-quantize's per-tensor progress, e.g. `[  12/ 291]  blk.0.attn_k.weight - ...`
+pub fn parse_progress(line: &str) -> Option<(u64, u64)> {
+    let inner = line.trim_start().strip_prefix('[')?;
+    let (counts, _) = inner.split_once(']')?;
+    let (done, total) = counts.split_once('/')?;
+    let done = done.trim().parse().ok()?;
+    let total = total.trim().parse().ok()?;
+    (total > 0 && done <= total).then_some((done, total))
+}
+
+/// Command line arguments of llama-quantize
+pub fn quantize_args(
+    source: &Path,
+    output: &Path,
+    quant: &str,
+    allow_requantize: bool,
+    threads: Option<usize>,
+) -> Vec<OsString> {
+    let mut args = Vec::new();
+    if allow_requantize {
+        args.push(OsString::from("--allow-requantize"));
+    }
+    args.push(source.as_os_str().to_os_string());
+    args.push(output.as_os_str().to_os_string());
+    args.push(OsString::from(quant));
+    if let Some(threads) = threads.filter(|t| *t > 0) {
+        args.push(OsString::from(threads.to_string()));
+    }
+    args
+}
+
+/// Run llama-quantize, reporting `(tensors_done, tensors_total)` as tensors are converted
+pub async fn run_quantize(
+    binary: &Path,
+    args: &[OsString],
+    cancel: &CancellationToken,
+    mut on_progress: impl FnMut(u64, u64),
+) -> Result<(), String> {
+    let mut cmd = Command::new(binary);
+    cmd.args(args);
+    // The shared libraries of llama.cpp builds live next to the binary
+    #[cfg(target_os = "linux")]
+    if let Some(dir) = binary.parent() {
+        cmd.env("LD_LIBRARY_PATH", dir);
+    }
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    hide_window(&mut cmd);
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to start llama-quantize: {e}"))?;
+    let mut stdout = child.stdout.take().map(|s| BufReader::new(s).lines());
+    let mut stderr = child.stderr.take().map(|s| BufReader::new(s).lines());
+    // Keep the tail of the output for the error message
+    let mut last_line = String::new();
+
+    while stdout.is_some() || stderr.is_some() {
+        let line = tokio::select! {
+            line = async { stdout.as_mut().unwrap().next_line().await }, if stdout.is_some() => {
+                line.ok().flatten().or_else(|| { stdout = None; None })
+            }
+            line = async { stderr.as_mut().unwrap().next_line().await }, if stderr.is_some() => {
+                line.ok().flatten().or_else(|| { stderr = None; None })
+            }
+            _ = cancel.cancelled() => {
+                let _ = child.kill().await;
+                return Err("Cancelled".to_string());
+            }
+        };
+        if let Some(line) = line {
+            if let Some((done, total)) = parse_progress(&line) {
+                on_progress(done, total);
+            } else if !line.trim().is_empty() {
+                last_line = line;
+            }
+        }
+    }
+
+    let status = tokio::select! {
+        status = child.wait() => status.map_err(|e| e.to_string())?,
+        _ = cancel.cancelled() => {
+            let _ = child.kill().await;
+            return Err("Cancelled".to_string());
+        }
+    };
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("llama-quantize failed ({status}): {last_line}"))
+    }
+}
+
+/// Write the catalog entry of a converted model, derived from its source entry
+pub fn register_quantized_model(
+    data_folder: &Path,
+    source_id: &str,
+    source: &CatalogModel,
+    target_id: &str,
+    output: &Path,
+    quant: &str,
+) -> Result<CatalogModel, String> {
+    let size_bytes = fs::metadata(output).map_err(|e| e.to_string())?.len();
+    let mut extra = source.extra.clone();
+    // Hashes and download metadata describe the source file
+    extra.remove("model_sha256");
+    extra.remove("model_size_bytes");
+    let model = CatalogModel {
+        model_path: catalog_path(data_folder, output),
+        name: Some(format!(
+            "{} ({quant})",
+            source.name.as_deref().unwrap_or(source_id)
+        )),
+        size_bytes,
+        embedding: source.embedding,
+        mmproj_path: source.mmproj_path.clone(),
+        quantization: Some(quant.to_string()),
+        source_model: Some(source_id.to_string()),
+        // Re-quantizing keeps the vocabulary, so the draft pairing still holds
+        speculative: source.speculative.clone(),
+        extra,
+    };
+    write_model(data_folder, LLAMACPP_ENGINE, target_id, &model)?;
+    Ok(model)
+}
+
+/// A job's progress in the shared progress format; `now` is in milliseconds since the epoch
+pub fn job_payload(job: &QuantizeJob, now: i64) -> ProgressPayload {
+    let (operation, name) = (ProgressOperation::Quantize, job.target_model_id.as_str());
+    let state = match job.status {
+        QuantizeStatus::Running => {
+            let elapsed = Duration::from_millis(now.saturating_sub(job.started_at).max(0) as u64);
+            let remaining = job.tensors_total.saturating_sub(job.tensors_done);
+            return ProgressPayload::running(operation, &job.job_id, name, "quantizing")
+                .with_amount(job.tensors_done, job.tensors_total, ProgressUnit::Items)
+                .with_eta(estimate_eta(elapsed, job.tensors_done, remaining));
+        }
+        QuantizeStatus::Completed => ProgressState::Completed,
+        QuantizeStatus::Failed => ProgressState::Failed,
+        QuantizeStatus::Cancelled => ProgressState::Cancelled,
+    };
+    ProgressPayload::finished(operation, &job.job_id, name, state)
+}
@@ -0,0 +1,37 @@
+/*!
+   GGUF Quantization
+
+   Re-quantizes a downloaded GGUF model to a smaller quantization type with llama.cpp's
+   `llama-quantize` tool, taken from the installed llama.cpp backend or, failing that, the copy
+   bundled with the app. Conversions run as background jobs:
+   - the target type must be smaller than the source and enough free disk space must be
+     available for the estimated output before the job starts,
+   - per-tensor progress is parsed from the tool output and emitted as
+     `model-quantize-progress` events; jobs can be cancelled,
+   - the output is written next to a new catalog entry (`<model_id>-<TYPE>`) that records the
+     quantization and the source model, so it shows up like any other local model.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+use models::QuantizeJob;
+
+/// Quantization jobs of this session
+#[derive(Default)]
+pub struct QuantizeState {
+    pub jobs: Arc<Mutex<HashMap<String, QuantizeJob>>>,
+    /// Job id -> cancellation token of the running conversion
+    pub cancel_tokens: Arc<Mutex<HashMap<String, CancellationToken>>>,
+}
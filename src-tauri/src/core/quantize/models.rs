@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuantizeRequest {
+    /// Catalog id of the llama.cpp model to convert
+    pub model_id: String,
+    /// Target type, e.g. `Q4_K_M`
+    pub quant_type: String,
+    /// Catalog id of the new model; defaults to `<model_id>-<quant_type>`
+    #[serde(default)]
+    pub target_model_id: Option<String>,
+    /// Threads used by llama-quantize; it picks a default when absent
+    #[serde(default)]
+    pub threads: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum QuantizeStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// A quantization job, emitted on every progress update
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuantizeJob {
+    pub job_id: String,
+    pub model_id: String,
+    pub target_model_id: String,
+    pub quant_type: String,
+    pub status: QuantizeStatus,
+    pub tensors_done: u64,
+    pub tensors_total: u64,
+    /// Estimated size of the output in bytes; the actual size once completed
+    pub output_size: u64,
+    pub error: Option<String>,
+    /// Milliseconds since the Unix epoch
+    pub started_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QuantizationType {
+    pub name: String,
+    pub bits_per_weight: f32,
+}
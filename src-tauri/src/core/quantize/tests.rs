@@ -0,0 +1,159 @@
+use std::ffi::OsString;
+use std::fs;
+use std::path::Path;
+
+use jan_utils::progress::{ProgressKind, ProgressState};
+
+use super::helpers::{
+    detect_quantization, estimate_output_size, job_payload, needs_requantize, parse_progress,
+    quantize_args, register_quantized_model, target_file_name, validate_target,
+};
+use super::models::{QuantizeJob, QuantizeStatus};
+use crate::core::model_catalog::constants::LLAMACPP_ENGINE;
+use crate::core::model_catalog::helpers::read_model;
+use crate::core::model_catalog::models::CatalogModel;
+
+#[test]
+fn test_detect_quantization() {
+    assert_eq!(
+        detect_quantization("Qwen3-8B-Q4_K_M.gguf").as_deref(),
+        Some("Q4_K_M")
+    );
+    assert_eq!(
+        detect_quantization("llama-3.2-1b-instruct.f16.gguf").as_deref(),
+        Some("F16")
+    );
+    assert_eq!(detect_quantization("model.gguf"), None);
+}
+
+#[test]
+fn test_validate_target() {
+    assert_eq!(validate_target(Some("F16"), "q4_k_m").unwrap(), "Q4_K_M");
+    assert!(validate_target(Some("Q4_K_M"), "Q8_0").is_err());
+    assert!(validate_target(Some("Q4_K_M"), "Q4_K_M").is_err());
+    assert!(validate_target(None, "Q3_K_M").is_ok());
+    assert!(validate_target(None, "Q9_X").is_err());
+}
+
+#[test]
+fn test_requantize_and_size_estimate() {
+    assert!(!needs_requantize(Some("BF16")));
+    assert!(needs_requantize(Some("Q8_0")));
+    assert!(needs_requantize(None));
+    assert_eq!(estimate_output_size(1600, Some("F16"), "Q8_0"), 850);
+    assert_eq!(estimate_output_size(1600, None, "Q4_0"), 1600);
+}
+
+#[test]
+fn test_target_file_name() {
+    assert_eq!(
+        target_file_name("Qwen3-8B-Q8_0.gguf", Some("Q8_0"), "Q4_K_M"),
+        "Qwen3-8B-Q4_K_M.gguf"
+    );
+    assert_eq!(
+        target_file_name("model.gguf", None, "Q4_K_M"),
+        "model-Q4_K_M.gguf"
+    );
+}
+
+#[test]
+fn test_parse_progress() {
+    assert_eq!(
+        parse_progress("[  12/ 291]  blk.0.attn_k.weight - [ 4096,  1024], type = f16"),
+        Some((12, 291))
+    );
+    assert_eq!(
+        parse_progress("llama_model_quantize_impl: model size = 100 MB"),
+        None
+    );
+    assert_eq!(parse_progress("[ 5/ 0]"), None);
+}
+
+#[test]
+fn test_quantize_args() {
+    let args = quantize_args(
+        Path::new("in.gguf"),
+        Path::new("out.gguf"),
+        "Q4_K_M",
+        true,
+        Some(8),
+    );
+    assert_eq!(
+        args,
+        ["--allow-requantize", "in.gguf", "out.gguf", "Q4_K_M", "8"]
+            .iter()
+            .map(OsString::from)
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_register_quantized_model() {
+    let data = std::env::temp_dir().join(format!("jan-quantize-{}", uuid::Uuid::new_v4()));
+    let output = data.join("llamacpp/models/org/repo-Q4_K_M/repo-Q4_K_M.gguf");
+    fs::create_dir_all(output.parent().unwrap()).unwrap();
+    fs::write(&output, b"gguf").unwrap();
+
+    let mut source = CatalogModel {
+        model_path: "llamacpp/models/org/repo/repo-F16.gguf".to_string(),
+        name: Some("repo".to_string()),
+        size_bytes: 16,
+        ..Default::default()
+    };
+    source.extra.insert(
+        "model_sha256".to_string(),
+        serde_yaml::Value::String("abc".to_string()),
+    );
+    register_quantized_model(
+        &data,
+        "org/repo",
+        &source,
+        "org/repo-Q4_K_M",
+        &output,
+        "Q4_K_M",
+    )
+    .unwrap();
+
+    let model = read_model(&data, LLAMACPP_ENGINE, "org/repo-Q4_K_M").unwrap();
+    assert_eq!(
+        model.model_path,
+        "llamacpp/models/org/repo-Q4_K_M/repo-Q4_K_M.gguf"
+    );
+    assert_eq!(model.size_bytes, 4);
+    assert_eq!(model.quantization.as_deref(), Some("Q4_K_M"));
+    assert_eq!(model.source_model.as_deref(), Some("org/repo"));
+    assert_eq!(model.name.as_deref(), Some("repo (Q4_K_M)"));
+    assert!(!model.extra.contains_key("model_sha256"));
+
+    let _ = fs::remove_dir_all(data);
+}
+
+#[test]
+fn test_job_payload() {
+    let mut job = QuantizeJob {
+        job_id: "job-1".to_string(),
+        model_id: "llama".to_string(),
+        target_model_id: "llama-Q4_K_M".to_string(),
+        quant_type: "Q4_K_M".to_string(),
+        status: QuantizeStatus::Running,
+        tensors_done: 0,
+        tensors_total: 0,
+        output_size: 0,
+        error: None,
+        started_at: 1_000,
+    };
+    let payload = job_payload(&job, 2_000);
+    assert_eq!(payload.kind, ProgressKind::Indeterminate);
+    assert_eq!(payload.eta_secs, None);
+
+    job.tensors_done = 100;
+    job.tensors_total = 300;
+    let payload = job_payload(&job, 11_000);
+    assert_eq!(payload.label_key, "progress.quantize.quantizing");
+    assert_eq!(payload.label_params["name"], "llama-Q4_K_M");
+    assert_eq!(payload.current, Some(100));
+    assert_eq!(payload.eta_secs, Some(20));
+
+    job.status = QuantizeStatus::Cancelled;
+    assert_eq!(job_payload(&job, 12_000).state, ProgressState::Cancelled);
+}
@@ -0,0 +1,70 @@
+use tauri::{AppHandle, Runtime, State};
+
+use super::helpers::{write_config, Redactor};
+use super::models::{RedactionConfig, RedactionPreview, RedactionReport, ThreadRedactionOverride};
+use super::RedactionFilter;
+use crate::core::app::commands::get_jan_data_folder_path;
+
+#[tauri::command]
+pub fn get_redaction_config(filter: State<'_, RedactionFilter>) -> RedactionConfig {
+    filter.config()
+}
+
+/// Replace the redaction settings. Custom rules are compiled first, so an invalid pattern
+/// is rejected instead of silently disabling the filters.
+#[tauri::command]
+pub fn set_redaction_config<R: Runtime>(
+    app: AppHandle<R>,
+    filter: State<'_, RedactionFilter>,
+    config: RedactionConfig,
+) -> Result<RedactionConfig, String> {
+    Redactor::new(
+        &RedactionConfig {
+            enabled: true,
+            ..config.clone()
+        },
+        None,
+    )?;
+    write_config(&get_jan_data_folder_path(app), &config)?;
+    filter.set_config(config.clone());
+    Ok(config)
+}
+
+/// Set or, with `None`, clear the override of a thread
+#[tauri::command]
+pub fn set_thread_redaction<R: Runtime>(
+    app: AppHandle<R>,
+    filter: State<'_, RedactionFilter>,
+    thread_id: String,
+    thread_override: Option<ThreadRedactionOverride>,
+) -> Result<RedactionConfig, String> {
+    let mut config = filter.config();
+    match thread_override {
+        Some(thread_override) => {
+            config.thread_overrides.insert(thread_id, thread_override);
+        }
+        None => {
+            config.thread_overrides.remove(&thread_id);
+        }
+    }
+    write_config(&get_jan_data_folder_path(app), &config)?;
+    filter.set_config(config.clone());
+    Ok(config)
+}
+
+/// Show what the filters in effect for `thread_id` would send for `text`
+#[tauri::command]
+pub fn preview_redaction(
+    filter: State<'_, RedactionFilter>,
+    text: String,
+    thread_id: Option<String>,
+) -> RedactionPreview {
+    let mut report = RedactionReport::default();
+    let redacted = filter
+        .redactor(thread_id.as_deref())
+        .and_then(|redactor| redactor.redact_text(&text, &mut report));
+    RedactionPreview {
+        text: redacted.unwrap_or(text),
+        report,
+    }
+}
@@ -0,0 +1,32 @@
+pub const REDACTION_CONFIG_FILE: &str = "redaction.json";
+
+/// Key under a message's `metadata` holding the redaction report of the request behind it
+pub const REDACTION_METADATA_KEY: &str = "redaction";
+
+/// Header API clients can send to the local server to apply a thread's override
+pub const THREAD_ID_HEADER: &str = "x-jan-thread-id";
+
+/// Replacement of custom rules that do not define their own
+pub const DEFAULT_REPLACEMENT: &str = "[REDACTED]";
+
+pub const EMAIL_PATTERN: &str = r"(?i)\b[A-Z0-9._%+-]+@[A-Z0-9.-]+\.[A-Z]{2,}\b";
+/// Well-known key formats: OpenAI/Anthropic, Stripe, AWS, GitHub, Slack, Google, Hugging Face
+pub const API_KEY_PATTERN: &str = r"\b(?:sk-[A-Za-z0-9_-]{20,}|[sr]k_(?:live|test)_[A-Za-z0-9]{16,}|AKIA[0-9A-Z]{16}|gh[pousr]_[A-Za-z0-9]{30,}|github_pat_[A-Za-z0-9_]{30,}|xox[abprs]-[A-Za-z0-9-]{10,}|AIza[0-9A-Za-z_-]{35}|hf_[A-Za-z0-9]{30,})";
+/// 13 to 19 digits, optionally grouped by spaces or dashes; matches are Luhn-checked
+pub const CREDIT_CARD_PATTERN: &str = r"\b(?:\d[ -]?){12,18}\d\b";
+
+/// Body fields that carry identifiers, binary payloads or settings rather than user text
+pub const SKIPPED_KEYS: [&str; 12] = [
+    "model",
+    "role",
+    "type",
+    "id",
+    "tool_call_id",
+    "name",
+    "image_url",
+    "input_audio",
+    "source",
+    "file",
+    "cache_control",
+    "tools",
+];
@@ -0,0 +1,228 @@
+use std::borrow::Cow;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use regex::{Captures, Regex};
+use serde_json::Value;
+use tauri::{AppHandle, Manager, Runtime};
+
+use super::constants::{
+    API_KEY_PATTERN, CREDIT_CARD_PATTERN, DEFAULT_REPLACEMENT, EMAIL_PATTERN,
+    REDACTION_CONFIG_FILE, REDACTION_METADATA_KEY, SKIPPED_KEYS,
+};
+use super::models::{RedactionConfig, RedactionEntity, RedactionReport, ThreadRedactionOverride};
+use super::RedactionFilter;
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::config_store::helpers::write_json;
+use crate::core::inference::models::ModelEndpoint;
+
+pub fn get_config_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(REDACTION_CONFIG_FILE)
+}
+
+pub fn read_config(data_folder: &Path) -> RedactionConfig {
+    fs::read_to_string(get_config_path(data_folder))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+pub fn write_config(data_folder: &Path, config: &RedactionConfig) -> Result<(), String> {
+    write_json(&get_config_path(data_folder), config)
+}
+
+/// Load the stored settings into the managed filter
+pub fn load_redaction_config<R: Runtime>(app: &AppHandle<R>) {
+    let config = read_config(&get_jan_data_folder_path(app.clone()));
+    app.state::<RedactionFilter>().set_config(config);
+}
+
+/// Luhn checksum over the digits of `candidate`, ignoring separators
+pub fn luhn_valid(candidate: &str) -> bool {
+    let digits: Vec<u32> = candidate.chars().filter_map(|c| c.to_digit(10)).collect();
+    if !(13..=19).contains(&digits.len()) {
+        return false;
+    }
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| match (i % 2, d * 2) {
+            (0, _) => d,
+            (_, doubled) if doubled > 9 => doubled - 9,
+            (_, doubled) => doubled,
+        })
+        .sum();
+    sum % 10 == 0
+}
+
+struct CompiledRule {
+    label: String,
+    regex: Regex,
+    replacement: String,
+    luhn: bool,
+}
+
+/// Compiled filters for one request
+pub struct Redactor {
+    rules: Vec<CompiledRule>,
+}
+
+impl Redactor {
+    /// Compile the effective settings, `None` when redaction is off or nothing is selected
+    pub fn new(
+        config: &RedactionConfig,
+        thread: Option<&ThreadRedactionOverride>,
+    ) -> Result<Option<Self>, String> {
+        let enabled = thread.and_then(|t| t.enabled).unwrap_or(config.enabled);
+        if !enabled {
+            return Ok(None);
+        }
+        let entities = thread
+            .and_then(|t| t.entities.as_ref())
+            .unwrap_or(&config.entities);
+
+        let mut rules = Vec::new();
+        // Keys first, so a key containing digit runs is not reported as a card number
+        for entity in [
+            RedactionEntity::ApiKey,
+            RedactionEntity::CreditCard,
+            RedactionEntity::Email,
+        ] {
+            if !entities.contains(&entity) {
+                continue;
+            }
+            let pattern = match entity {
+                RedactionEntity::ApiKey => API_KEY_PATTERN,
+                RedactionEntity::CreditCard => CREDIT_CARD_PATTERN,
+                RedactionEntity::Email => EMAIL_PATTERN,
+            };
+            rules.push(CompiledRule {
+                label: entity.label().to_string(),
+                regex: Regex::new(pattern).map_err(|e| e.to_string())?,
+                replacement: entity.replacement().to_string(),
+                luhn: entity == RedactionEntity::CreditCard,
+            });
+        }
+        for rule in &config.rules {
+            rules.push(CompiledRule {
+                label: rule.name.clone(),
+                regex: Regex::new(&rule.pattern)
+                    .map_err(|e| format!("Invalid pattern of rule '{}': {e}", rule.name))?,
+                replacement: rule
+                    .replacement
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_REPLACEMENT.to_string()),
+                luhn: false,
+            });
+        }
+        Ok((!rules.is_empty()).then_some(Self { rules }))
+    }
+
+    /// Redact `text`, returning the new text only when something matched
+    pub fn redact_text(&self, text: &str, report: &mut RedactionReport) -> Option<String> {
+        let mut current: Option<String> = None;
+        for rule in &self.rules {
+            let source = current.as_deref().unwrap_or(text);
+            let mut count = 0;
+            let replaced = rule.regex.replace_all(source, |caps: &Captures| {
+                let matched = &caps[0];
+                if rule.luhn && !luhn_valid(matched) {
+                    return matched.to_string();
+                }
+                count += 1;
+                rule.replacement.clone()
+            });
+            if count > 0 {
+                report.add(&rule.label, count);
+                current = Some(replaced.into_owned());
+            }
+        }
+        current
+    }
+
+    /// Redact the user-supplied text of a request body in place: message contents, tool call
+    /// arguments, system prompts and completion/embedding inputs. Identifiers and binary
+    /// parts such as images are left alone.
+    pub fn redact_body(&self, body: &mut Value) -> RedactionReport {
+        let mut report = RedactionReport::default();
+        self.redact_value(body, &mut report);
+        report
+    }
+
+    fn redact_value(&self, value: &mut Value, report: &mut RedactionReport) {
+        match value {
+            Value::String(text) => {
+                if let Some(redacted) = self.redact_text(text, report) {
+                    *text = redacted;
+                }
+            }
+            Value::Array(items) => {
+                for item in items {
+                    self.redact_value(item, report);
+                }
+            }
+            Value::Object(map) => {
+                for (key, item) in map.iter_mut() {
+                    if !SKIPPED_KEYS.contains(&key.as_str()) {
+                        self.redact_value(item, report);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Redact a raw JSON request body. Returns `None` when it is not JSON or nothing matched,
+    /// so the original bytes can be forwarded untouched.
+    pub fn redact_json_bytes(&self, bytes: &[u8]) -> Option<(Vec<u8>, RedactionReport)> {
+        let mut body: Value = serde_json::from_slice(bytes).ok()?;
+        let report = self.redact_body(&mut body);
+        if report.is_empty() {
+            return None;
+        }
+        serde_json::to_vec(&body).ok().map(|data| (data, report))
+    }
+}
+
+/// Redactor for a request to `endpoint`. Local engines never see redacted text since
+/// nothing leaves the machine.
+pub fn redactor_for_endpoint<R: Runtime>(
+    app: &AppHandle<R>,
+    endpoint: &ModelEndpoint,
+    thread_id: Option<&str>,
+) -> Option<Redactor> {
+    if endpoint.is_local {
+        return None;
+    }
+    app.try_state::<RedactionFilter>()?.redactor(thread_id)
+}
+
+/// `body` as it may leave the machine for `endpoint`: scrubbed by the filters of `thread_id`
+/// unless the endpoint is local
+pub fn outbound_body<'a>(
+    filter: &RedactionFilter,
+    endpoint: &ModelEndpoint,
+    thread_id: Option<&str>,
+    body: &'a Value,
+) -> Cow<'a, Value> {
+    if endpoint.is_local {
+        return Cow::Borrowed(body);
+    }
+    let Some(redactor) = filter.redactor(thread_id) else {
+        return Cow::Borrowed(body);
+    };
+    let mut body = body.clone();
+    redactor.redact_body(&mut body);
+    Cow::Owned(body)
+}
+
+/// Record the report under the message's metadata
+pub fn attach_report(message: &mut Value, report: &RedactionReport) {
+    if !message.get("metadata").is_some_and(Value::is_object) {
+        message["metadata"] = Value::Object(Default::default());
+    }
+    if let Ok(report) = serde_json::to_value(report) {
+        message["metadata"][REDACTION_METADATA_KEY] = report;
+    }
+}
@@ -0,0 +1,68 @@
+/*!
+   Outbound Redaction Filters
+
+   Requests to remote providers can be scrubbed of sensitive data before they leave the
+   machine. Built-in detectors cover email addresses, well-known API key formats and card
+   numbers (Luhn-checked); users can add their own regex rules. The filters run in the
+   provider path of the local API server, and in `inference::helpers::build_request`, which
+   every request the core sends to a model goes through (agent runs, comparisons, context
+   and thread summaries, the outbox, structured output, reranking and embeddings). Requests
+   served by local engines are never touched.
+
+   Settings live in `redaction.json`, including per-thread overrides that switch redaction
+   on or off or narrow the detectors for one conversation. What was removed is reported by
+   count only; agent runs attach the report to the resulting message under
+   `metadata.redaction`.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
+
+use std::sync::{Arc, OnceLock, RwLock};
+
+use helpers::Redactor;
+use models::RedactionConfig;
+
+// The filter managed by the app, also used by requests built without an app handle
+static SHARED_FILTER: OnceLock<RedactionFilter> = OnceLock::new();
+
+/// Shared redaction settings, cheap to clone
+#[derive(Clone, Default)]
+pub struct RedactionFilter {
+    config: Arc<RwLock<RedactionConfig>>,
+}
+
+impl RedactionFilter {
+    /// The app's filter; every clone sees the settings loaded into the managed state
+    pub fn shared() -> Self {
+        SHARED_FILTER.get_or_init(Self::default).clone()
+    }
+
+    pub fn config(&self) -> RedactionConfig {
+        self.config.read().map(|c| c.clone()).unwrap_or_default()
+    }
+
+    pub fn set_config(&self, config: RedactionConfig) {
+        if let Ok(mut current) = self.config.write() {
+            *current = config;
+        }
+    }
+
+    /// Filters in effect for a request of `thread_id`, `None` when redaction is off
+    pub fn redactor(&self, thread_id: Option<&str>) -> Option<Redactor> {
+        let config = self.config.read().ok()?;
+        let thread = thread_id.and_then(|id| config.thread_overrides.get(id));
+        match Redactor::new(&config, thread) {
+            Ok(redactor) => redactor,
+            Err(e) => {
+                log::error!("Redaction is misconfigured, filters skipped: {e}");
+                None
+            }
+        }
+    }
+}
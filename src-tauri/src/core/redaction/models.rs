@@ -0,0 +1,111 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+/// Built-in kinds of sensitive data
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RedactionEntity {
+    Email,
+    ApiKey,
+    CreditCard,
+}
+
+impl RedactionEntity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Email => "email",
+            Self::ApiKey => "api_key",
+            Self::CreditCard => "credit_card",
+        }
+    }
+
+    pub fn replacement(self) -> &'static str {
+        match self {
+            Self::Email => "[REDACTED_EMAIL]",
+            Self::ApiKey => "[REDACTED_API_KEY]",
+            Self::CreditCard => "[REDACTED_CREDIT_CARD]",
+        }
+    }
+}
+
+fn default_entities() -> Vec<RedactionEntity> {
+    vec![
+        RedactionEntity::Email,
+        RedactionEntity::ApiKey,
+        RedactionEntity::CreditCard,
+    ]
+}
+
+/// User-defined regex rule
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RedactionRule {
+    /// Label used in redaction reports
+    pub name: String,
+    pub pattern: String,
+    /// Literal replacement text; `[REDACTED]` when absent
+    #[serde(default)]
+    pub replacement: Option<String>,
+}
+
+/// Per-thread deviation from the global settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ThreadRedactionOverride {
+    #[serde(default)]
+    pub enabled: Option<bool>,
+    /// Replaces the global entity list for the thread
+    #[serde(default)]
+    pub entities: Option<Vec<RedactionEntity>>,
+}
+
+/// Redaction settings, stored in `redaction.json`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RedactionConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_entities")]
+    pub entities: Vec<RedactionEntity>,
+    #[serde(default)]
+    pub rules: Vec<RedactionRule>,
+    /// Keyed by thread id
+    #[serde(default)]
+    pub thread_overrides: HashMap<String, ThreadRedactionOverride>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            entities: default_entities(),
+            rules: Vec::new(),
+            thread_overrides: HashMap::new(),
+        }
+    }
+}
+
+/// What was removed from a request, by entity or rule name. Never contains the matches.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct RedactionReport {
+    pub total: usize,
+    pub counts: BTreeMap<String, usize>,
+}
+
+impl RedactionReport {
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+
+    pub fn add(&mut self, label: &str, count: usize) {
+        if count > 0 {
+            self.total += count;
+            *self.counts.entry(label.to_string()).or_default() += count;
+        }
+    }
+}
+
+/// Result of previewing the filters on a piece of text
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RedactionPreview {
+    pub text: String,
+    pub report: RedactionReport,
+}
@@ -0,0 +1,193 @@
+use serde_json::{json, Value};
+
+use super::helpers::{attach_report, luhn_valid, outbound_body, Redactor};
+use super::models::{
+    RedactionConfig, RedactionEntity, RedactionReport, RedactionRule, ThreadRedactionOverride,
+};
+use super::RedactionFilter;
+use crate::core::embeddings::helpers::session_endpoint;
+
+fn enabled_config() -> RedactionConfig {
+    RedactionConfig {
+        enabled: true,
+        ..Default::default()
+    }
+}
+
+fn redactor(config: &RedactionConfig) -> Redactor {
+    Redactor::new(config, None).unwrap().unwrap()
+}
+
+#[test]
+fn test_luhn() {
+    assert!(luhn_valid("4111 1111 1111 1111"));
+    assert!(luhn_valid("5500-0000-0000-0004"));
+    assert!(!luhn_valid("4111 1111 1111 1112"));
+    assert!(!luhn_valid("1234"));
+}
+
+#[test]
+fn test_redact_builtin_entities() {
+    let redactor = redactor(&enabled_config());
+    let mut report = RedactionReport::default();
+    let text = "Mail jane.doe@example.com, key sk-proj-abcdefghijklmnopqrstuvwx, \
+                card 4111 1111 1111 1111, order 1234 5678 9012 3456";
+    let redacted = redactor.redact_text(text, &mut report).unwrap();
+    assert_eq!(
+        redacted,
+        "Mail [REDACTED_EMAIL], key [REDACTED_API_KEY], \
+         card [REDACTED_CREDIT_CARD], order 1234 5678 9012 3456"
+    );
+    assert_eq!(report.total, 3);
+    assert_eq!(report.counts.get("email"), Some(&1));
+    assert_eq!(report.counts.get("credit_card"), Some(&1));
+
+    let mut report = RedactionReport::default();
+    assert_eq!(redactor.redact_text("nothing to see", &mut report), None);
+    assert!(report.is_empty());
+}
+
+#[test]
+fn test_custom_rules_and_invalid_patterns() {
+    let mut config = enabled_config();
+    config.entities.clear();
+    config.rules.push(RedactionRule {
+        name: "ticket".to_string(),
+        pattern: r"ACME-\d+".to_string(),
+        replacement: Some("<ticket>".to_string()),
+    });
+    let mut report = RedactionReport::default();
+    assert_eq!(
+        redactor(&config)
+            .redact_text("See ACME-42 and ACME-7 (me@acme.io)", &mut report)
+            .as_deref(),
+        Some("See <ticket> and <ticket> (me@acme.io)")
+    );
+    assert_eq!(report.counts.get("ticket"), Some(&2));
+
+    config.rules[0].pattern = "(".to_string();
+    assert!(Redactor::new(&config, None).is_err());
+}
+
+#[test]
+fn test_thread_override() {
+    let config = RedactionConfig::default();
+    assert!(Redactor::new(&config, None).unwrap().is_none());
+
+    let thread = ThreadRedactionOverride {
+        enabled: Some(true),
+        entities: Some(vec![RedactionEntity::Email]),
+    };
+    let redactor = Redactor::new(&config, Some(&thread)).unwrap().unwrap();
+    let mut report = RedactionReport::default();
+    assert_eq!(
+        redactor
+            .redact_text("a@b.io 4111 1111 1111 1111", &mut report)
+            .as_deref(),
+        Some("[REDACTED_EMAIL] 4111 1111 1111 1111")
+    );
+
+    let opted_out = ThreadRedactionOverride {
+        enabled: Some(false),
+        entities: None,
+    };
+    assert!(Redactor::new(&enabled_config(), Some(&opted_out))
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn test_redact_streaming_chat_body() {
+    let body = json!({
+        "model": "gpt-4o",
+        "stream": true,
+        "stream_options": {"include_usage": true},
+        "messages": [
+            {"role": "system", "content": "Reply to ops@example.com"},
+            {"role": "user", "content": [
+                {"type": "text", "text": "My card is 5500-0000-0000-0004"},
+                {"type": "image_url", "image_url": {"url": "data:image/png;base64,a@b.io"}}
+            ]},
+            {"role": "assistant", "tool_calls": [{
+                "id": "call_1",
+                "type": "function",
+                "function": {"name": "send", "arguments": "{\"to\":\"x@y.org\"}"}
+            }]}
+        ]
+    });
+    let bytes = serde_json::to_vec(&body).unwrap();
+    let (redacted, report) = redactor(&enabled_config())
+        .redact_json_bytes(&bytes)
+        .unwrap();
+    let redacted: Value = serde_json::from_slice(&redacted).unwrap();
+
+    assert_eq!(report.total, 3);
+    assert_eq!(redacted["stream"], json!(true));
+    assert_eq!(redacted["stream_options"], body["stream_options"]);
+    assert_eq!(
+        redacted["messages"][0]["content"],
+        "Reply to [REDACTED_EMAIL]"
+    );
+    assert_eq!(
+        redacted["messages"][1]["content"][0]["text"],
+        "My card is [REDACTED_CREDIT_CARD]"
+    );
+    // Binary payloads are forwarded as they are
+    assert_eq!(
+        redacted["messages"][1]["content"][1],
+        body["messages"][1]["content"][1]
+    );
+    assert_eq!(
+        redacted["messages"][2]["tool_calls"][0]["function"]["arguments"],
+        "{\"to\":\"[REDACTED_EMAIL]\"}"
+    );
+}
+
+#[test]
+fn test_redact_anthropic_body_and_passthrough() {
+    let redactor = redactor(&enabled_config());
+    let mut body = json!({
+        "model": "claude-sonnet",
+        "stream": true,
+        "system": [{"type": "text", "text": "Owner: boss@corp.com"}],
+        "messages": [{"role": "user", "content": "hi"}]
+    });
+    let report = redactor.redact_body(&mut body);
+    assert_eq!(report.counts.get("email"), Some(&1));
+    assert_eq!(body["system"][0]["text"], "Owner: [REDACTED_EMAIL]");
+
+    // Clean and non-JSON bodies are left for the caller to forward unchanged
+    assert!(redactor.redact_json_bytes(b"{\"messages\":[]}").is_none());
+    assert!(redactor.redact_json_bytes(b"data: a@b.io").is_none());
+}
+
+#[test]
+fn test_attach_report() {
+    let mut report = RedactionReport::default();
+    report.add("email", 2);
+    let mut message = json!({"role": "assistant", "content": "ok"});
+    attach_report(&mut message, &report);
+    assert_eq!(
+        message["metadata"]["redaction"],
+        json!({"total": 2, "counts": {"email": 2}})
+    );
+}
+
+#[test]
+fn test_outbound_body() {
+    let filter = RedactionFilter::default();
+    filter.set_config(enabled_config());
+    let body = json!({"messages": [{"role": "user", "content": "mail me at a@example.com"}]});
+
+    let mut endpoint = session_endpoint("qwen3-8b", 3456, "key");
+    // Nothing is scrubbed for a local engine
+    assert_eq!(*outbound_body(&filter, &endpoint, None, &body), body);
+
+    endpoint.is_local = false;
+    let sent = outbound_body(&filter, &endpoint, None, &body);
+    let content = sent["messages"][0]["content"].as_str().unwrap();
+    assert!(!content.contains("a@example.com"));
+
+    filter.set_config(RedactionConfig::default());
+    assert_eq!(*outbound_body(&filter, &endpoint, None, &body), body);
+}
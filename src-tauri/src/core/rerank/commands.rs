@@ -0,0 +1,52 @@
+use tauri::{AppHandle, Runtime};
+
+use super::helpers::{config_for, rerank, update_store, validate_config};
+use super::metrics::latency_stats;
+use super::models::{RerankCandidate, RerankConfig, RerankLatencyStats, RerankResult};
+use crate::core::app::commands::get_jan_data_folder_path;
+
+#[tauri::command]
+pub async fn get_rerank_config<R: Runtime>(
+    app_handle: AppHandle<R>,
+    knowledge_base: String,
+) -> Result<RerankConfig, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    Ok(config_for(&data_folder, &knowledge_base))
+}
+
+#[tauri::command]
+pub async fn set_rerank_config<R: Runtime>(
+    app_handle: AppHandle<R>,
+    knowledge_base: String,
+    config: RerankConfig,
+) -> Result<RerankConfig, String> {
+    validate_config(&config)?;
+    let data_folder = get_jan_data_folder_path(app_handle);
+    update_store(&data_folder, |store| {
+        if config == RerankConfig::default() {
+            store.knowledge_bases.remove(&knowledge_base);
+        } else {
+            store
+                .knowledge_bases
+                .insert(knowledge_base.clone(), config.clone());
+        }
+    })?;
+    Ok(config)
+}
+
+/// Rerank vector-search candidates before they are assembled into the prompt
+#[tauri::command]
+pub async fn rerank_candidates<R: Runtime>(
+    app_handle: AppHandle<R>,
+    knowledge_base: String,
+    query: String,
+    candidates: Vec<RerankCandidate>,
+) -> Result<RerankResult, String> {
+    rerank(&app_handle, &knowledge_base, &query, candidates).await
+}
+
+/// Reranking latency and failures per knowledge base since the app started
+#[tauri::command]
+pub async fn get_rerank_metrics() -> Result<Vec<RerankLatencyStats>, String> {
+    Ok(latency_stats())
+}
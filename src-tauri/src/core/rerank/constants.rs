@@ -0,0 +1,11 @@
+// Rerank constants
+pub const RERANK_FILE: &str = "rerank.json";
+/// Candidates kept after reranking when the knowledge base doesn't choose
+pub const DEFAULT_TOP_N: usize = 8;
+/// Candidates sent to the reranker when the knowledge base doesn't choose
+pub const DEFAULT_MAX_CANDIDATES: usize = 50;
+pub const MAX_CANDIDATES_LIMIT: usize = 500;
+/// Timeout of one rerank request, in seconds
+pub const RERANK_TIMEOUT_SECS: u64 = 60;
+/// Latest latencies kept per knowledge base for the percentiles
+pub const LATENCY_WINDOW: usize = 200;
@@ -0,0 +1,279 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_llamacpp::state::LlamacppState;
+
+use super::constants::*;
+use super::metrics;
+use super::models::{
+    RankedCandidate, RerankBackend, RerankCandidate, RerankConfig, RerankResult, RerankStore,
+};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::config_store::helpers::config_store;
+use crate::core::embeddings::helpers::session_endpoint;
+use crate::core::inference::helpers::{build_request, resolve_model_endpoint};
+use crate::core::inference::models::ModelEndpoint;
+use crate::core::inference::retry::{policy_client, send_with_retry};
+
+pub fn get_rerank_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(RERANK_FILE)
+}
+
+/// Contents of `rerank.json`, empty when missing or unreadable
+pub fn read_store(data_folder: &Path) -> RerankStore {
+    let Ok(data) = config_store().read(&get_rerank_path(data_folder)) else {
+        return RerankStore::default();
+    };
+    serde_json::from_str(&data).unwrap_or_else(|e| {
+        log::warn!("Ignoring unreadable {RERANK_FILE}: {e}");
+        RerankStore::default()
+    })
+}
+
+/// Read-modify-write `rerank.json` under the config store's file lock
+pub fn update_store(
+    data_folder: &Path,
+    change: impl FnOnce(&mut RerankStore),
+) -> Result<(), String> {
+    config_store().update(&get_rerank_path(data_folder), |raw| {
+        let mut store: RerankStore = raw
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or_default();
+        change(&mut store);
+        serde_json::to_string_pretty(&store).map_err(|e| e.to_string())
+    })
+}
+
+/// Settings of a knowledge base; reranking is off for knowledge bases without any
+pub fn config_for(data_folder: &Path, knowledge_base: &str) -> RerankConfig {
+    read_store(data_folder)
+        .knowledge_bases
+        .remove(knowledge_base)
+        .unwrap_or_default()
+}
+
+pub fn validate_config(config: &RerankConfig) -> Result<(), String> {
+    if config.enabled && config.model.trim().is_empty() {
+        return Err("A rerank model is required when reranking is enabled".to_string());
+    }
+    if config.top_n == Some(0) {
+        return Err("top_n must be greater than 0".to_string());
+    }
+    if let Some(max) = config.max_candidates {
+        if max == 0 || max > MAX_CANDIDATES_LIMIT {
+            return Err(format!(
+                "max_candidates must be between 1 and {MAX_CANDIDATES_LIMIT}"
+            ));
+        }
+    }
+    if config.min_score.is_some_and(|s| !s.is_finite()) {
+        return Err("min_score must be a finite number".to_string());
+    }
+    Ok(())
+}
+
+/// Request body shared by llama.cpp, Cohere, Jina and Voyage style rerank APIs
+pub fn build_rerank_body(model: &str, query: &str, documents: &[&str], top_n: usize) -> Value {
+    json!({
+        "model": model,
+        "query": query,
+        "documents": documents,
+        "top_n": top_n.min(documents.len()),
+        "return_documents": false,
+    })
+}
+
+/// `(document index, score)` pairs of a rerank response. Accepts `results` (Cohere, Jina,
+/// llama.cpp) or `data` (Voyage) entries scored by `relevance_score` or `score`.
+pub fn parse_rerank_response(
+    response: &Value,
+    documents: usize,
+) -> Result<Vec<(usize, f32)>, String> {
+    let results = response
+        .get("results")
+        .or_else(|| response.get("data"))
+        .and_then(|r| r.as_array())
+        .ok_or_else(|| "Rerank response has no results".to_string())?;
+    results
+        .iter()
+        .map(|result| {
+            let index = result
+                .get("index")
+                .and_then(|i| i.as_u64())
+                .map(|i| i as usize)
+                .filter(|i| *i < documents)
+                .ok_or_else(|| "Rerank result has a missing or invalid index".to_string())?;
+            let score = result
+                .get("relevance_score")
+                .or_else(|| result.get("score"))
+                .and_then(|s| s.as_f64())
+                .ok_or_else(|| format!("Rerank result {index} has no score"))?;
+            Ok((index, score as f32))
+        })
+        .collect()
+}
+
+/// Order candidates by reranker score, best first, keeping at most `top_n` that score at
+/// least `min_score`. Candidates the reranker didn't score are dropped.
+pub fn rank_candidates(
+    candidates: Vec<RerankCandidate>,
+    scores: &[(usize, f32)],
+    top_n: usize,
+    min_score: Option<f32>,
+) -> Vec<RankedCandidate> {
+    let mut scored: Vec<(usize, f32)> = scores
+        .iter()
+        .copied()
+        .filter(|(_, score)| !min_score.is_some_and(|min| *score < min))
+        .collect();
+    // Ties keep their vector-search order
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+    let mut slots: Vec<Option<RerankCandidate>> = candidates.into_iter().map(Some).collect();
+    scored
+        .into_iter()
+        .filter_map(|(index, score)| {
+            slots[index].take().map(|candidate| RankedCandidate {
+                candidate,
+                relevance_score: Some(score),
+                original_rank: index,
+            })
+        })
+        .take(top_n)
+        .collect()
+}
+
+/// Candidates in vector-search order, for when reranking is off or failed
+pub fn passthrough(candidates: Vec<RerankCandidate>, top_n: Option<usize>) -> Vec<RankedCandidate> {
+    candidates
+        .into_iter()
+        .take(top_n.unwrap_or(usize::MAX))
+        .enumerate()
+        .map(|(original_rank, candidate)| RankedCandidate {
+            candidate,
+            relevance_score: None,
+            original_rank,
+        })
+        .collect()
+}
+
+/// Endpoint serving the rerank model of `config`
+pub async fn rerank_endpoint<R: Runtime>(
+    app: &AppHandle<R>,
+    config: &RerankConfig,
+) -> Result<ModelEndpoint, String> {
+    match config.backend {
+        RerankBackend::Local => {
+            let state = app
+                .try_state::<LlamacppState>()
+                .ok_or_else(|| "The llama.cpp engine is not available".to_string())?;
+            let sessions = state.llama_server_process.lock().await;
+            let session = sessions
+                .values()
+                .find(|s| s.info.model_id == config.model)
+                .ok_or_else(|| format!("Rerank model '{}' is not loaded", config.model))?;
+            if !session.info.is_embedding {
+                return Err(format!(
+                    "Model '{}' must be loaded in embedding mode with reranking enabled",
+                    config.model
+                ));
+            }
+            Ok(session_endpoint(
+                &config.model,
+                session.info.port,
+                &session.info.api_key,
+            ))
+        }
+        RerankBackend::Provider => {
+            let endpoint = resolve_model_endpoint(app, &config.model).await?;
+            if endpoint.is_local {
+                return Err(format!(
+                    "Model '{}' is not served by a remote provider",
+                    config.model
+                ));
+            }
+            Ok(endpoint)
+        }
+    }
+}
+
+async fn request_scores(
+    endpoint: &ModelEndpoint,
+    query: &str,
+    candidates: &[RerankCandidate],
+    top_n: usize,
+) -> Result<Vec<(usize, f32)>, String> {
+    let documents: Vec<&str> = candidates.iter().map(|c| c.text.as_str()).collect();
+    let body = build_rerank_body(&endpoint.model_id, query, &documents, top_n);
+    let client = policy_client(&endpoint.policy, Duration::from_secs(RERANK_TIMEOUT_SECS))?;
+    let response = send_with_retry(
+        &endpoint.policy,
+        build_request(&client, endpoint, "/rerank", None, &body),
+    )
+    .await
+    .map_err(|e| format!("Rerank request failed: {e}"))?;
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!(
+            "Rerank request failed with status {status}: {text}"
+        ));
+    }
+    let response: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid rerank response: {e}"))?;
+    parse_rerank_response(&response, documents.len())
+}
+
+/// Rerank vector-search candidates of `knowledge_base` against `query` as configured for
+/// it. Returns the candidates unchanged when reranking is off, and in vector-search order
+/// cut to `top_n` when the reranker fails.
+pub async fn rerank<R: Runtime>(
+    app: &AppHandle<R>,
+    knowledge_base: &str,
+    query: &str,
+    mut candidates: Vec<RerankCandidate>,
+) -> Result<RerankResult, String> {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let config = config_for(&data_folder, knowledge_base);
+    if !config.enabled || candidates.is_empty() {
+        return Ok(RerankResult {
+            candidates: passthrough(candidates, None),
+            reranked: false,
+            latency_ms: 0,
+            error: None,
+        });
+    }
+
+    let top_n = config.top_n.unwrap_or(DEFAULT_TOP_N);
+    candidates.truncate(config.max_candidates.unwrap_or(DEFAULT_MAX_CANDIDATES));
+    let started = Instant::now();
+    let scores = match rerank_endpoint(app, &config).await {
+        Ok(endpoint) => request_scores(&endpoint, query, &candidates, top_n).await,
+        Err(e) => Err(e),
+    };
+    let latency_ms = started.elapsed().as_millis() as u64;
+    match scores {
+        Ok(scores) => {
+            metrics::record_success(knowledge_base, latency_ms);
+            Ok(RerankResult {
+                candidates: rank_candidates(candidates, &scores, top_n, config.min_score),
+                reranked: true,
+                latency_ms,
+                error: None,
+            })
+        }
+        Err(e) => {
+            metrics::record_failure(knowledge_base);
+            log::warn!("Reranking for knowledge base '{knowledge_base}' failed: {e}");
+            Ok(RerankResult {
+                candidates: passthrough(candidates, Some(top_n)),
+                reranked: false,
+                latency_ms,
+                error: Some(e),
+            })
+        }
+    }
+}
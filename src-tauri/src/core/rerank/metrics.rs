@@ -0,0 +1,96 @@
+//! Reranking latency per knowledge base, kept in memory since the app started.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+
+use super::constants::LATENCY_WINDOW;
+use super::models::RerankLatencyStats;
+
+#[derive(Debug, Default)]
+struct LatencyWindow {
+    samples: VecDeque<u64>,
+    requests: u64,
+    failures: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct RerankMetrics {
+    knowledge_bases: BTreeMap<String, LatencyWindow>,
+}
+
+static RERANK_METRICS: OnceLock<Mutex<RerankMetrics>> = OnceLock::new();
+
+fn with_metrics<T>(f: impl FnOnce(&mut RerankMetrics) -> T) -> T {
+    let mut metrics = RERANK_METRICS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    f(&mut metrics)
+}
+
+/// Nearest-rank percentile of sorted samples
+fn percentile(sorted: &[u64], percent: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (sorted.len() * percent).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+impl RerankMetrics {
+    pub fn record_success(&mut self, knowledge_base: &str, latency_ms: u64) {
+        let window = self
+            .knowledge_bases
+            .entry(knowledge_base.to_string())
+            .or_default();
+        window.requests += 1;
+        if window.samples.len() == LATENCY_WINDOW {
+            window.samples.pop_front();
+        }
+        window.samples.push_back(latency_ms);
+    }
+
+    pub fn record_failure(&mut self, knowledge_base: &str) {
+        let window = self
+            .knowledge_bases
+            .entry(knowledge_base.to_string())
+            .or_default();
+        window.requests += 1;
+        window.failures += 1;
+    }
+
+    pub fn stats(&self) -> Vec<RerankLatencyStats> {
+        self.knowledge_bases
+            .iter()
+            .map(|(name, window)| {
+                let mut sorted: Vec<u64> = window.samples.iter().copied().collect();
+                sorted.sort_unstable();
+                let mean_ms = match sorted.len() as u64 {
+                    0 => 0,
+                    n => sorted.iter().sum::<u64>() / n,
+                };
+                RerankLatencyStats {
+                    knowledge_base: name.clone(),
+                    requests: window.requests,
+                    failures: window.failures,
+                    mean_ms,
+                    p50_ms: percentile(&sorted, 50),
+                    p95_ms: percentile(&sorted, 95),
+                    max_ms: sorted.last().copied().unwrap_or_default(),
+                }
+            })
+            .collect()
+    }
+}
+
+pub fn record_success(knowledge_base: &str, latency_ms: u64) {
+    with_metrics(|metrics| metrics.record_success(knowledge_base, latency_ms));
+}
+
+pub fn record_failure(knowledge_base: &str) {
+    with_metrics(|metrics| metrics.record_failure(knowledge_base));
+}
+
+pub fn latency_stats() -> Vec<RerankLatencyStats> {
+    with_metrics(|metrics| metrics.stats())
+}
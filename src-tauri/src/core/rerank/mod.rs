@@ -0,0 +1,26 @@
+/*!
+   Reranking for Retrieval
+
+   An optional stage between vector search and context assembly. Vector search returns more
+   candidates than fit the prompt; a reranker scores each candidate against the query and
+   only the best ones are kept.
+
+   Reranking is configured per knowledge base (a vector-db collection) in `rerank.json`:
+   - `local` runs a cross-encoder loaded in the llama.cpp engine with `reranking` enabled,
+   - `provider` calls the `/rerank` API of a configured remote provider (Cohere, Jina,
+     Voyage and compatible services).
+
+   Both backends take the same `{model, query, documents, top_n}` request. When reranking
+   fails, the candidates are returned in vector-search order so retrieval still works.
+   Latency and failures are recorded per knowledge base so users can judge whether the
+   quality gain is worth the extra time.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod metrics;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,80 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RerankBackend {
+    /// Cross-encoder loaded in the local llama.cpp engine
+    #[default]
+    Local,
+    /// `/rerank` API of a remote provider
+    Provider,
+}
+
+/// Reranking settings of one knowledge base
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RerankConfig {
+    pub enabled: bool,
+    pub backend: RerankBackend,
+    pub model: String,
+    /// Candidates kept after reranking
+    pub top_n: Option<usize>,
+    /// Candidates sent to the reranker, in vector-search order
+    pub max_candidates: Option<usize>,
+    /// Drop candidates the reranker scores below this
+    pub min_score: Option<f32>,
+}
+
+/// Contents of `rerank.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RerankStore {
+    #[serde(default)]
+    pub knowledge_bases: BTreeMap<String, RerankConfig>,
+}
+
+/// A vector-search result. Fields other than `text` and `score` are passed through.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RerankCandidate {
+    pub text: String,
+    /// Vector-search score
+    #[serde(default)]
+    pub score: Option<f32>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RankedCandidate {
+    #[serde(flatten)]
+    pub candidate: RerankCandidate,
+    /// Reranker score; absent when the candidates were not reranked
+    pub relevance_score: Option<f32>,
+    /// Position in the vector-search results
+    pub original_rank: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RerankResult {
+    pub candidates: Vec<RankedCandidate>,
+    /// Whether the reranker ran; false when disabled or failed
+    pub reranked: bool,
+    pub latency_ms: u64,
+    /// Why reranking was skipped after a failure
+    pub error: Option<String>,
+}
+
+/// Reranking latency of one knowledge base since the app started
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RerankLatencyStats {
+    pub knowledge_base: String,
+    pub requests: u64,
+    pub failures: u64,
+    /// Over the latest successful requests
+    pub mean_ms: u64,
+    pub p50_ms: u64,
+    pub p95_ms: u64,
+    pub max_ms: u64,
+}
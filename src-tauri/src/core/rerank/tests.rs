@@ -0,0 +1,114 @@
+use serde_json::json;
+
+use super::helpers::*;
+use super::metrics::RerankMetrics;
+use super::models::{RerankBackend, RerankCandidate, RerankConfig};
+
+fn candidates(count: usize) -> Vec<RerankCandidate> {
+    (0..count)
+        .map(|i| {
+            serde_json::from_value(json!({
+                "id": format!("chunk-{i}"),
+                "text": format!("text {i}"),
+                "score": 0.9 - i as f32 / 10.0,
+                "file_id": "file-1",
+                "chunk_file_order": i
+            }))
+            .unwrap()
+        })
+        .collect()
+}
+
+#[test]
+fn test_validate_config() {
+    assert!(validate_config(&RerankConfig::default()).is_ok());
+    let config = RerankConfig {
+        enabled: true,
+        backend: RerankBackend::Provider,
+        model: "rerank-v3.5".to_string(),
+        top_n: Some(5),
+        max_candidates: Some(40),
+        min_score: Some(0.1),
+    };
+    assert!(validate_config(&config).is_ok());
+    assert!(validate_config(&RerankConfig {
+        model: " ".to_string(),
+        ..config.clone()
+    })
+    .is_err());
+    assert!(validate_config(&RerankConfig {
+        top_n: Some(0),
+        ..config.clone()
+    })
+    .is_err());
+    assert!(validate_config(&RerankConfig {
+        max_candidates: Some(100_000),
+        ..config
+    })
+    .is_err());
+}
+
+#[test]
+fn test_parse_rerank_response() {
+    let cohere = json!({"results": [
+        {"index": 2, "relevance_score": 0.8},
+        {"index": 0, "relevance_score": 0.3}
+    ]});
+    assert_eq!(
+        parse_rerank_response(&cohere, 3).unwrap(),
+        vec![(2, 0.8), (0, 0.3)]
+    );
+    let voyage = json!({"data": [{"index": 1, "relevance_score": 0.5}]});
+    assert_eq!(parse_rerank_response(&voyage, 2).unwrap(), vec![(1, 0.5)]);
+    let llama = json!({"results": [{"index": 0, "score": -2.5}]});
+    assert_eq!(parse_rerank_response(&llama, 1).unwrap(), vec![(0, -2.5)]);
+
+    assert!(parse_rerank_response(&cohere, 2).is_err());
+    assert!(parse_rerank_response(&json!({"error": "boom"}), 2).is_err());
+
+    let body = build_rerank_body("m", "query", &["a", "b"], 8);
+    assert_eq!(body["top_n"], 2);
+    assert_eq!(body["documents"], json!(["a", "b"]));
+}
+
+#[test]
+fn test_rank_candidates() {
+    let scores = [(0, 0.1), (1, 0.7), (2, 0.7), (3, 0.9)];
+    let ranked = rank_candidates(candidates(5), &scores, 3, None);
+    let order: Vec<usize> = ranked.iter().map(|r| r.original_rank).collect();
+    assert_eq!(order, vec![3, 1, 2]);
+    assert_eq!(ranked[0].relevance_score, Some(0.9));
+    assert_eq!(ranked[0].candidate.extra["id"], "chunk-3");
+
+    let ranked = rank_candidates(candidates(5), &scores, 10, Some(0.5));
+    assert_eq!(ranked.len(), 3);
+
+    // Extra fields survive the round trip to the frontend
+    let value = serde_json::to_value(&ranked[0]).unwrap();
+    assert_eq!(value["file_id"], "file-1");
+    assert_eq!(value["original_rank"], 3);
+    assert_eq!(value["text"], "text 3");
+
+    let kept = passthrough(candidates(5), Some(2));
+    assert_eq!(kept.len(), 2);
+    assert!(kept.iter().all(|c| c.relevance_score.is_none()));
+}
+
+#[test]
+fn test_latency_stats() {
+    let mut metrics = RerankMetrics::default();
+    for ms in 1..=100 {
+        metrics.record_success("docs", ms);
+    }
+    metrics.record_failure("docs");
+    metrics.record_failure("notes");
+
+    let stats = metrics.stats();
+    assert_eq!(stats.len(), 2);
+    let docs = &stats[0];
+    assert_eq!(docs.knowledge_base, "docs");
+    assert_eq!((docs.requests, docs.failures), (101, 1));
+    assert_eq!((docs.p50_ms, docs.p95_ms, docs.max_ms), (50, 95, 100));
+    assert_eq!(docs.mean_ms, 50);
+    assert_eq!(stats[1].mean_ms, 0);
+}
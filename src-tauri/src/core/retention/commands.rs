@@ -0,0 +1,37 @@
+use tauri::{AppHandle, Runtime};
+
+use super::helpers::enforce_retention;
+use super::models::RetentionReport;
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::settings::helpers::retention_settings;
+use crate::core::settings::models::RetentionSettings;
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// Lists what the given retention settings, or those in effect, would delete now. Nothing is
+/// deleted.
+#[tauri::command]
+pub async fn preview_retention<R: Runtime>(
+    app_handle: AppHandle<R>,
+    settings: Option<RetentionSettings>,
+) -> Result<RetentionReport, String> {
+    let settings = settings.unwrap_or_else(|| retention_settings(&app_handle));
+    let data_folder = get_jan_data_folder_path(app_handle);
+    tokio::task::spawn_blocking(move || enforce_retention(&data_folder, &settings, now(), true))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Enforces the retention settings in effect right away
+#[tauri::command]
+pub async fn run_retention<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<RetentionReport, String> {
+    let settings = retention_settings(&app_handle);
+    let data_folder = get_jan_data_folder_path(app_handle);
+    tokio::task::spawn_blocking(move || enforce_retention(&data_folder, &settings, now(), false))
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,13 @@
+// Retention constants
+use std::time::Duration;
+
+/// First cleanup after start, once startup work has settled
+pub const RETENTION_STARTUP_DELAY: Duration = Duration::from_secs(120);
+pub const RETENTION_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Extensions of an interrupted download and of the url saved to resume it
+pub const DOWNLOAD_TEMP_EXTENSION: &str = "tmp";
+pub const DOWNLOAD_URL_EXTENSION: &str = "url";
+
+pub const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+pub const MB: u64 = 1024 * 1024;
@@ -0,0 +1,265 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde_json::Value;
+use tauri::{AppHandle, Runtime};
+
+use super::constants::{
+    DAY_MS, DOWNLOAD_TEMP_EXTENSION, DOWNLOAD_URL_EXTENSION, MB, RETENTION_INTERVAL,
+    RETENTION_STARTUP_DELAY,
+};
+use super::models::{ExpiredFile, ExpiryReason, RetainedFile, RetentionReport, RetentionTarget};
+use crate::core::agent::transcript::get_transcripts_dir;
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::config_store::helpers::config_store;
+use crate::core::mcp::constants::MCP_RECORDING_EXTENSION;
+use crate::core::mcp::recording::{active_recording_paths, recording_path, recordings_dir};
+use crate::core::model_catalog::constants::MODEL_ENGINES;
+use crate::core::model_catalog::helpers::{adapters_root, catalog_path, models_root};
+use crate::core::settings::helpers::retention_settings;
+use crate::core::settings::models::{RetentionPolicy, RetentionSettings};
+use crate::core::storage::constants::LOGS_DIR;
+
+fn modified_ms(path: &Path) -> Option<i64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    let duration = modified.duration_since(SystemTime::UNIX_EPOCH).ok()?;
+    i64::try_from(duration.as_millis()).ok()
+}
+
+fn retained_file(data_folder: &Path, path: &Path) -> Option<RetainedFile> {
+    let meta = fs::metadata(path).ok()?;
+    Some(RetainedFile {
+        path: catalog_path(data_folder, path),
+        size_bytes: meta.len(),
+        modified_at: modified_ms(path)?,
+    })
+}
+
+/// Files directly inside `dir`
+fn files_in(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_file())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Files below `dir` with extension `ext`, at any depth
+fn files_with_extension(dir: &Path, ext: &str) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    let mut stack = vec![dir.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for path in entries.flatten().map(|e| e.path()) {
+            if path.is_dir() {
+                stack.push(path);
+            } else if path.extension().is_some_and(|e| e == ext) {
+                found.push(path);
+            }
+        }
+    }
+    found
+}
+
+/// Sessions named by the `replay` field of configured MCP servers
+fn replayed_sessions(data_folder: &Path) -> Vec<PathBuf> {
+    let Ok(raw) = config_store().read(&data_folder.join("mcp_config.json")) else {
+        return Vec::new();
+    };
+    let config: Value = serde_json::from_str(&raw).unwrap_or_default();
+    config
+        .get("mcpServers")
+        .and_then(Value::as_object)
+        .map(|servers| {
+            servers
+                .values()
+                .filter_map(|server| server.get("replay").and_then(Value::as_str))
+                .map(|session| recording_path(data_folder, session.trim()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Files a policy of `target` applies to. Files in use are left out: the log being
+/// written, and recordings being written or replayed.
+pub fn retention_candidates(data_folder: &Path, target: RetentionTarget) -> Vec<RetainedFile> {
+    let mut paths = match target {
+        RetentionTarget::Logs => files_in(&data_folder.join(LOGS_DIR)),
+        RetentionTarget::ToolAudit => files_in(&get_transcripts_dir(data_folder)),
+        RetentionTarget::DownloadTemp => MODEL_ENGINES
+            .iter()
+            .flat_map(|engine| {
+                [
+                    models_root(data_folder, engine),
+                    adapters_root(data_folder, engine),
+                ]
+            })
+            .flat_map(|root| files_with_extension(&root, DOWNLOAD_TEMP_EXTENSION))
+            .collect(),
+        RetentionTarget::Recordings => {
+            let mut protected = active_recording_paths();
+            protected.extend(replayed_sessions(data_folder));
+            files_in(&recordings_dir(data_folder))
+                .into_iter()
+                .filter(|p| p.extension().is_some_and(|e| e == MCP_RECORDING_EXTENSION))
+                .filter(|p| !protected.contains(p))
+                .collect()
+        }
+    };
+    if target == RetentionTarget::Logs {
+        if let Some(newest) = paths.iter().max_by_key(|p| modified_ms(p)).cloned() {
+            paths.retain(|p| *p != newest);
+        }
+    }
+    paths
+        .iter()
+        .filter_map(|path| retained_file(data_folder, path))
+        .collect()
+}
+
+/// Files `policy` removes: those past the age limit, then the oldest until the rest fits
+/// the size limit
+pub fn expired_files(
+    mut files: Vec<RetainedFile>,
+    policy: &RetentionPolicy,
+    now: i64,
+) -> Vec<(RetainedFile, ExpiryReason)> {
+    files.sort_by(|a, b| a.modified_at.cmp(&b.modified_at).then(a.path.cmp(&b.path)));
+    let cutoff = (policy.max_age_days > 0).then(|| now - policy.max_age_days as i64 * DAY_MS);
+    let (aged, mut kept): (Vec<_>, Vec<_>) = files
+        .into_iter()
+        .partition(|file| cutoff.is_some_and(|cutoff| file.modified_at < cutoff));
+    let mut expired: Vec<_> = aged
+        .into_iter()
+        .map(|file| (file, ExpiryReason::MaxAge))
+        .collect();
+
+    if policy.max_size_mb > 0 {
+        let limit = policy.max_size_mb.saturating_mul(MB);
+        let mut total: u64 = kept.iter().map(|file| file.size_bytes).sum();
+        let mut oldest = 0;
+        while total > limit && oldest < kept.len() {
+            total -= kept[oldest].size_bytes;
+            oldest += 1;
+        }
+        expired.extend(
+            kept.drain(..oldest)
+                .map(|file| (file, ExpiryReason::MaxSize)),
+        );
+    }
+    expired
+}
+
+fn policy_for(settings: &RetentionSettings, target: RetentionTarget) -> RetentionPolicy {
+    match target {
+        RetentionTarget::Logs => settings.logs,
+        RetentionTarget::ToolAudit => settings.tool_audit,
+        // Partial downloads are resumed, so only their age counts
+        RetentionTarget::DownloadTemp => RetentionPolicy {
+            max_size_mb: 0,
+            ..settings.download_temp
+        },
+        RetentionTarget::Recordings => settings.recordings,
+    }
+}
+
+/// What `settings` would delete now
+pub fn plan_retention(
+    data_folder: &Path,
+    settings: &RetentionSettings,
+    now: i64,
+) -> Vec<ExpiredFile> {
+    let targets = [
+        RetentionTarget::Logs,
+        RetentionTarget::ToolAudit,
+        RetentionTarget::DownloadTemp,
+        RetentionTarget::Recordings,
+    ];
+    let mut expired = Vec::new();
+    for target in targets {
+        let policy = policy_for(settings, target);
+        if policy == RetentionPolicy::default() {
+            continue;
+        }
+        let files = retention_candidates(data_folder, target);
+        expired.extend(
+            expired_files(files, &policy, now)
+                .into_iter()
+                .map(|(file, reason)| ExpiredFile {
+                    target,
+                    file,
+                    reason,
+                }),
+        );
+    }
+    expired
+}
+
+/// Delete what `settings` expire, or only report it when `dry_run` is set
+pub fn enforce_retention(
+    data_folder: &Path,
+    settings: &RetentionSettings,
+    now: i64,
+    dry_run: bool,
+) -> RetentionReport {
+    let mut report = RetentionReport {
+        dry_run,
+        ran_at: now,
+        ..Default::default()
+    };
+    for expired in plan_retention(data_folder, settings, now) {
+        if !dry_run {
+            let path = data_folder.join(&expired.file.path);
+            if let Err(e) = fs::remove_file(&path) {
+                report.failed.push(format!("{}: {e}", expired.file.path));
+                continue;
+            }
+            if expired.target == RetentionTarget::DownloadTemp {
+                let _ = fs::remove_file(path.with_extension(DOWNLOAD_URL_EXTENSION));
+            }
+        }
+        report.freed_bytes += expired.file.size_bytes;
+        report.expired.push(expired);
+    }
+    report
+}
+
+/// Enforce the retention settings shortly after start, then every `RETENTION_INTERVAL`
+pub fn start_retention_job<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(RETENTION_STARTUP_DELAY).await;
+        let mut interval = tokio::time::interval(RETENTION_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+            let data_folder = get_jan_data_folder_path(app.clone());
+            let settings = retention_settings(&app);
+            let now = chrono::Utc::now().timestamp_millis();
+            let run = tokio::task::spawn_blocking(move || {
+                enforce_retention(&data_folder, &settings, now, false)
+            });
+            match run.await {
+                Ok(report) => {
+                    if !report.expired.is_empty() {
+                        log::info!(
+                            "Retention removed {} files, {} bytes",
+                            report.expired.len(),
+                            report.freed_bytes
+                        );
+                    }
+                    for failure in &report.failed {
+                        log::warn!("Retention could not remove {failure}");
+                    }
+                }
+                Err(e) => log::warn!("Retention run failed: {e}"),
+            }
+        }
+    });
+}
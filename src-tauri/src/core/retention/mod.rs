@@ -0,0 +1,20 @@
+/*!
+   Log and Artifact Retention
+
+   Applies the `retention` settings to files that pile up while the app runs: logs, agent
+   run transcripts (the tool-call audit trail), partial downloads and MCP session
+   recordings. Each kind has a maximum age and a maximum total size; files past the age
+   are deleted first, then the oldest until the total fits.
+
+   A background job enforces the policies shortly after start and then every few hours.
+   `preview_retention` reports what a policy would delete without deleting anything, so it
+   can be shown before settings are saved.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
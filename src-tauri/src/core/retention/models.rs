@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionTarget {
+    Logs,
+    ToolAudit,
+    DownloadTemp,
+    Recordings,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpiryReason {
+    MaxAge,
+    MaxSize,
+}
+
+/// A file subject to a retention policy
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetainedFile {
+    /// Relative to the data folder
+    pub path: String,
+    pub size_bytes: u64,
+    /// Milliseconds since epoch
+    pub modified_at: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExpiredFile {
+    pub target: RetentionTarget,
+    #[serde(flatten)]
+    pub file: RetainedFile,
+    pub reason: ExpiryReason,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetentionReport {
+    pub dry_run: bool,
+    /// Milliseconds since epoch
+    pub ran_at: i64,
+    pub expired: Vec<ExpiredFile>,
+    pub freed_bytes: u64,
+    /// Files that could not be deleted, with the reason
+    pub failed: Vec<String>,
+}
@@ -0,0 +1,139 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use super::constants::{DAY_MS, MB};
+use super::helpers::{enforce_retention, expired_files, retention_candidates};
+use super::models::{ExpiryReason, RetainedFile, RetentionTarget};
+use crate::core::settings::models::{RetentionPolicy, RetentionSettings};
+
+const NOW: i64 = 1_700_000_000_000;
+
+fn file(path: &str, size_mb: u64, age_days: i64) -> RetainedFile {
+    RetainedFile {
+        path: path.to_string(),
+        size_bytes: size_mb * MB,
+        modified_at: NOW - age_days * DAY_MS,
+    }
+}
+
+/// Write a file last modified `age_days` before `NOW`
+fn write_aged(root: &Path, path: &str, age_days: i64) -> PathBuf {
+    let path = root.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, b"data").unwrap();
+    let modified = SystemTime::UNIX_EPOCH + Duration::from_millis((NOW - age_days * DAY_MS) as u64);
+    fs::File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+    path
+}
+
+fn paths(expired: &[(RetainedFile, ExpiryReason)]) -> Vec<(&str, ExpiryReason)> {
+    expired
+        .iter()
+        .map(|(file, reason)| (file.path.as_str(), *reason))
+        .collect()
+}
+
+#[test]
+fn test_expired_files_by_age_then_size() {
+    let files = vec![
+        file("new", 10, 1),
+        file("old", 10, 40),
+        file("mid", 30, 10),
+        file("recent", 30, 5),
+    ];
+    let policy = RetentionPolicy {
+        max_age_days: 30,
+        max_size_mb: 50,
+    };
+    let expired = expired_files(files.clone(), &policy, NOW);
+    assert_eq!(
+        paths(&expired),
+        [
+            ("old", ExpiryReason::MaxAge),
+            ("mid", ExpiryReason::MaxSize)
+        ]
+    );
+
+    // Limits set to 0 are off
+    assert!(expired_files(files.clone(), &RetentionPolicy::default(), NOW).is_empty());
+    let size_only = RetentionPolicy {
+        max_age_days: 0,
+        max_size_mb: 100,
+    };
+    assert!(expired_files(files, &size_only, NOW).is_empty());
+}
+
+#[test]
+fn test_enforce_retention() {
+    let root = std::env::temp_dir().join(format!("jan-retention-{}", uuid::Uuid::new_v4()));
+    write_aged(&root, "logs/app_old.log", 60);
+    write_aged(&root, "logs/app.log", 90);
+    write_aged(&root, "agent_transcripts/run-1.json", 100);
+    write_aged(&root, "agent_transcripts/run-2.json", 1);
+    let partial = write_aged(&root, "llamacpp/models/org/m/model.gguf.tmp", 20);
+    write_aged(&root, "llamacpp/models/org/m/model.gguf.url", 20);
+    write_aged(&root, "llamacpp/models/org/n/model.gguf.tmp", 1);
+    write_aged(&root, "mcp_recordings/old.jsonl", 100);
+    write_aged(&root, "mcp_recordings/replayed.jsonl", 100);
+    fs::write(
+        root.join("mcp_config.json"),
+        r#"{ "mcpServers": { "files": { "command": "npx", "replay": "replayed.jsonl" } } }"#,
+    )
+    .unwrap();
+    // The newest log is the one being written, even when older than the others by mtime
+    fs::File::options()
+        .write(true)
+        .open(root.join("logs/app.log"))
+        .unwrap()
+        .set_modified(SystemTime::now())
+        .unwrap();
+
+    let logs = retention_candidates(&root, RetentionTarget::Logs);
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].path, "logs/app_old.log");
+
+    let policy = RetentionPolicy {
+        max_age_days: 14,
+        max_size_mb: 0,
+    };
+    let settings = RetentionSettings {
+        logs: policy,
+        tool_audit: policy,
+        download_temp: policy,
+        recordings: policy,
+    };
+    let preview = enforce_retention(&root, &settings, NOW, true);
+    let mut expired: Vec<&str> = preview
+        .expired
+        .iter()
+        .map(|e| e.file.path.as_str())
+        .collect();
+    expired.sort();
+    assert_eq!(
+        expired,
+        [
+            "agent_transcripts/run-1.json",
+            "llamacpp/models/org/m/model.gguf.tmp",
+            "logs/app_old.log",
+            "mcp_recordings/old.jsonl",
+        ]
+    );
+    assert!(partial.exists());
+
+    let report = enforce_retention(&root, &settings, NOW, false);
+    assert!(report.failed.is_empty(), "{:?}", report.failed);
+    assert_eq!(report.freed_bytes, preview.freed_bytes);
+    assert!(!partial.exists());
+    assert!(!root.join("llamacpp/models/org/m/model.gguf.url").exists());
+    assert!(root.join("llamacpp/models/org/n/model.gguf.tmp").exists());
+    assert!(root.join("mcp_recordings/replayed.jsonl").exists());
+    assert!(root.join("agent_transcripts/run-2.json").exists());
+    assert!(root.join("logs/app.log").exists());
+    let _ = fs::remove_dir_all(&root);
+}
@@ -0,0 +1,37 @@
+use tauri::{AppHandle, Runtime};
+
+use super::helpers::{clear_cache, download_runtime, runtime_info};
+use super::models::{RuntimeInfo, RuntimeKind};
+use crate::core::app::commands::get_jan_data_folder_path;
+
+/// Returns where bun and uv come from, their versions and the size of their caches.
+#[tauri::command]
+pub async fn get_runtimes<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<Vec<RuntimeInfo>, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    let mut runtimes = Vec::new();
+    for kind in RuntimeKind::ALL {
+        runtimes.push(runtime_info(&data_folder, kind).await);
+    }
+    Ok(runtimes)
+}
+
+/// Downloads the newest release of a runtime, replacing an earlier download.
+#[tauri::command]
+pub async fn install_runtime<R: Runtime>(
+    app_handle: AppHandle<R>,
+    runtime: RuntimeKind,
+) -> Result<RuntimeInfo, String> {
+    download_runtime(&app_handle, runtime).await?;
+    Ok(runtime_info(&get_jan_data_folder_path(app_handle), runtime).await)
+}
+
+/// Empties the package cache of a runtime. Returns the bytes freed.
+#[tauri::command]
+pub async fn clear_runtime_cache<R: Runtime>(
+    app_handle: AppHandle<R>,
+    runtime: RuntimeKind,
+) -> Result<u64, String> {
+    clear_cache(&get_jan_data_folder_path(app_handle), runtime)
+}
@@ -0,0 +1,18 @@
+// Runtime manager constants
+use std::time::Duration;
+
+pub const RUNTIMES_DIR: &str = "runtimes";
+pub const RUNTIME_MANIFEST_FILE: &str = "runtime.json";
+
+/// Release downloads of the newest bun and uv
+pub const BUN_RELEASE_URL: &str = "https://github.com/oven-sh/bun/releases/latest/download";
+pub const UV_RELEASE_URL: &str = "https://github.com/astral-sh/uv/releases/latest/download";
+/// Checksum list published with every bun release
+pub const BUN_CHECKSUMS_FILE: &str = "SHASUMS256.txt";
+
+/// Package caches below the data folder, shared with the MCP server spawn code
+pub const BUN_CACHE_DIR: &str = ".npx";
+pub const UV_CACHE_DIR: &str = ".uvx";
+
+pub const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(600);
+pub const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
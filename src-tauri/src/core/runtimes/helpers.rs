@@ -0,0 +1,358 @@
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_http::reqwest;
+use tokio::sync::Mutex;
+
+use super::constants::*;
+use super::models::{RuntimeInfo, RuntimeKind, RuntimeManifest, RuntimeSource};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::offline::helpers::check_url;
+
+// Serializes installs, so servers starting together download a runtime only once
+static INSTALL_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+fn install_lock() -> &'static Mutex<()> {
+    INSTALL_LOCK.get_or_init(|| Mutex::new(()))
+}
+
+/// Release archive of `kind` for an OS and architecture as named by `std::env::consts`
+pub fn release_asset(kind: RuntimeKind, os: &str, arch: &str) -> Option<String> {
+    let asset = match (kind, os, arch) {
+        (RuntimeKind::Bun, "macos", "aarch64") => "bun-darwin-aarch64.zip",
+        (RuntimeKind::Bun, "macos", "x86_64") => "bun-darwin-x64.zip",
+        (RuntimeKind::Bun, "linux", "aarch64") => "bun-linux-aarch64.zip",
+        (RuntimeKind::Bun, "linux", "x86_64") => "bun-linux-x64.zip",
+        (RuntimeKind::Bun, "windows", "x86_64") => "bun-windows-x64.zip",
+        (RuntimeKind::Uv, "macos", "aarch64") => "uv-aarch64-apple-darwin.tar.gz",
+        (RuntimeKind::Uv, "macos", "x86_64") => "uv-x86_64-apple-darwin.tar.gz",
+        (RuntimeKind::Uv, "linux", "aarch64") => "uv-aarch64-unknown-linux-gnu.tar.gz",
+        (RuntimeKind::Uv, "linux", "x86_64") => "uv-x86_64-unknown-linux-gnu.tar.gz",
+        (RuntimeKind::Uv, "windows", "x86_64") => "uv-x86_64-pc-windows-msvc.zip",
+        _ => return None,
+    };
+    Some(asset.to_string())
+}
+
+fn release_url(kind: RuntimeKind) -> &'static str {
+    match kind {
+        RuntimeKind::Bun => BUN_RELEASE_URL,
+        RuntimeKind::Uv => UV_RELEASE_URL,
+    }
+}
+
+/// Where the published SHA-256 of `asset` can be read
+pub fn checksum_url(kind: RuntimeKind, asset: &str) -> String {
+    match kind {
+        RuntimeKind::Bun => format!("{BUN_RELEASE_URL}/{BUN_CHECKSUMS_FILE}"),
+        RuntimeKind::Uv => format!("{UV_RELEASE_URL}/{asset}.sha256"),
+    }
+}
+
+/// The hash of `asset` in a checksum file of `<sha256>  <file>` lines (`*<file>` in
+/// binary mode). A file holding only a hash applies to the asset it was published for.
+pub fn parse_checksum(text: &str, asset: &str) -> Option<String> {
+    let is_hash = |token: &str| token.len() == 64 && token.chars().all(|c| c.is_ascii_hexdigit());
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    let single = lines.len() == 1;
+    for line in lines {
+        let mut tokens = line.split_whitespace();
+        let Some(hash) = tokens.next().filter(|token| is_hash(token)) else {
+            continue;
+        };
+        match tokens.next() {
+            Some(file) => {
+                let file = file.trim_start_matches('*');
+                if file == asset || file.ends_with(&format!("/{asset}")) {
+                    return Some(hash.to_ascii_lowercase());
+                }
+            }
+            None if single => return Some(hash.to_ascii_lowercase()),
+            None => {}
+        }
+    }
+    None
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(path).map_err(|e| format!("Failed to open {path:?}: {e}"))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read {path:?}: {e}"))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+fn is_binary_entry(path: &Path, binary: &str) -> bool {
+    path.file_name().is_some_and(|name| name == binary)
+}
+
+/// The bytes of the file named `binary` inside a `.zip` or `.tar.gz` release archive
+pub fn extract_binary(archive: &[u8], asset: &str, binary: &str) -> Result<Vec<u8>, String> {
+    let mut data = Vec::new();
+    if asset.ends_with(".zip") {
+        let mut zip = zip::ZipArchive::new(Cursor::new(archive)).map_err(|e| e.to_string())?;
+        for index in 0..zip.len() {
+            let mut entry = zip.by_index(index).map_err(|e| e.to_string())?;
+            if entry.is_file() && is_binary_entry(Path::new(entry.name()), binary) {
+                entry.read_to_end(&mut data).map_err(|e| e.to_string())?;
+                return Ok(data);
+            }
+        }
+    } else {
+        let decoder = flate2::read::GzDecoder::new(Cursor::new(archive));
+        let mut tar = tar::Archive::new(decoder);
+        for entry in tar.entries().map_err(|e| e.to_string())? {
+            let mut entry = entry.map_err(|e| e.to_string())?;
+            let path = entry.path().map_err(|e| e.to_string())?.into_owned();
+            if entry.header().entry_type().is_file() && is_binary_entry(&path, binary) {
+                entry.read_to_end(&mut data).map_err(|e| e.to_string())?;
+                return Ok(data);
+            }
+        }
+    }
+    Err(format!("{asset} does not contain {binary}"))
+}
+
+/// The version in the output of `<runtime> --version`: `1.1.38` for bun,
+/// `uv 0.5.4 (c62c83c37 2024-11-20)` for uv
+pub fn parse_version(output: &str) -> Option<String> {
+    output
+        .split_whitespace()
+        .find(|token| token.starts_with(|c: char| c.is_ascii_digit()))
+        .map(String::from)
+}
+
+pub fn runtime_dir(data_folder: &Path, kind: RuntimeKind) -> PathBuf {
+    data_folder.join(RUNTIMES_DIR).join(kind.as_str())
+}
+
+/// Package cache of a runtime, passed to it as `BUN_INSTALL` / `UV_CACHE_DIR`
+pub fn runtime_cache_dir(data_folder: &Path, kind: RuntimeKind) -> PathBuf {
+    match kind {
+        RuntimeKind::Bun => data_folder.join(BUN_CACHE_DIR),
+        RuntimeKind::Uv => data_folder.join(UV_CACHE_DIR),
+    }
+}
+
+/// The runtime shipped next to the Jan executable, if there is one
+pub fn bundled_binary(kind: RuntimeKind) -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let path = exe.parent()?.join(kind.binary_name());
+    path.is_file().then_some(path)
+}
+
+pub fn read_manifest(data_folder: &Path, kind: RuntimeKind) -> Option<RuntimeManifest> {
+    let path = runtime_dir(data_folder, kind).join(RUNTIME_MANIFEST_FILE);
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+}
+
+/// The downloaded runtime, when its binary still has the hash recorded at install time
+pub fn verified_binary(data_folder: &Path, kind: RuntimeKind) -> Option<PathBuf> {
+    let manifest = read_manifest(data_folder, kind)?;
+    let path = runtime_dir(data_folder, kind).join(kind.binary_name());
+    if !path.is_file() {
+        return None;
+    }
+    match sha256_file(&path) {
+        Ok(hash) if hash == manifest.binary_sha256 => Some(path),
+        Ok(_) => {
+            log::warn!("Ignoring {path:?}: it changed since it was installed");
+            None
+        }
+        Err(e) => {
+            log::warn!("Ignoring {}: {e}", kind.as_str());
+            None
+        }
+    }
+}
+
+/// The runtime to use: the bundled one, otherwise a verified download
+pub fn locate_runtime(data_folder: &Path, kind: RuntimeKind) -> (RuntimeSource, Option<PathBuf>) {
+    if let Some(path) = bundled_binary(kind) {
+        return (RuntimeSource::Bundled, Some(path));
+    }
+    match verified_binary(data_folder, kind) {
+        Some(path) => (RuntimeSource::Downloaded, Some(path)),
+        None => (RuntimeSource::Missing, None),
+    }
+}
+
+/// Total size of the files below `path`, without following symlinks
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(kind) if kind.is_dir() => dir_size(&entry.path()),
+            Ok(kind) if kind.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+/// Empty a runtime's package cache. Returns the bytes freed.
+pub fn clear_cache(data_folder: &Path, kind: RuntimeKind) -> Result<u64, String> {
+    let dir = runtime_cache_dir(data_folder, kind);
+    if !dir.exists() {
+        return Ok(0);
+    }
+    let freed = dir_size(&dir);
+    fs::remove_dir_all(&dir).map_err(|e| format!("Failed to clear {dir:?}: {e}"))?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to recreate {dir:?}: {e}"))?;
+    Ok(freed)
+}
+
+/// Version reported by a runtime binary
+pub async fn runtime_version(path: &Path) -> Option<String> {
+    let mut cmd = tokio::process::Command::new(path);
+    cmd.arg("--version").kill_on_drop(true);
+    #[cfg(windows)]
+    {
+        cmd.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+    let output = tokio::time::timeout(VERSION_TIMEOUT, cmd.output())
+        .await
+        .ok()?
+        .ok()?;
+    parse_version(&String::from_utf8_lossy(&output.stdout))
+}
+
+pub async fn runtime_info(data_folder: &Path, kind: RuntimeKind) -> RuntimeInfo {
+    let (source, path) = locate_runtime(data_folder, kind);
+    let version = match &path {
+        Some(path) => runtime_version(path).await,
+        None => None,
+    };
+    let cache_dir = runtime_cache_dir(data_folder, kind);
+    RuntimeInfo {
+        runtime: kind,
+        source,
+        path: path.map(|p| p.to_string_lossy().into_owned()),
+        version,
+        cache_bytes: dir_size(&cache_dir),
+        cache_dir: cache_dir.to_string_lossy().into_owned(),
+    }
+}
+
+async fn download<R: Runtime>(
+    app: &AppHandle<R>,
+    client: &reqwest::Client,
+    url: &str,
+) -> Result<Vec<u8>, String> {
+    check_url(app, url)?;
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download {url}: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to download {url}: {}", response.status()));
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to download {url}: {e}"))?;
+    Ok(bytes.to_vec())
+}
+
+/// Download the newest release of a runtime into `runtimes/<runtime>/`, verifying the
+/// archive against the published checksum
+pub async fn download_runtime<R: Runtime>(
+    app: &AppHandle<R>,
+    kind: RuntimeKind,
+) -> Result<PathBuf, String> {
+    let _guard = install_lock().lock().await;
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let asset = release_asset(kind, std::env::consts::OS, std::env::consts::ARCH)
+        .ok_or_else(|| format!("No {} release for this platform", kind.as_str()))?;
+    let client = reqwest::Client::builder()
+        .timeout(DOWNLOAD_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    log::info!("Downloading {} ({asset})", kind.as_str());
+    let checksums = download(app, &client, &checksum_url(kind, &asset)).await?;
+    let expected = parse_checksum(&String::from_utf8_lossy(&checksums), &asset)
+        .ok_or_else(|| format!("No published checksum for {asset}"))?;
+    let archive = download(app, &client, &format!("{}/{asset}", release_url(kind))).await?;
+    let actual = sha256_hex(&archive);
+    if actual != expected {
+        return Err(format!(
+            "Checksum mismatch for {asset}: expected {expected}, got {actual}"
+        ));
+    }
+
+    let binary = extract_binary(&archive, &asset, kind.binary_name())?;
+    let dir = runtime_dir(&data_folder, kind);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {dir:?}: {e}"))?;
+    let path = dir.join(kind.binary_name());
+    let partial = dir.join(format!("{}.partial", kind.binary_name()));
+    fs::write(&partial, &binary).map_err(|e| format!("Failed to write {partial:?}: {e}"))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&partial, fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to make {partial:?} executable: {e}"))?;
+    }
+    fs::rename(&partial, &path).map_err(|e| format!("Failed to install {path:?}: {e}"))?;
+
+    let manifest = RuntimeManifest {
+        asset,
+        archive_sha256: actual,
+        binary_sha256: sha256_hex(&binary),
+        version: runtime_version(&path).await,
+        installed_at: chrono::Utc::now().timestamp_millis(),
+    };
+    let data = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+    fs::write(dir.join(RUNTIME_MANIFEST_FILE), data).map_err(|e| e.to_string())?;
+    log::info!(
+        "Installed {} {} at {path:?}",
+        kind.as_str(),
+        manifest.version.as_deref().unwrap_or("(unknown version)")
+    );
+    Ok(path)
+}
+
+/// The runtime to run a server with, downloading it when it is missing. `None` when it
+/// can't be installed, in which case the system `npx`/`uvx` is used.
+pub async fn ensure_runtime<R: Runtime>(app: &AppHandle<R>, kind: RuntimeKind) -> Option<PathBuf> {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    if let (_, Some(path)) = locate_runtime(&data_folder, kind) {
+        return Some(path);
+    }
+    // Another server may have installed it while this one waited
+    drop(install_lock().lock().await);
+    if let (_, Some(path)) = locate_runtime(&data_folder, kind) {
+        return Some(path);
+    }
+    match download_runtime(app, kind).await {
+        Ok(path) => Some(path),
+        Err(e) => {
+            log::warn!("Could not install {}: {e}", kind.as_str());
+            None
+        }
+    }
+}
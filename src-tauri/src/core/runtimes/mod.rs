@@ -0,0 +1,21 @@
+/*!
+   Bundled Runtimes
+
+   npx- and uvx-based MCP servers run on bun and uv. Both normally ship next to the Jan
+   executable; when one is missing it is downloaded on demand into `runtimes/<runtime>/`:
+   - the release archive is checked against the SHA-256 sums the release publishes
+     (`SHASUMS256.txt` for bun, `<asset>.sha256` for uv) before anything is extracted,
+   - the hash of the extracted binary is kept in `runtimes/<runtime>/runtime.json` and checked
+     again before the binary is used, so a modified or truncated binary is never run,
+   - package caches live in `.npx` (bun) and `.uvx` (uv) under the data folder and can be
+     cleared from the settings.
+   When a runtime can't be found or installed, servers fall back to the system `npx`/`uvx`.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+/// A runtime the MCP servers may need
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuntimeKind {
+    Bun,
+    Uv,
+}
+
+/// Where a runtime binary comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuntimeSource {
+    /// Shipped next to the Jan executable
+    Bundled,
+    /// Downloaded into `runtimes/`
+    Downloaded,
+    Missing,
+}
+
+/// Contents of `runtimes/<runtime>/runtime.json`, written after an install
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuntimeManifest {
+    /// Release archive the binary was extracted from
+    pub asset: String,
+    /// SHA-256 of the release archive, as published with the release
+    pub archive_sha256: String,
+    /// SHA-256 of the extracted binary
+    pub binary_sha256: String,
+    pub version: Option<String>,
+    /// Milliseconds since the Unix epoch
+    pub installed_at: i64,
+}
+
+/// State of a runtime as shown in the settings
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuntimeInfo {
+    pub runtime: RuntimeKind,
+    pub source: RuntimeSource,
+    pub path: Option<String>,
+    pub version: Option<String>,
+    pub cache_dir: String,
+    pub cache_bytes: u64,
+}
+
+impl RuntimeKind {
+    pub const ALL: [RuntimeKind; 2] = [RuntimeKind::Bun, RuntimeKind::Uv];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Bun => "bun",
+            Self::Uv => "uv",
+        }
+    }
+
+    /// File name of the runtime's executable on this platform
+    pub fn binary_name(&self) -> &'static str {
+        match (self, cfg!(windows)) {
+            (Self::Bun, true) => "bun.exe",
+            (Self::Bun, false) => "bun",
+            (Self::Uv, true) => "uv.exe",
+            (Self::Uv, false) => "uv",
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::io::Write;
+
+use super::helpers::*;
+use super::models::RuntimeKind;
+
+#[test]
+fn test_release_assets_and_checksums() {
+    assert_eq!(
+        release_asset(RuntimeKind::Bun, "macos", "aarch64").as_deref(),
+        Some("bun-darwin-aarch64.zip")
+    );
+    assert_eq!(
+        release_asset(RuntimeKind::Uv, "windows", "x86_64").as_deref(),
+        Some("uv-x86_64-pc-windows-msvc.zip")
+    );
+    assert!(release_asset(RuntimeKind::Bun, "freebsd", "x86_64").is_none());
+    assert!(checksum_url(RuntimeKind::Uv, "uv-x.tar.gz").ends_with("/uv-x.tar.gz.sha256"));
+
+    let bun_sums = format!(
+        "{}  bun-darwin-x64.zip\n{}  bun-linux-x64.zip\n",
+        "a".repeat(64),
+        "B".repeat(64)
+    );
+    assert_eq!(
+        parse_checksum(&bun_sums, "bun-linux-x64.zip"),
+        Some("b".repeat(64))
+    );
+    assert_eq!(parse_checksum(&bun_sums, "bun-windows-x64.zip"), None);
+    let uv_sum = format!("{} *uv-x86_64-unknown-linux-gnu.tar.gz\n", "c".repeat(64));
+    assert_eq!(
+        parse_checksum(&uv_sum, "uv-x86_64-unknown-linux-gnu.tar.gz"),
+        Some("c".repeat(64))
+    );
+    assert_eq!(parse_checksum(&"d".repeat(64), "any"), Some("d".repeat(64)));
+    assert_eq!(parse_checksum("not a hash  file", "file"), None);
+
+    assert_eq!(parse_version("1.1.38\n").as_deref(), Some("1.1.38"));
+    assert_eq!(
+        parse_version("uv 0.5.4 (c62c83c37 2024-11-20)").as_deref(),
+        Some("0.5.4")
+    );
+}
+
+#[test]
+fn test_extract_binary_from_archives() {
+    let mut tar_gz = Vec::new();
+    {
+        let encoder = flate2::write::GzEncoder::new(&mut tar_gz, flate2::Compression::fast());
+        let mut builder = tar::Builder::new(encoder);
+        for (path, data) in [
+            ("uv-x/README.md", &b"docs"[..]),
+            ("uv-x/uv", &b"uv-binary"[..]),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o755);
+            header.set_cksum();
+            builder.append_data(&mut header, path, data).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+    assert_eq!(
+        extract_binary(&tar_gz, "uv-x.tar.gz", "uv").unwrap(),
+        b"uv-binary"
+    );
+    assert!(extract_binary(&tar_gz, "uv-x.tar.gz", "uvx").is_err());
+
+    let mut zip_data = Vec::new();
+    {
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(&mut zip_data));
+        let options = zip::write::FileOptions::default();
+        zip.add_directory("bun-linux-x64/", options).unwrap();
+        zip.start_file("bun-linux-x64/bun", options).unwrap();
+        zip.write_all(b"bun-binary").unwrap();
+        zip.finish().unwrap();
+    }
+    assert_eq!(
+        extract_binary(&zip_data, "bun-linux-x64.zip", "bun").unwrap(),
+        b"bun-binary"
+    );
+    assert_eq!(sha256_hex(b"bun-binary").len(), 64);
+}
+
+#[test]
+fn test_cache_size_and_clear() {
+    let data = std::env::temp_dir().join(format!("runtimes-{}", uuid::Uuid::new_v4()));
+    let cache = runtime_cache_dir(&data, RuntimeKind::Uv);
+    std::fs::create_dir_all(cache.join("wheels")).unwrap();
+    std::fs::write(cache.join("wheels/a.whl"), [0u8; 100]).unwrap();
+    std::fs::write(cache.join("index"), [0u8; 20]).unwrap();
+
+    assert_eq!(dir_size(&cache), 120);
+    assert_eq!(clear_cache(&data, RuntimeKind::Uv).unwrap(), 120);
+    assert!(cache.is_dir());
+    assert_eq!(dir_size(&cache), 0);
+    assert_eq!(clear_cache(&data, RuntimeKind::Bun).unwrap(), 0);
+    // A download whose binary no longer matches its manifest is not used
+    assert!(verified_binary(&data, RuntimeKind::Uv).is_none());
+    std::fs::remove_dir_all(&data).unwrap();
+}
@@ -0,0 +1,167 @@
+use std::fs;
+
+use chrono::Local;
+use tauri::{AppHandle, Runtime};
+use uuid::Uuid;
+
+use super::helpers::{
+    execute_scheduled_prompt, get_history_path, next_run_at_ms, read_runs, read_schedules,
+    schedules_lock, update_schedule, validate_schedule, write_schedules,
+};
+use super::models::{ScheduledPrompt, ScheduledPromptInput, ScheduledPromptRun};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::prompts::helpers::{builtin_values, render_template};
+
+fn validate_input(input: &ScheduledPromptInput) -> Result<(), String> {
+    if input.name.trim().is_empty() {
+        return Err("Scheduled prompt name cannot be empty".to_string());
+    }
+    if input.prompt.trim().is_empty() {
+        return Err("Scheduled prompt cannot be empty".to_string());
+    }
+    if input.model.trim().is_empty() {
+        return Err("A model is required to run a scheduled prompt".to_string());
+    }
+    // Only built-in variables can be filled in when nobody is around to provide values
+    render_template(&input.prompt, &Default::default(), &builtin_values())?;
+    validate_schedule(&input.schedule)
+}
+
+/// Lists all scheduled prompts.
+#[tauri::command]
+pub async fn list_scheduled_prompts<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<Vec<ScheduledPrompt>, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    read_schedules(&data_folder)
+}
+
+/// Creates a scheduled prompt and computes its first run time.
+#[tauri::command]
+pub async fn create_scheduled_prompt<R: Runtime>(
+    app_handle: AppHandle<R>,
+    input: ScheduledPromptInput,
+) -> Result<ScheduledPrompt, String> {
+    validate_input(&input)?;
+    let data_folder = get_jan_data_folder_path(app_handle);
+    let now = Local::now();
+    let schedule = ScheduledPrompt {
+        id: Uuid::new_v4().to_string(),
+        name: input.name.trim().to_string(),
+        prompt: input.prompt,
+        model: input.model,
+        assistant_id: input.assistant_id,
+        thread_id: input.thread_id,
+        next_run_at: next_run_at_ms(&input.schedule, now),
+        schedule: input.schedule,
+        enabled: input.enabled.unwrap_or(true),
+        created_at: now.timestamp_millis(),
+        last_run_at: None,
+        last_status: None,
+        last_error: None,
+        consecutive_failures: 0,
+    };
+
+    let _guard = schedules_lock().lock().await;
+    let mut schedules = read_schedules(&data_folder)?;
+    schedules.push(schedule.clone());
+    write_schedules(&data_folder, &schedules)?;
+    Ok(schedule)
+}
+
+/// Updates a scheduled prompt. Changing the schedule recomputes the next run time.
+#[tauri::command]
+pub async fn update_scheduled_prompt<R: Runtime>(
+    app_handle: AppHandle<R>,
+    schedule_id: String,
+    input: ScheduledPromptInput,
+) -> Result<ScheduledPrompt, String> {
+    validate_input(&input)?;
+    let data_folder = get_jan_data_folder_path(app_handle);
+    update_schedule(&data_folder, &schedule_id, move |s| {
+        if s.schedule != input.schedule {
+            s.next_run_at = next_run_at_ms(&input.schedule, Local::now());
+        }
+        s.name = input.name.trim().to_string();
+        s.prompt = input.prompt;
+        s.model = input.model;
+        s.assistant_id = input.assistant_id;
+        s.thread_id = input.thread_id;
+        s.schedule = input.schedule;
+        if let Some(enabled) = input.enabled {
+            s.enabled = enabled;
+        }
+    })
+    .await
+}
+
+/// Enables or disables a scheduled prompt. Re-enabling schedules the next occurrence
+/// from now instead of catching up on missed runs.
+#[tauri::command]
+pub async fn set_scheduled_prompt_enabled<R: Runtime>(
+    app_handle: AppHandle<R>,
+    schedule_id: String,
+    enabled: bool,
+) -> Result<ScheduledPrompt, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    update_schedule(&data_folder, &schedule_id, move |s| {
+        if enabled && !s.enabled {
+            s.next_run_at = next_run_at_ms(&s.schedule, Local::now());
+        }
+        s.enabled = enabled;
+    })
+    .await
+}
+
+/// Deletes a scheduled prompt and its run history. The results thread is kept.
+#[tauri::command]
+pub async fn delete_scheduled_prompt<R: Runtime>(
+    app_handle: AppHandle<R>,
+    schedule_id: String,
+) -> Result<(), String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    {
+        let _guard = schedules_lock().lock().await;
+        let mut schedules = read_schedules(&data_folder)?;
+        let before = schedules.len();
+        schedules.retain(|s| s.id != schedule_id);
+        if schedules.len() == before {
+            return Err(format!("Scheduled prompt {schedule_id} not found"));
+        }
+        write_schedules(&data_folder, &schedules)?;
+    }
+    let history = get_history_path(&data_folder, &schedule_id);
+    if history.exists() {
+        let _ = fs::remove_file(history);
+    }
+    Ok(())
+}
+
+/// Runs a scheduled prompt immediately and returns the recorded run.
+#[tauri::command]
+pub async fn run_scheduled_prompt_now<R: Runtime>(
+    app_handle: AppHandle<R>,
+    schedule_id: String,
+) -> Result<ScheduledPromptRun, String> {
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+    let schedule = read_schedules(&data_folder)?
+        .into_iter()
+        .find(|s| s.id == schedule_id)
+        .ok_or_else(|| format!("Scheduled prompt {schedule_id} not found"))?;
+    execute_scheduled_prompt(&app_handle, schedule).await
+}
+
+/// Lists past runs of a scheduled prompt, newest first.
+#[tauri::command]
+pub async fn list_scheduled_prompt_runs<R: Runtime>(
+    app_handle: AppHandle<R>,
+    schedule_id: String,
+    limit: Option<usize>,
+) -> Result<Vec<ScheduledPromptRun>, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    let mut runs = read_runs(&data_folder, &schedule_id)?;
+    if let Some(limit) = limit {
+        runs.truncate(limit);
+    }
+    Ok(runs)
+}
@@ -0,0 +1,13 @@
+// Scheduled prompt constants
+pub const SCHEDULED_PROMPTS_DIR: &str = "scheduled_prompts";
+pub const SCHEDULES_FILE: &str = "schedules.json";
+pub const HISTORY_DIR: &str = "history";
+pub const SCHEDULED_PROMPT_EVENT: &str = "scheduled-prompt-run";
+/// How often the runner looks for due schedules
+pub const RUNNER_TICK_SECS: u64 = 30;
+/// Runs kept in each schedule's history
+pub const MAX_RUN_HISTORY: usize = 100;
+/// Times a run is retried after being preempted by interactive chat
+pub const MAX_PREEMPTION_RETRIES: usize = 3;
+pub const PREEMPTION_RETRY_DELAY_SECS: u64 = 60;
+pub const MIN_INTERVAL_MINUTES: u32 = 1;
@@ -0,0 +1,467 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use chrono::{DateTime, Datelike, Days, Local, NaiveTime, TimeZone};
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use super::constants::{
+    HISTORY_DIR, MAX_PREEMPTION_RETRIES, MAX_RUN_HISTORY, MIN_INTERVAL_MINUTES,
+    PREEMPTION_RETRY_DELAY_SECS, RUNNER_TICK_SECS, SCHEDULED_PROMPTS_DIR, SCHEDULED_PROMPT_EVENT,
+    SCHEDULES_FILE,
+};
+use super::models::{PromptSchedule, RunStatus, ScheduledPrompt, ScheduledPromptRun};
+use super::ScheduledPromptState;
+use crate::core::agent::commands::execute_agent_run;
+use crate::core::agent::models::AgentRunRequest;
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::assistants::helpers::read_assistant;
+use crate::core::messages::helpers::localize;
+use crate::core::messages::models::{LocalizedMessage, MessageCode};
+use crate::core::notifications::{helpers::notify, models::NotificationCategory};
+use crate::core::prompts::helpers::{builtin_values, render_template};
+use crate::core::scheduler::constants::PREEMPTED_ERROR;
+use crate::core::scheduler::models::GenerationPriority;
+use crate::core::threads::commands::{create_message, create_thread};
+use crate::core::threads::utils::get_thread_metadata_path;
+
+// Global lock serializing read-modify-write cycles on schedules.json
+static SCHEDULES_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+pub fn schedules_lock() -> &'static Mutex<()> {
+    SCHEDULES_LOCK.get_or_init(|| Mutex::new(()))
+}
+
+pub fn get_schedules_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(SCHEDULED_PROMPTS_DIR).join(SCHEDULES_FILE)
+}
+
+pub fn get_history_path(data_folder: &Path, schedule_id: &str) -> PathBuf {
+    data_folder
+        .join(SCHEDULED_PROMPTS_DIR)
+        .join(HISTORY_DIR)
+        .join(format!("{schedule_id}.jsonl"))
+}
+
+/// Read all schedules, returning an empty list if the file does not exist yet
+pub fn read_schedules(data_folder: &Path) -> Result<Vec<ScheduledPrompt>, String> {
+    let path = get_schedules_path(data_folder);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&data).map_err(|e| format!("Failed to parse {}: {e}", path.display()))
+}
+
+pub fn write_schedules(data_folder: &Path, schedules: &[ScheduledPrompt]) -> Result<(), String> {
+    let path = get_schedules_path(data_folder);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let data = serde_json::to_string_pretty(schedules).map_err(|e| e.to_string())?;
+    fs::write(path, data).map_err(|e| e.to_string())
+}
+
+/// Apply `update` to one schedule under the schedules lock
+pub async fn update_schedule(
+    data_folder: &Path,
+    schedule_id: &str,
+    update: impl FnOnce(&mut ScheduledPrompt),
+) -> Result<ScheduledPrompt, String> {
+    let _guard = schedules_lock().lock().await;
+    let mut schedules = read_schedules(data_folder)?;
+    let schedule = schedules
+        .iter_mut()
+        .find(|s| s.id == schedule_id)
+        .ok_or_else(|| format!("Scheduled prompt {schedule_id} not found"))?;
+    update(schedule);
+    let updated = schedule.clone();
+    write_schedules(data_folder, &schedules)?;
+    Ok(updated)
+}
+
+pub fn parse_time(time: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(time.trim(), "%H:%M")
+        .map_err(|_| format!("Invalid time '{time}', expected HH:MM"))
+}
+
+pub fn validate_schedule(schedule: &PromptSchedule) -> Result<(), String> {
+    match schedule {
+        PromptSchedule::Interval { minutes } => {
+            if *minutes < MIN_INTERVAL_MINUTES {
+                return Err(format!(
+                    "Interval must be at least {MIN_INTERVAL_MINUTES} minute(s)"
+                ));
+            }
+        }
+        PromptSchedule::Daily { time } => {
+            parse_time(time)?;
+        }
+        PromptSchedule::Weekly { weekdays, time } => {
+            parse_time(time)?;
+            if weekdays.is_empty() || weekdays.iter().any(|d| *d > 6) {
+                return Err("Weekdays must be between 0 (Monday) and 6 (Sunday)".to_string());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Local datetime for `date` at `time`, skipping forward over DST gaps
+fn local_at<Tz: TimeZone>(tz: &Tz, date: chrono::NaiveDate, time: NaiveTime) -> DateTime<Tz> {
+    let naive = date.and_time(time);
+    tz.from_local_datetime(&naive)
+        .earliest()
+        .unwrap_or_else(|| {
+            tz.from_local_datetime(&(naive + chrono::Duration::hours(1)))
+                .earliest()
+                .unwrap_or_else(|| tz.from_utc_datetime(&naive))
+        })
+}
+
+/// First time strictly after `after` at which `schedule` fires
+pub fn next_run_after<Tz: TimeZone>(
+    schedule: &PromptSchedule,
+    after: &DateTime<Tz>,
+) -> Result<DateTime<Tz>, String> {
+    validate_schedule(schedule)?;
+    let tz = after.timezone();
+    let (weekdays, time): (Option<&[u8]>, &str) = match schedule {
+        PromptSchedule::Interval { minutes } => {
+            return Ok(after.clone() + chrono::Duration::minutes(i64::from(*minutes)));
+        }
+        PromptSchedule::Daily { time } => (None, time),
+        PromptSchedule::Weekly { weekdays, time } => (Some(weekdays), time),
+    };
+    let time = parse_time(time)?;
+    let today = after.date_naive();
+    for offset in 0..=7 {
+        let Some(date) = today.checked_add_days(Days::new(offset)) else {
+            break;
+        };
+        let day = date.weekday().num_days_from_monday() as u8;
+        if weekdays.is_some_and(|days| !days.contains(&day)) {
+            continue;
+        }
+        let candidate = local_at(&tz, date, time);
+        if candidate > *after {
+            return Ok(candidate);
+        }
+    }
+    Err("Schedule never fires".to_string())
+}
+
+pub fn next_run_at_ms(schedule: &PromptSchedule, after: DateTime<Local>) -> Option<i64> {
+    next_run_after(schedule, &after)
+        .ok()
+        .map(|t| t.timestamp_millis())
+}
+
+/// Append a run to the schedule's history, keeping the newest `MAX_RUN_HISTORY` runs
+pub fn append_run(data_folder: &Path, run: &ScheduledPromptRun) -> Result<(), String> {
+    let path = get_history_path(data_folder, &run.schedule_id);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let mut runs = read_runs(data_folder, &run.schedule_id)?;
+    if runs.len() + 1 > MAX_RUN_HISTORY {
+        // Stored oldest first; read_runs returns newest first
+        runs.truncate(MAX_RUN_HISTORY - 1);
+        runs.reverse();
+        runs.push(run.clone());
+        let mut data = String::new();
+        for r in &runs {
+            data.push_str(&serde_json::to_string(r).map_err(|e| e.to_string())?);
+            data.push('\n');
+        }
+        return fs::write(&path, data).map_err(|e| e.to_string());
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| e.to_string())?;
+    let line = serde_json::to_string(run).map_err(|e| e.to_string())?;
+    writeln!(file, "{line}").map_err(|e| e.to_string())
+}
+
+/// Run history of a schedule, newest first
+pub fn read_runs(data_folder: &Path, schedule_id: &str) -> Result<Vec<ScheduledPromptRun>, String> {
+    let path = get_history_path(data_folder, schedule_id);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let mut runs: Vec<ScheduledPromptRun> = data
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+    runs.reverse();
+    Ok(runs)
+}
+
+fn text_message(thread_id: &str, role: &str, text: &str, schedule_id: &str) -> serde_json::Value {
+    let now = chrono::Utc::now().timestamp_millis();
+    json!({
+        "object": "message",
+        "thread_id": thread_id,
+        "role": role,
+        "content": [{"type": "text", "text": {"value": text, "annotations": []}}],
+        "status": "ready",
+        "created_at": now,
+        "completed_at": now,
+        "metadata": {"scheduled_prompt_id": schedule_id},
+    })
+}
+
+/// Return the schedule's thread, creating it when missing
+async fn ensure_thread<R: Runtime>(
+    app: &AppHandle<R>,
+    schedule: &ScheduledPrompt,
+) -> Result<String, String> {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    if let Some(thread_id) = &schedule.thread_id {
+        if get_thread_metadata_path(&data_folder, thread_id).exists() {
+            return Ok(thread_id.clone());
+        }
+    }
+    let now = chrono::Utc::now().timestamp();
+    let thread = create_thread(
+        app.clone(),
+        json!({
+            "object": "thread",
+            "title": schedule.name,
+            "assistants": [],
+            "created": now,
+            "updated": now,
+            "metadata": {"scheduled_prompt_id": schedule.id},
+        }),
+    )
+    .await?;
+    let thread_id = thread["id"]
+        .as_str()
+        .ok_or_else(|| "Created thread has no id".to_string())?
+        .to_string();
+    let assigned = thread_id.clone();
+    update_schedule(&data_folder, &schedule.id, move |s| {
+        s.thread_id = Some(assigned)
+    })
+    .await?;
+    Ok(thread_id)
+}
+
+/// Render the prompt and drive it through the agent loop, retrying when preempted
+async fn run_prompt<R: Runtime>(
+    app: &AppHandle<R>,
+    schedule: &ScheduledPrompt,
+    prompt: &str,
+) -> Result<(String, usize), String> {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let mut messages = Vec::new();
+    let mut parameters = serde_json::Map::new();
+    if let Some(assistant_id) = &schedule.assistant_id {
+        let assistant = read_assistant(&data_folder, assistant_id)?;
+        if let Some(instructions) = assistant.instructions.filter(|i| !i.trim().is_empty()) {
+            messages.push(json!({"role": "system", "content": instructions}));
+        }
+        parameters = assistant.parameters;
+    }
+    messages.push(json!({"role": "user", "content": prompt}));
+
+    let mut attempt = 0;
+    loop {
+        let request = AgentRunRequest {
+            run_id: Some(format!("schedule:{}:{}", schedule.id, Uuid::new_v4())),
+            model: schedule.model.clone(),
+            messages: messages.clone(),
+            assistant_id: schedule.assistant_id.clone(),
+            thread_id: schedule.thread_id.clone(),
+            parameters: parameters.clone(),
+            max_iterations: None,
+            priority: GenerationPriority::Background,
+            context_size: None,
+        };
+        match execute_agent_run(app, request, None).await {
+            Ok(result) => {
+                return match result.provider_error {
+                    Some(failure) => Err(failure.error.to_string()),
+                    None => Ok((result.content, result.iterations)),
+                }
+            }
+            Err(e) if e == PREEMPTED_ERROR && attempt < MAX_PREEMPTION_RETRIES => {
+                attempt += 1;
+                log::info!(
+                    "Scheduled prompt {} was preempted, retrying ({attempt}/{MAX_PREEMPTION_RETRIES})",
+                    schedule.id
+                );
+                tokio::time::sleep(Duration::from_secs(PREEMPTION_RETRY_DELAY_SECS)).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Execute a schedule once: run the prompt, write the exchange into its thread, record
+/// the run and notify the frontend. Returns the recorded run.
+pub async fn execute_scheduled_prompt<R: Runtime>(
+    app: &AppHandle<R>,
+    schedule: ScheduledPrompt,
+) -> Result<ScheduledPromptRun, String> {
+    let state = app.state::<ScheduledPromptState>();
+    if !state.running.lock().await.insert(schedule.id.clone()) {
+        return Err(format!(
+            "Scheduled prompt {} is already running",
+            schedule.name
+        ));
+    }
+
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let started_at = chrono::Utc::now().timestamp_millis();
+    let mut thread_id = None;
+    let mut message_id = None;
+    let mut iterations = 0;
+
+    let outcome: Result<(), String> = async {
+        let prompt = render_template(&schedule.prompt, &Default::default(), &builtin_values())?;
+        let thread = ensure_thread(app, &schedule).await?;
+        thread_id = Some(thread.clone());
+        create_message(
+            app.clone(),
+            text_message(&thread, "user", &prompt, &schedule.id),
+        )
+        .await?;
+
+        let (content, used) = run_prompt(app, &schedule, &prompt).await?;
+        iterations = used;
+        let message = create_message(
+            app.clone(),
+            text_message(&thread, "assistant", &content, &schedule.id),
+        )
+        .await?;
+        message_id = message["id"].as_str().map(str::to_string);
+        Ok(())
+    }
+    .await;
+    state.running.lock().await.remove(&schedule.id);
+
+    let run = ScheduledPromptRun {
+        id: Uuid::new_v4().to_string(),
+        schedule_id: schedule.id.clone(),
+        schedule_name: schedule.name.clone(),
+        started_at,
+        finished_at: chrono::Utc::now().timestamp_millis(),
+        status: if outcome.is_ok() {
+            RunStatus::Succeeded
+        } else {
+            RunStatus::Failed
+        },
+        thread_id,
+        message_id,
+        error: outcome.err(),
+        iterations,
+    };
+    if let Some(error) = &run.error {
+        log::warn!("Scheduled prompt {} failed: {error}", schedule.name);
+    }
+
+    if let Err(e) = append_run(&data_folder, &run) {
+        log::error!(
+            "Failed to record run of scheduled prompt {}: {e}",
+            schedule.id
+        );
+    }
+    let recorded = run.clone();
+    let update = update_schedule(&data_folder, &schedule.id, move |s| {
+        s.last_run_at = Some(recorded.started_at);
+        s.last_status = Some(recorded.status);
+        s.last_error = recorded.error.clone();
+        s.consecutive_failures = match recorded.status {
+            RunStatus::Succeeded => 0,
+            RunStatus::Failed => s.consecutive_failures + 1,
+        };
+    })
+    .await;
+    if let Err(e) = update {
+        // The schedule may have been deleted while it was running
+        log::debug!("Failed to update scheduled prompt {}: {e}", schedule.id);
+    }
+
+    if let Err(e) = app.emit(SCHEDULED_PROMPT_EVENT, &run) {
+        log::error!("Failed to emit scheduled prompt event: {e}");
+    }
+    let body = match &run.error {
+        Some(error) => LocalizedMessage::new(MessageCode::NotificationScheduledPromptFailed)
+            .with("error", error),
+        None => LocalizedMessage::new(MessageCode::NotificationScheduledPromptReady),
+    };
+    notify(
+        app,
+        NotificationCategory::ScheduledPrompt,
+        &schedule.name,
+        &localize(app, &body),
+    );
+    Ok(run)
+}
+
+/// Claim due schedules by advancing their next run time, so each occurrence runs once
+async fn claim_due_schedules(
+    data_folder: &Path,
+    now: DateTime<Local>,
+) -> Result<Vec<ScheduledPrompt>, String> {
+    let _guard = schedules_lock().lock().await;
+    let mut schedules = read_schedules(data_folder)?;
+    let now_ms = now.timestamp_millis();
+    let mut due = Vec::new();
+    let mut changed = false;
+    for schedule in schedules.iter_mut().filter(|s| s.enabled) {
+        match schedule.next_run_at {
+            Some(next) if next <= now_ms => {
+                due.push(schedule.clone());
+                schedule.next_run_at = next_run_at_ms(&schedule.schedule, now);
+                changed = true;
+            }
+            Some(_) => {}
+            None => {
+                schedule.next_run_at = next_run_at_ms(&schedule.schedule, now);
+                changed = true;
+            }
+        }
+    }
+    if changed {
+        write_schedules(data_folder, &schedules)?;
+    }
+    Ok(due)
+}
+
+/// Start the background runner executing due scheduled prompts
+pub fn start_scheduled_prompt_runner<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(RUNNER_TICK_SECS));
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            interval.tick().await;
+            let data_folder = get_jan_data_folder_path(app.clone());
+            let due = match claim_due_schedules(&data_folder, Local::now()).await {
+                Ok(due) => due,
+                Err(e) => {
+                    log::error!("Failed to check scheduled prompts: {e}");
+                    continue;
+                }
+            };
+            for schedule in due {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = execute_scheduled_prompt(&app, schedule).await {
+                        log::warn!("{e}");
+                    }
+                });
+            }
+        }
+    });
+}
@@ -0,0 +1,31 @@
+/*!
+   Scheduled Prompts
+
+   Prompts that run on a timer ("summarize my RSS feed every morning at 8"). Schedules are stored
+   in `scheduled_prompts/schedules.json`; a background runner started at app setup checks for due
+   schedules and executes each one through the agent loop at background priority, so it yields
+   the local engine to interactive chat. The prompt and the answer are written into the
+   schedule's thread (created on the first run), every run is recorded in a per-schedule history
+   file, and a `scheduled-prompt-run` event is emitted so the UI can notify the user about
+   results and failures.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+
+/// Runtime state of the scheduled prompt runner
+#[derive(Default)]
+pub struct ScheduledPromptState {
+    /// Ids of schedules currently executing, to avoid overlapping runs
+    pub running: Arc<Mutex<HashSet<String>>>,
+}
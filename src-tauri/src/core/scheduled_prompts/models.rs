@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+/// When a prompt runs. Times are `HH:MM` in local time.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PromptSchedule {
+    Interval {
+        minutes: u32,
+    },
+    Daily {
+        time: String,
+    },
+    /// `weekdays` are numbered from Monday (0) to Sunday (6)
+    Weekly {
+        weekdays: Vec<u8>,
+        time: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledPrompt {
+    pub id: String,
+    pub name: String,
+    /// Prompt text; built-in template variables such as `{{date}}` are rendered at run time
+    pub prompt: String,
+    pub model: String,
+    #[serde(default)]
+    pub assistant_id: Option<String>,
+    /// Thread receiving the results; created on the first run when absent
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    pub schedule: PromptSchedule,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub created_at: i64,
+    /// Milliseconds since epoch
+    #[serde(default)]
+    pub next_run_at: Option<i64>,
+    #[serde(default)]
+    pub last_run_at: Option<i64>,
+    #[serde(default)]
+    pub last_status: Option<RunStatus>,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub consecutive_failures: u32,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// User-editable fields of a scheduled prompt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledPromptInput {
+    pub name: String,
+    pub prompt: String,
+    pub model: String,
+    #[serde(default)]
+    pub assistant_id: Option<String>,
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    pub schedule: PromptSchedule,
+    #[serde(default)]
+    pub enabled: Option<bool>,
+}
+
+/// One execution of a scheduled prompt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledPromptRun {
+    pub id: String,
+    pub schedule_id: String,
+    pub schedule_name: String,
+    pub started_at: i64,
+    pub finished_at: i64,
+    pub status: RunStatus,
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    /// Assistant message written with the result
+    #[serde(default)]
+    pub message_id: Option<String>,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub iterations: usize,
+}
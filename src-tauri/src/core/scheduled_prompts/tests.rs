@@ -0,0 +1,171 @@
+use super::commands::*;
+use super::helpers::{append_run, next_run_after, read_runs};
+use super::models::{PromptSchedule, RunStatus, ScheduledPromptInput, ScheduledPromptRun};
+use crate::core::app::commands::get_jan_data_folder_path;
+use chrono::{FixedOffset, TimeZone};
+use std::fs;
+use tauri::test::mock_app;
+
+fn at(day: u32, hour: u32, minute: u32) -> chrono::DateTime<FixedOffset> {
+    // 2025-01-06 is a Monday
+    FixedOffset::east_opt(0)
+        .unwrap()
+        .with_ymd_and_hms(2025, 1, day, hour, minute, 0)
+        .unwrap()
+}
+
+fn input(schedule: PromptSchedule) -> ScheduledPromptInput {
+    ScheduledPromptInput {
+        name: "Morning digest".to_string(),
+        prompt: "Summarize my feeds for {{date}}".to_string(),
+        model: "llama".to_string(),
+        assistant_id: None,
+        thread_id: None,
+        schedule,
+        enabled: None,
+    }
+}
+
+#[test]
+fn test_next_run_interval() {
+    let next = next_run_after(&PromptSchedule::Interval { minutes: 90 }, &at(6, 8, 0)).unwrap();
+    assert_eq!(next, at(6, 9, 30));
+}
+
+#[test]
+fn test_next_run_daily() {
+    let daily = PromptSchedule::Daily {
+        time: "08:00".to_string(),
+    };
+    assert_eq!(next_run_after(&daily, &at(6, 7, 0)).unwrap(), at(6, 8, 0));
+    // Exactly at the scheduled time moves on to the next day
+    assert_eq!(next_run_after(&daily, &at(6, 8, 0)).unwrap(), at(7, 8, 0));
+}
+
+#[test]
+fn test_next_run_weekly() {
+    let weekly = PromptSchedule::Weekly {
+        weekdays: vec![0, 4],
+        time: "09:30".to_string(),
+    };
+    // Monday after 09:30 -> Friday
+    assert_eq!(
+        next_run_after(&weekly, &at(6, 10, 0)).unwrap(),
+        at(10, 9, 30)
+    );
+    // Friday after 09:30 -> next Monday
+    assert_eq!(
+        next_run_after(&weekly, &at(10, 10, 0)).unwrap(),
+        at(13, 9, 30)
+    );
+}
+
+#[test]
+fn test_invalid_schedules_are_rejected() {
+    let invalid = [
+        PromptSchedule::Interval { minutes: 0 },
+        PromptSchedule::Daily {
+            time: "25:00".to_string(),
+        },
+        PromptSchedule::Weekly {
+            weekdays: vec![7],
+            time: "08:00".to_string(),
+        },
+    ];
+    for schedule in invalid {
+        assert!(next_run_after(&schedule, &at(6, 8, 0)).is_err());
+    }
+}
+
+#[tokio::test]
+async fn test_scheduled_prompt_crud() {
+    let app = mock_app();
+    let data_dir = get_jan_data_folder_path(app.handle().clone());
+
+    let created = create_scheduled_prompt(
+        app.handle().clone(),
+        input(PromptSchedule::Daily {
+            time: "08:00".to_string(),
+        }),
+    )
+    .await
+    .unwrap();
+    assert!(created.enabled);
+    assert!(created.next_run_at.is_some());
+
+    let disabled = set_scheduled_prompt_enabled(app.handle().clone(), created.id.clone(), false)
+        .await
+        .unwrap();
+    assert!(!disabled.enabled);
+
+    let mut changed = input(PromptSchedule::Interval { minutes: 15 });
+    changed.name = "Every quarter hour".to_string();
+    let updated = update_scheduled_prompt(app.handle().clone(), created.id.clone(), changed)
+        .await
+        .unwrap();
+    assert_eq!(updated.name, "Every quarter hour");
+    assert!(!updated.enabled);
+
+    let listed = list_scheduled_prompts(app.handle().clone()).await.unwrap();
+    assert_eq!(listed.len(), 1);
+
+    delete_scheduled_prompt(app.handle().clone(), created.id.clone())
+        .await
+        .unwrap();
+    assert!(list_scheduled_prompts(app.handle().clone())
+        .await
+        .unwrap()
+        .is_empty());
+
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_create_rejects_unknown_variables() {
+    let app = mock_app();
+    let data_dir = get_jan_data_folder_path(app.handle().clone());
+
+    let mut bad = input(PromptSchedule::Interval { minutes: 5 });
+    bad.prompt = "Summarize {{topic}}".to_string();
+    assert!(create_scheduled_prompt(app.handle().clone(), bad)
+        .await
+        .is_err());
+
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_run_history_is_newest_first() {
+    let app = mock_app();
+    let data_dir = get_jan_data_folder_path(app.handle().clone());
+
+    for i in 0..3 {
+        let run = ScheduledPromptRun {
+            id: format!("run-{i}"),
+            schedule_id: "s1".to_string(),
+            schedule_name: "Digest".to_string(),
+            started_at: i,
+            finished_at: i,
+            status: if i == 2 {
+                RunStatus::Failed
+            } else {
+                RunStatus::Succeeded
+            },
+            thread_id: None,
+            message_id: None,
+            error: (i == 2).then(|| "model offline".to_string()),
+            iterations: 1,
+        };
+        append_run(&data_dir, &run).unwrap();
+    }
+    let runs = read_runs(&data_dir, "s1").unwrap();
+    assert_eq!(runs[0].id, "run-2");
+    assert_eq!(runs[0].error.as_deref(), Some("model offline"));
+
+    let limited = list_scheduled_prompt_runs(app.handle().clone(), "s1".to_string(), Some(1))
+        .await
+        .unwrap();
+    assert_eq!(limited.len(), 1);
+
+    let _ = fs::remove_dir_all(data_dir);
+}
@@ -0,0 +1,12 @@
+use tauri::State;
+
+use super::models::ModelQueueSnapshot;
+use super::GenerationScheduler;
+
+/// Lists running and waiting generation jobs per local model.
+#[tauri::command]
+pub async fn get_generation_queue(
+    scheduler: State<'_, GenerationScheduler>,
+) -> Result<Vec<ModelQueueSnapshot>, String> {
+    Ok(scheduler.snapshot())
+}
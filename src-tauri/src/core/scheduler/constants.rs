@@ -0,0 +1,8 @@
+use std::time::Duration;
+
+// Generation scheduler constants
+pub const GENERATION_QUEUE_EVENT: &str = "generation-queue";
+/// Waiting this long raises a job one priority level
+pub const PRIORITY_AGING_INTERVAL: Duration = Duration::from_secs(30);
+/// Error returned by jobs that gave up the engine to an interactive request
+pub const PREEMPTED_ERROR: &str = "Preempted by an interactive request";
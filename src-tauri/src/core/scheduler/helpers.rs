@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tokio::sync::oneshot;
+use tokio_util::sync::CancellationToken;
+
+use super::constants::{GENERATION_QUEUE_EVENT, PRIORITY_AGING_INTERVAL};
+use super::models::{GenerationPriority, ModelQueueSnapshot, QueueEvent, QueueStatus, QueuedJob};
+use super::GenerationScheduler;
+
+pub type EventSink = Arc<dyn Fn(&QueueEvent) + Send + Sync>;
+
+struct Waiter {
+    job_id: String,
+    priority: GenerationPriority,
+    seq: u64,
+    enqueued_at: Instant,
+    tx: oneshot::Sender<GenerationPermit>,
+}
+
+struct Running {
+    job_id: String,
+    priority: GenerationPriority,
+    started_at: Instant,
+    preempt: CancellationToken,
+}
+
+#[derive(Default)]
+struct ModelQueue {
+    running: Option<Running>,
+    waiting: Vec<Waiter>,
+}
+
+#[derive(Default)]
+pub struct SchedulerQueues {
+    models: HashMap<String, ModelQueue>,
+    next_seq: u64,
+}
+
+/// Priority after aging: every `PRIORITY_AGING_INTERVAL` spent waiting adds one level
+pub fn effective_rank(priority: GenerationPriority, enqueued_at: Instant, now: Instant) -> u8 {
+    let aged = (now.duration_since(enqueued_at).as_secs() / PRIORITY_AGING_INTERVAL.as_secs())
+        .min(u8::MAX as u64) as u8;
+    priority
+        .rank()
+        .saturating_add(aged)
+        .min(GenerationPriority::Interactive.rank())
+}
+
+impl ModelQueue {
+    /// Live waiters in the order they will run
+    fn ordered_waiters(&mut self, now: Instant) -> Vec<usize> {
+        self.waiting.retain(|w| !w.tx.is_closed());
+        let mut order: Vec<usize> = (0..self.waiting.len()).collect();
+        order.sort_by(|&a, &b| {
+            let (a, b) = (&self.waiting[a], &self.waiting[b]);
+            effective_rank(b.priority, b.enqueued_at, now)
+                .cmp(&effective_rank(a.priority, a.enqueued_at, now))
+                .then(a.seq.cmp(&b.seq))
+        });
+        order
+    }
+
+    fn position_events(&mut self, model_id: &str, events: &mut Vec<QueueEvent>) {
+        let order = self.ordered_waiters(Instant::now());
+        for (position, &i) in order.iter().enumerate() {
+            let waiter = &self.waiting[i];
+            events.push(QueueEvent {
+                job_id: waiter.job_id.clone(),
+                model_id: model_id.to_string(),
+                priority: waiter.priority,
+                status: QueueStatus::Queued {
+                    position: position + 1,
+                },
+            });
+        }
+    }
+}
+
+/// Exclusive right to generate on a local model. Dropping it hands the engine to the
+/// next queued job.
+pub struct GenerationPermit {
+    scheduler: GenerationScheduler,
+    model_id: String,
+    job_id: String,
+    priority: GenerationPriority,
+    preempt: CancellationToken,
+}
+
+impl GenerationPermit {
+    pub fn job_id(&self) -> &str {
+        &self.job_id
+    }
+
+    pub fn priority(&self) -> GenerationPriority {
+        self.priority
+    }
+
+    /// Cancelled when an interactive request needs the engine
+    pub fn preempted(&self) -> &CancellationToken {
+        &self.preempt
+    }
+}
+
+impl Drop for GenerationPermit {
+    fn drop(&mut self) {
+        self.scheduler.release(&self.model_id, &self.job_id);
+    }
+}
+
+impl GenerationScheduler {
+    /// Install the callback receiving queue events
+    pub fn set_event_sink(&self, sink: impl Fn(&QueueEvent) + Send + Sync + 'static) {
+        if let Ok(mut guard) = self.sink.write() {
+            *guard = Some(Arc::new(sink));
+        }
+    }
+
+    fn emit(&self, events: Vec<QueueEvent>) {
+        let sink = self.sink.read().ok().and_then(|s| s.clone());
+        if let Some(sink) = sink {
+            for event in &events {
+                sink(event);
+            }
+        }
+    }
+
+    fn start(
+        &self,
+        queue: &mut ModelQueue,
+        model_id: &str,
+        job_id: String,
+        priority: GenerationPriority,
+        events: &mut Vec<QueueEvent>,
+    ) -> GenerationPermit {
+        let preempt = CancellationToken::new();
+        queue.running = Some(Running {
+            job_id: job_id.clone(),
+            priority,
+            started_at: Instant::now(),
+            preempt: preempt.clone(),
+        });
+        events.push(QueueEvent {
+            job_id: job_id.clone(),
+            model_id: model_id.to_string(),
+            priority,
+            status: QueueStatus::Started,
+        });
+        GenerationPermit {
+            scheduler: self.clone(),
+            model_id: model_id.to_string(),
+            job_id,
+            priority,
+            preempt,
+        }
+    }
+
+    /// Wait for a turn on `model_id`. Dropping the returned future leaves the queue.
+    pub async fn acquire(
+        &self,
+        model_id: &str,
+        job_id: &str,
+        priority: GenerationPriority,
+    ) -> Result<GenerationPermit, String> {
+        let mut events = Vec::new();
+        let rx = {
+            let mut queues = self
+                .queues
+                .lock()
+                .map_err(|_| "Generation scheduler is poisoned".to_string())?;
+            let seq = queues.next_seq;
+            queues.next_seq += 1;
+            let queue = queues.models.entry(model_id.to_string()).or_default();
+            queue.waiting.retain(|w| !w.tx.is_closed());
+
+            if queue.running.is_none() && queue.waiting.is_empty() {
+                let permit = self.start(queue, model_id, job_id.to_string(), priority, &mut events);
+                drop(queues);
+                self.emit(events);
+                return Ok(permit);
+            }
+
+            let (tx, rx) = oneshot::channel();
+            queue.waiting.push(Waiter {
+                job_id: job_id.to_string(),
+                priority,
+                seq,
+                enqueued_at: Instant::now(),
+                tx,
+            });
+            if priority == GenerationPriority::Interactive {
+                if let Some(running) = &queue.running {
+                    if running.priority.is_preemptible() && !running.preempt.is_cancelled() {
+                        log::info!(
+                            "Preempting background job {} on {model_id} for {job_id}",
+                            running.job_id
+                        );
+                        running.preempt.cancel();
+                        events.push(QueueEvent {
+                            job_id: running.job_id.clone(),
+                            model_id: model_id.to_string(),
+                            priority: running.priority,
+                            status: QueueStatus::Preempted,
+                        });
+                    }
+                }
+            }
+            queue.position_events(model_id, &mut events);
+            rx
+        };
+        self.emit(events);
+
+        rx.await
+            .map_err(|_| format!("Generation job {job_id} was dropped from the queue"))
+    }
+
+    fn release(&self, model_id: &str, job_id: &str) {
+        let mut events = Vec::new();
+        let next = {
+            let Ok(mut queues) = self.queues.lock() else {
+                return;
+            };
+            let Some(queue) = queues.models.get_mut(model_id) else {
+                return;
+            };
+            if queue.running.as_ref().is_some_and(|r| r.job_id == job_id) {
+                if let Some(running) = queue.running.take() {
+                    events.push(QueueEvent {
+                        job_id: running.job_id,
+                        model_id: model_id.to_string(),
+                        priority: running.priority,
+                        status: QueueStatus::Finished,
+                    });
+                }
+            }
+
+            let mut next = None;
+            if queue.running.is_none() {
+                if let Some(&i) = queue.ordered_waiters(Instant::now()).first() {
+                    let waiter = queue.waiting.remove(i);
+                    let permit =
+                        self.start(queue, model_id, waiter.job_id, waiter.priority, &mut events);
+                    next = Some((waiter.tx, permit));
+                }
+            }
+            queue.position_events(model_id, &mut events);
+            if queue.running.is_none() && queue.waiting.is_empty() {
+                queues.models.remove(model_id);
+            }
+            next
+        };
+        self.emit(events);
+
+        // If the waiter went away in the meantime the permit comes back and is dropped,
+        // which releases the engine to the next job.
+        if let Some((tx, permit)) = next {
+            let _ = tx.send(permit);
+        }
+    }
+
+    pub fn snapshot(&self) -> Vec<ModelQueueSnapshot> {
+        let Ok(mut queues) = self.queues.lock() else {
+            return Vec::new();
+        };
+        let now = Instant::now();
+        let mut snapshots: Vec<ModelQueueSnapshot> = queues
+            .models
+            .iter_mut()
+            .map(|(model_id, queue)| {
+                let order = queue.ordered_waiters(now);
+                ModelQueueSnapshot {
+                    model_id: model_id.clone(),
+                    running: queue.running.as_ref().map(|r| QueuedJob {
+                        job_id: r.job_id.clone(),
+                        priority: r.priority,
+                        waited_ms: now.duration_since(r.started_at).as_millis() as u64,
+                    }),
+                    waiting: order
+                        .iter()
+                        .map(|&i| {
+                            let w = &queue.waiting[i];
+                            QueuedJob {
+                                job_id: w.job_id.clone(),
+                                priority: w.priority,
+                                waited_ms: now.duration_since(w.enqueued_at).as_millis() as u64,
+                            }
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+        snapshots.sort_by(|a, b| a.model_id.cmp(&b.model_id));
+        snapshots
+    }
+}
+
+/// Forward scheduler queue events to the frontend
+pub fn forward_queue_events<R: Runtime>(app: &AppHandle<R>) {
+    let handle = app.clone();
+    app.state::<GenerationScheduler>()
+        .set_event_sink(move |event| {
+            if let Err(e) = handle.emit(GENERATION_QUEUE_EVENT, event) {
+                log::error!("Failed to emit generation queue event: {e}");
+            }
+        });
+}
+
+/// Take a turn on the engine when the endpoint is local; remote endpoints are not scheduled
+pub async fn acquire_for_endpoint<R: Runtime>(
+    app: &AppHandle<R>,
+    endpoint: &crate::core::inference::models::ModelEndpoint,
+    job_id: &str,
+    priority: GenerationPriority,
+) -> Result<Option<GenerationPermit>, String> {
+    if !endpoint.is_local {
+        return Ok(None);
+    }
+    let Some(scheduler) = app.try_state::<GenerationScheduler>() else {
+        return Ok(None);
+    };
+    scheduler
+        .acquire(&endpoint.model_id, job_id, priority)
+        .await
+        .map(Some)
+}
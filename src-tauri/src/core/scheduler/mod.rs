@@ -0,0 +1,31 @@
+/*!
+   Generation Scheduler
+
+   A local engine serves one generation at a time per model, so concurrent requests from
+   several threads, background jobs and API clients are queued here instead of racing:
+   - jobs are ordered by priority (interactive chat > background jobs > API clients),
+     FIFO within a priority, and waiting jobs age into higher priorities so nothing starves,
+   - queue position updates are emitted as `generation-queue` events,
+   - an interactive request preempts a running background job, which observes this through
+     its permit and aborts its generation.
+   Remote providers are not scheduled.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
+
+use std::sync::{Arc, Mutex, RwLock};
+
+use helpers::{EventSink, SchedulerQueues};
+
+/// Shared generation scheduler, cheap to clone
+#[derive(Clone, Default)]
+pub struct GenerationScheduler {
+    queues: Arc<Mutex<SchedulerQueues>>,
+    sink: Arc<RwLock<Option<EventSink>>>,
+}
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+/// Priority class of a generation job, lowest first
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum GenerationPriority {
+    /// Requests coming through the local API server
+    Api,
+    /// Title generation, summarization and other jobs the user is not waiting on
+    Background,
+    /// Chat the user is actively waiting for
+    #[default]
+    Interactive,
+}
+
+impl GenerationPriority {
+    pub fn rank(self) -> u8 {
+        self as u8
+    }
+
+    /// Background jobs give up the engine when an interactive request arrives
+    pub fn is_preemptible(self) -> bool {
+        self == GenerationPriority::Background
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum QueueStatus {
+    /// Waiting; position 1 runs next
+    Queued {
+        position: usize,
+    },
+    Started,
+    /// Asked to stop so an interactive request can run
+    Preempted,
+    Finished,
+}
+
+/// Payload of `generation-queue` events
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QueueEvent {
+    pub job_id: String,
+    pub model_id: String,
+    pub priority: GenerationPriority,
+    #[serde(flatten)]
+    pub status: QueueStatus,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedJob {
+    pub job_id: String,
+    pub priority: GenerationPriority,
+    pub waited_ms: u64,
+}
+
+/// Current state of one model's queue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelQueueSnapshot {
+    pub model_id: String,
+    pub running: Option<QueuedJob>,
+    /// Waiting jobs in the order they will run
+    pub waiting: Vec<QueuedJob>,
+}
@@ -0,0 +1,190 @@
+use super::constants::PRIORITY_AGING_INTERVAL;
+use super::helpers::effective_rank;
+use super::models::{GenerationPriority, QueueEvent, QueueStatus};
+use super::GenerationScheduler;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+fn recording_scheduler() -> (GenerationScheduler, Arc<Mutex<Vec<QueueEvent>>>) {
+    let scheduler = GenerationScheduler::default();
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink_events = events.clone();
+    scheduler.set_event_sink(move |event| sink_events.lock().unwrap().push(event.clone()));
+    (scheduler, events)
+}
+
+async fn settle() {
+    tokio::time::sleep(Duration::from_millis(20)).await;
+}
+
+#[tokio::test]
+async fn test_first_job_starts_immediately() {
+    let (scheduler, events) = recording_scheduler();
+    let permit = scheduler
+        .acquire("m", "a", GenerationPriority::Api)
+        .await
+        .unwrap();
+    assert_eq!(permit.job_id(), "a");
+    assert_eq!(events.lock().unwrap()[0].status, QueueStatus::Started);
+    drop(permit);
+    assert!(scheduler.snapshot().is_empty());
+}
+
+#[tokio::test]
+async fn test_priority_order_and_positions() {
+    let (scheduler, events) = recording_scheduler();
+    let running = scheduler
+        .acquire("m", "running", GenerationPriority::Interactive)
+        .await
+        .unwrap();
+
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let mut handles = Vec::new();
+    for (job, priority) in [
+        ("api", GenerationPriority::Api),
+        ("background", GenerationPriority::Background),
+        ("chat", GenerationPriority::Interactive),
+    ] {
+        let scheduler = scheduler.clone();
+        let order = order.clone();
+        handles.push(tokio::spawn(async move {
+            let permit = scheduler.acquire("m", job, priority).await.unwrap();
+            order.lock().unwrap().push(job.to_string());
+            drop(permit);
+        }));
+        settle().await;
+    }
+
+    let waiting: Vec<String> = scheduler.snapshot()[0]
+        .waiting
+        .iter()
+        .map(|j| j.job_id.clone())
+        .collect();
+    assert_eq!(waiting, vec!["chat", "background", "api"]);
+    let last_api_position = events
+        .lock()
+        .unwrap()
+        .iter()
+        .rev()
+        .find(|e| e.job_id == "api")
+        .map(|e| e.status.clone());
+    assert_eq!(last_api_position, Some(QueueStatus::Queued { position: 3 }));
+
+    drop(running);
+    for handle in handles {
+        handle.await.unwrap();
+    }
+    assert_eq!(*order.lock().unwrap(), vec!["chat", "background", "api"]);
+}
+
+#[tokio::test]
+async fn test_interactive_preempts_background() {
+    let (scheduler, events) = recording_scheduler();
+    let background = scheduler
+        .acquire("m", "title", GenerationPriority::Background)
+        .await
+        .unwrap();
+
+    let waiter = scheduler.clone();
+    let chat = tokio::spawn(async move {
+        waiter
+            .acquire("m", "chat", GenerationPriority::Interactive)
+            .await
+            .map(|p| p.job_id().to_string())
+    });
+    settle().await;
+    assert!(background.preempted().is_cancelled());
+    assert!(events
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|e| e.job_id == "title" && e.status == QueueStatus::Preempted));
+
+    drop(background);
+    assert_eq!(chat.await.unwrap().unwrap(), "chat");
+}
+
+#[tokio::test]
+async fn test_api_jobs_are_not_preempted() {
+    let (scheduler, _) = recording_scheduler();
+    let api = scheduler
+        .acquire("m", "api", GenerationPriority::Api)
+        .await
+        .unwrap();
+    let waiter = scheduler.clone();
+    let chat = tokio::spawn(async move {
+        let _ = waiter
+            .acquire("m", "chat", GenerationPriority::Interactive)
+            .await;
+    });
+    settle().await;
+    assert!(!api.preempted().is_cancelled());
+    drop(api);
+    chat.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_abandoned_waiter_is_skipped() {
+    let (scheduler, _) = recording_scheduler();
+    let running = scheduler
+        .acquire("m", "running", GenerationPriority::Interactive)
+        .await
+        .unwrap();
+
+    let abandoned = scheduler.clone();
+    let handle = tokio::spawn(async move {
+        let _ = abandoned
+            .acquire("m", "gone", GenerationPriority::Interactive)
+            .await;
+    });
+    settle().await;
+    handle.abort();
+    let _ = handle.await;
+
+    let waiter = scheduler.clone();
+    let next = tokio::spawn(async move {
+        waiter
+            .acquire("m", "next", GenerationPriority::Api)
+            .await
+            .map(|p| p.job_id().to_string())
+    });
+    settle().await;
+    drop(running);
+    assert_eq!(next.await.unwrap().unwrap(), "next");
+}
+
+#[tokio::test]
+async fn test_models_are_scheduled_independently() {
+    let (scheduler, _) = recording_scheduler();
+    let _a = scheduler
+        .acquire("model-a", "a", GenerationPriority::Interactive)
+        .await
+        .unwrap();
+    let b = tokio::time::timeout(
+        Duration::from_millis(100),
+        scheduler.acquire("model-b", "b", GenerationPriority::Api),
+    )
+    .await;
+    assert!(b.is_ok());
+}
+
+#[test]
+fn test_waiting_jobs_age_into_higher_priority() {
+    let now = Instant::now();
+    assert_eq!(
+        effective_rank(GenerationPriority::Api, now, now),
+        GenerationPriority::Api.rank()
+    );
+    if let Some(enqueued) = now.checked_sub(PRIORITY_AGING_INTERVAL) {
+        assert_eq!(
+            effective_rank(GenerationPriority::Api, enqueued, now),
+            GenerationPriority::Background.rank()
+        );
+    }
+    if let Some(enqueued) = now.checked_sub(PRIORITY_AGING_INTERVAL * 10) {
+        assert_eq!(
+            effective_rank(GenerationPriority::Api, enqueued, now),
+            GenerationPriority::Interactive.rank()
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use serde_json::Value;
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Manager, Runtime, State};
+use uuid::Uuid;
+
+use super::helpers::{
+    scratchpad_context_request, scratchpad_messages, stash_entry, take_entry, text_message,
+};
+use super::models::{ScratchpadEntry, ScratchpadRequest, ScratchpadResult};
+use super::ScratchpadState;
+use crate::core::agent::commands::execute_agent_run;
+use crate::core::agent::models::AgentRunRequest;
+use crate::core::context::commands::assemble_thread_context;
+use crate::core::scheduler::models::GenerationPriority;
+use crate::core::streaming::models::TokenChunk;
+use crate::core::threads::commands::create_message;
+
+/// Answers a side question against a copy of the thread context. The run goes through the
+/// agent loop like any other, but nothing is added to the thread; the answer is held until
+/// it is kept with `keep_scratchpad_result` or discarded.
+#[tauri::command]
+pub async fn execute_in_scratchpad<R: Runtime>(
+    app: AppHandle<R>,
+    request: ScratchpadRequest,
+    on_token: Option<Channel<TokenChunk>>,
+) -> Result<ScratchpadResult, String> {
+    if request.prompt.trim().is_empty() {
+        return Err("Scratchpad prompt is empty".to_string());
+    }
+    let context =
+        assemble_thread_context(app.clone(), scratchpad_context_request(&request)).await?;
+    let scratchpad_id = request
+        .run_id
+        .clone()
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let run = execute_agent_run(
+        &app,
+        AgentRunRequest {
+            run_id: Some(scratchpad_id.clone()),
+            model: request.model.clone(),
+            messages: scratchpad_messages(&context.messages, &request.prompt),
+            assistant_id: request.assistant_id.clone(),
+            thread_id: Some(request.thread_id.clone()),
+            parameters: request.parameters.clone(),
+            max_iterations: request.max_iterations,
+            priority: GenerationPriority::Interactive,
+            context_size: Some(request.context_size),
+        },
+        on_token,
+    )
+    .await?;
+
+    if run.provider_error.is_none() && !run.content.trim().is_empty() {
+        let entry = ScratchpadEntry {
+            id: scratchpad_id.clone(),
+            thread_id: request.thread_id.clone(),
+            prompt: request.prompt.clone(),
+            content: run.content.clone(),
+            created_at: chrono::Utc::now().timestamp_millis(),
+        };
+        let state = app.state::<ScratchpadState>();
+        stash_entry(&mut *state.entries.lock().await, entry);
+    }
+    Ok(ScratchpadResult {
+        scratchpad_id,
+        run,
+        included_message_ids: context.included_message_ids,
+    })
+}
+
+/// Adds a scratchpad question and its answer to the thread. Returns the created messages.
+#[tauri::command]
+pub async fn keep_scratchpad_result<R: Runtime>(
+    app: AppHandle<R>,
+    scratchpad_id: String,
+) -> Result<Vec<Value>, String> {
+    let entry = {
+        let state = app.state::<ScratchpadState>();
+        let mut entries = state.entries.lock().await;
+        take_entry(&mut entries, &scratchpad_id)
+    }
+    .ok_or_else(|| format!("Scratchpad result {scratchpad_id} not found"))?;
+
+    let question = text_message(&entry.thread_id, "user", &entry.prompt, &entry.id);
+    let answer = text_message(&entry.thread_id, "assistant", &entry.content, &entry.id);
+    Ok(vec![
+        create_message(app.clone(), question).await?,
+        create_message(app, answer).await?,
+    ])
+}
+
+/// Drops a scratchpad answer without adding it to the thread
+#[tauri::command]
+pub async fn discard_scratchpad_result(
+    state: State<'_, ScratchpadState>,
+    scratchpad_id: String,
+) -> Result<(), String> {
+    take_entry(&mut *state.entries.lock().await, &scratchpad_id)
+        .map(|_| ())
+        .ok_or_else(|| format!("Scratchpad result {scratchpad_id} not found"))
+}
+
+/// Lists the scratchpad answers of a thread that are still held, oldest first
+#[tauri::command]
+pub async fn list_scratchpad_results(
+    state: State<'_, ScratchpadState>,
+    thread_id: String,
+) -> Result<Vec<ScratchpadEntry>, String> {
+    Ok(state
+        .entries
+        .lock()
+        .await
+        .iter()
+        .filter(|entry| entry.thread_id == thread_id)
+        .cloned()
+        .collect())
+}
@@ -0,0 +1,5 @@
+// Scratchpad constants
+/// Answers held for keeping; older ones are dropped
+pub const MAX_SCRATCHPAD_ENTRIES: usize = 20;
+/// Metadata key marking thread messages kept from a scratchpad
+pub const SCRATCHPAD_METADATA_KEY: &str = "scratchpad_id";
@@ -0,0 +1,60 @@
+use serde_json::{json, Value};
+
+use super::constants::{MAX_SCRATCHPAD_ENTRIES, SCRATCHPAD_METADATA_KEY};
+use super::models::{ScratchpadEntry, ScratchpadRequest};
+use crate::core::context::constants::DEFAULT_RESPONSE_RESERVE_TOKENS;
+use crate::core::context::helpers::estimate_message_tokens;
+use crate::core::context::models::{ChatMessage, ContextRequest};
+
+/// Context request for the thread of a scratchpad run. The question is appended after
+/// assembly, so its tokens are reserved on top of those for the answer.
+pub fn scratchpad_context_request(request: &ScratchpadRequest) -> ContextRequest {
+    let reserve = request
+        .reserve_tokens
+        .unwrap_or(DEFAULT_RESPONSE_RESERVE_TOKENS);
+    ContextRequest {
+        thread_id: request.thread_id.clone(),
+        context_size: request.context_size,
+        reserve_tokens: Some(reserve + estimate_message_tokens(&request.prompt)),
+        system_prompt: request.system_prompt.clone(),
+        summarizer_model: request.summarizer_model.clone(),
+        sources: request.sources.clone(),
+    }
+}
+
+/// Agent run messages: the assembled thread context followed by the question
+pub fn scratchpad_messages(context: &[ChatMessage], prompt: &str) -> Vec<Value> {
+    context
+        .iter()
+        .map(|message| json!({"role": message.role, "content": message.content}))
+        .chain(std::iter::once(json!({"role": "user", "content": prompt})))
+        .collect()
+}
+
+/// Hold an answer, dropping the oldest ones beyond the limit
+pub fn stash_entry(entries: &mut Vec<ScratchpadEntry>, entry: ScratchpadEntry) {
+    entries.push(entry);
+    let overflow = entries.len().saturating_sub(MAX_SCRATCHPAD_ENTRIES);
+    entries.drain(..overflow);
+}
+
+/// Take a held answer out of the scratchpad
+pub fn take_entry(entries: &mut Vec<ScratchpadEntry>, id: &str) -> Option<ScratchpadEntry> {
+    let index = entries.iter().position(|entry| entry.id == id)?;
+    Some(entries.remove(index))
+}
+
+/// Thread message for a kept question or answer
+pub fn text_message(thread_id: &str, role: &str, text: &str, scratchpad_id: &str) -> Value {
+    let now = chrono::Utc::now().timestamp_millis();
+    json!({
+        "object": "message",
+        "thread_id": thread_id,
+        "role": role,
+        "content": [{"type": "text", "text": {"value": text, "annotations": []}}],
+        "status": "ready",
+        "created_at": now,
+        "completed_at": now,
+        "metadata": {SCRATCHPAD_METADATA_KEY: scratchpad_id},
+    })
+}
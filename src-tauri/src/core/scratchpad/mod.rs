@@ -0,0 +1,27 @@
+/*!
+   Scratchpad
+
+   Quick side questions about a thread that don't become part of it. A scratchpad run works on
+   a copy of the thread context, assembled like the context of a regular message, with the
+   question appended, and goes through the agent loop. Nothing is written to the thread: the
+   answer is kept in memory until the user keeps it, which adds the question and the answer
+   to the thread, or discards it. Only the most recent answers are held.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
+
+use tokio::sync::Mutex;
+
+use models::ScratchpadEntry;
+
+/// Scratchpad answers waiting to be kept or discarded, oldest first
+#[derive(Default)]
+pub struct ScratchpadState {
+    pub entries: Mutex<Vec<ScratchpadEntry>>,
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::core::agent::models::AgentRunResult;
+use crate::core::citations::models::SourceChunk;
+
+/// A side question about a thread
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScratchpadRequest {
+    pub thread_id: String,
+    pub model: String,
+    pub prompt: String,
+    /// Model context window size in tokens; the thread context is assembled to fit it
+    pub context_size: usize,
+    #[serde(default)]
+    pub reserve_tokens: Option<usize>,
+    #[serde(default)]
+    pub system_prompt: Option<String>,
+    /// Model summarizing history that doesn't fit; it is dropped when absent
+    #[serde(default)]
+    pub summarizer_model: Option<String>,
+    #[serde(default)]
+    pub sources: Vec<SourceChunk>,
+    /// Client-chosen run id used for events and cancellation; generated when absent
+    #[serde(default)]
+    pub run_id: Option<String>,
+    #[serde(default)]
+    pub assistant_id: Option<String>,
+    #[serde(default)]
+    pub parameters: Map<String, Value>,
+    #[serde(default)]
+    pub max_iterations: Option<usize>,
+}
+
+/// Answer of a scratchpad run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScratchpadResult {
+    /// Id to keep or discard the answer by; the run id of the agent run
+    pub scratchpad_id: String,
+    pub run: AgentRunResult,
+    /// Thread messages the run saw verbatim
+    pub included_message_ids: Vec<String>,
+}
+
+/// An answer held until the user keeps or discards it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScratchpadEntry {
+    pub id: String,
+    pub thread_id: String,
+    pub prompt: String,
+    pub content: String,
+    pub created_at: i64,
+}
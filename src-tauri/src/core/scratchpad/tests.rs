@@ -0,0 +1,86 @@
+use super::constants::{MAX_SCRATCHPAD_ENTRIES, SCRATCHPAD_METADATA_KEY};
+use super::helpers::{
+    scratchpad_context_request, scratchpad_messages, stash_entry, take_entry, text_message,
+};
+use super::models::{ScratchpadEntry, ScratchpadRequest};
+use crate::core::context::constants::DEFAULT_RESPONSE_RESERVE_TOKENS;
+use crate::core::context::helpers::estimate_message_tokens;
+use crate::core::context::models::ChatMessage;
+use serde_json::json;
+
+fn entry(id: &str) -> ScratchpadEntry {
+    ScratchpadEntry {
+        id: id.to_string(),
+        thread_id: "thread".to_string(),
+        prompt: "What did we decide?".to_string(),
+        content: "To ship on Friday.".to_string(),
+        created_at: 0,
+    }
+}
+
+#[test]
+fn test_scratchpad_context_request_reserves_prompt() {
+    let request: ScratchpadRequest = serde_json::from_value(json!({
+        "thread_id": "thread",
+        "model": "llama",
+        "prompt": "Summarize the open questions so far",
+        "context_size": 4096,
+        "system_prompt": "Be brief",
+    }))
+    .unwrap();
+    let context = scratchpad_context_request(&request);
+    assert_eq!(context.thread_id, "thread");
+    assert_eq!(context.context_size, 4096);
+    assert_eq!(context.system_prompt.as_deref(), Some("Be brief"));
+    assert_eq!(
+        context.reserve_tokens,
+        Some(DEFAULT_RESPONSE_RESERVE_TOKENS + estimate_message_tokens(&request.prompt))
+    );
+}
+
+#[test]
+fn test_scratchpad_messages_append_prompt() {
+    let context = vec![
+        ChatMessage {
+            role: "system".to_string(),
+            content: "Be brief".to_string(),
+        },
+        ChatMessage {
+            role: "user".to_string(),
+            content: "Hi".to_string(),
+        },
+    ];
+    let messages = scratchpad_messages(&context, "Side question");
+    assert_eq!(messages.len(), 3);
+    assert_eq!(
+        messages[0],
+        json!({"role": "system", "content": "Be brief"})
+    );
+    assert_eq!(
+        messages[2],
+        json!({"role": "user", "content": "Side question"})
+    );
+}
+
+#[test]
+fn test_stash_and_take_entries() {
+    let mut entries = Vec::new();
+    for i in 0..MAX_SCRATCHPAD_ENTRIES + 2 {
+        stash_entry(&mut entries, entry(&i.to_string()));
+    }
+    assert_eq!(entries.len(), MAX_SCRATCHPAD_ENTRIES);
+    assert_eq!(entries[0].id, "2");
+
+    assert_eq!(take_entry(&mut entries, "5"), Some(entry("5")));
+    assert_eq!(take_entry(&mut entries, "5"), None);
+    assert_eq!(entries.len(), MAX_SCRATCHPAD_ENTRIES - 1);
+}
+
+#[test]
+fn test_text_message_marks_scratchpad() {
+    let message = text_message("thread", "assistant", "Answer", "pad");
+    assert_eq!(message["thread_id"], "thread");
+    assert_eq!(message["role"], "assistant");
+    assert_eq!(message["content"][0]["text"]["value"], "Answer");
+    assert_eq!(message["metadata"][SCRATCHPAD_METADATA_KEY], "pad");
+}
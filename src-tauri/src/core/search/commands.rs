@@ -0,0 +1,39 @@
+use tauri::{AppHandle, Runtime};
+
+use super::helpers::{read_settings, run_search, write_settings};
+use super::models::{SearchQuery, SearchResponse, SearchSettings};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::offline::constants::OFFLINE_ERROR;
+use crate::core::offline::helpers::is_offline;
+
+/// Returns the web search settings.
+#[tauri::command]
+pub async fn get_search_settings<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<SearchSettings, String> {
+    Ok(read_settings(&get_jan_data_folder_path(app_handle)))
+}
+
+/// Replaces the web search settings.
+#[tauri::command]
+pub async fn update_search_settings<R: Runtime>(
+    app_handle: AppHandle<R>,
+    settings: SearchSettings,
+) -> Result<SearchSettings, String> {
+    write_settings(&get_jan_data_folder_path(app_handle), &settings)?;
+    Ok(settings)
+}
+
+/// Searches the web with the requested or configured engine and returns normalized,
+/// numbered results with citation metadata.
+#[tauri::command]
+pub async fn web_search<R: Runtime>(
+    app_handle: AppHandle<R>,
+    query: SearchQuery,
+) -> Result<SearchResponse, String> {
+    if is_offline(&app_handle) {
+        return Err(OFFLINE_ERROR.to_string());
+    }
+    let settings = read_settings(&get_jan_data_folder_path(app_handle));
+    run_search(&settings, query).await
+}
@@ -0,0 +1,22 @@
+use std::time::Duration;
+
+pub const SEARCH_DIR: &str = "search";
+pub const SEARCH_SETTINGS_FILE: &str = "settings.json";
+
+/// Name of the built-in search tool offered to the agent loop
+pub const WEB_SEARCH_TOOL: &str = "web_search";
+
+pub const DEFAULT_MAX_RESULTS: usize = 5;
+pub const MAX_RESULTS_LIMIT: usize = 20;
+/// Snippets are cut to this many characters to keep tool output small
+pub const MAX_SNIPPET_CHARS: usize = 400;
+
+pub const SEARCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+pub const BRAVE_SEARCH_URL: &str = "https://api.search.brave.com/res/v1/web/search";
+pub const TAVILY_SEARCH_URL: &str = "https://api.tavily.com/search";
+
+/// Minimum time between two requests to the same engine
+pub const BRAVE_MIN_INTERVAL: Duration = Duration::from_millis(1100);
+pub const TAVILY_MIN_INTERVAL: Duration = Duration::from_millis(500);
+pub const SEARXNG_MIN_INTERVAL: Duration = Duration::from_millis(250);
@@ -0,0 +1,214 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use super::constants::{
+    BRAVE_MIN_INTERVAL, BRAVE_SEARCH_URL, SEARXNG_MIN_INTERVAL, TAVILY_MIN_INTERVAL,
+    TAVILY_SEARCH_URL,
+};
+use super::models::{SearchEngineKind, SearchQuery, SearchResult, SearchSettings};
+
+/// A web search backend. Implementations return raw results; normalization, numbering and
+/// rate limiting are applied by the caller.
+#[async_trait]
+pub trait SearchEngine: Send + Sync {
+    fn kind(&self) -> SearchEngineKind;
+
+    /// Minimum time between two requests to this engine
+    fn min_interval(&self) -> Duration;
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &SearchQuery,
+        max_results: usize,
+    ) -> Result<Vec<SearchResult>, String>;
+}
+
+async fn json_response(response: reqwest::Response, engine: &str) -> Result<Value, String> {
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!(
+            "{engine} search failed with status {status}: {text}"
+        ));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("Invalid {engine} response: {e}"))
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+fn collect_results(
+    items: Option<&Value>,
+    snippet_key: &str,
+    published_keys: &[&str],
+) -> Vec<SearchResult> {
+    items
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|item| {
+            Some(SearchResult {
+                title: str_field(item, "title").unwrap_or_default(),
+                url: str_field(item, "url")?,
+                snippet: str_field(item, snippet_key).unwrap_or_default(),
+                published: published_keys.iter().find_map(|key| str_field(item, key)),
+            })
+        })
+        .collect()
+}
+
+pub fn parse_searxng_results(body: &Value) -> Vec<SearchResult> {
+    collect_results(body.get("results"), "content", &["publishedDate"])
+}
+
+pub fn parse_brave_results(body: &Value) -> Vec<SearchResult> {
+    collect_results(
+        body.pointer("/web/results"),
+        "description",
+        &["page_age", "age"],
+    )
+}
+
+pub fn parse_tavily_results(body: &Value) -> Vec<SearchResult> {
+    collect_results(body.get("results"), "content", &["published_date"])
+}
+
+pub struct SearxngEngine {
+    pub base_url: String,
+}
+
+#[async_trait]
+impl SearchEngine for SearxngEngine {
+    fn kind(&self) -> SearchEngineKind {
+        SearchEngineKind::Searxng
+    }
+
+    fn min_interval(&self) -> Duration {
+        SEARXNG_MIN_INTERVAL
+    }
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &SearchQuery,
+        _max_results: usize,
+    ) -> Result<Vec<SearchResult>, String> {
+        let mut params = vec![("q", query.query.clone()), ("format", "json".to_string())];
+        if let Some(language) = &query.language {
+            params.push(("language", language.clone()));
+        }
+        let response = client
+            .get(format!("{}/search", self.base_url.trim_end_matches('/')))
+            .query(&params)
+            .send()
+            .await
+            .map_err(|e| format!("SearxNG request failed: {e}"))?;
+        Ok(parse_searxng_results(
+            &json_response(response, "SearxNG").await?,
+        ))
+    }
+}
+
+pub struct BraveEngine {
+    pub api_key: String,
+}
+
+#[async_trait]
+impl SearchEngine for BraveEngine {
+    fn kind(&self) -> SearchEngineKind {
+        SearchEngineKind::Brave
+    }
+
+    fn min_interval(&self) -> Duration {
+        BRAVE_MIN_INTERVAL
+    }
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &SearchQuery,
+        max_results: usize,
+    ) -> Result<Vec<SearchResult>, String> {
+        let mut params = vec![
+            ("q", query.query.clone()),
+            ("count", max_results.to_string()),
+        ];
+        if let Some(language) = &query.language {
+            params.push(("search_lang", language.clone()));
+        }
+        let response = client
+            .get(BRAVE_SEARCH_URL)
+            .header("X-Subscription-Token", &self.api_key)
+            .header("Accept", "application/json")
+            .query(&params)
+            .send()
+            .await
+            .map_err(|e| format!("Brave request failed: {e}"))?;
+        Ok(parse_brave_results(
+            &json_response(response, "Brave").await?,
+        ))
+    }
+}
+
+pub struct TavilyEngine {
+    pub api_key: String,
+}
+
+#[async_trait]
+impl SearchEngine for TavilyEngine {
+    fn kind(&self) -> SearchEngineKind {
+        SearchEngineKind::Tavily
+    }
+
+    fn min_interval(&self) -> Duration {
+        TAVILY_MIN_INTERVAL
+    }
+
+    async fn search(
+        &self,
+        client: &reqwest::Client,
+        query: &SearchQuery,
+        max_results: usize,
+    ) -> Result<Vec<SearchResult>, String> {
+        let response = client
+            .post(TAVILY_SEARCH_URL)
+            .bearer_auth(&self.api_key)
+            .json(&json!({
+                "query": query.query,
+                "max_results": max_results,
+                "search_depth": "basic",
+            }))
+            .send()
+            .await
+            .map_err(|e| format!("Tavily request failed: {e}"))?;
+        Ok(parse_tavily_results(
+            &json_response(response, "Tavily").await?,
+        ))
+    }
+}
+
+/// Build the engine of the given kind from the settings, failing when it is not configured.
+pub fn build_engine(
+    settings: &SearchSettings,
+    kind: SearchEngineKind,
+) -> Result<Box<dyn SearchEngine>, String> {
+    let non_empty = |value: &Option<String>| value.clone().filter(|v| !v.trim().is_empty());
+    match kind {
+        SearchEngineKind::Searxng => non_empty(&settings.searxng_url)
+            .map(|base_url| Box::new(SearxngEngine { base_url }) as Box<dyn SearchEngine>)
+            .ok_or_else(|| "SearxNG URL is not configured".to_string()),
+        SearchEngineKind::Brave => non_empty(&settings.brave_api_key)
+            .map(|api_key| Box::new(BraveEngine { api_key }) as Box<dyn SearchEngine>)
+            .ok_or_else(|| "Brave API key is not configured".to_string()),
+        SearchEngineKind::Tavily => non_empty(&settings.tavily_api_key)
+            .map(|api_key| Box::new(TavilyEngine { api_key }) as Box<dyn SearchEngine>)
+            .ok_or_else(|| "Tavily API key is not configured".to_string()),
+    }
+}
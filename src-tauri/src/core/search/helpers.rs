@@ -0,0 +1,229 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Instant;
+
+use serde_json::{json, Map, Value};
+use tokio::sync::Mutex;
+
+use super::constants::{
+    DEFAULT_MAX_RESULTS, MAX_RESULTS_LIMIT, MAX_SNIPPET_CHARS, SEARCH_DIR, SEARCH_SETTINGS_FILE,
+    SEARCH_TIMEOUT, WEB_SEARCH_TOOL,
+};
+use super::engines::{build_engine, SearchEngine};
+use super::models::{
+    Citation, SearchEngineKind, SearchQuery, SearchResponse, SearchResult, SearchSettings,
+};
+use crate::core::mcp::models::ToolWithServer;
+
+// Time of the last request per engine, used to space requests out
+static LAST_REQUESTS: OnceLock<Mutex<HashMap<SearchEngineKind, Instant>>> = OnceLock::new();
+
+pub fn get_settings_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(SEARCH_DIR).join(SEARCH_SETTINGS_FILE)
+}
+
+/// Read search settings, defaulting to search being disabled
+pub fn read_settings(data_folder: &Path) -> SearchSettings {
+    fs::read_to_string(get_settings_path(data_folder))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+pub fn write_settings(data_folder: &Path, settings: &SearchSettings) -> Result<(), String> {
+    let path = get_settings_path(data_folder);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let data = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    fs::write(path, data).map_err(|e| e.to_string())
+}
+
+/// Remove HTML tags and decode the few entities search engines put into snippets.
+pub fn strip_html(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => output.push(c),
+            _ => {}
+        }
+    }
+    let output = output
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&");
+    output.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => format!("{}…", text[..end].trim_end()),
+        None => text.to_string(),
+    }
+}
+
+/// Clean up raw engine results: strip markup, drop results without a usable URL and
+/// duplicates (ignoring a trailing slash and fragment), cap snippets and the result count.
+pub fn normalize_results(results: Vec<SearchResult>, max_results: usize) -> Vec<SearchResult> {
+    let mut seen = HashSet::new();
+    results
+        .into_iter()
+        .filter_map(|result| {
+            let url = result.url.trim().to_string();
+            if !(url.starts_with("http://") || url.starts_with("https://")) {
+                return None;
+            }
+            let key = url
+                .split('#')
+                .next()
+                .unwrap_or(&url)
+                .trim_end_matches('/')
+                .to_string();
+            if !seen.insert(key) {
+                return None;
+            }
+            let title = strip_html(&result.title);
+            Some(SearchResult {
+                title: if title.is_empty() { url.clone() } else { title },
+                snippet: truncate_chars(&strip_html(&result.snippet), MAX_SNIPPET_CHARS),
+                published: result.published.filter(|p| !p.trim().is_empty()),
+                url,
+            })
+        })
+        .take(max_results)
+        .collect()
+}
+
+/// Citation entries numbered from 1 in result order
+pub fn citations(results: &[SearchResult]) -> Vec<Citation> {
+    let accessed_at = chrono::Utc::now().to_rfc3339();
+    results
+        .iter()
+        .enumerate()
+        .map(|(i, result)| Citation {
+            index: i + 1,
+            title: result.title.clone(),
+            url: result.url.clone(),
+            accessed_at: accessed_at.clone(),
+        })
+        .collect()
+}
+
+/// Wait until the engine's minimum interval since its previous request has passed.
+async fn wait_for_slot(engine: &dyn SearchEngine) {
+    let last_requests = LAST_REQUESTS.get_or_init(|| Mutex::new(HashMap::new()));
+    let mut last_requests = last_requests.lock().await;
+    if let Some(last) = last_requests.get(&engine.kind()) {
+        let elapsed = last.elapsed();
+        if elapsed < engine.min_interval() {
+            // Holding the lock queues concurrent searches behind this one
+            tokio::time::sleep(engine.min_interval() - elapsed).await;
+        }
+    }
+    last_requests.insert(engine.kind(), Instant::now());
+}
+
+/// Run a query against the requested or configured engine.
+pub async fn run_search(
+    settings: &SearchSettings,
+    query: SearchQuery,
+) -> Result<SearchResponse, String> {
+    if query.query.trim().is_empty() {
+        return Err("Search query cannot be empty".to_string());
+    }
+    let kind = query
+        .engine
+        .or(settings.engine)
+        .ok_or_else(|| "No search engine is configured".to_string())?;
+    let engine = build_engine(settings, kind)?;
+    let max_results = query
+        .max_results
+        .or(settings.max_results)
+        .unwrap_or(DEFAULT_MAX_RESULTS)
+        .clamp(1, MAX_RESULTS_LIMIT);
+
+    let client = reqwest::Client::builder()
+        .timeout(SEARCH_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    wait_for_slot(engine.as_ref()).await;
+    let results = engine.search(&client, &query, max_results).await?;
+    let results = normalize_results(results, max_results);
+    Ok(SearchResponse {
+        citations: citations(&results),
+        query: query.query,
+        engine: kind,
+        results,
+    })
+}
+
+/// Render results as numbered text for the model, with an instruction to cite them.
+pub fn format_for_model(response: &SearchResponse) -> String {
+    if response.results.is_empty() {
+        return format!("No results found for \"{}\".", response.query);
+    }
+    let mut text = format!(
+        "Search results for \"{}\". Cite sources as [n].\n",
+        response.query
+    );
+    for (i, result) in response.results.iter().enumerate() {
+        text.push_str(&format!("\n[{}] {}\n{}\n", i + 1, result.title, result.url));
+        if let Some(published) = &result.published {
+            text.push_str(&format!("Published: {published}\n"));
+        }
+        if !result.snippet.is_empty() {
+            text.push_str(&format!("{}\n", result.snippet));
+        }
+    }
+    text
+}
+
+/// Definition of the built-in search tool.
+pub fn web_search_tool(server: &str) -> ToolWithServer {
+    ToolWithServer {
+        name: WEB_SEARCH_TOOL.to_string(),
+        description: Some(
+            "Search the web and return numbered results with titles, URLs and snippets."
+                .to_string(),
+        ),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "query": {"type": "string", "description": "Search query"},
+                "max_results": {
+                    "type": "integer",
+                    "description": "Number of results to return",
+                    "minimum": 1,
+                    "maximum": MAX_RESULTS_LIMIT,
+                }
+            },
+            "required": ["query"]
+        }),
+        server: server.to_string(),
+    }
+}
+
+/// Parse the built-in tool's arguments into a query.
+pub fn query_from_arguments(arguments: &Map<String, Value>) -> Result<SearchQuery, String> {
+    let query = arguments
+        .get("query")
+        .and_then(Value::as_str)
+        .ok_or_else(|| "Missing 'query' argument".to_string())?;
+    Ok(SearchQuery {
+        query: query.to_string(),
+        max_results: arguments
+            .get("max_results")
+            .and_then(Value::as_u64)
+            .map(|n| n as usize),
+        language: None,
+        engine: None,
+    })
+}
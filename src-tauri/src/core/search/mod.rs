@@ -0,0 +1,27 @@
+/*!
+   Web Search
+
+   Built-in web search for users without a search MCP server. Three engines sit behind the
+   `SearchEngine` trait:
+   - SearxNG: a self-hosted or public instance with the JSON output format enabled
+   - Brave Search API (API key)
+   - Tavily (API key)
+
+   Results are normalized (HTML stripped from snippets, duplicate URLs dropped, length capped)
+   and numbered so answers can cite them as `[n]`; every response carries citation metadata.
+   Requests to each engine are spaced out by a minimum interval to stay within free-tier rate
+   limits.
+
+   Settings (engine, endpoint and keys) are stored in `search/settings.json`. Search is exposed
+   as the `web_search` command and, when enabled, as a built-in `web_search` tool offered to
+   the agent loop unless an MCP server already provides a tool with that name.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod engines;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchEngineKind {
+    Searxng,
+    Brave,
+    Tavily,
+}
+
+/// Contents of `search/settings.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchSettings {
+    /// Engine used when a request does not name one; search is disabled when unset
+    #[serde(default)]
+    pub engine: Option<SearchEngineKind>,
+    /// Base URL of the SearxNG instance, e.g. `http://localhost:8888`
+    #[serde(default)]
+    pub searxng_url: Option<String>,
+    #[serde(default)]
+    pub brave_api_key: Option<String>,
+    #[serde(default)]
+    pub tavily_api_key: Option<String>,
+    #[serde(default)]
+    pub max_results: Option<usize>,
+    /// Offer search to the agent loop as the built-in `web_search` tool
+    #[serde(default)]
+    pub builtin_tool_enabled: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchQuery {
+    pub query: String,
+    #[serde(default)]
+    pub max_results: Option<usize>,
+    /// Preferred result language, e.g. `en` or `de`
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub engine: Option<SearchEngineKind>,
+}
+
+/// A search result normalized across engines
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub title: String,
+    pub url: String,
+    pub snippet: String,
+    #[serde(default)]
+    pub published: Option<String>,
+}
+
+/// Metadata needed to cite a result as `[index]`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Citation {
+    pub index: usize,
+    pub title: String,
+    pub url: String,
+    /// RFC 3339 time the result was retrieved
+    pub accessed_at: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchResponse {
+    pub query: String,
+    pub engine: SearchEngineKind,
+    pub results: Vec<SearchResult>,
+    pub citations: Vec<Citation>,
+}
@@ -0,0 +1,123 @@
+use super::engines::{
+    build_engine, parse_brave_results, parse_searxng_results, parse_tavily_results,
+};
+use super::helpers::{
+    citations, format_for_model, normalize_results, query_from_arguments, run_search, strip_html,
+};
+use super::models::{SearchEngineKind, SearchQuery, SearchResponse, SearchResult, SearchSettings};
+use serde_json::json;
+
+fn result(title: &str, url: &str, snippet: &str) -> SearchResult {
+    SearchResult {
+        title: title.to_string(),
+        url: url.to_string(),
+        snippet: snippet.to_string(),
+        published: None,
+    }
+}
+
+#[test]
+fn test_parse_engine_responses() {
+    let searxng = parse_searxng_results(&json!({
+        "results": [{"title": "Rust", "url": "https://rust-lang.org", "content": "A language", "publishedDate": "2024-01-01"}]
+    }));
+    assert_eq!(searxng[0].snippet, "A language");
+    assert_eq!(searxng[0].published.as_deref(), Some("2024-01-01"));
+
+    let brave = parse_brave_results(&json!({
+        "web": {"results": [{"title": "Tokio", "url": "https://tokio.rs", "description": "An <strong>async</strong> runtime", "age": "2 days ago"}]}
+    }));
+    assert_eq!(brave[0].url, "https://tokio.rs");
+    assert_eq!(brave[0].published.as_deref(), Some("2 days ago"));
+
+    let tavily = parse_tavily_results(&json!({
+        "results": [{"title": "Serde", "url": "https://serde.rs", "content": "Serialization"}, {"title": "No url"}]
+    }));
+    assert_eq!(tavily.len(), 1);
+}
+
+#[test]
+fn test_strip_html() {
+    assert_eq!(
+        strip_html("An <strong>async</strong>\n runtime &amp; more"),
+        "An async runtime & more"
+    );
+}
+
+#[test]
+fn test_normalize_dedupes_and_limits() {
+    let results = vec![
+        result("<b>Rust</b>", "https://rust-lang.org/", "one"),
+        result("Rust again", "https://rust-lang.org#learn", "dup"),
+        result("Bad", "javascript:alert(1)", "x"),
+        result("", "https://doc.rust-lang.org", &"long ".repeat(200)),
+        result("Third", "https://crates.io", "three"),
+    ];
+    let normalized = normalize_results(results, 2);
+    assert_eq!(normalized.len(), 2);
+    assert_eq!(normalized[0].title, "Rust");
+    assert_eq!(normalized[1].title, "https://doc.rust-lang.org");
+    assert!(normalized[1].snippet.ends_with('…'));
+    assert!(normalized[1].snippet.chars().count() <= 401);
+}
+
+#[test]
+fn test_format_for_model_numbers_results() {
+    let results = vec![
+        result("Rust", "https://rust-lang.org", "A language"),
+        result("Tokio", "https://tokio.rs", ""),
+    ];
+    let response = SearchResponse {
+        query: "rust async".to_string(),
+        engine: SearchEngineKind::Brave,
+        citations: citations(&results),
+        results,
+    };
+    assert_eq!(response.citations[1].index, 2);
+    let text = format_for_model(&response);
+    assert!(text.contains("[1] Rust\nhttps://rust-lang.org\nA language"));
+    assert!(text.contains("[2] Tokio"));
+}
+
+#[test]
+fn test_engine_requires_configuration() {
+    let settings = SearchSettings {
+        brave_api_key: Some("  ".to_string()),
+        ..Default::default()
+    };
+    assert!(build_engine(&settings, SearchEngineKind::Brave).is_err());
+    assert!(build_engine(&settings, SearchEngineKind::Searxng).is_err());
+    let settings = SearchSettings {
+        tavily_api_key: Some("key".to_string()),
+        ..Default::default()
+    };
+    assert_eq!(
+        build_engine(&settings, SearchEngineKind::Tavily)
+            .unwrap()
+            .kind(),
+        SearchEngineKind::Tavily
+    );
+}
+
+#[tokio::test]
+async fn test_search_without_engine_fails() {
+    let query = SearchQuery {
+        query: "rust".to_string(),
+        max_results: None,
+        language: None,
+        engine: None,
+    };
+    let err = run_search(&SearchSettings::default(), query)
+        .await
+        .unwrap_err();
+    assert!(err.contains("No search engine"));
+}
+
+#[test]
+fn test_query_from_tool_arguments() {
+    let arguments = json!({"query": "rust", "max_results": 3});
+    let query = query_from_arguments(arguments.as_object().unwrap()).unwrap();
+    assert_eq!(query.query, "rust");
+    assert_eq!(query.max_results, Some(3));
+    assert!(query_from_arguments(&Default::default()).is_err());
+}
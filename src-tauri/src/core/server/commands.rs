@@ -2,11 +2,21 @@ use tauri::{AppHandle, Manager, Runtime, State};
 use tauri_plugin_llamacpp::state::LlamacppState;
 use tauri_plugin_mlx::state::MlxState;
 
+use crate::core::guardrails::Guardrails;
+use crate::core::mcp::admin::McpAdminHandle;
+use crate::core::messages::models::{LocalizedMessage, MessageCode};
+use crate::core::offline::OfflineMode;
+use crate::core::param_profiles::ParamProfiles;
+use crate::core::peers::PeerAccess;
+use crate::core::redaction::RedactionFilter;
+use crate::core::scheduler::GenerationScheduler;
 use crate::core::server::proxy;
+use crate::core::settings::helpers::record_server_settings;
+use crate::core::settings::models::ServerSettings;
 use crate::core::state::AppState;
 
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, Clone)]
 pub struct StartServerConfig {
     pub host: String,
     pub port: u16,
@@ -16,10 +26,41 @@ pub struct StartServerConfig {
     pub proxy_timeout: u64,
 }
 
-#[tauri::command]
-pub async fn start_server<R: Runtime>(
-    app_handle: AppHandle<R>,
-    state: State<'_, AppState>,
+impl From<StartServerConfig> for ServerSettings {
+    fn from(config: StartServerConfig) -> Self {
+        Self {
+            host: config.host,
+            port: config.port,
+            prefix: config.prefix,
+            api_key: config.api_key,
+            trusted_hosts: config.trusted_hosts,
+            proxy_timeout: config.proxy_timeout,
+        }
+    }
+}
+
+impl From<ServerSettings> for StartServerConfig {
+    fn from(settings: ServerSettings) -> Self {
+        Self {
+            host: settings.host,
+            port: settings.port,
+            prefix: settings.prefix,
+            api_key: settings.api_key,
+            trusted_hosts: settings.trusted_hosts,
+            proxy_timeout: settings.proxy_timeout,
+        }
+    }
+}
+
+fn stop_error(error: impl std::fmt::Display) -> String {
+    LocalizedMessage::new(MessageCode::ServerStopFailed)
+        .with("reason", error)
+        .into()
+}
+
+async fn start_proxy<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    state: &AppState,
     config: StartServerConfig,
 ) -> Result<u16, String> {
     let StartServerConfig {
@@ -30,6 +71,8 @@ pub async fn start_server<R: Runtime>(
         trusted_hosts,
         proxy_timeout,
     } = config;
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    let advertised = (host.clone(), prefix.clone(), !api_key.is_empty());
     let server_handle = state.server_handle.clone();
     let llama_state: State<LlamacppState> = app_handle.state();
     let sessions = llama_state.llama_server_process.clone();
@@ -48,19 +91,73 @@ pub async fn start_server<R: Runtime>(
         vec![trusted_hosts],
         proxy_timeout,
         state.provider_configs.clone(),
+        app_handle.state::<GenerationScheduler>().inner().clone(),
+        app_handle.state::<RedactionFilter>().inner().clone(),
+        app_handle.state::<ParamProfiles>().inner().clone(),
+        app_handle.state::<Guardrails>().inner().clone(),
+        app_handle.state::<OfflineMode>().inner().clone(),
+        McpAdminHandle::new(app_handle.clone()),
+        app_handle.state::<PeerAccess>().inner().clone(),
     )
     .await
-    .map_err(|e| e.to_string())?;
+    .map_err(|e| match e.downcast::<LocalizedMessage>() {
+        Ok(message) => String::from(*message),
+        Err(e) => LocalizedMessage::new(MessageCode::ServerStartFailed)
+            .with("reason", e)
+            .into(),
+    })?;
+
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    {
+        let (host, prefix, requires_api_key) = advertised;
+        crate::core::lan::helpers::update_advertisement(
+            app_handle,
+            &host,
+            actual_port,
+            &prefix,
+            requires_api_key,
+        );
+    }
+    Ok(actual_port)
+}
+
+#[tauri::command]
+pub async fn start_server<R: Runtime>(
+    app_handle: AppHandle<R>,
+    state: State<'_, AppState>,
+    config: StartServerConfig,
+) -> Result<u16, String> {
+    let actual_port = start_proxy(&app_handle, &state, config.clone()).await?;
+    record_server_settings(&app_handle, config.into()).await;
     Ok(actual_port)
 }
 
+/// Restart a running server with `settings`; a stopped server stays stopped
+pub async fn restart_server_if_running<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    settings: &ServerSettings,
+) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    if state.server_handle.lock().await.is_none() {
+        return Ok(());
+    }
+    log::info!("Restarting the local API server with updated settings");
+    proxy::stop_server(state.server_handle.clone())
+        .await
+        .map_err(stop_error)?;
+    start_proxy(app_handle, &state, settings.clone().into()).await?;
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn stop_server(state: State<'_, AppState>) -> Result<(), String> {
     let server_handle = state.server_handle.clone();
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    crate::core::lan::helpers::withdraw_advertisement();
 
     proxy::stop_server(server_handle)
         .await
-        .map_err(|e| e.to_string())?;
+        .map_err(stop_error)?;
     Ok(())
 }
 
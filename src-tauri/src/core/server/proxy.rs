@@ -1,5 +1,6 @@
 use futures_util::StreamExt;
-use hyper::body::Bytes;
+use hyper::body::{Bytes, HttpBody};
+use hyper::server::conn::AddrStream;
 use hyper::service::{make_service_fn, service_fn};
 use hyper::{Body, Request, Response, Server, StatusCode};
 use jan_utils::{is_cors_header, is_valid_host, remove_prefix};
@@ -10,9 +11,42 @@ use std::convert::Infallible;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tauri_plugin_llamacpp::LLamaBackendSession;
-use tokio::sync::Mutex;
-
-use crate::core::state::{ProviderConfig, ServerHandle};
+use tokio::sync::{Mutex, OwnedSemaphorePermit};
+use tracing::Instrument;
+
+use crate::core::embeddings::helpers::{create_embeddings, find_embedding_endpoint};
+use crate::core::embeddings::models::EmbeddingRequest;
+use crate::core::guardrails::helpers::budget_key;
+use crate::core::guardrails::Guardrails;
+use crate::core::inference::cache_control::apply_anthropic_cache_control;
+use crate::core::inference::models::{
+    ProviderErrorKind, RequestPolicy, StreamEvent, StreamFormat, ToolSchemaLimits,
+};
+use crate::core::inference::retry::{next_chunk, policy_client, send_with_retry};
+use crate::core::inference::stream::{SseData, SseLineBuffer, StreamNormalizer};
+use crate::core::inference::tool_schema::compact_request_tools;
+use crate::core::mcp::admin::{handle_admin_request, is_admin_path, McpAdminHandle};
+use crate::core::mcp::metrics::{render_metrics, PROMETHEUS_CONTENT_TYPE};
+use crate::core::messages::models::{LocalizedMessage, MessageCode};
+use crate::core::offline::OfflineMode;
+use crate::core::param_profiles::constants::ASSISTANT_ID_HEADER;
+use crate::core::param_profiles::helpers::apply_defaults;
+use crate::core::param_profiles::models::ResolveParamsRequest;
+use crate::core::param_profiles::ParamProfiles;
+use crate::core::peers::constants::{PEER_CONFIRM_PATH, PEER_PAIR_PATH, PEER_PATHS};
+use crate::core::peers::helpers::{
+    handle_pairing_request, peer_token, requested_model, throttle_delay,
+};
+use crate::core::peers::models::PeerGrant;
+use crate::core::peers::PeerAccess;
+use crate::core::redaction::constants::THREAD_ID_HEADER;
+use crate::core::redaction::RedactionFilter;
+use crate::core::scheduler::models::GenerationPriority;
+use crate::core::scheduler::GenerationScheduler;
+use crate::core::state::{ServerHandle, SharedProviderConfigs};
+use crate::core::vision::helpers::{
+    has_inline_images, limits_for_url, local_limits, preprocess_messages_async,
+};
 
 /// Transform Anthropic /messages API body to OpenAI /chat/completions body
 fn transform_anthropic_to_openai(body: &serde_json::Value) -> Option<serde_json::Value> {
@@ -414,6 +448,8 @@ pub struct ProxyConfig {
     pub trusted_hosts: Vec<Vec<String>>,
     pub host: String,
     pub port: u16,
+    /// Overall timeout of upstream requests, in seconds
+    pub proxy_timeout: u64,
 }
 
 /// Determines the final destination path based on the original request path
@@ -430,7 +466,14 @@ async fn proxy_request(
     config: ProxyConfig,
     sessions: Arc<Mutex<HashMap<i32, LLamaBackendSession>>>,
     mlx_sessions: Arc<Mutex<HashMap<i32, MlxBackendSession>>>,
-    provider_configs: Arc<Mutex<HashMap<String, ProviderConfig>>>,
+    provider_configs: SharedProviderConfigs,
+    scheduler: GenerationScheduler,
+    redaction: RedactionFilter,
+    profiles: ParamProfiles,
+    guardrails: Guardrails,
+    offline: OfflineMode,
+    mcp_admin: McpAdminHandle,
+    peer: Option<PeerGrant>,
 ) -> Result<Response<Body>, hyper::Error> {
     if req.method() == hyper::Method::OPTIONS {
         log::debug!(
@@ -610,7 +653,9 @@ async fn proxy_request(
     ];
     let is_whitelisted_path = whitelisted_paths.contains(&path.as_str());
 
-    if !is_whitelisted_path {
+    if let Some(peer) = &peer {
+        log::debug!("Bypassing host validation for LAN peer {}", peer.name);
+    } else if !is_whitelisted_path {
         if !host_header.is_empty() {
             if !is_valid_host(&host_header, &config.trusted_hosts) {
                 let mut error_response = Response::builder().status(StatusCode::FORBIDDEN);
@@ -640,7 +685,7 @@ async fn proxy_request(
         log::debug!("Bypassing host validation for whitelisted path: {path}");
     }
 
-    if !is_whitelisted_path && !config.proxy_api_key.is_empty() {
+    if !is_whitelisted_path && !config.proxy_api_key.is_empty() && peer.is_none() {
         // Check Authorization header (Bearer token)
         let auth_valid = parts
             .headers
@@ -694,6 +739,34 @@ async fn proxy_request(
     let mut buffered_body: Option<Bytes> = None;
     let mut target_base_url: Option<String> = None;
     let mut is_anthropic_messages = false;
+    // Set when the request is served by a local engine and must be scheduled
+    let mut local_model_id: Option<String> = None;
+    // Set when the request goes to a remote provider, whose timeouts and retries then apply
+    let mut provider_policy: Option<RequestPolicy> = None;
+    let mut prompt_caching = false;
+    // Set when the request goes to a remote provider that compacts advertised tool schemas
+    let mut tool_schema: Option<ToolSchemaLimits> = None;
+
+    if is_admin_path(&destination_path) {
+        let (status, body) = handle_admin_request(
+            &mcp_admin,
+            method.as_str(),
+            &destination_path,
+            parts.uri.query(),
+            !config.proxy_api_key.is_empty(),
+        )
+        .await;
+        let mut response_builder = Response::builder()
+            .status(StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR))
+            .header(hyper::header::CONTENT_TYPE, "application/json");
+        response_builder = add_cors_headers_with_host_and_origin(
+            response_builder,
+            &host_header,
+            &origin_header,
+            &config.trusted_hosts,
+        );
+        return Ok(response_builder.body(Body::from(body.to_string())).unwrap());
+    }
 
     match (method.clone(), destination_path.as_str()) {
         // Anthropic /messages endpoint - tries /messages first, falls back to /chat/completions on error
@@ -724,7 +797,7 @@ async fn proxy_request(
             match serde_json::from_slice::<serde_json::Value>(&body_bytes) {
                 Ok(json_body) => {
                     if let Some(model_id) = json_body.get("model").and_then(|v| v.as_str()) {
-                        let pc = provider_configs.lock().await;
+                        let pc = provider_configs.read().await;
 
                         // Try to find a provider for this model
                         let provider_name: Option<String> = pc
@@ -742,10 +815,12 @@ async fn proxy_request(
                             });
 
                         drop(pc);
+                        // Peers only get the models running on this machine
+                        let provider_name = provider_name.filter(|_| peer.is_none());
 
                         if let Some(ref p) = provider_name {
                             log::info!("Using remote provider '{p}' for model '{model_id}'");
-                            let pc2 = provider_configs.lock().await;
+                            let pc2 = provider_configs.read().await;
                             let provider_config = pc2.get(p.as_str()).cloned();
                             drop(pc2);
 
@@ -754,6 +829,9 @@ async fn proxy_request(
                                     format!("{}{}", url.trim_end_matches('/'), "/messages")
                                 });
                                 session_api_key = provider_cfg.api_key.clone();
+                                provider_policy = Some(RequestPolicy::from_provider(&provider_cfg));
+                                tool_schema = ToolSchemaLimits::from_provider(&provider_cfg);
+                                prompt_caching = provider_cfg.prompt_caching.unwrap_or(true);
                             }
                         } else {
                             // No remote provider, try local sessions
@@ -775,11 +853,13 @@ async fn proxy_request(
                                 session_api_key = Some(session.info.api_key.clone());
                                 target_base_url =
                                     Some(format!("http://127.0.0.1:{}/v1/messages", target_port));
+                                local_model_id = Some(model_id.to_string());
                             } else if let Some(info) = mlx_session_info {
                                 let target_port = info.port;
                                 session_api_key = Some(info.api_key.clone());
                                 target_base_url =
                                     Some(format!("http://127.0.0.1:{}/v1/messages", target_port));
+                                local_model_id = Some(model_id.to_string());
                             } else {
                                 log::warn!("No running session found for model_id: {model_id}");
                                 let mut error_response =
@@ -856,7 +936,7 @@ async fn proxy_request(
                         log::debug!("Extracted model_id: {model_id}");
 
                         // First, check if there's a registered remote provider for this model
-                        let pc = provider_configs.lock().await;
+                        let pc = provider_configs.read().await;
 
                         // Try to find a provider that has this model configured
                         let provider_name = pc
@@ -879,13 +959,15 @@ async fn proxy_request(
                             });
 
                         drop(pc);
+                        // Peers only get the models running on this machine
+                        let provider_name = provider_name.filter(|_| peer.is_none());
 
                         if let Some(ref provider) = provider_name {
                             // Found a remote provider, stream the response directly
                             log::info!("Found remote provider '{provider}' for model '{model_id}'");
 
                             // Get the provider config
-                            let pc2 = provider_configs.lock().await;
+                            let pc2 = provider_configs.read().await;
                             let provider_config = pc2.get(provider.as_str()).cloned();
 
                             // Log registered providers for debugging
@@ -907,6 +989,8 @@ async fn proxy_request(
                                 } else {
                                     session_api_key = None;
                                 }
+                                provider_policy = Some(RequestPolicy::from_provider(&provider_cfg));
+                                tool_schema = ToolSchemaLimits::from_provider(&provider_cfg);
                             } else {
                                 log::error!("Provider config not found for '{provider}'");
                             }
@@ -959,13 +1043,61 @@ async fn proxy_request(
                                     .unwrap());
                             }
 
-                            if let Some(session) = llama_session {
+                            if let Some(session) =
+                                llama_session.filter(|_| destination_path == "/embeddings")
+                            {
+                                // Embeddings are batched and post-processed here rather
+                                // than forwarded as-is
+                                let result = match find_embedding_endpoint(
+                                    &sessions_guard,
+                                    &session.info.model_id,
+                                ) {
+                                    Ok(endpoint) => {
+                                        drop(sessions_guard);
+                                        match serde_json::from_value::<EmbeddingRequest>(
+                                            json_body.clone(),
+                                        ) {
+                                            Ok(request) => create_embeddings(&endpoint, request)
+                                                .await
+                                                .map_err(|e| (StatusCode::BAD_GATEWAY, e)),
+                                            Err(e) => Err((
+                                                StatusCode::BAD_REQUEST,
+                                                format!("Invalid embeddings request: {e}"),
+                                            )),
+                                        }
+                                    }
+                                    Err(e) => Err((StatusCode::BAD_REQUEST, e)),
+                                };
+                                let (status, body) = match result {
+                                    Ok(response) => (
+                                        StatusCode::OK,
+                                        serde_json::to_vec(&response).unwrap_or_default(),
+                                    ),
+                                    Err((status, e)) => {
+                                        log::warn!("Embeddings request failed: {e}");
+                                        (status, e.into_bytes())
+                                    }
+                                };
+                                let mut response_builder = Response::builder().status(status);
+                                if status == StatusCode::OK {
+                                    response_builder = response_builder
+                                        .header(hyper::header::CONTENT_TYPE, "application/json");
+                                }
+                                response_builder = add_cors_headers_with_host_and_origin(
+                                    response_builder,
+                                    &host_header,
+                                    &origin_header,
+                                    &config.trusted_hosts,
+                                );
+                                return Ok(response_builder.body(Body::from(body)).unwrap());
+                            } else if let Some(session) = llama_session {
                                 let target_port = session.info.port;
                                 session_api_key = Some(session.info.api_key.clone());
                                 log::debug!("Found llama.cpp session for model_id {model_id}");
                                 target_base_url = Some(format!(
                                     "http://127.0.0.1:{target_port}/v1{destination_path}"
                                 ));
+                                local_model_id = Some(model_id.to_string());
                             } else if let Some(info) = mlx_session {
                                 let target_port = info.port;
                                 session_api_key = Some(info.api_key.clone());
@@ -973,6 +1105,7 @@ async fn proxy_request(
                                 target_base_url = Some(format!(
                                     "http://127.0.0.1:{target_port}/v1{destination_path}"
                                 ));
+                                local_model_id = Some(model_id.to_string());
                             } else {
                                 log::warn!("No running session found for model_id: {model_id}");
                                 let mut error_response =
@@ -1020,13 +1153,29 @@ async fn proxy_request(
                 }
             }
         }
+        (hyper::Method::GET, "/metrics") => {
+            let mut response_builder = Response::builder()
+                .status(StatusCode::OK)
+                .header(hyper::header::CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE);
+            response_builder = add_cors_headers_with_host_and_origin(
+                response_builder,
+                &host_header,
+                &origin_header,
+                &config.trusted_hosts,
+            );
+            return Ok(response_builder
+                .body(Body::from(render_metrics()))
+                .unwrap());
+        }
         (hyper::Method::GET, "/models") => {
             log::debug!("Handling GET /v1/models request");
+            let peer_allows = |model_id: &str| peer.iter().all(|peer| peer.allows(model_id));
 
             // Get local llama.cpp sessions
             let sessions_guard = sessions.lock().await;
             let local_models: Vec<_> = sessions_guard
                 .values()
+                .filter(|session| peer_allows(&session.info.model_id))
                 .map(|session| {
                     serde_json::json!({
                         "id": session.info.model_id,
@@ -1043,6 +1192,7 @@ async fn proxy_request(
                 let mlx_guard = mlx_sessions.lock().await;
                 mlx_guard
                     .values()
+                    .filter(|session| peer_allows(&session.info.model_id))
                     .map(|session| {
                         serde_json::json!({
                             "id": session.info.model_id,
@@ -1054,10 +1204,11 @@ async fn proxy_request(
                     .collect()
             };
 
-            // Get remote provider models
-            let pc = provider_configs.lock().await;
+            // Get remote provider models, which peers can't use
+            let pc = provider_configs.read().await;
             let remote_models: Vec<_> = pc
                 .values()
+                .filter(|_| peer.is_none())
                 .flat_map(|provider_cfg| provider_cfg.models.clone())
                 .map(|model_id| {
                     serde_json::json!({
@@ -1268,10 +1419,194 @@ async fn proxy_request(
         "Proxying request to model server at base URL {upstream_url}, path: {destination_path}"
     );
 
+    // Fill in the parameters the client left out from the applicable profile
+    let mut body_rewritten = false;
+    if destination_path == "/chat/completions" || destination_path == "/completions" {
+        if let Some(mut json_body) = buffered_body
+            .as_ref()
+            .and_then(|bytes| serde_json::from_slice::<serde_json::Value>(bytes).ok())
+        {
+            let header = |name: &str| {
+                headers
+                    .get(name)
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string)
+            };
+            let resolved = profiles.resolve(&ResolveParamsRequest {
+                model: json_body["model"].as_str().unwrap_or_default().to_string(),
+                assistant_id: header(ASSISTANT_ID_HEADER),
+                thread_id: header(THREAD_ID_HEADER),
+                parameters: serde_json::Map::new(),
+            });
+            if let Some(body) = json_body.as_object_mut() {
+                if apply_defaults(body, &resolved.parameters) {
+                    log::debug!(
+                        "Applied parameter profile {:?} to {destination_path}",
+                        resolved.profile_id
+                    );
+                    buffered_body = Some(Bytes::from(json_body.to_string()));
+                    body_rewritten = true;
+                }
+            }
+        }
+    }
+
+    // Cap output tokens per request and per hour of the caller, drop banned stop sequences
+    if matches!(
+        destination_path.as_str(),
+        "/chat/completions" | "/completions" | "/messages"
+    ) {
+        if let Some(mut json_body) = buffered_body
+            .as_ref()
+            .and_then(|bytes| serde_json::from_slice::<serde_json::Value>(bytes).ok())
+        {
+            let key = budget_key(
+                peer.as_ref().map(|peer| peer.name.as_str()),
+                headers
+                    .get(hyper::header::AUTHORIZATION)
+                    .and_then(|v| v.to_str().ok()),
+            );
+            match guardrails.check(&key, &mut json_body) {
+                Ok(true) => {
+                    buffered_body = Some(Bytes::from(json_body.to_string()));
+                    body_rewritten = true;
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    log::warn!("Refusing request to {destination_path} for {key}: {e}");
+                    let mut error_response =
+                        Response::builder().status(StatusCode::TOO_MANY_REQUESTS);
+                    error_response = add_cors_headers_with_host_and_origin(
+                        error_response,
+                        &host_header,
+                        &origin_header,
+                        &config.trusted_hosts,
+                    );
+                    return Ok(error_response.body(Body::from(e)).unwrap());
+                }
+            }
+        }
+    }
+
+    // Shrink and strip inline images to what the target accepts, or refuse the request
+    if destination_path == "/chat/completions" || destination_path == "/messages" {
+        let limits = if local_model_id.is_some() {
+            local_limits()
+        } else {
+            limits_for_url(&upstream_url)
+        };
+        if let Some(mut json_body) = buffered_body
+            .as_ref()
+            .and_then(|bytes| serde_json::from_slice::<serde_json::Value>(bytes).ok())
+        {
+            let messages = match json_body.get_mut("messages").map(|m| m.take()) {
+                Some(serde_json::Value::Array(messages)) => messages,
+                _ => Vec::new(),
+            };
+            if has_inline_images(&messages) {
+                match preprocess_messages_async(messages, limits).await {
+                    Ok((messages, _)) => {
+                        json_body["messages"] = serde_json::Value::Array(messages);
+                        buffered_body = Some(Bytes::from(json_body.to_string()));
+                        body_rewritten = true;
+                    }
+                    Err(e) => {
+                        log::warn!("Rejecting request with images to {destination_path}: {e}");
+                        let mut error_response =
+                            Response::builder().status(StatusCode::PAYLOAD_TOO_LARGE);
+                        error_response = add_cors_headers_with_host_and_origin(
+                            error_response,
+                            &host_header,
+                            &origin_header,
+                            &config.trusted_hosts,
+                        );
+                        return Ok(error_response.body(Body::from(e)).unwrap());
+                    }
+                }
+            }
+        }
+    }
+
+    // Scrub remote requests before they leave the machine; local engines get them as sent
+    if local_model_id.is_none() {
+        if let Err(e) = offline.check_url(&upstream_url) {
+            log::warn!("Refusing request to {upstream_url}: {e}");
+            let mut error_response = Response::builder().status(StatusCode::SERVICE_UNAVAILABLE);
+            error_response = add_cors_headers_with_host_and_origin(
+                error_response,
+                &host_header,
+                &origin_header,
+                &config.trusted_hosts,
+            );
+            return Ok(error_response.body(Body::from(e)).unwrap());
+        }
+        let thread_id = headers.get(THREAD_ID_HEADER).and_then(|v| v.to_str().ok());
+        if let Some((bytes, report)) = redaction.redactor(thread_id).and_then(|redactor| {
+            buffered_body
+                .as_ref()
+                .and_then(|bytes| redactor.redact_json_bytes(bytes))
+        }) {
+            log::info!(
+                "Redacted {} item(s) from request to {destination_path}",
+                report.total
+            );
+            buffered_body = Some(Bytes::from(bytes));
+            body_rewritten = true;
+        }
+        // Keep huge MCP tool schemas within what the provider accepts
+        if let Some(limits) = &tool_schema {
+            if let Some(mut json_body) = buffered_body
+                .as_ref()
+                .and_then(|bytes| serde_json::from_slice::<serde_json::Value>(bytes).ok())
+            {
+                let saved = compact_request_tools(&mut json_body, limits);
+                if saved > 0 {
+                    log::debug!("Compacted tool schemas of {destination_path} by {saved} bytes");
+                    buffered_body = Some(Bytes::from(json_body.to_string()));
+                    body_rewritten = true;
+                }
+            }
+        }
+        // Let Anthropic cache the system prompt, tools and conversation prefix
+        if prompt_caching && is_anthropic_messages {
+            if let Some(mut json_body) = buffered_body
+                .as_ref()
+                .and_then(|bytes| serde_json::from_slice::<serde_json::Value>(bytes).ok())
+            {
+                let breakpoints = apply_anthropic_cache_control(&mut json_body);
+                if breakpoints > 0 {
+                    log::debug!(
+                        "Added {breakpoints} prompt cache breakpoint(s) to {destination_path}"
+                    );
+                    buffered_body = Some(Bytes::from(json_body.to_string()));
+                    body_rewritten = true;
+                }
+            }
+        }
+    }
+
+    // Remote providers get a client with their connect timeout
+    let client = match &provider_policy {
+        Some(policy) => {
+            match policy_client(policy, std::time::Duration::from_secs(config.proxy_timeout)) {
+                Ok(policy_client) => policy_client,
+                Err(e) => {
+                    log::warn!("Failed to build provider client, using the default one: {e}");
+                    client
+                }
+            }
+        }
+        None => client,
+    };
     let mut outbound_req = client.request(method.clone(), upstream_url);
 
     for (name, value) in headers.iter() {
-        if name != hyper::header::HOST && name != hyper::header::AUTHORIZATION {
+        if name != hyper::header::HOST
+            && name != hyper::header::AUTHORIZATION
+            && name != THREAD_ID_HEADER
+            && name != ASSISTANT_ID_HEADER
+            && !(body_rewritten && name == hyper::header::CONTENT_LENGTH)
+        {
             outbound_req = outbound_req.header(name, value);
         }
     }
@@ -1304,7 +1639,51 @@ async fn proxy_request(
     // For Anthropic /messages, we need to track if we should transform the response
     let destination_path = path.clone();
 
-    match outbound_req_with_body.send().await {
+    // Local engines serve one generation at a time; API clients wait their turn
+    let generation_permit = match &local_model_id {
+        Some(model_id) => {
+            let job_id = format!("api:{}", uuid::Uuid::new_v4());
+            match scheduler
+                .acquire(model_id, &job_id, GenerationPriority::Api)
+                .await
+            {
+                Ok(permit) => Some(permit),
+                Err(e) => {
+                    log::warn!("Failed to schedule API request for {model_id}: {e}");
+                    None
+                }
+            }
+        }
+        None => None,
+    };
+
+    let read_timeout = provider_policy.as_ref().and_then(|p| p.read_timeout());
+    let request_span = tracing::info_span!(
+        "provider.request",
+        path = %destination_path,
+        local = local_model_id.is_some(),
+        "http.response.status_code" = tracing::field::Empty,
+    );
+    let send_result = match &provider_policy {
+        Some(policy) => {
+            send_with_retry(policy, outbound_req_with_body)
+                .instrument(request_span.clone())
+                .await
+        }
+        None => outbound_req_with_body
+            .send()
+            .instrument(request_span.clone())
+            .await
+            .map_err(|e| e.to_string()),
+    };
+    match &send_result {
+        Ok(response) => {
+            request_span.record("http.response.status_code", response.status().as_u16());
+        }
+        Err(e) => tracing::error!(parent: &request_span, error = %e, "Provider request failed"),
+    }
+
+    match send_result {
         Ok(response) => {
             let status = response.status();
 
@@ -1414,6 +1793,7 @@ async fn proxy_request(
                         let dest_path = destination_path.clone();
 
                         tokio::spawn(async move {
+                            let _generation_permit = generation_permit;
                             if is_streaming {
                                 let stream = res.bytes_stream();
                                 transform_and_forward_stream(stream, sender, &dest_path).await;
@@ -1480,9 +1860,11 @@ async fn proxy_request(
             let (mut sender, body) = hyper::Body::channel();
 
             tokio::spawn(async move {
+                // Keep the engine reserved until the whole response has been forwarded
+                let _generation_permit = generation_permit;
                 // Regular passthrough - when /messages succeeds directly,
                 // the response is already in the correct format
-                while let Some(chunk_result) = stream.next().await {
+                while let Some(chunk_result) = next_chunk(&mut stream, read_timeout).await {
                     match chunk_result {
                         Ok(chunk) => {
                             if sender.send_data(chunk).await.is_err() {
@@ -1516,6 +1898,177 @@ async fn proxy_request(
     }
 }
 
+/// Serves a request from `remote_addr`: the pairing endpoints, requests of paired LAN peers
+/// within their limits, and everything else through `proxy_request`
+#[allow(clippy::too_many_arguments)]
+async fn serve_request(
+    req: Request<Body>,
+    remote_addr: SocketAddr,
+    client: Client,
+    config: ProxyConfig,
+    sessions: Arc<Mutex<HashMap<i32, LLamaBackendSession>>>,
+    mlx_sessions: Arc<Mutex<HashMap<i32, MlxBackendSession>>>,
+    provider_configs: SharedProviderConfigs,
+    scheduler: GenerationScheduler,
+    redaction: RedactionFilter,
+    profiles: ParamProfiles,
+    guardrails: Guardrails,
+    offline: OfflineMode,
+    mcp_admin: McpAdminHandle,
+    peers: PeerAccess,
+) -> Result<Response<Body>, hyper::Error> {
+    let path = get_destination_path(req.uri().path(), &config.prefix);
+    let origin_header = req
+        .headers()
+        .get(hyper::header::ORIGIN)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    let peer_response = |status: StatusCode, body: String| {
+        add_cors_headers_with_host_and_origin(
+            Response::builder().status(status),
+            "",
+            &origin_header,
+            &config.trusted_hosts,
+        )
+        .body(Body::from(body))
+        .unwrap()
+    };
+
+    // Pairing needs no credentials: the code is only shown on this machine
+    if req.method() == hyper::Method::POST && (path == PEER_PAIR_PATH || path == PEER_CONFIRM_PATH)
+    {
+        let Ok(body) = hyper::body::to_bytes(req.into_body()).await else {
+            return Ok(peer_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to read request body".to_string(),
+            ));
+        };
+        let (status, body) =
+            handle_pairing_request(&peers, &path, &remote_addr.ip().to_string(), &body);
+        let mut response = peer_response(
+            StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+            body.to_string(),
+        );
+        response.headers_mut().insert(
+            hyper::header::CONTENT_TYPE,
+            hyper::header::HeaderValue::from_static("application/json"),
+        );
+        return Ok(response);
+    }
+
+    let grant = match peer_token(req.headers()) {
+        Some(token) => peers.authenticate(token),
+        None => None,
+    };
+    let Some(grant) = grant.filter(|_| req.method() != hyper::Method::OPTIONS) else {
+        return proxy_request(
+            req,
+            client,
+            config,
+            sessions,
+            mlx_sessions,
+            provider_configs,
+            scheduler,
+            redaction,
+            profiles,
+            guardrails,
+            offline,
+            mcp_admin,
+            None,
+        )
+        .await;
+    };
+
+    if !PEER_PATHS.contains(&path.as_str()) {
+        log::warn!("LAN peer {} refused access to {path}", grant.name);
+        return Ok(peer_response(
+            StatusCode::FORBIDDEN,
+            "Not available to LAN peers".to_string(),
+        ));
+    }
+    let (parts, body) = req.into_parts();
+    let Ok(body) = hyper::body::to_bytes(body).await else {
+        return Ok(peer_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to read request body".to_string(),
+        ));
+    };
+    if let Some(model) = requested_model(&body).filter(|model| !grant.allows(model)) {
+        log::warn!("LAN peer {} refused model '{model}'", grant.name);
+        return Ok(peer_response(
+            StatusCode::FORBIDDEN,
+            format!("Model '{model}' is not shared with this peer"),
+        ));
+    }
+    let permit = match grant.try_acquire() {
+        Ok(permit) => permit,
+        Err(e) => return Ok(peer_response(StatusCode::TOO_MANY_REQUESTS, e)),
+    };
+
+    let kib_per_second = grant.max_kib_per_second;
+    let response = proxy_request(
+        Request::from_parts(parts, Body::from(body)),
+        client,
+        config,
+        sessions,
+        mlx_sessions,
+        provider_configs,
+        scheduler,
+        redaction,
+        profiles,
+        guardrails,
+        offline,
+        mcp_admin,
+        Some(grant),
+    )
+    .await?;
+    Ok(limit_response(response, kib_per_second, permit))
+}
+
+/// Stream `response` at no more than `kib_per_second` (0 for no limit), holding the peer's
+/// request slot until the whole body is sent
+fn limit_response(
+    response: Response<Body>,
+    kib_per_second: u64,
+    permit: Option<OwnedSemaphorePermit>,
+) -> Response<Body> {
+    if kib_per_second == 0 && permit.is_none() {
+        return response;
+    }
+    // Large chunks are sent in pieces of about a tenth of a second each
+    let piece_size = match kib_per_second {
+        0 => usize::MAX,
+        kib => (kib as usize * 1024 / 10).max(1),
+    };
+    let (parts, mut body) = response.into_parts();
+    let (mut sender, limited) = Body::channel();
+    tokio::spawn(async move {
+        let _permit = permit;
+        let started = std::time::Instant::now();
+        let mut sent = 0u64;
+        while let Some(chunk) = body.data().await {
+            let Ok(mut chunk) = chunk else {
+                sender.abort();
+                return;
+            };
+            while !chunk.is_empty() {
+                let piece = chunk.split_to(chunk.len().min(piece_size));
+                sent += piece.len() as u64;
+                // The peer went away
+                if sender.send_data(piece).await.is_err() {
+                    return;
+                }
+                let delay = throttle_delay(sent, started.elapsed(), kib_per_second);
+                if !delay.is_zero() {
+                    tokio::time::sleep(delay).await;
+                }
+            }
+        }
+    });
+    Response::from_parts(parts, limited)
+}
+
 fn add_cors_headers_with_host_and_origin(
     builder: hyper::http::response::Builder,
     _host: &str,
@@ -1558,7 +2111,14 @@ pub async fn start_server(
     proxy_api_key: String,
     trusted_hosts: Vec<Vec<String>>,
     proxy_timeout: u64,
-    provider_configs: Arc<Mutex<HashMap<String, ProviderConfig>>>,
+    provider_configs: SharedProviderConfigs,
+    scheduler: GenerationScheduler,
+    redaction: RedactionFilter,
+    profiles: ParamProfiles,
+    guardrails: Guardrails,
+    offline: OfflineMode,
+    mcp_admin: McpAdminHandle,
+    peers: PeerAccess,
 ) -> Result<u16, Box<dyn std::error::Error + Send + Sync>> {
     start_server_internal(
         server_handle,
@@ -1571,10 +2131,33 @@ pub async fn start_server(
         trusted_hosts,
         proxy_timeout,
         provider_configs,
+        scheduler,
+        redaction,
+        profiles,
+        guardrails,
+        offline,
+        mcp_admin,
+        peers,
     )
     .await
 }
 
+/// Describe a failure to bind the server's port, so the user learns when it is taken
+fn bind_error(error: hyper::Error, port: u16) -> Box<dyn std::error::Error + Send + Sync> {
+    let kind = std::error::Error::source(&error)
+        .and_then(|source| source.downcast_ref::<std::io::Error>())
+        .map(|e| e.kind());
+    match kind {
+        Some(std::io::ErrorKind::AddrInUse) => {
+            Box::new(LocalizedMessage::new(MessageCode::ServerPortInUse).with("port", port))
+        }
+        Some(std::io::ErrorKind::PermissionDenied) => {
+            Box::new(LocalizedMessage::new(MessageCode::ServerPortNotPermitted).with("port", port))
+        }
+        _ => Box::new(error),
+    }
+}
+
 async fn start_server_internal(
     server_handle: Arc<Mutex<Option<ServerHandle>>>,
     sessions: Arc<Mutex<HashMap<i32, LLamaBackendSession>>>,
@@ -1585,16 +2168,24 @@ async fn start_server_internal(
     proxy_api_key: String,
     trusted_hosts: Vec<Vec<String>>,
     proxy_timeout: u64,
-    provider_configs: Arc<Mutex<HashMap<String, ProviderConfig>>>,
+    provider_configs: SharedProviderConfigs,
+    scheduler: GenerationScheduler,
+    redaction: RedactionFilter,
+    profiles: ParamProfiles,
+    guardrails: Guardrails,
+    offline: OfflineMode,
+    mcp_admin: McpAdminHandle,
+    peers: PeerAccess,
 ) -> Result<u16, Box<dyn std::error::Error + Send + Sync>> {
     let mut handle_guard = server_handle.lock().await;
     if handle_guard.is_some() {
-        return Err("Server is already running".into());
+        return Err(LocalizedMessage::new(MessageCode::ServerAlreadyRunning).into());
     }
 
-    let addr: SocketAddr = format!("{host}:{port}")
-        .parse()
-        .map_err(|e| format!("Invalid address: {e}"))?;
+    let addr: SocketAddr = format!("{host}:{port}").parse().map_err(|_| {
+        LocalizedMessage::new(MessageCode::ServerInvalidAddress)
+            .with("address", format!("{host}:{port}"))
+    })?;
 
     let config = ProxyConfig {
         prefix,
@@ -1602,6 +2193,7 @@ async fn start_server_internal(
         trusted_hosts,
         host: host.clone(),
         port,
+        proxy_timeout,
     };
 
     let client = Client::builder()
@@ -1610,22 +2202,38 @@ async fn start_server_internal(
         .pool_idle_timeout(std::time::Duration::from_secs(30))
         .build()?;
 
-    let make_svc = make_service_fn(move |_conn| {
+    let make_svc = make_service_fn(move |conn: &AddrStream| {
+        let remote_addr = conn.remote_addr();
         let client = client.clone();
         let config = config.clone();
         let sessions = sessions.clone();
         let mlx_sessions = mlx_sessions.clone();
         let provider_configs = provider_configs.clone();
+        let scheduler = scheduler.clone();
+        let redaction = redaction.clone();
+        let profiles = profiles.clone();
+        let guardrails = guardrails.clone();
+        let offline = offline.clone();
+        let mcp_admin = mcp_admin.clone();
+        let peers = peers.clone();
 
         async move {
             Ok::<_, Infallible>(service_fn(move |req| {
-                proxy_request(
+                serve_request(
                     req,
+                    remote_addr,
                     client.clone(),
                     config.clone(),
                     sessions.clone(),
                     mlx_sessions.clone(),
                     provider_configs.clone(),
+                    scheduler.clone(),
+                    redaction.clone(),
+                    profiles.clone(),
+                    guardrails.clone(),
+                    offline.clone(),
+                    mcp_admin.clone(),
+                    peers.clone(),
                 )
             }))
         }
@@ -1635,7 +2243,7 @@ async fn start_server_internal(
         Ok(builder) => builder.serve(make_svc),
         Err(e) => {
             log::error!("Failed to bind to {addr}: {e}");
-            return Err(Box::new(e));
+            return Err(bind_error(e, addr.port()));
         }
     };
     log::info!("Jan API server started on http://{addr}");
@@ -1679,292 +2287,280 @@ fn sse_event(data: &serde_json::Value) -> Bytes {
     Bytes::from(format!("event: {event_type}\ndata: {data}\n\n"))
 }
 
-/// Transform and forward streaming OpenAI response as Anthropic /messages chunks.
-/// Handles both text content and tool_calls streaming.
-async fn transform_and_forward_stream<S>(
-    mut stream: S,
-    mut sender: hyper::body::Sender,
-    _destination_path: &str,
-) where
-    S: futures_util::Stream<Item = Result<Bytes, reqwest::Error>> + Unpin,
-{
-    let mut is_first = true;
-    let mut accumulated_content = String::new();
-
-    // Track active Anthropic content blocks
-    let mut text_block_index: Option<usize> = None;
-    let mut tool_blocks: HashMap<usize, usize> = HashMap::new(); // OAI tool index -> Anthropic block index
-    let mut next_block_index: usize = 0;
+/// Anthropic error type of a classified provider failure
+fn anthropic_error_type(kind: ProviderErrorKind) -> &'static str {
+    match kind {
+        ProviderErrorKind::RateLimit => "rate_limit_error",
+        ProviderErrorKind::Overloaded => "overloaded_error",
+        ProviderErrorKind::QuotaExceeded => "billing_error",
+        ProviderErrorKind::Authentication => "authentication_error",
+        ProviderErrorKind::ContentFilter | ProviderErrorKind::InvalidRequest => {
+            "invalid_request_error"
+        }
+        ProviderErrorKind::Network | ProviderErrorKind::Other => "api_error",
+    }
+}
 
-    while let Some(chunk_result) = stream.next().await {
-        match chunk_result {
-            Ok(chunk) => {
-                let chunk_str = String::from_utf8_lossy(&chunk);
+/// Re-encodes normalized stream events as Anthropic /messages SSE events
+#[derive(Debug, Default)]
+pub struct AnthropicStreamEncoder {
+    started: bool,
+    finished: bool,
+    text_block_index: Option<usize>,
+    thinking_block_index: Option<usize>,
+    /// Tool call index -> Anthropic block index
+    tool_blocks: HashMap<usize, usize>,
+    next_block_index: usize,
+    accumulated_content: String,
+    output_tokens: Option<u64>,
+}
 
-                for line in chunk_str.lines() {
-                    if !line.starts_with("data:") {
-                        continue;
-                    }
-                    let data = line.trim_start_matches("data:").trim();
-
-                    if data == "[DONE]" {
-                        // Close any remaining open blocks
-                        if let Some(idx) = text_block_index.take() {
-                            let stop =
-                                serde_json::json!({"type": "content_block_stop", "index": idx});
-                            if sender.send_data(sse_event(&stop)).await.is_err() {
-                                return;
-                            }
-                        }
-                        let mut tool_indices: Vec<usize> = tool_blocks.values().copied().collect();
-                        tool_indices.sort();
-                        for idx in tool_indices {
-                            let stop =
-                                serde_json::json!({"type": "content_block_stop", "index": idx});
-                            if sender.send_data(sse_event(&stop)).await.is_err() {
-                                return;
-                            }
-                        }
+impl AnthropicStreamEncoder {
+    /// `message_start` event for the first upstream chunk; `None` once the message started
+    pub fn start(&mut self, chunk: &serde_json::Value) -> Option<serde_json::Value> {
+        if self.started {
+            return None;
+        }
+        self.started = true;
+        let role = chunk
+            .get("choices")
+            .and_then(|c| c.get(0))
+            .and_then(|c| c.get("delta"))
+            .and_then(|d| d.get("role"))
+            .and_then(|r| r.as_str())
+            .unwrap_or("assistant");
+        Some(serde_json::json!({
+            "type": "message_start",
+            "message": {
+                "id": chunk.get("id").cloned().unwrap_or(serde_json::json!("")),
+                "type": "message",
+                "role": role,
+                "content": [],
+                "model": chunk.get("model").cloned().unwrap_or(serde_json::json!("")),
+                "stop_reason": serde_json::Value::Null,
+                "stop_sequence": serde_json::Value::Null,
+                "usage": { "input_tokens": 0, "output_tokens": 0 }
+            }
+        }))
+    }
 
-                        let stop_reason = if tool_blocks.is_empty() {
-                            "end_turn"
-                        } else {
-                            "tool_use"
-                        };
-                        let output_tokens = accumulated_content.split_whitespace().count() as u64;
-
-                        let delta_event = serde_json::json!({
-                            "type": "message_delta",
-                            "delta": {
-                                "stop_reason": stop_reason,
-                                "stop_sequence": serde_json::Value::Null
-                            },
-                            "usage": { "output_tokens": output_tokens }
-                        });
-                        if sender.send_data(sse_event(&delta_event)).await.is_err() {
-                            return;
-                        }
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
 
-                        let final_stop = serde_json::json!({"type": "message_stop"});
-                        if sender.send_data(sse_event(&final_stop)).await.is_err() {
-                            return;
-                        }
-                        log::debug!("Sent Anthropic final events");
-                        return;
+    /// Anthropic events for a normalized stream event
+    pub fn encode(&mut self, event: StreamEvent) -> Vec<serde_json::Value> {
+        let mut events = Vec::new();
+        match event {
+            StreamEvent::ReasoningDelta { text } => {
+                let idx = match self.thinking_block_index {
+                    Some(idx) => idx,
+                    None => {
+                        let idx = self.open_block();
+                        self.thinking_block_index = Some(idx);
+                        events.push(serde_json::json!({
+                            "type": "content_block_start",
+                            "index": idx,
+                            "content_block": { "type": "thinking", "thinking": "" }
+                        }));
+                        idx
                     }
-
-                    let json_chunk = match serde_json::from_str::<serde_json::Value>(data) {
-                        Ok(v) => v,
-                        Err(_) => continue,
-                    };
-
-                    let choice = json_chunk
-                        .get("choices")
-                        .and_then(|c| c.as_array())
-                        .and_then(|c| c.first());
-                    let delta = match choice.and_then(|c| c.get("delta")) {
-                        Some(d) => d,
-                        None => continue,
-                    };
-                    let finish_reason = choice.and_then(|c| c.get("finish_reason"));
-                    let has_finish = finish_reason.is_some() && !finish_reason.unwrap().is_null();
-
-                    // First chunk: send message_start
-                    if is_first {
-                        let role = delta
-                            .get("role")
-                            .and_then(|r| r.as_str())
-                            .unwrap_or("assistant");
-                        let message_id = json_chunk
-                            .get("id")
-                            .unwrap_or(&serde_json::json!(""))
-                            .clone();
-                        let model = json_chunk
-                            .get("model")
-                            .unwrap_or(&serde_json::json!(""))
-                            .clone();
-
-                        let start_event = serde_json::json!({
-                            "type": "message_start",
-                            "message": {
-                                "id": message_id,
-                                "type": "message",
-                                "role": role,
-                                "content": [],
-                                "model": model,
-                                "stop_reason": serde_json::Value::Null,
-                                "stop_sequence": serde_json::Value::Null,
-                                "usage": { "input_tokens": 0, "output_tokens": 0 }
-                            }
-                        });
-                        if sender.send_data(sse_event(&start_event)).await.is_err() {
-                            return;
-                        }
-                        is_first = false;
+                };
+                events.push(serde_json::json!({
+                    "type": "content_block_delta",
+                    "index": idx,
+                    "delta": { "type": "thinking_delta", "thinking": text }
+                }));
+            }
+            StreamEvent::TextDelta { text } => {
+                // The answer follows the reasoning
+                if let Some(idx) = self.thinking_block_index.take() {
+                    events.push(serde_json::json!({"type": "content_block_stop", "index": idx}));
+                }
+                let idx = match self.text_block_index {
+                    Some(idx) => idx,
+                    None => {
+                        let idx = self.open_block();
+                        self.text_block_index = Some(idx);
+                        events.push(serde_json::json!({
+                            "type": "content_block_start",
+                            "index": idx,
+                            "content_block": { "type": "text", "text": "" }
+                        }));
+                        idx
                     }
-
-                    // Handle text content
-                    if let Some(text) =
-                        delta
-                            .get("content")
-                            .and_then(|c| if c.is_null() { None } else { c.as_str() })
-                    {
-                        if !text.is_empty() {
-                            // Open text block if needed
-                            if text_block_index.is_none() {
-                                let idx = next_block_index;
-                                next_block_index += 1;
-                                text_block_index = Some(idx);
-
-                                let block_start = serde_json::json!({
-                                    "type": "content_block_start",
-                                    "index": idx,
-                                    "content_block": { "type": "text", "text": "" }
-                                });
-                                if sender.send_data(sse_event(&block_start)).await.is_err() {
-                                    return;
-                                }
-                            }
-
-                            accumulated_content.push_str(text);
-                            let delta_event = serde_json::json!({
-                                "type": "content_block_delta",
-                                "index": text_block_index.unwrap(),
-                                "delta": { "type": "text_delta", "text": text }
-                            });
-                            if sender.send_data(sse_event(&delta_event)).await.is_err() {
-                                return;
-                            }
+                };
+                self.accumulated_content.push_str(&text);
+                events.push(serde_json::json!({
+                    "type": "content_block_delta",
+                    "index": idx,
+                    "delta": { "type": "text_delta", "text": text }
+                }));
+            }
+            StreamEvent::ToolCallDelta {
+                index,
+                id,
+                name,
+                arguments,
+            } => {
+                // Close text and thinking blocks before tool blocks
+                events.extend(self.close_text_blocks());
+                // New tool call (has id + function.name)
+                if let Some(id) = id {
+                    let idx = self.open_block();
+                    self.tool_blocks.insert(index, idx);
+                    events.push(serde_json::json!({
+                        "type": "content_block_start",
+                        "index": idx,
+                        "content_block": {
+                            "type": "tool_use",
+                            "id": id,
+                            "name": name.unwrap_or_default(),
+                            "input": {}
                         }
+                    }));
+                }
+                if let Some(&idx) = self
+                    .tool_blocks
+                    .get(&index)
+                    .filter(|_| !arguments.is_empty())
+                {
+                    events.push(serde_json::json!({
+                        "type": "content_block_delta",
+                        "index": idx,
+                        "delta": { "type": "input_json_delta", "partial_json": arguments }
+                    }));
+                }
+            }
+            StreamEvent::Usage { output_tokens, .. } => {
+                if output_tokens.is_some() {
+                    self.output_tokens = output_tokens;
+                }
+            }
+            StreamEvent::Finish { reason } => events.extend(self.finish(Some(&reason))),
+            // Anthropic ends a failed stream with an `error` event and nothing after it
+            StreamEvent::Error { error } => {
+                self.finished = true;
+                events.push(serde_json::json!({
+                    "type": "error",
+                    "error": {
+                        "type": anthropic_error_type(error.kind),
+                        "message": error.message
                     }
+                }));
+            }
+        }
+        events
+    }
 
-                    // Handle tool calls
-                    if let Some(tool_calls) = delta.get("tool_calls").and_then(|tc| tc.as_array()) {
-                        // Close text block before tool blocks
-                        if let Some(idx) = text_block_index.take() {
-                            let stop = serde_json::json!(
-                                {"type": "content_block_stop", "index": idx}
-                            );
-                            if sender.send_data(sse_event(&stop)).await.is_err() {
-                                return;
-                            }
-                        }
+    /// Close open blocks and end the message. Without a finish reason (the upstream
+    /// only sent `[DONE]`) the stop reason is inferred from the blocks that were sent.
+    pub fn finish(&mut self, reason: Option<&str>) -> Vec<serde_json::Value> {
+        if self.finished {
+            return Vec::new();
+        }
+        self.finished = true;
+        let mut events = Vec::new();
+        events.extend(self.close_text_blocks());
+        let mut tool_indices: Vec<usize> = self.tool_blocks.values().copied().collect();
+        tool_indices.sort();
+        for idx in tool_indices {
+            events.push(serde_json::json!({"type": "content_block_stop", "index": idx}));
+        }
 
-                        for tc in tool_calls {
-                            let tc_index =
-                                tc.get("index").and_then(|i| i.as_u64()).unwrap_or(0) as usize;
-
-                            // New tool call (has id + function.name)
-                            if let Some(id) = tc.get("id").and_then(|v| v.as_str()) {
-                                let name = tc
-                                    .get("function")
-                                    .and_then(|f| f.get("name"))
-                                    .and_then(|n| n.as_str())
-                                    .unwrap_or("");
-
-                                let idx = next_block_index;
-                                next_block_index += 1;
-                                tool_blocks.insert(tc_index, idx);
-
-                                let block_start = serde_json::json!({
-                                    "type": "content_block_start",
-                                    "index": idx,
-                                    "content_block": {
-                                        "type": "tool_use",
-                                        "id": id,
-                                        "name": name,
-                                        "input": {}
-                                    }
-                                });
-                                if sender.send_data(sse_event(&block_start)).await.is_err() {
-                                    return;
-                                }
-                            }
+        let stop_reason = match reason {
+            Some("stop") => "end_turn",
+            Some("length") => "max_tokens",
+            Some("tool_calls") => "tool_use",
+            Some(other) => other,
+            None if self.tool_blocks.is_empty() => "end_turn",
+            None => "tool_use",
+        };
+        // Fall back to a rough estimate when the upstream does not report usage
+        let output_tokens = self
+            .output_tokens
+            .unwrap_or_else(|| self.accumulated_content.split_whitespace().count() as u64);
+        events.push(serde_json::json!({
+            "type": "message_delta",
+            "delta": {
+                "stop_reason": stop_reason,
+                "stop_sequence": serde_json::Value::Null
+            },
+            "usage": { "output_tokens": output_tokens }
+        }));
+        events.push(serde_json::json!({"type": "message_stop"}));
+        events
+    }
 
-                            // Argument delta
-                            if let Some(args) = tc
-                                .get("function")
-                                .and_then(|f| f.get("arguments"))
-                                .and_then(|a| a.as_str())
-                            {
-                                if !args.is_empty() {
-                                    if let Some(&idx) = tool_blocks.get(&tc_index) {
-                                        let delta_event = serde_json::json!({
-                                            "type": "content_block_delta",
-                                            "index": idx,
-                                            "delta": {
-                                                "type": "input_json_delta",
-                                                "partial_json": args
-                                            }
-                                        });
-                                        if sender.send_data(sse_event(&delta_event)).await.is_err()
-                                        {
-                                            return;
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
+    /// Stop events of the open thinking and text blocks
+    fn close_text_blocks(&mut self) -> Vec<serde_json::Value> {
+        let thinking = self.thinking_block_index.take();
+        let text = self.text_block_index.take();
+        [thinking, text]
+            .into_iter()
+            .flatten()
+            .map(|idx| serde_json::json!({"type": "content_block_stop", "index": idx}))
+            .collect()
+    }
 
-                    // Handle finish
-                    if has_finish {
-                        // Close text block
-                        if let Some(idx) = text_block_index.take() {
-                            let stop = serde_json::json!(
-                                {"type": "content_block_stop", "index": idx}
-                            );
-                            if sender.send_data(sse_event(&stop)).await.is_err() {
-                                return;
-                            }
-                        }
-                        // Close all tool blocks
-                        let mut tool_indices: Vec<usize> = tool_blocks.values().copied().collect();
-                        tool_indices.sort();
-                        for idx in tool_indices {
-                            let stop = serde_json::json!(
-                                {"type": "content_block_stop", "index": idx}
-                            );
-                            if sender.send_data(sse_event(&stop)).await.is_err() {
-                                return;
-                            }
-                        }
+    fn open_block(&mut self) -> usize {
+        let idx = self.next_block_index;
+        self.next_block_index += 1;
+        idx
+    }
+}
 
-                        let reason = finish_reason
-                            .and_then(|fr| fr.as_str())
-                            .unwrap_or("end_turn");
-                        let stop_reason = match reason {
-                            "stop" => "end_turn",
-                            "length" => "max_tokens",
-                            "tool_calls" => "tool_use",
-                            _ => reason,
-                        };
-                        let output_tokens = accumulated_content.split_whitespace().count() as u64;
-
-                        let delta_event = serde_json::json!({
-                            "type": "message_delta",
-                            "delta": {
-                                "stop_reason": stop_reason,
-                                "stop_sequence": serde_json::Value::Null
-                            },
-                            "usage": { "output_tokens": output_tokens }
-                        });
-                        if sender.send_data(sse_event(&delta_event)).await.is_err() {
-                            return;
-                        }
+/// Transform and forward streaming OpenAI response as Anthropic /messages chunks.
+/// Handles both text content and tool_calls streaming.
+async fn transform_and_forward_stream<S>(
+    mut stream: S,
+    mut sender: hyper::body::Sender,
+    _destination_path: &str,
+) where
+    S: futures_util::Stream<Item = Result<Bytes, reqwest::Error>> + Unpin,
+{
+    let mut lines = SseLineBuffer::default();
+    let mut normalizer = StreamNormalizer::new(StreamFormat::OpenAi);
+    let mut encoder = AnthropicStreamEncoder::default();
 
-                        let final_stop = serde_json::json!({"type": "message_stop"});
-                        if sender.send_data(sse_event(&final_stop)).await.is_err() {
-                            return;
-                        }
-                        return;
-                    }
-                }
-            }
+    while let Some(chunk_result) = stream.next().await {
+        let chunk = match chunk_result {
+            Ok(chunk) => chunk,
             Err(e) => {
                 log::error!("Stream error: {e}");
                 break;
             }
+        };
+
+        for data in lines.push(&chunk) {
+            let mut events = Vec::new();
+            match data {
+                SseData::Done => {
+                    for event in normalizer.finish() {
+                        events.extend(encoder.encode(event));
+                    }
+                    events.extend(encoder.finish(None));
+                }
+                SseData::Json(json_chunk) => {
+                    let normalized = normalizer.normalize(&json_chunk);
+                    if normalized.is_empty() {
+                        continue;
+                    }
+                    events.extend(encoder.start(&json_chunk));
+                    for event in normalized {
+                        events.extend(encoder.encode(event));
+                    }
+                }
+            }
+            for event in &events {
+                if sender.send_data(sse_event(event)).await.is_err() {
+                    return;
+                }
+            }
+            if encoder.is_finished() {
+                log::debug!("Sent Anthropic final events");
+                return;
+            }
         }
     }
     log::debug!("Streaming complete (Anthropic format)");
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
+use crate::core::inference::models::NativeTool;
+use crate::core::settings::helpers::{admin_policy, check_provider_allowed};
 use crate::core::state::{AppState, ProviderConfig};
 
 /// Custom header for provider requests
@@ -18,6 +20,20 @@ pub struct RegisterProviderRequest {
     pub base_url: Option<String>,
     pub custom_headers: Vec<ProviderCustomHeader>,
     pub models: Vec<String>,
+    pub connect_timeout_secs: Option<u64>,
+    pub read_timeout_secs: Option<u64>,
+    pub max_retries: Option<u32>,
+    pub retry_on_statuses: Option<Vec<u16>>,
+    pub backoff_initial_ms: Option<u64>,
+    pub backoff_max_ms: Option<u64>,
+    pub prompt_caching: Option<bool>,
+    pub compact_tool_schemas: Option<bool>,
+    pub max_schema_description_chars: Option<usize>,
+    pub max_schema_enum_values: Option<usize>,
+    #[serde(default)]
+    pub native_tools: Vec<NativeTool>,
+    #[serde(default)]
+    pub failover_models: Vec<String>,
 }
 
 /// Register a remote provider configuration
@@ -26,8 +42,9 @@ pub async fn register_provider_config(
     state: State<'_, AppState>,
     request: RegisterProviderRequest,
 ) -> Result<(), String> {
+    check_provider_allowed(admin_policy(), &request.provider)?;
     let provider_configs = state.provider_configs.clone();
-    let mut configs = provider_configs.lock().await;
+    let mut configs = provider_configs.write().await;
 
     let config = ProviderConfig {
         provider: request.provider.clone(),
@@ -42,6 +59,18 @@ pub async fn register_provider_config(
             })
             .collect(),
         models: request.models, // Models will be added when they are configured
+        connect_timeout_secs: request.connect_timeout_secs,
+        read_timeout_secs: request.read_timeout_secs,
+        max_retries: request.max_retries,
+        retry_on_statuses: request.retry_on_statuses,
+        backoff_initial_ms: request.backoff_initial_ms,
+        backoff_max_ms: request.backoff_max_ms,
+        prompt_caching: request.prompt_caching,
+        compact_tool_schemas: request.compact_tool_schemas,
+        max_schema_description_chars: request.max_schema_description_chars,
+        max_schema_enum_values: request.max_schema_enum_values,
+        native_tools: request.native_tools,
+        failover_models: request.failover_models,
     };
 
     let provider_name = request.provider.clone();
@@ -57,7 +86,7 @@ pub async fn unregister_provider_config(
     provider: String,
 ) -> Result<(), String> {
     let provider_configs = state.provider_configs.clone();
-    let mut configs = provider_configs.lock().await;
+    let mut configs = provider_configs.write().await;
 
     if configs.remove(&provider).is_some() {
         log::info!("Unregistered provider config: {provider}");
@@ -75,7 +104,7 @@ pub async fn get_provider_config(
     provider: String,
 ) -> Result<Option<ProviderConfig>, String> {
     let provider_configs = state.provider_configs.clone();
-    let configs = provider_configs.lock().await;
+    let configs = provider_configs.read().await;
 
     Ok(configs.get(&provider).cloned())
 }
@@ -86,7 +115,7 @@ pub async fn list_provider_configs(
     state: State<'_, AppState>,
 ) -> Result<Vec<ProviderConfig>, String> {
     let provider_configs = state.provider_configs.clone();
-    let configs = provider_configs.lock().await;
+    let configs = provider_configs.read().await;
 
     Ok(configs.values().cloned().collect())
 }
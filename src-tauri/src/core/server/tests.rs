@@ -68,6 +68,7 @@ mod tests {
             trusted_hosts: vec![vec!["localhost".to_string()]],
             host: "localhost".to_string(),
             port: 1337,
+            proxy_timeout: 600,
         };
         assert_eq!(config.prefix, "/v1");
         assert_eq!(config.proxy_api_key, "test-key");
@@ -84,6 +85,7 @@ mod tests {
             trusted_hosts: vec![],
             host: "127.0.0.1".to_string(),
             port: 8080,
+            proxy_timeout: 600,
         };
         assert_eq!(config.prefix, "");
         assert_eq!(config.proxy_api_key, "");
@@ -293,4 +295,54 @@ mod tests {
         ];
         assert!(allowed_headers.contains(&"x-api-key"));
     }
+
+    #[test]
+    fn test_anthropic_stream_encoder_tool_call() {
+        use crate::core::inference::models::StreamEvent;
+
+        let mut encoder = proxy::AnthropicStreamEncoder::default();
+        let start = encoder
+            .start(&serde_json::json!({"id": "chatcmpl-1", "model": "m"}))
+            .unwrap();
+        assert_eq!(start["message"]["id"], "chatcmpl-1");
+        assert!(encoder.start(&serde_json::json!({})).is_none());
+
+        let mut events = encoder.encode(StreamEvent::TextDelta {
+            text: "Checking".to_string(),
+        });
+        events.extend(encoder.encode(StreamEvent::ToolCallDelta {
+            index: 0,
+            id: Some("call_1".to_string()),
+            name: Some("search".to_string()),
+            arguments: "{}".to_string(),
+        }));
+        events.extend(encoder.encode(StreamEvent::Usage {
+            input_tokens: None,
+            output_tokens: Some(4),
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+        }));
+        events.extend(encoder.encode(StreamEvent::Finish {
+            reason: "tool_calls".to_string(),
+        }));
+        let types: Vec<&str> = events.iter().map(|e| e["type"].as_str().unwrap()).collect();
+        assert_eq!(
+            types,
+            vec![
+                "content_block_start",
+                "content_block_delta",
+                "content_block_stop",
+                "content_block_start",
+                "content_block_delta",
+                "content_block_stop",
+                "message_delta",
+                "message_stop",
+            ]
+        );
+        assert_eq!(events[3]["content_block"]["name"], "search");
+        assert_eq!(events[6]["delta"]["stop_reason"], "tool_use");
+        assert_eq!(events[6]["usage"]["output_tokens"], 4);
+        assert!(encoder.is_finished());
+        assert!(encoder.finish(None).is_empty());
+    }
 }
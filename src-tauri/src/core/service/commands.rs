@@ -0,0 +1,42 @@
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Runtime};
+
+use super::helpers::{install_service, service_status, uninstall_service};
+use super::models::{ServiceOptions, ServiceStatus};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::system::commands::install_jan_cli_sync;
+
+/// Installs the headless API server as a service started at login, and starts it. Puts the
+/// bundled `jan` CLI on PATH first, since that is what the service runs.
+#[tauri::command]
+pub async fn install_server_service<R: Runtime>(
+    app_handle: AppHandle<R>,
+    options: ServiceOptions,
+) -> Result<ServiceStatus, String> {
+    let cli = install_jan_cli_sync(&app_handle)?;
+    let program = PathBuf::from(cli.path.ok_or("The jan CLI was not installed")?);
+    let data_folder = get_jan_data_folder_path(app_handle);
+    tokio::task::spawn_blocking(move || install_service(&program, &options, &data_folder))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Stops and removes the server service. The `jan` CLI stays installed.
+#[tauri::command]
+pub async fn uninstall_server_service() -> Result<(), String> {
+    tokio::task::spawn_blocking(uninstall_service)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Whether the service is installed, and which headless server runs on the data folder
+#[tauri::command]
+pub async fn get_server_service_status<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<ServiceStatus, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    tokio::task::spawn_blocking(move || service_status(&data_folder))
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,14 @@
+// Server service constants
+/// launchd label, also the plist's file name
+pub const LAUNCHD_LABEL: &str = "ai.jan.server";
+pub const SYSTEMD_UNIT_NAME: &str = "jan-server.service";
+pub const WINDOWS_TASK_NAME: &str = "Jan Server";
+/// `schtasks /TR` refuses longer commands
+pub const MAX_WINDOWS_TASK_COMMAND: usize = 261;
+/// Held in the data folder by a running `jan serve`
+pub const SERVER_LOCK_FILE: &str = "server.lock";
+/// Output of the launchd agent, relative to the data folder
+pub const SERVICE_LOG_FILE: &str = "logs/service.log";
+pub const DEFAULT_SERVICE_PORT: u16 = 6767;
+/// Wait after a crash before systemd starts the server again
+pub const SERVICE_RESTART_SECS: u64 = 10;
@@ -0,0 +1,341 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::constants::{
+    LAUNCHD_LABEL, MAX_WINDOWS_TASK_COMMAND, SERVER_LOCK_FILE, SERVICE_LOG_FILE,
+    SERVICE_RESTART_SECS, SYSTEMD_UNIT_NAME, WINDOWS_TASK_NAME,
+};
+use super::models::{ServerLockInfo, ServiceOptions, ServiceStatus};
+use crate::core::mcp::lockfile::is_process_alive;
+
+/// Arguments of the `jan serve` the service runs
+pub fn serve_args(options: &ServiceOptions) -> Vec<String> {
+    // `--flag=value`, so negative numbers aren't taken for flags
+    let mut args = vec![
+        "serve".to_string(),
+        options.model_id.clone(),
+        format!("--port={}", options.port),
+    ];
+    if !options.api_key.is_empty() {
+        args.push(format!("--api-key={}", options.api_key));
+    }
+    if let Some(ctx_size) = options.ctx_size {
+        args.push(format!("--ctx-size={ctx_size}"));
+    }
+    if options.fit {
+        args.push("--fit".to_string());
+    }
+    args
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+pub fn launchd_plist(program: &Path, args: &[String], log_path: &Path) -> String {
+    let arguments: String = std::iter::once(program.to_string_lossy().into_owned())
+        .chain(args.iter().cloned())
+        .map(|arg| format!("        <string>{}</string>\n", xml_escape(&arg)))
+        .collect();
+    let log_path = xml_escape(&log_path.to_string_lossy());
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{LAUNCHD_LABEL}</string>
+    <key>ProgramArguments</key>
+    <array>
+{arguments}    </array>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <dict>
+        <key>SuccessfulExit</key>
+        <false/>
+    </dict>
+    <key>ThrottleInterval</key>
+    <integer>{SERVICE_RESTART_SECS}</integer>
+    <key>StandardOutPath</key>
+    <string>{log_path}</string>
+    <key>StandardErrorPath</key>
+    <string>{log_path}</string>
+</dict>
+</plist>
+"#
+    )
+}
+
+/// Quote an `ExecStart` word, escaping what systemd would expand
+fn systemd_quote(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%")
+        .replace('$', "$$");
+    format!("\"{escaped}\"")
+}
+
+pub fn systemd_unit(program: &Path, args: &[String]) -> String {
+    let exec_start = std::iter::once(program.to_string_lossy().into_owned())
+        .chain(args.iter().cloned())
+        .map(|arg| systemd_quote(&arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "[Unit]\n\
+         Description=Jan headless API server\n\
+         After=network.target\n\
+         \n\
+         [Service]\n\
+         ExecStart={exec_start}\n\
+         Restart=on-failure\n\
+         RestartSec={SERVICE_RESTART_SECS}\n\
+         \n\
+         [Install]\n\
+         WantedBy=default.target\n"
+    )
+}
+
+/// The command line of the Windows task, refused when `schtasks` would truncate it
+pub fn windows_task_command(program: &Path, args: &[String]) -> Result<String, String> {
+    let command = std::iter::once(program.to_string_lossy().into_owned())
+        .chain(args.iter().cloned())
+        .map(|arg| {
+            if arg.contains([' ', '\t']) {
+                format!("\"{arg}\"")
+            } else {
+                arg
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    if command.len() > MAX_WINDOWS_TASK_COMMAND {
+        return Err(format!(
+            "The server command is longer than the {MAX_WINDOWS_TASK_COMMAND} characters a \
+             scheduled task allows; install jan to a shorter path"
+        ));
+    }
+    Ok(command)
+}
+
+/// Where the service definition lives on this platform, `None` for the Windows task
+pub fn definition_path() -> Result<Option<PathBuf>, String> {
+    if cfg!(target_os = "macos") {
+        let home = dirs::home_dir().ok_or("Cannot find the home folder")?;
+        Ok(Some(
+            home.join("Library/LaunchAgents")
+                .join(format!("{LAUNCHD_LABEL}.plist")),
+        ))
+    } else if cfg!(windows) {
+        Ok(None)
+    } else {
+        let config = dirs::config_dir().ok_or("Cannot find the config folder")?;
+        Ok(Some(config.join("systemd/user").join(SYSTEMD_UNIT_NAME)))
+    }
+}
+
+fn run(program: &str, args: &[&str]) -> Result<(), String> {
+    let mut command = Command::new(program);
+    command.args(args);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(0x08000000); // CREATE_NO_WINDOW
+    }
+    let output = command
+        .output()
+        .map_err(|e| format!("Failed to run {program}: {e}"))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{program} {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+fn write_definition(path: &Path, contents: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    fs::write(path, contents).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// Install the service running `program` (the `jan` binary) and start it. Replaces an
+/// installed service.
+pub fn install_service(
+    program: &Path,
+    options: &ServiceOptions,
+    data_folder: &Path,
+) -> Result<ServiceStatus, String> {
+    if options.model_id.trim().is_empty() {
+        return Err("Choose a model for the server".to_string());
+    }
+    if !program.exists() {
+        return Err(format!("{} does not exist", program.display()));
+    }
+    let args = serve_args(options);
+    match definition_path()? {
+        Some(path) if cfg!(target_os = "macos") => {
+            let log_path = data_folder.join(SERVICE_LOG_FILE);
+            if let Some(parent) = log_path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            let path_str = path.to_string_lossy().into_owned();
+            if path.exists() {
+                let _ = run("launchctl", &["unload", "-w", &path_str]);
+            }
+            write_definition(&path, &launchd_plist(program, &args, &log_path))?;
+            run("launchctl", &["load", "-w", &path_str])?;
+        }
+        Some(path) => {
+            write_definition(&path, &systemd_unit(program, &args))?;
+            run("systemctl", &["--user", "daemon-reload"])?;
+            run("systemctl", &["--user", "enable", SYSTEMD_UNIT_NAME])?;
+            run("systemctl", &["--user", "restart", SYSTEMD_UNIT_NAME])?;
+        }
+        None => {
+            let command = windows_task_command(program, &args)?;
+            run(
+                "schtasks",
+                &[
+                    "/Create",
+                    "/F",
+                    "/SC",
+                    "ONLOGON",
+                    "/RL",
+                    "LIMITED",
+                    "/TN",
+                    WINDOWS_TASK_NAME,
+                    "/TR",
+                    &command,
+                ],
+            )?;
+            run("schtasks", &["/Run", "/TN", WINDOWS_TASK_NAME])?;
+        }
+    }
+    log::info!("Installed the server service for {}", options.model_id);
+    Ok(service_status(data_folder))
+}
+
+/// Stop and remove the service, if installed
+pub fn uninstall_service() -> Result<(), String> {
+    match definition_path()? {
+        Some(path) if cfg!(target_os = "macos") => {
+            if path.exists() {
+                let _ = run("launchctl", &["unload", "-w", &path.to_string_lossy()]);
+                fs::remove_file(&path)
+                    .map_err(|e| format!("Failed to remove {}: {e}", path.display()))?;
+            }
+        }
+        Some(path) => {
+            if path.exists() {
+                let _ = run(
+                    "systemctl",
+                    &["--user", "disable", "--now", SYSTEMD_UNIT_NAME],
+                );
+                fs::remove_file(&path)
+                    .map_err(|e| format!("Failed to remove {}: {e}", path.display()))?;
+                run("systemctl", &["--user", "daemon-reload"])?;
+            }
+        }
+        None => {
+            if windows_task_exists() {
+                let _ = run("schtasks", &["/End", "/TN", WINDOWS_TASK_NAME]);
+                run("schtasks", &["/Delete", "/F", "/TN", WINDOWS_TASK_NAME])?;
+            }
+        }
+    }
+    log::info!("Uninstalled the server service");
+    Ok(())
+}
+
+fn windows_task_exists() -> bool {
+    run("schtasks", &["/Query", "/TN", WINDOWS_TASK_NAME]).is_ok()
+}
+
+pub fn service_status(data_folder: &Path) -> ServiceStatus {
+    let path = definition_path().ok().flatten();
+    let installed = match &path {
+        Some(path) => path.exists(),
+        None => cfg!(windows) && windows_task_exists(),
+    };
+    ServiceStatus {
+        installed,
+        definition_path: path.map(|p| p.to_string_lossy().into_owned()),
+        running: read_server_lock(data_folder),
+    }
+}
+
+pub fn server_lock_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(SERVER_LOCK_FILE)
+}
+
+/// The server holding the lock, if its process is alive
+pub fn read_server_lock(data_folder: &Path) -> Option<ServerLockInfo> {
+    let data = fs::read_to_string(server_lock_path(data_folder)).ok()?;
+    let info: ServerLockInfo = serde_json::from_str(&data).ok()?;
+    is_process_alive(info.pid).then_some(info)
+}
+
+/// Removes `server.lock` when dropped
+#[derive(Debug)]
+pub struct ServerLock {
+    path: PathBuf,
+}
+
+impl Drop for ServerLock {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            log::warn!("Failed to remove {}: {e}", self.path.display());
+        }
+    }
+}
+
+/// Claim the data folder for a headless server. Fails while another one is running; the
+/// lock of a server that died is taken over.
+pub fn acquire_server_lock(
+    data_folder: &Path,
+    info: &ServerLockInfo,
+) -> Result<ServerLock, String> {
+    let path = server_lock_path(data_folder);
+    let data = serde_json::to_string_pretty(info).map_err(|e| e.to_string())?;
+    fs::create_dir_all(data_folder).map_err(|e| e.to_string())?;
+    for _ in 0..2 {
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(mut file) => {
+                file.write_all(data.as_bytes())
+                    .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+                return Ok(ServerLock { path });
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                if let Some(running) = read_server_lock(data_folder) {
+                    return Err(format!(
+                        "A Jan server is already running on this data folder (PID {}, {} on port {})",
+                        running.pid, running.model_id, running.port
+                    ));
+                }
+                log::info!("Taking over the stale {}", path.display());
+                let _ = fs::remove_file(&path);
+            }
+            Err(e) => return Err(format!("Failed to create {}: {e}", path.display())),
+        }
+    }
+    Err(format!("Failed to create {}", path.display()))
+}
@@ -0,0 +1,22 @@
+/*!
+   Server Service
+
+   Installs the headless API server (`jan serve`) as a user-level service, so a model is served
+   from login on without the app running:
+   - macOS: a launchd agent in `~/Library/LaunchAgents`,
+   - Linux: a systemd user unit in `~/.config/systemd/user`,
+   - Windows: a scheduled task that runs at logon, which needs no administrator rights unlike
+     a Windows service.
+
+   The service runs the same `jan` binary the app puts on PATH, against the same data folder.
+   `jan serve` holds `server.lock` in the data folder while it runs, so a manual `jan serve`
+   and the service never run side by side, and the app can tell whether the service is up.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+use super::constants::DEFAULT_SERVICE_PORT;
+
+fn default_port() -> u16 {
+    DEFAULT_SERVICE_PORT
+}
+
+/// What the service serves, passed on to `jan serve`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServiceOptions {
+    pub model_id: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    /// Required from clients when not empty
+    #[serde(default)]
+    pub api_key: String,
+    /// Context window in tokens, `jan serve`'s default when unset
+    #[serde(default)]
+    pub ctx_size: Option<i32>,
+    /// Fit the context to the available VRAM
+    #[serde(default)]
+    pub fit: bool,
+}
+
+/// Contents of `server.lock`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerLockInfo {
+    pub pid: u32,
+    pub model_id: String,
+    pub port: u16,
+    /// RFC 3339
+    pub started_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceStatus {
+    pub installed: bool,
+    /// The plist or unit file; unset for the Windows task
+    pub definition_path: Option<String>,
+    /// The headless server running on this data folder, installed as a service or not
+    pub running: Option<ServerLockInfo>,
+}
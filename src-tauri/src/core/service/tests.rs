@@ -0,0 +1,96 @@
+use std::path::Path;
+
+use super::helpers::{
+    acquire_server_lock, launchd_plist, read_server_lock, serve_args, server_lock_path,
+    systemd_unit, windows_task_command,
+};
+use super::models::{ServerLockInfo, ServiceOptions};
+
+fn options() -> ServiceOptions {
+    ServiceOptions {
+        model_id: "qwen3-8b".to_string(),
+        port: 6767,
+        api_key: String::new(),
+        ctx_size: Some(-1),
+        fit: true,
+    }
+}
+
+#[test]
+fn test_serve_args() {
+    assert_eq!(
+        serve_args(&options()),
+        vec!["serve", "qwen3-8b", "--port=6767", "--ctx-size=-1", "--fit"]
+    );
+    let with_key = ServiceOptions {
+        api_key: "secret".to_string(),
+        ctx_size: None,
+        fit: false,
+        ..options()
+    };
+    assert_eq!(
+        serve_args(&with_key),
+        vec!["serve", "qwen3-8b", "--port=6767", "--api-key=secret"]
+    );
+}
+
+#[test]
+fn test_service_definitions() {
+    let args = vec!["serve".to_string(), "a&b".to_string()];
+    let plist = launchd_plist(
+        Path::new("/usr/local/bin/jan"),
+        &args,
+        Path::new("/tmp/service.log"),
+    );
+    assert!(plist.contains("<string>/usr/local/bin/jan</string>"));
+    assert!(plist.contains("<string>a&amp;b</string>"));
+    assert!(plist.contains("<string>ai.jan.server</string>"));
+
+    let args = vec!["--api-key=50%$off".to_string()];
+    let unit = systemd_unit(Path::new("/home/me/.local/bin/jan"), &args);
+    assert!(unit.contains("ExecStart=\"/home/me/.local/bin/jan\" \"--api-key=50%%$$off\"\n"));
+    assert!(unit.contains("WantedBy=default.target"));
+
+    let command = windows_task_command(
+        Path::new(r"C:\Program Files\Jan\jan.exe"),
+        &["serve".to_string()],
+    )
+    .unwrap();
+    assert_eq!(command, r#""C:\Program Files\Jan\jan.exe" serve"#);
+    assert!(windows_task_command(Path::new("jan.exe"), &["x".repeat(300)]).is_err());
+}
+
+#[test]
+fn test_server_lock() {
+    let dir = std::env::temp_dir().join(format!("jan-service-{}", uuid::Uuid::new_v4()));
+    let info = ServerLockInfo {
+        pid: std::process::id(),
+        model_id: "qwen3-8b".to_string(),
+        port: 6767,
+        started_at: "2026-01-01T00:00:00Z".to_string(),
+    };
+    let lock = acquire_server_lock(&dir, &info).unwrap();
+    assert_eq!(read_server_lock(&dir), Some(info.clone()));
+    assert!(acquire_server_lock(&dir, &info)
+        .unwrap_err()
+        .contains("already running"));
+    drop(lock);
+    assert!(!server_lock_path(&dir).exists());
+
+    // The lock of a process that is gone is taken over
+    let dead = ServerLockInfo {
+        pid: 0x3FFF_FFFF,
+        ..info.clone()
+    };
+    std::fs::write(
+        server_lock_path(&dir),
+        serde_json::to_string(&dead).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(read_server_lock(&dir), None);
+    let lock = acquire_server_lock(&dir, &info).unwrap();
+    assert_eq!(read_server_lock(&dir), Some(info));
+    drop(lock);
+
+    let _ = std::fs::remove_dir_all(dir);
+}
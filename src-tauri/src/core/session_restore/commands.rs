@@ -0,0 +1,18 @@
+use tauri::{AppHandle, Runtime};
+
+use super::helpers::{clear_session, read_session};
+use super::models::SessionSnapshot;
+use crate::core::app::commands::get_jan_data_folder_path;
+
+/// The session saved at the last exit, if any
+#[tauri::command]
+pub fn get_saved_session<R: Runtime>(app_handle: AppHandle<R>) -> Option<SessionSnapshot> {
+    read_session(&get_jan_data_folder_path(app_handle))
+}
+
+/// Forget the saved session, so nothing is restored on the next launch unless the session is
+/// saved again at exit
+#[tauri::command]
+pub fn clear_saved_session<R: Runtime>(app_handle: AppHandle<R>) -> Result<(), String> {
+    clear_session(&get_jan_data_folder_path(app_handle))
+}
@@ -0,0 +1,7 @@
+pub const SESSION_FILE: &str = "session.json";
+
+pub const SESSION_RESTORED_EVENT: &str = "session-restored";
+
+/// Environment variable holding the API key of a llama.cpp session. Not saved; a restored
+/// session gets a new key.
+pub const LLAMA_API_KEY_ENV: &str = "LLAMA_API_KEY";
@@ -0,0 +1,275 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use jan_utils::progress::ProgressPayload;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_llamacpp::{
+    get_random_available_port, load_llama_model_impl, LLamaBackendSession, LlamacppState,
+};
+
+use super::constants::{LLAMA_API_KEY_ENV, SESSION_FILE, SESSION_RESTORED_EVENT};
+use super::models::{RestorableModel, SessionRestore, SessionSnapshot};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::config_store::helpers::write_atomic;
+use crate::core::events::helpers::emit_task_progress;
+use crate::core::mcp::helpers::{extract_active_status, start_mcp_server};
+use crate::core::mcp::logs::record_server_log;
+use crate::core::settings::helpers::session_restore_settings;
+use crate::core::state::{AppState, InFlightOperation};
+
+pub fn get_session_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(SESSION_FILE)
+}
+
+/// The saved session, if there is one that can be read
+pub fn read_session(data_folder: &Path) -> Option<SessionSnapshot> {
+    let data = fs::read_to_string(get_session_path(data_folder)).ok()?;
+    match serde_json::from_str(&data) {
+        Ok(snapshot) => Some(snapshot),
+        Err(e) => {
+            log::warn!("Ignoring unreadable {SESSION_FILE}: {e}");
+            None
+        }
+    }
+}
+
+pub fn write_session(data_folder: &Path, snapshot: &SessionSnapshot) -> Result<(), String> {
+    let data = serde_json::to_vec_pretty(snapshot).map_err(|e| e.to_string())?;
+    write_atomic(&get_session_path(data_folder), &data)
+}
+
+pub fn clear_session(data_folder: &Path) -> Result<(), String> {
+    let path = get_session_path(data_folder);
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Active MCP servers that `run_mcp_commands` won't start on its own: those missing from
+/// `mcp_config` or marked inactive there
+pub fn manually_started_servers(
+    active: &HashMap<String, Value>,
+    mcp_config: &Value,
+) -> BTreeMap<String, Value> {
+    let configured = mcp_config.get("mcpServers");
+    active
+        .iter()
+        .filter(
+            |(name, _)| match configured.and_then(|servers| servers.get(*name)) {
+                Some(config) => extract_active_status(config) == Some(false),
+                None => true,
+            },
+        )
+        .map(|(name, config)| (name.clone(), config.clone()))
+        .collect()
+}
+
+/// A loaded session as it is saved. The API key is left out.
+pub fn restorable_model(session: &LLamaBackendSession) -> RestorableModel {
+    let mut load = session.load.clone();
+    load.envs.remove(LLAMA_API_KEY_ENV);
+    RestorableModel {
+        model_id: session.info.model_id.clone(),
+        model_path: session.info.model_path.clone(),
+        mmproj_path: session.info.mmproj_path.clone(),
+        is_embedding: session.info.is_embedding,
+        load,
+    }
+}
+
+/// What is loaded and running now
+pub async fn capture_session<R: Runtime>(app: &AppHandle<R>) -> SessionSnapshot {
+    let settings = session_restore_settings(app);
+    let mut snapshot = SessionSnapshot {
+        saved_at: chrono::Utc::now().timestamp_millis(),
+        ..Default::default()
+    };
+    if settings.restore_models {
+        if let Some(llama) = app.try_state::<LlamacppState>() {
+            let sessions = llama.llama_server_process.lock().await;
+            snapshot.models = sessions.values().map(restorable_model).collect();
+            snapshot.models.sort_by(|a, b| a.model_id.cmp(&b.model_id));
+        }
+    }
+    if settings.restore_mcp_servers {
+        let mcp_config =
+            fs::read_to_string(get_jan_data_folder_path(app.clone()).join("mcp_config.json"))
+                .ok()
+                .and_then(|data| serde_json::from_str(&data).ok())
+                .unwrap_or(Value::Null);
+        let active = app.state::<AppState>().mcp_active_servers.lock().await;
+        snapshot.mcp_servers = manually_started_servers(&active, &mcp_config);
+    }
+    snapshot
+}
+
+/// Save the session for the next launch, or drop the saved one when restoring is off. Runs
+/// at exit, before the servers and models are shut down.
+pub async fn save_session<R: Runtime>(app: &AppHandle<R>) {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let result = if session_restore_settings(app).enabled {
+        let snapshot = capture_session(app).await;
+        log::info!(
+            "Saving session with {} models and {} MCP servers",
+            snapshot.models.len(),
+            snapshot.mcp_servers.len()
+        );
+        write_session(&data_folder, &snapshot)
+    } else {
+        clear_session(&data_folder)
+    };
+    if let Err(e) = result {
+        log::error!("Failed to save the session: {e}");
+    }
+}
+
+/// Start the saved MCP servers that aren't running yet. Returns the started servers.
+pub async fn restore_mcp_servers<R: Runtime>(
+    app: &AppHandle<R>,
+    servers: &BTreeMap<String, Value>,
+) -> Vec<String> {
+    let state = app.state::<AppState>();
+    let mut restored = Vec::new();
+    for (name, config) in servers {
+        if state.mcp_active_servers.lock().await.contains_key(name) {
+            continue;
+        }
+        log::info!("Restoring MCP server {name} from the last session");
+        record_server_log(name, log::Level::Info, "Restoring from the last session");
+        // Started directly rather than through a restart, so the restart metrics only count
+        // servers that failed on their own
+        let app = app.clone();
+        let servers_state = state.mcp_servers.clone();
+        let in_flight = state.in_flight.clone();
+        let (name, config) = (name.clone(), config.clone());
+        restored.push(name.clone());
+        tauri::async_runtime::spawn(async move {
+            let start = start_mcp_server(app, servers_state, name.clone(), config);
+            let _ = in_flight
+                .run(InFlightOperation::McpStart(name), start)
+                .await;
+        });
+    }
+    restored
+}
+
+/// Load the saved models again, one after another. Returns the loaded models and the ones
+/// that failed with the reason.
+pub async fn restore_engine_sessions<R: Runtime>(
+    app: &AppHandle<R>,
+    models: &[RestorableModel],
+) -> (Vec<String>, Vec<(String, String)>) {
+    let mut restored = Vec::new();
+    let mut failed = Vec::new();
+    let Some(llama) = app.try_state::<LlamacppState>() else {
+        return (restored, failed);
+    };
+    for model in models {
+        let loaded = llama
+            .llama_server_process
+            .lock()
+            .await
+            .values()
+            .any(|session| session.info.model_id == model.model_id);
+        if loaded {
+            continue;
+        }
+        match restore_engine_session(app, &llama, model).await {
+            Ok(()) => {
+                log::info!("Restored model {} from the last session", model.model_id);
+                restored.push(model.model_id.clone());
+            }
+            Err(e) => {
+                log::warn!("Failed to restore model {}: {e}", model.model_id);
+                failed.push((model.model_id.clone(), e));
+            }
+        }
+    }
+    (restored, failed)
+}
+
+async fn restore_engine_session<R: Runtime>(
+    app: &AppHandle<R>,
+    llama: &LlamacppState,
+    model: &RestorableModel,
+) -> Result<(), String> {
+    for path in [&model.load.backend_path, &model.model_path]
+        .into_iter()
+        .chain(model.mmproj_path.as_ref())
+    {
+        if !Path::new(path).exists() {
+            return Err(format!("{path} no longer exists"));
+        }
+    }
+    let port = get_random_available_port(app.clone()).await?;
+    let mut envs = model.load.envs.clone();
+    envs.insert(
+        LLAMA_API_KEY_ENV.to_string(),
+        hex::encode(rand::random::<[u8; 32]>()),
+    );
+    let emitter = app.clone();
+    let process_map = llama.llama_server_process.clone();
+    let key = model.model_id.clone();
+    let model = model.clone();
+    // The UI may load the same model meanwhile; then both get the one session
+    llama
+        .load_once(&key, async move {
+            load_llama_model_impl(
+                process_map,
+                &model.load.backend_path,
+                model.model_id,
+                model.model_path,
+                port,
+                model.load.config,
+                envs,
+                model.mmproj_path,
+                model.is_embedding,
+                model.load.timeout,
+                Some(Arc::new(move |payload: ProgressPayload| {
+                    emit_task_progress(&emitter, &payload)
+                })),
+            )
+            .await
+        })
+        .await
+        .map(drop)
+        .map_err(|e| e.to_string())
+}
+
+/// Bring back the saved session and tell the UI through `session-restored`
+pub async fn restore_session<R: Runtime>(
+    app: &AppHandle<R>,
+    snapshot: &SessionSnapshot,
+) -> SessionRestore {
+    let settings = session_restore_settings(app);
+    let mut restore = SessionRestore::default();
+    if settings.restore_mcp_servers {
+        restore.restored_servers = restore_mcp_servers(app, &snapshot.mcp_servers).await;
+    }
+    if settings.restore_models {
+        (restore.restored_models, restore.failed_models) =
+            restore_engine_sessions(app, &snapshot.models).await;
+    }
+    if let Err(e) = app.emit(SESSION_RESTORED_EVENT, &restore) {
+        log::error!("Failed to emit {SESSION_RESTORED_EVENT} event: {e}");
+    }
+    restore
+}
+
+/// Restore the last session in the background, if session restore is enabled
+pub fn start_session_restore<R: Runtime>(app: &AppHandle<R>) {
+    if !session_restore_settings(app).enabled {
+        return;
+    }
+    let Some(snapshot) = read_session(&get_jan_data_folder_path(app.clone())) else {
+        return;
+    };
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        restore_session(&app, &snapshot).await;
+    });
+}
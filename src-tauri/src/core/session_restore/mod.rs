@@ -0,0 +1,22 @@
+/*!
+   Session Restore
+
+   With session restore enabled, the app remembers at exit which llama.cpp models were
+   loaded and which MCP servers were started by hand, and brings them back on the next
+   launch. Servers that are active in `mcp_config.json` are started from the config anyway,
+   so only those missing from it or marked inactive are saved.
+
+   The snapshot lives in `session.json` in the data folder. A model is saved with the
+   arguments it was loaded with, minus its API key; it gets a fresh port and key when it is
+   loaded again. Models are loaded one after another so the restore doesn't compete for
+   memory, and models that are already loaded or whose files are gone are skipped. A
+   `session-restored` event tells the UI what came back.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,35 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri_plugin_llamacpp::LoadRequest;
+
+/// A model that was loaded at exit, with what it takes to load it again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestorableModel {
+    pub model_id: String,
+    pub model_path: String,
+    pub mmproj_path: Option<String>,
+    pub is_embedding: bool,
+    pub load: LoadRequest,
+}
+
+/// Contents of `session.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionSnapshot {
+    pub saved_at: i64,
+    pub models: Vec<RestorableModel>,
+    /// Configs of the MCP servers started by hand, by name
+    pub mcp_servers: BTreeMap<String, Value>,
+}
+
+/// Payload of the `session-restored` event
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionRestore {
+    pub restored_models: Vec<String>,
+    /// Models that couldn't be loaded again, with the reason
+    pub failed_models: Vec<(String, String)>,
+    pub restored_servers: Vec<String>,
+}
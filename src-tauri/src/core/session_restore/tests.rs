@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+use std::fs;
+
+use serde_json::json;
+
+use super::helpers::{clear_session, manually_started_servers, read_session, write_session};
+use super::models::{SessionRestore, SessionSnapshot};
+
+#[test]
+fn test_manually_started_servers() {
+    let mcp_config = json!({
+        "mcpServers": {
+            "filesystem": { "command": "npx", "active": true },
+            "fetch": { "command": "uvx" },
+            "browser": { "command": "npx", "active": false }
+        }
+    });
+    let active: HashMap<_, _> = ["filesystem", "fetch", "browser", "scratch"]
+        .into_iter()
+        .map(|name| (name.to_string(), json!({ "command": name })))
+        .collect();
+
+    let manual = manually_started_servers(&active, &mcp_config);
+    // Active in the config, or active by default, means started from the config
+    assert_eq!(
+        manual.keys().collect::<Vec<_>>(),
+        vec!["browser", "scratch"]
+    );
+    assert_eq!(manual["scratch"], json!({ "command": "scratch" }));
+
+    // Without a readable config every running server was started by hand
+    assert_eq!(
+        manually_started_servers(&active, &serde_json::Value::Null).len(),
+        4
+    );
+}
+
+#[test]
+fn test_session_round_trip() {
+    let root = std::env::temp_dir().join(format!("jan-session-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&root).unwrap();
+    assert!(read_session(&root).is_none());
+
+    let snapshot = SessionSnapshot {
+        saved_at: 1_700_000_000_000,
+        models: Vec::new(),
+        mcp_servers: [("scratch".to_string(), json!({ "command": "node" }))]
+            .into_iter()
+            .collect(),
+    };
+    write_session(&root, &snapshot).unwrap();
+    let read = read_session(&root).unwrap();
+    assert_eq!(read.saved_at, snapshot.saved_at);
+    assert_eq!(read.mcp_servers, snapshot.mcp_servers);
+
+    clear_session(&root).unwrap();
+    assert!(read_session(&root).is_none());
+    // Clearing twice is fine
+    clear_session(&root).unwrap();
+
+    // A damaged file is ignored rather than failing the launch
+    fs::write(root.join("session.json"), b"{ not json").unwrap();
+    assert!(read_session(&root).is_none());
+    fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_session_restore_payload() {
+    let restore = SessionRestore {
+        restored_models: vec!["llama3.2-3b".to_string()],
+        failed_models: vec![("qwen3-8b".to_string(), "gone".to_string())],
+        restored_servers: vec!["scratch".to_string()],
+    };
+    assert_eq!(
+        serde_json::to_value(&restore).unwrap(),
+        json!({
+            "restoredModels": ["llama3.2-3b"],
+            "failedModels": [["qwen3-8b", "gone"]],
+            "restoredServers": ["scratch"]
+        })
+    );
+}
@@ -0,0 +1,35 @@
+use serde_json::Value;
+use tauri::{AppHandle, Runtime, State};
+
+use super::helpers::{
+    admin_policy, admin_policy_path, current_settings, locked_keys, update_settings,
+};
+use super::models::{AdminPolicyStatus, Settings};
+use super::SettingsState;
+
+#[tauri::command]
+pub async fn get_settings<R: Runtime>(app: AppHandle<R>) -> Result<Settings, String> {
+    Ok(current_settings(&app).await)
+}
+
+/// Apply a JSON merge patch such as `{"server": {"port": 1338}}`. The result is validated as
+/// a whole; nothing is stored when any value is out of range.
+#[tauri::command]
+pub async fn set_settings<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, SettingsState>,
+    patch: Value,
+) -> Result<Settings, String> {
+    update_settings(&app, &state, &patch).await
+}
+
+/// The admin policy of this machine and the keys it locks
+#[tauri::command]
+pub fn get_admin_policy() -> AdminPolicyStatus {
+    let policy = admin_policy();
+    AdminPolicyStatus {
+        path: admin_policy_path().to_string_lossy().into_owned(),
+        policy: policy.clone(),
+        locked_keys: locked_keys(policy),
+    }
+}
@@ -0,0 +1,56 @@
+pub const SETTINGS_FILE: &str = "core_settings.json";
+
+// Emitted with the changed keys whenever settings are updated
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+
+// Section stored in mcp_config.json, where servers read it, rather than in SETTINGS_FILE
+pub const MCP_SECTION: &str = "mcp";
+
+pub const DEFAULT_SERVER_HOST: &str = "127.0.0.1";
+pub const DEFAULT_SERVER_PORT: u16 = 1337;
+pub const DEFAULT_SERVER_PREFIX: &str = "/v1";
+pub const DEFAULT_PROXY_TIMEOUT_SECS: u64 = 600;
+pub const MAX_PROXY_TIMEOUT_SECS: u64 = 86_400;
+
+pub const DEFAULT_MAX_PARALLEL_DOWNLOADS: usize = 8;
+pub const MAX_PARALLEL_DOWNLOADS: usize = 16;
+
+pub const DEFAULT_SUMMARY_EVERY_MESSAGES: usize = 6;
+pub const MAX_SUMMARY_EVERY_MESSAGES: usize = 200;
+
+pub const DEFAULT_OUTBOX_MAX_ATTEMPTS: u32 = 10;
+pub const MAX_OUTBOX_ATTEMPTS: u32 = 100;
+
+pub const DEFAULT_GUARDRAIL_MAX_TOKENS_PER_REQUEST: u64 = 8_192;
+pub const MAX_GUARDRAIL_TOKENS_PER_REQUEST: u64 = 1_000_000;
+pub const DEFAULT_GUARDRAIL_MAX_TOKENS_PER_HOUR: u64 = 200_000;
+pub const MAX_GUARDRAIL_TOKENS_PER_HOUR: u64 = 1_000_000_000;
+
+pub const DEFAULT_LOG_RETENTION_DAYS: u64 = 30;
+pub const DEFAULT_LOG_RETENTION_MB: u64 = 500;
+pub const DEFAULT_TEMP_DOWNLOAD_RETENTION_DAYS: u64 = 14;
+pub const MAX_RETENTION_DAYS: u64 = 3_650;
+pub const MAX_RETENTION_MB: u64 = 1_000_000;
+
+pub const DEFAULT_EVENT_COALESCE_INTERVAL_MS: u64 = 100;
+pub const MAX_EVENT_COALESCE_INTERVAL_MS: u64 = 5_000;
+
+pub const DEFAULT_TRACING_OTLP_ENDPOINT: &str = "http://localhost:4318";
+pub const DEFAULT_TRACING_SERVICE_NAME: &str = "jan";
+
+pub const MAX_MCP_TOOL_CALL_TIMEOUT_SECS: u64 = 3_600;
+pub const MIN_MCP_RESTART_DELAY_MS: u64 = 100;
+pub const MAX_MCP_RESTART_DELAY_MS: u64 = 600_000;
+pub const MAX_MCP_BACKOFF_MULTIPLIER: f64 = 10.0;
+pub const MAX_MCP_STARTUP_CONCURRENCY: usize = 32;
+pub const MAX_MCP_STARTUP_BUDGET_SECS: u64 = 600;
+
+// Machine-level policy of an administrator, read once at launch; see `helpers::admin_policy`
+pub const ADMIN_POLICY_FILE: &str = "policy.json";
+/// Overrides the policy path in debug builds only
+pub const ADMIN_POLICY_ENV: &str = "JAN_POLICY_FILE";
+// Keys reported as locked for what the policy fixes outside the core settings
+pub const LOCKED_OFFLINE_KEY: &str = "offline.enabled";
+pub const LOCKED_TELEMETRY_KEY: &str = "telemetry.enabled";
+pub const LOCKED_PROVIDERS_KEY: &str = "providers";
+pub const LOCKED_MCP_COMMANDS_KEY: &str = "mcpServers.command";
@@ -0,0 +1,663 @@
+use std::fmt::Display;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use super::constants::{
+    ADMIN_POLICY_ENV, ADMIN_POLICY_FILE, LOCKED_MCP_COMMANDS_KEY, LOCKED_OFFLINE_KEY,
+    LOCKED_PROVIDERS_KEY, LOCKED_TELEMETRY_KEY, MAX_EVENT_COALESCE_INTERVAL_MS,
+    MAX_GUARDRAIL_TOKENS_PER_HOUR, MAX_GUARDRAIL_TOKENS_PER_REQUEST, MAX_MCP_BACKOFF_MULTIPLIER,
+    MAX_MCP_RESTART_DELAY_MS, MAX_MCP_STARTUP_BUDGET_SECS, MAX_MCP_STARTUP_CONCURRENCY,
+    MAX_MCP_TOOL_CALL_TIMEOUT_SECS, MAX_OUTBOX_ATTEMPTS, MAX_PARALLEL_DOWNLOADS,
+    MAX_PROXY_TIMEOUT_SECS, MAX_RETENTION_DAYS, MAX_RETENTION_MB, MAX_SUMMARY_EVERY_MESSAGES,
+    MCP_SECTION, MIN_MCP_RESTART_DELAY_MS, SETTINGS_CHANGED_EVENT, SETTINGS_FILE,
+};
+use super::models::{
+    AdminPolicy, DownloadSettings, EventSettings, GuardrailSettings, LanSettings,
+    LocalTextSettings, OutboxSettings, ReasoningSettings, RetentionSettings, ServerSettings,
+    SessionRestoreSettings, SettingChange, Settings, SettingsChangedEvent, SummarySettings,
+    TracingSettings,
+};
+use super::SettingsState;
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::config_history::helpers::record_config_change;
+use crate::core::config_store::helpers::{config_store, write_json};
+use crate::core::events::EventCoalescer;
+use crate::core::guardrails::Guardrails;
+use crate::core::mcp::migrations::update_config_async;
+use crate::core::mcp::models::McpSettings;
+use crate::core::otel::helpers::{apply_tracing_settings, otlp_traces_url};
+use crate::core::server::commands::restart_server_if_running;
+use crate::core::state::AppState;
+
+pub fn get_settings_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(SETTINGS_FILE)
+}
+
+/// Stored settings, with defaults for anything missing or unreadable
+pub fn read_settings(data_folder: &Path) -> Settings {
+    let Ok(data) = config_store().read(&get_settings_path(data_folder)) else {
+        return Settings::default();
+    };
+    serde_json::from_str(&data).unwrap_or_else(|e| {
+        log::warn!("Ignoring unreadable {SETTINGS_FILE}: {e}");
+        Settings::default()
+    })
+}
+
+pub fn write_settings(data_folder: &Path, settings: &Settings) -> Result<(), String> {
+    let mut value = serde_json::to_value(settings).map_err(|e| e.to_string())?;
+    if let Some(object) = value.as_object_mut() {
+        object.remove(MCP_SECTION);
+    }
+    write_json(&get_settings_path(data_folder), &value)
+}
+
+/// Load the stored settings into the managed state
+pub fn load_settings<R: Runtime>(app: &AppHandle<R>) {
+    let settings = read_settings(&get_jan_data_folder_path(app.clone()));
+    app.state::<SettingsState>().set(settings);
+}
+
+/// Settings in effect, with the MCP section as currently loaded from `mcp_config.json`
+pub async fn current_settings<R: Runtime>(app: &AppHandle<R>) -> Settings {
+    let mut settings = app.state::<SettingsState>().stored();
+    settings.mcp = app.state::<AppState>().mcp_settings.lock().await.clone();
+    apply_policy(admin_policy(), settings)
+}
+
+/// Download settings in effect, defaults when the state is not managed
+pub fn download_settings<R: Runtime>(app: &AppHandle<R>) -> DownloadSettings {
+    app.try_state::<SettingsState>()
+        .map(|state| state.get().downloads)
+        .unwrap_or_default()
+}
+
+/// Title and summary settings in effect, defaults (disabled) when the state is not managed
+pub fn summary_settings<R: Runtime>(app: &AppHandle<R>) -> SummarySettings {
+    app.try_state::<SettingsState>()
+        .map(|state| state.get().summaries)
+        .unwrap_or_default()
+}
+
+/// LAN settings in effect, defaults (not advertised) when the state is not managed
+pub fn lan_settings<R: Runtime>(app: &AppHandle<R>) -> LanSettings {
+    app.try_state::<SettingsState>()
+        .map(|state| state.get().lan)
+        .unwrap_or_default()
+}
+
+/// Outbox settings in effect, defaults (disabled) when the state is not managed
+pub fn outbox_settings<R: Runtime>(app: &AppHandle<R>) -> OutboxSettings {
+    app.try_state::<SettingsState>()
+        .map(|state| state.get().outbox)
+        .unwrap_or_default()
+}
+
+/// Local text generation settings in effect, defaults when the state is not managed
+pub fn local_text_settings<R: Runtime>(app: &AppHandle<R>) -> LocalTextSettings {
+    app.try_state::<SettingsState>()
+        .map(|state| state.get().local_text)
+        .unwrap_or_default()
+}
+
+/// Guardrail settings in effect, defaults (disabled) when the state is not managed
+pub fn guardrail_settings<R: Runtime>(app: &AppHandle<R>) -> GuardrailSettings {
+    app.try_state::<SettingsState>()
+        .map(|state| state.get().guardrails)
+        .unwrap_or_default()
+}
+
+/// Retention settings in effect, defaults when the state is not managed
+pub fn retention_settings<R: Runtime>(app: &AppHandle<R>) -> RetentionSettings {
+    app.try_state::<SettingsState>()
+        .map(|state| state.get().retention)
+        .unwrap_or_default()
+}
+
+/// Event coalescing settings in effect, defaults when the state is not managed
+pub fn event_settings<R: Runtime>(app: &AppHandle<R>) -> EventSettings {
+    app.try_state::<SettingsState>()
+        .map(|state| state.get().events)
+        .unwrap_or_default()
+}
+
+/// Trace export settings in effect, defaults when the state is not managed
+pub fn tracing_settings<R: Runtime>(app: &AppHandle<R>) -> TracingSettings {
+    app.try_state::<SettingsState>()
+        .map(|state| state.get().tracing)
+        .unwrap_or_default()
+}
+
+/// Session restore settings in effect, defaults (disabled) when the state is not managed
+pub fn session_restore_settings<R: Runtime>(app: &AppHandle<R>) -> SessionRestoreSettings {
+    app.try_state::<SettingsState>()
+        .map(|state| state.get().session_restore)
+        .unwrap_or_default()
+}
+
+/// Reasoning settings in effect, defaults (stored, not sent back) when the state is not
+/// managed
+pub fn reasoning_settings<R: Runtime>(app: &AppHandle<R>) -> ReasoningSettings {
+    app.try_state::<SettingsState>()
+        .map(|state| state.get().reasoning)
+        .unwrap_or_default()
+}
+
+/// RFC 7386 merge: objects are merged recursively and `null` resets a key to its default
+pub fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+/// Reject keys of `patch` that are not part of the schema, so typos do not pass silently
+fn check_known_keys(
+    prefix: &str,
+    patch: &Map<String, Value>,
+    schema: &Value,
+) -> Result<(), String> {
+    for (key, value) in patch {
+        let path = join_key(prefix, key);
+        let Some(known) = schema.get(key) else {
+            return Err(format!("Unknown setting '{path}'"));
+        };
+        if let (Value::Object(nested), Value::Object(_)) = (value, known) {
+            check_known_keys(&path, nested, known)?;
+        }
+    }
+    Ok(())
+}
+
+/// Apply a merge patch to `current` and validate the result
+pub fn apply_patch(current: &Settings, patch: &Value) -> Result<Settings, String> {
+    let Value::Object(patch_object) = patch else {
+        return Err("Settings patch must be a JSON object".to_string());
+    };
+    let mut value = serde_json::to_value(current).map_err(|e| e.to_string())?;
+    check_known_keys("", patch_object, &value)?;
+    merge_patch(&mut value, patch);
+    let settings: Settings =
+        serde_json::from_value(value).map_err(|e| format!("Invalid settings: {e}"))?;
+    validate_settings(&settings)?;
+    Ok(settings)
+}
+
+fn check_range<T: PartialOrd + Display>(
+    key: &str,
+    value: T,
+    range: RangeInclusive<T>,
+) -> Result<(), String> {
+    if range.contains(&value) {
+        Ok(())
+    } else {
+        Err(format!(
+            "{key} must be between {} and {}, got {value}",
+            range.start(),
+            range.end()
+        ))
+    }
+}
+
+pub fn validate_settings(settings: &Settings) -> Result<(), String> {
+    let mcp = &settings.mcp;
+    check_range(
+        "mcp.toolCallTimeoutSeconds",
+        mcp.tool_call_timeout_seconds,
+        1..=MAX_MCP_TOOL_CALL_TIMEOUT_SECS,
+    )?;
+    check_range(
+        "mcp.baseRestartDelayMs",
+        mcp.base_restart_delay_ms,
+        MIN_MCP_RESTART_DELAY_MS..=MAX_MCP_RESTART_DELAY_MS,
+    )?;
+    check_range(
+        "mcp.maxRestartDelayMs",
+        mcp.max_restart_delay_ms,
+        mcp.base_restart_delay_ms..=MAX_MCP_RESTART_DELAY_MS,
+    )?;
+    check_range(
+        "mcp.backoffMultiplier",
+        mcp.backoff_multiplier,
+        1.0..=MAX_MCP_BACKOFF_MULTIPLIER,
+    )?;
+    check_range(
+        "mcp.startupConcurrency",
+        mcp.startup_concurrency,
+        1..=MAX_MCP_STARTUP_CONCURRENCY,
+    )?;
+    check_range(
+        "mcp.startupBudgetSeconds",
+        mcp.startup_budget_seconds,
+        0..=MAX_MCP_STARTUP_BUDGET_SECS,
+    )?;
+    check_range(
+        "downloads.max_parallel_files",
+        settings.downloads.max_parallel_files,
+        1..=MAX_PARALLEL_DOWNLOADS,
+    )?;
+
+    let summaries = &settings.summaries;
+    check_range(
+        "summaries.every_messages",
+        summaries.every_messages,
+        1..=MAX_SUMMARY_EVERY_MESSAGES,
+    )?;
+    if summaries.enabled && summaries.model.trim().is_empty() {
+        return Err("summaries.model must be set when summaries are enabled".to_string());
+    }
+    check_range(
+        "outbox.max_attempts",
+        settings.outbox.max_attempts,
+        1..=MAX_OUTBOX_ATTEMPTS,
+    )?;
+    let guardrails = &settings.guardrails;
+    check_range(
+        "guardrails.max_tokens_per_request",
+        guardrails.max_tokens_per_request,
+        0..=MAX_GUARDRAIL_TOKENS_PER_REQUEST,
+    )?;
+    check_range(
+        "guardrails.max_tokens_per_hour",
+        guardrails.max_tokens_per_hour,
+        0..=MAX_GUARDRAIL_TOKENS_PER_HOUR,
+    )?;
+    if guardrails
+        .banned_stop_sequences
+        .iter()
+        .any(String::is_empty)
+    {
+        return Err("guardrails.banned_stop_sequences must not contain empty strings".to_string());
+    }
+    let retention = &settings.retention;
+    for (name, policy) in [
+        ("logs", &retention.logs),
+        ("tool_audit", &retention.tool_audit),
+        ("download_temp", &retention.download_temp),
+        ("recordings", &retention.recordings),
+    ] {
+        check_range(
+            &format!("retention.{name}.max_age_days"),
+            policy.max_age_days,
+            0..=MAX_RETENTION_DAYS,
+        )?;
+        check_range(
+            &format!("retention.{name}.max_size_mb"),
+            policy.max_size_mb,
+            0..=MAX_RETENTION_MB,
+        )?;
+    }
+    check_range(
+        "events.coalesce_interval_ms",
+        settings.events.coalesce_interval_ms,
+        0..=MAX_EVENT_COALESCE_INTERVAL_MS,
+    )?;
+    if settings.tracing.service_name.trim().is_empty() {
+        return Err("tracing.service_name must not be empty".to_string());
+    }
+    if settings.tracing.enabled {
+        otlp_traces_url(&settings.tracing.otlp_endpoint)?;
+    }
+
+    let server = &settings.server;
+    if server.host.trim().is_empty() {
+        return Err("server.host must not be empty".to_string());
+    }
+    check_range("server.port", server.port, 1..=u16::MAX)?;
+    if !server.prefix.is_empty() && !server.prefix.starts_with('/') {
+        return Err("server.prefix must start with '/'".to_string());
+    }
+    check_range(
+        "server.proxy_timeout",
+        server.proxy_timeout,
+        1..=MAX_PROXY_TIMEOUT_SECS,
+    )
+}
+
+fn join_key(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{prefix}.{key}")
+    }
+}
+
+fn diff_values(key: &str, old: &Value, new: &Value, changes: &mut Vec<SettingChange>) {
+    match (old, new) {
+        (Value::Object(old), Value::Object(new)) => {
+            for (name, new_value) in new {
+                let old_value = old.get(name).unwrap_or(&Value::Null);
+                diff_values(&join_key(key, name), old_value, new_value, changes);
+            }
+            for (name, old_value) in old.iter().filter(|(name, _)| !new.contains_key(*name)) {
+                diff_values(&join_key(key, name), old_value, &Value::Null, changes);
+            }
+        }
+        _ if old != new => changes.push(SettingChange {
+            key: key.to_string(),
+            old: old.clone(),
+            new: new.clone(),
+        }),
+        _ => {}
+    }
+}
+
+/// Changed leaves between two settings, keyed by dotted path
+pub fn diff_settings(old: &Settings, new: &Settings) -> Vec<SettingChange> {
+    let (Ok(old), Ok(new)) = (serde_json::to_value(old), serde_json::to_value(new)) else {
+        return Vec::new();
+    };
+    let mut changes = Vec::new();
+    diff_values("", &old, &new, &mut changes);
+    changes
+}
+
+fn section_changed(changes: &[SettingChange], section: &str) -> bool {
+    changes
+        .iter()
+        .any(|change| change.key.split('.').next() == Some(section))
+}
+
+/// Let the subsystems whose section changed pick up the new values
+pub async fn apply_changes<R: Runtime>(
+    app: &AppHandle<R>,
+    settings: &Settings,
+    changes: &[SettingChange],
+) -> Result<(), String> {
+    if section_changed(changes, MCP_SECTION) {
+        *app.state::<AppState>().mcp_settings.lock().await = settings.mcp.clone();
+        let data_folder = get_jan_data_folder_path(app.clone());
+        let path = data_folder.join("mcp_config.json");
+        if path.exists() {
+            let mcp = serde_json::to_value(&settings.mcp).map_err(|e| e.to_string())?;
+            update_config_async(&path, move |config| {
+                config
+                    .as_object_mut()
+                    .ok_or("Config root is not an object")?
+                    .insert("mcpSettings".to_string(), mcp);
+                Ok(())
+            })
+            .await?;
+            record_config_change(&data_folder, "Update MCP settings");
+        }
+    }
+    if section_changed(changes, "guardrails") {
+        if let Some(guardrails) = app.try_state::<Guardrails>() {
+            guardrails.set_settings(settings.guardrails.clone());
+        }
+    }
+    if section_changed(changes, "events") {
+        if let Some(coalescer) = app.try_state::<EventCoalescer>() {
+            coalescer.set_interval(Duration::from_millis(settings.events.coalesce_interval_ms));
+        }
+    }
+    if section_changed(changes, "tracing") {
+        apply_tracing_settings(app, &settings.tracing).await?;
+    }
+    // The server is advertised on the LAN when it starts
+    if section_changed(changes, "server") || section_changed(changes, "lan") {
+        restart_server_if_running(app, &settings.server).await?;
+    }
+    Ok(())
+}
+
+/// Apply a JSON merge patch to the settings in effect, then store, announce and apply the
+/// changes
+pub async fn update_settings<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &SettingsState,
+    patch: &Value,
+) -> Result<Settings, String> {
+    let _guard = state.update_lock.lock().await;
+    let policy = admin_policy();
+    check_unlocked(policy, patch)?;
+    let current = current_settings(app).await;
+    // The patch applies to the settings as stored, which don't hold the policy's values
+    let mut stored = state.stored();
+    stored.mcp = current.mcp.clone();
+    let stored = apply_patch(&stored, patch)?;
+    let updated = apply_policy(policy, stored.clone());
+    let changes = diff_settings(&current, &updated);
+    if changes.is_empty() {
+        return Ok(updated);
+    }
+
+    write_settings(&get_jan_data_folder_path(app.clone()), &stored)?;
+    state.set(stored);
+    log::info!(
+        "Settings changed: {}",
+        changes
+            .iter()
+            .map(|change| change.key.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let _ = app.emit(
+        SETTINGS_CHANGED_EVENT,
+        SettingsChangedEvent {
+            changes: changes.clone(),
+            settings: updated.clone(),
+        },
+    );
+    apply_changes(app, &updated, &changes).await?;
+    Ok(updated)
+}
+
+/// Remember the configuration the server was started with, without restarting it
+pub async fn record_server_settings<R: Runtime>(app: &AppHandle<R>, server: ServerSettings) {
+    let Some(state) = app.try_state::<SettingsState>() else {
+        return;
+    };
+    let _guard = state.update_lock.lock().await;
+    let mut settings = state.stored();
+    if settings.server == server {
+        return;
+    }
+    settings.server = server;
+    if let Err(e) = write_settings(&get_jan_data_folder_path(app.clone()), &settings) {
+        log::warn!("Failed to store server settings: {e}");
+    }
+    state.set(settings);
+}
+
+/// Where the admin policy is read from: a machine-wide folder only administrators can write
+/// to. Debug builds may point `JAN_POLICY_FILE` elsewhere; release builds ignore it, as any
+/// user could otherwise lift the policy.
+pub fn admin_policy_path() -> PathBuf {
+    if cfg!(debug_assertions) {
+        if let Some(path) = std::env::var_os(ADMIN_POLICY_ENV).filter(|path| !path.is_empty()) {
+            return PathBuf::from(path);
+        }
+    }
+    let folder = if cfg!(target_os = "windows") {
+        std::env::var_os("ProgramData")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(r"C:\ProgramData"))
+            .join("Jan")
+    } else if cfg!(target_os = "macos") {
+        PathBuf::from("/Library/Application Support/Jan")
+    } else {
+        PathBuf::from("/etc/jan")
+    };
+    folder.join(ADMIN_POLICY_FILE)
+}
+
+/// The policy at `path`, empty when there is none. Settings fixed to values that don't pass
+/// validation are dropped rather than forced on the user.
+pub fn read_admin_policy(path: &Path) -> AdminPolicy {
+    let Ok(data) = fs::read_to_string(path) else {
+        return AdminPolicy::default();
+    };
+    let mut policy: AdminPolicy = match serde_json::from_str(&data) {
+        Ok(policy) => policy,
+        Err(e) => {
+            log::error!("Ignoring unreadable admin policy {}: {e}", path.display());
+            return AdminPolicy::default();
+        }
+    };
+    let fixed = Value::Object(policy.settings.clone());
+    if let Err(e) = apply_patch(&Settings::default(), &fixed) {
+        log::error!(
+            "Ignoring the settings of admin policy {}: {e}",
+            path.display()
+        );
+        policy.settings.clear();
+    }
+    policy
+}
+
+static ADMIN_POLICY: OnceLock<AdminPolicy> = OnceLock::new();
+
+/// The admin policy of this machine, read on first use. Changes take effect on the next
+/// launch.
+pub fn admin_policy() -> &'static AdminPolicy {
+    ADMIN_POLICY.get_or_init(|| {
+        let path = admin_policy_path();
+        let policy = read_admin_policy(&path);
+        if policy != AdminPolicy::default() {
+            log::info!(
+                "Admin policy {} locks {}",
+                path.display(),
+                locked_keys(&policy).join(", ")
+            );
+        }
+        policy
+    })
+}
+
+/// `settings` with the values `policy` fixes
+pub fn apply_policy(policy: &AdminPolicy, settings: Settings) -> Settings {
+    if policy.settings.is_empty() {
+        return settings;
+    }
+    let Ok(mut value) = serde_json::to_value(&settings) else {
+        return settings;
+    };
+    merge_patch(&mut value, &Value::Object(policy.settings.clone()));
+    serde_json::from_value(value).unwrap_or(settings)
+}
+
+/// MCP settings as loaded from `mcp_config.json`, with the values the admin policy fixes
+pub fn enforce_mcp_policy(mcp: McpSettings) -> McpSettings {
+    let settings = Settings {
+        mcp,
+        ..Default::default()
+    };
+    apply_policy(admin_policy(), settings).mcp
+}
+
+/// Leaves of a settings patch by dotted key. Empty objects have none.
+fn patch_leaves<'a>(prefix: &str, value: &'a Value, leaves: &mut Vec<(String, &'a Value)>) {
+    match value {
+        Value::Object(entries) => {
+            for (key, entry) in entries {
+                patch_leaves(&join_key(prefix, key), entry, leaves);
+            }
+        }
+        _ => leaves.push((prefix.to_string(), value)),
+    }
+}
+
+/// Keys users can't change under `policy`, sorted
+pub fn locked_keys(policy: &AdminPolicy) -> Vec<String> {
+    let fixed = Value::Object(policy.settings.clone());
+    let mut leaves = Vec::new();
+    patch_leaves("", &fixed, &mut leaves);
+    let mut keys: Vec<String> = leaves.into_iter().map(|(key, _)| key).collect();
+    let others = [
+        (policy.force_offline, LOCKED_OFFLINE_KEY),
+        (policy.disable_telemetry, LOCKED_TELEMETRY_KEY),
+        (policy.allowed_providers.is_some(), LOCKED_PROVIDERS_KEY),
+        (
+            !policy.banned_mcp_commands.is_empty(),
+            LOCKED_MCP_COMMANDS_KEY,
+        ),
+    ];
+    keys.extend(
+        others
+            .into_iter()
+            .filter(|(locked, _)| *locked)
+            .map(|(_, key)| key.to_string()),
+    );
+    keys.sort();
+    keys
+}
+
+/// Refuse a patch that changes a setting `policy` fixes. Setting it to the fixed value is
+/// fine, so a UI may send a whole section back.
+pub fn check_unlocked(policy: &AdminPolicy, patch: &Value) -> Result<(), String> {
+    let fixed = Value::Object(policy.settings.clone());
+    let mut locked = Vec::new();
+    patch_leaves("", &fixed, &mut locked);
+    let mut changed = Vec::new();
+    patch_leaves("", patch, &mut changed);
+    for (key, value) in &changed {
+        for (locked_key, locked_value) in &locked {
+            let conflict = if key == locked_key {
+                value != locked_value
+            } else {
+                locked_key.starts_with(&format!("{key}."))
+                    || key.starts_with(&format!("{locked_key}."))
+            };
+            if conflict {
+                return Err(format!(
+                    "Setting '{locked_key}' is locked by the administrator policy"
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Refuse a remote provider `policy` doesn't allow
+pub fn check_provider_allowed(policy: &AdminPolicy, provider: &str) -> Result<(), String> {
+    match &policy.allowed_providers {
+        Some(allowed) if !allowed.iter().any(|a| a.eq_ignore_ascii_case(provider)) => Err(format!(
+            "Provider '{provider}' is not allowed by the administrator policy"
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Refuse an MCP server command `policy` bans. An entry naming a program bans it wherever it
+/// is installed, with or without an extension such as `.exe`; an entry with a path only bans
+/// that path.
+pub fn check_mcp_command_allowed(policy: &AdminPolicy, command: &str) -> Result<(), String> {
+    let command = command.trim();
+    let program = |path: &str| {
+        Path::new(path)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_ascii_lowercase())
+    };
+    let banned = policy.banned_mcp_commands.iter().any(|entry| {
+        if entry.contains(&['/', '\\'][..]) {
+            Path::new(entry) == Path::new(command)
+        } else {
+            program(entry).is_some() && program(entry) == program(command)
+        }
+    });
+    if banned {
+        return Err(format!(
+            "The administrator policy doesn't allow MCP servers to run '{command}'"
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,56 @@
+/*!
+   Settings Module
+
+   Typed core settings grouped by subsystem. Updates are JSON merge patches that are validated
+   as a whole, persisted through the config store and announced with `settings-changed`,
+   carrying the changed keys. The MCP, server, LAN, event and tracing subsystems apply their
+   section right away; downloads and thread summaries read theirs whenever they start work,
+   session restore reads its section at exit and launch, and reasoning retention is read
+   whenever a message is stored or a conversation is sent.
+
+   An administrator may lock settings for every user of the machine with a policy file
+   (`/etc/jan/policy.json`, `/Library/Application Support/Jan/policy.json` or
+   `%ProgramData%\Jan\policy.json`; only debug builds honour `JAN_POLICY_FILE` instead).
+   Its `settings` are merged read-only over the stored ones, and it can restrict the remote
+   providers, force offline mode, ban MCP server commands and keep telemetry off. Patches
+   changing a locked key are refused; `get_admin_policy` reports the locked keys.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
+
+use std::sync::RwLock;
+
+use helpers::{admin_policy, apply_policy};
+use models::Settings;
+
+#[derive(Default)]
+pub struct SettingsState {
+    settings: RwLock<Settings>,
+    // Serializes read-validate-write cycles of concurrent updates
+    pub update_lock: tokio::sync::Mutex<()>,
+}
+
+impl SettingsState {
+    /// Settings in effect: the stored ones with the values the admin policy fixes
+    pub fn get(&self) -> Settings {
+        apply_policy(admin_policy(), self.stored())
+    }
+
+    /// Settings as the user chose them
+    pub fn stored(&self) -> Settings {
+        self.settings
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    pub fn set(&self, settings: Settings) {
+        *self.settings.write().unwrap_or_else(|e| e.into_inner()) = settings;
+    }
+}
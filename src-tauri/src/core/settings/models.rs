@@ -0,0 +1,331 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::constants::{
+    DEFAULT_EVENT_COALESCE_INTERVAL_MS, DEFAULT_GUARDRAIL_MAX_TOKENS_PER_HOUR,
+    DEFAULT_GUARDRAIL_MAX_TOKENS_PER_REQUEST, DEFAULT_LOG_RETENTION_DAYS, DEFAULT_LOG_RETENTION_MB,
+    DEFAULT_MAX_PARALLEL_DOWNLOADS, DEFAULT_OUTBOX_MAX_ATTEMPTS, DEFAULT_PROXY_TIMEOUT_SECS,
+    DEFAULT_SERVER_HOST, DEFAULT_SERVER_PORT, DEFAULT_SERVER_PREFIX,
+    DEFAULT_SUMMARY_EVERY_MESSAGES, DEFAULT_TEMP_DOWNLOAD_RETENTION_DAYS,
+    DEFAULT_TRACING_OTLP_ENDPOINT, DEFAULT_TRACING_SERVICE_NAME,
+};
+use crate::core::mcp::models::McpSettings;
+
+/// Whether Hugging Face downloads try the Jan mirror first
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MirrorPolicy {
+    /// Mirror first, falling back to the original URL
+    #[default]
+    Auto,
+    /// Always download from the original URL
+    Off,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DownloadSettings {
+    pub mirror: MirrorPolicy,
+    /// Files of one download task transferred at the same time
+    pub max_parallel_files: usize,
+}
+
+impl Default for DownloadSettings {
+    fn default() -> Self {
+        Self {
+            mirror: MirrorPolicy::Auto,
+            max_parallel_files: DEFAULT_MAX_PARALLEL_DOWNLOADS,
+        }
+    }
+}
+
+/// Local API server, as last started or configured
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerSettings {
+    pub host: String,
+    pub port: u16,
+    pub prefix: String,
+    pub api_key: String,
+    pub trusted_hosts: Vec<String>,
+    pub proxy_timeout: u64,
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            host: DEFAULT_SERVER_HOST.to_string(),
+            port: DEFAULT_SERVER_PORT,
+            prefix: DEFAULT_SERVER_PREFIX.to_string(),
+            api_key: String::new(),
+            trusted_hosts: Vec::new(),
+            proxy_timeout: DEFAULT_PROXY_TIMEOUT_SECS,
+        }
+    }
+}
+
+/// Background generation of thread titles and running summaries
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SummarySettings {
+    pub enabled: bool,
+    /// Model used for summaries, ideally a small and cheap one. Titles use it only when it
+    /// runs locally, see `LocalTextSettings`.
+    pub model: String,
+    /// New messages in a thread before its summary is brought up to date
+    pub every_messages: usize,
+    /// Whether titles are generated; titles the user has set are never replaced
+    pub update_titles: bool,
+}
+
+impl Default for SummarySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            model: String::new(),
+            every_messages: DEFAULT_SUMMARY_EVERY_MESSAGES,
+            update_titles: true,
+        }
+    }
+}
+
+/// Sharing the local API server with other Jan instances on the LAN
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LanSettings {
+    /// Advertise the API server over mDNS while it listens on a non-loopback address
+    pub advertise: bool,
+}
+
+/// Queueing messages while offline or while their provider is down
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OutboxSettings {
+    /// Whether messages that can't be sent may be queued for later delivery
+    pub enabled: bool,
+    /// Delivery attempts before a queued message is marked failed
+    pub max_attempts: u32,
+}
+
+impl Default for OutboxSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_attempts: DEFAULT_OUTBOX_MAX_ATTEMPTS,
+        }
+    }
+}
+
+/// Hard caps on generated tokens for API clients and agent runs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuardrailSettings {
+    pub enabled: bool,
+    /// Largest `max_tokens` of a single request; 0 for no cap
+    pub max_tokens_per_request: u64,
+    /// Output tokens each API key, LAN peer or the agent may use per hour; 0 for no cap
+    pub max_tokens_per_hour: u64,
+    /// Stop sequences removed from requests
+    pub banned_stop_sequences: Vec<String>,
+}
+
+impl Default for GuardrailSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_tokens_per_request: DEFAULT_GUARDRAIL_MAX_TOKENS_PER_REQUEST,
+            max_tokens_per_hour: DEFAULT_GUARDRAIL_MAX_TOKENS_PER_HOUR,
+            banned_stop_sequences: Vec::new(),
+        }
+    }
+}
+
+/// Titles, export filenames and notification snippets, generated by a local model only
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LocalTextSettings {
+    /// Local model to use; empty uses `summaries.model` when that one runs locally
+    pub model: String,
+    /// Whether generation notifications show a generated snippet instead of the reply's start
+    pub notification_snippets: bool,
+}
+
+/// Limits on one kind of file; 0 turns a limit off
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    /// Files last modified longer ago than this are deleted
+    pub max_age_days: u64,
+    /// Beyond this total, the oldest files are deleted
+    pub max_size_mb: u64,
+}
+
+/// How long logs and generated records are kept, enforced by the periodic cleanup
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionSettings {
+    /// App logs; the file being written is always kept
+    pub logs: RetentionPolicy,
+    /// Agent run transcripts, which record every tool call and result
+    pub tool_audit: RetentionPolicy,
+    /// Partial downloads left behind; only their age counts, as they can be resumed
+    pub download_temp: RetentionPolicy,
+    /// MCP session recordings; sessions being recorded or replayed are kept
+    pub recordings: RetentionPolicy,
+}
+
+impl Default for RetentionSettings {
+    fn default() -> Self {
+        Self {
+            logs: RetentionPolicy {
+                max_age_days: DEFAULT_LOG_RETENTION_DAYS,
+                max_size_mb: DEFAULT_LOG_RETENTION_MB,
+            },
+            tool_audit: RetentionPolicy::default(),
+            download_temp: RetentionPolicy {
+                max_age_days: DEFAULT_TEMP_DOWNLOAD_RETENTION_DAYS,
+                max_size_mb: 0,
+            },
+            recordings: RetentionPolicy::default(),
+        }
+    }
+}
+
+/// Rate of high-frequency events such as download progress
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventSettings {
+    /// Shortest time between two progress values of one stream; 0 sends every value
+    pub coalesce_interval_ms: u64,
+}
+
+impl Default for EventSettings {
+    fn default() -> Self {
+        Self {
+            coalesce_interval_ms: DEFAULT_EVENT_COALESCE_INTERVAL_MS,
+        }
+    }
+}
+
+/// Export of agent, provider and tool call spans to an OpenTelemetry collector
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TracingSettings {
+    /// Spans are only exported once the user enables it
+    pub enabled: bool,
+    /// OTLP/HTTP endpoint; a URL without a path gets `/v1/traces`
+    pub otlp_endpoint: String,
+    /// `service.name` the spans are reported under
+    pub service_name: String,
+}
+
+impl Default for TracingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            otlp_endpoint: DEFAULT_TRACING_OTLP_ENDPOINT.to_string(),
+            service_name: DEFAULT_TRACING_SERVICE_NAME.to_string(),
+        }
+    }
+}
+
+/// Bringing back the models and MCP servers of the previous session on launch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionRestoreSettings {
+    /// Nothing is saved or restored until the user enables it
+    pub enabled: bool,
+    /// Load the llama.cpp models that were loaded at exit again
+    pub restore_models: bool,
+    /// Start the MCP servers again that were started by hand rather than from the config
+    pub restore_mcp_servers: bool,
+}
+
+impl Default for SessionRestoreSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            restore_models: true,
+            restore_mcp_servers: true,
+        }
+    }
+}
+
+/// What happens to the reasoning of model replies
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReasoningRetention {
+    /// Dropped from stored messages and never sent back
+    Strip,
+    /// Stored with the message, but left out of the context of later turns
+    #[default]
+    Persist,
+    /// Stored, and sent back to the model with later turns
+    Context,
+}
+
+/// Handling of reasoning segments, see `core::inference::reasoning`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReasoningSettings {
+    pub retention: ReasoningRetention,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub mcp: McpSettings,
+    pub downloads: DownloadSettings,
+    pub server: ServerSettings,
+    pub summaries: SummarySettings,
+    pub lan: LanSettings,
+    pub outbox: OutboxSettings,
+    pub local_text: LocalTextSettings,
+    pub guardrails: GuardrailSettings,
+    pub retention: RetentionSettings,
+    pub events: EventSettings,
+    pub tracing: TracingSettings,
+    pub session_restore: SessionRestoreSettings,
+    pub reasoning: ReasoningSettings,
+}
+
+/// One changed leaf, keyed by its dotted path such as `server.port`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SettingChange {
+    pub key: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// Payload of `settings-changed`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsChangedEvent {
+    pub changes: Vec<SettingChange>,
+    pub settings: Settings,
+}
+
+/// Settings an administrator fixes for every user of the machine. The app only reads it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdminPolicy {
+    /// Remote providers that may be registered; unset allows any
+    pub allowed_providers: Option<Vec<String>>,
+    /// Keep offline mode on
+    pub force_offline: bool,
+    /// Programs MCP servers may not run, by name (`docker`) or full path
+    pub banned_mcp_commands: Vec<String>,
+    /// Keep local telemetry off
+    pub disable_telemetry: bool,
+    /// Core settings fixed to these values, written like a `set_settings` patch
+    pub settings: Map<String, Value>,
+}
+
+/// Payload of `get_admin_policy`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdminPolicyStatus {
+    /// Where the policy is read from
+    pub path: String,
+    pub policy: AdminPolicy,
+    /// Dotted keys users can't change, such as `server.port` or `offline.enabled`
+    pub locked_keys: Vec<String>,
+}
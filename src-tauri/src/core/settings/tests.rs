@@ -0,0 +1,185 @@
+use serde_json::json;
+use tauri::test::mock_app;
+use tauri::Manager;
+
+use super::commands::{get_settings, set_settings};
+use super::helpers::{
+    apply_patch, apply_policy, check_mcp_command_allowed, check_provider_allowed, check_unlocked,
+    diff_settings, locked_keys, read_admin_policy, read_settings,
+};
+use super::models::{AdminPolicy, MirrorPolicy, Settings};
+use super::SettingsState;
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::state::AppState;
+
+#[test]
+fn test_apply_patch_merges_and_validates() {
+    let current = Settings::default();
+    let updated = apply_patch(
+        &current,
+        &json!({"server": {"port": 1338}, "downloads": {"mirror": "off"}}),
+    )
+    .unwrap();
+    assert_eq!(updated.server.port, 1338);
+    assert_eq!(updated.server.host, current.server.host);
+    assert_eq!(updated.downloads.mirror, MirrorPolicy::Off);
+
+    // `null` resets a key to its default
+    let reset = apply_patch(&updated, &json!({"server": {"port": null}})).unwrap();
+    assert_eq!(reset.server.port, current.server.port);
+
+    let out_of_range = apply_patch(&current, &json!({"downloads": {"max_parallel_files": 0}}));
+    assert!(out_of_range
+        .unwrap_err()
+        .contains("downloads.max_parallel_files"));
+    let bad_enum = apply_patch(&current, &json!({"downloads": {"mirror": "sometimes"}}));
+    assert!(bad_enum.unwrap_err().starts_with("Invalid settings"));
+    let unknown = apply_patch(&current, &json!({"server": {"prot": 1}}));
+    assert_eq!(unknown.unwrap_err(), "Unknown setting 'server.prot'");
+    let inverted = apply_patch(
+        &current,
+        &json!({"mcp": {"baseRestartDelayMs": 5000, "maxRestartDelayMs": 1000}}),
+    );
+    assert!(inverted.unwrap_err().contains("mcp.maxRestartDelayMs"));
+    assert!(apply_patch(&current, &json!([1])).is_err());
+}
+
+#[test]
+fn test_diff_settings() {
+    let old = Settings::default();
+    let new = apply_patch(
+        &old,
+        &json!({"server": {"trusted_hosts": ["a.local"], "proxy_timeout": 30}}),
+    )
+    .unwrap();
+    let mut keys: Vec<_> = diff_settings(&old, &new)
+        .into_iter()
+        .map(|change| (change.key, change.new))
+        .collect();
+    keys.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        keys,
+        vec![
+            ("server.proxy_timeout".to_string(), json!(30)),
+            ("server.trusted_hosts".to_string(), json!(["a.local"])),
+        ]
+    );
+    assert!(diff_settings(&new, &new).is_empty());
+}
+
+#[tokio::test]
+async fn test_set_settings_persists_and_applies_mcp() {
+    let app = mock_app();
+    app.manage(AppState::default());
+    app.manage(SettingsState::default());
+    let data_folder = get_jan_data_folder_path(app.handle().clone());
+    std::fs::create_dir_all(&data_folder).unwrap();
+
+    let updated = set_settings(
+        app.handle().clone(),
+        app.state::<SettingsState>(),
+        json!({"mcp": {"toolCallTimeoutSeconds": 90}, "downloads": {"max_parallel_files": 2}}),
+    )
+    .await
+    .unwrap();
+    assert_eq!(updated.downloads.max_parallel_files, 2);
+    assert_eq!(
+        app.state::<AppState>()
+            .mcp_settings
+            .lock()
+            .await
+            .tool_call_timeout_seconds,
+        90
+    );
+    let loaded = get_settings(app.handle().clone()).await.unwrap();
+    assert_eq!(loaded.mcp.tool_call_timeout_seconds, 90);
+
+    // The MCP section stays in mcp_config.json
+    let stored = read_settings(&data_folder);
+    assert_eq!(stored.downloads.max_parallel_files, 2);
+    assert_ne!(stored.mcp.tool_call_timeout_seconds, 90);
+
+    // Invalid patches change nothing
+    assert!(set_settings(
+        app.handle().clone(),
+        app.state::<SettingsState>(),
+        json!({"server": {"prefix": "v1"}}),
+    )
+    .await
+    .is_err());
+    assert_eq!(app.state::<SettingsState>().get().server.prefix, "/v1");
+
+    let _ = std::fs::remove_dir_all(data_folder);
+}
+
+#[test]
+fn test_admin_policy_locks_settings() {
+    let dir = std::env::temp_dir().join(format!("jan-policy-{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("policy.json");
+    assert_eq!(read_admin_policy(&path), AdminPolicy::default());
+
+    std::fs::write(
+        &path,
+        json!({
+            "allowed_providers": ["openai"],
+            "force_offline": true,
+            "banned_mcp_commands": ["docker", "/opt/tools/shell-mcp"],
+            "disable_telemetry": true,
+            "settings": {"server": {"port": 8080}, "tracing": {"enabled": false}}
+        })
+        .to_string(),
+    )
+    .unwrap();
+    let policy = read_admin_policy(&path);
+    assert_eq!(
+        locked_keys(&policy),
+        [
+            "mcpServers.command",
+            "offline.enabled",
+            "providers",
+            "server.port",
+            "telemetry.enabled",
+            "tracing.enabled"
+        ]
+    );
+
+    // The policy wins over stored values, leaving the others alone
+    let stored = apply_patch(
+        &Settings::default(),
+        &json!({"server": {"port": 1338, "prefix": "/api"}}),
+    )
+    .unwrap();
+    let effective = apply_policy(&policy, stored);
+    assert_eq!(effective.server.port, 8080);
+    assert_eq!(effective.server.prefix, "/api");
+
+    // Locked keys can't be changed or reset, but may be sent with their fixed value
+    assert!(check_unlocked(&policy, &json!({"server": {"port": 1339}})).is_err());
+    assert!(check_unlocked(&policy, &json!({"server": null})).is_err());
+    assert!(check_unlocked(&policy, &json!({"server": {"port": 8080, "prefix": "/v2"}})).is_ok());
+    assert!(check_unlocked(&policy, &json!({"downloads": {"mirror": "off"}})).is_ok());
+
+    assert!(check_provider_allowed(&policy, "OpenAI").is_ok());
+    assert!(check_provider_allowed(&policy, "anthropic").is_err());
+    assert!(check_provider_allowed(&AdminPolicy::default(), "anthropic").is_ok());
+
+    assert!(check_mcp_command_allowed(&policy, "docker").is_err());
+    assert!(check_mcp_command_allowed(&policy, "/usr/local/bin/docker").is_err());
+    assert!(check_mcp_command_allowed(&policy, "Docker.exe").is_err());
+    assert!(check_mcp_command_allowed(&policy, "/opt/tools/shell-mcp").is_err());
+    assert!(check_mcp_command_allowed(&policy, "shell-mcp").is_ok());
+    assert!(check_mcp_command_allowed(&policy, "npx").is_ok());
+    assert!(check_mcp_command_allowed(&policy, "").is_ok());
+
+    // Settings fixed to invalid values are dropped, the rest of the policy stays
+    std::fs::write(
+        &path,
+        json!({"force_offline": true, "settings": {"server": {"port": 0}}}).to_string(),
+    )
+    .unwrap();
+    let policy = read_admin_policy(&path);
+    assert!(policy.force_offline);
+    assert!(policy.settings.is_empty());
+    let _ = std::fs::remove_dir_all(dir);
+}
@@ -18,6 +18,7 @@ use tauri_plugin_store::Store;
 use crate::core::app::commands::get_jan_data_folder_path;
 use crate::core::mcp::constants::DEFAULT_MCP_CONFIG;
 use crate::core::mcp::helpers::add_server_config;
+use crate::core::mcp::migrations::update_config;
 
 use super::{
     extensions::commands::get_jan_extensions_path, mcp::helpers::run_mcp_commands, state::AppState,
@@ -164,7 +165,7 @@ pub fn migrate_mcp_servers(
         .unwrap_or(0);
     if mcp_version < 1 {
         log::info!("Migrating MCP schema version 1");
-        let result = add_server_config(
+        let result = tauri::async_runtime::block_on(add_server_config(
             app_handle.clone(),
             "exa".to_string(),
             serde_json::json!({
@@ -173,14 +174,14 @@ pub fn migrate_mcp_servers(
                   "env": { "EXA_API_KEY": "YOUR_EXA_API_KEY_HERE" },
                   "active": false
             }),
-        );
+        ));
         if let Err(e) = result {
             log::error!("Failed to add server config: {e}");
         }
     }
     if mcp_version < 2 {
         log::info!("Migrating MCP schema version 2: Adding Jan Browser MCP");
-        let result = add_server_config(
+        let result = tauri::async_runtime::block_on(add_server_config(
             app_handle.clone(),
             "Jan Browser MCP".to_string(),
             serde_json::json!({
@@ -193,7 +194,7 @@ pub fn migrate_mcp_servers(
                 "active": false,
                 "official": true
             }),
-        );
+        ));
         if let Err(e) = result {
             log::error!("Failed to add Jan Browser MCP server config: {e}");
         }
@@ -212,34 +213,22 @@ pub fn migrate_mcp_servers(
 fn migrate_exa_to_http(app_handle: tauri::AppHandle) -> Result<(), String> {
     let config_path = get_jan_data_folder_path(app_handle).join("mcp_config.json");
 
-    let config_str =
-        fs::read_to_string(&config_path).map_err(|e| format!("Failed to read MCP config: {e}"))?;
-
-    let mut config: serde_json::Value = serde_json::from_str(&config_str)
-        .map_err(|e| format!("Failed to parse MCP config: {e}"))?;
-
-    if let Some(servers) = config.get_mut("mcpServers").and_then(|s| s.as_object_mut()) {
-        servers.insert(
-            "exa".to_string(),
-            serde_json::json!({
-                "type": "http",
-                "url": "https://mcp.exa.ai/mcp".to_string(),
-                "command": "",
-                "args": [],
-                "env": {},
-                "active": true
-            }),
-        );
-    }
-
-    fs::write(
-        &config_path,
-        serde_json::to_string_pretty(&config)
-            .map_err(|e| format!("Failed to serialize MCP config: {e}"))?,
-    )
-    .map_err(|e| format!("Failed to write MCP config: {e}"))?;
-
-    Ok(())
+    update_config(&config_path, |config| {
+        if let Some(servers) = config.get_mut("mcpServers").and_then(|s| s.as_object_mut()) {
+            servers.insert(
+                "exa".to_string(),
+                serde_json::json!({
+                    "type": "http",
+                    "url": "https://mcp.exa.ai/mcp".to_string(),
+                    "command": "",
+                    "args": [],
+                    "env": {},
+                    "active": true
+                }),
+            );
+        }
+        Ok(())
+    })
 }
 
 pub fn extract_extension_manifest<R: Read>(
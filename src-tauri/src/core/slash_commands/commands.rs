@@ -0,0 +1,221 @@
+use rmcp::model::CallToolRequestParam;
+use serde_json::Value;
+use tauri::{AppHandle, Manager, Runtime};
+
+use super::helpers::{
+    bind_arguments, commands_lock, read_commands, render_value, split_command, suggestion,
+    validate_input, write_commands,
+};
+use super::models::{
+    SlashAction, SlashCommand, SlashCommandInput, SlashCommandResult, SlashCommandSuggestion,
+};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::approvals::{helpers::authorize_tool_call, ToolApprovalState};
+use crate::core::assistants::helpers::resolve_tool_scope;
+use crate::core::mcp::helpers::{call_tool_on_server, tool_input_schema};
+use crate::core::plugins::PluginHostState;
+use crate::core::prompts::commands::get_prompt_template;
+use crate::core::prompts::helpers::{builtin_values, render_template};
+use crate::core::state::AppState;
+use crate::core::workspaces::helpers::scope_thread_tool_arguments;
+
+/// Lists all slash commands.
+#[tauri::command]
+pub async fn list_slash_commands<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<Vec<SlashCommand>, String> {
+    read_commands(&get_jan_data_folder_path(app_handle))
+}
+
+/// Describes a slash command, including its usage line, for autocompletion tooltips.
+#[tauri::command]
+pub async fn describe_slash_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    name: String,
+) -> Result<SlashCommandSuggestion, String> {
+    read_commands(&get_jan_data_folder_path(app_handle))?
+        .iter()
+        .find(|c| c.name == name)
+        .map(suggestion)
+        .ok_or_else(|| format!("Slash command /{name} not found"))
+}
+
+/// Suggests slash commands whose name starts with `prefix` (with or without the leading slash).
+#[tauri::command]
+pub async fn complete_slash_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    prefix: String,
+) -> Result<Vec<SlashCommandSuggestion>, String> {
+    let prefix = prefix.trim_start().trim_start_matches('/').to_lowercase();
+    let mut suggestions: Vec<_> = read_commands(&get_jan_data_folder_path(app_handle))?
+        .iter()
+        .filter(|c| c.name.to_lowercase().starts_with(&prefix))
+        .map(suggestion)
+        .collect();
+    suggestions.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(suggestions)
+}
+
+/// Creates a slash command. Names must be unique.
+#[tauri::command]
+pub async fn create_slash_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    command: SlashCommandInput,
+) -> Result<SlashCommand, String> {
+    validate_input(&command)?;
+    let data_folder = get_jan_data_folder_path(app_handle);
+    let now = chrono::Utc::now().timestamp_millis();
+
+    let _guard = commands_lock().lock().await;
+    let mut commands = read_commands(&data_folder)?;
+    if commands.iter().any(|c| c.name == command.name) {
+        return Err(format!("Slash command /{} already exists", command.name));
+    }
+    let created = SlashCommand {
+        name: command.name,
+        description: command.description,
+        arguments: command.arguments,
+        action: command.action,
+        created_at: now,
+        updated_at: now,
+    };
+    commands.push(created.clone());
+    write_commands(&data_folder, &commands)?;
+    Ok(created)
+}
+
+/// Replaces a slash command; renaming it is allowed as long as the new name is free.
+#[tauri::command]
+pub async fn update_slash_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    name: String,
+    command: SlashCommandInput,
+) -> Result<SlashCommand, String> {
+    validate_input(&command)?;
+    let data_folder = get_jan_data_folder_path(app_handle);
+
+    let _guard = commands_lock().lock().await;
+    let mut commands = read_commands(&data_folder)?;
+    if command.name != name && commands.iter().any(|c| c.name == command.name) {
+        return Err(format!("Slash command /{} already exists", command.name));
+    }
+    let existing = commands
+        .iter_mut()
+        .find(|c| c.name == name)
+        .ok_or_else(|| format!("Slash command /{name} not found"))?;
+    existing.name = command.name;
+    existing.description = command.description;
+    existing.arguments = command.arguments;
+    existing.action = command.action;
+    existing.updated_at = chrono::Utc::now().timestamp_millis();
+    let updated = existing.clone();
+    write_commands(&data_folder, &commands)?;
+    Ok(updated)
+}
+
+/// Deletes a slash command.
+#[tauri::command]
+pub async fn delete_slash_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    name: String,
+) -> Result<(), String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    let _guard = commands_lock().lock().await;
+    let mut commands = read_commands(&data_folder)?;
+    let before = commands.len();
+    commands.retain(|c| c.name != name);
+    if commands.len() == before {
+        return Err(format!("Slash command /{name} not found"));
+    }
+    write_commands(&data_folder, &commands)
+}
+
+/// Parses and executes typed slash command input such as `/translate de "good morning"`.
+/// Prompt actions return the rendered prompt for the client to send; tool calls go through the
+/// usual approval policies, with their path arguments scoped to the workspace of `thread_id`.
+#[tauri::command]
+pub async fn execute_slash_command<R: Runtime>(
+    app_handle: AppHandle<R>,
+    input: String,
+    assistant_id: Option<String>,
+    thread_id: Option<String>,
+) -> Result<SlashCommandResult, String> {
+    let (name, arguments) =
+        split_command(&input).ok_or_else(|| "Input is not a slash command".to_string())?;
+    let command = read_commands(&get_jan_data_folder_path(app_handle.clone()))?
+        .into_iter()
+        .find(|c| c.name == name)
+        .ok_or_else(|| format!("Unknown slash command /{name}"))?;
+    let values = bind_arguments(&command, arguments)?;
+    let builtins = builtin_values();
+
+    match command.action {
+        SlashAction::Prompt { content } => Ok(SlashCommandResult::Prompt {
+            content: render_template(&content, &values, &builtins)?,
+        }),
+        SlashAction::Template { template_id } => {
+            let template = get_prompt_template(app_handle, template_id).await?;
+            Ok(SlashCommandResult::Prompt {
+                content: render_template(&template.content, &values, &builtins)?,
+            })
+        }
+        SlashAction::Tool {
+            server,
+            tool,
+            arguments,
+        } => {
+            let arguments = match render_value(&Value::Object(arguments), &values, &builtins)? {
+                Value::Object(arguments) => arguments,
+                _ => unreachable!("rendering preserves the value type"),
+            };
+            let data_folder = get_jan_data_folder_path(app_handle.clone());
+            let scope = resolve_tool_scope(&data_folder, assistant_id.as_deref())?;
+            if scope.is_some_and(|scope| !scope.permits(&server, &tool)) {
+                return Err(format!(
+                    "Assistant is not allowed to call tool '{tool}' on server '{server}'"
+                ));
+            }
+            let state = app_handle.state::<AppState>();
+            let input_schema = tool_input_schema(&state.mcp_servers, &server, &tool).await?;
+            let arguments = scope_thread_tool_arguments(
+                &data_folder,
+                thread_id.as_deref(),
+                &input_schema,
+                arguments,
+            )?;
+            authorize_tool_call(
+                &app_handle,
+                &app_handle.state::<ToolApprovalState>(),
+                &server,
+                &tool,
+                Some(&arguments),
+                assistant_id.as_deref(),
+                None,
+            )
+            .await?;
+            let timeout = state.mcp_settings.lock().await.tool_call_timeout_duration();
+            let params = CallToolRequestParam {
+                name: tool.into(),
+                arguments: Some(arguments),
+            };
+            let result = call_tool_on_server(&state.mcp_servers, &server, params, timeout).await?;
+            Ok(SlashCommandResult::Tool {
+                result: serde_json::to_value(result).map_err(|e| e.to_string())?,
+            })
+        }
+        SlashAction::Plugin { plugin_id, command } => {
+            let process = app_handle
+                .state::<PluginHostState>()
+                .running
+                .lock()
+                .await
+                .get(&plugin_id)
+                .cloned()
+                .ok_or_else(|| format!("Plugin {plugin_id} is not running"))?;
+            let result = process
+                .execute_command(&command, serde_json::to_value(&values).unwrap_or_default())
+                .await?;
+            Ok(SlashCommandResult::Plugin { result })
+        }
+    }
+}
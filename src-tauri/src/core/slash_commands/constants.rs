@@ -0,0 +1,6 @@
+// Slash command constants
+pub const SLASH_COMMANDS_DIR: &str = "slash_commands";
+pub const SLASH_COMMANDS_FILE: &str = "commands.json";
+
+/// Placeholder holding everything typed after the command name
+pub const INPUT_VARIABLE: &str = "input";
@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde_json::Value;
+use tokio::sync::Mutex;
+
+use super::constants::{INPUT_VARIABLE, SLASH_COMMANDS_DIR, SLASH_COMMANDS_FILE};
+use super::models::{SlashCommand, SlashCommandInput, SlashCommandSuggestion};
+use crate::core::prompts::helpers::render_template;
+
+// Global lock serializing read-modify-write cycles on commands.json
+static COMMANDS_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+pub fn commands_lock() -> &'static Mutex<()> {
+    COMMANDS_LOCK.get_or_init(|| Mutex::new(()))
+}
+
+pub fn get_commands_path(data_folder: &Path) -> PathBuf {
+    data_folder
+        .join(SLASH_COMMANDS_DIR)
+        .join(SLASH_COMMANDS_FILE)
+}
+
+/// Read the slash commands, returning an empty list if the file does not exist yet
+pub fn read_commands(data_folder: &Path) -> Result<Vec<SlashCommand>, String> {
+    let path = get_commands_path(data_folder);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    if data.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(&data).map_err(|e| format!("Failed to parse slash commands: {e}"))
+}
+
+pub fn write_commands(data_folder: &Path, commands: &[SlashCommand]) -> Result<(), String> {
+    let path = get_commands_path(data_folder);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let data = serde_json::to_string_pretty(commands).map_err(|e| e.to_string())?;
+    fs::write(path, data).map_err(|e| e.to_string())
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Validate the command name and argument list.
+pub fn validate_input(input: &SlashCommandInput) -> Result<(), String> {
+    if !is_valid_name(&input.name) {
+        return Err(format!(
+            "Invalid slash command name '{}': use letters, digits, '-' and '_'",
+            input.name
+        ));
+    }
+    let mut seen: Vec<&str> = Vec::new();
+    for (index, argument) in input.arguments.iter().enumerate() {
+        if !is_valid_name(&argument.name) || argument.name == INPUT_VARIABLE {
+            return Err(format!("Invalid argument name '{}'", argument.name));
+        }
+        if seen.contains(&argument.name.as_str()) {
+            return Err(format!("Duplicate argument '{}'", argument.name));
+        }
+        if argument.rest && index + 1 != input.arguments.len() {
+            return Err(format!(
+                "Argument '{}' takes the rest of the input and must be last",
+                argument.name
+            ));
+        }
+        seen.push(&argument.name);
+    }
+    Ok(())
+}
+
+/// Usage line such as `/translate <lang> [text...]`
+pub fn usage(command: &SlashCommand) -> String {
+    let mut usage = format!("/{}", command.name);
+    for argument in &command.arguments {
+        let name = if argument.rest {
+            format!("{}...", argument.name)
+        } else {
+            argument.name.clone()
+        };
+        if argument.required {
+            usage.push_str(&format!(" <{name}>"));
+        } else {
+            usage.push_str(&format!(" [{name}]"));
+        }
+    }
+    usage
+}
+
+pub fn suggestion(command: &SlashCommand) -> SlashCommandSuggestion {
+    SlashCommandSuggestion {
+        name: command.name.clone(),
+        description: command.description.clone(),
+        usage: usage(command),
+    }
+}
+
+/// Split `/name rest of input` into the command name and the raw argument text.
+/// Returns `None` when the input is not a slash command.
+pub fn split_command(input: &str) -> Option<(&str, &str)> {
+    let input = input.trim_start().strip_prefix('/')?;
+    let (name, rest) = match input.find(char::is_whitespace) {
+        Some(pos) => (&input[..pos], input[pos..].trim()),
+        None => (input, ""),
+    };
+    if name.is_empty() {
+        return None;
+    }
+    Some((name, rest))
+}
+
+/// Split arguments on whitespace, honouring double and single quotes. Each token is returned
+/// with the byte offset where it starts in `input`.
+pub fn tokenize(input: &str) -> Result<Vec<(String, usize)>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let mut token = String::new();
+        let mut quote: Option<char> = None;
+        while let Some(&(_, c)) = chars.peek() {
+            match quote {
+                Some(q) if c == q => quote = None,
+                Some(_) => token.push(c),
+                None if c == '"' || c == '\'' => quote = Some(c),
+                None if c.is_whitespace() => break,
+                None => token.push(c),
+            }
+            chars.next();
+        }
+        if quote.is_some() {
+            return Err("Unterminated quote in slash command arguments".to_string());
+        }
+        tokens.push((token, start));
+    }
+    Ok(tokens)
+}
+
+/// Bind the argument text to the command's arguments. The result also holds the raw text
+/// under `input`.
+pub fn bind_arguments(
+    command: &SlashCommand,
+    input: &str,
+) -> Result<HashMap<String, String>, String> {
+    let tokens = tokenize(input)?;
+    let mut values = HashMap::new();
+    values.insert(INPUT_VARIABLE.to_string(), input.to_string());
+
+    let mut next = 0;
+    for argument in &command.arguments {
+        let value = if argument.rest {
+            let value = tokens
+                .get(next)
+                .map(|(_, start)| input[*start..].trim().to_string());
+            next = tokens.len();
+            value
+        } else {
+            next += 1;
+            tokens.get(next - 1).map(|(token, _)| token.clone())
+        };
+        match value.or_else(|| argument.default.clone()) {
+            Some(value) => {
+                values.insert(argument.name.clone(), value);
+            }
+            None if argument.required => {
+                return Err(format!(
+                    "Missing argument '{}'. Usage: {}",
+                    argument.name,
+                    usage(command)
+                ));
+            }
+            // Optional arguments without a value render as empty text
+            None => {
+                values.insert(argument.name.clone(), String::new());
+            }
+        }
+    }
+    if next < tokens.len() {
+        return Err(format!("Too many arguments. Usage: {}", usage(command)));
+    }
+    Ok(values)
+}
+
+/// Render every string inside `value` as a template.
+pub fn render_value(
+    value: &Value,
+    values: &HashMap<String, String>,
+    builtins: &HashMap<String, String>,
+) -> Result<Value, String> {
+    Ok(match value {
+        Value::String(s) => Value::String(render_template(s, values, builtins)?),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| render_value(item, values, builtins))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| Ok((k.clone(), render_value(v, values, builtins)?)))
+                .collect::<Result<_, String>>()?,
+        ),
+        other => other.clone(),
+    })
+}
@@ -0,0 +1,25 @@
+/*!
+   Slash Commands
+
+   User-defined slash commands such as `/summarize` or `/translate de`, stored in
+   `slash_commands/commands.json`. Each command declares positional arguments and maps to one
+   action:
+   - a prompt, written inline or taken from the prompt template library, rendered into the
+     message to send
+   - an MCP tool call whose string arguments may contain placeholders
+   - a command registered by a running plugin
+
+   The core parses the typed input (quoted arguments are supported; a `rest` argument takes the
+   remaining text verbatim), binds the arguments and executes the action, so every client gets
+   the same behaviour. Placeholders use the prompt template syntax: argument names, the raw
+   argument text as `{{input}}`, and the built-in date and time variables. List, describe and
+   completion commands feed the UI's autocompletion.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// A positional argument of a slash command
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SlashArgument {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+    #[serde(default)]
+    pub default: Option<String>,
+    /// Takes the rest of the input verbatim; only allowed on the last argument
+    #[serde(default)]
+    pub rest: bool,
+}
+
+/// What a slash command does
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SlashAction {
+    /// Render an inline prompt
+    Prompt { content: String },
+    /// Render a template from the prompt library
+    Template { template_id: String },
+    /// Call an MCP tool; string values in `arguments` are rendered as templates
+    Tool {
+        server: String,
+        tool: String,
+        #[serde(default)]
+        arguments: Map<String, Value>,
+    },
+    /// Run a command registered by a plugin with the bound arguments
+    Plugin { plugin_id: String, command: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SlashCommand {
+    /// Name typed after the slash, e.g. `translate`
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub arguments: Vec<SlashArgument>,
+    pub action: SlashAction,
+    #[serde(default)]
+    pub created_at: i64,
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+/// Payload used to create or update a slash command
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlashCommandInput {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub arguments: Vec<SlashArgument>,
+    pub action: SlashAction,
+}
+
+/// Entry shown in the UI's autocompletion list
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SlashCommandSuggestion {
+    pub name: String,
+    pub description: Option<String>,
+    /// Usage line such as `/translate <lang> [text...]`
+    pub usage: String,
+}
+
+/// Outcome of executing a slash command
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SlashCommandResult {
+    /// Rendered prompt for the client to send as the user message
+    Prompt { content: String },
+    /// Result of the MCP tool call
+    Tool { result: Value },
+    /// Value returned by the plugin command
+    Plugin { result: Value },
+}
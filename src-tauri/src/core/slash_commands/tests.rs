@@ -0,0 +1,142 @@
+use super::commands::*;
+use super::helpers::{bind_arguments, split_command, tokenize, usage, validate_input};
+use super::models::{
+    SlashAction, SlashArgument, SlashCommand, SlashCommandInput, SlashCommandResult,
+};
+use crate::core::app::commands::get_jan_data_folder_path;
+use std::fs;
+use tauri::test::mock_app;
+
+fn argument(name: &str, required: bool, rest: bool) -> SlashArgument {
+    SlashArgument {
+        name: name.to_string(),
+        description: None,
+        required,
+        default: None,
+        rest,
+    }
+}
+
+fn translate_input() -> SlashCommandInput {
+    SlashCommandInput {
+        name: "translate".to_string(),
+        description: Some("Translate text".to_string()),
+        arguments: vec![argument("lang", true, false), argument("text", false, true)],
+        action: SlashAction::Prompt {
+            content: "Translate into {{lang}}: {{text}}".to_string(),
+        },
+    }
+}
+
+fn translate() -> SlashCommand {
+    let input = translate_input();
+    SlashCommand {
+        name: input.name,
+        description: input.description,
+        arguments: input.arguments,
+        action: input.action,
+        created_at: 0,
+        updated_at: 0,
+    }
+}
+
+#[test]
+fn test_split_and_tokenize() {
+    assert_eq!(split_command("/summarize"), Some(("summarize", "")));
+    assert_eq!(
+        split_command("  /translate de  hello"),
+        Some(("translate", "de  hello"))
+    );
+    assert_eq!(split_command("hello /world"), None);
+    assert_eq!(split_command("/"), None);
+
+    let tokens = tokenize(r#"de "good morning" it's"#);
+    assert!(tokens.is_err());
+    let tokens = tokenize(r#"de "good morning" x"#).unwrap();
+    let words: Vec<_> = tokens.iter().map(|(t, _)| t.as_str()).collect();
+    assert_eq!(words, vec!["de", "good morning", "x"]);
+}
+
+#[test]
+fn test_bind_arguments_rest_keeps_text_verbatim() {
+    let values = bind_arguments(&translate(), "de  Hello,   \"world\"").unwrap();
+    assert_eq!(values["lang"], "de");
+    assert_eq!(values["text"], "Hello,   \"world\"");
+    assert_eq!(values["input"], "de  Hello,   \"world\"");
+
+    let err = bind_arguments(&translate(), "").unwrap_err();
+    assert!(err.contains("Missing argument 'lang'"));
+    assert_eq!(usage(&translate()), "/translate <lang> [text...]");
+}
+
+#[test]
+fn test_bind_arguments_rejects_extra_tokens() {
+    let mut command = translate();
+    command.arguments.pop();
+    assert!(bind_arguments(&command, "de fr").is_err());
+}
+
+#[test]
+fn test_validate_input() {
+    assert!(validate_input(&translate_input()).is_ok());
+
+    let mut input = translate_input();
+    input.arguments.reverse();
+    assert!(validate_input(&input).unwrap_err().contains("must be last"));
+
+    let mut input = translate_input();
+    input.name = "bad name".to_string();
+    assert!(validate_input(&input).is_err());
+}
+
+#[tokio::test]
+async fn test_create_complete_and_execute() {
+    let app = mock_app();
+    let data_dir = get_jan_data_folder_path(app.handle().clone());
+
+    create_slash_command(app.handle().clone(), translate_input())
+        .await
+        .unwrap();
+    assert!(
+        create_slash_command(app.handle().clone(), translate_input())
+            .await
+            .is_err()
+    );
+
+    let suggestions = complete_slash_command(app.handle().clone(), "/tr".to_string())
+        .await
+        .unwrap();
+    assert_eq!(suggestions.len(), 1);
+    assert_eq!(suggestions[0].usage, "/translate <lang> [text...]");
+
+    let result = execute_slash_command(
+        app.handle().clone(),
+        "/translate de good morning".to_string(),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        result,
+        SlashCommandResult::Prompt {
+            content: "Translate into de: good morning".to_string()
+        }
+    );
+
+    assert!(
+        execute_slash_command(app.handle().clone(), "/unknown".to_string(), None, None)
+            .await
+            .is_err()
+    );
+
+    delete_slash_command(app.handle().clone(), "translate".to_string())
+        .await
+        .unwrap();
+    assert!(list_slash_commands(app.handle().clone())
+        .await
+        .unwrap()
+        .is_empty());
+
+    let _ = fs::remove_dir_all(data_dir);
+}
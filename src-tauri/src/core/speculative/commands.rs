@@ -0,0 +1,99 @@
+use tauri::{AppHandle, Runtime};
+
+use super::helpers::{
+    check_draft_compatibility as check_pair, read_model_info, speculative_config, validate_settings,
+};
+use super::models::{DraftCompatibility, SpeculativeConfig};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::model_catalog::constants::LLAMACPP_ENGINE;
+use crate::core::model_catalog::helpers::{list_model_ids, read_model, write_model};
+use crate::core::model_catalog::models::SpeculativeSettings;
+
+/// Check whether `draft_model_id` can draft for `model_id`
+#[tauri::command]
+pub async fn check_draft_compatibility<R: Runtime>(
+    app: AppHandle<R>,
+    model_id: String,
+    draft_model_id: String,
+) -> Result<DraftCompatibility, String> {
+    let data_folder = get_jan_data_folder_path(app);
+    let target = read_model_info(&data_folder, &model_id).await?;
+    let draft = read_model_info(&data_folder, &draft_model_id).await?;
+    Ok(check_pair(&target, &draft))
+}
+
+/// Installed models that can draft for `model_id`, smallest first
+#[tauri::command]
+pub async fn list_draft_candidates<R: Runtime>(
+    app: AppHandle<R>,
+    model_id: String,
+) -> Result<Vec<String>, String> {
+    let data_folder = get_jan_data_folder_path(app);
+    let target = read_model_info(&data_folder, &model_id).await?;
+    let mut candidates = Vec::new();
+    for id in list_model_ids(&data_folder, LLAMACPP_ENGINE) {
+        if id == model_id {
+            continue;
+        }
+        match read_model_info(&data_folder, &id).await {
+            Ok(draft) if check_pair(&target, &draft).compatible => {
+                candidates.push((draft.size_bytes, id))
+            }
+            Ok(_) => {}
+            Err(e) => log::debug!("Skipping draft candidate {id}: {e}"),
+        }
+    }
+    candidates.sort();
+    Ok(candidates.into_iter().map(|(_, id)| id).collect())
+}
+
+/// Save (or clear, when `None`) the draft model pairing of a model
+#[tauri::command]
+pub async fn set_speculative_settings<R: Runtime>(
+    app: AppHandle<R>,
+    model_id: String,
+    settings: Option<SpeculativeSettings>,
+) -> Result<(), String> {
+    let data_folder = get_jan_data_folder_path(app);
+    if let Some(settings) = &settings {
+        validate_settings(settings)?;
+        let target = read_model_info(&data_folder, &model_id).await?;
+        let draft = read_model_info(&data_folder, &settings.draft_model).await?;
+        let compatibility = check_pair(&target, &draft);
+        if !compatibility.compatible {
+            return Err(compatibility.issues.join("; "));
+        }
+    }
+    let mut model = read_model(&data_folder, LLAMACPP_ENGINE, &model_id)?;
+    model.speculative = settings;
+    write_model(&data_folder, LLAMACPP_ENGINE, &model_id, &model)
+}
+
+#[tauri::command]
+pub async fn get_speculative_settings<R: Runtime>(
+    app: AppHandle<R>,
+    model_id: String,
+) -> Result<Option<SpeculativeSettings>, String> {
+    let data_folder = get_jan_data_folder_path(app);
+    Ok(read_model(&data_folder, LLAMACPP_ENGINE, &model_id)?.speculative)
+}
+
+/// Draft arguments to merge into the llama.cpp config when loading `model_id`. A pairing whose
+/// draft model was removed is ignored so the model still loads.
+#[tauri::command]
+pub async fn get_speculative_config<R: Runtime>(
+    app: AppHandle<R>,
+    model_id: String,
+) -> Result<Option<SpeculativeConfig>, String> {
+    let data_folder = get_jan_data_folder_path(app);
+    let Some(settings) = read_model(&data_folder, LLAMACPP_ENGINE, &model_id)?.speculative else {
+        return Ok(None);
+    };
+    match speculative_config(&data_folder, &settings) {
+        Ok(config) => Ok(Some(config)),
+        Err(e) => {
+            log::warn!("Ignoring the draft model of {model_id}: {e}");
+            Ok(None)
+        }
+    }
+}
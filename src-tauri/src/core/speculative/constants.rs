@@ -0,0 +1,9 @@
+// GGUF tokenizer metadata keys
+pub const TOKENIZER_MODEL_KEY: &str = "tokenizer.ggml.model";
+pub const TOKENIZER_PRE_KEY: &str = "tokenizer.ggml.pre";
+pub const TOKENS_KEY: &str = "tokenizer.ggml.tokens";
+pub const BOS_TOKEN_KEY: &str = "tokenizer.ggml.bos_token_id";
+pub const EOS_TOKEN_KEY: &str = "tokenizer.ggml.eos_token_id";
+
+/// Largest vocabulary size difference llama.cpp accepts between target and draft
+pub const VOCAB_SIZE_TOLERANCE: u64 = 128;
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use super::constants::{
+    BOS_TOKEN_KEY, EOS_TOKEN_KEY, TOKENIZER_MODEL_KEY, TOKENIZER_PRE_KEY, TOKENS_KEY,
+    VOCAB_SIZE_TOLERANCE,
+};
+use super::models::{DraftCompatibility, SpeculativeConfig};
+use crate::core::lora::constants::{ARCHITECTURE_KEY, GENERAL_TYPE_KEY};
+use crate::core::lora::helpers::read_metadata;
+use crate::core::model_catalog::constants::LLAMACPP_ENGINE;
+use crate::core::model_catalog::helpers::{read_model, resolve_catalog_path};
+use crate::core::model_catalog::models::{CatalogModel, SpeculativeSettings};
+
+/// What the compatibility check needs to know about a catalog model
+pub struct ModelInfo {
+    pub model: CatalogModel,
+    pub metadata: HashMap<String, String>,
+    pub size_bytes: u64,
+}
+
+pub async fn read_model_info(data_folder: &Path, model_id: &str) -> Result<ModelInfo, String> {
+    let model = read_model(data_folder, LLAMACPP_ENGINE, model_id)?;
+    let path = resolve_catalog_path(data_folder, &model.model_path);
+    let metadata = read_metadata(&path).await?;
+    let size_bytes = fs::metadata(&path)
+        .map(|m| m.len())
+        .unwrap_or(model.size_bytes);
+    Ok(ModelInfo {
+        model,
+        metadata,
+        size_bytes,
+    })
+}
+
+/// Vocabulary size, from `<arch>.vocab_size` or the length of the token list
+pub fn vocab_size(metadata: &HashMap<String, String>) -> Option<u64> {
+    let declared = metadata
+        .get(ARCHITECTURE_KEY)
+        .and_then(|arch| metadata.get(&format!("{arch}.vocab_size")))
+        .and_then(|size| size.parse().ok());
+    declared.or_else(|| {
+        // Long arrays are summarized as `<Array of type String with N elements, data skipped>`
+        let tokens = metadata.get(TOKENS_KEY)?;
+        let (_, rest) = tokens.split_once(" with ")?;
+        let (count, _) = rest.split_once(' ')?;
+        count.parse().ok()
+    })
+}
+
+/// Compare `key` in both models; a mismatch is reported through `report`
+fn compare(
+    target: &HashMap<String, String>,
+    draft: &HashMap<String, String>,
+    key: &str,
+    mut report: impl FnMut(String),
+) {
+    if let (Some(t), Some(d)) = (target.get(key), draft.get(key)) {
+        if t != d {
+            report(format!("{key} differs: {t} (target) vs {d} (draft)"));
+        }
+    }
+}
+
+pub fn check_draft_compatibility(target: &ModelInfo, draft: &ModelInfo) -> DraftCompatibility {
+    let mut issues = Vec::new();
+    let mut warnings = Vec::new();
+
+    if target.model.embedding || draft.model.embedding {
+        issues.push("Embedding models cannot be used for speculative decoding".to_string());
+    }
+    if draft
+        .metadata
+        .get(GENERAL_TYPE_KEY)
+        .is_some_and(|t| t == "adapter")
+    {
+        issues.push("The draft is an adapter, not a model".to_string());
+    }
+    if draft.size_bytes >= target.size_bytes {
+        issues.push("The draft model must be smaller than the target model".to_string());
+    }
+
+    match (
+        target.metadata.get(TOKENIZER_MODEL_KEY),
+        draft.metadata.get(TOKENIZER_MODEL_KEY),
+    ) {
+        (Some(t), Some(d)) if t != d => {
+            issues.push(format!("Tokenizers differ: {t} (target) vs {d} (draft)"))
+        }
+        (Some(_), Some(_)) => {}
+        _ => warnings.push("Tokenizer type is unknown for one of the models".to_string()),
+    }
+    match (vocab_size(&target.metadata), vocab_size(&draft.metadata)) {
+        (Some(t), Some(d)) if t.abs_diff(d) > VOCAB_SIZE_TOLERANCE => issues.push(format!(
+            "Vocabulary sizes differ too much: {t} (target) vs {d} (draft)"
+        )),
+        (Some(_), Some(_)) => {}
+        _ => warnings.push("Vocabulary size is unknown for one of the models".to_string()),
+    }
+    compare(&target.metadata, &draft.metadata, BOS_TOKEN_KEY, |m| {
+        issues.push(m)
+    });
+    compare(&target.metadata, &draft.metadata, EOS_TOKEN_KEY, |m| {
+        issues.push(m)
+    });
+    // Pre-tokenizer differences change how some text splits, lowering the acceptance rate
+    compare(&target.metadata, &draft.metadata, TOKENIZER_PRE_KEY, |m| {
+        warnings.push(m)
+    });
+
+    DraftCompatibility {
+        compatible: issues.is_empty(),
+        issues,
+        warnings,
+    }
+}
+
+pub fn validate_settings(settings: &SpeculativeSettings) -> Result<(), String> {
+    if let (Some(min), Some(max)) = (settings.draft_min, settings.draft_max) {
+        if min > max {
+            return Err(format!("draft_min ({min}) exceeds draft_max ({max})"));
+        }
+    }
+    if settings.draft_max == Some(0) {
+        return Err("draft_max must be at least 1".to_string());
+    }
+    if settings
+        .draft_p_min
+        .is_some_and(|p| !(0.0..=1.0).contains(&p))
+    {
+        return Err("draft_p_min must be between 0 and 1".to_string());
+    }
+    Ok(())
+}
+
+/// Load config values for the draft model of `settings`
+pub fn speculative_config(
+    data_folder: &Path,
+    settings: &SpeculativeSettings,
+) -> Result<SpeculativeConfig, String> {
+    let draft = read_model(data_folder, LLAMACPP_ENGINE, &settings.draft_model)?;
+    let as_i32 = |v: Option<u32>| v.map_or(0, |v| v.min(i32::MAX as u32) as i32);
+    Ok(SpeculativeConfig {
+        draft_model_path: resolve_catalog_path(data_folder, &draft.model_path)
+            .to_string_lossy()
+            .into_owned(),
+        draft_max: as_i32(settings.draft_max),
+        draft_min: as_i32(settings.draft_min),
+        draft_p_min: settings.draft_p_min.unwrap_or_default(),
+    })
+}
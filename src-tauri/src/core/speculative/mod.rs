@@ -0,0 +1,19 @@
+/*!
+   Speculative Decoding
+
+   Pairs a llama.cpp model with a smaller draft model that proposes tokens for the large model
+   to verify in one pass. The pairing is stored per model in the catalog (`speculative` in
+   `model.yml`) and turned into llama-server draft arguments when the model is loaded.
+
+   Drafting only pays off when both models tokenize text identically, so pairings are checked
+   against the GGUF metadata first: same tokenizer, vocabulary sizes within llama.cpp's
+   tolerance, same special tokens, and a draft that is actually smaller than the target.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// Result of checking a draft model against a target model
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DraftCompatibility {
+    pub compatible: bool,
+    /// Reasons the pairing cannot work
+    pub issues: Vec<String>,
+    /// Differences that may reduce the speed-up without breaking generation
+    pub warnings: Vec<String>,
+}
+
+/// Draft settings in the shape of the llama.cpp load config; zero means the server default
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct SpeculativeConfig {
+    pub draft_model_path: String,
+    pub draft_max: i32,
+    pub draft_min: i32,
+    pub draft_p_min: f32,
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use tauri::test::mock_app;
+
+use super::commands::{
+    get_speculative_config, get_speculative_settings, list_draft_candidates,
+    set_speculative_settings,
+};
+use super::helpers::{check_draft_compatibility, validate_settings, vocab_size, ModelInfo};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::model_catalog::models::{CatalogModel, SpeculativeSettings};
+
+fn info(size_bytes: u64, pairs: &[(&str, &str)]) -> ModelInfo {
+    ModelInfo {
+        model: CatalogModel::default(),
+        metadata: pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect::<HashMap<_, _>>(),
+        size_bytes,
+    }
+}
+
+const QWEN_TOKENIZER: &[(&str, &str)] = &[
+    ("general.architecture", "qwen3"),
+    ("tokenizer.ggml.model", "gpt2"),
+    ("tokenizer.ggml.pre", "qwen2"),
+    (
+        "tokenizer.ggml.tokens",
+        "<Array of type String with 151936 elements, data skipped>",
+    ),
+    ("tokenizer.ggml.bos_token_id", "151643"),
+    ("tokenizer.ggml.eos_token_id", "151645"),
+];
+
+#[test]
+fn test_vocab_size() {
+    assert_eq!(vocab_size(&info(0, QWEN_TOKENIZER).metadata), Some(151936));
+    let declared = info(
+        0,
+        &[
+            ("general.architecture", "llama"),
+            ("llama.vocab_size", "32000"),
+        ],
+    );
+    assert_eq!(vocab_size(&declared.metadata), Some(32000));
+    assert_eq!(vocab_size(&info(0, &[]).metadata), None);
+}
+
+#[test]
+fn test_check_draft_compatibility() {
+    let target = info(16_000, QWEN_TOKENIZER);
+    let draft = info(600, QWEN_TOKENIZER);
+    let result = check_draft_compatibility(&target, &draft);
+    assert!(result.compatible, "{:?}", result.issues);
+    assert!(result.warnings.is_empty());
+
+    // Larger draft
+    assert!(!check_draft_compatibility(&draft, &target).compatible);
+
+    let mut other = info(600, QWEN_TOKENIZER);
+    other
+        .metadata
+        .insert("tokenizer.ggml.model".to_string(), "llama".to_string());
+    other.metadata.insert(
+        "tokenizer.ggml.tokens".to_string(),
+        "<Array of type String with 32000 elements, data skipped>".to_string(),
+    );
+    let result = check_draft_compatibility(&target, &other);
+    assert!(!result.compatible);
+    assert_eq!(result.issues.len(), 2);
+
+    // A small vocabulary difference and another pre-tokenizer only warn
+    let mut close = info(600, QWEN_TOKENIZER);
+    close.metadata.insert(
+        "tokenizer.ggml.tokens".to_string(),
+        "<Array of type String with 151900 elements, data skipped>".to_string(),
+    );
+    close
+        .metadata
+        .insert("tokenizer.ggml.pre".to_string(), "llama-bpe".to_string());
+    let result = check_draft_compatibility(&target, &close);
+    assert!(result.compatible);
+    assert_eq!(result.warnings.len(), 1);
+}
+
+#[test]
+fn test_validate_settings() {
+    let settings = |min, max, p| SpeculativeSettings {
+        draft_model: "draft".to_string(),
+        draft_min: min,
+        draft_max: max,
+        draft_p_min: p,
+    };
+    assert!(validate_settings(&settings(Some(2), Some(16), Some(0.8))).is_ok());
+    assert!(validate_settings(&settings(None, None, None)).is_ok());
+    assert!(validate_settings(&settings(Some(8), Some(4), None)).is_err());
+    assert!(validate_settings(&settings(None, Some(0), None)).is_err());
+    assert!(validate_settings(&settings(None, None, Some(1.5))).is_err());
+}
+
+/// Minimal GGUF v3 file with string metadata and no tensors
+fn write_gguf(path: &Path, pairs: &[(&str, &str)], padding: usize) {
+    let mut data = b"GGUF".to_vec();
+    data.extend(3u32.to_le_bytes());
+    data.extend(0u64.to_le_bytes());
+    data.extend((pairs.len() as u64).to_le_bytes());
+    for (key, value) in pairs {
+        data.extend((key.len() as u64).to_le_bytes());
+        data.extend(key.as_bytes());
+        data.extend(8u32.to_le_bytes());
+        data.extend((value.len() as u64).to_le_bytes());
+        data.extend(value.as_bytes());
+    }
+    data.resize(data.len() + padding, 0);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, data).unwrap();
+}
+
+#[tokio::test]
+async fn test_speculative_settings_round_trip() {
+    let app = mock_app();
+    let data = get_jan_data_folder_path(app.handle().clone());
+    let models = data.join("llamacpp").join("models");
+    for (id, padding) in [("spec-test-large", 4096), ("spec-test-small", 0)] {
+        write_gguf(&models.join(id).join("model.gguf"), QWEN_TOKENIZER, padding);
+        fs::write(
+            models.join(id).join("model.yml"),
+            format!("model_path: llamacpp/models/{id}/model.gguf\n"),
+        )
+        .unwrap();
+    }
+
+    let candidates = list_draft_candidates(app.handle().clone(), "spec-test-large".to_string())
+        .await
+        .unwrap();
+    assert!(candidates.contains(&"spec-test-small".to_string()));
+    assert!(!candidates.contains(&"spec-test-large".to_string()));
+
+    let settings = SpeculativeSettings {
+        draft_model: "spec-test-small".to_string(),
+        draft_max: Some(16),
+        ..Default::default()
+    };
+    set_speculative_settings(
+        app.handle().clone(),
+        "spec-test-large".to_string(),
+        Some(settings.clone()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(
+        get_speculative_settings(app.handle().clone(), "spec-test-large".to_string())
+            .await
+            .unwrap(),
+        Some(settings)
+    );
+    let config = get_speculative_config(app.handle().clone(), "spec-test-large".to_string())
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(config.draft_model_path.ends_with("model.gguf"));
+    assert_eq!(config.draft_max, 16);
+
+    // The large model cannot draft for the small one
+    assert!(set_speculative_settings(
+        app.handle().clone(),
+        "spec-test-small".to_string(),
+        Some(SpeculativeSettings {
+            draft_model: "spec-test-large".to_string(),
+            ..Default::default()
+        }),
+    )
+    .await
+    .is_err());
+
+    // A removed draft model is ignored at load time
+    let _ = fs::remove_dir_all(models.join("spec-test-small"));
+    assert_eq!(
+        get_speculative_config(app.handle().clone(), "spec-test-large".to_string())
+            .await
+            .unwrap(),
+        None
+    );
+    let _ = fs::remove_dir_all(models.join("spec-test-large"));
+}
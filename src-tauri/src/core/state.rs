@@ -1,12 +1,16 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, fmt, sync::Arc};
 
-use crate::core::{downloads::models::DownloadManagerState, mcp::models::McpSettings};
+use crate::core::{
+    cancellation::CancellationTree, inference::models::NativeTool, mcp::models::McpSettings,
+    mcp::roots::JanClient,
+};
+use jan_utils::InFlight;
 use rmcp::{
-    model::{CallToolRequestParam, CallToolResult, InitializeRequestParam, Tool},
-    service::RunningService,
+    model::{CallToolRequestParam, CallToolResult, ClientRequest, ServerResult, Tool},
+    service::{Peer, RunningService},
     RoleClient, ServiceError,
 };
-use tokio::sync::{oneshot, Mutex};
+use tokio::sync::{oneshot, Mutex, RwLock};
 
 /// Server handle type for managing the proxy server lifecycle
 pub type ServerHandle =
@@ -20,6 +24,28 @@ pub struct ProviderConfig {
     pub base_url: Option<String>,
     pub custom_headers: Vec<ProviderCustomHeader>,
     pub models: Vec<String>,
+    /// Request policy overrides; unset fields fall back to the defaults of
+    /// `core::inference::retry`
+    pub connect_timeout_secs: Option<u64>,
+    pub read_timeout_secs: Option<u64>,
+    pub max_retries: Option<u32>,
+    pub retry_on_statuses: Option<Vec<u16>>,
+    pub backoff_initial_ms: Option<u64>,
+    pub backoff_max_ms: Option<u64>,
+    /// Add prompt-cache breakpoints to Anthropic requests; on unless set to false
+    pub prompt_caching: Option<bool>,
+    /// Compact tool schemas advertised to this provider; on unless set to false. The limits
+    /// fall back to the defaults of `core::inference::tool_schema`.
+    pub compact_tool_schemas: Option<bool>,
+    pub max_schema_description_chars: Option<usize>,
+    pub max_schema_enum_values: Option<usize>,
+    /// Tools the provider runs itself, such as web search; see `core::inference::native_tools`
+    #[serde(default)]
+    pub native_tools: Vec<NativeTool>,
+    /// Models an agent run continues with, in order, when this provider keeps failing with
+    /// rate limits, overload or an exhausted quota
+    #[serde(default)]
+    pub failover_models: Vec<String>,
 }
 
 #[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
@@ -29,26 +55,58 @@ pub struct ProviderCustomHeader {
 }
 
 pub enum RunningServiceEnum {
-    NoInit(RunningService<RoleClient, ()>),
-    WithInit(RunningService<RoleClient, InitializeRequestParam>),
+    /// Local, socket and replayed servers, greeted with the default client info
+    NoInit(RunningService<RoleClient, JanClient>),
+    /// Remote servers, greeted with Jan's own client name
+    WithInit(RunningService<RoleClient, JanClient>),
+}
+/// Operation that runs at most once at a time, so repeated requests for it wait for the
+/// running one instead of racing it
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum InFlightOperation {
+    /// Starting an MCP server, by server name
+    McpStart(String),
+    /// A download, by task id
+    Download(String),
+}
+
+impl fmt::Display for InFlightOperation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::McpStart(name) => write!(f, "start of MCP server {name}"),
+            Self::Download(id) => write!(f, "download task {id}"),
+        }
+    }
 }
-pub type SharedMcpServers = Arc<Mutex<HashMap<String, RunningServiceEnum>>>;
+
+/// Running MCP services by server name. Requests go through a cloned `peer()`, so the lock is
+/// only held to look a server up, never across a call to it.
+pub type SharedMcpServers = Arc<RwLock<HashMap<String, RunningServiceEnum>>>;
+/// Remote provider configurations by provider name, read on every proxied request
+pub type SharedProviderConfigs = Arc<RwLock<HashMap<String, ProviderConfig>>>;
 
 #[derive(Default)]
 pub struct AppState {
     pub app_token: Option<String>,
     pub mcp_servers: SharedMcpServers,
-    pub download_manager: Arc<Mutex<DownloadManagerState>>,
     pub mcp_active_servers: Arc<Mutex<HashMap<String, serde_json::Value>>>,
     pub server_handle: Arc<Mutex<Option<ServerHandle>>>,
-    pub tool_call_cancellations: Arc<Mutex<HashMap<String, oneshot::Sender<()>>>>,
+    /// Cancellation tokens of running turns, tool calls and downloads
+    pub cancellations: CancellationTree,
+    /// Operations running now that a repeated request must not start twice
+    pub in_flight: InFlight<InFlightOperation>,
     pub mcp_settings: Arc<Mutex<McpSettings>>,
     pub mcp_shutdown_in_progress: Arc<Mutex<bool>>,
     pub mcp_monitoring_tasks: Arc<Mutex<HashMap<String, tokio::task::JoinHandle<()>>>>,
     pub background_cleanup_handle: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
     pub mcp_server_pids: Arc<Mutex<HashMap<String, u32>>>,
+    /// Ports servers got for `{{port:..}}` placeholders and bridge ports, by server name and
+    /// placeholder or env var name
+    pub mcp_server_ports: Arc<Mutex<HashMap<String, HashMap<String, u16>>>>,
+    /// Secrets an MCP config import waits for the user to enter, by request id
+    pub mcp_secret_requests: Arc<Mutex<HashMap<String, oneshot::Sender<Option<String>>>>>,
     /// Remote provider configurations (e.g., Anthropic, OpenAI, etc.)
-    pub provider_configs: Arc<Mutex<HashMap<String, ProviderConfig>>>,
+    pub provider_configs: SharedProviderConfigs,
 }
 
 impl RunningServiceEnum {
@@ -67,4 +125,17 @@ impl RunningServiceEnum {
             Self::WithInit(s) => s.call_tool(params).await,
         }
     }
+    pub async fn send_request(&self, request: ClientRequest) -> Result<ServerResult, ServiceError> {
+        match self {
+            Self::NoInit(s) => s.send_request(request).await,
+            Self::WithInit(s) => s.send_request(request).await,
+        }
+    }
+    /// Handle for sending requests without holding the lock on the running servers
+    pub fn peer(&self) -> Peer<RoleClient> {
+        match self {
+            Self::NoInit(s) => s.peer().clone(),
+            Self::WithInit(s) => s.peer().clone(),
+        }
+    }
 }
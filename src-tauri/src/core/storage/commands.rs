@@ -0,0 +1,60 @@
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Manager, Runtime};
+
+use super::helpers::{clean_data_folder, record_model_load, storage_report};
+use super::models::{CleanStorageRequest, CleanStorageResult, StorageReport};
+use crate::core::app::commands::get_jan_data_folder_path;
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+/// Folders besides the data folder that hold lock files
+fn lock_dirs<R: Runtime>(app: &AppHandle<R>) -> Vec<PathBuf> {
+    app.path().app_data_dir().into_iter().collect()
+}
+
+/// Reports the data folder usage by category, largest items first
+#[tauri::command]
+pub async fn analyze_storage<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<StorageReport, String> {
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+    let lock_dirs = lock_dirs(&app_handle);
+    tokio::task::spawn_blocking(move || storage_report(&data_folder, &lock_dirs))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Clears caches, old logs, stale locks and unused models as selected
+#[tauri::command]
+pub async fn clean_storage<R: Runtime>(
+    app_handle: AppHandle<R>,
+    request: CleanStorageRequest,
+) -> Result<CleanStorageResult, String> {
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+    let lock_dirs = lock_dirs(&app_handle);
+    let result = tokio::task::spawn_blocking(move || {
+        clean_data_folder(&data_folder, &lock_dirs, &request, now())
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    if !result.removed.is_empty() {
+        log::info!(
+            "Storage cleanup removed {} entries, {} bytes",
+            result.removed.len(),
+            result.freed_bytes
+        );
+    }
+    Ok(result)
+}
+
+/// Records that a model was loaded, so unused models can be found later
+#[tauri::command]
+pub fn mark_model_loaded<R: Runtime>(
+    app_handle: AppHandle<R>,
+    model_id: String,
+) -> Result<(), String> {
+    record_model_load(&get_jan_data_folder_path(app_handle), &model_id, now())
+}
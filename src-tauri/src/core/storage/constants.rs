@@ -0,0 +1,11 @@
+// Storage constants
+pub const MODEL_LOADS_FILE: &str = "model_loads.json";
+pub const LOGS_DIR: &str = "logs";
+
+/// Data folder entries holding thread attachments and tool output
+pub const ATTACHMENT_DIRS: &[&str] = &["files", "tool_artifacts"];
+/// Lock files are named `*.lock`, MCP server locks `mcp_lock_<port>.json`
+pub const LOCK_FILE_EXTENSION: &str = "lock";
+pub const MCP_LOCK_PREFIX: &str = "mcp_lock_";
+
+pub const DAY_MS: i64 = 24 * 60 * 60 * 1000;
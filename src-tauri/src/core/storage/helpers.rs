@@ -0,0 +1,370 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+use super::constants::{
+    ATTACHMENT_DIRS, DAY_MS, LOCK_FILE_EXTENSION, LOGS_DIR, MCP_LOCK_PREFIX, MODEL_LOADS_FILE,
+};
+use super::models::{
+    CategoryUsage, CleanStorageRequest, CleanStorageResult, StorageCategory, StorageItem,
+    StorageReport,
+};
+use crate::core::mcp::constants::TOOL_CACHE_FILE;
+use crate::core::mcp::lockfile::is_process_alive;
+use crate::core::model_catalog::constants::MODEL_ENGINES;
+use crate::core::model_catalog::helpers::{
+    catalog_path, list_model_ids, model_dir, read_model, resolve_catalog_path,
+};
+use crate::core::prompt_cache::constants::PROMPT_CACHE_DIR;
+use crate::core::runtimes::constants::{BUN_CACHE_DIR, UV_CACHE_DIR};
+use crate::core::runtimes::helpers::dir_size;
+use crate::core::web_fetch::constants::{WEB_FETCH_CACHE_DIR, WEB_FETCH_DIR};
+
+static MODEL_LOADS_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+pub fn model_loads_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(MODEL_LOADS_FILE)
+}
+
+/// Last load time of each model id, in milliseconds since epoch
+pub fn read_model_loads(data_folder: &Path) -> HashMap<String, i64> {
+    fs::read_to_string(model_loads_path(data_folder))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn write_model_loads(data_folder: &Path, loads: &HashMap<String, i64>) -> Result<(), String> {
+    let data = serde_json::to_string_pretty(loads).map_err(|e| e.to_string())?;
+    fs::write(model_loads_path(data_folder), data).map_err(|e| e.to_string())
+}
+
+pub fn record_model_load(data_folder: &Path, model_id: &str, now: i64) -> Result<(), String> {
+    let _guard = MODEL_LOADS_LOCK
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let mut loads = read_model_loads(data_folder);
+    loads.insert(model_id.to_string(), now);
+    write_model_loads(data_folder, &loads)
+}
+
+fn forget_model_loads(data_folder: &Path, model_ids: &[String]) -> Result<(), String> {
+    let _guard = MODEL_LOADS_LOCK
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let mut loads = read_model_loads(data_folder);
+    loads.retain(|id, _| !model_ids.contains(id));
+    write_model_loads(data_folder, &loads)
+}
+
+fn millis(time: std::io::Result<SystemTime>) -> Option<i64> {
+    let duration = time.ok()?.duration_since(SystemTime::UNIX_EPOCH).ok()?;
+    i64::try_from(duration.as_millis()).ok()
+}
+
+fn entry_size(path: &Path) -> u64 {
+    match fs::symlink_metadata(path) {
+        Ok(meta) if meta.is_dir() => dir_size(path),
+        Ok(meta) => meta.len(),
+        Err(_) => 0,
+    }
+}
+
+fn storage_item(data_folder: &Path, path: &Path) -> StorageItem {
+    StorageItem {
+        path: catalog_path(data_folder, path),
+        size_bytes: entry_size(path),
+        modified_at: fs::metadata(path).ok().and_then(|m| millis(m.modified())),
+        engine: None,
+        model_id: None,
+        last_loaded_at: None,
+        stale: false,
+    }
+}
+
+fn child_paths(dir: &Path) -> Vec<PathBuf> {
+    fs::read_dir(dir)
+        .map(|entries| entries.flatten().map(|e| e.path()).collect())
+        .unwrap_or_default()
+}
+
+/// Recorded load time, else when the weights were last read
+fn model_last_loaded(
+    data_folder: &Path,
+    engine: &str,
+    model_id: &str,
+    loads: &HashMap<String, i64>,
+) -> Option<i64> {
+    if let Some(at) = loads.get(model_id) {
+        return Some(*at);
+    }
+    let model = read_model(data_folder, engine, model_id).ok()?;
+    let meta = fs::metadata(resolve_catalog_path(data_folder, &model.model_path)).ok()?;
+    millis(meta.accessed()).or_else(|| millis(meta.modified()))
+}
+
+fn model_items(data_folder: &Path) -> Vec<StorageItem> {
+    let loads = read_model_loads(data_folder);
+    let mut items = Vec::new();
+    for engine in MODEL_ENGINES {
+        for model_id in list_model_ids(data_folder, engine) {
+            let Ok(dir) = model_dir(data_folder, engine, &model_id) else {
+                continue;
+            };
+            let mut item = storage_item(data_folder, &dir);
+            item.last_loaded_at = model_last_loaded(data_folder, engine, &model_id, &loads);
+            item.engine = Some(engine.to_string());
+            item.model_id = Some(model_id);
+            items.push(item);
+        }
+    }
+    items
+}
+
+fn is_lock_file(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .unwrap_or_default();
+    path.is_file()
+        && (path
+            .extension()
+            .is_some_and(|ext| ext == LOCK_FILE_EXTENSION)
+            || (name.starts_with(MCP_LOCK_PREFIX) && name.ends_with(".json")))
+}
+
+/// The lock names a process that is no longer running. Locks that can't be read are kept.
+pub fn lock_is_stale(path: &Path) -> bool {
+    let pid = fs::read_to_string(path)
+        .ok()
+        .and_then(|data| serde_json::from_str::<serde_json::Value>(&data).ok())
+        .and_then(|lock| lock.get("pid").and_then(|pid| pid.as_u64()))
+        .and_then(|pid| u32::try_from(pid).ok());
+    match pid {
+        Some(pid) => !is_process_alive(pid),
+        None => false,
+    }
+}
+
+fn cache_paths(data_folder: &Path) -> Vec<PathBuf> {
+    vec![
+        data_folder.join(PROMPT_CACHE_DIR),
+        data_folder.join(WEB_FETCH_DIR).join(WEB_FETCH_CACHE_DIR),
+        data_folder.join(TOOL_CACHE_FILE),
+    ]
+}
+
+/// Entries below `dir` that are neither claimed nor hold a claimed path
+fn unclaimed_paths(dir: &Path, claimed: &[PathBuf]) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for path in child_paths(dir) {
+        if claimed.contains(&path) {
+            continue;
+        }
+        if path.is_dir() && claimed.iter().any(|c| c.starts_with(&path)) {
+            paths.extend(unclaimed_paths(&path, claimed));
+        } else {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+/// Usage of the data folder. `lock_dirs` are searched for lock files as well, for locks kept
+/// outside the data folder.
+pub fn storage_report(data_folder: &Path, lock_dirs: &[PathBuf]) -> StorageReport {
+    let mut categories: HashMap<StorageCategory, Vec<PathBuf>> = HashMap::new();
+    let mut add = |category, paths: Vec<PathBuf>| {
+        let existing: Vec<PathBuf> = paths.into_iter().filter(|p| p.exists()).collect();
+        categories.entry(category).or_default().extend(existing);
+    };
+
+    add(
+        StorageCategory::Attachments,
+        ATTACHMENT_DIRS
+            .iter()
+            .flat_map(|dir| child_paths(&data_folder.join(dir)))
+            .collect(),
+    );
+    add(StorageCategory::Caches, cache_paths(data_folder));
+    add(
+        StorageCategory::Logs,
+        child_paths(&data_folder.join(LOGS_DIR)),
+    );
+    add(
+        StorageCategory::PackageCaches,
+        vec![
+            data_folder.join(BUN_CACHE_DIR),
+            data_folder.join(UV_CACHE_DIR),
+        ],
+    );
+    let mut lock_search = vec![data_folder.to_path_buf()];
+    lock_search.extend(lock_dirs.iter().filter(|d| *d != data_folder).cloned());
+    add(
+        StorageCategory::LockFiles,
+        lock_search
+            .iter()
+            .flat_map(|dir| child_paths(dir))
+            .filter(|path| is_lock_file(path))
+            .collect(),
+    );
+
+    let models = model_items(data_folder);
+    let mut claimed: Vec<PathBuf> = categories.values().flatten().cloned().collect();
+    claimed.extend(
+        ATTACHMENT_DIRS
+            .iter()
+            .chain([LOGS_DIR].iter())
+            .map(|dir| data_folder.join(dir)),
+    );
+    claimed.extend(
+        models
+            .iter()
+            .map(|item| resolve_catalog_path(data_folder, &item.path)),
+    );
+    let other = unclaimed_paths(data_folder, &claimed);
+
+    let mut usage: Vec<CategoryUsage> = Vec::new();
+    for category in StorageCategory::ALL {
+        let mut items = match category {
+            StorageCategory::Models => models.clone(),
+            StorageCategory::Other => other.iter().map(|p| storage_item(data_folder, p)).collect(),
+            _ => categories
+                .get(&category)
+                .map(|paths| paths.iter().map(|p| storage_item(data_folder, p)).collect())
+                .unwrap_or_default(),
+        };
+        if category == StorageCategory::LockFiles {
+            for item in &mut items {
+                item.stale = lock_is_stale(&resolve_catalog_path(data_folder, &item.path));
+            }
+        }
+        items.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes).then(a.path.cmp(&b.path)));
+        usage.push(CategoryUsage {
+            category,
+            size_bytes: items.iter().map(|item| item.size_bytes).sum(),
+            items,
+        });
+    }
+
+    StorageReport {
+        data_folder: data_folder.to_string_lossy().into_owned(),
+        total_bytes: usage.iter().map(|c| c.size_bytes).sum(),
+        categories: usage,
+    }
+}
+
+fn category_items(report: &StorageReport, category: StorageCategory) -> Vec<StorageItem> {
+    report
+        .categories
+        .iter()
+        .find(|usage| usage.category == category)
+        .map(|usage| usage.items.clone())
+        .unwrap_or_default()
+}
+
+/// What `request` would remove, by category
+pub fn items_to_clean(
+    report: &StorageReport,
+    request: &CleanStorageRequest,
+    now: i64,
+) -> Vec<(StorageCategory, StorageItem)> {
+    let mut selected = Vec::new();
+    let mut select = |category, items: Vec<StorageItem>| {
+        selected.extend(items.into_iter().map(|item| (category, item)));
+    };
+    if request.caches {
+        select(
+            StorageCategory::Caches,
+            category_items(report, StorageCategory::Caches),
+        );
+    }
+    if request.package_caches {
+        select(
+            StorageCategory::PackageCaches,
+            category_items(report, StorageCategory::PackageCaches),
+        );
+    }
+    if request.logs {
+        let mut logs = category_items(report, StorageCategory::Logs);
+        // The newest file is the one being written
+        if let Some(newest) = logs
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, item)| item.modified_at)
+            .map(|(i, _)| i)
+        {
+            logs.remove(newest);
+        }
+        select(StorageCategory::Logs, logs);
+    }
+    if request.stale_locks {
+        let locks = category_items(report, StorageCategory::LockFiles);
+        select(
+            StorageCategory::LockFiles,
+            locks.into_iter().filter(|item| item.stale).collect(),
+        );
+    }
+    if let Some(days) = request.unused_models_days {
+        let cutoff = now - days as i64 * DAY_MS;
+        let models = category_items(report, StorageCategory::Models);
+        select(
+            StorageCategory::Models,
+            models
+                .into_iter()
+                .filter(|item| item.last_loaded_at.is_some_and(|at| at < cutoff))
+                .collect(),
+        );
+    }
+    selected
+}
+
+fn remove_path(path: &Path, keep_dir: bool) -> std::io::Result<()> {
+    if fs::symlink_metadata(path)?.is_dir() {
+        fs::remove_dir_all(path)?;
+        if keep_dir {
+            fs::create_dir_all(path)?;
+        }
+        Ok(())
+    } else {
+        fs::remove_file(path)
+    }
+}
+
+/// Remove what `request` selects. Package caches are emptied rather than removed, the
+/// runtimes expect their folders to exist.
+pub fn clean_data_folder(
+    data_folder: &Path,
+    lock_dirs: &[PathBuf],
+    request: &CleanStorageRequest,
+    now: i64,
+) -> CleanStorageResult {
+    let report = storage_report(data_folder, lock_dirs);
+    let mut result = CleanStorageResult::default();
+    let mut removed_models = Vec::new();
+    for (category, item) in items_to_clean(&report, request, now) {
+        let path = resolve_catalog_path(data_folder, &item.path);
+        if !request.dry_run {
+            let keep_dir = category == StorageCategory::PackageCaches;
+            if let Err(e) = remove_path(&path, keep_dir) {
+                result.failed.push(format!("{}: {e}", item.path));
+                continue;
+            }
+        }
+        if let Some(model_id) = item.model_id {
+            removed_models.push(model_id);
+        }
+        result.freed_bytes += item.size_bytes;
+        result.removed.push(item.path);
+    }
+    if !request.dry_run && !removed_models.is_empty() {
+        if let Err(e) = forget_model_loads(data_folder, &removed_models) {
+            log::warn!("Failed to update {MODEL_LOADS_FILE}: {e}");
+        }
+    }
+    result
+}
@@ -0,0 +1,23 @@
+/*!
+   Data Folder Storage
+
+   Reports what the Jan data folder holds, by category, and frees space safely:
+   - models: one entry per installed model, with the last time it was loaded. Loads are
+     recorded in `model_loads.json` by the app and the CLI; models loaded before that are
+     dated by the weights file's access time.
+   - attachments: images and files added to threads, and tool artifacts. Never cleaned.
+   - caches: saved prompt caches, fetched web pages and the MCP tool list cache. All of them
+     are rebuilt on demand.
+   - logs: the newest log file is the one being written and is kept when cleaning.
+   - package caches: the bun (`.npx`) and uv (`.uvx`) caches of the MCP servers.
+   - lock files: only locks whose owning process is gone are removed.
+   Everything else (threads, assistants, settings, engine backends) is reported as `other`.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
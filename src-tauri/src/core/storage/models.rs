@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageCategory {
+    Models,
+    Attachments,
+    Caches,
+    Logs,
+    PackageCaches,
+    LockFiles,
+    Other,
+}
+
+impl StorageCategory {
+    pub const ALL: [StorageCategory; 7] = [
+        StorageCategory::Models,
+        StorageCategory::Attachments,
+        StorageCategory::Caches,
+        StorageCategory::Logs,
+        StorageCategory::PackageCaches,
+        StorageCategory::LockFiles,
+        StorageCategory::Other,
+    ];
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StorageItem {
+    /// Relative to the data folder when inside it
+    pub path: String,
+    pub size_bytes: u64,
+    /// Milliseconds since epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub engine: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_id: Option<String>,
+    /// Models only, milliseconds since epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_loaded_at: Option<i64>,
+    /// Lock files only: the owning process is gone
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub stale: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CategoryUsage {
+    pub category: StorageCategory,
+    pub size_bytes: u64,
+    /// Largest first
+    pub items: Vec<StorageItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StorageReport {
+    pub data_folder: String,
+    pub total_bytes: u64,
+    pub categories: Vec<CategoryUsage>,
+}
+
+/// What `clean_storage` removes
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CleanStorageRequest {
+    /// Empty the prompt, web page and MCP tool caches
+    pub caches: bool,
+    /// Empty the bun and uv package caches
+    pub package_caches: bool,
+    /// Delete every log file but the one being written
+    pub logs: bool,
+    /// Delete lock files whose owner is gone
+    pub stale_locks: bool,
+    /// Delete models not loaded for this many days
+    pub unused_models_days: Option<u64>,
+    /// Report what would be removed without removing it
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CleanStorageResult {
+    pub freed_bytes: u64,
+    pub removed: Vec<String>,
+    /// Paths that could not be removed, with the reason
+    pub failed: Vec<String>,
+}
@@ -0,0 +1,184 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::json;
+
+use super::constants::DAY_MS;
+use super::helpers::{
+    clean_data_folder, items_to_clean, read_model_loads, record_model_load, storage_report,
+};
+use super::models::{CleanStorageRequest, StorageCategory, StorageReport};
+use crate::core::model_catalog::helpers::write_model;
+use crate::core::model_catalog::models::CatalogModel;
+
+const NOW: i64 = 1_700_000_000_000;
+/// Above any real pid limit, so never a running process
+const DEAD_PID: u32 = 99_999_999;
+
+fn write(root: &Path, path: &str, bytes: usize) {
+    let path = root.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, vec![b'x'; bytes]).unwrap();
+}
+
+fn add_model(root: &Path, model_id: &str, bytes: usize) {
+    let model_path = format!("llamacpp/models/{model_id}/model.gguf");
+    write(root, &model_path, bytes);
+    write_model(
+        root,
+        "llamacpp",
+        model_id,
+        &CatalogModel {
+            model_path,
+            size_bytes: bytes as u64,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+}
+
+fn data_folder() -> PathBuf {
+    let root = std::env::temp_dir().join(format!("jan-storage-{}", uuid::Uuid::new_v4()));
+    add_model(&root, "org/old", 4096);
+    add_model(&root, "org/recent", 2048);
+    write(&root, "llamacpp/backends/b1/llama-server", 300);
+    write(&root, "files/pic.png", 100);
+    write(&root, "prompt_cache/slot.bin", 500);
+    write(&root, "web_fetch/cache/page.json", 50);
+    write(&root, "web_fetch/settings.json", 10);
+    write(&root, "mcp_tool_cache.json", 20);
+    write(&root, "logs/app.log", 70);
+    write(&root, ".npx/pkg/index.js", 60);
+    write(&root, "threads/t1/thread.json", 30);
+    let lock = |pid: u32| json!({ "pid": pid }).to_string();
+    fs::write(root.join("server.lock"), lock(DEAD_PID)).unwrap();
+    fs::write(root.join("app.lock"), lock(std::process::id())).unwrap();
+    root
+}
+
+fn paths(report: &StorageReport, category: StorageCategory) -> Vec<String> {
+    let usage = report
+        .categories
+        .iter()
+        .find(|c| c.category == category)
+        .unwrap();
+    usage.items.iter().map(|item| item.path.clone()).collect()
+}
+
+#[test]
+fn test_storage_report_categories() {
+    let root = data_folder();
+    let report = storage_report(&root, &[]);
+
+    assert_eq!(
+        paths(&report, StorageCategory::Models),
+        ["llamacpp/models/org/old", "llamacpp/models/org/recent"]
+    );
+    assert_eq!(
+        paths(&report, StorageCategory::Attachments),
+        ["files/pic.png"]
+    );
+    assert_eq!(
+        paths(&report, StorageCategory::Caches),
+        ["prompt_cache", "web_fetch/cache", "mcp_tool_cache.json"]
+    );
+    assert_eq!(paths(&report, StorageCategory::Logs), ["logs/app.log"]);
+    assert_eq!(paths(&report, StorageCategory::PackageCaches), [".npx"]);
+    let mut other = paths(&report, StorageCategory::Other);
+    other.sort();
+    assert_eq!(
+        other,
+        ["llamacpp/backends", "threads", "web_fetch/settings.json"]
+    );
+
+    let locks = &report
+        .categories
+        .iter()
+        .find(|c| c.category == StorageCategory::LockFiles)
+        .unwrap()
+        .items;
+    let stale: Vec<_> = locks.iter().filter(|l| l.stale).map(|l| &l.path).collect();
+    assert_eq!(locks.len(), 2);
+    assert_eq!(stale, ["server.lock"]);
+
+    // Model sizes include model.yml, so every byte on disk is accounted for once
+    let sum: u64 = report.categories.iter().map(|c| c.size_bytes).sum();
+    assert_eq!(report.total_bytes, sum);
+    assert!(report.total_bytes > 4096 + 2048 + 300 + 500);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_clean_unused_models_and_caches() {
+    let root = data_folder();
+    record_model_load(&root, "org/old", NOW - 40 * DAY_MS).unwrap();
+    record_model_load(&root, "org/recent", NOW - DAY_MS).unwrap();
+
+    let request = CleanStorageRequest {
+        caches: true,
+        package_caches: true,
+        stale_locks: true,
+        unused_models_days: Some(30),
+        dry_run: true,
+        ..Default::default()
+    };
+    let dry = clean_data_folder(&root, &[], &request, NOW);
+    assert!(dry.removed.contains(&"llamacpp/models/org/old".to_string()));
+    assert!(!dry
+        .removed
+        .contains(&"llamacpp/models/org/recent".to_string()));
+    assert!(root.join("llamacpp/models/org/old").exists());
+
+    let result = clean_data_folder(
+        &root,
+        &[],
+        &CleanStorageRequest {
+            dry_run: false,
+            ..request
+        },
+        NOW,
+    );
+    assert!(result.failed.is_empty(), "{:?}", result.failed);
+    assert_eq!(result.removed, dry.removed);
+    assert_eq!(result.freed_bytes, dry.freed_bytes);
+    assert!(!root.join("llamacpp/models/org/old").exists());
+    assert!(root.join("llamacpp/models/org/recent").exists());
+    assert!(!root.join("prompt_cache").exists());
+    assert!(!root.join("web_fetch/cache").exists());
+    assert!(root.join("web_fetch/settings.json").exists());
+    // Package caches are emptied, not removed
+    assert!(root.join(".npx").is_dir());
+    assert!(!root.join(".npx/pkg").exists());
+    assert!(!root.join("server.lock").exists());
+    assert!(root.join("app.lock").exists());
+    assert!(root.join("files/pic.png").exists());
+
+    let loads = read_model_loads(&root);
+    assert!(!loads.contains_key("org/old"));
+    assert!(loads.contains_key("org/recent"));
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn test_clean_logs_keeps_newest() {
+    let root = data_folder();
+    write(&root, "logs/app_2024-01-01.log", 40);
+    let old = fs::File::options()
+        .write(true)
+        .open(root.join("logs/app_2024-01-01.log"))
+        .unwrap();
+    old.set_modified(std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1))
+        .unwrap();
+
+    let report = storage_report(&root, &[]);
+    let request = CleanStorageRequest {
+        logs: true,
+        ..Default::default()
+    };
+    let selected: Vec<String> = items_to_clean(&report, &request, NOW)
+        .into_iter()
+        .map(|(_, item)| item.path)
+        .collect();
+    assert_eq!(selected, ["logs/app_2024-01-01.log"]);
+    let _ = fs::remove_dir_all(&root);
+}
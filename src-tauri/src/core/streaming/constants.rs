@@ -0,0 +1,7 @@
+use std::time::Duration;
+
+// Token streaming constants
+/// Interval at which coalesced deltas are flushed to the frontend (~60fps)
+pub const FLUSH_INTERVAL: Duration = Duration::from_millis(16);
+/// Flush immediately once this many bytes are pending
+pub const MAX_COALESCED_BYTES: usize = 4096;
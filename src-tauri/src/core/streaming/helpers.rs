@@ -0,0 +1,105 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use tauri::ipc::Channel;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+use super::constants::{FLUSH_INTERVAL, MAX_COALESCED_BYTES};
+use super::models::TokenChunk;
+
+struct Shared {
+    pending: Mutex<String>,
+    notify: Notify,
+    closed: AtomicBool,
+}
+
+/// Coalescing token streamer. `push` never blocks the producer; a background task
+/// drains the buffer every `FLUSH_INTERVAL` and hands the merged delta to the sink.
+pub struct TokenStreamer {
+    shared: Arc<Shared>,
+    task: JoinHandle<()>,
+}
+
+impl TokenStreamer {
+    /// Start a streamer delivering chunks to `sink`. Delivery stops when the sink fails.
+    pub fn spawn<F>(stream_id: String, sink: F) -> Self
+    where
+        F: Fn(TokenChunk) -> Result<(), String> + Send + 'static,
+    {
+        let shared = Arc::new(Shared {
+            pending: Mutex::new(String::new()),
+            notify: Notify::new(),
+            closed: AtomicBool::new(false),
+        });
+        let task_shared = shared.clone();
+        let task = tokio::spawn(async move {
+            let mut seq = 0u64;
+            let mut sink_alive = true;
+            loop {
+                task_shared.notify.notified().await;
+                let closed = task_shared.closed.load(Ordering::SeqCst);
+                if !closed {
+                    // Give the producer a moment to add more tokens to this chunk
+                    let large = task_shared
+                        .pending
+                        .lock()
+                        .map(|p| p.len() >= MAX_COALESCED_BYTES)
+                        .unwrap_or(false);
+                    if !large {
+                        tokio::time::sleep(FLUSH_INTERVAL).await;
+                    }
+                }
+                let closed = task_shared.closed.load(Ordering::SeqCst);
+                let delta = task_shared
+                    .pending
+                    .lock()
+                    .map(|mut p| std::mem::take(&mut *p))
+                    .unwrap_or_default();
+
+                if sink_alive && (!delta.is_empty() || closed) {
+                    let chunk = TokenChunk {
+                        stream_id: stream_id.clone(),
+                        seq,
+                        delta,
+                        done: closed,
+                    };
+                    seq += 1;
+                    if let Err(e) = sink(chunk) {
+                        log::warn!("Token stream {stream_id} receiver is gone: {e}");
+                        sink_alive = false;
+                    }
+                }
+                if closed {
+                    break;
+                }
+            }
+        });
+        Self { shared, task }
+    }
+
+    /// Start a streamer delivering chunks over a Tauri IPC channel
+    pub fn for_channel(stream_id: String, channel: Channel<TokenChunk>) -> Self {
+        Self::spawn(stream_id, move |chunk| {
+            channel.send(chunk).map_err(|e| e.to_string())
+        })
+    }
+
+    /// Queue a delta for delivery
+    pub fn push(&self, delta: &str) {
+        if delta.is_empty() {
+            return;
+        }
+        if let Ok(mut pending) = self.shared.pending.lock() {
+            pending.push_str(delta);
+        }
+        self.shared.notify.notify_one();
+    }
+
+    /// Flush what is pending, send the final chunk and wait for delivery
+    pub async fn finish(self) {
+        self.shared.closed.store(true, Ordering::SeqCst);
+        self.shared.notify.notify_one();
+        let _ = self.task.await;
+    }
+}
@@ -0,0 +1,16 @@
+/*!
+   Token Streaming Pipeline
+
+   High-frequency token deltas are delivered over a dedicated Tauri IPC channel instead of the
+   global event bus, which otherwise causes UI jank on long generations. Deltas pushed by a
+   producer are coalesced in a buffer and flushed at a fixed cadence (or when the buffer grows
+   large), so the number of IPC messages is bounded no matter how fast the model produces
+   tokens. Lifecycle events (started, finished, errors) keep using the normal event bus.
+*/
+
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// Message sent over a token channel
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TokenChunk {
+    pub stream_id: String,
+    /// Monotonic sequence number, lets the receiver detect gaps
+    pub seq: u64,
+    /// Coalesced text since the previous chunk
+    pub delta: String,
+    /// Set on the final chunk of the stream
+    pub done: bool,
+}
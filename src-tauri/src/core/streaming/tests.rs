@@ -0,0 +1,58 @@
+use super::helpers::TokenStreamer;
+use super::models::TokenChunk;
+use std::sync::{Arc, Mutex};
+
+fn collecting_streamer() -> (TokenStreamer, Arc<Mutex<Vec<TokenChunk>>>) {
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let sink_received = received.clone();
+    let streamer = TokenStreamer::spawn("s1".to_string(), move |chunk| {
+        sink_received.lock().unwrap().push(chunk);
+        Ok(())
+    });
+    (streamer, received)
+}
+
+#[tokio::test]
+async fn test_streamer_coalesces_bursts() {
+    let (streamer, received) = collecting_streamer();
+    for token in ["Hel", "lo", ", ", "world"] {
+        streamer.push(token);
+    }
+    streamer.finish().await;
+
+    let chunks = received.lock().unwrap();
+    let text: String = chunks.iter().map(|c| c.delta.as_str()).collect();
+    assert_eq!(text, "Hello, world");
+    // A burst pushed before the first flush is delivered as far fewer messages
+    assert!(chunks.len() <= 2, "got {} chunks", chunks.len());
+    assert!(chunks.last().unwrap().done);
+    for (i, chunk) in chunks.iter().enumerate() {
+        assert_eq!(chunk.seq, i as u64);
+        assert_eq!(chunk.stream_id, "s1");
+    }
+}
+
+#[tokio::test]
+async fn test_streamer_finish_without_tokens_sends_done() {
+    let (streamer, received) = collecting_streamer();
+    streamer.finish().await;
+    let chunks = received.lock().unwrap();
+    assert_eq!(chunks.len(), 1);
+    assert!(chunks[0].done);
+    assert!(chunks[0].delta.is_empty());
+}
+
+#[tokio::test]
+async fn test_streamer_stops_after_sink_failure() {
+    let calls = Arc::new(Mutex::new(0));
+    let sink_calls = calls.clone();
+    let streamer = TokenStreamer::spawn("s2".to_string(), move |_| {
+        *sink_calls.lock().unwrap() += 1;
+        Err("closed".to_string())
+    });
+    streamer.push("a");
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    streamer.push("b");
+    streamer.finish().await;
+    assert_eq!(*calls.lock().unwrap(), 1);
+}
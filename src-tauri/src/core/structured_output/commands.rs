@@ -0,0 +1,14 @@
+use tauri::{AppHandle, Runtime};
+
+use super::helpers::generate_structured;
+use super::models::{StructuredOutput, StructuredOutputRequest};
+
+/// Ask `request.model` for a JSON value conforming to `request.schema`, enforced natively,
+/// by grammar or by instruction depending on the model, and repaired when invalid
+#[tauri::command]
+pub async fn generate_structured_output<R: Runtime>(
+    app_handle: AppHandle<R>,
+    request: StructuredOutputRequest,
+) -> Result<StructuredOutput, String> {
+    generate_structured(&app_handle, request).await
+}
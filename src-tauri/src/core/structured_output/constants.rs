@@ -0,0 +1,18 @@
+// Structured output constants
+
+/// Repair attempts after the first answer when none is requested
+pub const DEFAULT_MAX_REPAIRS: u32 = 2;
+/// Upper bound on requested repair attempts
+pub const MAX_REPAIRS_LIMIT: u32 = 5;
+/// Schema name sent to providers when the caller doesn't give one
+pub const DEFAULT_SCHEMA_NAME: &str = "response";
+/// Validation errors quoted back to the model in a repair request
+pub const MAX_ERRORS_REPORTED: usize = 10;
+
+/// System instruction carrying the schema; `{schema}` is replaced with the schema JSON
+pub const SCHEMA_INSTRUCTION: &str = "Respond only with a JSON value that conforms to this \
+JSON schema, without any explanation or markdown:\n{schema}";
+
+/// Follow-up sent after invalid output; `{errors}` is replaced with one error per line
+pub const REPAIR_INSTRUCTION: &str = "Your previous response did not conform to the JSON \
+schema:\n{errors}\nReply again with only the corrected JSON value.";
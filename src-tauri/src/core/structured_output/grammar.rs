@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+/// Rules every generated grammar can refer to. Each value rule consumes trailing whitespace.
+const PRIMITIVE_RULES: &str = r#"ws ::= ([ \t\n] ws)?
+char ::= [^"\\\x7F\x00-\x1F] | "\\" (["\\bfnrt/] | "u" [0-9a-fA-F]{4})
+string ::= "\"" char* "\"" ws
+integer ::= "-"? ([0-9] | [1-9] [0-9]{0,15}) ws
+number ::= "-"? ([0-9] | [1-9] [0-9]{0,15}) ("." [0-9]+)? ([eE] [-+]? [0-9]{1,15})? ws
+boolean ::= ("true" | "false") ws
+null ::= "null" ws
+object ::= "{" ws (string ":" ws value ("," ws string ":" ws value)*)? "}" ws
+array ::= "[" ws (value ("," ws value)*)? "]" ws
+value ::= object | array | string | number | boolean | null
+"#;
+
+/// Grammar literal for an exact JSON value
+fn literal(value: &Value) -> String {
+    let json = value.to_string();
+    let mut out = String::with_capacity(json.len() + 2);
+    out.push('"');
+    for c in json.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Rule names may only contain letters, digits and dashes
+fn rule_name(raw: &str) -> String {
+    let name: String = raw
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    if name.is_empty() {
+        "rule".to_string()
+    } else {
+        name
+    }
+}
+
+/// `{min,max}` repetition suffix for a grammar item
+fn repetition(min: u64, max: Option<u64>) -> String {
+    match max {
+        Some(max) => format!("{{{min},{max}}}"),
+        None => format!("{{{min},}}"),
+    }
+}
+
+struct GrammarBuilder<'a> {
+    root: &'a Value,
+    rules: Vec<(String, String)>,
+    refs: HashMap<String, String>,
+}
+
+impl<'a> GrammarBuilder<'a> {
+    /// Add a rule under a name derived from `name` and return the name used
+    fn add_rule(&mut self, name: &str, body: String) -> String {
+        let base = rule_name(name);
+        let mut candidate = base.clone();
+        let mut index = 1;
+        loop {
+            match self.rules.iter().find(|(n, _)| *n == candidate) {
+                Some((_, existing)) if *existing == body => return candidate,
+                Some(_) => {
+                    index += 1;
+                    candidate = format!("{base}-{index}");
+                }
+                None => {
+                    self.rules.push((candidate.clone(), body));
+                    return candidate;
+                }
+            }
+        }
+    }
+
+    fn resolve_ref(&mut self, reference: &str) -> Result<String, String> {
+        if let Some(name) = self.refs.get(reference) {
+            return Ok(name.clone());
+        }
+        let target = reference
+            .strip_prefix('#')
+            .and_then(|pointer| self.root.pointer(pointer))
+            .ok_or_else(|| format!("Unresolvable schema reference '{reference}'"))?;
+        let name = rule_name(&format!(
+            "ref-{}",
+            reference.rsplit('/').next().unwrap_or_default()
+        ));
+        // Reserve the name first so recursive definitions refer back to it
+        self.refs.insert(reference.to_string(), name.clone());
+        self.rules.push((name.clone(), String::new()));
+        let body = self.body(target, &name)?;
+        if let Some(rule) = self.rules.iter_mut().find(|(n, _)| *n == name) {
+            rule.1 = body;
+        }
+        Ok(name)
+    }
+
+    /// Rule name matching `schema`, adding rules as needed
+    fn visit(&mut self, schema: &Value, name: &str) -> Result<String, String> {
+        if let Some(reference) = schema.get("$ref").and_then(|r| r.as_str()) {
+            return self.resolve_ref(reference);
+        }
+        let body = self.body(schema, name)?;
+        // Plain references to a primitive need no rule of their own
+        if PRIMITIVE_RULES
+            .lines()
+            .any(|line| line.split(" ::= ").next() == Some(body.as_str()))
+        {
+            return Ok(body);
+        }
+        Ok(self.add_rule(name, body))
+    }
+
+    fn alternatives(&mut self, schemas: &[Value], name: &str) -> Result<String, String> {
+        let mut names = Vec::with_capacity(schemas.len());
+        for (i, schema) in schemas.iter().enumerate() {
+            names.push(self.visit(schema, &format!("{name}-{i}"))?);
+        }
+        Ok(names.join(" | "))
+    }
+
+    /// Right-hand side of the rule for `schema`
+    fn body(&mut self, schema: &Value, name: &str) -> Result<String, String> {
+        let Some(object) = schema.as_object() else {
+            return match schema {
+                Value::Bool(false) => Err("Schema 'false' matches nothing".to_string()),
+                _ => Ok("value".to_string()),
+            };
+        };
+        if let Some(reference) = object.get("$ref").and_then(|r| r.as_str()) {
+            return self.resolve_ref(reference);
+        }
+        if let Some(value) = object.get("const") {
+            return Ok(format!("{} ws", literal(value)));
+        }
+        if let Some(values) = object.get("enum").and_then(|e| e.as_array()) {
+            if values.is_empty() {
+                return Err("Schema enum is empty".to_string());
+            }
+            let literals: Vec<String> = values.iter().map(literal).collect();
+            return Ok(format!("({}) ws", literals.join(" | ")));
+        }
+        for key in ["anyOf", "oneOf"] {
+            if let Some(schemas) = object.get(key).and_then(|s| s.as_array()) {
+                return self.alternatives(schemas, name);
+            }
+        }
+        if let Some(types) = object.get("type").and_then(|t| t.as_array()) {
+            let variants: Vec<Value> = types
+                .iter()
+                .map(|t| {
+                    let mut variant = schema.clone();
+                    variant["type"] = t.clone();
+                    variant
+                })
+                .collect();
+            return self.alternatives(&variants, name);
+        }
+
+        let kind = object.get("type").and_then(|t| t.as_str()).or_else(|| {
+            if object.contains_key("properties") {
+                Some("object")
+            } else if object.contains_key("items") {
+                Some("array")
+            } else {
+                None
+            }
+        });
+        match kind {
+            Some("object") => self.object_body(object, name),
+            Some("array") => self.array_body(object, name),
+            Some("string") => {
+                let min = object.get("minLength").and_then(|v| v.as_u64());
+                let max = object.get("maxLength").and_then(|v| v.as_u64());
+                if min.is_none() && max.is_none() {
+                    return Ok("string".to_string());
+                }
+                Ok(format!(
+                    "\"\\\"\" char{} \"\\\"\" ws",
+                    repetition(min.unwrap_or(0), max)
+                ))
+            }
+            Some(primitive @ ("integer" | "number" | "boolean" | "null")) => {
+                Ok(primitive.to_string())
+            }
+            Some(other) => Err(format!("Unsupported schema type '{other}'")),
+            None => Ok("value".to_string()),
+        }
+    }
+
+    fn object_body(
+        &mut self,
+        object: &serde_json::Map<String, Value>,
+        name: &str,
+    ) -> Result<String, String> {
+        let Some(properties) = object.get("properties").and_then(|p| p.as_object()) else {
+            return Ok("object".to_string());
+        };
+        let required: Vec<&str> = object
+            .get("required")
+            .and_then(|r| r.as_array())
+            .map(|r| r.iter().filter_map(|v| v.as_str()).collect())
+            .unwrap_or_default();
+
+        let mut kvs = HashMap::new();
+        let mut optional_kvs = Vec::new();
+        for (key, property) in properties {
+            let value_rule = self.visit(property, &format!("{name}-{key}"))?;
+            let kv = self.add_rule(
+                &format!("{name}-{key}-kv"),
+                format!(
+                    "{} ws \":\" ws {value_rule}",
+                    literal(&Value::from(key.as_str()))
+                ),
+            );
+            if required.contains(&key.as_str()) {
+                kvs.insert(key.as_str(), kv);
+            } else {
+                optional_kvs.push(kv);
+            }
+        }
+        let required_kvs: Vec<String> = required.iter().filter_map(|k| kvs.remove(k)).collect();
+
+        // Required properties come first in the order they are listed; optional ones may
+        // follow, each at most once and in order
+        let optional_tail = |from: usize| -> String {
+            optional_kvs[from..]
+                .iter()
+                .map(|kv| format!(" (\",\" ws {kv})?"))
+                .collect()
+        };
+        let members = if required_kvs.is_empty() {
+            if optional_kvs.is_empty() {
+                String::new()
+            } else {
+                let starts: Vec<String> = optional_kvs
+                    .iter()
+                    .enumerate()
+                    .map(|(i, kv)| format!("{kv}{}", optional_tail(i + 1)))
+                    .collect();
+                format!(" ({})?", starts.join(" | "))
+            }
+        } else {
+            format!(" {}{}", required_kvs.join(" \",\" ws "), optional_tail(0))
+        };
+        Ok(format!("\"{{\" ws{members} \"}}\" ws"))
+    }
+
+    fn array_body(
+        &mut self,
+        object: &serde_json::Map<String, Value>,
+        name: &str,
+    ) -> Result<String, String> {
+        let item = match object.get("items") {
+            Some(items) => self.visit(items, &format!("{name}-item"))?,
+            None => "value".to_string(),
+        };
+        let min = object.get("minItems").and_then(|v| v.as_u64()).unwrap_or(0);
+        let max = object.get("maxItems").and_then(|v| v.as_u64());
+        if max == Some(0) {
+            return Ok("\"[\" ws \"]\" ws".to_string());
+        }
+        let rest = format!(
+            "(\",\" ws {item}){}",
+            repetition(min.saturating_sub(1), max.map(|m| m - 1))
+        );
+        if min == 0 {
+            Ok(format!("\"[\" ws ({item} {rest})? \"]\" ws"))
+        } else {
+            Ok(format!("\"[\" ws {item} {rest} \"]\" ws"))
+        }
+    }
+}
+
+/// Convert a JSON schema into a GBNF grammar for llama.cpp's grammar-constrained decoding.
+/// Supports objects with properties (required ones first, then optional ones in order),
+/// arrays with item counts, strings with lengths, numbers, booleans, null, `enum`, `const`,
+/// `anyOf`/`oneOf`, type lists and local `$ref`s. Keywords that can't be expressed, such as
+/// `pattern` or `minimum`, are left to validation of the output.
+pub fn schema_to_gbnf(schema: &Value) -> Result<String, String> {
+    let mut builder = GrammarBuilder {
+        root: schema,
+        rules: Vec::new(),
+        refs: HashMap::new(),
+    };
+    let root = builder.visit(schema, "root")?;
+    // The root rule goes first so the grammar reads top-down
+    let mut grammar = String::new();
+    match builder.rules.iter().position(|(name, _)| *name == root) {
+        Some(index) if root == "root" => {
+            let (name, body) = builder.rules.remove(index);
+            grammar.push_str(&format!("{name} ::= {body}\n"));
+        }
+        _ => grammar.push_str(&format!("root ::= {root}\n")),
+    }
+    for (name, body) in &builder.rules {
+        grammar.push_str(&format!("{name} ::= {body}\n"));
+    }
+    grammar.push_str(PRIMITIVE_RULES);
+    Ok(grammar)
+}
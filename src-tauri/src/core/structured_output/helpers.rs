@@ -0,0 +1,220 @@
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_llamacpp::state::LlamacppState;
+use tauri_plugin_mlx::state::MlxState;
+
+use super::constants::*;
+use super::grammar::schema_to_gbnf;
+use super::models::{EnforcementMode, StructuredOutput, StructuredOutputRequest};
+use super::validate::validate;
+use crate::core::inference::helpers::{
+    chat_completion, completion_text, resolve_model_endpoint, DEFAULT_COMPLETION_TIMEOUT,
+};
+use crate::core::inference::models::ModelEndpoint;
+use crate::core::scheduler::helpers::acquire_for_endpoint;
+use crate::core::scheduler::models::GenerationPriority;
+
+/// Parse the JSON value in a model answer, skipping a reasoning preamble, markdown code
+/// fences and any prose around the value
+pub fn extract_json(text: &str) -> Option<Value> {
+    let text = match text.rfind("</think>") {
+        Some(end) => &text[end + "</think>".len()..],
+        None => text,
+    };
+    let mut text = text.trim();
+    if let Some(fenced) = text.strip_prefix("```") {
+        // Drop the language tag of the opening fence
+        let body = fenced.split_once('\n').map_or("", |(_, body)| body);
+        text = body
+            .rsplit_once("```")
+            .map_or(body, |(body, _)| body)
+            .trim();
+    }
+    if let Ok(value) = serde_json::from_str(text) {
+        return Some(value);
+    }
+    // Take the first complete value starting at an opening bracket
+    text.char_indices()
+        .filter(|(_, c)| *c == '{' || *c == '[')
+        .find_map(|(start, _)| {
+            serde_json::Deserializer::from_str(&text[start..])
+                .into_iter::<Value>()
+                .next()
+                .and_then(Result::ok)
+        })
+}
+
+/// Parse and validate a model answer. Errors are meant to be shown to the model.
+pub fn check_output(text: &str, schema: &Value) -> Result<Value, Vec<String>> {
+    let value = extract_json(text).ok_or_else(|| vec!["the response is not valid JSON".into()])?;
+    let errors = validate(&value, schema);
+    if errors.is_empty() {
+        Ok(value)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Add the schema instruction to the conversation, merged into a leading plain-text system
+/// message since some chat templates accept only one
+pub fn with_schema_instruction(mut messages: Vec<Value>, schema: &Value) -> Vec<Value> {
+    let instruction = SCHEMA_INSTRUCTION.replace("{schema}", &schema.to_string());
+    match messages.first_mut() {
+        Some(first)
+            if first.get("role").and_then(|r| r.as_str()) == Some("system")
+                && first.get("content").is_some_and(|c| c.is_string()) =>
+        {
+            let content = first["content"].as_str().unwrap_or_default();
+            first["content"] = Value::String(format!("{content}\n\n{instruction}"));
+        }
+        _ => messages.insert(0, json!({"role": "system", "content": instruction})),
+    }
+    messages
+}
+
+/// User message asking the model to fix its previous answer
+pub fn repair_message(errors: &[String]) -> Value {
+    let mut listed: Vec<String> = errors
+        .iter()
+        .take(MAX_ERRORS_REPORTED)
+        .map(|e| format!("- {e}"))
+        .collect();
+    if errors.len() > MAX_ERRORS_REPORTED {
+        listed.push(format!("- and {} more", errors.len() - MAX_ERRORS_REPORTED));
+    }
+    json!({
+        "role": "user",
+        "content": REPAIR_INSTRUCTION.replace("{errors}", &listed.join("\n")),
+    })
+}
+
+/// Put the schema on a chat completion body in the form `mode` calls for
+pub fn apply_response_format(
+    body: &mut Value,
+    schema: &Value,
+    name: &str,
+    strict: bool,
+    mode: EnforcementMode,
+) -> Result<(), String> {
+    match mode {
+        EnforcementMode::NativeSchema => {
+            body["response_format"] = json!({
+                "type": "json_schema",
+                "json_schema": {"name": name, "schema": schema, "strict": strict},
+            });
+        }
+        EnforcementMode::Grammar => {
+            body["grammar"] = Value::String(schema_to_gbnf(schema)?);
+        }
+        EnforcementMode::PromptOnly => {}
+    }
+    Ok(())
+}
+
+/// Pick how to enforce a schema on `endpoint`: grammars for llama.cpp sessions, instructions
+/// for engines and providers without schema support, and `response_format` otherwise
+pub async fn enforcement_mode<R: Runtime>(
+    app: &AppHandle<R>,
+    endpoint: &ModelEndpoint,
+) -> EnforcementMode {
+    if endpoint.is_local {
+        if let Some(state) = app.try_state::<LlamacppState>() {
+            let sessions = state.llama_server_process.lock().await;
+            if sessions
+                .values()
+                .any(|s| s.info.model_id == endpoint.model_id)
+            {
+                return EnforcementMode::Grammar;
+            }
+        }
+        if let Some(state) = app.try_state::<MlxState>() {
+            let sessions = state.mlx_server_process.lock().await;
+            if sessions
+                .values()
+                .any(|s| s.info.model_id == endpoint.model_id)
+            {
+                return EnforcementMode::PromptOnly;
+            }
+        }
+    }
+    let host = url::Url::parse(&endpoint.base_url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+        .unwrap_or_default();
+    if host.ends_with("anthropic.com") {
+        EnforcementMode::PromptOnly
+    } else {
+        EnforcementMode::NativeSchema
+    }
+}
+
+/// Generate a JSON value conforming to `request.schema`. Output that fails validation is
+/// returned to the model together with the errors until it conforms or the repair attempts
+/// run out.
+pub async fn generate_structured<R: Runtime>(
+    app: &AppHandle<R>,
+    request: StructuredOutputRequest,
+) -> Result<StructuredOutput, String> {
+    if !request.schema.is_object() {
+        return Err("The schema must be a JSON object".to_string());
+    }
+    let endpoint = resolve_model_endpoint(app, &request.model).await?;
+    let mut mode = match request.mode {
+        Some(mode) => mode,
+        None => enforcement_mode(app, &endpoint).await,
+    };
+    if mode == EnforcementMode::Grammar {
+        if let Err(e) = schema_to_gbnf(&request.schema) {
+            log::warn!("Falling back to instructions for {}: {e}", request.model);
+            mode = EnforcementMode::PromptOnly;
+        }
+    }
+    let name = request
+        .schema_name
+        .as_deref()
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or(DEFAULT_SCHEMA_NAME);
+    let max_repairs = request
+        .max_repairs
+        .unwrap_or(DEFAULT_MAX_REPAIRS)
+        .min(MAX_REPAIRS_LIMIT);
+
+    let mut body = Value::Object(request.parameters.clone());
+    apply_response_format(&mut body, &request.schema, name, request.strict, mode)?;
+    let mut messages = with_schema_instruction(request.messages, &request.schema);
+    let job_id = format!("structured-output:{}", uuid::Uuid::new_v4());
+    let mut errors = Vec::new();
+    for attempt in 1..=max_repairs + 1 {
+        body["messages"] = Value::Array(messages.clone());
+        let permit =
+            acquire_for_endpoint(app, &endpoint, &job_id, GenerationPriority::Interactive).await?;
+        let response =
+            chat_completion(&endpoint, body.clone(), None, DEFAULT_COMPLETION_TIMEOUT).await;
+        drop(permit);
+        let text = completion_text(&response?).unwrap_or_default();
+        match check_output(&text, &request.schema) {
+            Ok(value) => {
+                return Ok(StructuredOutput {
+                    value,
+                    mode,
+                    attempts: attempt,
+                })
+            }
+            Err(found) => {
+                log::debug!(
+                    "Structured output attempt {attempt} for {} is invalid: {}",
+                    request.model,
+                    found.join("; ")
+                );
+                messages.push(json!({"role": "assistant", "content": text}));
+                messages.push(repair_message(&found));
+                errors = found;
+            }
+        }
+    }
+    Err(format!(
+        "Model output did not conform to the schema after {} attempt(s): {}",
+        max_repairs + 1,
+        errors.join("; ")
+    ))
+}
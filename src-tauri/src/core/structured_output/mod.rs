@@ -0,0 +1,25 @@
+/*!
+   Structured Output
+
+   Makes a model answer with JSON matching a caller-supplied JSON schema:
+   - providers with native support receive the schema as an OpenAI-style `response_format`
+     of type `json_schema`,
+   - local llama.cpp models are constrained during decoding by a GBNF grammar generated
+     from the schema, so they can only produce matching JSON,
+   - providers with neither (Anthropic's OpenAI-compatible endpoint) get the schema as an
+     instruction.
+
+   Whatever the mode, the output is parsed and validated against the schema. Invalid output
+   is sent back to the model with the validation errors, up to a configurable number of
+   repair attempts.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod grammar;
+pub mod helpers;
+pub mod models;
+pub mod validate;
+
+#[cfg(test)]
+mod tests;
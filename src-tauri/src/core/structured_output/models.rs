@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// How the schema is enforced on a model
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnforcementMode {
+    /// `response_format` with a JSON schema, enforced by the provider
+    NativeSchema,
+    /// GBNF grammar constraining llama.cpp's sampler
+    Grammar,
+    /// Schema given as an instruction only
+    PromptOnly,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructuredOutputRequest {
+    pub model: String,
+    pub messages: Vec<Value>,
+    pub schema: Value,
+    #[serde(default)]
+    pub schema_name: Option<String>,
+    /// Ask native providers for strict schema adherence
+    #[serde(default)]
+    pub strict: bool,
+    /// Extra completion parameters such as `temperature`
+    #[serde(default)]
+    pub parameters: Map<String, Value>,
+    #[serde(default)]
+    pub max_repairs: Option<u32>,
+    /// Overrides the mode picked for the model
+    #[serde(default)]
+    pub mode: Option<EnforcementMode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructuredOutput {
+    pub value: Value,
+    pub mode: EnforcementMode,
+    /// Completions requested, including repairs
+    pub attempts: u32,
+}
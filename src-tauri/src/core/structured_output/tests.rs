@@ -0,0 +1,187 @@
+use serde_json::json;
+
+use super::grammar::schema_to_gbnf;
+use super::helpers::*;
+use super::models::EnforcementMode;
+use super::validate::validate;
+
+fn rule<'a>(grammar: &'a str, name: &str) -> &'a str {
+    grammar
+        .lines()
+        .find_map(|line| line.strip_prefix(&format!("{name} ::= ")))
+        .unwrap_or_else(|| panic!("no rule '{name}' in:\n{grammar}"))
+}
+
+#[test]
+fn test_gbnf_for_object() {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "name": {"type": "string"},
+            "age": {"type": "integer"},
+            "tags": {"type": "array", "items": {"type": "string"}, "maxItems": 3}
+        },
+        "required": ["name"]
+    });
+    let grammar = schema_to_gbnf(&schema).unwrap();
+    assert_eq!(
+        rule(&grammar, "root"),
+        r#""{" ws root-name-kv ("," ws root-age-kv)? ("," ws root-tags-kv)? "}" ws"#
+    );
+    assert_eq!(
+        rule(&grammar, "root-name-kv"),
+        r#""\"name\"" ws ":" ws string"#
+    );
+    assert_eq!(
+        rule(&grammar, "root-tags"),
+        r#""[" ws (string ("," ws string){0,2})? "]" ws"#
+    );
+    for primitive in ["ws", "string", "integer", "number", "value"] {
+        rule(&grammar, primitive);
+    }
+}
+
+#[test]
+fn test_gbnf_for_enums_unions_and_refs() {
+    let schema = json!({
+        "$defs": {
+            "node": {
+                "type": "object",
+                "properties": {
+                    "kind": {"enum": ["leaf", "branch"]},
+                    "children": {"type": "array", "items": {"$ref": "#/$defs/node"}}
+                },
+                "required": ["kind"]
+            }
+        },
+        "anyOf": [{"$ref": "#/$defs/node"}, {"type": "null"}]
+    });
+    let grammar = schema_to_gbnf(&schema).unwrap();
+    assert_eq!(rule(&grammar, "root"), "ref-node | null");
+    assert_eq!(
+        rule(&grammar, "ref-node-kind"),
+        r#"("\"leaf\"" | "\"branch\"") ws"#
+    );
+    assert!(rule(&grammar, "ref-node-children").contains("ref-node"));
+
+    assert!(schema_to_gbnf(&json!({"$ref": "#/$defs/missing"})).is_err());
+    assert!(schema_to_gbnf(&json!({"type": "date"})).is_err());
+}
+
+#[test]
+fn test_validate() {
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "name": {"type": "string", "minLength": 2},
+            "score": {"type": "number", "minimum": 0, "maximum": 1},
+            "items": {"type": "array", "items": {"type": "integer"}},
+            "code": {"type": "string", "pattern": "^[A-Z]{3}$"}
+        },
+        "required": ["name", "score"],
+        "additionalProperties": false
+    });
+    assert!(validate(
+        &json!({"name": "ok", "score": 0.5, "items": [1, 2.0]}),
+        &schema
+    )
+    .is_empty());
+
+    let mut errors = validate(
+        &json!({"name": "x", "score": 2, "items": [1, "two"], "code": "abc", "extra": true}),
+        &schema,
+    );
+    errors.sort();
+    assert_eq!(
+        errors,
+        vec![
+            "$.code: does not match pattern '^[A-Z]{3}$'",
+            "$.items[1]: expected integer, found string",
+            "$.name: shorter than 2 characters",
+            "$.score: 2 is above the maximum",
+            "$: unexpected property 'extra'",
+        ]
+    );
+    assert_eq!(
+        validate(&json!({}), &schema),
+        vec![
+            "$: missing required property 'name'",
+            "$: missing required property 'score'"
+        ]
+    );
+    assert!(!validate(
+        &json!(3),
+        &json!({"oneOf": [{"type": "integer"}, {"type": "number"}]})
+    )
+    .is_empty());
+}
+
+#[test]
+fn test_extract_json() {
+    assert_eq!(extract_json(r#"{"a": 1}"#), Some(json!({"a": 1})));
+    assert_eq!(
+        extract_json("<think>maybe {x}</think>\n```json\n[1, 2]\n```"),
+        Some(json!([1, 2]))
+    );
+    assert_eq!(
+        extract_json("Sure! Here it is: {\"a\": {\"b\": true}} Hope that helps."),
+        Some(json!({"a": {"b": true}}))
+    );
+    assert_eq!(extract_json("no json here"), None);
+
+    let schema = json!({"type": "object", "required": ["a"]});
+    assert!(check_output("{\"a\": 1}", &schema).is_ok());
+    assert_eq!(
+        check_output("{}", &schema).unwrap_err(),
+        vec!["$: missing required property 'a'"]
+    );
+}
+
+#[test]
+fn test_apply_response_format_and_messages() {
+    let schema = json!({"type": "object", "properties": {"a": {"type": "boolean"}}});
+
+    let mut body = json!({"temperature": 0});
+    apply_response_format(
+        &mut body,
+        &schema,
+        "answer",
+        true,
+        EnforcementMode::NativeSchema,
+    )
+    .unwrap();
+    assert_eq!(body["response_format"]["type"], "json_schema");
+    assert_eq!(body["response_format"]["json_schema"]["name"], "answer");
+    assert_eq!(body["response_format"]["json_schema"]["strict"], true);
+
+    let mut body = json!({});
+    apply_response_format(
+        &mut body,
+        &schema,
+        "answer",
+        false,
+        EnforcementMode::Grammar,
+    )
+    .unwrap();
+    assert!(body["grammar"].as_str().unwrap().starts_with("root ::= "));
+    assert!(body.get("response_format").is_none());
+
+    let messages = with_schema_instruction(
+        vec![
+            json!({"role": "system", "content": "Be brief."}),
+            json!({"role": "user", "content": "Hi"}),
+        ],
+        &schema,
+    );
+    assert_eq!(messages.len(), 2);
+    assert!(messages[0]["content"]
+        .as_str()
+        .unwrap()
+        .starts_with("Be brief.\n\nRespond only with"));
+
+    let errors: Vec<String> = (0..12).map(|i| format!("error {i}")).collect();
+    let repair = repair_message(&errors);
+    let content = repair["content"].as_str().unwrap();
+    assert!(content.contains("- error 9\n- and 2 more"));
+    assert!(!content.contains("error 10"));
+}
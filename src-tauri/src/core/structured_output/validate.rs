@@ -0,0 +1,238 @@
+use serde_json::Value;
+
+/// JSON type name of `value` as used by JSON schema
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn matches_type(value: &Value, expected: &str) -> bool {
+    match (expected, type_name(value)) {
+        ("number", "integer") => true,
+        // 3.0 is an integer as far as JSON schema is concerned
+        ("integer", "number") => value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        (expected, actual) => expected == actual,
+    }
+}
+
+struct Validator<'a> {
+    root: &'a Value,
+    errors: Vec<String>,
+    /// Guards against `$ref` cycles that never consume any input
+    depth: usize,
+}
+
+const MAX_DEPTH: usize = 64;
+
+impl<'a> Validator<'a> {
+    fn is_valid(&self, value: &Value, schema: &Value) -> bool {
+        let mut probe = Validator {
+            root: self.root,
+            errors: Vec::new(),
+            depth: self.depth,
+        };
+        probe.check(value, schema, "$");
+        probe.errors.is_empty()
+    }
+
+    fn check(&mut self, value: &Value, schema: &Value, path: &str) {
+        let Some(schema) = schema.as_object() else {
+            if schema == &Value::Bool(false) {
+                self.errors
+                    .push(format!("{path}: no value is allowed here"));
+            }
+            return;
+        };
+        if self.depth >= MAX_DEPTH {
+            self.errors.push(format!("{path}: schema nests too deeply"));
+            return;
+        }
+        self.depth += 1;
+        self.check_keywords(value, schema, path);
+        self.depth -= 1;
+    }
+
+    fn check_keywords(
+        &mut self,
+        value: &Value,
+        schema: &serde_json::Map<String, Value>,
+        path: &str,
+    ) {
+        if let Some(reference) = schema.get("$ref").and_then(|r| r.as_str()) {
+            match reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.root.pointer(pointer))
+            {
+                Some(target) => self.check(value, target, path),
+                None => self
+                    .errors
+                    .push(format!("{path}: unresolvable reference '{reference}'")),
+            }
+        }
+
+        let types: Vec<&str> = match schema.get("type") {
+            Some(Value::String(t)) => vec![t.as_str()],
+            Some(Value::Array(types)) => types.iter().filter_map(|t| t.as_str()).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|t| matches_type(value, t)) {
+            self.errors.push(format!(
+                "{path}: expected {}, found {}",
+                types.join(" or "),
+                type_name(value)
+            ));
+            return;
+        }
+        if let Some(values) = schema.get("enum").and_then(|e| e.as_array()) {
+            if !values.contains(value) {
+                self.errors.push(format!(
+                    "{path}: {value} is not one of {}",
+                    Value::Array(values.clone())
+                ));
+            }
+        }
+        if let Some(expected) = schema.get("const") {
+            if expected != value {
+                self.errors
+                    .push(format!("{path}: expected {expected}, found {value}"));
+            }
+        }
+        if let Some(schemas) = schema.get("allOf").and_then(|s| s.as_array()) {
+            for sub in schemas {
+                self.check(value, sub, path);
+            }
+        }
+        if let Some(schemas) = schema.get("anyOf").and_then(|s| s.as_array()) {
+            if !schemas.iter().any(|sub| self.is_valid(value, sub)) {
+                self.errors
+                    .push(format!("{path}: matches none of the allowed schemas"));
+            }
+        }
+        if let Some(schemas) = schema.get("oneOf").and_then(|s| s.as_array()) {
+            let matching = schemas
+                .iter()
+                .filter(|sub| self.is_valid(value, sub))
+                .count();
+            if matching != 1 {
+                self.errors.push(format!(
+                    "{path}: must match exactly one schema, matches {matching}"
+                ));
+            }
+        }
+
+        match value {
+            Value::Object(object) => {
+                let properties = schema.get("properties").and_then(|p| p.as_object());
+                if let Some(required) = schema.get("required").and_then(|r| r.as_array()) {
+                    for key in required.iter().filter_map(|k| k.as_str()) {
+                        if !object.contains_key(key) {
+                            self.errors
+                                .push(format!("{path}: missing required property '{key}'"));
+                        }
+                    }
+                }
+                for (key, item) in object {
+                    let item_path = format!("{path}.{key}");
+                    match properties.and_then(|p| p.get(key)) {
+                        Some(sub) => self.check(item, sub, &item_path),
+                        None => match schema.get("additionalProperties") {
+                            Some(Value::Bool(false)) => self
+                                .errors
+                                .push(format!("{path}: unexpected property '{key}'")),
+                            Some(sub @ Value::Object(_)) => self.check(item, sub, &item_path),
+                            _ => {}
+                        },
+                    }
+                }
+            }
+            Value::Array(items) => {
+                if let Some(sub) = schema.get("items") {
+                    for (i, item) in items.iter().enumerate() {
+                        self.check(item, sub, &format!("{path}[{i}]"));
+                    }
+                }
+                if let Some(min) = schema.get("minItems").and_then(|v| v.as_u64()) {
+                    if (items.len() as u64) < min {
+                        self.errors
+                            .push(format!("{path}: expected at least {min} items"));
+                    }
+                }
+                if let Some(max) = schema.get("maxItems").and_then(|v| v.as_u64()) {
+                    if items.len() as u64 > max {
+                        self.errors
+                            .push(format!("{path}: expected at most {max} items"));
+                    }
+                }
+            }
+            Value::String(text) => {
+                let length = text.chars().count() as u64;
+                if let Some(min) = schema.get("minLength").and_then(|v| v.as_u64()) {
+                    if length < min {
+                        self.errors
+                            .push(format!("{path}: shorter than {min} characters"));
+                    }
+                }
+                if let Some(max) = schema.get("maxLength").and_then(|v| v.as_u64()) {
+                    if length > max {
+                        self.errors
+                            .push(format!("{path}: longer than {max} characters"));
+                    }
+                }
+                if let Some(pattern) = schema.get("pattern").and_then(|p| p.as_str()) {
+                    match regex::Regex::new(pattern) {
+                        Ok(re) if !re.is_match(text) => self
+                            .errors
+                            .push(format!("{path}: does not match pattern '{pattern}'")),
+                        Ok(_) => {}
+                        Err(e) => log::warn!("Ignoring invalid schema pattern '{pattern}': {e}"),
+                    }
+                }
+            }
+            Value::Number(number) => {
+                let Some(n) = number.as_f64() else {
+                    return;
+                };
+                let bound = |key: &str| schema.get(key).and_then(|v| v.as_f64());
+                if bound("minimum").is_some_and(|min| n < min) {
+                    self.errors
+                        .push(format!("{path}: {n} is below the minimum"));
+                }
+                if bound("maximum").is_some_and(|max| n > max) {
+                    self.errors
+                        .push(format!("{path}: {n} is above the maximum"));
+                }
+                if bound("exclusiveMinimum").is_some_and(|min| n <= min) {
+                    self.errors
+                        .push(format!("{path}: {n} is not above the exclusive minimum"));
+                }
+                if bound("exclusiveMaximum").is_some_and(|max| n >= max) {
+                    self.errors
+                        .push(format!("{path}: {n} is not below the exclusive maximum"));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Validate `value` against a JSON schema. Covers the keywords used for structured output:
+/// `type`, `enum`, `const`, `allOf`/`anyOf`/`oneOf`, object properties, `required` and
+/// `additionalProperties`, array items and counts, string lengths and `pattern`, numeric
+/// bounds and local `$ref`s. Returns one message per violation, each prefixed with the
+/// path of the offending value (`$.items[2].name`); empty when the value conforms.
+pub fn validate(value: &Value, schema: &Value) -> Vec<String> {
+    let mut validator = Validator {
+        root: schema,
+        errors: Vec::new(),
+        depth: 0,
+    };
+    validator.check(value, schema, "$");
+    validator.errors
+}
@@ -0,0 +1,324 @@
+//! Remote storage of synced objects. Both backends only get, put and delete whole objects
+//! by key; the manifest kept next to them replaces listing.
+
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use reqwest::{Client, Method, Response, StatusCode};
+use sha2::{Digest, Sha256};
+
+use super::constants::{S3_SERVICE, SYNC_REQUEST_TIMEOUT};
+use super::models::SyncBackendConfig;
+
+type HmacSha256 = Hmac<Sha256>;
+
+#[async_trait]
+pub trait SyncBackend: Send + Sync {
+    /// Where objects are stored, for the offline check
+    fn base_url(&self) -> String;
+
+    /// The object at `key`, `None` when there is none
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String>;
+
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), String>;
+
+    /// Delete the object at `key`; deleting a missing object succeeds
+    async fn delete(&self, key: &str) -> Result<(), String>;
+}
+
+pub fn backend_for(config: &SyncBackendConfig) -> Result<Box<dyn SyncBackend>, String> {
+    let client = Client::builder()
+        .timeout(SYNC_REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    Ok(match config.clone() {
+        SyncBackendConfig::S3 {
+            endpoint,
+            bucket,
+            region,
+            access_key_id,
+            secret_access_key,
+            prefix,
+        } => Box::new(S3Backend {
+            client,
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            bucket,
+            region,
+            access_key_id,
+            secret_access_key,
+            prefix,
+        }),
+        SyncBackendConfig::WebDav {
+            url,
+            username,
+            password,
+        } => Box::new(WebDavBackend {
+            client,
+            url: url.trim_end_matches('/').to_string(),
+            username,
+            password,
+            folders: Mutex::new(HashSet::new()),
+        }),
+    })
+}
+
+/// Identifies the storage a config points at; credentials may change without a new sync
+pub fn backend_id(config: &SyncBackendConfig) -> String {
+    match config {
+        SyncBackendConfig::S3 {
+            endpoint,
+            bucket,
+            prefix,
+            ..
+        } => format!("s3:{}/{bucket}/{prefix}", endpoint.trim_end_matches('/')),
+        SyncBackendConfig::WebDav { url, .. } => {
+            format!("webdav:{}", url.trim_end_matches('/'))
+        }
+    }
+}
+
+async fn check_response(response: Response, action: &str, key: &str) -> Result<Response, String> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let text = response.text().await.unwrap_or_default();
+    Err(format!(
+        "Failed to {action} {key}: HTTP status {status} {}",
+        text.chars().take(200).collect::<String>()
+    ))
+}
+
+async fn object_body(response: Response, key: &str) -> Result<Option<Vec<u8>>, String> {
+    if response.status() == StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let response = check_response(response, "download", key).await?;
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to download {key}: {e}"))?;
+    Ok(Some(bytes.to_vec()))
+}
+
+pub struct WebDavBackend {
+    client: Client,
+    url: String,
+    username: String,
+    password: String,
+    /// Folders known to exist
+    folders: Mutex<HashSet<String>>,
+}
+
+impl WebDavBackend {
+    fn request(&self, method: Method, path: &str) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, format!("{}/{path}", self.url));
+        if self.username.is_empty() {
+            request
+        } else {
+            request.basic_auth(&self.username, Some(&self.password))
+        }
+    }
+
+    /// Create the folder of `key`, which WebDAV servers don't do on upload
+    async fn ensure_folder(&self, key: &str) -> Result<(), String> {
+        let Some((folder, _)) = key.rsplit_once('/') else {
+            return Ok(());
+        };
+        if self
+            .folders
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(folder)
+        {
+            return Ok(());
+        }
+        let mkcol = Method::from_bytes(b"MKCOL").map_err(|e| e.to_string())?;
+        let response = self
+            .request(mkcol, &format!("{folder}/"))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to create {folder}: {e}"))?;
+        // 405: the folder exists already
+        if response.status() != StatusCode::METHOD_NOT_ALLOWED {
+            check_response(response, "create", folder).await?;
+        }
+        self.folders
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(folder.to_string());
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SyncBackend for WebDavBackend {
+    fn base_url(&self) -> String {
+        self.url.clone()
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        let response = self
+            .request(Method::GET, key)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to download {key}: {e}"))?;
+        object_body(response, key).await
+    }
+
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), String> {
+        self.ensure_folder(key).await?;
+        let response = self
+            .request(Method::PUT, key)
+            .body(data)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to upload {key}: {e}"))?;
+        check_response(response, "upload", key).await.map(|_| ())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        let response = self
+            .request(Method::DELETE, key)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to delete {key}: {e}"))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+        check_response(response, "delete", key).await.map(|_| ())
+    }
+}
+
+pub struct S3Backend {
+    client: Client,
+    endpoint: String,
+    bucket: String,
+    region: String,
+    access_key_id: String,
+    secret_access_key: String,
+    prefix: String,
+}
+
+impl S3Backend {
+    /// Send a request signed with AWS Signature Version 4
+    async fn send(&self, method: Method, key: &str, body: Vec<u8>) -> Result<Response, String> {
+        let url = url::Url::parse(&format!(
+            "{}/{}/{}{key}",
+            self.endpoint, self.bucket, self.prefix
+        ))
+        .map_err(|e| format!("Invalid S3 endpoint {}: {e}", self.endpoint))?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (Some(host), None) => host.to_string(),
+            (None, _) => return Err(format!("Invalid S3 endpoint {}", self.endpoint)),
+        };
+        let payload_hash = hex::encode(Sha256::digest(&body));
+        let amz_date = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let authorization = s3_authorization(
+            &S3Request {
+                method: method.as_str(),
+                host: &host,
+                path: url.path(),
+                payload_hash: &payload_hash,
+                amz_date: &amz_date,
+            },
+            &self.region,
+            &self.access_key_id,
+            &self.secret_access_key,
+        );
+        self.client
+            .request(method, url)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", payload_hash)
+            .header(reqwest::header::AUTHORIZATION, authorization)
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach {}: {e}", self.endpoint))
+    }
+}
+
+#[async_trait]
+impl SyncBackend for S3Backend {
+    fn base_url(&self) -> String {
+        self.endpoint.clone()
+    }
+
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        let response = self.send(Method::GET, key, Vec::new()).await?;
+        object_body(response, key).await
+    }
+
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<(), String> {
+        let response = self.send(Method::PUT, key, data).await?;
+        check_response(response, "upload", key).await.map(|_| ())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), String> {
+        let response = self.send(Method::DELETE, key, Vec::new()).await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+        check_response(response, "delete", key).await.map(|_| ())
+    }
+}
+
+/// The parts of an S3 request that are signed
+pub struct S3Request<'a> {
+    pub method: &'a str,
+    pub host: &'a str,
+    /// URI encoded path; object keys only use characters that need no encoding
+    pub path: &'a str,
+    pub payload_hash: &'a str,
+    /// `YYYYMMDD'T'HHMMSS'Z'`
+    pub amz_date: &'a str,
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC can take key of any size");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+pub fn signing_key(secret_access_key: &str, date: &str, region: &str, service: &str) -> Vec<u8> {
+    let key = hmac_sha256(format!("AWS4{secret_access_key}").as_bytes(), date);
+    let key = hmac_sha256(&key, region);
+    let key = hmac_sha256(&key, service);
+    hmac_sha256(&key, "aws4_request")
+}
+
+/// `Authorization` header of a request without query, signing the host, date and payload
+pub fn s3_authorization(
+    request: &S3Request,
+    region: &str,
+    access_key_id: &str,
+    secret_access_key: &str,
+) -> String {
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{signed_headers}\n{}",
+        request.method,
+        request.path,
+        request.host,
+        request.payload_hash,
+        request.amz_date,
+        request.payload_hash
+    );
+    let date = &request.amz_date[..8.min(request.amz_date.len())];
+    let scope = format!("{date}/{region}/{S3_SERVICE}/aws4_request");
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{scope}\n{}",
+        request.amz_date,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+    let signature = hex::encode(hmac_sha256(
+        &signing_key(secret_access_key, date, region, S3_SERVICE),
+        &string_to_sign,
+    ));
+    format!(
+        "AWS4-HMAC-SHA256 Credential={access_key_id}/{scope}, SignedHeaders={signed_headers}, Signature={signature}"
+    )
+}
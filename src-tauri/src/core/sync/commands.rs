@@ -0,0 +1,41 @@
+use tauri::{AppHandle, Runtime};
+
+use super::helpers::{
+    emit_sync_status, normalize_config, read_config, run_sync, sync_status, unlock_storage,
+    write_config,
+};
+use super::models::{SyncConfig, SyncReport, SyncStatus};
+use crate::core::app::commands::get_jan_data_folder_path;
+
+#[tauri::command]
+pub fn get_sync_config<R: Runtime>(app: AppHandle<R>) -> SyncConfig {
+    read_config(&get_jan_data_folder_path(app))
+}
+
+/// Store the sync config. With a passphrase, the storage is unlocked with it, or set up when
+/// no device synced to it yet; without one, the passphrase entered before stays in use.
+#[tauri::command]
+pub async fn set_sync_config<R: Runtime>(
+    app: AppHandle<R>,
+    config: SyncConfig,
+    passphrase: Option<String>,
+) -> Result<SyncStatus, String> {
+    let config = normalize_config(config)?;
+    if let (Some(backend), Some(passphrase)) = (&config.backend, &passphrase) {
+        unlock_storage(&app, backend, passphrase).await?;
+    }
+    let data_folder = get_jan_data_folder_path(app.clone());
+    write_config(&data_folder, &config)?;
+    emit_sync_status(&app);
+    Ok(sync_status(&data_folder))
+}
+
+#[tauri::command]
+pub fn get_sync_status<R: Runtime>(app: AppHandle<R>) -> SyncStatus {
+    sync_status(&get_jan_data_folder_path(app))
+}
+
+#[tauri::command]
+pub async fn sync_now<R: Runtime>(app: AppHandle<R>) -> Result<SyncReport, String> {
+    run_sync(&app).await
+}
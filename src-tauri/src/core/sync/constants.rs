@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+pub const SYNC_CONFIG_FILE: &str = "sync.json";
+/// What was synced last, the base of three-way merges
+pub const SYNC_STATE_FILE: &str = "sync_state.json";
+
+// Remote layout, below the configured prefix or folder
+/// Key derivation salt and passphrase check, the only object stored unencrypted
+pub const REMOTE_KEYS_OBJECT: &str = "keys.json";
+pub const REMOTE_MANIFEST_OBJECT: &str = "manifest.enc";
+pub const REMOTE_SETTINGS_OBJECT: &str = "settings.enc";
+pub const REMOTE_THREADS_DIR: &str = "threads";
+pub const REMOTE_ASSISTANTS_DIR: &str = "assistants";
+pub const REMOTE_OBJECT_EXTENSION: &str = "enc";
+
+/// Marks objects sealed by this format, followed by the nonce and the ciphertext
+pub const SEALED_MAGIC: &[u8] = b"JANSYNC1";
+pub const KEY_DERIVATION_ROUNDS: u32 = 210_000;
+/// Sealed into `keys.json` to tell a wrong passphrase from a corrupted object
+pub const KEY_CHECK_PLAINTEXT: &[u8] = b"jan-sync-key-check";
+
+/// Sections of the core settings that belong to one machine and are never synced
+pub const LOCAL_SETTINGS_SECTIONS: &[&str] = &["server", "lan"];
+
+pub const DEFAULT_SYNC_INTERVAL_MINUTES: u64 = 15;
+pub const MIN_SYNC_INTERVAL_MINUTES: u64 = 1;
+/// How often the scheduler checks whether a sync is due
+pub const SYNC_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+pub const SYNC_REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+pub const SYNC_STATUS_EVENT: &str = "sync-status-changed";
+/// AWS Signature Version 4 service name of S3
+pub const S3_SERVICE: &str = "s3";
@@ -0,0 +1,110 @@
+//! Encryption of synced objects: XChaCha20-Poly1305 with a random nonce per object, under a
+//! key derived from the sync passphrase with PBKDF2-HMAC-SHA256 and a salt kept on the
+//! remote, so every device derives the same key. Private threads seal their messages the
+//! same way, with their own `Sealing`.
+
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use sha2::{Digest, Sha256};
+
+use super::constants::{KEY_CHECK_PLAINTEXT, KEY_DERIVATION_ROUNDS, SEALED_MAGIC};
+use super::models::RemoteKeys;
+
+const NONCE_LEN: usize = 24;
+
+/// One use of the sealed format: the magic its objects start with, the key derivation rounds,
+/// the plaintext of its check value and what it seals, named in errors
+pub struct Sealing {
+    pub magic: &'static [u8],
+    pub rounds: u32,
+    pub check_plaintext: &'static [u8],
+    pub what: &'static str,
+}
+
+pub const SYNC_SEALING: Sealing = Sealing {
+    magic: SEALED_MAGIC,
+    rounds: KEY_DERIVATION_ROUNDS,
+    check_plaintext: KEY_CHECK_PLAINTEXT,
+    what: "sync object",
+};
+
+impl Sealing {
+    pub fn derive_key(&self, passphrase: &str, salt: &[u8]) -> [u8; 32] {
+        let mut key = [0u8; 32];
+        pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, self.rounds, &mut key);
+        key
+    }
+
+    pub fn seal(&self, key: &[u8; 32], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+        let nonce: [u8; NONCE_LEN] = rand::random();
+        let ciphertext = XChaCha20Poly1305::new(key.into())
+            .encrypt(XNonce::from_slice(&nonce), plaintext)
+            .map_err(|_| format!("Failed to encrypt {}", self.what))?;
+        let mut sealed = Vec::with_capacity(self.magic.len() + NONCE_LEN + ciphertext.len());
+        sealed.extend_from_slice(self.magic);
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    pub fn open(&self, key: &[u8; 32], sealed: &[u8]) -> Result<Vec<u8>, String> {
+        let body = sealed
+            .strip_prefix(self.magic)
+            .filter(|body| body.len() > NONCE_LEN)
+            .ok_or_else(|| format!("Not an encrypted {}", self.what))?;
+        let (nonce, ciphertext) = body.split_at(NONCE_LEN);
+        XChaCha20Poly1305::new(key.into())
+            .decrypt(XNonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                format!(
+                    "Failed to decrypt {}, was the passphrase changed?",
+                    self.what
+                )
+            })
+    }
+
+    /// A fresh salt and check value for `passphrase`, hex encoded, and the key they derive
+    pub fn new_check(&self, passphrase: &str) -> Result<(String, String, [u8; 32]), String> {
+        let salt: [u8; 16] = rand::random();
+        let key = self.derive_key(passphrase, &salt);
+        let check = self.seal(&key, self.check_plaintext)?;
+        Ok((hex::encode(salt), hex::encode(check), key))
+    }
+
+    /// The key `passphrase` derives with `salt`, if it opens `check`
+    pub fn check_key(&self, passphrase: &str, salt: &[u8], check: &[u8]) -> Option<[u8; 32]> {
+        let key = self.derive_key(passphrase, salt);
+        match self.open(&key, check) {
+            Ok(plaintext) if plaintext == self.check_plaintext => Some(key),
+            _ => None,
+        }
+    }
+}
+
+pub fn seal(key: &[u8; 32], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    SYNC_SEALING.seal(key, plaintext)
+}
+
+pub fn open(key: &[u8; 32], sealed: &[u8]) -> Result<Vec<u8>, String> {
+    SYNC_SEALING.open(key, sealed)
+}
+
+/// New `keys.json` contents for `passphrase`, and the key
+pub fn new_keys(passphrase: &str) -> Result<(RemoteKeys, [u8; 32]), String> {
+    let (salt, check, key) = SYNC_SEALING.new_check(passphrase)?;
+    Ok((RemoteKeys { salt, check }, key))
+}
+
+/// The key `passphrase` derives with the remote salt, if it is the passphrase the remote
+/// was set up with
+pub fn unlock_keys(keys: &RemoteKeys, passphrase: &str) -> Result<[u8; 32], String> {
+    let salt = hex::decode(&keys.salt).map_err(|_| "Invalid salt in keys.json")?;
+    let check = hex::decode(&keys.check).map_err(|_| "Invalid check in keys.json")?;
+    SYNC_SEALING
+        .check_key(passphrase, &salt, &check)
+        .ok_or_else(|| "Wrong sync passphrase".to_string())
+}
+
+pub fn content_hash(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
@@ -0,0 +1,734 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use super::backend::{backend_for, backend_id, SyncBackend};
+use super::constants::{
+    LOCAL_SETTINGS_SECTIONS, MIN_SYNC_INTERVAL_MINUTES, REMOTE_ASSISTANTS_DIR, REMOTE_KEYS_OBJECT,
+    REMOTE_MANIFEST_OBJECT, REMOTE_OBJECT_EXTENSION, REMOTE_SETTINGS_OBJECT, REMOTE_THREADS_DIR,
+    SYNC_CHECK_INTERVAL, SYNC_CONFIG_FILE, SYNC_STATE_FILE, SYNC_STATUS_EVENT,
+};
+use super::crypto::{content_hash, new_keys, open, seal, unlock_keys};
+use super::merge::{merge_json, merge_threads};
+use super::models::{
+    RemoteKeys, RemoteManifest, SyncBackendConfig, SyncConfig, SyncReport, SyncState, SyncStatus,
+    SyncedDocument, ThreadDocument,
+};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::assistants::helpers::{get_assistant_path, get_assistants_dir};
+use crate::core::config_store::helpers::write_json;
+use crate::core::offline::helpers::check_url;
+use crate::core::offline::OfflineMode;
+use crate::core::settings::helpers::{current_settings, update_settings};
+use crate::core::settings::SettingsState;
+use crate::core::threads::branches::message_id;
+use crate::core::threads::helpers::{
+    get_lock_for_thread, read_messages_from_file, update_thread_metadata, write_messages_to_file,
+};
+use crate::core::threads::private::is_private_thread;
+use crate::core::threads::utils::{
+    ensure_thread_dir_exists, get_branches_path, get_data_dir, get_messages_path, get_thread_dir,
+    get_thread_metadata_path,
+};
+
+// ── Config and state ──────────────────────────────────────────────────────
+
+pub fn get_config_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(SYNC_CONFIG_FILE)
+}
+
+pub fn get_state_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(SYNC_STATE_FILE)
+}
+
+fn read_json_file<T: DeserializeOwned + Default>(path: &Path) -> T {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+pub fn read_config(data_folder: &Path) -> SyncConfig {
+    read_json_file(&get_config_path(data_folder))
+}
+
+pub fn write_config(data_folder: &Path, config: &SyncConfig) -> Result<(), String> {
+    write_json(&get_config_path(data_folder), config)
+}
+
+pub fn read_state(data_folder: &Path) -> SyncState {
+    read_json_file(&get_state_path(data_folder))
+}
+
+pub fn write_state(data_folder: &Path, state: &SyncState) -> Result<(), String> {
+    write_json(&get_state_path(data_folder), state)
+}
+
+fn is_http_url(url: &str) -> bool {
+    url::Url::parse(url).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+}
+
+/// Check a config from the UI, and end a non-empty S3 prefix with `/`
+pub fn normalize_config(mut config: SyncConfig) -> Result<SyncConfig, String> {
+    config.interval_minutes = config.interval_minutes.max(MIN_SYNC_INTERVAL_MINUTES);
+    match &mut config.backend {
+        Some(SyncBackendConfig::S3 {
+            endpoint,
+            bucket,
+            region,
+            access_key_id,
+            secret_access_key,
+            prefix,
+        }) => {
+            if !is_http_url(endpoint) {
+                return Err(format!("Invalid S3 endpoint: {endpoint}"));
+            }
+            if [&*bucket, &*region, &*access_key_id, &*secret_access_key]
+                .iter()
+                .any(|value| value.trim().is_empty())
+            {
+                return Err("S3 needs a bucket, a region and credentials".to_string());
+            }
+            let valid_prefix = prefix
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'));
+            if !valid_prefix || prefix.starts_with('/') || prefix.contains("..") {
+                return Err(format!("Invalid S3 prefix: {prefix}"));
+            }
+            if !prefix.is_empty() && !prefix.ends_with('/') {
+                prefix.push('/');
+            }
+        }
+        Some(SyncBackendConfig::WebDav { url, .. }) if !is_http_url(url) => {
+            return Err(format!("Invalid WebDAV URL: {url}"));
+        }
+        None if config.enabled => return Err("Choose where to sync to".to_string()),
+        Some(SyncBackendConfig::WebDav { .. }) | None => {}
+    }
+    Ok(config)
+}
+
+/// The encryption key of the configured storage, once its passphrase was entered here
+fn storage_key(config: &SyncBackendConfig, state: &SyncState) -> Option<[u8; 32]> {
+    if state.backend_id != backend_id(config) {
+        return None;
+    }
+    hex::decode(state.key.as_ref()?).ok()?.try_into().ok()
+}
+
+/// Derive the key of `config`'s storage from `passphrase`. Storage another device set up
+/// must be unlocked with the same passphrase; new storage is set up with it. Moving to
+/// other storage starts over, as if nothing had been synced yet.
+pub async fn unlock_storage<R: Runtime>(
+    app: &AppHandle<R>,
+    config: &SyncBackendConfig,
+    passphrase: &str,
+) -> Result<(), String> {
+    if passphrase.chars().count() < 8 {
+        return Err("The sync passphrase needs at least 8 characters".to_string());
+    }
+    let backend = backend_for(config)?;
+    check_url(app, &backend.base_url())?;
+    let key = match backend.get(REMOTE_KEYS_OBJECT).await? {
+        Some(data) => {
+            let keys: RemoteKeys = serde_json::from_slice(&data)
+                .map_err(|e| format!("Invalid {REMOTE_KEYS_OBJECT} on the sync storage: {e}"))?;
+            unlock_keys(&keys, passphrase)?
+        }
+        None => {
+            let (keys, key) = new_keys(passphrase)?;
+            let data = serde_json::to_vec_pretty(&keys).map_err(|e| e.to_string())?;
+            backend.put(REMOTE_KEYS_OBJECT, data).await?;
+            key
+        }
+    };
+
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let mut state = read_state(&data_folder);
+    let id = backend_id(config);
+    if state.backend_id != id {
+        state = SyncState {
+            backend_id: id,
+            ..Default::default()
+        };
+    }
+    state.key = Some(hex::encode(key));
+    write_state(&data_folder, &state)
+}
+
+// ── Status ────────────────────────────────────────────────────────────────
+
+#[derive(Default)]
+struct SyncRuntime {
+    syncing: bool,
+    last_report: Option<SyncReport>,
+}
+
+static RUNTIME: OnceLock<Mutex<SyncRuntime>> = OnceLock::new();
+
+fn runtime() -> std::sync::MutexGuard<'static, SyncRuntime> {
+    RUNTIME
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+}
+
+pub fn sync_status(data_folder: &Path) -> SyncStatus {
+    let config = read_config(data_folder);
+    let state = read_state(data_folder);
+    let runtime = runtime();
+    SyncStatus {
+        enabled: config.enabled,
+        configured: config
+            .backend
+            .as_ref()
+            .is_some_and(|backend| storage_key(backend, &state).is_some()),
+        syncing: runtime.syncing,
+        last_synced_at: state.last_synced_at,
+        last_error: state.last_error,
+        last_report: runtime.last_report.clone(),
+    }
+}
+
+pub fn emit_sync_status<R: Runtime>(app: &AppHandle<R>) {
+    let status = sync_status(&get_jan_data_folder_path(app.clone()));
+    if let Err(e) = app.emit(SYNC_STATUS_EVENT, &status) {
+        log::warn!("Failed to emit {SYNC_STATUS_EVENT}: {e}");
+    }
+}
+
+// ── Remote objects ────────────────────────────────────────────────────────
+
+/// The sync storage, reading and writing encrypted JSON
+pub struct RemoteStore {
+    pub backend: Box<dyn SyncBackend>,
+    pub key: [u8; 32],
+}
+
+impl RemoteStore {
+    /// The object at `key` and the hash of its contents
+    pub async fn get_json<T: DeserializeOwned>(
+        &self,
+        key: &str,
+    ) -> Result<Option<(T, String)>, String> {
+        let Some(sealed) = self.backend.get(key).await? else {
+            return Ok(None);
+        };
+        let data = open(&self.key, &sealed)?;
+        let value = serde_json::from_slice(&data).map_err(|e| format!("Invalid {key}: {e}"))?;
+        Ok(Some((value, content_hash(&data))))
+    }
+
+    /// Store `value` at `key`; returns the hash of its contents
+    pub async fn put_json<T: Serialize>(&self, key: &str, value: &T) -> Result<String, String> {
+        let data = serde_json::to_vec(value).map_err(|e| e.to_string())?;
+        let hash = content_hash(&data);
+        self.backend.put(key, seal(&self.key, &data)?).await?;
+        Ok(hash)
+    }
+}
+
+/// What to do with a document, from the hashes of its local and remote copies and what
+/// was synced last
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncAction {
+    Nothing,
+    Push,
+    Pull,
+    /// Both sides changed, or both have a copy that was never synced
+    Merge,
+    DeleteLocal,
+    DeleteRemote,
+    /// Gone on both sides
+    Forget,
+}
+
+pub fn plan_sync(
+    local_hash: Option<&str>,
+    remote_hash: Option<&str>,
+    base: Option<&SyncedDocument>,
+) -> SyncAction {
+    match (local_hash, remote_hash, base) {
+        (None, None, _) => SyncAction::Forget,
+        (Some(_), None, None) => SyncAction::Push,
+        (None, Some(_), None) => SyncAction::Pull,
+        (Some(_), Some(_), None) => SyncAction::Merge,
+        // Deleted on the other side; edits made here since keep the document
+        (Some(local), None, Some(base)) if local == base.local_hash => SyncAction::DeleteLocal,
+        (Some(_), None, Some(_)) => SyncAction::Push,
+        (None, Some(remote), Some(base)) if remote == base.remote_hash => SyncAction::DeleteRemote,
+        (None, Some(_), Some(_)) => SyncAction::Pull,
+        (Some(local), Some(remote), Some(base)) => {
+            match (local != base.local_hash, remote != base.remote_hash) {
+                (false, false) => SyncAction::Nothing,
+                (true, false) => SyncAction::Push,
+                (false, true) => SyncAction::Pull,
+                (true, true) => SyncAction::Merge,
+            }
+        }
+    }
+}
+
+/// Ids of synced documents become file and object names
+pub fn is_valid_document_id(id: &str) -> bool {
+    !id.is_empty()
+        && id != "."
+        && id != ".."
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+// ── Local documents ───────────────────────────────────────────────────────
+
+/// A kind of document kept in the data folder and synced one object per document
+pub trait LocalDocuments {
+    type Doc: Serialize + DeserializeOwned + PartialEq + Send + Sync;
+
+    /// Remote folder of the documents
+    const REMOTE_DIR: &'static str;
+    /// Whether reads and writes take the per-thread lock
+    const THREAD_LOCKED: bool;
+
+    fn ids(&self) -> Vec<String>;
+    fn read(&self, id: &str) -> Option<Self::Doc>;
+    fn write(&self, id: &str, doc: &Self::Doc) -> Result<(), String>;
+    fn delete(&self, id: &str) -> Result<(), String>;
+    /// Merge of a document changed on both sides
+    fn merge(&self, local: &Self::Doc, remote: &Self::Doc, base: &SyncedDocument) -> Self::Doc;
+    /// Message ids recorded to merge the next time
+    fn message_ids(&self, _doc: &Self::Doc) -> Vec<String> {
+        Vec::new()
+    }
+    /// Documents left alone on both sides, e.g. private threads
+    fn is_excluded(&self, _id: &str) -> bool {
+        false
+    }
+}
+
+fn subfolders_with(dir: &Path, file_name: &str) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|entry| entry.path().join(file_name).is_file())
+        .filter_map(|entry| entry.file_name().to_str().map(str::to_string))
+        .collect()
+}
+
+/// Threads with their messages and branch selection, and whether private threads are synced
+pub struct Threads<'a>(pub &'a Path, pub bool);
+
+impl LocalDocuments for Threads<'_> {
+    type Doc = ThreadDocument;
+
+    const REMOTE_DIR: &'static str = REMOTE_THREADS_DIR;
+    const THREAD_LOCKED: bool = true;
+
+    fn ids(&self) -> Vec<String> {
+        let metadata = get_thread_metadata_path(self.0, "_");
+        let file_name = metadata
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        subfolders_with(&get_data_dir(self.0), file_name)
+    }
+
+    fn read(&self, id: &str) -> Option<ThreadDocument> {
+        let thread = fs::read_to_string(get_thread_metadata_path(self.0, id)).ok()?;
+        let thread = serde_json::from_str(&thread).ok()?;
+        let messages = read_messages_from_file(self.0, id).ok()?;
+        let branches = fs::read_to_string(get_branches_path(self.0, id))
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok());
+        Some(ThreadDocument {
+            thread,
+            messages,
+            branches,
+        })
+    }
+
+    fn write(&self, id: &str, doc: &ThreadDocument) -> Result<(), String> {
+        ensure_thread_dir_exists(self.0, id)?;
+        write_messages_to_file(&doc.messages, &get_messages_path(self.0, id))?;
+        let branches_path = get_branches_path(self.0, id);
+        match &doc.branches {
+            Some(branches) => {
+                let data = serde_json::to_string_pretty(branches).map_err(|e| e.to_string())?;
+                fs::write(branches_path, data).map_err(|e| e.to_string())?;
+            }
+            None if branches_path.exists() => {
+                fs::remove_file(branches_path).map_err(|e| e.to_string())?;
+            }
+            None => {}
+        }
+        // Last, since a thread without metadata isn't listed
+        update_thread_metadata(self.0, id, &doc.thread)
+    }
+
+    fn delete(&self, id: &str) -> Result<(), String> {
+        fs::remove_dir_all(get_thread_dir(self.0, id)).map_err(|e| e.to_string())
+    }
+
+    fn merge(
+        &self,
+        local: &ThreadDocument,
+        remote: &ThreadDocument,
+        base: &SyncedDocument,
+    ) -> ThreadDocument {
+        merge_threads(local, remote, &base.message_ids)
+    }
+
+    fn message_ids(&self, doc: &ThreadDocument) -> Vec<String> {
+        doc.messages
+            .iter()
+            .filter_map(message_id)
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn is_excluded(&self, id: &str) -> bool {
+        !self.1 && is_private_thread(self.0, id)
+    }
+}
+
+/// Assistant definitions, synced as they are stored
+pub struct Assistants<'a>(pub &'a Path);
+
+impl LocalDocuments for Assistants<'_> {
+    type Doc = Value;
+
+    const REMOTE_DIR: &'static str = REMOTE_ASSISTANTS_DIR;
+    const THREAD_LOCKED: bool = false;
+
+    fn ids(&self) -> Vec<String> {
+        let path = get_assistant_path(self.0, "_");
+        let file_name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default();
+        subfolders_with(&get_assistants_dir(self.0), file_name)
+    }
+
+    fn read(&self, id: &str) -> Option<Value> {
+        let data = fs::read_to_string(get_assistant_path(self.0, id)).ok()?;
+        serde_json::from_str(&data).ok()
+    }
+
+    fn write(&self, id: &str, doc: &Value) -> Result<(), String> {
+        let path = get_assistant_path(self.0, id);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let data = serde_json::to_string_pretty(doc).map_err(|e| e.to_string())?;
+        fs::write(path, data).map_err(|e| e.to_string())
+    }
+
+    fn delete(&self, id: &str) -> Result<(), String> {
+        fs::remove_dir_all(get_assistants_dir(self.0).join(id)).map_err(|e| e.to_string())
+    }
+
+    /// Assistants are edited as a whole, so the copy on this device wins
+    fn merge(&self, local: &Value, _remote: &Value, _base: &SyncedDocument) -> Value {
+        local.clone()
+    }
+}
+
+fn document_hash<T: Serialize>(doc: &T) -> String {
+    content_hash(&serde_json::to_vec(doc).unwrap_or_default())
+}
+
+fn object_key(dir: &str, id: &str) -> String {
+    format!("{dir}/{id}.{REMOTE_OBJECT_EXTENSION}")
+}
+
+/// Sync every document of one kind, updating the manifest entries and the base in place
+pub async fn sync_documents<D: LocalDocuments>(
+    docs: &D,
+    store: &RemoteStore,
+    remote: &mut BTreeMap<String, String>,
+    synced: &mut BTreeMap<String, SyncedDocument>,
+    report: &mut SyncReport,
+) -> Result<(), String> {
+    let ids: BTreeSet<String> = docs
+        .ids()
+        .into_iter()
+        .chain(remote.keys().cloned())
+        .chain(synced.keys().cloned())
+        .filter(|id| is_valid_document_id(id))
+        .collect();
+    for id in ids {
+        let lock = if D::THREAD_LOCKED {
+            Some(get_lock_for_thread(&id).await)
+        } else {
+            None
+        };
+        let _guard = match &lock {
+            Some(lock) => Some(lock.lock().await),
+            None => None,
+        };
+        // Neither pushed, pulled nor deleted, so other devices keep their copy
+        if docs.is_excluded(&id) {
+            continue;
+        }
+        let key = object_key(D::REMOTE_DIR, &id);
+        let local = docs.read(&id);
+        let local_hash = local.as_ref().map(document_hash);
+        let remote_hash = remote.get(&id).cloned();
+        let base = synced.get(&id).cloned();
+
+        let action = plan_sync(local_hash.as_deref(), remote_hash.as_deref(), base.as_ref());
+        // The copy the remote has after this sync
+        let mut pushed: Option<String> = None;
+        match action {
+            SyncAction::Nothing => continue,
+            SyncAction::Forget => {
+                synced.remove(&id);
+                continue;
+            }
+            SyncAction::DeleteLocal => {
+                docs.delete(&id)?;
+                synced.remove(&id);
+                report.deleted += 1;
+                continue;
+            }
+            SyncAction::DeleteRemote => {
+                store.backend.delete(&key).await?;
+                remote.remove(&id);
+                synced.remove(&id);
+                report.deleted += 1;
+                continue;
+            }
+            SyncAction::Push => {
+                if let Some(local) = &local {
+                    pushed = Some(store.put_json(&key, local).await?);
+                    report.pushed += 1;
+                }
+            }
+            SyncAction::Pull | SyncAction::Merge => {
+                let Some((remote_doc, hash)) = store.get_json::<D::Doc>(&key).await? else {
+                    // Listed but gone, e.g. deleted by a sync that didn't finish
+                    remote.remove(&id);
+                    continue;
+                };
+                let merged = match (&local, action) {
+                    (Some(local), SyncAction::Merge) => {
+                        report.conflicts += 1;
+                        docs.merge(local, &remote_doc, &base.clone().unwrap_or_default())
+                    }
+                    _ => remote_doc,
+                };
+                if local.as_ref() != Some(&merged) {
+                    docs.write(&id, &merged)?;
+                    report.pulled += 1;
+                }
+                pushed = Some(if document_hash(&merged) == hash {
+                    hash
+                } else {
+                    report.pushed += 1;
+                    store.put_json(&key, &merged).await?
+                });
+            }
+        }
+
+        // Hash what is on disk now, which is what the next sync compares against
+        let Some(remote_hash) = pushed else {
+            continue;
+        };
+        let Some(stored) = docs.read(&id) else {
+            continue;
+        };
+        remote.insert(id.clone(), remote_hash.clone());
+        synced.insert(
+            id,
+            SyncedDocument {
+                local_hash: document_hash(&stored),
+                remote_hash,
+                message_ids: docs.message_ids(&stored),
+            },
+        );
+    }
+    Ok(())
+}
+
+// ── Settings ──────────────────────────────────────────────────────────────
+
+/// The settings shared between devices, without the sections of this machine
+pub fn synced_settings(settings: &Value) -> Value {
+    let mut settings = settings.clone();
+    if let Some(settings) = settings.as_object_mut() {
+        for section in LOCAL_SETTINGS_SECTIONS {
+            settings.remove(*section);
+        }
+    }
+    settings
+}
+
+async fn sync_settings<R: Runtime>(
+    app: &AppHandle<R>,
+    store: &RemoteStore,
+    manifest: &mut RemoteManifest,
+    state: &mut SyncState,
+    report: &mut SyncReport,
+) -> Result<(), String> {
+    let current = serde_json::to_value(current_settings(app).await).map_err(|e| e.to_string())?;
+    let local = synced_settings(&current);
+    let remote_changed =
+        manifest.settings.is_some() && manifest.settings != state.settings_remote_hash;
+    let remote = if remote_changed {
+        store
+            .get_json::<Value>(REMOTE_SETTINGS_OBJECT)
+            .await?
+            .map(|(settings, _)| settings)
+    } else {
+        manifest
+            .settings
+            .as_ref()
+            .and_then(|_| state.settings.clone())
+    };
+
+    let merged = match &remote {
+        Some(remote) => merge_json(state.settings.as_ref(), &local, remote),
+        None => local.clone(),
+    };
+    if remote.is_some() && remote_changed && state.settings.as_ref() != Some(&local) {
+        report.conflicts += 1;
+    }
+    if merged != local {
+        update_settings(app, &app.state::<SettingsState>(), &merged).await?;
+        report.pulled += 1;
+    }
+    if remote.as_ref() != Some(&merged) {
+        let hash = store.put_json(REMOTE_SETTINGS_OBJECT, &merged).await?;
+        manifest.settings = Some(hash);
+        report.pushed += 1;
+    }
+    state.settings_remote_hash = manifest.settings.clone();
+    state.settings = Some(merged);
+    Ok(())
+}
+
+// ── Sync ──────────────────────────────────────────────────────────────────
+
+static SYNC_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+async fn sync_once<R: Runtime>(
+    app: &AppHandle<R>,
+    data_folder: &Path,
+) -> Result<SyncReport, String> {
+    let config = read_config(data_folder);
+    let backend_config = config.backend.ok_or("Choose where to sync to first")?;
+    let mut state = read_state(data_folder);
+    let key = storage_key(&backend_config, &state)
+        .ok_or("Enter the sync passphrase for this storage first")?;
+    let backend = backend_for(&backend_config)?;
+    check_url(app, &backend.base_url())?;
+    let store = RemoteStore { backend, key };
+
+    let original = store
+        .get_json::<RemoteManifest>(REMOTE_MANIFEST_OBJECT)
+        .await?
+        .map(|(manifest, _)| manifest)
+        .unwrap_or_default();
+    let mut manifest = original.clone();
+    let mut report = SyncReport::default();
+    sync_documents(
+        &Threads(data_folder, config.include_private_threads),
+        &store,
+        &mut manifest.threads,
+        &mut state.threads,
+        &mut report,
+    )
+    .await?;
+    sync_documents(
+        &Assistants(data_folder),
+        &store,
+        &mut manifest.assistants,
+        &mut state.assistants,
+        &mut report,
+    )
+    .await?;
+    sync_settings(app, &store, &mut manifest, &mut state, &mut report).await?;
+
+    if manifest != original {
+        store.put_json(REMOTE_MANIFEST_OBJECT, &manifest).await?;
+    }
+    state.last_synced_at = Some(chrono::Utc::now().to_rfc3339());
+    state.last_error = None;
+    write_state(data_folder, &state)?;
+    Ok(report)
+}
+
+/// Sync threads, assistants and settings with the configured storage. Only one sync runs
+/// at a time; progress and the outcome are broadcast as `sync-status-changed`.
+pub async fn run_sync<R: Runtime>(app: &AppHandle<R>) -> Result<SyncReport, String> {
+    let _guard = SYNC_LOCK
+        .try_lock()
+        .map_err(|_| "A sync is already running".to_string())?;
+    let data_folder = get_jan_data_folder_path(app.clone());
+    runtime().syncing = true;
+    emit_sync_status(app);
+
+    let result = sync_once(app, &data_folder).await;
+    match &result {
+        Ok(report) => {
+            log::info!(
+                "Synced: {} pushed, {} pulled, {} deleted, {} merged",
+                report.pushed,
+                report.pulled,
+                report.deleted,
+                report.conflicts
+            );
+            runtime().last_report = Some(report.clone());
+        }
+        Err(e) => {
+            log::warn!("Sync failed: {e}");
+            let mut state = read_state(&data_folder);
+            state.last_error = Some(e.clone());
+            if let Err(e) = write_state(&data_folder, &state) {
+                log::warn!("Failed to store the sync state: {e}");
+            }
+        }
+    }
+    runtime().syncing = false;
+    emit_sync_status(app);
+    result
+}
+
+/// Sync in the background every `interval_minutes` while sync is enabled and online
+pub fn start_sync_scheduler<R: Runtime>(app: &AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let data_folder = get_jan_data_folder_path(app.clone());
+        let mut last_run: Option<Instant> = None;
+        loop {
+            tokio::time::sleep(SYNC_CHECK_INTERVAL).await;
+            let config = read_config(&data_folder);
+            if !config.enabled || config.backend.is_none() {
+                continue;
+            }
+            if app
+                .try_state::<OfflineMode>()
+                .is_some_and(|mode| mode.is_enabled())
+            {
+                continue;
+            }
+            let interval =
+                Duration::from_secs(config.interval_minutes.max(MIN_SYNC_INTERVAL_MINUTES) * 60);
+            if last_run.is_some_and(|last| last.elapsed() < interval) {
+                continue;
+            }
+            last_run = Some(Instant::now());
+            // Failures are recorded in the sync status
+            let _ = run_sync(&app).await;
+        }
+    });
+}
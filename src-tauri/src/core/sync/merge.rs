@@ -0,0 +1,142 @@
+//! Three-way merges of documents changed on this device and on the remote since the last
+//! sync. Threads merge per message, the newer copy of a message winning; settings merge per
+//! key, this device winning when both changed the same value.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use serde_json::{Map, Value};
+
+use super::models::ThreadDocument;
+use crate::core::threads::branches::message_id;
+
+fn timestamp(value: &Value, key: &str) -> i64 {
+    value.get(key).and_then(Value::as_f64).unwrap_or(0.0) as i64
+}
+
+/// Version of a message for last-writer-wins: the newest of its timestamps
+pub fn message_version(message: &Value) -> i64 {
+    ["updated_at", "completed_at", "created_at"]
+        .iter()
+        .map(|key| timestamp(message, key))
+        .max()
+        .unwrap_or(0)
+}
+
+/// The newer of two copies. Equal versions are decided by content, so every device picks
+/// the same copy.
+fn newer<'a>(a: &'a Value, b: &'a Value, version: impl Fn(&Value) -> i64) -> &'a Value {
+    let order = version(a)
+        .cmp(&version(b))
+        .then_with(|| a.to_string().cmp(&b.to_string()));
+    if order.is_ge() {
+        a
+    } else {
+        b
+    }
+}
+
+/// Merge a thread changed on both sides. Messages are matched by id and the newer copy wins;
+/// a message only one side has was deleted on the other side if it was synced before
+/// (`base_ids`), and is new otherwise.
+pub fn merge_threads(
+    local: &ThreadDocument,
+    remote: &ThreadDocument,
+    base_ids: &[String],
+) -> ThreadDocument {
+    let base: HashSet<&str> = base_ids.iter().map(String::as_str).collect();
+    let remote_messages: HashMap<&str, &Value> = remote
+        .messages
+        .iter()
+        .filter_map(|message| Some((message_id(message)?, message)))
+        .collect();
+    let local_ids: HashSet<&str> = local.messages.iter().filter_map(message_id).collect();
+
+    let mut messages = Vec::with_capacity(local.messages.len().max(remote.messages.len()));
+    for message in &local.messages {
+        let Some(id) = message_id(message) else {
+            // Can't be matched, so it stays as it is
+            messages.push(message.clone());
+            continue;
+        };
+        match remote_messages.get(id) {
+            Some(remote) => messages.push(newer(message, remote, message_version).clone()),
+            None if base.contains(id) => {}
+            None => messages.push(message.clone()),
+        }
+    }
+    messages.extend(
+        remote
+            .messages
+            .iter()
+            .filter(|message| {
+                message_id(message).is_some_and(|id| !local_ids.contains(id) && !base.contains(id))
+            })
+            .cloned(),
+    );
+    // Interleave the messages of both sides, unless some lack the time to order them by
+    if messages
+        .iter()
+        .all(|message| message.get("created_at").is_some())
+    {
+        messages.sort_by_key(|message| timestamp(message, "created_at"));
+    }
+
+    let thread = newer(&local.thread, &remote.thread, |thread| {
+        timestamp(thread, "updated")
+    })
+    .clone();
+    // Keep the branch shown here while it still exists
+    let ids: HashSet<&str> = messages.iter().filter_map(message_id).collect();
+    let leaf_exists = |branches: &Option<Value>| {
+        branches
+            .as_ref()
+            .and_then(|branches| branches.get("active_leaf_id"))
+            .and_then(Value::as_str)
+            .is_some_and(|leaf| ids.contains(leaf))
+    };
+    let branches = if leaf_exists(&local.branches) || !leaf_exists(&remote.branches) {
+        local.branches.clone()
+    } else {
+        remote.branches.clone()
+    };
+
+    ThreadDocument {
+        thread,
+        messages,
+        branches,
+    }
+}
+
+/// Merge JSON changed on both sides since `base`. Objects merge key by key: a value changed
+/// on one side only takes that side's value, a key removed on one side and unchanged on the
+/// other is removed. Values changed on both sides keep the local one.
+pub fn merge_json(base: Option<&Value>, local: &Value, remote: &Value) -> Value {
+    if local == remote {
+        return local.clone();
+    }
+    let (Value::Object(local_map), Value::Object(remote_map)) = (local, remote) else {
+        return if base == Some(local) {
+            remote.clone()
+        } else {
+            local.clone()
+        };
+    };
+    let base_map = base.and_then(Value::as_object);
+    let keys: BTreeSet<&String> = local_map.keys().chain(remote_map.keys()).collect();
+    let mut merged = Map::new();
+    for key in keys {
+        let base_value = base_map.and_then(|base| base.get(key));
+        let value = match (local_map.get(key), remote_map.get(key)) {
+            (Some(local), Some(remote)) => Some(merge_json(base_value, local, remote)),
+            // Removed on the other side, kept only when changed here since
+            (Some(value), None) | (None, Some(value)) => {
+                (base_value != Some(value)).then(|| value.clone())
+            }
+            (None, None) => None,
+        };
+        if let Some(value) = value {
+            merged.insert(key.clone(), value);
+        }
+    }
+    Value::Object(merged)
+}
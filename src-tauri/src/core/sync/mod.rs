@@ -0,0 +1,31 @@
+/*!
+   Sync
+
+   Optional sync of threads, assistants and core settings through storage the user provides:
+   an S3 bucket (or any S3-compatible store) or a WebDAV folder. Everything is encrypted on
+   the device with a key derived from a sync passphrase; the storage only sees a salt and
+   opaque objects, one per thread and assistant plus the settings and a manifest of content
+   hashes.
+
+   Each sync compares the local and the remote copy of every document with the hashes
+   recorded at the last sync (`sync_state.json`), so only documents changed on one side are
+   transferred. Documents changed on both sides are merged: messages of a thread by id, the
+   newer copy winning, and settings key by key. The server and LAN sections of the settings
+   belong to one machine and are not synced. Syncs run on demand and every
+   `interval_minutes` in the background, and report through `sync-status-changed`.
+
+   Threads are synced from the file storage used on desktop. Private threads are left alone on
+   both sides unless `include_private_threads` is set, and then travel as the ciphertext they
+   are stored as.
+*/
+
+pub mod backend;
+pub mod commands;
+pub mod constants;
+pub mod crypto;
+pub mod helpers;
+pub mod merge;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::constants::DEFAULT_SYNC_INTERVAL_MINUTES;
+
+/// Storage the sync engine pushes to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SyncBackendConfig {
+    /// S3 or any S3-compatible store, addressed path-style as `<endpoint>/<bucket>/<key>`
+    #[serde(rename_all = "camelCase")]
+    S3 {
+        endpoint: String,
+        bucket: String,
+        region: String,
+        access_key_id: String,
+        secret_access_key: String,
+        /// Key prefix such as `jan/`
+        #[serde(default)]
+        prefix: String,
+    },
+    /// A WebDAV folder, e.g. on Nextcloud
+    #[serde(rename_all = "camelCase")]
+    WebDav {
+        url: String,
+        #[serde(default)]
+        username: String,
+        #[serde(default)]
+        password: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SyncConfig {
+    pub enabled: bool,
+    pub backend: Option<SyncBackendConfig>,
+    /// Minutes between background syncs
+    pub interval_minutes: u64,
+    /// Private threads are only synced when set, and then as ciphertext
+    pub include_private_threads: bool,
+}
+
+impl Default for SyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            backend: None,
+            interval_minutes: DEFAULT_SYNC_INTERVAL_MINUTES,
+            include_private_threads: false,
+        }
+    }
+}
+
+/// Unencrypted `keys.json`: the salt the key is derived with, and a sealed known text
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteKeys {
+    pub salt: String,
+    pub check: String,
+}
+
+/// Content hashes of the synced objects on the remote, by id
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RemoteManifest {
+    pub threads: BTreeMap<String, String>,
+    pub assistants: BTreeMap<String, String>,
+    pub settings: Option<String>,
+}
+
+/// One synced document as of the last sync: the hash of the local files and of the remote
+/// object, which tell which side changed since
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SyncedDocument {
+    pub local_hash: String,
+    pub remote_hash: String,
+    /// Messages of a thread, to tell deleted messages from new ones
+    pub message_ids: Vec<String>,
+}
+
+/// Local record of the last sync
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SyncState {
+    /// Hex encoded encryption key, derived from the passphrase
+    pub key: Option<String>,
+    /// Backend the state belongs to; a different one starts over
+    pub backend_id: String,
+    pub threads: BTreeMap<String, SyncedDocument>,
+    pub assistants: BTreeMap<String, SyncedDocument>,
+    /// Synced settings as of the last sync
+    pub settings: Option<Value>,
+    pub settings_remote_hash: Option<String>,
+    pub last_synced_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+/// A thread as synced: its metadata, all its messages and its branch selection
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThreadDocument {
+    pub thread: Value,
+    pub messages: Vec<Value>,
+    #[serde(default)]
+    pub branches: Option<Value>,
+}
+
+/// What one sync did
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    pub pushed: usize,
+    pub pulled: usize,
+    pub deleted: usize,
+    /// Documents changed on both sides and merged
+    pub conflicts: usize,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+    pub enabled: bool,
+    /// Whether a backend and a passphrase are set up
+    pub configured: bool,
+    pub syncing: bool,
+    pub last_synced_at: Option<String>,
+    pub last_error: Option<String>,
+    pub last_report: Option<SyncReport>,
+}
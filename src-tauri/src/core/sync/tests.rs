@@ -0,0 +1,184 @@
+use serde_json::json;
+
+use super::backend::{s3_authorization, signing_key, S3Request};
+use super::crypto::{new_keys, open, seal, unlock_keys};
+use super::helpers::{is_valid_document_id, normalize_config, plan_sync, SyncAction};
+use super::merge::{merge_json, merge_threads};
+use super::models::{SyncBackendConfig, SyncConfig, SyncedDocument, ThreadDocument};
+
+#[test]
+fn test_seal_and_open() {
+    let key = [7u8; 32];
+    let sealed = seal(&key, b"hello").unwrap();
+    assert_ne!(&sealed[8..], b"hello");
+    assert_eq!(open(&key, &sealed).unwrap(), b"hello");
+    // A fresh nonce per object
+    assert_ne!(seal(&key, b"hello").unwrap(), sealed);
+
+    assert!(open(&[8u8; 32], &sealed).is_err());
+    let mut tampered = sealed.clone();
+    *tampered.last_mut().unwrap() ^= 1;
+    assert!(open(&key, &tampered).is_err());
+    assert!(open(&key, b"plain text").is_err());
+}
+
+#[test]
+fn test_unlock_keys() {
+    let (keys, key) = new_keys("correct horse").unwrap();
+    assert_eq!(unlock_keys(&keys, "correct horse").unwrap(), key);
+    assert_eq!(
+        unlock_keys(&keys, "wrong horse").unwrap_err(),
+        "Wrong sync passphrase"
+    );
+}
+
+#[test]
+fn test_s3_signing() {
+    // Example from the AWS Signature Version 4 documentation
+    let key = signing_key(
+        "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+        "20120215",
+        "us-east-1",
+        "iam",
+    );
+    assert_eq!(
+        hex::encode(key),
+        "f4780e2d9f65fa895f9c67b32ce1baf0b0d8a43505a000a1a9e090d414db404d"
+    );
+
+    let request = S3Request {
+        method: "GET",
+        host: "localhost:9000",
+        path: "/jan/keys.json",
+        payload_hash: "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        amz_date: "20240101T000000Z",
+    };
+    let authorization = s3_authorization(&request, "us-east-1", "AKID", "secret");
+    assert!(authorization.starts_with(
+        "AWS4-HMAC-SHA256 Credential=AKID/20240101/us-east-1/s3/aws4_request, \
+         SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature="
+    ));
+    assert_eq!(
+        authorization,
+        s3_authorization(&request, "us-east-1", "AKID", "secret")
+    );
+}
+
+#[test]
+fn test_normalize_config() {
+    let s3 = |endpoint: &str, prefix: &str| SyncConfig {
+        enabled: true,
+        backend: Some(SyncBackendConfig::S3 {
+            endpoint: endpoint.to_string(),
+            bucket: "jan".to_string(),
+            region: "us-east-1".to_string(),
+            access_key_id: "AKID".to_string(),
+            secret_access_key: "secret".to_string(),
+            prefix: prefix.to_string(),
+        }),
+        interval_minutes: 0,
+        include_private_threads: false,
+    };
+    let config = normalize_config(s3("https://s3.example.com", "laptop")).unwrap();
+    assert_eq!(config.interval_minutes, 1);
+    assert!(matches!(
+        config.backend,
+        Some(SyncBackendConfig::S3 { ref prefix, .. }) if prefix == "laptop/"
+    ));
+    assert!(normalize_config(s3("ftp://s3.example.com", "")).is_err());
+    assert!(normalize_config(s3("https://s3.example.com", "../up")).is_err());
+    assert!(normalize_config(SyncConfig {
+        enabled: true,
+        ..Default::default()
+    })
+    .is_err());
+    assert!(normalize_config(SyncConfig::default()).is_ok());
+}
+
+#[test]
+fn test_plan_sync() {
+    let base = SyncedDocument {
+        local_hash: "l".to_string(),
+        remote_hash: "r".to_string(),
+        message_ids: Vec::new(),
+    };
+    let base = Some(&base);
+    assert_eq!(plan_sync(Some("l"), Some("r"), base), SyncAction::Nothing);
+    assert_eq!(plan_sync(Some("l2"), Some("r"), base), SyncAction::Push);
+    assert_eq!(plan_sync(Some("l"), Some("r2"), base), SyncAction::Pull);
+    assert_eq!(plan_sync(Some("l2"), Some("r2"), base), SyncAction::Merge);
+    assert_eq!(plan_sync(Some("l"), None, base), SyncAction::DeleteLocal);
+    assert_eq!(plan_sync(Some("l2"), None, base), SyncAction::Push);
+    assert_eq!(plan_sync(None, Some("r"), base), SyncAction::DeleteRemote);
+    assert_eq!(plan_sync(None, Some("r2"), base), SyncAction::Pull);
+    assert_eq!(plan_sync(None, None, base), SyncAction::Forget);
+    assert_eq!(plan_sync(Some("l"), None, None), SyncAction::Push);
+    assert_eq!(plan_sync(None, Some("r"), None), SyncAction::Pull);
+    assert_eq!(plan_sync(Some("l"), Some("r"), None), SyncAction::Merge);
+
+    assert!(is_valid_document_id("jan_1700000000"));
+    assert!(!is_valid_document_id(".."));
+    assert!(!is_valid_document_id("a/b"));
+}
+
+fn message(id: &str, created_at: i64, text: &str) -> serde_json::Value {
+    json!({"id": id, "created_at": created_at, "content": text})
+}
+
+fn thread(updated: i64, messages: Vec<serde_json::Value>) -> ThreadDocument {
+    ThreadDocument {
+        thread: json!({"id": "t1", "updated": updated}),
+        messages,
+        branches: None,
+    }
+}
+
+#[test]
+fn test_merge_threads() {
+    let base_ids = vec!["a".to_string(), "b".to_string()];
+    let mut edited = message("b", 2, "edited");
+    edited["updated_at"] = json!(10);
+    let local = thread(
+        5,
+        vec![message("a", 1, "hi"), edited, message("c", 3, "local")],
+    );
+    // Removed "a" and added "d" on the other device
+    let remote = thread(
+        6,
+        vec![message("b", 2, "original"), message("d", 4, "remote")],
+    );
+
+    let merged = merge_threads(&local, &remote, &base_ids);
+    let ids: Vec<_> = merged
+        .messages
+        .iter()
+        .map(|m| m["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, vec!["b", "c", "d"]);
+    assert_eq!(merged.messages[0]["content"], "edited");
+    assert_eq!(merged.thread["updated"], 6);
+
+    // Every device merges to the same thread
+    let reverse = merge_threads(&remote, &local, &base_ids);
+    assert_eq!(reverse.messages, merged.messages);
+}
+
+#[test]
+fn test_merge_json() {
+    let base = json!({"theme": "dark", "model": {"temp": 0.7, "top_p": 0.9}, "old": 1});
+    let local = json!({"theme": "light", "model": {"temp": 0.7, "top_p": 0.9}, "old": 1});
+    let remote = json!({"theme": "dark", "model": {"temp": 0.2, "top_p": 0.9}, "new": true});
+    assert_eq!(
+        merge_json(Some(&base), &local, &remote),
+        json!({"theme": "light", "model": {"temp": 0.2, "top_p": 0.9}, "new": true})
+    );
+
+    // Both changed the same value: this device wins
+    let remote = json!({"theme": "solarized", "model": {"temp": 0.7, "top_p": 0.9}, "old": 1});
+    assert_eq!(merge_json(Some(&base), &local, &remote)["theme"], "light");
+    // Nothing synced before: keys of both sides are kept
+    assert_eq!(
+        merge_json(None, &json!({"a": 1}), &json!({"b": 2})),
+        json!({"a": 1, "b": 2})
+    );
+}
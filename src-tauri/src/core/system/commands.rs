@@ -1,14 +1,21 @@
 use std::fs;
 use std::path::PathBuf;
-use tauri::{AppHandle, Manager, Runtime, State};
-use tauri_plugin_llamacpp::cleanup_llama_processes;
+use tauri::{AppHandle, Emitter, Manager, Runtime, State};
 
+use super::constants::PROVIDER_KEYS_CLEARED_EVENT;
+use super::helpers::{
+    cancel_agent_runs, model_dirs, reset_dir, shutdown_engines_for_reset, shutdown_mcp_for_reset,
+};
 use crate::core::app::commands::{
     default_data_folder_path, get_jan_data_folder_path, update_app_configuration,
 };
 use crate::core::app::models::AppConfiguration;
-use crate::core::mcp::helpers::{stop_mcp_servers_with_context, ShutdownContext};
+use crate::core::config_history::helpers::record_config_change;
+use crate::core::config_store::helpers::config_store;
+use crate::core::mcp::constants::DEFAULT_MCP_CONFIG;
 use crate::core::state::AppState;
+use crate::core::threads::helpers::should_use_sqlite;
+use crate::core::threads::utils::get_data_dir;
 
 /// Detect the user's default shell and return the appropriate env file path.
 /// Returns (shell_name, env_file_path).
@@ -74,29 +81,18 @@ pub fn factory_reset<R: Runtime>(app_handle: tauri::AppHandle<R>, state: State<'
     log::info!("Factory reset, removing data folder: {data_folder:?}");
 
     tauri::async_runtime::block_on(async {
-        let _ =
-            stop_mcp_servers_with_context(&app_handle, &state, ShutdownContext::FactoryReset).await;
-
-        {
-            let mut active_servers = state.mcp_active_servers.lock().await;
-            active_servers.clear();
-        }
-
-        use crate::core::mcp::lockfile::cleanup_own_locks;
-        if let Err(e) = cleanup_own_locks(&app_handle) {
-            log::warn!("Failed to cleanup lock files: {}", e);
-        }
-        let _ = cleanup_llama_processes(app_handle.clone()).await;
+        shutdown_mcp_for_reset(&app_handle, &state).await;
+        shutdown_engines_for_reset(&app_handle).await;
 
         if data_folder.exists() {
-            if let Err(e) = fs::remove_dir_all(&data_folder) {
+            if let Err(e) = tokio::fs::remove_dir_all(&data_folder).await {
                 log::error!("Failed to remove data folder: {e}");
                 return;
             }
         }
 
         // Recreate the data folder
-        let _ = fs::create_dir_all(&data_folder).map_err(|e| e.to_string());
+        let _ = tokio::fs::create_dir_all(&data_folder).await;
 
         // Reset the configuration
         let mut default_config = AppConfiguration::default();
@@ -107,6 +103,82 @@ pub fn factory_reset<R: Runtime>(app_handle: tauri::AppHandle<R>, state: State<'
     });
 }
 
+/// Stops all MCP servers and restores `mcp_config.json` to the default config.
+/// Other data is left untouched.
+#[tauri::command]
+pub async fn reset_mcp_config<R: Runtime>(
+    app_handle: AppHandle<R>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    log::info!("Resetting MCP configuration");
+    shutdown_mcp_for_reset(&app_handle, &state).await;
+
+    let data_folder = get_jan_data_folder_path(app_handle);
+    config_store()
+        .write_async(&data_folder.join("mcp_config.json"), DEFAULT_MCP_CONFIG)
+        .await
+        .map_err(|e| format!("Failed to write default MCP config: {e}"))?;
+    record_config_change(&data_folder, "Reset MCP config");
+    Ok(())
+}
+
+/// Cancels running agent loops and removes every thread and its messages.
+#[tauri::command]
+pub async fn clear_threads<R: Runtime>(app_handle: AppHandle<R>) -> Result<(), String> {
+    log::info!("Clearing all threads");
+    cancel_agent_runs(&app_handle).await;
+
+    if should_use_sqlite() {
+        #[cfg(any(target_os = "android", target_os = "ios"))]
+        {
+            use crate::core::threads::db;
+            for thread in db::db_list_threads(app_handle.clone()).await? {
+                if let Some(thread_id) = thread.get("id").and_then(|id| id.as_str()) {
+                    db::db_delete_thread(app_handle.clone(), thread_id).await?;
+                }
+            }
+            return Ok(());
+        }
+    }
+
+    let data_folder = get_jan_data_folder_path(app_handle);
+    reset_dir(&get_data_dir(&data_folder))
+}
+
+/// Stops the local engines and removes every downloaded model.
+/// Engine backends and settings are kept.
+#[tauri::command]
+pub async fn clear_models<R: Runtime>(app_handle: AppHandle<R>) -> Result<(), String> {
+    log::info!("Clearing downloaded models");
+    shutdown_engines_for_reset(&app_handle).await;
+
+    let data_folder = get_jan_data_folder_path(app_handle);
+    for dir in model_dirs(&data_folder) {
+        if dir.exists() {
+            reset_dir(&dir)?;
+        }
+    }
+    Ok(())
+}
+
+/// Drops all registered remote provider configs, including their API keys. The keys the
+/// frontend persisted are cleared when it receives `provider-keys-cleared`, so they aren't
+/// registered again on the next launch.
+#[tauri::command]
+pub async fn clear_provider_keys<R: Runtime>(
+    app_handle: AppHandle<R>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    {
+        let mut configs = state.provider_configs.write().await;
+        log::info!("Clearing {} provider configs", configs.len());
+        configs.clear();
+    }
+    app_handle
+        .emit(PROVIDER_KEYS_CLEARED_EVENT, ())
+        .map_err(|e| format!("Failed to emit {PROVIDER_KEYS_CLEARED_EVENT}: {e}"))
+}
+
 #[tauri::command]
 pub fn relaunch<R: Runtime>(app: AppHandle<R>) {
     app.restart()
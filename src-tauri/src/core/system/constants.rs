@@ -0,0 +1,3 @@
+// System constants
+/// Emitted after `clear_provider_keys`, so the frontend drops the keys it persisted
+pub const PROVIDER_KEYS_CLEARED_EVENT: &str = "provider-keys-cleared";
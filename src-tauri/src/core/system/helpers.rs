@@ -0,0 +1,64 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_llamacpp::cleanup_llama_processes;
+
+use crate::core::cancellation::models::CancelScope;
+use crate::core::mcp::helpers::{stop_mcp_servers_with_context, ShutdownContext};
+use crate::core::state::AppState;
+
+/// Engines whose downloaded models live under `<data folder>/<engine>/models`.
+const MODEL_ENGINES: [&str; 2] = ["llamacpp", "mlx"];
+
+/// Stops every MCP server with factory-reset timeouts and drops the lock files they hold.
+pub async fn shutdown_mcp_for_reset<R: Runtime>(app: &AppHandle<R>, state: &AppState) {
+    let _ = stop_mcp_servers_with_context(app, state, ShutdownContext::FactoryReset).await;
+
+    {
+        let mut active_servers = state.mcp_active_servers.lock().await;
+        active_servers.clear();
+    }
+
+    use crate::core::mcp::lockfile::cleanup_own_locks;
+    if let Err(e) = cleanup_own_locks(app) {
+        log::warn!("Failed to cleanup lock files: {}", e);
+    }
+}
+
+/// Stops local inference engines so no model file stays open.
+pub async fn shutdown_engines_for_reset<R: Runtime>(app: &AppHandle<R>) {
+    let _ = cleanup_llama_processes(app.clone()).await;
+
+    #[cfg(feature = "mlx")]
+    {
+        use tauri_plugin_mlx::cleanup_mlx_processes;
+        if let Err(e) = cleanup_mlx_processes(app.clone()).await {
+            log::warn!("Failed to cleanup MLX processes: {}", e);
+        }
+    }
+}
+
+/// Cancels running agent loops so none of them writes into a thread being removed.
+pub async fn cancel_agent_runs<R: Runtime>(app: &AppHandle<R>) {
+    app.state::<AppState>()
+        .cancellations
+        .cancel_where(|scope| matches!(scope, CancelScope::Turn(_)));
+}
+
+/// Model folders of every local engine in the data folder.
+pub fn model_dirs(data_folder: &Path) -> Vec<PathBuf> {
+    MODEL_ENGINES
+        .iter()
+        .map(|engine| data_folder.join(engine).join("models"))
+        .collect()
+}
+
+/// Removes a directory and recreates it empty.
+pub fn reset_dir(path: &Path) -> Result<(), String> {
+    if path.exists() {
+        fs::remove_dir_all(path)
+            .map_err(|e| format!("Failed to remove {}: {e}", path.display()))?;
+    }
+    fs::create_dir_all(path).map_err(|e| format!("Failed to create {}: {e}", path.display()))
+}
@@ -1 +1,6 @@
 pub mod commands;
+pub mod constants;
+pub mod helpers;
+
+#[cfg(test)]
+mod tests;
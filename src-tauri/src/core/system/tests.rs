@@ -0,0 +1,51 @@
+use super::helpers::{model_dirs, reset_dir};
+use std::fs;
+use std::path::PathBuf;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("jan-system-test-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn test_reset_dir_empties_existing_directory() {
+    let dir = temp_dir("reset");
+    fs::create_dir_all(dir.join("thread-1")).unwrap();
+    fs::write(dir.join("thread-1").join("thread.json"), "{}").unwrap();
+
+    reset_dir(&dir).unwrap();
+
+    assert!(dir.exists());
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_reset_dir_creates_missing_directory() {
+    let dir = temp_dir("missing");
+
+    reset_dir(&dir).unwrap();
+
+    assert!(dir.is_dir());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_model_dirs_leave_backends_untouched() {
+    let data_folder = temp_dir("models");
+    let backends = data_folder.join("llamacpp").join("backends");
+    fs::create_dir_all(&backends).unwrap();
+    fs::create_dir_all(data_folder.join("llamacpp").join("models").join("qwen")).unwrap();
+
+    for dir in model_dirs(&data_folder) {
+        if dir.exists() {
+            reset_dir(&dir).unwrap();
+        }
+    }
+
+    assert!(backends.exists());
+    assert!(!data_folder.join("llamacpp/models/qwen").exists());
+    assert!(!data_folder.join("mlx").exists());
+    fs::remove_dir_all(data_folder).unwrap();
+}
@@ -0,0 +1,71 @@
+use std::fs;
+use std::path::PathBuf;
+
+use tauri::{AppHandle, Runtime};
+
+use super::helpers::{build_report, clear_metrics, read_metrics, read_settings, write_settings};
+use super::models::{LocalMetrics, TelemetryReport, TelemetrySettings};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::settings::helpers::admin_policy;
+
+/// Returns whether local telemetry is enabled.
+#[tauri::command]
+pub async fn get_telemetry_settings<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<TelemetrySettings, String> {
+    Ok(read_settings(&get_jan_data_folder_path(app_handle)))
+}
+
+/// Opts in or out of local telemetry. Opting out deletes the collected counters.
+#[tauri::command]
+pub async fn set_telemetry_enabled<R: Runtime>(
+    app_handle: AppHandle<R>,
+    enabled: bool,
+) -> Result<TelemetrySettings, String> {
+    if enabled && admin_policy().disable_telemetry {
+        return Err("Telemetry is disabled by the administrator policy".to_string());
+    }
+    let data_folder = get_jan_data_folder_path(app_handle);
+    let settings = TelemetrySettings { enabled };
+    write_settings(&data_folder, &settings)?;
+    if !enabled {
+        clear_metrics(&data_folder)?;
+    }
+    Ok(settings)
+}
+
+/// Returns the raw counters stored on this machine.
+#[tauri::command]
+pub async fn get_local_metrics<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<LocalMetrics, String> {
+    Ok(read_metrics(&get_jan_data_folder_path(app_handle)))
+}
+
+/// Returns exactly the report that would be shared, without sending anything.
+#[tauri::command]
+pub async fn preview_telemetry_report<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<TelemetryReport, String> {
+    let version = app_handle.package_info().version.to_string();
+    let metrics = read_metrics(&get_jan_data_folder_path(app_handle));
+    Ok(build_report(&version, metrics))
+}
+
+/// Writes the previewed report to `path` so the user can share it themselves.
+#[tauri::command]
+pub async fn export_telemetry_report<R: Runtime>(
+    app_handle: AppHandle<R>,
+    path: String,
+) -> Result<TelemetryReport, String> {
+    let report = preview_telemetry_report(app_handle).await?;
+    let data = serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?;
+    fs::write(PathBuf::from(&path), data).map_err(|e| format!("Failed to write {path}: {e}"))?;
+    Ok(report)
+}
+
+/// Deletes the collected counters and starts a new period.
+#[tauri::command]
+pub async fn reset_local_metrics<R: Runtime>(app_handle: AppHandle<R>) -> Result<(), String> {
+    clear_metrics(&get_jan_data_folder_path(app_handle))
+}
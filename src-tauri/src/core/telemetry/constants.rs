@@ -0,0 +1,5 @@
+// Telemetry constants
+pub const TELEMETRY_DIR: &str = "telemetry";
+pub const TELEMETRY_SETTINGS_FILE: &str = "settings.json";
+pub const METRICS_FILE: &str = "metrics.json";
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
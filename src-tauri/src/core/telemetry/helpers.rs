@@ -0,0 +1,149 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use tauri::{AppHandle, Runtime};
+
+use super::constants::{
+    METRICS_FILE, REPORT_SCHEMA_VERSION, TELEMETRY_DIR, TELEMETRY_SETTINGS_FILE,
+};
+use super::models::{LocalMetrics, Metric, TelemetryReport, TelemetrySettings};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::config_store::helpers::{config_store, write_json, write_json_debounced};
+use crate::core::inference::models::TokenUsage;
+use crate::core::settings::helpers::admin_policy;
+
+// Serializes read-modify-write cycles on metrics.json. A std mutex so the panic hook can use it.
+static METRICS_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+fn metrics_lock() -> &'static Mutex<()> {
+    METRICS_LOCK.get_or_init(|| Mutex::new(()))
+}
+
+pub fn get_telemetry_dir(data_folder: &Path) -> PathBuf {
+    data_folder.join(TELEMETRY_DIR)
+}
+
+pub fn get_metrics_path(data_folder: &Path) -> PathBuf {
+    get_telemetry_dir(data_folder).join(METRICS_FILE)
+}
+
+/// Stored telemetry settings; telemetry stays off while the admin policy disables it
+pub fn read_settings(data_folder: &Path) -> TelemetrySettings {
+    let mut settings: TelemetrySettings = config_store()
+        .read(&get_telemetry_dir(data_folder).join(TELEMETRY_SETTINGS_FILE))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default();
+    settings.enabled &= !admin_policy().disable_telemetry;
+    settings
+}
+
+pub fn write_settings(data_folder: &Path, settings: &TelemetrySettings) -> Result<(), String> {
+    write_json_debounced(
+        &get_telemetry_dir(data_folder).join(TELEMETRY_SETTINGS_FILE),
+        settings,
+    )
+}
+
+pub fn read_metrics(data_folder: &Path) -> LocalMetrics {
+    fs::read_to_string(get_metrics_path(data_folder))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn write_metrics(data_folder: &Path, metrics: &LocalMetrics) -> Result<(), String> {
+    write_json(&get_metrics_path(data_folder), metrics)
+}
+
+/// Delete all collected counters
+pub fn clear_metrics(data_folder: &Path) -> Result<(), String> {
+    let _guard = metrics_lock().lock().map_err(|e| e.to_string())?;
+    let path = get_metrics_path(data_folder);
+    if path.exists() {
+        fs::remove_file(path).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn increment(data_folder: &Path, amounts: &[(Metric, u64)]) -> Result<(), String> {
+    if !read_settings(data_folder).enabled {
+        return Ok(());
+    }
+    let mut metrics = read_metrics(data_folder);
+    let now = chrono::Utc::now().timestamp_millis();
+    metrics.period_start.get_or_insert(now);
+    metrics.period_end = Some(now);
+    for (metric, amount) in amounts {
+        *metrics
+            .counters
+            .entry(metric.key().to_string())
+            .or_insert(0) += amount;
+    }
+    write_metrics(data_folder, &metrics)
+}
+
+/// Count `metric` in the data folder if the user opted in
+pub fn record_in(data_folder: &Path, metric: Metric) {
+    let Ok(_guard) = metrics_lock().lock() else {
+        return;
+    };
+    if let Err(e) = increment(data_folder, &[(metric, 1)]) {
+        log::debug!("Failed to record {} metric: {e}", metric.key());
+    }
+}
+
+/// Add the token usage of a run to the token counters if the user opted in
+pub fn record_usage_in(data_folder: &Path, usage: &TokenUsage) {
+    if *usage == TokenUsage::default() {
+        return;
+    }
+    let Ok(_guard) = metrics_lock().lock() else {
+        return;
+    };
+    let amounts = [
+        (Metric::InputTokens, usage.input_tokens),
+        (Metric::OutputTokens, usage.output_tokens),
+        (Metric::CacheReadTokens, usage.cache_read_tokens),
+        (Metric::CacheWriteTokens, usage.cache_write_tokens),
+    ];
+    if let Err(e) = increment(data_folder, &amounts) {
+        log::debug!("Failed to record token usage: {e}");
+    }
+}
+
+pub fn record_usage<R: Runtime>(app: &AppHandle<R>, usage: &TokenUsage) {
+    record_usage_in(&get_jan_data_folder_path(app.clone()), usage);
+}
+
+/// Count `metric` if the user opted in
+pub fn record<R: Runtime>(app: &AppHandle<R>, metric: Metric) {
+    record_in(&get_jan_data_folder_path(app.clone()), metric);
+}
+
+/// Count panics as crashes, keeping the previously installed hook
+pub fn install_crash_counter<R: Runtime>(app: &AppHandle<R>) {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        // Never block inside the hook: skip counting if the panic happened mid-update
+        if let Ok(_guard) = metrics_lock().try_lock() {
+            let _ = increment(&data_folder, &[(Metric::Crash, 1)]);
+        }
+        previous(info);
+    }));
+}
+
+/// Build the report that would be shared from the local counters
+pub fn build_report(app_version: &str, metrics: LocalMetrics) -> TelemetryReport {
+    TelemetryReport {
+        schema_version: REPORT_SCHEMA_VERSION,
+        app_version: app_version.to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        period_start: metrics.period_start,
+        period_end: metrics.period_end,
+        counters: metrics.counters,
+    }
+}
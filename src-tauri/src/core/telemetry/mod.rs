@@ -0,0 +1,20 @@
+/*!
+   Local Telemetry
+
+   Opt-in, purely local usage counters (generations, tool calls, crashes, tokens and
+   prompt-cache hits). Nothing is collected until the user enables it, and nothing is sent
+   anywhere by the app:
+   - counters are aggregated into `telemetry/metrics.json`, which the user can open and inspect,
+   - `preview_telemetry_report` returns exactly the report that would be shared,
+   - `export_telemetry_report` writes that same report to a file the user chooses, so sharing is
+     always an explicit action by the user.
+   Disabling telemetry deletes the collected counters.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,68 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Events counted by local telemetry
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    GenerationCompleted,
+    GenerationCancelled,
+    GenerationFailed,
+    ToolCall,
+    ToolCallFailed,
+    Crash,
+    /// Token counters, summed over all agent runs
+    InputTokens,
+    OutputTokens,
+    CacheReadTokens,
+    CacheWriteTokens,
+}
+
+impl Metric {
+    pub fn key(self) -> &'static str {
+        match self {
+            Metric::GenerationCompleted => "generation_completed",
+            Metric::GenerationCancelled => "generation_cancelled",
+            Metric::GenerationFailed => "generation_failed",
+            Metric::ToolCall => "tool_call",
+            Metric::ToolCallFailed => "tool_call_failed",
+            Metric::Crash => "crash",
+            Metric::InputTokens => "input_tokens",
+            Metric::OutputTokens => "output_tokens",
+            Metric::CacheReadTokens => "cache_read_tokens",
+            Metric::CacheWriteTokens => "cache_write_tokens",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct TelemetrySettings {
+    /// Telemetry is off until the user opts in
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+/// Counters as stored on disk
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LocalMetrics {
+    /// Milliseconds since epoch of the first counted event
+    #[serde(default)]
+    pub period_start: Option<i64>,
+    #[serde(default)]
+    pub period_end: Option<i64>,
+    #[serde(default)]
+    pub counters: BTreeMap<String, u64>,
+}
+
+/// The complete payload a user may choose to share. Contains no identifiers or content.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TelemetryReport {
+    pub schema_version: u32,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub period_start: Option<i64>,
+    pub period_end: Option<i64>,
+    pub counters: BTreeMap<String, u64>,
+}
@@ -0,0 +1,97 @@
+use super::commands::*;
+use super::helpers::{record_in, record_usage_in};
+use super::models::Metric;
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::inference::models::TokenUsage;
+use std::fs;
+use tauri::test::mock_app;
+
+#[tokio::test]
+async fn test_nothing_is_recorded_without_opt_in() {
+    let app = mock_app();
+    let data_dir = get_jan_data_folder_path(app.handle().clone());
+
+    record_in(&data_dir, Metric::GenerationCompleted);
+    let metrics = get_local_metrics(app.handle().clone()).await.unwrap();
+    assert!(metrics.counters.is_empty());
+    assert!(metrics.period_start.is_none());
+
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_report_matches_local_counters() {
+    let app = mock_app();
+    let data_dir = get_jan_data_folder_path(app.handle().clone());
+
+    set_telemetry_enabled(app.handle().clone(), true)
+        .await
+        .unwrap();
+    record_in(&data_dir, Metric::GenerationCompleted);
+    record_in(&data_dir, Metric::GenerationCompleted);
+    record_in(&data_dir, Metric::ToolCallFailed);
+
+    let report = preview_telemetry_report(app.handle().clone())
+        .await
+        .unwrap();
+    assert_eq!(report.counters.get("generation_completed"), Some(&2));
+    assert_eq!(report.counters.get("tool_call_failed"), Some(&1));
+    assert!(report.period_start.is_some());
+
+    // The exported file contains exactly the previewed report
+    let path = data_dir.join("report.json");
+    let exported = export_telemetry_report(app.handle().clone(), path.display().to_string())
+        .await
+        .unwrap();
+    assert_eq!(exported, report);
+    let on_disk: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(on_disk, serde_json::to_value(&report).unwrap());
+
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_opting_out_deletes_counters() {
+    let app = mock_app();
+    let data_dir = get_jan_data_folder_path(app.handle().clone());
+
+    set_telemetry_enabled(app.handle().clone(), true)
+        .await
+        .unwrap();
+    record_in(&data_dir, Metric::Crash);
+    set_telemetry_enabled(app.handle().clone(), false)
+        .await
+        .unwrap();
+
+    let metrics = get_local_metrics(app.handle().clone()).await.unwrap();
+    assert!(metrics.counters.is_empty());
+
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_token_usage_is_summed() {
+    let app = mock_app();
+    let data_dir = get_jan_data_folder_path(app.handle().clone());
+
+    set_telemetry_enabled(app.handle().clone(), true)
+        .await
+        .unwrap();
+    let usage = TokenUsage {
+        input_tokens: 1_200,
+        output_tokens: 80,
+        cache_read_tokens: 1_024,
+        cache_write_tokens: 0,
+    };
+    record_usage_in(&data_dir, &usage);
+    record_usage_in(&data_dir, &usage);
+
+    let metrics = get_local_metrics(app.handle().clone()).await.unwrap();
+    assert_eq!(metrics.counters.get("input_tokens"), Some(&2_400));
+    assert_eq!(metrics.counters.get("output_tokens"), Some(&160));
+    assert_eq!(metrics.counters.get("cache_read_tokens"), Some(&2_048));
+    assert_eq!(metrics.counters.get("cache_write_tokens"), Some(&0));
+
+    let _ = fs::remove_dir_all(data_dir);
+}
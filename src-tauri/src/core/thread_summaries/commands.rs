@@ -0,0 +1,37 @@
+use tauri::{AppHandle, Runtime};
+
+use super::helpers::{claim_thread, read_thread_summary, release_thread, update_thread_summary};
+use super::models::ThreadSummary;
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::settings::helpers::summary_settings;
+
+/// Returns the running summary of a thread, if one has been generated.
+#[tauri::command]
+pub async fn get_thread_summary<R: Runtime>(
+    app_handle: AppHandle<R>,
+    thread_id: String,
+) -> Result<Option<ThreadSummary>, String> {
+    let data_folder = get_jan_data_folder_path(app_handle);
+    Ok(read_thread_summary(&data_folder, &thread_id))
+}
+
+/// Brings the summary and title of a thread up to date now, however few messages are new.
+/// Returns `None` when there was nothing to summarize.
+#[tauri::command]
+pub async fn refresh_thread_summary<R: Runtime>(
+    app_handle: AppHandle<R>,
+    thread_id: String,
+) -> Result<Option<ThreadSummary>, String> {
+    let settings = summary_settings(&app_handle);
+    if settings.model.trim().is_empty() {
+        return Err("No summary model is configured".to_string());
+    }
+    if !claim_thread(&thread_id) {
+        return Err(format!(
+            "The summary of thread {thread_id} is already being updated"
+        ));
+    }
+    let result = update_thread_summary(&app_handle, &thread_id, &settings, true).await;
+    release_thread(&thread_id);
+    result
+}
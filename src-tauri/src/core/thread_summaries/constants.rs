@@ -0,0 +1,15 @@
+// Thread title and summary constants
+pub const THREAD_SUMMARY_FILE: &str = "thread_summary.json";
+pub const THREAD_SUMMARY_EVENT: &str = "thread-summary-updated";
+
+/// Title the frontend gives threads before anything better is known
+pub const DEFAULT_THREAD_TITLE: &str = "New Thread";
+pub const MAX_TITLE_CHARS: usize = 80;
+/// Each message is cut to this many estimated tokens before it is summarized
+pub const MAX_MESSAGE_TOKENS: usize = 1_000;
+
+/// Times an update is retried after being preempted by interactive chat
+pub const MAX_PREEMPTION_RETRIES: usize = 3;
+pub const PREEMPTION_RETRY_DELAY_SECS: u64 = 30;
+
+pub const RUNNING_SUMMARY_PROMPT: &str = "You maintain a running summary of a conversation. Update the earlier summary, if any, with the new messages. Keep facts, decisions, names, numbers and open questions in a few short paragraphs. Write in the language of the conversation and reply with the summary only.";
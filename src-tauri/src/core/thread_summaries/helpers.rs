@@ -0,0 +1,315 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, Runtime};
+
+use super::constants::{
+    DEFAULT_THREAD_TITLE, MAX_MESSAGE_TOKENS, MAX_PREEMPTION_RETRIES, MAX_TITLE_CHARS,
+    PREEMPTION_RETRY_DELAY_SECS, RUNNING_SUMMARY_PROMPT, THREAD_SUMMARY_EVENT, THREAD_SUMMARY_FILE,
+};
+use super::models::{ThreadSummary, ThreadSummaryEvent};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::config_store::helpers::write_json;
+use crate::core::context::helpers::{message_text, truncate_to_tokens};
+use crate::core::inference::helpers::{
+    chat_completion, completion_text, resolve_model_endpoint, DEFAULT_COMPLETION_TIMEOUT,
+};
+use crate::core::inference::models::ModelEndpoint;
+use crate::core::local_text::helpers::{generate_title, local_text_endpoint};
+use crate::core::scheduler::constants::PREEMPTED_ERROR;
+use crate::core::scheduler::helpers::acquire_for_endpoint;
+use crate::core::scheduler::models::GenerationPriority;
+use crate::core::settings::helpers::summary_settings;
+use crate::core::settings::models::SummarySettings;
+use crate::core::threads::commands::{list_messages, modify_thread};
+use crate::core::threads::export::split_reasoning;
+use crate::core::threads::helpers::should_use_sqlite;
+use crate::core::threads::private::is_private_thread;
+use crate::core::threads::utils::{get_thread_dir, get_thread_metadata_path};
+
+// Threads with an update in progress, so replies saved meanwhile do not start another one
+static IN_FLIGHT: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+
+fn in_flight() -> &'static Mutex<HashSet<String>> {
+    IN_FLIGHT.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// Mark `thread_id` as being updated; `false` when it already is
+pub fn claim_thread(thread_id: &str) -> bool {
+    in_flight().lock().unwrap().insert(thread_id.to_string())
+}
+
+pub fn release_thread(thread_id: &str) {
+    in_flight().lock().unwrap().remove(thread_id);
+}
+
+pub fn get_thread_summary_path(data_folder: &Path, thread_id: &str) -> PathBuf {
+    get_thread_dir(data_folder, thread_id).join(THREAD_SUMMARY_FILE)
+}
+
+pub fn read_thread_summary(data_folder: &Path, thread_id: &str) -> Option<ThreadSummary> {
+    let data = fs::read_to_string(get_thread_summary_path(data_folder, thread_id)).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+pub fn write_thread_summary(
+    data_folder: &Path,
+    thread_id: &str,
+    summary: &ThreadSummary,
+) -> Result<(), String> {
+    write_json(&get_thread_summary_path(data_folder, thread_id), summary)
+}
+
+/// Finished user and assistant messages that carry text, in thread order
+pub fn summarizable_messages(messages: &[Value]) -> Vec<&Value> {
+    messages
+        .iter()
+        .filter(|m| {
+            matches!(
+                m.get("role").and_then(Value::as_str),
+                Some("user" | "assistant")
+            )
+        })
+        .filter(|m| m.get("status").and_then(Value::as_str) != Some("pending"))
+        .filter(|m| m.get("id").and_then(Value::as_str).is_some())
+        .filter(|m| !message_text(m).trim().is_empty())
+        .collect()
+}
+
+/// Messages not yet covered by `record`, and whether its summary is still a prefix of them.
+/// When the covered message left the active branch, everything is summarized afresh.
+pub fn pending_since<'a>(
+    messages: &'a [&'a Value],
+    record: Option<&ThreadSummary>,
+) -> (&'a [&'a Value], bool) {
+    let covered = record.and_then(|record| {
+        messages
+            .iter()
+            .position(|m| m.get("id").and_then(Value::as_str) == Some(&record.last_message_id))
+    });
+    match covered {
+        Some(pos) => (&messages[pos + 1..], true),
+        None => (messages, false),
+    }
+}
+
+pub fn build_summary_request(pending: &[&Value], previous: Option<&str>) -> Value {
+    let mut transcript = String::new();
+    if let Some(previous) = previous {
+        transcript.push_str(&format!("[Earlier summary]\n{previous}\n\n"));
+    }
+    for message in pending {
+        let role = message
+            .get("role")
+            .and_then(Value::as_str)
+            .unwrap_or("user");
+        let text = message_text(message);
+        let (_, answer) = split_reasoning(&text);
+        let answer = truncate_to_tokens(answer, MAX_MESSAGE_TOKENS);
+        transcript.push_str(&format!("{role}: {answer}\n\n"));
+    }
+    json!({
+        "messages": [
+            { "role": "system", "content": RUNNING_SUMMARY_PROMPT },
+            { "role": "user", "content": transcript }
+        ],
+        "temperature": 0.2
+    })
+}
+
+/// First line of a model reply without reasoning, labels, quotes and markdown decoration
+pub fn clean_title(raw: &str) -> Option<String> {
+    let (_, answer) = split_reasoning(raw);
+    let line = answer.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let line = match line.split_once(':') {
+        Some((label, rest)) if label.trim().eq_ignore_ascii_case("title") => rest,
+        _ => line,
+    };
+    let title = line
+        .trim_matches(|c: char| c.is_whitespace() || matches!(c, '"' | '\'' | '*' | '#' | '`'))
+        .trim_end_matches('.');
+    let title: String = title.chars().take(MAX_TITLE_CHARS).collect();
+    let title = title.trim();
+    (!title.is_empty()).then(|| title.to_string())
+}
+
+/// Whether `title` is a placeholder rather than one the user chose: empty, the default, the
+/// title generated last time, or the start of the first message as the frontend names threads
+pub fn is_replaceable_title(
+    title: &str,
+    record: Option<&ThreadSummary>,
+    first_user_text: &str,
+) -> bool {
+    let title = title.trim();
+    title.is_empty()
+        || title.eq_ignore_ascii_case(DEFAULT_THREAD_TITLE)
+        || record.and_then(|r| r.title.as_deref()) == Some(title)
+        || first_user_text.trim().starts_with(title)
+}
+
+/// Run one completion at background priority, retrying when interactive chat preempts it
+pub async fn complete_in_background<R: Runtime>(
+    app: &AppHandle<R>,
+    endpoint: &ModelEndpoint,
+    job_id: &str,
+    thread_id: Option<&str>,
+    request: &Value,
+) -> Result<String, String> {
+    let mut attempt = 0;
+    loop {
+        let permit =
+            acquire_for_endpoint(app, endpoint, job_id, GenerationPriority::Background).await?;
+        let preempted = permit
+            .as_ref()
+            .map(|p| p.preempted().clone())
+            .unwrap_or_default();
+        let response = tokio::select! {
+            response = chat_completion(endpoint, request.clone(), thread_id, DEFAULT_COMPLETION_TIMEOUT) => response,
+            _ = preempted.cancelled() => Err(PREEMPTED_ERROR.to_string()),
+        };
+        drop(permit);
+        match response {
+            Ok(response) => {
+                return completion_text(&response)
+                    .filter(|text| !text.trim().is_empty())
+                    .ok_or_else(|| "The model returned an empty response".to_string());
+            }
+            Err(e) if e == PREEMPTED_ERROR && attempt < MAX_PREEMPTION_RETRIES => {
+                attempt += 1;
+                log::info!("{job_id} was preempted, retrying ({attempt}/{MAX_PREEMPTION_RETRIES})");
+                tokio::time::sleep(Duration::from_secs(PREEMPTION_RETRY_DELAY_SECS)).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Generate a title from `summary` with the local model and store it on the thread, unless
+/// the user named it. Returns the new title.
+async fn update_title<R: Runtime>(
+    app: &AppHandle<R>,
+    thread_id: &str,
+    summary: &str,
+    record: Option<&ThreadSummary>,
+    first_user_text: &str,
+) -> Result<Option<String>, String> {
+    let data_folder = get_jan_data_folder_path(app.clone());
+    let data = fs::read_to_string(get_thread_metadata_path(&data_folder, thread_id))
+        .map_err(|e| e.to_string())?;
+    let mut thread: Value = serde_json::from_str(&data).map_err(|e| e.to_string())?;
+    let current = thread.get("title").and_then(Value::as_str).unwrap_or("");
+    if !is_replaceable_title(current, record, first_user_text) {
+        return Ok(None);
+    }
+
+    // Titles are shown outside the thread, so they never come from a remote provider
+    let endpoint = local_text_endpoint(app).await?;
+    let job_id = format!("thread-title:{thread_id}");
+    let title = generate_title(app, &endpoint, &job_id, summary).await?;
+    if title == current {
+        return Ok(None);
+    }
+    thread["title"] = json!(title);
+    modify_thread(app.clone(), thread).await?;
+    Ok(Some(title))
+}
+
+/// Fold the messages added since the last update into the thread's summary and retitle it.
+/// Returns `None` without calling the model when fewer than `every_messages` messages are
+/// new, unless `force` is set.
+pub async fn update_thread_summary<R: Runtime>(
+    app: &AppHandle<R>,
+    thread_id: &str,
+    settings: &SummarySettings,
+    force: bool,
+) -> Result<Option<ThreadSummary>, String> {
+    if should_use_sqlite() {
+        return Err("Thread summaries are not supported with database storage".to_string());
+    }
+    let data_folder = get_jan_data_folder_path(app.clone());
+    if is_private_thread(&data_folder, thread_id) {
+        return Err("Private threads are not summarized".to_string());
+    }
+    let messages = list_messages(app.clone(), thread_id.to_string()).await?;
+    let messages = summarizable_messages(&messages);
+    let Some(newest) = messages.last() else {
+        return Ok(None);
+    };
+    let record = read_thread_summary(&data_folder, thread_id);
+    let (pending, extends) = pending_since(&messages, record.as_ref());
+    if pending.is_empty() || (!force && pending.len() < settings.every_messages) {
+        return Ok(None);
+    }
+
+    let endpoint = resolve_model_endpoint(app, &settings.model).await?;
+    let previous = record
+        .as_ref()
+        .filter(|_| extends)
+        .map(|r| r.summary.as_str());
+    let request = build_summary_request(pending, previous);
+    let job_id = format!("thread-summary:{thread_id}");
+    let summary = complete_in_background(app, &endpoint, &job_id, Some(thread_id), &request)
+        .await?
+        .trim()
+        .to_string();
+
+    let mut title = None;
+    if settings.update_titles {
+        let first_user_text = messages
+            .iter()
+            .find(|m| m.get("role").and_then(Value::as_str) == Some("user"))
+            .map(|m| message_text(m))
+            .unwrap_or_default();
+        title = update_title(app, thread_id, &summary, record.as_ref(), &first_user_text)
+            .await
+            .unwrap_or_else(|e| {
+                log::warn!("Failed to generate a title for thread {thread_id}: {e}");
+                None
+            });
+    }
+
+    let updated = ThreadSummary {
+        last_message_id: newest
+            .get("id")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        message_count: messages.len(),
+        summary,
+        title: title.clone().or(record.and_then(|r| r.title)),
+        model: settings.model.clone(),
+        updated_at: chrono::Utc::now().timestamp_millis(),
+    };
+    write_thread_summary(&data_folder, thread_id, &updated)?;
+
+    let event = ThreadSummaryEvent {
+        thread_id: thread_id.to_string(),
+        summary: updated.summary.clone(),
+        title,
+    };
+    if let Err(e) = app.emit(THREAD_SUMMARY_EVENT, &event) {
+        log::error!("Failed to emit thread summary event: {e}");
+    }
+    Ok(Some(updated))
+}
+
+/// Bring the summary of `thread_id` up to date in the background when enough new messages
+/// have accumulated. Does nothing when summaries are disabled or an update is running.
+pub fn schedule_thread_summary<R: Runtime>(app: &AppHandle<R>, thread_id: &str) {
+    let settings = summary_settings(app);
+    if !settings.enabled || settings.model.trim().is_empty() || !claim_thread(thread_id) {
+        return;
+    }
+    let app = app.clone();
+    let thread_id = thread_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = update_thread_summary(&app, &thread_id, &settings, false).await {
+            log::warn!("Failed to update the summary of thread {thread_id}: {e}");
+        }
+        release_thread(&thread_id);
+    });
+}
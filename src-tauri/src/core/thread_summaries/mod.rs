@@ -0,0 +1,21 @@
+/*!
+   Thread Titles and Summaries
+
+   Background service keeping a title and a running summary for each thread. Whenever an
+   assistant reply is saved and at least `summaries.every_messages` new messages have
+   accumulated since the last update, the new messages are folded into the thread's summary
+   with the configured (preferably small) model, and a title is derived from it by a local
+   model only (see `local_text`), even when the summary model is remote. Requests go
+   through the generation scheduler at background priority, so they wait for and yield to
+   interactive chat. The summary is kept in `thread_summary.json` in the thread directory;
+   titles the user has set are left alone. Every update is announced with a
+   `thread-summary-updated` event.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
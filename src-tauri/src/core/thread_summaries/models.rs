@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+/// Running summary of a thread, persisted in its directory
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThreadSummary {
+    /// Id of the newest message covered by the summary
+    pub last_message_id: String,
+    /// Messages covered by the summary
+    pub message_count: usize,
+    pub summary: String,
+    /// Title last generated for the thread, to tell it apart from one the user typed
+    #[serde(default)]
+    pub title: Option<String>,
+    pub model: String,
+    pub updated_at: i64,
+}
+
+/// Payload of `thread-summary-updated`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadSummaryEvent {
+    pub thread_id: String,
+    pub summary: String,
+    /// New title, when the thread was renamed
+    pub title: Option<String>,
+}
@@ -0,0 +1,174 @@
+use super::helpers::{
+    build_summary_request, clean_title, get_thread_summary_path, is_replaceable_title,
+    pending_since, read_thread_summary, summarizable_messages, update_thread_summary,
+    write_thread_summary,
+};
+use super::models::ThreadSummary;
+use crate::core::settings::helpers::validate_settings;
+use crate::core::settings::models::{Settings, SummarySettings};
+use serde_json::{json, Value};
+
+fn message(id: &str, role: &str, text: &str) -> Value {
+    json!({"id": id, "role": role, "content": [{"type": "text", "text": {"value": text}}]})
+}
+
+fn record(last_message_id: &str, title: Option<&str>) -> ThreadSummary {
+    ThreadSummary {
+        last_message_id: last_message_id.to_string(),
+        summary: "earlier".to_string(),
+        title: title.map(str::to_string),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_summarizable_messages_skip_system_pending_and_empty() {
+    let mut pending = message("d", "assistant", "partial");
+    pending["status"] = json!("pending");
+    let messages = vec![
+        message("a", "system", "be brief"),
+        message("b", "user", "hello"),
+        message("c", "assistant", "  "),
+        pending,
+        message("e", "assistant", "hi"),
+    ];
+    let ids: Vec<&str> = summarizable_messages(&messages)
+        .iter()
+        .map(|m| m["id"].as_str().unwrap())
+        .collect();
+    assert_eq!(ids, vec!["b", "e"]);
+}
+
+#[test]
+fn test_pending_since_extends_or_restarts() {
+    let messages = [
+        message("a", "user", "1"),
+        message("b", "assistant", "2"),
+        message("c", "user", "3"),
+    ];
+    let refs: Vec<&Value> = messages.iter().collect();
+
+    let (pending, extends) = pending_since(&refs, Some(&record("b", None)));
+    assert!(extends);
+    assert_eq!(pending.len(), 1);
+
+    // The covered message is no longer on the active branch
+    let (pending, extends) = pending_since(&refs, Some(&record("gone", None)));
+    assert!(!extends);
+    assert_eq!(pending.len(), 3);
+
+    let (pending, extends) = pending_since(&refs, None);
+    assert!(!extends);
+    assert_eq!(pending.len(), 3);
+}
+
+#[test]
+fn test_build_summary_request_includes_previous_and_drops_reasoning() {
+    let messages = [message(
+        "a",
+        "assistant",
+        "<think>hmm</think>The answer is 4",
+    )];
+    let refs: Vec<&Value> = messages.iter().collect();
+    let request = build_summary_request(&refs, Some("They asked about math"));
+    let transcript = request["messages"][1]["content"].as_str().unwrap();
+    assert!(transcript.starts_with("[Earlier summary]\nThey asked about math"));
+    assert!(transcript.contains("assistant: The answer is 4"));
+    assert!(!transcript.contains("hmm"));
+}
+
+#[test]
+fn test_clean_title() {
+    assert_eq!(
+        clean_title("Title: \"Rust lifetimes explained.\"\nmore"),
+        Some("Rust lifetimes explained".to_string())
+    );
+    assert_eq!(
+        clean_title("<think>short</think>\n\n**Trip to Lisbon**"),
+        Some("Trip to Lisbon".to_string())
+    );
+    assert_eq!(clean_title("  \n\"\""), None);
+    assert_eq!(clean_title(&"a".repeat(200)).unwrap().len(), 80);
+}
+
+#[test]
+fn test_is_replaceable_title() {
+    let previous = record("a", Some("Old generated"));
+    assert!(is_replaceable_title("", None, "hi"));
+    assert!(is_replaceable_title("New Thread", None, "hi"));
+    assert!(is_replaceable_title("Old generated", Some(&previous), "hi"));
+    assert!(is_replaceable_title(
+        "How do I",
+        None,
+        "How do I parse JSON?"
+    ));
+    assert!(!is_replaceable_title(
+        "My project",
+        Some(&previous),
+        "How do I parse JSON?"
+    ));
+}
+
+#[test]
+fn test_summary_settings_validation() {
+    let mut settings = Settings::default();
+    assert!(validate_settings(&settings).is_ok());
+
+    settings.summaries = SummarySettings {
+        enabled: true,
+        ..Default::default()
+    };
+    assert!(validate_settings(&settings).is_err());
+
+    settings.summaries.model = "qwen3-0.6b".to_string();
+    assert!(validate_settings(&settings).is_ok());
+
+    settings.summaries.every_messages = 0;
+    assert!(validate_settings(&settings).is_err());
+}
+
+#[test]
+fn test_thread_summary_round_trip() {
+    let data_folder = std::env::temp_dir().join(format!("jan-summary-{}", uuid::Uuid::new_v4()));
+    assert!(read_thread_summary(&data_folder, "t1").is_none());
+
+    let summary = record("m1", Some("Title"));
+    write_thread_summary(&data_folder, "t1", &summary).unwrap();
+    assert!(get_thread_summary_path(&data_folder, "t1").exists());
+    assert_eq!(read_thread_summary(&data_folder, "t1"), Some(summary));
+
+    let _ = std::fs::remove_dir_all(data_folder);
+}
+
+#[tokio::test]
+async fn test_update_waits_for_enough_new_messages() {
+    use crate::core::threads::commands::{create_message, create_thread, delete_thread};
+
+    let app = tauri::test::mock_app();
+    let thread = create_thread(app.handle().clone(), json!({"title": "New Thread"}))
+        .await
+        .unwrap();
+    let thread_id = thread["id"].as_str().unwrap().to_string();
+    for (role, text) in [("user", "hello"), ("assistant", "hi there")] {
+        let mut message = message("", role, text);
+        message.as_object_mut().unwrap().remove("id");
+        message["thread_id"] = json!(thread_id);
+        create_message(app.handle().clone(), message).await.unwrap();
+    }
+
+    // Below the threshold the model, which does not exist here, is never called
+    let settings = SummarySettings {
+        enabled: true,
+        model: "missing-model".to_string(),
+        every_messages: 3,
+        update_titles: true,
+    };
+    let result = update_thread_summary(app.handle(), &thread_id, &settings, false).await;
+    assert_eq!(result, Ok(None));
+
+    // Forcing reaches the model resolution
+    let result = update_thread_summary(app.handle(), &thread_id, &settings, true).await;
+    assert!(result.unwrap_err().contains("missing-model"));
+
+    let _ = delete_thread(app.handle().clone(), thread_id).await;
+}
@@ -0,0 +1,182 @@
+/*!
+   Cold storage of archived threads
+
+   Archiving moves the messages of a thread out of hot storage (`messages.jsonl`, or the
+   `messages` table on mobile) into zstd-compressed segments under the thread's `archive`
+   directory. Each segment holds up to `ARCHIVE_SEGMENT_MESSAGES` messages; `archive.json`
+   lists them with their message counts and SHA-256 digests and is written last, so a thread
+   only counts as archived once every segment is on disk.
+
+   The thread metadata stays hot and records the archive under `metadata.archived`, together
+   with the thread summary, so titles and summaries stay searchable. Opening an archived
+   thread rehydrates it: the segments are verified, written back to hot storage and removed.
+*/
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use super::constants::{
+    ARCHIVE_DIR, ARCHIVE_MANIFEST_FILE, ARCHIVE_SEGMENT_MESSAGES, ARCHIVE_ZSTD_LEVEL,
+};
+use super::utils::get_thread_dir;
+use crate::core::config_store::helpers::write_atomic;
+
+/// One compressed run of consecutive messages
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveSegment {
+    pub file: String,
+    pub messages: usize,
+    /// Compressed size
+    pub bytes: u64,
+    pub sha256: String,
+}
+
+/// Contents of `archive.json`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub archived_at: i64,
+    pub message_count: usize,
+    /// Size of the messages as JSON lines
+    pub original_bytes: u64,
+    pub compressed_bytes: u64,
+    pub segments: Vec<ArchiveSegment>,
+}
+
+pub fn get_archive_dir(data_folder: &Path, thread_id: &str) -> PathBuf {
+    get_thread_dir(data_folder, thread_id).join(ARCHIVE_DIR)
+}
+
+fn get_manifest_path(data_folder: &Path, thread_id: &str) -> PathBuf {
+    get_archive_dir(data_folder, thread_id).join(ARCHIVE_MANIFEST_FILE)
+}
+
+pub fn is_archived(data_folder: &Path, thread_id: &str) -> bool {
+    get_manifest_path(data_folder, thread_id).exists()
+}
+
+pub fn read_manifest(data_folder: &Path, thread_id: &str) -> Result<ArchiveManifest, String> {
+    let data = fs::read_to_string(get_manifest_path(data_folder, thread_id))
+        .map_err(|e| format!("Failed to read the archive of thread {thread_id}: {e}"))?;
+    serde_json::from_str(&data).map_err(|e| e.to_string())
+}
+
+/// Compress `messages` as JSON lines. Returns the segment and the uncompressed size.
+pub fn encode_segment(messages: &[Value]) -> Result<(Vec<u8>, u64), String> {
+    let mut lines = Vec::new();
+    for message in messages {
+        serde_json::to_writer(&mut lines, message).map_err(|e| e.to_string())?;
+        lines.write_all(b"\n").map_err(|e| e.to_string())?;
+    }
+    let compressed = zstd::stream::encode_all(lines.as_slice(), ARCHIVE_ZSTD_LEVEL)
+        .map_err(|e| e.to_string())?;
+    Ok((compressed, lines.len() as u64))
+}
+
+pub fn decode_segment(data: &[u8]) -> Result<Vec<Value>, String> {
+    let lines = zstd::stream::decode_all(data).map_err(|e| e.to_string())?;
+    lines
+        .split(|b| *b == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| serde_json::from_slice(line).map_err(|e| e.to_string()))
+        .collect()
+}
+
+/// Write `messages` into cold segments. Anything left over from an earlier archive is replaced.
+pub fn write_archive(
+    data_folder: &Path,
+    thread_id: &str,
+    messages: &[Value],
+    archived_at: i64,
+) -> Result<ArchiveManifest, String> {
+    remove_archive(data_folder, thread_id)?;
+    let dir = get_archive_dir(data_folder, thread_id);
+    let mut manifest = ArchiveManifest {
+        archived_at,
+        message_count: messages.len(),
+        original_bytes: 0,
+        compressed_bytes: 0,
+        segments: Vec::new(),
+    };
+    for (index, chunk) in messages.chunks(ARCHIVE_SEGMENT_MESSAGES).enumerate() {
+        let (data, original) = encode_segment(chunk)?;
+        let file = format!("segment-{index:05}.jsonl.zst");
+        write_atomic(&dir.join(&file), &data)?;
+        manifest.original_bytes += original;
+        manifest.compressed_bytes += data.len() as u64;
+        manifest.segments.push(ArchiveSegment {
+            file,
+            messages: chunk.len(),
+            bytes: data.len() as u64,
+            sha256: hex::encode(Sha256::digest(&data)),
+        });
+    }
+    let data = serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?;
+    write_atomic(&get_manifest_path(data_folder, thread_id), &data)?;
+    Ok(manifest)
+}
+
+/// Messages of an archived thread, in their original order. Fails if a segment is missing
+/// or does not match the manifest.
+pub fn read_archive(data_folder: &Path, thread_id: &str) -> Result<Vec<Value>, String> {
+    let manifest = read_manifest(data_folder, thread_id)?;
+    let dir = get_archive_dir(data_folder, thread_id);
+    let mut messages = Vec::with_capacity(manifest.message_count);
+    for segment in &manifest.segments {
+        let data = fs::read(dir.join(&segment.file))
+            .map_err(|e| format!("Failed to read archive segment {}: {e}", segment.file))?;
+        if hex::encode(Sha256::digest(&data)) != segment.sha256 {
+            return Err(format!("Archive segment {} is corrupted", segment.file));
+        }
+        let decoded = decode_segment(&data)?;
+        if decoded.len() != segment.messages {
+            return Err(format!(
+                "Archive segment {} holds {} messages instead of {}",
+                segment.file,
+                decoded.len(),
+                segment.messages
+            ));
+        }
+        messages.extend(decoded);
+    }
+    Ok(messages)
+}
+
+/// Delete the cold segments of a thread. The manifest goes first, so an interrupted removal
+/// never leaves a thread that looks archived but is missing segments.
+pub fn remove_archive(data_folder: &Path, thread_id: &str) -> Result<(), String> {
+    let manifest = get_manifest_path(data_folder, thread_id);
+    if manifest.exists() {
+        fs::remove_file(&manifest).map_err(|e| e.to_string())?;
+    }
+    let dir = get_archive_dir(data_folder, thread_id);
+    if dir.exists() {
+        fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Record the archive in the thread metadata, with the summary so it stays searchable
+pub fn mark_archived(thread: &mut Value, manifest: &ArchiveManifest, summary: Option<&str>) {
+    if !thread.get("metadata").is_some_and(Value::is_object) {
+        thread["metadata"] = Value::Object(Default::default());
+    }
+    thread["metadata"]["archived"] = serde_json::json!({
+        "archived_at": manifest.archived_at,
+        "message_count": manifest.message_count,
+        "summary": summary,
+    });
+}
+
+/// Drop the archive record from the thread metadata. Returns whether there was one.
+pub fn clear_archived(thread: &mut Value) -> bool {
+    thread
+        .get_mut("metadata")
+        .and_then(Value::as_object_mut)
+        .and_then(|metadata| metadata.remove("archived"))
+        .is_some()
+}
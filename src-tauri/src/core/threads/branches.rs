@@ -0,0 +1,241 @@
+/*!
+   Message branch tree
+
+   Regenerations and edits are stored as siblings instead of overwriting messages. Every
+   message records its `parent_id`; messages written before branching existed have no such
+   field and implicitly follow the previous message in the file. The leaf of the branch the
+   user is looking at is persisted in `branches.json`, and the active path is the chain of
+   parents from that leaf back to the root.
+*/
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::utils::get_branches_path;
+
+/// Persisted branch selection of a thread
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BranchState {
+    pub active_leaf_id: Option<String>,
+}
+
+/// Lightweight view of one message in the tree
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageNode {
+    pub id: String,
+    pub parent_id: Option<String>,
+    pub role: Option<String>,
+    pub children: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageTreeView {
+    pub nodes: Vec<MessageNode>,
+    /// Message ids from the root to the active leaf
+    pub active_path: Vec<String>,
+}
+
+/// Alternatives sharing the same parent, e.g. regenerations of one answer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageBranches {
+    pub parent_id: Option<String>,
+    /// Sibling messages in creation order
+    pub alternatives: Vec<Value>,
+    /// Index of the sibling on the active path
+    pub active_index: Option<usize>,
+}
+
+pub fn message_id(message: &Value) -> Option<&str> {
+    message.get("id").and_then(|v| v.as_str())
+}
+
+pub fn read_branch_state(data_folder: &Path, thread_id: &str) -> BranchState {
+    fs::read_to_string(get_branches_path(data_folder, thread_id))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+pub fn write_branch_state(
+    data_folder: &Path,
+    thread_id: &str,
+    state: &BranchState,
+) -> Result<(), String> {
+    let data = serde_json::to_string_pretty(state).map_err(|e| e.to_string())?;
+    fs::write(get_branches_path(data_folder, thread_id), data).map_err(|e| e.to_string())
+}
+
+/// Parent/child index over the messages of a thread, in file order
+pub struct MessageTree<'a> {
+    messages: &'a [Value],
+    parents: Vec<Option<usize>>,
+    /// Children of each message in creation order
+    children: Vec<Vec<usize>>,
+    roots: Vec<usize>,
+    index: HashMap<&'a str, usize>,
+}
+
+impl<'a> MessageTree<'a> {
+    pub fn build(messages: &'a [Value]) -> Self {
+        let index: HashMap<&str, usize> = messages
+            .iter()
+            .enumerate()
+            .filter_map(|(i, m)| message_id(m).map(|id| (id, i)))
+            .collect();
+        let parents: Vec<Option<usize>> = messages
+            .iter()
+            .enumerate()
+            .map(|(i, m)| match m.get("parent_id") {
+                // Legacy linear history: the previous message is the parent
+                None => i.checked_sub(1),
+                Some(parent) => parent
+                    .as_str()
+                    .and_then(|id| index.get(id).copied())
+                    .filter(|&p| p != i),
+            })
+            .collect();
+        let mut children = vec![Vec::new(); messages.len()];
+        let mut roots = Vec::new();
+        for (i, parent) in parents.iter().enumerate() {
+            match parent {
+                Some(p) => children[*p].push(i),
+                None => roots.push(i),
+            }
+        }
+        Self {
+            messages,
+            parents,
+            children,
+            roots,
+            index,
+        }
+    }
+
+    pub fn find(&self, id: &str) -> Option<usize> {
+        self.index.get(id).copied()
+    }
+
+    pub fn parent(&self, i: usize) -> Option<usize> {
+        self.parents[i]
+    }
+
+    pub fn parent_id(&self, i: usize) -> Option<&'a str> {
+        self.parents[i].and_then(|p| message_id(&self.messages[p]))
+    }
+
+    /// Children of a message (or the roots for `None`) in creation order
+    pub fn children(&self, parent: Option<usize>) -> &[usize] {
+        match parent {
+            Some(p) => &self.children[p],
+            None => &self.roots,
+        }
+    }
+
+    /// Message indices from the root down to `leaf`
+    pub fn path_to(&self, leaf: usize) -> Vec<usize> {
+        let mut path = vec![leaf];
+        let mut visited = vec![false; self.messages.len()];
+        visited[leaf] = true;
+        let mut current = leaf;
+        while let Some(parent) = self.parents[current] {
+            // Guard against cycles introduced by hand-edited files
+            if visited[parent] {
+                break;
+            }
+            visited[parent] = true;
+            path.push(parent);
+            current = parent;
+        }
+        path.reverse();
+        path
+    }
+
+    /// Follow the newest child down to a leaf
+    pub fn newest_leaf(&self, from: usize) -> usize {
+        let mut current = from;
+        let mut visited = vec![false; self.messages.len()];
+        visited[from] = true;
+        while let Some(&child) = self.children[current].last() {
+            if visited[child] {
+                break;
+            }
+            visited[child] = true;
+            current = child;
+        }
+        current
+    }
+
+    /// `root` and all of its descendants
+    pub fn subtree(&self, root: usize) -> Vec<usize> {
+        let mut result = vec![root];
+        let mut visited = vec![false; self.messages.len()];
+        visited[root] = true;
+        let mut cursor = 0;
+        while cursor < result.len() {
+            for &child in &self.children[result[cursor]] {
+                if !visited[child] {
+                    visited[child] = true;
+                    result.push(child);
+                }
+            }
+            cursor += 1;
+        }
+        result
+    }
+
+    /// Leaf of the active branch; falls back to the newest message
+    pub fn active_leaf(&self, state: &BranchState) -> Option<usize> {
+        state
+            .active_leaf_id
+            .as_deref()
+            .and_then(|id| self.find(id))
+            .or_else(|| self.messages.len().checked_sub(1))
+    }
+
+    pub fn active_path(&self, state: &BranchState) -> Vec<usize> {
+        self.active_leaf(state)
+            .map(|leaf| self.path_to(leaf))
+            .unwrap_or_default()
+    }
+
+    pub fn view(&self, state: &BranchState) -> MessageTreeView {
+        let nodes = (0..self.messages.len())
+            .filter_map(|i| {
+                let id = message_id(&self.messages[i])?;
+                Some(MessageNode {
+                    id: id.to_string(),
+                    parent_id: self.parent_id(i).map(str::to_string),
+                    role: self.messages[i]
+                        .get("role")
+                        .and_then(|r| r.as_str())
+                        .map(str::to_string),
+                    children: self.children[i]
+                        .iter()
+                        .filter_map(|&c| message_id(&self.messages[c]).map(str::to_string))
+                        .collect(),
+                })
+            })
+            .collect();
+        MessageTreeView {
+            nodes,
+            active_path: self
+                .active_path(state)
+                .into_iter()
+                .filter_map(|i| message_id(&self.messages[i]).map(str::to_string))
+                .collect(),
+        }
+    }
+}
+
+/// Messages on the active branch, root first
+pub fn active_messages(messages: &[Value], state: &BranchState) -> Vec<Value> {
+    MessageTree::build(messages)
+        .active_path(state)
+        .into_iter()
+        .map(|i| messages[i].clone())
+        .collect()
+}
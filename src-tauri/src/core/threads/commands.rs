@@ -1,14 +1,27 @@
 use std::fs::{self, File};
 use std::io::Write;
-use tauri::Runtime;
+use tauri::{Manager, Runtime};
 use uuid::Uuid;
 
+use super::archive::{
+    clear_archived, is_archived, mark_archived, read_archive, read_manifest, remove_archive,
+    write_archive, ArchiveManifest,
+};
+use super::branches::{
+    active_messages, message_id, read_branch_state, write_branch_state, BranchState,
+    MessageBranches, MessageTree, MessageTreeView,
+};
 #[cfg(any(target_os = "android", target_os = "ios"))]
 use super::db;
+use super::export::render_thread_html;
 use super::helpers::{
     get_lock_for_thread, read_messages_from_file, should_use_sqlite, update_thread_metadata,
     write_messages_to_file,
 };
+use super::private::{
+    check_passphrase, new_key, open_messages, private_key_of, seal_for, seal_message,
+    set_private_key, unlock_threads, PrivateVault,
+};
 use super::{
     constants::THREADS_FILE,
     utils::{
@@ -17,6 +30,18 @@ use super::{
     },
 };
 use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::bookmarks::helpers::{forget_message, forget_thread};
+use crate::core::cancellation::models::CancelScope;
+use crate::core::config_store::helpers::write_atomic;
+use crate::core::inference::reasoning::store_reasoning;
+use crate::core::language::helpers::forget_thread_language;
+use crate::core::mcp::roots::notify_roots_changed;
+use crate::core::settings::helpers::reasoning_settings;
+use crate::core::state::AppState;
+use crate::core::thread_summaries::helpers::{
+    get_thread_summary_path, read_thread_summary, schedule_thread_summary,
+};
+use crate::core::workspaces::helpers::unbind_thread;
 
 /// Lists all threads by reading their metadata from the threads directory or database.
 /// Returns a vector of thread metadata as JSON values.
@@ -93,8 +118,19 @@ pub async fn create_thread<R: Runtime>(
 #[tauri::command]
 pub async fn modify_thread<R: Runtime>(
     app_handle: tauri::AppHandle<R>,
-    thread: serde_json::Value,
+    mut thread: serde_json::Value,
 ) -> Result<(), String> {
+    // Threads only become private or public through `set_thread_private`, which also
+    // seals or opens their messages
+    if let Some(thread_id) = thread.get("id").and_then(|id| id.as_str()) {
+        let data_folder = get_jan_data_folder_path(app_handle.clone());
+        if let Ok(stored) = read_thread_metadata(&data_folder, thread_id).await {
+            let private = private_key_of(&stored);
+            if private != private_key_of(&thread) {
+                set_private_key(&mut thread, private.as_ref());
+            }
+        }
+    }
     if should_use_sqlite() {
         #[cfg(any(target_os = "android", target_os = "ios"))]
         return db::db_modify_thread(app_handle, thread).await;
@@ -122,51 +158,93 @@ pub async fn delete_thread<R: Runtime>(
     app_handle: tauri::AppHandle<R>,
     thread_id: String,
 ) -> Result<(), String> {
+    // Runs still writing into the thread stop with it
+    if let Some(state) = app_handle.try_state::<AppState>() {
+        state
+            .cancellations
+            .cancel(&CancelScope::Thread(thread_id.clone()));
+    }
     if should_use_sqlite() {
         #[cfg(any(target_os = "android", target_os = "ios"))]
-        return db::db_delete_thread(app_handle, &thread_id).await;
+        {
+            let data_folder = get_jan_data_folder_path(app_handle.clone());
+            if let Err(e) = remove_archive(&data_folder, &thread_id) {
+                log::warn!("Failed to remove the archive of thread {thread_id}: {e}");
+            }
+            return db::db_delete_thread(app_handle, &thread_id).await;
+        }
     }
 
     // Use file-based storage on desktop
-    let data_folder = get_jan_data_folder_path(app_handle);
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
     let thread_dir = get_thread_dir(&data_folder, &thread_id);
     if thread_dir.exists() {
         let _ = fs::remove_dir_all(thread_dir);
     }
+    match unbind_thread(&data_folder, &thread_id).await {
+        Ok(true) => {
+            if let Some(state) = app_handle.try_state::<AppState>() {
+                notify_roots_changed(&state.mcp_servers).await;
+            }
+        }
+        Ok(false) => {}
+        Err(e) => log::warn!("Failed to remove workspace of thread {thread_id}: {e}"),
+    }
+    if let Err(e) = forget_thread(&data_folder, &thread_id) {
+        log::warn!("Failed to remove bookmarks of thread {thread_id}: {e}");
+    }
+    if let Err(e) = forget_thread_language(&data_folder, &thread_id).await {
+        log::warn!("Failed to remove the language of thread {thread_id}: {e}");
+    }
     Ok(())
 }
 
-/// Lists all messages for a given thread by reading and parsing its messages.jsonl file.
-/// Returns a vector of message JSON values.
+/// Lists the messages on the active branch of a thread by reading and parsing its messages.jsonl file.
+/// Returns a vector of message JSON values, root first. Archived threads are rehydrated first.
 #[tauri::command]
 pub async fn list_messages<R: Runtime>(
     app_handle: tauri::AppHandle<R>,
     thread_id: String,
 ) -> Result<Vec<serde_json::Value>, String> {
+    rehydrate_thread(&app_handle, &thread_id).await?;
+    let key = thread_key(&app_handle, &thread_id).await?;
     if should_use_sqlite() {
         #[cfg(any(target_os = "android", target_os = "ios"))]
-        return db::db_list_messages(app_handle, &thread_id).await;
+        return open_messages(
+            key.as_ref(),
+            db::db_list_messages(app_handle, &thread_id).await?,
+        );
     }
 
     // Use file-based storage on desktop
     let data_folder = get_jan_data_folder_path(app_handle);
-    read_messages_from_file(&data_folder, &thread_id)
+    let messages = read_messages_from_file(&data_folder, &thread_id)?;
+    let state = read_branch_state(&data_folder, &thread_id);
+    open_messages(key.as_ref(), active_messages(&messages, &state))
 }
 
 /// Appends a new message to a thread's messages.jsonl file.
+/// Without a `parent_id` the message continues the active branch; with one it starts a new
+/// branch under that parent (regeneration or edit). Either way it becomes the active leaf.
 /// Uses a per-thread async lock to prevent race conditions and ensure file consistency.
+/// Saving an assistant reply may start a background update of the thread summary.
 #[tauri::command]
 pub async fn create_message<R: Runtime>(
     app_handle: tauri::AppHandle<R>,
     mut message: serde_json::Value,
 ) -> Result<serde_json::Value, String> {
+    store_reasoning(&mut message, reasoning_settings(&app_handle).retention);
+    let key = message_thread_key(&app_handle, &message).await?;
     if should_use_sqlite() {
         #[cfg(any(target_os = "android", target_os = "ios"))]
-        return db::db_create_message(app_handle, message).await;
+        {
+            db::db_create_message(app_handle, seal_for(key.as_ref(), &message)?).await?;
+            return Ok(message);
+        }
     }
 
     // Use file-based storage on desktop
-    let data_folder = get_jan_data_folder_path(app_handle);
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
     let thread_id = {
         let id = message
             .get("thread_id")
@@ -189,19 +267,43 @@ pub async fn create_message<R: Runtime>(
         // Ensure directory exists right before file operations to handle race conditions
         ensure_thread_dir_exists(&data_folder, &thread_id)?;
 
+        if message.get("parent_id").is_none() {
+            let messages = read_messages_from_file(&data_folder, &thread_id)?;
+            let tree = MessageTree::build(&messages);
+            let state = read_branch_state(&data_folder, &thread_id);
+            message["parent_id"] = tree
+                .active_leaf(&state)
+                .and_then(|leaf| message_id(&messages[leaf]))
+                .map(|id| serde_json::Value::String(id.to_string()))
+                .unwrap_or(serde_json::Value::Null);
+        }
+
         let mut file: File = fs::OpenOptions::new()
             .create(true)
             .append(true)
             .open(path)
             .map_err(|e| e.to_string())?;
 
-        let data = serde_json::to_string(&message).map_err(|e| e.to_string())?;
+        let data =
+            serde_json::to_string(&seal_for(key.as_ref(), &message)?).map_err(|e| e.to_string())?;
         writeln!(file, "{data}").map_err(|e| e.to_string())?;
 
         // Explicitly flush to ensure data is written before returning
         file.flush().map_err(|e| e.to_string())?;
+
+        write_branch_state(
+            &data_folder,
+            &thread_id,
+            &BranchState {
+                active_leaf_id: message_id(&message).map(str::to_string),
+            },
+        )?;
     }
 
+    // Private threads are never summarized
+    if key.is_none() && message.get("role").and_then(|r| r.as_str()) == Some("assistant") {
+        schedule_thread_summary(&app_handle, &thread_id);
+    }
     Ok(message)
 }
 
@@ -211,11 +313,16 @@ pub async fn create_message<R: Runtime>(
 #[tauri::command]
 pub async fn modify_message<R: Runtime>(
     app_handle: tauri::AppHandle<R>,
-    message: serde_json::Value,
+    mut message: serde_json::Value,
 ) -> Result<serde_json::Value, String> {
+    store_reasoning(&mut message, reasoning_settings(&app_handle).retention);
+    let key = message_thread_key(&app_handle, &message).await?;
     if should_use_sqlite() {
         #[cfg(any(target_os = "android", target_os = "ios"))]
-        return db::db_modify_message(app_handle, message).await;
+        {
+            db::db_modify_message(app_handle, seal_for(key.as_ref(), &message)?).await?;
+            return Ok(message);
+        }
     }
 
     // Use file-based storage on desktop
@@ -223,26 +330,34 @@ pub async fn modify_message<R: Runtime>(
     let thread_id = message
         .get("thread_id")
         .and_then(|v| v.as_str())
-        .ok_or("Missing thread_id")?;
+        .ok_or("Missing thread_id")?
+        .to_string();
     let message_id = message
         .get("id")
         .and_then(|v| v.as_str())
-        .ok_or("Missing message id")?;
+        .ok_or("Missing message id")?
+        .to_string();
 
     // Acquire per-thread lock before modifying
     {
-        let lock = get_lock_for_thread(thread_id).await;
+        let lock = get_lock_for_thread(&thread_id).await;
         let _guard = lock.lock().await;
 
-        let mut messages = read_messages_from_file(&data_folder, thread_id)?;
+        let mut messages = read_messages_from_file(&data_folder, &thread_id)?;
         if let Some(index) = messages
             .iter()
-            .position(|m| m.get("id").and_then(|v| v.as_str()) == Some(message_id))
+            .position(|m| m.get("id").and_then(|v| v.as_str()) == Some(message_id.as_str()))
         {
-            messages[index] = message.clone();
+            // Keep the message attached to its branch when the caller omits the parent
+            if message.get("parent_id").is_none() {
+                if let Some(parent_id) = messages[index].get("parent_id") {
+                    message["parent_id"] = parent_id.clone();
+                }
+            }
+            messages[index] = seal_for(key.as_ref(), &message)?;
 
             // Rewrite all messages
-            let path = get_messages_path(&data_folder, thread_id);
+            let path = get_messages_path(&data_folder, &thread_id);
             write_messages_to_file(&messages, &path)?;
         }
     }
@@ -250,6 +365,7 @@ pub async fn modify_message<R: Runtime>(
 }
 
 /// Deletes a message from a thread's messages.jsonl file by message ID.
+/// Its children are reattached to its parent so the rest of the branch survives.
 /// Rewrites the entire messages.jsonl file for the thread.
 /// Uses a per-thread async lock to prevent race conditions and ensure file consistency.
 #[tauri::command]
@@ -258,6 +374,7 @@ pub async fn delete_message<R: Runtime>(
     thread_id: String,
     message_id: String,
 ) -> Result<(), String> {
+    rehydrate_thread(&app_handle, &thread_id).await?;
     if should_use_sqlite() {
         #[cfg(any(target_os = "android", target_os = "ios"))]
         return db::db_delete_message(app_handle, &thread_id, &message_id).await;
@@ -271,16 +388,469 @@ pub async fn delete_message<R: Runtime>(
         let _guard = lock.lock().await;
 
         let mut messages = read_messages_from_file(&data_folder, &thread_id)?;
-        messages.retain(|m| m.get("id").and_then(|v| v.as_str()) != Some(message_id.as_str()));
+        let tree = MessageTree::build(&messages);
+        let Some(index) = tree.find(&message_id) else {
+            return Ok(());
+        };
+        let parent_id = tree.parent_id(index).map(str::to_string);
+        let children = tree.children(Some(index)).to_vec();
+        let mut state = read_branch_state(&data_folder, &thread_id);
+
+        let parent_value = parent_id
+            .clone()
+            .map(serde_json::Value::String)
+            .unwrap_or(serde_json::Value::Null);
+        for child in children {
+            messages[child]["parent_id"] = parent_value.clone();
+        }
+        messages.remove(index);
 
         // Rewrite remaining messages
         let path = get_messages_path(&data_folder, &thread_id);
         write_messages_to_file(&messages, &path)?;
+
+        if state.active_leaf_id.as_deref() == Some(message_id.as_str()) {
+            state.active_leaf_id = parent_id;
+            write_branch_state(&data_folder, &thread_id, &state)?;
+        }
+    }
+
+    if let Err(e) = forget_message(&data_folder, &thread_id, &message_id) {
+        log::warn!("Failed to remove the pin of message {message_id}: {e}");
+    }
+    Ok(())
+}
+
+/// Returns the message tree of a thread together with the active path.
+#[tauri::command]
+pub async fn get_message_tree<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: String,
+) -> Result<MessageTreeView, String> {
+    ensure_branching_supported()?;
+    rehydrate_thread(&app_handle, &thread_id).await?;
+    let data_folder = get_jan_data_folder_path(app_handle);
+    let messages = read_messages_from_file(&data_folder, &thread_id)?;
+    let state = read_branch_state(&data_folder, &thread_id);
+    Ok(MessageTree::build(&messages).view(&state))
+}
+
+/// Lists the alternatives of a message: itself and its siblings under the same parent,
+/// so regenerations and edits can be compared side by side.
+#[tauri::command]
+pub async fn list_message_branches<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: String,
+    message_id: String,
+) -> Result<MessageBranches, String> {
+    ensure_branching_supported()?;
+    rehydrate_thread(&app_handle, &thread_id).await?;
+    let key = thread_key(&app_handle, &thread_id).await?;
+    let data_folder = get_jan_data_folder_path(app_handle);
+    let messages = read_messages_from_file(&data_folder, &thread_id)?;
+    let tree = MessageTree::build(&messages);
+    let index = tree
+        .find(&message_id)
+        .ok_or_else(|| format!("Message {message_id} not found in thread {thread_id}"))?;
+
+    let siblings = tree.children(tree.parent(index));
+    let active_path = tree.active_path(&read_branch_state(&data_folder, &thread_id));
+    Ok(MessageBranches {
+        parent_id: tree.parent_id(index).map(str::to_string),
+        active_index: siblings.iter().position(|s| active_path.contains(s)),
+        alternatives: open_messages(
+            key.as_ref(),
+            siblings.iter().map(|&i| messages[i].clone()).collect(),
+        )?,
+    })
+}
+
+/// Makes the branch through `message_id` active, following the newest replies below it.
+/// Returns the messages of the new active path.
+#[tauri::command]
+pub async fn switch_message_branch<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: String,
+    message_id: String,
+) -> Result<Vec<serde_json::Value>, String> {
+    ensure_branching_supported()?;
+    rehydrate_thread(&app_handle, &thread_id).await?;
+    let key = thread_key(&app_handle, &thread_id).await?;
+    let data_folder = get_jan_data_folder_path(app_handle);
+    let lock = get_lock_for_thread(&thread_id).await;
+    let _guard = lock.lock().await;
+
+    let messages = read_messages_from_file(&data_folder, &thread_id)?;
+    let tree = MessageTree::build(&messages);
+    let index = tree
+        .find(&message_id)
+        .ok_or_else(|| format!("Message {message_id} not found in thread {thread_id}"))?;
+    let state = BranchState {
+        active_leaf_id: message_id_of(&messages, tree.newest_leaf(index)),
+    };
+    write_branch_state(&data_folder, &thread_id, &state)?;
+    open_messages(key.as_ref(), active_messages(&messages, &state))
+}
+
+/// Deletes `message_id` together with every reply below it. When the active branch is
+/// removed, the newest remaining sibling branch (or the parent) becomes active.
+/// Returns the messages of the active path after pruning.
+#[tauri::command]
+pub async fn prune_message_branch<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: String,
+    message_id: String,
+) -> Result<Vec<serde_json::Value>, String> {
+    ensure_branching_supported()?;
+    rehydrate_thread(&app_handle, &thread_id).await?;
+    let key = thread_key(&app_handle, &thread_id).await?;
+    let data_folder = get_jan_data_folder_path(app_handle);
+    let lock = get_lock_for_thread(&thread_id).await;
+    let _guard = lock.lock().await;
+
+    let messages = read_messages_from_file(&data_folder, &thread_id)?;
+    let tree = MessageTree::build(&messages);
+    let index = tree
+        .find(&message_id)
+        .ok_or_else(|| format!("Message {message_id} not found in thread {thread_id}"))?;
+    let removed = tree.subtree(index);
+    let mut state = read_branch_state(&data_folder, &thread_id);
+
+    let active_removed = tree
+        .active_leaf(&state)
+        .map_or(true, |leaf| removed.contains(&leaf));
+    if active_removed {
+        let parent = tree.parent(index);
+        let fallback = tree
+            .children(parent)
+            .iter()
+            .rev()
+            .find(|&&s| s != index)
+            .map(|&sibling| tree.newest_leaf(sibling))
+            .or(parent);
+        state.active_leaf_id = fallback.and_then(|i| message_id_of(&messages, i));
+    }
+
+    let remaining: Vec<serde_json::Value> = messages
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !removed.contains(i))
+        .map(|(_, m)| m.clone())
+        .collect();
+    write_messages_to_file(&remaining, &get_messages_path(&data_folder, &thread_id))?;
+    write_branch_state(&data_folder, &thread_id, &state)?;
+    open_messages(key.as_ref(), active_messages(&remaining, &state))
+}
+
+fn message_id_of(messages: &[serde_json::Value], index: usize) -> Option<String> {
+    message_id(&messages[index]).map(str::to_string)
+}
+
+fn ensure_branching_supported() -> Result<(), String> {
+    if should_use_sqlite() {
+        return Err("Message branches are not supported with database storage".to_string());
+    }
+    Ok(())
+}
+
+/// Reads a thread's metadata from thread.json or the database
+async fn read_thread_metadata(
+    data_folder: &std::path::Path,
+    thread_id: &str,
+) -> Result<serde_json::Value, String> {
+    if should_use_sqlite() {
+        #[cfg(any(target_os = "android", target_os = "ios"))]
+        return db::db_get_thread(thread_id).await;
+    }
+    let path = get_thread_metadata_path(data_folder, thread_id);
+    if !path.exists() {
+        return Err("Thread not found".to_string());
     }
+    let data = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&data).map_err(|e| e.to_string())
+}
+
+async fn write_thread_metadata<R: Runtime>(
+    _app_handle: &tauri::AppHandle<R>,
+    data_folder: &std::path::Path,
+    thread_id: &str,
+    thread: serde_json::Value,
+) -> Result<(), String> {
+    if should_use_sqlite() {
+        #[cfg(any(target_os = "android", target_os = "ios"))]
+        return db::db_modify_thread(_app_handle.clone(), thread).await;
+    }
+    update_thread_metadata(data_folder, thread_id, &thread)
+}
+
+/// Every message of a thread in hot storage, all branches included
+async fn read_hot_messages<R: Runtime>(
+    _app_handle: &tauri::AppHandle<R>,
+    data_folder: &std::path::Path,
+    thread_id: &str,
+) -> Result<Vec<serde_json::Value>, String> {
+    if should_use_sqlite() {
+        #[cfg(any(target_os = "android", target_os = "ios"))]
+        return db::db_list_messages(_app_handle.clone(), thread_id).await;
+    }
+    read_messages_from_file(data_folder, thread_id)
+}
 
+async fn clear_hot_messages(data_folder: &std::path::Path, thread_id: &str) -> Result<(), String> {
+    if should_use_sqlite() {
+        #[cfg(any(target_os = "android", target_os = "ios"))]
+        return db::db_delete_thread_messages(thread_id).await;
+    }
+    let path = get_messages_path(data_folder, thread_id);
+    if path.exists() {
+        fs::remove_file(path).map_err(|e| e.to_string())?;
+    }
     Ok(())
 }
 
+async fn restore_hot_messages(
+    data_folder: &std::path::Path,
+    thread_id: &str,
+    messages: &[serde_json::Value],
+) -> Result<(), String> {
+    if should_use_sqlite() {
+        #[cfg(any(target_os = "android", target_os = "ios"))]
+        {
+            db::db_delete_thread_messages(thread_id).await?;
+            return db::db_restore_messages(thread_id, messages).await;
+        }
+    }
+    ensure_thread_dir_exists(data_folder, thread_id)?;
+    write_messages_to_file(messages, &get_messages_path(data_folder, thread_id))
+}
+
+/// Key of a private thread, `None` for any other thread
+async fn thread_key<R: Runtime>(
+    app_handle: &tauri::AppHandle<R>,
+    thread_id: &str,
+) -> Result<Option<[u8; 32]>, String> {
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+    let Ok(thread) = read_thread_metadata(&data_folder, thread_id).await else {
+        return Ok(None);
+    };
+    let Some(private) = private_key_of(&thread) else {
+        return Ok(None);
+    };
+    let vault = app_handle
+        .try_state::<PrivateVault>()
+        .ok_or("Private threads are locked, enter the passphrase first")?;
+    vault.key_for(thread_id, &private).map(Some)
+}
+
+/// Rehydrates the thread `message` belongs to and returns its key
+async fn message_thread_key<R: Runtime>(
+    app_handle: &tauri::AppHandle<R>,
+    message: &serde_json::Value,
+) -> Result<Option<[u8; 32]>, String> {
+    let Some(thread_id) = message.get("thread_id").and_then(|v| v.as_str()) else {
+        return Ok(None);
+    };
+    rehydrate_thread(app_handle, thread_id).await?;
+    thread_key(app_handle, thread_id).await
+}
+
+/// Moves the messages of an archived thread back into hot storage and drops its archive.
+/// Returns whether the thread was archived.
+async fn rehydrate_thread<R: Runtime>(
+    app_handle: &tauri::AppHandle<R>,
+    thread_id: &str,
+) -> Result<bool, String> {
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+    if !is_archived(&data_folder, thread_id) {
+        return Ok(false);
+    }
+    let lock = get_lock_for_thread(thread_id).await;
+    let _guard = lock.lock().await;
+    // Another caller may have rehydrated it while we waited for the lock
+    if !is_archived(&data_folder, thread_id) {
+        return Ok(false);
+    }
+
+    let messages = read_archive(&data_folder, thread_id)?;
+    restore_hot_messages(&data_folder, thread_id, &messages).await?;
+    match read_thread_metadata(&data_folder, thread_id).await {
+        Ok(mut thread) => {
+            if clear_archived(&mut thread) {
+                write_thread_metadata(app_handle, &data_folder, thread_id, thread).await?;
+            }
+        }
+        Err(e) => log::warn!("Failed to read thread {thread_id} while rehydrating it: {e}"),
+    }
+    remove_archive(&data_folder, thread_id)?;
+    log::info!(
+        "Rehydrated {} messages of archived thread {thread_id}",
+        messages.len()
+    );
+    Ok(true)
+}
+
+/// Moves the messages of a thread into compressed cold storage. The thread keeps its title
+/// and, under `metadata.archived`, its summary; its messages come back transparently the next
+/// time they are listed or written. Archiving an archived thread returns its manifest.
+#[tauri::command]
+pub async fn archive_thread<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: String,
+) -> Result<ArchiveManifest, String> {
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+    let lock = get_lock_for_thread(&thread_id).await;
+    let _guard = lock.lock().await;
+    if is_archived(&data_folder, &thread_id) {
+        return read_manifest(&data_folder, &thread_id);
+    }
+
+    let mut thread = read_thread_metadata(&data_folder, &thread_id).await?;
+    let messages = read_hot_messages(&app_handle, &data_folder, &thread_id).await?;
+    let manifest = write_archive(
+        &data_folder,
+        &thread_id,
+        &messages,
+        chrono::Utc::now().timestamp_millis(),
+    )?;
+    let summary = read_thread_summary(&data_folder, &thread_id).map(|record| record.summary);
+    mark_archived(&mut thread, &manifest, summary.as_deref());
+    write_thread_metadata(&app_handle, &data_folder, &thread_id, thread).await?;
+    // Hot messages go last: until then an interruption leaves both copies, never neither
+    clear_hot_messages(&data_folder, &thread_id).await?;
+    Ok(manifest)
+}
+
+/// Brings an archived thread back into hot storage right away instead of on first access.
+/// Returns whether the thread was archived.
+#[tauri::command]
+pub async fn unarchive_thread<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: String,
+) -> Result<bool, String> {
+    rehydrate_thread(&app_handle, &thread_id).await
+}
+
+/// Unlocks private threads for this session. The passphrase is checked against every
+/// private thread and fails when it opens none of them. Returns the ids of the private
+/// threads it doesn't open, which stay locked.
+#[tauri::command]
+pub async fn unlock_private_threads<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    passphrase: String,
+) -> Result<Vec<String>, String> {
+    check_passphrase(&passphrase)?;
+    let threads = list_threads(app_handle.clone()).await?;
+    let checked = passphrase.clone();
+    // Deriving a key per thread is deliberately slow
+    let (keys, mismatched) =
+        tokio::task::spawn_blocking(move || unlock_threads(&threads, &checked))
+            .await
+            .map_err(|e| e.to_string())?;
+    if keys.is_empty() && !mismatched.is_empty() {
+        return Err("Wrong passphrase for private threads".to_string());
+    }
+    if !mismatched.is_empty() {
+        log::warn!(
+            "The private thread passphrase doesn't open the threads {}",
+            mismatched.join(", ")
+        );
+    }
+    app_handle.state::<PrivateVault>().unlock(&passphrase, keys);
+    Ok(mismatched)
+}
+
+/// Forgets the passphrase and the keys of private threads until they are unlocked again
+#[tauri::command]
+pub fn lock_private_threads<R: Runtime>(app_handle: tauri::AppHandle<R>) {
+    app_handle.state::<PrivateVault>().lock();
+}
+
+#[tauri::command]
+pub fn are_private_threads_unlocked<R: Runtime>(app_handle: tauri::AppHandle<R>) -> bool {
+    app_handle.state::<PrivateVault>().is_unlocked()
+}
+
+/// Makes a thread private, sealing its messages with a key derived from the unlocked
+/// passphrase, or public again, opening them. The thread summary is deleted when a thread
+/// becomes private. Returns the updated thread.
+#[tauri::command]
+pub async fn set_thread_private<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: String,
+    private: bool,
+) -> Result<serde_json::Value, String> {
+    let vault = app_handle.state::<PrivateVault>().inner().clone();
+    let passphrase = vault.passphrase()?;
+    rehydrate_thread(&app_handle, &thread_id).await?;
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+    let lock = get_lock_for_thread(&thread_id).await;
+    let _guard = lock.lock().await;
+
+    let mut thread = read_thread_metadata(&data_folder, &thread_id).await?;
+    let current = private_key_of(&thread);
+    if current.is_some() == private {
+        return Ok(thread);
+    }
+    let messages = read_hot_messages(&app_handle, &data_folder, &thread_id).await?;
+    match current {
+        None => {
+            let (key, derived) = new_key(&passphrase)?;
+            let sealed = messages
+                .iter()
+                .map(|message| seal_message(&derived, message))
+                .collect::<Result<Vec<_>, String>>()?;
+            // Metadata first: messages not sealed yet still read as they are
+            set_private_key(&mut thread, Some(&key));
+            write_thread_metadata(&app_handle, &data_folder, &thread_id, thread.clone()).await?;
+            restore_hot_messages(&data_folder, &thread_id, &sealed).await?;
+            let summary = get_thread_summary_path(&data_folder, &thread_id);
+            if summary.exists() {
+                fs::remove_file(summary).map_err(|e| e.to_string())?;
+            }
+        }
+        Some(key) => {
+            let derived = vault.key_for(&thread_id, &key)?;
+            let opened = open_messages(Some(&derived), messages)?;
+            // Messages first: opened messages read the same while the thread is still private
+            restore_hot_messages(&data_folder, &thread_id, &opened).await?;
+            set_private_key(&mut thread, None);
+            write_thread_metadata(&app_handle, &data_folder, &thread_id, thread.clone()).await?;
+        }
+    }
+    vault.forget(&thread_id);
+    Ok(thread)
+}
+
+/// Renders the active branch of a thread into a self-contained HTML file at `path`.
+/// Private threads are only exported when `include_private` is set.
+/// Returns the size of the written file in bytes.
+#[tauri::command]
+pub async fn export_thread_html<R: Runtime>(
+    app_handle: tauri::AppHandle<R>,
+    thread_id: String,
+    path: String,
+    include_private: Option<bool>,
+) -> Result<u64, String> {
+    if should_use_sqlite() {
+        return Err("Thread export is not supported with database storage".to_string());
+    }
+    rehydrate_thread(&app_handle, &thread_id).await?;
+    let key = thread_key(&app_handle, &thread_id).await?;
+    if key.is_some() && !include_private.unwrap_or(false) {
+        return Err("Private threads are not exported unless explicitly included".to_string());
+    }
+    let data_folder = get_jan_data_folder_path(app_handle);
+    let metadata = fs::read_to_string(get_thread_metadata_path(&data_folder, &thread_id))
+        .map_err(|e| format!("Failed to read thread {thread_id}: {e}"))?;
+    let thread: serde_json::Value = serde_json::from_str(&metadata).map_err(|e| e.to_string())?;
+    let messages = read_messages_from_file(&data_folder, &thread_id)?;
+    let state = read_branch_state(&data_folder, &thread_id);
+    let messages = open_messages(key.as_ref(), active_messages(&messages, &state))?;
+    let html = render_thread_html(&thread, &messages, &data_folder)?;
+    write_atomic(std::path::Path::new(&path), html.as_bytes())?;
+    Ok(html.len() as u64)
+}
+
 /// Retrieves the first assistant associated with a thread.
 /// Returns an error if the thread or assistant is not found.
 #[tauri::command]
@@ -2,3 +2,41 @@
 pub const THREADS_DIR: &str = "threads";
 pub const THREADS_FILE: &str = "thread.json";
 pub const MESSAGES_FILE: &str = "messages.jsonl";
+pub const BRANCHES_FILE: &str = "branches.json";
+
+// Thread export limits
+/// Largest exported HTML page
+pub const EXPORT_MAX_HTML_BYTES: usize = 50 * 1024 * 1024;
+/// Largest single image embedded into an export
+pub const EXPORT_MAX_ATTACHMENT_BYTES: usize = 10 * 1024 * 1024;
+/// Total size of the `data:` URLs embedded into one export
+pub const EXPORT_MAX_INLINED_BYTES: usize = 40 * 1024 * 1024;
+/// Tool inputs and outputs are cut to this many characters
+pub const EXPORT_MAX_TOOL_OUTPUT_CHARS: usize = 20_000;
+
+// Thread archive
+/// Directory of the cold segments inside a thread directory
+pub const ARCHIVE_DIR: &str = "archive";
+pub const ARCHIVE_MANIFEST_FILE: &str = "archive.json";
+/// Messages per compressed segment
+pub const ARCHIVE_SEGMENT_MESSAGES: usize = 1000;
+pub const ARCHIVE_ZSTD_LEVEL: i32 = 12;
+
+// Private threads
+/// Marks message content sealed by this format, followed by the nonce and the ciphertext
+pub const PRIVATE_SEALED_MAGIC: &[u8] = b"JANPRIV1";
+pub const PRIVATE_KEY_DERIVATION_ROUNDS: u32 = 210_000;
+/// Sealed into `metadata.private` to tell a wrong passphrase from a damaged message
+pub const PRIVATE_KEY_CHECK_PLAINTEXT: &[u8] = b"jan-private-thread-check";
+pub const PRIVATE_MIN_PASSPHRASE_CHARS: usize = 8;
+/// Message fields left readable in private threads, enough to build the branch tree
+pub const PRIVATE_PLAIN_MESSAGE_FIELDS: &[&str] = &[
+    "id",
+    "object",
+    "thread_id",
+    "parent_id",
+    "role",
+    "status",
+    "created_at",
+    "completed_at",
+];
@@ -94,6 +94,8 @@ pub async fn init_database<R: Runtime>(app: &AppHandle<R>) -> Result<(), String>
         .await
         .map_err(|e| format!("Failed to create created_at index: {}", e))?;
 
+    crate::core::bookmarks::db::create_tables(&pool).await?;
+
     // Store pool globally
     DB_POOL
         .get_or_init(|| Mutex::new(None))
@@ -106,7 +108,7 @@ pub async fn init_database<R: Runtime>(app: &AppHandle<R>) -> Result<(), String>
 }
 
 /// Get database pool
-async fn get_pool() -> Result<SqlitePool, String> {
+pub(crate) async fn get_pool() -> Result<SqlitePool, String> {
     let pool_mutex = DB_POOL.get().ok_or("Database not initialized")?;
 
     let pool_guard = pool_mutex.lock().await;
@@ -207,12 +209,13 @@ pub async fn db_list_messages<R: Runtime>(
 ) -> Result<Vec<Value>, String> {
     let pool = get_pool().await?;
 
-    let rows =
-        sqlx::query("SELECT data FROM messages WHERE thread_id = ?1 ORDER BY created_at ASC")
-            .bind(thread_id)
-            .fetch_all(&pool)
-            .await
-            .map_err(|e| format!("Failed to list messages: {}", e))?;
+    let rows = sqlx::query(
+        "SELECT data FROM messages WHERE thread_id = ?1 ORDER BY created_at ASC, rowid ASC",
+    )
+    .bind(thread_id)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| format!("Failed to list messages: {}", e))?;
 
     let messages: Result<Vec<Value>, _> = rows
         .iter()
@@ -225,6 +228,59 @@ pub async fn db_list_messages<R: Runtime>(
     messages
 }
 
+/// Get a thread's metadata from database
+pub async fn db_get_thread(thread_id: &str) -> Result<Value, String> {
+    let pool = get_pool().await?;
+
+    let row = sqlx::query("SELECT data FROM threads WHERE id = ?1")
+        .bind(thread_id)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| format!("Failed to get thread: {}", e))?
+        .ok_or("Thread not found")?;
+
+    let data: String = row.get("data");
+    serde_json::from_str(&data).map_err(|e| e.to_string())
+}
+
+/// Delete all messages of a thread, e.g. once they are moved to cold storage
+pub async fn db_delete_thread_messages(thread_id: &str) -> Result<(), String> {
+    let pool = get_pool().await?;
+
+    sqlx::query("DELETE FROM messages WHERE thread_id = ?1")
+        .bind(thread_id)
+        .execute(&pool)
+        .await
+        .map_err(|e| format!("Failed to delete messages: {}", e))?;
+
+    Ok(())
+}
+
+/// Insert the messages of a thread in order, in one transaction
+pub async fn db_restore_messages(thread_id: &str, messages: &[Value]) -> Result<(), String> {
+    let pool = get_pool().await?;
+    let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+    for message in messages {
+        let message_id = message
+            .get("id")
+            .and_then(|v| v.as_str())
+            .ok_or("Missing message id")?;
+        let data = serde_json::to_string(message).map_err(|e| e.to_string())?;
+
+        sqlx::query("INSERT OR REPLACE INTO messages (id, thread_id, data) VALUES (?1, ?2, ?3)")
+            .bind(message_id)
+            .bind(thread_id)
+            .bind(&data)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to restore message: {}", e))?;
+    }
+
+    tx.commit().await.map_err(|e| e.to_string())?;
+    Ok(())
+}
+
 /// Create a new message in database
 pub async fn db_create_message<R: Runtime>(
     _app_handle: AppHandle<R>,
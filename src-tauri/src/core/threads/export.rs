@@ -0,0 +1,399 @@
+/*!
+   Static HTML export of a thread
+
+   A thread is rendered into one self-contained page that can be shared without Jan: styles
+   are inlined, reasoning and tool calls become collapsible `<details>` blocks, and images are
+   embedded as `data:` URLs. Every piece of message text is escaped and the page carries a
+   content security policy that forbids scripts and remote loads, so a conversation cannot
+   smuggle active content into the export. Tool output, embedded images and the page as a
+   whole are capped (see `constants`).
+*/
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use base64::Engine;
+use serde_json::Value;
+
+use super::constants::{
+    EXPORT_MAX_ATTACHMENT_BYTES, EXPORT_MAX_HTML_BYTES, EXPORT_MAX_INLINED_BYTES,
+    EXPORT_MAX_TOOL_OUTPUT_CHARS,
+};
+
+const PAGE_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta http-equiv="Content-Security-Policy" content="default-src 'none'; img-src data:; style-src 'unsafe-inline'">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<meta name="generator" content="Jan">
+<title>{{title}}</title>
+<style>
+body { margin: 0 auto; max-width: 860px; padding: 24px; font: 15px/1.6 system-ui, sans-serif; color: #1f2328; background: #fff; }
+h1 { font-size: 22px; margin: 0 0 4px; }
+.exported { color: #656d76; font-size: 13px; margin-bottom: 24px; }
+.message { border: 1px solid #d0d7de; border-radius: 8px; padding: 12px 16px; margin: 12px 0; }
+.message.user { background: #f6f8fa; }
+.message header { display: flex; justify-content: space-between; color: #656d76; font-size: 13px; margin-bottom: 6px; }
+.role { font-weight: 600; text-transform: capitalize; }
+pre { background: #f6f8fa; border-radius: 6px; padding: 10px; overflow-x: auto; white-space: pre-wrap; word-break: break-word; }
+details { border-left: 3px solid #d0d7de; padding-left: 10px; margin: 8px 0; }
+summary { cursor: pointer; color: #656d76; }
+img { max-width: 100%; border-radius: 6px; }
+.omitted { color: #9a6700; font-style: italic; }
+</style>
+</head>
+<body>
+<h1>{{title}}</h1>
+<div class="exported">Exported from Jan on {{exported}}</div>
+{{messages}}
+</body>
+</html>
+"#;
+
+/// Fill `{{key}}` placeholders of `template` in a single pass, so substituted values are
+/// never scanned for further placeholders
+pub fn fill_template(template: &str, values: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let value = after.find("}}").and_then(|end| {
+            let key = &after[..end];
+            values
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, v)| (*v, end + 2))
+        });
+        match value {
+            Some((value, consumed)) => {
+                out.push_str(value);
+                rest = &after[consumed..];
+            }
+            None => {
+                out.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+pub fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Separate a leading `<think>…</think>` block from the answer text
+pub fn split_reasoning(text: &str) -> (Option<&str>, &str) {
+    let trimmed = text.trim_start();
+    let Some(inner) = trimmed.strip_prefix("<think>") else {
+        return (None, text);
+    };
+    match inner.find("</think>") {
+        Some(end) => (
+            Some(inner[..end].trim()),
+            inner[end + "</think>".len()..].trim_start(),
+        ),
+        // Still thinking when the message was saved
+        None => (Some(inner.trim()), ""),
+    }
+}
+
+/// Escape `text` and lay it out as paragraphs, keeping fenced code blocks verbatim
+pub fn render_text(text: &str) -> String {
+    let mut out = String::new();
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut code: Option<Vec<&str>> = None;
+
+    let flush = |paragraph: &mut Vec<&str>, out: &mut String| {
+        if !paragraph.is_empty() {
+            let lines: Vec<String> = paragraph.iter().map(|l| escape_html(l)).collect();
+            out.push_str(&format!("<p>{}</p>\n", lines.join("<br>\n")));
+            paragraph.clear();
+        }
+    };
+
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            match code.take() {
+                Some(lines) => {
+                    out.push_str(&format!(
+                        "<pre><code>{}</code></pre>\n",
+                        escape_html(&lines.join("\n"))
+                    ));
+                }
+                None => {
+                    flush(&mut paragraph, &mut out);
+                    code = Some(Vec::new());
+                }
+            }
+        } else if let Some(lines) = code.as_mut() {
+            lines.push(line);
+        } else if line.trim().is_empty() {
+            flush(&mut paragraph, &mut out);
+        } else {
+            paragraph.push(line);
+        }
+    }
+    // An unterminated fence still renders as code
+    if let Some(lines) = code {
+        out.push_str(&format!(
+            "<pre><code>{}</code></pre>\n",
+            escape_html(&lines.join("\n"))
+        ));
+    }
+    flush(&mut paragraph, &mut out);
+    out
+}
+
+/// Cut `text` to `max` characters, noting how much was dropped
+pub fn truncate_chars(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((cut, _)) => {
+            let omitted = text[cut..].chars().count();
+            format!("{}\n… ({omitted} more characters omitted)", &text[..cut])
+        }
+        None => text.to_string(),
+    }
+}
+
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => serde_json::to_string_pretty(other).unwrap_or_default(),
+    }
+}
+
+/// MIME type of an image that is safe to embed; SVG is excluded since it can carry script
+fn image_mime(extension: &str) -> Option<&'static str> {
+    match extension.to_ascii_lowercase().as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// Images embedded so far, bounded by `EXPORT_MAX_INLINED_BYTES`
+struct Inliner<'a> {
+    data_folder: &'a Path,
+    remaining: usize,
+}
+
+impl Inliner<'_> {
+    /// `data:` URL for the image at `url`, or why it was left out
+    fn inline(&mut self, url: &str) -> Result<String, &'static str> {
+        let data_url = if let Some(data) = url.strip_prefix("data:") {
+            let (header, payload) = data.split_once(',').ok_or("invalid image")?;
+            let mime = header.strip_suffix(";base64").ok_or("invalid image")?;
+            let allowed = mime
+                .strip_prefix("image/")
+                .and_then(image_mime)
+                .is_some_and(|m| m == mime);
+            let valid = payload
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'+' | b'/' | b'='));
+            if !allowed || !valid {
+                return Err("unsupported image");
+            }
+            if payload.len() / 4 * 3 > EXPORT_MAX_ATTACHMENT_BYTES {
+                return Err("image too large");
+            }
+            url.to_string()
+        } else if url.starts_with("http://") || url.starts_with("https://") {
+            return Err("remote image");
+        } else {
+            let path = self.local_path(url).ok_or("image not found")?;
+            let mime = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .and_then(image_mime)
+                .ok_or("unsupported image")?;
+            let size = fs::metadata(&path).map_err(|_| "image not found")?.len();
+            if size > EXPORT_MAX_ATTACHMENT_BYTES as u64 {
+                return Err("image too large");
+            }
+            let bytes = fs::read(&path).map_err(|_| "image not found")?;
+            let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+            format!("data:{mime};base64,{encoded}")
+        };
+        if data_url.len() > self.remaining {
+            return Err("attachment budget exhausted");
+        }
+        self.remaining -= data_url.len();
+        Ok(data_url)
+    }
+
+    /// Resolve a stored attachment path; only files inside the data folder are embedded
+    fn local_path(&self, url: &str) -> Option<PathBuf> {
+        let raw = url.strip_prefix("file://").unwrap_or(url);
+        let path = Path::new(raw);
+        let path = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.data_folder.join(path)
+        };
+        let path = path.canonicalize().ok()?;
+        let root = self.data_folder.canonicalize().ok()?;
+        path.starts_with(root).then_some(path)
+    }
+}
+
+fn details(class: &str, summary: &str, body: &str) -> String {
+    format!(
+        "<details class=\"{class}\"><summary>{}</summary>\n{body}</details>\n",
+        escape_html(summary)
+    )
+}
+
+fn tool_block(name: &str, input: Option<&Value>, output: Option<&Value>) -> String {
+    let mut body = String::new();
+    for (label, value) in [("Input", input), ("Output", output)] {
+        let Some(value) = value.filter(|v| !v.is_null()) else {
+            continue;
+        };
+        let text = truncate_chars(&value_text(value), EXPORT_MAX_TOOL_OUTPUT_CHARS);
+        body.push_str(&format!(
+            "<div>{label}</div><pre>{}</pre>\n",
+            escape_html(&text)
+        ));
+    }
+    details("tool", &format!("Tool call: {name}"), &body)
+}
+
+fn render_part(part: &Value, inliner: &mut Inliner) -> String {
+    match part.get("type").and_then(Value::as_str) {
+        Some("text") => {
+            let text = match part.get("text") {
+                Some(Value::String(s)) => s.as_str(),
+                Some(text) => text.get("value").and_then(Value::as_str).unwrap_or(""),
+                None => "",
+            };
+            let (reasoning, answer) = split_reasoning(text);
+            let mut out = reasoning
+                .filter(|r| !r.is_empty())
+                .map(|r| details("reasoning", "Reasoning", &render_text(r)))
+                .unwrap_or_default();
+            out.push_str(&render_text(answer));
+            out
+        }
+        Some("reasoning") => {
+            let text = part
+                .get("text")
+                .and_then(|t| t.get("value").or(Some(t)))
+                .and_then(Value::as_str)
+                .unwrap_or("");
+            details("reasoning", "Reasoning", &render_text(text))
+        }
+        Some("image_url") => {
+            let url = part
+                .get("image_url")
+                .and_then(|i| i.get("url"))
+                .and_then(Value::as_str)
+                .unwrap_or("");
+            match inliner.inline(url) {
+                Ok(data_url) => format!(
+                    "<p><img src=\"{}\" alt=\"image\"></p>\n",
+                    escape_html(&data_url)
+                ),
+                Err(reason) => format!("<p class=\"omitted\">[Image omitted: {reason}]</p>\n"),
+            }
+        }
+        Some("tool_call") => tool_block(
+            part.get("tool_name")
+                .and_then(Value::as_str)
+                .unwrap_or("tool"),
+            part.get("input"),
+            part.get("output"),
+        ),
+        _ => String::new(),
+    }
+}
+
+/// Format a message timestamp, stored either in seconds or in milliseconds
+fn format_timestamp(value: Option<&Value>) -> String {
+    let Some(ts) = value.and_then(Value::as_i64).filter(|ts| *ts > 0) else {
+        return String::new();
+    };
+    let millis = if ts < 100_000_000_000 { ts * 1000 } else { ts };
+    chrono::DateTime::from_timestamp_millis(millis)
+        .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_default()
+}
+
+fn render_message(message: &Value, inliner: &mut Inliner) -> String {
+    let role = message
+        .get("role")
+        .and_then(Value::as_str)
+        .unwrap_or("assistant");
+    let body = if role == "tool" {
+        // A tool result sent back to the model
+        let id = message.get("tool_call_id").and_then(Value::as_str);
+        tool_block(id.unwrap_or("result"), None, message.get("content"))
+    } else {
+        match message.get("content") {
+            Some(Value::Array(parts)) => parts.iter().map(|p| render_part(p, inliner)).collect(),
+            Some(Value::String(text)) => render_text(text),
+            _ => String::new(),
+        }
+    };
+    let role_class: String = role.chars().filter(char::is_ascii_alphanumeric).collect();
+    format!(
+        "<section class=\"message {role_class}\">\n<header><span class=\"role\">{}</span><time>{}</time></header>\n{body}</section>\n",
+        escape_html(role),
+        format_timestamp(message.get("created_at")),
+    )
+}
+
+/// Render `thread` and its `messages` (root first) into a standalone HTML page. Images are
+/// resolved against `data_folder`. Fails when the page exceeds `EXPORT_MAX_HTML_BYTES`.
+pub fn render_thread_html(
+    thread: &Value,
+    messages: &[Value],
+    data_folder: &Path,
+) -> Result<String, String> {
+    let title = thread
+        .get("title")
+        .and_then(Value::as_str)
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or("Untitled thread");
+    let mut inliner = Inliner {
+        data_folder,
+        remaining: EXPORT_MAX_INLINED_BYTES,
+    };
+    let body: String = messages
+        .iter()
+        .map(|m| render_message(m, &mut inliner))
+        .collect();
+    let exported = chrono::Utc::now().format("%Y-%m-%d %H:%M UTC").to_string();
+    let html = fill_template(
+        PAGE_TEMPLATE,
+        &[
+            ("title", &escape_html(title)),
+            ("exported", &exported),
+            ("messages", &body),
+        ],
+    );
+    if html.len() > EXPORT_MAX_HTML_BYTES {
+        return Err(format!(
+            "Exported thread is {} bytes, more than the {EXPORT_MAX_HTML_BYTES} byte limit",
+            html.len()
+        ));
+    }
+    Ok(html)
+}
@@ -3,6 +3,11 @@
 
    This module provides all logic for managing threads and their messages, including creation, modification, deletion, and listing.
    Messages for each thread are persisted in a JSONL file (messages.jsonl) per thread directory.
+   Regenerations and edits are kept as branches of a message tree (see `branches`); listing messages
+   returns the active branch. `export` renders a thread into a standalone HTML page for sharing.
+   Archived threads keep their messages in compressed cold storage (see `archive`) and are
+   rehydrated the first time their messages are accessed again. Messages of private threads
+   are stored encrypted (see `private`).
 
    **Concurrency and Consistency Guarantee:**
    - All operations that write or modify messages for a thread are protected by a global, per-thread asynchronous lock.
@@ -10,11 +15,15 @@
    - As a result, the messages.jsonl file for each thread is always consistent and never corrupted, even under concurrent access.
 */
 
+pub mod archive;
+pub mod branches;
 pub mod commands;
 pub mod constants;
 #[cfg(any(target_os = "android", target_os = "ios"))]
 pub mod db;
+pub mod export;
 pub mod helpers;
+pub mod private;
 pub mod utils;
 
 #[cfg(test)]
@@ -0,0 +1,246 @@
+/*!
+   Private threads
+
+   A thread marked private stores its messages encrypted with XChaCha20-Poly1305. Each private
+   thread has its own key, derived from the user's private-thread passphrase with
+   PBKDF2-HMAC-SHA256 and a salt kept in the thread metadata (`metadata.private`) next to a
+   sealed check value, so a wrong passphrase is told apart from a damaged message. The key
+   only lives in memory while private threads are unlocked.
+
+   Only the content of a message is sealed: its id, parent, role, status and timestamps stay
+   readable so the branch tree can be built without the key. Messages are sealed and opened
+   by the thread commands, so the files, the database rows, archives and anything reading
+   storage directly only ever see ciphertext. Private threads are left out of exports and
+   sync unless asked for, and are never summarized.
+*/
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::constants::{
+    PRIVATE_KEY_CHECK_PLAINTEXT, PRIVATE_KEY_DERIVATION_ROUNDS, PRIVATE_MIN_PASSPHRASE_CHARS,
+    PRIVATE_PLAIN_MESSAGE_FIELDS, PRIVATE_SEALED_MAGIC,
+};
+use super::utils::get_thread_metadata_path;
+use crate::core::sync::crypto::Sealing;
+
+const PRIVATE_SEALING: Sealing = Sealing {
+    magic: PRIVATE_SEALED_MAGIC,
+    rounds: PRIVATE_KEY_DERIVATION_ROUNDS,
+    check_plaintext: PRIVATE_KEY_CHECK_PLAINTEXT,
+    what: "private message",
+};
+
+/// `metadata.private` of a private thread
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrivateThreadKey {
+    pub salt: String,
+    pub check: String,
+}
+
+#[derive(Default)]
+struct VaultState {
+    passphrase: Option<String>,
+    /// Derived keys by thread id
+    keys: HashMap<String, [u8; 32]>,
+}
+
+/// Passphrase of the private threads while they are unlocked
+#[derive(Clone, Default)]
+pub struct PrivateVault(Arc<Mutex<VaultState>>);
+
+impl PrivateVault {
+    fn state(&self) -> std::sync::MutexGuard<'_, VaultState> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Unlock with `passphrase` and the keys already derived with it, by thread id
+    pub fn unlock(&self, passphrase: &str, keys: HashMap<String, [u8; 32]>) {
+        let mut state = self.state();
+        state.passphrase = Some(passphrase.to_string());
+        state.keys = keys;
+    }
+
+    pub fn lock(&self) {
+        let mut state = self.state();
+        state.passphrase = None;
+        state.keys.clear();
+    }
+
+    pub fn is_unlocked(&self) -> bool {
+        self.state().passphrase.is_some()
+    }
+
+    pub fn passphrase(&self) -> Result<String, String> {
+        self.state()
+            .passphrase
+            .clone()
+            .ok_or_else(|| "Private threads are locked, enter the passphrase first".to_string())
+    }
+
+    /// Key of a private thread, derived once per unlock
+    pub fn key_for(&self, thread_id: &str, private: &PrivateThreadKey) -> Result<[u8; 32], String> {
+        if let Some(key) = self.state().keys.get(thread_id) {
+            return Ok(*key);
+        }
+        let key = unlock_key(private, &self.passphrase()?)?;
+        self.state().keys.insert(thread_id.to_string(), key);
+        Ok(key)
+    }
+
+    pub fn forget(&self, thread_id: &str) {
+        self.state().keys.remove(thread_id);
+    }
+}
+
+pub fn check_passphrase(passphrase: &str) -> Result<(), String> {
+    if passphrase.chars().count() < PRIVATE_MIN_PASSPHRASE_CHARS {
+        return Err(format!(
+            "The private thread passphrase needs at least {PRIVATE_MIN_PASSPHRASE_CHARS} characters"
+        ));
+    }
+    Ok(())
+}
+
+pub fn seal(key: &[u8; 32], plaintext: &[u8]) -> Result<Vec<u8>, String> {
+    PRIVATE_SEALING.seal(key, plaintext)
+}
+
+pub fn open(key: &[u8; 32], sealed: &[u8]) -> Result<Vec<u8>, String> {
+    PRIVATE_SEALING.open(key, sealed)
+}
+
+/// A fresh salt and check value for `passphrase`, and the key they derive
+pub fn new_key(passphrase: &str) -> Result<(PrivateThreadKey, [u8; 32]), String> {
+    let (salt, check, key) = PRIVATE_SEALING.new_check(passphrase)?;
+    Ok((PrivateThreadKey { salt, check }, key))
+}
+
+/// The key `passphrase` derives for a thread, if it is the passphrase the thread was sealed with
+pub fn unlock_key(private: &PrivateThreadKey, passphrase: &str) -> Result<[u8; 32], String> {
+    let salt = hex::decode(&private.salt).map_err(|_| "Invalid salt of private thread")?;
+    let check = hex::decode(&private.check).map_err(|_| "Invalid check of private thread")?;
+    PRIVATE_SEALING
+        .check_key(passphrase, &salt, &check)
+        .ok_or_else(|| "Wrong passphrase for private threads".to_string())
+}
+
+/// Check `passphrase` against every private thread of `threads`. Returns the keys it derives
+/// by thread id, and the ids of the private threads it doesn't open.
+pub fn unlock_threads(
+    threads: &[Value],
+    passphrase: &str,
+) -> (HashMap<String, [u8; 32]>, Vec<String>) {
+    let mut keys = HashMap::new();
+    let mut mismatched = Vec::new();
+    for thread in threads {
+        let Some(private) = private_key_of(thread) else {
+            continue;
+        };
+        let id = thread
+            .get("id")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        match unlock_key(&private, passphrase) {
+            Ok(key) => {
+                keys.insert(id, key);
+            }
+            Err(_) => mismatched.push(id),
+        }
+    }
+    (keys, mismatched)
+}
+
+/// `metadata.private` of `thread`, if it is private
+pub fn private_key_of(thread: &Value) -> Option<PrivateThreadKey> {
+    thread
+        .get("metadata")
+        .and_then(|metadata| metadata.get("private"))
+        .and_then(|private| serde_json::from_value(private.clone()).ok())
+}
+
+pub fn set_private_key(thread: &mut Value, private: Option<&PrivateThreadKey>) {
+    if !thread.get("metadata").is_some_and(Value::is_object) {
+        thread["metadata"] = Value::Object(Default::default());
+    }
+    let Some(metadata) = thread["metadata"].as_object_mut() else {
+        return;
+    };
+    match private {
+        Some(private) => {
+            metadata.insert("private".to_string(), serde_json::json!(private));
+        }
+        None => {
+            metadata.remove("private");
+        }
+    }
+}
+
+/// Whether the thread stored in the data folder is private
+pub fn is_private_thread(data_folder: &Path, thread_id: &str) -> bool {
+    fs::read_to_string(get_thread_metadata_path(data_folder, thread_id))
+        .ok()
+        .and_then(|data| serde_json::from_str::<Value>(&data).ok())
+        .and_then(|thread| private_key_of(&thread))
+        .is_some()
+}
+
+pub fn is_sealed(message: &Value) -> bool {
+    message.get("sealed").is_some_and(Value::is_string)
+}
+
+/// `message` with everything but its structural fields sealed into `sealed`
+pub fn seal_message(key: &[u8; 32], message: &Value) -> Result<Value, String> {
+    let Some(fields) = message.as_object() else {
+        return Err("A message must be a JSON object".to_string());
+    };
+    if is_sealed(message) {
+        return Ok(message.clone());
+    }
+    let (plain, content): (Map<String, Value>, Map<String, Value>) = fields
+        .clone()
+        .into_iter()
+        .partition(|(name, _)| PRIVATE_PLAIN_MESSAGE_FIELDS.contains(&name.as_str()));
+    let data = serde_json::to_vec(&content).map_err(|e| e.to_string())?;
+    let mut sealed = plain;
+    sealed.insert(
+        "sealed".to_string(),
+        Value::String(hex::encode(seal(key, &data)?)),
+    );
+    Ok(Value::Object(sealed))
+}
+
+/// The readable form of a message. Messages written before the thread became private are
+/// returned as they are.
+pub fn open_message(key: &[u8; 32], message: &Value) -> Result<Value, String> {
+    let Some(sealed) = message.get("sealed").and_then(Value::as_str) else {
+        return Ok(message.clone());
+    };
+    let data = hex::decode(sealed).map_err(|_| "Invalid private message")?;
+    let content: Map<String, Value> =
+        serde_json::from_slice(&open(key, &data)?).map_err(|e| e.to_string())?;
+    let mut opened = message.as_object().cloned().unwrap_or_default();
+    opened.remove("sealed");
+    opened.extend(content);
+    Ok(Value::Object(opened))
+}
+
+pub fn open_messages(key: Option<&[u8; 32]>, messages: Vec<Value>) -> Result<Vec<Value>, String> {
+    match key {
+        Some(key) => messages.iter().map(|m| open_message(key, m)).collect(),
+        None => Ok(messages),
+    }
+}
+
+pub fn seal_for(key: Option<&[u8; 32]>, message: &Value) -> Result<Value, String> {
+    match key {
+        Some(key) => seal_message(key, message),
+        None => Ok(message.clone()),
+    }
+}
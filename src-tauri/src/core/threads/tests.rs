@@ -1,11 +1,20 @@
+use super::archive::{
+    clear_archived, decode_segment, encode_segment, get_archive_dir, is_archived, mark_archived,
+    ArchiveManifest,
+};
 use super::commands::*;
 use super::helpers::should_use_sqlite;
+use super::private::{
+    new_key, open_message, seal_message, set_private_key, unlock_threads, PrivateVault,
+};
+use super::utils::get_messages_path;
 use crate::core::app::commands::get_jan_data_folder_path;
 use futures_util::future;
 use serde_json::json;
 use std::fs;
 use std::path::PathBuf;
 use tauri::test::{mock_app, MockRuntime};
+use tauri::Manager;
 
 // Helper to create a mock app handle with a temp data dir
 fn mock_app_with_temp_data_dir() -> (tauri::App<MockRuntime>, PathBuf) {
@@ -502,3 +511,524 @@ async fn test_empty_message_list() {
 
     let _ = fs::remove_dir_all(data_dir);
 }
+
+// Helper to create a message with a role, optionally as a branch under `parent_id`
+async fn create_branch_message(
+    app: &tauri::App<MockRuntime>,
+    thread_id: &str,
+    role: &str,
+    text: &str,
+    parent_id: Option<&str>,
+) -> String {
+    let mut message = create_test_message(thread_id, text);
+    message["role"] = json!(role);
+    if let Some(parent_id) = parent_id {
+        message["parent_id"] = json!(parent_id);
+    }
+    let created = create_message(app.handle().clone(), message).await.unwrap();
+    created["id"].as_str().unwrap().to_string()
+}
+
+fn message_texts(messages: &[serde_json::Value]) -> Vec<String> {
+    messages
+        .iter()
+        .map(|m| m["content"][0]["text"].as_str().unwrap_or("").to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_regeneration_creates_branch() {
+    let (app, data_dir) = mock_app_with_temp_data_dir();
+    let created = create_thread(app.handle().clone(), create_test_thread("Branches"))
+        .await
+        .unwrap();
+    let thread_id = created["id"].as_str().unwrap().to_string();
+
+    let question = create_branch_message(&app, &thread_id, "user", "question", None).await;
+    let first = create_branch_message(&app, &thread_id, "assistant", "answer 1", None).await;
+    let second =
+        create_branch_message(&app, &thread_id, "assistant", "answer 2", Some(&question)).await;
+
+    // Only the newest regeneration is on the active path
+    let messages = list_messages(app.handle().clone(), thread_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(message_texts(&messages), vec!["question", "answer 2"]);
+
+    let branches = list_message_branches(app.handle().clone(), thread_id.clone(), first.clone())
+        .await
+        .unwrap();
+    assert_eq!(branches.parent_id.as_deref(), Some(question.as_str()));
+    assert_eq!(branches.alternatives.len(), 2);
+    assert_eq!(branches.active_index, Some(1));
+
+    // Switching back keeps both alternatives
+    let messages = switch_message_branch(app.handle().clone(), thread_id.clone(), first.clone())
+        .await
+        .unwrap();
+    assert_eq!(message_texts(&messages), vec!["question", "answer 1"]);
+
+    // New messages continue the active branch
+    create_branch_message(&app, &thread_id, "user", "follow up", None).await;
+    let tree = get_message_tree(app.handle().clone(), thread_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(tree.nodes.len(), 4);
+    assert_eq!(tree.active_path.len(), 3);
+    assert_eq!(tree.active_path[1], first);
+    assert!(!tree.active_path.contains(&second));
+
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_prune_branch_removes_descendants() {
+    let (app, data_dir) = mock_app_with_temp_data_dir();
+    let created = create_thread(app.handle().clone(), create_test_thread("Prune"))
+        .await
+        .unwrap();
+    let thread_id = created["id"].as_str().unwrap().to_string();
+
+    let question = create_branch_message(&app, &thread_id, "user", "question", None).await;
+    create_branch_message(&app, &thread_id, "assistant", "answer 1", None).await;
+    let second =
+        create_branch_message(&app, &thread_id, "assistant", "answer 2", Some(&question)).await;
+    create_branch_message(&app, &thread_id, "user", "follow up", None).await;
+
+    let messages = prune_message_branch(app.handle().clone(), thread_id.clone(), second)
+        .await
+        .unwrap();
+    // The active branch was removed, so the remaining sibling becomes active
+    assert_eq!(message_texts(&messages), vec!["question", "answer 1"]);
+
+    let tree = get_message_tree(app.handle().clone(), thread_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(tree.nodes.len(), 2);
+
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_delete_message_reattaches_children() {
+    let (app, data_dir) = mock_app_with_temp_data_dir();
+    let created = create_thread(app.handle().clone(), create_test_thread("Reattach"))
+        .await
+        .unwrap();
+    let thread_id = created["id"].as_str().unwrap().to_string();
+
+    create_branch_message(&app, &thread_id, "user", "one", None).await;
+    let two = create_branch_message(&app, &thread_id, "assistant", "two", None).await;
+    create_branch_message(&app, &thread_id, "user", "three", None).await;
+
+    delete_message(app.handle().clone(), thread_id.clone(), two)
+        .await
+        .unwrap();
+    let messages = list_messages(app.handle().clone(), thread_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(message_texts(&messages), vec!["one", "three"]);
+
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[test]
+fn test_legacy_messages_form_linear_path() {
+    use super::branches::{active_messages, BranchState};
+
+    // Messages written before branching have no parent_id
+    let messages = vec![
+        json!({"id": "a", "role": "user"}),
+        json!({"id": "b", "role": "assistant"}),
+        json!({"id": "c", "role": "user"}),
+    ];
+    let path = active_messages(&messages, &BranchState::default());
+    assert_eq!(path.len(), 3);
+    assert_eq!(path[2]["id"], "c");
+}
+
+#[test]
+fn test_message_tree_indexes_children() {
+    use super::branches::{BranchState, MessageTree};
+
+    // Two answers to one question, the second regenerated twice, plus a cycle by hand
+    let messages = vec![
+        json!({"id": "q", "role": "user", "parent_id": null}),
+        json!({"id": "a1", "role": "assistant", "parent_id": "q"}),
+        json!({"id": "a2", "role": "assistant", "parent_id": "q"}),
+        json!({"id": "f1", "role": "user", "parent_id": "a2"}),
+        json!({"id": "f2", "role": "user", "parent_id": "a2"}),
+        json!({"id": "x", "parent_id": "y"}),
+        json!({"id": "y", "parent_id": "x"}),
+    ];
+    let tree = MessageTree::build(&messages);
+    assert_eq!(tree.children(None), &[0]);
+    assert_eq!(tree.children(Some(0)), &[1, 2]);
+    assert_eq!(tree.children(Some(2)), &[3, 4]);
+    assert!(tree.children(Some(1)).is_empty());
+    assert_eq!(tree.newest_leaf(0), 4);
+    assert_eq!(tree.subtree(2), vec![2, 3, 4]);
+    assert_eq!(tree.path_to(4), vec![0, 2, 4]);
+    assert_eq!(tree.path_to(6), vec![5, 6]);
+    assert_eq!(tree.subtree(5), vec![5, 6]);
+
+    let view = tree.view(&BranchState {
+        active_leaf_id: Some("f1".to_string()),
+    });
+    assert_eq!(view.nodes[0].children, vec!["a1", "a2"]);
+    assert_eq!(view.active_path, vec!["q", "a2", "f1"]);
+}
+
+#[test]
+fn test_export_escapes_content_and_collapses_reasoning() {
+    use super::export::render_thread_html;
+
+    let thread = json!({"title": "<script>alert(1)</script>"});
+    let messages = vec![
+        json!({"role": "user", "content": [{"type": "text", "text": {"value": "hi <b>there</b>"}}]}),
+        json!({"role": "assistant", "created_at": 1_700_000_000, "content": [
+            {"type": "text", "text": {"value": "<think>plan</think>```\nlet x = 1 < 2;\n```"}},
+            {"type": "tool_call", "tool_name": "search", "input": {"q": "rust"}, "output": "ok"},
+            {"type": "image_url", "image_url": {"url": "https://example.com/cat.png"}},
+        ]}),
+    ];
+    let html = render_thread_html(&thread, &messages, &std::env::temp_dir()).unwrap();
+
+    assert!(!html.contains("<script>"));
+    assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+    assert!(html.contains("hi &lt;b&gt;there&lt;/b&gt;"));
+    assert!(html.contains("<details class=\"reasoning\"><summary>Reasoning</summary>\n<p>plan</p>"));
+    assert!(html.contains("<pre><code>let x = 1 &lt; 2;</code></pre>"));
+    assert!(html.contains("<summary>Tool call: search</summary>"));
+    assert!(html.contains("[Image omitted: remote image]"));
+    assert!(html.contains("2023-11-14"));
+}
+
+#[test]
+fn test_export_inlines_only_data_folder_images() {
+    use super::export::render_thread_html;
+
+    let data_dir = std::env::temp_dir().join(format!("jan-export-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(data_dir.join("files")).unwrap();
+    fs::write(data_dir.join("files/pic.png"), [0x89, b'P', b'N', b'G']).unwrap();
+
+    let image = |url: &str| json!({"role": "user", "content": [{"type": "image_url", "image_url": {"url": url}}]});
+    let messages = vec![
+        image("files/pic.png"),
+        image("../outside.png"),
+        image("data:image/svg+xml;base64,PHN2Zz4="),
+    ];
+    let html = render_thread_html(&json!({}), &messages, &data_dir).unwrap();
+
+    assert!(html.contains("<img src=\"data:image/png;base64,iVBORw==\""));
+    assert!(html.contains("[Image omitted: image not found]"));
+    assert!(html.contains("[Image omitted: unsupported image]"));
+    assert!(html.contains("<title>Untitled thread</title>"));
+
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[test]
+fn test_export_template_and_truncation() {
+    use super::export::{fill_template, truncate_chars};
+
+    // Substituted values are not expanded again
+    let filled = fill_template("{{a}} {{b}} {{c}}", &[("a", "{{b}}"), ("b", "2")]);
+    assert_eq!(filled, "{{b}} 2 {{c}}");
+
+    assert_eq!(truncate_chars("héllo", 10), "héllo");
+    assert_eq!(
+        truncate_chars("héllo", 2),
+        "hé\n… (3 more characters omitted)"
+    );
+}
+
+#[tokio::test]
+async fn test_export_thread_html_writes_active_branch() {
+    if should_use_sqlite() {
+        return;
+    }
+    let (app, data_dir) = mock_app_with_temp_data_dir();
+    let created = create_thread(app.handle().clone(), create_test_thread("Shared"))
+        .await
+        .unwrap();
+    let thread_id = created["id"].as_str().unwrap().to_string();
+    create_message(
+        app.handle().clone(),
+        create_test_message(&thread_id, "exported text"),
+    )
+    .await
+    .unwrap();
+
+    let out = data_dir.join(format!("export-{thread_id}.html"));
+    let size = export_thread_html(
+        app.handle().clone(),
+        thread_id.clone(),
+        out.to_string_lossy().into_owned(),
+        None,
+    )
+    .await
+    .unwrap();
+
+    let html = fs::read_to_string(&out).unwrap();
+    assert_eq!(size, html.len() as u64);
+    assert!(html.contains("<h1>Shared</h1>"));
+    assert!(html.contains("<p>exported text</p>"));
+
+    let _ = fs::remove_file(out);
+    let _ = delete_thread(app.handle().clone(), thread_id).await;
+}
+
+#[test]
+fn test_archive_segments_round_trip() {
+    let messages: Vec<serde_json::Value> = (0..3)
+        .map(|i| json!({"id": format!("m{i}"), "content": "é\nline"}))
+        .collect();
+    let (data, original) = encode_segment(&messages).unwrap();
+    assert!(original > 0);
+    assert_eq!(decode_segment(&data).unwrap(), messages);
+    assert!(decode_segment(b"not zstd").is_err());
+
+    let mut thread = create_test_thread("Archived");
+    let manifest = ArchiveManifest {
+        archived_at: 1,
+        message_count: 3,
+        original_bytes: original,
+        compressed_bytes: data.len() as u64,
+        segments: vec![],
+    };
+    mark_archived(&mut thread, &manifest, Some("a summary"));
+    assert_eq!(thread["metadata"]["archived"]["summary"], "a summary");
+    assert!(clear_archived(&mut thread));
+    assert!(!clear_archived(&mut thread));
+}
+
+#[tokio::test]
+async fn test_archived_thread_rehydrates_on_open() {
+    if should_use_sqlite() {
+        return;
+    }
+    let (app, data_dir) = mock_app_with_temp_data_dir();
+    let created = create_thread(app.handle().clone(), create_test_thread("Cold"))
+        .await
+        .unwrap();
+    let thread_id = created["id"].as_str().unwrap().to_string();
+    for text in ["first", "second"] {
+        create_message(app.handle().clone(), create_test_message(&thread_id, text))
+            .await
+            .unwrap();
+    }
+
+    let manifest = archive_thread(app.handle().clone(), thread_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(manifest.message_count, 2);
+    assert_eq!(manifest.segments.len(), 1);
+    assert!(is_archived(&data_dir, &thread_id));
+    assert!(!get_messages_path(&data_dir, &thread_id).exists());
+    let threads = list_threads(app.handle().clone()).await.unwrap();
+    let listed = threads
+        .iter()
+        .find(|t| t["id"] == thread_id.as_str())
+        .unwrap();
+    assert_eq!(listed["title"], "Cold");
+    assert_eq!(listed["metadata"]["archived"]["message_count"], 2);
+
+    // Archiving twice keeps the first archive
+    let again = archive_thread(app.handle().clone(), thread_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(again.archived_at, manifest.archived_at);
+
+    let messages = list_messages(app.handle().clone(), thread_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(message_texts(&messages), vec!["first", "second"]);
+    assert!(!is_archived(&data_dir, &thread_id));
+    let metadata = fs::read_to_string(
+        data_dir
+            .join("threads")
+            .join(&thread_id)
+            .join("thread.json"),
+    )
+    .unwrap();
+    assert!(!metadata.contains("archived"));
+
+    // A new message on an archived thread lands after the archived ones
+    archive_thread(app.handle().clone(), thread_id.clone())
+        .await
+        .unwrap();
+    create_message(
+        app.handle().clone(),
+        create_test_message(&thread_id, "third"),
+    )
+    .await
+    .unwrap();
+    let messages = list_messages(app.handle().clone(), thread_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(message_texts(&messages), vec!["first", "second", "third"]);
+    assert!(!unarchive_thread(app.handle().clone(), thread_id.clone())
+        .await
+        .unwrap());
+
+    let _ = delete_thread(app.handle().clone(), thread_id).await;
+}
+
+#[tokio::test]
+async fn test_corrupted_archive_is_not_rehydrated() {
+    if should_use_sqlite() {
+        return;
+    }
+    let (app, data_dir) = mock_app_with_temp_data_dir();
+    let created = create_thread(app.handle().clone(), create_test_thread("Broken"))
+        .await
+        .unwrap();
+    let thread_id = created["id"].as_str().unwrap().to_string();
+    create_message(
+        app.handle().clone(),
+        create_test_message(&thread_id, "kept"),
+    )
+    .await
+    .unwrap();
+    let manifest = archive_thread(app.handle().clone(), thread_id.clone())
+        .await
+        .unwrap();
+
+    let segment = get_archive_dir(&data_dir, &thread_id).join(&manifest.segments[0].file);
+    fs::write(&segment, b"garbage").unwrap();
+    let error = list_messages(app.handle().clone(), thread_id.clone())
+        .await
+        .unwrap_err();
+    assert!(error.contains("corrupted"), "{error}");
+    // The archive stays in place so nothing is lost
+    assert!(is_archived(&data_dir, &thread_id));
+
+    let _ = delete_thread(app.handle().clone(), thread_id).await;
+}
+
+#[test]
+fn test_private_messages_seal_content_only() {
+    let key = [7u8; 32];
+    let mut message = create_test_message("t1", "secret text");
+    message["id"] = json!("m1");
+    message["parent_id"] = json!(null);
+    let sealed = seal_message(&key, &message).unwrap();
+    assert!(!sealed.to_string().contains("secret text"));
+    assert_eq!(sealed["id"], "m1");
+    assert_eq!(sealed["role"], "user");
+    assert!(sealed.get("content").is_none());
+
+    assert_eq!(open_message(&key, &sealed).unwrap(), message);
+    assert!(open_message(&[8u8; 32], &sealed).is_err());
+    // Messages written before the thread became private read as they are
+    assert_eq!(open_message(&key, &message).unwrap(), message);
+}
+
+#[test]
+fn test_unlock_checks_every_private_thread() {
+    let thread = |id: &str, passphrase: Option<&str>| {
+        let mut thread = json!({ "id": id });
+        if let Some(passphrase) = passphrase {
+            set_private_key(&mut thread, Some(&new_key(passphrase).unwrap().0));
+        }
+        thread
+    };
+    let threads = vec![
+        thread("public", None),
+        thread("mine", Some("correct horse battery")),
+        thread("other", Some("another passphrase")),
+    ];
+    let (keys, mismatched) = unlock_threads(&threads, "correct horse battery");
+    assert_eq!(keys.keys().collect::<Vec<_>>(), vec!["mine"]);
+    assert_eq!(mismatched, vec!["other"]);
+}
+
+#[tokio::test]
+async fn test_private_thread_is_stored_encrypted() {
+    if should_use_sqlite() {
+        return;
+    }
+    let (app, data_dir) = mock_app_with_temp_data_dir();
+    app.manage(PrivateVault::default());
+    let created = create_thread(app.handle().clone(), create_test_thread("Private"))
+        .await
+        .unwrap();
+    let thread_id = created["id"].as_str().unwrap().to_string();
+    create_message(
+        app.handle().clone(),
+        create_test_message(&thread_id, "first secret"),
+    )
+    .await
+    .unwrap();
+    let raw = || fs::read_to_string(get_messages_path(&data_dir, &thread_id)).unwrap();
+
+    assert!(
+        set_thread_private(app.handle().clone(), thread_id.clone(), true)
+            .await
+            .is_err()
+    );
+    assert!(
+        unlock_private_threads(app.handle().clone(), "short".to_string())
+            .await
+            .is_err()
+    );
+    let passphrase = "correct horse battery".to_string();
+    unlock_private_threads(app.handle().clone(), passphrase.clone())
+        .await
+        .unwrap();
+    let thread = set_thread_private(app.handle().clone(), thread_id.clone(), true)
+        .await
+        .unwrap();
+    assert!(thread["metadata"]["private"]["salt"].is_string());
+    assert!(!raw().contains("first secret"));
+
+    create_message(
+        app.handle().clone(),
+        create_test_message(&thread_id, "second secret"),
+    )
+    .await
+    .unwrap();
+    assert!(!raw().contains("second secret"));
+    let messages = list_messages(app.handle().clone(), thread_id.clone())
+        .await
+        .unwrap();
+    assert_eq!(
+        message_texts(&messages),
+        vec!["first secret", "second secret"]
+    );
+
+    // A stale copy of the thread does not make it public again
+    modify_thread(app.handle().clone(), created.clone())
+        .await
+        .unwrap();
+    let out = data_dir.join(format!("export-{thread_id}.html"));
+    let export = export_thread_html(
+        app.handle().clone(),
+        thread_id.clone(),
+        out.to_string_lossy().into_owned(),
+        None,
+    )
+    .await;
+    assert!(export.is_err());
+
+    lock_private_threads(app.handle().clone());
+    assert!(list_messages(app.handle().clone(), thread_id.clone())
+        .await
+        .is_err());
+    assert!(
+        unlock_private_threads(app.handle().clone(), "wrong passphrase".to_string())
+            .await
+            .is_err()
+    );
+
+    unlock_private_threads(app.handle().clone(), passphrase)
+        .await
+        .unwrap();
+    set_thread_private(app.handle().clone(), thread_id.clone(), false)
+        .await
+        .unwrap();
+    assert!(raw().contains("second secret"));
+
+    let _ = delete_thread(app.handle().clone(), thread_id).await;
+}
@@ -1,7 +1,7 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use super::constants::{MESSAGES_FILE, THREADS_DIR, THREADS_FILE};
+use super::constants::{BRANCHES_FILE, MESSAGES_FILE, THREADS_DIR, THREADS_FILE};
 
 pub fn get_data_dir(data_folder: &Path) -> PathBuf {
     data_folder.join(THREADS_DIR)
@@ -35,3 +35,7 @@ pub fn ensure_thread_dir_exists(data_folder: &Path, thread_id: &str) -> Result<(
     }
     Ok(())
 }
+
+pub fn get_branches_path(data_folder: &Path, thread_id: &str) -> PathBuf {
+    get_thread_dir(data_folder, thread_id).join(BRANCHES_FILE)
+}
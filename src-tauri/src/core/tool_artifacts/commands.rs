@@ -0,0 +1,51 @@
+use tauri::{AppHandle, Runtime};
+
+use super::helpers::{delete_artifact, read_artifact_page, read_settings, write_settings};
+use super::models::{ArtifactPage, ToolArtifactSettings};
+use crate::core::app::commands::get_jan_data_folder_path;
+
+/// Returns characters `offset..offset + length` of a stored tool result.
+#[tauri::command]
+pub async fn fetch_tool_artifact<R: Runtime>(
+    app_handle: AppHandle<R>,
+    artifact_id: String,
+    offset: Option<usize>,
+    length: Option<usize>,
+) -> Result<ArtifactPage, String> {
+    read_artifact_page(
+        &get_jan_data_folder_path(app_handle),
+        &artifact_id,
+        offset.unwrap_or(0),
+        length,
+    )
+}
+
+/// Deletes a stored tool result.
+#[tauri::command]
+pub async fn delete_tool_artifact<R: Runtime>(
+    app_handle: AppHandle<R>,
+    artifact_id: String,
+) -> Result<(), String> {
+    delete_artifact(&get_jan_data_folder_path(app_handle), &artifact_id)
+}
+
+/// Returns the tool result offloading settings.
+#[tauri::command]
+pub async fn get_tool_artifact_settings<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<ToolArtifactSettings, String> {
+    Ok(read_settings(&get_jan_data_folder_path(app_handle)))
+}
+
+/// Replaces the tool result offloading settings.
+#[tauri::command]
+pub async fn update_tool_artifact_settings<R: Runtime>(
+    app_handle: AppHandle<R>,
+    settings: ToolArtifactSettings,
+) -> Result<ToolArtifactSettings, String> {
+    if settings.threshold_bytes == Some(0) || settings.preview_chars == Some(0) {
+        return Err("threshold_bytes and preview_chars must be greater than 0".to_string());
+    }
+    write_settings(&get_jan_data_folder_path(app_handle), &settings)?;
+    Ok(settings)
+}
@@ -0,0 +1,18 @@
+// Tool artifact constants
+pub const TOOL_ARTIFACTS_DIR: &str = "tool_artifacts";
+pub const TOOL_ARTIFACTS_SETTINGS_FILE: &str = "settings.json";
+
+/// Name of the built-in tool that reads stored artifacts
+pub const READ_ARTIFACT_TOOL: &str = "read_tool_artifact";
+
+/// Results larger than this many bytes are offloaded by default
+pub const DEFAULT_THRESHOLD_BYTES: usize = 32 * 1024;
+/// Characters of an offloaded result shown to the model by default
+pub const DEFAULT_PREVIEW_CHARS: usize = 4_000;
+/// Characters returned by one read of an artifact by default, and at most
+pub const DEFAULT_READ_CHARS: usize = 8_000;
+pub const MAX_READ_CHARS: usize = 20_000;
+/// Keys or items listed in the outline of a JSON result
+pub const OUTLINE_ITEMS: usize = 20;
+/// Oldest artifacts beyond this count are removed
+pub const MAX_ARTIFACTS: usize = 500;
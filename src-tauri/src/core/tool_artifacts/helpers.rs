@@ -0,0 +1,355 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+use super::constants::*;
+use super::models::{ArtifactPage, ToolArtifact, ToolArtifactSettings};
+use crate::core::mcp::models::{McpToolCallResult, ToolWithServer};
+
+pub fn get_artifacts_dir(data_folder: &Path) -> PathBuf {
+    data_folder.join(TOOL_ARTIFACTS_DIR)
+}
+
+pub fn get_settings_path(data_folder: &Path) -> PathBuf {
+    get_artifacts_dir(data_folder).join(TOOL_ARTIFACTS_SETTINGS_FILE)
+}
+
+pub fn read_settings(data_folder: &Path) -> ToolArtifactSettings {
+    fs::read_to_string(get_settings_path(data_folder))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+pub fn write_settings(data_folder: &Path, settings: &ToolArtifactSettings) -> Result<(), String> {
+    let path = get_settings_path(data_folder);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let data = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    fs::write(path, data).map_err(|e| e.to_string())
+}
+
+/// Paths of the metadata and the content of an artifact. Ids are uuids, so they can't
+/// escape the artifacts folder.
+fn artifact_paths(data_folder: &Path, id: &str) -> Result<(PathBuf, PathBuf), String> {
+    let id = Uuid::parse_str(id).map_err(|_| format!("Invalid artifact id '{id}'"))?;
+    let dir = get_artifacts_dir(data_folder);
+    Ok((
+        dir.join(format!("{id}.json")),
+        dir.join(format!("{id}.txt")),
+    ))
+}
+
+fn format_size(bytes: usize) -> String {
+    match bytes {
+        b if b >= 1024 * 1024 => format!("{:.1} MB", b as f64 / (1024.0 * 1024.0)),
+        b if b >= 1024 => format!("{:.1} KB", b as f64 / 1024.0),
+        b => format!("{b} bytes"),
+    }
+}
+
+fn listed_keys(object: &Map<String, Value>) -> String {
+    let mut keys: Vec<&str> = object
+        .keys()
+        .take(OUTLINE_ITEMS)
+        .map(String::as_str)
+        .collect();
+    if object.len() > OUTLINE_ITEMS {
+        keys.push("…");
+    }
+    keys.join(", ")
+}
+
+/// One-line description of the shape of a JSON result
+pub fn json_outline(value: &Value) -> String {
+    match value {
+        Value::Object(object) => format!(
+            "JSON object with {} keys: {}",
+            object.len(),
+            listed_keys(object)
+        ),
+        Value::Array(items) => match items.first() {
+            Some(Value::Object(first)) => format!(
+                "JSON array of {} items; the first item has the keys: {}",
+                items.len(),
+                listed_keys(first)
+            ),
+            _ => format!("JSON array of {} items", items.len()),
+        },
+        Value::String(text) => format!("JSON string of {} characters", text.chars().count()),
+        other => format!("JSON value {other}"),
+    }
+}
+
+/// Characters `offset..offset + length` of `text`
+fn char_range(text: &str, offset: usize, length: usize) -> &str {
+    let byte_at = |chars: usize| {
+        text.char_indices()
+            .nth(chars)
+            .map_or(text.len(), |(byte, _)| byte)
+    };
+    let start = byte_at(offset);
+    let end = byte_at(offset.saturating_add(length)).max(start);
+    &text[start..end]
+}
+
+/// Text the model gets instead of an offloaded result
+pub fn preview_text(artifact: &ToolArtifact, text: &str, preview_chars: usize) -> String {
+    let mut preview = format!(
+        "[The result of '{}' is {} ({} characters), too large to include in full. It was \
+         stored as artifact {}; call {READ_ARTIFACT_TOOL} with this artifact_id and an offset \
+         to read more of it.]",
+        artifact.tool,
+        format_size(artifact.size_bytes),
+        artifact.total_chars,
+        artifact.id
+    );
+    if artifact.is_json {
+        if let Ok(value) = serde_json::from_str::<Value>(text) {
+            preview.push_str(&format!("\n\nOutline: {}", json_outline(&value)));
+        }
+    }
+    let shown = char_range(text, 0, preview_chars);
+    preview.push_str(&format!(
+        "\n\nFirst {} characters:\n{shown}",
+        shown.chars().count()
+    ));
+    preview
+}
+
+/// Remove the oldest artifacts beyond `MAX_ARTIFACTS`
+fn prune_artifacts(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut artifacts: Vec<ToolArtifact> = entries
+        .flatten()
+        .filter(|e| e.file_name() != TOOL_ARTIFACTS_SETTINGS_FILE)
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|e| fs::read_to_string(e.path()).ok())
+        .filter_map(|data| serde_json::from_str(&data).ok())
+        .collect();
+    if artifacts.len() <= MAX_ARTIFACTS {
+        return;
+    }
+    artifacts.sort_by_key(|a| a.created_at);
+    let excess = artifacts.len() - MAX_ARTIFACTS;
+    for artifact in &artifacts[..excess] {
+        let _ = fs::remove_file(dir.join(format!("{}.txt", artifact.id)));
+        let _ = fs::remove_file(dir.join(format!("{}.json", artifact.id)));
+    }
+}
+
+/// Store a tool result and return its metadata
+pub fn store_artifact(
+    data_folder: &Path,
+    server: &str,
+    tool: &str,
+    call_id: Option<&str>,
+    text: &str,
+) -> Result<ToolArtifact, String> {
+    let artifact = ToolArtifact {
+        id: Uuid::new_v4().to_string(),
+        tool: tool.to_string(),
+        server: server.to_string(),
+        call_id: call_id.map(String::from),
+        size_bytes: text.len(),
+        total_chars: text.chars().count(),
+        is_json: serde_json::from_str::<Value>(text).is_ok(),
+        created_at: chrono::Utc::now().timestamp_millis(),
+    };
+    let (meta_path, content_path) = artifact_paths(data_folder, &artifact.id)?;
+    let dir = get_artifacts_dir(data_folder);
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    fs::write(&content_path, text).map_err(|e| format!("Failed to store tool result: {e}"))?;
+    let meta = serde_json::to_string_pretty(&artifact).map_err(|e| e.to_string())?;
+    fs::write(&meta_path, meta).map_err(|e| format!("Failed to store tool result: {e}"))?;
+    prune_artifacts(&dir);
+    Ok(artifact)
+}
+
+/// Offload `text` when it exceeds the threshold. Returns the text to give the model
+/// instead and the stored artifact, or `None` when the result is small enough.
+pub fn offload_if_large(
+    data_folder: &Path,
+    settings: &ToolArtifactSettings,
+    server: &str,
+    tool: &str,
+    call_id: Option<&str>,
+    text: &str,
+) -> Result<Option<(String, ToolArtifact)>, String> {
+    let threshold = settings.threshold_bytes.unwrap_or(DEFAULT_THRESHOLD_BYTES);
+    // Reads of an artifact are already bounded
+    if !settings.enabled || text.len() <= threshold || tool == READ_ARTIFACT_TOOL {
+        return Ok(None);
+    }
+    let artifact = store_artifact(data_folder, server, tool, call_id, text)?;
+    let preview_chars = settings.preview_chars.unwrap_or(DEFAULT_PREVIEW_CHARS);
+    Ok(Some((
+        preview_text(&artifact, text, preview_chars),
+        artifact,
+    )))
+}
+
+/// Text fed back to the model for a tool result, offloading it when large. Results are
+/// passed through unchanged when storing them fails.
+pub fn tool_result_for_model(
+    data_folder: &Path,
+    server: &str,
+    tool: &str,
+    call_id: &str,
+    text: String,
+) -> String {
+    let settings = read_settings(data_folder);
+    match offload_if_large(data_folder, &settings, server, tool, Some(call_id), &text) {
+        Ok(Some((preview, _))) => preview,
+        Ok(None) => text,
+        Err(e) => {
+            log::warn!("Failed to offload the result of '{tool}': {e}");
+            text
+        }
+    }
+}
+
+/// Offload the text blocks of a large MCP tool result. The blocks are replaced by one text
+/// block with the preview and `artifact_id` is set; other blocks such as images are kept.
+/// Structured content is dropped with them, since the artifact holds the full result.
+pub fn offload_call_result(
+    data_folder: &Path,
+    server: &str,
+    tool: &str,
+    mut result: McpToolCallResult,
+) -> McpToolCallResult {
+    if result.is_error == Some(true) {
+        return result;
+    }
+    let is_text = |block: &Value| block.get("type").and_then(Value::as_str) == Some("text");
+    let text = result
+        .content
+        .iter()
+        .filter(|block| is_text(block))
+        .filter_map(|block| block.get("text").and_then(Value::as_str))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let settings = read_settings(data_folder);
+    match offload_if_large(data_folder, &settings, server, tool, None, &text) {
+        Ok(Some((preview, artifact))) => {
+            result.content.retain(|block| !is_text(block));
+            result
+                .content
+                .insert(0, json!({"type": "text", "text": preview}));
+            result.structured_content = None;
+            result.artifact_id = Some(artifact.id);
+        }
+        Ok(None) => {}
+        Err(e) => log::warn!("Failed to offload the result of '{tool}': {e}"),
+    }
+    result
+}
+
+pub fn read_artifact(data_folder: &Path, id: &str) -> Result<(ToolArtifact, String), String> {
+    let (meta_path, content_path) = artifact_paths(data_folder, id)?;
+    let meta = fs::read_to_string(&meta_path).map_err(|_| format!("Artifact {id} not found"))?;
+    let artifact: ToolArtifact = serde_json::from_str(&meta).map_err(|e| e.to_string())?;
+    let content =
+        fs::read_to_string(&content_path).map_err(|_| format!("Artifact {id} not found"))?;
+    Ok((artifact, content))
+}
+
+/// Characters `offset..offset + length` of an artifact; `length` is capped at
+/// `MAX_READ_CHARS`
+pub fn read_artifact_page(
+    data_folder: &Path,
+    id: &str,
+    offset: usize,
+    length: Option<usize>,
+) -> Result<ArtifactPage, String> {
+    let (artifact, text) = read_artifact(data_folder, id)?;
+    if offset > artifact.total_chars {
+        return Err(format!(
+            "Offset {offset} is past the end of the artifact ({} characters)",
+            artifact.total_chars
+        ));
+    }
+    let length = length
+        .unwrap_or(DEFAULT_READ_CHARS)
+        .clamp(1, MAX_READ_CHARS);
+    let content = char_range(&text, offset, length).to_string();
+    let end = offset + content.chars().count();
+    Ok(ArtifactPage {
+        next_offset: (end < artifact.total_chars).then_some(end),
+        artifact,
+        offset,
+        content,
+    })
+}
+
+pub fn delete_artifact(data_folder: &Path, id: &str) -> Result<(), String> {
+    let (meta_path, content_path) = artifact_paths(data_folder, id)?;
+    if !meta_path.exists() {
+        return Err(format!("Artifact {id} not found"));
+    }
+    let _ = fs::remove_file(content_path);
+    fs::remove_file(meta_path).map_err(|e| e.to_string())
+}
+
+pub fn read_artifact_tool(server: &str) -> ToolWithServer {
+    ToolWithServer {
+        name: READ_ARTIFACT_TOOL.to_string(),
+        description: Some(
+            "Read more of a tool result that was too large to include in full, by the artifact \
+             id given in its preview."
+                .to_string(),
+        ),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "artifact_id": {"type": "string", "description": "Id of the stored result"},
+                "offset": {
+                    "type": "integer",
+                    "description": "Character to start reading from; defaults to 0"
+                },
+                "length": {
+                    "type": "integer",
+                    "description": format!(
+                        "Characters to read; defaults to {DEFAULT_READ_CHARS}, at most {MAX_READ_CHARS}"
+                    )
+                }
+            },
+            "required": ["artifact_id"]
+        }),
+        server: server.to_string(),
+    }
+}
+
+/// Run the `read_tool_artifact` tool and return the text fed back to the model
+pub fn call_read_artifact_tool(
+    data_folder: &Path,
+    arguments: &Map<String, Value>,
+) -> Result<String, String> {
+    let id = arguments
+        .get("artifact_id")
+        .and_then(Value::as_str)
+        .ok_or_else(|| "Missing 'artifact_id' argument".to_string())?;
+    let offset = arguments.get("offset").and_then(Value::as_u64).unwrap_or(0) as usize;
+    let length = arguments
+        .get("length")
+        .and_then(Value::as_u64)
+        .map(|l| l as usize);
+    let page = read_artifact_page(data_folder, id, offset, length)?;
+    let end = page.offset + page.content.chars().count();
+    let position = match page.next_offset {
+        Some(next) => format!(
+            "[Characters {}-{end} of {}. Continue with offset {next}.]",
+            page.offset, page.artifact.total_chars
+        ),
+        None => format!(
+            "[Characters {}-{end} of {}. End of the result.]",
+            page.offset, page.artifact.total_chars
+        ),
+    };
+    Ok(format!("{}\n\n{position}", page.content))
+}
@@ -0,0 +1,21 @@
+/*!
+   Tool Result Artifacts
+
+   Tool results can be far larger than a context window (a database MCP returning a few
+   megabytes of JSON). Results above a size threshold are offloaded:
+   - the full result is stored as an artifact under `tool_artifacts/`,
+   - the model gets a preview (an outline of JSON results and their beginning) plus the
+     artifact id,
+   - the built-in `read_tool_artifact` tool lets the model page through the rest, and
+     `fetch_tool_artifact` gives the frontend the same access.
+   Failed tool calls are never offloaded, since their error text is what the model needs.
+   The oldest artifacts are removed once more than `MAX_ARTIFACTS` are stored.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
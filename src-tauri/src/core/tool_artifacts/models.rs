@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+/// Contents of `tool_artifacts/settings.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolArtifactSettings {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Results above this many bytes are offloaded
+    #[serde(default)]
+    pub threshold_bytes: Option<usize>,
+    /// Characters of an offloaded result included in the preview
+    #[serde(default)]
+    pub preview_chars: Option<usize>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl Default for ToolArtifactSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold_bytes: None,
+            preview_chars: None,
+        }
+    }
+}
+
+/// A stored tool result
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolArtifact {
+    pub id: String,
+    pub tool: String,
+    pub server: String,
+    #[serde(default)]
+    pub call_id: Option<String>,
+    pub size_bytes: usize,
+    pub total_chars: usize,
+    /// Whether the result parsed as JSON
+    pub is_json: bool,
+    pub created_at: i64,
+}
+
+/// A range of characters of an artifact
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArtifactPage {
+    pub artifact: ToolArtifact,
+    pub offset: usize,
+    pub content: String,
+    /// Offset to continue reading from, when there is more
+    pub next_offset: Option<usize>,
+}
@@ -0,0 +1,129 @@
+use std::fs;
+use std::path::PathBuf;
+
+use serde_json::{json, Map, Value};
+
+use super::constants::READ_ARTIFACT_TOOL;
+use super::helpers::*;
+use super::models::ToolArtifactSettings;
+use crate::core::mcp::models::McpToolCallResult;
+
+fn temp_folder() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("tool-artifacts-{}", uuid::Uuid::new_v4()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn settings(threshold: usize) -> ToolArtifactSettings {
+    ToolArtifactSettings {
+        enabled: true,
+        threshold_bytes: Some(threshold),
+        preview_chars: Some(20),
+    }
+}
+
+fn rows(count: usize) -> String {
+    let rows: Vec<Value> = (0..count)
+        .map(|i| json!({"id": i, "name": format!("row {i}")}))
+        .collect();
+    Value::Array(rows).to_string()
+}
+
+#[test]
+fn test_json_outline() {
+    assert_eq!(
+        json_outline(&json!([{"id": 1, "name": "a"}, {"id": 2}])),
+        "JSON array of 2 items; the first item has the keys: id, name"
+    );
+    assert_eq!(
+        json_outline(&json!({"rows": [], "total": 3})),
+        "JSON object with 2 keys: rows, total"
+    );
+    assert_eq!(json_outline(&json!([1, 2, 3])), "JSON array of 3 items");
+}
+
+#[test]
+fn test_offload_and_read_back() {
+    let dir = temp_folder();
+    let text = rows(200);
+    assert!(
+        offload_if_large(&dir, &settings(text.len()), "db", "query", None, &text)
+            .unwrap()
+            .is_none()
+    );
+    let disabled = ToolArtifactSettings {
+        enabled: false,
+        ..settings(10)
+    };
+    assert!(
+        offload_if_large(&dir, &disabled, "db", "query", None, &text)
+            .unwrap()
+            .is_none()
+    );
+
+    let (preview, artifact) = offload_if_large(&dir, &settings(1024), "db", "query", None, &text)
+        .unwrap()
+        .unwrap();
+    assert!(artifact.is_json);
+    assert!(preview.contains(&artifact.id));
+    assert!(preview.contains("JSON array of 200 items"));
+    assert!(preview.contains("First 20 characters:\n[{\"id\":0,\"name\":\"ro"));
+    assert!(preview.len() < text.len());
+
+    let first = read_artifact_page(&dir, &artifact.id, 0, Some(100)).unwrap();
+    assert_eq!(first.content, &text[..100]);
+    assert_eq!(first.next_offset, Some(100));
+    let last = read_artifact_page(&dir, &artifact.id, text.len() - 10, Some(100)).unwrap();
+    assert_eq!(last.content, &text[text.len() - 10..]);
+    assert_eq!(last.next_offset, None);
+    assert!(read_artifact_page(&dir, &artifact.id, text.len() + 1, None).is_err());
+    assert!(read_artifact_page(&dir, "../settings", 0, None).is_err());
+
+    let mut arguments = Map::new();
+    arguments.insert("artifact_id".to_string(), json!(artifact.id));
+    arguments.insert("offset".to_string(), json!(5));
+    arguments.insert("length".to_string(), json!(10));
+    let read = call_read_artifact_tool(&dir, &arguments).unwrap();
+    assert!(read.starts_with(&text[5..15]));
+    assert!(read.ends_with("Continue with offset 15.]"));
+
+    // Reading an artifact never creates another one
+    assert!(
+        offload_if_large(&dir, &settings(10), "jan", READ_ARTIFACT_TOOL, None, &read)
+            .unwrap()
+            .is_none()
+    );
+
+    delete_artifact(&dir, &artifact.id).unwrap();
+    assert!(read_artifact(&dir, &artifact.id).is_err());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_offload_call_result_keeps_other_blocks() {
+    let dir = temp_folder();
+    write_settings(&dir, &settings(1024)).unwrap();
+    let result = McpToolCallResult {
+        content: vec![
+            json!({"type": "text", "text": rows(100)}),
+            json!({"type": "image", "data": "aGk=", "mimeType": "image/png"}),
+        ],
+        structured_content: Some(json!({"rows": []})),
+        is_error: None,
+        artifact_id: None,
+    };
+    let offloaded = offload_call_result(&dir, "db", "query", result.clone());
+    assert!(offloaded.artifact_id.is_some());
+    assert_eq!(offloaded.content.len(), 2);
+    assert_eq!(offloaded.content[1]["type"], "image");
+    assert!(offloaded.structured_content.is_none());
+
+    let failed = McpToolCallResult {
+        is_error: Some(true),
+        ..result
+    };
+    assert!(offload_call_result(&dir, "db", "query", failed)
+        .artifact_id
+        .is_none());
+    fs::remove_dir_all(&dir).unwrap();
+}
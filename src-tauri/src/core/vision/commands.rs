@@ -0,0 +1,26 @@
+use serde_json::Value;
+use tauri::{AppHandle, Runtime};
+
+use super::helpers::{limits_for_endpoint, local_limits, preprocess_messages_async};
+use super::models::PreprocessedMessages;
+use crate::core::inference::helpers::resolve_model_endpoint;
+
+/// Prepare the inline images of `messages` for `model` and return the rewritten messages.
+/// Fails when the images can't be made to fit the model's request limits.
+#[tauri::command]
+pub async fn preprocess_message_images<R: Runtime>(
+    app_handle: AppHandle<R>,
+    model: String,
+    messages: Vec<Value>,
+) -> Result<PreprocessedMessages, String> {
+    let limits = match resolve_model_endpoint(&app_handle, &model).await {
+        Ok(endpoint) => limits_for_endpoint(&endpoint),
+        Err(e) => {
+            // Not loaded yet; local engines have the tightest dimension limit
+            log::debug!("Using local image limits for {model}: {e}");
+            local_limits()
+        }
+    };
+    let (messages, report) = preprocess_messages_async(messages, limits).await?;
+    Ok(PreprocessedMessages { messages, report })
+}
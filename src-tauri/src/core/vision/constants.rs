@@ -0,0 +1,30 @@
+// Vision constants
+
+/// Longest edge of images sent to each kind of target, in pixels
+pub const ANTHROPIC_MAX_DIMENSION: u32 = 1568;
+pub const OPENAI_MAX_DIMENSION: u32 = 2048;
+pub const GEMINI_MAX_DIMENSION: u32 = 3072;
+pub const REMOTE_MAX_DIMENSION: u32 = 2048;
+/// Vision projectors of local engines downscale further anyway
+pub const LOCAL_MAX_DIMENSION: u32 = 1344;
+
+/// Largest single image, measured as base64 payload
+pub const ANTHROPIC_MAX_IMAGE_BYTES: usize = 5 * 1024 * 1024;
+pub const OPENAI_MAX_IMAGE_BYTES: usize = 20 * 1024 * 1024;
+pub const GEMINI_MAX_IMAGE_BYTES: usize = 15 * 1024 * 1024;
+pub const REMOTE_MAX_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+pub const LOCAL_MAX_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+
+/// All inline images of one request, measured as base64 payload
+pub const ANTHROPIC_MAX_TOTAL_BYTES: usize = 24 * 1024 * 1024;
+pub const OPENAI_MAX_TOTAL_BYTES: usize = 40 * 1024 * 1024;
+pub const GEMINI_MAX_TOTAL_BYTES: usize = 15 * 1024 * 1024;
+pub const REMOTE_MAX_TOTAL_BYTES: usize = 20 * 1024 * 1024;
+pub const LOCAL_MAX_TOTAL_BYTES: usize = 32 * 1024 * 1024;
+
+/// JPEG qualities tried in order before an image is scaled down further
+pub const JPEG_QUALITIES: [u8; 3] = [85, 70, 55];
+/// Each further downscale keeps this share of the longest edge
+pub const DOWNSCALE_PERCENT: u32 = 75;
+/// Images are not shrunk below this longest edge to meet a budget
+pub const MIN_DIMENSION: u32 = 256;
@@ -0,0 +1,273 @@
+use std::io::Cursor;
+
+use base64::Engine;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::metadata::Orientation;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader};
+use serde_json::Value;
+
+use super::constants::*;
+use super::models::{ImageLimits, ImagePreprocessReport, PreparedImage};
+use crate::core::inference::models::ModelEndpoint;
+
+const LOCAL_LIMITS: ImageLimits = ImageLimits {
+    max_dimension: LOCAL_MAX_DIMENSION,
+    max_image_bytes: LOCAL_MAX_IMAGE_BYTES,
+    max_total_bytes: LOCAL_MAX_TOTAL_BYTES,
+};
+
+/// Image limits of the provider behind `base_url`, recognized by host
+pub fn limits_for_url(base_url: &str) -> ImageLimits {
+    let host = url::Url::parse(base_url)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+        .unwrap_or_default();
+    if host.ends_with("anthropic.com") {
+        ImageLimits {
+            max_dimension: ANTHROPIC_MAX_DIMENSION,
+            max_image_bytes: ANTHROPIC_MAX_IMAGE_BYTES,
+            max_total_bytes: ANTHROPIC_MAX_TOTAL_BYTES,
+        }
+    } else if host.ends_with("openai.com") || host.ends_with("openai.azure.com") {
+        ImageLimits {
+            max_dimension: OPENAI_MAX_DIMENSION,
+            max_image_bytes: OPENAI_MAX_IMAGE_BYTES,
+            max_total_bytes: OPENAI_MAX_TOTAL_BYTES,
+        }
+    } else if host.ends_with("googleapis.com") {
+        ImageLimits {
+            max_dimension: GEMINI_MAX_DIMENSION,
+            max_image_bytes: GEMINI_MAX_IMAGE_BYTES,
+            max_total_bytes: GEMINI_MAX_TOTAL_BYTES,
+        }
+    } else {
+        ImageLimits {
+            max_dimension: REMOTE_MAX_DIMENSION,
+            max_image_bytes: REMOTE_MAX_IMAGE_BYTES,
+            max_total_bytes: REMOTE_MAX_TOTAL_BYTES,
+        }
+    }
+}
+
+pub fn local_limits() -> ImageLimits {
+    LOCAL_LIMITS
+}
+
+pub fn limits_for_endpoint(endpoint: &ModelEndpoint) -> ImageLimits {
+    if endpoint.is_local {
+        LOCAL_LIMITS
+    } else {
+        limits_for_url(&endpoint.base_url)
+    }
+}
+
+fn base64_len(bytes: usize) -> usize {
+    bytes.div_ceil(3) * 4
+}
+
+fn encode(image: &DynamicImage, format: ImageFormat, quality: u8) -> Result<Vec<u8>, String> {
+    let mut buf = Vec::new();
+    match format {
+        ImageFormat::Jpeg => {
+            // JPEG has no alpha channel
+            let rgb = DynamicImage::ImageRgb8(image.to_rgb8());
+            JpegEncoder::new_with_quality(&mut buf, quality)
+                .encode_image(&rgb)
+                .map_err(|e| format!("Failed to encode image: {e}"))?;
+        }
+        _ => image
+            .write_to(&mut Cursor::new(&mut buf), format)
+            .map_err(|e| format!("Failed to encode image: {e}"))?,
+    }
+    Ok(buf)
+}
+
+/// Decode an image, apply its EXIF orientation and re-encode it without metadata, scaled to
+/// `limits.max_dimension` and compressed or scaled further until its base64 payload fits
+/// `budget`. PNGs stay PNG while they fit; everything else becomes JPEG.
+pub fn prepare_image(
+    bytes: &[u8],
+    limits: &ImageLimits,
+    budget: usize,
+) -> Result<PreparedImage, String> {
+    let reader = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to read image: {e}"))?;
+    let source_format = reader
+        .format()
+        .ok_or_else(|| "Unrecognized image format".to_string())?;
+    let mut decoder = reader
+        .into_decoder()
+        .map_err(|e| format!("Unsupported image: {e}"))?;
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let mut image =
+        DynamicImage::from_decoder(decoder).map_err(|e| format!("Failed to decode image: {e}"))?;
+    image.apply_orientation(orientation);
+
+    let mut resized = false;
+    let mut max_dimension = limits.max_dimension;
+    loop {
+        if image.width().max(image.height()) > max_dimension {
+            image = image.resize(max_dimension, max_dimension, FilterType::Lanczos3);
+            resized = true;
+        }
+        let mut attempts: Vec<(ImageFormat, u8)> = Vec::new();
+        if source_format == ImageFormat::Png {
+            attempts.push((ImageFormat::Png, 0));
+        }
+        attempts.extend(JPEG_QUALITIES.iter().map(|q| (ImageFormat::Jpeg, *q)));
+        for (format, quality) in attempts {
+            let encoded = encode(&image, format, quality)?;
+            if base64_len(encoded.len()) <= budget {
+                return Ok(PreparedImage {
+                    mime: format.to_mime_type(),
+                    data: base64::engine::general_purpose::STANDARD.encode(encoded),
+                    width: image.width(),
+                    height: image.height(),
+                    resized,
+                    converted: format != source_format,
+                });
+            }
+        }
+        max_dimension = image.width().max(image.height()) * DOWNSCALE_PERCENT / 100;
+        if max_dimension < MIN_DIMENSION {
+            return Err(format!(
+                "it doesn't fit in {} KB even when scaled down to {MIN_DIMENSION}px",
+                budget / 1024
+            ));
+        }
+    }
+}
+
+/// Bytes of a base64 data URL
+pub fn decode_data_url(url: &str) -> Option<Vec<u8>> {
+    let (header, data) = url.strip_prefix("data:")?.split_once(',')?;
+    header.strip_suffix(";base64")?;
+    base64::engine::general_purpose::STANDARD
+        .decode(data.trim())
+        .ok()
+}
+
+/// Where an inline image lives inside a message content part
+#[derive(Clone, Copy)]
+enum InlineImage {
+    /// OpenAI `{"type": "image_url", "image_url": {"url": "data:..."}}`
+    DataUrl,
+    /// Anthropic `{"type": "image", "source": {"type": "base64", ...}}`
+    Base64Source,
+}
+
+fn inline_image(part: &Value) -> Option<InlineImage> {
+    match part.get("type").and_then(|t| t.as_str()) {
+        Some("image_url") => part
+            .get("image_url")
+            .and_then(|i| i.get("url"))
+            .and_then(|u| u.as_str())
+            .filter(|u| u.starts_with("data:"))
+            .map(|_| InlineImage::DataUrl),
+        Some("image") => part
+            .get("source")
+            .filter(|s| s.get("type").and_then(|t| t.as_str()) == Some("base64"))
+            .map(|_| InlineImage::Base64Source),
+        _ => None,
+    }
+}
+
+fn image_parts(messages: &mut [Value]) -> Vec<&mut Value> {
+    messages
+        .iter_mut()
+        .filter_map(|m| m.get_mut("content").and_then(|c| c.as_array_mut()))
+        .flatten()
+        .filter(|part| inline_image(part).is_some())
+        .collect()
+}
+
+/// Whether any message carries an inline image
+pub fn has_inline_images(messages: &[Value]) -> bool {
+    messages
+        .iter()
+        .filter_map(|m| m.get("content").and_then(|c| c.as_array()))
+        .flatten()
+        .any(|part| inline_image(part).is_some())
+}
+
+/// Prepare every inline image of `messages` in place. Each image may use an equal share of
+/// what is left of the request budget, so small images leave room for later ones.
+pub fn preprocess_messages(
+    messages: &mut [Value],
+    limits: &ImageLimits,
+) -> Result<ImagePreprocessReport, String> {
+    let mut parts = image_parts(messages);
+    let count = parts.len();
+    let mut report = ImagePreprocessReport {
+        images: count,
+        ..Default::default()
+    };
+    for (index, part) in parts.iter_mut().enumerate() {
+        let Some(kind) = inline_image(part) else {
+            continue;
+        };
+        let encoded = match kind {
+            InlineImage::DataUrl => part["image_url"]["url"].as_str().unwrap_or_default(),
+            InlineImage::Base64Source => part["source"]["data"].as_str().unwrap_or_default(),
+        };
+        let bytes = match kind {
+            InlineImage::DataUrl => decode_data_url(encoded),
+            InlineImage::Base64Source => base64::engine::general_purpose::STANDARD
+                .decode(encoded.trim())
+                .ok(),
+        }
+        .ok_or_else(|| format!("Image {} is not valid base64 data", index + 1))?;
+        report.original_bytes += base64_len(bytes.len());
+
+        let remaining = limits.max_total_bytes.saturating_sub(report.final_bytes);
+        let budget = limits.max_image_bytes.min(remaining / (count - index));
+        let prepared = prepare_image(&bytes, limits, budget).map_err(|e| {
+            format!(
+                "Image {} of {count} doesn't fit the request size limit: {e}",
+                index + 1
+            )
+        })?;
+        report.resized += usize::from(prepared.resized);
+        report.converted += usize::from(prepared.converted);
+        report.final_bytes += prepared.data.len();
+        match kind {
+            InlineImage::DataUrl => {
+                part["image_url"]["url"] = Value::String(prepared.data_url());
+            }
+            InlineImage::Base64Source => {
+                part["source"]["media_type"] = Value::String(prepared.mime.to_string());
+                part["source"]["data"] = Value::String(prepared.data);
+            }
+        }
+    }
+    Ok(report)
+}
+
+/// `preprocess_messages` off the async runtime; messages without inline images are returned
+/// untouched
+pub async fn preprocess_messages_async(
+    mut messages: Vec<Value>,
+    limits: ImageLimits,
+) -> Result<(Vec<Value>, ImagePreprocessReport), String> {
+    if !has_inline_images(&messages) {
+        return Ok((messages, ImagePreprocessReport::default()));
+    }
+    tokio::task::spawn_blocking(move || {
+        let report = preprocess_messages(&mut messages, &limits)?;
+        if report.resized + report.converted > 0 {
+            log::info!(
+                "Prepared {} image(s): {} resized, {} converted, {} KB -> {} KB",
+                report.images,
+                report.resized,
+                report.converted,
+                report.original_bytes / 1024,
+                report.final_bytes / 1024
+            );
+        }
+        Ok((messages, report))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
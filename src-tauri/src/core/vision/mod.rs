@@ -0,0 +1,24 @@
+/*!
+   Vision Attachment Preprocessing
+
+   Images attached to multimodal requests are prepared in Rust before anything is sent:
+   - the image is decoded, turned upright according to its EXIF orientation and re-encoded,
+     which drops EXIF and other metadata (camera details, GPS position),
+   - images larger than the target's maximum dimension are scaled down, keeping the aspect
+     ratio; limits differ for Anthropic, OpenAI, Gemini, other remote providers and local
+     engines,
+   - formats the targets don't all accept (WebP, GIF, BMP, ...) are converted to PNG or JPEG,
+   - all inline images of a request share a payload budget; images are re-compressed or scaled
+     further to fit, and a request that still doesn't fit fails before it is sent.
+
+   Both OpenAI `image_url` data URLs and Anthropic base64 `image` blocks are handled. Images
+   referenced by http(s) URL are left to the provider.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// What a target accepts for inline images
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImageLimits {
+    /// Longest edge in pixels
+    pub max_dimension: u32,
+    /// Largest single image, as base64 payload
+    pub max_image_bytes: usize,
+    /// All inline images of a request together, as base64 payload
+    pub max_total_bytes: usize,
+}
+
+/// An image ready to be sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PreparedImage {
+    pub mime: &'static str,
+    /// Base64 of the encoded image
+    pub data: String,
+    pub width: u32,
+    pub height: u32,
+    pub resized: bool,
+    pub converted: bool,
+}
+
+impl PreparedImage {
+    pub fn data_url(&self) -> String {
+        format!("data:{};base64,{}", self.mime, self.data)
+    }
+}
+
+/// What preprocessing did to the images of a request
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImagePreprocessReport {
+    pub images: usize,
+    pub resized: usize,
+    /// Images re-encoded to another format
+    pub converted: usize,
+    /// Base64 payload before and after preprocessing
+    pub original_bytes: usize,
+    pub final_bytes: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreprocessedMessages {
+    pub messages: Vec<Value>,
+    pub report: ImagePreprocessReport,
+}
@@ -0,0 +1,153 @@
+use std::io::Cursor;
+
+use base64::Engine;
+use image::{DynamicImage, ImageFormat, Rgb, RgbImage, Rgba, RgbaImage};
+use serde_json::json;
+
+use super::helpers::*;
+use super::models::ImageLimits;
+
+const LIMITS: ImageLimits = ImageLimits {
+    max_dimension: 512,
+    max_image_bytes: 1024 * 1024,
+    max_total_bytes: 2 * 1024 * 1024,
+};
+
+/// Gradients with some texture, so encoders can't compress it to nothing
+fn test_image(width: u32, height: u32) -> DynamicImage {
+    DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+        let texture = ((x * y) & 0x1f) as u8;
+        Rgb([(x * 3) as u8 ^ texture, (y * 5) as u8, ((x + y) * 2) as u8])
+    }))
+}
+
+fn encoded(image: &DynamicImage, format: ImageFormat) -> Vec<u8> {
+    let mut buf = Vec::new();
+    image.write_to(&mut Cursor::new(&mut buf), format).unwrap();
+    buf
+}
+
+/// A JPEG with an EXIF block holding orientation 6 (rotate 90° clockwise)
+fn jpeg_with_exif(width: u32, height: u32) -> Vec<u8> {
+    let jpeg = encoded(&test_image(width, height), ImageFormat::Jpeg);
+    let mut exif = b"Exif\0\0MM\0\x2a\0\0\0\x08\0\x01".to_vec();
+    exif.extend_from_slice(&[0x01, 0x12, 0x00, 0x03, 0, 0, 0, 1, 0x00, 0x06, 0, 0]);
+    exif.extend_from_slice(&[0, 0, 0, 0]);
+    let mut out = jpeg[..2].to_vec();
+    out.extend_from_slice(&[0xFF, 0xE1]);
+    out.extend_from_slice(&((exif.len() + 2) as u16).to_be_bytes());
+    out.extend_from_slice(&exif);
+    out.extend_from_slice(&jpeg[2..]);
+    out
+}
+
+fn decode(data: &str) -> Vec<u8> {
+    base64::engine::general_purpose::STANDARD
+        .decode(data)
+        .unwrap()
+}
+
+#[test]
+fn test_limits_by_provider() {
+    assert_eq!(
+        limits_for_url("https://api.anthropic.com/v1").max_dimension,
+        1568
+    );
+    assert_eq!(
+        limits_for_url("https://api.openai.com/v1").max_dimension,
+        2048
+    );
+    assert_eq!(
+        limits_for_url("https://generativelanguage.googleapis.com/v1beta/openai").max_dimension,
+        3072
+    );
+    assert_eq!(
+        limits_for_url("https://openrouter.ai/api/v1"),
+        limits_for_url("not a url")
+    );
+}
+
+#[test]
+fn test_prepare_image_strips_exif_and_applies_orientation() {
+    let prepared = prepare_image(&jpeg_with_exif(300, 200), &LIMITS, usize::MAX).unwrap();
+    assert_eq!((prepared.width, prepared.height), (200, 300));
+    assert_eq!(prepared.mime, "image/jpeg");
+    assert!(!prepared.resized);
+
+    let bytes = decode(&prepared.data);
+    assert!(!bytes.windows(4).any(|w| w == b"Exif"));
+}
+
+#[test]
+fn test_prepare_image_resizes_and_converts() {
+    let webp = encoded(&test_image(1024, 256), ImageFormat::WebP);
+    let prepared = prepare_image(&webp, &LIMITS, usize::MAX).unwrap();
+    assert_eq!((prepared.width, prepared.height), (512, 128));
+    assert_eq!(prepared.mime, "image/jpeg");
+    assert!(prepared.resized && prepared.converted);
+
+    // PNGs keep their format and transparency while they fit
+    let png = encoded(
+        &DynamicImage::ImageRgba8(RgbaImage::from_pixel(64, 64, Rgba([0, 0, 0, 0]))),
+        ImageFormat::Png,
+    );
+    let prepared = prepare_image(&png, &LIMITS, usize::MAX).unwrap();
+    assert_eq!(prepared.mime, "image/png");
+    assert!(!prepared.converted);
+
+    assert!(prepare_image(b"not an image", &LIMITS, usize::MAX).is_err());
+}
+
+#[test]
+fn test_prepare_image_meets_budget() {
+    let png = encoded(&test_image(512, 512), ImageFormat::Png);
+    let budget = 40 * 1024;
+    let prepared = prepare_image(&png, &LIMITS, budget).unwrap();
+    assert!(prepared.data.len() <= budget);
+    assert_eq!(prepared.mime, "image/jpeg");
+
+    let err = prepare_image(&png, &LIMITS, 100).unwrap_err();
+    assert!(err.contains("256px"), "{err}");
+}
+
+#[test]
+fn test_preprocess_messages_shares_the_budget() {
+    let data = base64::engine::general_purpose::STANDARD
+        .encode(encoded(&test_image(800, 800), ImageFormat::Png));
+    let mut messages = vec![
+        json!({"role": "system", "content": "Describe images."}),
+        json!({"role": "user", "content": [
+            {"type": "text", "text": "Two pictures"},
+            {"type": "image_url", "image_url": {"url": format!("data:image/png;base64,{data}")}},
+            {"type": "image_url", "image_url": {"url": "https://example.com/cat.png"}},
+            {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": data}}
+        ]}),
+    ];
+    assert!(has_inline_images(&messages));
+    let limits = ImageLimits {
+        max_total_bytes: 120 * 1024,
+        ..LIMITS
+    };
+    let report = preprocess_messages(&mut messages, &limits).unwrap();
+    assert_eq!(report.images, 2);
+    assert_eq!(report.resized, 2);
+    assert!(report.final_bytes <= limits.max_total_bytes);
+    assert!(report.final_bytes < report.original_bytes);
+
+    let parts = &messages[1]["content"];
+    assert!(parts[1]["image_url"]["url"]
+        .as_str()
+        .unwrap()
+        .starts_with("data:image/"));
+    assert_eq!(parts[2]["image_url"]["url"], "https://example.com/cat.png");
+    let media_type = parts[3]["source"]["media_type"].as_str().unwrap();
+    assert!(media_type == "image/png" || media_type == "image/jpeg");
+
+    // A budget no image can meet fails before anything is sent
+    let limits = ImageLimits {
+        max_total_bytes: 1024,
+        ..LIMITS
+    };
+    let err = preprocess_messages(&mut messages, &limits).unwrap_err();
+    assert!(err.starts_with("Image 1 of 2"), "{err}");
+}
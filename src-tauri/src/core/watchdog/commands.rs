@@ -0,0 +1,13 @@
+use std::time::Instant;
+
+use tauri::State;
+
+use super::models::WatchedTask;
+use super::Watchdog;
+
+/// Lists the startup, shutdown and download tasks being watched, oldest first, with the ones
+/// already reported as stuck marked.
+#[tauri::command]
+pub fn list_watched_tasks(watchdog: State<'_, Watchdog>) -> Vec<WatchedTask> {
+    watchdog.list(Instant::now())
+}
@@ -0,0 +1,17 @@
+use std::time::Duration;
+
+/// Emitted once for every task the watchdog finds stuck
+pub const WATCHDOG_EVENT: &str = "watchdog-stuck-task";
+
+/// How often running tasks are checked
+pub const WATCHDOG_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Starting an MCP server, up to and including the check that it didn't quit right away
+pub const MCP_STARTUP_EXPECTED: Duration = Duration::from_secs(60);
+/// Shutdown gets its overall timeout plus this long to force-kill what didn't stop
+pub const MCP_SHUTDOWN_GRACE: Duration = Duration::from_secs(3);
+/// A download that received no bytes for this long is stalled
+pub const DOWNLOAD_STALL_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Stages kept per task for the diagnosis, oldest dropped first
+pub const MAX_STAGE_HISTORY: usize = 16;
@@ -0,0 +1,76 @@
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+use super::constants::{WATCHDOG_EVENT, WATCHDOG_INTERVAL};
+use super::models::{StuckReason, StuckTaskReport, WatchedKind};
+use super::{WatchGuard, Watchdog};
+
+/// What the user can do about a stuck task
+pub fn suggestions(kind: WatchedKind, reason: StuckReason) -> Vec<String> {
+    let suggestions: &[&str] = match (kind, reason) {
+        (WatchedKind::McpStartup, _) => &[
+            "Check the server's log for what it is waiting on",
+            "Run the server's command in a terminal to see whether it asks for input",
+            "Deactivate the server if it keeps hanging on startup",
+        ],
+        (WatchedKind::McpShutdown, _) => &[
+            "Servers that don't stop are force-killed; wait a few more seconds",
+            "If the app doesn't quit, end the remaining MCP server processes",
+        ],
+        (WatchedKind::Download, StuckReason::Stalled) => &[
+            "Check the network connection and the proxy settings",
+            "Cancel the download and start it again; it resumes where it stopped",
+            "Turn the download mirror off if the mirror is unreachable",
+        ],
+        (WatchedKind::Download, StuckReason::Overdue) => {
+            &["Cancel the download and start it again; it resumes where it stopped"]
+        }
+    };
+    suggestions.iter().map(|s| s.to_string()).collect()
+}
+
+/// Watch a task with the app's watchdog. Without one the task is watched by nobody.
+pub fn watch<R: Runtime>(
+    app: &AppHandle<R>,
+    kind: WatchedKind,
+    name: impl Into<String>,
+    expected: Option<Duration>,
+    stall_after: Option<Duration>,
+) -> WatchGuard {
+    let watchdog = app
+        .try_state::<Watchdog>()
+        .map(|state| state.inner().clone())
+        .unwrap_or_default();
+    watchdog.watch(kind, name, expected, stall_after)
+}
+
+fn report<R: Runtime>(app: &AppHandle<R>, report: &StuckTaskReport) {
+    let task = &report.task;
+    let diagnosis = serde_json::to_string(report).unwrap_or_default();
+    log::error!(
+        "Watchdog: {} '{}' is {:?} after {} ms: {diagnosis}",
+        task.kind,
+        task.name,
+        report.reason,
+        task.elapsed_ms
+    );
+    if let Err(e) = app.emit(WATCHDOG_EVENT, report) {
+        log::warn!("Failed to emit {WATCHDOG_EVENT} event: {e}");
+    }
+}
+
+/// Check the watched tasks periodically for the lifetime of the app
+pub fn start_watchdog<R: Runtime>(app: AppHandle<R>) {
+    let watchdog = app.state::<Watchdog>().inner().clone();
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(WATCHDOG_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            for stuck in watchdog.check(Instant::now()) {
+                report(&app, &stuck);
+            }
+        }
+    });
+}
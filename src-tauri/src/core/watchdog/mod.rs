@@ -0,0 +1,220 @@
+/*!
+   Watchdog
+
+   Startup, shutdown and download tasks register with the watchdog for as long as they run,
+   with the time they are expected to take and, for transfers, how long they may go without
+   progress. A periodic check reports every task that is overdue or stalled exactly once:
+   a structured diagnosis is logged, with the stages the task went through and the backtrace
+   of where it was started when backtraces are enabled, and a `watchdog-stuck-task` event
+   carries it together with recovery suggestions to the UI.
+
+   The watchdog only reports; the tasks keep their own timeouts and cancellation.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
+
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use constants::MAX_STAGE_HISTORY;
+use helpers::suggestions;
+use models::{StuckReason, StuckTaskReport, WatchedKind, WatchedTask};
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_millis().try_into().unwrap_or(u64::MAX)
+}
+
+struct Task {
+    kind: WatchedKind,
+    name: String,
+    started: Instant,
+    last_progress: Instant,
+    expected: Option<Duration>,
+    stall_after: Option<Duration>,
+    progress: u64,
+    stages: VecDeque<String>,
+    backtrace: Option<String>,
+    stuck: Option<StuckReason>,
+}
+
+impl Task {
+    fn info(&self, now: Instant) -> WatchedTask {
+        WatchedTask {
+            kind: self.kind,
+            name: self.name.clone(),
+            elapsed_ms: millis(now.saturating_duration_since(self.started)),
+            idle_ms: millis(now.saturating_duration_since(self.last_progress)),
+            expected_ms: self.expected.map(millis),
+            stall_after_ms: self.stall_after.map(millis),
+            progress: self.progress,
+            stage: self.stages.back().cloned(),
+            stuck: self.stuck,
+        }
+    }
+
+    fn stuck_reason(&self, now: Instant) -> Option<StuckReason> {
+        let elapsed = now.saturating_duration_since(self.started);
+        let idle = now.saturating_duration_since(self.last_progress);
+        if self.expected.is_some_and(|expected| elapsed > expected) {
+            Some(StuckReason::Overdue)
+        } else if self
+            .stall_after
+            .is_some_and(|stall_after| idle > stall_after)
+        {
+            Some(StuckReason::Stalled)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Default)]
+struct WatchState {
+    tasks: HashMap<u64, Task>,
+    next_id: u64,
+}
+
+/// Tasks that are expected to finish or make progress in time, cheap to clone
+#[derive(Clone, Default)]
+pub struct Watchdog {
+    state: Arc<Mutex<WatchState>>,
+}
+
+impl Watchdog {
+    fn state(&self) -> MutexGuard<'_, WatchState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Watch a task until the returned guard is dropped. It is stuck once it runs longer
+    /// than `expected` or goes `stall_after` without progress.
+    pub fn watch(
+        &self,
+        kind: WatchedKind,
+        name: impl Into<String>,
+        expected: Option<Duration>,
+        stall_after: Option<Duration>,
+    ) -> WatchGuard {
+        let backtrace = Backtrace::capture();
+        let backtrace =
+            (backtrace.status() == BacktraceStatus::Captured).then(|| backtrace.to_string());
+        let now = Instant::now();
+        let mut state = self.state();
+        state.next_id += 1;
+        let id = state.next_id;
+        state.tasks.insert(
+            id,
+            Task {
+                kind,
+                name: name.into(),
+                started: now,
+                last_progress: now,
+                expected,
+                stall_after,
+                progress: 0,
+                stages: VecDeque::new(),
+                backtrace,
+                stuck: None,
+            },
+        );
+        WatchGuard {
+            handle: WatchHandle {
+                watchdog: self.clone(),
+                id,
+            },
+        }
+    }
+
+    fn with_task(&self, id: u64, update: impl FnOnce(&mut Task)) {
+        if let Some(task) = self.state().tasks.get_mut(&id) {
+            update(task);
+        }
+    }
+
+    /// Tasks that became stuck since the last check, each reported once
+    pub fn check(&self, now: Instant) -> Vec<StuckTaskReport> {
+        let mut state = self.state();
+        let mut reports = Vec::new();
+        for task in state.tasks.values_mut() {
+            if task.stuck.is_some() {
+                continue;
+            }
+            let Some(reason) = task.stuck_reason(now) else {
+                continue;
+            };
+            task.stuck = Some(reason);
+            reports.push(StuckTaskReport {
+                task: task.info(now),
+                reason,
+                stages: task.stages.iter().cloned().collect(),
+                backtrace: task.backtrace.clone(),
+                suggestions: suggestions(task.kind, reason),
+            });
+        }
+        reports
+    }
+
+    /// Running tasks, oldest first
+    pub fn list(&self, now: Instant) -> Vec<WatchedTask> {
+        let state = self.state();
+        let mut tasks: Vec<_> = state.tasks.values().collect();
+        tasks.sort_by_key(|task| task.started);
+        tasks.into_iter().map(|task| task.info(now)).collect()
+    }
+}
+
+/// Reports progress of a watched task; clones can be handed to its subtasks
+#[derive(Clone)]
+pub struct WatchHandle {
+    watchdog: Watchdog,
+    id: u64,
+}
+
+impl WatchHandle {
+    /// Record `amount` more bytes or steps. A task that made progress is no longer stalled.
+    pub fn progress(&self, amount: u64) {
+        self.watchdog.with_task(self.id, |task| {
+            task.progress = task.progress.saturating_add(amount);
+            task.last_progress = Instant::now();
+            if task.stuck == Some(StuckReason::Stalled) {
+                task.stuck = None;
+            }
+        });
+    }
+
+    /// Record the step the task is at, for the diagnosis
+    pub fn stage(&self, stage: impl Into<String>) {
+        let stage = stage.into();
+        self.watchdog.with_task(self.id, |task| {
+            if task.stages.len() == MAX_STAGE_HISTORY {
+                task.stages.pop_front();
+            }
+            task.stages.push_back(stage);
+        });
+    }
+}
+
+/// Keeps a task watched. Dropping it ends the task.
+pub struct WatchGuard {
+    handle: WatchHandle,
+}
+
+impl WatchGuard {
+    pub fn handle(&self) -> &WatchHandle {
+        &self.handle
+    }
+}
+
+impl Drop for WatchGuard {
+    fn drop(&mut self) {
+        let handle = &self.handle;
+        handle.watchdog.state().tasks.remove(&handle.id);
+    }
+}
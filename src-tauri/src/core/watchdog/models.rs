@@ -0,0 +1,60 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchedKind {
+    McpStartup,
+    McpShutdown,
+    Download,
+}
+
+impl fmt::Display for WatchedKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::McpStartup => write!(f, "MCP server startup"),
+            Self::McpShutdown => write!(f, "MCP shutdown"),
+            Self::Download => write!(f, "download"),
+        }
+    }
+}
+
+/// Why a task counts as stuck
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StuckReason {
+    /// Running longer than it is expected to take
+    Overdue,
+    /// No progress for longer than allowed
+    Stalled,
+}
+
+/// A task being watched, as listed by `list_watched_tasks`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WatchedTask {
+    pub kind: WatchedKind,
+    pub name: String,
+    pub elapsed_ms: u64,
+    /// Time since the last progress, or since the start when there was none
+    pub idle_ms: u64,
+    pub expected_ms: Option<u64>,
+    pub stall_after_ms: Option<u64>,
+    /// Bytes or steps reported so far
+    pub progress: u64,
+    pub stage: Option<String>,
+    /// Set once the watchdog has reported the task
+    pub stuck: Option<StuckReason>,
+}
+
+/// Diagnosis of a stuck task, logged and emitted as `watchdog-stuck-task`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StuckTaskReport {
+    pub task: WatchedTask,
+    pub reason: StuckReason,
+    /// Stages the task went through, oldest first
+    pub stages: Vec<String>,
+    /// Where the task was started, when backtraces are enabled (`RUST_BACKTRACE=1`)
+    pub backtrace: Option<String>,
+    pub suggestions: Vec<String>,
+}
@@ -0,0 +1,59 @@
+use std::time::{Duration, Instant};
+
+use super::models::{StuckReason, WatchedKind};
+use super::Watchdog;
+
+const SECOND: Duration = Duration::from_secs(1);
+
+#[test]
+fn test_overdue_tasks_are_reported_once() {
+    let watchdog = Watchdog::default();
+    let start = Instant::now();
+    let guard = watchdog.watch(WatchedKind::McpStartup, "fetch", Some(SECOND), None);
+    guard.handle().stage("spawning");
+    guard.handle().stage("initializing");
+
+    assert!(watchdog.check(start).is_empty());
+    let reports = watchdog.check(start + 2 * SECOND);
+    assert_eq!(reports.len(), 1);
+    let report = &reports[0];
+    assert_eq!(report.reason, StuckReason::Overdue);
+    assert_eq!(report.task.name, "fetch");
+    assert_eq!(report.task.stage.as_deref(), Some("initializing"));
+    assert_eq!(report.stages, ["spawning", "initializing"]);
+    assert!(!report.suggestions.is_empty());
+    assert!(watchdog.check(start + 3 * SECOND).is_empty());
+
+    let listed = watchdog.list(start + 3 * SECOND);
+    assert_eq!(listed[0].stuck, Some(StuckReason::Overdue));
+    drop(guard);
+    assert!(watchdog.list(start).is_empty());
+}
+
+#[test]
+fn test_progress_keeps_a_transfer_from_stalling() {
+    let watchdog = Watchdog::default();
+    let guard = watchdog.watch(WatchedKind::Download, "model", None, Some(SECOND));
+    let handle = guard.handle().clone();
+    let later = Instant::now() + 2 * SECOND;
+    assert_eq!(watchdog.check(later)[0].reason, StuckReason::Stalled);
+
+    // Progress clears the stall, so a second stall is reported again
+    handle.progress(512);
+    assert_eq!(watchdog.list(Instant::now())[0].progress, 512);
+    assert_eq!(watchdog.list(Instant::now())[0].stuck, None);
+    assert!(watchdog.check(Instant::now()).is_empty());
+    assert_eq!(watchdog.check(later + 2 * SECOND).len(), 1);
+}
+
+#[test]
+fn test_stage_history_is_bounded() {
+    let watchdog = Watchdog::default();
+    let guard = watchdog.watch(WatchedKind::McpShutdown, "all", Some(Duration::ZERO), None);
+    for step in 0..100 {
+        guard.handle().stage(format!("step {step}"));
+    }
+    let report = &watchdog.check(Instant::now() + SECOND)[0];
+    assert_eq!(report.stages.len(), super::constants::MAX_STAGE_HISTORY);
+    assert_eq!(report.stages.last().map(String::as_str), Some("step 99"));
+}
@@ -0,0 +1,43 @@
+use tauri::{AppHandle, Runtime};
+
+use super::helpers::{clear_cache, fetch_page, read_settings, write_settings};
+use super::models::{FetchedPage, WebFetchSettings};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::offline::helpers::check_url;
+
+/// Fetches a page and returns its main content as Markdown, from the cache unless
+/// `force_refresh` is set.
+#[tauri::command]
+pub async fn fetch_web_page<R: Runtime>(
+    app_handle: AppHandle<R>,
+    url: String,
+    force_refresh: Option<bool>,
+) -> Result<FetchedPage, String> {
+    check_url(&app_handle, &url)?;
+    let data_folder = get_jan_data_folder_path(app_handle);
+    fetch_page(&data_folder, &url, force_refresh.unwrap_or(false)).await
+}
+
+/// Removes all cached pages and returns how many were removed.
+#[tauri::command]
+pub async fn clear_web_fetch_cache<R: Runtime>(app_handle: AppHandle<R>) -> Result<usize, String> {
+    clear_cache(&get_jan_data_folder_path(app_handle))
+}
+
+/// Returns the web fetch settings.
+#[tauri::command]
+pub async fn get_web_fetch_settings<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<WebFetchSettings, String> {
+    Ok(read_settings(&get_jan_data_folder_path(app_handle)))
+}
+
+/// Replaces the web fetch settings.
+#[tauri::command]
+pub async fn update_web_fetch_settings<R: Runtime>(
+    app_handle: AppHandle<R>,
+    settings: WebFetchSettings,
+) -> Result<WebFetchSettings, String> {
+    write_settings(&get_jan_data_folder_path(app_handle), &settings)?;
+    Ok(settings)
+}
@@ -0,0 +1,23 @@
+use std::time::Duration;
+
+pub const WEB_FETCH_DIR: &str = "web_fetch";
+pub const WEB_FETCH_CACHE_DIR: &str = "cache";
+pub const WEB_FETCH_SETTINGS_FILE: &str = "settings.json";
+
+/// Name of the built-in fetch tool offered to the agent loop
+pub const WEB_FETCH_TOOL: &str = "web_fetch";
+
+/// Product token matched against `User-agent` lines in robots.txt
+pub const ROBOTS_AGENT: &str = "jan";
+
+/// Redirects followed before a fetch gives up
+pub const MAX_REDIRECTS: usize = 10;
+
+pub const FETCH_TIMEOUT: Duration = Duration::from_secs(20);
+pub const ROBOTS_TIMEOUT: Duration = Duration::from_secs(5);
+/// Pages larger than this are rejected instead of being read into memory
+pub const MAX_PAGE_BYTES: usize = 5 * 1024 * 1024;
+/// Longest Markdown returned to the model; the full text stays available to the command
+pub const MAX_TOOL_CHARS: usize = 20_000;
+/// Cached pages are reused for this long
+pub const CACHE_TTL: Duration = Duration::from_secs(60 * 60);
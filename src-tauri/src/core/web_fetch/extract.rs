@@ -0,0 +1,456 @@
+//! Readability-style extraction of the main content of an HTML page into Markdown. This is a
+//! tolerant single-pass converter, not a full HTML parser: it is meant for reading pages, not
+//! for reproducing their layout.
+
+use url::Url;
+
+/// Elements whose content is never part of the readable text
+const SKIPPED: &[&str] = &[
+    "head", "title", "script", "style", "noscript", "template", "svg", "nav", "header", "footer",
+    "aside", "form", "iframe", "button", "select", "textarea", "canvas",
+];
+/// Elements whose content is raw text that must not be parsed as markup
+const RAW_TEXT: &[&str] = &["script", "style", "textarea", "title"];
+const BLOCKS: &[&str] = &[
+    "p", "div", "section", "article", "main", "table", "tr", "ul", "ol", "dl", "figure", "hr",
+];
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Start {
+        name: String,
+        attrs: &'a str,
+        self_closing: bool,
+    },
+    End(String),
+    Text(&'a str),
+}
+
+/// Split HTML into tags and text. Comments and declarations are dropped and the content of
+/// raw text elements is returned as a single text token.
+fn tokenize(html: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let lower = html.to_ascii_lowercase();
+    let mut pos = 0;
+    while pos < html.len() {
+        let Some(offset) = html[pos..].find('<') else {
+            tokens.push(Token::Text(&html[pos..]));
+            break;
+        };
+        if offset > 0 {
+            tokens.push(Token::Text(&html[pos..pos + offset]));
+        }
+        let start = pos + offset;
+        let rest = &html[start..];
+        if rest.starts_with("<!--") {
+            pos = rest.find("-->").map_or(html.len(), |end| start + end + 3);
+            continue;
+        }
+        let Some(end) = find_tag_end(rest) else {
+            tokens.push(Token::Text(rest));
+            break;
+        };
+        let inner = &rest[1..end];
+        pos = start + end + 1;
+        if inner.starts_with('!') || inner.starts_with('?') {
+            continue;
+        }
+        if let Some(name) = inner.strip_prefix('/') {
+            tokens.push(Token::End(tag_name(name)));
+            continue;
+        }
+        let name = tag_name(inner);
+        if name.is_empty() {
+            // A lone '<' in text
+            tokens.push(Token::Text(&html[start..pos]));
+            continue;
+        }
+        let attrs = &inner[name.len().min(inner.len())..];
+        let self_closing = attrs.trim_end().ends_with('/');
+        let is_raw = RAW_TEXT.contains(&name.as_str());
+        tokens.push(Token::Start {
+            name: name.clone(),
+            attrs,
+            self_closing,
+        });
+        if is_raw && !self_closing {
+            let close = format!("</{name}");
+            let text_end = lower[pos..].find(&close).map_or(html.len(), |i| pos + i);
+            tokens.push(Token::Text(&html[pos..text_end]));
+            tokens.push(Token::End(name));
+            pos = html[text_end..]
+                .find('>')
+                .map_or(html.len(), |i| text_end + i + 1);
+        }
+    }
+    tokens
+}
+
+/// Index of the `>` closing the tag at the start of `rest`, skipping quoted attribute values.
+fn find_tag_end(rest: &str) -> Option<usize> {
+    let mut quote: Option<char> = None;
+    for (i, c) in rest.char_indices().skip(1) {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn tag_name(inner: &str) -> String {
+    inner
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+/// Value of attribute `name` in a tag's attribute text.
+fn attr(attrs: &str, name: &str) -> Option<String> {
+    let lower = attrs.to_ascii_lowercase();
+    let mut search = 0;
+    while let Some(found) = lower[search..].find(name) {
+        let start = search + found;
+        search = start + name.len();
+        let before_ok = start == 0 || lower.as_bytes()[start - 1].is_ascii_whitespace();
+        let after = attrs[search..].trim_start();
+        if !before_ok || !after.starts_with('=') {
+            continue;
+        }
+        let value = after[1..].trim_start();
+        let parsed = match value.chars().next() {
+            Some(q @ ('"' | '\'')) => value[1..].split(q).next().unwrap_or_default(),
+            _ => value
+                .split(|c: char| c.is_whitespace() || c == '>')
+                .next()
+                .unwrap_or_default(),
+        };
+        return Some(decode_entities(parsed));
+    }
+    None
+}
+
+/// Decode the named entities common in text plus numeric character references.
+pub fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find('&') {
+        output.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+        let entity_end = rest.find(';').filter(|end| *end <= 10);
+        let decoded = entity_end.and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some(' '),
+                "mdash" => Some('—'),
+                "ndash" => Some('–'),
+                "hellip" => Some('…'),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|d| d.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                output.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                output.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+struct MarkdownWriter<'a> {
+    base: Option<&'a Url>,
+    out: String,
+    /// Depth of skipped elements currently open
+    skip_depth: usize,
+    in_pre: bool,
+    list_depth: usize,
+    /// Link target and the position in `out` where its text starts
+    links: Vec<(Option<String>, usize)>,
+}
+
+impl<'a> MarkdownWriter<'a> {
+    fn new(base: Option<&'a Url>) -> Self {
+        Self {
+            base,
+            out: String::new(),
+            skip_depth: 0,
+            in_pre: false,
+            list_depth: 0,
+            links: Vec::new(),
+        }
+    }
+
+    fn ensure_blank_line(&mut self) {
+        let trimmed = self.out.trim_end_matches(' ').len();
+        self.out.truncate(trimmed);
+        if self.out.is_empty() || self.out.ends_with("\n\n") {
+            return;
+        }
+        if self.out.ends_with('\n') {
+            self.out.push('\n');
+        } else {
+            self.out.push_str("\n\n");
+        }
+    }
+
+    fn ensure_newline(&mut self) {
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        let text = decode_entities(text);
+        if self.in_pre {
+            self.out.push_str(&text);
+            return;
+        }
+        let leading = text.starts_with(char::is_whitespace);
+        let trailing = text.ends_with(char::is_whitespace);
+        let words: Vec<&str> = text.split_whitespace().collect();
+        if words.is_empty() {
+            if leading && !self.out.is_empty() && !self.out.ends_with([' ', '\n']) {
+                self.out.push(' ');
+            }
+            return;
+        }
+        if leading && !self.out.is_empty() && !self.out.ends_with([' ', '\n']) {
+            self.out.push(' ');
+        }
+        self.out.push_str(&words.join(" "));
+        if trailing {
+            self.out.push(' ');
+        }
+    }
+
+    fn resolve(&self, href: &str) -> Option<String> {
+        if href.starts_with('#') || href.starts_with("javascript:") || href.starts_with("mailto:") {
+            return None;
+        }
+        match self.base {
+            Some(base) => base.join(href).ok().map(|u| u.to_string()),
+            None => Some(href.to_string()),
+        }
+    }
+
+    fn start(&mut self, name: &str, attrs: &str, self_closing: bool) {
+        if self.skip_depth > 0 || SKIPPED.contains(&name) {
+            if !self_closing && !is_void(name) {
+                self.skip_depth += 1;
+            }
+            return;
+        }
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.ensure_blank_line();
+                let level = name[1..].parse::<usize>().unwrap_or(1);
+                self.out.push_str(&"#".repeat(level));
+                self.out.push(' ');
+            }
+            "br" => self.out.push('\n'),
+            "li" => {
+                self.ensure_newline();
+                self.out
+                    .push_str(&"  ".repeat(self.list_depth.saturating_sub(1)));
+                self.out.push_str("- ");
+            }
+            "ul" | "ol" => {
+                if self.list_depth == 0 {
+                    self.ensure_blank_line();
+                }
+                self.list_depth += 1;
+            }
+            "pre" => {
+                self.ensure_blank_line();
+                self.out.push_str("```\n");
+                self.in_pre = true;
+            }
+            "code" if !self.in_pre => self.out.push('`'),
+            "strong" | "b" => self.out.push_str("**"),
+            "em" | "i" => self.out.push('*'),
+            "blockquote" => {
+                self.ensure_blank_line();
+                self.out.push_str("> ");
+            }
+            "td" | "th" => self.out.push_str(" | "),
+            "a" => {
+                let href = attr(attrs, "href").and_then(|href| self.resolve(&href));
+                if href.is_some() {
+                    self.out.push('[');
+                }
+                self.links.push((href, self.out.len()));
+            }
+            "img" => {
+                if let Some(alt) = attr(attrs, "alt").filter(|a| !a.trim().is_empty()) {
+                    self.text(&format!(" [image: {}] ", alt.trim()));
+                }
+            }
+            _ if BLOCKS.contains(&name) => self.ensure_blank_line(),
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, name: &str) {
+        if self.skip_depth > 0 {
+            if SKIPPED.contains(&name) {
+                self.skip_depth -= 1;
+            }
+            return;
+        }
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "blockquote" => self.ensure_blank_line(),
+            "ul" | "ol" => {
+                self.list_depth = self.list_depth.saturating_sub(1);
+                if self.list_depth == 0 {
+                    self.ensure_blank_line();
+                }
+            }
+            "pre" => {
+                self.in_pre = false;
+                self.ensure_newline();
+                self.out.push_str("```");
+                self.ensure_blank_line();
+            }
+            "code" if !self.in_pre => self.out.push('`'),
+            "strong" | "b" => self.out.push_str("**"),
+            "em" | "i" => self.out.push('*'),
+            "tr" => self.ensure_newline(),
+            "a" => {
+                if let Some((Some(href), start)) = self.links.pop() {
+                    if self.out.len() == start {
+                        // Links without text are dropped
+                        self.out.truncate(start - 1);
+                    } else {
+                        self.out.push_str(&format!("]({href})"));
+                    }
+                }
+            }
+            _ if BLOCKS.contains(&name) => self.ensure_blank_line(),
+            _ => {}
+        }
+    }
+
+    fn finish(self) -> String {
+        let mut lines: Vec<&str> = Vec::new();
+        let mut blank = false;
+        for line in self.out.lines() {
+            let line = line.trim_end();
+            if line.trim().is_empty() {
+                if !blank && !lines.is_empty() {
+                    lines.push("");
+                }
+                blank = true;
+            } else {
+                lines.push(line);
+                blank = false;
+            }
+        }
+        lines.join("\n").trim().to_string()
+    }
+}
+
+fn is_void(name: &str) -> bool {
+    matches!(
+        name,
+        "br" | "hr" | "img" | "input" | "meta" | "link" | "source" | "area" | "wbr" | "col"
+    )
+}
+
+/// Tokens of the element holding the main content: the first `<article>`, else `<main>`,
+/// else `<body>`, else the whole document.
+fn main_content<'t, 'a>(tokens: &'t [Token<'a>]) -> &'t [Token<'a>] {
+    for candidate in ["article", "main", "body"] {
+        let Some(start) = tokens
+            .iter()
+            .position(|t| matches!(t, Token::Start { name, .. } if name == candidate))
+        else {
+            continue;
+        };
+        let mut depth = 0usize;
+        for (i, token) in tokens.iter().enumerate().skip(start) {
+            match token {
+                Token::Start { name, .. } if name == candidate => depth += 1,
+                Token::End(name) if name == candidate => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return &tokens[start + 1..i];
+                    }
+                }
+                _ => {}
+            }
+        }
+        return &tokens[start + 1..];
+    }
+    tokens
+}
+
+/// The page title from `<title>` or, failing that, the first `<h1>`.
+fn page_title(tokens: &[Token<'_>]) -> Option<String> {
+    let text_after = |tag: &str| {
+        let start = tokens
+            .iter()
+            .position(|t| matches!(t, Token::Start { name, .. } if name == tag))?;
+        let text: Vec<String> = tokens[start + 1..]
+            .iter()
+            .take_while(|t| !matches!(t, Token::End(name) if name == tag))
+            .filter_map(|t| match t {
+                Token::Text(text) => Some(decode_entities(text)),
+                _ => None,
+            })
+            .collect();
+        let text = text
+            .join(" ")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        (!text.is_empty()).then_some(text)
+    };
+    text_after("title").or_else(|| text_after("h1"))
+}
+
+/// Extract the title and the main content of an HTML page as Markdown. Relative links are
+/// resolved against `base`.
+pub fn html_to_markdown(html: &str, base: Option<&Url>) -> (Option<String>, String) {
+    let tokens = tokenize(html);
+    let title = page_title(&tokens);
+    let mut writer = MarkdownWriter::new(base);
+    for token in main_content(&tokens) {
+        match token {
+            Token::Start {
+                name,
+                attrs,
+                self_closing,
+            } => writer.start(name, attrs, *self_closing),
+            Token::End(name) => writer.end(name),
+            Token::Text(text) => {
+                if writer.skip_depth == 0 {
+                    writer.text(text)
+                }
+            }
+        }
+    }
+    (title, writer.finish())
+}
@@ -0,0 +1,389 @@
+use std::fs;
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use hyper::client::connect::dns::Name;
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use url::{Host, Url};
+
+use super::constants::{
+    CACHE_TTL, FETCH_TIMEOUT, MAX_PAGE_BYTES, MAX_REDIRECTS, MAX_TOOL_CHARS, ROBOTS_AGENT,
+    ROBOTS_TIMEOUT, WEB_FETCH_CACHE_DIR, WEB_FETCH_DIR, WEB_FETCH_SETTINGS_FILE, WEB_FETCH_TOOL,
+};
+use super::extract::html_to_markdown;
+use super::models::{FetchedPage, WebFetchSettings};
+use super::robots::Robots;
+use crate::core::mcp::models::ToolWithServer;
+
+pub fn get_settings_path(data_folder: &Path) -> PathBuf {
+    data_folder
+        .join(WEB_FETCH_DIR)
+        .join(WEB_FETCH_SETTINGS_FILE)
+}
+
+/// Read fetch settings, defaulting to the built-in tool being disabled
+pub fn read_settings(data_folder: &Path) -> WebFetchSettings {
+    fs::read_to_string(get_settings_path(data_folder))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+pub fn write_settings(data_folder: &Path, settings: &WebFetchSettings) -> Result<(), String> {
+    let path = get_settings_path(data_folder);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let data = serde_json::to_string_pretty(settings).map_err(|e| e.to_string())?;
+    fs::write(path, data).map_err(|e| e.to_string())
+}
+
+pub fn get_cache_dir(data_folder: &Path) -> PathBuf {
+    data_folder.join(WEB_FETCH_DIR).join(WEB_FETCH_CACHE_DIR)
+}
+
+fn cache_path(data_folder: &Path, url: &str) -> PathBuf {
+    let hash = hex::encode(Sha256::digest(url.as_bytes()));
+    get_cache_dir(data_folder).join(format!("{hash}.json"))
+}
+
+/// A cached page for `url` that is younger than the cache lifetime.
+pub fn read_cache(data_folder: &Path, url: &str) -> Option<FetchedPage> {
+    let data = fs::read_to_string(cache_path(data_folder, url)).ok()?;
+    let page: FetchedPage = serde_json::from_str(&data).ok()?;
+    let age = chrono::Utc::now().timestamp_millis() - page.fetched_at;
+    (age >= 0 && (age as u128) < CACHE_TTL.as_millis()).then_some(FetchedPage {
+        cached: true,
+        ..page
+    })
+}
+
+pub fn write_cache(data_folder: &Path, page: &FetchedPage) -> Result<(), String> {
+    let path = cache_path(data_folder, &page.url);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let data = serde_json::to_string(page).map_err(|e| e.to_string())?;
+    fs::write(path, data).map_err(|e| e.to_string())
+}
+
+/// Remove all cached pages, returning how many were removed.
+pub fn clear_cache(data_folder: &Path) -> Result<usize, String> {
+    let dir = get_cache_dir(data_folder);
+    if !dir.exists() {
+        return Ok(0);
+    }
+    let mut removed = 0;
+    for entry in fs::read_dir(&dir).map_err(|e| e.to_string())?.flatten() {
+        if entry.path().extension().is_some_and(|ext| ext == "json") {
+            fs::remove_file(entry.path()).map_err(|e| e.to_string())?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+/// Validate a user or model supplied URL. Only http and https are fetched.
+pub fn parse_fetch_url(url: &str) -> Result<Url, String> {
+    let mut parsed = Url::parse(url.trim()).map_err(|e| format!("Invalid URL '{url}': {e}"))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!(
+            "Only http and https URLs can be fetched, got '{url}'"
+        ));
+    }
+    if parsed.host_str().is_none() {
+        return Err(format!("URL '{url}' has no host"));
+    }
+    check_host_literal(&parsed)?;
+    parsed.set_fragment(None);
+    Ok(parsed)
+}
+
+/// Whether `ip` is on the public internet. Loopback, private (RFC 1918), link-local
+/// (169.254/16, fe80::/10), unique local (fc00::/7) and unspecified addresses are not, so a
+/// page or the model cannot point the fetcher at this machine or the local network.
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            !(ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast())
+        }
+        IpAddr::V6(ip) => {
+            if let Some(mapped) = ip.to_ipv4_mapped() {
+                return is_public_ip(IpAddr::V4(mapped));
+            }
+            let first = ip.segments()[0];
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80)
+        }
+    }
+}
+
+/// Reject a URL whose host is a non-public IP literal. Host names are checked when they are
+/// resolved, by `PublicResolver`.
+fn check_host_literal(url: &Url) -> Result<(), String> {
+    let ip = match url.host() {
+        Some(Host::Ipv4(ip)) => IpAddr::V4(ip),
+        Some(Host::Ipv6(ip)) => IpAddr::V6(ip),
+        _ => return Ok(()),
+    };
+    if is_public_ip(ip) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Fetching {url} is not allowed: {ip} is a local address"
+        ))
+    }
+}
+
+/// Resolve a host name, refusing it when any of its addresses is not public.
+async fn resolve_public(host: &str) -> Result<Vec<SocketAddr>, String> {
+    let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, 0))
+        .await
+        .map_err(|e| format!("Failed to resolve {host}: {e}"))?
+        .collect();
+    if addrs.is_empty() {
+        return Err(format!("Failed to resolve {host}"));
+    }
+    if let Some(addr) = addrs.iter().find(|addr| !is_public_ip(addr.ip())) {
+        return Err(format!(
+            "Fetching from {host} is not allowed: it resolves to the local address {}",
+            addr.ip()
+        ));
+    }
+    Ok(addrs)
+}
+
+/// DNS resolver for the fetch client. Connections only go to the addresses checked here, so
+/// every request and redirect hop is covered, including a name re-resolving to a local
+/// address after the first check.
+struct PublicResolver;
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let addrs = resolve_public(name.as_str()).await?;
+            Ok(Box::new(addrs.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// Follow redirects up to `MAX_REDIRECTS`, refusing hops to non-public IP literals, which
+/// never reach the resolver.
+fn redirect_policy() -> reqwest::redirect::Policy {
+    reqwest::redirect::Policy::custom(|attempt| {
+        if attempt.previous().len() >= MAX_REDIRECTS {
+            return attempt.error(format!("More than {MAX_REDIRECTS} redirects"));
+        }
+        match check_host_literal(attempt.url()) {
+            Ok(()) => attempt.follow(),
+            Err(e) => attempt.error(e),
+        }
+    })
+}
+
+fn user_agent() -> String {
+    format!(
+        "Mozilla/5.0 (compatible; Jan/{}; +https://jan.ai)",
+        env!("CARGO_PKG_VERSION")
+    )
+}
+
+/// Rules of the site's robots.txt. Following the usual conventions, a missing file allows
+/// everything and a server error disallows everything.
+async fn site_robots(client: &reqwest::Client, url: &Url) -> Robots {
+    let Ok(robots_url) = url.join("/robots.txt") else {
+        return Robots::allow_all();
+    };
+    let response = match client.get(robots_url).timeout(ROBOTS_TIMEOUT).send().await {
+        Ok(response) => response,
+        Err(e) => {
+            log::debug!("Could not fetch robots.txt for {url}: {e}");
+            return Robots::allow_all();
+        }
+    };
+    let status = response.status();
+    if status.is_server_error() {
+        return Robots::disallow_all();
+    }
+    if !status.is_success() {
+        return Robots::allow_all();
+    }
+    match response.text().await {
+        Ok(content) => Robots::parse(&content),
+        Err(_) => Robots::allow_all(),
+    }
+}
+
+fn robots_path(url: &Url) -> String {
+    match url.query() {
+        Some(query) => format!("{}?{query}", url.path()),
+        None => url.path().to_string(),
+    }
+}
+
+/// Read the body, failing as soon as it exceeds `MAX_PAGE_BYTES`.
+async fn read_limited(mut response: reqwest::Response) -> Result<Vec<u8>, String> {
+    if response
+        .content_length()
+        .is_some_and(|len| len as usize > MAX_PAGE_BYTES)
+    {
+        return Err(format!(
+            "Page is larger than {} MB",
+            MAX_PAGE_BYTES / (1024 * 1024)
+        ));
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+        if body.len() + chunk.len() > MAX_PAGE_BYTES {
+            return Err(format!(
+                "Page is larger than {} MB",
+                MAX_PAGE_BYTES / (1024 * 1024)
+            ));
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// Markdown for a page, prefixed with the source line used for citation.
+pub fn page_markdown(title: Option<&str>, url: &str, content: &str, fetched_at: i64) -> String {
+    let retrieved = chrono::DateTime::from_timestamp_millis(fetched_at)
+        .map(|at| at.format("%Y-%m-%d").to_string())
+        .unwrap_or_default();
+    format!(
+        "Source: [{}]({url})\nRetrieved: {retrieved}\n\n{content}",
+        title.unwrap_or(url)
+    )
+}
+
+/// Fetch a page and extract its main content, serving it from the cache when possible.
+pub async fn fetch_page(
+    data_folder: &Path,
+    url: &str,
+    force_refresh: bool,
+) -> Result<FetchedPage, String> {
+    let url = parse_fetch_url(url)?;
+    if !force_refresh {
+        if let Some(page) = read_cache(data_folder, url.as_str()) {
+            return Ok(page);
+        }
+    }
+
+    if let Some(Host::Domain(host)) = url.host() {
+        resolve_public(host).await?;
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .user_agent(user_agent())
+        .dns_resolver(Arc::new(PublicResolver))
+        .redirect(redirect_policy())
+        .build()
+        .map_err(|e| e.to_string())?;
+    if !site_robots(&client, &url)
+        .await
+        .is_allowed(ROBOTS_AGENT, &robots_path(&url))
+    {
+        return Err(format!(
+            "Fetching {url} is disallowed by the site's robots.txt"
+        ));
+    }
+
+    let response = client
+        .get(url.clone())
+        .header(
+            reqwest::header::ACCEPT,
+            "text/html,application/xhtml+xml,text/plain;q=0.8",
+        )
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch {url}: {e}"))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("Fetching {url} failed with status {status}"));
+    }
+    let final_url = response.url().clone();
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("text/html")
+        .to_ascii_lowercase();
+    let is_html =
+        content_type.starts_with("text/html") || content_type.starts_with("application/xhtml+xml");
+    if !is_html && !content_type.starts_with("text/plain") {
+        return Err(format!("Unsupported content type '{content_type}'"));
+    }
+    let body = read_limited(response).await?;
+    let text = String::from_utf8_lossy(&body);
+
+    let (title, content) = if is_html {
+        html_to_markdown(&text, Some(&final_url))
+    } else {
+        (None, text.trim().to_string())
+    };
+    if content.is_empty() {
+        return Err(format!("No readable content found at {final_url}"));
+    }
+    let fetched_at = chrono::Utc::now().timestamp_millis();
+    let page = FetchedPage {
+        url: url.to_string(),
+        final_url: final_url.to_string(),
+        markdown: page_markdown(title.as_deref(), final_url.as_str(), &content, fetched_at),
+        title,
+        fetched_at,
+        cached: false,
+    };
+    if let Err(e) = write_cache(data_folder, &page) {
+        log::warn!("Failed to cache {url}: {e}");
+    }
+    Ok(page)
+}
+
+/// Page Markdown for the model, truncated to `MAX_TOOL_CHARS`.
+pub fn format_for_model(page: &FetchedPage) -> String {
+    match page.markdown.char_indices().nth(MAX_TOOL_CHARS) {
+        Some((end, _)) => format!(
+            "{}\n\n[Content truncated. Cite the page as {}]",
+            page.markdown[..end].trim_end(),
+            page.final_url
+        ),
+        None => page.markdown.clone(),
+    }
+}
+
+/// Definition of the built-in fetch tool.
+pub fn web_fetch_tool(server: &str) -> ToolWithServer {
+    ToolWithServer {
+        name: WEB_FETCH_TOOL.to_string(),
+        description: Some(
+            "Fetch a web page and return its main content as Markdown with a source line to cite."
+                .to_string(),
+        ),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "url": {"type": "string", "description": "http or https URL of the page"}
+            },
+            "required": ["url"]
+        }),
+        server: server.to_string(),
+    }
+}
+
+/// The URL argument of the built-in tool.
+pub fn url_from_arguments(arguments: &Map<String, Value>) -> Result<String, String> {
+    arguments
+        .get("url")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| "Missing 'url' argument".to_string())
+}
@@ -0,0 +1,25 @@
+/*!
+   Web Page Fetch and Extract
+
+   Fetches a URL and turns it into readable Markdown for "chat with this link" and for the
+   agent loop's built-in `web_fetch` tool:
+   - `robots.txt` of the site is honoured for Jan's user agent (falling back to the `*` group),
+   - hosts resolving to loopback, private, link-local or unique local addresses are refused,
+     before the first request and on every redirect hop,
+   - responses are limited in size and only HTML and plain text are accepted,
+   - the main content (`<article>`, then `<main>`, then `<body>`) is extracted with navigation,
+     scripts, forms and other chrome removed, and converted to Markdown with absolute links,
+   - the result starts with a source line so answers can cite the page.
+
+   Extracted pages are cached under `web_fetch/cache/` for an hour, keyed by URL hash.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod extract;
+pub mod helpers;
+pub mod models;
+pub mod robots;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+/// Contents of `web_fetch/settings.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WebFetchSettings {
+    /// Offer page fetching to the agent loop as the built-in `web_fetch` tool
+    #[serde(default)]
+    pub builtin_tool_enabled: bool,
+}
+
+/// A fetched page converted to Markdown
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FetchedPage {
+    /// URL that was requested
+    pub url: String,
+    /// URL after redirects, used for links and citation
+    pub final_url: String,
+    pub title: Option<String>,
+    /// Extracted content, starting with a source line
+    pub markdown: String,
+    /// Milliseconds since the Unix epoch
+    pub fetched_at: i64,
+    /// Whether this page was served from the cache
+    #[serde(default)]
+    pub cached: bool,
+}
@@ -0,0 +1,138 @@
+//! Minimal `robots.txt` support: groups by user agent, `Allow`/`Disallow` with `*` and `$`
+//! patterns, longest match wins and `Allow` wins ties.
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Group {
+    agents: Vec<String>,
+    /// (allow, pattern)
+    rules: Vec<(bool, String)>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Robots {
+    groups: Vec<Group>,
+}
+
+impl Robots {
+    /// Rules that allow everything, used when a site has no robots.txt
+    pub fn allow_all() -> Self {
+        Self::default()
+    }
+
+    /// Rules that disallow everything, used when robots.txt is temporarily unavailable
+    pub fn disallow_all() -> Self {
+        Self {
+            groups: vec![Group {
+                agents: vec!["*".to_string()],
+                rules: vec![(false, "/".to_string())],
+            }],
+        }
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let mut groups: Vec<Group> = Vec::new();
+        let mut current: Option<Group> = None;
+        let mut in_agents = false;
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_ascii_lowercase();
+            let value = value.trim();
+            match key.as_str() {
+                "user-agent" => {
+                    // Consecutive user-agent lines share one group
+                    if !in_agents {
+                        if let Some(group) = current.take() {
+                            groups.push(group);
+                        }
+                        current = Some(Group::default());
+                    }
+                    if let Some(group) = current.as_mut() {
+                        group.agents.push(value.to_ascii_lowercase());
+                    }
+                    in_agents = true;
+                }
+                "allow" | "disallow" => {
+                    in_agents = false;
+                    // An empty Disallow allows everything and adds no rule
+                    if let (Some(group), false) = (current.as_mut(), value.is_empty()) {
+                        group.rules.push((key == "allow", value.to_string()));
+                    }
+                }
+                _ => in_agents = false,
+            }
+        }
+        if let Some(group) = current {
+            groups.push(group);
+        }
+        Self { groups }
+    }
+
+    /// Whether `agent` may fetch `path` (path plus query string).
+    pub fn is_allowed(&self, agent: &str, path: &str) -> bool {
+        let agent = agent.to_ascii_lowercase();
+        let specific: Vec<&Group> = self
+            .groups
+            .iter()
+            .filter(|g| {
+                g.agents
+                    .iter()
+                    .any(|a| a != "*" && agent.contains(a.as_str()))
+            })
+            .collect();
+        let groups = if specific.is_empty() {
+            self.groups
+                .iter()
+                .filter(|g| g.agents.iter().any(|a| a == "*"))
+                .collect()
+        } else {
+            specific
+        };
+
+        let mut best: Option<(usize, bool)> = None;
+        for (allow, pattern) in groups.iter().flat_map(|g| g.rules.iter()) {
+            if !pattern_matches(pattern, path) {
+                continue;
+            }
+            let len = pattern.len();
+            best = match best {
+                Some((best_len, best_allow))
+                    if best_len > len || (best_len == len && best_allow) =>
+                {
+                    Some((best_len, best_allow))
+                }
+                _ => Some((len, *allow)),
+            };
+        }
+        best.map_or(true, |(_, allow)| allow)
+    }
+}
+
+/// Match a robots.txt path pattern: a prefix match where `*` matches any run of characters
+/// and a trailing `$` anchors the end.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let mut rest = path;
+    for (i, part) in parts.iter().enumerate() {
+        if i == 0 {
+            match rest.strip_prefix(part) {
+                Some(r) => rest = r,
+                None => return false,
+            }
+        } else if i == parts.len() - 1 && anchored {
+            return rest.ends_with(part);
+        } else {
+            match rest.find(part) {
+                Some(pos) => rest = &rest[pos + part.len()..],
+                None => return false,
+            }
+        }
+    }
+    !anchored || rest.is_empty()
+}
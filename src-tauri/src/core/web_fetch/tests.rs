@@ -0,0 +1,178 @@
+use std::fs;
+
+use url::Url;
+
+use super::extract::{decode_entities, html_to_markdown};
+use super::helpers::{
+    clear_cache, format_for_model, is_public_ip, page_markdown, parse_fetch_url, read_cache,
+    write_cache,
+};
+use super::models::FetchedPage;
+use super::robots::Robots;
+use crate::core::app::commands::get_jan_data_folder_path;
+
+#[test]
+fn test_robots_groups_and_precedence() {
+    let robots = Robots::parse(
+        "# comment\n\
+         User-agent: *\n\
+         Disallow: /private\n\
+         Allow: /private/open\n\
+         \n\
+         User-agent: Jan\n\
+         Disallow: /no-jan/\n\
+         Disallow: /*.pdf$\n",
+    );
+    // The specific group replaces the wildcard group
+    assert!(robots.is_allowed("jan", "/private/page"));
+    assert!(!robots.is_allowed("jan", "/no-jan/page"));
+    assert!(!robots.is_allowed("jan", "/docs/file.pdf"));
+    assert!(robots.is_allowed("jan", "/docs/file.pdf?x=1"));
+
+    assert!(!robots.is_allowed("otherbot", "/private/page"));
+    assert!(robots.is_allowed("otherbot", "/private/open/page"));
+    assert!(robots.is_allowed("otherbot", "/public"));
+}
+
+#[test]
+fn test_robots_defaults() {
+    assert!(Robots::parse("").is_allowed("jan", "/anything"));
+    assert!(Robots::allow_all().is_allowed("jan", "/"));
+    assert!(!Robots::disallow_all().is_allowed("jan", "/"));
+    // An empty Disallow allows everything
+    assert!(Robots::parse("User-agent: *\nDisallow:\n").is_allowed("jan", "/x"));
+}
+
+#[test]
+fn test_extracts_article_as_markdown() {
+    let html = r#"<!DOCTYPE html>
+<html><head><title>Rust &amp; Friends</title><script>var x = "<p>no</p>";</script></head>
+<body>
+  <nav><a href="/home">Home</a></nav>
+  <article>
+    <h1>Ownership</h1>
+    <p>Every value has an <strong>owner</strong>. See <a href="/book/ch04">the book</a>.</p>
+    <!-- a comment -->
+    <ul><li>One</li><li>Two</li></ul>
+    <pre><code>let x = 1;
+let y = x;</code></pre>
+    <form><button>Subscribe</button></form>
+  </article>
+  <footer>Copyright</footer>
+</body></html>"#;
+    let base = Url::parse("https://example.com/post").unwrap();
+    let (title, markdown) = html_to_markdown(html, Some(&base));
+
+    assert_eq!(title.as_deref(), Some("Rust & Friends"));
+    assert!(markdown.starts_with("# Ownership"));
+    assert!(markdown.contains("Every value has an **owner**."));
+    assert!(markdown.contains("[the book](https://example.com/book/ch04)"));
+    assert!(markdown.contains("- One\n- Two"));
+    assert!(markdown.contains("```\nlet x = 1;\nlet y = x;\n```"));
+    for removed in ["Home", "Subscribe", "Copyright", "no</p>", "comment"] {
+        assert!(!markdown.contains(removed), "{removed} should be removed");
+    }
+}
+
+#[test]
+fn test_falls_back_to_body() {
+    let (title, markdown) = html_to_markdown(
+        "<html><body><h1>Heading</h1><div>Text<br>next line</div></body></html>",
+        None,
+    );
+    assert_eq!(title.as_deref(), Some("Heading"));
+    assert_eq!(markdown, "# Heading\n\nText\nnext line");
+}
+
+#[test]
+fn test_decode_entities() {
+    assert_eq!(
+        decode_entities("a &lt;b&gt; &#39;c&#x27; &quot;d&quot; &unknown; & e"),
+        "a <b> 'c' \"d\" &unknown; & e"
+    );
+}
+
+#[test]
+fn test_parse_fetch_url() {
+    assert_eq!(
+        parse_fetch_url(" https://example.com/a#section ")
+            .unwrap()
+            .as_str(),
+        "https://example.com/a"
+    );
+    assert!(parse_fetch_url("file:///etc/passwd").is_err());
+    assert!(parse_fetch_url("not a url").is_err());
+    assert!(parse_fetch_url("http://127.0.0.1:8080/").is_err());
+    assert!(parse_fetch_url("http://[::1]/").is_err());
+    assert!(parse_fetch_url("http://169.254.169.254/latest/meta-data").is_err());
+}
+
+#[test]
+fn test_is_public_ip() {
+    for ip in [
+        "127.0.0.1",
+        "10.1.2.3",
+        "172.16.0.1",
+        "192.168.1.1",
+        "169.254.169.254",
+        "0.0.0.0",
+        "::1",
+        "fc00::1",
+        "fd12:3456::1",
+        "fe80::1",
+        "::ffff:192.168.1.1",
+    ] {
+        assert!(!is_public_ip(ip.parse().unwrap()), "{ip}");
+    }
+    for ip in ["93.184.216.34", "172.32.0.1", "2606:2800:220:1::1"] {
+        assert!(is_public_ip(ip.parse().unwrap()), "{ip}");
+    }
+}
+
+#[test]
+fn test_cache_roundtrip_and_expiry() {
+    let app = tauri::test::mock_app();
+    let data_dir = get_jan_data_folder_path(app.handle().clone());
+    let now = chrono::Utc::now().timestamp_millis();
+    let page = FetchedPage {
+        url: "https://example.com/".to_string(),
+        final_url: "https://example.com/".to_string(),
+        title: Some("Example".to_string()),
+        markdown: page_markdown(Some("Example"), "https://example.com/", "Body", now),
+        fetched_at: now,
+        cached: false,
+    };
+    write_cache(&data_dir, &page).unwrap();
+    let cached = read_cache(&data_dir, "https://example.com/").unwrap();
+    assert!(cached.cached);
+    assert!(cached
+        .markdown
+        .starts_with("Source: [Example](https://example.com/)\nRetrieved: "));
+
+    let stale = FetchedPage {
+        url: "https://example.com/old".to_string(),
+        fetched_at: now - 2 * 60 * 60 * 1000,
+        ..page
+    };
+    write_cache(&data_dir, &stale).unwrap();
+    assert!(read_cache(&data_dir, "https://example.com/old").is_none());
+
+    assert_eq!(clear_cache(&data_dir).unwrap(), 2);
+    assert!(read_cache(&data_dir, "https://example.com/").is_none());
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[test]
+fn test_format_for_model_truncates() {
+    let page = FetchedPage {
+        url: "https://example.com/".to_string(),
+        final_url: "https://example.com/".to_string(),
+        title: None,
+        markdown: "word ".repeat(10_000),
+        fetched_at: 0,
+        cached: false,
+    };
+    let text = format_for_model(&page);
+    assert!(text.len() < page.markdown.len());
+    assert!(text.ends_with("[Content truncated. Cite the page as https://example.com/]"));
+}
@@ -0,0 +1,64 @@
+use tauri::{AppHandle, Manager, Runtime};
+
+use super::helpers::{
+    canonical_workspace, read_store, touch_recent, unbind_thread, workspaces_lock, write_store,
+};
+use super::models::{RecentWorkspace, ThreadWorkspace};
+use crate::core::app::commands::get_jan_data_folder_path;
+use crate::core::mcp::roots::notify_roots_changed;
+use crate::core::state::AppState;
+
+/// Binds a thread to a folder, which becomes the root for its file access and one of the
+/// roots advertised to MCP servers.
+#[tauri::command]
+pub async fn set_thread_workspace<R: Runtime>(
+    app_handle: AppHandle<R>,
+    thread_id: String,
+    path: String,
+) -> Result<ThreadWorkspace, String> {
+    let data_folder = get_jan_data_folder_path(app_handle.clone());
+    let path = canonical_workspace(&path)?.to_string_lossy().to_string();
+    {
+        let _guard = workspaces_lock().lock().await;
+        let mut store = read_store(&data_folder);
+        store.bindings.insert(thread_id.clone(), path.clone());
+        touch_recent(&mut store, &path);
+        write_store(&data_folder, &store)?;
+    }
+    notify_roots_changed(&app_handle.state::<AppState>().mcp_servers).await;
+    Ok(ThreadWorkspace { thread_id, path })
+}
+
+/// Removes a thread's workspace binding. Returns whether the thread had one.
+#[tauri::command]
+pub async fn clear_thread_workspace<R: Runtime>(
+    app_handle: AppHandle<R>,
+    thread_id: String,
+) -> Result<bool, String> {
+    let unbound = unbind_thread(&get_jan_data_folder_path(app_handle.clone()), &thread_id).await?;
+    if unbound {
+        notify_roots_changed(&app_handle.state::<AppState>().mcp_servers).await;
+    }
+    Ok(unbound)
+}
+
+/// Returns the workspace a thread is bound to.
+#[tauri::command]
+pub async fn get_thread_workspace<R: Runtime>(
+    app_handle: AppHandle<R>,
+    thread_id: String,
+) -> Result<Option<ThreadWorkspace>, String> {
+    let store = read_store(&get_jan_data_folder_path(app_handle));
+    Ok(store.bindings.get(&thread_id).map(|path| ThreadWorkspace {
+        thread_id,
+        path: path.clone(),
+    }))
+}
+
+/// Lists recently used workspace folders, most recent first.
+#[tauri::command]
+pub async fn list_recent_workspaces<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> Result<Vec<RecentWorkspace>, String> {
+    Ok(read_store(&get_jan_data_folder_path(app_handle)).recent)
+}
@@ -0,0 +1,28 @@
+pub const WORKSPACES_DIR: &str = "workspaces";
+pub const WORKSPACES_FILE: &str = "workspaces.json";
+
+/// Number of recently used workspaces kept
+pub const MAX_RECENT_WORKSPACES: usize = 10;
+
+/// Built-in tools offered when a run is bound to a workspace
+pub const LIST_DIRECTORY_TOOL: &str = "list_directory";
+pub const READ_FILE_TOOL: &str = "read_file";
+
+/// JSON Schema `format`s declaring a tool argument a file system path, compared
+/// case-insensitively
+pub const PATH_FORMATS: [&str; 3] = ["path", "file-path", "directory-path"];
+/// Phrases in an argument's description declaring it a file system path
+pub const PATH_DESCRIPTION_PHRASES: [&str; 7] = [
+    "file path",
+    "directory path",
+    "folder path",
+    "relative path",
+    "absolute path",
+    "path to",
+    "path of",
+];
+
+/// Largest file `read_file` returns
+pub const MAX_READ_BYTES: u64 = 256 * 1024;
+/// Most entries `list_directory` returns
+pub const MAX_LIST_ENTRIES: usize = 500;
@@ -0,0 +1,337 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+use serde_json::{json, Map, Value};
+use tokio::sync::Mutex;
+
+use super::constants::{
+    LIST_DIRECTORY_TOOL, MAX_LIST_ENTRIES, MAX_READ_BYTES, MAX_RECENT_WORKSPACES,
+    PATH_DESCRIPTION_PHRASES, PATH_FORMATS, READ_FILE_TOOL, WORKSPACES_DIR, WORKSPACES_FILE,
+};
+use super::models::{RecentWorkspace, WorkspaceStore};
+use crate::core::mcp::models::ToolWithServer;
+
+// Global lock serializing read-modify-write cycles on workspaces.json
+static WORKSPACES_LOCK: OnceLock<Mutex<()>> = OnceLock::new();
+
+pub fn workspaces_lock() -> &'static Mutex<()> {
+    WORKSPACES_LOCK.get_or_init(|| Mutex::new(()))
+}
+
+pub fn get_store_path(data_folder: &Path) -> PathBuf {
+    data_folder.join(WORKSPACES_DIR).join(WORKSPACES_FILE)
+}
+
+pub fn read_store(data_folder: &Path) -> WorkspaceStore {
+    fs::read_to_string(get_store_path(data_folder))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+pub fn write_store(data_folder: &Path, store: &WorkspaceStore) -> Result<(), String> {
+    let path = get_store_path(data_folder);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let data = serde_json::to_string_pretty(store).map_err(|e| e.to_string())?;
+    fs::write(path, data).map_err(|e| e.to_string())
+}
+
+/// Canonical form of a folder chosen as workspace.
+pub fn canonical_workspace(path: &str) -> Result<PathBuf, String> {
+    let canonical = Path::new(path.trim())
+        .canonicalize()
+        .map_err(|e| format!("Workspace folder '{path}' is not accessible: {e}"))?;
+    if !canonical.is_dir() {
+        return Err(format!("Workspace '{path}' is not a folder"));
+    }
+    Ok(canonical)
+}
+
+/// Move `path` to the front of the recent list.
+pub fn touch_recent(store: &mut WorkspaceStore, path: &str) {
+    store.recent.retain(|r| r.path != path);
+    store.recent.insert(
+        0,
+        RecentWorkspace {
+            path: path.to_string(),
+            last_used_at: chrono::Utc::now().timestamp_millis(),
+        },
+    );
+    store.recent.truncate(MAX_RECENT_WORKSPACES);
+}
+
+/// The workspace folder a thread is bound to, if it still exists.
+pub fn workspace_for_thread(data_folder: &Path, thread_id: &str) -> Option<PathBuf> {
+    let store = read_store(data_folder);
+    let path = store.bindings.get(thread_id)?;
+    match canonical_workspace(path) {
+        Ok(path) => Some(path),
+        Err(e) => {
+            log::warn!("Ignoring workspace of thread {thread_id}: {e}");
+            None
+        }
+    }
+}
+
+/// The distinct folders bound to threads that still exist, advertised to MCP servers as
+/// the client's roots.
+pub fn bound_workspaces(data_folder: &Path) -> Vec<PathBuf> {
+    let mut folders: Vec<PathBuf> = read_store(data_folder)
+        .bindings
+        .values()
+        .filter_map(|path| canonical_workspace(path).ok())
+        .collect();
+    folders.sort();
+    folders.dedup();
+    folders
+}
+
+/// Remove a thread's binding, e.g. when the thread is deleted.
+pub async fn unbind_thread(data_folder: &Path, thread_id: &str) -> Result<bool, String> {
+    let _guard = workspaces_lock().lock().await;
+    let mut store = read_store(data_folder);
+    if store.bindings.remove(thread_id).is_none() {
+        return Ok(false);
+    }
+    write_store(data_folder, &store)?;
+    Ok(true)
+}
+
+/// Resolve `path` (absolute, relative to the workspace, or a `file://` URI) and ensure it lies
+/// inside `root`, which must be canonical. Paths that do not exist yet are checked through
+/// their closest existing ancestor.
+pub fn resolve_in_workspace(root: &Path, path: &str) -> Result<PathBuf, String> {
+    let trimmed = path.trim();
+    let raw = trimmed.strip_prefix("file://").unwrap_or(trimmed);
+    let candidate = if Path::new(raw).is_absolute() {
+        PathBuf::from(raw)
+    } else {
+        root.join(raw)
+    };
+
+    let mut existing = candidate.as_path();
+    let mut missing = Vec::new();
+    let canonical = loop {
+        match existing.canonicalize() {
+            Ok(canonical) => break canonical,
+            Err(_) => {
+                let Some(name) = existing.file_name() else {
+                    return Err(format!("Path '{path}' cannot be resolved"));
+                };
+                missing.push(name.to_os_string());
+                existing = existing
+                    .parent()
+                    .ok_or_else(|| format!("Path '{path}' cannot be resolved"))?;
+            }
+        }
+    };
+    // Components that don't exist yet cannot be symlinks, but may still be `..`
+    if Path::new(raw)
+        .components()
+        .any(|c| matches!(c, Component::ParentDir))
+        && !missing.is_empty()
+    {
+        return Err(format!("Path '{path}' is outside the workspace"));
+    }
+    let resolved = missing
+        .into_iter()
+        .rev()
+        .fold(canonical, |resolved, name| resolved.join(name));
+    if !resolved.starts_with(root) {
+        return Err(format!("Path '{path}' is outside the workspace"));
+    }
+    Ok(resolved)
+}
+
+fn scope_value(root: &Path, value: &Value) -> Result<Value, String> {
+    match value {
+        Value::String(path) if !path.trim().is_empty() => Ok(Value::String(
+            resolve_in_workspace(root, path)?
+                .to_string_lossy()
+                .to_string(),
+        )),
+        Value::Array(items) => items
+            .iter()
+            .map(|item| scope_value(root, item))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array),
+        other => Ok(other.clone()),
+    }
+}
+
+/// Lowercase words of an argument name, split at separators and camelCase humps
+fn name_words(name: &str) -> Vec<String> {
+    let mut words = vec![String::new()];
+    let mut after_lowercase = false;
+    for c in name.chars() {
+        if !c.is_alphanumeric() {
+            words.push(String::new());
+            after_lowercase = false;
+            continue;
+        }
+        if c.is_uppercase() && after_lowercase {
+            words.push(String::new());
+        }
+        after_lowercase = c.is_lowercase();
+        if let Some(word) = words.last_mut() {
+            word.extend(c.to_lowercase());
+        }
+    }
+    words.retain(|word| !word.is_empty());
+    words
+}
+
+/// Whether the input schema declares the argument `name`, described by `property`, a file
+/// system path: through a path `format` (of the value or of its array items), a name with
+/// the word "path" in it, or a description saying so. Generic names like `source` or
+/// `target` are not enough.
+pub fn is_path_argument(name: &str, property: &Value) -> bool {
+    let declared_format = [Some(property), property.get("items")]
+        .into_iter()
+        .flatten()
+        .filter_map(|schema| schema.get("format").and_then(Value::as_str))
+        .any(|format| {
+            PATH_FORMATS
+                .iter()
+                .any(|path_format| format.eq_ignore_ascii_case(path_format))
+        });
+    let named = name_words(name)
+        .iter()
+        .any(|word| word == "path" || word == "paths");
+    let described = property
+        .get("description")
+        .and_then(Value::as_str)
+        .map(str::to_lowercase)
+        .is_some_and(|description| {
+            PATH_DESCRIPTION_PHRASES
+                .iter()
+                .any(|phrase| description.contains(phrase))
+        });
+    declared_format || named || described
+}
+
+/// Resolve the arguments a tool's `input_schema` declares as paths inside the workspace, so
+/// relative paths refer to the workspace and calls reaching outside it are rejected.
+/// Arguments the schema doesn't declare are passed on as they are.
+pub fn scope_tool_arguments(
+    root: &Path,
+    input_schema: &Value,
+    arguments: Map<String, Value>,
+) -> Result<Map<String, Value>, String> {
+    let properties = input_schema.get("properties").and_then(Value::as_object);
+    arguments
+        .into_iter()
+        .map(|(key, value)| {
+            let declared = properties
+                .and_then(|properties| properties.get(&key))
+                .is_some_and(|property| is_path_argument(&key, property));
+            if declared {
+                Ok((key, scope_value(root, &value)?))
+            } else {
+                Ok((key, value))
+            }
+        })
+        .collect()
+}
+
+/// Scope a tool call made in `thread_id` to the thread's workspace, when it is bound to one.
+pub fn scope_thread_tool_arguments(
+    data_folder: &Path,
+    thread_id: Option<&str>,
+    input_schema: &Value,
+    arguments: Map<String, Value>,
+) -> Result<Map<String, Value>, String> {
+    match thread_id.and_then(|thread_id| workspace_for_thread(data_folder, thread_id)) {
+        Some(root) => scope_tool_arguments(&root, input_schema, arguments),
+        None => Ok(arguments),
+    }
+}
+
+/// Definitions of the file tools offered in a workspace.
+pub fn workspace_tools(server: &str) -> Vec<ToolWithServer> {
+    vec![
+        ToolWithServer {
+            name: LIST_DIRECTORY_TOOL.to_string(),
+            description: Some(
+                "List the files and folders of a directory in the thread's workspace.".to_string(),
+            ),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Directory relative to the workspace; defaults to its root"
+                    }
+                }
+            }),
+            server: server.to_string(),
+        },
+        ToolWithServer {
+            name: READ_FILE_TOOL.to_string(),
+            description: Some("Read a text file from the thread's workspace.".to_string()),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "path": {"type": "string", "description": "File relative to the workspace"}
+                },
+                "required": ["path"]
+            }),
+            server: server.to_string(),
+        },
+    ]
+}
+
+/// Run a workspace file tool. `arguments` must already be scoped to `root`.
+pub fn call_workspace_tool(
+    root: &Path,
+    name: &str,
+    arguments: &Map<String, Value>,
+) -> Result<String, String> {
+    let path = arguments.get("path").and_then(Value::as_str);
+    match name {
+        LIST_DIRECTORY_TOOL => {
+            let dir = match path {
+                Some(path) => resolve_in_workspace(root, path)?,
+                None => root.to_path_buf(),
+            };
+            let mut entries: Vec<String> = fs::read_dir(&dir)
+                .map_err(|e| format!("Cannot list '{}': {e}", dir.display()))?
+                .flatten()
+                .map(|entry| {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    if entry.file_type().is_ok_and(|t| t.is_dir()) {
+                        format!("{name}/")
+                    } else {
+                        name
+                    }
+                })
+                .collect();
+            entries.sort();
+            let total = entries.len();
+            entries.truncate(MAX_LIST_ENTRIES);
+            let mut text = entries.join("\n");
+            if total > MAX_LIST_ENTRIES {
+                text.push_str(&format!("\n[{} more entries]", total - MAX_LIST_ENTRIES));
+            }
+            Ok(text)
+        }
+        READ_FILE_TOOL => {
+            let path = path.ok_or_else(|| "Missing 'path' argument".to_string())?;
+            let file = resolve_in_workspace(root, path)?;
+            let size = fs::metadata(&file)
+                .map_err(|e| format!("Cannot read '{path}': {e}"))?
+                .len();
+            if size > MAX_READ_BYTES {
+                return Err(format!(
+                    "'{path}' is {size} bytes, larger than the {MAX_READ_BYTES} byte limit"
+                ));
+            }
+            let bytes = fs::read(&file).map_err(|e| format!("Cannot read '{path}': {e}"))?;
+            String::from_utf8(bytes).map_err(|_| format!("'{path}' is not a text file"))
+        }
+        _ => Err(format!("Unknown workspace tool '{name}'")),
+    }
+}
@@ -0,0 +1,25 @@
+/*!
+   Thread Workspaces
+
+   A thread can be bound to a folder on disk. While an agent run belongs to a bound thread:
+   - the built-in `list_directory` and `read_file` tools are offered, rooted at the folder,
+   - the arguments a tool's input schema declares as paths (by `format`, by a name with
+     "path" in it, or by their description) are resolved against the folder and rejected
+     when they point outside of it after canonicalization, so symlinks and `..` cannot
+     escape the workspace. This applies to every tool call made for the thread: agent runs,
+     the chat's `call_tool` and slash commands.
+
+   The bound folders are also advertised to MCP servers as the client's roots (see
+   `mcp::roots`), which covers the arguments a schema doesn't declare to servers honouring
+   roots.
+
+   Bindings and the recently used folders live in `workspaces/workspaces.json`.
+*/
+
+pub mod commands;
+pub mod constants;
+pub mod helpers;
+pub mod models;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,27 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecentWorkspace {
+    pub path: String,
+    /// Milliseconds since the Unix epoch
+    pub last_used_at: i64,
+}
+
+/// Contents of `workspaces/workspaces.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WorkspaceStore {
+    /// Canonical workspace folder per thread id
+    #[serde(default)]
+    pub bindings: HashMap<String, String>,
+    /// Most recently used first
+    #[serde(default)]
+    pub recent: Vec<RecentWorkspace>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThreadWorkspace {
+    pub thread_id: String,
+    pub path: String,
+}
@@ -0,0 +1,183 @@
+use std::fs;
+
+use serde_json::{json, Value};
+
+use super::constants::{LIST_DIRECTORY_TOOL, MAX_RECENT_WORKSPACES, READ_FILE_TOOL};
+use super::helpers::{
+    bound_workspaces, call_workspace_tool, canonical_workspace, is_path_argument, read_store,
+    resolve_in_workspace, scope_tool_arguments, touch_recent, unbind_thread, workspace_for_thread,
+    write_store,
+};
+use super::models::WorkspaceStore;
+use crate::core::app::commands::get_jan_data_folder_path;
+
+fn setup() -> (std::path::PathBuf, std::path::PathBuf) {
+    let app = tauri::test::mock_app();
+    let data_dir = get_jan_data_folder_path(app.handle().clone());
+    let root = data_dir.join("project");
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(root.join("src").join("main.rs"), "fn main() {}").unwrap();
+    fs::create_dir_all(data_dir.join("outside")).unwrap();
+    fs::write(data_dir.join("outside").join("secret.txt"), "secret").unwrap();
+    (
+        data_dir,
+        canonical_workspace(root.to_str().unwrap()).unwrap(),
+    )
+}
+
+#[test]
+fn test_resolve_in_workspace() {
+    let (data_dir, root) = setup();
+    assert_eq!(
+        resolve_in_workspace(&root, "src/main.rs").unwrap(),
+        root.join("src").join("main.rs")
+    );
+    // Files that don't exist yet are allowed inside the workspace
+    assert_eq!(
+        resolve_in_workspace(&root, "src/new/file.txt").unwrap(),
+        root.join("src").join("new").join("file.txt")
+    );
+    assert!(resolve_in_workspace(&root, "../outside/secret.txt").is_err());
+    assert!(resolve_in_workspace(&root, "src/../../outside/new.txt").is_err());
+    let outside = data_dir.join("outside").join("secret.txt");
+    assert!(resolve_in_workspace(&root, outside.to_str().unwrap()).is_err());
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_symlinks_cannot_escape() {
+    let (data_dir, root) = setup();
+    std::os::unix::fs::symlink(data_dir.join("outside"), root.join("link")).unwrap();
+    assert!(resolve_in_workspace(&root, "link/secret.txt").is_err());
+    assert!(resolve_in_workspace(&root, "link/new.txt").is_err());
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[test]
+fn test_scope_tool_arguments() {
+    let (data_dir, root) = setup();
+    let schema = json!({
+        "type": "object",
+        "properties": {
+            "path": {"type": "string"},
+            "paths": {"type": "array", "items": {"type": "string"}},
+            "source": {"type": "string"},
+            "query": {"type": "string"},
+            "limit": {"type": "integer"},
+            "File_Path": {"type": "string"}
+        }
+    });
+    let arguments = json!({
+        "path": "src",
+        "paths": ["src/main.rs"],
+        "source": "../x",
+        "query": "../x",
+        "limit": 3,
+        "extra": "../x"
+    });
+    let scoped =
+        scope_tool_arguments(&root, &schema, arguments.as_object().unwrap().clone()).unwrap();
+    assert_eq!(
+        scoped["path"],
+        Value::String(root.join("src").to_string_lossy().to_string())
+    );
+    assert_eq!(
+        scoped["paths"][0],
+        Value::String(
+            root.join("src")
+                .join("main.rs")
+                .to_string_lossy()
+                .to_string()
+        )
+    );
+    // Only arguments the schema declares as paths are scoped
+    assert_eq!(scoped["source"], "../x");
+    assert_eq!(scoped["query"], "../x");
+    assert_eq!(scoped["limit"], 3);
+    assert_eq!(scoped["extra"], "../x");
+
+    let escaping = json!({"File_Path": "../outside/secret.txt"});
+    assert!(scope_tool_arguments(&root, &schema, escaping.as_object().unwrap().clone()).is_err());
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[test]
+fn test_is_path_argument() {
+    assert!(is_path_argument("path", &json!({"type": "string"})));
+    assert!(is_path_argument("filePath", &json!({"type": "string"})));
+    assert!(is_path_argument("source_paths", &json!({"type": "array"})));
+    assert!(is_path_argument(
+        "source",
+        &json!({"type": "string", "description": "The path to the file to move"})
+    ));
+    assert!(is_path_argument(
+        "target",
+        &json!({"type": "string", "format": "Directory-Path"})
+    ));
+    assert!(is_path_argument(
+        "inputs",
+        &json!({"type": "array", "items": {"type": "string", "format": "path"}})
+    ));
+
+    assert!(!is_path_argument("source", &json!({"type": "string"})));
+    assert!(!is_path_argument("xpath", &json!({"type": "string"})));
+    assert!(!is_path_argument(
+        "content",
+        &json!({"type": "string", "description": "Text written to the file"})
+    ));
+}
+
+#[test]
+fn test_workspace_tools() {
+    let (data_dir, root) = setup();
+    let listing = call_workspace_tool(&root, LIST_DIRECTORY_TOOL, &Default::default()).unwrap();
+    assert_eq!(listing, "src/");
+    let content = call_workspace_tool(
+        &root,
+        READ_FILE_TOOL,
+        json!({"path": "src/main.rs"}).as_object().unwrap(),
+    )
+    .unwrap();
+    assert_eq!(content, "fn main() {}");
+    assert!(call_workspace_tool(
+        &root,
+        READ_FILE_TOOL,
+        json!({"path": "../outside/secret.txt"})
+            .as_object()
+            .unwrap(),
+    )
+    .is_err());
+    let _ = fs::remove_dir_all(data_dir);
+}
+
+#[tokio::test]
+async fn test_bindings_and_recent() {
+    let (data_dir, root) = setup();
+    let path = root.to_string_lossy().to_string();
+    let mut store = WorkspaceStore::default();
+    store.bindings.insert("thread-1".to_string(), path.clone());
+    store.bindings.insert(
+        "thread-2".to_string(),
+        data_dir.join("gone").to_string_lossy().to_string(),
+    );
+    for i in 0..MAX_RECENT_WORKSPACES + 2 {
+        touch_recent(&mut store, &format!("/folder/{i}"));
+    }
+    touch_recent(&mut store, &path);
+    write_store(&data_dir, &store).unwrap();
+
+    let store = read_store(&data_dir);
+    assert_eq!(store.recent.len(), MAX_RECENT_WORKSPACES);
+    assert_eq!(store.recent[0].path, path);
+    assert_eq!(workspace_for_thread(&data_dir, "thread-1"), Some(root));
+    // Bindings to folders that no longer exist are ignored
+    assert_eq!(workspace_for_thread(&data_dir, "thread-2"), None);
+    assert_eq!(bound_workspaces(&data_dir), vec![root.clone()]);
+
+    assert!(unbind_thread(&data_dir, "thread-1").await.unwrap());
+    assert!(!unbind_thread(&data_dir, "thread-1").await.unwrap());
+    assert_eq!(workspace_for_thread(&data_dir, "thread-1"), None);
+    assert!(bound_workspaces(&data_dir).is_empty());
+    let _ = fs::remove_dir_all(data_dir);
+}
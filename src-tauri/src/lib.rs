@@ -6,7 +6,7 @@ pub use core::openclaw::OpenClawState;
 #[cfg(not(feature = "cli"))]
 use core::{
     app::commands::get_jan_data_folder_path,
-    downloads::models::DownloadManagerState,
+    cancellation::CancellationTree,
     mcp::models::McpSettings,
     setup::{self, setup_mcp},
     state::AppState,
@@ -20,7 +20,7 @@ use tauri::{Emitter, Manager, RunEvent};
 #[cfg(not(feature = "cli"))]
 use tauri_plugin_store::StoreExt;
 #[cfg(not(feature = "cli"))]
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, RwLock};
 
 #[cfg(not(feature = "cli"))]
 #[cfg_attr(
@@ -31,9 +31,8 @@ pub fn run() {
     let mut builder = tauri::Builder::default();
     #[cfg(desktop)]
     {
-        builder = builder.plugin(tauri_plugin_single_instance::init(|_app, argv, _cwd| {
-          println!("a new app instance was opened with {argv:?} and the deep link event was already triggered");
-          // when defining deep link schemes at runtime, you must also check `argv` here
+        builder = builder.plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+            core::instance::helpers::handle_second_instance(app, argv, cwd);
         }));
     }
 
@@ -42,6 +41,7 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_store::Builder::new().build())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_llamacpp::init())
         .plugin(tauri_plugin_vector_db::init())
@@ -116,6 +116,7 @@ pub fn run() {
         core::server::remote_provider_commands::list_provider_configs,
         // MCP commands
         core::mcp::commands::get_tools,
+        core::mcp::commands::get_cached_tools,
         core::mcp::commands::call_tool,
         core::mcp::commands::cancel_tool_call,
         core::mcp::commands::restart_mcp_servers,
@@ -125,6 +126,18 @@ pub fn run() {
         core::mcp::commands::activate_mcp_server,
         core::mcp::commands::deactivate_mcp_server,
         core::mcp::commands::check_jan_browser_extension_connected,
+        core::mcp::commands::check_mcp_server_updates,
+        core::mcp::commands::update_mcp_server_version,
+        core::mcp::commands::send_mcp_request,
+        core::mcp::commands::set_mcp_wire_logging,
+        core::mcp::commands::get_mcp_wire_log,
+        core::mcp::commands::clear_mcp_wire_log,
+        core::mcp::commands::start_mcp_recording,
+        core::mcp::commands::stop_mcp_recording,
+        core::mcp::commands::list_mcp_recordings,
+        core::mcp::commands::export_mcp_config,
+        core::mcp::commands::import_mcp_config,
+        core::mcp::commands::respond_mcp_secret,
         // Threads
         core::threads::commands::list_threads,
         core::threads::commands::create_thread,
@@ -134,9 +147,295 @@ pub fn run() {
         core::threads::commands::create_message,
         core::threads::commands::modify_message,
         core::threads::commands::delete_message,
+        core::threads::commands::get_message_tree,
+        core::threads::commands::list_message_branches,
+        core::threads::commands::switch_message_branch,
+        core::threads::commands::prune_message_branch,
+        core::threads::commands::archive_thread,
+        core::threads::commands::unarchive_thread,
+        core::threads::commands::unlock_private_threads,
+        core::threads::commands::lock_private_threads,
+        core::threads::commands::are_private_threads_unlocked,
+        core::threads::commands::set_thread_private,
         core::threads::commands::get_thread_assistant,
         core::threads::commands::create_thread_assistant,
         core::threads::commands::modify_thread_assistant,
+        // Prompt templates
+        core::prompts::commands::list_prompt_templates,
+        core::prompts::commands::get_prompt_template,
+        core::prompts::commands::create_prompt_template,
+        core::prompts::commands::update_prompt_template,
+        core::prompts::commands::delete_prompt_template,
+        core::prompts::commands::render_prompt_template,
+        core::prompts::commands::set_assistant_default_template,
+        core::prompts::commands::get_assistant_default_template,
+        // Assistants
+        core::assistants::commands::list_assistants,
+        core::assistants::commands::get_assistant,
+        core::assistants::commands::create_assistant,
+        core::assistants::commands::update_assistant,
+        core::assistants::commands::delete_assistant,
+        core::assistants::commands::set_assistant_tool_scope,
+        core::assistants::commands::export_assistant,
+        core::assistants::commands::import_assistant,
+        // Context window
+        core::context::commands::assemble_thread_context,
+        core::context::commands::set_message_pinned,
+        // Response language
+        core::language::commands::get_thread_language,
+        core::language::commands::set_thread_language,
+        core::language::commands::detect_text_language,
+        // Agent
+        core::agent::commands::agent_run,
+        core::agent::commands::agent_cancel,
+        // Scratchpad
+        core::scratchpad::commands::execute_in_scratchpad,
+        core::scratchpad::commands::keep_scratchpad_result,
+        core::scratchpad::commands::discard_scratchpad_result,
+        core::scratchpad::commands::list_scratchpad_results,
+        // Cancellation hierarchy
+        core::cancellation::commands::cancel_operation,
+        core::cancellation::commands::list_cancellable_operations,
+        // Tool approval policies
+        core::approvals::commands::get_tool_policies,
+        core::approvals::commands::set_tool_policy,
+        core::approvals::commands::remove_tool_policy,
+        core::approvals::commands::set_default_tool_policy,
+        core::approvals::commands::lock_tool_policies,
+        core::approvals::commands::respond_tool_approval,
+        // Generation scheduler
+        core::scheduler::commands::get_generation_queue,
+        // Scheduled prompts
+        core::scheduled_prompts::commands::list_scheduled_prompts,
+        core::scheduled_prompts::commands::create_scheduled_prompt,
+        core::scheduled_prompts::commands::update_scheduled_prompt,
+        core::scheduled_prompts::commands::set_scheduled_prompt_enabled,
+        core::scheduled_prompts::commands::delete_scheduled_prompt,
+        core::scheduled_prompts::commands::run_scheduled_prompt_now,
+        core::scheduled_prompts::commands::list_scheduled_prompt_runs,
+        // Notifications
+        core::notifications::commands::get_notification_settings,
+        core::notifications::commands::set_notifications_enabled,
+        core::notifications::commands::set_notification_category_enabled,
+        // Local telemetry
+        core::telemetry::commands::get_telemetry_settings,
+        core::telemetry::commands::set_telemetry_enabled,
+        core::telemetry::commands::get_local_metrics,
+        core::telemetry::commands::preview_telemetry_report,
+        core::telemetry::commands::export_telemetry_report,
+        core::telemetry::commands::reset_local_metrics,
+        // Selective reset
+        core::system::commands::reset_mcp_config,
+        core::system::commands::clear_threads,
+        core::system::commands::clear_models,
+        core::system::commands::clear_provider_keys,
+        // Chat history import
+        core::importer::commands::import_chat_history,
+        // Ollama
+        core::ollama::commands::get_ollama_status,
+        core::ollama::commands::list_ollama_model_tags,
+        core::ollama::commands::connect_ollama,
+        core::ollama::commands::disconnect_ollama,
+        core::ollama::commands::ollama_keep_alive,
+        core::ollama::commands::pull_ollama_model,
+        // LAN discovery
+        core::lan::commands::discover_lan_servers,
+        core::lan::commands::connect_lan_server_provider,
+        core::lan::commands::request_lan_pairing,
+        core::lan::commands::confirm_lan_pairing,
+        // LAN peers
+        core::peers::commands::list_lan_peers,
+        core::peers::commands::update_lan_peer,
+        core::peers::commands::remove_lan_peer,
+        core::peers::commands::list_lan_pairing_requests,
+        core::peers::commands::reject_lan_pairing_request,
+        // Plugins
+        core::plugins::commands::list_plugins,
+        core::plugins::commands::install_plugin,
+        core::plugins::commands::enable_plugin,
+        core::plugins::commands::disable_plugin,
+        core::plugins::commands::uninstall_plugin,
+        core::plugins::commands::execute_plugin_command,
+        // Slash commands
+        core::slash_commands::commands::list_slash_commands,
+        core::slash_commands::commands::describe_slash_command,
+        core::slash_commands::commands::complete_slash_command,
+        core::slash_commands::commands::create_slash_command,
+        core::slash_commands::commands::update_slash_command,
+        core::slash_commands::commands::delete_slash_command,
+        core::slash_commands::commands::execute_slash_command,
+        // Web search
+        core::search::commands::get_search_settings,
+        core::search::commands::update_search_settings,
+        core::search::commands::web_search,
+        // Web fetch
+        core::web_fetch::commands::fetch_web_page,
+        core::web_fetch::commands::clear_web_fetch_cache,
+        core::web_fetch::commands::get_web_fetch_settings,
+        core::web_fetch::commands::update_web_fetch_settings,
+        // Code execution sandbox
+        core::code_exec::commands::get_code_sandbox_status,
+        core::code_exec::commands::get_code_exec_settings,
+        core::code_exec::commands::update_code_exec_settings,
+        core::code_exec::commands::run_code_snippet,
+        // Thread workspaces
+        core::workspaces::commands::set_thread_workspace,
+        core::workspaces::commands::clear_thread_workspace,
+        core::workspaces::commands::get_thread_workspace,
+        core::workspaces::commands::list_recent_workspaces,
+        // Agent transcripts
+        core::agent::commands::list_turn_transcripts,
+        core::agent::commands::get_turn_transcript,
+        core::agent::commands::replay_turn,
+        core::agent::commands::delete_turn_transcript,
+        // GGUF quantization
+        core::quantize::commands::list_quantization_types,
+        core::quantize::commands::quantize_model,
+        core::quantize::commands::list_quantize_jobs,
+        core::quantize::commands::cancel_quantize_job,
+        // LoRA adapters
+        core::lora::commands::list_lora_adapters,
+        core::lora::commands::import_lora_adapter,
+        core::lora::commands::delete_lora_adapter,
+        core::lora::commands::check_lora_compatibility,
+        core::lora::commands::set_assistant_lora_adapters,
+        core::lora::commands::apply_lora_adapters,
+        core::lora::commands::apply_assistant_lora_adapters,
+        // Speculative decoding
+        core::speculative::commands::check_draft_compatibility,
+        core::speculative::commands::list_draft_candidates,
+        core::speculative::commands::set_speculative_settings,
+        core::speculative::commands::get_speculative_settings,
+        core::speculative::commands::get_speculative_config,
+        // Prompt cache
+        core::prompt_cache::commands::get_prompt_cache_dir,
+        core::prompt_cache::commands::warm_prompt_cache,
+        core::prompt_cache::commands::list_prompt_cache,
+        core::prompt_cache::commands::clear_prompt_cache,
+        core::prompt_cache::commands::set_prompt_cache_limit,
+        // Context budget
+        core::context::commands::check_context_budget,
+        core::context::commands::apply_context_remediation,
+        // Redaction
+        core::redaction::commands::get_redaction_config,
+        core::redaction::commands::set_redaction_config,
+        core::redaction::commands::set_thread_redaction,
+        core::redaction::commands::preview_redaction,
+        // Generation parameter profiles
+        core::param_profiles::commands::get_param_profiles,
+        core::param_profiles::commands::save_param_profile,
+        core::param_profiles::commands::delete_param_profile,
+        core::param_profiles::commands::assign_param_profile,
+        core::param_profiles::commands::set_default_param_profile,
+        core::param_profiles::commands::set_model_param_limits,
+        core::param_profiles::commands::resolve_generation_params,
+        // Token guardrails
+        core::guardrails::commands::get_guardrail_status,
+        // Data folder storage
+        core::storage::commands::analyze_storage,
+        core::storage::commands::clean_storage,
+        core::storage::commands::mark_model_loaded,
+        // Log and artifact retention
+        core::retention::commands::preview_retention,
+        core::retention::commands::run_retention,
+        // Session restore
+        core::session_restore::commands::get_saved_session,
+        core::session_restore::commands::clear_saved_session,
+        // Event coalescing
+        core::events::commands::get_event_stats,
+        // Trace export
+        core::otel::commands::get_trace_export_status,
+        // Backend messages
+        core::messages::commands::get_message_registry,
+        core::messages::commands::set_message_catalog,
+        // Watchdog
+        core::watchdog::commands::list_watched_tasks,
+        // Offline mode
+        core::offline::commands::get_offline_mode,
+        core::offline::commands::set_offline_mode,
+        // Outbox
+        core::outbox::commands::queue_outgoing_message,
+        core::outbox::commands::list_outbox,
+        core::outbox::commands::retry_outbox_message,
+        core::outbox::commands::remove_outbox_message,
+        // Model comparison
+        core::compare::commands::compare_models,
+        core::compare::commands::cancel_model_comparison,
+        core::compare::commands::get_model_comparison,
+        core::compare::commands::list_model_comparisons,
+        // Settings
+        core::settings::commands::get_settings,
+        core::settings::commands::set_settings,
+        core::settings::commands::get_admin_policy,
+        // Config history
+        core::config_history::commands::list_config_history,
+        core::config_history::commands::get_config_version,
+        core::config_history::commands::restore_config_version,
+        // Sync
+        core::sync::commands::get_sync_config,
+        core::sync::commands::set_sync_config,
+        core::sync::commands::get_sync_status,
+        core::sync::commands::sync_now,
+        // Thread export
+        core::threads::commands::export_thread_html,
+        // Thread summaries
+        core::thread_summaries::commands::get_thread_summary,
+        core::thread_summaries::commands::refresh_thread_summary,
+        // Local-only titles and filenames
+        core::local_text::commands::suggest_thread_title,
+        core::local_text::commands::suggest_export_filename,
+        // First-run setup
+        core::onboarding::commands::get_onboarding_state,
+        core::onboarding::commands::run_onboarding_hardware_scan,
+        core::onboarding::commands::download_onboarding_model,
+        core::onboarding::commands::set_onboarding_provider_key,
+        core::onboarding::commands::install_onboarding_mcp_servers,
+        core::onboarding::commands::skip_onboarding_step,
+        core::onboarding::commands::reset_onboarding,
+        // Hardware-based model picks
+        core::model_picker::commands::recommend_models,
+        // Launches forwarded from other instances
+        core::instance::commands::take_launch_files,
+        // Headless server service
+        core::service::commands::install_server_service,
+        core::service::commands::uninstall_server_service,
+        core::service::commands::get_server_service_status,
+        // Bookmarks
+        core::bookmarks::commands::bookmark_thread,
+        core::bookmarks::commands::remove_thread_bookmark,
+        core::bookmarks::commands::list_thread_bookmarks,
+        core::bookmarks::commands::pin_message,
+        core::bookmarks::commands::unpin_message,
+        core::bookmarks::commands::list_pinned_messages,
+        // Vision
+        core::vision::commands::preprocess_message_images,
+        // Structured output
+        core::structured_output::commands::generate_structured_output,
+        // Embeddings
+        core::embeddings::commands::create_embeddings,
+        // Reranking
+        core::rerank::commands::get_rerank_config,
+        core::rerank::commands::set_rerank_config,
+        core::rerank::commands::rerank_candidates,
+        core::rerank::commands::get_rerank_metrics,
+        // Knowledge base folder sync
+        core::knowledge_sync::commands::bind_knowledge_folder,
+        core::knowledge_sync::commands::unbind_knowledge_folder,
+        core::knowledge_sync::commands::list_knowledge_folders,
+        core::knowledge_sync::commands::sync_knowledge_folder,
+        core::knowledge_sync::commands::get_knowledge_sync_status,
+        // Citations
+        core::citations::commands::get_citation_source,
+        core::citations::commands::attach_message_citations,
+        // Tool artifacts
+        core::tool_artifacts::commands::fetch_tool_artifact,
+        core::tool_artifacts::commands::delete_tool_artifact,
+        core::tool_artifacts::commands::get_tool_artifact_settings,
+        core::tool_artifacts::commands::update_tool_artifact_settings,
+        // Runtimes
+        core::runtimes::commands::get_runtimes,
+        core::runtimes::commands::install_runtime,
+        core::runtimes::commands::clear_runtime_cache,
         // Download
         core::downloads::commands::download_files,
         core::downloads::commands::cancel_download_task,
@@ -276,6 +575,7 @@ pub fn run() {
         core::server::remote_provider_commands::abort_remote_stream,
         // MCP commands
         core::mcp::commands::get_tools,
+        core::mcp::commands::get_cached_tools,
         core::mcp::commands::call_tool,
         core::mcp::commands::cancel_tool_call,
         core::mcp::commands::restart_mcp_servers,
@@ -285,6 +585,18 @@ pub fn run() {
         core::mcp::commands::activate_mcp_server,
         core::mcp::commands::deactivate_mcp_server,
         core::mcp::commands::check_jan_browser_extension_connected,
+        core::mcp::commands::check_mcp_server_updates,
+        core::mcp::commands::update_mcp_server_version,
+        core::mcp::commands::send_mcp_request,
+        core::mcp::commands::set_mcp_wire_logging,
+        core::mcp::commands::get_mcp_wire_log,
+        core::mcp::commands::clear_mcp_wire_log,
+        core::mcp::commands::start_mcp_recording,
+        core::mcp::commands::stop_mcp_recording,
+        core::mcp::commands::list_mcp_recordings,
+        core::mcp::commands::export_mcp_config,
+        core::mcp::commands::import_mcp_config,
+        core::mcp::commands::respond_mcp_secret,
         // Threads
         core::threads::commands::list_threads,
         core::threads::commands::create_thread,
@@ -294,9 +606,224 @@ pub fn run() {
         core::threads::commands::create_message,
         core::threads::commands::modify_message,
         core::threads::commands::delete_message,
+        core::threads::commands::get_message_tree,
+        core::threads::commands::list_message_branches,
+        core::threads::commands::switch_message_branch,
+        core::threads::commands::prune_message_branch,
+        core::threads::commands::archive_thread,
+        core::threads::commands::unarchive_thread,
+        core::threads::commands::unlock_private_threads,
+        core::threads::commands::lock_private_threads,
+        core::threads::commands::are_private_threads_unlocked,
+        core::threads::commands::set_thread_private,
         core::threads::commands::get_thread_assistant,
         core::threads::commands::create_thread_assistant,
         core::threads::commands::modify_thread_assistant,
+        // Prompt templates
+        core::prompts::commands::list_prompt_templates,
+        core::prompts::commands::get_prompt_template,
+        core::prompts::commands::create_prompt_template,
+        core::prompts::commands::update_prompt_template,
+        core::prompts::commands::delete_prompt_template,
+        core::prompts::commands::render_prompt_template,
+        core::prompts::commands::set_assistant_default_template,
+        core::prompts::commands::get_assistant_default_template,
+        // Assistants
+        core::assistants::commands::list_assistants,
+        core::assistants::commands::get_assistant,
+        core::assistants::commands::create_assistant,
+        core::assistants::commands::update_assistant,
+        core::assistants::commands::delete_assistant,
+        core::assistants::commands::set_assistant_tool_scope,
+        core::assistants::commands::export_assistant,
+        core::assistants::commands::import_assistant,
+        // Context window
+        core::context::commands::assemble_thread_context,
+        core::context::commands::set_message_pinned,
+        // Response language
+        core::language::commands::get_thread_language,
+        core::language::commands::set_thread_language,
+        core::language::commands::detect_text_language,
+        // Agent
+        core::agent::commands::agent_run,
+        core::agent::commands::agent_cancel,
+        // Scratchpad
+        core::scratchpad::commands::execute_in_scratchpad,
+        core::scratchpad::commands::keep_scratchpad_result,
+        core::scratchpad::commands::discard_scratchpad_result,
+        core::scratchpad::commands::list_scratchpad_results,
+        // Cancellation hierarchy
+        core::cancellation::commands::cancel_operation,
+        core::cancellation::commands::list_cancellable_operations,
+        // Tool approval policies
+        core::approvals::commands::get_tool_policies,
+        core::approvals::commands::set_tool_policy,
+        core::approvals::commands::remove_tool_policy,
+        core::approvals::commands::set_default_tool_policy,
+        core::approvals::commands::lock_tool_policies,
+        core::approvals::commands::respond_tool_approval,
+        // Generation scheduler
+        core::scheduler::commands::get_generation_queue,
+        // Scheduled prompts
+        core::scheduled_prompts::commands::list_scheduled_prompts,
+        core::scheduled_prompts::commands::create_scheduled_prompt,
+        core::scheduled_prompts::commands::update_scheduled_prompt,
+        core::scheduled_prompts::commands::set_scheduled_prompt_enabled,
+        core::scheduled_prompts::commands::delete_scheduled_prompt,
+        core::scheduled_prompts::commands::run_scheduled_prompt_now,
+        core::scheduled_prompts::commands::list_scheduled_prompt_runs,
+        // Notifications
+        core::notifications::commands::get_notification_settings,
+        core::notifications::commands::set_notifications_enabled,
+        core::notifications::commands::set_notification_category_enabled,
+        // Local telemetry
+        core::telemetry::commands::get_telemetry_settings,
+        core::telemetry::commands::set_telemetry_enabled,
+        core::telemetry::commands::get_local_metrics,
+        core::telemetry::commands::preview_telemetry_report,
+        core::telemetry::commands::export_telemetry_report,
+        core::telemetry::commands::reset_local_metrics,
+        // Selective reset
+        core::system::commands::reset_mcp_config,
+        core::system::commands::clear_threads,
+        core::system::commands::clear_models,
+        core::system::commands::clear_provider_keys,
+        // Chat history import
+        core::importer::commands::import_chat_history,
+        // Ollama
+        core::ollama::commands::get_ollama_status,
+        core::ollama::commands::list_ollama_model_tags,
+        core::ollama::commands::connect_ollama,
+        core::ollama::commands::disconnect_ollama,
+        core::ollama::commands::ollama_keep_alive,
+        core::ollama::commands::pull_ollama_model,
+        // Plugins
+        core::plugins::commands::list_plugins,
+        core::plugins::commands::install_plugin,
+        core::plugins::commands::enable_plugin,
+        core::plugins::commands::disable_plugin,
+        core::plugins::commands::uninstall_plugin,
+        core::plugins::commands::execute_plugin_command,
+        // Slash commands
+        core::slash_commands::commands::list_slash_commands,
+        core::slash_commands::commands::describe_slash_command,
+        core::slash_commands::commands::complete_slash_command,
+        core::slash_commands::commands::create_slash_command,
+        core::slash_commands::commands::update_slash_command,
+        core::slash_commands::commands::delete_slash_command,
+        core::slash_commands::commands::execute_slash_command,
+        // Web search
+        core::search::commands::get_search_settings,
+        core::search::commands::update_search_settings,
+        core::search::commands::web_search,
+        // Web fetch
+        core::web_fetch::commands::fetch_web_page,
+        core::web_fetch::commands::clear_web_fetch_cache,
+        core::web_fetch::commands::get_web_fetch_settings,
+        core::web_fetch::commands::update_web_fetch_settings,
+        // Code execution sandbox
+        core::code_exec::commands::get_code_sandbox_status,
+        core::code_exec::commands::get_code_exec_settings,
+        core::code_exec::commands::update_code_exec_settings,
+        core::code_exec::commands::run_code_snippet,
+        // Thread workspaces
+        core::workspaces::commands::set_thread_workspace,
+        core::workspaces::commands::clear_thread_workspace,
+        core::workspaces::commands::get_thread_workspace,
+        core::workspaces::commands::list_recent_workspaces,
+        // Agent transcripts
+        core::agent::commands::list_turn_transcripts,
+        core::agent::commands::get_turn_transcript,
+        core::agent::commands::replay_turn,
+        core::agent::commands::delete_turn_transcript,
+        // Context budget
+        core::context::commands::check_context_budget,
+        core::context::commands::apply_context_remediation,
+        // Redaction
+        core::redaction::commands::get_redaction_config,
+        core::redaction::commands::set_redaction_config,
+        core::redaction::commands::set_thread_redaction,
+        core::redaction::commands::preview_redaction,
+        // Generation parameter profiles
+        core::param_profiles::commands::get_param_profiles,
+        core::param_profiles::commands::save_param_profile,
+        core::param_profiles::commands::delete_param_profile,
+        core::param_profiles::commands::assign_param_profile,
+        core::param_profiles::commands::set_default_param_profile,
+        core::param_profiles::commands::set_model_param_limits,
+        core::param_profiles::commands::resolve_generation_params,
+        // Token guardrails
+        core::guardrails::commands::get_guardrail_status,
+        // Data folder storage
+        core::storage::commands::analyze_storage,
+        core::storage::commands::clean_storage,
+        core::storage::commands::mark_model_loaded,
+        // Log and artifact retention
+        core::retention::commands::preview_retention,
+        core::retention::commands::run_retention,
+        // Session restore
+        core::session_restore::commands::get_saved_session,
+        core::session_restore::commands::clear_saved_session,
+        // Event coalescing
+        core::events::commands::get_event_stats,
+        // Trace export
+        core::otel::commands::get_trace_export_status,
+        // Backend messages
+        core::messages::commands::get_message_registry,
+        core::messages::commands::set_message_catalog,
+        // Watchdog
+        core::watchdog::commands::list_watched_tasks,
+        // Offline mode
+        core::offline::commands::get_offline_mode,
+        core::offline::commands::set_offline_mode,
+        // Outbox
+        core::outbox::commands::queue_outgoing_message,
+        core::outbox::commands::list_outbox,
+        core::outbox::commands::retry_outbox_message,
+        core::outbox::commands::remove_outbox_message,
+        // Model comparison
+        core::compare::commands::compare_models,
+        core::compare::commands::cancel_model_comparison,
+        core::compare::commands::get_model_comparison,
+        core::compare::commands::list_model_comparisons,
+        // Settings
+        core::settings::commands::get_settings,
+        core::settings::commands::set_settings,
+        core::settings::commands::get_admin_policy,
+        // Config history
+        core::config_history::commands::list_config_history,
+        core::config_history::commands::get_config_version,
+        core::config_history::commands::restore_config_version,
+        // Bookmarks
+        core::bookmarks::commands::bookmark_thread,
+        core::bookmarks::commands::remove_thread_bookmark,
+        core::bookmarks::commands::list_thread_bookmarks,
+        core::bookmarks::commands::pin_message,
+        core::bookmarks::commands::unpin_message,
+        core::bookmarks::commands::list_pinned_messages,
+        // Vision
+        core::vision::commands::preprocess_message_images,
+        // Structured output
+        core::structured_output::commands::generate_structured_output,
+        // Embeddings
+        core::embeddings::commands::create_embeddings,
+        // Reranking
+        core::rerank::commands::get_rerank_config,
+        core::rerank::commands::set_rerank_config,
+        core::rerank::commands::rerank_candidates,
+        core::rerank::commands::get_rerank_metrics,
+        // Citations
+        core::citations::commands::get_citation_source,
+        core::citations::commands::attach_message_citations,
+        // Tool artifacts
+        core::tool_artifacts::commands::fetch_tool_artifact,
+        core::tool_artifacts::commands::delete_tool_artifact,
+        core::tool_artifacts::commands::get_tool_artifact_settings,
+        core::tool_artifacts::commands::update_tool_artifact_settings,
+        // Runtimes
+        core::runtimes::commands::get_runtimes,
+        core::runtimes::commands::install_runtime,
+        core::runtimes::commands::clear_runtime_cache,
         // Download
         core::downloads::commands::download_files,
         core::downloads::commands::cancel_download_task,
@@ -305,19 +832,37 @@ pub fn run() {
     let app = app_builder
         .manage(AppState {
             app_token: Some(generate_app_token()),
-            mcp_servers: Arc::new(Mutex::new(HashMap::new())),
-            download_manager: Arc::new(Mutex::new(DownloadManagerState::default())),
+            mcp_servers: Arc::new(RwLock::new(HashMap::new())),
             mcp_active_servers: Arc::new(Mutex::new(HashMap::new())),
             server_handle: Arc::new(Mutex::new(None)),
-            tool_call_cancellations: Arc::new(Mutex::new(HashMap::new())),
+            cancellations: CancellationTree::default(),
             mcp_settings: Arc::new(Mutex::new(McpSettings::default())),
             mcp_shutdown_in_progress: Arc::new(Mutex::new(false)),
             mcp_monitoring_tasks: Arc::new(Mutex::new(HashMap::new())),
             background_cleanup_handle: Arc::new(Mutex::new(None)),
             mcp_server_pids: Arc::new(Mutex::new(HashMap::new())),
-            provider_configs: Arc::new(Mutex::new(HashMap::new())),
+            mcp_server_ports: Arc::new(Mutex::new(HashMap::new())),
+            provider_configs: Arc::new(RwLock::new(HashMap::new())),
         })
         .manage(OpenClawState::default())
+        .manage(core::approvals::ToolApprovalState::default())
+        .manage(core::scheduler::GenerationScheduler::default())
+        .manage(core::scheduled_prompts::ScheduledPromptState::default())
+        .manage(core::plugins::PluginHostState::default())
+        .manage(core::quantize::QuantizeState::default())
+        .manage(core::prompt_cache::PromptCacheState::default())
+        .manage(core::redaction::RedactionFilter::shared())
+        .manage(core::param_profiles::ParamProfiles::default())
+        .manage(core::guardrails::Guardrails::default())
+        .manage(core::events::EventCoalescer::default())
+        .manage(core::otel::TraceExport::default())
+        .manage(core::messages::MessageCatalog::default())
+        .manage(core::watchdog::Watchdog::default())
+        .manage(core::offline::OfflineMode::default())
+        .manage(core::peers::PeerAccess::default())
+        .manage(core::settings::SettingsState::default())
+        .manage(core::threads::private::PrivateVault::default())
+        .manage(core::scratchpad::ScratchpadState::default())
         .setup(|app| {
             app.handle().plugin(
                 tauri_plugin_log::Builder::default()
@@ -336,6 +881,12 @@ pub fn run() {
             app.handle()
                 .plugin(tauri_plugin_updater::Builder::new().build())?;
 
+            // Another build may already run on this data folder
+            #[cfg(desktop)]
+            if !core::instance::helpers::claim_data_folder(app.handle()) {
+                std::process::exit(0);
+            }
+
             // Start migration
             let mut store_path = get_jan_data_folder_path(app.handle().clone());
             store_path.push("store.json");
@@ -388,7 +939,34 @@ pub fn run() {
                 });
             }
 
+            // Before anything that may connect out
+            core::offline::helpers::load_offline_mode(app.handle());
+            core::settings::helpers::load_settings(app.handle());
+            core::events::helpers::load_event_settings(app.handle());
+            core::otel::helpers::load_tracing(app.handle());
+            core::watchdog::helpers::start_watchdog(app.handle().clone());
+            core::telemetry::helpers::install_crash_counter(app.handle());
+            core::ollama::helpers::start_ollama_detection(app.handle());
+            core::plugins::runtime::start_enabled_plugins(app.handle());
+            core::scheduler::helpers::forward_queue_events(app.handle());
+            core::redaction::helpers::load_redaction_config(app.handle());
+            core::param_profiles::helpers::load_param_profiles(app.handle());
+            core::guardrails::helpers::load_guardrails(app.handle());
+            core::peers::helpers::load_peers(app.handle());
+            core::config_history::helpers::load_config_history(app.handle());
+            core::scheduled_prompts::helpers::start_scheduled_prompt_runner(app.handle().clone());
+            core::outbox::helpers::start_outbox_worker(app.handle().clone());
+            core::retention::helpers::start_retention_job(app.handle().clone());
+            #[cfg(desktop)]
+            core::knowledge_sync::helpers::start_knowledge_sync_watcher(app.handle().clone());
             setup_mcp(app);
+            core::session_restore::helpers::start_session_restore(app.handle());
+            #[cfg(desktop)]
+            core::power::helpers::start_sleep_watcher(app.handle());
+            #[cfg(desktop)]
+            core::network::helpers::start_network_monitor(app.handle());
+            #[cfg(desktop)]
+            core::sync::helpers::start_sync_scheduler(app.handle());
             #[cfg(desktop)]
             setup::setup_jan_cli(app.handle().clone(), stored_version != app_version);
             setup::setup_theme_listener(app)?;
@@ -401,6 +979,13 @@ pub fn run() {
         if let RunEvent::Exit = event {
             let app_handle = app.clone();
 
+            // Persist settings changed within the last debounce window
+            if let Err(e) = crate::core::config_store::helpers::config_store().flush() {
+                log::error!("Failed to persist config files on exit: {e}");
+            }
+            #[cfg(desktop)]
+            core::instance::helpers::release_data_folder();
+
             #[cfg(not(any(target_os = "ios", target_os = "android")))]
             {
                 if let Some(window) = app_handle.get_webview_window("main") {
@@ -410,6 +995,17 @@ pub fn run() {
             }
 
             let state = app_handle.state::<AppState>();
+            // Stop running turns, tool calls and downloads before their servers go away
+            state.cancellations.shutdown();
+            // Send the buffered spans while the runtime can still run the export
+            core::otel::helpers::shutdown_tracing(&app_handle);
+
+            // Remember what is loaded and running before it gets shut down
+            tokio::task::block_in_place(|| {
+                tauri::async_runtime::block_on(core::session_restore::helpers::save_session(
+                    &app_handle,
+                ))
+            });
 
             // Check if cleanup already ran
             let cleanup_already_running = tokio::task::block_in_place(|| {
@@ -0,0 +1,202 @@
+//! Duplicate-request protection shared by the app and its plugins.
+//!
+//! A double click easily sends the same command twice, and two loads of one model or two
+//! starts of one MCP server then race each other. Operations that must not run twice are
+//! started through `InFlight::run` under a key naming what they act on: while one runs, a
+//! second call with the same key waits for it and gets its result instead of starting another.
+//! The operation runs on its own task, so it finishes even if the caller that started it goes
+//! away.
+
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::future::Future;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use tokio::sync::watch;
+
+type Outcome<T, E> = watch::Receiver<Option<Result<T, E>>>;
+
+/// Operations running now by key, cheap to clone
+pub struct InFlight<K> {
+    /// An `Outcome` of the operation's result type per key
+    operations: Arc<Mutex<HashMap<K, Box<dyn Any + Send + Sync>>>>,
+}
+
+impl<K> Clone for InFlight<K> {
+    fn clone(&self) -> Self {
+        Self {
+            operations: self.operations.clone(),
+        }
+    }
+}
+
+impl<K> Default for InFlight<K> {
+    fn default() -> Self {
+        Self {
+            operations: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+/// Forgets an operation once it ends, even if it panics
+struct Finished<K: Eq + Hash> {
+    in_flight: InFlight<K>,
+    key: K,
+}
+
+impl<K: Eq + Hash> Drop for Finished<K> {
+    fn drop(&mut self) {
+        self.in_flight.operations().remove(&self.key);
+    }
+}
+
+impl<K: Eq + Hash> InFlight<K> {
+    fn operations(&self) -> MutexGuard<'_, HashMap<K, Box<dyn Any + Send + Sync>>> {
+        self.operations.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<K> InFlight<K>
+where
+    K: Eq + Hash + Clone + Display + Send + 'static,
+{
+    pub fn is_running(&self, key: &K) -> bool {
+        self.operations().contains_key(key)
+    }
+
+    /// Run `operation` under `key`, or wait for the operation already running under it and
+    /// return its result. Must be called within a tokio runtime.
+    pub async fn run<T, E, F>(&self, key: K, operation: F) -> Result<T, E>
+    where
+        T: Clone + Send + Sync + 'static,
+        E: Clone + From<String> + Send + Sync + 'static,
+        F: Future<Output = Result<T, E>> + Send + 'static,
+    {
+        let mut outcome = {
+            let mut operations = self.operations();
+            match operations.get(&key) {
+                Some(running) => match running.downcast_ref::<Outcome<T, E>>() {
+                    Some(outcome) => {
+                        #[cfg(feature = "logging")]
+                        log::info!("{key} is already running, waiting for it");
+                        outcome.clone()
+                    }
+                    None => return Err(E::from(format!("{key} is already running"))),
+                },
+                None => {
+                    let (sender, outcome) = watch::channel(None);
+                    operations.insert(key.clone(), Box::new(outcome.clone()));
+                    let finished = Finished {
+                        in_flight: self.clone(),
+                        key: key.clone(),
+                    };
+                    tokio::spawn(async move {
+                        let result = operation.await;
+                        // Forgotten before the result is out, so a call that sees the
+                        // result and tries again starts a new operation
+                        drop(finished);
+                        let _ = sender.send(Some(result));
+                    });
+                    outcome
+                }
+            }
+        };
+        let result = match outcome.wait_for(Option::is_some).await {
+            Ok(result) => result.clone(),
+            Err(_) => None,
+        };
+        result.unwrap_or_else(|| Err(E::from(format!("{key} ended without a result"))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    async fn counted(runs: Arc<AtomicUsize>, result: Result<u32, String>) -> Result<u32, String> {
+        runs.fetch_add(1, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        result
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_calls_share_one_operation() {
+        let in_flight = InFlight::<String>::default();
+        let runs = Arc::new(AtomicUsize::new(0));
+        let key = "load model llama".to_string();
+
+        let (first, second) = tokio::join!(
+            in_flight.run(key.clone(), counted(runs.clone(), Ok(1))),
+            in_flight.run(key.clone(), counted(runs.clone(), Ok(2))),
+        );
+        assert_eq!(first, Ok(1));
+        assert_eq!(second, Ok(1));
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert!(!in_flight.is_running(&key));
+
+        // Once it is done, the next call starts the operation again
+        assert_eq!(
+            in_flight.run(key, counted(runs.clone(), Ok(3))).await,
+            Ok(3)
+        );
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_errors_are_shared_and_keys_independent() {
+        let in_flight = InFlight::<&str>::default();
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        let (first, second, other) = tokio::join!(
+            in_flight.run("a", counted(runs.clone(), Err("failed".to_string()))),
+            in_flight.run("a", counted(runs.clone(), Ok(2))),
+            in_flight.run("b", counted(runs.clone(), Ok(3))),
+        );
+        assert_eq!(first, Err("failed".to_string()));
+        assert_eq!(second, Err("failed".to_string()));
+        assert_eq!(other, Ok(3));
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_operation_outlives_its_caller() {
+        let in_flight = InFlight::<&str>::default();
+        let runs = Arc::new(AtomicUsize::new(0));
+
+        // The first caller gives up right away; a later caller still gets its result
+        let started = in_flight.run("a", counted(runs.clone(), Ok(1)));
+        let _ = tokio::time::timeout(Duration::from_millis(1), started).await;
+        assert!(in_flight.is_running(&"a"));
+        assert_eq!(
+            in_flight.run("a", counted(runs.clone(), Ok(2))).await,
+            Ok(1)
+        );
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_panicking_operation_is_forgotten() {
+        let in_flight = InFlight::<&str>::default();
+        let result: Result<u32, String> = in_flight
+            .run("a", async { panic!("operation failed") })
+            .await;
+        assert_eq!(result, Err("a ended without a result".to_string()));
+        assert!(!in_flight.is_running(&"a"));
+    }
+
+    #[tokio::test]
+    async fn test_mismatched_result_type_is_refused() {
+        let in_flight = InFlight::<&str>::default();
+        let runs = Arc::new(AtomicUsize::new(0));
+        let (first, second) = tokio::join!(
+            in_flight.run("a", counted(runs.clone(), Ok(1))),
+            in_flight.run("a", async { Ok::<String, String>("other".to_string()) }),
+        );
+        assert_eq!(first, Ok(1));
+        assert_eq!(second, Err("a is already running".to_string()));
+    }
+}
@@ -3,9 +3,11 @@ pub mod config;
 pub mod crypto;
 pub mod fs;
 pub mod http;
+pub mod inflight;
 pub mod math;
 pub mod network;
 pub mod path;
+pub mod progress;
 pub mod string;
 pub mod system;
 
@@ -15,8 +17,10 @@ pub use config::*;
 pub use crypto::*;
 pub use fs::*;
 pub use http::*;
+pub use inflight::*;
 pub use math::*;
 pub use network::*;
 pub use path::*;
+pub use progress::*;
 pub use string::*;
 pub use system::*;
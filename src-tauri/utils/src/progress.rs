@@ -0,0 +1,408 @@
+//! Progress reporting contract shared by the app and its plugins.
+//!
+//! Every long-running operation (downloads, model pulls and loads, quantization, imports and
+//! knowledge base indexing) reports its progress as a `ProgressPayload` on `PROGRESS_EVENT`,
+//! so progress bars and screen-reader announcements can be built once for all of them. A
+//! payload names the operation and task, the current phase, whether the amount done is known,
+//! an ETA when it can be estimated, and the message code of a label to announce.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// Event carrying a `ProgressPayload`
+pub const PROGRESS_EVENT: &str = "task-progress";
+
+/// Time to observe before estimating an ETA, so a burst at the start doesn't skew it
+pub const MIN_ETA_ELAPSED: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressOperation {
+    Download,
+    ModelPull,
+    ModelLoad,
+    Quantize,
+    Import,
+    Indexing,
+}
+
+impl ProgressOperation {
+    pub const ALL: [Self; 6] = [
+        Self::Download,
+        Self::ModelPull,
+        Self::ModelLoad,
+        Self::Quantize,
+        Self::Import,
+        Self::Indexing,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Download => "download",
+            Self::ModelPull => "model_pull",
+            Self::ModelLoad => "model_load",
+            Self::Quantize => "quantize",
+            Self::Import => "import",
+            Self::Indexing => "indexing",
+        }
+    }
+
+    /// Phases reported while the operation runs, in the order they happen
+    pub fn phases(self) -> &'static [&'static str] {
+        match self {
+            Self::Download => &["downloading", "verifying"],
+            Self::ModelPull => &["preparing", "downloading", "verifying"],
+            Self::ModelLoad => &[
+                "starting",
+                "loading_weights",
+                "creating_context",
+                "warming_up",
+            ],
+            Self::Quantize => &["quantizing"],
+            Self::Import => &["parsing", "importing"],
+            Self::Indexing => &["removing", "indexing"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressState {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl ProgressState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Running => "running",
+            Self::Completed => "completed",
+            Self::Failed => "failed",
+            Self::Cancelled => "cancelled",
+        }
+    }
+
+    /// Whether this is the last payload of the task
+    pub fn is_final(self) -> bool {
+        self != Self::Running
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressKind {
+    /// The amount done and the total are known
+    Determinate,
+    /// Only the phase is known
+    Indeterminate,
+}
+
+/// What `current` and `total` count, so the UI can format them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProgressUnit {
+    Bytes,
+    Items,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProgressPayload {
+    pub operation: ProgressOperation,
+    /// Id of the download, import, model or knowledge base the progress belongs to
+    pub task_id: String,
+    pub state: ProgressState,
+    /// One of `operation.phases()` while running, the state's name afterwards
+    pub phase: String,
+    pub kind: ProgressKind,
+    /// Amount done and in total; only for determinate progress
+    pub current: Option<u64>,
+    pub total: Option<u64>,
+    /// Between 0 and 1; only for determinate progress
+    pub fraction: Option<f64>,
+    pub unit: Option<ProgressUnit>,
+    /// Estimated seconds until the phase is done
+    pub eta_secs: Option<u64>,
+    /// Message code of the text to announce, `progress.<operation>.<phase>` while running and
+    /// `progress.<state>` afterwards
+    pub label_key: String,
+    /// Parameters of the label; `name` is the model, file or knowledge base worked on
+    pub label_params: BTreeMap<String, String>,
+}
+
+impl ProgressPayload {
+    fn new(
+        operation: ProgressOperation,
+        task_id: &str,
+        name: &str,
+        state: ProgressState,
+        phase: &str,
+        label_key: String,
+    ) -> Self {
+        Self {
+            operation,
+            task_id: task_id.to_string(),
+            state,
+            phase: phase.to_string(),
+            kind: ProgressKind::Indeterminate,
+            current: None,
+            total: None,
+            fraction: None,
+            unit: None,
+            eta_secs: None,
+            label_key,
+            label_params: BTreeMap::from([("name".to_string(), name.to_string())]),
+        }
+    }
+
+    /// Indeterminate progress of a running task; add the amount with `with_amount`
+    pub fn running(operation: ProgressOperation, task_id: &str, name: &str, phase: &str) -> Self {
+        let label_key = format!("progress.{}.{phase}", operation.as_str());
+        Self::new(
+            operation,
+            task_id,
+            name,
+            ProgressState::Running,
+            phase,
+            label_key,
+        )
+    }
+
+    /// The last payload of a task
+    pub fn finished(
+        operation: ProgressOperation,
+        task_id: &str,
+        name: &str,
+        state: ProgressState,
+    ) -> Self {
+        let label_key = format!("progress.{}", state.as_str());
+        let mut payload = Self::new(operation, task_id, name, state, state.as_str(), label_key);
+        if state == ProgressState::Completed {
+            payload.fraction = Some(1.0);
+        }
+        payload
+    }
+
+    /// Make the progress determinate. A total of 0 means it isn't known yet, and the progress
+    /// stays indeterminate.
+    pub fn with_amount(mut self, current: u64, total: u64, unit: ProgressUnit) -> Self {
+        if total == 0 {
+            return self;
+        }
+        self.kind = ProgressKind::Determinate;
+        self.current = Some(current);
+        self.total = Some(total);
+        self.fraction = Some((current as f64 / total as f64).clamp(0.0, 1.0));
+        self.unit = Some(unit);
+        self
+    }
+
+    /// Set the ETA; indeterminate progress has none
+    pub fn with_eta(mut self, eta_secs: Option<u64>) -> Self {
+        if self.kind == ProgressKind::Determinate {
+            self.eta_secs = eta_secs;
+        }
+        self
+    }
+
+    /// Key under which payloads of the same task replace each other
+    pub fn stream(&self) -> String {
+        format!("{}:{}", self.operation.as_str(), self.task_id)
+    }
+}
+
+/// Receives progress payloads, e.g. to emit them as `PROGRESS_EVENT`
+pub type ProgressSink = Arc<dyn Fn(ProgressPayload) + Send + Sync>;
+
+/// Reports the phases of one task to an optional sink. Phases reported after the task
+/// finished are ignored, e.g. from a server that keeps logging once it is up.
+#[derive(Clone)]
+pub struct PhaseReporter {
+    operation: ProgressOperation,
+    task_id: String,
+    name: String,
+    sink: Option<ProgressSink>,
+    finished: Arc<AtomicBool>,
+}
+
+impl PhaseReporter {
+    pub fn new(
+        operation: ProgressOperation,
+        task_id: &str,
+        name: &str,
+        sink: Option<ProgressSink>,
+    ) -> Self {
+        Self {
+            operation,
+            task_id: task_id.to_string(),
+            name: name.to_string(),
+            sink,
+            finished: Arc::default(),
+        }
+    }
+
+    fn send(&self, payload: ProgressPayload) {
+        if let Some(sink) = &self.sink {
+            sink(payload);
+        }
+    }
+
+    pub fn phase(&self, phase: &str) {
+        if !self.finished.load(Ordering::Relaxed) {
+            self.send(ProgressPayload::running(
+                self.operation,
+                &self.task_id,
+                &self.name,
+                phase,
+            ));
+        }
+    }
+
+    pub fn finish(&self, state: ProgressState) {
+        if !self.finished.swap(true, Ordering::Relaxed) {
+            self.send(ProgressPayload::finished(
+                self.operation,
+                &self.task_id,
+                &self.name,
+                state,
+            ));
+        }
+    }
+}
+
+/// Seconds left at the average rate so far, once `MIN_ETA_ELAPSED` has passed and some
+/// progress was made
+pub fn estimate_eta(elapsed: Duration, done: u64, remaining: u64) -> Option<u64> {
+    if remaining == 0 {
+        return Some(0);
+    }
+    if elapsed < MIN_ETA_ELAPSED || done == 0 {
+        return None;
+    }
+    let secs = elapsed.as_secs_f64() * remaining as f64 / done as f64;
+    Some(secs.ceil() as u64)
+}
+
+/// Estimates the ETA of one phase from the amount done since it started
+#[derive(Debug, Clone, Copy)]
+pub struct EtaEstimator {
+    started: Instant,
+    start_amount: u64,
+}
+
+impl EtaEstimator {
+    /// Start measuring now, with `start_amount` already done, e.g. a resumed download
+    pub fn new(start_amount: u64) -> Self {
+        Self {
+            started: Instant::now(),
+            start_amount,
+        }
+    }
+
+    pub fn eta(&self, current: u64, total: u64) -> Option<u64> {
+        self.eta_at(current, total, Instant::now())
+    }
+
+    pub fn eta_at(&self, current: u64, total: u64, now: Instant) -> Option<u64> {
+        if total == 0 {
+            return None;
+        }
+        estimate_eta(
+            now.saturating_duration_since(self.started),
+            current.saturating_sub(self.start_amount),
+            total.saturating_sub(current),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_running_payload_labels_its_phase() {
+        let payload = ProgressPayload::running(
+            ProgressOperation::Download,
+            "task-1",
+            "llama",
+            "downloading",
+        );
+        assert_eq!(payload.kind, ProgressKind::Indeterminate);
+        assert_eq!(payload.label_key, "progress.download.downloading");
+        assert_eq!(payload.label_params["name"], "llama");
+        assert_eq!(payload.stream(), "download:task-1");
+
+        let payload = payload.with_amount(25, 100, ProgressUnit::Bytes);
+        assert_eq!(payload.kind, ProgressKind::Determinate);
+        assert_eq!(payload.fraction, Some(0.25));
+        assert_eq!(payload.unit, Some(ProgressUnit::Bytes));
+    }
+
+    #[test]
+    fn test_unknown_total_stays_indeterminate() {
+        let payload = ProgressPayload::running(ProgressOperation::ModelPull, "m", "m", "preparing")
+            .with_amount(10, 0, ProgressUnit::Bytes)
+            .with_eta(Some(0));
+        assert_eq!(payload.kind, ProgressKind::Indeterminate);
+        assert_eq!(payload.eta_secs, None);
+        assert_eq!(payload.current, None);
+        assert_eq!(payload.fraction, None);
+    }
+
+    #[test]
+    fn test_finished_payload_uses_state_label() {
+        let payload = ProgressPayload::finished(
+            ProgressOperation::Import,
+            "import-1",
+            "chatgpt",
+            ProgressState::Completed,
+        );
+        assert!(payload.state.is_final());
+        assert_eq!(payload.phase, "completed");
+        assert_eq!(payload.label_key, "progress.completed");
+        assert_eq!(payload.fraction, Some(1.0));
+
+        let value = serde_json::to_value(&payload).unwrap();
+        assert_eq!(value["operation"], "import");
+        assert_eq!(value["kind"], "indeterminate");
+    }
+
+    #[test]
+    fn test_phase_reporter_stops_after_finish() {
+        let sent = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink: ProgressSink = {
+            let sent = sent.clone();
+            Arc::new(move |payload: ProgressPayload| sent.lock().unwrap().push(payload.phase))
+        };
+        let reporter = PhaseReporter::new(ProgressOperation::ModelLoad, "m", "m", Some(sink));
+        reporter.phase("starting");
+        reporter.finish(ProgressState::Completed);
+        reporter.phase("warming_up");
+        reporter.finish(ProgressState::Failed);
+        assert_eq!(*sent.lock().unwrap(), vec!["starting", "completed"]);
+
+        PhaseReporter::new(ProgressOperation::ModelLoad, "m", "m", None).phase("starting");
+    }
+
+    #[test]
+    fn test_eta_uses_average_rate_since_start() {
+        assert_eq!(estimate_eta(Duration::from_secs(1), 50, 50), None);
+        assert_eq!(estimate_eta(Duration::from_secs(10), 0, 50), None);
+        assert_eq!(estimate_eta(Duration::from_secs(10), 25, 75), Some(30));
+        assert_eq!(estimate_eta(Duration::ZERO, 0, 0), Some(0));
+
+        let start = Instant::now();
+        let estimator = EtaEstimator {
+            started: start,
+            start_amount: 100,
+        };
+        let later = start + Duration::from_secs(10);
+        assert_eq!(estimator.eta_at(200, 600, later), Some(40));
+        assert_eq!(estimator.eta_at(200, 0, later), None);
+    }
+}
@@ -21,6 +21,8 @@ import { setOpenClawRunningState, syncAllModelsToOpenClaw } from '@/utils/opencl
 import { useModelProvider } from '@/hooks/useModelProvider'
 import { useLocalApiServer } from '@/hooks/useLocalApiServer'
 import { useAppState } from '@/hooks/useAppState'
+import { useTranslation } from '@/i18n/react-i18next-compat'
+import { localizeBackendError, parseBackendMessage } from '@/lib/backendMessage'
 
 interface EnableProgressEvent {
   step: string
@@ -76,7 +78,9 @@ const STEP_LABELS: Record<string, string> = {
 
 function parseEnableError(errorStr: string): EnableError | null {
   try {
-    return JSON.parse(errorStr) as EnableError
+    const parsed = JSON.parse(errorStr)
+    // Coded backend messages are JSON too, but carry no recovery options
+    return parsed && Array.isArray(parsed.recovery) ? (parsed as EnableError) : null
   } catch {
     return null
   }
@@ -87,6 +91,7 @@ export function EnableProgressDialog({
   onOpenChange,
   onSuccess,
 }: EnableProgressDialogProps) {
+  const { t } = useTranslation()
   const [progress, setProgress] = useState(0)
   const [message, setMessage] = useState('')
   const [completedSteps, setCompletedSteps] = useState<string[]>([])
@@ -158,9 +163,13 @@ export function EnableProgressDialog({
               }).catch(() => {})
             }
             setServerStatus('running')
-          } catch {
+          } catch (serverError) {
             setServerStatus('stopped')
             // Non-fatal — user can start manually
+            console.warn(
+              'Failed to start Local API Server:',
+              parseBackendMessage(serverError)?.message ?? serverError
+            )
           }
         }
 
@@ -179,7 +188,7 @@ export function EnableProgressDialog({
       } else {
         setError({
           code: 'Unknown',
-          message: errorStr,
+          message: localizeBackendError(err, t),
           recovery: [
             {
               label: 'Retry',
@@ -192,7 +201,7 @@ export function EnableProgressDialog({
     } finally {
       setIsRunning(false)
     }
-  }, [resetState, onSuccess])
+  }, [resetState, onSuccess, t])
 
   // Listen for progress events
   useEffect(() => {
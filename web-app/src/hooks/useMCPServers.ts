@@ -7,8 +7,9 @@ export type MCPServerConfig = {
   args: string[]
   env: Record<string, string>
   active?: boolean
-  type?: 'stdio' | 'http' | 'sse'
+  type?: 'stdio' | 'http' | 'sse' | 'unix' | 'pipe'
   url?: string
+  socket?: string
   headers?: Record<string, string>
   timeout?: number
   official?: boolean
@@ -24,6 +25,8 @@ export type MCPSettings = {
   baseRestartDelayMs: number
   maxRestartDelayMs: number
   backoffMultiplier: number
+  startupConcurrency: number
+  startupBudgetSeconds: number
 }
 
 export const DEFAULT_MCP_SETTINGS: MCPSettings = {
@@ -31,6 +34,8 @@ export const DEFAULT_MCP_SETTINGS: MCPSettings = {
   baseRestartDelayMs: 1000,
   maxRestartDelayMs: 30000,
   backoffMultiplier: 2,
+  startupConcurrency: 4,
+  startupBudgetSeconds: 30,
 }
 
 type MCPServerStoreState = {
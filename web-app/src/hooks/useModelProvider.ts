@@ -20,6 +20,7 @@ type ModelProviderState = {
   addProvider: (provider: ModelProvider) => void
   deleteProvider: (providerName: string) => void
   deleteModel: (modelId: string) => void
+  clearProviderKeys: () => void
 }
 
 export const useModelProvider = create<ModelProviderState>()(
@@ -218,6 +219,26 @@ export const useModelProvider = create<ModelProviderState>()(
           ),
         }))
       },
+      // Drops every API key, both the field and the `api-key` setting it is edited in
+      clearProviderKeys: () => {
+        set((state) => ({
+          providers: state.providers.map((provider) => ({
+            ...provider,
+            api_key: '',
+            settings: provider.settings.map((setting) =>
+              setting.key === 'api-key'
+                ? {
+                    ...setting,
+                    controller_props: {
+                      ...setting.controller_props,
+                      value: '',
+                    },
+                  }
+                : setting
+            ),
+          })),
+        }))
+      },
     }),
     {
       name: localStorageKey.modelProvider,
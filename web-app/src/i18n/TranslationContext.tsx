@@ -2,6 +2,7 @@ import React, { ReactNode, useEffect, useCallback } from "react"
 import i18next, { loadTranslations } from "./setup"
 import { useGeneralSetting } from "@/hooks/useGeneralSetting"
 import { TranslationContext } from "./context"
+import { syncBackendMessages } from "@/lib/backendMessage"
 
 // Translation provider component
 export const TranslationProvider: React.FC<{ children: ReactNode }> = ({ children }) => {
@@ -21,6 +22,7 @@ export const TranslationProvider: React.FC<{ children: ReactNode }> = ({ childre
 	useEffect(() => {
 		if (currentLanguage) {
 			i18next.changeLanguage(currentLanguage)
+			syncBackendMessages(currentLanguage)
 		}
 	}, [currentLanguage])
 
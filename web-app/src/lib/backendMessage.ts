@@ -0,0 +1,65 @@
+import { invoke } from '@tauri-apps/api/core'
+import i18next from '@/i18n/setup'
+import { isPlatformTauri } from '@/lib/platform/utils'
+
+/** A user-visible message produced by the core, see `core::messages` */
+export interface BackendMessage {
+  code: string
+  params: Record<string, string>
+  /** English text, used when there is no translation for `code` */
+  message: string
+}
+
+/** The backend message carried by a command error, if it is one */
+export const parseBackendMessage = (error: unknown): BackendMessage | undefined => {
+  const text = error instanceof Error ? error.message : String(error)
+  try {
+    const parsed = JSON.parse(text)
+    if (parsed && typeof parsed.code === 'string' && typeof parsed.message === 'string') {
+      return { code: parsed.code, params: parsed.params ?? {}, message: parsed.message }
+    }
+  } catch {
+    // A plain error string
+  }
+  return undefined
+}
+
+/** Text of a command error in the user's language */
+export const localizeBackendError = (
+  error: unknown,
+  t: (key: string, options?: Record<string, unknown>) => string
+): string => {
+  const message = parseBackendMessage(error)
+  if (!message) {
+    return error instanceof Error ? error.message : String(error)
+  }
+  const key = `backend:${message.code}`
+  const translated = t(key, message.params)
+  return translated === key ? message.message : translated
+}
+
+const flatten = (
+  value: unknown,
+  prefix: string,
+  out: Record<string, string>
+): Record<string, string> => {
+  if (typeof value === 'string') {
+    out[prefix] = value
+  } else if (value && typeof value === 'object') {
+    Object.entries(value).forEach(([key, nested]) =>
+      flatten(nested, prefix ? `${prefix}.${key}` : key, out)
+    )
+  }
+  return out
+}
+
+/** Hand the core the translations of texts it shows itself, such as notifications */
+export const syncBackendMessages = async (language: string): Promise<void> => {
+  if (!isPlatformTauri()) return
+  const messages = flatten(i18next.resources[language]?.backend, '', {})
+  try {
+    await invoke('set_message_catalog', { locale: language, messages })
+  } catch (error) {
+    console.warn('Failed to send backend message translations:', error)
+  }
+}
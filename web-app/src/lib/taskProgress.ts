@@ -0,0 +1,53 @@
+/** Event the core emits the progress of every long-running task on */
+export const TASK_PROGRESS_EVENT = 'task-progress'
+
+export type TaskOperation =
+  | 'download'
+  | 'model_pull'
+  | 'model_load'
+  | 'quantize'
+  | 'import'
+  | 'indexing'
+
+export type TaskState = 'running' | 'completed' | 'failed' | 'cancelled'
+
+/** Progress of a download, model load, import or indexing run, see `jan_utils::progress` */
+export interface TaskProgress {
+  operation: TaskOperation
+  task_id: string
+  state: TaskState
+  phase: string
+  kind: 'determinate' | 'indeterminate'
+  current: number | null
+  total: number | null
+  /** Between 0 and 1 */
+  fraction: number | null
+  unit: 'bytes' | 'items' | null
+  eta_secs: number | null
+  /** Code of the label in the `backend` namespace */
+  label_key: string
+  label_params: Record<string, string>
+}
+
+/** Text announcing `progress` to screen readers, e.g. "Downloading llama, 40%, in 2 minutes" */
+export const describeTaskProgress = (
+  progress: TaskProgress,
+  language: string,
+  t: (key: string, options?: Record<string, unknown>) => string
+): string => {
+  const parts = [t(`backend:${progress.label_key}`, progress.label_params)]
+  if (progress.state === 'running' && progress.fraction !== null) {
+    parts.push(
+      new Intl.NumberFormat(language, { style: 'percent' }).format(progress.fraction)
+    )
+  }
+  if (progress.state === 'running' && progress.eta_secs !== null) {
+    const format = new Intl.RelativeTimeFormat(language, { numeric: 'auto' })
+    parts.push(
+      progress.eta_secs < 90
+        ? format.format(progress.eta_secs, 'second')
+        : format.format(Math.round(progress.eta_secs / 60), 'minute')
+    )
+  }
+  return parts.join(', ')
+}
@@ -14,6 +14,7 @@ import { AppEvent, events } from '@janhq/core'
 import { SystemEvent } from '@/types/events'
 import { isDev } from '@/lib/utils'
 import { invoke } from '@tauri-apps/api/core'
+import { parseBackendMessage } from '@/lib/backendMessage'
 
 type ProviderCustomHeader = {
   header: string
@@ -245,7 +246,11 @@ export function DataProvider() {
             })
         })
         .catch((error: unknown) => {
-          console.error('Failed to start Local API Server on startup:', error)
+          // Logged in English: coded backend errors carry the English text
+          console.error(
+            'Failed to start Local API Server on startup:',
+            parseBackendMessage(error)?.message ?? error
+          )
           setServerStatus('stopped')
         })
     }
@@ -2,6 +2,7 @@ import { useEffect } from 'react'
 import { events } from '@janhq/core'
 import { useModelProvider } from '@/hooks/useModelProvider'
 import { useServiceHub } from '@/hooks/useServiceHub'
+import { SystemEvent } from '@/types/events'
 
 /**
  * GlobalEventHandler handles global events that should be processed across all screens
@@ -50,6 +51,23 @@ export function GlobalEventHandler() {
     }
   }, [setProviders, serviceHub])
 
+  // The core dropped the registered provider keys; drop the persisted ones too so they
+  // aren't registered again on the next launch
+  useEffect(() => {
+    let unsubscribe = () => {}
+    serviceHub
+      .events()
+      .listen(SystemEvent.PROVIDER_KEYS_CLEARED, () => {
+        useModelProvider.getState().clearProviderKeys()
+      })
+      .then((unsub) => {
+        unsubscribe = unsub
+      })
+    return () => {
+      unsubscribe()
+    }
+  }, [serviceHub])
+
   // This component doesn't render anything
   return null
 }
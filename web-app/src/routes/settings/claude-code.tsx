@@ -20,6 +20,7 @@ import { JAN_CODE_HF_REPO } from '@/constants/models'
 import { toast } from 'sonner'
 import { getModelToStart } from '@/utils/getModelToStart'
 import { invoke } from '@tauri-apps/api/core'
+import { localizeBackendError } from '@/lib/backendMessage'
 import {
   Popover,
   PopoverTrigger,
@@ -179,7 +180,7 @@ function ClaudeCodeIntegration() {
       )
     } catch (error) {
       console.error('Failed to launch Claude Code:', error)
-      const errorMsg = error instanceof Error ? error.message : String(error)
+      const errorMsg = localizeBackendError(error, t)
       toast.error('Failed to configure env vars', {
         description: errorMsg,
       })
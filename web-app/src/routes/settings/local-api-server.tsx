@@ -17,6 +17,7 @@ import { useModelProvider } from '@/hooks/useModelProvider'
 import { useServiceHub } from '@/hooks/useServiceHub'
 import { IconSettings2 } from '@tabler/icons-react'
 import { cn } from '@/lib/utils'
+import { localizeBackendError, parseBackendMessage } from '@/lib/backendMessage'
 import { ApiKeyInput } from '@/containers/ApiKeyInput'
 import { useEffect, useState } from 'react'
 import { toast } from 'sonner'
@@ -294,8 +295,14 @@ function LocalAPIServerContent() {
               ? String(error.message)
               : String(error)
 
-          // Port-related errors (highest priority)
-          if (errorMsg.includes('Address already in use')) {
+          // Errors the core describes with a message code
+          if (parseBackendMessage(error)) {
+            toast.error('Failed to start server', {
+              description: localizeBackendError(error, t),
+            })
+          }
+          // Port-related errors
+          else if (errorMsg.includes('Address already in use')) {
             toast.error('Port has been occupied', {
               description: `Port ${serverPort} is already in use. Please try a different port.`,
             })
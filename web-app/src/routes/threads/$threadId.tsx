@@ -284,6 +284,7 @@ function ThreadDetail() {
               result = await serviceHub.mcp().callTool({
                 toolName,
                 arguments: toolCall.input,
+                threadId,
               })
             } else {
               // Tool not found in either service
@@ -2,11 +2,11 @@
  * Tauri MCP Service - Desktop implementation
  */
 
-import { invoke } from '@tauri-apps/api/core'
 import { MCPTool } from '@/types/completion'
 import { DEFAULT_MCP_SETTINGS } from '@/hooks/useMCPServers'
 import type { MCPServerConfig, MCPServers, MCPSettings } from '@/hooks/useMCPServers'
 import type { MCPConfig } from './types'
+import { commands, type JsonValue, type Result } from '@/types/mcp-bindings'
 import { DefaultMCPService } from './default'
 
 export class TauriMCPService extends DefaultMCPService {
@@ -69,6 +69,7 @@ export class TauriMCPService extends DefaultMCPService {
     toolName: string
     serverName?: string
     arguments: object
+    threadId?: string
   }): Promise<{ error: string; content: { text: string }[] }> {
     return window.core?.api?.callTool(args)
   }
@@ -78,6 +79,7 @@ export class TauriMCPService extends DefaultMCPService {
     serverName?: string
     arguments: object
     cancellationToken?: string
+    threadId?: string
   }): {
     promise: Promise<{ error: string; content: { text: string }[] }>
     cancel: () => Promise<void>
@@ -105,18 +107,25 @@ export class TauriMCPService extends DefaultMCPService {
   }
 
   async activateMCPServer(name: string, config: MCPServerConfig): Promise<void> {
-    return await invoke('activate_mcp_server', { name, config })
+    unwrapResult(
+      await commands.activateMcpServer(name, config as unknown as JsonValue)
+    )
   }
 
   async deactivateMCPServer(name: string): Promise<void> {
-    return await invoke('deactivate_mcp_server', { name })
+    unwrapResult(await commands.deactivateMcpServer(name))
   }
 
   async checkJanBrowserExtensionConnected(): Promise<boolean> {
-    return await invoke('check_jan_browser_extension_connected')
+    return unwrapResult(await commands.checkJanBrowserExtensionConnected())
   }
 }
 
+function unwrapResult<T>(result: Result<T, string>): T {
+  if (result.status === 'error') throw new Error(result.error)
+  return result.data
+}
+
 function isPlainObject(value: unknown): value is Record<string, unknown> {
   return typeof value === 'object' && value !== null && !Array.isArray(value)
 }
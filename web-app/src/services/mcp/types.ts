@@ -22,12 +22,18 @@ export interface MCPService {
   getMCPConfig(): Promise<MCPConfig>
   getTools(): Promise<MCPTool[]>
   getConnectedServers(): Promise<string[]>
-  callTool(args: { toolName: string; serverName?: string; arguments: object }): Promise<MCPToolCallResult>
+  callTool(args: {
+    toolName: string
+    serverName?: string
+    arguments: object
+    threadId?: string
+  }): Promise<MCPToolCallResult>
   callToolWithCancellation(args: {
     toolName: string
     serverName?: string
     arguments: object
     cancellationToken?: string
+    threadId?: string
   }): ToolCallWithCancellationResult
   cancelToolCall(cancellationToken: string): Promise<void>
 
@@ -2,5 +2,18 @@ export enum SystemEvent {
   MCP_UPDATE = 'mcp-update',
   KILL_SIDECAR = 'kill-sidecar',
   MCP_ERROR = 'mcp-error',
+  MCP_PORT_CHANGED = 'mcp-port-changed',
+  MCP_STARTUP = 'mcp-startup',
+  MCP_SERVER_MUTED = 'mcp-server-muted',
+  SYSTEM_RESUMED = 'system-resumed',
+  NETWORK_CHANGED = 'network-changed',
+  LAN_PAIRING_REQUEST = 'lan-pairing-request',
+  SYNC_STATUS_CHANGED = 'sync-status-changed',
+  OUTBOX_DELIVERY = 'outbox-delivery',
+  MODEL_COMPARISON_CANDIDATE = 'model-comparison-candidate',
+  GUARDRAIL_VIOLATION = 'guardrail-violation',
+  INSTANCE_OPEN_FILES = 'instance-open-files',
+  ONBOARDING_CHANGED = 'onboarding-changed',
   DEEP_LINK = 'deep-link',
+  PROVIDER_KEYS_CLEARED = 'provider-keys-cleared',
 }
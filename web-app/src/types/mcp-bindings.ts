@@ -0,0 +1,418 @@
+// @ts-nocheck
+
+// This file was generated by [tauri-specta](https://github.com/oscartbeaumont/tauri-specta). Do not edit this file manually.
+
+/** user-defined commands **/
+
+
+export const commands = {
+async activateMcpServer(name: string, config: JsonValue) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("activate_mcp_server", { name, config }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async deactivateMcpServer(name: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("deactivate_mcp_server", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async restartMcpServers() : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("restart_mcp_servers") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getConnectedServers() : Promise<Result<string[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_connected_servers") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Retrieves all available tools from all MCP servers with server information
+ * 
+ * # Arguments
+ * * `state` - Application state containing MCP server connections
+ * * `assistant_id` - Optional assistant whose tool scope filters the returned tools
+ * 
+ * # Returns
+ * * `Result<Vec<Tool>, String>` - A vector of all tools if successful, or an error message if failed
+ * 
+ * This function:
+ * 1. Locks the MCP servers mutex to access server connections
+ * 2. Iterates through all connected servers the assistant is allowed to use
+ * 3. Gets the list of tools from each server
+ * 4. Associates each tool with its parent server name
+ * 5. Combines all tools into a single vector
+ * 6. Returns the combined list of all available tools with server information
+ */
+async getTools(assistantId: string | null) : Promise<Result<ToolWithServer[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_tools", { assistantId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Returns the last-known tools of the active servers from the tool cache, so they can be
+ * shown before the servers are up, and reconciles the cache with the running servers in
+ * the background, emitting `mcp-update` when their tools changed.
+ */
+async getCachedTools(assistantId: string | null) : Promise<Result<ToolWithServer[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_cached_tools", { assistantId }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Cancels a running tool call by its cancellation token
+ * 
+ * # Arguments
+ * * `state` - Application state containing cancellation tokens
+ * * `cancellation_token` - Token identifying the tool call to cancel
+ * 
+ * # Returns
+ * * `Result<(), String>` - Success if token found and cancelled, error otherwise
+ */
+async cancelToolCall(cancellationToken: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("cancel_tool_call", { cancellationToken }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async getMcpConfigs() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_mcp_configs") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async saveMcpConfigs(configs: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("save_mcp_configs", { configs }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check if Jan Browser extension is connected via MCP
+ */
+async checkJanBrowserExtensionConnected() : Promise<Result<boolean, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_jan_browser_extension_connected") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Check configured npx/uvx-based servers, or only `name`, for newer package versions.
+ * Servers that aren't run from a package are skipped; a failed check of one server is
+ * logged and skipped too, unless it was the only one asked for.
+ */
+async checkMcpServerUpdates(name: string | null) : Promise<Result<McpPackageVersion[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("check_mcp_server_updates", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Pin an npx/uvx-based server to `version`, or to the newest release when unset, and
+ * restart it when it is running
+ */
+async updateMcpServerVersion(name: string, version: string | null) : Promise<Result<McpPackageVersion, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("update_mcp_server_version", { name, version }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+}},
+/**
+ * Send a raw JSON-RPC request to a running server and return the raw result, for the
+ * MCP inspector. Only the request methods of the MCP client role can be sent.
+ */
+async sendMcpRequest(server: string, method: string, params: JsonValue | null) : Promise<Result<JsonValue, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("send_mcp_request", { server, method, params }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Turn recording of every JSON-RPC frame exchanged with `server` on or off
+ */
+async setMcpWireLogging(server: string, enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("set_mcp_wire_logging", { server, enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The last `limit` recorded frames of `server`, oldest first
+ */
+async getMcpWireLog(server: string, limit: number | null) : Promise<Result<McpWireLog, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("get_mcp_wire_log", { server, limit }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async clearMcpWireLog(server: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("clear_mcp_wire_log", { server }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+}},
+/**
+ * Start recording every request and response of `server` into a new session file in
+ * the recordings folder. Returns the file name, usable as the `replay` of a server config.
+ */
+async startMcpRecording(server: string) : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("start_mcp_recording", { server }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Stop recording `server`. Returns the session file name, or null when it wasn't recorded.
+ */
+async stopMcpRecording(server: string) : Promise<Result<string | null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("stop_mcp_recording", { server }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The recorded sessions, newest first
+ */
+async listMcpRecordings() : Promise<Result<McpRecording[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("list_mcp_recordings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * The MCP config as a shareable setup, with the values of secret env vars and headers
+ * replaced by `{{secret:<NAME>}}` placeholders
+ */
+async exportMcpConfig() : Promise<Result<string, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("export_mcp_config") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Add the servers of a shared setup to the MCP config, replacing servers of the same name.
+ * Secrets come from `secrets`, then from the local config of the same server; the user is
+ * asked for the others through `mcp-secret-required`. Nothing changes when one is declined.
+ */
+async importMcpConfig(data: string, secrets: Partial<{ [key in string]: string }> | null) : Promise<Result<McpConfigImport, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("import_mcp_config", { data, secrets }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+/**
+ * Answer a pending `mcp-secret-required` request. No value declines it, which cancels the
+ * import.
+ */
+async respondMcpSecret(requestId: string, value: string | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("respond_mcp_secret", { requestId, value }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+}
+}
+
+/** user-defined events **/
+
+
+
+/** user-defined constants **/
+
+
+
+/** user-defined types **/
+
+export type JsonValue = null | boolean | number | string | JsonValue[] | { [key in string]: JsonValue }
+/**
+ * What `import_mcp_config` changed in the MCP config
+ */
+export type McpConfigImport = { 
+/**
+ * Servers that weren't configured before
+ */
+added: string[]; 
+/**
+ * Configured servers whose config was replaced
+ */
+replaced: string[] }
+/**
+ * Version of the package an npx/uvx-based server runs, compared with its newest release
+ */
+export type McpPackageVersion = { server: string; package: string; 
+/**
+ * `npm` or `pypi`
+ */
+registry: string; 
+/**
+ * Version the server is pinned to; unset when it runs whatever is newest
+ */
+pinned: string | null; latest: string; 
+/**
+ * Whether `latest` is newer than the pinned version
+ */
+updateAvailable: boolean }
+/**
+ * A recorded MCP session in the recordings folder
+ */
+export type McpRecording = { 
+/**
+ * File name, usable as the `replay` of a server config
+ */
+name: string; server: string; startedAt: string; frames: number }
+/**
+ * Runtime MCP settings that can be adjusted via UI
+ */
+export type McpSettings = { toolCallTimeoutSeconds: number; baseRestartDelayMs: number; maxRestartDelayMs: number; backoffMultiplier: number; 
+/**
+ * How many servers start at the same time when Jan launches
+ */
+startupConcurrency: number; 
+/**
+ * How long launch waits for servers to start; servers not up by then keep starting
+ * in the background. 0 waits for all of them.
+ */
+startupBudgetSeconds: number }
+/**
+ * Result of a tool call as returned to the webview. Mirrors the wire format of the MCP
+ * `CallToolResult`, which has no TypeScript type of its own.
+ */
+export type McpToolCallResult = { 
+/**
+ * MCP content blocks such as `{ "type": "text", "text": "..." }`
+ */
+content: JsonValue[]; structuredContent?: JsonValue; isError?: boolean; 
+/**
+ * Set when the full result was too large and stored as a tool artifact; `content` then
+ * holds a preview
+ */
+artifactId?: string }
+/**
+ * Tool with server information
+ */
+/**
+ * A JSON-RPC frame exchanged with a server, recorded while wire logging is enabled
+ */
+export type McpWireFrame = { timestamp: string; 
+/**
+ * `sent` to the server or `received` from it
+ */
+direction: string; message: JsonValue }
+/**
+ * Wire log of a server as shown by the inspector
+ */
+export type McpWireLog = { enabled: boolean; 
+/**
+ * Whether the server's traffic is being recorded to a session file
+ */
+recording: boolean; frames: McpWireFrame[] }
+export type ToolWithServer = { name: string; description: string | null; inputSchema: JsonValue; server: string }
+
+/** tauri-specta globals **/
+
+import {
+	invoke as TAURI_INVOKE,
+	Channel as TAURI_CHANNEL,
+} from "@tauri-apps/api/core";
+import * as TAURI_API_EVENT from "@tauri-apps/api/event";
+import { type WebviewWindow as __WebviewWindow__ } from "@tauri-apps/api/webviewWindow";
+
+type __EventObj__<T> = {
+	listen: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.listen<T>>;
+	once: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.once<T>>;
+	emit: null extends T
+		? (payload?: T) => ReturnType<typeof TAURI_API_EVENT.emit>
+		: (payload: T) => ReturnType<typeof TAURI_API_EVENT.emit>;
+};
+
+export type Result<T, E> =
+	| { status: "ok"; data: T }
+	| { status: "error"; error: E };
+
+function __makeEvents__<T extends Record<string, any>>(
+	mappings: Record<keyof T, string>,
+) {
+	return new Proxy(
+		{} as unknown as {
+			[K in keyof T]: __EventObj__<T[K]> & {
+				(handle: __WebviewWindow__): __EventObj__<T[K]>;
+			};
+		},
+		{
+			get: (_, event) => {
+				const name = mappings[event as keyof T];
+
+				return new Proxy((() => {}) as any, {
+					apply: (_, __, [window]: [__WebviewWindow__]) => ({
+						listen: (arg: any) => window.listen(name, arg),
+						once: (arg: any) => window.once(name, arg),
+						emit: (arg: any) => window.emit(name, arg),
+					}),
+					get: (_, command: keyof __EventObj__<any>) => {
+						switch (command) {
+							case "listen":
+								return (arg: any) => TAURI_API_EVENT.listen(name, arg);
+							case "once":
+								return (arg: any) => TAURI_API_EVENT.once(name, arg);
+							case "emit":
+								return (arg: any) => TAURI_API_EVENT.emit(name, arg);
+						}
+					},
+				});
+			},
+		},
+	);
+}
@@ -50,6 +50,17 @@ type Thread = {
       name: string
       updated_at: number
     }
+    /** Set while the messages are in cold storage, see `archive_thread` */
+    archived?: {
+      archived_at: number
+      message_count: number
+      summary: string | null
+    }
+    /** Set on private threads, whose messages are stored encrypted, see `set_thread_private` */
+    private?: {
+      salt: string
+      check: string
+    }
     [key: string]: unknown
   }
 }